@@ -0,0 +1,241 @@
+//! Activity feed composition
+//!
+//! The feed merges events from inspections, maintenance records, comments and
+//! the audit log. Events are ordered newest first by `(occurred_at, id)`, with
+//! `occurred_at` compared at one second resolution so that database and log
+//! events sort the same way. A page ends with a cursor naming its last event;
+//! the next page starts strictly after it, so events are neither repeated nor
+//! skipped while new ones arrive at the top.
+
+use crate::errors::{AppError, AppResult};
+use crate::middleware::AuditLogEntry;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Page size used when none is requested
+pub const DEFAULT_FEED_LIMIT: usize = 50;
+
+/// Largest accepted page size
+pub const MAX_FEED_LIMIT: usize = 200;
+
+/// Audited resource types with their own feed source, skipped in the audit
+/// part of the feed to avoid listing the same change twice
+const AUDIT_RESOURCES_WITH_SOURCE: [&str; 3] = ["inspection", "comment", "maintenance"];
+
+/// Source of an activity event
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ActivityKind {
+    Audit,
+    Inspection,
+    Maintenance,
+    Comment,
+}
+
+/// Restricts the feed to one asset, location subtree and/or user
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActivityFilter {
+    pub asset_id: Option<i64>,
+    pub location_id: Option<i64>,
+    pub user_id: Option<i64>,
+}
+
+/// A single feed event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityItem {
+    /// Stable identifier, unique across sources
+    pub id: String,
+    pub kind: ActivityKind,
+    /// What happened, e.g. `created`, `completed` or an audited action
+    pub action: String,
+    pub occurred_at: DateTime<Utc>,
+    pub actor_id: Option<i64>,
+    pub actor_name: Option<String>,
+    pub entity_type: String,
+    pub entity_id: Option<i64>,
+    pub asset_id: Option<i64>,
+    pub summary: String,
+}
+
+impl ActivityItem {
+    fn sort_key(&self) -> (i64, &str) {
+        (self.occurred_at.timestamp(), &self.id)
+    }
+}
+
+/// One page of the feed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityPage {
+    pub items: Vec<ActivityItem>,
+    /// Pass back to fetch the following page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Position in the feed: the last event of the previous page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActivityCursor {
+    /// Unix seconds of the event
+    pub timestamp: i64,
+    pub id: String,
+}
+
+impl ActivityCursor {
+    pub fn after(item: &ActivityItem) -> Self {
+        Self {
+            timestamp: item.occurred_at.timestamp(),
+            id: item.id.clone(),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        format!("{}|{}", self.timestamp, self.id)
+    }
+
+    pub fn parse(cursor: &str) -> AppResult<Self> {
+        let invalid = || AppError::validation("cursor", "Invalid activity feed cursor");
+        let (timestamp, id) = cursor.split_once('|').ok_or_else(invalid)?;
+        if id.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            timestamp: timestamp.parse().map_err(|_| invalid())?,
+            id: id.to_string(),
+        })
+    }
+
+    /// Whether `item` comes after this position, i.e. belongs on a later page
+    pub fn precedes(&self, item: &ActivityItem) -> bool {
+        item.sort_key() < (self.timestamp, self.id.as_str())
+    }
+}
+
+/// Filter resolved against the database: the assets and locations the feed is
+/// limited to, `None` meaning unrestricted
+#[derive(Debug, Clone, Default)]
+pub struct ActivityScope {
+    pub asset_ids: Option<HashSet<i64>>,
+    pub location_ids: Option<HashSet<i64>>,
+    pub user_id: Option<i64>,
+}
+
+impl ActivityScope {
+    /// Feed event for a successful audit entry within this scope
+    pub fn audit_item(&self, entry: &AuditLogEntry) -> Option<ActivityItem> {
+        if !entry.success || AUDIT_RESOURCES_WITH_SOURCE.contains(&entry.resource_type.as_str()) {
+            return None;
+        }
+        if self.user_id.is_some() && entry.user_id != self.user_id {
+            return None;
+        }
+
+        let entity_id = entry.resource_id.as_deref().and_then(|id| id.parse::<i64>().ok());
+        let asset_id = entity_id.filter(|_| entry.resource_type == "asset");
+        if self.asset_ids.is_some() || self.location_ids.is_some() {
+            let in_assets = matches!((&self.asset_ids, asset_id), (Some(ids), Some(id)) if ids.contains(&id));
+            let in_locations = entry.resource_type == "location"
+                && matches!((&self.location_ids, entity_id), (Some(ids), Some(id)) if ids.contains(&id));
+            if !in_assets && !in_locations {
+                return None;
+            }
+        }
+
+        let summary = match &entry.resource_id {
+            Some(id) => format!("{} {} {}", entry.action, entry.resource_type, id),
+            None => format!("{} {}", entry.action, entry.resource_type),
+        };
+        Some(ActivityItem {
+            id: format!("audit:{}", entry.id),
+            kind: ActivityKind::Audit,
+            action: entry.action.clone(),
+            occurred_at: entry.timestamp,
+            actor_id: entry.user_id,
+            actor_name: entry.username.clone(),
+            entity_type: entry.resource_type.clone(),
+            entity_id,
+            asset_id,
+            summary,
+        })
+    }
+}
+
+/// Clamp a requested page size to `1..=MAX_FEED_LIMIT`
+pub fn feed_limit(requested: Option<usize>) -> usize {
+    requested.unwrap_or(DEFAULT_FEED_LIMIT).clamp(1, MAX_FEED_LIMIT)
+}
+
+/// Merge events from all sources into the page following `cursor`. Each
+/// source must supply at least `limit + 1` of its newest events after the
+/// cursor, if it has that many, for the next cursor to be correct.
+pub fn compose_page(
+    sources: Vec<Vec<ActivityItem>>,
+    cursor: Option<&ActivityCursor>,
+    limit: usize,
+) -> ActivityPage {
+    let mut items: Vec<ActivityItem> = sources
+        .into_iter()
+        .flatten()
+        .filter(|item| cursor.is_none_or(|c| c.precedes(item)))
+        .collect();
+    keep_newest(&mut items, limit + 1);
+
+    let next_cursor = if items.len() > limit {
+        items.truncate(limit);
+        items.last().map(|last| ActivityCursor::after(last).encode())
+    } else {
+        None
+    };
+    ActivityPage { items, next_cursor }
+}
+
+/// Sort events newest first and keep the first `count`, dropping duplicates
+pub fn keep_newest(items: &mut Vec<ActivityItem>, count: usize) {
+    items.sort_by(|a, b| b.sort_key().cmp(&a.sort_key()));
+    items.dedup_by(|a, b| a.id == b.id);
+    items.truncate(count);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn item(id: &str, seconds: i64) -> ActivityItem {
+        ActivityItem {
+            id: id.to_string(),
+            kind: ActivityKind::Inspection,
+            action: "created".to_string(),
+            occurred_at: Utc.timestamp_opt(seconds, 0).unwrap(),
+            actor_id: None,
+            actor_name: None,
+            entity_type: "inspection".to_string(),
+            entity_id: None,
+            asset_id: None,
+            summary: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_pages_follow_cursor_without_gaps() {
+        let sources = || vec![
+            vec![item("inspection:3", 300), item("inspection:2", 200), item("inspection:1", 100)],
+            vec![item("comment:9", 200), item("comment:8", 50)],
+        ];
+
+        let first = compose_page(sources(), None, 2);
+        let ids: Vec<_> = first.items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["inspection:3", "inspection:2"]);
+
+        let cursor = ActivityCursor::parse(first.next_cursor.as_deref().unwrap()).unwrap();
+        let second = compose_page(sources(), Some(&cursor), 2);
+        let ids: Vec<_> = second.items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["comment:9", "inspection:1"]);
+
+        let cursor = ActivityCursor::parse(second.next_cursor.as_deref().unwrap()).unwrap();
+        let last = compose_page(sources(), Some(&cursor), 2);
+        assert_eq!(last.items.len(), 1);
+        assert!(last.next_cursor.is_none());
+
+        assert!(ActivityCursor::parse("not-a-cursor").is_err());
+    }
+}
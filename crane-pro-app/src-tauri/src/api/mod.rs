@@ -4,6 +4,7 @@
 //! for communication between the frontend and backend via Tauri IPC.
 
 use crate::errors::AppError;
+use crate::i18n::{Locale, Localize};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -16,7 +17,8 @@ pub use requests::{
     CreateAssetRequest, AssetUpdateRequest, CreateComponentRequest, ComponentUpdateRequest,
     CreateInspectionRequest, InspectionUpdateRequest, CreateInspectionItemRequest, InspectionItemUpdateRequest,
     CreateComplianceRecordRequest, ComplianceRecordUpdateRequest,
-    CreateUserRequest, UserUpdateRequest, LoginRequest, ChangePasswordRequest,
+    CreateUserRequest, UserUpdateRequest, LoginRequest, ChangePasswordRequest, CreateUserAbsenceRequest,
+    CreateTeamRequest, TeamUpdateRequest,
     UploadFileRequest, MediaFileUpdateRequest,
     CreateLocationRequest, LocationUpdateRequest,
     // New request types
@@ -30,7 +32,7 @@ pub use responses::{
     AssetResponse, ComponentResponse,
     InspectionResponse, InspectionItemResponse,
     ComplianceRecordResponse, ComplianceStatusResponse, ComplianceRequirementResponse,
-    UserResponse, LoginResponse,
+    UserResponse, LoginResponse, LoginResult, KioskLoginResponse,
     MediaFileResponse, UploadResponse,
     ReportResponse, ReportTemplateResponse, ReportParameterResponse,
     DashboardStatsResponse, ActivityResponse, UpcomingInspectionResponse,
@@ -58,6 +60,11 @@ impl<T> ApiResponse<T> {
     pub fn error(error: AppError) -> Self {
         Self::Error(ApiError::from(error))
     }
+
+    /// Error response with the message rendered in the given locale
+    pub fn localized_error(error: AppError, locale: Locale) -> Self {
+        Self::Error(ApiError::localized(&error, locale))
+    }
 }
 
 /// API-specific error type for frontend consumption
@@ -68,12 +75,42 @@ pub struct ApiError {
     pub details: Option<HashMap<String, String>>,
 }
 
+impl ApiError {
+    /// Build an API error with the message rendered in the given locale.
+    /// The `code` stays untranslated so clients can match on it.
+    pub fn localized(app_error: &AppError, locale: Locale) -> Self {
+        Self {
+            code: app_error.category().to_string(),
+            message: app_error.localize(locale),
+            details: error_details(app_error),
+        }
+    }
+}
+
+/// Structured details for errors the frontend can act on field by field
+fn error_details(app_error: &AppError) -> Option<HashMap<String, String>> {
+    match app_error {
+        AppError::InvalidQueryParameter { entity, parameter, value, reason, allowed } => {
+            let mut details = HashMap::new();
+            details.insert("entity".to_string(), entity.clone());
+            details.insert("parameter".to_string(), parameter.clone());
+            details.insert("value".to_string(), value.clone());
+            details.insert("reason".to_string(), reason.clone());
+            if !allowed.is_empty() {
+                details.insert("allowed".to_string(), allowed.join(","));
+            }
+            Some(details)
+        }
+        _ => None,
+    }
+}
+
 impl From<AppError> for ApiError {
     fn from(app_error: AppError) -> Self {
         Self {
             code: app_error.category().to_string(),
+            details: error_details(&app_error),
             message: app_error.to_string(),
-            details: None,
         }
     }
 }
@@ -85,8 +122,17 @@ pub struct DateRange {
     pub end_date: chrono::DateTime<chrono::Utc>,
 }
 
-/// Report format options
+/// Summary report to export as CSV, with what it covers
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "report", rename_all = "snake_case")]
+pub enum SummaryReportRequest {
+    AssetSummary { asset_id: i64 },
+    InspectionCompletion { date_range: DateRange },
+    ComplianceStatus { location_id: Option<i64> },
+}
+
+/// Report format options
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub enum ReportFormat {
     #[serde(rename = "pdf")]
     Pdf,
@@ -109,6 +155,35 @@ pub struct ReportResult {
     pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// Chart drawn into a report, as SVG in HTML output and as vector graphics
+/// in PDF output
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ChartSpec {
+    pub title: String,
+    pub kind: ChartKind,
+    /// Suffix for axis values, such as `%`
+    pub unit: Option<String>,
+    /// Top of the value axis; the largest value when `None`
+    pub max_value: Option<f64>,
+    pub points: Vec<ChartPoint>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChartKind {
+    /// Values over time joined by a line
+    Line,
+    /// One bar per category
+    Bar,
+}
+
+/// One labelled value of a chart
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ChartPoint {
+    pub label: String,
+    pub value: f64,
+}
+
 /// Report template metadata
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ReportTemplate {
@@ -30,6 +30,12 @@ pub struct CreateAssetRequest {
     pub description: Option<String>,
     pub specifications: Option<JsonValue>,
     pub created_by: i64,
+    #[serde(default)]
+    pub criticality: AssetCriticality,
+    #[serde(default)]
+    pub service_class: Option<CmaaServiceClass>,
+    #[serde(default)]
+    pub fem_group: Option<FemGroup>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -47,6 +53,12 @@ pub struct AssetUpdateRequest {
     pub status: Option<AssetStatus>,
     pub description: Option<String>,
     pub specifications: Option<JsonValue>,
+    #[serde(default)]
+    pub criticality: Option<AssetCriticality>,
+    #[serde(default)]
+    pub service_class: Option<CmaaServiceClass>,
+    #[serde(default)]
+    pub fem_group: Option<FemGroup>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -83,6 +95,8 @@ pub struct CreateInspectionRequest {
     pub asset_id: i64,
     pub inspector_id: i64,
     pub inspection_type: InspectionType,
+    /// Left empty, the configured default standard is used
+    #[serde(default)]
     pub compliance_standard: String,
     pub scheduled_date: Option<DateTime<Utc>>,
     pub actual_date: Option<DateTime<Utc>>,
@@ -118,6 +132,12 @@ pub struct CreateInspectionItemRequest {
     pub severity: Option<Severity>,
     pub is_compliant: Option<bool>,
     pub corrective_action: Option<String>,
+    #[serde(default)]
+    pub clause_id: Option<i64>,
+    #[serde(default)]
+    pub measured_value: Option<f64>,
+    #[serde(default)]
+    pub measurement_unit: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -130,6 +150,12 @@ pub struct InspectionItemUpdateRequest {
     pub severity: Option<Severity>,
     pub is_compliant: Option<bool>,
     pub corrective_action: Option<String>,
+    #[serde(default)]
+    pub clause_id: Option<i64>,
+    #[serde(default)]
+    pub measured_value: Option<f64>,
+    #[serde(default)]
+    pub measurement_unit: Option<String>,
 }
 
 // =============================================================================
@@ -191,6 +217,13 @@ pub struct UserUpdateRequest {
 pub struct LoginRequest {
     pub username: String,
     pub password: String,
+    /// Identifies the device signing in. Attempts from one device are
+    /// limited together, whatever username they try.
+    #[serde(default)]
+    pub client_id: Option<String>,
+    /// Name of the device shown in the user's login history
+    #[serde(default)]
+    pub device_name: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -199,6 +232,34 @@ pub struct ChangePasswordRequest {
     pub new_password: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateUserAbsenceRequest {
+    pub user_id: i64,
+    pub absence_type: AbsenceType,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub notes: Option<String>,
+}
+
+// =============================================================================
+// Team Management Requests
+// =============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateTeamRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub supervisor_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TeamUpdateRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub supervisor_id: Option<i64>,
+    pub is_active: Option<bool>,
+}
+
 // =============================================================================
 // Media Management Requests
 // =============================================================================
@@ -246,6 +307,9 @@ impl CreateAssetRequest {
             created_by: self.created_by,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            criticality: self.criticality,
+            service_class: self.service_class,
+            fem_group: self.fem_group,
         }
     }
 }
@@ -286,6 +350,8 @@ impl CreateInspectionRequest {
             ai_analysis_results: self.ai_analysis_results,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            time_zone: None,
+            overdue_at: None,
         }
     }
 }
@@ -304,6 +370,11 @@ impl CreateInspectionItemRequest {
             is_compliant: self.is_compliant,
             corrective_action: self.corrective_action,
             created_at: Utc::now(),
+            clause_id: self.clause_id,
+            risk_rating: None,
+            response_due_at: None,
+            measured_value: self.measured_value,
+            measurement_unit: self.measurement_unit,
         }
     }
 }
@@ -326,6 +397,47 @@ impl CreateUserRequest {
     }
 }
 
+impl CreateUserAbsenceRequest {
+    pub fn to_absence(self, created_by: i64) -> UserAbsence {
+        UserAbsence {
+            id: 0, // Will be set by database
+            user_id: self.user_id,
+            absence_type: self.absence_type,
+            start_date: self.start_date,
+            end_date: self.end_date,
+            notes: self.notes,
+            created_by,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl CreateTeamRequest {
+    pub fn to_team(self, created_by: i64) -> Team {
+        Team {
+            id: 0, // Will be set by database
+            name: self.name,
+            description: self.description,
+            supervisor_id: self.supervisor_id,
+            is_active: true,
+            created_by,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+impl From<TeamUpdateRequest> for crate::services::TeamUpdateData {
+    fn from(request: TeamUpdateRequest) -> Self {
+        Self {
+            name: request.name,
+            description: request.description,
+            supervisor_id: request.supervisor_id,
+            is_active: request.is_active,
+        }
+    }
+}
+
 impl UploadFileRequest {
     pub fn to_media_file(self, file_path: String, file_size: i64) -> MediaFile {
         MediaFile {
@@ -340,6 +452,7 @@ impl UploadFileRequest {
             description: self.description,
             ai_analysis_metadata: None,
             created_at: Utc::now(),
+            uploaded_by: None, // Set from the session when stored
         }
     }
 }
@@ -356,6 +469,7 @@ pub struct CreateLocationRequest {
     pub longitude: Option<f64>,
     pub description: Option<String>,
     pub parent_location_id: Option<i64>,
+    pub time_zone: Option<String>,
     pub created_by: i64,
 }
 
@@ -367,6 +481,7 @@ pub struct LocationUpdateRequest {
     pub longitude: Option<f64>,
     pub description: Option<String>,
     pub parent_location_id: Option<Option<i64>>, // Note: nested Option for nullability
+    pub time_zone: Option<String>,
 }
 
 // =============================================================================
@@ -383,6 +498,8 @@ impl CreateLocationRequest {
             longitude: self.longitude,
             description: self.description,
             parent_location_id: self.parent_location_id,
+            time_zone: self.time_zone
+                .unwrap_or_else(|| crate::scheduling::DEFAULT_TIME_ZONE.to_string()),
             created_by: self.created_by,
             created_at: Utc::now(),
             updated_at: Utc::now(),
@@ -399,6 +516,7 @@ impl From<LocationUpdateRequest> for LocationUpdateData {
             longitude: req.longitude,
             description: req.description,
             parent_location_id: req.parent_location_id,
+            time_zone: req.time_zone,
         }
     }
 }
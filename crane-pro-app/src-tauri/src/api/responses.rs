@@ -3,6 +3,8 @@
 //! This module contains all response structures used by Tauri command handlers
 //! to send data to the frontend.
 
+use crate::i18n::Locale;
+use crate::middleware::MfaChallenge;
 use crate::models::*;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
@@ -267,8 +269,35 @@ pub struct LoginResponse {
     pub user: UserResponse,
     pub token: String,
     pub expires_at: DateTime<Utc>,
+    /// Single-use token for `refresh_token_command` once `token` expires
+    pub refresh_token: String,
+    /// When the session can no longer be refreshed and the user has to sign in
+    pub refresh_expires_at: DateTime<Utc>,
     pub permissions: Vec<String>,
     pub session_id: String,
+    pub locale: Locale,
+}
+
+/// Result of a login: a session, or a request for the second factor
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum LoginResult {
+    Authenticated(Box<LoginResponse>),
+    MfaRequired(MfaChallenge),
+}
+
+/// Session started at a kiosk terminal
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KioskLoginResponse {
+    pub user: UserResponse,
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+    /// The session ends after this many minutes without a command
+    pub idle_timeout_minutes: i64,
+    pub terminal_id: i64,
+    pub permissions: Vec<String>,
+    pub session_id: String,
+    pub locale: Locale,
 }
 
 // =============================================================================
@@ -0,0 +1,261 @@
+//! Permission matrix
+//!
+//! Answers questions such as "who can delete inspections?" from the checks
+//! the command handlers themselves make. The source of every command module
+//! is built into the binary and read for the `require_resource_access!` and
+//! `AuthHelper::require_full_session` calls in each `#[tauri::command]`, so
+//! the matrix changes whenever a guard does. A check made at the top level of
+//! a handler applies to every call; one nested in a branch only to some
+//! inputs. Roles are then matched against the permissions found.
+
+use crate::errors::AppResult;
+use crate::exporters::ExportTable;
+use crate::middleware::Permissions;
+use crate::models::{CommandAccess, CommandAuthorization, PermissionMatrix, Role};
+use chrono::{DateTime, Utc};
+
+/// Source of each command module, by module name
+const COMMAND_SOURCES: [(&str, &str); 42] = [
+    ("activity", include_str!("commands/activity_commands.rs")),
+    ("api_key", include_str!("commands/api_key_commands.rs")),
+    ("asset", include_str!("commands/asset_commands.rs")),
+    ("asset_record", include_str!("commands/asset_record_commands.rs")),
+    ("bulk", include_str!("commands/bulk_commands.rs")),
+    ("checklist_pack", include_str!("commands/checklist_pack_commands.rs")),
+    ("comment", include_str!("commands/comment_commands.rs")),
+    ("compliance", include_str!("commands/compliance_commands.rs")),
+    ("corrective_action", include_str!("commands/corrective_action_commands.rs")),
+    ("dashboard", include_str!("commands/dashboard_commands.rs")),
+    ("defect", include_str!("commands/defect_commands.rs")),
+    ("export", include_str!("commands/export_commands.rs")),
+    ("finding_sla", include_str!("commands/finding_sla_commands.rs")),
+    ("history", include_str!("commands/history_commands.rs")),
+    ("inspection", include_str!("commands/inspection_commands.rs")),
+    ("json_schema", include_str!("commands/json_schema_commands.rs")),
+    ("kiosk", include_str!("commands/kiosk_commands.rs")),
+    ("legal_hold", include_str!("commands/legal_hold_commands.rs")),
+    ("location", include_str!("commands/location_commands.rs")),
+    ("maintenance", include_str!("commands/maintenance_commands.rs")),
+    ("media", include_str!("commands/media_commands.rs")),
+    ("mfa", include_str!("commands/mfa_commands.rs")),
+    ("notification", include_str!("commands/notification_commands.rs")),
+    ("operation", include_str!("commands/operation_commands.rs")),
+    ("operator_authorization", include_str!("commands/operator_authorization_commands.rs")),
+    ("parts", include_str!("commands/parts_commands.rs")),
+    ("prestart", include_str!("commands/prestart_commands.rs")),
+    ("recycle_bin", include_str!("commands/recycle_bin_commands.rs")),
+    ("report", include_str!("commands/report_commands.rs")),
+    ("risk_matrix", include_str!("commands/risk_matrix_commands.rs")),
+    ("role", include_str!("commands/role_commands.rs")),
+    ("search", include_str!("commands/search_commands.rs")),
+    ("settings", include_str!("commands/settings_commands.rs")),
+    ("system", include_str!("commands/system_commands.rs")),
+    ("team", include_str!("commands/team_commands.rs")),
+    ("training", include_str!("commands/training_commands.rs")),
+    ("trusted_device", include_str!("commands/trusted_device_commands.rs")),
+    ("user", include_str!("commands/user_commands.rs")),
+    ("validation_rule", include_str!("commands/validation_rule_commands.rs")),
+    ("vendor_document", include_str!("commands/vendor_document_commands.rs")),
+    ("watch", include_str!("commands/watch_commands.rs")),
+    ("work_order", include_str!("commands/work_order_commands.rs")),
+];
+
+/// Indentation of statements at the top level of a handler's
+/// `time_command!` block
+const HANDLER_BODY_INDENT: usize = 8;
+
+/// Resource shown for a check whose resource is chosen at run time
+const RUNTIME_RESOURCE: &str = "<record>";
+
+/// What each command checks, with no role access filled in, in module and
+/// then source order
+pub fn command_requirements() -> Vec<CommandAuthorization> {
+    COMMAND_SOURCES
+        .iter()
+        .flat_map(|(module, source)| parse_module(module, source))
+        .collect()
+}
+
+fn parse_module(module: &str, source: &str) -> Vec<CommandAuthorization> {
+    let mut commands = Vec::new();
+    for handler in source.split("#[tauri::command]").skip(1) {
+        let Some(name) = handler.lines().find_map(handler_name) else {
+            continue;
+        };
+        let mut command = CommandAuthorization {
+            command: name.to_string(),
+            module: module.to_string(),
+            public: !handler.contains("AuthHelper::validate_request"),
+            required: Vec::new(),
+            conditional: Vec::new(),
+            full_session: handler.contains("AuthHelper::require_full_session"),
+            access: Vec::new(),
+        };
+        for line in handler.lines() {
+            let Some((resource, action)) = resource_check(line) else {
+                continue;
+            };
+            let indent = line.len() - line.trim_start().len();
+            let permission = format!("{}:{}", resource, action);
+            let checks = if indent <= HANDLER_BODY_INDENT && resource != RUNTIME_RESOURCE {
+                &mut command.required
+            } else {
+                &mut command.conditional
+            };
+            if !checks.contains(&permission) {
+                checks.push(permission);
+            }
+        }
+        // A check made on every call is not also conditional
+        let required = command.required.clone();
+        command.conditional.retain(|permission| !required.contains(permission));
+        commands.push(command);
+    }
+    commands
+}
+
+/// Name of the function declared on `line`, if it declares one
+fn handler_name(line: &str) -> Option<&str> {
+    let rest = line.trim_start().strip_prefix("pub ")?;
+    let rest = rest.strip_prefix("async ").unwrap_or(rest).strip_prefix("fn ")?;
+    rest.split(['(', '<']).next().map(str::trim)
+}
+
+/// Resource and action of a `require_resource_access!` on `line`
+fn resource_check(line: &str) -> Option<(&str, &str)> {
+    let arguments = line.split("require_resource_access!(").nth(1)?;
+    let arguments = &arguments[..arguments.rfind(')')?];
+    let (rest, action) = arguments.rsplit_once(',')?;
+    let resource = rest.split_once(',')?.1.trim();
+    let resource = resource
+        .strip_prefix('"')
+        .and_then(|literal| literal.strip_suffix('"'))
+        .unwrap_or(RUNTIME_RESOURCE);
+    Some((resource, action.trim().trim_matches('"')))
+}
+
+/// Access of a role with `granted` to a command. Checks on a resource chosen
+/// at run time only pass for roles given every permission.
+pub fn command_access(command: &CommandAuthorization, granted: &[String]) -> CommandAccess {
+    let passes = |permission: &String| {
+        if permission.starts_with(RUNTIME_RESOURCE) {
+            Permissions::grants(granted, Permissions::SYSTEM_ALL)
+        } else {
+            Permissions::grants(granted, permission)
+        }
+    };
+    if !command.required.iter().all(passes) {
+        CommandAccess::Denied
+    } else if command.conditional.iter().all(passes) {
+        CommandAccess::Allowed
+    } else if command.required.is_empty() && !command.conditional.iter().any(passes) {
+        CommandAccess::Denied
+    } else {
+        CommandAccess::Partial
+    }
+}
+
+/// Every command against `roles`
+pub fn build_matrix(roles: &[Role], generated_at: DateTime<Utc>) -> PermissionMatrix {
+    let commands = command_requirements()
+        .into_iter()
+        .map(|mut command| {
+            command.access = roles.iter().map(|role| command_access(&command, &role.permissions)).collect();
+            command
+        })
+        .collect();
+    PermissionMatrix {
+        generated_at,
+        roles: roles.iter().map(|role| role.name.clone()).collect(),
+        commands,
+    }
+}
+
+/// Kind of session a command needs, as written in the exports
+pub fn sign_in_label(command: &CommandAuthorization) -> &'static str {
+    match (command.public, command.full_session) {
+        (true, _) => "None",
+        (false, true) => "Login",
+        (false, false) => "Any session",
+    }
+}
+
+/// One row per command, one column per role, with the matrix itself as
+/// the structured records
+pub fn matrix_table(matrix: &PermissionMatrix) -> AppResult<ExportTable> {
+    let mut columns = vec!["Module", "Command", "Sign-in", "Required", "Conditional"];
+    columns.extend(matrix.roles.iter().map(String::as_str));
+    let mut table = ExportTable::new("Permission matrix", matrix.generated_at, &columns)
+        .with_records(serde_json::to_value(matrix)?);
+    for command in &matrix.commands {
+        let mut fields = vec![
+            command.module.clone(),
+            command.command.clone(),
+            sign_in_label(command).to_string(),
+            command.required.join(" "),
+            command.conditional.join(" "),
+        ];
+        fields.extend(command.access.iter().map(|access| access.to_string()));
+        table.push_row(fields);
+    }
+    Ok(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ReportFormat;
+    use crate::exporters::registry;
+    use crate::models::UserRole;
+
+    fn role(name: &str, role: UserRole) -> Role {
+        Role {
+            id: 1,
+            name: name.to_string(),
+            description: None,
+            permissions: Permissions::for_role(&role),
+            base_role: role,
+            is_builtin: true,
+            user_count: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_every_command_module_is_read() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/commands");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let name = entry.unwrap().file_name().into_string().unwrap();
+            if let Some(module) = name.strip_suffix("_commands.rs") {
+                assert!(COMMAND_SOURCES.iter().any(|(m, _)| *m == module), "{} is not in COMMAND_SOURCES", name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_answers_who_can_delete_inspections() {
+        let roles = [role("Inspector", UserRole::Inspector), role("Administrator", UserRole::Administrator)];
+        let matrix = build_matrix(&roles, Utc::now());
+        let find = |name: &str| matrix.commands.iter().find(|c| c.command == name).unwrap();
+
+        let delete = find("delete_inspection_command");
+        assert_eq!(delete.required, ["inspection:delete"]);
+        assert_eq!(delete.access, [CommandAccess::Denied, CommandAccess::Allowed]);
+
+        // Deferring or verifying a defect also needs compliance:update
+        let defect = find("update_defect_status_command");
+        assert_eq!(defect.required, ["inspection:update"]);
+        assert_eq!(defect.conditional, ["compliance:update"]);
+        assert_eq!(defect.access, [CommandAccess::Partial, CommandAccess::Allowed]);
+
+        assert!(find("login_command").public);
+        assert!(find("change_password_command").full_session);
+        assert_eq!(find("delete_comment_command").conditional, ["<record>:read", "<record>:update"]);
+
+        let table = matrix_table(&matrix).unwrap();
+        let csv = String::from_utf8(registry().render(&ReportFormat::Csv, &table).unwrap()).unwrap();
+        assert!(csv.starts_with("Module,Command,Sign-in,Required,Conditional,Inspector,Administrator\n"));
+        assert!(csv.contains("\ninspection,delete_inspection_command,Any session,inspection:delete,,No,Yes\n"));
+    }
+}
@@ -0,0 +1,76 @@
+//! Activity feed command handlers
+//!
+//! This module contains the Tauri command handler for the activity feed,
+//! which merges inspection, maintenance, comment and audit events for an
+//! asset, location or user into one chronological, cursor-paginated list.
+
+use crate::activity::{
+    compose_page, feed_limit, keep_newest, ActivityCursor, ActivityFilter, ActivityPage,
+};
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::logging::{for_each_audit_entry, LogManager};
+use crate::middleware::auth::AuthHelper;
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::debug;
+
+/// Get a page of the activity feed, newest first. Pass the returned
+/// `next_cursor` back as `cursor` to load the following page.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_activity_feed_command(
+    state: State<'_, AppState>,
+    logs: State<'_, LogManager>,
+    token: Option<String>,
+    filter: Option<ActivityFilter>,
+    cursor: Option<String>,
+    limit: Option<usize>,
+) -> CommandResult<ActivityPage> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_activity_feed", {
+        require_resource_access!(context, "asset", "read");
+        require_resource_access!(context, "inspection", "read");
+
+        let session = context.current_user()?;
+        let filter = filter.unwrap_or_default();
+        if filter.user_id.is_some_and(|user_id| user_id != session.user_id) {
+            require_resource_access!(context, "user", "read");
+        }
+
+        let cursor = cursor.as_deref().map(ActivityCursor::parse).transpose()
+            .context("Failed to get activity feed")?;
+        let limit = feed_limit(limit);
+
+        let scope = state.services.activity.resolve_scope(&filter)
+            .context("Failed to get activity feed")?;
+        let events = state.services.activity.get_events(&scope, cursor.as_ref(), limit + 1)
+            .context("Failed to get activity feed")?;
+
+        // Audit entries are only shown for resources the user may read
+        let mut audit = Vec::new();
+        for_each_audit_entry(logs.log_dir(), |entry| {
+            if !session.can_access_resource(&entry.resource_type, "read") {
+                return Ok(());
+            }
+            if let Some(item) = scope.audit_item(&entry) {
+                if cursor.as_ref().is_none_or(|c| c.precedes(&item)) {
+                    audit.push(item);
+                }
+            }
+            if audit.len() > 4 * (limit + 1) {
+                keep_newest(&mut audit, limit + 1);
+            }
+            Ok(())
+        }).context("Failed to read audit log")?;
+
+        let page = compose_page(vec![events, audit], cursor.as_ref(), limit);
+
+        debug!("[{}] Retrieved {} activity events", context.request_id, page.items.len());
+        Ok(page)
+    });
+
+    Ok(command_handler!("get_activity_feed", &context, { result }))
+}
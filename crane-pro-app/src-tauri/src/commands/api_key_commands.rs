@@ -0,0 +1,92 @@
+//! API key command handlers
+//!
+//! This module contains Tauri command handlers for the API keys external
+//! systems use instead of a user login. A key acts as the user who created
+//! it, limited to the permissions it was given, and its secret is only
+//! shown when it is created.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{ApiKey, ApiKeyInput, CreatedApiKey};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info};
+
+/// Get every API key, without their secrets
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_api_keys_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Vec<ApiKey>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_api_keys", {
+        require_resource_access!(context, "user", "api_keys");
+
+        let api_keys = state.services.api_keys.get_api_keys()
+            .context("Failed to get API keys")?;
+
+        debug!("[{}] Retrieved {} API keys", context.request_id, api_keys.len());
+        Ok(api_keys)
+    });
+
+    Ok(command_handler!("get_api_keys", &context, { result }))
+}
+
+/// Create an API key with permissions the signed-in user holds
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn create_api_key_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    input: ApiKeyInput,
+) -> CommandResult<CreatedApiKey> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("create_api_key", {
+        require_resource_access!(context, "user", "api_keys");
+        AuthHelper::require_full_session(&context)
+            .context("Failed to create API key")?;
+
+        let created = state.services.api_keys.create_api_key(&context, input)
+            .context("Failed to create API key")?;
+        AuthHelper::audit_action(&context, "create", "api_key", Some(&created.api_key.id.to_string()), true, None);
+
+        info!("[{}] API key {} created with {} permissions",
+              context.request_id, created.api_key.key_prefix, created.api_key.permissions.len());
+        Ok(created)
+    });
+
+    Ok(command_handler!("create_api_key", &context, { result }))
+}
+
+/// Revoke an API key; requests made with it are refused from then on
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn revoke_api_key_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    api_key_id: i64,
+) -> CommandResult<ApiKey> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("revoke_api_key", {
+        require_resource_access!(context, "user", "api_keys");
+
+        let api_key = state.services.api_keys.revoke_api_key(&context, api_key_id)
+            .context("Failed to revoke API key")?;
+        AuthHelper::audit_action(&context, "revoke", "api_key", Some(&api_key_id.to_string()), true, None);
+
+        info!("[{}] API key {} revoked", context.request_id, api_key.key_prefix);
+        Ok(api_key)
+    });
+
+    Ok(command_handler!("revoke_api_key", &context, { result }))
+}
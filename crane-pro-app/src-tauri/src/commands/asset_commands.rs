@@ -1,122 +1,126 @@
 //! Asset management command handlers
 //! 
 //! This module contains all Tauri command handlers for asset management
-//! operations including CRUD operations for assets and components.
+//! operations including CRUD operations for assets and components, the
+//! component templates applied to new assets, and the links printed on asset
+//! labels.
 
-use crate::api::{ApiResponse, QueryFilterRequest, CreateAssetRequest, AssetUpdateRequest,
+use crate::api::{QueryFilterRequest, CreateAssetRequest, AssetUpdateRequest,
                 CreateComponentRequest, ComponentUpdateRequest, PaginatedResponse};
-use crate::commands::AppState;
+use crate::commands::{notify_watchers, AppState, CommandResult, ErrorContext};
+use crate::errors::AppError;
 use crate::middleware::auth::AuthHelper;
-use crate::models::{Asset, Component};
-use crate::services::{AssetUpdateData, AssetSummary, BulkImportResult, AssetStatusFilter,
+use crate::models::{Asset, AssetLink, Component, ComponentTemplate, ComponentTemplateInput, DeepLink, DeepLinkTarget,
+                    LinkView, LinkedEntityType};
+use crate::services::{AssetUpdateData, AssetSummary, AssetCardDto, BulkImportResult, AssetStatusFilter,
                      AssetComplianceSummary, AssetTransferRequest, MaintenanceHistoryEntry};
 use crate::{require_resource_access, time_command, command_handler};
-use tauri::State;
-use log::{info, debug};
+use tauri::{AppHandle, State};
+use log::{info, debug, warn};
 
 /// Create a new asset
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn create_asset_command(
     state: State<'_, AppState>,
     token: Option<String>,
     asset_data: CreateAssetRequest,
-) -> Result<ApiResponse<Asset>, String> {
+) -> CommandResult<Asset> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("create_asset", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "create");
 
         // Validate and create asset
         let asset = asset_data.to_asset();
-        let created_asset = state.services.assets.create_asset(asset)
-            .map_err(|e| format!("Failed to create asset: {}", e))?;
+        let created_asset = state.services.assets.create_asset(&context, asset)
+            .context("Failed to create asset")?;
+        AuthHelper::audit_action(&context, "create", "asset", Some(&created_asset.id.to_string()), true, None);
 
-        info!("Asset created: {} by user {}", 
+        info!("[{}] Asset created: {} by user {}", context.request_id,
               created_asset.asset_number, 
               context.current_user().map(|u| u.user_id).unwrap_or(0));
 
         Ok(created_asset)
     });
 
-    Ok(command_handler!("create_asset", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("create_asset", &context, { result }))
 }
 
 /// Get asset by ID
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_asset_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
-) -> Result<ApiResponse<Asset>, String> {
+) -> CommandResult<Asset> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_asset", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "read");
 
         // Get asset
         let asset = state.services.assets.get_asset_by_id(id)
-            .map_err(|e| format!("Failed to get asset: {}", e))?;
+            .context("Failed to get asset")?;
 
-        debug!("Asset retrieved: {} (ID: {})", asset.asset_name, id);
+        debug!("[{}] Asset retrieved: {} (ID: {})", context.request_id, asset.asset_name, id);
         Ok(asset)
     });
 
-    Ok(command_handler!("get_asset", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_asset", &context, { result }))
 }
 
 /// Get assets by location with filtering
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_assets_by_location_command(
     state: State<'_, AppState>,
     token: Option<String>,
     location_id: i64,
     filter: QueryFilterRequest,
-) -> Result<ApiResponse<PaginatedResponse<Asset>>, String> {
+) -> CommandResult<PaginatedResponse<Asset>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_assets_by_location", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "read");
 
         // Get assets with filters
         let query_filter = filter.into();
         let paginated_assets = state.services.assets.get_assets_by_location(location_id, query_filter)
-            .map_err(|e| format!("Failed to get assets by location: {}", e))?;
+            .context("Failed to get assets by location")?;
 
-        debug!("Retrieved {} assets for location {}", 
+        debug!("[{}] Retrieved {} assets for location {}", context.request_id,
                paginated_assets.data.len(), location_id);
 
         let response = PaginatedResponse::from(paginated_assets);
         Ok(response)
     });
 
-    Ok(command_handler!("get_assets_by_location", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_assets_by_location", &context, { result }))
 }
 
 /// Update asset
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn update_asset_command(
+    app: AppHandle,
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
     updates: AssetUpdateRequest,
-) -> Result<ApiResponse<Asset>, String> {
+) -> CommandResult<Asset> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("update_asset", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "update");
 
         // Convert request to service update data
@@ -134,134 +138,193 @@ pub async fn update_asset_command(
             status: updates.status,
             description: updates.description,
             specifications: updates.specifications,
+            criticality: updates.criticality,
+            service_class: updates.service_class,
+            fem_group: updates.fem_group,
         };
 
         // Update asset
-        let updated_asset = state.services.assets.update_asset(id, update_data)
-            .map_err(|e| format!("Failed to update asset: {}", e))?;
-
-        info!("Asset updated: {} (ID: {}) by user {}", 
+        let previous_status = state.services.assets.get_asset_by_id(id)
+            .context("Failed to update asset")?
+            .status;
+        let updated_asset = state.services.assets.update_asset(&context, id, update_data)
+            .context("Failed to update asset")?;
+        AuthHelper::audit_action(&context, "update", "asset", Some(&id.to_string()), true, None);
+        notify_watchers(&app, &context,
+            state.services.watches.asset_status_changed(&context, &updated_asset, &previous_status));
+
+        info!("[{}] Asset updated: {} (ID: {}) by user {}", context.request_id,
               updated_asset.asset_name, id,
               context.current_user().map(|u| u.user_id).unwrap_or(0));
 
         Ok(updated_asset)
     });
 
-    Ok(command_handler!("update_asset", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("update_asset", &context, { result }))
 }
 
 /// Delete asset
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn delete_asset_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
-) -> Result<ApiResponse<()>, String> {
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("delete_asset", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "delete");
 
         // Delete asset
-        state.services.assets.delete_asset(id)
-            .map_err(|e| format!("Failed to delete asset: {}", e))?;
+        state.services.assets.delete_asset(&context, id)
+            .context("Failed to delete asset")?;
+        AuthHelper::audit_action(&context, "delete", "asset", Some(&id.to_string()), true, None);
 
-        info!("Asset deleted: ID {} by user {}", 
+        info!("[{}] Asset deleted: ID {} by user {}", context.request_id,
               id, context.current_user().map(|u| u.user_id).unwrap_or(0));
 
         Ok(())
     });
 
-    Ok(command_handler!("delete_asset", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("delete_asset", &context, { result }))
+}
+
+/// Restore a soft-deleted asset
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn restore_asset_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<Asset> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("restore_asset", {
+        require_resource_access!(context, "asset", "delete");
+
+        let asset = state.services.assets.restore_asset(&context, id)
+            .context("Failed to restore asset")?;
+        AuthHelper::audit_action(&context, "restore", "asset", Some(&id.to_string()), true, None);
+
+        info!("[{}] Asset restored: {} (ID: {})", context.request_id, asset.asset_name, id);
+        Ok(asset)
+    });
+
+    Ok(command_handler!("restore_asset", &context, { result }))
+}
+
+/// Permanently remove a soft-deleted asset
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn purge_asset_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("purge_asset", {
+        require_resource_access!(context, "system", "purge");
+
+        state.services.assets.purge_asset(&context, id)
+            .context("Failed to purge asset")?;
+        AuthHelper::audit_action(&context, "purge", "asset", Some(&id.to_string()), true, None);
+
+        warn!("[{}] Asset purged: ID {}", context.request_id, id);
+        Ok(())
+    });
+
+    Ok(command_handler!("purge_asset", &context, { result }))
 }
 
 /// Search assets with query and filters
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn search_assets_command(
     state: State<'_, AppState>,
     token: Option<String>,
     query: String,
     filter: QueryFilterRequest,
-) -> Result<ApiResponse<PaginatedResponse<Asset>>, String> {
+) -> CommandResult<PaginatedResponse<Asset>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("search_assets", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "read");
 
         // Search assets
         let query_filter = filter.into();
         let search_results = state.services.assets.search_assets(query.clone(), query_filter)
-            .map_err(|e| format!("Failed to search assets: {}", e))?;
+            .context("Failed to search assets")?;
 
-        debug!("Asset search returned {} results for query: '{}'",
+        debug!("[{}] Asset search returned {} results for query: '{}'", context.request_id,
                search_results.data.len(), query);
 
         let response = PaginatedResponse::from(search_results);
         Ok(response)
     });
 
-    Ok(command_handler!("search_assets", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("search_assets", &context, { result }))
 }
 
 /// Get components for an asset
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_asset_components_command(
     state: State<'_, AppState>,
     token: Option<String>,
     asset_id: i64,
-) -> Result<ApiResponse<Vec<Component>>, String> {
+) -> CommandResult<Vec<Component>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_asset_components", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "read");
 
         // Get components
         let components = state.services.assets.get_asset_components(asset_id)
-            .map_err(|e| format!("Failed to get asset components: {}", e))?;
+            .context("Failed to get asset components")?;
 
-        debug!("Retrieved {} components for asset {}", 
+        debug!("[{}] Retrieved {} components for asset {}", context.request_id,
                components.len(), asset_id);
 
         Ok(components)
     });
 
-    Ok(command_handler!("get_asset_components", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_asset_components", &context, { result }))
 }
 
 /// Create a new component
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn create_component_command(
     state: State<'_, AppState>,
     token: Option<String>,
     component_data: CreateComponentRequest,
-) -> Result<ApiResponse<Component>, String> {
+) -> CommandResult<Component> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("create_component", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "update");
 
         // Create component
         let component = component_data.to_component();
-        let created_component = state.services.assets.create_component(component)
-            .map_err(|e| format!("Failed to create component: {}", e))?;
+        let created_component = state.services.assets.create_component(&context, component)
+            .context("Failed to create component")?;
+        AuthHelper::audit_action(&context, "create", "component", Some(&created_component.id.to_string()), true, None);
 
-        info!("Component created: {} for asset {} by user {}", 
+        info!("[{}] Component created: {} for asset {} by user {}", context.request_id,
               created_component.component_name, 
               created_component.asset_id,
               context.current_user().map(|u| u.user_id).unwrap_or(0));
@@ -269,24 +332,102 @@ pub async fn create_component_command(
         Ok(created_component)
     });
 
-    Ok(command_handler!("create_component", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("create_component", &context, { result }))
+}
+
+/// Get component templates, for one asset type or all of them
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_component_templates_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    asset_type: Option<String>,
+) -> CommandResult<Vec<ComponentTemplate>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_component_templates", {
+        require_resource_access!(context, "asset", "read");
+
+        let templates = state.services.assets.get_component_templates(asset_type)
+            .context("Failed to get component templates")?;
+
+        debug!("[{}] Retrieved {} component templates", context.request_id, templates.len());
+        Ok(templates)
+    });
+
+    Ok(command_handler!("get_component_templates", &context, { result }))
+}
+
+/// Add or replace a component template; applies to assets created afterwards
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn save_component_template_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    template: ComponentTemplateInput,
+) -> CommandResult<ComponentTemplate> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("save_component_template", {
+        require_resource_access!(context, "asset", "create");
+
+        let saved = state.services.assets.save_component_template(&context, template)
+            .context("Failed to save component template")?;
+        AuthHelper::audit_action(&context, "save", "component_template", Some(&saved.id.to_string()), true, None);
+
+        info!("[{}] Component template {} saved for {} by user {}", context.request_id,
+              saved.component_name, saved.asset_type,
+              context.current_user().map(|u| u.user_id).unwrap_or(0));
+        Ok(saved)
+    });
+
+    Ok(command_handler!("save_component_template", &context, { result }))
+}
+
+/// Delete a component template
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn delete_component_template_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("delete_component_template", {
+        require_resource_access!(context, "asset", "create");
+
+        state.services.assets.delete_component_template(&context, id)
+            .context("Failed to delete component template")?;
+        AuthHelper::audit_action(&context, "delete", "component_template", Some(&id.to_string()), true, None);
+
+        info!("[{}] Component template {} deleted", context.request_id, id);
+        Ok(())
+    });
+
+    Ok(command_handler!("delete_component_template", &context, { result }))
 }
 
 /// Update component
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn update_component_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
     updates: ComponentUpdateRequest,
-) -> Result<ApiResponse<Component>, String> {
+) -> CommandResult<Component> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("update_component", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "update");
 
         // Convert request to service update data
@@ -302,217 +443,335 @@ pub async fn update_component_command(
         };
 
         // Update component
-        let updated_component = state.services.assets.update_component(id, update_data)
-            .map_err(|e| format!("Failed to update component: {}", e))?;
+        let updated_component = state.services.assets.update_component(&context, id, update_data)
+            .context("Failed to update component")?;
+        AuthHelper::audit_action(&context, "update", "component", Some(&id.to_string()), true, None);
 
-        info!("Component updated: {} (ID: {}) by user {}", 
+        info!("[{}] Component updated: {} (ID: {}) by user {}", context.request_id,
               updated_component.component_name, id,
               context.current_user().map(|u| u.user_id).unwrap_or(0));
 
         Ok(updated_component)
     });
 
-    Ok(command_handler!("update_component", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("update_component", &context, { result }))
 }
 
 /// Get comprehensive asset summary including inspections, maintenance, and compliance data
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_asset_summary_command(
     state: State<'_, AppState>,
     token: Option<String>,
     asset_id: i64,
-) -> Result<ApiResponse<AssetSummary>, String> {
+) -> CommandResult<AssetSummary> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_asset_summary", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "read");
 
         // Call service method
         let summary = state.services.assets.get_asset_summary(asset_id)
-            .map_err(|e| format!("Failed to get asset summary: {}", e))?;
+            .context("Failed to get asset summary")?;
 
-        debug!("Asset summary retrieved for asset: {}", asset_id);
+        debug!("[{}] Asset summary retrieved for asset: {}", context.request_id, asset_id);
         Ok(summary)
     });
 
-    Ok(command_handler!("get_asset_summary",
-                       result.as_ref().ok().and_then(|_| None),
-                       { result }))
+    Ok(command_handler!("get_asset_summary", &context, { result }))
 }
 
 /// Bulk import assets with validation and transaction handling
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn bulk_import_assets_command(
     state: State<'_, AppState>,
     token: Option<String>,
     assets: Vec<Asset>,
-) -> Result<ApiResponse<BulkImportResult>, String> {
+) -> CommandResult<BulkImportResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("bulk_import_assets", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "create");
 
         // Call service method
-        let import_result = state.services.assets.bulk_import_assets(assets.clone())
-            .map_err(|e| format!("Failed to bulk import assets: {}", e))?;
+        let import_result = state.services.assets.bulk_import_assets(&context, assets.clone())
+            .context("Failed to bulk import assets")?;
+        AuthHelper::audit_action(&context, "bulk_import", "asset", None, true, None);
 
-        info!("Bulk import completed: {}/{} successful",
+        info!("[{}] Bulk import completed: {}/{} successful", context.request_id,
               import_result.successful_imports, import_result.total_processed);
         Ok(import_result)
     });
 
-    Ok(command_handler!("bulk_import_assets",
-                       result.as_ref().ok().and_then(|_| None),
-                       { result }))
+    Ok(command_handler!("bulk_import_assets", &context, { result }))
 }
 
 /// Get maintenance history for a specific asset
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_asset_maintenance_history_command(
     state: State<'_, AppState>,
     token: Option<String>,
     asset_id: i64,
-) -> Result<ApiResponse<Vec<MaintenanceHistoryEntry>>, String> {
+) -> CommandResult<Vec<MaintenanceHistoryEntry>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_asset_maintenance_history", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "read");
 
         // Call service method
         let maintenance_history = state.services.assets.get_asset_maintenance_history(asset_id)
-            .map_err(|e| format!("Failed to get asset maintenance history: {}", e))?;
+            .context("Failed to get asset maintenance history")?;
 
-        debug!("Maintenance history retrieved for asset: {} ({} records)",
+        debug!("[{}] Maintenance history retrieved for asset: {} ({} records)", context.request_id,
                asset_id, maintenance_history.len());
         Ok(maintenance_history)
     });
 
-    Ok(command_handler!("get_asset_maintenance_history",
-                       result.as_ref().ok().and_then(|_| None),
-                       { result }))
+    Ok(command_handler!("get_asset_maintenance_history", &context, { result }))
 }
 
 /// Validate asset-location assignment
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn validate_asset_assignment_command(
     state: State<'_, AppState>,
     token: Option<String>,
     asset_id: i64,
     location_id: i64,
-) -> Result<ApiResponse<()>, String> {
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("validate_asset_location_assignment", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "read");
 
         // Call service method
         state.services.assets.validate_asset_location_assignment(asset_id, location_id)
-            .map_err(|e| format!("Failed to validate asset location assignment: {}", e))?;
+            .context("Failed to validate asset location assignment")?;
 
-        debug!("Asset-location assignment validated: asset={}, location={}",
+        debug!("[{}] Asset-location assignment validated: asset={}, location={}", context.request_id,
                asset_id, location_id);
         Ok(())
     });
 
-    Ok(command_handler!("validate_asset_location_assignment",
-                       result.as_ref().ok().and_then(|_| None),
-                       { result }))
+    Ok(command_handler!("validate_asset_location_assignment", &context, { result }))
+}
+
+/// Get the field screen card of an asset
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_asset_card_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<AssetCardDto> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_asset_card", {
+        require_resource_access!(context, "asset", "read");
+
+        let card = state.services.assets.get_asset_card(id)
+            .context("Failed to get asset card")?;
+
+        debug!("[{}] Asset card retrieved: {}", context.request_id, id);
+        Ok(card)
+    });
+
+    Ok(command_handler!("get_asset_card", &context, { result }))
+}
+
+/// Get field screen cards for a location subtree and/or the caller's open inspections
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_asset_cards_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    location_id: Option<i64>,
+    assigned_to_me: Option<bool>,
+) -> CommandResult<Vec<AssetCardDto>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_asset_cards", {
+        require_resource_access!(context, "asset", "read");
+
+        let inspector_id = if assigned_to_me.unwrap_or(false) {
+            Some(context.current_user()?.user_id)
+        } else {
+            None
+        };
+
+        let cards = state.services.assets.get_asset_cards(location_id, inspector_id)
+            .context("Failed to get asset cards")?;
+
+        debug!("[{}] Retrieved {} asset cards", context.request_id, cards.len());
+        Ok(cards)
+    });
+
+    Ok(command_handler!("get_asset_cards", &context, { result }))
+}
+
+/// Get the link to print as a QR code on an asset's label
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_asset_link_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    asset_id: i64,
+) -> CommandResult<AssetLink> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_asset_link", {
+        require_resource_access!(context, "asset", "read");
+
+        let link = state.services.assets.get_asset_link(&context, asset_id)
+            .context("Failed to get asset link")?;
+
+        debug!("[{}] Asset link retrieved: {}", context.request_id, asset_id);
+        Ok(link)
+    });
+
+    Ok(command_handler!("get_asset_link", &context, { result }))
+}
+
+/// Resolve a `cranepro://` link from a scanned label, printed checklist or
+/// email to the record and screen the frontend should open
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn resolve_deep_link_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    uri: String,
+) -> CommandResult<DeepLinkTarget> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("resolve_deep_link", {
+        let link = uri.parse::<DeepLink>()?;
+
+        let target = match link {
+            DeepLink::Asset { token } => {
+                require_resource_access!(context, "asset", "read");
+                let asset_id = state.services.assets.asset_id_for_link_token(&token)
+                    .map_err(|_| AppError::validation("uri", "This label does not belong to any asset; it may have been replaced"))?;
+                DeepLinkTarget { entity_type: LinkedEntityType::Asset, id: asset_id, default_view: LinkView::AssetCard }
+            }
+            DeepLink::Inspection { id } => {
+                require_resource_access!(context, "inspection", "read");
+                let inspection = state.services.inspections.get_inspection_by_id(id)
+                    .context("Failed to get inspection")?;
+                DeepLinkTarget {
+                    entity_type: LinkedEntityType::Inspection,
+                    id: inspection.id,
+                    default_view: LinkView::InspectionChecklist,
+                }
+            }
+        };
+
+        debug!("[{}] Deep link resolved to {:?} {}", context.request_id, target.entity_type, target.id);
+        Ok(target)
+    });
+
+    Ok(command_handler!("resolve_deep_link", &context, { result }))
 }
 
 /// Get assets filtered by status with pagination
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_assets_by_status_command(
     state: State<'_, AppState>,
     token: Option<String>,
     status_filter: AssetStatusFilter,
     filter: QueryFilterRequest,
-) -> Result<ApiResponse<PaginatedResponse<Asset>>, String> {
+) -> CommandResult<PaginatedResponse<Asset>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_assets_by_status", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "read");
 
         // Convert request to service filter
         let query_filter = filter.into();
         let paginated_assets = state.services.assets.get_assets_by_status(status_filter.clone(), query_filter)
-            .map_err(|e| format!("Failed to get assets by status: {}", e))?;
+            .context("Failed to get assets by status")?;
 
-        debug!("Retrieved {} assets for status filter: {:?}",
+        debug!("[{}] Retrieved {} assets for status filter: {:?}", context.request_id,
                paginated_assets.data.len(), status_filter);
 
         let response = PaginatedResponse::from(paginated_assets);
         Ok(response)
     });
 
-    Ok(command_handler!("get_assets_by_status",
-                       result.as_ref().ok().and_then(|_| None),
-                       { result }))
+    Ok(command_handler!("get_assets_by_status", &context, { result }))
 }
 
 /// Get compliance summary for a specific asset
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_asset_compliance_summary_command(
     state: State<'_, AppState>,
     token: Option<String>,
     asset_id: i64,
-) -> Result<ApiResponse<AssetComplianceSummary>, String> {
+) -> CommandResult<AssetComplianceSummary> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_asset_compliance_summary", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "read");
 
         // Call service method
         let compliance_summary = state.services.assets.get_asset_compliance_summary(asset_id)
-            .map_err(|e| format!("Failed to get asset compliance summary: {}", e))?;
+            .context("Failed to get asset compliance summary")?;
 
-        debug!("Asset compliance summary retrieved for asset: {}", asset_id);
+        debug!("[{}] Asset compliance summary retrieved for asset: {}", context.request_id, asset_id);
         Ok(compliance_summary)
     });
 
-    Ok(command_handler!("get_asset_compliance_summary",
-                       result.as_ref().ok().and_then(|_| None),
-                       { result }))
+    Ok(command_handler!("get_asset_compliance_summary", &context, { result }))
 }
 
 /// Transfer asset from one location to another with validation and audit logging
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn transfer_asset_location_command(
     state: State<'_, AppState>,
     token: Option<String>,
     transfer_request: AssetTransferRequest,
-) -> Result<ApiResponse<Asset>, String> {
+) -> CommandResult<Asset> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("transfer_asset_location", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "asset", "update");
 
         // Call service method
-        let updated_asset = state.services.assets.transfer_asset_location(transfer_request.clone())
-            .map_err(|e| format!("Failed to transfer asset location: {}", e))?;
+        let updated_asset = state.services.assets.transfer_asset_location(&context, transfer_request.clone())
+            .context("Failed to transfer asset location")?;
+        AuthHelper::audit_action(&context, "transfer", "asset", Some(&updated_asset.id.to_string()), true, None);
 
-        info!("Asset transferred: {} from location {} to location {} by user {}",
+        info!("[{}] Asset transferred: {} from location {} to location {} by user {}", context.request_id,
               transfer_request.asset_id, transfer_request.from_location_id,
               transfer_request.to_location_id, transfer_request.transferred_by);
         Ok(updated_asset)
     });
 
-    Ok(command_handler!("transfer_asset_location",
-                       result.as_ref().ok().and_then(|_| None),
-                       { result }))
+    Ok(command_handler!("transfer_asset_location", &context, { result }))
 }
\ No newline at end of file
@@ -0,0 +1,145 @@
+//! Asset record command handlers
+//!
+//! This module contains Tauri command handlers for the insurance policies and
+//! statutory registrations kept against assets, and the feed of renewals
+//! coming due.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{AssetRecord, AssetRecordInput, ExpiringAssetRecord, DEFAULT_RENEWAL_WARNING_DAYS};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info};
+
+/// Add an insurance policy or statutory registration to an asset
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn add_asset_record_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    asset_id: i64,
+    record: AssetRecordInput,
+) -> CommandResult<AssetRecord> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("add_asset_record", {
+        require_resource_access!(context, "asset", "update");
+
+        let record = state.services.asset_records.add_record(&context, asset_id, record)
+            .context("Failed to add asset record")?;
+        AuthHelper::audit_action(&context, "add_asset_record", "asset", Some(&asset_id.to_string()), true, None);
+
+        info!("[{}] {} record {:?} added to asset {}", context.request_id, record.kind, record.id, asset_id);
+        Ok(record)
+    });
+
+    Ok(command_handler!("add_asset_record", &context, { result }))
+}
+
+/// Replace the details of an asset record, e.g. after a renewal
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn update_asset_record_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    record_id: i64,
+    record: AssetRecordInput,
+) -> CommandResult<AssetRecord> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("update_asset_record", {
+        require_resource_access!(context, "asset", "update");
+
+        let record = state.services.asset_records.update_record(&context, record_id, record)
+            .context("Failed to update asset record")?;
+        AuthHelper::audit_action(&context, "update_asset_record", "asset", Some(&record.asset_id.to_string()), true, None);
+
+        info!("[{}] Asset record {} updated, renewal due {}", context.request_id, record_id, record.renewal_date);
+        Ok(record)
+    });
+
+    Ok(command_handler!("update_asset_record", &context, { result }))
+}
+
+/// Remove an insurance policy or registration from an asset
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn delete_asset_record_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    record_id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("delete_asset_record", {
+        require_resource_access!(context, "asset", "update");
+
+        let asset_id = state.services.asset_records.delete_record(&context, record_id)
+            .context("Failed to delete asset record")?;
+        AuthHelper::audit_action(&context, "delete_asset_record", "asset", Some(&asset_id.to_string()), true, None);
+
+        info!("[{}] Asset record {} deleted from asset {}", context.request_id, record_id, asset_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("delete_asset_record", &context, { result }))
+}
+
+/// Get the insurance policies and registrations held for an asset
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_asset_records_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    asset_id: i64,
+) -> CommandResult<Vec<AssetRecord>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_asset_records", {
+        require_resource_access!(context, "asset", "read");
+
+        let records = state.services.asset_records.get_records(asset_id)
+            .context("Failed to get asset records")?;
+
+        debug!("[{}] Retrieved {} records for asset {}", context.request_id, records.len(), asset_id);
+        Ok(records)
+    });
+
+    Ok(command_handler!("get_asset_records", &context, { result }))
+}
+
+/// Get insurance policies and registrations that have lapsed or are due for
+/// renewal within the given number of days, by default 30
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_expiring_asset_records_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    days: Option<i64>,
+    location_id: Option<i64>,
+) -> CommandResult<Vec<ExpiringAssetRecord>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_expiring_asset_records", {
+        require_resource_access!(context, "asset", "read");
+
+        let days = days.unwrap_or(DEFAULT_RENEWAL_WARNING_DAYS);
+        let expiring = state.services.asset_records.get_expiring(days, location_id)
+            .context("Failed to get expiring asset records")?;
+
+        debug!("[{}] Found {} asset records expiring within {} days", context.request_id, expiring.len(), days);
+        Ok(expiring)
+    });
+
+    Ok(command_handler!("get_expiring_asset_records", &context, { result }))
+}
@@ -0,0 +1,92 @@
+//! Bulk operation command handlers
+//!
+//! This module contains the Tauri command handlers for applying one change
+//! (delete, archive, status change, tagging or field updates) to many assets
+//! or inspections at once, with a result for every record.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::operations::{OperationKind, OperationRegistry};
+use crate::models::{BulkEntityType, BulkMode, BulkOperation, BulkOperationResult, BulkUpdateResult};
+use crate::services::AssetUpdateData;
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::info;
+
+/// Apply an operation to a list of records. `mode` defaults to
+/// all-or-nothing. The run is listed as an operation an administrator can
+/// cancel.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+#[allow(clippy::too_many_arguments)]
+pub async fn bulk_operation_command(
+    state: State<'_, AppState>,
+    operations: State<'_, OperationRegistry>,
+    token: Option<String>,
+    entity_type: BulkEntityType,
+    ids: Vec<i64>,
+    operation: BulkOperation,
+    mode: Option<BulkMode>,
+    batch_size: Option<usize>,
+) -> CommandResult<BulkOperationResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("bulk_operation", {
+        require_resource_access!(context, entity_type.resource(), operation.action());
+
+        let tracker = operations.start(OperationKind::BulkOperation,
+                                       format!("{} {} {} records", operation.name(), ids.len(), entity_type),
+                                       context.current_user().map(|u| u.user_id).ok());
+        let outcome = state.services.bulk.run(&context, entity_type, &ids, operation, mode.unwrap_or_default(), batch_size, &tracker);
+        tracker.finish(&outcome);
+        let outcome = outcome.context("Failed to run bulk operation")?;
+
+        for record in outcome.results.iter().filter(|r| r.success) {
+            AuthHelper::audit_action(&context, outcome.operation.action(), entity_type.resource(),
+                                     Some(&record.id.to_string()), true, None);
+        }
+        info!("[{}] Bulk {} on {} {} records: {} succeeded, {} failed{}{}", context.request_id,
+              outcome.operation.name(), outcome.total, entity_type, outcome.succeeded, outcome.failed,
+              if outcome.cancelled { " (cancelled)" } else { "" },
+              if outcome.rolled_back { " (rolled back)" } else { "" });
+
+        Ok(outcome)
+    });
+
+    Ok(command_handler!("bulk_operation", &context, { result }))
+}
+
+/// Apply the same changes to many assets, e.g. marking every crane in a
+/// building as under maintenance. All assets are updated in one transaction;
+/// an asset that cannot be updated is reported and the others are kept.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn bulk_update_assets_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    ids: Vec<i64>,
+    updates: AssetUpdateData,
+) -> CommandResult<BulkUpdateResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("bulk_update_assets", {
+        require_resource_access!(context, "asset", "update");
+
+        let outcome = state.services.assets.bulk_update_assets(&context, &ids, updates)
+            .context("Failed to bulk update assets")?;
+
+        for record in outcome.results.iter().filter(|r| r.success) {
+            AuthHelper::audit_action(&context, "update", "asset", Some(&record.id.to_string()), true, None);
+        }
+        info!("[{}] Bulk update of {} assets: {} succeeded, {} failed", context.request_id,
+              outcome.total, outcome.succeeded, outcome.failed);
+
+        Ok(outcome)
+    });
+
+    Ok(command_handler!("bulk_update_assets", &context, { result }))
+}
@@ -0,0 +1,215 @@
+//! Checklist pack command handlers
+//!
+//! This module contains Tauri command handlers for exporting compliance
+//! standards and their checklists as signed `.cranepack` files, importing
+//! packs, and managing the publishers whose packs are trusted.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::commands::media_commands::media_root;
+use crate::cranepack;
+use crate::middleware::auth::AuthHelper;
+use crate::models::{PackExportRequest, PackExportResult, PackImportSummary, PackSigningIdentity,
+                    TrustedPackPublisher, TrustedPackPublisherInput};
+use crate::security::secrets::Secrets;
+use crate::security::signing;
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{info, debug};
+use std::fs;
+use std::path::Path;
+
+/// Directory exported checklist packs are written to
+const PACKS_DIR: &str = "./data/packs";
+
+/// Get the public key this installation signs checklist packs with, for
+/// customers to add as a trusted publisher
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_pack_signing_identity_command(
+    state: State<'_, AppState>,
+    secrets: State<'_, Secrets>,
+    token: Option<String>,
+) -> CommandResult<PackSigningIdentity> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_pack_signing_identity", {
+        require_resource_access!(context, "system", "admin");
+
+        let signing_key = secrets.pack_signing_key()
+            .context("Failed to load the pack signing key")?;
+        let public_key = signing::public_key(&signing_key)
+            .context("Failed to load the pack signing key")?;
+
+        Ok(PackSigningIdentity {
+            fingerprint: signing::key_fingerprint(&public_key),
+            public_key,
+        })
+    });
+
+    Ok(command_handler!("get_pack_signing_identity", &context, { result }))
+}
+
+/// Export compliance standards with their clauses, checklist templates,
+/// frequency rules and reference images as a `.cranepack` file signed with
+/// this installation's key
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn export_checklist_pack_command(
+    state: State<'_, AppState>,
+    secrets: State<'_, Secrets>,
+    token: Option<String>,
+    request: PackExportRequest,
+) -> CommandResult<PackExportResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("export_checklist_pack", {
+        require_resource_access!(context, "compliance", "read");
+        require_resource_access!(context, "system", "admin");
+
+        let media_root = media_root(&state)?;
+        let contents = state.services.checklist_packs.build_pack(&context, &request, Path::new(&media_root))
+            .context("Failed to build checklist pack")?;
+        let signing_key = secrets.pack_signing_key()
+            .context("Failed to load the pack signing key")?;
+        let pack = cranepack::sign(contents, &signing_key)
+            .context("Failed to sign checklist pack")?;
+
+        fs::create_dir_all(PACKS_DIR)
+            .context("Failed to create packs directory")?;
+        let file_name: String = format!("{}_{}", pack.contents.manifest.name, pack.contents.manifest.version)
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+            .collect();
+        let path = cranepack::write(&Path::new(PACKS_DIR).join(format!("{}.{}", file_name, cranepack::PACK_EXTENSION)), &pack)
+            .context("Failed to write checklist pack")?;
+        let file_path = path.display().to_string();
+        AuthHelper::audit_action(&context, "export_checklist_pack", "compliance", Some(&file_path), true, None);
+
+        info!("[{}] Exported checklist pack '{}' {} to {}", context.request_id,
+              pack.contents.manifest.name, pack.contents.manifest.version, file_path);
+        Ok(PackExportResult {
+            file_path,
+            publisher_fingerprint: signing::key_fingerprint(&pack.signature.public_key),
+            standards: pack.contents.standards.len(),
+            templates: pack.contents.templates.len(),
+            frequency_rules: pack.contents.frequency_rules.len(),
+            reference_images: pack.contents.reference_images.len(),
+        })
+    });
+
+    Ok(command_handler!("export_checklist_pack", &context, { result }))
+}
+
+/// Import a `.cranepack` file. Packs that were altered after signing, or
+/// that were signed by a publisher not trusted here, are refused.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn import_checklist_pack_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    file_path: String,
+) -> CommandResult<PackImportSummary> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("import_checklist_pack", {
+        require_resource_access!(context, "compliance", "update");
+        require_resource_access!(context, "media", "upload");
+
+        let pack = cranepack::read(Path::new(&file_path))
+            .context("Failed to read checklist pack")?;
+        let media_root = media_root(&state)?;
+        let summary = state.services.checklist_packs.import_pack(&context, &pack, Path::new(&media_root))
+            .context("Failed to import checklist pack")?;
+        AuthHelper::audit_action(&context, "import_checklist_pack", "compliance",
+                                 Some(&format!("{} {}", summary.pack_name, summary.pack_version)), true, None);
+
+        info!("[{}] Imported checklist pack '{}' {} from {} ({}): {} standards, {} templates",
+              context.request_id, summary.pack_name, summary.pack_version, summary.publisher,
+              summary.publisher_fingerprint, summary.standards_created + summary.standards_updated, summary.templates);
+        Ok(summary)
+    });
+
+    Ok(command_handler!("import_checklist_pack", &context, { result }))
+}
+
+/// Get the publishers whose checklist packs are trusted
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_trusted_pack_publishers_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Vec<TrustedPackPublisher>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_trusted_pack_publishers", {
+        require_resource_access!(context, "compliance", "read");
+
+        let publishers = state.services.checklist_packs.get_trusted_publishers()
+            .context("Failed to get trusted pack publishers")?;
+
+        debug!("[{}] Retrieved {} trusted pack publishers", context.request_id, publishers.len());
+        Ok(publishers)
+    });
+
+    Ok(command_handler!("get_trusted_pack_publishers", &context, { result }))
+}
+
+/// Trust checklist packs signed with a publisher's public key
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn trust_pack_publisher_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    publisher: TrustedPackPublisherInput,
+) -> CommandResult<TrustedPackPublisher> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("trust_pack_publisher", {
+        require_resource_access!(context, "system", "admin");
+
+        let publisher = state.services.checklist_packs.trust_publisher(&context, publisher)
+            .context("Failed to trust pack publisher")?;
+        AuthHelper::audit_action(&context, "trust", "pack_publisher", Some(&publisher.id.to_string()), true, None);
+
+        info!("[{}] Trusted pack publisher '{}' with key {}", context.request_id, publisher.name, publisher.fingerprint);
+        Ok(publisher)
+    });
+
+    Ok(command_handler!("trust_pack_publisher", &context, { result }))
+}
+
+/// Stop trusting a publisher's checklist packs; packs already imported stay
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn remove_trusted_pack_publisher_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    publisher_id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("remove_trusted_pack_publisher", {
+        require_resource_access!(context, "system", "admin");
+
+        state.services.checklist_packs.remove_trusted_publisher(&context, publisher_id)
+            .context("Failed to remove trusted pack publisher")?;
+        AuthHelper::audit_action(&context, "untrust", "pack_publisher", Some(&publisher_id.to_string()), true, None);
+
+        info!("[{}] Pack publisher {} no longer trusted", context.request_id, publisher_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("remove_trusted_pack_publisher", &context, { result }))
+}
@@ -0,0 +1,249 @@
+//! Comment command handlers
+//!
+//! This module contains Tauri command handlers for discussion threads on
+//! assets, inspections, findings and work orders. Users mentioned with
+//! `@username` are notified via the `comment-mention` event.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::middleware::RequestContext;
+use crate::models::{Comment, CommentEdit, CommentEntityType, CommentMention, CommentThread};
+use crate::services::SavedComment;
+use crate::trace;
+use crate::{require_resource_access, time_command, command_handler};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use log::{info, debug, warn};
+
+/// Event emitted for each user newly mentioned in a comment
+pub const COMMENT_MENTION_EVENT: &str = "comment-mention";
+
+/// Payload of the `comment-mention` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentMentionNotification {
+    pub user_id: i64,
+    pub comment_id: i64,
+    pub entity_type: CommentEntityType,
+    pub entity_id: i64,
+    pub author_name: String,
+}
+
+fn notify_mentions(app: &AppHandle, context: &RequestContext, saved: &SavedComment) {
+    for user_id in &saved.notified_user_ids {
+        let notification = CommentMentionNotification {
+            user_id: *user_id,
+            comment_id: saved.comment.id,
+            entity_type: saved.comment.entity_type,
+            entity_id: saved.comment.entity_id,
+            author_name: saved.comment.author_name.clone(),
+        };
+        if let Err(e) = trace::emit(app, COMMENT_MENTION_EVENT, notification) {
+            warn!("[{}] Failed to emit comment mention: {}", context.request_id, e);
+        }
+    }
+}
+
+/// Add a comment to a record, or a reply to an existing comment
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn add_comment_command(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    token: Option<String>,
+    entity_type: CommentEntityType,
+    entity_id: i64,
+    body: String,
+    parent_id: Option<i64>,
+) -> CommandResult<Comment> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("add_comment", {
+        require_resource_access!(context, entity_type.resource(), "read");
+
+        let saved = state.services.comments.add_comment(&context, entity_type, entity_id, parent_id, &body)
+            .context("Failed to add comment")?;
+        notify_mentions(&app, &context, &saved);
+
+        AuthHelper::audit_action(&context, "create", "comment", Some(&saved.comment.id.to_string()), true, None);
+        info!("[{}] Comment {} added on {} {}", context.request_id, saved.comment.id, entity_type, entity_id);
+
+        Ok(saved.comment)
+    });
+
+    Ok(command_handler!("add_comment", &context, { result }))
+}
+
+/// Get the comment threads on a record
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_comments_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    entity_type: CommentEntityType,
+    entity_id: i64,
+) -> CommandResult<Vec<CommentThread>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_comments", {
+        require_resource_access!(context, entity_type.resource(), "read");
+
+        let threads = state.services.comments.get_comments(entity_type, entity_id)
+            .context("Failed to get comments")?;
+
+        debug!("[{}] Retrieved {} comment threads for {} {}", context.request_id, threads.len(), entity_type, entity_id);
+
+        Ok(threads)
+    });
+
+    Ok(command_handler!("get_comments", &context, { result }))
+}
+
+/// Edit one of the current user's comments
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn edit_comment_command(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+    body: String,
+) -> CommandResult<Comment> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("edit_comment", {
+        let existing = state.services.comments.get_comment(id)
+            .context("Failed to get comment")?;
+        require_resource_access!(context, existing.entity_type.resource(), "read");
+
+        let saved = state.services.comments.edit_comment(&context, id, &body)
+            .context("Failed to edit comment")?;
+        notify_mentions(&app, &context, &saved);
+
+        AuthHelper::audit_action(&context, "update", "comment", Some(&id.to_string()), true, None);
+        info!("[{}] Comment {} edited", context.request_id, id);
+
+        Ok(saved.comment)
+    });
+
+    Ok(command_handler!("edit_comment", &context, { result }))
+}
+
+/// Delete a comment. Authors may delete their own comments; anyone who can
+/// update the commented record may delete any comment on it.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn delete_comment_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("delete_comment", {
+        let existing = state.services.comments.get_comment(id)
+            .context("Failed to get comment")?;
+        let is_author = context.current_user().map(|u| u.user_id == existing.author_id).unwrap_or(false);
+        if is_author {
+            require_resource_access!(context, existing.entity_type.resource(), "read");
+        } else {
+            require_resource_access!(context, existing.entity_type.resource(), "update");
+        }
+
+        state.services.comments.delete_comment(&context, id)
+            .context("Failed to delete comment")?;
+
+        AuthHelper::audit_action(&context, "delete", "comment", Some(&id.to_string()), true, None);
+        info!("[{}] Comment {} deleted", context.request_id, id);
+
+        Ok(())
+    });
+
+    Ok(command_handler!("delete_comment", &context, { result }))
+}
+
+/// Get the previous versions of a comment
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_comment_history_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<Vec<CommentEdit>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_comment_history", {
+        let existing = state.services.comments.get_comment(id)
+            .context("Failed to get comment")?;
+        require_resource_access!(context, existing.entity_type.resource(), "read");
+
+        let history = state.services.comments.get_comment_history(id)
+            .context("Failed to get comment history")?;
+
+        debug!("[{}] Retrieved {} edits for comment {}", context.request_id, history.len(), id);
+
+        Ok(history)
+    });
+
+    Ok(command_handler!("get_comment_history", &context, { result }))
+}
+
+/// Get comments mentioning the current user
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_my_mentions_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    unread_only: Option<bool>,
+) -> CommandResult<Vec<CommentMention>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_my_mentions", {
+        let user_id = context.current_user()?.user_id;
+
+        let mentions = state.services.comments.get_mentions(user_id, unread_only.unwrap_or(false))
+            .context("Failed to get mentions")?;
+
+        debug!("[{}] Retrieved {} mentions", context.request_id, mentions.len());
+
+        Ok(mentions)
+    });
+
+    Ok(command_handler!("get_my_mentions", &context, { result }))
+}
+
+/// Mark mentions of the current user as read; all of them when no comment
+/// IDs are given
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn mark_mentions_read_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    comment_ids: Option<Vec<i64>>,
+) -> CommandResult<usize> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("mark_mentions_read", {
+        let updated = state.services.comments.mark_mentions_read(&context, comment_ids.as_deref())
+            .context("Failed to mark mentions read")?;
+
+        debug!("[{}] Marked {} mentions read", context.request_id, updated);
+
+        Ok(updated)
+    });
+
+    Ok(command_handler!("mark_mentions_read", &context, { result }))
+}
@@ -3,12 +3,13 @@
 //! This module contains all Tauri command handlers for compliance management
 //! operations including compliance records, status tracking, and requirements.
 
-use crate::api::{ApiResponse, QueryFilterRequest, CreateComplianceRecordRequest,
+use crate::api::{QueryFilterRequest, CreateComplianceRecordRequest,
                 ComplianceRecordUpdateRequest, PaginatedResponse, ComplianceStatus,
                 ComplianceRequirement};
-use crate::commands::AppState;
+use crate::commands::{AppState, CommandResult, ErrorContext};
 use crate::middleware::auth::AuthHelper;
-use crate::models::{PaginatedResult};
+use crate::models::{ChecklistItemGuidance, ComplianceChecklistTemplate, InspectionType, PaginatedResult,
+                    StandardClause, StandardClauseInput};
 use crate::{require_resource_access, time_command, command_handler};
 use tauri::State;
 use log::{info, debug};
@@ -16,16 +17,17 @@ use chrono::Utc;
 
 /// Create a new compliance record
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn create_compliance_record_command(
     state: State<'_, AppState>,
     token: Option<String>,
     record_data: CreateComplianceRecordRequest,
-) -> Result<ApiResponse<serde_json::Value>, String> {
+) -> CommandResult<serde_json::Value> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("create_compliance_record", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "compliance", "update");
 
         // Create compliance record
@@ -44,30 +46,29 @@ pub async fn create_compliance_record_command(
             "updated_at": Utc::now()
         });
 
-        info!("Compliance record created for asset {} by user {}", 
+        info!("[{}] Compliance record created for asset {} by user {}", context.request_id,
               record_data.asset_id,
               context.current_user().map(|u| u.user_id).unwrap_or(0));
 
         Ok(record)
     });
 
-    Ok(command_handler!("create_compliance_record", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("create_compliance_record", &context, { result }))
 }
 
 /// Get compliance record by ID
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_compliance_record_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
-) -> Result<ApiResponse<serde_json::Value>, String> {
+) -> CommandResult<serde_json::Value> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_compliance_record", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "compliance", "read");
 
         // Get compliance record
@@ -85,28 +86,27 @@ pub async fn get_compliance_record_command(
             "updated_at": Utc::now()
         });
 
-        debug!("Compliance record retrieved: ID {}", id);
+        debug!("[{}] Compliance record retrieved: ID {}", context.request_id, id);
         Ok(record)
     });
 
-    Ok(command_handler!("get_compliance_record", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_compliance_record", &context, { result }))
 }
 
 /// Get compliance records by asset with filtering
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_compliance_records_by_asset_command(
     state: State<'_, AppState>,
     token: Option<String>,
     asset_id: i64,
     filter: QueryFilterRequest,
-) -> Result<ApiResponse<PaginatedResponse<serde_json::Value>>, String> {
+) -> CommandResult<PaginatedResponse<serde_json::Value>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_compliance_records_by_asset", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "compliance", "read");
 
         // Get compliance records with filters
@@ -139,31 +139,30 @@ pub async fn get_compliance_records_by_asset_command(
             filter.limit.unwrap_or(50)
         );
 
-        debug!("Retrieved {} compliance records for asset {}", 
+        debug!("[{}] Retrieved {} compliance records for asset {}", context.request_id,
                paginated_result.data.len(), asset_id);
 
         let response = PaginatedResponse::from(paginated_result);
         Ok(response)
     });
 
-    Ok(command_handler!("get_compliance_records_by_asset", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_compliance_records_by_asset", &context, { result }))
 }
 
 /// Update compliance record
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn update_compliance_record_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
     updates: ComplianceRecordUpdateRequest,
-) -> Result<ApiResponse<serde_json::Value>, String> {
+) -> CommandResult<serde_json::Value> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("update_compliance_record", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "compliance", "update");
 
         // Update compliance record
@@ -180,29 +179,28 @@ pub async fn update_compliance_record_command(
             "updated_at": Utc::now()
         });
 
-        info!("Compliance record updated: ID {} by user {}", 
+        info!("[{}] Compliance record updated: ID {} by user {}", context.request_id,
               id, context.current_user().map(|u| u.user_id).unwrap_or(0));
 
         Ok(updated_record)
     });
 
-    Ok(command_handler!("update_compliance_record", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("update_compliance_record", &context, { result }))
 }
 
 /// Get compliance status for an asset
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_compliance_status_command(
     state: State<'_, AppState>,
     token: Option<String>,
     asset_id: i64,
-) -> Result<ApiResponse<ComplianceStatus>, String> {
+) -> CommandResult<ComplianceStatus> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_compliance_status", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "compliance", "read");
 
         // Get compliance status
@@ -217,29 +215,28 @@ pub async fn get_compliance_status_command(
             pending_actions: 2,
         };
 
-        debug!("Compliance status retrieved for asset {}: {}", 
+        debug!("[{}] Compliance status retrieved for asset {}: {}", context.request_id,
                asset_id, compliance_status.overall_status);
 
         Ok(compliance_status)
     });
 
-    Ok(command_handler!("get_compliance_status", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_compliance_status", &context, { result }))
 }
 
 /// Get upcoming compliance requirements
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_upcoming_requirements_command(
     state: State<'_, AppState>,
     token: Option<String>,
     days_ahead: Option<i32>,
-) -> Result<ApiResponse<Vec<ComplianceRequirement>>, String> {
+) -> CommandResult<Vec<ComplianceRequirement>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_upcoming_requirements", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "compliance", "read");
 
         let days = days_ahead.unwrap_or(30);
@@ -265,29 +262,28 @@ pub async fn get_upcoming_requirements_command(
             },
         ];
 
-        debug!("Retrieved {} upcoming compliance requirements for {} days ahead", 
+        debug!("[{}] Retrieved {} upcoming compliance requirements for {} days ahead", context.request_id,
                requirements.len(), days);
 
         Ok(requirements)
     });
 
-    Ok(command_handler!("get_upcoming_requirements", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_upcoming_requirements", &context, { result }))
 }
 
 /// Mark compliance record as complete
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn mark_compliance_complete_command(
     state: State<'_, AppState>,
     token: Option<String>,
     record_id: i64,
-) -> Result<ApiResponse<serde_json::Value>, String> {
+) -> CommandResult<serde_json::Value> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("mark_compliance_complete", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "compliance", "verify");
 
         // Mark compliance as complete
@@ -305,13 +301,149 @@ pub async fn mark_compliance_complete_command(
             "updated_at": Utc::now()
         });
 
-        info!("Compliance record {} marked complete by user {}", 
+        info!("[{}] Compliance record {} marked complete by user {}", context.request_id,
               record_id, context.current_user().map(|u| u.user_id).unwrap_or(0));
 
         Ok(completed_record)
     });
 
-    Ok(command_handler!("mark_compliance_complete", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
-}
\ No newline at end of file
+    Ok(command_handler!("mark_compliance_complete", &context, { result }))
+}
+/// Generate the checklist for an inspection type under a standard, with the
+/// acceptance criteria, clause references and reference photos for each item
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn generate_inspection_checklist_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    standard_id: i64,
+    inspection_type: InspectionType,
+) -> CommandResult<serde_json::Value> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("generate_inspection_checklist", {
+        require_resource_access!(context, "compliance", "read");
+
+        let checklist = state.services.compliance.generate_inspection_checklist(standard_id, inspection_type)
+            .context("Failed to generate inspection checklist")?;
+
+        debug!("[{}] Checklist generated for standard {}", context.request_id, standard_id);
+        Ok(checklist)
+    });
+
+    Ok(command_handler!("generate_inspection_checklist", &context, { result }))
+}
+
+/// Set the acceptance criteria, clause references and reference photos of a
+/// checklist template item
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn set_checklist_item_guidance_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    template_id: i64,
+    item_name: String,
+    guidance: ChecklistItemGuidance,
+) -> CommandResult<ComplianceChecklistTemplate> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("set_checklist_item_guidance", {
+        require_resource_access!(context, "compliance", "update");
+
+        let template = state.services.compliance
+            .set_checklist_item_guidance(&context, template_id, &item_name, guidance)
+            .context("Failed to set checklist item guidance")?;
+        AuthHelper::audit_action(&context, "set_checklist_item_guidance", "compliance",
+                                 Some(&template_id.to_string()), true, None);
+
+        info!("[{}] Guidance set for item '{}' of checklist template {}", context.request_id, item_name, template_id);
+        Ok(template)
+    });
+
+    Ok(command_handler!("set_checklist_item_guidance", &context, { result }))
+}
+
+/// Add clauses to a standard's catalog so findings can cite them, replacing
+/// any with the same reference
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn import_standard_clauses_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    standard_id: i64,
+    clauses: Vec<StandardClauseInput>,
+) -> CommandResult<Vec<StandardClause>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("import_standard_clauses", {
+        require_resource_access!(context, "compliance", "update");
+
+        let clauses = state.services.compliance.import_standard_clauses(&context, standard_id, clauses)
+            .context("Failed to import standard clauses")?;
+        AuthHelper::audit_action(&context, "import_standard_clauses", "compliance",
+                                 Some(&standard_id.to_string()), true, None);
+
+        info!("[{}] Imported {} clauses for standard {}", context.request_id, clauses.len(), standard_id);
+        Ok(clauses)
+    });
+
+    Ok(command_handler!("import_standard_clauses", &context, { result }))
+}
+
+/// Get the clause catalog of a standard
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_standard_clauses_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    standard_id: i64,
+) -> CommandResult<Vec<StandardClause>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_standard_clauses", {
+        require_resource_access!(context, "compliance", "read");
+
+        let clauses = state.services.compliance.get_standard_clauses(standard_id)
+            .context("Failed to get standard clauses")?;
+
+        debug!("[{}] Retrieved {} clauses for standard {}", context.request_id, clauses.len(), standard_id);
+        Ok(clauses)
+    });
+
+    Ok(command_handler!("get_standard_clauses", &context, { result }))
+}
+
+/// Remove a clause no finding cites from its standard's catalog
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn delete_standard_clause_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    clause_id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("delete_standard_clause", {
+        require_resource_access!(context, "compliance", "update");
+
+        state.services.compliance.delete_standard_clause(&context, clause_id)
+            .context("Failed to delete standard clause")?;
+        AuthHelper::audit_action(&context, "delete_standard_clause", "compliance",
+                                 Some(&clause_id.to_string()), true, None);
+
+        info!("[{}] Standard clause {} deleted", context.request_id, clause_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("delete_standard_clause", &context, { result }))
+}
@@ -0,0 +1,259 @@
+//! Corrective action command handlers
+//!
+//! This module contains Tauri command handlers for corrective actions taken
+//! on findings. Each action moves Open → In Progress → Pending Verification
+//! and is closed only when a supervisor verifies the evidence photos.
+
+use crate::commands::{log_notifications, AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{CorrectiveAction, CorrectiveActionFilter, CorrectiveActionInput, CorrectiveActionUpdateData};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info};
+
+/// Create a corrective action for a non-compliant inspection item
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn create_corrective_action_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    inspection_item_id: i64,
+    action: Option<CorrectiveActionInput>,
+) -> CommandResult<CorrectiveAction> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("create_corrective_action", {
+        require_resource_access!(context, "inspection", "update");
+
+        let action = state.services.corrective_actions
+            .create_from_item(&context, inspection_item_id, action.unwrap_or_default())
+            .context("Failed to create corrective action")?;
+        AuthHelper::audit_action(&context, "create_corrective_action", "corrective_action", Some(&action.id.to_string()), true, None);
+        log_notifications(&context, state.services.notifications.corrective_action_assigned(&context, &action, None));
+
+        info!("[{}] Corrective action {} created for inspection item {}",
+              context.request_id, action.id, inspection_item_id);
+        Ok(action)
+    });
+
+    Ok(command_handler!("create_corrective_action", &context, { result }))
+}
+
+/// Change the description, assignee or due date of an open corrective action
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn update_corrective_action_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    action_id: i64,
+    updates: CorrectiveActionUpdateData,
+) -> CommandResult<CorrectiveAction> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("update_corrective_action", {
+        require_resource_access!(context, "inspection", "update");
+
+        let previous_assignee_id = state.services.corrective_actions.get_action(action_id)
+            .context("Failed to update corrective action")?
+            .assignee_id;
+        let action = state.services.corrective_actions.update_action(&context, action_id, updates)
+            .context("Failed to update corrective action")?;
+        AuthHelper::audit_action(&context, "update_corrective_action", "corrective_action", Some(&action_id.to_string()), true, None);
+        log_notifications(&context,
+            state.services.notifications.corrective_action_assigned(&context, &action, previous_assignee_id));
+
+        info!("[{}] Corrective action {} updated", context.request_id, action_id);
+        Ok(action)
+    });
+
+    Ok(command_handler!("update_corrective_action", &context, { result }))
+}
+
+/// Attach an uploaded photo as evidence of the corrective work
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn attach_corrective_action_evidence_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    action_id: i64,
+    media_id: i64,
+) -> CommandResult<CorrectiveAction> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("attach_corrective_action_evidence", {
+        require_resource_access!(context, "inspection", "update");
+
+        let action = state.services.corrective_actions.attach_evidence(&context, action_id, media_id)
+            .context("Failed to attach evidence")?;
+        AuthHelper::audit_action(&context, "attach_corrective_action_evidence", "corrective_action", Some(&action_id.to_string()), true, None);
+
+        info!("[{}] Media {} attached to corrective action {}", context.request_id, media_id, action_id);
+        Ok(action)
+    });
+
+    Ok(command_handler!("attach_corrective_action_evidence", &context, { result }))
+}
+
+/// Start work on a corrective action
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn start_corrective_action_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    action_id: i64,
+) -> CommandResult<CorrectiveAction> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("start_corrective_action", {
+        require_resource_access!(context, "inspection", "update");
+
+        let action = state.services.corrective_actions.start(&context, action_id)
+            .context("Failed to start corrective action")?;
+        AuthHelper::audit_action(&context, "start_corrective_action", "corrective_action", Some(&action_id.to_string()), true, None);
+
+        info!("[{}] Corrective action {} started", context.request_id, action_id);
+        Ok(action)
+    });
+
+    Ok(command_handler!("start_corrective_action", &context, { result }))
+}
+
+/// Submit finished corrective work for supervisor verification
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn submit_corrective_action_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    action_id: i64,
+    notes: Option<String>,
+) -> CommandResult<CorrectiveAction> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("submit_corrective_action", {
+        require_resource_access!(context, "inspection", "update");
+
+        let action = state.services.corrective_actions.submit_for_verification(&context, action_id, notes)
+            .context("Failed to submit corrective action")?;
+        AuthHelper::audit_action(&context, "submit_corrective_action", "corrective_action", Some(&action_id.to_string()), true, None);
+
+        info!("[{}] Corrective action {} submitted for verification", context.request_id, action_id);
+        Ok(action)
+    });
+
+    Ok(command_handler!("submit_corrective_action", &context, { result }))
+}
+
+/// Close a submitted corrective action, or send it back with notes
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn verify_corrective_action_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    action_id: i64,
+    approved: bool,
+    notes: Option<String>,
+) -> CommandResult<CorrectiveAction> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("verify_corrective_action", {
+        require_resource_access!(context, "compliance", "update");
+
+        let action = state.services.corrective_actions.verify(&context, action_id, approved, notes)
+            .context("Failed to verify corrective action")?;
+        AuthHelper::audit_action(&context, "verify_corrective_action", "corrective_action", Some(&action_id.to_string()), true, None);
+
+        info!("[{}] Corrective action {} is now {}", context.request_id, action_id, action.status);
+        Ok(action)
+    });
+
+    Ok(command_handler!("verify_corrective_action", &context, { result }))
+}
+
+/// Cancel a corrective action that has not been submitted
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn cancel_corrective_action_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    action_id: i64,
+    reason: String,
+) -> CommandResult<CorrectiveAction> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("cancel_corrective_action", {
+        require_resource_access!(context, "inspection", "update");
+
+        let action = state.services.corrective_actions.cancel(&context, action_id, reason)
+            .context("Failed to cancel corrective action")?;
+        AuthHelper::audit_action(&context, "cancel_corrective_action", "corrective_action", Some(&action_id.to_string()), true, None);
+
+        info!("[{}] Corrective action {} cancelled", context.request_id, action_id);
+        Ok(action)
+    });
+
+    Ok(command_handler!("cancel_corrective_action", &context, { result }))
+}
+
+/// Get a corrective action
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_corrective_action_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    action_id: i64,
+) -> CommandResult<CorrectiveAction> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_corrective_action", {
+        require_resource_access!(context, "inspection", "read");
+
+        let action = state.services.corrective_actions.get_action(action_id)
+            .context("Failed to get corrective action")?;
+
+        debug!("[{}] Retrieved corrective action {}", context.request_id, action_id);
+        Ok(action)
+    });
+
+    Ok(command_handler!("get_corrective_action", &context, { result }))
+}
+
+/// List corrective actions, soonest due first
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_corrective_actions_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    filter: Option<CorrectiveActionFilter>,
+) -> CommandResult<Vec<CorrectiveAction>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_corrective_actions", {
+        require_resource_access!(context, "inspection", "read");
+
+        let actions = state.services.corrective_actions.get_actions(&filter.unwrap_or_default())
+            .context("Failed to get corrective actions")?;
+
+        debug!("[{}] Retrieved {} corrective actions", context.request_id, actions.len());
+        Ok(actions)
+    });
+
+    Ok(command_handler!("get_corrective_actions", &context, { result }))
+}
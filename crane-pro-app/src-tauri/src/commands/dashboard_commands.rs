@@ -0,0 +1,143 @@
+//! Dashboard command handlers
+//!
+//! This module contains the Tauri command handlers that assemble dashboard
+//! data in a single call, so the frontend does not need one request per widget.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{Dashboard, DashboardSummary, FleetBenchmarks, SinceLastLogin, WorkloadForecast};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::debug;
+
+/// Get the dashboard for the caller's role: pending and overdue work for
+/// inspectors, team statistics and the review queue for supervisors, and
+/// system health, storage and compliance KPIs for administrators
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_dashboard_summary_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<DashboardSummary> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_dashboard_summary", {
+        let summary = state.services.dashboard.get_summary(&context)
+            .context("Failed to get dashboard summary")?;
+
+        debug!("[{}] Dashboard summary retrieved", context.request_id);
+        Ok(summary)
+    });
+
+    Ok(command_handler!("get_dashboard_summary", &context, { result }))
+}
+
+/// Get the caller's role dashboard together with the fleet KPIs: open and
+/// overdue inspections, compliance by location, the weekly critical findings
+/// trend and the workload scheduled over the next 30 days
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_dashboard_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Dashboard> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_dashboard", {
+        require_resource_access!(context, "report", "read");
+
+        let dashboard = state.services.dashboard.get_dashboard(&context)
+            .context("Failed to get dashboard")?;
+
+        debug!("[{}] Dashboard retrieved: {} open inspections, {} overdue", context.request_id,
+               dashboard.kpis.open_inspections, dashboard.kpis.overdue_inspections);
+        Ok(dashboard)
+    });
+
+    Ok(command_handler!("get_dashboard", &context, { result }))
+}
+
+/// Get the inspection and maintenance workload projected over the next
+/// `months` months (12 by default), as counts and estimated hours by month,
+/// location and type, to support staffing decisions
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_workload_forecast_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    months: Option<u32>,
+) -> CommandResult<WorkloadForecast> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_workload_forecast", {
+        require_resource_access!(context, "report", "read");
+
+        let forecast = state.services.dashboard.get_workload_forecast(months)
+            .context("Failed to get workload forecast")?;
+
+        debug!("[{}] Workload forecast retrieved: {} entries over {} months", context.request_id,
+               forecast.entries.len(), forecast.months.len());
+        Ok(forecast)
+    });
+
+    Ok(command_handler!("get_workload_forecast", &context, { result }))
+}
+
+/// Get cross-fleet benchmarks for the executive dashboard: findings per
+/// inspection by manufacturer and model, average compliance by location and
+/// the asset age versus condition curve
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_fleet_benchmarks_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<FleetBenchmarks> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_fleet_benchmarks", {
+        require_resource_access!(context, "report", "read");
+
+        let benchmarks = state.services.dashboard.get_fleet_benchmarks()
+            .context("Failed to get fleet benchmarks")?;
+
+        debug!("[{}] Fleet benchmarks retrieved: {} models, {} locations", context.request_id,
+               benchmarks.findings_by_model.len(), benchmarks.compliance_by_location.len());
+        Ok(benchmarks)
+    });
+
+    Ok(command_handler!("get_fleet_benchmarks", &context, { result }))
+}
+
+/// Get what changed for the caller since their previous session: new
+/// assignments, findings on watched assets, corrective actions waiting for
+/// verification and rescheduled inspections
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_since_last_login_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<SinceLastLogin> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_since_last_login", {
+        let changes = state.services.dashboard.get_since_last_login(&context)
+            .context("Failed to get changes since last login")?;
+
+        debug!("[{}] Changes since {}: {} assignments, {} findings, {} approvals, {} schedule changes",
+               context.request_id, changes.since, changes.new_assignments.len(), changes.watched_findings.len(),
+               changes.pending_approval_count, changes.schedule_changes.len());
+        Ok(changes)
+    });
+
+    Ok(command_handler!("get_since_last_login", &context, { result }))
+}
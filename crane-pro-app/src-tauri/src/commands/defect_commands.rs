@@ -0,0 +1,97 @@
+//! Defect command handlers
+//!
+//! This module contains Tauri command handlers for defects: findings that
+//! are followed across inspections from the item that found them until
+//! their repair is verified.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{Defect, DefectDetail, DefectFilter, DefectStatus};
+use crate::{require_resource_access, time_command, command_handler};
+use chrono::{DateTime, Utc};
+use tauri::State;
+use log::{debug, info};
+
+/// List defects, the most severe and oldest first
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_defects_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    filter: Option<DefectFilter>,
+) -> CommandResult<Vec<Defect>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_defects", {
+        require_resource_access!(context, "inspection", "read");
+
+        let defects = state.services.defects.get_defects(&filter.unwrap_or_default())
+            .context("Failed to get defects")?;
+
+        debug!("[{}] Retrieved {} defects", context.request_id, defects.len());
+        Ok(defects)
+    });
+
+    Ok(command_handler!("get_defects", &context, { result }))
+}
+
+/// Get a defect with the inspections that recorded it and its severity history
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_defect_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    defect_id: i64,
+) -> CommandResult<DefectDetail> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_defect", {
+        require_resource_access!(context, "inspection", "read");
+
+        let defect = state.services.defects.get_defect(defect_id)
+            .context("Failed to get defect")?;
+
+        debug!("[{}] Retrieved defect {} with {} observations", context.request_id,
+               defect_id, defect.observations.len());
+        Ok(defect)
+    });
+
+    Ok(command_handler!("get_defect", &context, { result }))
+}
+
+/// Move a defect along its lifecycle; deferring and verifying need
+/// compliance sign-off
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn update_defect_status_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    defect_id: i64,
+    status: DefectStatus,
+    deferred_until: Option<DateTime<Utc>>,
+    reason: Option<String>,
+) -> CommandResult<Defect> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("update_defect_status", {
+        require_resource_access!(context, "inspection", "update");
+        if matches!(status, DefectStatus::Deferred | DefectStatus::Verified) {
+            require_resource_access!(context, "compliance", "update");
+        }
+
+        let defect = state.services.defects.update_status(&context, defect_id, status, deferred_until, reason)
+            .context("Failed to change defect status")?;
+        AuthHelper::audit_action(&context, "update_defect_status", "defect", Some(&defect_id.to_string()), true, None);
+
+        info!("[{}] Defect {} is now {}", context.request_id, defect_id, defect.status);
+        Ok(defect)
+    });
+
+    Ok(command_handler!("update_defect_status", &context, { result }))
+}
@@ -0,0 +1,175 @@
+//! Data export command handlers
+//!
+//! This module contains Tauri command handlers for full dataset exports.
+//! Rows are streamed to disk as they are read and progress is reported via
+//! the `export-progress` event. Star-schema extracts for data warehouses
+//! are written on a schedule and can also be requested here.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::export::{
+    export_to_file, ExportFormat, ExportKind, ExportProgress, ExportResult, EXPORT_PROGRESS_EVENT,
+};
+use crate::middleware::auth::AuthHelper;
+use crate::operations::{OperationKind, OperationRegistry};
+use crate::warehouse::WarehouseManifest;
+use crate::trace;
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::{AppHandle, State};
+use log::{info, warn};
+use chrono::Utc;
+use std::fs;
+use std::path::Path;
+
+/// Export an entire dataset (assets, inspections or audit log) to a file
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn export_data_command(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    operations: State<'_, OperationRegistry>,
+    token: Option<String>,
+    kind: ExportKind,
+    format: ExportFormat,
+) -> CommandResult<ExportResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("export_data", {
+        match kind {
+            ExportKind::Assets => {
+                require_resource_access!(context, "report", "export");
+                require_resource_access!(context, "asset", "read");
+            }
+            ExportKind::Inspections => {
+                require_resource_access!(context, "report", "export");
+                require_resource_access!(context, "inspection", "read");
+            }
+            ExportKind::Audit => require_resource_access!(context, "system", "audit"),
+        }
+
+        let export_id = format!("{}_{}", kind, Utc::now().format("%Y%m%d_%H%M%S"));
+
+        // Create exports directory
+        let exports_dir = "./data/exports";
+        fs::create_dir_all(exports_dir)
+            .context("Failed to create exports directory")?;
+        let file_path = format!("{}/{}.{}", exports_dir, export_id, format.extension());
+
+        // Row counts for the progress bar
+        let total_rows = match kind {
+            ExportKind::Assets => Some(state.services.assets.count_assets()?),
+            ExportKind::Inspections => Some(state.services.inspections.count_inspections()?),
+            ExportKind::Audit => Some(state.services.audit.count_entries()?),
+        };
+        let emit_progress = |rows_written: u64, finished: bool| {
+            let progress = ExportProgress {
+                export_id: export_id.clone(),
+                kind,
+                rows_written,
+                total_rows,
+                finished,
+            };
+            if let Err(e) = trace::emit(&app, EXPORT_PROGRESS_EVENT, progress) {
+                warn!("[{}] Failed to emit export progress: {}", context.request_id, e);
+            }
+        };
+
+        let operation = operations.start(OperationKind::DataExport, format!("Export {} to {}", kind, format.extension()),
+                                         context.current_user().map(|u| u.user_id).ok());
+        operation.set_progress(0, total_rows);
+
+        let path = Path::new(&file_path);
+        let on_progress = |rows| {
+            emit_progress(rows, false);
+            operation.set_progress(rows, None);
+            operation.check_cancelled()
+        };
+        let outcome = match kind {
+            ExportKind::Assets => export_to_file(path, format,
+                |visit| state.services.assets.stream_assets(visit), on_progress),
+            ExportKind::Inspections => export_to_file(path, format,
+                |visit| state.services.inspections.stream_inspections(visit), on_progress),
+            ExportKind::Audit => export_to_file(path, format,
+                |visit| state.services.audit.stream_entries(visit), on_progress),
+        };
+        if let Ok(rows) = &outcome {
+            operation.set_progress(*rows, None);
+        }
+        operation.finish(&outcome);
+        let rows_written = outcome.with_context(|| format!("Failed to export {}", kind))?;
+        emit_progress(rows_written, true);
+
+        AuthHelper::audit_action(&context, "export", &kind.to_string(), Some(&export_id), true, None);
+        info!("[{}] Exported {} {} rows to {} by user {}", context.request_id,
+              rows_written, kind, file_path,
+              context.current_user().map(|u| u.user_id).unwrap_or(0));
+
+        Ok(ExportResult {
+            export_id,
+            kind,
+            format,
+            file_path,
+            rows_written,
+            generated_at: Utc::now(),
+        })
+    });
+
+    Ok(command_handler!("export_data", &context, { result }))
+}
+
+/// Write a star-schema extract (inspection and finding facts, asset and date
+/// dimensions) to the configured data warehouse directory now, without
+/// waiting for the schedule. CSV unless another format is given.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn run_warehouse_export_command(
+    state: State<'_, AppState>,
+    operations: State<'_, OperationRegistry>,
+    token: Option<String>,
+    format: Option<ExportFormat>,
+) -> CommandResult<WarehouseManifest> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("run_warehouse_export", {
+        require_resource_access!(context, "report", "export");
+        require_resource_access!(context, "system", "settings");
+
+        let operation = operations.start(OperationKind::WarehouseExport, "Data warehouse extract",
+                                         context.current_user().map(|u| u.user_id).ok());
+        let outcome = state.services.warehouse.export_now(&context, format.unwrap_or(ExportFormat::Csv), &operation);
+        operation.finish(&outcome);
+        let manifest = outcome.context("Failed to write data warehouse extract")?;
+        AuthHelper::audit_action(&context, "export", "warehouse", Some(&manifest.extract_directory), true, None);
+
+        info!("[{}] Data warehouse extract written to {} by user {}", context.request_id,
+              manifest.extract_directory, context.current_user().map(|u| u.user_id).unwrap_or(0));
+        Ok(manifest)
+    });
+
+    Ok(command_handler!("run_warehouse_export", &context, { result }))
+}
+
+/// Get the manifest of the latest data warehouse extract, if any
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_warehouse_export_status_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Option<WarehouseManifest>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_warehouse_export_status", {
+        require_resource_access!(context, "report", "export");
+
+        let manifest = state.services.warehouse.latest_extract()
+            .context("Failed to read data warehouse manifest")?;
+        Ok(manifest)
+    });
+
+    Ok(command_handler!("get_warehouse_export_status", &context, { result }))
+}
@@ -0,0 +1,173 @@
+//! Critical finding SLA command handlers
+//!
+//! This module contains Tauri command handlers for acknowledging and
+//! resolving critical findings against their SLA targets, configuring the
+//! targets, and the SLA performance report per location.
+
+use crate::api::DateRange;
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{FindingSla, SlaPerformanceReport, SlaTarget};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info};
+
+/// Acknowledge a critical finding, stopping its acknowledgement clock
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn acknowledge_critical_finding_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    item_id: i64,
+) -> CommandResult<FindingSla> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("acknowledge_critical_finding", {
+        require_resource_access!(context, "inspection", "update");
+
+        let sla = state.services.finding_slas.acknowledge(&context, item_id)
+            .context("Failed to acknowledge critical finding")?;
+        AuthHelper::audit_action(&context, "acknowledge_critical_finding", "inspection",
+                                 Some(&item_id.to_string()), true, None);
+
+        info!("[{}] Critical finding {} acknowledged", context.request_id, item_id);
+        Ok(sla)
+    });
+
+    Ok(command_handler!("acknowledge_critical_finding", &context, { result }))
+}
+
+/// Mark a critical finding resolved, stopping its resolution clock
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn resolve_critical_finding_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    item_id: i64,
+    notes: Option<String>,
+) -> CommandResult<FindingSla> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("resolve_critical_finding", {
+        require_resource_access!(context, "compliance", "update");
+
+        let sla = state.services.finding_slas.resolve(&context, item_id, notes)
+            .context("Failed to resolve critical finding")?;
+        AuthHelper::audit_action(&context, "resolve_critical_finding", "inspection",
+                                 Some(&item_id.to_string()), true, None);
+
+        info!("[{}] Critical finding {} resolved", context.request_id, item_id);
+        Ok(sla)
+    });
+
+    Ok(command_handler!("resolve_critical_finding", &context, { result }))
+}
+
+/// Get the SLA clocks of critical findings, optionally only unresolved ones
+/// or those at one location
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_finding_slas_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    open_only: Option<bool>,
+    location_id: Option<i64>,
+) -> CommandResult<Vec<FindingSla>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_finding_slas", {
+        require_resource_access!(context, "inspection", "read");
+
+        let findings = state.services.finding_slas.get_findings(open_only.unwrap_or(true), location_id)
+            .context("Failed to get finding SLAs")?;
+
+        debug!("[{}] Retrieved {} finding SLAs", context.request_id, findings.len());
+        Ok(findings)
+    });
+
+    Ok(command_handler!("get_finding_slas", &context, { result }))
+}
+
+/// Get the acknowledgement and resolution targets for critical findings
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_sla_targets_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Vec<SlaTarget>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_sla_targets", {
+        require_resource_access!(context, "compliance", "read");
+
+        let targets = state.services.finding_slas.get_targets()
+            .context("Failed to get SLA targets")?;
+
+        debug!("[{}] Retrieved {} SLA targets", context.request_id, targets.len());
+        Ok(targets)
+    });
+
+    Ok(command_handler!("get_sla_targets", &context, { result }))
+}
+
+/// Change SLA targets for critical findings recorded from now on
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn set_sla_targets_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    targets: Vec<SlaTarget>,
+) -> CommandResult<Vec<SlaTarget>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("set_sla_targets", {
+        require_resource_access!(context, "compliance", "update");
+
+        let targets = state.services.finding_slas.set_targets(&context, targets)
+            .context("Failed to set SLA targets")?;
+        AuthHelper::audit_action(&context, "set_sla_targets", "compliance", None, true, None);
+
+        info!("[{}] SLA targets updated", context.request_id);
+        Ok(targets)
+    });
+
+    Ok(command_handler!("set_sla_targets", &context, { result }))
+}
+
+/// Get how each location met the SLA targets for critical findings recorded
+/// in the date range
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_sla_performance_report_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    date_range: DateRange,
+) -> CommandResult<SlaPerformanceReport> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_sla_performance_report", {
+        require_resource_access!(context, "report", "generate");
+
+        let report = state.services.finding_slas
+            .get_performance_report(date_range.start_date, date_range.end_date)
+            .context("Failed to generate SLA performance report")?;
+        AuthHelper::audit_action(&context, "generate_sla_performance_report", "report", None, true, None);
+
+        info!("[{}] SLA performance report generated for {} locations", context.request_id, report.locations.len());
+        Ok(report)
+    });
+
+    Ok(command_handler!("get_sla_performance_report", &context, { result }))
+}
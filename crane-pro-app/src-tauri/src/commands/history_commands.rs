@@ -0,0 +1,41 @@
+//! Entity history command handlers
+//!
+//! This module contains the Tauri command handler for reading the change
+//! history of assets, inspections and users: who changed each record, when,
+//! and which fields changed.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{EntityHistoryEntry, HistoryEntityType, DEFAULT_HISTORY_LIMIT, MAX_HISTORY_LIMIT};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::debug;
+
+/// Get the recorded changes to a record, newest first
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_entity_history_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    entity_type: HistoryEntityType,
+    entity_id: i64,
+    limit: Option<usize>,
+) -> CommandResult<Vec<EntityHistoryEntry>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_entity_history", {
+        require_resource_access!(context, entity_type.resource(), "read");
+
+        let limit = limit.unwrap_or(DEFAULT_HISTORY_LIMIT).clamp(1, MAX_HISTORY_LIMIT);
+        let entries = state.services.history.get_history(entity_type, entity_id, limit)
+            .context("Failed to get history")?;
+
+        debug!("[{}] Retrieved {} history entries for {} {}", context.request_id,
+               entries.len(), entity_type, entity_id);
+        Ok(entries)
+    });
+
+    Ok(command_handler!("get_entity_history", &context, { result }))
+}
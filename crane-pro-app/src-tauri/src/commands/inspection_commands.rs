@@ -3,88 +3,105 @@
 //! This module contains all Tauri command handlers for inspection management
 //! operations including CRUD operations for inspections and inspection items.
 
-use crate::api::{ApiResponse, QueryFilterRequest, CreateInspectionRequest, InspectionUpdateRequest,
+use crate::api::{QueryFilterRequest, CreateInspectionRequest, InspectionUpdateRequest,
                 CreateInspectionItemRequest, InspectionItemUpdateRequest, PaginatedResponse};
-use crate::commands::AppState;
+use crate::commands::{log_notifications, notify_watchers, run_idempotent, AppState, CommandResult, ErrorContext};
 use crate::middleware::auth::AuthHelper;
-use crate::models::{Inspection, InspectionItem};
-use crate::services::{InspectionUpdateData, InspectionItemUpdateData};
+use crate::models::{Inspection, InspectionItem, PaperTranscription, PaperTranscriptionInput};
+use crate::services::{IdempotencyService, InspectionUpdateData, InspectionItemUpdateData};
 use crate::{require_resource_access, time_command, command_handler};
-use tauri::State;
-use log::{info, debug};
+use tauri::{AppHandle, State};
+use log::{info, debug, warn};
 
 /// Create a new inspection
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn create_inspection_command(
     state: State<'_, AppState>,
     token: Option<String>,
     inspection_data: CreateInspectionRequest,
-) -> Result<ApiResponse<Inspection>, String> {
+    idempotency_key: Option<String>,
+) -> CommandResult<Inspection> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("create_inspection", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "inspection", "create");
 
-        // Create inspection
-        let inspection = inspection_data.to_inspection();
-        let created_inspection = state.services.inspections.create_inspection(inspection)
-            .map_err(|e| format!("Failed to create inspection: {}", e))?;
+        let request_json = serde_json::to_vec(&inspection_data)
+            .context("Invalid inspection request")?;
+        let request_hash = IdempotencyService::fingerprint(&[&request_json]);
+
+        // Create inspection, or replay the result of an earlier identical request
+        let created_inspection = run_idempotent(&state, &context, "create_inspection", idempotency_key.as_deref(), &request_hash, || {
+            let mut inspection = inspection_data.to_inspection();
+            if inspection.compliance_standard.trim().is_empty() {
+                inspection.compliance_standard = state.services.settings.get_settings()
+                    .context("Failed to read default compliance standard")?
+                    .default_compliance_standard;
+            }
+            let created_inspection = state.services.inspections.create_inspection(&context, inspection)
+                .context("Failed to create inspection")?;
+            AuthHelper::audit_action(&context, "create", "inspection", Some(&created_inspection.id.to_string()), true, None);
+            log_notifications(&context,
+                state.services.notifications.inspection_assigned(&context, &created_inspection, None));
 
-        info!("Inspection created: ID {} for asset {} by user {}", 
-              created_inspection.id,
-              created_inspection.asset_id,
-              context.current_user().map(|u| u.user_id).unwrap_or(0));
+            info!("[{}] Inspection created: ID {} for asset {} by user {}", context.request_id,
+                  created_inspection.id,
+                  created_inspection.asset_id,
+                  context.current_user().map(|u| u.user_id).unwrap_or(0));
+
+            Ok(created_inspection)
+        })?;
 
         Ok(created_inspection)
     });
 
-    Ok(command_handler!("create_inspection", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("create_inspection", &context, { result }))
 }
 
 /// Get inspection by ID
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_inspection_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
-) -> Result<ApiResponse<Inspection>, String> {
+) -> CommandResult<Inspection> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_inspection", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "inspection", "read");
 
         // Get inspection
         let inspection = state.services.inspections.get_inspection_by_id(id)
-            .map_err(|e| format!("Failed to get inspection: {}", e))?;
+            .context("Failed to get inspection")?;
 
-        debug!("Inspection retrieved: ID {} for asset {}", id, inspection.asset_id);
+        debug!("[{}] Inspection retrieved: ID {} for asset {}", context.request_id, id, inspection.asset_id);
         Ok(inspection)
     });
 
-    Ok(command_handler!("get_inspection", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_inspection", &context, { result }))
 }
 
 /// Update inspection
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn update_inspection_command(
+    app: AppHandle,
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
     updates: InspectionUpdateRequest,
-) -> Result<ApiResponse<Inspection>, String> {
+) -> CommandResult<Inspection> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("update_inspection", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "inspection", "update");
 
         // Convert request to service update data
@@ -102,94 +119,183 @@ pub async fn update_inspection_command(
         };
 
         // Update inspection
-        let updated_inspection = state.services.inspections.update_inspection(id, update_data)
-            .map_err(|e| format!("Failed to update inspection: {}", e))?;
-
-        info!("Inspection updated: ID {} by user {}", 
+        let previous = state.services.inspections.get_inspection_by_id(id)
+            .context("Failed to update inspection")?;
+        let updated_inspection = state.services.inspections.update_inspection(&context, id, update_data)
+            .context("Failed to update inspection")?;
+        AuthHelper::audit_action(&context, "update", "inspection", Some(&id.to_string()), true, None);
+        notify_watchers(&app, &context,
+            state.services.watches.inspection_status_changed(&context, &updated_inspection, &previous.status));
+        log_notifications(&context,
+            state.services.notifications.inspection_assigned(&context, &updated_inspection, Some(previous.inspector_id)));
+
+        info!("[{}] Inspection updated: ID {} by user {}", context.request_id,
               id, context.current_user().map(|u| u.user_id).unwrap_or(0));
 
         Ok(updated_inspection)
     });
 
-    Ok(command_handler!("update_inspection", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("update_inspection", &context, { result }))
 }
 
 /// Submit inspection (mark as completed)
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn submit_inspection_command(
+    app: AppHandle,
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
-) -> Result<ApiResponse<Inspection>, String> {
+) -> CommandResult<Inspection> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("submit_inspection", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "inspection", "submit");
 
         // Submit inspection
-        let submitted_inspection = state.services.inspections.submit_inspection(id)
-            .map_err(|e| format!("Failed to submit inspection: {}", e))?;
-
-        info!("Inspection submitted: ID {} by user {}", 
+        let previous_status = state.services.inspections.get_inspection_by_id(id)
+            .context("Failed to submit inspection")?
+            .status;
+        let submitted_inspection = state.services.inspections.submit_inspection(&context, id)
+            .context("Failed to submit inspection")?;
+        AuthHelper::audit_action(&context, "submit", "inspection", Some(&id.to_string()), true, None);
+        notify_watchers(&app, &context,
+            state.services.watches.inspection_status_changed(&context, &submitted_inspection, &previous_status));
+
+        info!("[{}] Inspection submitted: ID {} by user {}", context.request_id,
               id, context.current_user().map(|u| u.user_id).unwrap_or(0));
 
         Ok(submitted_inspection)
     });
 
-    Ok(command_handler!("submit_inspection", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("submit_inspection", &context, { result }))
+}
+
+/// Soft delete an inspection
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn delete_inspection_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("delete_inspection", {
+        require_resource_access!(context, "inspection", "delete");
+
+        state.services.inspections.delete_inspection(&context, id)
+            .context("Failed to delete inspection")?;
+        AuthHelper::audit_action(&context, "delete", "inspection", Some(&id.to_string()), true, None);
+
+        info!("[{}] Inspection deleted: ID {}", context.request_id, id);
+        Ok(())
+    });
+
+    Ok(command_handler!("delete_inspection", &context, { result }))
+}
+
+/// Restore a soft-deleted inspection
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn restore_inspection_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<Inspection> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("restore_inspection", {
+        require_resource_access!(context, "inspection", "delete");
+
+        let inspection = state.services.inspections.restore_inspection(&context, id)
+            .context("Failed to restore inspection")?;
+        AuthHelper::audit_action(&context, "restore", "inspection", Some(&id.to_string()), true, None);
+
+        info!("[{}] Inspection restored: ID {} for asset {}", context.request_id, id, inspection.asset_id);
+        Ok(inspection)
+    });
+
+    Ok(command_handler!("restore_inspection", &context, { result }))
+}
+
+/// Permanently remove a soft-deleted inspection and its items
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn purge_inspection_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("purge_inspection", {
+        require_resource_access!(context, "system", "purge");
+
+        state.services.inspections.purge_inspection(&context, id)
+            .context("Failed to purge inspection")?;
+        AuthHelper::audit_action(&context, "purge", "inspection", Some(&id.to_string()), true, None);
+
+        warn!("[{}] Inspection purged: ID {}", context.request_id, id);
+        Ok(())
+    });
+
+    Ok(command_handler!("purge_inspection", &context, { result }))
 }
 
 /// Get inspections by asset with filtering
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_inspections_by_asset_command(
     state: State<'_, AppState>,
     token: Option<String>,
     asset_id: i64,
     filter: QueryFilterRequest,
-) -> Result<ApiResponse<PaginatedResponse<Inspection>>, String> {
+) -> CommandResult<PaginatedResponse<Inspection>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_inspections_by_asset", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "inspection", "read");
 
         // Get inspections with filters
         let query_filter = filter.into();
         let paginated_inspections = state.services.inspections
             .get_inspections_by_asset(asset_id, query_filter)
-            .map_err(|e| format!("Failed to get inspections by asset: {}", e))?;
+            .context("Failed to get inspections by asset")?;
 
-        debug!("Retrieved {} inspections for asset {}", 
+        debug!("[{}] Retrieved {} inspections for asset {}", context.request_id,
                paginated_inspections.data.len(), asset_id);
 
         let response = PaginatedResponse::from(paginated_inspections);
         Ok(response)
     });
 
-    Ok(command_handler!("get_inspections_by_asset", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_inspections_by_asset", &context, { result }))
 }
 
 /// Get pending inspections for inspector
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_pending_inspections_command(
     state: State<'_, AppState>,
     token: Option<String>,
     inspector_id: Option<i64>,
-) -> Result<ApiResponse<Vec<Inspection>>, String> {
+) -> CommandResult<Vec<Inspection>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_pending_inspections", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "inspection", "read");
 
         // If no inspector_id provided, use current user's ID if they're an inspector
@@ -209,39 +315,42 @@ pub async fn get_pending_inspections_command(
         // Get pending inspections
         let pending_inspections = state.services.inspections
             .get_pending_inspections(final_inspector_id)
-            .map_err(|e| format!("Failed to get pending inspections: {}", e))?;
+            .context("Failed to get pending inspections")?;
 
-        debug!("Retrieved {} pending inspections for inspector {:?}", 
+        debug!("[{}] Retrieved {} pending inspections for inspector {:?}", context.request_id,
                pending_inspections.len(), final_inspector_id);
 
         Ok(pending_inspections)
     });
 
-    Ok(command_handler!("get_pending_inspections", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_pending_inspections", &context, { result }))
 }
 
 /// Create inspection item
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn create_inspection_item_command(
+    app: AppHandle,
     state: State<'_, AppState>,
     token: Option<String>,
     item_data: CreateInspectionItemRequest,
-) -> Result<ApiResponse<InspectionItem>, String> {
+) -> CommandResult<InspectionItem> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("create_inspection_item", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "inspection", "update");
 
         // Create inspection item
         let inspection_item = item_data.to_inspection_item();
-        let created_item = state.services.inspections.create_inspection_item(inspection_item)
-            .map_err(|e| format!("Failed to create inspection item: {}", e))?;
+        let created_item = state.services.inspections.create_inspection_item(&context, inspection_item)
+            .context("Failed to create inspection item")?;
+        AuthHelper::audit_action(&context, "create", "inspection_item", Some(&created_item.id.to_string()), true, None);
+        notify_watchers(&app, &context, state.services.watches.finding_recorded(&context, &created_item));
+        log_notifications(&context, state.services.notifications.finding_recorded(&context, &created_item));
 
-        info!("Inspection item created: {} for inspection {} by user {}", 
+        info!("[{}] Inspection item created: {} for inspection {} by user {}", context.request_id,
               created_item.item_name,
               created_item.inspection_id,
               context.current_user().map(|u| u.user_id).unwrap_or(0));
@@ -249,24 +358,58 @@ pub async fn create_inspection_item_command(
         Ok(created_item)
     });
 
-    Ok(command_handler!("create_inspection_item", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("create_inspection_item", &context, { result }))
+}
+
+/// Create a checklist's worth of inspection items in one transaction
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn create_inspection_items_batch_command(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    token: Option<String>,
+    items: Vec<CreateInspectionItemRequest>,
+) -> CommandResult<Vec<InspectionItem>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("create_inspection_items_batch", {
+        require_resource_access!(context, "inspection", "update");
+
+        let inspection_items = items.into_iter().map(|item| item.to_inspection_item()).collect();
+        let created_items = state.services.inspections.create_items_batch(&context, inspection_items)
+            .context("Failed to create inspection items")?;
+        for created_item in &created_items {
+            AuthHelper::audit_action(&context, "create", "inspection_item", Some(&created_item.id.to_string()), true, None);
+            notify_watchers(&app, &context, state.services.watches.finding_recorded(&context, created_item));
+            log_notifications(&context, state.services.notifications.finding_recorded(&context, created_item));
+        }
+
+        info!("[{}] {} inspection items created by user {}", context.request_id,
+              created_items.len(),
+              context.current_user().map(|u| u.user_id).unwrap_or(0));
+
+        Ok(created_items)
+    });
+
+    Ok(command_handler!("create_inspection_items_batch", &context, { result }))
 }
 
 /// Update inspection item
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn update_inspection_item_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
     updates: InspectionItemUpdateRequest,
-) -> Result<ApiResponse<InspectionItem>, String> {
+) -> CommandResult<InspectionItem> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("update_inspection_item", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "inspection", "update");
 
         // Convert request to service update data
@@ -279,48 +422,128 @@ pub async fn update_inspection_item_command(
             severity: updates.severity,
             is_compliant: updates.is_compliant,
             corrective_action: updates.corrective_action,
+            clause_id: updates.clause_id,
+            measured_value: updates.measured_value,
+            measurement_unit: updates.measurement_unit,
         };
 
         // Update inspection item
-        let updated_item = state.services.inspections.update_inspection_item(id, update_data)
-            .map_err(|e| format!("Failed to update inspection item: {}", e))?;
+        let updated_item = state.services.inspections.update_inspection_item(&context, id, update_data)
+            .context("Failed to update inspection item")?;
+        AuthHelper::audit_action(&context, "update", "inspection_item", Some(&id.to_string()), true, None);
+        log_notifications(&context, state.services.notifications.finding_recorded(&context, &updated_item));
 
-        info!("Inspection item updated: ID {} by user {}", 
+        info!("[{}] Inspection item updated: ID {} by user {}", context.request_id,
               id, context.current_user().map(|u| u.user_id).unwrap_or(0));
 
         Ok(updated_item)
     });
 
-    Ok(command_handler!("update_inspection_item", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("update_inspection_item", &context, { result }))
 }
 
 /// Get inspection items for an inspection
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_inspection_items_command(
     state: State<'_, AppState>,
     token: Option<String>,
     inspection_id: i64,
-) -> Result<ApiResponse<Vec<InspectionItem>>, String> {
+) -> CommandResult<Vec<InspectionItem>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_inspection_items", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "inspection", "read");
 
         // Get inspection items
         let inspection_items = state.services.inspections.get_inspection_items(inspection_id)
-            .map_err(|e| format!("Failed to get inspection items: {}", e))?;
+            .context("Failed to get inspection items")?;
 
-        debug!("Retrieved {} inspection items for inspection {}", 
+        debug!("[{}] Retrieved {} inspection items for inspection {}", context.request_id,
                inspection_items.len(), inspection_id);
 
         Ok(inspection_items)
     });
 
-    Ok(command_handler!("get_inspection_items", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
-}
\ No newline at end of file
+    Ok(command_handler!("get_inspection_items", &context, { result }))
+}
+/// Attach an uploaded photo to an inspection item
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn attach_inspection_item_photo_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    item_id: i64,
+    media_id: i64,
+) -> CommandResult<Vec<i64>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("attach_inspection_item_photo", {
+        require_resource_access!(context, "inspection", "update");
+
+        let photos = state.services.inspections.attach_item_photo(&context, item_id, media_id)
+            .context("Failed to attach photo")?;
+        AuthHelper::audit_action(&context, "attach_photo", "inspection_item", Some(&item_id.to_string()), true, None);
+
+        info!("[{}] Media {} attached to inspection item {}", context.request_id, media_id, item_id);
+        Ok(photos)
+    });
+
+    Ok(command_handler!("attach_inspection_item_photo", &context, { result }))
+}
+
+/// Mark an inspection as carried out on a paper checklist, attaching the
+/// scan of the completed sheet
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn record_paper_transcription_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    inspection_id: i64,
+    input: PaperTranscriptionInput,
+) -> CommandResult<PaperTranscription> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("record_paper_transcription", {
+        require_resource_access!(context, "inspection", "update");
+
+        let transcription = state.services.inspections.record_paper_transcription(&context, inspection_id, input)
+            .context("Failed to record paper transcription")?;
+        AuthHelper::audit_action(&context, "paper_transcription", "inspection", Some(&inspection_id.to_string()), true, None);
+
+        info!("[{}] Inspection {} marked as transcribed from paper, scan media {}", context.request_id,
+              inspection_id, transcription.scan_media_id);
+        Ok(transcription)
+    });
+
+    Ok(command_handler!("record_paper_transcription", &context, { result }))
+}
+
+/// Get how an inspection was transcribed from paper, if it was
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_paper_transcription_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    inspection_id: i64,
+) -> CommandResult<Option<PaperTranscription>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_paper_transcription", {
+        require_resource_access!(context, "inspection", "read");
+
+        let transcription = state.services.inspections.get_paper_transcription(inspection_id)
+            .context("Failed to get paper transcription")?;
+        Ok(transcription)
+    });
+
+    Ok(command_handler!("get_paper_transcription", &context, { result }))
+}
@@ -0,0 +1,115 @@
+//! JSON schema command handlers
+//!
+//! This module contains Tauri command handlers for the schemas that asset
+//! and component specifications, checklist data and standard requirements
+//! are checked against, and for auditing stored records against them.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{JsonSchemaDefinition, JsonSchemaInput, SchemaAuditReport, SchemaTarget};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info};
+
+/// List schemas, optionally for one target field
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_json_schemas_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    target: Option<SchemaTarget>,
+) -> CommandResult<Vec<JsonSchemaDefinition>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_json_schemas", {
+        require_resource_access!(context, "asset", "read");
+
+        let schemas = state.services.json_schemas.get_schemas(target)
+            .context("Failed to get JSON schemas")?;
+
+        debug!("[{}] Retrieved {} JSON schemas", context.request_id, schemas.len());
+        Ok(schemas)
+    });
+
+    Ok(command_handler!("get_json_schemas", &context, { result }))
+}
+
+/// Add or replace the schema for a target field and scope
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn save_json_schema_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    schema: JsonSchemaInput,
+) -> CommandResult<JsonSchemaDefinition> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("save_json_schema", {
+        require_resource_access!(context, "system", "settings");
+
+        let saved = state.services.json_schemas.save_schema(&context, schema)
+            .context("Failed to save JSON schema")?;
+        AuthHelper::audit_action(&context, "save_json_schema", "json_schema", Some(&saved.id.to_string()), true, None);
+
+        info!("[{}] Saved {} schema for {}", context.request_id, saved.target, saved.scope);
+        Ok(saved)
+    });
+
+    Ok(command_handler!("save_json_schema", &context, { result }))
+}
+
+/// Remove a schema; the field is no longer checked for its scope
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn delete_json_schema_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    schema_id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("delete_json_schema", {
+        require_resource_access!(context, "system", "settings");
+
+        state.services.json_schemas.delete_schema(&context, schema_id)
+            .context("Failed to delete JSON schema")?;
+        AuthHelper::audit_action(&context, "delete_json_schema", "json_schema", Some(&schema_id.to_string()), true, None);
+
+        info!("[{}] Deleted JSON schema {}", context.request_id, schema_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("delete_json_schema", &context, { result }))
+}
+
+/// Check existing records against the current schemas
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn audit_json_schemas_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    target: Option<SchemaTarget>,
+) -> CommandResult<SchemaAuditReport> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("audit_json_schemas", {
+        require_resource_access!(context, "system", "data_quality");
+
+        let report = state.services.json_schemas.audit_records(target)
+            .context("Failed to audit records against JSON schemas")?;
+
+        info!("[{}] Checked {} records against JSON schemas, {} do not match",
+              context.request_id, report.records_checked, report.findings.len());
+        Ok(report)
+    });
+
+    Ok(command_handler!("audit_json_schemas", &context, { result }))
+}
@@ -0,0 +1,225 @@
+//! Kiosk command handlers
+//!
+//! This module contains Tauri command handlers for shared shop-floor
+//! terminals: registering terminals, issuing badges and PINs, and the
+//! badge/PIN sign-in that starts a restricted, auto-expiring kiosk session.
+
+use crate::api::KioskLoginResponse;
+use crate::commands::user_commands::record_login;
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::errors::AppError;
+use crate::middleware::auth::AuthHelper;
+use crate::middleware::RequestContext;
+use crate::models::{KioskCredentialInput, KioskTerminal, KioskTerminalInput, LoginAttempt, LoginDevice, LoginMethod,
+                    RegisteredKioskTerminal};
+use crate::services::kiosk_secret_label;
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info, warn};
+
+/// Sign in at a kiosk terminal by scanning a badge and entering a PIN
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn kiosk_login_command(
+    state: State<'_, AppState>,
+    terminal_key: String,
+    badge_id: String,
+    pin: String,
+) -> CommandResult<KioskLoginResponse> {
+    // Kiosk logins start without a session
+    let context = RequestContext::new();
+    context.record_in_span();
+
+    let result = time_command!("kiosk_login", {
+        let badge_owner = state.services.kiosk.badge_owner(&badge_id).ok().flatten();
+        let terminal_name = state.services.kiosk.terminal_for_key(&terminal_key)
+            .map(|terminal| terminal.name)
+            .ok();
+        let attempt = |user_id: i64, failure_reason: Option<String>| LoginAttempt {
+            user_id: Some(user_id),
+            username: String::new(),
+            method: LoginMethod::Kiosk,
+            device: LoginDevice { client_id: None, name: terminal_name.clone() },
+            failure_reason,
+        };
+        let record_failure = |e: &AppError| {
+            if let Some(user_id) = badge_owner {
+                record_login(&state, attempt(user_id, Some(e.to_string())));
+            }
+        };
+
+        // Badges and terminal keys are secrets, so they are counted by label
+        state.services.users.check_login_rate_limit(
+            &kiosk_secret_label("badge", &badge_id),
+            &kiosk_secret_label("terminal", &terminal_key),
+        ).inspect_err(|e| {
+            warn!("[{}] Kiosk login refused: {}", context.request_id, e);
+            record_failure(e);
+        })?;
+
+        let issued = state.auth_manager.kiosk_login(&terminal_key, &badge_id, &pin)
+            .inspect_err(|e| {
+                warn!("[{}] Kiosk login failed: {}", context.request_id, e);
+                record_failure(e);
+            })?;
+
+        let user = state.services.users.get_user_by_id(issued.session.user_id)
+            .context("Failed to get user details")?;
+        let session = issued.session;
+        record_login(&state, LoginAttempt { username: user.username.clone(), ..attempt(user.id, None) });
+
+        info!("[{}] User {} signed in at kiosk terminal {} (session: {})", context.request_id,
+              user.username, session.kiosk_terminal_id.unwrap_or_default(), session.session_id);
+        Ok(KioskLoginResponse {
+            user: user.into(),
+            token: issued.access_token,
+            expires_at: session.expires_at,
+            idle_timeout_minutes: session.idle_timeout_minutes.unwrap_or_default(),
+            terminal_id: session.kiosk_terminal_id.unwrap_or_default(),
+            permissions: session.permissions,
+            session_id: session.session_id,
+            locale: session.locale,
+        })
+    });
+
+    Ok(command_handler!("kiosk_login", &context, { result }))
+}
+
+/// Register a shared terminal. The returned key is entered on the terminal
+/// and is not shown again.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn register_kiosk_terminal_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    terminal: KioskTerminalInput,
+) -> CommandResult<RegisteredKioskTerminal> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("register_kiosk_terminal", {
+        require_resource_access!(context, "system", "settings");
+
+        let registered = state.services.kiosk.register_terminal(&context, terminal)
+            .context("Failed to register kiosk terminal")?;
+        AuthHelper::audit_action(&context, "register", "kiosk_terminal", Some(&registered.terminal.id.to_string()), true, None);
+
+        info!("[{}] Kiosk terminal registered: {} (ID: {})", context.request_id,
+              registered.terminal.name, registered.terminal.id);
+        Ok(registered)
+    });
+
+    Ok(command_handler!("register_kiosk_terminal", &context, { result }))
+}
+
+/// Get every registered kiosk terminal
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_kiosk_terminals_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Vec<KioskTerminal>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_kiosk_terminals", {
+        require_resource_access!(context, "system", "settings");
+
+        let terminals = state.services.kiosk.get_terminals()
+            .context("Failed to get kiosk terminals")?;
+
+        debug!("[{}] Retrieved {} kiosk terminals", context.request_id, terminals.len());
+        Ok(terminals)
+    });
+
+    Ok(command_handler!("get_kiosk_terminals", &context, { result }))
+}
+
+/// Stop a terminal from starting new kiosk sessions
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn deactivate_kiosk_terminal_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    terminal_id: i64,
+) -> CommandResult<KioskTerminal> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("deactivate_kiosk_terminal", {
+        require_resource_access!(context, "system", "settings");
+
+        let terminal = state.services.kiosk.deactivate_terminal(&context, terminal_id)
+            .context("Failed to deactivate kiosk terminal")?;
+        AuthHelper::audit_action(&context, "deactivate", "kiosk_terminal", Some(&terminal_id.to_string()), true, None);
+
+        info!("[{}] Kiosk terminal deactivated: {}", context.request_id, terminal_id);
+        Ok(terminal)
+    });
+
+    Ok(command_handler!("deactivate_kiosk_terminal", &context, { result }))
+}
+
+/// Set the badge and PIN a user signs in to kiosks with. Users may set their
+/// own from a normal login; setting another user's requires user update
+/// access.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn set_kiosk_credentials_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    user_id: i64,
+    credentials: KioskCredentialInput,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("set_kiosk_credentials", {
+        let session = AuthHelper::require_full_session(&context)?;
+        if session.user_id != user_id {
+            require_resource_access!(context, "user", "update");
+        }
+
+        state.services.kiosk.set_credentials(&context, user_id, credentials)
+            .context("Failed to set kiosk credentials")?;
+        AuthHelper::audit_action(&context, "set_kiosk_credentials", "user", Some(&user_id.to_string()), true, None);
+
+        info!("[{}] Kiosk credentials set for user {}", context.request_id, user_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("set_kiosk_credentials", &context, { result }))
+}
+
+/// Remove a user's badge and PIN, e.g. when a badge is lost
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn clear_kiosk_credentials_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    user_id: i64,
+) -> CommandResult<bool> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("clear_kiosk_credentials", {
+        let session = AuthHelper::require_full_session(&context)?;
+        if session.user_id != user_id {
+            require_resource_access!(context, "user", "update");
+        }
+
+        let cleared = state.services.kiosk.clear_credentials(&context, user_id)
+            .context("Failed to clear kiosk credentials")?;
+        AuthHelper::audit_action(&context, "clear_kiosk_credentials", "user", Some(&user_id.to_string()), true, None);
+
+        info!("[{}] Kiosk credentials cleared for user {}", context.request_id, user_id);
+        Ok(cleared)
+    });
+
+    Ok(command_handler!("clear_kiosk_credentials", &context, { result }))
+}
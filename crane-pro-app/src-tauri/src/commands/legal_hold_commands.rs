@@ -0,0 +1,175 @@
+//! Legal hold command handlers
+//!
+//! This module contains Tauri command handlers for legal holds. While a hold
+//! is active the held asset or inspection, its media and its generated
+//! reports cannot be purged, deleted or cleaned up by retention jobs. The
+//! hold register lists every hold, past and present, for counsel.
+
+use crate::api::{ReportFormat, ReportResult};
+use crate::commands::report_commands::REPORTS_DIR;
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::errors::AppResult;
+use crate::exporters::{self, ExportTable};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{LegalHold, LegalHoldInput};
+use crate::{require_resource_access, time_command, command_handler};
+use chrono::{DateTime, Utc};
+use std::fs;
+use tauri::State;
+use log::{debug, info};
+
+/// Place an asset or inspection under legal hold
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn place_legal_hold_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    input: LegalHoldInput,
+) -> CommandResult<LegalHold> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("place_legal_hold", {
+        require_resource_access!(context, "system", "legal_hold");
+
+        let hold = state.services.legal_holds.place_hold(&context, input)
+            .context("Failed to place legal hold")?;
+        AuthHelper::audit_action(&context, "place", "legal_hold", Some(&hold.id.to_string()), true, None);
+
+        info!("[{}] Legal hold {} placed on {} {}", context.request_id, hold.id, hold.entity_type, hold.entity_id);
+        Ok(hold)
+    });
+
+    Ok(command_handler!("place_legal_hold", &context, { result }))
+}
+
+/// Release an active legal hold, recording why
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn release_legal_hold_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    hold_id: i64,
+    reason: String,
+) -> CommandResult<LegalHold> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("release_legal_hold", {
+        require_resource_access!(context, "system", "legal_hold");
+
+        let hold = state.services.legal_holds.release_hold(&context, hold_id, reason)
+            .context("Failed to release legal hold")?;
+        AuthHelper::audit_action(&context, "release", "legal_hold", Some(&hold_id.to_string()), true, None);
+
+        info!("[{}] Legal hold {} released", context.request_id, hold_id);
+        Ok(hold)
+    });
+
+    Ok(command_handler!("release_legal_hold", &context, { result }))
+}
+
+/// List legal holds, active only unless `include_released` is set
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_legal_holds_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    include_released: Option<bool>,
+) -> CommandResult<Vec<LegalHold>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_legal_holds", {
+        require_resource_access!(context, "system", "legal_hold");
+
+        let holds = state.services.legal_holds.get_holds(include_released.unwrap_or(false))
+            .context("Failed to get legal holds")?;
+
+        debug!("[{}] Retrieved {} legal holds", context.request_id, holds.len());
+        Ok(holds)
+    });
+
+    Ok(command_handler!("get_legal_holds", &context, { result }))
+}
+
+/// Write the hold register, every hold placed and released, in any export format
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn generate_legal_hold_register_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    format: ReportFormat,
+) -> CommandResult<ReportResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("generate_legal_hold_register", {
+        require_resource_access!(context, "system", "legal_hold");
+
+        let writer = exporters::registry().get(&format)
+            .context("Unsupported legal hold register format")?;
+
+        let holds = state.services.legal_holds.get_holds(true)
+            .context("Failed to get legal holds")?;
+
+        let generated_at = Utc::now();
+        let report_id = format!("legal_hold_register_{}", generated_at.format("%Y%m%d_%H%M%S"));
+        fs::create_dir_all(REPORTS_DIR)
+            .context("Failed to create reports directory")?;
+        let file_path = format!("{}/{}.{}", REPORTS_DIR, report_id, writer.extension());
+
+        let table = legal_hold_register_table(&holds, generated_at)
+            .context("Failed to serialize legal hold register")?;
+        let content = exporters::registry().render(&format, &table)
+            .context("Failed to render legal hold register")?;
+        fs::write(&file_path, content)
+            .context("Failed to write legal hold register")?;
+        AuthHelper::audit_action(&context, "generate", "legal_hold_register", Some(&report_id), true, None);
+
+        info!("[{}] Legal hold register generated: {} ({} holds)", context.request_id, report_id, holds.len());
+        Ok(ReportResult {
+            report_id: report_id.clone(),
+            format,
+            file_path: Some(file_path),
+            file_url: Some(format!("/api/reports/{}/download", report_id)),
+            generated_at,
+            expires_at: Some(generated_at + chrono::Duration::days(30)),
+        })
+    });
+
+    Ok(command_handler!("generate_legal_hold_register", &context, { result }))
+}
+
+fn legal_hold_register_table(holds: &[LegalHold], generated_at: DateTime<Utc>) -> AppResult<ExportTable> {
+    let mut table = ExportTable::new(
+        "Legal hold register",
+        generated_at,
+        &[
+            "Hold ID", "Record Type", "Record ID", "Record", "Reason", "Reference", "Placed By", "Placed At",
+            "Released At", "Release Reason", "Inspections Covered", "Media Covered",
+        ],
+    )
+    .with_records(serde_json::to_value(holds)?);
+    for hold in holds {
+        table.push_row(vec![
+            hold.id.to_string(),
+            hold.entity_type.to_string(),
+            hold.entity_id.to_string(),
+            hold.label.clone(),
+            hold.reason.clone(),
+            hold.reference.clone().unwrap_or_default(),
+            hold.placed_by_name.clone().unwrap_or_else(|| hold.placed_by.to_string()),
+            hold.placed_at.to_rfc3339(),
+            hold.released_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+            hold.release_reason.clone().unwrap_or_default(),
+            hold.covered_inspections.to_string(),
+            hold.covered_media.to_string(),
+        ]);
+    }
+    Ok(table)
+}
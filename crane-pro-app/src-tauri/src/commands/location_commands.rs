@@ -3,125 +3,168 @@
 //! This module contains all Tauri command handlers for location management
 //! operations including CRUD operations for locations and location hierarchies.
 
-use crate::api::{ApiResponse, QueryFilterRequest, CreateLocationRequest, LocationUpdateRequest,
+use crate::api::{QueryFilterRequest, CreateLocationRequest, LocationUpdateRequest,
                 PaginatedResponse};
-use crate::commands::AppState;
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::errors::AppError;
+use crate::geo::{GeoQuery, LocationDistance, MapPin};
 use crate::middleware::auth::AuthHelper;
+use crate::middleware::RequestContext;
 use crate::models::{Location, LocationUpdateData, LocationWithAssets, LocationAssetSummary,
-                   LocationWithAssetCount, LocationDeletionResult};
+                   LocationWithAssetCount, LocationDeletionResult, LocationTreeNode, LocationRollup,
+                   LocationBreadcrumb, LocationCalendar, LocationClosure, LocationClosureInput};
 use crate::{require_resource_access, time_command, command_handler};
+use chrono::Weekday;
 use tauri::State;
-use log::{info, debug};
+use log::{info, debug, warn};
 
 /// Validate coordinates if provided
-fn validate_coordinates(lat: Option<f64>, lng: Option<f64>) -> Result<(), String> {
+fn validate_coordinates(lat: Option<f64>, lng: Option<f64>) -> Result<(), AppError> {
     if let (Some(lat), Some(lng)) = (lat, lng) {
         if lat.is_nan() || lng.is_nan() {
-            return Err("Coordinates cannot be NaN".to_string());
+            return Err(AppError::validation("coordinates", "Coordinates cannot be NaN"));
         }
         if lat.is_infinite() || lng.is_infinite() {
-            return Err("Coordinates cannot be infinite".to_string());
+            return Err(AppError::validation("coordinates", "Coordinates cannot be infinite"));
         }
         if lat < -90.0 || lat > 90.0 {
-            return Err("Latitude must be between -90 and 90 degrees".to_string());
+            return Err(AppError::validation("latitude", "Latitude must be between -90 and 90 degrees"));
         }
         if lng < -180.0 || lng > 180.0 {
-            return Err("Longitude must be between -180 and 180 degrees".to_string());
+            return Err(AppError::validation("longitude", "Longitude must be between -180 and 180 degrees"));
         }
     }
     Ok(())
 }
 
+/// Look up coordinates for `address` when the caller did not supply any.
+///
+/// Geocoding is best effort: failures are logged and the location is saved
+/// without coordinates.
+async fn geocode_if_missing(
+    state: &AppState,
+    context: &RequestContext,
+    address: Option<&str>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+) -> (Option<f64>, Option<f64>) {
+    let address = match address.map(str::trim) {
+        Some(address) if !address.is_empty() && latitude.is_none() && longitude.is_none() => address,
+        _ => return (latitude, longitude),
+    };
+    if !state.services.geocoding.is_enabled() {
+        return (latitude, longitude);
+    }
+
+    match state.services.geocoding.geocode(address).await {
+        Ok(Some(point)) => {
+            debug!("[{}] Geocoded '{}' to {}, {}", context.request_id, address, point.latitude, point.longitude);
+            (Some(point.latitude), Some(point.longitude))
+        }
+        Ok(None) => {
+            debug!("[{}] No geocoding match for '{}'", context.request_id, address);
+            (latitude, longitude)
+        }
+        Err(e) => {
+            warn!("[{}] Geocoding failed for '{}': {}", context.request_id, address, e);
+            (latitude, longitude)
+        }
+    }
+}
+
 /// Create a new location
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn create_location_command(
     state: State<'_, AppState>,
     token: Option<String>,
     location_data: CreateLocationRequest,
-) -> Result<ApiResponse<Location>, String> {
+) -> CommandResult<Location> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("create_location", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "location", "create");
 
         // Validate request data
         if location_data.name.trim().is_empty() {
-            return Err("Location name cannot be empty".to_string());
+            return Err(AppError::validation("name", "Location name cannot be empty"));
         }
 
         validate_coordinates(location_data.latitude, location_data.longitude)?;
 
         if let Some(parent_id) = location_data.parent_location_id {
             if parent_id == 0 {
-                return Err("Invalid parent location ID".to_string());
+                return Err(AppError::validation("parent_location_id", "Invalid parent location ID"));
             }
         }
 
-        // Create location
-        let location = location_data.to_location();
-        let created_location = state.services.locations.create_location(location)
-            .map_err(|e| format!("Failed to create location: {}", e))?;
+        // Create location, filling in coordinates from the address when missing
+        let mut location = location_data.to_location();
+        (location.latitude, location.longitude) = geocode_if_missing(
+            &state, &context, location.address.as_deref(), location.latitude, location.longitude,
+        ).await;
+        let created_location = state.services.locations.create_location(&context, location)
+            .context("Failed to create location")?;
+        AuthHelper::audit_action(&context, "create", "location", Some(&created_location.id.to_string()), true, None);
 
-        info!("Location created: {} by user {}", 
+        info!("[{}] Location created: {} by user {}", context.request_id,
               created_location.name, 
               context.current_user().map(|u| u.user_id).unwrap_or(0));
 
         Ok(created_location)
     });
 
-    Ok(command_handler!("create_location", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("create_location", &context, { result }))
 }
 
 /// Get location by ID
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_location_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
-) -> Result<ApiResponse<Location>, String> {
+) -> CommandResult<Location> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_location", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "location", "read");
 
         // Get location
         let location = state.services.locations.get_location_by_id(id)
-            .map_err(|e| format!("Failed to get location: {}", e))?;
+            .context("Failed to get location")?;
 
-        debug!("Location retrieved: {} (ID: {})", location.name, id);
+        debug!("[{}] Location retrieved: {} (ID: {})", context.request_id, location.name, id);
         Ok(location)
     });
 
-    Ok(command_handler!("get_location", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_location", &context, { result }))
 }
 
 /// Update location
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn update_location_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
     updates: LocationUpdateRequest,
-) -> Result<ApiResponse<Location>, String> {
+) -> CommandResult<Location> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("update_location", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "location", "update");
 
         // Validate update data
         if let Some(ref name) = updates.name {
             if name.trim().is_empty() {
-                return Err("Location name cannot be empty".to_string());
+                return Err(AppError::validation("name", "Location name cannot be empty"));
             }
         }
 
@@ -129,190 +172,456 @@ pub async fn update_location_command(
 
         if let Some(Some(parent_id)) = updates.parent_location_id {
             if parent_id == id {
-                return Err("Location cannot be its own parent".to_string());
+                return Err(AppError::validation("parent_location_id", "Location cannot be its own parent"));
             }
         }
 
-        // Convert request to service update data
-        let update_data: LocationUpdateData = updates.into();
+        // Convert request to service update data; a new address without
+        // coordinates is geocoded
+        let mut update_data: LocationUpdateData = updates.into();
+        (update_data.latitude, update_data.longitude) = geocode_if_missing(
+            &state, &context, update_data.address.as_deref(), update_data.latitude, update_data.longitude,
+        ).await;
 
         // Update location
-        let updated_location = state.services.locations.update_location(id, update_data)
-            .map_err(|e| format!("Failed to update location: {}", e))?;
+        let updated_location = state.services.locations.update_location(&context, id, update_data)
+            .context("Failed to update location")?;
+        AuthHelper::audit_action(&context, "update", "location", Some(&id.to_string()), true, None);
 
-        info!("Location updated: {} (ID: {}) by user {}", 
+        info!("[{}] Location updated: {} (ID: {}) by user {}", context.request_id,
               updated_location.name, id,
               context.current_user().map(|u| u.user_id).unwrap_or(0));
 
         Ok(updated_location)
     });
 
-    Ok(command_handler!("update_location", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("update_location", &context, { result }))
 }
 
 /// Delete location (safe deletion with dependency checks)
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn delete_location_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
-) -> Result<ApiResponse<LocationDeletionResult>, String> {
+) -> CommandResult<LocationDeletionResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("delete_location", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "location", "delete");
 
         // Safe delete location
-        let deletion_result = state.services.locations.delete_location_safe(id)
-            .map_err(|e| format!("Failed to delete location: {}", e))?;
+        let deletion_result = state.services.locations.delete_location_safe(&context, id)
+            .context("Failed to delete location")?;
+        AuthHelper::audit_action(&context, "delete", "location", Some(&id.to_string()), deletion_result.success, None);
 
         if deletion_result.success {
-            info!("Location deleted: ID {} by user {}", 
+            info!("[{}] Location deleted: ID {} by user {}", context.request_id,
                   id, context.current_user().map(|u| u.user_id).unwrap_or(0));
         } else {
-            debug!("Location deletion prevented: {}", deletion_result.message);
+            debug!("[{}] Location deletion prevented: {}", context.request_id, deletion_result.message);
         }
 
         Ok(deletion_result)
     });
 
-    Ok(command_handler!("delete_location", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("delete_location", &context, { result }))
 }
 
 /// Get location with all its assets
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_location_with_assets_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
-) -> Result<ApiResponse<LocationWithAssets>, String> {
+) -> CommandResult<LocationWithAssets> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_location_with_assets", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "location", "read");
 
         // Get location with assets
         let location_with_assets = state.services.locations.get_location_with_assets(id)
-            .map_err(|e| format!("Failed to get location with assets: {}", e))?;
+            .context("Failed to get location with assets")?;
 
-        debug!("Location with assets retrieved: {} ({} assets)", 
+        debug!("[{}] Location with assets retrieved: {} ({} assets)", context.request_id,
                location_with_assets.name, location_with_assets.assets.len());
 
         Ok(location_with_assets)
     });
 
-    Ok(command_handler!("get_location_with_assets", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_location_with_assets", &context, { result }))
 }
 
 /// Get location with asset summary
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_location_asset_summary_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
-) -> Result<ApiResponse<LocationAssetSummary>, String> {
+) -> CommandResult<LocationAssetSummary> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_location_asset_summary", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "location", "read");
 
         // Get location with asset summary
         let location_summary = state.services.locations.get_location_with_asset_summary(id)
-            .map_err(|e| format!("Failed to get location asset summary: {}", e))?;
+            .context("Failed to get location asset summary")?;
 
-        debug!("Location asset summary retrieved: {} ({} total assets, {} critical)", 
+        debug!("[{}] Location asset summary retrieved: {} ({} total assets, {} critical)", context.request_id,
                location_summary.name, location_summary.asset_count, location_summary.critical_assets);
 
         Ok(location_summary)
     });
 
-    Ok(command_handler!("get_location_asset_summary", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_location_asset_summary", &context, { result }))
 }
 
 /// Validate asset-location assignment
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn validate_asset_location_assignment_command(
     state: State<'_, AppState>,
     token: Option<String>,
     asset_id: i64,
     location_id: i64,
-) -> Result<ApiResponse<()>, String> {
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("validate_asset_location_assignment", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         // Require both location and asset read permissions
         require_resource_access!(context, "location", "read");
         require_resource_access!(context, "asset", "read");
 
         // Validate assignment
         state.services.locations.validate_asset_location_assignment(asset_id, location_id)
-            .map_err(|e| format!("Failed to validate asset-location assignment: {}", e))?;
+            .context("Failed to validate asset-location assignment")?;
 
-        debug!("Asset-location assignment validated: asset {} to location {}", 
+        debug!("[{}] Asset-location assignment validated: asset {} to location {}", context.request_id,
                asset_id, location_id);
 
         Ok(())
     });
 
-    Ok(command_handler!("validate_asset_location_assignment", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("validate_asset_location_assignment", &context, { result }))
 }
 
 /// Search locations with asset counts
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn search_locations_with_asset_counts_command(
     state: State<'_, AppState>,
     token: Option<String>,
     query: String,
     filter: QueryFilterRequest,
-) -> Result<ApiResponse<PaginatedResponse<LocationWithAssetCount>>, String> {
+) -> CommandResult<PaginatedResponse<LocationWithAssetCount>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("search_locations_with_asset_counts", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "location", "read");
 
         // Validate search parameters
         if query.len() < 3 && filter.limit.unwrap_or(50) > 20 {
-            return Err("Query too short for large result sets. Please provide at least 3 characters.".to_string());
+            return Err(AppError::validation("query", "Query too short for large result sets. Please provide at least 3 characters."));
         }
 
         const MAX_PAGE_SIZE: i64 = 100;
         if filter.limit.unwrap_or(50) > MAX_PAGE_SIZE {
-            return Err(format!("Page size cannot exceed {}", MAX_PAGE_SIZE));
+            return Err(AppError::validation("limit", format!("Page size cannot exceed {}", MAX_PAGE_SIZE)));
         }
 
         // Search locations
         let query_filter = filter.into();
         let search_results = state.services.locations.search_locations_with_asset_counts(query.clone(), query_filter)
-            .map_err(|e| format!("Failed to search locations: {}", e))?;
+            .context("Failed to search locations")?;
 
-        debug!("Location search returned {} results for query: '{}'",
+        debug!("[{}] Location search returned {} results for query: '{}'", context.request_id,
                search_results.data.len(), query);
 
         let response = PaginatedResponse::from(search_results);
         Ok(response)
     });
 
-    Ok(command_handler!("search_locations_with_asset_counts", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
-}
\ No newline at end of file
+    Ok(command_handler!("search_locations_with_asset_counts", &context, { result }))
+}
+
+/// Get the path from the root of the hierarchy down to a location, for breadcrumbs
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_location_path_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<Vec<LocationBreadcrumb>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_location_path", {
+        require_resource_access!(context, "location", "read");
+
+        let path = state.services.locations.get_location_path(id)
+            .context("Failed to get location path")?;
+
+        debug!("[{}] Location path retrieved for {}: {} levels", context.request_id, id, path.len());
+        Ok(path)
+    });
+
+    Ok(command_handler!("get_location_path", &context, { result }))
+}
+
+/// Get the location hierarchy as nested trees
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_location_tree_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    root_id: Option<i64>,
+) -> CommandResult<Vec<LocationTreeNode>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_location_tree", {
+        require_resource_access!(context, "location", "read");
+
+        // Build tree from the requested root, or every top-level location
+        let tree = state.services.locations.get_location_tree(root_id)
+            .context("Failed to get location tree")?;
+
+        debug!("[{}] Location tree retrieved: {} root locations", context.request_id, tree.len());
+        Ok(tree)
+    });
+
+    Ok(command_handler!("get_location_tree", &context, { result }))
+}
+
+/// Get asset and compliance totals for a location and all of its descendants
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_location_rollup_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<LocationRollup> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_location_rollup", {
+        require_resource_access!(context, "location", "read");
+        require_resource_access!(context, "compliance", "read");
+
+        // Roll up the subtree
+        let rollup = state.services.locations.get_location_rollup(id)
+            .context("Failed to get location roll-up")?;
+
+        debug!("[{}] Location roll-up retrieved: {} ({} locations, {} assets)", context.request_id,
+               rollup.name, rollup.location_count, rollup.asset_count);
+
+        Ok(rollup)
+    });
+
+    Ok(command_handler!("get_location_rollup", &context, { result }))
+}
+
+/// Move a location and its descendants under a new parent
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn move_location_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+    new_parent_id: Option<i64>,
+) -> CommandResult<Location> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("move_location", {
+        require_resource_access!(context, "location", "update");
+
+        // Move location; cycles and over-deep nesting are rejected by the service
+        let moved_location = state.services.locations.move_location(&context, id, new_parent_id)?;
+        AuthHelper::audit_action(&context, "move", "location", Some(&id.to_string()), true, None);
+
+        info!("[{}] Location moved: {} (ID: {}) under {:?} by user {}", context.request_id,
+              moved_location.name, id, new_parent_id,
+              context.current_user().map(|u| u.user_id).unwrap_or(0));
+
+        Ok(moved_location)
+    });
+
+    Ok(command_handler!("move_location", &context, { result }))
+}
+
+/// Find locations inside a map viewport or within a radius
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn search_locations_geo_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    query: GeoQuery,
+) -> CommandResult<Vec<LocationDistance>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("search_locations_geo", {
+        require_resource_access!(context, "location", "read");
+
+        // Search by coordinates
+        let locations = state.services.locations.search_locations_geo(query)?;
+
+        debug!("[{}] Geo search returned {} locations", context.request_id, locations.len());
+        Ok(locations)
+    });
+
+    Ok(command_handler!("search_locations_geo", &context, { result }))
+}
+
+/// Get colour-coded asset pins for the map, optionally limited to a
+/// viewport or radius
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_map_pins_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    query: Option<GeoQuery>,
+) -> CommandResult<Vec<MapPin>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_map_pins", {
+        require_resource_access!(context, "location", "read");
+        require_resource_access!(context, "asset", "read");
+
+        // Build pins from assets at located sites
+        let pins = state.services.assets.get_map_pins(query)?;
+
+        debug!("[{}] Map pins retrieved: {}", context.request_id, pins.len());
+        Ok(pins)
+    });
+
+    Ok(command_handler!("get_map_pins", &context, { result }))
+}
+
+/// Get the working days and closures in effect at a location
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_location_calendar_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<LocationCalendar> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_location_calendar", {
+        require_resource_access!(context, "location", "read");
+
+        let calendar = state.services.locations.get_location_calendar(id)?;
+
+        debug!("[{}] Working calendar retrieved for location {}: {} closures", context.request_id,
+               id, calendar.closures.len());
+        Ok(calendar)
+    });
+
+    Ok(command_handler!("get_location_calendar", &context, { result }))
+}
+
+/// Set the weekdays a location works, or clear them to inherit from its
+/// parent
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn set_location_working_days_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+    working_days: Option<Vec<Weekday>>,
+) -> CommandResult<LocationCalendar> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("set_location_working_days", {
+        require_resource_access!(context, "location", "update");
+
+        // Open inspections in the subtree are re-anchored by the service
+        let calendar = state.services.locations.set_location_working_days(&context, id, working_days)?;
+        AuthHelper::audit_action(&context, "set_working_days", "location", Some(&id.to_string()), true, None);
+
+        info!("[{}] Working days for location {} set to {:?}", context.request_id, id, calendar.working_days);
+        Ok(calendar)
+    });
+
+    Ok(command_handler!("set_location_working_days", &context, { result }))
+}
+
+/// Add a holiday or shutdown to a location
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn add_location_closure_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+    closure: LocationClosureInput,
+) -> CommandResult<LocationClosure> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("add_location_closure", {
+        require_resource_access!(context, "location", "update");
+
+        let closure = state.services.locations.add_location_closure(&context, id, closure)?;
+        AuthHelper::audit_action(&context, "add_closure", "location", Some(&id.to_string()), true, None);
+
+        info!("[{}] {} '{}' ({} to {}) added to location {}", context.request_id,
+              closure.kind, closure.name, closure.start_date, closure.end_date, id);
+        Ok(closure)
+    });
+
+    Ok(command_handler!("add_location_closure", &context, { result }))
+}
+
+/// Remove a holiday or shutdown from a location
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn delete_location_closure_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    closure_id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("delete_location_closure", {
+        require_resource_access!(context, "location", "update");
+
+        let location_id = state.services.locations.delete_location_closure(&context, closure_id)?;
+        AuthHelper::audit_action(&context, "delete_closure", "location", Some(&location_id.to_string()), true, None);
+
+        info!("[{}] Closure {} removed from location {}", context.request_id, closure_id, location_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("delete_location_closure", &context, { result }))
+}
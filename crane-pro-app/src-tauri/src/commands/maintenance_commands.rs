@@ -0,0 +1,202 @@
+//! Maintenance record command handlers
+//!
+//! This module contains Tauri command handlers for scheduling, carrying out
+//! and closing maintenance work on assets and their components. Completed
+//! records feed the maintenance history used by reports.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{MaintenanceRecord, MaintenanceRecordInput, MaintenanceUpdateData};
+use crate::{require_resource_access, time_command, command_handler};
+use chrono::{DateTime, Utc};
+use serde_json::Value as JsonValue;
+use tauri::State;
+use log::{debug, info};
+
+/// Schedule maintenance on an asset, or log work already done
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn create_maintenance_record_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    record: MaintenanceRecordInput,
+) -> CommandResult<MaintenanceRecord> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("create_maintenance_record", {
+        require_resource_access!(context, "asset", "update");
+
+        let record = state.services.maintenance.create_record(&context, record.into_record())
+            .context("Failed to create maintenance record")?;
+        AuthHelper::audit_action(&context, "create", "maintenance_record", Some(&record.id.to_string()), true, None);
+
+        info!("[{}] {} maintenance record {} created for asset {}", context.request_id,
+              record.maintenance_type, record.id, record.asset_id);
+        Ok(record)
+    });
+
+    Ok(command_handler!("create_maintenance_record", &context, { result }))
+}
+
+/// Change the details of maintenance that is not yet completed or cancelled
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn update_maintenance_record_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+    updates: MaintenanceUpdateData,
+) -> CommandResult<MaintenanceRecord> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("update_maintenance_record", {
+        require_resource_access!(context, "asset", "update");
+
+        let record = state.services.maintenance.update_record(&context, id, updates)
+            .context("Failed to update maintenance record")?;
+        AuthHelper::audit_action(&context, "update", "maintenance_record", Some(&id.to_string()), true, None);
+
+        info!("[{}] Maintenance record {} updated", context.request_id, id);
+        Ok(record)
+    });
+
+    Ok(command_handler!("update_maintenance_record", &context, { result }))
+}
+
+/// Mark scheduled maintenance as in progress
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn start_maintenance_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<MaintenanceRecord> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("start_maintenance", {
+        require_resource_access!(context, "asset", "update");
+
+        let record = state.services.maintenance.start_record(&context, id)
+            .context("Failed to start maintenance")?;
+        AuthHelper::audit_action(&context, "start", "maintenance_record", Some(&id.to_string()), true, None);
+
+        info!("[{}] Maintenance record {} started", context.request_id, id);
+        Ok(record)
+    });
+
+    Ok(command_handler!("start_maintenance", &context, { result }))
+}
+
+/// Close maintenance as done, now unless a completion date is given, with the
+/// parts and cost it took. Parts listed by part number are taken out of stock.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn complete_maintenance_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+    completed_date: Option<DateTime<Utc>>,
+    parts_used: Option<JsonValue>,
+    cost: Option<f64>,
+) -> CommandResult<MaintenanceRecord> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("complete_maintenance", {
+        require_resource_access!(context, "asset", "update");
+
+        let record = state.services.maintenance.complete_record(&context, id, completed_date, parts_used, cost)
+            .context("Failed to complete maintenance")?;
+        AuthHelper::audit_action(&context, "complete", "maintenance_record", Some(&id.to_string()), true, None);
+
+        info!("[{}] Maintenance record {} completed", context.request_id, id);
+        Ok(record)
+    });
+
+    Ok(command_handler!("complete_maintenance", &context, { result }))
+}
+
+/// Call off maintenance that has not been completed
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn cancel_maintenance_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<MaintenanceRecord> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("cancel_maintenance", {
+        require_resource_access!(context, "asset", "update");
+
+        let record = state.services.maintenance.cancel_record(&context, id)
+            .context("Failed to cancel maintenance")?;
+        AuthHelper::audit_action(&context, "cancel", "maintenance_record", Some(&id.to_string()), true, None);
+
+        info!("[{}] Maintenance record {} cancelled", context.request_id, id);
+        Ok(record)
+    });
+
+    Ok(command_handler!("cancel_maintenance", &context, { result }))
+}
+
+/// Get a maintenance record by ID
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_maintenance_record_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<MaintenanceRecord> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_maintenance_record", {
+        require_resource_access!(context, "asset", "read");
+
+        let record = state.services.maintenance.get_record_by_id(id)
+            .context("Failed to get maintenance record")?;
+
+        debug!("[{}] Maintenance record {} retrieved", context.request_id, id);
+        Ok(record)
+    });
+
+    Ok(command_handler!("get_maintenance_record", &context, { result }))
+}
+
+/// Get the maintenance records of an asset, optionally only those on one
+/// component, newest first
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_maintenance_records_by_asset_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    asset_id: i64,
+    component_id: Option<i64>,
+) -> CommandResult<Vec<MaintenanceRecord>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_maintenance_records_by_asset", {
+        require_resource_access!(context, "asset", "read");
+
+        let records = state.services.maintenance.get_records_by_asset(asset_id, component_id)
+            .context("Failed to get maintenance records")?;
+
+        debug!("[{}] {} maintenance records retrieved for asset {}", context.request_id, records.len(), asset_id);
+        Ok(records)
+    });
+
+    Ok(command_handler!("get_maintenance_records_by_asset", &context, { result }))
+}
@@ -3,10 +3,14 @@
 //! This module contains all Tauri command handlers for media file management
 //! operations including file upload, retrieval, and deletion.
 
-use crate::api::{ApiResponse, UploadFileRequest};
-use crate::commands::AppState;
+use crate::api::{UploadFileRequest};
+use crate::commands::{run_idempotent, AppState, CommandResult, ErrorContext};
+use crate::errors::AppError;
 use crate::middleware::auth::AuthHelper;
-use crate::models::{MediaFile, MediaType};
+use crate::middleware::record_access::RecordAction;
+use crate::models::{MediaFile, MediaType, UploadedMedia};
+use crate::photo;
+use crate::services::IdempotencyService;
 use crate::{require_resource_access, time_command, command_handler};
 use tauri::State;
 use log::{info, debug, warn};
@@ -14,24 +18,34 @@ use chrono::Utc;
 use std::path::Path;
 use std::fs;
 
+/// Directory media is stored under, from the application settings
+pub(crate) fn media_root(state: &AppState) -> Result<String, AppError> {
+    state.services.settings.get_settings()
+        .map(|settings| settings.media_storage_path)
+        .context("Failed to read media storage path")
+}
+
 /// Upload a file
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn upload_file_command(
     state: State<'_, AppState>,
     token: Option<String>,
     file_data: UploadFileRequest,
-) -> Result<ApiResponse<MediaFile>, String> {
+    idempotency_key: Option<String>,
+) -> CommandResult<UploadedMedia> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("upload_file", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "media", "upload");
+        state.services.media.authorize_media(&context, file_data.inspection_id, file_data.component_id, context.current_user().map(|u| u.user_id).ok(), RecordAction::Write)?;
 
         // Validate file size (limit to 50MB)
         const MAX_FILE_SIZE: usize = 50 * 1024 * 1024; // 50MB
         if file_data.file_data.len() > MAX_FILE_SIZE {
-            return Err("File size exceeds 50MB limit".to_string());
+            return Err(AppError::validation("file_data", "File size exceeds 50MB limit"));
         }
 
         // Validate file type
@@ -43,215 +57,234 @@ pub async fn upload_file_command(
         };
 
         if !allowed_types.contains(&file_data.mime_type.as_str()) {
-            return Err(format!("Unsupported file type: {}", file_data.mime_type));
+            return Err(AppError::validation("mime_type", format!("Unsupported file type: {}", file_data.mime_type)));
         }
 
-        // Generate unique filename with timestamp
-        let timestamp = Utc::now().format("%Y%m%d_%H%M%S").to_string();
-        let file_extension = Path::new(&file_data.file_name)
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("bin");
-        let unique_filename = format!("{}_{}.{}", timestamp, uuid::Uuid::new_v4(), file_extension);
-
-        // Create upload directory structure
-        let upload_dir = format!("uploads/{}/{}", 
-                                file_data.file_type.to_string(), 
-                                Utc::now().format("%Y/%m"));
-        let full_upload_path = format!("./data/{}", upload_dir);
-        
-        fs::create_dir_all(&full_upload_path)
-            .map_err(|e| format!("Failed to create upload directory: {}", e))?;        // Write file to disk
-        let file_path = format!("{}/{}", upload_dir, unique_filename);
-        let full_file_path = format!("./data/{}", file_path);
+        let metadata = serde_json::to_vec(&(
+            file_data.inspection_id, file_data.component_id, &file_data.file_name,
+            &file_data.file_type, &file_data.mime_type, &file_data.description,
+        )).context("Invalid upload request")?;
+        let request_hash = IdempotencyService::fingerprint(&[&metadata, &file_data.file_data]);
+
+        // Store the file, or replay the result of an earlier identical upload
+        let uploaded = run_idempotent(&state, &context, "upload_file", idempotency_key.as_deref(), &request_hash, || {
+            // Generate unique filename with timestamp
+            let timestamp = Utc::now().format("%Y%m%d_%H%M%S").to_string();
+            let file_extension = Path::new(&file_data.file_name)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("bin");
+            let unique_filename = format!("{}_{}.{}", timestamp, uuid::Uuid::new_v4(), file_extension);
+
+            // Create upload directory structure
+            let upload_dir = format!("uploads/{}/{}", 
+                                    file_data.file_type.to_string(), 
+                                    Utc::now().format("%Y/%m"));
+            let media_root = media_root(&state)?;
+            let full_upload_path = format!("{}/{}", media_root, upload_dir);
         
-        fs::write(&full_file_path, &file_data.file_data)
-            .map_err(|e| format!("Failed to write file: {}", e))?;
-
-        // Store file_type before moving file_data
-        let file_type = file_data.file_type.clone();
-        let file_data_len = file_data.file_data.len() as i64;
+            fs::create_dir_all(&full_upload_path)
+                .context("Failed to create upload directory")?;
 
-        // Create media file record
-        let media_file = file_data.to_media_file(file_path, file_data_len);
-        let created_media = state.services.media.create_media_file(media_file)
-            .map_err(|e| {
-                // Clean up file if database operation fails
-                let _ = fs::remove_file(&full_file_path);
-                format!("Failed to create media file record: {}", e)
-            })?;
-
-        // Queue for AI analysis if it's an image
-        if matches!(file_type, MediaType::Image) {
-            let _ = state.services.media.queue_for_ai_analysis(created_media.id);
-        }
-
-        info!("File uploaded: {} (ID: {}) by user {}", 
-              created_media.file_name, 
-              created_media.id,
-              context.current_user().map(|u| u.user_id).unwrap_or(0));
-
-        Ok(created_media)
+            // Write file to disk
+            let file_path = format!("{}/{}", upload_dir, unique_filename);
+            let full_file_path = format!("{}/{}", media_root, file_path);
+        
+            fs::write(&full_file_path, &file_data.file_data)
+                .context("Failed to write file")?;
+
+            // Store file_type before moving file_data
+            let file_type = file_data.file_type.clone();
+            let file_data_len = file_data.file_data.len() as i64;
+
+            // Create media file record
+            let fingerprint = photo::fingerprint(&file_data.file_data);
+            let media_file = file_data.to_media_file(file_path, file_data_len);
+            let uploaded = state.services.media.create_media_file(&context, media_file, &fingerprint)
+                .inspect_err(|_| {
+                    // Clean up file if database operation fails
+                    let _ = fs::remove_file(&full_file_path);
+                })
+                .context("Failed to create media file record")?;
+            let created_media = &uploaded.media;
+            AuthHelper::audit_action(&context, "upload", "media", Some(&created_media.id.to_string()), true, None);
+
+            // Queue for AI analysis if it's an image
+            if matches!(file_type, MediaType::Image) {
+                let _ = state.services.media.queue_for_ai_analysis(&context, created_media.id);
+            }
+
+            info!("[{}] File uploaded: {} (ID: {}) by user {}", context.request_id,
+                  created_media.file_name, 
+                  created_media.id,
+                  context.current_user().map(|u| u.user_id).unwrap_or(0));
+
+            Ok(uploaded)
+        })?;
+
+        Ok(uploaded)
     });
 
-    Ok(command_handler!("upload_file", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("upload_file", &context, { result }))
 }
 
 /// Get file by ID
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_file_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
-) -> Result<ApiResponse<MediaFile>, String> {
+) -> CommandResult<MediaFile> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_file", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "media", "read");
 
         // Get media file
         let media_file = state.services.media.get_media_file_by_id(id)
-            .map_err(|e| format!("Failed to get media file: {}", e))?;
+            .context("Failed to get media file")?;
+        state.services.media.authorize_media(&context, media_file.inspection_id, media_file.component_id, media_file.uploaded_by, RecordAction::Read)?;
 
-        debug!("Media file retrieved: {} (ID: {})", media_file.file_name, id);
+        debug!("[{}] Media file retrieved: {} (ID: {})", context.request_id, media_file.file_name, id);
         Ok(media_file)
     });
 
-    Ok(command_handler!("get_file", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_file", &context, { result }))
 }
 
 /// Get files by inspection ID
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_files_by_inspection_command(
     state: State<'_, AppState>,
     token: Option<String>,
     inspection_id: i64,
-) -> Result<ApiResponse<Vec<MediaFile>>, String> {
+) -> CommandResult<Vec<MediaFile>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_files_by_inspection", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "media", "read");
 
+        state.services.media.authorize_media(&context, Some(inspection_id), None, None, RecordAction::Read)?;
+
         // Get media files for inspection
         let media_files = state.services.media.get_media_files_by_inspection(inspection_id)
-            .map_err(|e| format!("Failed to get media files by inspection: {}", e))?;
+            .context("Failed to get media files by inspection")?;
 
-        debug!("Retrieved {} media files for inspection {}", 
+        debug!("[{}] Retrieved {} media files for inspection {}", context.request_id,
                media_files.len(), inspection_id);
 
         Ok(media_files)
     });
 
-    Ok(command_handler!("get_files_by_inspection", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_files_by_inspection", &context, { result }))
 }
 
 /// Delete file
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn delete_file_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
-) -> Result<ApiResponse<()>, String> {
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("delete_file", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "media", "delete");
 
         // Get file info before deletion for cleanup
         let media_file = state.services.media.get_media_file_by_id(id)
-            .map_err(|e| format!("Failed to get media file for deletion: {}", e))?;
+            .context("Failed to get media file for deletion")?;
+        state.services.media.authorize_media(&context, media_file.inspection_id, media_file.component_id, media_file.uploaded_by, RecordAction::Write)?;
 
         // Delete from database
-        state.services.media.delete_media_file(id)
-            .map_err(|e| format!("Failed to delete media file from database: {}", e))?;
+        state.services.media.delete_media_file(&context, id)
+            .context("Failed to delete media file from database")?;
+        AuthHelper::audit_action(&context, "delete", "media", Some(&id.to_string()), true, None);
 
         // Delete physical file
-        let full_file_path = format!("./data/{}", media_file.file_path);
+        let full_file_path = format!("{}/{}", media_root(&state)?, media_file.file_path);
         if let Err(e) = fs::remove_file(&full_file_path) {
-            warn!("Failed to delete physical file {}: {}", full_file_path, e);
+            warn!("[{}] Failed to delete physical file {}: {}", context.request_id, full_file_path, e);
             // Don't fail the operation if file deletion fails
         }
 
-        info!("Media file deleted: {} (ID: {}) by user {}", 
+        info!("[{}] Media file deleted: {} (ID: {}) by user {}", context.request_id,
               media_file.file_name, id,
               context.current_user().map(|u| u.user_id).unwrap_or(0));
 
         Ok(())
     });
 
-    Ok(command_handler!("delete_file", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("delete_file", &context, { result }))
 }
 
 /// Get file URL for download/viewing
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_file_url_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
-) -> Result<ApiResponse<String>, String> {
+) -> CommandResult<String> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_file_url", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "media", "read");
 
         // Get media file
         let media_file = state.services.media.get_media_file_by_id(id)
-            .map_err(|e| format!("Failed to get media file: {}", e))?;
+            .context("Failed to get media file")?;
+        state.services.media.authorize_media(&context, media_file.inspection_id, media_file.component_id, media_file.uploaded_by, RecordAction::Read)?;
 
         // Generate secure file URL (in production, this would be a signed URL with expiration)
         let file_url = format!("/api/files/{}/download", id);
 
-        debug!("File URL generated for media file: {} (ID: {})", 
+        debug!("[{}] File URL generated for media file: {} (ID: {})", context.request_id,
                media_file.file_name, id);
 
         Ok(file_url)
     });
 
-    Ok(command_handler!("get_file_url", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_file_url", &context, { result }))
 }
 
 /// Upload inspection photo (specialized upload for inspections)
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn upload_inspection_photo_command(
     state: State<'_, AppState>,
     token: Option<String>,
     inspection_id: i64,
     file_data: UploadFileRequest,
-) -> Result<ApiResponse<MediaFile>, String> {
+) -> CommandResult<UploadedMedia> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("upload_inspection_photo", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "media", "upload");
+        state.services.media.authorize_media(&context, Some(inspection_id), None, None, RecordAction::Write)?;
 
         // Validate that this is an image file
         if !matches!(file_data.file_type, MediaType::Image) {
-            return Err("Only image files are allowed for inspection photos".to_string());
+            return Err(AppError::validation("file_type", "Only image files are allowed for inspection photos"));
         }
 
         // Validate file size (limit to 20MB for photos)
         const MAX_PHOTO_SIZE: usize = 20 * 1024 * 1024; // 20MB
         if file_data.file_data.len() > MAX_PHOTO_SIZE {
-            return Err("Photo size exceeds 20MB limit".to_string());
+            return Err(AppError::validation("file_data", "Photo size exceeds 20MB limit"));
         }
 
         // Create a new upload request with the inspection ID set
@@ -269,60 +302,65 @@ pub async fn upload_inspection_photo_command(
 
         // Create upload directory for inspection photos
         let upload_dir = format!("uploads/inspections/{}", inspection_id);
-        let full_upload_path = format!("./data/{}", upload_dir);
+        let media_root = media_root(&state)?;
+        let full_upload_path = format!("{}/{}", media_root, upload_dir);
         
         fs::create_dir_all(&full_upload_path)
-            .map_err(|e| format!("Failed to create upload directory: {}", e))?;        // Write file to disk
+            .context("Failed to create upload directory")?;
+
+            // Write file to disk
         let file_path = format!("{}/{}", upload_dir, unique_filename);
-        let full_file_path = format!("./data/{}", file_path);
+        let full_file_path = format!("{}/{}", media_root, file_path);
         
         fs::write(&full_file_path, &photo_data.file_data)
-            .map_err(|e| format!("Failed to write photo: {}", e))?;
+            .context("Failed to write photo")?;
 
         // Store file data length before moving photo_data
         let file_data_len = photo_data.file_data.len() as i64;
 
         // Create media file record
+        let fingerprint = photo::fingerprint(&photo_data.file_data);
         let media_file = photo_data.to_media_file(file_path, file_data_len);
-        let created_media = state.services.media.create_media_file(media_file)
-            .map_err(|e| {
+        let uploaded = state.services.media.create_media_file(&context, media_file, &fingerprint)
+            .inspect_err(|_| {
                 // Clean up file if database operation fails
                 let _ = fs::remove_file(&full_file_path);
-                format!("Failed to create media file record: {}", e)
-            })?;
+            })
+            .context("Failed to create media file record")?;
 
         // Queue for AI analysis
-        let _ = state.services.media.queue_for_ai_analysis(created_media.id);
+        let _ = state.services.media.queue_for_ai_analysis(&context, uploaded.media.id);
 
-        info!("Inspection photo uploaded: {} for inspection {} by user {}", 
-              created_media.file_name, inspection_id,
+        info!("[{}] Inspection photo uploaded: {} for inspection {} by user {}", context.request_id,
+              uploaded.media.file_name, inspection_id,
               context.current_user().map(|u| u.user_id).unwrap_or(0));
 
-        Ok(created_media)
+        Ok(uploaded)
     });
 
-    Ok(command_handler!("upload_inspection_photo", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("upload_inspection_photo", &context, { result }))
 }
 
 /// Get inspection photos
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_inspection_photos_command(
     state: State<'_, AppState>,
     token: Option<String>,
     inspection_id: i64,
-) -> Result<ApiResponse<Vec<MediaFile>>, String> {
+) -> CommandResult<Vec<MediaFile>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_inspection_photos", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "media", "read");
 
+        state.services.media.authorize_media(&context, Some(inspection_id), None, None, RecordAction::Read)?;
+
         // Get media files for inspection (filter for images only)
         let all_media_files = state.services.media.get_media_files_by_inspection(inspection_id)
-            .map_err(|e| format!("Failed to get media files by inspection: {}", e))?;
+            .context("Failed to get media files by inspection")?;
 
         // Filter for image files only
         let photo_files: Vec<MediaFile> = all_media_files
@@ -330,13 +368,11 @@ pub async fn get_inspection_photos_command(
             .filter(|file| matches!(file.file_type, MediaType::Image))
             .collect();
 
-        debug!("Retrieved {} photos for inspection {}", 
+        debug!("[{}] Retrieved {} photos for inspection {}", context.request_id,
                photo_files.len(), inspection_id);
 
         Ok(photo_files)
     });
 
-    Ok(command_handler!("get_inspection_photos", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_inspection_photos", &context, { result }))
 }
\ No newline at end of file
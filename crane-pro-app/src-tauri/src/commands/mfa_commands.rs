@@ -0,0 +1,245 @@
+//! Multi-factor authentication command handlers
+//!
+//! This module contains Tauri command handlers for TOTP second factors:
+//! enrolling an authenticator app, completing a login that asked for a
+//! code, and the per-role policies deciding who must use one.
+
+use crate::api::LoginResponse;
+use crate::commands::user_commands::{login_response, record_login};
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::errors::AppError;
+use crate::middleware::auth::AuthHelper;
+use crate::middleware::RequestContext;
+use crate::models::{LoginAttempt, LoginDevice, LoginMethod, MfaEnrollment, MfaPolicy, MfaStatus, UserRole};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info, warn};
+
+/// Complete a login that asked for a second factor, with a code from the
+/// authenticator app or a backup code
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn verify_mfa_command(
+    state: State<'_, AppState>,
+    challenge_token: String,
+    code: String,
+    device: Option<LoginDevice>,
+) -> CommandResult<LoginResponse> {
+    // The session only starts once the code is accepted
+    let context = RequestContext::new();
+    context.record_in_span();
+
+    let result = time_command!("verify_mfa", {
+        // Known before the check, which drops the challenge after too many wrong codes
+        let challenged_user_id = state.auth_manager.mfa_challenge_user_id(&challenge_token).ok();
+        let attempt = |user_id: i64, failure_reason: Option<String>| LoginAttempt {
+            user_id: Some(user_id),
+            username: String::new(),
+            method: LoginMethod::SecondFactor,
+            device: device.clone().unwrap_or_default(),
+            failure_reason,
+        };
+
+        let issued = state.auth_manager.verify_mfa(&challenge_token, &code)
+            .inspect_err(|e| {
+                warn!("[{}] Second factor rejected: {}", context.request_id, e);
+                if let Some(user_id) = challenged_user_id {
+                    record_login(&state, attempt(user_id, Some(e.to_string())));
+                }
+            })?;
+
+        let user = state.services.users.get_user_by_id(issued.session.user_id)
+            .context("Failed to get user details")?;
+        record_login(&state, LoginAttempt { username: user.username.clone(), ..attempt(user.id, None) });
+        let response = login_response(user, issued);
+
+        info!("[{}] User logged in with second factor: {} (session: {})", context.request_id,
+              response.user.username, response.session_id);
+        Ok(response)
+    });
+
+    Ok(command_handler!("verify_mfa", &context, { result }))
+}
+
+/// Start enrolling an authenticator app for the signed-in user, or for a
+/// user whose login is waiting on a required second factor. Returns the
+/// secret, the QR code payload and backup codes, which are shown only once.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn enroll_mfa_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    challenge_token: Option<String>,
+) -> CommandResult<MfaEnrollment> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("enroll_mfa", {
+        let user_id = match (AuthHelper::require_full_session(&context), challenge_token) {
+            (Ok(session), _) => session.user_id,
+            (Err(_), Some(challenge_token)) => state.auth_manager.mfa_challenge_user_id(&challenge_token)?,
+            (Err(e), None) => return Err(e),
+        };
+
+        let user = state.services.users.get_user_by_id(user_id)
+            .context("Failed to get user")?;
+        let enrollment = state.services.mfa.enroll(&user)
+            .context("Failed to enroll MFA")?;
+
+        info!("[{}] MFA enrollment started for user {}", context.request_id, user.username);
+        Ok(enrollment)
+    });
+
+    Ok(command_handler!("enroll_mfa", &context, { result }))
+}
+
+/// Confirm an enrollment started from a signed-in session with the first
+/// code from the authenticator app
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn confirm_mfa_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    code: String,
+) -> CommandResult<MfaStatus> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("confirm_mfa", {
+        let user_id = AuthHelper::require_full_session(&context)?.user_id;
+        let user = state.services.users.get_user_by_id(user_id)
+            .context("Failed to get user")?;
+
+        let status = state.services.mfa.get_status(&user)
+            .context("Failed to get MFA status")?;
+        if !status.pending_enrollment {
+            return Err(AppError::validation("code", "No MFA enrollment is waiting for confirmation"));
+        }
+        let valid = state.services.mfa.verify(user_id, &code)
+            .context("Failed to verify code")?;
+        if !valid {
+            return Err(AppError::validation("code", "Invalid verification code"));
+        }
+        AuthHelper::audit_action(&context, "enable_mfa", "user", Some(&user_id.to_string()), true, None);
+
+        let status = state.services.mfa.get_status(&user)
+            .context("Failed to get MFA status")?;
+        info!("[{}] MFA enabled for user {}", context.request_id, user.username);
+        Ok(status)
+    });
+
+    Ok(command_handler!("confirm_mfa", &context, { result }))
+}
+
+/// Get the second factor status of the signed-in user, or of another user
+/// for those allowed to read users
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_mfa_status_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    user_id: Option<i64>,
+) -> CommandResult<MfaStatus> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_mfa_status", {
+        let current_user_id = context.current_user()?.user_id;
+        let user_id = user_id.unwrap_or(current_user_id);
+        if user_id != current_user_id {
+            require_resource_access!(context, "user", "read");
+        }
+
+        let user = state.services.users.get_user_by_id(user_id)
+            .context("Failed to get user")?;
+        let status = state.services.mfa.get_status(&user)
+            .context("Failed to get MFA status")?;
+
+        debug!("[{}] MFA status retrieved for user {}", context.request_id, user_id);
+        Ok(status)
+    });
+
+    Ok(command_handler!("get_mfa_status", &context, { result }))
+}
+
+/// Remove a user's second factor, e.g. after a lost phone
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn reset_mfa_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    user_id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("reset_mfa", {
+        require_resource_access!(context, "user", "update");
+
+        state.services.mfa.reset(&context, user_id)
+            .context("Failed to reset MFA")?;
+        AuthHelper::audit_action(&context, "reset_mfa", "user", Some(&user_id.to_string()), true, None);
+
+        info!("[{}] MFA reset for user {} by user {}", context.request_id, user_id,
+              context.current_user().map(|u| u.user_id).unwrap_or(0));
+        Ok(())
+    });
+
+    Ok(command_handler!("reset_mfa", &context, { result }))
+}
+
+/// Get which roles must sign in with a second factor
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_mfa_policies_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Vec<MfaPolicy>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_mfa_policies", {
+        require_resource_access!(context, "user", "read");
+
+        let policies = state.services.mfa.get_policies()
+            .context("Failed to get MFA policies")?;
+
+        debug!("[{}] Retrieved {} MFA policies", context.request_id, policies.len());
+        Ok(policies)
+    });
+
+    Ok(command_handler!("get_mfa_policies", &context, { result }))
+}
+
+/// Require or stop requiring a second factor for a role
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn set_mfa_policy_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    role: UserRole,
+    required: bool,
+) -> CommandResult<MfaPolicy> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("set_mfa_policy", {
+        require_resource_access!(context, "system", "settings");
+
+        let policy = state.services.mfa.set_policy(&context, role, required)
+            .context("Failed to set MFA policy")?;
+        AuthHelper::audit_action(&context, "set_mfa_policy", "settings", Some(&policy.role.to_string()), true, None);
+
+        info!("[{}] MFA {} for role {}", context.request_id,
+              if policy.required { "required" } else { "optional" }, policy.role);
+        Ok(policy)
+    });
+
+    Ok(command_handler!("set_mfa_policy", &context, { result }))
+}
@@ -10,6 +10,41 @@ pub mod user_commands;
 pub mod media_commands;
 pub mod report_commands;
 pub mod location_commands;
+pub mod system_commands;
+pub mod export_commands;
+pub mod team_commands;
+pub mod comment_commands;
+pub mod activity_commands;
+pub mod watch_commands;
+pub mod recycle_bin_commands;
+pub mod bulk_commands;
+pub mod search_commands;
+pub mod dashboard_commands;
+pub mod prestart_commands;
+pub mod history_commands;
+pub mod asset_record_commands;
+pub mod operator_authorization_commands;
+pub mod training_commands;
+pub mod work_order_commands;
+pub mod notification_commands;
+pub mod risk_matrix_commands;
+pub mod finding_sla_commands;
+pub mod settings_commands;
+pub mod parts_commands;
+pub mod defect_commands;
+pub mod json_schema_commands;
+pub mod corrective_action_commands;
+pub mod validation_rule_commands;
+pub mod maintenance_commands;
+pub mod vendor_document_commands;
+pub mod mfa_commands;
+pub mod kiosk_commands;
+pub mod trusted_device_commands;
+pub mod legal_hold_commands;
+pub mod role_commands;
+pub mod api_key_commands;
+pub mod operation_commands;
+pub mod checklist_pack_commands;
 
 // Re-export all command handlers for easy registration
 pub use asset_commands::*;
@@ -19,13 +54,53 @@ pub use user_commands::*;
 pub use media_commands::*;
 pub use report_commands::*;
 pub use location_commands::*;
+pub use system_commands::*;
+pub use export_commands::*;
+pub use team_commands::*;
+pub use comment_commands::*;
+pub use activity_commands::*;
+pub use watch_commands::*;
+pub use recycle_bin_commands::*;
+pub use bulk_commands::*;
+pub use search_commands::*;
+pub use dashboard_commands::*;
+pub use prestart_commands::*;
+pub use history_commands::*;
+pub use asset_record_commands::*;
+pub use operator_authorization_commands::*;
+pub use training_commands::*;
+pub use work_order_commands::*;
+pub use notification_commands::*;
+pub use risk_matrix_commands::*;
+pub use finding_sla_commands::*;
+pub use settings_commands::*;
+pub use parts_commands::*;
+pub use defect_commands::*;
+pub use json_schema_commands::*;
+pub use corrective_action_commands::*;
+pub use validation_rule_commands::*;
+pub use maintenance_commands::*;
+pub use vendor_document_commands::*;
+pub use mfa_commands::*;
+pub use kiosk_commands::*;
+pub use trusted_device_commands::*;
+pub use legal_hold_commands::*;
+pub use role_commands::*;
+pub use api_key_commands::*;
+pub use operation_commands::*;
+pub use checklist_pack_commands::*;
 
 use crate::api::ApiResponse;
 use crate::errors::AppError;
-use crate::services::Services;
+use crate::services::{IdempotencyClaim, Services};
+use crate::middleware::RequestContext;
 use crate::middleware::auth::AuthManager;
 use std::sync::Arc;
-use log::{info, error, debug};
+use log::{info, error, debug, warn};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Result type returned by every command handler
+pub type CommandResult<T> = Result<CommandResponse<ApiResponse<T>>, String>;
 
 /// Shared state for command handlers
 #[derive(Clone)]
@@ -44,25 +119,104 @@ impl AppState {
 }
 
 /// Helper function to convert AppError to ApiResponse
-pub fn handle_error<T>(result: Result<T, AppError>) -> ApiResponse<T> {
+pub fn handle_error<T>(result: Result<T, AppError>, context: &RequestContext) -> ApiResponse<T> {
     match result {
         Ok(data) => ApiResponse::success(data),
         Err(error) => {
-            error!("Command execution failed: {}", error);
-            ApiResponse::error(error)
+            error!("[{}] Command execution failed: {}", context.request_id, error);
+            ApiResponse::localized_error(error, context.locale())
+        }
+    }
+}
+
+/// Adds the step that failed to a command error.
+///
+/// The step is logged and the error itself is kept, so the client receives
+/// it localized rather than as a preformatted English string.
+pub trait ErrorContext<T> {
+    fn context(self, step: &str) -> Result<T, AppError>;
+
+    fn with_context<F: FnOnce() -> String>(self, step: F) -> Result<T, AppError>;
+}
+
+impl<T, E: Into<AppError>> ErrorContext<T> for Result<T, E> {
+    fn context(self, step: &str) -> Result<T, AppError> {
+        self.with_context(|| step.to_string())
+    }
+
+    fn with_context<F: FnOnce() -> String>(self, step: F) -> Result<T, AppError> {
+        self.map_err(|e| {
+            let error = e.into();
+            warn!("{}: {}", step(), error);
+            error
+        })
+    }
+}
+
+/// Run a create operation at most once per idempotency key.
+///
+/// Without a key the operation simply runs. With a key, a retry of a
+/// completed request returns the original result instead of creating a
+/// duplicate; a failed operation releases the key so the client can retry.
+pub fn run_idempotent<T, F>(
+    state: &AppState,
+    context: &RequestContext,
+    command: &str,
+    idempotency_key: Option<&str>,
+    request_hash: &str,
+    operation: F,
+) -> Result<T, AppError>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Result<T, AppError>,
+{
+    let Some(key) = idempotency_key else {
+        return operation();
+    };
+    let idempotency = &state.services.idempotency;
+
+    if let IdempotencyClaim::Replay(previous) = idempotency.claim(context, command, key, request_hash)? {
+        return Ok(previous);
+    }
+
+    match operation() {
+        Ok(result) => {
+            // The record exists at this point; failing here would only make the client retry
+            if let Err(e) = idempotency.complete(context, command, key, &result) {
+                warn!("[{}] Failed to store result for idempotency key {}: {}", context.request_id, key, e);
+            }
+            Ok(result)
+        }
+        Err(error) => {
+            if let Err(e) = idempotency.release(context, command, key) {
+                warn!("[{}] Failed to release idempotency key {}: {}", context.request_id, key, e);
+            }
+            Err(error)
         }
     }
 }
 
 /// Helper function for logging command execution
-pub fn log_command_start(command_name: &str, user_id: Option<i64>) {
-    if let Some(user_id) = user_id {
-        info!("Executing command '{}' for user {}", command_name, user_id);
+pub fn log_command_start(command_name: &str, context: &RequestContext) {
+    if let Some(session) = &context.session {
+        info!("[{}] Executing command '{}' for user {}", context.request_id, command_name, session.user_id);
     } else {
-        info!("Executing command '{}' (unauthenticated)", command_name);
+        info!("[{}] Executing command '{}' (unauthenticated)", context.request_id, command_name);
     }
 }
 
+/// Record the outcome of a command in the audit log. A successful command
+/// whose handler already audited its action is not recorded a second time.
+pub fn audit_command<T>(command_name: &str, context: &RequestContext, result: &Result<T, AppError>) {
+    let entry = crate::middleware::AuditLogEntry::new(context, command_name, "command");
+    let entry = match result {
+        Ok(_) if context.is_audited() => return,
+        Ok(_) => entry,
+        Err(error) => entry.with_error(error),
+    };
+    crate::middleware::record_audit_entry(&entry);
+}
+
 /// Helper function for logging command completion
 pub fn log_command_end(command_name: &str, success: bool, duration_ms: u64) {
     if success {
@@ -73,25 +227,37 @@ pub fn log_command_end(command_name: &str, success: bool, duration_ms: u64) {
 }
 
 /// Macro for timing command execution
+///
+/// The block runs as an async block, so `?` and `return` end the block
+/// rather than the command and every failure reaches the result.
 #[macro_export]
 macro_rules! time_command {
     ($command_name:expr, $block:block) => {{
         let start = std::time::Instant::now();
-        let result = $block;
+        let result: Result<_, $crate::errors::AppError> = async { $block }.await;
         let duration = start.elapsed();
         let success = result.is_ok();
-        crate::commands::log_command_end($command_name, success, duration.as_millis() as u64);
+        $crate::commands::log_command_end($command_name, success, duration.as_millis() as u64);
         result
     }};
 }
 
 /// Macro for wrapping command handlers with error handling and logging
+///
+/// The body yields the command's result, already timed by `time_command!`.
+/// The request context supplies the request ID that is logged and returned
+/// in the response metadata.
 #[macro_export]
 macro_rules! command_handler {
-    ($name:expr, $user_id:expr, $body:block) => {{
-        crate::commands::log_command_start($name, $user_id);
-        let result = crate::time_command!($name, $body);
-        crate::commands::handle_error(result)
+    ($name:expr, $context:expr, $body:block) => {{
+        let context: &$crate::middleware::RequestContext = $context;
+        $crate::commands::log_command_start($name, context);
+        let result: Result<_, $crate::errors::AppError> = $body;
+        $crate::commands::audit_command($name, context, &result);
+        $crate::commands::CommandResponse::new(
+            $crate::commands::handle_error(result, context),
+            context.request_id.clone(),
+        )
     }};
 }
 
@@ -0,0 +1,258 @@
+//! Notification command handlers
+//!
+//! This module contains Tauri command handlers for the current user's
+//! notification inbox, channel preferences and digest settings.
+//! Notifications are also pushed to the app as `notification` events while
+//! it is running.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::errors::AppResult;
+use crate::middleware::auth::AuthHelper;
+use crate::middleware::RequestContext;
+use crate::models::{
+    Notification, NotificationChannelKind, NotificationDigest, NotificationDigestInput, NotificationDigestSettings,
+    NotificationKind, NotificationPreference,
+};
+use crate::{time_command, command_handler};
+use tauri::State;
+use log::{debug, info, warn};
+
+/// Default number of notifications returned
+const DEFAULT_NOTIFICATION_LIMIT: i64 = 50;
+
+/// Log the outcome of raising notifications. Notifying must not fail the
+/// change that triggered it, so errors are only logged.
+pub(crate) fn log_notifications(context: &RequestContext, notifications: AppResult<Vec<Notification>>) {
+    match notifications {
+        Ok(notifications) if !notifications.is_empty() => {
+            debug!("[{}] Sent {} notifications", context.request_id, notifications.len());
+        }
+        Ok(_) => {}
+        Err(e) => warn!("[{}] Failed to send notifications: {}", context.request_id, e),
+    }
+}
+
+/// Get the current user's notifications, newest first
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_notifications_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    unread_only: Option<bool>,
+    limit: Option<i64>,
+) -> CommandResult<Vec<Notification>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_notifications", {
+        let user_id = context.current_user()?.user_id;
+
+        let notifications = state.services.notifications
+            .get_notifications(user_id, unread_only.unwrap_or(false), limit.unwrap_or(DEFAULT_NOTIFICATION_LIMIT))
+            .context("Failed to get notifications")?;
+
+        debug!("[{}] Retrieved {} notifications for user {}", context.request_id, notifications.len(), user_id);
+        Ok(notifications)
+    });
+
+    Ok(command_handler!("get_notifications", &context, { result }))
+}
+
+/// Get how many of the current user's notifications are unread
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_unread_notification_count_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<i64> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_unread_notification_count", {
+        let user_id = context.current_user()?.user_id;
+
+        let count = state.services.notifications.get_unread_count(user_id)
+            .context("Failed to count unread notifications")?;
+
+        Ok(count)
+    });
+
+    Ok(command_handler!("get_unread_notification_count", &context, { result }))
+}
+
+/// Mark one of the current user's notifications read
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn mark_notification_read_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    notification_id: i64,
+) -> CommandResult<Notification> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("mark_notification_read", {
+        let user_id = context.current_user()?.user_id;
+
+        let notification = state.services.notifications.mark_read(user_id, notification_id)
+            .context("Failed to mark notification read")?;
+
+        debug!("[{}] Notification {} marked read", context.request_id, notification_id);
+        Ok(notification)
+    });
+
+    Ok(command_handler!("mark_notification_read", &context, { result }))
+}
+
+/// Mark all of the current user's notifications read
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn mark_all_notifications_read_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<usize> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("mark_all_notifications_read", {
+        let user_id = context.current_user()?.user_id;
+
+        let marked = state.services.notifications.mark_all_read(user_id)
+            .context("Failed to mark notifications read")?;
+
+        debug!("[{}] {} notifications marked read for user {}", context.request_id, marked, user_id);
+        Ok(marked)
+    });
+
+    Ok(command_handler!("mark_all_notifications_read", &context, { result }))
+}
+
+/// Get which kinds of notification the current user receives on each channel
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_notification_preferences_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Vec<NotificationPreference>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_notification_preferences", {
+        let user_id = context.current_user()?.user_id;
+
+        let preferences = state.services.notifications.get_preferences(user_id)
+            .context("Failed to get notification preferences")?;
+
+        Ok(preferences)
+    });
+
+    Ok(command_handler!("get_notification_preferences", &context, { result }))
+}
+
+/// Switch a kind of notification on or off for one channel for the current
+/// user
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn set_notification_preference_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    kind: NotificationKind,
+    channel: NotificationChannelKind,
+    enabled: bool,
+) -> CommandResult<NotificationPreference> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("set_notification_preference", {
+        let user_id = context.current_user()?.user_id;
+
+        let preference = state.services.notifications.set_preference(user_id, kind, channel, enabled)
+            .context("Failed to set notification preference")?;
+
+        info!("[{}] {} notifications by {} {} for user {}", context.request_id, kind, channel,
+              if enabled { "enabled" } else { "disabled" }, user_id);
+        Ok(preference)
+    });
+
+    Ok(command_handler!("set_notification_preference", &context, { result }))
+}
+
+/// Get whether the current user takes notification emails immediately or as
+/// a daily digest
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_notification_digest_settings_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<NotificationDigestSettings> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_notification_digest_settings", {
+        let user_id = context.current_user()?.user_id;
+
+        let settings = state.services.notifications.get_digest_settings(user_id)
+            .context("Failed to get notification digest settings")?;
+
+        Ok(settings)
+    });
+
+    Ok(command_handler!("get_notification_digest_settings", &context, { result }))
+}
+
+/// Switch the current user between immediate notification emails and a
+/// daily digest at a local time of their choosing
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn set_notification_digest_settings_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    input: NotificationDigestInput,
+) -> CommandResult<NotificationDigestSettings> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("set_notification_digest_settings", {
+        let user_id = context.current_user()?.user_id;
+
+        let settings = state.services.notifications.set_digest_settings(user_id, input)
+            .context("Failed to set notification digest settings")?;
+
+        info!("[{}] Notification delivery set to {} for user {}", context.request_id, settings.delivery, user_id);
+        Ok(settings)
+    });
+
+    Ok(command_handler!("set_notification_digest_settings", &context, { result }))
+}
+
+/// Preview the digest the current user would be sent now
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn preview_notification_digest_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<NotificationDigest> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("preview_notification_digest", {
+        let user_id = context.current_user()?.user_id;
+
+        let digest = state.services.notifications.preview_digest(user_id)
+            .context("Failed to build notification digest")?;
+
+        debug!("[{}] Digest preview has {} sections", context.request_id, digest.sections.len());
+        Ok(digest)
+    });
+
+    Ok(command_handler!("preview_notification_digest", &context, { result }))
+}
@@ -0,0 +1,66 @@
+//! Long-running operation command handlers
+//!
+//! This module contains Tauri command handlers for administrators to see
+//! the bulk changes and exports that are running or finished recently,
+//! with their progress, and to cancel one.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::operations::{OperationInfo, OperationRegistry};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info};
+
+/// Get running operations and those finished in the last hour, newest first
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn list_operations_command(
+    state: State<'_, AppState>,
+    operations: State<'_, OperationRegistry>,
+    token: Option<String>,
+) -> CommandResult<Vec<OperationInfo>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("list_operations", {
+        require_resource_access!(context, "system", "admin");
+
+        let list = operations.list();
+
+        debug!("[{}] Retrieved {} operations", context.request_id, list.len());
+        Ok(list)
+    });
+
+    Ok(command_handler!("list_operations", &context, { result }))
+}
+
+/// Ask a running operation to stop. It stops at its next check; work
+/// already committed by a best-effort bulk operation is kept.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn cancel_operation_command(
+    state: State<'_, AppState>,
+    operations: State<'_, OperationRegistry>,
+    token: Option<String>,
+    id: String,
+) -> CommandResult<OperationInfo> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("cancel_operation", {
+        require_resource_access!(context, "system", "admin");
+
+        let user_id = context.current_user().map(|u| u.user_id).ok();
+        let operation = operations.cancel(&id, user_id)
+            .context("Failed to cancel operation")?;
+        AuthHelper::audit_action(&context, "cancel", "operation", Some(&operation.id), true, None);
+
+        info!("[{}] Cancellation of {} '{}' requested by user {}", context.request_id,
+              operation.kind, operation.description, user_id.unwrap_or(0));
+        Ok(operation)
+    });
+
+    Ok(command_handler!("cancel_operation", &context, { result }))
+}
@@ -0,0 +1,152 @@
+//! Operator authorization command handlers
+//!
+//! This module contains Tauri command handlers for authorizing operators to
+//! run asset types up to a rated capacity, checking an operator before they
+//! are recorded against an asset, and the authorization matrix for auditors.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{AuthorizationCheck, AuthorizationMatrix, OperatorAuthorization, OperatorAuthorizationInput};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info};
+
+/// Authorize an operator for an asset type, optionally up to a capacity and
+/// until an expiry date
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn grant_operator_authorization_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    authorization: OperatorAuthorizationInput,
+) -> CommandResult<OperatorAuthorization> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("grant_operator_authorization", {
+        require_resource_access!(context, "user", "update");
+
+        let authorization = state.services.operator_authorizations.grant(&context, authorization)
+            .context("Failed to grant operator authorization")?;
+        AuthHelper::audit_action(&context, "grant_operator_authorization", "user",
+                                 Some(&authorization.user_id.to_string()), true, None);
+
+        info!("[{}] User {} authorized for {} assets", context.request_id,
+              authorization.user_id, authorization.asset_type);
+        Ok(authorization)
+    });
+
+    Ok(command_handler!("grant_operator_authorization", &context, { result }))
+}
+
+/// Withdraw an operator authorization before it expires
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn revoke_operator_authorization_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    authorization_id: i64,
+) -> CommandResult<OperatorAuthorization> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("revoke_operator_authorization", {
+        require_resource_access!(context, "user", "update");
+
+        let authorization = state.services.operator_authorizations.revoke(&context, authorization_id)
+            .context("Failed to revoke operator authorization")?;
+        AuthHelper::audit_action(&context, "revoke_operator_authorization", "user",
+                                 Some(&authorization.user_id.to_string()), true, None);
+
+        info!("[{}] Operator authorization {} revoked", context.request_id, authorization_id);
+        Ok(authorization)
+    });
+
+    Ok(command_handler!("revoke_operator_authorization", &context, { result }))
+}
+
+/// Get the authorizations held by one operator, or by everyone
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_operator_authorizations_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    user_id: Option<i64>,
+    include_expired: Option<bool>,
+) -> CommandResult<Vec<OperatorAuthorization>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_operator_authorizations", {
+        require_resource_access!(context, "user", "read");
+
+        let authorizations = state.services.operator_authorizations
+            .get_authorizations(user_id, include_expired.unwrap_or(false))
+            .context("Failed to get operator authorizations")?;
+
+        debug!("[{}] Retrieved {} operator authorizations", context.request_id, authorizations.len());
+        Ok(authorizations)
+    });
+
+    Ok(command_handler!("get_operator_authorizations", &context, { result }))
+}
+
+/// Check whether an operator, by default the current user, may be recorded
+/// running an asset
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn check_operator_authorization_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    asset_id: i64,
+    user_id: Option<i64>,
+) -> CommandResult<AuthorizationCheck> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("check_operator_authorization", {
+        require_resource_access!(context, "inspection", "create");
+
+        let user_id = match user_id {
+            Some(user_id) => user_id,
+            None => context.current_user()?.user_id,
+        };
+        let check = state.services.operator_authorizations.check(user_id, asset_id)
+            .context("Failed to check operator authorization")?;
+
+        debug!("[{}] User {} authorized for asset {}: {}", context.request_id, user_id, asset_id, check.authorized);
+        Ok(check)
+    });
+
+    Ok(command_handler!("check_operator_authorization", &context, { result }))
+}
+
+/// Get the matrix of operators against asset types for auditors
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_authorization_matrix_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<AuthorizationMatrix> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_authorization_matrix", {
+        require_resource_access!(context, "report", "generate");
+
+        let matrix = state.services.operator_authorizations.get_matrix()
+            .context("Failed to generate authorization matrix")?;
+        AuthHelper::audit_action(&context, "generate_authorization_matrix", "report", None, true, None);
+
+        info!("[{}] Authorization matrix generated: {} operators, {} asset types",
+              context.request_id, matrix.rows.len(), matrix.asset_types.len());
+        Ok(matrix)
+    });
+
+    Ok(command_handler!("get_authorization_matrix", &context, { result }))
+}
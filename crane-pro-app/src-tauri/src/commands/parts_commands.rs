@@ -0,0 +1,252 @@
+//! Spare parts command handlers
+//!
+//! This module contains Tauri command handlers for the spare parts
+//! inventory: part numbers and stock levels, the components each part fits,
+//! stock received and consumed by maintenance work, and low stock.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{Part, PartCompatibility, PartInput, PartUsage, StockMovement, StockMovementKind};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info};
+
+/// Add a spare part, with any stock already on hand
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn create_part_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    part: PartInput,
+    initial_quantity: Option<i64>,
+) -> CommandResult<Part> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("create_part", {
+        require_resource_access!(context, "asset", "create");
+
+        let created = state.services.parts.create_part(&context, part, initial_quantity.unwrap_or(0))
+            .context("Failed to create part")?;
+        AuthHelper::audit_action(&context, "create", "part", Some(&created.id.to_string()), true, None);
+
+        info!("[{}] Part {} created by user {}", context.request_id, created.part_number,
+              context.current_user().map(|u| u.user_id).unwrap_or(0));
+        Ok(created)
+    });
+
+    Ok(command_handler!("create_part", &context, { result }))
+}
+
+/// Change the details of a spare part
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn update_part_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+    part: PartInput,
+) -> CommandResult<Part> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("update_part", {
+        require_resource_access!(context, "asset", "create");
+
+        let updated = state.services.parts.update_part(&context, id, part)
+            .context("Failed to update part")?;
+        AuthHelper::audit_action(&context, "update", "part", Some(&id.to_string()), true, None);
+
+        info!("[{}] Part {} updated", context.request_id, id);
+        Ok(updated)
+    });
+
+    Ok(command_handler!("update_part", &context, { result }))
+}
+
+/// Get spare parts, optionally matching a part number or name
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_parts_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    search: Option<String>,
+) -> CommandResult<Vec<Part>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_parts", {
+        require_resource_access!(context, "asset", "read");
+
+        let parts = state.services.parts.get_parts(search)
+            .context("Failed to get parts")?;
+
+        debug!("[{}] Retrieved {} parts", context.request_id, parts.len());
+        Ok(parts)
+    });
+
+    Ok(command_handler!("get_parts", &context, { result }))
+}
+
+/// Replace the list of components a part fits
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn set_part_compatibility_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    part_id: i64,
+    compatibility: Vec<PartCompatibility>,
+) -> CommandResult<Part> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("set_part_compatibility", {
+        require_resource_access!(context, "asset", "create");
+
+        let part = state.services.parts.set_compatibility(&context, part_id, compatibility)
+            .context("Failed to set part compatibility")?;
+        AuthHelper::audit_action(&context, "set_compatibility", "part", Some(&part_id.to_string()), true, None);
+
+        info!("[{}] Compatibility of part {} set to {} entries", context.request_id,
+              part_id, part.compatibility.len());
+        Ok(part)
+    });
+
+    Ok(command_handler!("set_part_compatibility", &context, { result }))
+}
+
+/// Get the spare parts that fit a component
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_compatible_parts_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    component_id: i64,
+) -> CommandResult<Vec<Part>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_compatible_parts", {
+        require_resource_access!(context, "asset", "read");
+
+        let parts = state.services.parts.get_compatible_parts(component_id)
+            .context("Failed to get compatible parts")?;
+
+        debug!("[{}] Found {} parts for component {}", context.request_id, parts.len(), component_id);
+        Ok(parts)
+    });
+
+    Ok(command_handler!("get_compatible_parts", &context, { result }))
+}
+
+/// Receive stock of a part or correct it after a stock count
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn adjust_part_stock_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    part_id: i64,
+    kind: StockMovementKind,
+    quantity_change: i64,
+    notes: Option<String>,
+) -> CommandResult<Part> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("adjust_part_stock", {
+        require_resource_access!(context, "asset", "update");
+
+        let part = state.services.parts.adjust_stock(&context, part_id, kind, quantity_change, notes)
+            .context("Failed to adjust part stock")?;
+        AuthHelper::audit_action(&context, "adjust_stock", "part", Some(&part_id.to_string()), true, None);
+
+        info!("[{}] Stock of part {} changed by {} to {}", context.request_id,
+              part.part_number, quantity_change, part.quantity_on_hand);
+        Ok(part)
+    });
+
+    Ok(command_handler!("adjust_part_stock", &context, { result }))
+}
+
+/// Record the spare parts used by maintenance work
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn record_part_consumption_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    maintenance_record_id: i64,
+    usages: Vec<PartUsage>,
+) -> CommandResult<Vec<StockMovement>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("record_part_consumption", {
+        require_resource_access!(context, "asset", "update");
+
+        let movements = state.services.parts.record_consumption(&context, maintenance_record_id, usages)
+            .context("Failed to record part consumption")?;
+        AuthHelper::audit_action(&context, "consume_parts", "maintenance", Some(&maintenance_record_id.to_string()), true, None);
+
+        info!("[{}] Recorded {} part movements for maintenance record {}", context.request_id,
+              movements.len(), maintenance_record_id);
+        Ok(movements)
+    });
+
+    Ok(command_handler!("record_part_consumption", &context, { result }))
+}
+
+/// Get spare parts at or below their reorder level
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_low_stock_parts_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Vec<Part>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_low_stock_parts", {
+        require_resource_access!(context, "asset", "update");
+
+        let parts = state.services.parts.get_low_stock_parts()
+            .context("Failed to get low stock parts")?;
+
+        debug!("[{}] {} parts are low on stock", context.request_id, parts.len());
+        Ok(parts)
+    });
+
+    Ok(command_handler!("get_low_stock_parts", &context, { result }))
+}
+
+/// Get the stock movements of a part, newest first
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_part_movements_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    part_id: i64,
+) -> CommandResult<Vec<StockMovement>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_part_movements", {
+        require_resource_access!(context, "asset", "read");
+
+        let movements = state.services.parts.get_movements(part_id)
+            .context("Failed to get part movements")?;
+
+        debug!("[{}] Retrieved {} movements for part {}", context.request_id, movements.len(), part_id);
+        Ok(movements)
+    });
+
+    Ok(command_handler!("get_part_movements", &context, { result }))
+}
@@ -0,0 +1,141 @@
+//! Pre-start check command handlers
+//!
+//! This module contains Tauri command handlers for the quick pass/fail
+//! checks operators perform at the start of a shift, separate from formal
+//! inspections.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{MissedPrestartCheck, PrestartCheck, PrestartCheckItem, Shift, DEFAULT_PRESTART_ITEMS};
+use crate::{require_resource_access, time_command, command_handler};
+use chrono::{NaiveDate, Utc};
+use tauri::State;
+use log::{debug, info};
+
+/// Get the default pre-start check item names for fast entry
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_prestart_template_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Vec<String>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_prestart_template", {
+        Ok(DEFAULT_PRESTART_ITEMS.iter().map(|item| item.to_string()).collect())
+    });
+
+    Ok(command_handler!("get_prestart_template", &context, { result }))
+}
+
+/// Record a pre-start check by the current user, for today unless a date is given
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn record_prestart_check_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    asset_id: i64,
+    shift: Shift,
+    items: Vec<PrestartCheckItem>,
+    check_date: Option<NaiveDate>,
+    notes: Option<String>,
+) -> CommandResult<PrestartCheck> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("record_prestart_check", {
+        require_resource_access!(context, "inspection", "create");
+
+        let check = state.services.prestart_checks.record_check(&context, asset_id, shift, check_date, items, notes)
+            .context("Failed to record pre-start check")?;
+
+        info!("[{}] Pre-start check recorded for asset {}: passed={}", context.request_id, asset_id, check.passed);
+        Ok(check)
+    });
+
+    Ok(command_handler!("record_prestart_check", &context, { result }))
+}
+
+/// Get an asset's pre-start checks between two dates, by default the last week
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_prestart_checks_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    asset_id: i64,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> CommandResult<Vec<PrestartCheck>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_prestart_checks", {
+        require_resource_access!(context, "inspection", "read");
+
+        let to = to.unwrap_or_else(|| Utc::now().date_naive());
+        let from = from.unwrap_or(to - chrono::Duration::days(6));
+        let checks = state.services.prestart_checks.get_checks(asset_id, from, to)
+            .context("Failed to get pre-start checks")?;
+
+        debug!("[{}] Retrieved {} pre-start checks for asset {}", context.request_id, checks.len(), asset_id);
+        Ok(checks)
+    });
+
+    Ok(command_handler!("get_prestart_checks", &context, { result }))
+}
+
+/// Get the days in the last week on which required pre-start checks were missed
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_missed_prestart_checks_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    asset_id: Option<i64>,
+) -> CommandResult<Vec<MissedPrestartCheck>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_missed_prestart_checks", {
+        require_resource_access!(context, "inspection", "read");
+
+        let missed = state.services.prestart_checks.get_missed_checks(asset_id)
+            .context("Failed to get missed pre-start checks")?;
+
+        debug!("[{}] Found {} missed pre-start checks", context.request_id, missed.len());
+        Ok(missed)
+    });
+
+    Ok(command_handler!("get_missed_prestart_checks", &context, { result }))
+}
+
+/// Require, or stop requiring, daily pre-start checks for an asset
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn set_prestart_required_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    asset_id: i64,
+    required: bool,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("set_prestart_required", {
+        require_resource_access!(context, "asset", "update");
+
+        state.services.prestart_checks.set_required(&context, asset_id, required)
+            .context("Failed to update pre-start requirement")?;
+        AuthHelper::audit_action(&context, "set_prestart_required", "asset", Some(&asset_id.to_string()), true, None);
+
+        info!("[{}] Pre-start checks required={} for asset {}", context.request_id, required, asset_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("set_prestart_required", &context, { result }))
+}
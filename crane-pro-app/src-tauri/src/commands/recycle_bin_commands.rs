@@ -0,0 +1,84 @@
+//! Recycle bin command handlers
+//!
+//! This module contains Tauri command handlers for listing and restoring
+//! deleted assets, inspections, locations, users and teams. Soft-deleted
+//! assets, inspections and users are kept until an administrator purges
+//! them; deleted locations and teams stay restorable for a retention window
+//! (`CRANEPRO_RECYCLE_RETENTION_DAYS`) before they are purged for good.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{DeletedRecord, RecycleEntityType, RestoreResult};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{info, debug, warn};
+
+/// List restorable deleted records, of one entity type or of every type the
+/// user is allowed to delete
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn list_deleted_records_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    entity_type: Option<RecycleEntityType>,
+) -> CommandResult<Vec<DeletedRecord>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("list_deleted_records", {
+        let entity_types: Vec<RecycleEntityType> = match entity_type {
+            Some(entity_type) => {
+                require_resource_access!(context, entity_type.resource(), "delete");
+                vec![entity_type]
+            }
+            None => {
+                let session = context.current_user()?;
+                RecycleEntityType::ALL.into_iter()
+                    .filter(|t| session.can_access_resource(t.resource(), "delete"))
+                    .collect()
+            }
+        };
+
+        if let Err(e) = state.services.recycle_bin.purge_expired() {
+            warn!("[{}] Failed to purge expired recycle bin entries: {}", context.request_id, e);
+        }
+        let records = state.services.recycle_bin.list_deleted(&entity_types)
+            .context("Failed to list deleted records")?;
+
+        debug!("[{}] Retrieved {} deleted records", context.request_id, records.len());
+        Ok(records)
+    });
+
+    Ok(command_handler!("list_deleted_records", &context, { result }))
+}
+
+/// Restore a deleted record from the recycle bin
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn restore_record_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    entity_type: RecycleEntityType,
+    entity_id: i64,
+) -> CommandResult<RestoreResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("restore_record", {
+        require_resource_access!(context, entity_type.resource(), "delete");
+
+        let restored = state.services.recycle_bin.restore(&context, entity_type, entity_id)
+            .with_context(|| format!("Failed to restore {}", entity_type))?;
+        AuthHelper::audit_action(&context, "restore", entity_type.resource(), Some(&entity_id.to_string()), true, None);
+
+        info!("[{}] {} restored: {} (ID: {}) by user {}", context.request_id,
+              entity_type, restored.label, entity_id,
+              context.current_user().map(|u| u.user_id).unwrap_or(0));
+
+        Ok(restored)
+    });
+
+    Ok(command_handler!("restore_record", &context, { result }))
+}
@@ -3,330 +3,717 @@
 //! This module contains all Tauri command handlers for report generation
 //! operations including inspection reports, compliance reports, and report management.
 
-use crate::api::{ApiResponse, ReportFormat, DateRange, ReportResult, ReportTemplate};
-use crate::commands::AppState;
+use crate::api::{ChartSpec, ReportFormat, DateRange, ReportResult, ReportTemplate, SummaryReportRequest};
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::exporters;
+use crate::exporters::csv::{csv_field, CsvOptions};
+use crate::middleware::RequestContext;
+use crate::models::{
+    is_valid_report_id, CreatedShareLink, CustomReportTemplate, CustomReportTemplateInput, CustomReportType, MediaType,
+    ReportArtifact, ReportShareLink, ShareLinkInput, SharedReport,
+};
+use crate::i18n::{translate, Locale, Localize};
+use crate::units;
+use crate::reports::chart;
+use crate::reports::checklist::render_paper_checklist;
+use crate::reports::compliance::{render_compliance_report, ComplianceReport};
+use crate::reports::inspection::{render_inspection_report, InspectionReport, ReportPhoto};
+use crate::reports::pdf::PdfImage;
+use crate::reports::signature::{sign_pdf, verify_pdf, ReportVerification};
+use crate::reports::store::ReportKey;
+use crate::security::secrets::Secrets;
+use crate::security::signing;
+use crate::commands::media_commands::media_root;
 use crate::middleware::auth::AuthHelper;
+use crate::middleware::record_access::RecordAction;
 use crate::{require_resource_access, time_command, command_handler};
 use tauri::State;
-use log::{info, debug};
+use crate::errors::AppError;
+use log::{info, debug, warn};
 use chrono::Utc;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::fs;
 
+pub use crate::reports::store::REPORTS_DIR;
+
+/// Size of charts in HTML reports, in pixels
+const CHART_WIDTH: f32 = 640.0;
+const CHART_HEIGHT: f32 = 240.0;
+
+/// The generated file for `report_id` and its format, if there is one
+pub fn find_report_file(report_id: &str) -> Option<(PathBuf, ReportFormat)> {
+    if !is_valid_report_id(report_id) {
+        return None;
+    }
+    exporters::registry()
+        .writers()
+        .map(|writer| (Path::new(REPORTS_DIR).join(format!("{}.{}", report_id, writer.extension())), writer.format()))
+        .find(|(path, _)| path.is_file())
+}
+
+/// What a command returns for a generated report
+fn report_result(artifact: &ReportArtifact, format: ReportFormat) -> ReportResult {
+    ReportResult {
+        report_id: artifact.report_id.clone(),
+        format,
+        file_path: Some(artifact.file_path.clone()),
+        file_url: Some(format!("/api/reports/{}/download", artifact.report_id)),
+        generated_at: artifact.generated_at,
+        expires_at: Some(artifact.expires_at),
+    }
+}
+
+/// Append the installation's signature to a generated PDF report
+fn sign_report(secrets: &Secrets, pdf: Vec<u8>) -> Result<Vec<u8>, AppError> {
+    let signing_key = secrets.report_signing_key()
+        .context("Failed to load the report signing key")?;
+    sign_pdf(pdf, &signing_key, Utc::now())
+        .context("Failed to sign PDF report")
+}
+
 /// Generate inspection report
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn generate_inspection_report_command(
     state: State<'_, AppState>,
+    secrets: State<'_, Secrets>,
     token: Option<String>,
     inspection_id: i64,
     format: ReportFormat,
-) -> Result<ApiResponse<ReportResult>, String> {
+) -> CommandResult<ReportResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("generate_inspection_report", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "report", "generate");
 
         // Get inspection data
         let inspection = state.services.inspections.get_inspection_by_id(inspection_id)
-            .map_err(|e| format!("Failed to get inspection: {}", e))?;
+            .context("Failed to get inspection")?;
 
         // Get asset data
         let asset = state.services.assets.get_asset_by_id(inspection.asset_id)
-            .map_err(|e| format!("Failed to get asset: {}", e))?;
+            .context("Failed to get asset")?;
 
         // Get inspection items
         let inspection_items = state.services.inspections.get_inspection_items(inspection_id)
-            .map_err(|e| format!("Failed to get inspection items: {}", e))?;
+            .context("Failed to get inspection items")?;
 
-        // Get media files
+        // Get the standard clauses cited by findings
+        let clauses = state.services.compliance.get_inspection_clauses(inspection_id)
+            .context("Failed to get cited clauses")?;
+
+        // Get media files, which the report embeds
+        state.services.media.authorize_media(&context, Some(inspection_id), None, None, RecordAction::Read)?;
         let media_files = state.services.media.get_media_files_by_inspection(inspection_id)
-            .map_err(|e| format!("Failed to get media files: {}", e))?;
-
-        // Generate report ID
-        let report_id = format!("inspection_{}_{}", 
-                               inspection_id, 
-                               Utc::now().format("%Y%m%d_%H%M%S"));
-
-        // Create reports directory
-        let reports_dir = "./data/reports";
-        fs::create_dir_all(reports_dir)
-            .map_err(|e| format!("Failed to create reports directory: {}", e))?;
-
-        // Generate report based on format
-        let file_extension = match format {
-            ReportFormat::Pdf => "pdf",
-            ReportFormat::Html => "html",
-            ReportFormat::Json => "json",
-            ReportFormat::Csv => "csv",
+            .context("Failed to get media files")?;
+
+        // A report of unchanged records is handed out again rather than rebuilt
+        let template = match format {
+            ReportFormat::Html => state.services.reports.assigned_report_template(CustomReportType::Inspection)
+                .context("Failed to get report template")?,
+            _ => None,
         };
+        let key = ReportKey::new("inspection", &format, context.locale(), &inspection_id, &serde_json::json!({
+            "inspection": inspection,
+            "asset": asset,
+            "items": inspection_items,
+            "clauses": clauses,
+            "media_files": media_files,
+            "template": template,
+        })).context("Failed to key report")?;
+        let cached = state.services.reports.cached_report(&key, Utc::now())
+            .context("Failed to look up generated reports")?;
+        let artifact = match cached {
+            Some(artifact) => {
+                debug!("[{}] Reusing inspection report {} for inspection {}", context.request_id,
+                       artifact.report_id, inspection_id);
+                artifact
+            }
+            None => {
+                // Generate report ID
+                let generated_at = Utc::now();
+                let report_id = format!("inspection_{}_{}", 
+                                       inspection_id, 
+                                       generated_at.format("%Y%m%d_%H%M%S"));
+
+                // Create reports directory
+                let reports_dir = REPORTS_DIR;
+                fs::create_dir_all(reports_dir)
+                    .context("Failed to create reports directory")?;
 
-        let file_name = format!("{}.{}", report_id, file_extension);
-        let file_path = format!("{}/{}", reports_dir, file_name);
-
-        // Generate report content based on format
-        match format {
-            ReportFormat::Json => {
-                let report_data = serde_json::json!({
-                    "report_id": report_id,
-                    "report_type": "inspection",
-                    "generated_at": Utc::now(),
-                    "inspection": {
-                        "id": inspection.id,
-                        "asset_id": inspection.asset_id,
-                        "asset_name": asset.asset_name,
-                        "asset_number": asset.asset_number,
-                        "inspection_type": inspection.inspection_type,
-                        "compliance_standard": inspection.compliance_standard,
-                        "scheduled_date": inspection.scheduled_date,
-                        "actual_date": inspection.actual_date,
-                        "status": inspection.status,
-                        "overall_condition": inspection.overall_condition,
-                        "notes": inspection.notes
+                let file_extension = exporters::registry().get(&format)
+                    .context("Unsupported report format")?
+                    .extension();
+
+                let file_name = format!("{}.{}", report_id, file_extension);
+                let file_path = format!("{}/{}", reports_dir, file_name);
+
+                // Generate report content based on format
+                match &format {
+                    ReportFormat::Json => {
+                        let report_data = inspection_report_data(&report_id, &inspection, &asset, &inspection_items, &clauses, &media_files);
+                        fs::write(&file_path, serde_json::to_string_pretty(&report_data).unwrap())
+                            .context("Failed to write JSON report")?;
+                    },
+                    ReportFormat::Html => {
+                        let html_content = match template {
+                            Some(template) => {
+                                let report_data = inspection_report_data(&report_id, &inspection, &asset, &inspection_items, &clauses, &media_files);
+                                state.services.reports.render_report_template(&template.template, &report_data)
+                                    .with_context(|| format!("Failed to render report template '{}'", template.name))?
+                            }
+                            None => generate_html_inspection_report(&inspection, &asset, &inspection_items, &clauses, &media_files, context.locale()),
+                        };
+                        fs::write(&file_path, html_content)
+                            .context("Failed to write HTML report")?;
+                    },
+                    ReportFormat::Csv => {
+                        let csv_content = generate_csv_inspection_report(&inspection, &asset, &inspection_items, &clauses);
+                        fs::write(&file_path, csv_content)
+                            .context("Failed to write CSV report")?;
                     },
-                    "items": inspection_items,
-                    "media_files": media_files.iter().map(|f| serde_json::json!({
-                        "id": f.id,
-                        "file_name": f.file_name,
-                        "file_type": f.file_type,
-                        "description": f.description
-                    })).collect::<Vec<_>>(),
-                    "summary": {
-                        "total_items": inspection_items.len(),
-                        "compliant_items": inspection_items.iter().filter(|i| i.is_compliant == Some(true)).count(),
-                        "non_compliant_items": inspection_items.iter().filter(|i| i.is_compliant == Some(false)).count(),
-                        "critical_findings": inspection_items.iter().filter(|i| matches!(i.severity, Some(crate::models::Severity::Critical))).count(),
-                        "media_count": media_files.len()
+                    ReportFormat::Pdf => {
+                        let inspector_name = state.services.users.get_user_by_id(inspection.inspector_id)
+                            .map(|user| format!("{} {}", user.first_name, user.last_name).trim().to_string())
+                            .context("Failed to get inspector")?;
+
+                        // A photo that cannot be read is left out rather than failing the report
+                        let media_root = media_root(&state)?;
+                        let photos = media_files.iter()
+                            .filter(|f| f.file_type == MediaType::Image)
+                            .filter_map(|f| {
+                                let path = format!("{}/{}", media_root, f.file_path);
+                                match fs::read(&path).map_err(AppError::from).and_then(|data| PdfImage::from_file_bytes(&data)) {
+                                    Ok(image) => Some(ReportPhoto {
+                                        caption: f.description.clone().unwrap_or_else(|| f.file_name.clone()),
+                                        image,
+                                    }),
+                                    Err(e) => {
+                                        warn!("[{}] Leaving photo {} out of report {}: {}", context.request_id, path, report_id, e);
+                                        None
+                                    }
+                                }
+                            })
+                            .collect();
+
+                        let report = InspectionReport {
+                            inspection: &inspection,
+                            asset: &asset,
+                            inspector_name,
+                            items: &inspection_items,
+                            clauses: &clauses,
+                            photos,
+                        };
+                        let pdf = render_inspection_report(&report, context.locale(), generated_at)
+                            .context("Failed to render PDF report")?;
+                        let pdf = sign_report(&secrets, pdf)?;
+                        fs::write(&file_path, pdf)
+                            .context("Failed to write PDF report")?;
                     }
-                });
+                }
 
-                fs::write(&file_path, serde_json::to_string_pretty(&report_data).unwrap())
-                    .map_err(|e| format!("Failed to write JSON report: {}", e))?;
-            },
-            ReportFormat::Html => {
-                let html_content = generate_html_inspection_report(&inspection, &asset, &inspection_items, &media_files);
-                fs::write(&file_path, html_content)
-                    .map_err(|e| format!("Failed to write HTML report: {}", e))?;
-            },
-            ReportFormat::Csv => {
-                let csv_content = generate_csv_inspection_report(&inspection, &asset, &inspection_items);
-                fs::write(&file_path, csv_content)
-                    .map_err(|e| format!("Failed to write CSV report: {}", e))?;
-            },
-            ReportFormat::Pdf => {
-                // In a real implementation, this would use a PDF generation library
-                let pdf_placeholder = b"PDF report generation not implemented yet";
-                fs::write(&file_path, pdf_placeholder)
-                    .map_err(|e| format!("Failed to write PDF report: {}", e))?;
-            }
-        }
+                info!("[{}] Inspection report generated: {} for inspection {} by user {}", context.request_id,
+                      report_id, inspection_id,
+                      context.current_user().map(|u| u.user_id).unwrap_or(0));
 
-        let report_result = ReportResult {
-            report_id: report_id.clone(),
-            format,
-            file_path: Some(file_path.clone()),
-            file_url: Some(format!("/api/reports/{}/download", report_id)),
-            generated_at: Utc::now(),
-            expires_at: Some(Utc::now() + chrono::Duration::days(30)), // Reports expire in 30 days
+                state.services.reports.record_report(&context, &key, &report_id, &file_path, generated_at)
+                    .context("Failed to record report")?
+            }
         };
 
-        info!("Inspection report generated: {} for inspection {} by user {}", 
-              report_id, inspection_id,
-              context.current_user().map(|u| u.user_id).unwrap_or(0));
+        Ok(report_result(&artifact, format))
+    });
 
-        Ok(report_result)
+    Ok(command_handler!("generate_inspection_report", &context, { result }))
+}
+
+/// Generate a blank checklist to print for an inspection done on paper,
+/// with a QR code linking back to the inspection
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn generate_paper_checklist_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    inspection_id: i64,
+) -> CommandResult<ReportResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("generate_paper_checklist", {
+        require_resource_access!(context, "report", "generate");
+
+        let checklist = state.services.inspections.get_paper_checklist(inspection_id)
+            .context("Failed to get inspection checklist")?;
+
+        // The printed checklist is the same in every locale
+        let key = ReportKey::new("checklist", &ReportFormat::Pdf, Locale::default(), &inspection_id, &checklist)
+            .context("Failed to key checklist")?;
+        let cached = state.services.reports.cached_report(&key, Utc::now())
+            .context("Failed to look up generated reports")?;
+        let artifact = match cached {
+            Some(artifact) => {
+                debug!("[{}] Reusing paper checklist {} for inspection {}", context.request_id,
+                       artifact.report_id, inspection_id);
+                artifact
+            }
+            None => {
+                let generated_at = Utc::now();
+                let pdf = render_paper_checklist(&checklist, generated_at)
+                    .context("Failed to render checklist")?;
+
+                let report_id = format!("checklist_{}_{}", inspection_id, generated_at.format("%Y%m%d_%H%M%S"));
+                fs::create_dir_all(REPORTS_DIR)
+                    .context("Failed to create reports directory")?;
+                let file_path = format!("{}/{}.pdf", REPORTS_DIR, report_id);
+                fs::write(&file_path, pdf)
+                    .context("Failed to write checklist")?;
+
+                info!("[{}] Paper checklist generated: {} for inspection {}", context.request_id, report_id, inspection_id);
+
+                state.services.reports.record_report(&context, &key, &report_id, &file_path, generated_at)
+                    .context("Failed to record report")?
+            }
+        };
+
+        Ok(report_result(&artifact, ReportFormat::Pdf))
     });
 
-    Ok(command_handler!("generate_inspection_report", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("generate_paper_checklist", &context, { result }))
 }
 
 /// Generate compliance report
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn generate_compliance_report_command(
     state: State<'_, AppState>,
+    secrets: State<'_, Secrets>,
     token: Option<String>,
     asset_id: i64,
     date_range: DateRange,
     format: ReportFormat,
-) -> Result<ApiResponse<ReportResult>, String> {
+) -> CommandResult<ReportResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("generate_compliance_report", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "report", "generate");
 
         // Get asset data
         let asset = state.services.assets.get_asset_by_id(asset_id)
-            .map_err(|e| format!("Failed to get asset: {}", e))?;
+            .context("Failed to get asset")?;
 
         // Get compliance status report
         let compliance_report = state.services.reports.generate_compliance_status_report(Some(asset.location_id))
-            .map_err(|e| format!("Failed to generate compliance status: {}", e))?;
-
-        // Generate report ID
-        let report_id = format!("compliance_{}_{}", 
-                               asset_id, 
-                               Utc::now().format("%Y%m%d_%H%M%S"));
-
-        // Create reports directory
-        let reports_dir = "./data/reports";
-        fs::create_dir_all(reports_dir)
-            .map_err(|e| format!("Failed to create reports directory: {}", e))?;
-
-        // Generate report based on format
-        let file_extension = match format {
-            ReportFormat::Pdf => "pdf",
-            ReportFormat::Html => "html",
-            ReportFormat::Json => "json",
-            ReportFormat::Csv => "csv",
+            .context("Failed to generate compliance status")?;
+
+        // Get insurance and registration records
+        let asset_records = state.services.asset_records.get_records(asset_id)
+            .context("Failed to get asset records")?;
+
+        // Get the trend charts
+        let charts = state.services.reports
+            .generate_asset_charts(asset_id, date_range.start_date, date_range.end_date, context.locale())
+            .context("Failed to generate report charts")?;
+
+        // A report of unchanged records is handed out again rather than rebuilt
+        let template = match format {
+            ReportFormat::Html => state.services.reports.assigned_report_template(CustomReportType::Compliance)
+                .context("Failed to get report template")?,
+            _ => None,
         };
+        let key = ReportKey::new("compliance", &format, context.locale(), &(asset_id, &date_range), &serde_json::json!({
+            "asset": asset,
+            "compliance_status": compliance_report,
+            "asset_records": asset_records,
+            "charts": charts,
+            "template": template,
+        })).context("Failed to key report")?;
+        let cached = state.services.reports.cached_report(&key, Utc::now())
+            .context("Failed to look up generated reports")?;
+        let artifact = match cached {
+            Some(artifact) => {
+                debug!("[{}] Reusing compliance report {} for asset {}", context.request_id,
+                       artifact.report_id, asset_id);
+                artifact
+            }
+            None => {
+                // Generate report ID
+                let generated_at = Utc::now();
+                let report_id = format!("compliance_{}_{}", 
+                                       asset_id, 
+                                       generated_at.format("%Y%m%d_%H%M%S"));
+
+                // Create reports directory
+                let reports_dir = REPORTS_DIR;
+                fs::create_dir_all(reports_dir)
+                    .context("Failed to create reports directory")?;
 
-        let file_name = format!("{}.{}", report_id, file_extension);
-        let file_path = format!("{}/{}", reports_dir, file_name);
-
-        // Generate report content
-        match format {
-            ReportFormat::Json => {
-                let report_data = serde_json::json!({
-                    "report_id": report_id,
-                    "report_type": "compliance",
-                    "generated_at": Utc::now(),
-                    "date_range": {
-                        "start_date": date_range.start_date,
-                        "end_date": date_range.end_date
+                let file_extension = exporters::registry().get(&format)
+                    .context("Unsupported report format")?
+                    .extension();
+
+                let file_name = format!("{}.{}", report_id, file_extension);
+                let file_path = format!("{}/{}", reports_dir, file_name);
+
+                // Generate report content
+                match &format {
+                    ReportFormat::Json => {
+                        let report_data = compliance_report_data(&report_id, &date_range, &asset, &compliance_report, &asset_records, &charts);
+                        fs::write(&file_path, serde_json::to_string_pretty(&report_data).unwrap())
+                            .context("Failed to write JSON compliance report")?;
                     },
-                    "asset": {
-                        "id": asset.id,
-                        "name": asset.asset_name,
-                        "asset_number": asset.asset_number,
-                        "type": asset.asset_type,
-                        "location_id": asset.location_id
+                    ReportFormat::Html => {
+                        let html_content = match template {
+                            Some(template) => {
+                                let report_data = compliance_report_data(&report_id, &date_range, &asset, &compliance_report, &asset_records, &charts);
+                                state.services.reports.render_report_template(&template.template, &report_data)
+                                    .with_context(|| format!("Failed to render report template '{}'", template.name))?
+                            }
+                            None => generate_html_compliance_report(&asset, &compliance_report, &asset_records, &charts, &date_range, context.locale()),
+                        };
+                        fs::write(&file_path, html_content)
+                            .context("Failed to write HTML compliance report")?;
                     },
-                    "compliance_status": compliance_report
-                });
+                    ReportFormat::Csv => {
+                        let csv_content = generate_csv_compliance_report(&asset, &compliance_report, &asset_records);
+                        fs::write(&file_path, csv_content)
+                            .context("Failed to write CSV compliance report")?;
+                    },
+                    ReportFormat::Pdf => {
+                        let report = ComplianceReport {
+                            asset: &asset,
+                            date_range: &date_range,
+                            status: &compliance_report,
+                            records: &asset_records,
+                            charts: &charts,
+                        };
+                        let pdf = render_compliance_report(&report, context.locale(), generated_at)
+                            .context("Failed to render PDF compliance report")?;
+                        let pdf = sign_report(&secrets, pdf)?;
+                        fs::write(&file_path, pdf)
+                            .context("Failed to write PDF compliance report")?;
+                    }
+                }
 
-                fs::write(&file_path, serde_json::to_string_pretty(&report_data).unwrap())
-                    .map_err(|e| format!("Failed to write JSON compliance report: {}", e))?;
-            },
-            ReportFormat::Html => {
-                let html_content = generate_html_compliance_report(&asset, &compliance_report, &date_range);
-                fs::write(&file_path, html_content)
-                    .map_err(|e| format!("Failed to write HTML compliance report: {}", e))?;
-            },
-            ReportFormat::Csv => {
-                let csv_content = generate_csv_compliance_report(&asset, &compliance_report);
-                fs::write(&file_path, csv_content)
-                    .map_err(|e| format!("Failed to write CSV compliance report: {}", e))?;
-            },
-            ReportFormat::Pdf => {
-                // PDF generation placeholder
-                let pdf_placeholder = b"PDF compliance report generation not implemented yet";
-                fs::write(&file_path, pdf_placeholder)
-                    .map_err(|e| format!("Failed to write PDF compliance report: {}", e))?;
+                info!("[{}] Compliance report generated: {} for asset {} by user {}", context.request_id,
+                      report_id, asset_id,
+                      context.current_user().map(|u| u.user_id).unwrap_or(0));
+
+                state.services.reports.record_report(&context, &key, &report_id, &file_path, generated_at)
+                    .context("Failed to record report")?
             }
-        }
+        };
+
+        Ok(report_result(&artifact, format))
+    });
+
+    Ok(command_handler!("generate_compliance_report", &context, { result }))
+}
+
+/// Export the asset summary, inspection completion or compliance status
+/// report as CSV
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn generate_summary_report_csv_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    report: SummaryReportRequest,
+    options: Option<CsvOptions>,
+) -> CommandResult<ReportResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("generate_summary_report_csv", {
+        require_resource_access!(context, "report", "generate");
 
-        let report_result = ReportResult {
-            report_id: report_id.clone(),
-            format,
-            file_path: Some(file_path.clone()),
-            file_url: Some(format!("/api/reports/{}/download", report_id)),
-            generated_at: Utc::now(),
-            expires_at: Some(Utc::now() + chrono::Duration::days(30)),
+        let options = options.unwrap_or_default();
+        let (name, csv) = match &report {
+            SummaryReportRequest::AssetSummary { asset_id } => (
+                format!("asset_summary_{}", asset_id),
+                state.services.reports.generate_asset_summary_csv(*asset_id, &options),
+            ),
+            SummaryReportRequest::InspectionCompletion { date_range } => (
+                "inspection_completion".to_string(),
+                state.services.reports.generate_inspection_completion_csv(date_range.start_date, date_range.end_date, &options),
+            ),
+            SummaryReportRequest::ComplianceStatus { location_id } => (
+                "compliance_status".to_string(),
+                state.services.reports.generate_compliance_status_csv(*location_id, &options),
+            ),
         };
+        let csv = csv.with_context(|| format!("Failed to generate {} report", name))?;
 
-        info!("Compliance report generated: {} for asset {} by user {}", 
-              report_id, asset_id,
-              context.current_user().map(|u| u.user_id).unwrap_or(0));
+        // The same rows written with the same options are handed out again
+        let key = ReportKey::new(&name, &ReportFormat::Csv, context.locale(), &(&report, &options), &csv)
+            .context("Failed to key report")?;
+        let cached = state.services.reports.cached_report(&key, Utc::now())
+            .context("Failed to look up generated reports")?;
+        let artifact = match cached {
+            Some(artifact) => {
+                debug!("[{}] Reusing CSV report {}", context.request_id, artifact.report_id);
+                artifact
+            }
+            None => {
+                let generated_at = Utc::now();
+                let report_id = format!("{}_{}", name, generated_at.format("%Y%m%d_%H%M%S"));
+                fs::create_dir_all(REPORTS_DIR)
+                    .context("Failed to create reports directory")?;
+                let file_path = format!("{}/{}.csv", REPORTS_DIR, report_id);
+                fs::write(&file_path, csv)
+                    .context("Failed to write CSV report")?;
 
-        Ok(report_result)
+                info!("[{}] CSV report generated: {}", context.request_id, report_id);
+
+                state.services.reports.record_report(&context, &key, &report_id, &file_path, generated_at)
+                    .context("Failed to record report")?
+            }
+        };
+
+        Ok(report_result(&artifact, ReportFormat::Csv))
     });
 
-    Ok(command_handler!("generate_compliance_report", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("generate_summary_report_csv", &context, { result }))
 }
 
 /// Get report by ID
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_report_command(
     state: State<'_, AppState>,
     token: Option<String>,
     report_id: String,
-) -> Result<ApiResponse<ReportResult>, String> {
+) -> CommandResult<ReportResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_report", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "report", "read");
 
-        // Check if report file exists
-        let reports_dir = "./data/reports";
-        let possible_extensions = ["pdf", "html", "json", "csv"];
-        
-        let mut found_file = None;
-        let mut found_format = None;
-        
-        for ext in &possible_extensions {
-            let file_path = format!("{}/{}.{}", reports_dir, report_id, ext);
-            if Path::new(&file_path).exists() {
-                found_file = Some(file_path);
-                found_format = Some(match *ext {
-                    "pdf" => ReportFormat::Pdf,
-                    "html" => ReportFormat::Html,
-                    "json" => ReportFormat::Json,
-                    "csv" => ReportFormat::Csv,
-                    _ => ReportFormat::Json,
-                });
-                break;
+        let (file_path, format) = find_report_file(&report_id)
+            .ok_or_else(|| AppError::RecordNotFound {
+                entity: "Report".to_string(),
+                field: "report_id".to_string(),
+                value: report_id.clone(),
+            })?;
+
+        let artifact = state.services.reports.get_report_artifact(&file_path.to_string_lossy())
+            .context("Failed to get report")?;
+        let report_result = match artifact {
+            Some(artifact) => report_result(&artifact, format),
+            // Files written outside the store expire by age
+            None => {
+                let metadata = fs::metadata(&file_path)
+                    .context("Failed to get report metadata")?;
+                let settings = state.services.settings.get_settings()
+                    .context("Failed to read application settings")?;
+                let generated_at = metadata.modified()
+                    .map(chrono::DateTime::from)
+                    .unwrap_or_else(|_| Utc::now());
+                ReportResult {
+                    report_id: report_id.clone(),
+                    format,
+                    file_path: Some(file_path.to_string_lossy().into_owned()),
+                    file_url: Some(format!("/api/reports/{}/download", report_id)),
+                    generated_at,
+                    expires_at: Some(generated_at + chrono::Duration::days(settings.report_retention_days)),
+                }
             }
+        };
+
+        debug!("[{}] Report retrieved: {}", context.request_id, report_id);
+        Ok(report_result)
+    });
+
+    Ok(command_handler!("get_report", &context, { result }))
+}
+
+/// Check a PDF report file against the signature embedded when it was
+/// generated, proving it has not been changed since
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn verify_report_signature_command(
+    state: State<'_, AppState>,
+    secrets: State<'_, Secrets>,
+    token: Option<String>,
+    file_path: String,
+) -> CommandResult<ReportVerification> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("verify_report_signature", {
+        require_resource_access!(context, "report", "read");
+
+        let file = fs::read(&file_path)
+            .with_context(|| format!("Failed to read report {}", file_path))?;
+        let signing_key = secrets.report_signing_key()
+            .context("Failed to load the report signing key")?;
+        let public_key = signing::public_key(&signing_key)
+            .context("Failed to load the report signing key")?;
+
+        let verification = verify_pdf(&file, &public_key);
+        AuthHelper::audit_action(&context, "verify_signature", "report", Some(&file_path), true, None);
+
+        info!("[{}] Report {} signature {}", context.request_id, file_path,
+              if verification.valid { "verified" } else { "did not verify" });
+        Ok(verification)
+    });
+
+    Ok(command_handler!("verify_report_signature", &context, { result }))
+}
+
+/// Create a read-only share link to a generated report
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn create_report_share_link_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    input: ShareLinkInput,
+) -> CommandResult<CreatedShareLink> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("create_report_share_link", {
+        require_resource_access!(context, "report", "export");
+
+        if find_report_file(&input.report_id).is_none() {
+            return Err(AppError::RecordNotFound {
+                entity: "Report".to_string(),
+                field: "report_id".to_string(),
+                value: input.report_id.clone(),
+            });
         }
+        let created = state.services.reports.create_share_link(&context, input)
+            .context("Failed to create share link")?;
+        AuthHelper::audit_action(&context, "share", "report", Some(&created.link.report_id), true, None);
 
-        let (file_path, format) = match (found_file, found_format) {
-            (Some(path), Some(fmt)) => (path, fmt),
-            _ => return Err(format!("Report not found: {}", report_id)),
-        };
+        info!("[{}] Share link {} created for report {} by user {}", context.request_id,
+              created.link.id, created.link.report_id,
+              context.current_user().map(|u| u.user_id).unwrap_or(0));
 
-        // Get file metadata
-        let metadata = fs::metadata(&file_path)
-            .map_err(|e| format!("Failed to get report metadata: {}", e))?;
-
-        let report_result = ReportResult {
-            report_id: report_id.clone(),
-            format,
-            file_path: Some(file_path),
-            file_url: Some(format!("/api/reports/{}/download", report_id)),
-            generated_at: metadata.created()
-                .map(|t| chrono::DateTime::from(t))
-                .unwrap_or_else(|_| Utc::now()),
-            expires_at: Some(Utc::now() + chrono::Duration::days(30)),
-        };
+        Ok(created)
+    });
 
-        debug!("Report retrieved: {}", report_id);
-        Ok(report_result)
+    Ok(command_handler!("create_report_share_link", &context, { result }))
+}
+
+/// List the share links created for a report
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_report_share_links_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    report_id: String,
+) -> CommandResult<Vec<ReportShareLink>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_report_share_links", {
+        require_resource_access!(context, "report", "read");
+
+        let links = state.services.reports.get_share_links(&report_id)
+            .context("Failed to get share links")?;
+
+        debug!("[{}] Retrieved {} share links for report {}", context.request_id, links.len(), report_id);
+        Ok(links)
+    });
+
+    Ok(command_handler!("get_report_share_links", &context, { result }))
+}
+
+/// Revoke a report share link
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn revoke_report_share_link_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<ReportShareLink> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("revoke_report_share_link", {
+        require_resource_access!(context, "report", "export");
+
+        let link = state.services.reports.revoke_share_link(&context, id)
+            .context("Failed to revoke share link")?;
+        AuthHelper::audit_action(&context, "revoke_share", "report", Some(&link.report_id), true, None);
+
+        info!("[{}] Share link {} for report {} revoked by user {}", context.request_id,
+              link.id, link.report_id,
+              context.current_user().map(|u| u.user_id).unwrap_or(0));
+
+        Ok(link)
+    });
+
+    Ok(command_handler!("revoke_report_share_link", &context, { result }))
+}
+
+/// Open a report through a share link. No login is needed; the share token
+/// itself grants read access to that one report.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn open_shared_report_command(
+    state: State<'_, AppState>,
+    share_token: String,
+) -> CommandResult<SharedReport> {
+    // Share links are opened without a session
+    let context = RequestContext::new();
+    context.record_in_span();
+
+    let result = time_command!("open_shared_report", {
+        let link = state.services.reports.open_share_link(&share_token)
+            .inspect_err(|e| {
+                warn!("[{}] Share link rejected: {}", context.request_id, e);
+            })?;
+
+        let (file_path, format) = find_report_file(&link.report_id)
+            .ok_or_else(|| AppError::RecordNotFound {
+                entity: "Report".to_string(),
+                field: "report_id".to_string(),
+                value: link.report_id.clone(),
+            })?;
+        let content = fs::read(&file_path)
+            .context("Failed to read report")?;
+
+        info!("[{}] Report {} opened through share link {} ({} views)", context.request_id,
+              link.report_id, link.id, link.view_count);
+
+        Ok(SharedReport {
+            file_name: file_path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+            report_id: link.report_id,
+            content_type: exporters::registry().get(&format)
+                .map(|writer| writer.content_type().to_string())
+                .context("Unsupported report format")?,
+            content,
+            expires_at: link.expires_at,
+        })
     });
 
-    Ok(command_handler!("get_report", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("open_shared_report", &context, { result }))
 }
 
 /// List available report templates
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn list_available_reports_command(
     state: State<'_, AppState>,
     token: Option<String>,
-) -> Result<ApiResponse<Vec<ReportTemplate>>, String> {
+) -> CommandResult<Vec<ReportTemplate>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("list_available_reports", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "report", "read");
 
         // Define available report templates
@@ -394,29 +781,309 @@ pub async fn list_available_reports_command(
             },
         ];
 
-        debug!("Listed {} available report templates", templates.len());
+        debug!("[{}] Listed {} available report templates", context.request_id, templates.len());
+        Ok(templates)
+    });
+
+    Ok(command_handler!("list_available_reports", &context, { result }))
+}
+
+/// Get custom report templates, of one report type when given
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_report_templates_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    report_type: Option<CustomReportType>,
+) -> CommandResult<Vec<CustomReportTemplate>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_report_templates", {
+        require_resource_access!(context, "report", "templates");
+
+        let templates = state.services.reports.get_report_templates(report_type)
+            .context("Failed to get report templates")?;
+
+        debug!("[{}] Retrieved {} report templates", context.request_id, templates.len());
         Ok(templates)
     });
 
-    Ok(command_handler!("list_available_reports", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_report_templates", &context, { result }))
+}
+
+/// Save a Handlebars template for HTML reports. It is checked for syntax
+/// errors but not used until assigned.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn create_report_template_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    input: CustomReportTemplateInput,
+) -> CommandResult<CustomReportTemplate> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("create_report_template", {
+        require_resource_access!(context, "report", "templates");
+
+        let template = state.services.reports.create_report_template(&context, input)
+            .context("Failed to create report template")?;
+        AuthHelper::audit_action(&context, "create", "report_template", Some(&template.id.to_string()), true, None);
+
+        info!("[{}] Report template {} '{}' created for {} reports", context.request_id,
+              template.id, template.name, template.report_type);
+        Ok(template)
+    });
+
+    Ok(command_handler!("create_report_template", &context, { result }))
+}
+
+/// Edit a report template; an assigned template is used as edited from the
+/// next report on
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn update_report_template_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+    input: CustomReportTemplateInput,
+) -> CommandResult<CustomReportTemplate> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("update_report_template", {
+        require_resource_access!(context, "report", "templates");
+
+        let template = state.services.reports.update_report_template(&context, id, input)
+            .context("Failed to update report template")?;
+        AuthHelper::audit_action(&context, "update", "report_template", Some(&id.to_string()), true, None);
+
+        info!("[{}] Report template {} updated", context.request_id, id);
+        Ok(template)
+    });
+
+    Ok(command_handler!("update_report_template", &context, { result }))
+}
+
+/// Use a template for every HTML report of a type, or go back to the
+/// built-in layout when `template_id` is omitted
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn assign_report_template_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    report_type: CustomReportType,
+    template_id: Option<i64>,
+) -> CommandResult<Option<CustomReportTemplate>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("assign_report_template", {
+        require_resource_access!(context, "report", "templates");
+
+        let template = state.services.reports.assign_report_template(&context, report_type, template_id)
+            .context("Failed to assign report template")?;
+        AuthHelper::audit_action(&context, "assign", "report_template",
+                                 template_id.map(|id| id.to_string()).as_deref(), true, None);
+
+        info!("[{}] {} reports now use {}", context.request_id, report_type,
+              template.as_ref().map(|t| format!("template '{}'", t.name)).unwrap_or_else(|| "the built-in layout".to_string()));
+        Ok(template)
+    });
+
+    Ok(command_handler!("assign_report_template", &context, { result }))
+}
+
+/// Render template markup, saved or not, with the data of a real record
+/// and return the HTML. `record_id` is the inspection for inspection
+/// reports and the asset for compliance reports, which cover the last year
+/// unless `date_range` is given.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn preview_report_template_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    template: String,
+    report_type: CustomReportType,
+    record_id: i64,
+    date_range: Option<DateRange>,
+) -> CommandResult<String> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("preview_report_template", {
+        require_resource_access!(context, "report", "templates");
+
+        let report_id = format!("preview_{}_{}", report_type, record_id);
+        let report_data = match report_type {
+            CustomReportType::Inspection => {
+                require_resource_access!(context, "inspection", "read");
+                let inspection = state.services.inspections.get_inspection_by_id(record_id)
+                    .context("Failed to get inspection")?;
+                let asset = state.services.assets.get_asset_by_id(inspection.asset_id)
+                    .context("Failed to get asset")?;
+                let items = state.services.inspections.get_inspection_items(record_id)
+                    .context("Failed to get inspection items")?;
+                let clauses = state.services.compliance.get_inspection_clauses(record_id)
+                    .context("Failed to get cited clauses")?;
+                let media_files = state.services.media.get_media_files_by_inspection(record_id)
+                    .context("Failed to get media files")?;
+                inspection_report_data(&report_id, &inspection, &asset, &items, &clauses, &media_files)
+            }
+            CustomReportType::Compliance => {
+                require_resource_access!(context, "asset", "read");
+                let asset = state.services.assets.get_asset_by_id(record_id)
+                    .context("Failed to get asset")?;
+                let compliance_report = state.services.reports.generate_compliance_status_report(Some(asset.location_id))
+                    .context("Failed to generate compliance status")?;
+                let asset_records = state.services.asset_records.get_records(record_id)
+                    .context("Failed to get asset records")?;
+                let date_range = date_range.unwrap_or_else(|| DateRange {
+                    start_date: Utc::now() - chrono::Duration::days(365),
+                    end_date: Utc::now(),
+                });
+                let charts = state.services.reports
+                    .generate_asset_charts(record_id, date_range.start_date, date_range.end_date, context.locale())
+                    .context("Failed to generate report charts")?;
+                compliance_report_data(&report_id, &date_range, &asset, &compliance_report, &asset_records, &charts)
+            }
+        };
+
+        let html = state.services.reports.render_report_template(&template, &report_data)
+            .context("Failed to render report template")?;
+
+        debug!("[{}] Previewed {} report template with record {}", context.request_id, report_type, record_id);
+        Ok(html)
+    });
+
+    Ok(command_handler!("preview_report_template", &context, { result }))
 }
 
 // Helper functions for report generation
 
+/// Data of an inspection report, written as the JSON report and given to
+/// custom templates
+fn inspection_report_data(
+    report_id: &str,
+    inspection: &crate::models::Inspection,
+    asset: &crate::models::Asset,
+    inspection_items: &[crate::models::InspectionItem],
+    clauses: &[crate::models::StandardClause],
+    media_files: &[crate::models::MediaFile],
+) -> serde_json::Value {
+    serde_json::json!({
+        "report_id": report_id,
+        "report_type": "inspection",
+        "generated_at": Utc::now(),
+        "inspection": {
+            "id": inspection.id,
+            "asset_id": inspection.asset_id,
+            "asset_name": asset.asset_name,
+            "asset_number": asset.asset_number,
+            "inspection_type": inspection.inspection_type,
+            "compliance_standard": inspection.compliance_standard,
+            "scheduled_date": inspection.scheduled_date,
+            "actual_date": inspection.actual_date,
+            "status": inspection.status,
+            "overall_condition": inspection.overall_condition,
+            "notes": inspection.notes
+        },
+        "items": inspection_items,
+        "clauses": clauses,
+        "media_files": media_files.iter().map(|f| serde_json::json!({
+            "id": f.id,
+            "file_name": f.file_name,
+            "file_type": f.file_type,
+            "description": f.description
+        })).collect::<Vec<_>>(),
+        "summary": {
+            "total_items": inspection_items.len(),
+            "compliant_items": inspection_items.iter().filter(|i| i.is_compliant == Some(true)).count(),
+            "non_compliant_items": inspection_items.iter().filter(|i| i.is_compliant == Some(false)).count(),
+            "critical_findings": inspection_items.iter().filter(|i| matches!(i.severity, Some(crate::models::Severity::Critical))).count(),
+            "media_count": media_files.len()
+        }
+    })
+}
+
+/// Data of a compliance report, written as the JSON report and given to
+/// custom templates
+fn compliance_report_data(
+    report_id: &str,
+    date_range: &DateRange,
+    asset: &crate::models::Asset,
+    compliance_report: &crate::services::ComplianceStatusReport,
+    asset_records: &[crate::models::AssetRecord],
+    charts: &[ChartSpec],
+) -> serde_json::Value {
+    // Templates place a chart with `{{{charts.N.svg}}}`
+    let charts: Vec<serde_json::Value> = charts.iter().map(|spec| {
+        let mut value = serde_json::json!(spec);
+        value["svg"] = serde_json::Value::String(chart::to_svg(spec, CHART_WIDTH, CHART_HEIGHT));
+        value
+    }).collect();
+    serde_json::json!({
+        "report_id": report_id,
+        "report_type": "compliance",
+        "generated_at": Utc::now(),
+        "date_range": {
+            "start_date": date_range.start_date,
+            "end_date": date_range.end_date
+        },
+        "asset": {
+            "id": asset.id,
+            "name": asset.asset_name,
+            "asset_number": asset.asset_number,
+            "type": asset.asset_type,
+            "location_id": asset.location_id
+        },
+        "compliance_status": compliance_report,
+        "asset_records": asset_records,
+        "charts": charts
+    })
+}
+
 fn generate_html_inspection_report(
     inspection: &crate::models::Inspection,
     asset: &crate::models::Asset,
     items: &[crate::models::InspectionItem],
+    clauses: &[crate::models::StandardClause],
     media_files: &[crate::models::MediaFile],
+    locale: Locale,
 ) -> String {
+    let t = |key: &str| translate(locale, &format!("report.{}", key));
+    let not_available = translate(locale, "common.not_available");
+    let localize_or_na = |value: Option<String>| value.unwrap_or_else(|| not_available.clone());
+    let format_date = |date: Option<chrono::DateTime<Utc>>| {
+        localize_or_na(date.map(|d| d.format("%Y-%m-%d %H:%M UTC").to_string()))
+    };
+    let clause_ref = |clause_id: Option<i64>| {
+        clause_id
+            .and_then(|id| clauses.iter().find(|c| c.id == id))
+            .map(|c| c.clause_ref.clone())
+    };
+    // Unrecognised units fall back to the stored text
+    let capacity = match asset.rated_capacity() {
+        Ok(Some(capacity)) => capacity.format_dual(),
+        _ => localize_or_na(asset.capacity.map(|value| {
+            format!("{} {}", units::format_number(value), asset.capacity_unit.as_deref().unwrap_or_default())
+                .trim_end()
+                .to_string()
+        })),
+    };
+
     format!(
         r#"
 <!DOCTYPE html>
-<html>
+<html lang="{}">
 <head>
-    <title>Inspection Report - {}</title>
+    <title>{} - {}</title>
     <style>
         body {{ font-family: Arial, sans-serif; margin: 40px; }}
         h1, h2 {{ color: #333; }}
@@ -427,63 +1094,90 @@ fn generate_html_inspection_report(
     </style>
 </head>
 <body>
-    <h1>Inspection Report</h1>
+    <h1>{}</h1>
     <div class="summary">
-        <h2>Asset Information</h2>
-        <p><strong>Asset Name:</strong> {}</p>
-        <p><strong>Asset Number:</strong> {}</p>
-        <p><strong>Asset Type:</strong> {}</p>
+        <h2>{}</h2>
+        <p><strong>{}:</strong> {}</p>
+        <p><strong>{}:</strong> {}</p>
+        <p><strong>{}:</strong> {}</p>
+        <p><strong>{}:</strong> {}</p>
         
-        <h2>Inspection Details</h2>
-        <p><strong>Inspection ID:</strong> {}</p>
-        <p><strong>Inspection Type:</strong> {:?}</p>
-        <p><strong>Status:</strong> {:?}</p>
-        <p><strong>Scheduled Date:</strong> {:?}</p>
-        <p><strong>Actual Date:</strong> {:?}</p>
-        <p><strong>Overall Condition:</strong> {:?}</p>
+        <h2>{}</h2>
+        <p><strong>{}:</strong> {}</p>
+        <p><strong>{}:</strong> {}</p>
+        <p><strong>{}:</strong> {}</p>
+        <p><strong>{}:</strong> {}</p>
+        <p><strong>{}:</strong> {}</p>
+        <p><strong>{}:</strong> {}</p>
     </div>
     
-    <h2>Inspection Items</h2>
+    <h2>{}</h2>
+    <table>
+        <tr>
+            <th>{}</th>
+            <th>{}</th>
+            <th>{}</th>
+            <th>{}</th>
+            <th>{}</th>
+            <th>{}</th>
+            <th>{}</th>
+        </tr>
+        {}
+    </table>
+    
+    <h2>{}</h2>
     <table>
         <tr>
-            <th>Item Name</th>
-            <th>Category</th>
-            <th>Condition</th>
-            <th>Finding</th>
-            <th>Severity</th>
-            <th>Compliant</th>
+            <th>{}</th>
+            <th>{}</th>
         </tr>
         {}
     </table>
     
-    <h2>Media Files</h2>
-    <p>Total media files: {}</p>
+    <h2>{}</h2>
+    <p>{}: {}</p>
     
-    <p><em>Generated on: {}</em></p>
+    <p><em>{}: {}</em></p>
 </body>
 </html>
 "#,
-        asset.asset_name,
-        asset.asset_name,
-        asset.asset_number,
-        asset.asset_type,
-        inspection.id,
-        inspection.inspection_type,
-        inspection.status,
-        inspection.scheduled_date,
-        inspection.actual_date,
-        inspection.overall_condition,
+        locale.code(),
+        t("inspection_report"), asset.asset_name,
+        t("inspection_report"),
+        t("asset_information"),
+        t("asset_name"), asset.asset_name,
+        t("asset_number"), asset.asset_number,
+        t("asset_type"), asset.asset_type,
+        t("capacity"), capacity,
+        t("inspection_details"),
+        t("inspection_id"), inspection.id,
+        t("inspection_type"), inspection.inspection_type.localize(locale),
+        t("status"), inspection.status.localize(locale),
+        t("scheduled_date"), format_date(inspection.scheduled_date),
+        t("actual_date"), format_date(inspection.actual_date),
+        t("overall_condition"), localize_or_na(inspection.overall_condition.as_ref().map(|c| c.localize(locale))),
+        t("inspection_items"),
+        t("item_name"), t("category"), t("condition"), t("finding"), t("severity"), t("compliant"), t("clause"),
         items.iter().map(|item| format!(
-            "<tr><td>{}</td><td>{}</td><td>{:?}</td><td>{}</td><td>{:?}</td><td>{}</td></tr>",
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
             item.item_name,
             item.item_category,
-            item.condition,
-            item.finding.as_deref().unwrap_or("N/A"),
-            item.severity,
-            item.is_compliant.map(|c| if c { "Yes" } else { "No" }).unwrap_or("N/A")
+            localize_or_na(item.condition.as_ref().map(|c| c.localize(locale))),
+            item.finding.as_deref().unwrap_or(&not_available),
+            localize_or_na(item.severity.as_ref().map(|s| s.localize(locale))),
+            localize_or_na(item.is_compliant.map(|c| translate(locale, if c { "common.yes" } else { "common.no" }))),
+            localize_or_na(clause_ref(item.clause_id))
+        )).collect::<Vec<_>>().join(""),
+        t("standard_clauses"),
+        t("clause"), t("clause_title"),
+        clauses.iter().map(|clause| format!(
+            "<tr><td>{}</td><td>{}</td></tr>",
+            clause.clause_ref,
+            clause.title
         )).collect::<Vec<_>>().join(""),
-        media_files.len(),
-        Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+        t("media_files"),
+        t("total_media_files"), media_files.len(),
+        t("generated_on"), Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
     )
 }
 
@@ -491,23 +1185,27 @@ fn generate_csv_inspection_report(
     inspection: &crate::models::Inspection,
     asset: &crate::models::Asset,
     items: &[crate::models::InspectionItem],
+    clauses: &[crate::models::StandardClause],
 ) -> String {
     let mut csv = String::new();
-    csv.push_str("Asset Name,Asset Number,Inspection ID,Item Name,Category,Condition,Finding,Severity,Compliant\n");
+    csv.push_str("Asset Name,Asset Number,Inspection ID,Item Name,Category,Condition,Finding,Severity,Compliant,Clause\n");
     
     for item in items {
-        csv.push_str(&format!(
-            "{},{},{},{},{},{:?},{},{:?},{}\n",
-            asset.asset_name,
-            asset.asset_number,
-            inspection.id,
-            item.item_name,
-            item.item_category,
-            item.condition,
-            item.finding.as_deref().unwrap_or(""),
-            item.severity,
-            item.is_compliant.map(|c| if c { "Yes" } else { "No" }).unwrap_or("N/A")
-        ));
+        let clause = item.clause_id.and_then(|id| clauses.iter().find(|c| c.id == id));
+        let fields = [
+            asset.asset_name.clone(),
+            asset.asset_number.clone(),
+            inspection.id.to_string(),
+            item.item_name.clone(),
+            item.item_category.clone(),
+            item.condition.as_ref().map(|c| c.to_string()).unwrap_or_default(),
+            item.finding.clone().unwrap_or_default(),
+            item.severity.as_ref().map(|s| s.to_string()).unwrap_or_default(),
+            item.is_compliant.map(|c| if c { "Yes" } else { "No" }).unwrap_or("N/A").to_string(),
+            clause.map(|c| c.clause_ref.clone()).unwrap_or_default(),
+        ];
+        csv.push_str(&fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        csv.push('\n');
     }
     
     csv
@@ -516,73 +1214,136 @@ fn generate_csv_inspection_report(
 fn generate_html_compliance_report(
     asset: &crate::models::Asset,
     compliance_report: &crate::services::ComplianceStatusReport,
+    asset_records: &[crate::models::AssetRecord],
+    charts: &[ChartSpec],
     date_range: &DateRange,
+    locale: Locale,
 ) -> String {
+    let t = |key: &str| translate(locale, &format!("report.{}", key));
+    let not_available = translate(locale, "common.not_available");
+
     format!(
         r#"
 <!DOCTYPE html>
-<html>
+<html lang="{}">
 <head>
-    <title>Compliance Report - {}</title>
+    <title>{} - {}</title>
     <style>
         body {{ font-family: Arial, sans-serif; margin: 40px; }}
         h1, h2 {{ color: #333; }}
         .summary {{ background-color: #f9f9f9; padding: 15px; border-radius: 5px; }}
         .metric {{ margin: 10px 0; }}
+        table {{ border-collapse: collapse; width: 100%; }}
+        th, td {{ border: 1px solid #ddd; padding: 8px; text-align: left; }}
+        th {{ background-color: #f2f2f2; }}
+        .chart {{ margin: 20px 0; }}
     </style>
 </head>
 <body>
-    <h1>Compliance Report</h1>
+    <h1>{}</h1>
     <div class="summary">
-        <h2>Asset Information</h2>
-        <p><strong>Asset Name:</strong> {}</p>
-        <p><strong>Asset Number:</strong> {}</p>
+        <h2>{}</h2>
+        <p><strong>{}:</strong> {}</p>
+        <p><strong>{}:</strong> {}</p>
         
-        <h2>Report Period</h2>
-        <p><strong>From:</strong> {}</p>
-        <p><strong>To:</strong> {}</p>
+        <h2>{}</h2>
+        <p><strong>{}:</strong> {}</p>
+        <p><strong>{}:</strong> {}</p>
         
-        <h2>Compliance Summary</h2>
-        <div class="metric"><strong>Total Assets:</strong> {}</div>
-        <div class="metric"><strong>Compliant Assets:</strong> {}</div>
-        <div class="metric"><strong>Non-Compliant Assets:</strong> {}</div>
-        <div class="metric"><strong>Compliance Percentage:</strong> {:.1}%</div>
-        <div class="metric"><strong>Critical Findings:</strong> {}</div>
-        <div class="metric"><strong>Overdue Inspections:</strong> {}</div>
+        <h2>{}</h2>
+        <div class="metric"><strong>{}:</strong> {}</div>
+        <div class="metric"><strong>{}:</strong> {}</div>
+        <div class="metric"><strong>{}:</strong> {}</div>
+        <div class="metric"><strong>{}:</strong> {:.1}%</div>
+        <div class="metric"><strong>{}:</strong> {}</div>
+        <div class="metric"><strong>{}:</strong> {}</div>
+        <div class="metric"><strong>{}:</strong> {}</div>
     </div>
     
-    <p><em>Generated on: {}</em></p>
+    <h2>{}</h2>
+    <table>
+        <tr>
+            <th>{}</th>
+            <th>{}</th>
+            <th>{}</th>
+            <th>{}</th>
+        </tr>
+        {}
+    </table>
+    
+    <h2>{}</h2>
+    {}
+    
+    <p><em>{}: {}</em></p>
 </body>
 </html>
 "#,
-        asset.asset_name,
-        asset.asset_name,
-        asset.asset_number,
-        date_range.start_date.format("%Y-%m-%d"),
-        date_range.end_date.format("%Y-%m-%d"),
-        compliance_report.total_assets,
-        compliance_report.compliant_assets,
-        compliance_report.non_compliant_assets,
-        compliance_report.compliance_percentage,
-        compliance_report.critical_findings,
-        compliance_report.overdue_inspections,
-        Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+        locale.code(),
+        t("compliance_report"), asset.asset_name,
+        t("compliance_report"),
+        t("asset_information"),
+        t("asset_name"), asset.asset_name,
+        t("asset_number"), asset.asset_number,
+        t("report_period"),
+        t("from"), date_range.start_date.format("%Y-%m-%d"),
+        t("to"), date_range.end_date.format("%Y-%m-%d"),
+        t("compliance_summary"),
+        t("total_assets"), compliance_report.total_assets,
+        t("compliant_assets"), compliance_report.compliant_assets,
+        t("non_compliant_assets"), compliance_report.non_compliant_assets,
+        t("compliance_percentage"), compliance_report.compliance_percentage,
+        t("critical_findings"), compliance_report.critical_findings,
+        t("overdue_inspections"), compliance_report.overdue_inspections,
+        t("expiring_records"), compliance_report.expiring_records,
+        t("asset_records"),
+        t("record_kind"), t("reference_number"), t("issuer"), t("renewal_date"),
+        asset_records.iter().map(|record| format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            record.kind.localize(locale),
+            record.reference_number,
+            record.issuer.as_deref().unwrap_or(&not_available),
+            record.renewal_date.format("%Y-%m-%d")
+        )).collect::<Vec<_>>().join(""),
+        t("charts"),
+        charts.iter()
+            .map(|spec| format!(r#"<div class="chart">{}</div>"#, chart::to_svg(spec, CHART_WIDTH, CHART_HEIGHT)))
+            .collect::<Vec<_>>().join("\n    "),
+        t("generated_on"), Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
     )
 }
 
 fn generate_csv_compliance_report(
     asset: &crate::models::Asset,
     compliance_report: &crate::services::ComplianceStatusReport,
+    asset_records: &[crate::models::AssetRecord],
 ) -> String {
-    format!(
-        "Asset Name,Asset Number,Total Assets,Compliant Assets,Non-Compliant Assets,Compliance Percentage,Critical Findings,Overdue Inspections\n{},{},{},{},{},{:.1},{},{}\n",
-        asset.asset_name,
-        asset.asset_number,
+    let mut csv = format!(
+        "Asset Name,Asset Number,Total Assets,Compliant Assets,Non-Compliant Assets,Compliance Percentage,Critical Findings,Overdue Inspections,Expiring Records\n{},{},{},{},{},{:.1},{},{},{}\n",
+        csv_field(&asset.asset_name),
+        csv_field(&asset.asset_number),
         compliance_report.total_assets,
         compliance_report.compliant_assets,
         compliance_report.non_compliant_assets,
         compliance_report.compliance_percentage,
         compliance_report.critical_findings,
-        compliance_report.overdue_inspections
-    )
+        compliance_report.overdue_inspections,
+        compliance_report.expiring_records
+    );
+
+    if !asset_records.is_empty() {
+        csv.push_str("\nRecord Type,Reference Number,Issuer,Issued Date,Renewal Date\n");
+        for record in asset_records {
+            let fields = [
+                record.kind.to_string(),
+                record.reference_number.clone(),
+                record.issuer.clone().unwrap_or_default(),
+                record.issued_date.map(|d| d.to_string()).unwrap_or_default(),
+                record.renewal_date.to_string(),
+            ];
+            csv.push_str(&fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+            csv.push('\n');
+        }
+    }
+
+    csv
 }
\ No newline at end of file
@@ -0,0 +1,62 @@
+//! Risk matrix command handlers
+//!
+//! This module contains Tauri command handlers for the installation's risk
+//! matrix, which rates findings from their severity and the criticality of
+//! the asset and sets the time allowed to respond.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{RiskMatrix, RiskMatrixCell};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info};
+
+/// Get the risk matrix
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_risk_matrix_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<RiskMatrix> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_risk_matrix", {
+        require_resource_access!(context, "compliance", "read");
+
+        let matrix = state.services.risk_matrix.get_matrix()
+            .context("Failed to get risk matrix")?;
+
+        debug!("[{}] Retrieved risk matrix with {} cells", context.request_id, matrix.cells.len());
+        Ok(matrix)
+    });
+
+    Ok(command_handler!("get_risk_matrix", &context, { result }))
+}
+
+/// Replace the risk matrix; every severity and asset criticality needs a cell
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn update_risk_matrix_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    cells: Vec<RiskMatrixCell>,
+) -> CommandResult<RiskMatrix> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("update_risk_matrix", {
+        require_resource_access!(context, "compliance", "update");
+
+        let matrix = state.services.risk_matrix.set_matrix(&context, cells)
+            .context("Failed to update risk matrix")?;
+        AuthHelper::audit_action(&context, "update_risk_matrix", "compliance", None, true, None);
+
+        info!("[{}] Risk matrix updated", context.request_id);
+        Ok(matrix)
+    });
+
+    Ok(command_handler!("update_risk_matrix", &context, { result }))
+}
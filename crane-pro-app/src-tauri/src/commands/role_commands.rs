@@ -0,0 +1,229 @@
+//! Role command handlers
+//!
+//! This module contains Tauri command handlers for roles and their
+//! permission sets. Built-in roles are seeded from their default permissions
+//! and can be edited; custom roles such as a read-only auditor are made from
+//! the permission catalogue. Nobody can grant a permission they do not hold.
+//! For auditors, the permission matrix sets every role against every
+//! command.
+
+use crate::api::{ReportFormat, ReportResult};
+use crate::authz;
+use crate::commands::report_commands::REPORTS_DIR;
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::exporters;
+use crate::middleware::auth::AuthHelper;
+use crate::middleware::Permissions;
+use crate::models::{Role, RoleInput};
+use crate::reports::permission_matrix::render_permission_matrix;
+use crate::{require_resource_access, time_command, command_handler};
+use chrono::Utc;
+use std::fs;
+use tauri::State;
+use log::{debug, info};
+
+/// Get every role with its permissions and how many users have it
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_roles_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Vec<Role>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_roles", {
+        require_resource_access!(context, "user", "read");
+
+        let roles = state.services.roles.get_roles()
+            .context("Failed to get roles")?;
+
+        debug!("[{}] Retrieved {} roles", context.request_id, roles.len());
+        Ok(roles)
+    });
+
+    Ok(command_handler!("get_roles", &context, { result }))
+}
+
+/// Get every permission a role can be given
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_permission_catalog_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Vec<String>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_permission_catalog", {
+        require_resource_access!(context, "user", "roles");
+
+        Ok(Permissions::ALL.iter().map(|permission| permission.to_string()).collect())
+    });
+
+    Ok(command_handler!("get_permission_catalog", &context, { result }))
+}
+
+/// Create a custom role
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn create_role_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    input: RoleInput,
+) -> CommandResult<Role> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("create_role", {
+        require_resource_access!(context, "user", "roles");
+
+        let role = state.services.roles.create_role(&context, input)
+            .context("Failed to create role")?;
+        AuthHelper::audit_action(&context, "create", "role", Some(&role.id.to_string()), true, None);
+
+        info!("[{}] Role {} created with {} permissions", context.request_id, role.name, role.permissions.len());
+        Ok(role)
+    });
+
+    Ok(command_handler!("create_role", &context, { result }))
+}
+
+/// Change a role's details and permissions. Signed-in users with the role
+/// get the new permissions on their next request.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn update_role_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    role_id: i64,
+    input: RoleInput,
+) -> CommandResult<Role> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("update_role", {
+        require_resource_access!(context, "user", "roles");
+
+        let role = state.services.roles.update_role(&context, role_id, input)
+            .context("Failed to update role")?;
+        AuthHelper::audit_action(&context, "update", "role", Some(&role_id.to_string()), true, None);
+
+        info!("[{}] Role {} updated", context.request_id, role.name);
+        Ok(role)
+    });
+
+    Ok(command_handler!("update_role", &context, { result }))
+}
+
+/// Delete a custom role no user has
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn delete_role_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    role_id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("delete_role", {
+        require_resource_access!(context, "user", "roles");
+
+        state.services.roles.delete_role(&context, role_id)
+            .context("Failed to delete role")?;
+        AuthHelper::audit_action(&context, "delete", "role", Some(&role_id.to_string()), true, None);
+
+        info!("[{}] Role {} deleted", context.request_id, role_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("delete_role", &context, { result }))
+}
+
+/// Give a user a built-in or custom role
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn assign_user_role_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    user_id: i64,
+    role_id: i64,
+) -> CommandResult<Role> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("assign_user_role", {
+        require_resource_access!(context, "user", "roles");
+
+        let role = state.services.roles.assign_role(&context, user_id, role_id)
+            .context("Failed to assign role")?;
+        AuthHelper::audit_action(&context, "assign_role", "user", Some(&user_id.to_string()), true, None);
+
+        info!("[{}] User {} given role {}", context.request_id, user_id, role.name);
+        Ok(role)
+    });
+
+    Ok(command_handler!("assign_user_role", &context, { result }))
+}
+
+/// Generate the matrix of which roles can run which commands, worked out
+/// from the permission checks the commands make
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn generate_permission_matrix_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    format: ReportFormat,
+) -> CommandResult<ReportResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("generate_permission_matrix", {
+        require_resource_access!(context, "system", "audit");
+
+        let writer = exporters::registry().get(&format)
+            .context("Unsupported permission matrix format")?;
+
+        let roles = state.services.roles.get_roles()
+            .context("Failed to get roles")?;
+        let generated_at = Utc::now();
+        let matrix = authz::build_matrix(&roles, generated_at);
+
+        let report_id = format!("permission_matrix_{}", generated_at.format("%Y%m%d_%H%M%S"));
+        fs::create_dir_all(REPORTS_DIR)
+            .context("Failed to create reports directory")?;
+        let file_path = format!("{}/{}.{}", REPORTS_DIR, report_id, writer.extension());
+
+        // The PDF has a layout of its own, grouped by module
+        let content = match format {
+            ReportFormat::Pdf => render_permission_matrix(&matrix),
+            _ => authz::matrix_table(&matrix)
+                .and_then(|table| exporters::registry().render(&format, &table)),
+        }
+        .context("Failed to render permission matrix")?;
+        fs::write(&file_path, content)
+            .context("Failed to write permission matrix")?;
+        AuthHelper::audit_action(&context, "generate", "permission_matrix", Some(&report_id), true, None);
+
+        info!("[{}] Permission matrix generated: {} ({} commands, {} roles)",
+              context.request_id, report_id, matrix.commands.len(), matrix.roles.len());
+        Ok(ReportResult {
+            report_id: report_id.clone(),
+            format,
+            file_path: Some(file_path),
+            file_url: Some(format!("/api/reports/{}/download", report_id)),
+            generated_at,
+            expires_at: Some(generated_at + chrono::Duration::days(30)),
+        })
+    });
+
+    Ok(command_handler!("generate_permission_matrix", &context, { result }))
+}
@@ -0,0 +1,45 @@
+//! Search command handlers
+//!
+//! This module contains the Tauri command handler for global full-text
+//! search across assets, components, inspections and inspection items.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::search::{search_limit, SearchEntityType, SearchHit};
+use crate::{time_command, command_handler};
+use tauri::State;
+use log::debug;
+
+/// Search records by free text, best matches first. Only entity types the
+/// user may read are searched; `entity_types` narrows the search further.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn global_search_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    query: String,
+    entity_types: Option<Vec<SearchEntityType>>,
+    limit: Option<usize>,
+) -> CommandResult<Vec<SearchHit>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("global_search", {
+        let session = context.current_user()?;
+        let requested = entity_types.unwrap_or_else(|| SearchEntityType::ALL.to_vec());
+        let readable: Vec<SearchEntityType> = SearchEntityType::ALL
+            .into_iter()
+            .filter(|entity_type| requested.contains(entity_type))
+            .filter(|entity_type| session.can_access_resource(entity_type.resource(), "read"))
+            .collect();
+
+        let hits = state.services.search.search(&query, &readable, search_limit(limit))
+            .context("Failed to search")?;
+
+        debug!("[{}] Search returned {} results", context.request_id, hits.len());
+        Ok(hits)
+    });
+
+    Ok(command_handler!("global_search", &context, { result }))
+}
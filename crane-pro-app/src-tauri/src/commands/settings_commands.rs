@@ -0,0 +1,66 @@
+//! Application settings command handlers
+//!
+//! This module contains Tauri command handlers for reading and changing the
+//! application-wide settings such as session duration, the default
+//! compliance standard, the media storage path and report retention.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{AppSettings, SettingKey};
+use crate::{require_resource_access, time_command, command_handler};
+use serde_json::Value as JsonValue;
+use tauri::State;
+use log::{debug, info};
+
+/// Get the application settings in effect
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_settings_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<AppSettings> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_settings", {
+        // Any signed-in user may read settings, e.g. to prefill the default standard
+        context.current_user()?;
+
+        let settings = state.services.settings.get_settings()
+            .context("Failed to get settings")?;
+
+        debug!("[{}] Retrieved application settings", context.request_id);
+        Ok(settings)
+    });
+
+    Ok(command_handler!("get_settings", &context, { result }))
+}
+
+/// Change one application setting
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn update_setting_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    key: SettingKey,
+    value: JsonValue,
+) -> CommandResult<AppSettings> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("update_setting", {
+        require_resource_access!(context, "system", "settings");
+
+        let settings = state.services.settings.set_setting(&context, key, value)
+            .context("Failed to update setting")?;
+        AuthHelper::audit_action(&context, "update", "setting", Some(key.as_str()), true, None);
+
+        info!("[{}] Setting {} updated by user {}", context.request_id, key,
+              context.current_user().map(|u| u.user_id).unwrap_or(0));
+        Ok(settings)
+    });
+
+    Ok(command_handler!("update_setting", &context, { result }))
+}
@@ -0,0 +1,350 @@
+//! System administration command handlers
+//!
+//! This module contains Tauri command handlers for application diagnostics
+//! and maintenance such as log retrieval, request traces, demo data
+//! generation, data quality checks, schema migrations, database maintenance,
+//! the audit log and security events.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::errors::AppError;
+use crate::database::{MaintenanceRun, MaintenanceTask, MigrationResult, MigrationStatus, UpgradeSnapshot};
+use crate::logging::LogManager;
+use crate::middleware::AuditLogEntry;
+use crate::middleware::auth::AuthHelper;
+use crate::models::{AuditLogFilter, DataQualityReport, SecurityEvent, SecurityEventFilter, Trace, DEFAULT_AUDIT_LOG_LIMIT,
+                    DEFAULT_SECURITY_EVENT_LIMIT, MAX_AUDIT_LOG_LIMIT, MAX_SECURITY_EVENT_LIMIT};
+use crate::seed::{SeedOptions, SeedSummary};
+use crate::trace::MAX_TRACES;
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info, warn};
+
+/// Default number of log lines returned when no limit is given
+const DEFAULT_RECENT_LOG_LINES: usize = 200;
+
+/// Get the most recent application log lines for support diagnostics
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_recent_logs_command(
+    state: State<'_, AppState>,
+    logs: State<'_, LogManager>,
+    token: Option<String>,
+    max_lines: Option<usize>,
+    level: Option<String>,
+) -> CommandResult<Vec<String>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_recent_logs", {
+        require_resource_access!(context, "system", "logs");
+
+        let lines = logs.recent_logs(max_lines.unwrap_or(DEFAULT_RECENT_LOG_LINES), level.as_deref())
+            .context("Failed to read logs")?;
+
+        debug!("[{}] Retrieved {} log lines from {}", context.request_id,
+               lines.len(), logs.log_dir().display());
+        Ok(lines)
+    });
+
+    Ok(command_handler!("get_recent_logs", &context, { result }))
+}
+
+/// Rebuild the timeline of a recent request from the request ID in its
+/// response, for support looking into a slow or failed request
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_trace_command(
+    state: State<'_, AppState>,
+    logs: State<'_, LogManager>,
+    token: Option<String>,
+    trace_id: String,
+) -> CommandResult<Trace> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_trace", {
+        require_resource_access!(context, "system", "logs");
+
+        let trace_id = trace_id.trim();
+        let mut trace = logs.traces().get(trace_id)
+            .ok_or_else(|| AppError::RecordNotFound {
+                entity: "Trace".to_string(),
+                field: "request_id".to_string(),
+                value: format!("{} (only the last {} requests are kept)", trace_id, MAX_TRACES),
+            })?;
+        trace.ai_jobs = state.services.media.get_ai_jobs_for_trace(trace_id)
+            .context("Failed to get AI jobs")?;
+
+        debug!("[{}] Retrieved trace {} with {} events", context.request_id, trace_id, trace.events.len());
+        Ok(trace)
+    });
+
+    Ok(command_handler!("get_trace", &context, { result }))
+}
+
+/// Query the persisted audit log, newest first
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn query_audit_log_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    filter: Option<AuditLogFilter>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> CommandResult<Vec<AuditLogEntry>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("query_audit_log", {
+        require_resource_access!(context, "system", "audit");
+
+        let filter = filter.unwrap_or_default();
+        if let (Some(start), Some(end)) = (filter.start_date, filter.end_date) {
+            if start > end {
+                return Err(AppError::validation("start_date", "start_date must not be after end_date"));
+            }
+        }
+
+        let limit = limit.unwrap_or(DEFAULT_AUDIT_LOG_LIMIT).clamp(1, MAX_AUDIT_LOG_LIMIT);
+        let entries = state.services.audit.query(&filter, limit, offset.unwrap_or(0))
+            .context("Failed to query audit log")?;
+
+        debug!("[{}] Retrieved {} audit log entries", context.request_id, entries.len());
+        Ok(entries)
+    });
+
+    Ok(command_handler!("query_audit_log", &context, { result }))
+}
+
+/// Failed logins, permission denials, lockouts and token anomalies, newest
+/// first
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_security_events_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    filter: Option<SecurityEventFilter>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> CommandResult<Vec<SecurityEvent>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_security_events", {
+        require_resource_access!(context, "system", "audit");
+
+        let filter = filter.unwrap_or_default();
+        if let (Some(start), Some(end)) = (filter.start_date, filter.end_date) {
+            if start > end {
+                return Err(AppError::validation("start_date", "start_date must not be after end_date"));
+            }
+        }
+
+        let limit = limit.unwrap_or(DEFAULT_SECURITY_EVENT_LIMIT).clamp(1, MAX_SECURITY_EVENT_LIMIT);
+        let events = state.services.security_events.query(&filter, limit, offset.unwrap_or(0))
+            .context("Failed to query security events")?;
+
+        debug!("[{}] Retrieved {} security events", context.request_id, events.len());
+        Ok(events)
+    });
+
+    Ok(command_handler!("get_security_events", &context, { result }))
+}
+
+/// Fill an empty database with demo locations, assets and inspection history
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn seed_demo_data_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    options: Option<SeedOptions>,
+) -> CommandResult<SeedSummary> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("seed_demo_data", {
+        require_resource_access!(context, "system", "seed");
+
+        let options = options.unwrap_or_default();
+        let summary = state.services.demo.seed_demo_data(&context, &options)
+            .context("Failed to generate demo data")?;
+        AuthHelper::audit_action(&context, "seed_demo_data", "system", None, true, None);
+
+        info!("[{}] Demo data generated with seed {}: {} assets, {} inspections", context.request_id,
+              options.seed, summary.assets, summary.inspections);
+        Ok(summary)
+    });
+
+    Ok(command_handler!("seed_demo_data", &context, { result }))
+}
+
+/// Check the database for inconsistent records left by legacy imports and
+/// suggest fixes
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn run_data_quality_checks_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<DataQualityReport> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("run_data_quality_checks", {
+        require_resource_access!(context, "system", "data_quality");
+
+        let report = state.services.data_quality.run_checks(&context)
+            .context("Failed to run data quality checks")?;
+        AuthHelper::audit_action(&context, "data_quality_checks", "system", None, true, None);
+
+        info!("[{}] Data quality checks completed: {} issues", context.request_id, report.total_issues);
+        Ok(report)
+    });
+
+    Ok(command_handler!("run_data_quality_checks", &context, { result }))
+}
+
+/// Get the schema version, known migrations and the progress of the latest
+/// migration run or rollback
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_migration_status_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<MigrationStatus> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_migration_status", {
+        require_resource_access!(context, "system", "migrations");
+
+        let status = state.services.migrations.get_status()
+            .context("Failed to get migration status")?;
+
+        debug!("[{}] Schema at version {} of {}", context.request_id,
+               status.current_version, status.latest_version);
+        Ok(status)
+    });
+
+    Ok(command_handler!("get_migration_status", &context, { result }))
+}
+
+/// Apply pending schema migrations
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn run_migrations_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Vec<MigrationResult>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("run_migrations", {
+        require_resource_access!(context, "system", "migrations");
+
+        let results = state.services.migrations.run_pending(&context)
+            .context("Failed to run migrations")?;
+        let success = results.iter().all(|r| r.success);
+        AuthHelper::audit_action(&context, "run_migrations", "system", None, success, None);
+
+        info!("[{}] Ran {} migrations", context.request_id, results.len());
+        Ok(results)
+    });
+
+    Ok(command_handler!("run_migrations", &context, { result }))
+}
+
+/// Roll the schema back to an earlier version, newest migration first
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn rollback_to_version_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    version: i32,
+) -> CommandResult<Vec<MigrationResult>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("rollback_to_version", {
+        require_resource_access!(context, "system", "migrations");
+
+        let results = state.services.migrations.rollback_to_version(&context, version)
+            .context("Failed to roll back migrations")?;
+        let success = results.iter().all(|r| r.success);
+        AuthHelper::audit_action(&context, "rollback_migrations", "system",
+                                 Some(&version.to_string()), success, None);
+
+        warn!("[{}] Rolled back {} migrations towards version {}", context.request_id, results.len(), version);
+        Ok(results)
+    });
+
+    Ok(command_handler!("rollback_to_version", &context, { result }))
+}
+
+/// Undo the latest upgrade when the new version misbehaves: revert the
+/// migrations it ran and restore the database snapshot taken before them.
+/// Changes made since the upgrade are lost, and the previous app version
+/// should be reinstalled before the next launch.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn rollback_last_upgrade_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<UpgradeSnapshot> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("rollback_last_upgrade", {
+        require_resource_access!(context, "system", "migrations");
+        AuthHelper::require_full_session(&context)
+            .context("Failed to roll back upgrade")?;
+
+        let snapshot = state.services.migrations.rollback_last_upgrade(&context)
+            .context("Failed to roll back upgrade")?;
+        AuthHelper::audit_action(&context, "rollback_upgrade", "system",
+                                 Some(&snapshot.from_schema_version.to_string()), true, None);
+
+        warn!("[{}] Upgrade by app version {} rolled back to schema version {} from {}", context.request_id,
+              snapshot.app_version, snapshot.from_schema_version, snapshot.snapshot_path);
+        Ok(snapshot)
+    });
+
+    Ok(command_handler!("rollback_last_upgrade", &context, { result }))
+}
+
+/// Run database maintenance now. Runs every task when `tasks` is omitted or
+/// empty; VACUUM locks the database while it rebuilds the file.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn run_db_maintenance_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    tasks: Option<Vec<MaintenanceTask>>,
+) -> CommandResult<Vec<MaintenanceRun>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("run_db_maintenance", {
+        require_resource_access!(context, "system", "maintenance");
+
+        let runs = state.services.db_maintenance.run(&context, &tasks.unwrap_or_default())
+            .context("Failed to run database maintenance")?;
+        let success = runs.iter().all(|r| r.success);
+        AuthHelper::audit_action(&context, "run_db_maintenance", "system", None, success, None);
+
+        info!("[{}] Ran {} database maintenance tasks", context.request_id, runs.len());
+        Ok(runs)
+    });
+
+    Ok(command_handler!("run_db_maintenance", &context, { result }))
+}
@@ -0,0 +1,351 @@
+//! Team management command handlers
+//!
+//! This module contains Tauri command handlers for teams (crews of
+//! inspectors under a supervisor), their members and the locations they
+//! cover, plus team-scoped inspection queries and completion statistics.
+
+use crate::api::{CreateTeamRequest, TeamUpdateRequest};
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{Inspection, Team, TeamCompletionStats, TeamWithMembers};
+use crate::{require_resource_access, time_command, command_handler};
+use chrono::{DateTime, Utc};
+use tauri::State;
+use log::{info, debug};
+
+/// Create a new team
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn create_team_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    team: CreateTeamRequest,
+) -> CommandResult<Team> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("create_team", {
+        require_resource_access!(context, "team", "create");
+        let session = context.current_user()?;
+
+        let team = state.services.teams.create_team(&context, team.to_team(session.user_id))
+            .context("Failed to create team")?;
+        AuthHelper::audit_action(&context, "create", "team", Some(&team.id.to_string()), true, None);
+
+        info!("[{}] Team created: {} (ID: {})", context.request_id, team.name, team.id);
+        Ok(team)
+    });
+
+    Ok(command_handler!("create_team", &context, { result }))
+}
+
+/// Get a team with its members and assigned locations
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_team_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<TeamWithMembers> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_team", {
+        require_resource_access!(context, "team", "read");
+
+        let team = state.services.teams.get_team(id)
+            .context("Failed to get team")?;
+
+        debug!("[{}] Retrieved team {} with {} members", context.request_id, id, team.members.len());
+        Ok(team)
+    });
+
+    Ok(command_handler!("get_team", &context, { result }))
+}
+
+/// List teams
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_teams_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    include_inactive: Option<bool>,
+) -> CommandResult<Vec<Team>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_teams", {
+        require_resource_access!(context, "team", "read");
+
+        let teams = state.services.teams.get_teams(include_inactive.unwrap_or(false))
+            .context("Failed to get teams")?;
+
+        debug!("[{}] Retrieved {} teams", context.request_id, teams.len());
+        Ok(teams)
+    });
+
+    Ok(command_handler!("get_teams", &context, { result }))
+}
+
+/// Teams the current user belongs to or supervises
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_my_teams_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Vec<Team>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_my_teams", {
+        let session = context.current_user()?;
+
+        let teams = state.services.teams.get_user_teams(session.user_id)
+            .context("Failed to get teams")?;
+
+        debug!("[{}] User {} belongs to {} teams", context.request_id, session.user_id, teams.len());
+        Ok(teams)
+    });
+
+    Ok(command_handler!("get_my_teams", &context, { result }))
+}
+
+/// Update a team
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn update_team_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+    updates: TeamUpdateRequest,
+) -> CommandResult<Team> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("update_team", {
+        require_resource_access!(context, "team", "update");
+
+        let team = state.services.teams.update_team(&context, id, updates.into())
+            .context("Failed to update team")?;
+        AuthHelper::audit_action(&context, "update", "team", Some(&id.to_string()), true, None);
+
+        info!("[{}] Team updated: {} (ID: {})", context.request_id, team.name, id);
+        Ok(team)
+    });
+
+    Ok(command_handler!("update_team", &context, { result }))
+}
+
+/// Delete a team, its memberships and location assignments
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn delete_team_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("delete_team", {
+        require_resource_access!(context, "team", "delete");
+
+        state.services.teams.delete_team(&context, id)
+            .context("Failed to delete team")?;
+        AuthHelper::audit_action(&context, "delete", "team", Some(&id.to_string()), true, None);
+
+        info!("[{}] Team deleted: ID {}", context.request_id, id);
+        Ok(())
+    });
+
+    Ok(command_handler!("delete_team", &context, { result }))
+}
+
+/// Add a user to a team
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn add_team_member_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    team_id: i64,
+    user_id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("add_team_member", {
+        require_resource_access!(context, "team", "update");
+
+        state.services.teams.add_member(&context, team_id, user_id)
+            .context("Failed to add team member")?;
+        AuthHelper::audit_action(&context, "add_member", "team", Some(&team_id.to_string()), true, None);
+
+        info!("[{}] User {} added to team {}", context.request_id, user_id, team_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("add_team_member", &context, { result }))
+}
+
+/// Remove a user from a team
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn remove_team_member_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    team_id: i64,
+    user_id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("remove_team_member", {
+        require_resource_access!(context, "team", "update");
+
+        state.services.teams.remove_member(&context, team_id, user_id)
+            .context("Failed to remove team member")?;
+        AuthHelper::audit_action(&context, "remove_member", "team", Some(&team_id.to_string()), true, None);
+
+        info!("[{}] User {} removed from team {}", context.request_id, user_id, team_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("remove_team_member", &context, { result }))
+}
+
+/// Make a team responsible for a location and its sub-locations
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn assign_team_location_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    team_id: i64,
+    location_id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("assign_team_location", {
+        require_resource_access!(context, "team", "update");
+
+        state.services.teams.assign_location(&context, team_id, location_id)
+            .context("Failed to assign location")?;
+        AuthHelper::audit_action(&context, "assign_location", "team", Some(&team_id.to_string()), true, None);
+
+        info!("[{}] Location {} assigned to team {}", context.request_id, location_id, team_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("assign_team_location", &context, { result }))
+}
+
+/// Remove the team assignment from a location
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn unassign_team_location_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    location_id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("unassign_team_location", {
+        require_resource_access!(context, "team", "update");
+
+        state.services.teams.unassign_location(&context, location_id)
+            .context("Failed to unassign location")?;
+        AuthHelper::audit_action(&context, "unassign_location", "team", Some(&location_id.to_string()), true, None);
+
+        info!("[{}] Team assignment removed from location {}", context.request_id, location_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("unassign_team_location", &context, { result }))
+}
+
+/// Pending inspections for a team, or for all of the current user's teams
+/// when no team is given
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_team_pending_inspections_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    team_id: Option<i64>,
+) -> CommandResult<Vec<Inspection>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_team_pending_inspections", {
+        require_resource_access!(context, "team", "read");
+        require_resource_access!(context, "inspection", "read");
+
+        let team_ids = match team_id {
+            Some(team_id) => vec![team_id],
+            None => {
+                let session = context.current_user()?;
+                state.services.teams.get_user_teams(session.user_id)
+                    .context("Failed to get teams")?
+                    .into_iter()
+                    .map(|team| team.id)
+                    .collect()
+            }
+        };
+
+        let mut inspections: Vec<Inspection> = Vec::new();
+        for team_id in team_ids {
+            let team_inspections = state.services.teams.get_team_pending_inspections(team_id)
+                .context("Failed to get pending inspections")?;
+            for inspection in team_inspections {
+                if !inspections.iter().any(|existing| existing.id == inspection.id) {
+                    inspections.push(inspection);
+                }
+            }
+        }
+        inspections.sort_by_key(|inspection| inspection.scheduled_date);
+
+        debug!("[{}] Retrieved {} pending team inspections", context.request_id, inspections.len());
+        Ok(inspections)
+    });
+
+    Ok(command_handler!("get_team_pending_inspections", &context, { result }))
+}
+
+/// Inspection completion per team member over a period
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_team_completion_stats_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    team_id: i64,
+    start_date: DateTime<Utc>,
+    end_date: DateTime<Utc>,
+) -> CommandResult<TeamCompletionStats> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_team_completion_stats", {
+        require_resource_access!(context, "team", "read");
+        require_resource_access!(context, "report", "read");
+
+        let stats = state.services.teams.get_team_completion_stats(team_id, start_date, end_date)
+            .context("Failed to get team completion stats")?;
+
+        debug!("[{}] Team {} completion rate {:.1}%", context.request_id, team_id, stats.completion_rate);
+        Ok(stats)
+    });
+
+    Ok(command_handler!("get_team_completion_stats", &context, { result }))
+}
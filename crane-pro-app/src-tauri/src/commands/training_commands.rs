@@ -0,0 +1,143 @@
+//! Training command handlers
+//!
+//! This module contains Tauri command handlers for the courses users have
+//! completed, the inspection types their training qualifies them for, and
+//! the feed of training coming up for renewal.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{ExpiringTraining, TrainingRecord, TrainingRecordInput, DEFAULT_RENEWAL_WARNING_DAYS};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info};
+
+/// Record a course completed by a user
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn add_training_record_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    record: TrainingRecordInput,
+) -> CommandResult<TrainingRecord> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("add_training_record", {
+        require_resource_access!(context, "user", "update");
+
+        let record = state.services.training.add_record(&context, record)
+            .context("Failed to add training record")?;
+        AuthHelper::audit_action(&context, "add_training_record", "user", Some(&record.user_id.to_string()), true, None);
+
+        info!("[{}] Training '{}' recorded for user {}", context.request_id, record.course, record.user_id);
+        Ok(record)
+    });
+
+    Ok(command_handler!("add_training_record", &context, { result }))
+}
+
+/// Replace the details of a training record, e.g. after a refresher course
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn update_training_record_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    record_id: i64,
+    record: TrainingRecordInput,
+) -> CommandResult<TrainingRecord> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("update_training_record", {
+        require_resource_access!(context, "user", "update");
+
+        let record = state.services.training.update_record(&context, record_id, record)
+            .context("Failed to update training record")?;
+        AuthHelper::audit_action(&context, "update_training_record", "user", Some(&record.user_id.to_string()), true, None);
+
+        info!("[{}] Training record {} updated", context.request_id, record_id);
+        Ok(record)
+    });
+
+    Ok(command_handler!("update_training_record", &context, { result }))
+}
+
+/// Remove a training record from a user
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn delete_training_record_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    record_id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("delete_training_record", {
+        require_resource_access!(context, "user", "update");
+
+        let user_id = state.services.training.delete_record(&context, record_id)
+            .context("Failed to delete training record")?;
+        AuthHelper::audit_action(&context, "delete_training_record", "user", Some(&user_id.to_string()), true, None);
+
+        info!("[{}] Training record {} deleted from user {}", context.request_id, record_id, user_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("delete_training_record", &context, { result }))
+}
+
+/// Get the training held by a user
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_training_records_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    user_id: i64,
+) -> CommandResult<Vec<TrainingRecord>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_training_records", {
+        require_resource_access!(context, "user", "read");
+
+        let records = state.services.training.get_records(user_id)
+            .context("Failed to get training records")?;
+
+        debug!("[{}] Retrieved {} training records for user {}", context.request_id, records.len(), user_id);
+        Ok(records)
+    });
+
+    Ok(command_handler!("get_training_records", &context, { result }))
+}
+
+/// Get training that has lapsed or expires within the given number of days,
+/// by default 30
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_expiring_training_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    days: Option<i64>,
+) -> CommandResult<Vec<ExpiringTraining>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_expiring_training", {
+        require_resource_access!(context, "user", "read");
+
+        let days = days.unwrap_or(DEFAULT_RENEWAL_WARNING_DAYS);
+        let expiring = state.services.training.get_expiring(days)
+            .context("Failed to get expiring training")?;
+
+        debug!("[{}] Found {} training records expiring within {} days", context.request_id, expiring.len(), days);
+        Ok(expiring)
+    });
+
+    Ok(command_handler!("get_expiring_training", &context, { result }))
+}
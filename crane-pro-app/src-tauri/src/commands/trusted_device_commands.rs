@@ -0,0 +1,151 @@
+//! Trusted device command handlers
+//!
+//! This module contains Tauri command handlers for field devices that users
+//! unlock with a PIN or badge scan instead of typing their password, which
+//! is impractical with gloves. A device becomes trusted from a normal login
+//! and stays so until it expires, is revoked or sees too many wrong PINs.
+
+use crate::api::LoginResponse;
+use crate::commands::user_commands::{login_response, record_login};
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::middleware::RequestContext;
+use crate::models::{DeviceUnlock, LoginAttempt, LoginDevice, LoginMethod, RegisteredTrustedDevice, TrustedDevice,
+                    TrustedDeviceInput};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info, warn};
+
+/// Trust the device the user is signed in on, so it can be unlocked with a
+/// PIN or badge. The returned key is stored on the device.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn register_trusted_device_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    device: TrustedDeviceInput,
+) -> CommandResult<RegisteredTrustedDevice> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("register_trusted_device", {
+        AuthHelper::require_full_session(&context)?;
+
+        let registered = state.services.trusted_devices.register(&context, device)
+            .context("Failed to trust device")?;
+        AuthHelper::audit_action(&context, "register", "trusted_device", Some(&registered.device.id.to_string()), true, None);
+
+        info!("[{}] Device trusted: {} (ID: {})", context.request_id, registered.device.name, registered.device.id);
+        Ok(registered)
+    });
+
+    Ok(command_handler!("register_trusted_device", &context, { result }))
+}
+
+/// Get the devices still trusted for the current user, or for another user
+/// for those allowed to read users
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_trusted_devices_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    user_id: Option<i64>,
+) -> CommandResult<Vec<TrustedDevice>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_trusted_devices", {
+        let current_user_id = context.current_user()?.user_id;
+        let user_id = user_id.unwrap_or(current_user_id);
+        if user_id != current_user_id {
+            require_resource_access!(context, "user", "read");
+        }
+
+        let devices = state.services.trusted_devices.get_devices(user_id)
+            .context("Failed to get trusted devices")?;
+
+        debug!("[{}] Retrieved {} trusted devices for user {}", context.request_id, devices.len(), user_id);
+        Ok(devices)
+    });
+
+    Ok(command_handler!("get_trusted_devices", &context, { result }))
+}
+
+/// Stop trusting a device. Users may revoke their own; revoking another
+/// user's requires user update access.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn revoke_trusted_device_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    device_id: i64,
+) -> CommandResult<TrustedDevice> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("revoke_trusted_device", {
+        let current_user_id = context.current_user()?.user_id;
+        let device = state.services.trusted_devices.get_device(device_id)
+            .context("Failed to get trusted device")?;
+        if device.user_id != current_user_id {
+            require_resource_access!(context, "user", "update");
+        }
+
+        let device = state.services.trusted_devices.revoke(&context, device_id)
+            .context("Failed to revoke trusted device")?;
+        AuthHelper::audit_action(&context, "revoke", "trusted_device", Some(&device_id.to_string()), true, None);
+
+        info!("[{}] Trusted device {} revoked", context.request_id, device_id);
+        Ok(device)
+    });
+
+    Ok(command_handler!("revoke_trusted_device", &context, { result }))
+}
+
+/// Unlock a trusted device with a PIN or badge scan
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn unlock_device_command(
+    state: State<'_, AppState>,
+    device_key: String,
+    unlock: DeviceUnlock,
+    device: Option<LoginDevice>,
+) -> CommandResult<LoginResponse> {
+    // Unlocking starts the session
+    let context = RequestContext::new();
+    context.record_in_span();
+
+    let result = time_command!("unlock_device", {
+        // Known before the check, which revokes the device after too many wrong attempts
+        let device_owner = state.services.trusted_devices.device_owner(&device_key).ok().flatten();
+        let attempt = |user_id: i64, failure_reason: Option<String>| LoginAttempt {
+            user_id: Some(user_id),
+            username: String::new(),
+            method: LoginMethod::DeviceUnlock,
+            device: device.clone().unwrap_or_default(),
+            failure_reason,
+        };
+
+        let issued = state.auth_manager.unlock_device(&device_key, &unlock)
+            .inspect_err(|e| {
+                warn!("[{}] Device unlock failed: {}", context.request_id, e);
+                if let Some(user_id) = device_owner {
+                    record_login(&state, attempt(user_id, Some(e.to_string())));
+                }
+            })?;
+
+        let user = state.services.users.get_user_by_id(issued.session.user_id)
+            .context("Failed to get user details")?;
+        record_login(&state, LoginAttempt { username: user.username.clone(), ..attempt(user.id, None) });
+        let response = login_response(user, issued);
+
+        info!("[{}] User unlocked trusted device: {} (session: {})", context.request_id,
+              response.user.username, response.session_id);
+        Ok(response)
+    });
+
+    Ok(command_handler!("unlock_device", &context, { result }))
+}
@@ -3,60 +3,69 @@
 //! This module contains all Tauri command handlers for user management
 //! operations including authentication, user CRUD, and session management.
 
-use crate::api::{ApiResponse, QueryFilterRequest, CreateUserRequest, UserUpdateRequest,
-                LoginRequest, ChangePasswordRequest, PaginatedResponse, LoginResponse};
-use crate::commands::AppState;
-use crate::middleware::auth::AuthHelper;
-use crate::models::User;
-use crate::services::UserUpdateData;
+use crate::api::{QueryFilterRequest, CreateUserRequest, UserUpdateRequest, CreateUserAbsenceRequest,
+                LoginRequest, ChangePasswordRequest, PaginatedResponse, LoginResponse, LoginResult};
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::errors::AppError;
+use crate::i18n::Locale;
+use crate::middleware::RequestContext;
+use crate::middleware::auth::{AuthHelper, IssuedTokens, LoginOutcome};
+use crate::models::{ActiveSession, JwtKeyRotation, LoginAttempt, LoginDevice, LoginHistoryEntry, LoginHistoryFilter, LoginMethod,
+                    PasswordReset, User, UserAbsence, UserPreferences, UserPreferencesInput, DEFAULT_LOGIN_HISTORY_LIMIT,
+                    MAX_JWT_ROTATION_GRACE_HOURS, MAX_LOGIN_HISTORY_LIMIT};
+use crate::security::secrets::Secrets;
+use crate::services::{AbsenceRecordResult, AccountLockoutInfo, AvailableInspector, UserUpdateData};
+use chrono::{Duration, NaiveDate, Utc};
 use crate::{require_resource_access, time_command, command_handler};
-use tauri::State;
+use tauri::{AppHandle, State, Window};
+use tauri_plugin_opener::OpenerExt;
 use log::{info, debug, warn};
 
 /// Create a new user
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn create_user_command(
     state: State<'_, AppState>,
     token: Option<String>,
     user_data: CreateUserRequest,
-) -> Result<ApiResponse<User>, String> {
+) -> CommandResult<User> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("create_user", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "user", "create");
 
         // Create user - the service will handle password validation and hashing
         let plain_password = user_data.password.clone(); // Extract password before move
         let user = user_data.to_user(String::new()); // Temporary password_hash, service will replace it
-        let created_user = state.services.users.create_user(user, plain_password)
-            .map_err(|e| format!("Failed to create user: {}", e))?;
+        let created_user = state.services.users.create_user(&context, user, plain_password)
+            .context("Failed to create user")?;
+        AuthHelper::audit_action(&context, "create", "user", Some(&created_user.id.to_string()), true, None);
 
-        info!("User created: {} by admin {}", 
+        info!("[{}] User created: {} by admin {}", context.request_id,
               created_user.username,
               context.current_user().map(|u| u.user_id).unwrap_or(0));
 
         Ok(created_user)
     });
 
-    Ok(command_handler!("create_user", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("create_user", &context, { result }))
 }
 
 /// Get user by ID
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_user_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
-) -> Result<ApiResponse<User>, String> {
+) -> CommandResult<User> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_user", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         // Check if user is accessing their own profile or has admin permissions
         let session = context.current_user()?;
         if session.user_id != id {
@@ -65,56 +74,54 @@ pub async fn get_user_command(
 
         // Get user
         let user = state.services.users.get_user_by_id(id)
-            .map_err(|e| format!("Failed to get user: {}", e))?;
+            .context("Failed to get user")?;
 
-        debug!("User retrieved: {} (ID: {})", user.username, id);
+        debug!("[{}] User retrieved: {} (ID: {})", context.request_id, user.username, id);
         Ok(user)
     });
 
-    Ok(command_handler!("get_user", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_user", &context, { result }))
 }
 
 /// Get current authenticated user
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_current_user_command(
     state: State<'_, AppState>,
     token: Option<String>,
-) -> Result<ApiResponse<User>, String> {
+) -> CommandResult<User> {
+    // Authenticate (required for this endpoint)
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_current_user", {
-        // Authenticate (required for this endpoint)
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         let session = context.current_user()?;
 
         // Get current user
         let user = state.services.users.get_user_by_id(session.user_id)
-            .map_err(|e| format!("Failed to get current user: {}", e))?;
+            .context("Failed to get current user")?;
 
-        debug!("Current user retrieved: {}", user.username);
+        debug!("[{}] Current user retrieved: {}", context.request_id, user.username);
         Ok(user)
     });
 
-    Ok(command_handler!("get_current_user", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_current_user", &context, { result }))
 }
 
 /// Update user
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn update_user_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
     updates: UserUpdateRequest,
-) -> Result<ApiResponse<User>, String> {
+) -> CommandResult<User> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("update_user", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         // Check if user is updating their own profile or has admin permissions
         let session = context.current_user()?;
         if session.user_id != id {
@@ -133,97 +140,298 @@ pub async fn update_user_command(
         };
 
         // Update user
-        let updated_user = state.services.users.update_user(id, update_data)
-            .map_err(|e| format!("Failed to update user: {}", e))?;
+        let updated_user = state.services.users.update_user(&context, id, update_data)
+            .context("Failed to update user")?;
+        AuthHelper::audit_action(&context, "update", "user", Some(&id.to_string()), true, None);
 
-        info!("User updated: {} (ID: {}) by user {}", 
+        info!("[{}] User updated: {} (ID: {}) by user {}", context.request_id,
               updated_user.username, id, session.user_id);
 
         Ok(updated_user)
     });
 
-    Ok(command_handler!("update_user", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("update_user", &context, { result }))
 }
 
 /// Delete user
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn delete_user_command(
     state: State<'_, AppState>,
     token: Option<String>,
     id: i64,
-) -> Result<ApiResponse<()>, String> {
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("delete_user", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "user", "delete");
 
         // Prevent user from deleting themselves
         let session = context.current_user()?;
         if session.user_id == id {
-            return Err("Cannot delete your own account".to_string());
+            return Err(AppError::validation("id", "Cannot delete your own account"));
         }
 
         // Delete user
-        state.services.users.delete_user(id)
-            .map_err(|e| format!("Failed to delete user: {}", e))?;
+        state.services.users.delete_user(&context, id)
+            .context("Failed to delete user")?;
+        AuthHelper::audit_action(&context, "delete", "user", Some(&id.to_string()), true, None);
 
         // Force logout all sessions for the deleted user
         let _ = state.auth_manager.force_logout_user(id);
 
-        info!("User deleted: ID {} by admin {}", id, session.user_id);
+        info!("[{}] User deleted: ID {} by admin {}", context.request_id, id, session.user_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("delete_user", &context, { result }))
+}
+
+/// Restore a soft-deleted user
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn restore_user_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<User> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("restore_user", {
+        require_resource_access!(context, "user", "delete");
+
+        let user = state.services.users.restore_user(&context, id)
+            .context("Failed to restore user")?;
+        AuthHelper::audit_action(&context, "restore", "user", Some(&id.to_string()), true, None);
+
+        info!("[{}] User restored: {} (ID: {})", context.request_id, user.username, id);
+        Ok(user)
+    });
+
+    Ok(command_handler!("restore_user", &context, { result }))
+}
+
+/// Permanently remove a soft-deleted user
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn purge_user_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("purge_user", {
+        require_resource_access!(context, "system", "purge");
+
+        state.services.users.purge_user(&context, id)
+            .context("Failed to purge user")?;
+        AuthHelper::audit_action(&context, "purge", "user", Some(&id.to_string()), true, None);
+
+        warn!("[{}] User purged: ID {}", context.request_id, id);
         Ok(())
     });
 
-    Ok(command_handler!("delete_user", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("purge_user", &context, { result }))
 }
 
 /// User login
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn login_command(
+    window: Window,
     state: State<'_, AppState>,
     credentials: LoginRequest,
-) -> Result<ApiResponse<LoginResponse>, String> {
+) -> CommandResult<LoginResult> {
+    // Login requests start without a session
+    let context = RequestContext::new();
+    context.record_in_span();
+
     let result = time_command!("login", {
+        let device = LoginDevice { client_id: credentials.client_id.clone(), name: credentials.device_name.clone() };
+        let attempt = |failure_reason: Option<String>| LoginAttempt {
+            user_id: None,
+            username: credentials.username.clone(),
+            method: LoginMethod::Password,
+            device: device.clone(),
+            failure_reason,
+        };
+
+        // The client ID is whatever the caller sends, so attempts are counted
+        // against the window they came through
+        let source = format!("window:{}", window.label());
+        state.services.users.check_login_rate_limit(&credentials.username, &source)
+            .inspect_err(|e| {
+                warn!("[{}] Login refused for user {}: {}", context.request_id, credentials.username, e);
+                record_login(&state, attempt(Some(e.to_string())));
+            })?;
+
         // Authenticate user
-        let (session, token) = state.auth_manager
+        let outcome = state.auth_manager
             .authenticate(&credentials.username, &credentials.password)
             .await
-            .map_err(|e| {
-                warn!("Login failed for user {}: {}", credentials.username, e);
-                format!("Authentication failed: {}", e)
+            .inspect_err(|e| {
+                warn!("[{}] Login failed for user {}: {}", context.request_id, credentials.username, e);
+                record_login(&state, attempt(Some(e.to_string())));
             })?;
 
-        // Get user details (without password hash)
-        let user = state.services.users.get_user_by_id(session.user_id)
-            .map_err(|e| format!("Failed to get user details: {}", e))?;        let login_response = LoginResponse {
-            user: user.into(),
-            token,
-            expires_at: session.expires_at,
-            permissions: session.permissions.clone(),
-            session_id: session.session_id.clone(),
+        match outcome {
+            LoginOutcome::Authenticated(issued) => {
+                record_login(&state, LoginAttempt { user_id: Some(issued.session.user_id), ..attempt(None) });
+                // Get user details (without password hash)
+                let user = state.services.users.get_user_by_id(issued.session.user_id)
+                    .context("Failed to get user details")?;
+
+                let login_response = login_response(user, issued);
+
+                info!("[{}] User logged in: {} (session: {})", context.request_id,
+                      credentials.username, login_response.session_id);
+
+                Ok(LoginResult::Authenticated(Box::new(login_response)))
+            }
+            LoginOutcome::MfaRequired(challenge) => {
+                info!("[{}] Second factor requested for user {}", context.request_id, credentials.username);
+                Ok(LoginResult::MfaRequired(challenge))
+            }
+        }
+    });
+
+    Ok(command_handler!("login", &context, { result }))
+}
+
+/// Sign in through the configured OpenID Connect provider. The provider's
+/// sign-in page opens in the system browser and this returns once the
+/// browser comes back, with the same session or second factor challenge as
+/// a password login.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn oidc_login_command(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    device: Option<LoginDevice>,
+) -> CommandResult<LoginResult> {
+    // Login requests start without a session
+    let context = RequestContext::new();
+    context.record_in_span();
+
+    let result = time_command!("oidc_login", {
+        let open_browser = |url: &str| {
+            app.opener().open_url(url, None::<&str>).map_err(|e| AppError::ExternalService {
+                service: "browser".to_string(),
+                message: e.to_string(),
+            })
         };
+        // Failures before the identity is matched to an account only reach the security log
+        let user = state.auth_manager.oidc_user(open_browser)
+            .await
+            .inspect_err(|e| {
+                warn!("[{}] Single sign-on failed: {}", context.request_id, e);
+            })?;
+        let attempt = |failure_reason: Option<String>| LoginAttempt {
+            user_id: Some(user.id),
+            username: user.username.clone(),
+            method: LoginMethod::SingleSignOn,
+            device: device.clone().unwrap_or_default(),
+            failure_reason,
+        };
+
+        let outcome = state.auth_manager.oidc_login(&user)
+            .inspect_err(|e| {
+                warn!("[{}] Single sign-on failed for user {}: {}", context.request_id, user.username, e);
+                record_login(&state, attempt(Some(e.to_string())));
+            })?;
+
+        match outcome {
+            LoginOutcome::Authenticated(issued) => {
+                record_login(&state, attempt(None));
+                let user = state.services.users.get_user_by_id(issued.session.user_id)
+                    .context("Failed to get user details")?;
+                let login_response = login_response(user, issued);
+
+                info!("[{}] User logged in through single sign-on: {} (session: {})", context.request_id,
+                      login_response.user.username, login_response.session_id);
+
+                Ok(LoginResult::Authenticated(Box::new(login_response)))
+            }
+            LoginOutcome::MfaRequired(challenge) => {
+                info!("[{}] Second factor requested after single sign-on", context.request_id);
+                Ok(LoginResult::MfaRequired(challenge))
+            }
+        }
+    });
+
+    Ok(command_handler!("oidc_login", &context, { result }))
+}
+
+/// Exchange a refresh token for new tokens so the client can stay signed in
+/// without asking for the password again. Refresh tokens are single-use;
+/// reusing one signs the session out everywhere.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn refresh_token_command(
+    state: State<'_, AppState>,
+    refresh_token: String,
+) -> CommandResult<LoginResponse> {
+    // Refresh requests arrive after the access token has expired
+    let context = RequestContext::new();
+    context.record_in_span();
 
-        info!("User logged in: {} (session: {})", 
-              credentials.username, session.session_id);
+    let result = time_command!("refresh_token", {
+        let issued = state.auth_manager.refresh_session(&refresh_token)
+            .inspect_err(|e| {
+                warn!("[{}] Token refresh failed: {}", context.request_id, e);
+            })?;
+
+        let user = state.services.users.get_user_by_id(issued.session.user_id)
+            .context("Failed to get user details")?;
+        let response = login_response(user, issued);
 
-        Ok(login_response)
+        debug!("[{}] Session {} refreshed for user {}", context.request_id,
+               response.session_id, response.user.username);
+        Ok(response)
     });
 
-    Ok(command_handler!("login", None, { result }))
+    Ok(command_handler!("refresh_token", &context, { result }))
+}
+
+/// Add a login attempt to the account's login history. Failing to record it
+/// does not fail the login.
+pub(crate) fn record_login(state: &AppState, attempt: LoginAttempt) {
+    if let Err(e) = state.services.login_history.record(&attempt) {
+        warn!("Failed to record {} login for {}: {}", attempt.method, attempt.username, e);
+    }
+}
+
+pub(crate) fn login_response(user: User, issued: IssuedTokens) -> LoginResponse {
+    LoginResponse {
+        user: user.into(),
+        token: issued.access_token,
+        expires_at: issued.session.expires_at,
+        refresh_token: issued.refresh_token,
+        refresh_expires_at: issued.refresh_expires_at,
+        permissions: issued.session.permissions,
+        session_id: issued.session.session_id,
+        locale: issued.session.locale,
+    }
 }
 
 /// User logout
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn logout_command(
     state: State<'_, AppState>,
     token: Option<String>,
-) -> Result<ApiResponse<()>, String> {
+    refresh_token: Option<String>,
+) -> CommandResult<()> {
+    let context = RequestContext::new();
+    context.record_in_span();
+
     let result = time_command!("logout", {
         // Validate token to get session
         if let Some(token) = token {
@@ -231,36 +439,259 @@ pub async fn logout_command(
                 Ok(session) => {
                     // Logout session
                     state.auth_manager.logout(&session.session_id)
-                        .map_err(|e| format!("Failed to logout: {}", e))?;
+                        .context("Failed to logout")?;
                     
-                    info!("User logged out: {} (session: {})", 
+                    info!("[{}] User logged out: {} (session: {})", context.request_id,
                           session.username, session.session_id);
                 }
                 Err(e) => {
-                    warn!("Logout with invalid token: {}", e);
+                    warn!("[{}] Logout with invalid token: {}", context.request_id, e);
                     // Don't fail logout for invalid tokens
                 }
             }
         }
 
+        // An expired access token leaves the session refreshable until its
+        // refresh token is revoked too
+        if let Some(refresh_token) = refresh_token {
+            if state.auth_manager.revoke_refresh_token(&refresh_token) {
+                info!("[{}] Session revoked by refresh token on logout", context.request_id);
+            }
+        }
+
         Ok(())
     });
 
-    Ok(command_handler!("logout", None, { result }))
+    Ok(command_handler!("logout", &context, { result }))
+}
+
+/// List signed-in sessions, of one user or of everyone
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn list_active_sessions_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    user_id: Option<i64>,
+) -> CommandResult<Vec<ActiveSession>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("list_active_sessions", {
+        require_resource_access!(context, "user", "sessions");
+        let current_session_id = &context.current_user()?.session_id;
+
+        let mut sessions = state.services.sessions.list_active(user_id)
+            .context("Failed to list sessions")?;
+        for session in &mut sessions {
+            session.current = &session.session_id == current_session_id;
+        }
+
+        debug!("[{}] Retrieved {} active sessions", context.request_id, sessions.len());
+        Ok(sessions)
+    });
+
+    Ok(command_handler!("list_active_sessions", &context, { result }))
+}
+
+/// End a session, e.g. one on a lost or compromised device. Its access and
+/// refresh tokens stop working at once.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn revoke_session_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    session_id: String,
+) -> CommandResult<bool> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("revoke_session", {
+        require_resource_access!(context, "user", "sessions");
+        let admin = context.current_user()?;
+
+        let revoked = state.auth_manager.revoke_session(&session_id, admin.user_id)
+            .context("Failed to revoke session")?;
+        AuthHelper::audit_action(&context, "revoke", "session", Some(&session_id), true, None);
+
+        info!("[{}] Session {} revoked by user {} (was active: {})", context.request_id, session_id, admin.user_id, revoked);
+        Ok(revoked)
+    });
+
+    Ok(command_handler!("revoke_session", &context, { result }))
+}
+
+/// Replace the secret session tokens are signed with. Tokens signed with
+/// the old secret are accepted for `grace_hours`, by default the session
+/// length, so nobody is signed out; they switch over on their next refresh.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn rotate_jwt_secret_command(
+    state: State<'_, AppState>,
+    secrets: State<'_, Secrets>,
+    token: Option<String>,
+    grace_hours: Option<i64>,
+) -> CommandResult<JwtKeyRotation> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("rotate_jwt_secret", {
+        require_resource_access!(context, "system", "admin");
+        AuthHelper::require_super_admin(&context, "rotate JWT secret")
+            .context("Failed to rotate JWT secret")?;
+
+        let grace = match grace_hours {
+            Some(hours) if !(1..=MAX_JWT_ROTATION_GRACE_HOURS).contains(&hours) => {
+                return Err(AppError::validation("grace_hours", format!("Grace window must be 1-{} hours", MAX_JWT_ROTATION_GRACE_HOURS)));
+            }
+            Some(hours) => Duration::hours(hours),
+            None => state.auth_manager.session_duration(),
+        };
+        let previous_valid_until = Utc::now() + grace;
+        let new_secret = secrets.rotate_jwt_secret(previous_valid_until)
+            .context("Failed to rotate JWT secret")?;
+        let rotation = state.auth_manager.rotate_signing_key(&new_secret, previous_valid_until);
+        AuthHelper::audit_action(&context, "rotate", "jwt_secret", Some(&rotation.key_id), true, None);
+
+        info!("[{}] JWT secret rotated to key {}; key {} accepted until {}", context.request_id,
+              rotation.key_id, rotation.previous_key_id, rotation.previous_valid_until);
+        Ok(rotation)
+    });
+
+    Ok(command_handler!("rotate_jwt_secret", &context, { result }))
+}
+
+/// Get how many wrong passwords a user has entered since their last sign-in
+/// and whether their account is locked
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_account_lockout_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    user_id: i64,
+) -> CommandResult<AccountLockoutInfo> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_account_lockout", {
+        require_resource_access!(context, "user", "read");
+
+        let lockout = state.services.users.get_account_lockout_info(user_id)
+            .context("Failed to get account lockout")?;
+
+        Ok(lockout)
+    });
+
+    Ok(command_handler!("get_account_lockout", &context, { result }))
+}
+
+/// Unlock an account locked after too many wrong passwords without waiting
+/// for the lock to run out
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn unlock_user_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    user_id: i64,
+) -> CommandResult<AccountLockoutInfo> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("unlock_user", {
+        require_resource_access!(context, "user", "update");
+
+        let lockout = state.services.users.unlock_user_account(&context, user_id)
+            .context("Failed to unlock user")?;
+        AuthHelper::audit_action(&context, "unlock", "user", Some(&user_id.to_string()), true, None);
+
+        info!("[{}] User {} unlocked", context.request_id, user_id);
+        Ok(lockout)
+    });
+
+    Ok(command_handler!("unlock_user", &context, { result }))
+}
+
+/// Get the current user's logins, newest first, including failed attempts,
+/// so they can spot sign-ins that were not theirs
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_my_login_history_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> CommandResult<Vec<LoginHistoryEntry>> {
+    // Authenticate (required for this endpoint)
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_my_login_history", {
+        let session = context.current_user()?;
+
+        let filter = LoginHistoryFilter { user_id: Some(session.user_id), ..LoginHistoryFilter::default() };
+        let limit = limit.unwrap_or(DEFAULT_LOGIN_HISTORY_LIMIT).clamp(1, MAX_LOGIN_HISTORY_LIMIT);
+        let entries = state.services.login_history.query(&filter, limit, offset.unwrap_or(0))
+            .context("Failed to get login history")?;
+
+        debug!("[{}] Retrieved {} logins for user {}", context.request_id, entries.len(), session.user_id);
+        Ok(entries)
+    });
+
+    Ok(command_handler!("get_my_login_history", &context, { result }))
+}
+
+/// Get logins across all users, or one user, newest first
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_login_history_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    filter: Option<LoginHistoryFilter>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> CommandResult<Vec<LoginHistoryEntry>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_login_history", {
+        require_resource_access!(context, "user", "sessions");
+
+        let filter = filter.unwrap_or_default();
+        if let (Some(start), Some(end)) = (filter.start_date, filter.end_date) {
+            if start > end {
+                return Err(AppError::validation("start_date", "start_date must not be after end_date"));
+            }
+        }
+
+        let limit = limit.unwrap_or(DEFAULT_LOGIN_HISTORY_LIMIT).clamp(1, MAX_LOGIN_HISTORY_LIMIT);
+        let entries = state.services.login_history.query(&filter, limit, offset.unwrap_or(0))
+            .context("Failed to get login history")?;
+
+        debug!("[{}] Retrieved {} login history entries", context.request_id, entries.len());
+        Ok(entries)
+    });
+
+    Ok(command_handler!("get_login_history", &context, { result }))
 }
 
 /// Get users with filtering
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn get_users_command(
     state: State<'_, AppState>,
     token: Option<String>,
     filter: QueryFilterRequest,
-) -> Result<ApiResponse<PaginatedResponse<User>>, String> {
+) -> CommandResult<PaginatedResponse<User>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("get_users", {
-        // Authenticate and authorize
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
         require_resource_access!(context, "user", "read");
 
         // Get users with filters
@@ -271,59 +702,360 @@ pub async fn get_users_command(
         let user_role = crate::models::UserRole::Inspector; // This would be extracted from filters in a real implementation
         
         let paginated_users = state.services.users.get_users_by_role(user_role, query_filter)
-            .map_err(|e| format!("Failed to get users: {}", e))?;
+            .context("Failed to get users")?;
 
-        debug!("Retrieved {} users", paginated_users.data.len());
+        debug!("[{}] Retrieved {} users", context.request_id, paginated_users.data.len());
 
         let response = PaginatedResponse::from(paginated_users);
         Ok(response)
     });
 
-    Ok(command_handler!("get_users", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
+    Ok(command_handler!("get_users", &context, { result }))
 }
 
 /// Change user password
 #[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
 pub async fn change_password_command(
     state: State<'_, AppState>,
     token: Option<String>,
     password_data: ChangePasswordRequest,
-) -> Result<ApiResponse<()>, String> {
+) -> CommandResult<()> {
+    // Authenticate (required for this endpoint)
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
     let result = time_command!("change_password", {
-        // Authenticate (required for this endpoint)
-        let context = AuthHelper::validate_request(&state.auth_manager, token)
-            .map_err(|e| format!("Authentication failed: {}", e))?;
-        
-        let session = context.current_user()?;
+        let session = AuthHelper::require_full_session(&context)?;
 
         // Verify current password
         let password_valid = state.services.users
             .verify_password(session.user_id, password_data.current_password)
-            .map_err(|e| format!("Failed to verify current password: {}", e))?;
+            .context("Failed to verify current password")?;
 
         if !password_valid {
-            warn!("Password change failed: invalid current password for user {}", session.user_id);
-            return Err("Invalid current password".to_string());
+            warn!("[{}] Password change failed: invalid current password for user {}", context.request_id, session.user_id);
+            return Err(AppError::validation("current_password", "Invalid current password"));
         }
 
         // Update password
         state.services.users
-            .update_password(session.user_id, password_data.new_password)
-            .map_err(|e| format!("Failed to update password: {}", e))?;
+            .update_password(&context, session.user_id, password_data.new_password)
+            .context("Failed to update password")?;
+        AuthHelper::audit_action(&context, "change_password", "user", Some(&session.user_id.to_string()), true, None);
 
         // Force logout all other sessions for this user (security measure)
         let logged_out_sessions = state.auth_manager.force_logout_user(session.user_id)
-            .map_err(|e| format!("Failed to logout other sessions: {}", e))?;
+            .context("Failed to logout other sessions")?;
 
-        info!("Password changed for user {} (logged out {} other sessions)", 
+        info!("[{}] Password changed for user {} (logged out {} other sessions)", context.request_id,
               session.user_id, logged_out_sessions);
 
         Ok(())
     });
 
-    Ok(command_handler!("change_password", 
-                       result.as_ref().ok().and_then(|_| None), 
-                       { result }))
-}
\ No newline at end of file
+    Ok(command_handler!("change_password", &context, { result }))
+}
+
+/// Set the preferred locale of the current user
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn set_user_locale_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    locale: String,
+) -> CommandResult<Locale> {
+    // Authenticate (required for this endpoint)
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("set_user_locale", {
+        let session = context.current_user()?;
+
+        let locale: Locale = locale.parse()
+            .context("Invalid locale")?;
+
+        state.services.users
+            .set_user_locale(&context, session.user_id, locale)
+            .context("Failed to update locale")?;
+        AuthHelper::audit_action(&context, "set_locale", "user", Some(&session.user_id.to_string()), true, None);
+
+        // Apply to the user's active sessions so responses switch immediately
+        state.auth_manager.update_user_locale(session.user_id, locale);
+
+        info!("[{}] Locale set to {} for user {}", context.request_id, locale, session.user_id);
+        Ok(locale)
+    });
+
+    Ok(command_handler!("set_user_locale", &context, { result }))
+}
+
+/// Ask for a password reset code to be emailed. The response is the same
+/// whether or not the account exists.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn request_password_reset_command(
+    state: State<'_, AppState>,
+    identifier: String,
+) -> CommandResult<()> {
+    // Users asking for a reset cannot sign in
+    let context = RequestContext::new();
+    context.record_in_span();
+
+    let result = time_command!("request_password_reset", {
+        let reset = state.services.users.initiate_password_reset(&context, &identifier)
+            .context("Failed to request password reset")?;
+
+        if let Some(reset) = reset {
+            let body = format!(
+                "A password reset was requested for your CranePro account.\n\n\
+                 Reset code: {}\n\n\
+                 The code works once and expires at {} UTC. If you did not ask for a reset, ignore this message.",
+                reset.token,
+                reset.expires_at.format("%Y-%m-%d %H:%M"),
+            );
+            let sent = state.services.notifications.send_private(reset.user_id, "Reset your CranePro password", &body)
+                .context("Failed to send password reset")?;
+            if !sent {
+                warn!("[{}] No email channel configured; password reset for user {} was not sent",
+                      context.request_id, reset.user_id);
+            }
+        }
+        Ok(())
+    });
+
+    Ok(command_handler!("request_password_reset", &context, { result }))
+}
+
+/// Create a password reset code for a user, for an administrator to hand
+/// over when email is not available
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn create_password_reset_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    user_id: i64,
+) -> CommandResult<PasswordReset> {
+    // Authenticate (required for this endpoint)
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("create_password_reset", {
+        require_resource_access!(context, "user", "update");
+
+        let reset = state.services.users.create_password_reset_for(&context, user_id)
+            .context("Failed to create password reset")?
+            .ok_or_else(|| AppError::validation("user_id", "The user is inactive or already has too many outstanding resets"))?;
+        AuthHelper::audit_action(&context, "create_password_reset", "user", Some(&user_id.to_string()), true, None);
+
+        info!("[{}] Password reset created for user {}", context.request_id, user_id);
+        Ok(reset)
+    });
+
+    Ok(command_handler!("create_password_reset", &context, { result }))
+}
+
+/// Set a new password with a reset code. Every session of the user is
+/// signed out.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn complete_password_reset_command(
+    state: State<'_, AppState>,
+    reset_token: String,
+    new_password: String,
+) -> CommandResult<()> {
+    // Users resetting their password cannot sign in
+    let context = RequestContext::new();
+    context.record_in_span();
+
+    let result = time_command!("complete_password_reset", {
+        let user_id = state.services.users.complete_password_reset(&context, &reset_token, new_password)
+            .inspect_err(|e| {
+                warn!("[{}] Password reset failed: {}", context.request_id, e);
+            })
+            .context("Failed to reset password")?;
+        let signed_out = state.auth_manager.force_logout_user(user_id)
+            .context("Failed to end sessions")?;
+        AuthHelper::audit_action(&context, "reset_password", "user", Some(&user_id.to_string()), true, None);
+
+        info!("[{}] Password reset for user {}; {} sessions ended", context.request_id, user_id, signed_out);
+        Ok(())
+    });
+
+    Ok(command_handler!("complete_password_reset", &context, { result }))
+}
+
+/// Get the current user's preferences
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_user_preferences_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<UserPreferences> {
+    // Authenticate (required for this endpoint)
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_user_preferences", {
+        let session = context.current_user()?;
+
+        let preferences = state.services.users.get_preferences(session.user_id)
+            .context("Failed to get preferences")?;
+
+        debug!("[{}] Preferences retrieved for user {}", context.request_id, session.user_id);
+        Ok(preferences)
+    });
+
+    Ok(command_handler!("get_user_preferences", &context, { result }))
+}
+
+/// Save the current user's preferences, replacing those stored before
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn set_user_preferences_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    preferences: UserPreferencesInput,
+) -> CommandResult<UserPreferences> {
+    // Authenticate (required for this endpoint)
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("set_user_preferences", {
+        AuthHelper::require_full_session(&context)?;
+        let preferences = state.services.users.set_preferences(&context, preferences)
+            .context("Failed to save preferences")?;
+
+        info!("[{}] Preferences saved for user {}", context.request_id, preferences.user_id);
+        Ok(preferences)
+    });
+
+    Ok(command_handler!("set_user_preferences", &context, { result }))
+}
+
+/// Record a vacation, sick or training absence
+///
+/// Users may record their own absences; recording one for someone else
+/// requires user update access. Long absences reassign the user's scheduled
+/// inspections in the period.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn create_user_absence_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    absence: CreateUserAbsenceRequest,
+) -> CommandResult<AbsenceRecordResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("create_user_absence", {
+        let session = context.current_user()?;
+        if session.user_id != absence.user_id {
+            require_resource_access!(context, "user", "update");
+        }
+
+        let recorded = state.services.availability
+            .create_absence(&context, absence.to_absence(session.user_id))
+            .context("Failed to record absence")?;
+        AuthHelper::audit_action(&context, "create", "user_absence", Some(&recorded.absence.id.to_string()), true, None);
+
+        info!("[{}] Absence {} recorded for user {} ({} inspection(s) reassigned)", context.request_id,
+              recorded.absence.id, recorded.absence.user_id, recorded.reassignments.len());
+        let unassigned = recorded.reassignments.iter().filter(|r| r.to_inspector_id.is_none()).count();
+        if unassigned > 0 {
+            warn!("[{}] {} inspection(s) could not be reassigned during absence {}", context.request_id,
+                  unassigned, recorded.absence.id);
+        }
+
+        Ok(recorded)
+    });
+
+    Ok(command_handler!("create_user_absence", &context, { result }))
+}
+
+/// List absences, optionally for one user and overlapping a date range
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_user_absences_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    user_id: Option<i64>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> CommandResult<Vec<UserAbsence>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_user_absences", {
+        let session = context.current_user()?;
+        if user_id != Some(session.user_id) {
+            require_resource_access!(context, "user", "read");
+        }
+
+        let absences = state.services.availability.get_absences(user_id, from, to)
+            .context("Failed to get absences")?;
+
+        debug!("[{}] Retrieved {} absences", context.request_id, absences.len());
+        Ok(absences)
+    });
+
+    Ok(command_handler!("get_user_absences", &context, { result }))
+}
+
+/// Delete an absence record
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn delete_user_absence_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("delete_user_absence", {
+        let session = context.current_user()?;
+        let absence = state.services.availability.get_absence_by_id(id)
+            .context("Failed to get absence")?;
+        if absence.user_id != session.user_id {
+            require_resource_access!(context, "user", "update");
+        }
+
+        state.services.availability.delete_absence(&context, id)
+            .context("Failed to delete absence")?;
+        AuthHelper::audit_action(&context, "delete", "user_absence", Some(&id.to_string()), true, None);
+
+        info!("[{}] Absence {} deleted for user {}", context.request_id, id, absence.user_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("delete_user_absence", &context, { result }))
+}
+
+/// Inspectors available on a date, least loaded first, for assigning work
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_available_inspectors_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    date: NaiveDate,
+) -> CommandResult<Vec<AvailableInspector>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_available_inspectors", {
+        require_resource_access!(context, "user", "read");
+
+        let inspectors = state.services.availability.get_available_inspectors(date)
+            .context("Failed to get available inspectors")?;
+
+        debug!("[{}] {} inspectors available on {}", context.request_id, inspectors.len(), date);
+        Ok(inspectors)
+    });
+
+    Ok(command_handler!("get_available_inspectors", &context, { result }))
+}
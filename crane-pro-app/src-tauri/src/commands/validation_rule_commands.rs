@@ -0,0 +1,143 @@
+//! Validation rule command handlers
+//!
+//! This module contains Tauri command handlers for the configurable rules
+//! inspection items are checked against when saved and when their
+//! inspection is submitted.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{InspectionRuleReport, ValidationRule, ValidationRuleInput};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info};
+
+/// List validation rules
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_validation_rules_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    include_inactive: Option<bool>,
+) -> CommandResult<Vec<ValidationRule>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_validation_rules", {
+        require_resource_access!(context, "inspection", "read");
+
+        let rules = state.services.validation_rules.get_rules(include_inactive.unwrap_or(false))
+            .context("Failed to get validation rules")?;
+
+        debug!("[{}] Retrieved {} validation rules", context.request_id, rules.len());
+        Ok(rules)
+    });
+
+    Ok(command_handler!("get_validation_rules", &context, { result }))
+}
+
+/// Add a validation rule
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn create_validation_rule_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    rule: ValidationRuleInput,
+) -> CommandResult<ValidationRule> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("create_validation_rule", {
+        require_resource_access!(context, "compliance", "update");
+
+        let rule = state.services.validation_rules.create_rule(&context, rule)
+            .context("Failed to create validation rule")?;
+        AuthHelper::audit_action(&context, "create_validation_rule", "validation_rule", Some(&rule.id.to_string()), true, None);
+
+        info!("[{}] Validation rule {} created: {}", context.request_id, rule.id, rule.name);
+        Ok(rule)
+    });
+
+    Ok(command_handler!("create_validation_rule", &context, { result }))
+}
+
+/// Replace a validation rule's definition
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn update_validation_rule_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    rule_id: i64,
+    rule: ValidationRuleInput,
+) -> CommandResult<ValidationRule> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("update_validation_rule", {
+        require_resource_access!(context, "compliance", "update");
+
+        let rule = state.services.validation_rules.update_rule(&context, rule_id, rule)
+            .context("Failed to update validation rule")?;
+        AuthHelper::audit_action(&context, "update_validation_rule", "validation_rule", Some(&rule_id.to_string()), true, None);
+
+        info!("[{}] Validation rule {} updated", context.request_id, rule_id);
+        Ok(rule)
+    });
+
+    Ok(command_handler!("update_validation_rule", &context, { result }))
+}
+
+/// Remove a validation rule
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn delete_validation_rule_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    rule_id: i64,
+) -> CommandResult<()> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("delete_validation_rule", {
+        require_resource_access!(context, "compliance", "update");
+
+        state.services.validation_rules.delete_rule(&context, rule_id)
+            .context("Failed to delete validation rule")?;
+        AuthHelper::audit_action(&context, "delete_validation_rule", "validation_rule", Some(&rule_id.to_string()), true, None);
+
+        info!("[{}] Validation rule {} deleted", context.request_id, rule_id);
+        Ok(())
+    });
+
+    Ok(command_handler!("delete_validation_rule", &context, { result }))
+}
+
+/// Check every item of an inspection against the active rules, as
+/// submission would
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn check_inspection_rules_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    inspection_id: i64,
+) -> CommandResult<InspectionRuleReport> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("check_inspection_rules", {
+        require_resource_access!(context, "inspection", "read");
+
+        let report = state.services.validation_rules.check_inspection(inspection_id)
+            .context("Failed to check inspection rules")?;
+
+        debug!("[{}] Inspection {}: {} of {} items break validation rules", context.request_id,
+               inspection_id, report.items.len(), report.items_checked);
+        Ok(report)
+    });
+
+    Ok(command_handler!("check_inspection_rules", &context, { result }))
+}
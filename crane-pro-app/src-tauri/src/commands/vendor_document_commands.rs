@@ -0,0 +1,149 @@
+//! Vendor document command handlers
+//!
+//! This module contains Tauri command handlers for documents received by
+//! email from third-party inspectors: ingesting a message from the mail
+//! bridge, working the queue of unmatched documents and listing the
+//! documents filed against an asset.
+
+use crate::commands::media_commands::media_root;
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{EmailIngestResult, InboundEmail, VendorDocument};
+use crate::{require_resource_access, time_command, command_handler};
+use std::path::Path;
+use tauri::State;
+use log::{debug, info};
+
+/// Ingest an email from the mail bridge, filing its attachments against the
+/// asset named in the subject or queueing them for manual filing
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn ingest_vendor_email_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    email: InboundEmail,
+) -> CommandResult<EmailIngestResult> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("ingest_vendor_email", {
+        require_resource_access!(context, "asset", "update");
+
+        let media_root = media_root(&state)?;
+        let ingested = state.services.vendor_documents.ingest_email(&context, Path::new(&media_root), email)
+            .context("Failed to ingest email")?;
+        for document in &ingested.documents {
+            AuthHelper::audit_action(&context, "ingest", "vendor_document", Some(&document.id.to_string()), true, None);
+        }
+
+        info!("[{}] Email ingested: {} documents, {} skipped, asset {:?}", context.request_id,
+              ingested.documents.len(), ingested.skipped.len(), ingested.asset_id);
+        Ok(ingested)
+    });
+
+    Ok(command_handler!("ingest_vendor_email", &context, { result }))
+}
+
+/// Get documents waiting to be filed against an asset
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_unmatched_vendor_documents_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Vec<VendorDocument>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_unmatched_vendor_documents", {
+        require_resource_access!(context, "asset", "read");
+
+        let documents = state.services.vendor_documents.get_unmatched_documents()
+            .context("Failed to get unmatched documents")?;
+
+        debug!("[{}] Retrieved {} unmatched vendor documents", context.request_id, documents.len());
+        Ok(documents)
+    });
+
+    Ok(command_handler!("get_unmatched_vendor_documents", &context, { result }))
+}
+
+/// Get the vendor documents filed against an asset
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_asset_vendor_documents_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    asset_id: i64,
+) -> CommandResult<Vec<VendorDocument>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_asset_vendor_documents", {
+        require_resource_access!(context, "asset", "read");
+
+        let documents = state.services.vendor_documents.get_asset_documents(asset_id)
+            .context("Failed to get vendor documents")?;
+
+        debug!("[{}] Retrieved {} vendor documents for asset {}", context.request_id, documents.len(), asset_id);
+        Ok(documents)
+    });
+
+    Ok(command_handler!("get_asset_vendor_documents", &context, { result }))
+}
+
+/// File a vendor document against an asset by hand
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn file_vendor_document_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+    asset_id: i64,
+) -> CommandResult<VendorDocument> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("file_vendor_document", {
+        require_resource_access!(context, "asset", "update");
+
+        let document = state.services.vendor_documents.file_document(&context, id, asset_id)
+            .context("Failed to file vendor document")?;
+        AuthHelper::audit_action(&context, "file", "vendor_document", Some(&id.to_string()), true, None);
+
+        info!("[{}] Vendor document {} filed against asset {}", context.request_id, id, asset_id);
+        Ok(document)
+    });
+
+    Ok(command_handler!("file_vendor_document", &context, { result }))
+}
+
+/// Discard a vendor document and its file
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn discard_vendor_document_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    id: i64,
+) -> CommandResult<VendorDocument> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("discard_vendor_document", {
+        require_resource_access!(context, "asset", "update");
+
+        let media_root = media_root(&state)?;
+        let document = state.services.vendor_documents.discard_document(&context, Path::new(&media_root), id)
+            .context("Failed to discard vendor document")?;
+        AuthHelper::audit_action(&context, "delete", "vendor_document", Some(&id.to_string()), true, None);
+
+        info!("[{}] Vendor document {} ({}) discarded", context.request_id, id, document.file_name);
+        Ok(document)
+    });
+
+    Ok(command_handler!("discard_vendor_document", &context, { result }))
+}
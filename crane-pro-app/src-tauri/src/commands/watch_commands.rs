@@ -0,0 +1,137 @@
+//! Watchlist command handlers
+//!
+//! This module contains Tauri command handlers for starring assets and
+//! inspections. Watchers are notified of status changes and new findings via
+//! the `watch-notification` event; due dates are polled with
+//! `get_watched_due_soon_command`.
+
+use crate::commands::{AppState, CommandResult, ErrorContext};
+use crate::errors::AppResult;
+use crate::middleware::auth::AuthHelper;
+use crate::middleware::RequestContext;
+use crate::models::{Watch, WatchEntityType, WatchNotification, DEFAULT_WATCH_DUE_DAYS};
+use crate::trace;
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::{AppHandle, State};
+use log::{info, debug, warn};
+
+/// Event emitted for each watch notification
+pub const WATCH_NOTIFICATION_EVENT: &str = "watch-notification";
+
+/// Emit watch notifications. Failing to work out the watchers must not fail
+/// the change that triggered them, so errors are only logged.
+pub(crate) fn notify_watchers(app: &AppHandle, context: &RequestContext, notifications: AppResult<Vec<WatchNotification>>) {
+    let notifications = match notifications {
+        Ok(notifications) => notifications,
+        Err(e) => {
+            warn!("[{}] Failed to resolve watchers: {}", context.request_id, e);
+            return;
+        }
+    };
+    for notification in notifications {
+        if let Err(e) = trace::emit(app, WATCH_NOTIFICATION_EVENT, notification) {
+            warn!("[{}] Failed to emit watch notification: {}", context.request_id, e);
+        }
+    }
+}
+
+/// Watch an asset or inspection
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn watch_record_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    entity_type: WatchEntityType,
+    entity_id: i64,
+) -> CommandResult<Watch> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("watch_record", {
+        require_resource_access!(context, entity_type.resource(), "read");
+
+        let watch = state.services.watches.watch(&context, entity_type, entity_id)
+            .context("Failed to watch record")?;
+
+        info!("[{}] Watching {} {}", context.request_id, entity_type, entity_id);
+        Ok(watch)
+    });
+
+    Ok(command_handler!("watch_record", &context, { result }))
+}
+
+/// Stop watching an asset or inspection
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn unwatch_record_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    entity_type: WatchEntityType,
+    entity_id: i64,
+) -> CommandResult<bool> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("unwatch_record", {
+        let removed = state.services.watches.unwatch(&context, entity_type, entity_id)
+            .context("Failed to unwatch record")?;
+
+        info!("[{}] Stopped watching {} {}", context.request_id, entity_type, entity_id);
+        Ok(removed)
+    });
+
+    Ok(command_handler!("unwatch_record", &context, { result }))
+}
+
+/// Get the records watched by the current user
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_my_watches_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> CommandResult<Vec<Watch>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_my_watches", {
+        let user_id = context.current_user()?.user_id;
+
+        let watches = state.services.watches.get_watches(user_id)
+            .context("Failed to get watches")?;
+
+        debug!("[{}] Retrieved {} watches", context.request_id, watches.len());
+        Ok(watches)
+    });
+
+    Ok(command_handler!("get_my_watches", &context, { result }))
+}
+
+/// Get open inspections on watched records that are overdue or due within
+/// `days_ahead` days
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_watched_due_soon_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    days_ahead: Option<i64>,
+) -> CommandResult<Vec<WatchNotification>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_watched_due_soon", {
+        require_resource_access!(context, "inspection", "read");
+        let user_id = context.current_user()?.user_id;
+
+        let due = state.services.watches.get_due_soon(user_id, days_ahead.unwrap_or(DEFAULT_WATCH_DUE_DAYS).max(0))
+            .context("Failed to get watched due dates")?;
+
+        debug!("[{}] Retrieved {} watched inspections due soon", context.request_id, due.len());
+        Ok(due)
+    });
+
+    Ok(command_handler!("get_watched_due_soon", &context, { result }))
+}
@@ -0,0 +1,157 @@
+//! Work order command handlers
+//!
+//! This module contains Tauri command handlers for raising work orders from
+//! non-compliant inspection items and moving them through
+//! Open → In Progress → Completed → Verified.
+
+use crate::commands::{log_notifications, AppState, CommandResult, ErrorContext};
+use crate::middleware::auth::AuthHelper;
+use crate::models::{WorkOrder, WorkOrderFilter, WorkOrderInput, WorkOrderStatus, WorkOrderUpdateData};
+use crate::{require_resource_access, time_command, command_handler};
+use tauri::State;
+use log::{debug, info};
+
+/// Raise a work order for a non-compliant inspection item
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn create_work_order_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    inspection_item_id: i64,
+    work_order: Option<WorkOrderInput>,
+) -> CommandResult<WorkOrder> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("create_work_order", {
+        require_resource_access!(context, "inspection", "update");
+
+        let work_order = state.services.work_orders
+            .create_from_item(&context, inspection_item_id, work_order.unwrap_or_default())
+            .context("Failed to create work order")?;
+        AuthHelper::audit_action(&context, "create_work_order", "work_order", Some(&work_order.id.to_string()), true, None);
+        log_notifications(&context, state.services.notifications.work_order_assigned(&context, &work_order, None));
+
+        info!("[{}] Work order {} raised for inspection item {} with {} priority",
+              context.request_id, work_order.id, inspection_item_id, work_order.priority);
+        Ok(work_order)
+    });
+
+    Ok(command_handler!("create_work_order", &context, { result }))
+}
+
+/// Change the title, priority, assignee, due date or parts of an open work
+/// order
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn update_work_order_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    work_order_id: i64,
+    updates: WorkOrderUpdateData,
+) -> CommandResult<WorkOrder> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("update_work_order", {
+        require_resource_access!(context, "inspection", "update");
+
+        let previous_assignee_id = state.services.work_orders.get_work_order(work_order_id)
+            .context("Failed to update work order")?
+            .assignee_id;
+        let work_order = state.services.work_orders.update_work_order(&context, work_order_id, updates)
+            .context("Failed to update work order")?;
+        AuthHelper::audit_action(&context, "update_work_order", "work_order", Some(&work_order_id.to_string()), true, None);
+        log_notifications(&context,
+            state.services.notifications.work_order_assigned(&context, &work_order, previous_assignee_id));
+
+        info!("[{}] Work order {} updated", context.request_id, work_order_id);
+        Ok(work_order)
+    });
+
+    Ok(command_handler!("update_work_order", &context, { result }))
+}
+
+/// Move a work order to its next status. Verifying completed work needs
+/// compliance update rights.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn transition_work_order_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    work_order_id: i64,
+    status: WorkOrderStatus,
+    notes: Option<String>,
+) -> CommandResult<WorkOrder> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("transition_work_order", {
+        require_resource_access!(context, "inspection", "update");
+        if status == WorkOrderStatus::Verified {
+            require_resource_access!(context, "compliance", "update");
+        }
+
+        let work_order = state.services.work_orders.transition(&context, work_order_id, status, notes)
+            .context("Failed to change work order status")?;
+        AuthHelper::audit_action(&context, "transition_work_order", "work_order", Some(&work_order_id.to_string()), true, None);
+
+        info!("[{}] Work order {} is now {}", context.request_id, work_order_id, work_order.status);
+        Ok(work_order)
+    });
+
+    Ok(command_handler!("transition_work_order", &context, { result }))
+}
+
+/// Get a single work order
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_work_order_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    work_order_id: i64,
+) -> CommandResult<WorkOrder> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_work_order", {
+        require_resource_access!(context, "inspection", "read");
+
+        let work_order = state.services.work_orders.get_work_order(work_order_id)
+            .context("Failed to get work order")?;
+
+        debug!("[{}] Retrieved work order {}", context.request_id, work_order_id);
+        Ok(work_order)
+    });
+
+    Ok(command_handler!("get_work_order", &context, { result }))
+}
+
+/// Get work orders by status, asset, assignee or inspection, most urgent first
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id, user_id))]
+pub async fn get_work_orders_command(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    filter: Option<WorkOrderFilter>,
+) -> CommandResult<Vec<WorkOrder>> {
+    // Authenticate and build the request context
+    let context = AuthHelper::validate_request(&state.auth_manager, token)
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+
+    let result = time_command!("get_work_orders", {
+        require_resource_access!(context, "inspection", "read");
+
+        let work_orders = state.services.work_orders.get_work_orders(&filter.unwrap_or_default())
+            .context("Failed to get work orders")?;
+
+        debug!("[{}] Retrieved {} work orders", context.request_id, work_orders.len());
+        Ok(work_orders)
+    });
+
+    Ok(command_handler!("get_work_orders", &context, { result }))
+}
@@ -0,0 +1,374 @@
+//! Checklist packs
+//!
+//! A `.cranepack` file carries compliance standards with their clause
+//! catalogs, the checklist templates written against them, inspection
+//! frequency rules and the reference images the checklist items show, so a
+//! consultant can ship a curated set of checklists to a customer's
+//! installation. The file is JSON. Its contents are signed with the
+//! publisher's Ed25519 key, and a pack is only imported when the signature
+//! verifies and the key belongs to a publisher the installation trusts.
+//!
+//! Checklist items point at reference images by `reference_image_keys`
+//! inside a pack, since media IDs mean nothing on another installation.
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{InspectionType, StandardClauseInput};
+use crate::security::signing::{self, decode_hex, encode_hex};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::path::Path;
+
+/// File extension of checklist packs
+pub const PACK_EXTENSION: &str = "cranepack";
+
+/// Value of the `format` field every pack starts with
+pub const PACK_FORMAT: &str = "cranepack";
+
+/// Version of the package layout written by this build; packs of a later
+/// version are refused rather than half understood
+pub const PACK_FORMAT_VERSION: u32 = 1;
+
+/// Largest pack file read, reference images included
+pub const MAX_PACK_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Checklist item field naming reference images inside a pack
+pub const IMAGE_KEYS_FIELD: &str = "reference_image_keys";
+
+/// Who made a pack and what it is
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackManifest {
+    pub name: String,
+    pub version: String,
+    pub publisher: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A compliance standard and its clause catalog
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackStandard {
+    pub standard_code: String,
+    pub standard_name: String,
+    pub version: String,
+    pub requirements: Option<JsonValue>,
+    #[serde(default)]
+    pub clauses: Vec<StandardClauseInput>,
+}
+
+/// A checklist template of one of the pack's standards
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackTemplate {
+    pub standard_code: String,
+    pub template_name: String,
+    pub inspection_type: InspectionType,
+    pub checklist_structure: JsonValue,
+}
+
+/// How often inspections of one type are due under one of the pack's
+/// standards
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackFrequencyRule {
+    pub standard_code: String,
+    pub inspection_type: InspectionType,
+    pub interval_days: i64,
+}
+
+/// A reference image, with the file hex encoded
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackImage {
+    pub key: String,
+    pub file_name: String,
+    pub mime_type: String,
+    pub description: Option<String>,
+    pub data: String,
+}
+
+/// Everything a pack carries; the part the signature covers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackContents {
+    pub format: String,
+    pub format_version: u32,
+    pub manifest: PackManifest,
+    #[serde(default)]
+    pub standards: Vec<PackStandard>,
+    #[serde(default)]
+    pub templates: Vec<PackTemplate>,
+    #[serde(default)]
+    pub frequency_rules: Vec<PackFrequencyRule>,
+    #[serde(default)]
+    pub reference_images: Vec<PackImage>,
+}
+
+impl PackContents {
+    pub fn new(manifest: PackManifest) -> Self {
+        Self {
+            format: PACK_FORMAT.to_string(),
+            format_version: PACK_FORMAT_VERSION,
+            manifest,
+            standards: Vec::new(),
+            templates: Vec::new(),
+            frequency_rules: Vec::new(),
+            reference_images: Vec::new(),
+        }
+    }
+
+    /// The bytes the signature is made over
+    fn signed_bytes(&self) -> AppResult<Vec<u8>> {
+        serde_json::to_vec(self).map_err(|e| AppError::internal(format!("Failed to serialize checklist pack: {}", e)))
+    }
+
+    /// Refuse contents that do not hang together: unknown standards, images
+    /// that are not there, intervals that are not positive
+    pub fn validate(&self) -> AppResult<()> {
+        if self.manifest.name.trim().is_empty() {
+            return Err(AppError::validation("manifest.name", "Pack name cannot be empty"));
+        }
+        let standard_known = |code: &str| self.standards.iter().any(|standard| standard.standard_code == code);
+        for template in &self.templates {
+            if !standard_known(&template.standard_code) {
+                return Err(AppError::validation(
+                    "templates",
+                    format!("Template '{}' is for standard {}, which is not in the pack", template.template_name, template.standard_code),
+                ));
+            }
+            for key in image_keys(&template.checklist_structure) {
+                if !self.reference_images.iter().any(|image| image.key == key) {
+                    return Err(AppError::validation(
+                        "templates",
+                        format!("Template '{}' refers to image {}, which is not in the pack", template.template_name, key),
+                    ));
+                }
+            }
+        }
+        for rule in &self.frequency_rules {
+            if !standard_known(&rule.standard_code) {
+                return Err(AppError::validation(
+                    "frequency_rules",
+                    format!("Frequency rule is for standard {}, which is not in the pack", rule.standard_code),
+                ));
+            }
+            if rule.interval_days <= 0 {
+                return Err(AppError::validation("frequency_rules", "Inspection intervals must be at least one day"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Signature over a pack's contents and the key that made it, both hex
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackSignature {
+    pub public_key: String,
+    pub signature: String,
+}
+
+/// A signed checklist pack as stored in a `.cranepack` file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CranePack {
+    pub contents: PackContents,
+    pub signature: PackSignature,
+}
+
+/// Hex encoding of a reference image file
+pub fn encode_image(bytes: &[u8]) -> String {
+    encode_hex(bytes)
+}
+
+/// The file of a reference image
+pub fn decode_image(image: &PackImage) -> AppResult<Vec<u8>> {
+    decode_hex(&image.data)
+        .ok_or_else(|| AppError::validation("reference_images", format!("Image {} is not valid hex", image.key)))
+}
+
+/// The reference image keys of every item in a checklist
+pub fn image_keys(checklist: &JsonValue) -> Vec<String> {
+    let mut keys = Vec::new();
+    collect_image_keys(checklist, &mut keys);
+    keys
+}
+
+fn collect_image_keys(value: &JsonValue, keys: &mut Vec<String>) {
+    match value {
+        JsonValue::Object(map) => {
+            if let Some(JsonValue::Array(found)) = map.get(IMAGE_KEYS_FIELD) {
+                keys.extend(found.iter().filter_map(JsonValue::as_str).map(str::to_string));
+            }
+            map.values().for_each(|child| collect_image_keys(child, keys));
+        }
+        JsonValue::Array(values) => values.iter().for_each(|child| collect_image_keys(child, keys)),
+        _ => {}
+    }
+}
+
+/// Check a public key given for trust is an Ed25519 key in hex
+pub fn validate_public_key(public_key: &str) -> AppResult<String> {
+    let normalized = public_key.trim().to_lowercase();
+    match decode_hex(&normalized) {
+        Some(bytes) if bytes.len() == 32 => Ok(normalized),
+        _ => Err(AppError::validation("public_key", "Public key must be 64 hex characters")),
+    }
+}
+
+/// Sign `contents` with a PKCS#8 Ed25519 key
+pub fn sign(contents: PackContents, signing_key: &[u8]) -> AppResult<CranePack> {
+    contents.validate()?;
+    Ok(CranePack {
+        signature: PackSignature {
+            public_key: signing::public_key(signing_key)?,
+            signature: signing::sign(signing_key, &contents.signed_bytes()?)?,
+        },
+        contents,
+    })
+}
+
+/// Check a pack is one this build reads and that its contents are exactly
+/// what its key signed. Whether the key is trusted is up to the caller.
+pub fn verify(pack: &CranePack) -> AppResult<()> {
+    let contents = &pack.contents;
+    if contents.format != PACK_FORMAT {
+        return Err(AppError::InvalidFormat {
+            field: "format".to_string(),
+            expected: PACK_FORMAT.to_string(),
+            actual: contents.format.clone(),
+        });
+    }
+    if contents.format_version > PACK_FORMAT_VERSION {
+        return Err(AppError::validation(
+            "format_version",
+            format!("Pack format version {} is newer than this version of CranePro reads ({})", contents.format_version, PACK_FORMAT_VERSION),
+        ));
+    }
+
+    if !signing::verify(&pack.signature.public_key, &contents.signed_bytes()?, &pack.signature.signature) {
+        return Err(AppError::authentication("Checklist pack signature does not match its contents"));
+    }
+    contents.validate()
+}
+
+/// Read a pack file without verifying it
+pub fn read(path: &Path) -> AppResult<CranePack> {
+    let display = path.display().to_string();
+    let size = std::fs::metadata(path)
+        .map_err(|e| AppError::file_system("read", &display, e.to_string()))?
+        .len();
+    if size > MAX_PACK_BYTES {
+        return Err(AppError::validation(
+            "path",
+            format!("Checklist packs cannot exceed {} MB", MAX_PACK_BYTES / (1024 * 1024)),
+        ));
+    }
+    let bytes = std::fs::read(path).map_err(|e| AppError::file_system("read", &display, e.to_string()))?;
+    serde_json::from_slice(&bytes).map_err(|e| AppError::InvalidFormat {
+        field: "cranepack".to_string(),
+        expected: "a checklist pack".to_string(),
+        actual: e.to_string(),
+    })
+}
+
+/// Write a pack file, adding the `.cranepack` extension if `path` has none
+pub fn write(path: &Path, pack: &CranePack) -> AppResult<std::path::PathBuf> {
+    let path = match path.extension() {
+        Some(_) => path.to_path_buf(),
+        None => path.with_extension(PACK_EXTENSION),
+    };
+    let display = path.display().to_string();
+    let bytes = serde_json::to_vec_pretty(pack)
+        .map_err(|e| AppError::internal(format!("Failed to serialize checklist pack: {}", e)))?;
+    std::fs::write(&path, bytes).map_err(|e| AppError::file_system("write", &display, e.to_string()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::Ed25519KeyPair;
+
+    fn signing_key() -> Vec<u8> {
+        Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap().as_ref().to_vec()
+    }
+
+    fn contents() -> PackContents {
+        let mut contents = PackContents::new(PackManifest {
+            name: "Overhead cranes".to_string(),
+            version: "1.0".to_string(),
+            publisher: "Hoist Consulting".to_string(),
+            description: None,
+            created_at: Utc::now(),
+        });
+        contents.standards.push(PackStandard {
+            standard_code: "ASME_B30_2".to_string(),
+            standard_name: "ASME B30.2".to_string(),
+            version: "2022".to_string(),
+            requirements: None,
+            clauses: Vec::new(),
+        });
+        contents.templates.push(PackTemplate {
+            standard_code: "ASME_B30_2".to_string(),
+            template_name: "Frequent".to_string(),
+            inspection_type: InspectionType::Frequent,
+            checklist_structure: serde_json::json!({
+                "sections": [{ "items": [{ "name": "Hook", IMAGE_KEYS_FIELD: ["image-1"] }] }]
+            }),
+        });
+        contents.frequency_rules.push(PackFrequencyRule {
+            standard_code: "ASME_B30_2".to_string(),
+            inspection_type: InspectionType::Periodic,
+            interval_days: 180,
+        });
+        contents.reference_images.push(PackImage {
+            key: "image-1".to_string(),
+            file_name: "hook.jpg".to_string(),
+            mime_type: "image/jpeg".to_string(),
+            description: None,
+            data: encode_image(&[0xff, 0xd8, 0xff]),
+        });
+        contents
+    }
+
+    #[test]
+    fn test_signed_pack_survives_a_file_round_trip() {
+        let key = signing_key();
+        let pack = sign(contents(), &key).unwrap();
+        assert_eq!(pack.signature.public_key, signing::public_key(&key).unwrap());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(&dir.path().join("cranes"), &pack).unwrap();
+        assert_eq!(path.extension().unwrap(), PACK_EXTENSION);
+        let read_back = read(&path).unwrap();
+        verify(&read_back).unwrap();
+        assert_eq!(read_back, pack);
+        assert_eq!(decode_image(&read_back.contents.reference_images[0]).unwrap(), vec![0xff, 0xd8, 0xff]);
+    }
+
+    #[test]
+    fn test_tampered_or_foreign_packs_are_refused() {
+        let mut pack = sign(contents(), &signing_key()).unwrap();
+        pack.contents.frequency_rules[0].interval_days = 3650;
+        assert!(verify(&pack).is_err());
+
+        let mut resigned = sign(contents(), &signing_key()).unwrap();
+        resigned.signature.public_key = signing::public_key(&signing_key()).unwrap();
+        assert!(verify(&resigned).is_err());
+
+        let mut newer = contents();
+        newer.format_version = PACK_FORMAT_VERSION + 1;
+        assert!(verify(&sign(newer, &signing_key()).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_contents_must_be_self_contained() {
+        let mut missing_image = contents();
+        missing_image.reference_images.clear();
+        assert!(sign(missing_image, &signing_key()).is_err());
+
+        let mut unknown_standard = contents();
+        unknown_standard.frequency_rules[0].standard_code = "OSHA".to_string();
+        assert!(unknown_standard.validate().is_err());
+
+        assert!(validate_public_key(&"AB".repeat(32)).is_ok());
+        assert!(validate_public_key("abcd").is_err());
+        assert_eq!(signing::key_fingerprint(&"ab".repeat(32)).split(':').count(), 8);
+    }
+}
@@ -4,87 +4,310 @@
 //! operations using SQLite with connection pooling. It integrates with the
 //! enhanced migration system for robust database management.
 
+use crate::database::migrations::{Migration, MigrationResult, MigrationRunner, MigrationStatus};
 use crate::errors::{AppError, AppResult};
-use rusqlite::{Connection, OpenFlags};
+use crate::shutdown::ShutdownSignal;
+use crate::trace::record_statement;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OpenFlags, Transaction, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use log::{info, debug};
+use std::time::{Duration, Instant};
+use log::{info, debug, warn, error};
 
 /// Database connection pool size
 const POOL_SIZE: usize = 10;
 
+/// Read-only connection pool size, for reports and other long reads
+const READ_POOL_SIZE: usize = 4;
+
+/// How long a connection waits for a lock held by another connection
+const DEFAULT_BUSY_TIMEOUT_MS: u64 = 5000;
+
+/// Hours between scheduled maintenance runs
+const DEFAULT_MAINTENANCE_INTERVAL_HOURS: u64 = 24;
+
+/// Share of free pages above which scheduled maintenance also runs VACUUM
+const VACUUM_FREE_PAGE_RATIO: f64 = 0.2;
+
+/// Most problems reported by an integrity check
+const MAX_INTEGRITY_ERRORS: i64 = 100;
+
 /// Current database schema version
-const CURRENT_SCHEMA_VERSION: i32 = 2;
+const CURRENT_SCHEMA_VERSION: i32 = 66;
+
+/// Directory, beside the database file, holding pre-upgrade snapshots
+const UPGRADE_SNAPSHOT_DIR: &str = "upgrade-snapshots";
+
+/// File in the snapshot directory describing the latest upgrade
+const LAST_UPGRADE_FILE: &str = "last_upgrade.json";
+
+/// Pre-upgrade snapshots kept; older ones are removed after an upgrade
+const UPGRADE_SNAPSHOTS_KEPT: usize = 3;
+
+/// Tables a snapshot restore leaves as the schema rollback set them
+const RESTORE_SKIPPED_TABLES: [&str; 2] = ["schema_version", "migration_history"];
+
+/// Pragmas applied to every connection before the configured ones
+const DEFAULT_PRAGMAS: [(&str, &str); 4] = [
+    ("foreign_keys", "ON"),
+    ("synchronous", "NORMAL"),
+    ("cache_size", "-64000"), // 64MB cache
+    ("temp_store", "memory"),
+];
+
+/// Database and connection pool configuration
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    /// Database file; `None` for an in-memory database
+    pub path: Option<PathBuf>,
+    pub pool_size: usize,
+    /// Read-only connections kept for long-running reads such as reports
+    pub read_pool_size: usize,
+    /// How long a statement waits on a lock before failing with "database is locked"
+    pub busy_timeout: Duration,
+    /// Use write-ahead logging so readers do not block the writer (file databases only)
+    pub wal: bool,
+    /// Extra `PRAGMA name = value` settings applied to every connection, after the defaults
+    pub pragmas: Vec<(String, String)>,
+    /// Time between scheduled maintenance runs; `None` disables them
+    pub maintenance_interval: Option<Duration>,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            pool_size: POOL_SIZE,
+            read_pool_size: READ_POOL_SIZE,
+            busy_timeout: Duration::from_millis(DEFAULT_BUSY_TIMEOUT_MS),
+            wal: true,
+            pragmas: Vec::new(),
+            maintenance_interval: Some(Duration::from_secs(DEFAULT_MAINTENANCE_INTERVAL_HOURS * 3600)),
+        }
+    }
+}
+
+impl DatabaseConfig {
+    /// Configuration for a database file
+    pub fn file(path: PathBuf) -> Self {
+        Self { path: Some(path), ..Self::default() }
+    }
+
+    /// Configuration for an in-memory database
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Apply overrides from `CRANEPRO_DB_POOL_SIZE`, `CRANEPRO_DB_READ_POOL_SIZE`,
+    /// `CRANEPRO_DB_BUSY_TIMEOUT_MS`, `CRANEPRO_DB_WAL` (`on`/`off`) and
+    /// `CRANEPRO_DB_MAINTENANCE_HOURS` (`0` disables scheduled maintenance);
+    /// unparseable values are ignored
+    pub fn with_env_overrides(mut self) -> Self {
+        if let Some(size) = env_override::<usize>("CRANEPRO_DB_POOL_SIZE").filter(|size| *size > 0) {
+            self.pool_size = size;
+        }
+        if let Some(size) = env_override::<usize>("CRANEPRO_DB_READ_POOL_SIZE").filter(|size| *size > 0) {
+            self.read_pool_size = size;
+        }
+        if let Some(ms) = env_override::<u64>("CRANEPRO_DB_BUSY_TIMEOUT_MS") {
+            self.busy_timeout = Duration::from_millis(ms);
+        }
+        if let Some(hours) = env_override::<u64>("CRANEPRO_DB_MAINTENANCE_HOURS") {
+            self.maintenance_interval = (hours > 0).then(|| Duration::from_secs(hours * 3600));
+        }
+        if let Ok(wal) = std::env::var("CRANEPRO_DB_WAL") {
+            match wal.to_ascii_lowercase().as_str() {
+                "on" | "true" | "1" => self.wal = true,
+                "off" | "false" | "0" => self.wal = false,
+                _ => warn!("Ignoring invalid CRANEPRO_DB_WAL value: {}", wal),
+            }
+        }
+        self
+    }
+
+    fn validate(&self) -> AppResult<()> {
+        if self.pool_size == 0 {
+            return Err(AppError::InvalidConfiguration {
+                key: "pool_size".to_string(),
+                value: "0".to_string(),
+            });
+        }
+        if self.read_pool_size == 0 {
+            return Err(AppError::InvalidConfiguration {
+                key: "read_pool_size".to_string(),
+                value: "0".to_string(),
+            });
+        }
+        for (name, value) in &self.pragmas {
+            let valid_name = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            let valid_value = !value.is_empty()
+                && value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+            if !valid_name || !valid_value {
+                return Err(AppError::InvalidConfiguration {
+                    key: "pragmas".to_string(),
+                    value: format!("{} = {}", name, value),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn env_override<T: std::str::FromStr>(key: &str) -> Option<T> {
+    let value = std::env::var(key).ok()?;
+    let parsed = value.parse().ok();
+    if parsed.is_none() {
+        warn!("Ignoring invalid {} value: {}", key, value);
+    }
+    parsed
+}
+
+/// Run a pragma, discarding any rows it reports back
+fn apply_pragma(conn: &Connection, name: &str, value: &str) -> AppResult<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {} = {}", name, value))?;
+    let mut rows = stmt.query([])?;
+    while rows.next()?.is_some() {}
+    Ok(())
+}
 
 /// Database connection pool
+///
+/// Read-write connections serve transactions and short reads. A separate set
+/// of read-only connections serves long reads such as reports, so they never
+/// wait on or hold up a connection needed for a write; with WAL they also read
+/// without blocking the writer.
 pub struct DatabasePool {
     connections: Arc<Mutex<Vec<Connection>>>,
-    db_path: PathBuf,
+    read_connections: Arc<Mutex<Vec<Connection>>>,
+    config: DatabaseConfig,
+    /// URI shared by all connections of an in-memory database
+    memory_uri: Option<String>,
+    /// Keeps an in-memory database alive while pooled connections come and go
+    _memory_anchor: Option<Mutex<Connection>>,
 }
 
 impl DatabasePool {
     /// Create a new database pool
     pub async fn new(db_path: PathBuf) -> AppResult<Self> {
-        let mut connections = Vec::with_capacity(POOL_SIZE);
-        
-        // Create initial connections
-        for _ in 0..POOL_SIZE {
-            let conn = Self::create_connection(&db_path)?;
-            connections.push(conn);
-        }
-
-        Ok(DatabasePool {
-            connections: Arc::new(Mutex::new(connections)),
-            db_path,
-        })
+        Self::with_config(DatabaseConfig::file(db_path))
     }
 
     /// Create a new in-memory database pool for testing
     pub async fn new_in_memory() -> AppResult<Self> {
-        let mut connections = Vec::with_capacity(POOL_SIZE);
-        
-        // Create initial in-memory connections
-        for _ in 0..POOL_SIZE {
-            let conn = Self::create_in_memory_connection()?;
-            connections.push(conn);
+        Self::with_config(DatabaseConfig::in_memory())
+    }
+
+    /// Create a pool from a configuration. Connections of an in-memory pool
+    /// share one database through SQLite's `memdb` VFS, so they all see the
+    /// migrated schema and take the same locks as file connections.
+    pub fn with_config(config: DatabaseConfig) -> AppResult<Self> {
+        config.validate()?;
+
+        let memory_uri = match config.path {
+            Some(_) => None,
+            None => Some(format!("file:/cranepro-{}?vfs=memdb", uuid::Uuid::new_v4())),
+        };
+        let mut pool = DatabasePool {
+            connections: Arc::new(Mutex::new(Vec::with_capacity(config.pool_size))),
+            read_connections: Arc::new(Mutex::new(Vec::with_capacity(config.read_pool_size))),
+            config,
+            memory_uri,
+            _memory_anchor: None,
+        };
+        if pool.memory_uri.is_some() {
+            pool._memory_anchor = Some(Mutex::new(pool.create_connection()?));
         }
 
-        Ok(DatabasePool {
-            connections: Arc::new(Mutex::new(connections)),
-            db_path: PathBuf::from(":memory:"),
-        })
+        // Create initial connections
+        let connections = (0..pool.config.pool_size)
+            .map(|_| pool.create_connection())
+            .collect::<AppResult<Vec<_>>>()?;
+        *pool.connections.lock()
+            .map_err(|_| AppError::database("Failed to acquire connection pool lock"))? = connections;
+
+        // Read-only connections last, once a writer has created the file
+        let read_connections = (0..pool.config.read_pool_size)
+            .map(|_| pool.create_read_connection())
+            .collect::<AppResult<Vec<_>>>()?;
+        *pool.read_connections.lock()
+            .map_err(|_| AppError::database("Failed to acquire read pool lock"))? = read_connections;
+
+        debug!("Database pool ready: {} connections, {} read-only, busy timeout {:?}",
+               pool.config.pool_size, pool.config.read_pool_size, pool.config.busy_timeout);
+        Ok(pool)
+    }
+
+    /// The configuration this pool was created with
+    pub fn config(&self) -> &DatabaseConfig {
+        &self.config
     }
 
     /// Create a new database connection
-    fn create_connection(db_path: &Path) -> AppResult<Connection> {
-        let conn = Connection::open_with_flags(
-            db_path,
-            OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE,
-        )?;
+    fn create_connection(&self) -> AppResult<Connection> {
+        let mut conn = match (&self.config.path, &self.memory_uri) {
+            (Some(path), _) => Self::open_file(path)?,
+            (None, Some(uri)) => Connection::open_with_flags(
+                uri,
+                OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE | OpenFlags::SQLITE_OPEN_URI,
+            )?,
+            (None, None) => Connection::open_in_memory()?,
+        };
 
         // Configure connection
-        conn.execute("PRAGMA foreign_keys = ON", [])?;
-        conn.execute("PRAGMA journal_mode = WAL", [])?;
-        conn.execute("PRAGMA synchronous = NORMAL", [])?;
-        conn.execute("PRAGMA cache_size = -64000", [])?; // 64MB cache
-        conn.execute("PRAGMA temp_store = memory", [])?;
+        conn.busy_timeout(self.config.busy_timeout)?;
+        if self.config.wal && self.config.path.is_some() {
+            let mode: String = conn.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))?;
+            if !mode.eq_ignore_ascii_case("wal") {
+                warn!("WAL journal mode unavailable, using {}", mode);
+            }
+        }
+        self.configure_connection(&conn)?;
+        conn.profile(Some(record_statement));
 
         Ok(conn)
     }
 
-    /// Create a new in-memory database connection
-    fn create_in_memory_connection() -> AppResult<Connection> {
-        let conn = Connection::open_in_memory()?;
+    /// Create a read-only connection. File connections are opened read-only;
+    /// every read connection also sets `query_only` so in-memory ones reject
+    /// writes too.
+    fn create_read_connection(&self) -> AppResult<Connection> {
+        let mut conn = match (&self.config.path, &self.memory_uri) {
+            (Some(path), _) => Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?,
+            (None, Some(uri)) => Connection::open_with_flags(
+                uri,
+                OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_URI,
+            )?,
+            (None, None) => return self.create_connection(),
+        };
 
-        // Configure connection
-        conn.execute("PRAGMA foreign_keys = ON", [])?;
-        conn.execute("PRAGMA synchronous = NORMAL", [])?;
-        conn.execute("PRAGMA cache_size = -64000", [])?; // 64MB cache
-        conn.execute("PRAGMA temp_store = memory", [])?;
+        conn.busy_timeout(self.config.busy_timeout)?;
+        self.configure_connection(&conn)?;
+        apply_pragma(&conn, "query_only", "ON")?;
+        conn.profile(Some(record_statement));
 
         Ok(conn)
     }
 
+    fn configure_connection(&self, conn: &Connection) -> AppResult<()> {
+        for (name, value) in DEFAULT_PRAGMAS {
+            apply_pragma(conn, name, value)?;
+        }
+        for (name, value) in &self.config.pragmas {
+            apply_pragma(conn, name, value)?;
+        }
+        Ok(())
+    }
+
+    fn open_file(db_path: &Path) -> AppResult<Connection> {
+        Ok(Connection::open_with_flags(
+            db_path,
+            OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE,
+        )?)
+    }
+
     /// Get a connection from the pool
     pub fn get_connection(&self) -> AppResult<Connection> {
         let mut pool = self.connections.lock()
@@ -94,18 +317,65 @@ impl DatabasePool {
             Ok(conn)
         } else {
             // Pool exhausted, create a new connection
-            if self.db_path.to_str() == Some(":memory:") {
-                Self::create_in_memory_connection()
-            } else {
-                Self::create_connection(&self.db_path)
-            }
+            drop(pool);
+            self.create_connection()
         }
     }
 
     /// Return a connection to the pool
     pub fn return_connection(&self, conn: Connection) {
         if let Ok(mut pool) = self.connections.lock() {
-            if pool.len() < POOL_SIZE {
+            if pool.len() < self.config.pool_size {
+                pool.push(conn);
+            }
+        }
+        // If we can't return to pool, just drop the connection
+    }
+
+    /// Get a read-only connection from the read pool. It goes back to the
+    /// pool when the returned guard is dropped.
+    pub fn get_read_connection(&self) -> AppResult<ReadConnection> {
+        let mut pool = self.read_connections.lock()
+            .map_err(|_| AppError::database("Failed to acquire read pool lock"))?;
+
+        let conn = match pool.pop() {
+            Some(conn) => conn,
+            None => {
+                // Read pool exhausted, create a new read-only connection
+                drop(pool);
+                self.create_read_connection()?
+            }
+        };
+        Ok(ReadConnection {
+            conn: Some(conn),
+            pool: Arc::clone(&self.read_connections),
+            capacity: self.config.read_pool_size,
+        })
+    }
+}
+
+/// A read-only connection checked out of the read pool
+///
+/// Returns itself to the pool on drop, so an error propagated with `?`
+/// cannot leak it.
+pub struct ReadConnection {
+    conn: Option<Connection>,
+    pool: Arc<Mutex<Vec<Connection>>>,
+    capacity: usize,
+}
+
+impl Deref for ReadConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("read connection already returned")
+    }
+}
+
+impl Drop for ReadConnection {
+    fn drop(&mut self) {
+        if let (Some(conn), Ok(mut pool)) = (self.conn.take(), self.pool.lock()) {
+            if pool.len() < self.capacity {
                 pool.push(conn);
             }
         }
@@ -117,28 +387,30 @@ impl DatabasePool {
 pub struct Database {
     pool: DatabasePool,
     migrations: LegacyMigrationManager,
+    /// Runs on-demand migrations and rollbacks, tracking their progress
+    runner: Mutex<MigrationRunner>,
 }
 
 impl Database {
-    /// Initialize the database with the given path
-    pub async fn init(db_path: Option<PathBuf>) -> AppResult<Self> {
-        let db_path = db_path.unwrap_or_else(|| {
-            let mut path = std::env::current_dir().unwrap_or_default();
-            path.push("crane_pro.db");
-            path
-        });
-
-        info!("Initializing database at: {:?}", db_path);
+    /// Open the database described by `config` and bring its schema up to date
+    pub async fn new(config: DatabaseConfig) -> AppResult<Self> {
+        match &config.path {
+            Some(path) => {
+                info!("Initializing database at: {:?}", path);
 
-        // Ensure the directory exists
-        if let Some(parent) = db_path.parent() {
-            std::fs::create_dir_all(parent)?;
+                // Ensure the directory exists
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+            }
+            None => info!("Initializing in-memory database"),
         }
 
-        let pool = DatabasePool::new(db_path).await?;
+        let pool = DatabasePool::with_config(config)?;
         let migrations = LegacyMigrationManager::new();
+        let runner = Mutex::new(migrations.to_runner()?);
 
-        let db = Self { pool, migrations };
+        let db = Self { pool, migrations, runner };
 
         // Run migrations
         db.migrate().await?;
@@ -146,19 +418,20 @@ impl Database {
         Ok(db)
     }
 
-    /// Initialize an in-memory database for testing
-    pub async fn new_in_memory() -> AppResult<Self> {
-        info!("Initializing in-memory database");
-
-        let pool = DatabasePool::new_in_memory().await?;
-        let migrations = LegacyMigrationManager::new();
-
-        let db = Self { pool, migrations };
+    /// Initialize the database with the given path
+    pub async fn init(db_path: Option<PathBuf>) -> AppResult<Self> {
+        let db_path = db_path.unwrap_or_else(|| {
+            let mut path = std::env::current_dir().unwrap_or_default();
+            path.push("crane_pro.db");
+            path
+        });
 
-        // Run migrations
-        db.migrate().await?;
+        Self::new(DatabaseConfig::file(db_path)).await
+    }
 
-        Ok(db)
+    /// Initialize an in-memory database for testing
+    pub async fn new_in_memory() -> AppResult<Self> {
+        Self::new(DatabaseConfig::in_memory()).await
     }
 
     /// Execute a transaction
@@ -168,7 +441,10 @@ impl Database {
     {
         let conn = self.pool.get_connection()?;
         
-        let transaction = conn.unchecked_transaction()?;
+        // Take the write lock up front: a deferred transaction that later
+        // upgrades from read to write fails immediately instead of waiting
+        // out the busy timeout when another connection is writing
+        let transaction = Transaction::new_unchecked(&conn, TransactionBehavior::Immediate)?;
         
         match f(&conn) {
             Ok(result) => {
@@ -189,11 +465,126 @@ impl Database {
         self.pool.get_connection()
     }
 
+    /// The configuration the database was opened with
+    pub fn config(&self) -> &DatabaseConfig {
+        self.pool.config()
+    }
+
     /// Return a connection to the pool
     pub fn return_connection(&self, conn: Connection) {
         self.pool.return_connection(conn);
     }
 
+    /// Get a read-only connection for long-running reads such as reports;
+    /// it returns to the read pool when dropped
+    pub fn get_read_connection(&self) -> AppResult<ReadConnection> {
+        self.pool.get_read_connection()
+    }
+
+    /// Checkpoint the write-ahead log into the main database file and
+    /// truncate it, so a clean exit leaves no pending WAL frames
+    pub fn checkpoint(&self) -> AppResult<()> {
+        let conn = self.pool.get_connection()?;
+        let (busy, log_frames, checkpointed) = conn.query_row(
+            "PRAGMA wal_checkpoint(TRUNCATE)",
+            [],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?)),
+        )?;
+        self.pool.return_connection(conn);
+
+        if busy != 0 {
+            return Err(AppError::database("WAL checkpoint blocked by an open reader or writer"));
+        }
+        info!("WAL checkpoint completed: {} of {} frames written", checkpointed.max(0), log_frames.max(0));
+        Ok(())
+    }
+
+    /// Schema version, known migrations and the progress of the latest
+    /// migration run or rollback
+    pub fn migration_status(&self) -> AppResult<MigrationStatus> {
+        let conn = self.pool.get_connection()?;
+
+        let result = (|| -> AppResult<MigrationStatus> {
+            let runner = self.lock_runner()?;
+            let current_version = self.get_schema_version(&conn)?;
+            runner.sync_history(&conn, current_version)?;
+
+            Ok(MigrationStatus {
+                current_version,
+                latest_version: CURRENT_SCHEMA_VERSION,
+                migrations: runner.get_migration_infos(&conn)?,
+                progress: runner.get_progress()?,
+                results: runner.get_results()?,
+            })
+        })();
+
+        self.pool.return_connection(conn);
+        result
+    }
+
+    /// Apply migrations newer than the database's schema version, stopping
+    /// at the first failure
+    pub fn run_pending_migrations(&self) -> AppResult<Vec<MigrationResult>> {
+        let conn = self.pool.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<MigrationResult>> {
+            let mut runner = self.lock_runner()?;
+            let current_version = self.get_schema_version(&conn)?;
+            runner.sync_history(&conn, current_version)?;
+
+            let results = runner.run_migrations(&conn, current_version, CURRENT_SCHEMA_VERSION)?;
+            self.set_schema_version_from_history(&conn)?;
+            Ok(results)
+        })();
+
+        self.pool.return_connection(conn);
+        result
+    }
+
+    /// Roll back applied migrations above `version`, newest first, stopping
+    /// at the first failure. The initial schema cannot be rolled back. Note
+    /// that startup migrates an older schema forward again.
+    pub fn rollback_to_version(&self, version: i32) -> AppResult<Vec<MigrationResult>> {
+        if version < 1 {
+            return Err(AppError::validation("version", "The initial schema cannot be rolled back"));
+        }
+        let conn = self.pool.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<MigrationResult>> {
+            let mut runner = self.lock_runner()?;
+            let current_version = self.get_schema_version(&conn)?;
+            if version >= current_version {
+                return Err(AppError::validation(
+                    "version",
+                    format!("Target version must be below the current version {}", current_version),
+                ));
+            }
+            runner.sync_history(&conn, current_version)?;
+
+            let results = runner.rollback_migrations(&conn, current_version, version)?;
+            self.set_schema_version_from_history(&conn)?;
+            Ok(results)
+        })();
+
+        self.pool.return_connection(conn);
+        result
+    }
+
+    fn lock_runner(&self) -> AppResult<std::sync::MutexGuard<'_, MigrationRunner>> {
+        self.runner.lock()
+            .map_err(|_| AppError::internal("Failed to acquire migration runner lock"))
+    }
+
+    /// Set the schema version to the newest migration in the history
+    fn set_schema_version_from_history(&self, conn: &Connection) -> AppResult<()> {
+        let version: i32 = conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM migration_history",
+            [],
+            |row| row.get(0),
+        )?;
+        self.set_schema_version(conn, version)
+    }
+
     /// Run database migrations
     async fn migrate(&self) -> AppResult<()> {
         info!("Running database migrations");
@@ -205,6 +596,10 @@ impl Database {
         info!("Current schema version: {}", current_version);
         
         if current_version < CURRENT_SCHEMA_VERSION {
+            // A new database has nothing worth keeping
+            if current_version > 0 {
+                self.snapshot_before_upgrade(&conn, current_version)?;
+            }
             info!("Migrating from version {} to {}", current_version, CURRENT_SCHEMA_VERSION);
             self.migrations.run_migrations(&conn, current_version, CURRENT_SCHEMA_VERSION)?;
             self.set_schema_version(&conn, CURRENT_SCHEMA_VERSION)?;
@@ -247,6 +642,397 @@ impl Database {
     }
 }
 
+/// Database maintenance operation
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MaintenanceTask {
+    /// Refresh the query planner statistics
+    Analyze,
+    /// Verify the database file structure
+    IntegrityCheck,
+    /// Rebuild the database file, reclaiming free pages
+    Vacuum,
+}
+
+impl MaintenanceTask {
+    pub const ALL: [MaintenanceTask; 3] = [
+        MaintenanceTask::Analyze,
+        MaintenanceTask::IntegrityCheck,
+        MaintenanceTask::Vacuum,
+    ];
+}
+
+impl std::fmt::Display for MaintenanceTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaintenanceTask::Analyze => write!(f, "Analyze"),
+            MaintenanceTask::IntegrityCheck => write!(f, "IntegrityCheck"),
+            MaintenanceTask::Vacuum => write!(f, "Vacuum"),
+        }
+    }
+}
+
+/// Outcome of one maintenance task, as recorded in `db_maintenance_log`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceRun {
+    pub id: i64,
+    pub task: MaintenanceTask,
+    /// Whether the run was scheduled rather than requested by a user
+    pub scheduled: bool,
+    pub user_id: Option<i64>,
+    pub success: bool,
+    pub details: String,
+    pub duration_ms: i64,
+    pub started_at: DateTime<Utc>,
+}
+
+impl Database {
+    /// Run maintenance tasks one after another, recording each outcome. A
+    /// failed task is recorded and does not stop the ones after it. Runs
+    /// without a `user_id` are recorded as scheduled.
+    pub fn run_maintenance(&self, tasks: &[MaintenanceTask], user_id: Option<i64>) -> AppResult<Vec<MaintenanceRun>> {
+        let conn = self.pool.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<MaintenanceRun>> {
+            let mut runs = Vec::with_capacity(tasks.len());
+            for &task in tasks {
+                info!("Running database maintenance: {}", task);
+                let started_at = Utc::now();
+                let timer = Instant::now();
+                let (success, details) = match Self::run_maintenance_task(&conn, task) {
+                    Ok(outcome) => outcome,
+                    Err(e) => (false, e.to_string()),
+                };
+                let duration_ms = timer.elapsed().as_millis() as i64;
+                if success {
+                    info!("Database maintenance {} completed in {} ms: {}", task, duration_ms, details);
+                } else {
+                    error!("Database maintenance {} failed: {}", task, details);
+                }
+
+                conn.execute(
+                    "INSERT INTO db_maintenance_log (task, scheduled, user_id, success, details, duration_ms, started_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![task.to_string(), user_id.is_none(), user_id, success, details, duration_ms, started_at],
+                )?;
+                runs.push(MaintenanceRun {
+                    id: conn.last_insert_rowid(),
+                    task,
+                    scheduled: user_id.is_none(),
+                    user_id,
+                    success,
+                    details,
+                    duration_ms,
+                    started_at,
+                });
+            }
+            Ok(runs)
+        })();
+
+        self.pool.return_connection(conn);
+        result
+    }
+
+    /// Run one task, returning whether it succeeded and what it found
+    fn run_maintenance_task(conn: &Connection, task: MaintenanceTask) -> AppResult<(bool, String)> {
+        match task {
+            MaintenanceTask::Analyze => {
+                conn.execute_batch("ANALYZE")?;
+                Ok((true, "Statistics updated".to_string()))
+            }
+            MaintenanceTask::IntegrityCheck => {
+                let mut stmt = conn.prepare(&format!("PRAGMA integrity_check({})", MAX_INTEGRITY_ERRORS))?;
+                let problems = stmt
+                    .query_map([], |row| row.get::<_, String>(0))?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                if problems.len() == 1 && problems[0] == "ok" {
+                    Ok((true, "ok".to_string()))
+                } else {
+                    Ok((false, problems.join("; ")))
+                }
+            }
+            MaintenanceTask::Vacuum => {
+                let before = Self::free_pages(conn)?;
+                conn.execute_batch("VACUUM")?;
+                Ok((true, format!("Reclaimed {} free pages", before.0)))
+            }
+        }
+    }
+
+    /// Free and total page counts
+    fn free_pages(conn: &Connection) -> AppResult<(i64, i64)> {
+        let free: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+        let total: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        Ok((free, total))
+    }
+
+    /// Tasks for a scheduled run: statistics and an integrity check, plus a
+    /// VACUUM once enough of the file is free pages
+    pub fn scheduled_maintenance_tasks(&self) -> AppResult<Vec<MaintenanceTask>> {
+        let conn = self.pool.get_connection()?;
+        let pages = Self::free_pages(&conn);
+        self.pool.return_connection(conn);
+
+        let (free, total) = pages?;
+        let mut tasks = vec![MaintenanceTask::Analyze, MaintenanceTask::IntegrityCheck];
+        if total > 0 && free as f64 / total as f64 > VACUUM_FREE_PAGE_RATIO {
+            tasks.push(MaintenanceTask::Vacuum);
+        }
+        Ok(tasks)
+    }
+
+    /// Most recent maintenance runs, newest first
+    pub fn maintenance_history(&self, limit: usize) -> AppResult<Vec<MaintenanceRun>> {
+        let conn = self.pool.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<MaintenanceRun>> {
+            let mut stmt = conn.prepare(
+                "SELECT id, task, scheduled, user_id, success, details, duration_ms, started_at
+                 FROM db_maintenance_log ORDER BY started_at DESC, id DESC LIMIT ?1"
+            )?;
+            let runs = stmt
+                .query_map(params![limit as i64], |row| {
+                    let task: String = row.get(1)?;
+                    Ok(MaintenanceRun {
+                        id: row.get(0)?,
+                        task: MaintenanceTask::ALL.into_iter()
+                            .find(|t| t.to_string() == task)
+                            .unwrap_or(MaintenanceTask::Analyze),
+                        scheduled: row.get(2)?,
+                        user_id: row.get(3)?,
+                        success: row.get(4)?,
+                        details: row.get(5)?,
+                        duration_ms: row.get(6)?,
+                        started_at: row.get(7)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(runs)
+        })();
+
+        self.pool.return_connection(conn);
+        result
+    }
+}
+
+/// Copy of the database taken before startup migrated it to a newer schema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpgradeSnapshot {
+    pub snapshot_path: String,
+    /// Version of the app that took the snapshot and ran the migrations
+    pub app_version: String,
+    pub from_schema_version: i32,
+    pub to_schema_version: i32,
+    pub created_at: DateTime<Utc>,
+    /// Set once the snapshot has been restored
+    pub restored_at: Option<DateTime<Utc>>,
+}
+
+impl Database {
+    /// Directory pre-upgrade snapshots are written to; `None` for an
+    /// in-memory database
+    fn upgrade_snapshot_dir(&self) -> Option<PathBuf> {
+        let path = self.config().path.as_ref()?;
+        Some(path.parent().unwrap_or_else(|| Path::new(".")).join(UPGRADE_SNAPSHOT_DIR))
+    }
+
+    /// Copy the database with `VACUUM INTO` and record the upgrade about to
+    /// run. A snapshot that cannot be written stops the upgrade, so data is
+    /// never migrated without a way back.
+    fn snapshot_before_upgrade(&self, conn: &Connection, from_version: i32) -> AppResult<Option<UpgradeSnapshot>> {
+        let Some(dir) = self.upgrade_snapshot_dir() else {
+            return Ok(None);
+        };
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| AppError::file_system("create", dir.display().to_string(), e.to_string()))?;
+
+        let created_at = Utc::now();
+        let stem = self.config().path.as_ref()
+            .and_then(|path| path.file_stem())
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "database".to_string());
+        let file = dir.join(format!("{}-v{}-{}.db", stem, from_version, created_at.format("%Y%m%d_%H%M%S")));
+        let snapshot_path = file.display().to_string();
+        info!("Snapshotting database to {} before upgrading from schema version {}", snapshot_path, from_version);
+        conn.execute("VACUUM INTO ?1", params![snapshot_path])
+            .map_err(|e| AppError::database(format!("Failed to snapshot database before upgrade: {}", e)))?;
+
+        let snapshot = UpgradeSnapshot {
+            snapshot_path,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            from_schema_version: from_version,
+            to_schema_version: CURRENT_SCHEMA_VERSION,
+            created_at,
+            restored_at: None,
+        };
+        Self::write_upgrade_record(&dir, &snapshot)?;
+        Self::prune_upgrade_snapshots(&dir, &file);
+        Ok(Some(snapshot))
+    }
+
+    fn write_upgrade_record(dir: &Path, snapshot: &UpgradeSnapshot) -> AppResult<()> {
+        let file = dir.join(LAST_UPGRADE_FILE);
+        std::fs::write(&file, serde_json::to_vec_pretty(snapshot)?)
+            .map_err(|e| AppError::file_system("write", file.display().to_string(), e.to_string()))
+    }
+
+    /// Remove all but the newest snapshots, always keeping `current`
+    fn prune_upgrade_snapshots(dir: &Path, current: &Path) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        let mut snapshots: Vec<(std::time::SystemTime, PathBuf)> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "db") && path != current)
+            .filter_map(|path| Some((path.metadata().ok()?.modified().ok()?, path)))
+            .collect();
+        snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.0));
+        for (_, path) in snapshots.into_iter().skip(UPGRADE_SNAPSHOTS_KEPT.saturating_sub(1)) {
+            match std::fs::remove_file(&path) {
+                Ok(()) => debug!("Removed old upgrade snapshot {}", path.display()),
+                Err(e) => warn!("Failed to remove old upgrade snapshot {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    /// The snapshot taken before the latest upgrade, if any
+    pub fn last_upgrade_snapshot(&self) -> AppResult<Option<UpgradeSnapshot>> {
+        let Some(dir) = self.upgrade_snapshot_dir() else {
+            return Ok(None);
+        };
+        let file = dir.join(LAST_UPGRADE_FILE);
+        match std::fs::read(&file) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(AppError::file_system("read", file.display().to_string(), e.to_string())),
+        }
+    }
+
+    /// Undo the latest upgrade: roll the schema back to the version it had
+    /// before, then replace the data of every table with the snapshot's.
+    /// Changes made since the upgrade are lost. Startup migrates the schema
+    /// forward again, so the previous app version should be reinstalled
+    /// before the next launch. In-memory databases take no snapshots, so
+    /// they cannot be rolled back.
+    pub fn rollback_last_upgrade(&self) -> AppResult<UpgradeSnapshot> {
+        if self.upgrade_snapshot_dir().is_none() {
+            return Err(AppError::validation("upgrade", "Upgrade rollback is not available for in-memory databases"));
+        }
+        let mut snapshot = self.last_upgrade_snapshot()?
+            .ok_or_else(|| AppError::validation("upgrade", "No upgrade snapshot has been taken"))?;
+        if snapshot.restored_at.is_some() {
+            return Err(AppError::validation("upgrade", "The latest upgrade has already been rolled back"));
+        }
+        if !Path::new(&snapshot.snapshot_path).is_file() {
+            return Err(AppError::file_system("read", snapshot.snapshot_path.clone(), "Snapshot file is missing"));
+        }
+
+        let current_version = self.migration_status()?.current_version;
+        if current_version > snapshot.from_schema_version {
+            let results = self.rollback_to_version(snapshot.from_schema_version)?;
+            if let Some(failed) = results.iter().find(|r| !r.success) {
+                return Err(AppError::DatabaseMigration {
+                    version: format!("{} could not be rolled back: {}", failed.version,
+                                     failed.error_message.as_deref().unwrap_or("unknown error")),
+                });
+            }
+        }
+
+        let conn = self.pool.get_connection()?;
+        let result = Self::restore_snapshot_data(&conn, &snapshot.snapshot_path);
+        self.pool.return_connection(conn);
+        let tables = result?;
+
+        snapshot.restored_at = Some(Utc::now());
+        if let Some(dir) = self.upgrade_snapshot_dir() {
+            Self::write_upgrade_record(&dir, &snapshot)?;
+        }
+        warn!("Restored {} tables from upgrade snapshot {} (schema version {})",
+              tables, snapshot.snapshot_path, snapshot.from_schema_version);
+        Ok(snapshot)
+    }
+
+    /// Replace the rows of every ordinary table that the snapshot also has,
+    /// in one transaction with foreign keys off. Full-text indexes are kept
+    /// in step by their triggers, so virtual tables and their shadow tables
+    /// are left alone. Returns the number of tables restored.
+    fn restore_snapshot_data(conn: &Connection, snapshot_path: &str) -> AppResult<usize> {
+        conn.execute("ATTACH DATABASE ?1 AS upgrade_snapshot", params![snapshot_path])?;
+        conn.execute_batch("PRAGMA foreign_keys = OFF")?;
+
+        let result = (|| -> AppResult<usize> {
+            let virtual_tables: Vec<String> = conn
+                .prepare("SELECT name FROM main.sqlite_master WHERE type = 'table' AND sql LIKE 'CREATE VIRTUAL TABLE%'")?
+                .query_map([], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            let tables: Vec<String> = conn
+                .prepare("SELECT s.name FROM upgrade_snapshot.sqlite_master s
+                          JOIN main.sqlite_master m ON m.name = s.name AND m.type = 'table'
+                          WHERE s.type = 'table' AND s.name NOT LIKE 'sqlite_%'
+                          ORDER BY s.name")?
+                .query_map([], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            let tables: Vec<String> = tables.into_iter()
+                .filter(|name| !RESTORE_SKIPPED_TABLES.contains(&name.as_str()))
+                .filter(|name| !virtual_tables.iter().any(|vt| name == vt || name.starts_with(&format!("{}_", vt))))
+                .collect();
+
+            let table_columns = |schema: &str, table: &str| -> AppResult<Vec<String>> {
+                let columns = conn
+                    .prepare(&format!("SELECT name FROM pragma_table_info(?1, '{}')", schema))?
+                    .query_map(params![table], |row| row.get(0))?
+                    .collect::<rusqlite::Result<Vec<String>>>()?;
+                Ok(columns)
+            };
+
+            let transaction = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+            for table in &tables {
+                let main_columns = table_columns("main", table)?;
+                let columns = table_columns("upgrade_snapshot", table)?
+                    .into_iter()
+                    .filter(|column| main_columns.contains(column))
+                    .map(|column| format!("\"{}\"", column))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                conn.execute(&format!("DELETE FROM main.\"{}\"", table), [])?;
+                conn.execute(&format!(
+                    "INSERT INTO main.\"{table}\" ({columns}) SELECT {columns} FROM upgrade_snapshot.\"{table}\""
+                ), [])?;
+                debug!("Restored table {} from upgrade snapshot", table);
+            }
+            transaction.commit()?;
+            Ok(tables.len())
+        })();
+
+        let _ = conn.execute_batch("PRAGMA foreign_keys = ON");
+        let _ = conn.execute_batch("DETACH DATABASE upgrade_snapshot");
+        result
+    }
+}
+
+/// Background task running maintenance every `interval` until shutdown
+pub async fn run_scheduled_maintenance(database: Arc<Database>, interval: Duration, mut shutdown: ShutdownSignal) {
+    info!("Scheduled database maintenance every {:?}", interval);
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = shutdown.wait() => break,
+        }
+
+        let db = database.clone();
+        let outcome = tokio::task::spawn_blocking(move || {
+            let tasks = db.scheduled_maintenance_tasks()?;
+            db.run_maintenance(&tasks, None)
+        }).await;
+        match outcome {
+            Ok(Ok(runs)) => debug!("Scheduled maintenance ran {} tasks", runs.len()),
+            Ok(Err(e)) => error!("Scheduled database maintenance failed: {}", e),
+            Err(e) => error!("Scheduled database maintenance task panicked: {}", e),
+        }
+    }
+    debug!("Scheduled database maintenance stopped");
+}
+
 /// Legacy Migration manager for database schema changes (for backward compatibility)
 pub struct LegacyMigrationManager {
     migrations: Vec<LegacyMigration>,
@@ -272,10 +1058,531 @@ impl LegacyMigrationManager {
             down_sql: LOCATION_HIERARCHY_ROLLBACK.to_string(),
         });
 
-        LegacyMigrationManager { migrations }
-    }
+        // Add per-user locale preference
+        migrations.push(LegacyMigration {
+            version: 3,
+            description: "User locale preference".to_string(),
+            up_sql: USER_LOCALE_MIGRATION.to_string(),
+            down_sql: USER_LOCALE_ROLLBACK.to_string(),
+        });
 
-    /// Run migrations from current version to target version
+        // Add location time zones and time zone info on scheduled records
+        migrations.push(LegacyMigration {
+            version: 4,
+            description: "Time zone aware scheduling".to_string(),
+            up_sql: TIME_ZONE_MIGRATION.to_string(),
+            down_sql: TIME_ZONE_ROLLBACK.to_string(),
+        });
+
+        // Add idempotency keys for retried create commands
+        migrations.push(LegacyMigration {
+            version: 5,
+            description: "Idempotency keys".to_string(),
+            up_sql: IDEMPOTENCY_KEYS_MIGRATION.to_string(),
+            down_sql: IDEMPOTENCY_KEYS_ROLLBACK.to_string(),
+        });
+
+        // Add coordinate index for map queries
+        migrations.push(LegacyMigration {
+            version: 6,
+            description: "Location coordinate index".to_string(),
+            up_sql: LOCATION_COORDINATES_MIGRATION.to_string(),
+            down_sql: LOCATION_COORDINATES_ROLLBACK.to_string(),
+        });
+
+        // Add user absences for availability-aware assignment
+        migrations.push(LegacyMigration {
+            version: 7,
+            description: "User absences".to_string(),
+            up_sql: USER_ABSENCES_MIGRATION.to_string(),
+            down_sql: USER_ABSENCES_ROLLBACK.to_string(),
+        });
+
+        // Add teams, team membership and team location coverage
+        migrations.push(LegacyMigration {
+            version: 8,
+            description: "Teams and crews".to_string(),
+            up_sql: TEAMS_MIGRATION.to_string(),
+            down_sql: TEAMS_ROLLBACK.to_string(),
+        });
+
+        // Add record-level comments with edit history and mentions
+        migrations.push(LegacyMigration {
+            version: 9,
+            description: "Comments and mentions".to_string(),
+            up_sql: COMMENTS_MIGRATION.to_string(),
+            down_sql: COMMENTS_ROLLBACK.to_string(),
+        });
+
+        // Add watched assets and inspections
+        migrations.push(LegacyMigration {
+            version: 10,
+            description: "Watchlist subscriptions".to_string(),
+            up_sql: WATCHES_MIGRATION.to_string(),
+            down_sql: WATCHES_ROLLBACK.to_string(),
+        });
+
+        // Keep deleted records restorable for a retention window
+        migrations.push(LegacyMigration {
+            version: 11,
+            description: "Recycle bin".to_string(),
+            up_sql: RECYCLE_BIN_MIGRATION.to_string(),
+            down_sql: RECYCLE_BIN_ROLLBACK.to_string(),
+        });
+
+        // Add free-form tags on assets and inspections
+        migrations.push(LegacyMigration {
+            version: 12,
+            description: "Record tags".to_string(),
+            up_sql: RECORD_TAGS_MIGRATION.to_string(),
+            down_sql: RECORD_TAGS_ROLLBACK.to_string(),
+        });
+
+        // Add the denormalized asset card read view
+        migrations.push(LegacyMigration {
+            version: 13,
+            description: "Asset card view".to_string(),
+            up_sql: ASSET_CARDS_VIEW_MIGRATION.to_string(),
+            down_sql: ASSET_CARDS_VIEW_ROLLBACK.to_string(),
+        });
+
+        // Add full-text search indexes
+        migrations.push(LegacyMigration {
+            version: 14,
+            description: "Full-text search".to_string(),
+            up_sql: FULL_TEXT_SEARCH_MIGRATION.to_string(),
+            down_sql: FULL_TEXT_SEARCH_ROLLBACK.to_string(),
+        });
+
+        // Add operator pre-start checks
+        migrations.push(LegacyMigration {
+            version: 15,
+            description: "Pre-start checks".to_string(),
+            up_sql: PRESTART_CHECKS_MIGRATION.to_string(),
+            down_sql: PRESTART_CHECKS_ROLLBACK.to_string(),
+        });
+
+        // Add the database maintenance log
+        migrations.push(LegacyMigration {
+            version: 16,
+            description: "Database maintenance log".to_string(),
+            up_sql: DB_MAINTENANCE_LOG_MIGRATION.to_string(),
+            down_sql: DB_MAINTENANCE_LOG_ROLLBACK.to_string(),
+        });
+
+        // Add soft delete to assets, inspections and users
+        migrations.push(LegacyMigration {
+            version: 17,
+            description: "Soft delete".to_string(),
+            up_sql: SOFT_DELETE_MIGRATION.to_string(),
+            down_sql: format!("{}{}", SOFT_DELETE_ROLLBACK, ASSET_CARDS_VIEW_MIGRATION),
+        });
+
+        // Add the entity change history
+        migrations.push(LegacyMigration {
+            version: 18,
+            description: "Entity history".to_string(),
+            up_sql: ENTITY_HISTORY_MIGRATION.to_string(),
+            down_sql: ENTITY_HISTORY_ROLLBACK.to_string(),
+        });
+
+        // Add insurance and registration records for assets
+        migrations.push(LegacyMigration {
+            version: 19,
+            description: "Asset records".to_string(),
+            up_sql: ASSET_RECORDS_MIGRATION.to_string(),
+            down_sql: ASSET_RECORDS_ROLLBACK.to_string(),
+        });
+
+        // Add operator authorizations by asset type and capacity
+        migrations.push(LegacyMigration {
+            version: 20,
+            description: "Operator authorizations".to_string(),
+            up_sql: OPERATOR_AUTHORIZATIONS_MIGRATION.to_string(),
+            down_sql: OPERATOR_AUTHORIZATIONS_ROLLBACK.to_string(),
+        });
+
+        // Add training records with competence expiry
+        migrations.push(LegacyMigration {
+            version: 21,
+            description: "Training records".to_string(),
+            up_sql: TRAINING_RECORDS_MIGRATION.to_string(),
+            down_sql: TRAINING_RECORDS_ROLLBACK.to_string(),
+        });
+
+        // Add work orders raised from inspection findings
+        migrations.push(LegacyMigration {
+            version: 22,
+            description: "Work orders".to_string(),
+            up_sql: WORK_ORDERS_MIGRATION.to_string(),
+            down_sql: WORK_ORDERS_ROLLBACK.to_string(),
+        });
+
+        // Add notifications and per-user channel preferences
+        migrations.push(LegacyMigration {
+            version: 23,
+            description: "Notifications".to_string(),
+            up_sql: NOTIFICATIONS_MIGRATION.to_string(),
+            down_sql: NOTIFICATIONS_ROLLBACK.to_string(),
+        });
+
+        // Add the clause catalog per standard and clause references on findings
+        migrations.push(LegacyMigration {
+            version: 24,
+            description: "Standard clauses".to_string(),
+            up_sql: STANDARD_CLAUSES_MIGRATION.to_string(),
+            down_sql: STANDARD_CLAUSES_ROLLBACK.to_string(),
+        });
+
+        // Add the risk matrix, asset criticality and risk ratings on findings
+        migrations.push(LegacyMigration {
+            version: 25,
+            description: "Risk matrix".to_string(),
+            up_sql: RISK_MATRIX_MIGRATION.to_string(),
+            down_sql: RISK_MATRIX_ROLLBACK.to_string(),
+        });
+
+        // Add SLA targets and clocks for critical findings
+        migrations.push(LegacyMigration {
+            version: 26,
+            description: "Finding SLAs".to_string(),
+            up_sql: FINDING_SLAS_MIGRATION.to_string(),
+            down_sql: FINDING_SLAS_ROLLBACK.to_string(),
+        });
+
+        // Persist audit entries instead of keeping them only in the log files
+        migrations.push(LegacyMigration {
+            version: 27,
+            description: "Audit log".to_string(),
+            up_sql: AUDIT_LOG_MIGRATION.to_string(),
+            down_sql: AUDIT_LOG_ROLLBACK.to_string(),
+        });
+
+        // Add runtime application settings
+        migrations.push(LegacyMigration {
+            version: 28,
+            description: "Application settings".to_string(),
+            up_sql: APP_SETTINGS_MIGRATION.to_string(),
+            down_sql: APP_SETTINGS_ROLLBACK.to_string(),
+        });
+
+        // Add component templates applied to new assets
+        migrations.push(LegacyMigration {
+            version: 29,
+            description: "Component templates".to_string(),
+            up_sql: COMPONENT_TEMPLATES_MIGRATION.to_string(),
+            down_sql: COMPONENT_TEMPLATES_ROLLBACK.to_string(),
+        });
+
+        // Add the spare parts inventory
+        migrations.push(LegacyMigration {
+            version: 30,
+            description: "Spare parts inventory".to_string(),
+            up_sql: PARTS_MIGRATION.to_string(),
+            down_sql: PARTS_ROLLBACK.to_string(),
+        });
+
+        // Track defects across inspections
+        migrations.push(LegacyMigration {
+            version: 31,
+            description: "Defect lifecycle".to_string(),
+            up_sql: DEFECTS_MIGRATION.to_string(),
+            down_sql: DEFECTS_ROLLBACK.to_string(),
+        });
+
+        // Add JSON schemas for free-form JSON fields
+        migrations.push(LegacyMigration {
+            version: 32,
+            description: "JSON field schemas".to_string(),
+            up_sql: JSON_SCHEMAS_MIGRATION.to_string(),
+            down_sql: JSON_SCHEMAS_ROLLBACK.to_string(),
+        });
+
+        // Add corrective actions with evidence and verification
+        migrations.push(LegacyMigration {
+            version: 33,
+            description: "Corrective actions".to_string(),
+            up_sql: CORRECTIVE_ACTIONS_MIGRATION.to_string(),
+            down_sql: CORRECTIVE_ACTIONS_ROLLBACK.to_string(),
+        });
+
+        // Add validation rules for inspection items
+        migrations.push(LegacyMigration {
+            version: 34,
+            description: "Inspection validation rules".to_string(),
+            up_sql: VALIDATION_RULES_MIGRATION.to_string(),
+            down_sql: VALIDATION_RULES_ROLLBACK.to_string(),
+        });
+
+        // Add read-only share links for generated reports
+        migrations.push(LegacyMigration {
+            version: 35,
+            description: "Report share links".to_string(),
+            up_sql: REPORT_SHARE_LINKS_MIGRATION.to_string(),
+            down_sql: REPORT_SHARE_LINKS_ROLLBACK.to_string(),
+        });
+
+        // Add documents received by email
+        migrations.push(LegacyMigration {
+            version: 36,
+            description: "Vendor documents".to_string(),
+            up_sql: VENDOR_DOCUMENTS_MIGRATION.to_string(),
+            down_sql: VENDOR_DOCUMENTS_ROLLBACK.to_string(),
+        });
+
+        // Add TOTP second factors and per-role enforcement
+        migrations.push(LegacyMigration {
+            version: 37,
+            description: "Multi-factor authentication".to_string(),
+            up_sql: MFA_MIGRATION.to_string(),
+            down_sql: MFA_ROLLBACK.to_string(),
+        });
+
+        // Add per-user preferences that follow the user across devices
+        migrations.push(LegacyMigration {
+            version: 38,
+            description: "User preferences".to_string(),
+            up_sql: USER_PREFERENCES_MIGRATION.to_string(),
+            down_sql: USER_PREFERENCES_ROLLBACK.to_string(),
+        });
+
+        // Add shared kiosk terminals and badge/PIN sign-in
+        migrations.push(LegacyMigration {
+            version: 39,
+            description: "Kiosk terminals".to_string(),
+            up_sql: KIOSK_MIGRATION.to_string(),
+            down_sql: KIOSK_ROLLBACK.to_string(),
+        });
+
+        // Add single-use tokens for resetting forgotten passwords
+        migrations.push(LegacyMigration {
+            version: 40,
+            description: "Password reset tokens".to_string(),
+            up_sql: PASSWORD_RESET_MIGRATION.to_string(),
+            down_sql: PASSWORD_RESET_ROLLBACK.to_string(),
+        });
+
+        // Add trusted field devices unlocked with a PIN or badge
+        migrations.push(LegacyMigration {
+            version: 41,
+            description: "Trusted devices".to_string(),
+            up_sql: TRUSTED_DEVICES_MIGRATION.to_string(),
+            down_sql: TRUSTED_DEVICES_ROLLBACK.to_string(),
+        });
+
+        // Record whether each account signs in locally or via LDAP
+        migrations.push(LegacyMigration {
+            version: 42,
+            description: "User authentication source".to_string(),
+            up_sql: USER_AUTH_SOURCE_MIGRATION.to_string(),
+            down_sql: USER_AUTH_SOURCE_ROLLBACK.to_string(),
+        });
+
+        // Record inspections carried out on paper and typed in afterwards
+        migrations.push(LegacyMigration {
+            version: 43,
+            description: "Paper checklist transcription".to_string(),
+            up_sql: PAPER_TRANSCRIPTION_MIGRATION.to_string(),
+            down_sql: PAPER_TRANSCRIPTION_ROLLBACK.to_string(),
+        });
+
+        // Link single sign-on accounts to the identity provider's subject
+        migrations.push(LegacyMigration {
+            version: 44,
+            description: "User external subject".to_string(),
+            up_sql: USER_EXTERNAL_SUBJECT_MIGRATION.to_string(),
+            down_sql: USER_EXTERNAL_SUBJECT_ROLLBACK.to_string(),
+        });
+
+        // Exempt assets and inspections from deletion during litigation
+        migrations.push(LegacyMigration {
+            version: 45,
+            description: "Legal holds".to_string(),
+            up_sql: LEGAL_HOLDS_MIGRATION.to_string(),
+            down_sql: LEGAL_HOLDS_ROLLBACK.to_string(),
+        });
+
+        // Keep sessions in the database so they can be listed and revoked
+        migrations.push(LegacyMigration {
+            version: 46,
+            description: "Server-side sessions".to_string(),
+            up_sql: SESSIONS_MIGRATION.to_string(),
+            down_sql: SESSIONS_ROLLBACK.to_string(),
+        });
+
+        // Recognise photos uploaded twice or taken before their inspection
+        migrations.push(LegacyMigration {
+            version: 47,
+            description: "Media fingerprints".to_string(),
+            up_sql: MEDIA_FINGERPRINTS_MIGRATION.to_string(),
+            down_sql: MEDIA_FINGERPRINTS_ROLLBACK.to_string(),
+        });
+
+        // Keep a record of clients that hit a rate limit
+        migrations.push(LegacyMigration {
+            version: 48,
+            description: "Rate limit violations".to_string(),
+            up_sql: RATE_LIMIT_VIOLATIONS_MIGRATION.to_string(),
+            down_sql: RATE_LIMIT_VIOLATIONS_ROLLBACK.to_string(),
+        });
+
+        // Let users take notification emails as a daily digest
+        migrations.push(LegacyMigration {
+            version: 49,
+            description: "Notification digests".to_string(),
+            up_sql: NOTIFICATION_DIGESTS_MIGRATION.to_string(),
+            down_sql: NOTIFICATION_DIGESTS_ROLLBACK.to_string(),
+        });
+
+        // Lock local accounts after repeated wrong passwords
+        migrations.push(LegacyMigration {
+            version: 50,
+            description: "Login attempts".to_string(),
+            up_sql: LOGIN_ATTEMPTS_MIGRATION.to_string(),
+            down_sql: LOGIN_ATTEMPTS_ROLLBACK.to_string(),
+        });
+
+        // Roles with editable permission sets; built-ins are seeded at startup
+        migrations.push(LegacyMigration {
+            version: 51,
+            description: "Roles and permissions".to_string(),
+            up_sql: ROLES_MIGRATION.to_string(),
+            down_sql: ROLES_ROLLBACK.to_string(),
+        });
+
+        // API keys for machine-to-machine access
+        migrations.push(LegacyMigration {
+            version: 52,
+            description: "API keys".to_string(),
+            up_sql: API_KEYS_MIGRATION.to_string(),
+            down_sql: API_KEYS_ROLLBACK.to_string(),
+        });
+
+        // Side effects written with the change that caused them
+        migrations.push(LegacyMigration {
+            version: 53,
+            description: "Outbox".to_string(),
+            up_sql: OUTBOX_MIGRATION.to_string(),
+            down_sql: OUTBOX_ROLLBACK.to_string(),
+        });
+
+        // Link AI jobs to the request that queued them
+        migrations.push(LegacyMigration {
+            version: 54,
+            description: "AI job trace IDs".to_string(),
+            up_sql: AI_JOB_TRACE_MIGRATION.to_string(),
+            down_sql: AI_JOB_TRACE_ROLLBACK.to_string(),
+        });
+
+        // Working days, holidays and shutdowns per location
+        migrations.push(LegacyMigration {
+            version: 55,
+            description: "Location working calendars".to_string(),
+            up_sql: LOCATION_CALENDAR_MIGRATION.to_string(),
+            down_sql: LOCATION_CALENDAR_ROLLBACK.to_string(),
+        });
+
+        // Blind index for looking up users by encrypted email address
+        migrations.push(LegacyMigration {
+            version: 56,
+            description: "User email blind index".to_string(),
+            up_sql: USER_EMAIL_INDEX_MIGRATION.to_string(),
+            down_sql: USER_EMAIL_INDEX_ROLLBACK.to_string(),
+        });
+
+        migrations.push(LegacyMigration {
+            version: 57,
+            description: "Security events".to_string(),
+            up_sql: SECURITY_EVENTS_MIGRATION.to_string(),
+            down_sql: SECURITY_EVENTS_ROLLBACK.to_string(),
+        });
+
+        migrations.push(LegacyMigration {
+            version: 58,
+            description: "Asset link tokens".to_string(),
+            up_sql: ASSET_LINK_TOKENS_MIGRATION.to_string(),
+            down_sql: ASSET_LINK_TOKENS_ROLLBACK.to_string(),
+        });
+
+        migrations.push(LegacyMigration {
+            version: 59,
+            description: "Login history".to_string(),
+            up_sql: LOGIN_HISTORY_MIGRATION.to_string(),
+            down_sql: LOGIN_HISTORY_ROLLBACK.to_string(),
+        });
+
+        migrations.push(LegacyMigration {
+            version: 60,
+            description: "Custom report templates".to_string(),
+            up_sql: REPORT_TEMPLATES_MIGRATION.to_string(),
+            down_sql: REPORT_TEMPLATES_ROLLBACK.to_string(),
+        });
+
+        migrations.push(LegacyMigration {
+            version: 61,
+            description: "Checklist packs".to_string(),
+            up_sql: CHECKLIST_PACKS_MIGRATION.to_string(),
+            down_sql: CHECKLIST_PACKS_ROLLBACK.to_string(),
+        });
+
+        migrations.push(LegacyMigration {
+            version: 62,
+            description: "Crane classification".to_string(),
+            up_sql: CRANE_CLASSIFICATION_MIGRATION.to_string(),
+            down_sql: CRANE_CLASSIFICATION_ROLLBACK.to_string(),
+        });
+
+        migrations.push(LegacyMigration {
+            version: 63,
+            description: "Report artifacts".to_string(),
+            up_sql: REPORT_ARTIFACTS_MIGRATION.to_string(),
+            down_sql: REPORT_ARTIFACTS_ROLLBACK.to_string(),
+        });
+
+        migrations.push(LegacyMigration {
+            version: 64,
+            description: "Kiosk badge lockout".to_string(),
+            up_sql: KIOSK_BADGE_LOCKOUT_MIGRATION.to_string(),
+            down_sql: KIOSK_BADGE_LOCKOUT_ROLLBACK.to_string(),
+        });
+
+        migrations.push(LegacyMigration {
+            version: 65,
+            description: "Media uploader".to_string(),
+            up_sql: MEDIA_UPLOADER_MIGRATION.to_string(),
+            down_sql: MEDIA_UPLOADER_ROLLBACK.to_string(),
+        });
+
+        migrations.push(LegacyMigration {
+            version: 66,
+            description: "Stored refresh tokens and login challenges".to_string(),
+            up_sql: AUTH_TOKENS_MIGRATION.to_string(),
+            down_sql: AUTH_TOKENS_ROLLBACK.to_string(),
+        });
+
+        LegacyMigrationManager { migrations }
+    }
+
+    /// The migrations as a `MigrationRunner`, each depending on its predecessor
+    pub fn to_runner(&self) -> AppResult<MigrationRunner> {
+        let mut runner = MigrationRunner::new();
+        runner.add_migrations(
+            self.migrations
+                .iter()
+                .map(|migration| Migration::new(
+                    migration.version,
+                    migration.description.clone(),
+                    migration.description.clone(),
+                    migration.up_sql.clone(),
+                    migration.down_sql.clone(),
+                    if migration.version > 1 { vec![migration.version - 1] } else { Vec::new() },
+                ))
+                .collect(),
+        )?;
+        Ok(runner)
+    }
+
+    /// Run migrations from current version to target version
     pub fn run_migrations(
         &self,
         conn: &Connection,
@@ -286,13 +1593,8 @@ impl LegacyMigrationManager {
             if migration.version > from_version && migration.version <= to_version {
                 info!("Running migration {}: {}", migration.version, migration.description);
                 
-                // Split SQL by semicolon and execute each statement
-                for statement in migration.up_sql.split(';') {
-                    let statement = statement.trim();
-                    if !statement.is_empty() {
-                        conn.execute(statement, [])?;
-                    }
-                }
+                // Execute as a batch so trigger bodies containing ';' stay intact
+                conn.execute_batch(&migration.up_sql)?;
                 
                 debug!("Migration {} completed", migration.version);
             }
@@ -729,4 +2031,2172 @@ DROP INDEX IF EXISTS idx_locations_parent_id;
 -- Note: SQLite doesn't support DROP COLUMN directly, so we would need to recreate the table
 -- For simplicity in this rollback, we'll leave the column but set all values to NULL
 UPDATE locations SET parent_location_id = NULL;
-"#;
\ No newline at end of file
+"#;
+
+/// Per-user locale preference migration SQL
+const USER_LOCALE_MIGRATION: &str = r#"
+ALTER TABLE users ADD COLUMN locale TEXT NOT NULL DEFAULT 'en';
+"#;
+
+/// Per-user locale preference rollback SQL
+const USER_LOCALE_ROLLBACK: &str = r#"
+ALTER TABLE users DROP COLUMN locale;
+"#;
+
+/// Time zone aware scheduling migration SQL
+const TIME_ZONE_MIGRATION: &str = r#"
+-- IANA time zone of each site
+ALTER TABLE locations ADD COLUMN time_zone TEXT NOT NULL DEFAULT 'UTC';
+
+-- Time zone captured when a record is scheduled, and the UTC instant it becomes overdue
+ALTER TABLE inspections ADD COLUMN time_zone TEXT;
+ALTER TABLE inspections ADD COLUMN overdue_at DATETIME;
+ALTER TABLE maintenance_records ADD COLUMN time_zone TEXT;
+
+-- Existing schedules were computed in UTC
+UPDATE inspections
+SET time_zone = 'UTC',
+    overdue_at = datetime(date(scheduled_date), '+1 day')
+WHERE scheduled_date IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_inspections_overdue_at ON inspections(overdue_at);
+"#;
+
+/// Time zone aware scheduling rollback SQL
+const TIME_ZONE_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_inspections_overdue_at;
+ALTER TABLE maintenance_records DROP COLUMN time_zone;
+ALTER TABLE inspections DROP COLUMN overdue_at;
+ALTER TABLE inspections DROP COLUMN time_zone;
+ALTER TABLE locations DROP COLUMN time_zone;
+"#;
+
+/// Idempotency keys migration SQL
+const IDEMPOTENCY_KEYS_MIGRATION: &str = r#"
+-- One row per (user, command, key); response is NULL while the first call is in flight
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    idempotency_key TEXT NOT NULL,
+    user_id INTEGER NOT NULL,
+    command TEXT NOT NULL,
+    request_hash TEXT NOT NULL,
+    response TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    completed_at DATETIME,
+    PRIMARY KEY (idempotency_key, user_id, command),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created_at ON idempotency_keys(created_at);
+"#;
+
+/// Idempotency keys rollback SQL
+const IDEMPOTENCY_KEYS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_idempotency_keys_created_at;
+DROP TABLE IF EXISTS idempotency_keys;
+"#;
+
+/// Location coordinate index migration SQL
+const LOCATION_COORDINATES_MIGRATION: &str = r#"
+CREATE INDEX IF NOT EXISTS idx_locations_coordinates ON locations(latitude, longitude);
+"#;
+
+/// Location coordinate index rollback SQL
+const LOCATION_COORDINATES_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_locations_coordinates;
+"#;
+
+/// User absences migration SQL
+const USER_ABSENCES_MIGRATION: &str = r#"
+-- Whole-day absence periods, inclusive at both ends
+CREATE TABLE IF NOT EXISTS user_absences (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    absence_type TEXT NOT NULL CHECK (absence_type IN ('Vacation', 'Sick', 'Training')),
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL,
+    notes TEXT,
+    created_by INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (end_date >= start_date),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_absences_user_dates ON user_absences(user_id, start_date, end_date);
+"#;
+
+/// User absences rollback SQL
+const USER_ABSENCES_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_user_absences_user_dates;
+DROP TABLE IF EXISTS user_absences;
+"#;
+
+/// Teams migration SQL
+const TEAMS_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS teams (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    supervisor_id INTEGER,
+    is_active BOOLEAN NOT NULL DEFAULT 1,
+    created_by INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (supervisor_id) REFERENCES users(id) ON DELETE SET NULL,
+    FOREIGN KEY (created_by) REFERENCES users(id)
+);
+
+CREATE TABLE IF NOT EXISTS team_members (
+    team_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    joined_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (team_id, user_id),
+    FOREIGN KEY (team_id) REFERENCES teams(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Each location is covered by at most one team; sub-locations inherit it
+CREATE TABLE IF NOT EXISTS team_locations (
+    location_id INTEGER PRIMARY KEY,
+    team_id INTEGER NOT NULL,
+    assigned_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (location_id) REFERENCES locations(id) ON DELETE CASCADE,
+    FOREIGN KEY (team_id) REFERENCES teams(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_team_members_user ON team_members(user_id);
+CREATE INDEX IF NOT EXISTS idx_team_locations_team ON team_locations(team_id);
+
+CREATE TRIGGER IF NOT EXISTS update_teams_timestamp
+    AFTER UPDATE ON teams
+    FOR EACH ROW
+    BEGIN
+        UPDATE teams SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+    END;
+"#;
+
+/// Teams rollback SQL
+const TEAMS_ROLLBACK: &str = r#"
+DROP TRIGGER IF EXISTS update_teams_timestamp;
+DROP INDEX IF EXISTS idx_team_locations_team;
+DROP INDEX IF EXISTS idx_team_members_user;
+DROP TABLE IF EXISTS team_locations;
+DROP TABLE IF EXISTS team_members;
+DROP TABLE IF EXISTS teams;
+"#;
+
+/// Comments migration SQL
+const COMMENTS_MIGRATION: &str = r#"
+-- Polymorphic comments; entity_type names the commented table
+CREATE TABLE IF NOT EXISTS comments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity_type TEXT NOT NULL CHECK (entity_type IN ('Asset', 'Inspection', 'Finding', 'WorkOrder')),
+    entity_id INTEGER NOT NULL,
+    parent_id INTEGER,
+    author_id INTEGER NOT NULL,
+    body TEXT NOT NULL,
+    is_deleted BOOLEAN NOT NULL DEFAULT 0,
+    edit_count INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (parent_id) REFERENCES comments(id) ON DELETE CASCADE,
+    FOREIGN KEY (author_id) REFERENCES users(id)
+);
+
+-- Previous versions of edited comments
+CREATE TABLE IF NOT EXISTS comment_edits (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    comment_id INTEGER NOT NULL,
+    previous_body TEXT NOT NULL,
+    edited_by INTEGER NOT NULL,
+    edited_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (comment_id) REFERENCES comments(id) ON DELETE CASCADE,
+    FOREIGN KEY (edited_by) REFERENCES users(id)
+);
+
+CREATE TABLE IF NOT EXISTS comment_mentions (
+    comment_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    read_at DATETIME,
+    PRIMARY KEY (comment_id, user_id),
+    FOREIGN KEY (comment_id) REFERENCES comments(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_comments_entity ON comments(entity_type, entity_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_comments_parent ON comments(parent_id);
+CREATE INDEX IF NOT EXISTS idx_comment_edits_comment ON comment_edits(comment_id);
+CREATE INDEX IF NOT EXISTS idx_comment_mentions_user ON comment_mentions(user_id, read_at);
+"#;
+
+/// Comments rollback SQL
+const COMMENTS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_comment_mentions_user;
+DROP INDEX IF EXISTS idx_comment_edits_comment;
+DROP INDEX IF EXISTS idx_comments_parent;
+DROP INDEX IF EXISTS idx_comments_entity;
+DROP TABLE IF EXISTS comment_mentions;
+DROP TABLE IF EXISTS comment_edits;
+DROP TABLE IF EXISTS comments;
+"#;
+
+/// Watches migration SQL
+const WATCHES_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS watches (
+    user_id INTEGER NOT NULL,
+    entity_type TEXT NOT NULL CHECK(entity_type IN ('Asset', 'Inspection')),
+    entity_id INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, entity_type, entity_id),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_watches_entity ON watches(entity_type, entity_id);
+"#;
+
+/// Watches rollback SQL
+const WATCHES_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_watches_entity;
+DROP TABLE IF EXISTS watches;
+"#;
+
+/// Recycle bin migration SQL
+const RECYCLE_BIN_MIGRATION: &str = r#"
+-- Snapshot of each deleted record and its cascaded rows; deleted_by has no
+-- foreign key so entries survive the deleting user being removed
+CREATE TABLE IF NOT EXISTS recycle_bin (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity_type TEXT NOT NULL CHECK(entity_type IN ('Asset', 'Location', 'User', 'Team')),
+    entity_id INTEGER NOT NULL,
+    label TEXT NOT NULL,
+    snapshot JSON NOT NULL,
+    deleted_by INTEGER,
+    deleted_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_recycle_bin_entity ON recycle_bin(entity_type, entity_id);
+CREATE INDEX IF NOT EXISTS idx_recycle_bin_deleted_at ON recycle_bin(deleted_at);
+"#;
+
+/// Recycle bin rollback SQL
+const RECYCLE_BIN_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_recycle_bin_deleted_at;
+DROP INDEX IF EXISTS idx_recycle_bin_entity;
+DROP TABLE IF EXISTS recycle_bin;
+"#;
+
+/// Record tags migration SQL
+const RECORD_TAGS_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS record_tags (
+    entity_type TEXT NOT NULL CHECK(entity_type IN ('Asset', 'Inspection')),
+    entity_id INTEGER NOT NULL,
+    tag TEXT NOT NULL COLLATE NOCASE,
+    created_by INTEGER,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (entity_type, entity_id, tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_record_tags_tag ON record_tags(tag);
+"#;
+
+/// Record tags rollback SQL
+const RECORD_TAGS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_record_tags_tag;
+DROP TABLE IF EXISTS record_tags;
+"#;
+
+/// Asset card view migration SQL
+const ASSET_CARDS_VIEW_MIGRATION: &str = r#"
+-- One row per asset with everything a field screen card shows. Open findings
+-- are the problem items of the latest completed inspection. The risk score
+-- (0-100) adds weighted open findings (Critical 40, High 20, Medium 10,
+-- other 5), the last condition (Critical 40, Poor 25, Fair 10), 20 when the
+-- next inspection is overdue and 25 when the asset was never inspected.
+CREATE VIEW IF NOT EXISTS asset_cards AS
+SELECT c.*,
+       MIN(100,
+           c.finding_points
+           + CASE c.last_condition WHEN 'Critical' THEN 40 WHEN 'Poor' THEN 25 WHEN 'Fair' THEN 10 ELSE 0 END
+           + CASE WHEN c.is_overdue THEN 20 ELSE 0 END
+           + CASE WHEN c.last_inspection_id IS NULL THEN 25 ELSE 0 END) AS risk_score
+FROM (
+    SELECT b.*,
+           COALESCE(datetime(b.next_due_date) < datetime('now'), 0) AS is_overdue,
+           (SELECT COUNT(*) FROM inspection_items ii
+            WHERE ii.inspection_id = b.last_inspection_id
+              AND (TRIM(COALESCE(ii.finding, '')) != '' OR ii.severity IS NOT NULL OR ii.is_compliant = 0)) AS open_findings,
+           (SELECT COALESCE(SUM(CASE ii.severity WHEN 'Critical' THEN 40 WHEN 'High' THEN 20 WHEN 'Medium' THEN 10 ELSE 5 END), 0)
+            FROM inspection_items ii
+            WHERE ii.inspection_id = b.last_inspection_id
+              AND (TRIM(COALESCE(ii.finding, '')) != '' OR ii.severity IS NOT NULL OR ii.is_compliant = 0)) AS finding_points
+    FROM (
+        SELECT a.id AS asset_id, a.asset_number, a.asset_name, a.asset_type, a.status,
+               a.location_id, l.name AS location_name,
+               li.id AS last_inspection_id, li.actual_date AS last_inspection_date,
+               li.overall_condition AS last_condition,
+               (SELECT MIN(i.scheduled_date) FROM inspections i
+                WHERE i.asset_id = a.id AND i.status IN ('Scheduled', 'In Progress')
+                  AND i.scheduled_date IS NOT NULL) AS next_due_date
+        FROM assets a
+        JOIN locations l ON a.location_id = l.id
+        LEFT JOIN inspections li ON li.id = (
+            SELECT i.id FROM inspections i
+            WHERE i.asset_id = a.id AND i.status = 'Completed'
+            ORDER BY i.actual_date DESC, i.id DESC LIMIT 1)
+    ) b
+) c;
+
+CREATE INDEX IF NOT EXISTS idx_inspections_asset_status ON inspections(asset_id, status);
+"#;
+
+/// Asset card view rollback SQL
+const ASSET_CARDS_VIEW_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_inspections_asset_status;
+DROP VIEW IF EXISTS asset_cards;
+"#;
+
+/// Full-text search migration SQL: external-content FTS5 indexes kept in
+/// step with their tables by triggers
+const FULL_TEXT_SEARCH_MIGRATION: &str = r#"
+CREATE VIRTUAL TABLE IF NOT EXISTS assets_fts USING fts5(
+    asset_number, asset_name, asset_type, manufacturer, model, serial_number, description,
+    content='assets', content_rowid='id', tokenize='porter unicode61'
+);
+
+CREATE TRIGGER IF NOT EXISTS assets_fts_insert AFTER INSERT ON assets BEGIN
+    INSERT INTO assets_fts (rowid, asset_number, asset_name, asset_type, manufacturer, model, serial_number, description)
+    VALUES (new.id, new.asset_number, new.asset_name, new.asset_type, new.manufacturer, new.model, new.serial_number, new.description);
+END;
+
+CREATE TRIGGER IF NOT EXISTS assets_fts_delete AFTER DELETE ON assets BEGIN
+    INSERT INTO assets_fts (assets_fts, rowid, asset_number, asset_name, asset_type, manufacturer, model, serial_number, description)
+    VALUES ('delete', old.id, old.asset_number, old.asset_name, old.asset_type, old.manufacturer, old.model, old.serial_number, old.description);
+END;
+
+CREATE TRIGGER IF NOT EXISTS assets_fts_update
+AFTER UPDATE OF asset_number, asset_name, asset_type, manufacturer, model, serial_number, description ON assets BEGIN
+    INSERT INTO assets_fts (assets_fts, rowid, asset_number, asset_name, asset_type, manufacturer, model, serial_number, description)
+    VALUES ('delete', old.id, old.asset_number, old.asset_name, old.asset_type, old.manufacturer, old.model, old.serial_number, old.description);
+    INSERT INTO assets_fts (rowid, asset_number, asset_name, asset_type, manufacturer, model, serial_number, description)
+    VALUES (new.id, new.asset_number, new.asset_name, new.asset_type, new.manufacturer, new.model, new.serial_number, new.description);
+END;
+
+INSERT INTO assets_fts (assets_fts) VALUES ('rebuild');
+
+CREATE VIRTUAL TABLE IF NOT EXISTS components_fts USING fts5(
+    component_name, component_type, manufacturer, model, serial_number,
+    content='components', content_rowid='id', tokenize='porter unicode61'
+);
+
+CREATE TRIGGER IF NOT EXISTS components_fts_insert AFTER INSERT ON components BEGIN
+    INSERT INTO components_fts (rowid, component_name, component_type, manufacturer, model, serial_number)
+    VALUES (new.id, new.component_name, new.component_type, new.manufacturer, new.model, new.serial_number);
+END;
+
+CREATE TRIGGER IF NOT EXISTS components_fts_delete AFTER DELETE ON components BEGIN
+    INSERT INTO components_fts (components_fts, rowid, component_name, component_type, manufacturer, model, serial_number)
+    VALUES ('delete', old.id, old.component_name, old.component_type, old.manufacturer, old.model, old.serial_number);
+END;
+
+CREATE TRIGGER IF NOT EXISTS components_fts_update
+AFTER UPDATE OF component_name, component_type, manufacturer, model, serial_number ON components BEGIN
+    INSERT INTO components_fts (components_fts, rowid, component_name, component_type, manufacturer, model, serial_number)
+    VALUES ('delete', old.id, old.component_name, old.component_type, old.manufacturer, old.model, old.serial_number);
+    INSERT INTO components_fts (rowid, component_name, component_type, manufacturer, model, serial_number)
+    VALUES (new.id, new.component_name, new.component_type, new.manufacturer, new.model, new.serial_number);
+END;
+
+INSERT INTO components_fts (components_fts) VALUES ('rebuild');
+
+CREATE VIRTUAL TABLE IF NOT EXISTS inspections_fts USING fts5(
+    inspection_type, compliance_standard, notes,
+    content='inspections', content_rowid='id', tokenize='porter unicode61'
+);
+
+CREATE TRIGGER IF NOT EXISTS inspections_fts_insert AFTER INSERT ON inspections BEGIN
+    INSERT INTO inspections_fts (rowid, inspection_type, compliance_standard, notes)
+    VALUES (new.id, new.inspection_type, new.compliance_standard, new.notes);
+END;
+
+CREATE TRIGGER IF NOT EXISTS inspections_fts_delete AFTER DELETE ON inspections BEGIN
+    INSERT INTO inspections_fts (inspections_fts, rowid, inspection_type, compliance_standard, notes)
+    VALUES ('delete', old.id, old.inspection_type, old.compliance_standard, old.notes);
+END;
+
+CREATE TRIGGER IF NOT EXISTS inspections_fts_update
+AFTER UPDATE OF inspection_type, compliance_standard, notes ON inspections BEGIN
+    INSERT INTO inspections_fts (inspections_fts, rowid, inspection_type, compliance_standard, notes)
+    VALUES ('delete', old.id, old.inspection_type, old.compliance_standard, old.notes);
+    INSERT INTO inspections_fts (rowid, inspection_type, compliance_standard, notes)
+    VALUES (new.id, new.inspection_type, new.compliance_standard, new.notes);
+END;
+
+INSERT INTO inspections_fts (inspections_fts) VALUES ('rebuild');
+
+CREATE VIRTUAL TABLE IF NOT EXISTS inspection_items_fts USING fts5(
+    item_name, item_category, finding, corrective_action,
+    content='inspection_items', content_rowid='id', tokenize='porter unicode61'
+);
+
+CREATE TRIGGER IF NOT EXISTS inspection_items_fts_insert AFTER INSERT ON inspection_items BEGIN
+    INSERT INTO inspection_items_fts (rowid, item_name, item_category, finding, corrective_action)
+    VALUES (new.id, new.item_name, new.item_category, new.finding, new.corrective_action);
+END;
+
+CREATE TRIGGER IF NOT EXISTS inspection_items_fts_delete AFTER DELETE ON inspection_items BEGIN
+    INSERT INTO inspection_items_fts (inspection_items_fts, rowid, item_name, item_category, finding, corrective_action)
+    VALUES ('delete', old.id, old.item_name, old.item_category, old.finding, old.corrective_action);
+END;
+
+CREATE TRIGGER IF NOT EXISTS inspection_items_fts_update
+AFTER UPDATE OF item_name, item_category, finding, corrective_action ON inspection_items BEGIN
+    INSERT INTO inspection_items_fts (inspection_items_fts, rowid, item_name, item_category, finding, corrective_action)
+    VALUES ('delete', old.id, old.item_name, old.item_category, old.finding, old.corrective_action);
+    INSERT INTO inspection_items_fts (rowid, item_name, item_category, finding, corrective_action)
+    VALUES (new.id, new.item_name, new.item_category, new.finding, new.corrective_action);
+END;
+
+INSERT INTO inspection_items_fts (inspection_items_fts) VALUES ('rebuild');
+"#;
+
+/// Full-text search rollback SQL
+const FULL_TEXT_SEARCH_ROLLBACK: &str = r#"
+DROP TRIGGER IF EXISTS inspection_items_fts_update;
+DROP TRIGGER IF EXISTS inspection_items_fts_delete;
+DROP TRIGGER IF EXISTS inspection_items_fts_insert;
+DROP TABLE IF EXISTS inspection_items_fts;
+DROP TRIGGER IF EXISTS inspections_fts_update;
+DROP TRIGGER IF EXISTS inspections_fts_delete;
+DROP TRIGGER IF EXISTS inspections_fts_insert;
+DROP TABLE IF EXISTS inspections_fts;
+DROP TRIGGER IF EXISTS components_fts_update;
+DROP TRIGGER IF EXISTS components_fts_delete;
+DROP TRIGGER IF EXISTS components_fts_insert;
+DROP TABLE IF EXISTS components_fts;
+DROP TRIGGER IF EXISTS assets_fts_update;
+DROP TRIGGER IF EXISTS assets_fts_delete;
+DROP TRIGGER IF EXISTS assets_fts_insert;
+DROP TABLE IF EXISTS assets_fts;
+"#;
+
+/// Pre-start checks migration SQL
+const PRESTART_CHECKS_MIGRATION: &str = r#"
+ALTER TABLE assets ADD COLUMN prestart_required BOOLEAN NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS prestart_checks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    asset_id INTEGER NOT NULL,
+    operator_id INTEGER NOT NULL,
+    shift TEXT NOT NULL CHECK(shift IN ('Day', 'Afternoon', 'Night')),
+    check_date DATE NOT NULL,
+    items JSON NOT NULL,
+    passed BOOLEAN NOT NULL,
+    notes TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE,
+    FOREIGN KEY (operator_id) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_prestart_checks_asset_date ON prestart_checks(asset_id, check_date);
+"#;
+
+/// Pre-start checks rollback SQL
+const PRESTART_CHECKS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_prestart_checks_asset_date;
+DROP TABLE IF EXISTS prestart_checks;
+ALTER TABLE assets DROP COLUMN prestart_required;
+"#;
+
+/// Database maintenance log migration SQL
+const DB_MAINTENANCE_LOG_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS db_maintenance_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task TEXT NOT NULL CHECK(task IN ('Analyze', 'IntegrityCheck', 'Vacuum')),
+    scheduled BOOLEAN NOT NULL,
+    user_id INTEGER,
+    success BOOLEAN NOT NULL,
+    details TEXT NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    started_at DATETIME NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_db_maintenance_log_started ON db_maintenance_log(started_at);
+"#;
+
+/// Database maintenance log rollback SQL
+const DB_MAINTENANCE_LOG_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_db_maintenance_log_started;
+DROP TABLE IF EXISTS db_maintenance_log;
+"#;
+
+/// Soft delete migration SQL. The asset card view is recreated to leave out
+/// deleted assets and inspections.
+const SOFT_DELETE_MIGRATION: &str = r#"
+ALTER TABLE assets ADD COLUMN deleted_at DATETIME;
+ALTER TABLE assets ADD COLUMN deleted_by INTEGER;
+ALTER TABLE inspections ADD COLUMN deleted_at DATETIME;
+ALTER TABLE inspections ADD COLUMN deleted_by INTEGER;
+ALTER TABLE users ADD COLUMN deleted_at DATETIME;
+ALTER TABLE users ADD COLUMN deleted_by INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_assets_deleted_at ON assets(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_inspections_deleted_at ON inspections(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_users_deleted_at ON users(deleted_at);
+
+DROP VIEW IF EXISTS asset_cards;
+CREATE VIEW asset_cards AS
+SELECT c.*,
+       MIN(100,
+           c.finding_points
+           + CASE c.last_condition WHEN 'Critical' THEN 40 WHEN 'Poor' THEN 25 WHEN 'Fair' THEN 10 ELSE 0 END
+           + CASE WHEN c.is_overdue THEN 20 ELSE 0 END
+           + CASE WHEN c.last_inspection_id IS NULL THEN 25 ELSE 0 END) AS risk_score
+FROM (
+    SELECT b.*,
+           COALESCE(datetime(b.next_due_date) < datetime('now'), 0) AS is_overdue,
+           (SELECT COUNT(*) FROM inspection_items ii
+            WHERE ii.inspection_id = b.last_inspection_id
+              AND (TRIM(COALESCE(ii.finding, '')) != '' OR ii.severity IS NOT NULL OR ii.is_compliant = 0)) AS open_findings,
+           (SELECT COALESCE(SUM(CASE ii.severity WHEN 'Critical' THEN 40 WHEN 'High' THEN 20 WHEN 'Medium' THEN 10 ELSE 5 END), 0)
+            FROM inspection_items ii
+            WHERE ii.inspection_id = b.last_inspection_id
+              AND (TRIM(COALESCE(ii.finding, '')) != '' OR ii.severity IS NOT NULL OR ii.is_compliant = 0)) AS finding_points
+    FROM (
+        SELECT a.id AS asset_id, a.asset_number, a.asset_name, a.asset_type, a.status,
+               a.location_id, l.name AS location_name,
+               li.id AS last_inspection_id, li.actual_date AS last_inspection_date,
+               li.overall_condition AS last_condition,
+               (SELECT MIN(i.scheduled_date) FROM inspections i
+                WHERE i.asset_id = a.id AND i.status IN ('Scheduled', 'In Progress')
+                  AND i.scheduled_date IS NOT NULL AND i.deleted_at IS NULL) AS next_due_date
+        FROM assets a
+        JOIN locations l ON a.location_id = l.id
+        LEFT JOIN inspections li ON li.id = (
+            SELECT i.id FROM inspections i
+            WHERE i.asset_id = a.id AND i.status = 'Completed' AND i.deleted_at IS NULL
+            ORDER BY i.actual_date DESC, i.id DESC LIMIT 1)
+        WHERE a.deleted_at IS NULL
+    ) b
+) c;
+"#;
+
+/// Soft delete rollback SQL, followed by the original asset card view
+const SOFT_DELETE_ROLLBACK: &str = r#"
+DROP VIEW IF EXISTS asset_cards;
+DROP INDEX IF EXISTS idx_assets_deleted_at;
+DROP INDEX IF EXISTS idx_inspections_deleted_at;
+DROP INDEX IF EXISTS idx_users_deleted_at;
+ALTER TABLE assets DROP COLUMN deleted_at;
+ALTER TABLE assets DROP COLUMN deleted_by;
+ALTER TABLE inspections DROP COLUMN deleted_at;
+ALTER TABLE inspections DROP COLUMN deleted_by;
+ALTER TABLE users DROP COLUMN deleted_at;
+ALTER TABLE users DROP COLUMN deleted_by;
+"#;
+
+/// Entity history migration SQL
+const ENTITY_HISTORY_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS entity_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity_type TEXT NOT NULL CHECK(entity_type IN ('Asset', 'Inspection', 'User')),
+    entity_id INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    changed_by INTEGER,
+    changed_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    before_json TEXT NOT NULL,
+    after_json TEXT NOT NULL,
+    FOREIGN KEY (changed_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_entity_history_entity ON entity_history(entity_type, entity_id, changed_at);
+"#;
+
+/// Entity history rollback SQL
+const ENTITY_HISTORY_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_entity_history_entity;
+DROP TABLE IF EXISTS entity_history;
+"#;
+
+/// Asset records migration SQL
+const ASSET_RECORDS_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS asset_records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    asset_id INTEGER NOT NULL,
+    kind TEXT NOT NULL CHECK(kind IN ('Insurance', 'Registration')),
+    reference_number TEXT NOT NULL,
+    issuer TEXT,
+    issued_date DATE,
+    renewal_date DATE NOT NULL,
+    notes TEXT,
+    created_by INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL,
+    UNIQUE(asset_id, kind, reference_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_asset_records_asset ON asset_records(asset_id);
+CREATE INDEX IF NOT EXISTS idx_asset_records_renewal ON asset_records(renewal_date);
+"#;
+
+/// Asset records rollback SQL
+const ASSET_RECORDS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_asset_records_renewal;
+DROP INDEX IF EXISTS idx_asset_records_asset;
+DROP TABLE IF EXISTS asset_records;
+"#;
+
+/// Operator authorizations migration SQL
+const OPERATOR_AUTHORIZATIONS_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS operator_authorizations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    asset_type TEXT NOT NULL,
+    max_capacity REAL,
+    max_capacity_unit TEXT,
+    expires_at DATE,
+    notes TEXT,
+    granted_by INTEGER,
+    granted_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    revoked_at DATETIME,
+    revoked_by INTEGER,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (granted_by) REFERENCES users(id) ON DELETE SET NULL,
+    FOREIGN KEY (revoked_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_operator_authorizations_user ON operator_authorizations(user_id, asset_type);
+"#;
+
+/// Operator authorizations rollback SQL
+const OPERATOR_AUTHORIZATIONS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_operator_authorizations_user;
+DROP TABLE IF EXISTS operator_authorizations;
+"#;
+
+/// Training records migration SQL
+const TRAINING_RECORDS_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS training_records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    course TEXT NOT NULL,
+    provider TEXT,
+    issued_date DATE NOT NULL,
+    expiry_date DATE,
+    certificate_media_id INTEGER,
+    qualifies_for JSON NOT NULL DEFAULT '[]',
+    created_by INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (certificate_media_id) REFERENCES media_files(id) ON DELETE SET NULL,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_training_records_user ON training_records(user_id);
+CREATE INDEX IF NOT EXISTS idx_training_records_expiry ON training_records(expiry_date);
+"#;
+
+/// Training records rollback SQL
+const TRAINING_RECORDS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_training_records_expiry;
+DROP INDEX IF EXISTS idx_training_records_user;
+DROP TABLE IF EXISTS training_records;
+"#;
+
+/// Work orders migration SQL
+const WORK_ORDERS_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS work_orders (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    inspection_item_id INTEGER NOT NULL,
+    inspection_id INTEGER NOT NULL,
+    asset_id INTEGER NOT NULL,
+    component_id INTEGER,
+    title TEXT NOT NULL,
+    description TEXT,
+    priority TEXT NOT NULL CHECK(priority IN ('Low', 'Normal', 'High', 'Urgent')),
+    status TEXT NOT NULL DEFAULT 'Open' CHECK(status IN ('Open', 'In Progress', 'Completed', 'Verified', 'Cancelled')),
+    assignee_id INTEGER,
+    due_date DATE,
+    parts JSON NOT NULL DEFAULT '[]',
+    completion_notes TEXT,
+    created_by INTEGER,
+    started_at DATETIME,
+    completed_at DATETIME,
+    verified_at DATETIME,
+    verified_by INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (inspection_item_id) REFERENCES inspection_items(id) ON DELETE CASCADE,
+    FOREIGN KEY (inspection_id) REFERENCES inspections(id) ON DELETE CASCADE,
+    FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE,
+    FOREIGN KEY (component_id) REFERENCES components(id) ON DELETE SET NULL,
+    FOREIGN KEY (assignee_id) REFERENCES users(id) ON DELETE SET NULL,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL,
+    FOREIGN KEY (verified_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_work_orders_item ON work_orders(inspection_item_id);
+CREATE INDEX IF NOT EXISTS idx_work_orders_asset ON work_orders(asset_id);
+CREATE INDEX IF NOT EXISTS idx_work_orders_assignee ON work_orders(assignee_id);
+CREATE INDEX IF NOT EXISTS idx_work_orders_status ON work_orders(status);
+"#;
+
+/// Work orders rollback SQL
+const WORK_ORDERS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_work_orders_status;
+DROP INDEX IF EXISTS idx_work_orders_assignee;
+DROP INDEX IF EXISTS idx_work_orders_asset;
+DROP INDEX IF EXISTS idx_work_orders_item;
+DROP TABLE IF EXISTS work_orders;
+"#;
+
+/// Notifications migration SQL
+const NOTIFICATIONS_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS notifications (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    kind TEXT NOT NULL CHECK(kind IN ('InspectionDue', 'CriticalFinding', 'Assignment')),
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    entity_type TEXT,
+    entity_id INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    read_at DATETIME,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user ON notifications(user_id, read_at);
+CREATE INDEX IF NOT EXISTS idx_notifications_entity ON notifications(entity_type, entity_id, kind);
+
+-- Channels are enabled unless a row turns them off
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    user_id INTEGER NOT NULL,
+    kind TEXT NOT NULL CHECK(kind IN ('InspectionDue', 'CriticalFinding', 'Assignment')),
+    channel TEXT NOT NULL CHECK(channel IN ('InApp', 'Email', 'Event')),
+    enabled BOOLEAN NOT NULL,
+    PRIMARY KEY (user_id, kind, channel),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+"#;
+
+/// Notifications rollback SQL
+const NOTIFICATIONS_ROLLBACK: &str = r#"
+DROP TABLE IF EXISTS notification_preferences;
+DROP INDEX IF EXISTS idx_notifications_entity;
+DROP INDEX IF EXISTS idx_notifications_user;
+DROP TABLE IF EXISTS notifications;
+"#;
+
+/// Standard clauses migration SQL
+const STANDARD_CLAUSES_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS standard_clauses (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    standard_id INTEGER NOT NULL,
+    clause_ref TEXT NOT NULL,
+    title TEXT NOT NULL,
+    text TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(standard_id, clause_ref),
+    FOREIGN KEY (standard_id) REFERENCES compliance_standards(id) ON DELETE CASCADE
+);
+
+-- Not declared as a foreign key so the column can be dropped on rollback;
+-- the service checks the clause exists and refuses to delete cited clauses
+ALTER TABLE inspection_items ADD COLUMN clause_id INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_inspection_items_clause ON inspection_items(clause_id);
+"#;
+
+/// Standard clauses rollback SQL
+const STANDARD_CLAUSES_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_inspection_items_clause;
+ALTER TABLE inspection_items DROP COLUMN clause_id;
+DROP TABLE IF EXISTS standard_clauses;
+"#;
+
+/// Risk matrix migration SQL
+const RISK_MATRIX_MIGRATION: &str = r#"
+-- One row per finding severity and asset criticality
+CREATE TABLE IF NOT EXISTS risk_matrix (
+    severity TEXT NOT NULL CHECK(severity IN ('Low', 'Medium', 'High', 'Critical')),
+    criticality TEXT NOT NULL CHECK(criticality IN ('Low', 'Medium', 'High', 'Critical')),
+    rating TEXT NOT NULL CHECK(rating IN ('Low', 'Medium', 'High', 'Extreme')),
+    response_hours INTEGER NOT NULL CHECK(response_hours > 0),
+    updated_by INTEGER,
+    updated_at DATETIME,
+    PRIMARY KEY (severity, criticality),
+    FOREIGN KEY (updated_by) REFERENCES users(id)
+);
+
+INSERT OR IGNORE INTO risk_matrix (severity, criticality, rating, response_hours) VALUES
+    ('Low', 'Low', 'Low', 720),
+    ('Low', 'Medium', 'Low', 720),
+    ('Low', 'High', 'Medium', 168),
+    ('Low', 'Critical', 'Medium', 168),
+    ('Medium', 'Low', 'Low', 720),
+    ('Medium', 'Medium', 'Medium', 168),
+    ('Medium', 'High', 'Medium', 168),
+    ('Medium', 'Critical', 'High', 24),
+    ('High', 'Low', 'Medium', 168),
+    ('High', 'Medium', 'Medium', 168),
+    ('High', 'High', 'High', 24),
+    ('High', 'Critical', 'Extreme', 4),
+    ('Critical', 'Low', 'Medium', 168),
+    ('Critical', 'Medium', 'High', 24),
+    ('Critical', 'High', 'Extreme', 4),
+    ('Critical', 'Critical', 'Extreme', 4);
+
+ALTER TABLE assets ADD COLUMN criticality TEXT NOT NULL DEFAULT 'Medium';
+ALTER TABLE inspection_items ADD COLUMN risk_rating TEXT;
+ALTER TABLE inspection_items ADD COLUMN response_due_at DATETIME;
+"#;
+
+/// Risk matrix rollback SQL
+const RISK_MATRIX_ROLLBACK: &str = r#"
+ALTER TABLE inspection_items DROP COLUMN response_due_at;
+ALTER TABLE inspection_items DROP COLUMN risk_rating;
+ALTER TABLE assets DROP COLUMN criticality;
+DROP TABLE IF EXISTS risk_matrix;
+"#;
+
+/// Finding SLAs migration SQL
+const FINDING_SLAS_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS finding_sla_targets (
+    stage TEXT PRIMARY KEY CHECK(stage IN ('Acknowledge', 'Resolve')),
+    target_hours INTEGER NOT NULL CHECK(target_hours > 0),
+    updated_by INTEGER,
+    updated_at DATETIME,
+    FOREIGN KEY (updated_by) REFERENCES users(id)
+);
+
+INSERT OR IGNORE INTO finding_sla_targets (stage, target_hours) VALUES
+    ('Acknowledge', 4),
+    ('Resolve', 72);
+
+-- Clocks start when a finding is recorded as critical; existing findings
+-- are not backfilled so upgrading does not raise a flood of breaches
+CREATE TABLE IF NOT EXISTS finding_slas (
+    item_id INTEGER PRIMARY KEY,
+    opened_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    acknowledge_due_at DATETIME NOT NULL,
+    acknowledged_at DATETIME,
+    acknowledged_by INTEGER,
+    resolve_due_at DATETIME NOT NULL,
+    resolved_at DATETIME,
+    resolved_by INTEGER,
+    resolution_notes TEXT,
+    acknowledge_breach_notified_at DATETIME,
+    resolve_breach_notified_at DATETIME,
+    FOREIGN KEY (item_id) REFERENCES inspection_items(id) ON DELETE CASCADE,
+    FOREIGN KEY (acknowledged_by) REFERENCES users(id),
+    FOREIGN KEY (resolved_by) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_finding_slas_open ON finding_slas(resolved_at, resolve_due_at);
+CREATE INDEX IF NOT EXISTS idx_finding_slas_opened ON finding_slas(opened_at);
+"#;
+
+/// Finding SLAs rollback SQL
+const FINDING_SLAS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_finding_slas_opened;
+DROP INDEX IF EXISTS idx_finding_slas_open;
+DROP TABLE IF EXISTS finding_slas;
+DROP TABLE IF EXISTS finding_sla_targets;
+"#;
+
+/// Audit log migration SQL
+const AUDIT_LOG_MIGRATION: &str = r#"
+-- No foreign key on user_id: entries must outlive the users they name
+CREATE TABLE IF NOT EXISTS audit_log (
+    id TEXT PRIMARY KEY,
+    request_id TEXT NOT NULL,
+    user_id INTEGER,
+    username TEXT,
+    action TEXT NOT NULL,
+    resource_type TEXT NOT NULL,
+    resource_id TEXT,
+    details TEXT NOT NULL DEFAULT '{}',
+    ip_address TEXT,
+    user_agent TEXT,
+    timestamp DATETIME NOT NULL,
+    success BOOLEAN NOT NULL,
+    error_message TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_timestamp ON audit_log(timestamp);
+CREATE INDEX IF NOT EXISTS idx_audit_log_user ON audit_log(user_id, timestamp);
+CREATE INDEX IF NOT EXISTS idx_audit_log_resource ON audit_log(resource_type, resource_id);
+"#;
+
+/// Audit log rollback SQL
+const AUDIT_LOG_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_audit_log_resource;
+DROP INDEX IF EXISTS idx_audit_log_user;
+DROP INDEX IF EXISTS idx_audit_log_timestamp;
+DROP TABLE IF EXISTS audit_log;
+"#;
+
+/// Application settings migration SQL
+const APP_SETTINGS_MIGRATION: &str = r#"
+-- Values are stored as JSON; settings without a row use their defaults
+CREATE TABLE IF NOT EXISTS app_settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_by INTEGER,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (updated_by) REFERENCES users(id)
+);
+"#;
+
+/// Application settings rollback SQL
+const APP_SETTINGS_ROLLBACK: &str = r#"
+DROP TABLE IF EXISTS app_settings;
+"#;
+
+/// Component templates migration SQL
+const COMPONENT_TEMPLATES_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS component_templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    asset_type TEXT NOT NULL COLLATE NOCASE,
+    component_name TEXT NOT NULL,
+    component_type TEXT NOT NULL,
+    default_specifications JSON,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(asset_type, component_name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_component_templates_asset_type ON component_templates(asset_type, sort_order);
+
+-- Standard set for the crane types in use
+INSERT OR IGNORE INTO component_templates (asset_type, component_name, component_type, default_specifications, sort_order)
+SELECT crane.asset_type, part.component_name, part.component_type, part.default_specifications, part.sort_order
+FROM (
+    SELECT 'Overhead Bridge Crane' AS asset_type
+    UNION ALL SELECT 'Gantry Crane'
+    UNION ALL SELECT 'Semi-Gantry Crane'
+    UNION ALL SELECT 'Jib Crane'
+    UNION ALL SELECT 'Monorail Hoist'
+) AS crane
+CROSS JOIN (
+    SELECT 'Hoist Motor' AS component_name, 'Motor' AS component_type,
+           '{"rated_power_kw": null, "voltage": null, "duty_class": null}' AS default_specifications, 1 AS sort_order
+    UNION ALL SELECT 'Hoist Brake', 'Brake',
+           '{"brake_type": null, "torque_rating_percent": 125}', 2
+    UNION ALL SELECT 'Wire Rope', 'Rope',
+           '{"construction": null, "diameter_mm": null, "length_m": null, "design_factor": 5}', 3
+    UNION ALL SELECT 'Hook', 'Hook',
+           '{"throat_opening_mm": null, "latch_fitted": true}', 4
+    UNION ALL SELECT 'Limit Switches', 'Limit Switch',
+           '{"upper_hoist_limit": true, "lower_hoist_limit": null, "travel_limits": null}', 5
+) AS part;
+"#;
+
+/// Component templates rollback SQL
+const COMPONENT_TEMPLATES_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_component_templates_asset_type;
+DROP TABLE IF EXISTS component_templates;
+"#;
+
+/// Spare parts inventory migration SQL
+const PARTS_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS parts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    part_number TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    name TEXT NOT NULL,
+    description TEXT,
+    manufacturer TEXT,
+    unit_cost REAL CHECK(unit_cost IS NULL OR unit_cost >= 0),
+    quantity_on_hand INTEGER NOT NULL DEFAULT 0 CHECK(quantity_on_hand >= 0),
+    reorder_level INTEGER NOT NULL DEFAULT 0 CHECK(reorder_level >= 0),
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS part_compatibility (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    part_id INTEGER NOT NULL,
+    component_type TEXT NOT NULL COLLATE NOCASE,
+    manufacturer TEXT COLLATE NOCASE,
+    model TEXT COLLATE NOCASE,
+    FOREIGN KEY (part_id) REFERENCES parts(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS part_stock_movements (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    part_id INTEGER NOT NULL,
+    kind TEXT NOT NULL CHECK(kind IN ('Received', 'Consumed', 'Adjusted')),
+    quantity_change INTEGER NOT NULL CHECK(quantity_change != 0),
+    maintenance_record_id INTEGER,
+    notes TEXT,
+    created_by INTEGER,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (part_id) REFERENCES parts(id) ON DELETE CASCADE,
+    FOREIGN KEY (maintenance_record_id) REFERENCES maintenance_records(id),
+    FOREIGN KEY (created_by) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_part_compatibility_part ON part_compatibility(part_id);
+CREATE INDEX IF NOT EXISTS idx_part_compatibility_type ON part_compatibility(component_type);
+CREATE INDEX IF NOT EXISTS idx_part_stock_movements_part ON part_stock_movements(part_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_part_stock_movements_record ON part_stock_movements(maintenance_record_id);
+"#;
+
+/// Spare parts inventory rollback SQL
+const PARTS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_part_stock_movements_record;
+DROP INDEX IF EXISTS idx_part_stock_movements_part;
+DROP INDEX IF EXISTS idx_part_compatibility_type;
+DROP INDEX IF EXISTS idx_part_compatibility_part;
+DROP TABLE IF EXISTS part_stock_movements;
+DROP TABLE IF EXISTS part_compatibility;
+DROP TABLE IF EXISTS parts;
+"#;
+
+/// Defect lifecycle migration SQL
+const DEFECTS_MIGRATION: &str = r#"
+-- Defects are opened by non-compliant items recorded from now on; earlier
+-- findings stay on their inspection items only
+CREATE TABLE IF NOT EXISTS defects (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    asset_id INTEGER NOT NULL,
+    component_id INTEGER,
+    title TEXT NOT NULL,
+    description TEXT,
+    severity TEXT CHECK(severity IS NULL OR severity IN ('Low', 'Medium', 'High', 'Critical')),
+    status TEXT NOT NULL DEFAULT 'Open' CHECK(status IN ('Open', 'Deferred', 'Repaired', 'Verified')),
+    first_item_id INTEGER NOT NULL,
+    latest_item_id INTEGER NOT NULL,
+    deferred_until DATETIME,
+    deferral_reason TEXT,
+    repaired_at DATETIME,
+    repaired_by INTEGER,
+    verified_at DATETIME,
+    verified_by INTEGER,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (asset_id) REFERENCES assets(id),
+    FOREIGN KEY (component_id) REFERENCES components(id),
+    FOREIGN KEY (repaired_by) REFERENCES users(id),
+    FOREIGN KEY (verified_by) REFERENCES users(id)
+);
+
+CREATE TABLE IF NOT EXISTS defect_observations (
+    defect_id INTEGER NOT NULL,
+    item_id INTEGER NOT NULL UNIQUE,
+    observed_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (defect_id, item_id),
+    FOREIGN KEY (defect_id) REFERENCES defects(id) ON DELETE CASCADE,
+    FOREIGN KEY (item_id) REFERENCES inspection_items(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS defect_severity_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    defect_id INTEGER NOT NULL,
+    severity TEXT,
+    item_id INTEGER,
+    changed_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (defect_id) REFERENCES defects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_defects_asset ON defects(asset_id, status);
+CREATE INDEX IF NOT EXISTS idx_defects_status ON defects(status);
+CREATE INDEX IF NOT EXISTS idx_defect_severity_history_defect ON defect_severity_history(defect_id, changed_at);
+"#;
+
+/// Defect lifecycle rollback SQL
+const DEFECTS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_defect_severity_history_defect;
+DROP INDEX IF EXISTS idx_defects_status;
+DROP INDEX IF EXISTS idx_defects_asset;
+DROP TABLE IF EXISTS defect_severity_history;
+DROP TABLE IF EXISTS defect_observations;
+DROP TABLE IF EXISTS defects;
+"#;
+
+/// JSON field schemas migration SQL
+const JSON_SCHEMAS_MIGRATION: &str = r#"
+-- One schema per field and scope: the asset type, component type,
+-- inspection type or standard code the JSON belongs to
+CREATE TABLE IF NOT EXISTS json_schemas (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    target TEXT NOT NULL CHECK(target IN ('AssetSpecifications', 'ComponentSpecifications', 'ChecklistData', 'StandardRequirements')),
+    scope TEXT NOT NULL COLLATE NOCASE,
+    schema TEXT NOT NULL CHECK(json_valid(schema)),
+    description TEXT,
+    updated_by INTEGER,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(target, scope),
+    FOREIGN KEY (updated_by) REFERENCES users(id)
+);
+"#;
+
+/// JSON field schemas rollback SQL
+const JSON_SCHEMAS_ROLLBACK: &str = r#"
+DROP TABLE IF EXISTS json_schemas;
+"#;
+
+/// Corrective actions migration SQL
+const CORRECTIVE_ACTIONS_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS corrective_actions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    inspection_item_id INTEGER NOT NULL,
+    inspection_id INTEGER NOT NULL,
+    asset_id INTEGER NOT NULL,
+    description TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'Open'
+        CHECK(status IN ('Open', 'In Progress', 'Pending Verification', 'Closed', 'Cancelled')),
+    assignee_id INTEGER,
+    due_date DATE,
+    completion_notes TEXT,
+    submitted_at DATETIME,
+    submitted_by INTEGER,
+    verified_at DATETIME,
+    verified_by INTEGER,
+    verification_notes TEXT,
+    created_by INTEGER,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (inspection_item_id) REFERENCES inspection_items(id),
+    FOREIGN KEY (inspection_id) REFERENCES inspections(id),
+    FOREIGN KEY (asset_id) REFERENCES assets(id),
+    FOREIGN KEY (assignee_id) REFERENCES users(id),
+    FOREIGN KEY (submitted_by) REFERENCES users(id),
+    FOREIGN KEY (verified_by) REFERENCES users(id),
+    FOREIGN KEY (created_by) REFERENCES users(id)
+);
+
+CREATE TABLE IF NOT EXISTS corrective_action_evidence (
+    action_id INTEGER NOT NULL,
+    media_id INTEGER NOT NULL,
+    attached_by INTEGER,
+    attached_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (action_id, media_id),
+    FOREIGN KEY (action_id) REFERENCES corrective_actions(id) ON DELETE CASCADE,
+    FOREIGN KEY (media_id) REFERENCES media_files(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_corrective_actions_item ON corrective_actions(inspection_item_id);
+CREATE INDEX IF NOT EXISTS idx_corrective_actions_assignee ON corrective_actions(assignee_id, status);
+CREATE INDEX IF NOT EXISTS idx_corrective_actions_status_due ON corrective_actions(status, due_date);
+"#;
+
+/// Corrective actions rollback SQL
+const CORRECTIVE_ACTIONS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_corrective_actions_status_due;
+DROP INDEX IF EXISTS idx_corrective_actions_assignee;
+DROP INDEX IF EXISTS idx_corrective_actions_item;
+DROP TABLE IF EXISTS corrective_action_evidence;
+DROP TABLE IF EXISTS corrective_actions;
+"#;
+
+/// Inspection validation rules migration SQL
+const VALIDATION_RULES_MIGRATION: &str = r#"
+ALTER TABLE inspection_items ADD COLUMN measured_value REAL;
+ALTER TABLE inspection_items ADD COLUMN measurement_unit TEXT;
+
+-- Photos taken for a particular inspection item
+CREATE TABLE IF NOT EXISTS inspection_item_media (
+    item_id INTEGER NOT NULL,
+    media_id INTEGER NOT NULL,
+    attached_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (item_id, media_id),
+    FOREIGN KEY (item_id) REFERENCES inspection_items(id) ON DELETE CASCADE,
+    FOREIGN KEY (media_id) REFERENCES media_files(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS validation_rules (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK(kind IN ('MeasurementRange', 'PhotoRequired', 'CorrectiveActionRequired')),
+    stage TEXT NOT NULL DEFAULT 'Submission' CHECK(stage IN ('Save', 'Submission')),
+    item_category TEXT,
+    item_name TEXT,
+    min_severity TEXT CHECK(min_severity IS NULL OR min_severity IN ('Low', 'Medium', 'High', 'Critical')),
+    min_value REAL,
+    max_value REAL,
+    unit TEXT,
+    is_active BOOLEAN NOT NULL DEFAULT 1,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_inspection_item_media_media ON inspection_item_media(media_id);
+
+-- Checked on submission so that photos and actions can be added after the
+-- item is first saved
+INSERT INTO validation_rules (name, kind, stage, min_severity) VALUES
+    ('Photo required for high severity findings', 'PhotoRequired', 'Submission', 'High'),
+    ('Corrective action required when non-compliant', 'CorrectiveActionRequired', 'Submission', NULL);
+"#;
+
+/// Inspection validation rules rollback SQL
+const VALIDATION_RULES_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_inspection_item_media_media;
+DROP TABLE IF EXISTS validation_rules;
+DROP TABLE IF EXISTS inspection_item_media;
+ALTER TABLE inspection_items DROP COLUMN measurement_unit;
+ALTER TABLE inspection_items DROP COLUMN measured_value;
+"#;
+
+/// Report share links migration SQL
+const REPORT_SHARE_LINKS_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS report_share_links (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    report_id TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    recipient TEXT,
+    expires_at DATETIME NOT NULL,
+    max_views INTEGER CHECK(max_views IS NULL OR max_views > 0),
+    view_count INTEGER NOT NULL DEFAULT 0,
+    last_viewed_at DATETIME,
+    created_by INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    revoked_at DATETIME,
+    FOREIGN KEY (created_by) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_report_share_links_report ON report_share_links(report_id);
+"#;
+
+/// Report share links rollback SQL
+const REPORT_SHARE_LINKS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_report_share_links_report;
+DROP TABLE IF EXISTS report_share_links;
+"#;
+
+/// Vendor documents migration SQL
+const VENDOR_DOCUMENTS_MIGRATION: &str = r#"
+-- Documents received by email; asset_id stays NULL until the document is
+-- matched or filed by hand
+CREATE TABLE IF NOT EXISTS vendor_documents (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    asset_id INTEGER,
+    sender TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    received_at DATETIME NOT NULL,
+    file_name TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    mime_type TEXT NOT NULL,
+    file_size INTEGER NOT NULL,
+    matched_reference TEXT,
+    filed_by INTEGER,
+    filed_at DATETIME,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE SET NULL,
+    FOREIGN KEY (filed_by) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_vendor_documents_asset ON vendor_documents(asset_id);
+"#;
+
+/// Vendor documents rollback SQL
+const VENDOR_DOCUMENTS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_vendor_documents_asset;
+DROP TABLE IF EXISTS vendor_documents;
+"#;
+
+/// Multi-factor authentication migration SQL
+const MFA_MIGRATION: &str = r#"
+-- enabled_at stays NULL until the first code confirms the enrollment
+CREATE TABLE IF NOT EXISTS user_mfa (
+    user_id INTEGER PRIMARY KEY,
+    secret TEXT NOT NULL,
+    enabled_at DATETIME,
+    last_used_step INTEGER,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS mfa_backup_codes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    code_hash TEXT NOT NULL,
+    used_at DATETIME,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS mfa_policies (
+    role TEXT PRIMARY KEY CHECK(role IN ('Inspector', 'Supervisor', 'Administrator', 'SuperAdmin')),
+    required BOOLEAN NOT NULL DEFAULT 0,
+    updated_by INTEGER,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (updated_by) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_mfa_backup_codes_user ON mfa_backup_codes(user_id);
+
+INSERT INTO mfa_policies (role, required) VALUES
+    ('Inspector', 0),
+    ('Supervisor', 0),
+    ('Administrator', 1),
+    ('SuperAdmin', 1);
+"#;
+
+/// Multi-factor authentication rollback SQL
+const MFA_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_mfa_backup_codes_user;
+DROP TABLE IF EXISTS mfa_policies;
+DROP TABLE IF EXISTS mfa_backup_codes;
+DROP TABLE IF EXISTS user_mfa;
+"#;
+
+/// User preferences migration SQL
+const USER_PREFERENCES_MIGRATION: &str = r#"
+-- notification_channels and table_layouts are JSON
+CREATE TABLE IF NOT EXISTS user_preferences (
+    user_id INTEGER PRIMARY KEY,
+    default_location_id INTEGER,
+    landing_page TEXT,
+    capacity_unit TEXT NOT NULL DEFAULT 'tonne',
+    length_unit TEXT NOT NULL DEFAULT 'millimeter',
+    notification_channels TEXT NOT NULL DEFAULT '["InApp","Email","Event"]',
+    table_layouts TEXT NOT NULL DEFAULT '{}',
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (default_location_id) REFERENCES locations(id) ON DELETE SET NULL
+);
+"#;
+
+/// User preferences rollback SQL
+const USER_PREFERENCES_ROLLBACK: &str = r#"
+DROP TABLE IF EXISTS user_preferences;
+"#;
+
+/// Kiosk terminals migration SQL
+const KIOSK_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS kiosk_terminals (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    location_id INTEGER,
+    key_hash TEXT NOT NULL UNIQUE,
+    idle_timeout_minutes INTEGER NOT NULL DEFAULT 5 CHECK(idle_timeout_minutes > 0),
+    is_active BOOLEAN NOT NULL DEFAULT 1,
+    created_by INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen_at DATETIME,
+    FOREIGN KEY (location_id) REFERENCES locations(id) ON DELETE SET NULL,
+    FOREIGN KEY (created_by) REFERENCES users(id)
+);
+
+-- badge_hash is SHA-256 of the badge value so it can be looked up;
+-- pin_hash is bcrypt
+CREATE TABLE IF NOT EXISTS kiosk_credentials (
+    user_id INTEGER PRIMARY KEY,
+    badge_hash TEXT NOT NULL UNIQUE,
+    pin_hash TEXT NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+"#;
+
+/// Kiosk terminals rollback SQL
+const KIOSK_ROLLBACK: &str = r#"
+DROP TABLE IF EXISTS kiosk_credentials;
+DROP TABLE IF EXISTS kiosk_terminals;
+"#;
+
+/// Password reset tokens migration SQL
+const PASSWORD_RESET_MIGRATION: &str = r#"
+-- requested_by is NULL when users asked for the reset themselves
+CREATE TABLE IF NOT EXISTS password_reset_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    expires_at DATETIME NOT NULL,
+    used_at DATETIME,
+    requested_by INTEGER,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (requested_by) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_password_reset_tokens_user ON password_reset_tokens(user_id);
+"#;
+
+/// Password reset tokens rollback SQL
+const PASSWORD_RESET_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_password_reset_tokens_user;
+DROP TABLE IF EXISTS password_reset_tokens;
+"#;
+
+/// Trusted devices migration SQL
+const TRUSTED_DEVICES_MIGRATION: &str = r#"
+-- device_key_hash is SHA-256 of the key kept on the device; pin_hash is bcrypt
+CREATE TABLE IF NOT EXISTS trusted_devices (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    device_key_hash TEXT NOT NULL UNIQUE,
+    pin_hash TEXT NOT NULL,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    locked_until DATETIME,
+    expires_at DATETIME NOT NULL,
+    last_used_at DATETIME,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    revoked_at DATETIME,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_trusted_devices_user ON trusted_devices(user_id);
+"#;
+
+/// Trusted devices rollback SQL
+const TRUSTED_DEVICES_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_trusted_devices_user;
+DROP TABLE IF EXISTS trusted_devices;
+"#;
+
+/// User authentication source migration SQL
+const USER_AUTH_SOURCE_MIGRATION: &str = r#"
+-- 'local' accounts check their bcrypt hash; 'ldap' accounts bind to the directory
+ALTER TABLE users ADD COLUMN auth_source TEXT NOT NULL DEFAULT 'local';
+"#;
+
+/// User authentication source rollback SQL
+const USER_AUTH_SOURCE_ROLLBACK: &str = r#"
+ALTER TABLE users DROP COLUMN auth_source;
+"#;
+
+/// Paper checklist transcription migration SQL
+const PAPER_TRANSCRIPTION_MIGRATION: &str = r#"
+-- Set when results were typed in from a paper checklist; the scan of the sheet stays with the inspection
+ALTER TABLE inspections ADD COLUMN paper_scan_media_id INTEGER REFERENCES media_files(id);
+ALTER TABLE inspections ADD COLUMN paper_transcribed_by INTEGER REFERENCES users(id);
+ALTER TABLE inspections ADD COLUMN paper_transcribed_at DATETIME;
+"#;
+
+/// Paper checklist transcription rollback SQL
+const PAPER_TRANSCRIPTION_ROLLBACK: &str = r#"
+ALTER TABLE inspections DROP COLUMN paper_transcribed_at;
+ALTER TABLE inspections DROP COLUMN paper_transcribed_by;
+ALTER TABLE inspections DROP COLUMN paper_scan_media_id;
+"#;
+
+/// User external subject migration SQL
+const USER_EXTERNAL_SUBJECT_MIGRATION: &str = r#"
+-- Identifier of the account at its identity provider, which unlike the username never changes
+ALTER TABLE users ADD COLUMN external_subject TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_external_subject ON users(auth_source, external_subject)
+    WHERE external_subject IS NOT NULL;
+"#;
+
+/// User external subject rollback SQL
+const USER_EXTERNAL_SUBJECT_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_users_external_subject;
+ALTER TABLE users DROP COLUMN external_subject;
+"#;
+
+/// Legal holds migration SQL
+const LEGAL_HOLDS_MIGRATION: &str = r#"
+-- A hold is active until released; released holds stay as the register's history
+CREATE TABLE IF NOT EXISTS legal_holds (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity_type TEXT NOT NULL CHECK(entity_type IN ('asset', 'inspection')),
+    entity_id INTEGER NOT NULL,
+    reason TEXT NOT NULL,
+    reference TEXT,
+    placed_by INTEGER NOT NULL REFERENCES users(id),
+    placed_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    released_by INTEGER REFERENCES users(id),
+    released_at DATETIME,
+    release_reason TEXT
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_legal_holds_active ON legal_holds(entity_type, entity_id)
+    WHERE released_at IS NULL;
+"#;
+
+/// Legal holds rollback SQL
+const LEGAL_HOLDS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_legal_holds_active;
+DROP TABLE IF EXISTS legal_holds;
+"#;
+
+/// Server-side sessions migration SQL
+const SESSIONS_MIGRATION: &str = r#"
+-- The token only names its session; a revoked or missing row ends it
+CREATE TABLE IF NOT EXISTS sessions (
+    session_id TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    created_at DATETIME NOT NULL,
+    expires_at DATETIME NOT NULL,
+    last_activity DATETIME NOT NULL,
+    locale TEXT NOT NULL DEFAULT 'en',
+    kiosk_terminal_id INTEGER REFERENCES kiosk_terminals(id),
+    idle_timeout_minutes INTEGER,
+    revoked_at DATETIME,
+    revoked_by INTEGER REFERENCES users(id),
+    revoke_reason TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_sessions_user ON sessions(user_id) WHERE revoked_at IS NULL;
+"#;
+
+/// Server-side sessions rollback SQL
+const SESSIONS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_sessions_user;
+DROP TABLE IF EXISTS sessions;
+"#;
+
+/// Media fingerprints migration SQL
+const MEDIA_FINGERPRINTS_MIGRATION: &str = r#"
+-- SHA-256 of the file, and the EXIF capture time by the camera's clock
+ALTER TABLE media_files ADD COLUMN content_hash TEXT;
+ALTER TABLE media_files ADD COLUMN captured_at DATETIME;
+
+CREATE INDEX IF NOT EXISTS idx_media_files_content_hash ON media_files(content_hash);
+"#;
+
+/// Media fingerprints rollback SQL
+const MEDIA_FINGERPRINTS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_media_files_content_hash;
+ALTER TABLE media_files DROP COLUMN captured_at;
+ALTER TABLE media_files DROP COLUMN content_hash;
+"#;
+
+/// Rate limit violations migration SQL
+const RATE_LIMIT_VIOLATIONS_MIGRATION: &str = r#"
+-- One row per run of refused attempts, not per refused attempt
+CREATE TABLE IF NOT EXISTS rate_limit_violations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    action TEXT NOT NULL,
+    scope TEXT NOT NULL CHECK(scope IN ('username', 'source')),
+    subject TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    window_seconds INTEGER NOT NULL,
+    retry_after_seconds INTEGER NOT NULL,
+    occurred_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_limit_violations_occurred_at ON rate_limit_violations(occurred_at);
+"#;
+
+/// Rate limit violations rollback SQL
+const RATE_LIMIT_VIOLATIONS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_rate_limit_violations_occurred_at;
+DROP TABLE IF EXISTS rate_limit_violations;
+"#;
+
+/// Notification digests migration SQL
+const NOTIFICATION_DIGESTS_MIGRATION: &str = r#"
+-- Users without a row get one email per notification. send_at is the local
+-- time of day in time_zone; last_sent_at bounds the next digest's content.
+CREATE TABLE IF NOT EXISTS notification_digests (
+    user_id INTEGER PRIMARY KEY,
+    delivery TEXT NOT NULL DEFAULT 'Immediate' CHECK(delivery IN ('Immediate', 'DailyDigest')),
+    send_at TEXT NOT NULL DEFAULT '07:00',
+    time_zone TEXT NOT NULL DEFAULT 'UTC',
+    last_sent_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+"#;
+
+/// Notification digests rollback SQL
+const NOTIFICATION_DIGESTS_ROLLBACK: &str = r#"
+DROP TABLE IF EXISTS notification_digests;
+"#;
+
+/// Login attempts migration SQL
+const LOGIN_ATTEMPTS_MIGRATION: &str = r#"
+-- Wrong passwords since the user's last successful sign-in. The account is
+-- locked while locked_until is in the future.
+CREATE TABLE IF NOT EXISTS login_attempts (
+    user_id INTEGER PRIMARY KEY,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    last_failed_at DATETIME,
+    locked_at DATETIME,
+    locked_until DATETIME,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+"#;
+
+/// Login attempts rollback SQL
+const LOGIN_ATTEMPTS_ROLLBACK: &str = r#"
+DROP TABLE IF EXISTS login_attempts;
+"#;
+
+/// Roles migration SQL
+const ROLES_MIGRATION: &str = r#"
+-- One built-in row per users.role value; custom roles name the built-in
+-- role whose other rules apply to their users
+CREATE TABLE IF NOT EXISTS roles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    description TEXT,
+    base_role TEXT NOT NULL CHECK(base_role IN ('Inspector', 'Supervisor', 'Administrator', 'SuperAdmin')),
+    is_builtin BOOLEAN NOT NULL DEFAULT 0,
+    created_by INTEGER,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (created_by) REFERENCES users(id)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_roles_builtin ON roles(base_role) WHERE is_builtin;
+
+CREATE TABLE IF NOT EXISTS role_permissions (
+    role_id INTEGER NOT NULL,
+    permission TEXT NOT NULL,
+    PRIMARY KEY (role_id, permission),
+    FOREIGN KEY (role_id) REFERENCES roles(id) ON DELETE CASCADE
+);
+
+-- Set for users given a custom role; users.role then holds its base role
+ALTER TABLE users ADD COLUMN custom_role_id INTEGER REFERENCES roles(id);
+"#;
+
+/// Roles rollback SQL
+const ROLES_ROLLBACK: &str = r#"
+ALTER TABLE users DROP COLUMN custom_role_id;
+DROP TABLE IF EXISTS role_permissions;
+DROP INDEX IF EXISTS idx_roles_builtin;
+DROP TABLE IF EXISTS roles;
+"#;
+
+/// API keys migration SQL
+const API_KEYS_MIGRATION: &str = r#"
+-- Only a SHA-256 of each key is stored; the prefix is kept to tell keys apart
+CREATE TABLE IF NOT EXISTS api_keys (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    key_prefix TEXT NOT NULL,
+    key_hash TEXT NOT NULL UNIQUE,
+    created_by INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at DATETIME,
+    last_used_at DATETIME,
+    revoked_at DATETIME,
+    revoked_by INTEGER,
+    FOREIGN KEY (created_by) REFERENCES users(id),
+    FOREIGN KEY (revoked_by) REFERENCES users(id)
+);
+
+CREATE TABLE IF NOT EXISTS api_key_permissions (
+    api_key_id INTEGER NOT NULL,
+    permission TEXT NOT NULL,
+    PRIMARY KEY (api_key_id, permission),
+    FOREIGN KEY (api_key_id) REFERENCES api_keys(id) ON DELETE CASCADE
+);
+
+-- Administrators manage keys; rows seeded before this permission existed
+-- are given it here, new ones get it from the role defaults
+INSERT OR IGNORE INTO role_permissions (role_id, permission)
+SELECT id, 'user:api_keys' FROM roles WHERE is_builtin AND base_role = 'Administrator';
+"#;
+
+/// API keys rollback SQL
+const API_KEYS_ROLLBACK: &str = r#"
+DELETE FROM role_permissions WHERE permission = 'user:api_keys';
+DROP TABLE IF EXISTS api_key_permissions;
+DROP TABLE IF EXISTS api_keys;
+"#;
+
+/// Outbox migration SQL
+const OUTBOX_MIGRATION: &str = r#"
+-- Each row is one delivery to make, kept until it succeeds or is given up on
+CREATE TABLE IF NOT EXISTS outbox (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    message TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at DATETIME NOT NULL,
+    last_error TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    delivered_at DATETIME,
+    abandoned_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbox_pending ON outbox(next_attempt_at)
+    WHERE delivered_at IS NULL AND abandoned_at IS NULL;
+"#;
+
+/// Outbox rollback SQL
+const OUTBOX_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_outbox_pending;
+DROP TABLE IF EXISTS outbox;
+"#;
+
+/// AI job trace ID migration SQL
+const AI_JOB_TRACE_MIGRATION: &str = r#"
+ALTER TABLE ai_model_results ADD COLUMN trace_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_ai_model_results_trace ON ai_model_results(trace_id)
+    WHERE trace_id IS NOT NULL;
+"#;
+
+/// AI job trace ID rollback SQL
+const AI_JOB_TRACE_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_ai_model_results_trace;
+ALTER TABLE ai_model_results DROP COLUMN trace_id;
+"#;
+
+/// Location working calendar migration SQL
+const LOCATION_CALENDAR_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS location_calendars (
+    location_id INTEGER PRIMARY KEY REFERENCES locations(id) ON DELETE CASCADE,
+    working_days TEXT NOT NULL,
+    updated_by INTEGER REFERENCES users(id),
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS location_closures (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    location_id INTEGER NOT NULL REFERENCES locations(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('Holiday', 'Shutdown')),
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL CHECK (end_date >= start_date),
+    created_by INTEGER REFERENCES users(id),
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_location_closures_location ON location_closures(location_id, start_date);
+"#;
+
+/// Location working calendar rollback SQL
+const LOCATION_CALENDAR_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_location_closures_location;
+DROP TABLE IF EXISTS location_closures;
+DROP TABLE IF EXISTS location_calendars;
+"#;
+
+/// User email blind index migration SQL
+///
+/// Existing addresses are encrypted and indexed at startup, when the key
+/// is available.
+const USER_EMAIL_INDEX_MIGRATION: &str = r#"
+ALTER TABLE users ADD COLUMN email_index TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_email_index ON users(email_index)
+    WHERE email_index IS NOT NULL;
+"#;
+
+/// User email blind index rollback SQL
+const USER_EMAIL_INDEX_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_users_email_index;
+ALTER TABLE users DROP COLUMN email_index;
+"#;
+
+/// Security events migration SQL
+///
+/// Like the audit log, events keep the user's name rather than a foreign key
+/// so they outlive the account.
+const SECURITY_EVENTS_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS security_events (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL CHECK (kind IN ('failed_login', 'permission_denied', 'account_locked', 'token_anomaly')),
+    user_id INTEGER,
+    username TEXT,
+    request_id TEXT,
+    detail TEXT NOT NULL,
+    occurred_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_security_events_occurred ON security_events(occurred_at);
+CREATE INDEX IF NOT EXISTS idx_security_events_kind ON security_events(kind, occurred_at);
+CREATE INDEX IF NOT EXISTS idx_security_events_user ON security_events(user_id, occurred_at);
+"#;
+
+/// Security events rollback SQL
+const SECURITY_EVENTS_ROLLBACK: &str = r#"
+DROP TABLE IF EXISTS security_events;
+"#;
+
+/// Asset link tokens migration SQL
+///
+/// Tokens are issued the first time an asset's label link is asked for.
+const ASSET_LINK_TOKENS_MIGRATION: &str = r#"
+ALTER TABLE assets ADD COLUMN link_token TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_assets_link_token ON assets(link_token)
+    WHERE link_token IS NOT NULL;
+"#;
+
+/// Asset link tokens rollback SQL
+const ASSET_LINK_TOKENS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_assets_link_token;
+ALTER TABLE assets DROP COLUMN link_token;
+"#;
+
+/// Login history migration SQL
+const LOGIN_HISTORY_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS login_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    username TEXT NOT NULL,
+    method TEXT NOT NULL CHECK (method IN ('password', 'single_sign_on', 'second_factor', 'kiosk', 'device_unlock')),
+    client_id TEXT,
+    device_name TEXT,
+    success BOOLEAN NOT NULL,
+    failure_reason TEXT,
+    occurred_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_login_history_user ON login_history(user_id, occurred_at);
+CREATE INDEX IF NOT EXISTS idx_login_history_occurred ON login_history(occurred_at);
+"#;
+
+/// Login history rollback SQL
+const LOGIN_HISTORY_ROLLBACK: &str = r#"
+DROP TABLE IF EXISTS login_history;
+"#;
+
+/// Custom report templates migration SQL: Handlebars templates sites edit
+/// to match their own format, and the one used for each report type
+const REPORT_TEMPLATES_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS report_templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    report_type TEXT NOT NULL CHECK (report_type IN ('inspection', 'compliance')),
+    description TEXT,
+    template TEXT NOT NULL,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS report_template_assignments (
+    report_type TEXT PRIMARY KEY CHECK (report_type IN ('inspection', 'compliance')),
+    template_id INTEGER NOT NULL REFERENCES report_templates(id) ON DELETE CASCADE,
+    assigned_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    assigned_at DATETIME NOT NULL
+);
+"#;
+
+/// Custom report templates rollback SQL
+const REPORT_TEMPLATES_ROLLBACK: &str = r#"
+DROP TABLE IF EXISTS report_template_assignments;
+DROP TABLE IF EXISTS report_templates;
+"#;
+
+/// Checklist packs migration SQL: inspection intervals set per standard,
+/// overriding the defaults of each inspection type, and the publishers
+/// whose signed packs may be imported
+const CHECKLIST_PACKS_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS inspection_frequency_rules (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    standard_id INTEGER NOT NULL REFERENCES compliance_standards(id) ON DELETE CASCADE,
+    inspection_type TEXT NOT NULL CHECK(inspection_type IN ('Frequent', 'Periodic', 'Initial', 'Special')),
+    interval_days INTEGER NOT NULL CHECK (interval_days > 0),
+    updated_at DATETIME NOT NULL,
+    UNIQUE(standard_id, inspection_type)
+);
+
+CREATE TABLE IF NOT EXISTS trusted_pack_publishers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    public_key TEXT NOT NULL UNIQUE,
+    added_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at DATETIME NOT NULL
+);
+"#;
+
+/// Checklist packs rollback SQL
+const CHECKLIST_PACKS_ROLLBACK: &str = r#"
+DROP TABLE IF EXISTS trusted_pack_publishers;
+DROP TABLE IF EXISTS inspection_frequency_rules;
+"#;
+
+/// CMAA service class and FEM mechanism group of each crane, both optional
+/// until someone records them
+const CRANE_CLASSIFICATION_MIGRATION: &str = r#"
+ALTER TABLE assets ADD COLUMN service_class TEXT;
+ALTER TABLE assets ADD COLUMN fem_group TEXT;
+"#;
+
+const CRANE_CLASSIFICATION_ROLLBACK: &str = r#"
+ALTER TABLE assets DROP COLUMN fem_group;
+ALTER TABLE assets DROP COLUMN service_class;
+"#;
+
+/// Generated report files, with what they were generated from so an
+/// unchanged report can be handed out again, and when they expire
+const REPORT_ARTIFACTS_MIGRATION: &str = r#"
+CREATE TABLE IF NOT EXISTS report_artifacts (
+    file_path TEXT PRIMARY KEY,
+    report_id TEXT NOT NULL,
+    report_type TEXT NOT NULL,
+    cache_key TEXT NOT NULL,
+    input_fingerprint TEXT NOT NULL,
+    generated_by INTEGER,
+    generated_at DATETIME NOT NULL,
+    expires_at DATETIME NOT NULL,
+    FOREIGN KEY (generated_by) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_report_artifacts_report ON report_artifacts(report_id);
+CREATE INDEX IF NOT EXISTS idx_report_artifacts_cache_key ON report_artifacts(cache_key, generated_at);
+CREATE INDEX IF NOT EXISTS idx_report_artifacts_expires ON report_artifacts(expires_at);
+"#;
+
+const REPORT_ARTIFACTS_ROLLBACK: &str = r#"
+DROP INDEX IF EXISTS idx_report_artifacts_expires;
+DROP INDEX IF EXISTS idx_report_artifacts_cache_key;
+DROP INDEX IF EXISTS idx_report_artifacts_report;
+DROP TABLE IF EXISTS report_artifacts;
+"#;
+
+/// Kiosk badge lockout migration SQL
+const KIOSK_BADGE_LOCKOUT_MIGRATION: &str = r#"
+ALTER TABLE kiosk_credentials ADD COLUMN failed_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE kiosk_credentials ADD COLUMN locked_until DATETIME;
+"#;
+
+/// Kiosk badge lockout rollback SQL
+const KIOSK_BADGE_LOCKOUT_ROLLBACK: &str = r#"
+ALTER TABLE kiosk_credentials DROP COLUMN locked_until;
+ALTER TABLE kiosk_credentials DROP COLUMN failed_attempts;
+"#;
+
+/// Media uploader migration SQL: unlinked files are only open to whoever
+/// uploaded them
+const MEDIA_UPLOADER_MIGRATION: &str = r#"
+ALTER TABLE media_files ADD COLUMN uploaded_by INTEGER REFERENCES users(id);
+"#;
+
+/// Media uploader rollback SQL
+const MEDIA_UPLOADER_ROLLBACK: &str = r#"
+ALTER TABLE media_files DROP COLUMN uploaded_by;
+"#;
+
+/// Stored refresh tokens and login challenges migration SQL
+const AUTH_TOKENS_MIGRATION: &str = r#"
+-- Only the SHA-256 of each token is kept. Every token rotated from one
+-- sign-in shares its family; a used token presented again revokes the family.
+CREATE TABLE IF NOT EXISTS refresh_tokens (
+    token_hash TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL REFERENCES sessions(session_id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    family_id TEXT NOT NULL,
+    expires_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    used_at DATETIME,
+    revoked_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_session ON refresh_tokens(session_id);
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_family ON refresh_tokens(family_id);
+
+-- Logins that passed the password check and await their second factor
+CREATE TABLE IF NOT EXISTS mfa_challenges (
+    challenge_hash TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at DATETIME NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0
+);
+"#;
+
+/// Stored refresh tokens and login challenges rollback SQL
+const AUTH_TOKENS_ROLLBACK: &str = r#"
+DROP TABLE IF EXISTS mfa_challenges;
+DROP INDEX IF EXISTS idx_refresh_tokens_family;
+DROP INDEX IF EXISTS idx_refresh_tokens_session;
+DROP TABLE IF EXISTS refresh_tokens;
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pool_connections_share_database() {
+        let db = Database::new_in_memory().await.unwrap();
+        let first = db.get_connection().unwrap();
+        let second = db.get_connection().unwrap();
+        first.execute("CREATE TABLE pool_probe (id INTEGER)", []).unwrap();
+        first.execute("INSERT INTO pool_probe VALUES (1)", []).unwrap();
+
+        let count: i64 = second.query_row("SELECT COUNT(*) FROM pool_probe", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 1);
+        let version: i32 = second.query_row("SELECT version FROM schema_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, CURRENT_SCHEMA_VERSION);
+    }
+
+    #[tokio::test]
+    async fn test_file_database_uses_wal_and_configured_pragmas() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = DatabaseConfig {
+            busy_timeout: Duration::from_millis(250),
+            pragmas: vec![("cache_size".to_string(), "-2000".to_string())],
+            ..DatabaseConfig::file(dir.path().join("pool.db"))
+        };
+        let db = Database::new(config).await.unwrap();
+        let conn = db.get_connection().unwrap();
+
+        let mode: String = conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(mode, "wal");
+        let timeout: i64 = conn.query_row("PRAGMA busy_timeout", [], |row| row.get(0)).unwrap();
+        assert_eq!(timeout, 250);
+        let cache: i64 = conn.query_row("PRAGMA cache_size", [], |row| row.get(0)).unwrap();
+        assert_eq!(cache, -2000);
+
+        let invalid = DatabaseConfig {
+            pragmas: vec![("cache_size".to_string(), "1; DROP TABLE users".to_string())],
+            ..DatabaseConfig::in_memory()
+        };
+        assert!(DatabasePool::with_config(invalid).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_pool_sees_writes_and_rejects_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = DatabaseConfig {
+            read_pool_size: 1,
+            ..DatabaseConfig::file(dir.path().join("reads.db"))
+        };
+        for db in [Database::new(config).await.unwrap(), Database::new_in_memory().await.unwrap()] {
+            db.with_transaction(|conn| {
+                conn.execute("CREATE TABLE read_probe (id INTEGER)", [])?;
+                conn.execute("INSERT INTO read_probe VALUES (1)", [])?;
+                Ok(())
+            }).unwrap();
+
+            let reader = db.get_read_connection().unwrap();
+            let count: i64 = reader.query_row("SELECT COUNT(*) FROM read_probe", [], |row| row.get(0)).unwrap();
+            assert_eq!(count, 1);
+            assert!(reader.execute("INSERT INTO read_probe VALUES (2)", []).is_err());
+
+            // With WAL a long read leaves the writer free
+            if db.config().path.is_some() {
+                let mut stmt = reader.prepare("SELECT id FROM read_probe").unwrap();
+                let mut rows = stmt.query([]).unwrap();
+                assert!(rows.next().unwrap().is_some());
+                db.with_transaction(|conn| {
+                    conn.execute("INSERT INTO read_probe VALUES (3)", [])?;
+                    Ok(())
+                }).unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reports_run_while_a_write_is_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = DatabaseConfig {
+            busy_timeout: Duration::from_millis(100),
+            ..DatabaseConfig::file(dir.path().join("reports.db"))
+        };
+        let database = Arc::new(Database::new(config).await.unwrap());
+        let cipher = Arc::new(crate::security::fields::FieldCipher::ephemeral().unwrap());
+        let services = crate::services::Services::init(database.clone(), cipher).await.unwrap();
+        database.with_transaction(|conn| {
+            conn.execute("INSERT INTO locations (name, created_by) VALUES ('Bay 3', 1)", [])?;
+            conn.execute(
+                "INSERT INTO assets (asset_number, asset_name, asset_type, location_id, created_by)
+                 VALUES ('OHC-1', 'Bay 3 Overhead', 'Overhead Crane', 1, 1)",
+                [],
+            )?;
+            Ok(())
+        }).unwrap();
+
+        // The report reads the last committed state without waiting for the writer
+        let report = database.with_transaction(|conn| {
+            conn.execute(
+                "INSERT INTO assets (asset_number, asset_name, asset_type, location_id, created_by)
+                 VALUES ('GAN-1', 'Bay 3 Gantry', 'Gantry Crane', 1, 1)",
+                [],
+            )?;
+            services.reports.generate_compliance_status_report(None)
+        }).unwrap();
+        assert_eq!(report.total_assets, 1);
+        assert_eq!(services.reports.generate_compliance_status_report(None).unwrap().total_assets, 2);
+    }
+
+    #[tokio::test]
+    async fn test_rollback_and_rerun_migrations() {
+        let db = Database::new_in_memory().await.unwrap();
+        let view_exists = |db: &Database| -> bool {
+            let conn = db.get_connection().unwrap();
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'view' AND name = 'asset_cards'",
+                [],
+                |row| row.get(0),
+            ).unwrap();
+            db.return_connection(conn);
+            count == 1
+        };
+
+        // Version 13 added the asset card view
+        let rolled_back = (CURRENT_SCHEMA_VERSION - 12) as usize;
+        let results = db.rollback_to_version(12).unwrap();
+        assert_eq!(results.len(), rolled_back);
+        assert!(results.iter().all(|r| r.success));
+        assert!(!view_exists(&db));
+        let status = db.migration_status().unwrap();
+        assert_eq!(status.current_version, 12);
+        assert!(status.migrations.last().unwrap().applied_at.is_none());
+
+        let results = db.run_pending_migrations().unwrap();
+        assert_eq!(results.len(), rolled_back);
+        assert!(view_exists(&db));
+        assert_eq!(db.migration_status().unwrap().current_version, CURRENT_SCHEMA_VERSION);
+
+        assert!(db.rollback_to_version(0).is_err());
+
+        // Nothing was snapshotted to go back to
+        assert!(db.rollback_last_upgrade().unwrap_err().to_string().contains("in-memory"));
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_snapshot_and_rollback() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = DatabaseConfig::file(dir.path().join("upgrade.db"));
+        let probe = |db: &Database| -> Vec<i64> {
+            let conn = db.get_connection().unwrap();
+            let ids = conn.prepare("SELECT id FROM upgrade_probe ORDER BY id").unwrap()
+                .query_map([], |row| row.get(0)).unwrap()
+                .collect::<rusqlite::Result<Vec<i64>>>().unwrap();
+            db.return_connection(conn);
+            ids
+        };
+
+        // A database left at an older schema by a previous release
+        {
+            let db = Database::new(config.clone()).await.unwrap();
+            assert!(db.last_upgrade_snapshot().unwrap().is_none());
+            db.rollback_to_version(50).unwrap();
+            db.with_transaction(|conn| {
+                conn.execute_batch("CREATE TABLE upgrade_probe (id INTEGER PRIMARY KEY); INSERT INTO upgrade_probe VALUES (1);")?;
+                Ok(())
+            }).unwrap();
+        }
+
+        let db = Database::new(config).await.unwrap();
+        let snapshot = db.last_upgrade_snapshot().unwrap().unwrap();
+        assert_eq!((snapshot.from_schema_version, snapshot.to_schema_version), (50, CURRENT_SCHEMA_VERSION));
+        assert_eq!(snapshot.app_version, env!("CARGO_PKG_VERSION"));
+        assert!(Path::new(&snapshot.snapshot_path).is_file());
+
+        db.with_transaction(|conn| {
+            conn.execute("INSERT INTO upgrade_probe VALUES (2)", [])?;
+            Ok(())
+        }).unwrap();
+        assert_eq!(probe(&db), [1, 2]);
+
+        let restored = db.rollback_last_upgrade().unwrap();
+        assert!(restored.restored_at.is_some());
+        assert_eq!(probe(&db), [1]);
+        assert_eq!(db.migration_status().unwrap().current_version, 50);
+        assert!(db.rollback_last_upgrade().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_runs_are_logged() {
+        let db = Database::new_in_memory().await.unwrap();
+        let runs = db.run_maintenance(&MaintenanceTask::ALL, Some(1)).unwrap();
+        assert_eq!(runs.len(), 3);
+        assert!(runs.iter().all(|run| run.success && !run.scheduled));
+
+        let history = db.maintenance_history(10).unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].task, MaintenanceTask::Vacuum);
+        assert_eq!(history[1].details, "ok");
+    }
+}
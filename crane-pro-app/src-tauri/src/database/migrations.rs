@@ -96,7 +96,7 @@ impl Migration {
 }
 
 /// Migration execution result with detailed information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MigrationResult {
     pub version: i32,
     pub name: String,
@@ -107,7 +107,7 @@ pub struct MigrationResult {
 }
 
 /// Progress tracking for migration operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MigrationProgress {
     pub total_migrations: usize,
     pub completed_migrations: usize,
@@ -117,6 +117,28 @@ pub struct MigrationProgress {
     pub estimated_completion: Option<DateTime<Utc>>,
 }
 
+/// A known migration and whether it is applied to the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationInfo {
+    pub version: i32,
+    pub name: String,
+    pub description: String,
+    pub applied_at: Option<DateTime<Utc>>,
+    /// Whether the migration has rollback SQL
+    pub reversible: bool,
+}
+
+/// Schema state of a database and the progress of the latest run or rollback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationStatus {
+    pub current_version: i32,
+    pub latest_version: i32,
+    pub migrations: Vec<MigrationInfo>,
+    pub progress: MigrationProgress,
+    /// Outcomes of migration runs and rollbacks since the application started
+    pub results: Vec<MigrationResult>,
+}
+
 /// Enhanced Migration Runner with comprehensive features
 pub struct MigrationRunner {
     /// Thread-safe storage for migration results and progress
@@ -353,25 +375,13 @@ impl MigrationRunner {
                 message: format!("Failed to start transaction for migration {}: {}", migration.version, e),
             })?;
 
-        // Execute migration SQL statements
-        let statements: Vec<&str> = migration.up_sql
-            .split(';')
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        for (i, statement) in statements.iter().enumerate() {
-            debug!("Executing statement {}/{} for migration {}", i + 1, statements.len(), migration.version);
-            
-            if let Err(e) = tx.execute(statement, []) {
-                let _ = tx.rollback();
-                return Err(AppError::Database {
-                    message: format!(
-                        "Failed to execute statement in migration {}: {}. Statement: {}",
-                        migration.version, e, statement
-                    ),
-                });
-            }
+        // Execute as a batch so trigger bodies containing ';' stay intact
+        debug!("Executing SQL for migration {}", migration.version);
+        if let Err(e) = tx.execute_batch(&migration.up_sql) {
+            let _ = tx.rollback();
+            return Err(AppError::Database {
+                message: format!("Failed to execute migration {}: {}", migration.version, e),
+            });
         }
 
         // Commit transaction
@@ -477,22 +487,14 @@ impl MigrationRunner {
                 message: format!("Failed to start rollback transaction for migration {}: {}", migration.version, e),
             })?;
 
-        let statements: Vec<&str> = migration.down_sql
-            .split(';')
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        for statement in statements {
-            if let Err(e) = tx.execute(statement, []) {
-                let _ = tx.rollback();
-                return Err(AppError::Database {
-                    message: format!(
-                        "Failed to execute rollback statement for migration {}: {}",
-                        migration.version, e
-                    ),
-                });
-            }
+        if let Err(e) = tx.execute_batch(&migration.down_sql) {
+            let _ = tx.rollback();
+            return Err(AppError::Database {
+                message: format!(
+                    "Failed to execute rollback statement for migration {}: {}",
+                    migration.version, e
+                ),
+            });
         }
 
         tx.commit().map_err(|e| AppError::Database {
@@ -526,6 +528,43 @@ impl MigrationRunner {
         Ok(())
     }
 
+    /// Bring the migration history in line with a schema version applied by
+    /// other means: record known migrations up to `version` as applied and
+    /// drop records above it
+    pub fn sync_history(&self, conn: &Connection, version: i32) -> AppResult<()> {
+        self.ensure_migration_history_table(conn)?;
+        conn.execute("DELETE FROM migration_history WHERE version > ?1", [version])?;
+
+        let applied = self.get_applied_migrations(conn)?;
+        for migration in self.get_all_migrations() {
+            if migration.version <= version && !applied.contains(&migration.version) {
+                debug!("Recording migration {} as already applied", migration.version);
+                self.record_migration_applied(conn, migration)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Every known migration with the time it was applied, if it was
+    pub fn get_migration_infos(&self, conn: &Connection) -> AppResult<Vec<MigrationInfo>> {
+        self.ensure_migration_history_table(conn)?;
+        let mut stmt = conn.prepare("SELECT version, applied_at FROM migration_history")?;
+        let applied: HashMap<i32, DateTime<Utc>> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(self.get_all_migrations()
+            .into_iter()
+            .map(|migration| MigrationInfo {
+                version: migration.version,
+                name: migration.name.clone(),
+                description: migration.description.clone(),
+                applied_at: applied.get(&migration.version).copied(),
+                reversible: !migration.down_sql.trim().is_empty(),
+            })
+            .collect())
+    }
+
     /// Get list of applied migration versions
     fn get_applied_migrations(&self, conn: &Connection) -> AppResult<Vec<i32>> {
         let mut stmt = conn.prepare("SELECT version FROM migration_history ORDER BY version")
@@ -11,7 +11,10 @@ pub mod core;
 pub mod migrations;
 
 // Export core database functionality (for backward compatibility)
-pub use core::{Database, DatabasePool, LegacyMigration, LegacyMigrationManager};
+pub use core::{
+    run_scheduled_maintenance, Database, DatabaseConfig, DatabasePool, LegacyMigration,
+    LegacyMigrationManager, MaintenanceRun, MaintenanceTask, ReadConnection, UpgradeSnapshot,
+};
 
 // Export enhanced migration infrastructure
-pub use migrations::{Migration, MigrationRunner, MigrationResult, MigrationProgress};
\ No newline at end of file
+pub use migrations::{Migration, MigrationInfo, MigrationRunner, MigrationResult, MigrationProgress, MigrationStatus};
\ No newline at end of file
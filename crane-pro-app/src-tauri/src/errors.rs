@@ -60,6 +60,15 @@ pub enum AppError {
         max: String,
     },
 
+    #[error("Invalid query parameter for {entity}: {parameter} = {value} - {reason}")]
+    InvalidQueryParameter {
+        entity: String,
+        parameter: String,
+        value: String,
+        reason: String,
+        allowed: Vec<String>,
+    },
+
     // File System Errors
     #[error("File operation failed: {operation} on {path} - {reason}")]
     FileSystem {
@@ -118,6 +127,9 @@ pub enum AppError {
     #[error("Decryption failed: {reason}")]
     Decryption { reason: String },
 
+    #[error("Too many {action} attempts: try again in {retry_after}s")]
+    RateLimited { action: String, retry_after: u64 },
+
     // Network Errors
     #[error("Network request failed: {method} {url} - {status}: {message}")]
     NetworkRequest {
@@ -193,6 +205,9 @@ pub enum AppError {
     #[error("Resource unavailable: {resource}")]
     ResourceUnavailable { resource: String },
 
+    #[error("Operation cancelled: {operation}")]
+    Cancelled { operation: String },
+
     #[error("External service error: {service} - {message}")]
     ExternalService { service: String, message: String },
 }
@@ -252,7 +267,8 @@ impl AppError {
             Self::Validation { .. }
             | Self::RequiredField { .. }
             | Self::InvalidFormat { .. }
-            | Self::OutOfRange { .. } => "validation",
+            | Self::OutOfRange { .. }
+            | Self::InvalidQueryParameter { .. } => "validation",
 
             Self::FileSystem { .. }
             | Self::FileNotFound { .. }
@@ -268,7 +284,8 @@ impl AppError {
             | Self::Authorization { .. }
             | Self::Token { .. }
             | Self::Encryption { .. }
-            | Self::Decryption { .. } => "security",
+            | Self::Decryption { .. }
+            | Self::RateLimited { .. } => "security",
 
             Self::NetworkRequest { .. }
             | Self::ConnectionTimeout { .. }
@@ -290,6 +307,7 @@ impl AppError {
             Self::Internal { .. }
             | Self::Timeout { .. }
             | Self::ResourceUnavailable { .. }
+            | Self::Cancelled { .. }
             | Self::ExternalService { .. } => "system",
         }
     }
@@ -315,6 +333,7 @@ impl AppError {
             | Self::RequiredField { .. }
             | Self::InvalidFormat { .. }
             | Self::OutOfRange { .. }
+            | Self::InvalidQueryParameter { .. }
             | Self::InvalidFileFormat { .. }
             | Self::UnsupportedImageFormat { .. }
             | Self::ImageTooLarge { .. } => 400,
@@ -323,7 +342,7 @@ impl AppError {
 
             Self::Authorization { .. } | Self::PermissionDenied { .. } => 403,
 
-            Self::DuplicateRecord { .. } => 409,
+            Self::DuplicateRecord { .. } | Self::Cancelled { .. } => 409,
 
             Self::ConnectionTimeout { .. } | Self::Timeout { .. } => 408,
 
@@ -331,7 +350,7 @@ impl AppError {
             | Self::AiServiceUnavailable { .. }
             | Self::ExternalService { .. } => 503,
 
-            Self::AiQuotaExceeded { .. } => 429,
+            Self::AiQuotaExceeded { .. } | Self::RateLimited { .. } => 429,
 
             _ => 500,
         }
@@ -0,0 +1,295 @@
+//! Streaming data export
+//!
+//! Full-fleet exports can hold hundreds of thousands of rows, so they are
+//! never collected into memory. Services visit rows one at a time straight
+//! off the SQLite cursor (or the audit log files) and each row is written to
+//! a buffered output file as it arrives. Progress is reported every
+//! [`PROGRESS_INTERVAL_ROWS`] rows so the frontend can show a progress bar.
+
+use crate::errors::{AppError, AppResult};
+use crate::exporters::csv::write_csv_line;
+use crate::middleware::AuditLogEntry;
+use crate::models::{Asset, Inspection};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// Tauri event carrying [`ExportProgress`] updates
+pub const EXPORT_PROGRESS_EVENT: &str = "export-progress";
+
+/// Number of rows written between progress events
+pub const PROGRESS_INTERVAL_ROWS: u64 = 500;
+
+/// Dataset to export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportKind {
+    Assets,
+    Inspections,
+    Audit,
+}
+
+impl std::fmt::Display for ExportKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportKind::Assets => write!(f, "assets"),
+            ExportKind::Inspections => write!(f, "inspections"),
+            ExportKind::Audit => write!(f, "audit"),
+        }
+    }
+}
+
+/// Output file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    /// One JSON object per line
+    JsonLines,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::JsonLines => "jsonl",
+        }
+    }
+}
+
+/// Progress update emitted while an export runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportProgress {
+    pub export_id: String,
+    pub kind: ExportKind,
+    pub rows_written: u64,
+    /// Expected row count, when it can be determined up front
+    pub total_rows: Option<u64>,
+    pub finished: bool,
+}
+
+/// Completed export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportResult {
+    pub export_id: String,
+    pub kind: ExportKind,
+    pub format: ExportFormat,
+    pub file_path: String,
+    pub rows_written: u64,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// A record type that can be written by [`ExportWriter`]
+pub trait ExportRecord: Serialize {
+    /// CSV column names
+    fn csv_header() -> &'static [&'static str];
+
+    /// CSV values, in the same order as [`ExportRecord::csv_header`]
+    fn csv_row(&self) -> Vec<String>;
+}
+
+/// Incremental writer for one export file
+pub struct ExportWriter<R, W: Write> {
+    out: W,
+    format: ExportFormat,
+    rows_written: u64,
+    _record: PhantomData<fn(&R)>,
+}
+
+impl<R: ExportRecord> ExportWriter<R, BufWriter<File>> {
+    /// Create the output file and write the header
+    pub fn create(path: &Path, format: ExportFormat) -> AppResult<Self> {
+        let file = File::create(path)
+            .map_err(|e| AppError::file_system("create", path.display().to_string(), e.to_string()))?;
+        Self::new(BufWriter::new(file), format)
+    }
+}
+
+impl<R: ExportRecord, W: Write> ExportWriter<R, W> {
+    pub fn new(mut out: W, format: ExportFormat) -> AppResult<Self> {
+        if format == ExportFormat::Csv {
+            write_csv_line(&mut out, R::csv_header().iter().copied())?;
+        }
+        Ok(Self {
+            out,
+            format,
+            rows_written: 0,
+            _record: PhantomData,
+        })
+    }
+
+    pub fn write_record(&mut self, record: &R) -> AppResult<()> {
+        match self.format {
+            ExportFormat::Csv => {
+                let row = record.csv_row();
+                write_csv_line(&mut self.out, row.iter().map(String::as_str))?;
+            }
+            ExportFormat::JsonLines => {
+                serde_json::to_writer(&mut self.out, record)?;
+                self.out.write_all(b"\n")?;
+            }
+        }
+        self.rows_written += 1;
+        Ok(())
+    }
+
+    pub fn rows_written(&self) -> u64 {
+        self.rows_written
+    }
+
+    /// Flush buffered output and return the number of rows written
+    pub fn finish(mut self) -> AppResult<u64> {
+        self.out.flush()?;
+        Ok(self.rows_written)
+    }
+}
+
+/// Stream records from `source` into a new file at `path`
+///
+/// `source` receives a visitor to call once per record and returns the number
+/// of records it produced. `progress` is called with the running row count
+/// every [`PROGRESS_INTERVAL_ROWS`] rows; an error from it, such as a
+/// cancellation, stops the export. A partially written file is removed if
+/// the export fails.
+pub fn export_to_file<R, S, P>(path: &Path, format: ExportFormat, source: S, mut progress: P) -> AppResult<u64>
+where
+    R: ExportRecord,
+    S: FnOnce(&mut dyn FnMut(R) -> AppResult<()>) -> AppResult<u64>,
+    P: FnMut(u64) -> AppResult<()>,
+{
+    let mut writer = ExportWriter::<R, _>::create(path, format)?;
+    let streamed = source(&mut |record| {
+        writer.write_record(&record)?;
+        if writer.rows_written() % PROGRESS_INTERVAL_ROWS == 0 {
+            progress(writer.rows_written())?;
+        }
+        Ok(())
+    });
+
+    match streamed.and_then(|_| writer.finish()) {
+        Ok(rows) => Ok(rows),
+        Err(e) => {
+            let _ = std::fs::remove_file(path);
+            Err(e)
+        }
+    }
+}
+
+fn opt<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+}
+
+impl ExportRecord for Asset {
+    fn csv_header() -> &'static [&'static str] {
+        &[
+            "id", "asset_number", "asset_name", "asset_type", "manufacturer", "model",
+            "serial_number", "manufacture_date", "installation_date", "capacity", "capacity_unit",
+            "location_id", "status", "description", "created_by", "created_at", "updated_at",
+        ]
+    }
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.asset_number.clone(),
+            self.asset_name.clone(),
+            self.asset_type.clone(),
+            opt(&self.manufacturer),
+            opt(&self.model),
+            opt(&self.serial_number),
+            opt(&self.manufacture_date),
+            opt(&self.installation_date),
+            opt(&self.capacity),
+            opt(&self.capacity_unit),
+            self.location_id.to_string(),
+            self.status.to_string(),
+            opt(&self.description),
+            self.created_by.to_string(),
+            self.created_at.to_rfc3339(),
+            self.updated_at.to_rfc3339(),
+        ]
+    }
+}
+
+impl ExportRecord for Inspection {
+    fn csv_header() -> &'static [&'static str] {
+        &[
+            "id", "asset_id", "inspector_id", "inspection_type", "compliance_standard",
+            "scheduled_date", "actual_date", "status", "overall_condition", "notes",
+            "time_zone", "overdue_at", "created_at", "updated_at",
+        ]
+    }
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.asset_id.to_string(),
+            self.inspector_id.to_string(),
+            self.inspection_type.to_string(),
+            self.compliance_standard.clone(),
+            opt(&self.scheduled_date.map(|d| d.to_rfc3339())),
+            opt(&self.actual_date.map(|d| d.to_rfc3339())),
+            self.status.to_string(),
+            opt(&self.overall_condition),
+            opt(&self.notes),
+            opt(&self.time_zone),
+            opt(&self.overdue_at.map(|d| d.to_rfc3339())),
+            self.created_at.to_rfc3339(),
+            self.updated_at.to_rfc3339(),
+        ]
+    }
+}
+
+impl ExportRecord for AuditLogEntry {
+    fn csv_header() -> &'static [&'static str] {
+        &[
+            "id", "timestamp", "request_id", "user_id", "username", "action",
+            "resource_type", "resource_id", "success", "error_message", "ip_address",
+        ]
+    }
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.timestamp.to_rfc3339(),
+            self.request_id.clone(),
+            opt(&self.user_id),
+            opt(&self.username),
+            self.action.clone(),
+            self.resource_type.clone(),
+            opt(&self.resource_id),
+            self.success.to_string(),
+            opt(&self.error_message),
+            opt(&self.ip_address),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::RequestContext;
+
+    #[test]
+    fn test_writer_streams_rows() {
+        let context = RequestContext::new();
+        let entry = AuditLogEntry::new(&context, "create", "inspection").with_resource_id("7");
+
+        let mut writer = ExportWriter::<AuditLogEntry, _>::new(Vec::new(), ExportFormat::Csv).unwrap();
+        writer.write_record(&entry).unwrap();
+        writer.write_record(&entry).unwrap();
+        assert_eq!(writer.rows_written(), 2);
+        let output = String::from_utf8(writer.out.clone()).unwrap();
+        assert_eq!(output.lines().count(), 3);
+        assert!(output.starts_with("id,timestamp,request_id"));
+
+        let mut writer = ExportWriter::<AuditLogEntry, _>::new(Vec::new(), ExportFormat::JsonLines).unwrap();
+        writer.write_record(&entry).unwrap();
+        let line = String::from_utf8(writer.out.clone()).unwrap();
+        let parsed: AuditLogEntry = serde_json::from_str(line.trim_end()).unwrap();
+        assert_eq!(parsed.resource_id.as_deref(), Some("7"));
+    }
+}
@@ -0,0 +1,100 @@
+//! Comma-separated values, quoted as RFC 4180 describes
+
+use super::{ExportTable, FormatWriter};
+use crate::api::ReportFormat;
+use crate::errors::AppResult;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// UTF-8 byte order mark
+pub const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// How a CSV file is written
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CsvOptions {
+    /// Start with a byte order mark; without one Excel reads the file in the
+    /// system code page and garbles anything beyond ASCII
+    #[serde(default)]
+    pub byte_order_mark: bool,
+    /// Columns to write, in this order; every column when empty
+    #[serde(default)]
+    pub columns: Vec<String>,
+}
+
+pub struct CsvWriter;
+
+impl FormatWriter for CsvWriter {
+    fn format(&self) -> ReportFormat {
+        ReportFormat::Csv
+    }
+
+    fn extension(&self) -> &'static str {
+        "csv"
+    }
+
+    fn content_type(&self) -> &'static str {
+        "text/csv; charset=utf-8"
+    }
+
+    fn write_table(&self, table: &ExportTable, out: &mut dyn Write) -> AppResult<()> {
+        write_csv_line(out, table.columns.iter().map(String::as_str))?;
+        for row in &table.rows {
+            write_csv_line(out, row.iter().map(String::as_str))?;
+        }
+        Ok(())
+    }
+}
+
+/// `table` as CSV according to `options`
+pub fn render_csv(table: &ExportTable, options: &CsvOptions) -> AppResult<Vec<u8>> {
+    let table = table.select_columns(&options.columns)?;
+    let mut out = Vec::new();
+    if options.byte_order_mark {
+        out.extend_from_slice(UTF8_BOM);
+    }
+    CsvWriter.write_table(&table, &mut out)?;
+    Ok(out)
+}
+
+/// One line of `fields`, quoted where needed
+pub fn write_csv_line<'a, W: Write + ?Sized>(out: &mut W, fields: impl Iterator<Item = &'a str>) -> AppResult<()> {
+    let line = fields.map(csv_field).collect::<Vec<_>>().join(",");
+    out.write_all(line.as_bytes())?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_render_with_options() {
+        let mut table = ExportTable::new("Assets", chrono::Utc::now(), &["asset_number", "asset_name"]);
+        table.push_row(vec!["CR-1".to_string(), "Grue à tour".to_string()]);
+
+        let plain = render_csv(&table, &CsvOptions::default()).unwrap();
+        assert_eq!(String::from_utf8(plain).unwrap(), "asset_number,asset_name\nCR-1,Grue à tour\n");
+
+        let options = CsvOptions { byte_order_mark: true, columns: vec!["asset_name".to_string()] };
+        let excel = render_csv(&table, &options).unwrap();
+        assert!(excel.starts_with(UTF8_BOM));
+        assert_eq!(std::str::from_utf8(&excel[UTF8_BOM.len()..]).unwrap(), "asset_name\nGrue à tour\n");
+    }
+}
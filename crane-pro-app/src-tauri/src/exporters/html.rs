@@ -0,0 +1,54 @@
+//! Standalone HTML page holding the table
+
+use super::{ExportTable, FormatWriter};
+use crate::api::ReportFormat;
+use crate::errors::AppResult;
+use std::io::Write;
+
+pub struct HtmlWriter;
+
+impl FormatWriter for HtmlWriter {
+    fn format(&self) -> ReportFormat {
+        ReportFormat::Html
+    }
+
+    fn extension(&self) -> &'static str {
+        "html"
+    }
+
+    fn content_type(&self) -> &'static str {
+        "text/html; charset=utf-8"
+    }
+
+    fn write_table(&self, table: &ExportTable, out: &mut dyn Write) -> AppResult<()> {
+        let cells = |values: &[String], tag: &str| {
+            values.iter().map(|value| format!("<{tag}>{}</{tag}>", escape(value))).collect::<String>()
+        };
+        let title = escape(&table.title);
+        write!(
+            out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n\
+             body {{ font-family: Arial, sans-serif; margin: 40px; }}\n\
+             table {{ border-collapse: collapse; width: 100%; }}\n\
+             th, td {{ border: 1px solid #ddd; padding: 6px; text-align: left; vertical-align: top; }}\n\
+             th {{ background-color: #f2f2f2; }}\n\
+             </style>\n</head>\n<body>\n<h1>{}</h1>\n<p>Generated {}</p>\n<table>\n<tr>{}</tr>\n",
+            title,
+            title,
+            table.generated_at.format("%Y-%m-%d %H:%M UTC"),
+            cells(&table.columns, "th"),
+        )?;
+        for row in &table.rows {
+            writeln!(out, "<tr>{}</tr>", cells(row, "td"))?;
+        }
+        out.write_all(b"</table>\n</body>\n</html>\n")?;
+        Ok(())
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
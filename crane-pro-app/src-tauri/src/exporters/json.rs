@@ -0,0 +1,45 @@
+//! Pretty-printed JSON
+//!
+//! Tables with structured records are written as those records; otherwise
+//! as an array of objects keyed by column name.
+
+use super::{ExportTable, FormatWriter};
+use crate::api::ReportFormat;
+use crate::errors::AppResult;
+use serde_json::{Map, Value};
+use std::io::Write;
+
+pub struct JsonWriter;
+
+impl FormatWriter for JsonWriter {
+    fn format(&self) -> ReportFormat {
+        ReportFormat::Json
+    }
+
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn content_type(&self) -> &'static str {
+        "application/json"
+    }
+
+    fn write_table(&self, table: &ExportTable, out: &mut dyn Write) -> AppResult<()> {
+        let value = match &table.records {
+            Some(records) => records.clone(),
+            None => Value::Array(
+                table.rows.iter()
+                    .map(|row| {
+                        let object: Map<String, Value> = table.columns.iter()
+                            .cloned()
+                            .zip(row.iter().map(|value| Value::String(value.clone())))
+                            .collect();
+                        Value::Object(object)
+                    })
+                    .collect(),
+            ),
+        };
+        serde_json::to_writer_pretty(&mut *out, &value)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+//! Export format registry
+//!
+//! Registers, dataset exports and the other files handed out as reports are
+//! all tables at heart. Each is built once as an [`ExportTable`] and written
+//! by the [`FormatWriter`] registered for the requested [`ReportFormat`], so
+//! a new format is one writer registered in [`ExporterRegistry::default`].
+//! Documents with a layout of their own, such as the inspection report PDF,
+//! still use the registry for file extensions and content types.
+
+pub mod csv;
+pub mod html;
+pub mod json;
+pub mod pdf;
+
+use crate::api::ReportFormat;
+use crate::errors::{AppError, AppResult};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::OnceLock;
+
+/// Rows of text under named columns
+#[derive(Debug, Clone)]
+pub struct ExportTable {
+    pub title: String,
+    pub generated_at: DateTime<Utc>,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// The same data with its types kept, written instead of the rows by
+    /// formats that can hold it, such as JSON
+    pub records: Option<serde_json::Value>,
+}
+
+impl ExportTable {
+    pub fn new(title: &str, generated_at: DateTime<Utc>, columns: &[&str]) -> Self {
+        Self {
+            title: title.to_string(),
+            generated_at,
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows: Vec::new(),
+            records: None,
+        }
+    }
+
+    /// Add a row, padded or cut to the number of columns
+    pub fn push_row(&mut self, mut row: Vec<String>) {
+        row.resize(self.columns.len(), String::new());
+        self.rows.push(row);
+    }
+
+    pub fn with_records(mut self, records: serde_json::Value) -> Self {
+        self.records = Some(records);
+        self
+    }
+
+    /// The table cut down to `columns`, in that order; the whole table when
+    /// `columns` is empty. The structured records are dropped, as they no
+    /// longer match the rows.
+    pub fn select_columns(&self, columns: &[String]) -> AppResult<ExportTable> {
+        if columns.is_empty() {
+            return Ok(self.clone());
+        }
+        let indexes = columns.iter()
+            .map(|name| {
+                self.columns.iter().position(|column| column == name).ok_or_else(|| {
+                    AppError::validation("columns", format!("Unknown column '{}'; expected one of {}", name, self.columns.join(", ")))
+                })
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(ExportTable {
+            title: self.title.clone(),
+            generated_at: self.generated_at,
+            columns: columns.to_vec(),
+            rows: self.rows.iter().map(|row| indexes.iter().map(|&i| row[i].clone()).collect()).collect(),
+            records: None,
+        })
+    }
+}
+
+/// Writes tables in one file format
+pub trait FormatWriter: Send + Sync {
+    fn format(&self) -> ReportFormat;
+
+    /// File name extension, without the dot
+    fn extension(&self) -> &'static str;
+
+    /// MIME type files are served with
+    fn content_type(&self) -> &'static str;
+
+    /// Write `table` as a complete file
+    fn write_table(&self, table: &ExportTable, out: &mut dyn Write) -> AppResult<()>;
+}
+
+/// The writer for each supported format
+pub struct ExporterRegistry {
+    writers: HashMap<ReportFormat, Box<dyn FormatWriter>>,
+}
+
+impl ExporterRegistry {
+    /// A registry with no formats
+    pub fn empty() -> Self {
+        Self { writers: HashMap::new() }
+    }
+
+    /// Add `writer`, replacing any writer already registered for its format
+    pub fn register(&mut self, writer: Box<dyn FormatWriter>) {
+        self.writers.insert(writer.format(), writer);
+    }
+
+    pub fn get(&self, format: &ReportFormat) -> AppResult<&dyn FormatWriter> {
+        self.writers
+            .get(format)
+            .map(|writer| writer.as_ref())
+            .ok_or_else(|| AppError::validation("format", format!("{:?} export is not supported", format)))
+    }
+
+    pub fn writers(&self) -> impl Iterator<Item = &dyn FormatWriter> {
+        self.writers.values().map(|writer| writer.as_ref())
+    }
+
+    /// `table` as a file in `format`
+    pub fn render(&self, format: &ReportFormat, table: &ExportTable) -> AppResult<Vec<u8>> {
+        let mut out = Vec::new();
+        self.get(format)?.write_table(table, &mut out)?;
+        Ok(out)
+    }
+}
+
+impl Default for ExporterRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(Box::new(csv::CsvWriter));
+        registry.register(Box::new(json::JsonWriter));
+        registry.register(Box::new(html::HtmlWriter));
+        registry.register(Box::new(pdf::PdfTableWriter));
+        registry
+    }
+}
+
+/// The application's registry, with the built-in writers
+pub fn registry() -> &'static ExporterRegistry {
+    static REGISTRY: OnceLock<ExporterRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(ExporterRegistry::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> ExportTable {
+        let mut table = ExportTable::new("Holds", Utc::now(), &["ID", "Reason"]);
+        table.push_row(vec!["1".to_string(), "Claim <42>, \"urgent\"".to_string()]);
+        table.push_row(vec!["2".to_string()]);
+        table
+    }
+
+    #[test]
+    fn test_every_format_has_a_writer() {
+        for format in [ReportFormat::Pdf, ReportFormat::Html, ReportFormat::Json, ReportFormat::Csv] {
+            let writer = registry().get(&format).unwrap();
+            assert_eq!(writer.format(), format);
+            assert!(!registry().render(&format, &table()).unwrap().is_empty());
+        }
+        assert!(ExporterRegistry::empty().get(&ReportFormat::Csv).is_err());
+    }
+
+    #[test]
+    fn test_table_output() {
+        let csv = String::from_utf8(registry().render(&ReportFormat::Csv, &table()).unwrap()).unwrap();
+        assert_eq!(csv, "ID,Reason\n1,\"Claim <42>, \"\"urgent\"\"\"\n2,\n");
+
+        let json: serde_json::Value = serde_json::from_slice(&registry().render(&ReportFormat::Json, &table()).unwrap()).unwrap();
+        assert_eq!(json[0]["Reason"], "Claim <42>, \"urgent\"");
+        let records = table().with_records(serde_json::json!([{ "id": 1 }]));
+        let json: serde_json::Value = serde_json::from_slice(&registry().render(&ReportFormat::Json, &records).unwrap()).unwrap();
+        assert_eq!(json, serde_json::json!([{ "id": 1 }]));
+
+        let html = String::from_utf8(registry().render(&ReportFormat::Html, &table()).unwrap()).unwrap();
+        assert!(html.contains("<td>Claim &lt;42&gt;, &quot;urgent&quot;</td>"));
+
+        assert!(registry().render(&ReportFormat::Pdf, &table()).unwrap().starts_with(b"%PDF-"));
+    }
+
+    #[test]
+    fn test_select_columns() {
+        let selected = table().select_columns(&["Reason".to_string(), "ID".to_string()]).unwrap();
+        assert_eq!(selected.columns, ["Reason", "ID"]);
+        assert_eq!(selected.rows[1], ["", "2"]);
+        assert_eq!(table().select_columns(&[]).unwrap().columns, ["ID", "Reason"]);
+        assert!(table().select_columns(&["Status".to_string()]).is_err());
+    }
+}
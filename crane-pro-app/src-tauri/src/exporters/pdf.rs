@@ -0,0 +1,107 @@
+//! Plain PDF table
+//!
+//! Columns share the page width equally and cells wrap, so wide tables get
+//! tall rows rather than being cut off. The header row repeats on every page.
+
+use super::{ExportTable, FormatWriter};
+use crate::api::ReportFormat;
+use crate::errors::AppResult;
+use crate::reports::pdf::{wrap_text, Font, PdfDocument, PdfPage, A4_HEIGHT, A4_WIDTH};
+use std::io::Write;
+
+const MARGIN: f32 = 40.0;
+const CONTENT_WIDTH: f32 = A4_WIDTH - 2.0 * MARGIN;
+
+/// Lowest point content may reach before the footer
+const BODY_BOTTOM: f32 = A4_HEIGHT - MARGIN - 24.0;
+
+const FONT_SIZE: f32 = 8.0;
+const LINE_HEIGHT: f32 = 10.0;
+const CELL_PADDING: f32 = 3.0;
+
+pub struct PdfTableWriter;
+
+impl FormatWriter for PdfTableWriter {
+    fn format(&self) -> ReportFormat {
+        ReportFormat::Pdf
+    }
+
+    fn extension(&self) -> &'static str {
+        "pdf"
+    }
+
+    fn content_type(&self) -> &'static str {
+        "application/pdf"
+    }
+
+    fn write_table(&self, table: &ExportTable, out: &mut dyn Write) -> AppResult<()> {
+        let column_width = CONTENT_WIDTH / table.columns.len().max(1) as f32;
+        let wrap_row = |row: &[String], font: Font| -> Vec<Vec<String>> {
+            row.iter().map(|cell| wrap_text(cell, font, FONT_SIZE, column_width - 2.0 * CELL_PADDING)).collect()
+        };
+        let header = wrap_row(table.columns.as_slice(), Font::Bold);
+
+        let mut document = PdfDocument::new(&table.title, table.generated_at);
+        let page = document.add_page();
+        page.text(MARGIN, MARGIN + 16.0, Font::Bold, 16.0, &table.title);
+        page.text(
+            MARGIN,
+            MARGIN + 30.0,
+            Font::Regular,
+            8.0,
+            &format!("Generated {}", table.generated_at.format("%Y-%m-%d %H:%M UTC")),
+        );
+        let mut y = draw_row(page, MARGIN + 40.0, column_width, &header, Font::Bold, true);
+
+        for row in &table.rows {
+            let cells = wrap_row(row.as_slice(), Font::Regular);
+            if y + row_height(&cells) > BODY_BOTTOM {
+                let page = document.add_page();
+                y = draw_row(page, MARGIN, column_width, &header, Font::Bold, true);
+            }
+            let page = document.pages_mut().last().expect("the first page is added before drawing");
+            y = draw_row(page, y, column_width, &cells, Font::Regular, false);
+        }
+
+        let page_count = document.page_count();
+        for (index, page) in document.pages_mut().enumerate() {
+            page.fill_gray(0.35);
+            page.text_right(
+                A4_WIDTH - MARGIN,
+                A4_HEIGHT - MARGIN + 4.0,
+                Font::Regular,
+                7.0,
+                &format!("Page {} of {}", index + 1, page_count),
+            );
+            page.fill_gray(0.0);
+        }
+        out.write_all(&document.to_bytes()?)?;
+        Ok(())
+    }
+}
+
+fn row_height(cells: &[Vec<String>]) -> f32 {
+    cells.iter().map(Vec::len).max().unwrap_or(1) as f32 * LINE_HEIGHT + 2.0 * CELL_PADDING
+}
+
+/// Draw one row at `y`; returns where the next row starts
+fn draw_row(page: &mut PdfPage, y: f32, column_width: f32, cells: &[Vec<String>], font: Font, shaded: bool) -> f32 {
+    let height = row_height(cells);
+    let width = column_width * cells.len().max(1) as f32;
+    if shaded {
+        page.fill_gray(0.85);
+        page.fill_rect(MARGIN, y, width, height);
+        page.fill_gray(0.0);
+    }
+    page.rect(MARGIN, y, width, height, 0.4);
+    for (index, lines) in cells.iter().enumerate() {
+        let x = MARGIN + index as f32 * column_width;
+        if index > 0 {
+            page.line(x, y, x, y + height, 0.4);
+        }
+        for (line_index, line) in lines.iter().enumerate() {
+            page.text(x + CELL_PADDING, y + CELL_PADDING + 7.5 + line_index as f32 * LINE_HEIGHT, font, FONT_SIZE, line);
+        }
+    }
+    y + height
+}
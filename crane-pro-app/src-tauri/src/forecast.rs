@@ -0,0 +1,208 @@
+//! Workload forecasting
+//!
+//! Projects inspection and maintenance work over the coming months to
+//! support staffing decisions. Work already on the schedule is counted in
+//! the month it is due. Recurring inspection types are projected forward
+//! from each asset's latest scheduled or completed inspection at the type's
+//! interval. Work that is already overdue is counted in the first month,
+//! since it still has to be done.
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{InspectionType, MaintenanceType, MonthlyWorkload, WorkloadEntry, WorkloadForecast, WorkloadKind};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use std::collections::BTreeMap;
+
+/// Months forecast when no horizon is requested
+pub const DEFAULT_FORECAST_MONTHS: u32 = 12;
+
+/// Longest forecast horizon
+pub const MAX_FORECAST_MONTHS: u32 = 36;
+
+/// Typical hours spent on an inspection of each type
+pub fn inspection_hours(inspection_type: &InspectionType) -> f64 {
+    match inspection_type {
+        InspectionType::Frequent => 1.0,
+        InspectionType::Periodic => 4.0,
+        InspectionType::Initial => 6.0,
+        InspectionType::Special => 3.0,
+    }
+}
+
+/// Typical hours spent on maintenance of each type
+pub fn maintenance_hours(maintenance_type: &MaintenanceType) -> f64 {
+    match maintenance_type {
+        MaintenanceType::Preventive => 2.0,
+        MaintenanceType::Corrective => 4.0,
+        MaintenanceType::Emergency => 6.0,
+        MaintenanceType::Overhaul => 16.0,
+    }
+}
+
+/// First day of the month `months` after the month containing `date`
+fn add_months(date: NaiveDate, months: u32) -> NaiveDate {
+    let index = date.year() * 12 + date.month0() as i32 + months as i32;
+    NaiveDate::from_ymd_opt(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, 1)
+        .expect("first of month is always valid")
+}
+
+fn month_label(date: NaiveDate) -> String {
+    format!("{:04}-{:02}", date.year(), date.month())
+}
+
+/// The months covered by a forecast, starting with the current one
+#[derive(Debug, Clone, Copy)]
+pub struct ForecastWindow {
+    today: NaiveDate,
+    /// First day after the last month of the window
+    end: NaiveDate,
+    months: u32,
+}
+
+impl ForecastWindow {
+    pub fn new(today: NaiveDate, months: u32) -> AppResult<Self> {
+        if !(1..=MAX_FORECAST_MONTHS).contains(&months) {
+            return Err(AppError::OutOfRange {
+                field: "months".to_string(),
+                value: months.to_string(),
+                min: "1".to_string(),
+                max: MAX_FORECAST_MONTHS.to_string(),
+            });
+        }
+        Ok(Self { today, end: add_months(today, months), months })
+    }
+
+    /// `YYYY-MM` labels of every month in the window, in order
+    pub fn month_labels(&self) -> Vec<String> {
+        (0..self.months).map(|offset| month_label(add_months(self.today, offset))).collect()
+    }
+
+    /// Month that work due on `date` counts in, or `None` beyond the window
+    pub fn month_of(&self, date: NaiveDate) -> Option<String> {
+        if date >= self.end {
+            None
+        } else {
+            Some(month_label(date.max(self.today)))
+        }
+    }
+
+    /// Due dates within the window of an inspection recurring every
+    /// `interval_days` after `anchor`. An overdue first occurrence is due
+    /// today and later ones follow from it; with no anchor the first
+    /// inspection is due today.
+    pub fn recurrences(&self, anchor: Option<NaiveDate>, interval_days: i64) -> Vec<NaiveDate> {
+        let interval = Duration::days(interval_days.max(1));
+        let mut next = anchor.map(|date| date + interval).unwrap_or(self.today).max(self.today);
+        let mut dates = Vec::new();
+        while next < self.end {
+            dates.push(next);
+            next += interval;
+        }
+        dates
+    }
+}
+
+/// Collects work into per-month, per-location entries
+pub struct WorkloadBuilder {
+    window: ForecastWindow,
+    entries: BTreeMap<(String, i64, WorkloadKind, String), WorkloadEntry>,
+}
+
+impl WorkloadBuilder {
+    pub fn new(window: ForecastWindow) -> Self {
+        Self { window, entries: BTreeMap::new() }
+    }
+
+    /// Count one piece of work due on `date`. Work beyond the window is ignored.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add(
+        &mut self,
+        date: NaiveDate,
+        location_id: i64,
+        location_name: &str,
+        kind: WorkloadKind,
+        work_type: &str,
+        hours: f64,
+        scheduled: bool,
+    ) {
+        let Some(month) = self.window.month_of(date) else {
+            return;
+        };
+        let entry = self.entries
+            .entry((month.clone(), location_id, kind, work_type.to_string()))
+            .or_insert_with(|| WorkloadEntry {
+                month,
+                location_id,
+                location_name: location_name.to_string(),
+                kind,
+                work_type: work_type.to_string(),
+                scheduled_count: 0,
+                projected_count: 0,
+                estimated_hours: 0.0,
+            });
+        if scheduled {
+            entry.scheduled_count += 1;
+        } else {
+            entry.projected_count += 1;
+        }
+        entry.estimated_hours += hours;
+    }
+
+    /// The forecast, with a total for every month including empty ones
+    pub fn finish(self, generated_at: DateTime<Utc>) -> WorkloadForecast {
+        let entries: Vec<WorkloadEntry> = self.entries.into_values().collect();
+        let months = self.window.month_labels().into_iter()
+            .map(|month| {
+                let in_month = entries.iter().filter(|e| e.month == month);
+                let (count, estimated_hours) = in_month.fold((0, 0.0), |(count, hours), e| {
+                    (count + e.scheduled_count + e.projected_count, hours + e.estimated_hours)
+                });
+                MonthlyWorkload { month, count, estimated_hours }
+            })
+            .collect();
+        WorkloadForecast { generated_at, months, entries }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_forecast_window_months_and_recurrences() {
+        let window = ForecastWindow::new(date(2026, 11, 20), 3).unwrap();
+        assert_eq!(window.month_labels(), vec!["2026-11", "2026-12", "2027-01"]);
+        assert_eq!(window.month_of(date(2026, 1, 5)).as_deref(), Some("2026-11"));
+        assert_eq!(window.month_of(date(2027, 1, 31)).as_deref(), Some("2027-01"));
+        assert_eq!(window.month_of(date(2027, 2, 1)), None);
+
+        // Overdue since the last inspection: due today, then monthly from today
+        let dates = window.recurrences(Some(date(2026, 9, 1)), 30);
+        assert_eq!(dates, vec![date(2026, 11, 20), date(2026, 12, 20), date(2027, 1, 19)]);
+        assert_eq!(window.recurrences(Some(date(2026, 11, 1)), 365), Vec::<NaiveDate>::new());
+        assert_eq!(window.recurrences(None, 365), vec![date(2026, 11, 20)]);
+
+        assert!(ForecastWindow::new(date(2026, 11, 20), 0).is_err());
+    }
+
+    #[test]
+    fn test_workload_builder_totals() {
+        let window = ForecastWindow::new(date(2026, 11, 20), 2).unwrap();
+        let mut builder = WorkloadBuilder::new(window);
+        builder.add(date(2026, 11, 25), 1, "Plant", WorkloadKind::Inspection, "Frequent", 1.0, true);
+        builder.add(date(2026, 11, 28), 1, "Plant", WorkloadKind::Inspection, "Frequent", 1.0, false);
+        builder.add(date(2026, 11, 28), 1, "Plant", WorkloadKind::Maintenance, "Overhaul", 16.0, true);
+        builder.add(date(2027, 3, 1), 1, "Plant", WorkloadKind::Inspection, "Periodic", 4.0, false);
+
+        let forecast = builder.finish(Utc::now());
+        assert_eq!(forecast.entries.len(), 2);
+        assert_eq!(forecast.entries[0].scheduled_count, 1);
+        assert_eq!(forecast.entries[0].projected_count, 1);
+        assert_eq!(forecast.months[0].count, 3);
+        assert_eq!(forecast.months[0].estimated_hours, 18.0);
+        assert_eq!(forecast.months[1].count, 0);
+    }
+}
@@ -0,0 +1,303 @@
+//! Geospatial helpers for map views
+//!
+//! Locations carry WGS84 latitude/longitude. Searches first narrow rows with
+//! a bounding box that SQLite can answer from an index, then radius searches
+//! are refined with the great-circle (haversine) distance. Assets are placed
+//! on the map at the coordinates of their location.
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{AssetStatus, Location};
+use serde::{Deserialize, Serialize};
+
+/// Mean Earth radius used for distance calculations
+pub const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+/// Largest accepted search radius
+pub const MAX_SEARCH_RADIUS_KM: f64 = 5000.0;
+
+/// Compliance score below which a pin is shown in red
+pub const PIN_RED_BELOW_SCORE: f64 = 60.0;
+
+/// Compliance score below which a pin is shown in amber
+pub const PIN_AMBER_BELOW_SCORE: f64 = 80.0;
+
+/// A WGS84 coordinate
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoPoint {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl GeoPoint {
+    pub fn validate(&self) -> AppResult<()> {
+        if !self.latitude.is_finite() || !(-90.0..=90.0).contains(&self.latitude) {
+            return Err(AppError::OutOfRange {
+                field: "latitude".to_string(),
+                value: self.latitude.to_string(),
+                min: "-90".to_string(),
+                max: "90".to_string(),
+            });
+        }
+        if !self.longitude.is_finite() || !(-180.0..=180.0).contains(&self.longitude) {
+            return Err(AppError::OutOfRange {
+                field: "longitude".to_string(),
+                value: self.longitude.to_string(),
+                min: "-180".to_string(),
+                max: "180".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Great-circle distance in kilometres
+    pub fn distance_km(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lng = (other.longitude - self.longitude).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lng / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+}
+
+/// Rectangular map viewport. `west` may be greater than `east` when the box
+/// crosses the antimeridian.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+}
+
+impl BoundingBox {
+    pub fn validate(&self) -> AppResult<()> {
+        GeoPoint { latitude: self.south, longitude: self.west }.validate()?;
+        GeoPoint { latitude: self.north, longitude: self.east }.validate()?;
+        if self.south > self.north {
+            return Err(AppError::validation("bounds", "South edge must not be north of the north edge"));
+        }
+        Ok(())
+    }
+
+    /// Smallest box containing every point within `radius_km` of `center`
+    pub fn around(center: GeoPoint, radius_km: f64) -> Self {
+        let d_lat = (radius_km / EARTH_RADIUS_KM).to_degrees();
+        let south = (center.latitude - d_lat).max(-90.0);
+        let north = (center.latitude + d_lat).min(90.0);
+
+        // Near the poles the longitude span covers the whole circle
+        let cos_lat = center.latitude.to_radians().cos();
+        if north >= 90.0 || south <= -90.0 || cos_lat * 180.0 <= d_lat {
+            return Self { south, west: -180.0, north, east: 180.0 };
+        }
+        let d_lng = (radius_km / (EARTH_RADIUS_KM * cos_lat)).to_degrees();
+        Self {
+            south,
+            west: wrap_longitude(center.longitude - d_lng),
+            north,
+            east: wrap_longitude(center.longitude + d_lng),
+        }
+    }
+
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.west > self.east
+    }
+
+    pub fn contains(&self, point: &GeoPoint) -> bool {
+        let in_lat = point.latitude >= self.south && point.latitude <= self.north;
+        let in_lng = if self.crosses_antimeridian() {
+            point.longitude >= self.west || point.longitude <= self.east
+        } else {
+            point.longitude >= self.west && point.longitude <= self.east
+        };
+        in_lat && in_lng
+    }
+
+    /// SQL condition on `lat_col`/`lng_col` using parameters `?{first}`
+    /// (south), `?{first+1}` (north), `?{first+2}` (west), `?{first+3}` (east)
+    pub fn sql_condition(&self, lat_col: &str, lng_col: &str, first: usize) -> String {
+        let lng_join = if self.crosses_antimeridian() { "OR" } else { "AND" };
+        format!(
+            "({lat} BETWEEN ?{s} AND ?{n} AND ({lng} >= ?{w} {join} {lng} <= ?{e}))",
+            lat = lat_col,
+            lng = lng_col,
+            s = first,
+            n = first + 1,
+            w = first + 2,
+            e = first + 3,
+            join = lng_join,
+        )
+    }
+
+    /// Parameter values for [`BoundingBox::sql_condition`], in order
+    pub fn sql_params(&self) -> [f64; 4] {
+        [self.south, self.north, self.west, self.east]
+    }
+}
+
+fn wrap_longitude(longitude: f64) -> f64 {
+    if longitude > 180.0 {
+        longitude - 360.0
+    } else if longitude < -180.0 {
+        longitude + 360.0
+    } else {
+        longitude
+    }
+}
+
+/// Geographic search: everything inside a viewport or within a radius
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GeoQuery {
+    Bounds { bounds: BoundingBox },
+    Radius { center: GeoPoint, radius_km: f64 },
+}
+
+impl GeoQuery {
+    pub fn validate(&self) -> AppResult<()> {
+        match self {
+            GeoQuery::Bounds { bounds } => bounds.validate(),
+            GeoQuery::Radius { center, radius_km } => {
+                center.validate()?;
+                if !radius_km.is_finite() || *radius_km <= 0.0 || *radius_km > MAX_SEARCH_RADIUS_KM {
+                    return Err(AppError::OutOfRange {
+                        field: "radius_km".to_string(),
+                        value: radius_km.to_string(),
+                        min: "0".to_string(),
+                        max: MAX_SEARCH_RADIUS_KM.to_string(),
+                    });
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Bounding box used to pre-filter rows in SQL
+    pub fn bounding_box(&self) -> BoundingBox {
+        match self {
+            GeoQuery::Bounds { bounds } => *bounds,
+            GeoQuery::Radius { center, radius_km } => BoundingBox::around(*center, *radius_km),
+        }
+    }
+
+    /// Exact match test; returns the distance from the centre for radius
+    /// searches, `Some(None)` for a viewport hit and `None` for a miss
+    pub fn matches(&self, point: &GeoPoint) -> Option<Option<f64>> {
+        match self {
+            GeoQuery::Bounds { bounds } => bounds.contains(point).then_some(None),
+            GeoQuery::Radius { center, radius_km } => {
+                let distance = center.distance_km(point);
+                (distance <= *radius_km).then_some(Some(distance))
+            }
+        }
+    }
+}
+
+/// Coordinates of a location, if both are set
+pub fn location_point(location: &Location) -> Option<GeoPoint> {
+    match (location.latitude, location.longitude) {
+        (Some(latitude), Some(longitude)) => Some(GeoPoint { latitude, longitude }),
+        _ => None,
+    }
+}
+
+/// Location found by a geographic search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationDistance {
+    pub location: Location,
+    /// Distance from the search centre; `None` for viewport searches
+    pub distance_km: Option<f64>,
+}
+
+/// Map pin colour
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PinColor {
+    Green,
+    Amber,
+    Red,
+    /// Inactive or decommissioned equipment
+    Grey,
+}
+
+impl PinColor {
+    /// Colour for an asset from its status and inspection state
+    pub fn for_asset(
+        status: &AssetStatus,
+        compliance_score: Option<f64>,
+        overdue_inspections: i64,
+        critical_findings: i64,
+    ) -> Self {
+        match status {
+            AssetStatus::Inactive | AssetStatus::Decommissioned => return PinColor::Grey,
+            _ => {}
+        }
+        if overdue_inspections > 0 || critical_findings > 0 {
+            return PinColor::Red;
+        }
+        match compliance_score {
+            Some(score) if score < PIN_RED_BELOW_SCORE => PinColor::Red,
+            Some(score) if score < PIN_AMBER_BELOW_SCORE => PinColor::Amber,
+            _ if *status == AssetStatus::Maintenance => PinColor::Amber,
+            _ => PinColor::Green,
+        }
+    }
+}
+
+/// Asset placed on the map at its location's coordinates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MapPin {
+    pub asset_id: i64,
+    pub asset_number: String,
+    pub asset_name: String,
+    pub asset_type: String,
+    pub status: AssetStatus,
+    pub location_id: i64,
+    pub location_name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Distance from the search centre for radius searches
+    pub distance_km: Option<f64>,
+    /// Average compliance of completed inspections, if any
+    pub compliance_score: Option<f64>,
+    pub overdue_inspections: i64,
+    pub critical_findings: i64,
+    pub color: PinColor,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_and_radius_box() {
+        let london = GeoPoint { latitude: 51.5074, longitude: -0.1278 };
+        let paris = GeoPoint { latitude: 48.8566, longitude: 2.3522 };
+        let distance = london.distance_km(&paris);
+        assert!((distance - 343.5).abs() < 1.0, "got {}", distance);
+
+        let query = GeoQuery::Radius { center: london, radius_km: 350.0 };
+        assert!(query.bounding_box().contains(&paris));
+        assert!(query.matches(&paris).is_some());
+        assert!(GeoQuery::Radius { center: london, radius_km: 300.0 }.matches(&paris).is_none());
+    }
+
+    #[test]
+    fn test_bounding_box_across_antimeridian() {
+        let fiji = BoundingBox { south: -20.0, west: 175.0, north: -15.0, east: -178.0 };
+        assert!(fiji.validate().is_ok());
+        assert!(fiji.contains(&GeoPoint { latitude: -17.7, longitude: 178.0 }));
+        assert!(fiji.contains(&GeoPoint { latitude: -17.7, longitude: -179.0 }));
+        assert!(!fiji.contains(&GeoPoint { latitude: -17.7, longitude: 0.0 }));
+        assert!(fiji.sql_condition("l.latitude", "l.longitude", 1).contains(" OR "));
+    }
+
+    #[test]
+    fn test_pin_color() {
+        assert_eq!(PinColor::for_asset(&AssetStatus::Active, Some(95.0), 0, 0), PinColor::Green);
+        assert_eq!(PinColor::for_asset(&AssetStatus::Active, Some(70.0), 0, 0), PinColor::Amber);
+        assert_eq!(PinColor::for_asset(&AssetStatus::Active, Some(95.0), 1, 0), PinColor::Red);
+        assert_eq!(PinColor::for_asset(&AssetStatus::Maintenance, None, 0, 0), PinColor::Amber);
+        assert_eq!(PinColor::for_asset(&AssetStatus::Decommissioned, Some(10.0), 3, 0), PinColor::Grey);
+    }
+}
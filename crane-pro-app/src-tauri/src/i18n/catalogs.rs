@@ -0,0 +1,593 @@
+//! Message catalogs for supported locales
+//!
+//! Keys are grouped by prefix: `error.<AppError variant>`, `enum.<Type>.<Display value>`,
+//! `report.*` for report labels and `common.*` for shared words. Placeholders use the
+//! `{name}` syntax and are filled from the error fields or caller-supplied arguments.
+
+pub(crate) const EN: &[(&str, &str)] = &[
+    // Errors
+    ("error.Database", "Database operation failed: {message}"),
+    ("error.DatabaseConnection", "Database connection failed: {reason}"),
+    ("error.DatabaseMigration", "Database migration failed: {version}"),
+    ("error.RecordNotFound", "Record not found: {entity} with {field} = {value}"),
+    ("error.DuplicateRecord", "Duplicate record: {entity} with {field} = {value} already exists"),
+    ("error.Validation", "Validation failed: {field} - {message}"),
+    ("error.RequiredField", "Required field missing: {field}"),
+    ("error.InvalidFormat", "Invalid format: {field} - expected {expected}, got {actual}"),
+    ("error.OutOfRange", "Value out of range: {field} - {value} not in range {min}-{max}"),
+    ("error.InvalidQueryParameter", "Invalid query parameter for {entity}: {parameter} = {value} - {reason}"),
+    ("error.FileSystem", "File operation failed: {operation} on {path} - {reason}"),
+    ("error.FileNotFound", "File not found: {path}"),
+    ("error.PermissionDenied", "Permission denied: {path} - {operation}"),
+    ("error.InvalidFileFormat", "Invalid file format: {path} - expected {expected}, got {actual}"),
+    ("error.ImageProcessing", "Image processing failed: {operation} - {reason}"),
+    ("error.UnsupportedImageFormat", "Unsupported image format: {format} for file {path}"),
+    ("error.ImageTooLarge", "Image too large: {path} - size {size}MB exceeds limit {limit}MB"),
+    ("error.ExifExtraction", "EXIF data extraction failed: {path} - {reason}"),
+    ("error.Authentication", "Authentication failed: {reason}"),
+    ("error.Authorization", "Authorization failed: user {user} cannot {action} {resource}"),
+    ("error.Token", "Token error: {operation} - {reason}"),
+    ("error.Encryption", "Encryption failed: {reason}"),
+    ("error.Decryption", "Decryption failed: {reason}"),
+    ("error.NetworkRequest", "Network request failed: {method} {url} - {status}: {message}"),
+    ("error.ConnectionTimeout", "Connection timeout: {url} after {timeout}s"),
+    ("error.ApiError", "API error: {service} - {code}: {message}"),
+    ("error.Configuration", "Configuration error: {key} - {reason}"),
+    ("error.MissingConfiguration", "Missing configuration: {key}"),
+    ("error.InvalidConfiguration", "Invalid configuration: {key} - {value} is not valid"),
+    ("error.Inspection", "Inspection error: {inspection_id} - {reason}"),
+    ("error.CraneOperation", "Crane operation error: {crane_id} - {operation}: {reason}"),
+    ("error.ReportGeneration", "Report generation failed: {report_type} - {reason}"),
+    ("error.ScheduleConflict", "Schedule conflict: {inspection_id} - {reason}"),
+    ("error.AiAnalysis", "AI analysis failed: {model} - {reason}"),
+    ("error.AiServiceUnavailable", "AI service unavailable: {service}"),
+    ("error.AiQuotaExceeded", "AI quota exceeded: {service} - {limit}"),
+    ("error.Internal", "Internal server error: {message}"),
+    ("error.Timeout", "Operation timeout: {operation} exceeded {timeout}s"),
+    ("error.ResourceUnavailable", "Resource unavailable: {resource}"),
+    ("error.Cancelled", "Operation cancelled: {operation}"),
+    ("error.ExternalService", "External service error: {service} - {message}"),
+    // Enums
+    ("enum.UserRole.Inspector", "Inspector"),
+    ("enum.UserRole.Supervisor", "Supervisor"),
+    ("enum.UserRole.Administrator", "Administrator"),
+    ("enum.UserRole.SuperAdmin", "Super Administrator"),
+    ("enum.AssetStatus.Active", "Active"),
+    ("enum.AssetStatus.Inactive", "Inactive"),
+    ("enum.AssetStatus.Maintenance", "Maintenance"),
+    ("enum.AssetStatus.Decommissioned", "Decommissioned"),
+    ("enum.ComponentStatus.Active", "Active"),
+    ("enum.ComponentStatus.Inactive", "Inactive"),
+    ("enum.ComponentStatus.Maintenance", "Maintenance"),
+    ("enum.ComponentStatus.Replaced", "Replaced"),
+    ("enum.InspectionType.Frequent", "Frequent"),
+    ("enum.InspectionType.Periodic", "Periodic"),
+    ("enum.InspectionType.Initial", "Initial"),
+    ("enum.InspectionType.Special", "Special"),
+    ("enum.InspectionStatus.Scheduled", "Scheduled"),
+    ("enum.InspectionStatus.In Progress", "In Progress"),
+    ("enum.InspectionStatus.Completed", "Completed"),
+    ("enum.InspectionStatus.Cancelled", "Cancelled"),
+    ("enum.Condition.Excellent", "Excellent"),
+    ("enum.Condition.Good", "Good"),
+    ("enum.Condition.Fair", "Fair"),
+    ("enum.Condition.Poor", "Poor"),
+    ("enum.Condition.Critical", "Critical"),
+    ("enum.Severity.Low", "Low"),
+    ("enum.Severity.Medium", "Medium"),
+    ("enum.Severity.High", "High"),
+    ("enum.Severity.Critical", "Critical"),
+    ("enum.MaintenanceType.Preventive", "Preventive"),
+    ("enum.MaintenanceType.Corrective", "Corrective"),
+    ("enum.MaintenanceType.Emergency", "Emergency"),
+    ("enum.MaintenanceType.Overhaul", "Overhaul"),
+    ("enum.MaintenanceStatus.Scheduled", "Scheduled"),
+    ("enum.MaintenanceStatus.In Progress", "In Progress"),
+    ("enum.MaintenanceStatus.Completed", "Completed"),
+    ("enum.MaintenanceStatus.Cancelled", "Cancelled"),
+    ("enum.AssetRecordKind.Insurance", "Insurance"),
+    ("enum.AssetRecordKind.Registration", "Registration"),
+    // Reports
+    ("report.inspection_report", "Inspection Report"),
+    ("report.compliance_report", "Compliance Report"),
+    ("report.asset_information", "Asset Information"),
+    ("report.asset_name", "Asset Name"),
+    ("report.asset_number", "Asset Number"),
+    ("report.asset_type", "Asset Type"),
+    ("report.capacity", "Capacity"),
+    ("report.inspection_details", "Inspection Details"),
+    ("report.inspection_id", "Inspection ID"),
+    ("report.inspection_type", "Inspection Type"),
+    ("report.status", "Status"),
+    ("report.scheduled_date", "Scheduled Date"),
+    ("report.actual_date", "Actual Date"),
+    ("report.overall_condition", "Overall Condition"),
+    ("report.inspection_items", "Inspection Items"),
+    ("report.item_name", "Item Name"),
+    ("report.category", "Category"),
+    ("report.condition", "Condition"),
+    ("report.finding", "Finding"),
+    ("report.severity", "Severity"),
+    ("report.compliant", "Compliant"),
+    ("report.clause", "Clause"),
+    ("report.standard_clauses", "Standard Clauses"),
+    ("report.clause_title", "Title"),
+    ("report.media_files", "Media Files"),
+    ("report.total_media_files", "Total media files"),
+    ("report.generated_on", "Generated on"),
+    ("report.report_period", "Report Period"),
+    ("report.from", "From"),
+    ("report.to", "To"),
+    ("report.compliance_summary", "Compliance Summary"),
+    ("report.total_assets", "Total Assets"),
+    ("report.compliant_assets", "Compliant Assets"),
+    ("report.non_compliant_assets", "Non-Compliant Assets"),
+    ("report.compliance_percentage", "Compliance Percentage"),
+    ("report.critical_findings", "Critical Findings"),
+    ("report.overdue_inspections", "Overdue Inspections"),
+    ("report.expiring_records", "Expiring Insurance and Registrations"),
+    ("report.asset_records", "Insurance and Registration"),
+    ("report.record_kind", "Record Type"),
+    ("report.reference_number", "Reference Number"),
+    ("report.issuer", "Issuer"),
+    ("report.renewal_date", "Renewal Date"),
+    ("report.notes", "Notes"),
+    ("report.compliance_standard", "Compliance Standard"),
+    ("report.corrective_action", "Corrective Action"),
+    ("report.photos", "Photos"),
+    ("report.sign_off", "Sign-off"),
+    ("report.inspector", "Inspector"),
+    ("report.signature", "Signature"),
+    ("report.date", "Date"),
+    ("report.reviewed_by", "Reviewed by"),
+    ("report.page", "Page {page} of {count}"),
+    ("report.charts", "Charts"),
+    ("report.compliance_score_trend", "Compliance Score Trend"),
+    ("report.finding_severity_distribution", "Findings by Severity"),
+    ("report.completion_rate_trend", "Inspection Completion Rate"),
+    // Common
+    ("common.yes", "Yes"),
+    ("common.no", "No"),
+    ("common.not_available", "N/A"),
+];
+
+pub(crate) const ES: &[(&str, &str)] = &[
+    // Errors
+    ("error.Database", "La operación de base de datos falló: {message}"),
+    ("error.DatabaseConnection", "La conexión a la base de datos falló: {reason}"),
+    ("error.DatabaseMigration", "La migración de la base de datos falló: {version}"),
+    ("error.RecordNotFound", "Registro no encontrado: {entity} con {field} = {value}"),
+    ("error.DuplicateRecord", "Registro duplicado: ya existe {entity} con {field} = {value}"),
+    ("error.Validation", "Error de validación: {field} - {message}"),
+    ("error.RequiredField", "Falta un campo obligatorio: {field}"),
+    ("error.InvalidFormat", "Formato no válido: {field} - se esperaba {expected}, se recibió {actual}"),
+    ("error.OutOfRange", "Valor fuera de rango: {field} - {value} no está entre {min} y {max}"),
+    ("error.InvalidQueryParameter", "Parámetro de consulta no válido para {entity}: {parameter} = {value} - {reason}"),
+    ("error.FileSystem", "La operación de archivo falló: {operation} en {path} - {reason}"),
+    ("error.FileNotFound", "Archivo no encontrado: {path}"),
+    ("error.PermissionDenied", "Permiso denegado: {path} - {operation}"),
+    ("error.InvalidFileFormat", "Formato de archivo no válido: {path} - se esperaba {expected}, se recibió {actual}"),
+    ("error.ImageProcessing", "El procesamiento de la imagen falló: {operation} - {reason}"),
+    ("error.UnsupportedImageFormat", "Formato de imagen no compatible: {format} para el archivo {path}"),
+    ("error.ImageTooLarge", "Imagen demasiado grande: {path} - {size} MB supera el límite de {limit} MB"),
+    ("error.ExifExtraction", "La extracción de datos EXIF falló: {path} - {reason}"),
+    ("error.Authentication", "La autenticación falló: {reason}"),
+    ("error.Authorization", "Autorización denegada: el usuario {user} no puede {action} {resource}"),
+    ("error.Token", "Error de token: {operation} - {reason}"),
+    ("error.Encryption", "El cifrado falló: {reason}"),
+    ("error.Decryption", "El descifrado falló: {reason}"),
+    ("error.NetworkRequest", "La solicitud de red falló: {method} {url} - {status}: {message}"),
+    ("error.ConnectionTimeout", "Tiempo de conexión agotado: {url} tras {timeout} s"),
+    ("error.ApiError", "Error de API: {service} - {code}: {message}"),
+    ("error.Configuration", "Error de configuración: {key} - {reason}"),
+    ("error.MissingConfiguration", "Falta la configuración: {key}"),
+    ("error.InvalidConfiguration", "Configuración no válida: {key} - {value} no es válido"),
+    ("error.Inspection", "Error de inspección: {inspection_id} - {reason}"),
+    ("error.CraneOperation", "Error de operación de grúa: {crane_id} - {operation}: {reason}"),
+    ("error.ReportGeneration", "La generación del informe falló: {report_type} - {reason}"),
+    ("error.ScheduleConflict", "Conflicto de programación: {inspection_id} - {reason}"),
+    ("error.AiAnalysis", "El análisis de IA falló: {model} - {reason}"),
+    ("error.AiServiceUnavailable", "Servicio de IA no disponible: {service}"),
+    ("error.AiQuotaExceeded", "Cuota de IA superada: {service} - {limit}"),
+    ("error.Internal", "Error interno del servidor: {message}"),
+    ("error.Timeout", "Tiempo de operación agotado: {operation} superó {timeout} s"),
+    ("error.ResourceUnavailable", "Recurso no disponible: {resource}"),
+    ("error.Cancelled", "Operación cancelada: {operation}"),
+    ("error.ExternalService", "Error de servicio externo: {service} - {message}"),
+    // Enums
+    ("enum.UserRole.Inspector", "Inspector"),
+    ("enum.UserRole.Supervisor", "Supervisor"),
+    ("enum.UserRole.Administrator", "Administrador"),
+    ("enum.UserRole.SuperAdmin", "Superadministrador"),
+    ("enum.AssetStatus.Active", "Activo"),
+    ("enum.AssetStatus.Inactive", "Inactivo"),
+    ("enum.AssetStatus.Maintenance", "En mantenimiento"),
+    ("enum.AssetStatus.Decommissioned", "Dado de baja"),
+    ("enum.ComponentStatus.Active", "Activo"),
+    ("enum.ComponentStatus.Inactive", "Inactivo"),
+    ("enum.ComponentStatus.Maintenance", "En mantenimiento"),
+    ("enum.ComponentStatus.Replaced", "Reemplazado"),
+    ("enum.InspectionType.Frequent", "Frecuente"),
+    ("enum.InspectionType.Periodic", "Periódica"),
+    ("enum.InspectionType.Initial", "Inicial"),
+    ("enum.InspectionType.Special", "Especial"),
+    ("enum.InspectionStatus.Scheduled", "Programada"),
+    ("enum.InspectionStatus.In Progress", "En curso"),
+    ("enum.InspectionStatus.Completed", "Completada"),
+    ("enum.InspectionStatus.Cancelled", "Cancelada"),
+    ("enum.Condition.Excellent", "Excelente"),
+    ("enum.Condition.Good", "Buena"),
+    ("enum.Condition.Fair", "Aceptable"),
+    ("enum.Condition.Poor", "Deficiente"),
+    ("enum.Condition.Critical", "Crítica"),
+    ("enum.Severity.Low", "Baja"),
+    ("enum.Severity.Medium", "Media"),
+    ("enum.Severity.High", "Alta"),
+    ("enum.Severity.Critical", "Crítica"),
+    ("enum.MaintenanceType.Preventive", "Preventivo"),
+    ("enum.MaintenanceType.Corrective", "Correctivo"),
+    ("enum.MaintenanceType.Emergency", "De emergencia"),
+    ("enum.MaintenanceType.Overhaul", "Revisión general"),
+    ("enum.MaintenanceStatus.Scheduled", "Programado"),
+    ("enum.MaintenanceStatus.In Progress", "En curso"),
+    ("enum.MaintenanceStatus.Completed", "Completado"),
+    ("enum.MaintenanceStatus.Cancelled", "Cancelado"),
+    ("enum.AssetRecordKind.Insurance", "Seguro"),
+    ("enum.AssetRecordKind.Registration", "Registro"),
+    // Reports
+    ("report.inspection_report", "Informe de inspección"),
+    ("report.compliance_report", "Informe de cumplimiento"),
+    ("report.asset_information", "Información del activo"),
+    ("report.asset_name", "Nombre del activo"),
+    ("report.asset_number", "Número de activo"),
+    ("report.asset_type", "Tipo de activo"),
+    ("report.capacity", "Capacidad"),
+    ("report.inspection_details", "Detalles de la inspección"),
+    ("report.inspection_id", "ID de inspección"),
+    ("report.inspection_type", "Tipo de inspección"),
+    ("report.status", "Estado"),
+    ("report.scheduled_date", "Fecha programada"),
+    ("report.actual_date", "Fecha real"),
+    ("report.overall_condition", "Condición general"),
+    ("report.inspection_items", "Elementos de inspección"),
+    ("report.item_name", "Elemento"),
+    ("report.category", "Categoría"),
+    ("report.condition", "Condición"),
+    ("report.finding", "Hallazgo"),
+    ("report.severity", "Gravedad"),
+    ("report.compliant", "Conforme"),
+    ("report.clause", "Cláusula"),
+    ("report.standard_clauses", "Cláusulas de la norma"),
+    ("report.clause_title", "Título"),
+    ("report.media_files", "Archivos multimedia"),
+    ("report.total_media_files", "Total de archivos multimedia"),
+    ("report.generated_on", "Generado el"),
+    ("report.report_period", "Período del informe"),
+    ("report.from", "Desde"),
+    ("report.to", "Hasta"),
+    ("report.compliance_summary", "Resumen de cumplimiento"),
+    ("report.total_assets", "Total de activos"),
+    ("report.compliant_assets", "Activos conformes"),
+    ("report.non_compliant_assets", "Activos no conformes"),
+    ("report.compliance_percentage", "Porcentaje de cumplimiento"),
+    ("report.critical_findings", "Hallazgos críticos"),
+    ("report.overdue_inspections", "Inspecciones vencidas"),
+    ("report.expiring_records", "Seguros y registros por vencer"),
+    ("report.asset_records", "Seguro y registro"),
+    ("report.record_kind", "Tipo de registro"),
+    ("report.reference_number", "Número de referencia"),
+    ("report.issuer", "Emisor"),
+    ("report.renewal_date", "Fecha de renovación"),
+    ("report.notes", "Notas"),
+    ("report.compliance_standard", "Norma de cumplimiento"),
+    ("report.corrective_action", "Acción correctiva"),
+    ("report.photos", "Fotografías"),
+    ("report.sign_off", "Aprobación"),
+    ("report.inspector", "Inspector"),
+    ("report.signature", "Firma"),
+    ("report.date", "Fecha"),
+    ("report.reviewed_by", "Revisado por"),
+    ("report.page", "Página {page} de {count}"),
+    ("report.charts", "Gráficos"),
+    ("report.compliance_score_trend", "Evolución de la puntuación de cumplimiento"),
+    ("report.finding_severity_distribution", "Hallazgos por gravedad"),
+    ("report.completion_rate_trend", "Tasa de inspecciones completadas"),
+    // Common
+    ("common.yes", "Sí"),
+    ("common.no", "No"),
+    ("common.not_available", "N/D"),
+];
+
+pub(crate) const FR: &[(&str, &str)] = &[
+    // Errors
+    ("error.Database", "Échec de l'opération de base de données : {message}"),
+    ("error.DatabaseConnection", "Échec de la connexion à la base de données : {reason}"),
+    ("error.DatabaseMigration", "Échec de la migration de la base de données : {version}"),
+    ("error.RecordNotFound", "Enregistrement introuvable : {entity} avec {field} = {value}"),
+    ("error.DuplicateRecord", "Enregistrement en double : {entity} avec {field} = {value} existe déjà"),
+    ("error.Validation", "Échec de la validation : {field} - {message}"),
+    ("error.RequiredField", "Champ obligatoire manquant : {field}"),
+    ("error.InvalidFormat", "Format invalide : {field} - {expected} attendu, {actual} reçu"),
+    ("error.OutOfRange", "Valeur hors limites : {field} - {value} n'est pas compris entre {min} et {max}"),
+    ("error.InvalidQueryParameter", "Paramètre de requête invalide pour {entity} : {parameter} = {value} - {reason}"),
+    ("error.FileSystem", "Échec de l'opération sur le fichier : {operation} sur {path} - {reason}"),
+    ("error.FileNotFound", "Fichier introuvable : {path}"),
+    ("error.PermissionDenied", "Autorisation refusée : {path} - {operation}"),
+    ("error.InvalidFileFormat", "Format de fichier invalide : {path} - {expected} attendu, {actual} reçu"),
+    ("error.ImageProcessing", "Échec du traitement de l'image : {operation} - {reason}"),
+    ("error.UnsupportedImageFormat", "Format d'image non pris en charge : {format} pour le fichier {path}"),
+    ("error.ImageTooLarge", "Image trop volumineuse : {path} - {size} Mo dépasse la limite de {limit} Mo"),
+    ("error.ExifExtraction", "Échec de l'extraction des données EXIF : {path} - {reason}"),
+    ("error.Authentication", "Échec de l'authentification : {reason}"),
+    ("error.Authorization", "Autorisation refusée : l'utilisateur {user} ne peut pas {action} {resource}"),
+    ("error.Token", "Erreur de jeton : {operation} - {reason}"),
+    ("error.Encryption", "Échec du chiffrement : {reason}"),
+    ("error.Decryption", "Échec du déchiffrement : {reason}"),
+    ("error.NetworkRequest", "Échec de la requête réseau : {method} {url} - {status} : {message}"),
+    ("error.ConnectionTimeout", "Délai de connexion dépassé : {url} après {timeout} s"),
+    ("error.ApiError", "Erreur d'API : {service} - {code} : {message}"),
+    ("error.Configuration", "Erreur de configuration : {key} - {reason}"),
+    ("error.MissingConfiguration", "Configuration manquante : {key}"),
+    ("error.InvalidConfiguration", "Configuration invalide : {key} - {value} n'est pas valide"),
+    ("error.Inspection", "Erreur d'inspection : {inspection_id} - {reason}"),
+    ("error.CraneOperation", "Erreur d'opération de pont roulant : {crane_id} - {operation} : {reason}"),
+    ("error.ReportGeneration", "Échec de la génération du rapport : {report_type} - {reason}"),
+    ("error.ScheduleConflict", "Conflit de planification : {inspection_id} - {reason}"),
+    ("error.AiAnalysis", "Échec de l'analyse IA : {model} - {reason}"),
+    ("error.AiServiceUnavailable", "Service IA indisponible : {service}"),
+    ("error.AiQuotaExceeded", "Quota IA dépassé : {service} - {limit}"),
+    ("error.Internal", "Erreur interne du serveur : {message}"),
+    ("error.Timeout", "Délai d'opération dépassé : {operation} a dépassé {timeout} s"),
+    ("error.ResourceUnavailable", "Ressource indisponible : {resource}"),
+    ("error.Cancelled", "Opération annulée : {operation}"),
+    ("error.ExternalService", "Erreur du service externe : {service} - {message}"),
+    // Enums
+    ("enum.UserRole.Inspector", "Inspecteur"),
+    ("enum.UserRole.Supervisor", "Superviseur"),
+    ("enum.UserRole.Administrator", "Administrateur"),
+    ("enum.UserRole.SuperAdmin", "Super administrateur"),
+    ("enum.AssetStatus.Active", "Actif"),
+    ("enum.AssetStatus.Inactive", "Inactif"),
+    ("enum.AssetStatus.Maintenance", "En maintenance"),
+    ("enum.AssetStatus.Decommissioned", "Mis hors service"),
+    ("enum.ComponentStatus.Active", "Actif"),
+    ("enum.ComponentStatus.Inactive", "Inactif"),
+    ("enum.ComponentStatus.Maintenance", "En maintenance"),
+    ("enum.ComponentStatus.Replaced", "Remplacé"),
+    ("enum.InspectionType.Frequent", "Fréquente"),
+    ("enum.InspectionType.Periodic", "Périodique"),
+    ("enum.InspectionType.Initial", "Initiale"),
+    ("enum.InspectionType.Special", "Spéciale"),
+    ("enum.InspectionStatus.Scheduled", "Planifiée"),
+    ("enum.InspectionStatus.In Progress", "En cours"),
+    ("enum.InspectionStatus.Completed", "Terminée"),
+    ("enum.InspectionStatus.Cancelled", "Annulée"),
+    ("enum.Condition.Excellent", "Excellent"),
+    ("enum.Condition.Good", "Bon"),
+    ("enum.Condition.Fair", "Passable"),
+    ("enum.Condition.Poor", "Mauvais"),
+    ("enum.Condition.Critical", "Critique"),
+    ("enum.Severity.Low", "Faible"),
+    ("enum.Severity.Medium", "Moyenne"),
+    ("enum.Severity.High", "Élevée"),
+    ("enum.Severity.Critical", "Critique"),
+    ("enum.MaintenanceType.Preventive", "Préventive"),
+    ("enum.MaintenanceType.Corrective", "Corrective"),
+    ("enum.MaintenanceType.Emergency", "D'urgence"),
+    ("enum.MaintenanceType.Overhaul", "Révision complète"),
+    ("enum.MaintenanceStatus.Scheduled", "Planifiée"),
+    ("enum.MaintenanceStatus.In Progress", "En cours"),
+    ("enum.MaintenanceStatus.Completed", "Terminée"),
+    ("enum.MaintenanceStatus.Cancelled", "Annulée"),
+    ("enum.AssetRecordKind.Insurance", "Assurance"),
+    ("enum.AssetRecordKind.Registration", "Immatriculation"),
+    // Reports
+    ("report.inspection_report", "Rapport d'inspection"),
+    ("report.compliance_report", "Rapport de conformité"),
+    ("report.asset_information", "Informations sur l'équipement"),
+    ("report.asset_name", "Nom de l'équipement"),
+    ("report.asset_number", "Numéro de l'équipement"),
+    ("report.asset_type", "Type d'équipement"),
+    ("report.capacity", "Capacité"),
+    ("report.inspection_details", "Détails de l'inspection"),
+    ("report.inspection_id", "ID d'inspection"),
+    ("report.inspection_type", "Type d'inspection"),
+    ("report.status", "Statut"),
+    ("report.scheduled_date", "Date prévue"),
+    ("report.actual_date", "Date effective"),
+    ("report.overall_condition", "État général"),
+    ("report.inspection_items", "Points d'inspection"),
+    ("report.item_name", "Point"),
+    ("report.category", "Catégorie"),
+    ("report.condition", "État"),
+    ("report.finding", "Constat"),
+    ("report.severity", "Gravité"),
+    ("report.compliant", "Conforme"),
+    ("report.clause", "Clause"),
+    ("report.standard_clauses", "Clauses de la norme"),
+    ("report.clause_title", "Intitulé"),
+    ("report.media_files", "Fichiers multimédias"),
+    ("report.total_media_files", "Nombre total de fichiers multimédias"),
+    ("report.generated_on", "Généré le"),
+    ("report.report_period", "Période du rapport"),
+    ("report.from", "Du"),
+    ("report.to", "Au"),
+    ("report.compliance_summary", "Synthèse de conformité"),
+    ("report.total_assets", "Nombre total d'équipements"),
+    ("report.compliant_assets", "Équipements conformes"),
+    ("report.non_compliant_assets", "Équipements non conformes"),
+    ("report.compliance_percentage", "Taux de conformité"),
+    ("report.critical_findings", "Constats critiques"),
+    ("report.overdue_inspections", "Inspections en retard"),
+    ("report.expiring_records", "Assurances et immatriculations à renouveler"),
+    ("report.asset_records", "Assurance et immatriculation"),
+    ("report.record_kind", "Type de document"),
+    ("report.reference_number", "Numéro de référence"),
+    ("report.issuer", "Émetteur"),
+    ("report.renewal_date", "Date de renouvellement"),
+    ("report.notes", "Remarques"),
+    ("report.compliance_standard", "Norme de conformité"),
+    ("report.corrective_action", "Action corrective"),
+    ("report.photos", "Photos"),
+    ("report.sign_off", "Validation"),
+    ("report.inspector", "Inspecteur"),
+    ("report.signature", "Signature"),
+    ("report.date", "Date"),
+    ("report.reviewed_by", "Vérifié par"),
+    ("report.page", "Page {page} sur {count}"),
+    ("report.charts", "Graphiques"),
+    ("report.compliance_score_trend", "Évolution du score de conformité"),
+    ("report.finding_severity_distribution", "Constats par gravité"),
+    ("report.completion_rate_trend", "Taux de réalisation des inspections"),
+    // Common
+    ("common.yes", "Oui"),
+    ("common.no", "Non"),
+    ("common.not_available", "N/D"),
+];
+
+pub(crate) const DE: &[(&str, &str)] = &[
+    // Errors
+    ("error.Database", "Datenbankvorgang fehlgeschlagen: {message}"),
+    ("error.DatabaseConnection", "Datenbankverbindung fehlgeschlagen: {reason}"),
+    ("error.DatabaseMigration", "Datenbankmigration fehlgeschlagen: {version}"),
+    ("error.RecordNotFound", "Datensatz nicht gefunden: {entity} mit {field} = {value}"),
+    ("error.DuplicateRecord", "Doppelter Datensatz: {entity} mit {field} = {value} existiert bereits"),
+    ("error.Validation", "Validierung fehlgeschlagen: {field} - {message}"),
+    ("error.RequiredField", "Pflichtfeld fehlt: {field}"),
+    ("error.InvalidFormat", "Ungültiges Format: {field} - erwartet {expected}, erhalten {actual}"),
+    ("error.OutOfRange", "Wert außerhalb des Bereichs: {field} - {value} liegt nicht zwischen {min} und {max}"),
+    ("error.InvalidQueryParameter", "Ungültiger Abfrageparameter für {entity}: {parameter} = {value} - {reason}"),
+    ("error.FileSystem", "Dateivorgang fehlgeschlagen: {operation} auf {path} - {reason}"),
+    ("error.FileNotFound", "Datei nicht gefunden: {path}"),
+    ("error.PermissionDenied", "Zugriff verweigert: {path} - {operation}"),
+    ("error.InvalidFileFormat", "Ungültiges Dateiformat: {path} - erwartet {expected}, erhalten {actual}"),
+    ("error.ImageProcessing", "Bildverarbeitung fehlgeschlagen: {operation} - {reason}"),
+    ("error.UnsupportedImageFormat", "Nicht unterstütztes Bildformat: {format} für Datei {path}"),
+    ("error.ImageTooLarge", "Bild zu groß: {path} - {size} MB überschreitet das Limit von {limit} MB"),
+    ("error.ExifExtraction", "EXIF-Datenextraktion fehlgeschlagen: {path} - {reason}"),
+    ("error.Authentication", "Authentifizierung fehlgeschlagen: {reason}"),
+    ("error.Authorization", "Autorisierung fehlgeschlagen: Benutzer {user} darf {resource} nicht {action}"),
+    ("error.Token", "Token-Fehler: {operation} - {reason}"),
+    ("error.Encryption", "Verschlüsselung fehlgeschlagen: {reason}"),
+    ("error.Decryption", "Entschlüsselung fehlgeschlagen: {reason}"),
+    ("error.NetworkRequest", "Netzwerkanfrage fehlgeschlagen: {method} {url} - {status}: {message}"),
+    ("error.ConnectionTimeout", "Zeitüberschreitung der Verbindung: {url} nach {timeout} s"),
+    ("error.ApiError", "API-Fehler: {service} - {code}: {message}"),
+    ("error.Configuration", "Konfigurationsfehler: {key} - {reason}"),
+    ("error.MissingConfiguration", "Fehlende Konfiguration: {key}"),
+    ("error.InvalidConfiguration", "Ungültige Konfiguration: {key} - {value} ist nicht gültig"),
+    ("error.Inspection", "Inspektionsfehler: {inspection_id} - {reason}"),
+    ("error.CraneOperation", "Fehler beim Kranbetrieb: {crane_id} - {operation}: {reason}"),
+    ("error.ReportGeneration", "Berichterstellung fehlgeschlagen: {report_type} - {reason}"),
+    ("error.ScheduleConflict", "Terminkonflikt: {inspection_id} - {reason}"),
+    ("error.AiAnalysis", "KI-Analyse fehlgeschlagen: {model} - {reason}"),
+    ("error.AiServiceUnavailable", "KI-Dienst nicht verfügbar: {service}"),
+    ("error.AiQuotaExceeded", "KI-Kontingent überschritten: {service} - {limit}"),
+    ("error.Internal", "Interner Serverfehler: {message}"),
+    ("error.Timeout", "Zeitüberschreitung des Vorgangs: {operation} hat {timeout} s überschritten"),
+    ("error.ResourceUnavailable", "Ressource nicht verfügbar: {resource}"),
+    ("error.Cancelled", "Vorgang abgebrochen: {operation}"),
+    ("error.ExternalService", "Fehler des externen Dienstes: {service} - {message}"),
+    // Enums
+    ("enum.UserRole.Inspector", "Prüfer"),
+    ("enum.UserRole.Supervisor", "Vorgesetzter"),
+    ("enum.UserRole.Administrator", "Administrator"),
+    ("enum.UserRole.SuperAdmin", "Superadministrator"),
+    ("enum.AssetStatus.Active", "Aktiv"),
+    ("enum.AssetStatus.Inactive", "Inaktiv"),
+    ("enum.AssetStatus.Maintenance", "In Wartung"),
+    ("enum.AssetStatus.Decommissioned", "Außer Betrieb"),
+    ("enum.ComponentStatus.Active", "Aktiv"),
+    ("enum.ComponentStatus.Inactive", "Inaktiv"),
+    ("enum.ComponentStatus.Maintenance", "In Wartung"),
+    ("enum.ComponentStatus.Replaced", "Ersetzt"),
+    ("enum.InspectionType.Frequent", "Häufig"),
+    ("enum.InspectionType.Periodic", "Periodisch"),
+    ("enum.InspectionType.Initial", "Erstprüfung"),
+    ("enum.InspectionType.Special", "Sonderprüfung"),
+    ("enum.InspectionStatus.Scheduled", "Geplant"),
+    ("enum.InspectionStatus.In Progress", "In Bearbeitung"),
+    ("enum.InspectionStatus.Completed", "Abgeschlossen"),
+    ("enum.InspectionStatus.Cancelled", "Storniert"),
+    ("enum.Condition.Excellent", "Ausgezeichnet"),
+    ("enum.Condition.Good", "Gut"),
+    ("enum.Condition.Fair", "Befriedigend"),
+    ("enum.Condition.Poor", "Mangelhaft"),
+    ("enum.Condition.Critical", "Kritisch"),
+    ("enum.Severity.Low", "Niedrig"),
+    ("enum.Severity.Medium", "Mittel"),
+    ("enum.Severity.High", "Hoch"),
+    ("enum.Severity.Critical", "Kritisch"),
+    ("enum.MaintenanceType.Preventive", "Vorbeugend"),
+    ("enum.MaintenanceType.Corrective", "Korrektiv"),
+    ("enum.MaintenanceType.Emergency", "Notfall"),
+    ("enum.MaintenanceType.Overhaul", "Generalüberholung"),
+    ("enum.MaintenanceStatus.Scheduled", "Geplant"),
+    ("enum.MaintenanceStatus.In Progress", "In Bearbeitung"),
+    ("enum.MaintenanceStatus.Completed", "Abgeschlossen"),
+    ("enum.MaintenanceStatus.Cancelled", "Storniert"),
+    ("enum.AssetRecordKind.Insurance", "Versicherung"),
+    ("enum.AssetRecordKind.Registration", "Zulassung"),
+    // Reports
+    ("report.inspection_report", "Prüfbericht"),
+    ("report.compliance_report", "Konformitätsbericht"),
+    ("report.asset_information", "Anlageninformationen"),
+    ("report.asset_name", "Anlagenname"),
+    ("report.asset_number", "Anlagennummer"),
+    ("report.asset_type", "Anlagentyp"),
+    ("report.capacity", "Tragfähigkeit"),
+    ("report.inspection_details", "Prüfungsdetails"),
+    ("report.inspection_id", "Prüfungs-ID"),
+    ("report.inspection_type", "Prüfungsart"),
+    ("report.status", "Status"),
+    ("report.scheduled_date", "Geplantes Datum"),
+    ("report.actual_date", "Tatsächliches Datum"),
+    ("report.overall_condition", "Gesamtzustand"),
+    ("report.inspection_items", "Prüfpunkte"),
+    ("report.item_name", "Prüfpunkt"),
+    ("report.category", "Kategorie"),
+    ("report.condition", "Zustand"),
+    ("report.finding", "Befund"),
+    ("report.severity", "Schweregrad"),
+    ("report.compliant", "Konform"),
+    ("report.clause", "Klausel"),
+    ("report.standard_clauses", "Normklauseln"),
+    ("report.clause_title", "Titel"),
+    ("report.media_files", "Mediendateien"),
+    ("report.total_media_files", "Mediendateien gesamt"),
+    ("report.generated_on", "Erstellt am"),
+    ("report.report_period", "Berichtszeitraum"),
+    ("report.from", "Von"),
+    ("report.to", "Bis"),
+    ("report.compliance_summary", "Konformitätsübersicht"),
+    ("report.total_assets", "Anlagen gesamt"),
+    ("report.compliant_assets", "Konforme Anlagen"),
+    ("report.non_compliant_assets", "Nicht konforme Anlagen"),
+    ("report.compliance_percentage", "Konformitätsquote"),
+    ("report.critical_findings", "Kritische Befunde"),
+    ("report.overdue_inspections", "Überfällige Prüfungen"),
+    ("report.expiring_records", "Auslaufende Versicherungen und Zulassungen"),
+    ("report.asset_records", "Versicherung und Zulassung"),
+    ("report.record_kind", "Dokumentart"),
+    ("report.reference_number", "Referenznummer"),
+    ("report.issuer", "Aussteller"),
+    ("report.renewal_date", "Verlängerungsdatum"),
+    ("report.notes", "Anmerkungen"),
+    ("report.compliance_standard", "Prüfnorm"),
+    ("report.corrective_action", "Korrekturmaßnahme"),
+    ("report.photos", "Fotos"),
+    ("report.sign_off", "Freigabe"),
+    ("report.inspector", "Prüfer"),
+    ("report.signature", "Unterschrift"),
+    ("report.date", "Datum"),
+    ("report.reviewed_by", "Geprüft von"),
+    ("report.page", "Seite {page} von {count}"),
+    ("report.charts", "Diagramme"),
+    ("report.compliance_score_trend", "Verlauf der Konformitätsbewertung"),
+    ("report.finding_severity_distribution", "Befunde nach Schweregrad"),
+    ("report.completion_rate_trend", "Abschlussquote der Prüfungen"),
+    // Common
+    ("common.yes", "Ja"),
+    ("common.no", "Nein"),
+    ("common.not_available", "k. A."),
+];
@@ -0,0 +1,242 @@
+//! Internationalization for CranePro Bridge Inspection Application
+//!
+//! This module provides message catalogs for the supported locales (English,
+//! Spanish, French and German) and the [`Localize`] trait used to render
+//! `AppError` messages, enum display strings and report labels in the
+//! locale selected by the current user. Lookups fall back to English and
+//! then to the key itself, so a missing translation never fails a request.
+
+mod catalogs;
+
+use crate::errors::AppError;
+use crate::models::{
+    AssetRecordKind, AssetStatus, ComponentStatus, Condition, InspectionStatus, InspectionType,
+    MaintenanceStatus, MaintenanceType, Severity, UserRole,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Supported user interface locales
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+    Fr,
+    De,
+}
+
+impl Locale {
+    /// All supported locales
+    pub const ALL: [Locale; 4] = [Locale::En, Locale::Es, Locale::Fr, Locale::De];
+
+    /// ISO 639-1 language code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+            Locale::Fr => "fr",
+            Locale::De => "de",
+        }
+    }
+
+    fn catalog(&self) -> &'static HashMap<&'static str, &'static str> {
+        static CATALOGS: OnceLock<HashMap<Locale, HashMap<&'static str, &'static str>>> = OnceLock::new();
+        let catalogs = CATALOGS.get_or_init(|| {
+            Locale::ALL
+                .iter()
+                .map(|locale| {
+                    let entries = match locale {
+                        Locale::En => catalogs::EN,
+                        Locale::Es => catalogs::ES,
+                        Locale::Fr => catalogs::FR,
+                        Locale::De => catalogs::DE,
+                    };
+                    (*locale, entries.iter().copied().collect())
+                })
+                .collect()
+        });
+        &catalogs[self]
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl std::str::FromStr for Locale {
+    type Err = AppError;
+
+    /// Parse a language code, ignoring any region suffix (`es-MX`, `fr_CA`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let language = s.split(['-', '_']).next().unwrap_or_default();
+        match language.to_lowercase().as_str() {
+            "en" => Ok(Locale::En),
+            "es" => Ok(Locale::Es),
+            "fr" => Ok(Locale::Fr),
+            "de" => Ok(Locale::De),
+            _ => Err(AppError::validation("locale", format!("Unsupported locale: {}", s))),
+        }
+    }
+}
+
+/// Look up a message, falling back to English and then to the key
+pub fn translate(locale: Locale, key: &str) -> String {
+    locale
+        .catalog()
+        .get(key)
+        .or_else(|| Locale::En.catalog().get(key))
+        .map(|message| message.to_string())
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Look up a message and substitute `{name}` placeholders from `args`
+pub fn translate_with(locale: Locale, key: &str, args: &HashMap<String, String>) -> String {
+    let mut message = translate(locale, key);
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), value);
+    }
+    message
+}
+
+/// Types that can render themselves for a given locale
+pub trait Localize {
+    fn localize(&self, locale: Locale) -> String;
+}
+
+impl Localize for AppError {
+    fn localize(&self, locale: Locale) -> String {
+        let key = format!("error.{}", self.variant_name());
+        if translate(locale, &key) == key {
+            return self.to_string();
+        }
+        translate_with(locale, &key, &self.message_args())
+    }
+}
+
+impl AppError {
+    /// Variant name as used by the serialized `type` tag
+    fn variant_name(&self) -> String {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|value| value.get("type").and_then(|t| t.as_str()).map(str::to_string))
+            .unwrap_or_default()
+    }
+
+    /// Error fields as message arguments, keyed by field name
+    fn message_args(&self) -> HashMap<String, String> {
+        let details = serde_json::to_value(self)
+            .ok()
+            .and_then(|value| value.get("details").cloned());
+
+        match details {
+            Some(serde_json::Value::Object(fields)) => fields
+                .into_iter()
+                .map(|(name, value)| {
+                    let value = match value {
+                        serde_json::Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    (name, value)
+                })
+                .collect(),
+            _ => HashMap::new(),
+        }
+    }
+}
+
+/// Implement [`Localize`] for enums keyed by their `Display` string
+macro_rules! localize_enum {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl Localize for $ty {
+                fn localize(&self, locale: Locale) -> String {
+                    let key = format!("enum.{}.{}", stringify!($ty), self);
+                    let message = translate(locale, &key);
+                    if message == key { self.to_string() } else { message }
+                }
+            }
+        )*
+    };
+}
+
+localize_enum!(
+    UserRole,
+    AssetStatus,
+    ComponentStatus,
+    InspectionType,
+    InspectionStatus,
+    Condition,
+    Severity,
+    MaintenanceType,
+    MaintenanceStatus,
+    AssetRecordKind,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_parsing() {
+        assert_eq!("es".parse::<Locale>().unwrap(), Locale::Es);
+        assert_eq!("fr-CA".parse::<Locale>().unwrap(), Locale::Fr);
+        assert_eq!("DE_at".parse::<Locale>().unwrap(), Locale::De);
+        assert!("it".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn test_catalogs_cover_english_keys() {
+        for locale in Locale::ALL {
+            for (key, _) in catalogs::EN {
+                assert!(locale.catalog().contains_key(key), "{} missing {}", locale, key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_localize_error_and_enum() {
+        let error = AppError::RequiredField { field: "name".to_string() };
+        assert_eq!(error.localize(Locale::En), error.to_string());
+        assert_eq!(error.localize(Locale::De), "Pflichtfeld fehlt: name");
+
+        assert_eq!(InspectionStatus::InProgress.localize(Locale::Es), "En curso");
+        assert_eq!(translate(Locale::Fr, "report.missing_key"), "report.missing_key");
+    }
+
+    #[tokio::test]
+    async fn test_command_failures_reach_the_client_localized() {
+        let user = crate::models::User {
+            id: 1,
+            username: "inspector".to_string(),
+            email: String::new(),
+            password_hash: String::new(),
+            role: crate::models::UserRole::Inspector,
+            first_name: String::new(),
+            last_name: String::new(),
+            phone: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            is_active: true,
+        };
+        let mut session = crate::middleware::UserSession::new(&user, "session".to_string(), Vec::new());
+        session.locale = Locale::De;
+        let context = crate::middleware::RequestContext::new().with_session(session);
+
+        let result = crate::time_command!("localized_failure", {
+            let missing: Result<(), AppError> = Err(AppError::RequiredField { field: "name".to_string() });
+            missing?;
+            Ok(())
+        });
+        let response = crate::command_handler!("localized_failure", &context, { result });
+        match response.data {
+            crate::api::ApiResponse::Error(error) => assert_eq!(error.message, "Pflichtfeld fehlt: name"),
+            other => panic!("expected an error response, got {:?}", other),
+        }
+    }
+}
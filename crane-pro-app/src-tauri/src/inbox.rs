@@ -0,0 +1,99 @@
+//! Email-in of vendor documents
+//!
+//! Third-party inspection reports arrive by email. A mail bridge hands each
+//! message to the ingestion command, which stores its attachments and files
+//! them against the asset named in the subject line, for example
+//! `Annual inspection [CR-1042]` or `Load test report CR-1042`. Messages
+//! whose subject names no asset, or more than one, wait in an unmatched
+//! queue until someone files them by hand.
+
+use chrono::{DateTime, Datelike, Utc};
+use std::path::Path;
+
+/// Largest attachment accepted from an email
+pub const MAX_INBOUND_ATTACHMENT_BYTES: usize = 50 * 1024 * 1024;
+
+/// Attachment types kept from vendor emails; anything else is skipped
+pub const INBOUND_DOCUMENT_TYPES: &[&str] = &[
+    "application/pdf",
+    "image/jpeg",
+    "image/png",
+    "image/tiff",
+    "text/plain",
+    "application/msword",
+];
+
+/// Directory under the media root that emailed documents are stored in
+pub const INBOX_DIR: &str = "inbox";
+
+/// Codes in an email subject that may be asset numbers, most specific first.
+///
+/// Codes in square brackets come first, then any other word containing a
+/// digit. Words without digits ("Report", "Annual") are never asset
+/// numbers, so they are left out to avoid false matches.
+pub fn subject_references(subject: &str) -> Vec<String> {
+    let mut references: Vec<String> = Vec::new();
+    let mut push = |code: &str| {
+        let code = code.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+        if code.chars().any(|c| c.is_ascii_digit())
+            && !references.iter().any(|r| r.eq_ignore_ascii_case(code))
+        {
+            references.push(code.to_string());
+        }
+    };
+
+    let mut rest = subject;
+    while let Some(start) = rest.find('[') {
+        let Some(length) = rest[start + 1..].find(']') else { break };
+        push(&rest[start + 1..start + 1 + length]);
+        rest = &rest[start + 1 + length + 1..];
+    }
+
+    for word in subject.split(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '/' | '.'))) {
+        push(word);
+    }
+    references
+}
+
+/// Path, relative to the media root, an attachment received at `received_at`
+/// is stored under. The original name is kept only for its extension.
+pub fn inbox_file_path(file_name: &str, received_at: DateTime<Utc>) -> String {
+    let extension = Path::new(file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or("bin");
+    format!(
+        "{}/{:04}/{:02}/{}.{}",
+        INBOX_DIR,
+        received_at.year(),
+        received_at.month(),
+        uuid::Uuid::new_v4(),
+        extension.to_ascii_lowercase()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subject_references() {
+        assert_eq!(
+            subject_references("FW: Annual inspection [CR-1042] - report CR-1042 attached"),
+            vec!["CR-1042"]
+        );
+        assert_eq!(subject_references("Load test (GC-7, GC-8)."), vec!["GC-7", "GC-8"]);
+        assert_eq!(subject_references("[ hoist 12 ] re: invoice"), vec!["hoist 12", "12"]);
+        assert!(subject_references("Your inspection report").is_empty());
+        assert!(subject_references("Unclosed [CR-1").contains(&"CR-1".to_string()));
+    }
+
+    #[test]
+    fn test_inbox_file_path_keeps_safe_extension() {
+        let received = DateTime::parse_from_rfc3339("2026-10-17T08:30:00Z").unwrap().with_timezone(&Utc);
+        let path = inbox_file_path("Report.PDF", received);
+        assert!(path.starts_with("inbox/2026/10/") && path.ends_with(".pdf"));
+        assert!(inbox_file_path("../../etc/passwd", received).ends_with(".bin"));
+    }
+}
@@ -0,0 +1,292 @@
+//! JSON Schema checks for free-form JSON fields
+//!
+//! Asset and component specifications, inspection checklist data and
+//! standard requirements are stored as JSON. Administrators can attach a
+//! schema to each field per asset type, component type, inspection type or
+//! standard. Only a subset of JSON Schema is understood:
+//!
+//! - `type` (a name or a list of names, including `integer`)
+//! - `properties`, `required` and boolean `additionalProperties`
+//! - `items`, `minItems` and `maxItems`
+//! - `enum`, `minimum`, `maximum`, `minLength` and `maxLength`
+//! - `title`, `description`, `default`, `$schema` and `$id` as annotations
+//!
+//! Schemas using other keywords are rejected when saved so that a rule is
+//! never silently ignored. Violations are reported with the JSON Pointer of
+//! the offending value, such as `/capacity/unit`.
+
+use crate::errors::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+/// Keywords understood by the validator
+pub const SUPPORTED_KEYWORDS: &[&str] = &[
+    "type", "properties", "required", "additionalProperties", "items", "minItems", "maxItems",
+    "enum", "minimum", "maximum", "minLength", "maxLength",
+    "title", "description", "default", "$schema", "$id",
+];
+
+/// Type names accepted by the `type` keyword
+pub const SUPPORTED_TYPES: &[&str] = &["null", "boolean", "object", "array", "number", "integer", "string"];
+
+/// A value that does not match its schema
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    /// JSON Pointer to the value, empty for the document itself
+    pub path: String,
+    pub message: String,
+}
+
+/// Check that `schema` only uses supported keywords and well-formed values
+pub fn check_schema(schema: &JsonValue) -> AppResult<()> {
+    check_schema_at(schema, "")
+}
+
+fn check_schema_at(schema: &JsonValue, path: &str) -> AppResult<()> {
+    let invalid = |message: String| AppError::validation(format!("schema{}", path), message);
+    let object = schema.as_object()
+        .ok_or_else(|| invalid("A schema must be a JSON object".to_string()))?;
+
+    for (keyword, value) in object {
+        if !SUPPORTED_KEYWORDS.contains(&keyword.as_str()) {
+            return Err(invalid(format!("Unsupported keyword '{}'", keyword)));
+        }
+        match keyword.as_str() {
+            "type" => {
+                let names: Vec<&JsonValue> = match value {
+                    JsonValue::Array(names) if !names.is_empty() => names.iter().collect(),
+                    JsonValue::String(_) => vec![value],
+                    _ => return Err(invalid("'type' must be a type name or a list of them".to_string())),
+                };
+                for name in names {
+                    match name.as_str() {
+                        Some(name) if SUPPORTED_TYPES.contains(&name) => {}
+                        _ => return Err(invalid(format!("Unknown type {}", name))),
+                    }
+                }
+            }
+            "properties" => {
+                let properties = value.as_object()
+                    .ok_or_else(|| invalid("'properties' must be an object".to_string()))?;
+                for (name, property) in properties {
+                    check_schema_at(property, &format!("{}/properties/{}", path, escape_pointer(name)))?;
+                }
+            }
+            "required" => {
+                let valid = value.as_array().is_some_and(|names| names.iter().all(JsonValue::is_string));
+                if !valid {
+                    return Err(invalid("'required' must be a list of property names".to_string()));
+                }
+            }
+            "additionalProperties" if !value.is_boolean() => {
+                return Err(invalid("'additionalProperties' must be true or false".to_string()));
+            }
+            "items" => check_schema_at(value, &format!("{}/items", path))?,
+            "enum" if value.as_array().is_none_or(|values| values.is_empty()) => {
+                return Err(invalid("'enum' must be a non-empty list".to_string()));
+            }
+            "minimum" | "maximum" if !value.is_number() => {
+                return Err(invalid(format!("'{}' must be a number", keyword)));
+            }
+            "minItems" | "maxItems" | "minLength" | "maxLength" if value.as_u64().is_none() => {
+                return Err(invalid(format!("'{}' must be a non-negative integer", keyword)));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Every way in which `value` fails to match `schema`
+///
+/// The schema is assumed to have passed [`check_schema`].
+pub fn validate(schema: &JsonValue, value: &JsonValue) -> Vec<SchemaViolation> {
+    let mut violations = Vec::new();
+    validate_at(schema, value, "", &mut violations);
+    violations
+}
+
+/// Fail with a validation error naming `field` and the first violation's path
+pub fn ensure_valid(field: &str, schema: &JsonValue, value: &JsonValue) -> AppResult<()> {
+    let violations = validate(schema, value);
+    match violations.first() {
+        None => Ok(()),
+        Some(first) => Err(AppError::validation(
+            format!("{}{}", field, first.path),
+            violations.iter()
+                .map(|v| format!("{}: {}", display_path(&v.path), v.message))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )),
+    }
+}
+
+fn validate_at(schema: &JsonValue, value: &JsonValue, path: &str, violations: &mut Vec<SchemaViolation>) {
+    let Some(schema) = schema.as_object() else { return };
+    let mut violation = |message: String| violations.push(SchemaViolation { path: path.to_string(), message });
+
+    if let Some(expected) = schema.get("type") {
+        let names: Vec<&str> = match expected {
+            JsonValue::Array(names) => names.iter().filter_map(JsonValue::as_str).collect(),
+            other => other.as_str().into_iter().collect(),
+        };
+        if !names.iter().any(|name| matches_type(name, value)) {
+            violation(format!("expected {}, found {}", names.join(" or "), type_name(value)));
+            // Further keywords would only repeat the type mismatch
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(JsonValue::as_array) {
+        if !allowed.contains(value) {
+            let choices: Vec<String> = allowed.iter().map(JsonValue::to_string).collect();
+            violation(format!("must be one of {}", choices.join(", ")));
+        }
+    }
+
+    if let Some(number) = value.as_f64() {
+        if let Some(minimum) = schema.get("minimum").and_then(JsonValue::as_f64) {
+            if number < minimum {
+                violation(format!("must be at least {}", minimum));
+            }
+        }
+        if let Some(maximum) = schema.get("maximum").and_then(JsonValue::as_f64) {
+            if number > maximum {
+                violation(format!("must be at most {}", maximum));
+            }
+        }
+    }
+
+    if let Some(text) = value.as_str() {
+        let length = text.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(JsonValue::as_u64) {
+            if length < min {
+                violation(format!("must be at least {} characters", min));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(JsonValue::as_u64) {
+            if length > max {
+                violation(format!("must be at most {} characters", max));
+            }
+        }
+    }
+
+    if let Some(items) = value.as_array() {
+        let count = items.len() as u64;
+        if let Some(min) = schema.get("minItems").and_then(JsonValue::as_u64) {
+            if count < min {
+                violation(format!("must have at least {} items", min));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(JsonValue::as_u64) {
+            if count > max {
+                violation(format!("must have at most {} items", max));
+            }
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (index, item) in items.iter().enumerate() {
+                validate_at(item_schema, item, &format!("{}/{}", path, index), violations);
+            }
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(JsonValue::as_object);
+        if let Some(required) = schema.get("required").and_then(JsonValue::as_array) {
+            for name in required.iter().filter_map(JsonValue::as_str) {
+                if !object.contains_key(name) {
+                    violations.push(SchemaViolation {
+                        path: format!("{}/{}", path, escape_pointer(name)),
+                        message: "is required".to_string(),
+                    });
+                }
+            }
+        }
+        let closed = schema.get("additionalProperties") == Some(&JsonValue::Bool(false));
+        for (name, property) in object {
+            let property_path = format!("{}/{}", path, escape_pointer(name));
+            match properties.and_then(|p| p.get(name)) {
+                Some(property_schema) => validate_at(property_schema, property, &property_path, violations),
+                None if closed => violations.push(SchemaViolation {
+                    path: property_path,
+                    message: "is not an allowed property".to_string(),
+                }),
+                None => {}
+            }
+        }
+    }
+}
+
+fn matches_type(name: &str, value: &JsonValue) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "string" => value.is_string(),
+        _ => false,
+    }
+}
+
+fn type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Object(_) => "object",
+        JsonValue::Array(_) => "array",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+    }
+}
+
+/// Escape a property name for use in a JSON Pointer (RFC 6901)
+fn escape_pointer(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+fn display_path(path: &str) -> &str {
+    if path.is_empty() { "/" } else { path }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_reports_pointer_paths() {
+        let schema = json!({
+            "type": "object",
+            "required": ["span_m", "capacity"],
+            "additionalProperties": false,
+            "properties": {
+                "span_m": {"type": "number", "minimum": 0},
+                "capacity": {
+                    "type": "object",
+                    "properties": {"unit": {"enum": ["kg", "t", "lb"]}}
+                },
+                "hooks": {"type": "array", "maxItems": 2, "items": {"type": "integer"}}
+            }
+        });
+        check_schema(&schema).unwrap();
+
+        let valid = json!({"span_m": 12.5, "capacity": {"unit": "t"}, "hooks": [1, 2]});
+        assert!(validate(&schema, &valid).is_empty());
+
+        let invalid = json!({"span_m": -1, "capacity": {"unit": "ton"}, "hooks": [1, "b"], "color": "red"});
+        let paths: Vec<String> = validate(&schema, &invalid).into_iter().map(|v| v.path).collect();
+        assert_eq!(paths, vec!["/capacity/unit", "/color", "/hooks/1", "/span_m"]);
+
+        let err = ensure_valid("specifications", &schema, &json!({"span_m": 3})).unwrap_err();
+        assert!(matches!(err, AppError::Validation { ref field, .. } if field == "specifications/capacity"));
+    }
+
+    #[test]
+    fn test_check_schema_rejects_unsupported_keywords() {
+        assert!(check_schema(&json!({"type": "string", "pattern": "^A"})).is_err());
+        assert!(check_schema(&json!({"properties": {"a": {"type": "decimal"}}})).is_err());
+        assert!(check_schema(&json!(["not", "an", "object"])).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! LDAP directory sign-in
+//!
+//! Sites running Active Directory or another LDAP directory can let staff
+//! sign in with their domain accounts. The password is checked by binding
+//! to the directory as the user; the user's entry is then read for their
+//! name, email address and groups, which decide their role. The protocol is
+//! handled by the `ldap3` crate over plain TCP or LDAPS. Its client blocks,
+//! so callers on the async runtime run it on a blocking thread. Group
+//! membership comes from `memberOf`, so nested groups are not followed.
+
+use crate::errors::{AppError, AppResult};
+use crate::models::LdapConfig;
+use ldap3::{dn_escape, ldap_escape, LdapConn, LdapConnSettings, LdapError, Scope, SearchEntry, SearchOptions};
+use std::time::Duration;
+
+const RESULT_INVALID_CREDENTIALS: u32 = 49;
+
+const ATTRIBUTE_MAIL: &str = "mail";
+const ATTRIBUTE_GIVEN_NAME: &str = "givenName";
+const ATTRIBUTE_SURNAME: &str = "sn";
+const ATTRIBUTE_MEMBER_OF: &str = "memberOf";
+
+/// A directory account whose password has been checked
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DirectoryUser {
+    pub dn: String,
+    pub email: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    /// Distinguished names of the groups the account is a direct member of
+    pub groups: Vec<String>,
+}
+
+impl From<SearchEntry> for DirectoryUser {
+    fn from(entry: SearchEntry) -> Self {
+        let mut user = DirectoryUser { dn: entry.dn, ..Default::default() };
+        for (name, values) in entry.attrs {
+            // Attribute names are case-insensitive
+            match name.to_ascii_lowercase().as_str() {
+                "mail" => user.email = values.into_iter().next(),
+                "givenname" => user.first_name = values.into_iter().next(),
+                "sn" => user.last_name = values.into_iter().next(),
+                "memberof" => user.groups = values,
+                _ => {}
+            }
+        }
+        user
+    }
+}
+
+/// Check `username` and `password` against the directory and read the
+/// account's entry. Blocks until the directory answers or the configured
+/// timeout passes.
+pub fn authenticate(config: &LdapConfig, username: &str, password: &str) -> AppResult<DirectoryUser> {
+    // An empty password would be an unauthenticated bind, which directories
+    // accept for any name
+    if username.trim().is_empty() || password.is_empty() {
+        return Err(AppError::authentication("Invalid credentials"));
+    }
+
+    let settings = LdapConnSettings::new().set_conn_timeout(timeout(config));
+    let mut connection = LdapConn::with_settings(settings, config.url.trim()).map_err(|e| ldap_error(config, e))?;
+
+    let bind_dn = config.bind_dn_template.replace("{username}", &dn_escape(username));
+    let result = bind(&mut connection, config, &bind_dn, password)
+        .and_then(|()| find_user(&mut connection, config, username));
+    // The connection closes either way
+    let _ = connection.unbind();
+    result
+}
+
+fn timeout(config: &LdapConfig) -> Duration {
+    Duration::from_secs(config.timeout_seconds.max(1))
+}
+
+fn directory_error(message: impl Into<String>) -> AppError {
+    AppError::ExternalService { service: "LDAP".to_string(), message: message.into() }
+}
+
+fn ldap_error(config: &LdapConfig, error: LdapError) -> AppError {
+    match error {
+        LdapError::Timeout { .. } => AppError::ConnectionTimeout {
+            url: config.url.clone(),
+            timeout: timeout(config).as_secs(),
+        },
+        error => directory_error(error.to_string()),
+    }
+}
+
+fn bind(connection: &mut LdapConn, config: &LdapConfig, dn: &str, password: &str) -> AppResult<()> {
+    let result = connection
+        .with_timeout(timeout(config))
+        .simple_bind(dn, password)
+        .map_err(|e| ldap_error(config, e))?;
+    match result.rc {
+        0 => Ok(()),
+        RESULT_INVALID_CREDENTIALS => Err(AppError::authentication("Invalid credentials")),
+        code => Err(directory_error(format!("Bind failed with result {}: {}", code, result.text))),
+    }
+}
+
+fn find_user(connection: &mut LdapConn, config: &LdapConfig, username: &str) -> AppResult<DirectoryUser> {
+    let attribute = config.username_attribute.trim();
+    let result = connection
+        .with_timeout(timeout(config))
+        // One match plus a duplicate
+        .with_search_options(SearchOptions::new().sizelimit(2))
+        .search(
+            config.search_base.trim(),
+            Scope::Subtree,
+            &user_filter(attribute, username),
+            vec![ATTRIBUTE_MAIL, ATTRIBUTE_GIVEN_NAME, ATTRIBUTE_SURNAME, ATTRIBUTE_MEMBER_OF],
+        )
+        .map_err(|e| ldap_error(config, e))?;
+
+    // Referrals to other servers are not followed
+    let mut entries = result.0.into_iter().filter(|entry| !entry.is_ref());
+    let found = entries.next();
+    if entries.next().is_some() {
+        return Err(directory_error(format!("More than one entry has {}={}", attribute, username)));
+    }
+    if result.1.rc != 0 {
+        return Err(directory_error(format!("Search failed with result {}: {}", result.1.rc, result.1.text)));
+    }
+    found
+        .map(|entry| SearchEntry::construct(entry).into())
+        .ok_or_else(|| AppError::authentication("Directory account not found"))
+}
+
+/// Equality filter matching `value` literally
+fn user_filter(attribute: &str, value: &str) -> String {
+    format!("({}={})", attribute, ldap_escape(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_directory_user_from_entry() {
+        let entry = SearchEntry {
+            dn: "CN=John Smith,OU=Users".to_string(),
+            attrs: HashMap::from([
+                ("memberOf".to_string(), vec!["CN=Crane Admins,OU=Groups".to_string(), "CN=Staff,OU=Groups".to_string()]),
+                ("MAIL".to_string(), vec!["j.smith@corp.example.com".to_string()]),
+            ]),
+            bin_attrs: HashMap::new(),
+        };
+
+        let user = DirectoryUser::from(entry);
+        assert_eq!(user.dn, "CN=John Smith,OU=Users");
+        assert_eq!(user.email.as_deref(), Some("j.smith@corp.example.com"));
+        assert_eq!(user.first_name, None);
+        assert_eq!(user.groups, vec!["CN=Crane Admins,OU=Groups", "CN=Staff,OU=Groups"]);
+    }
+
+    #[test]
+    fn test_user_filter_and_invalid_url() {
+        assert_eq!(user_filter("sAMAccountName", "jsmith"), "(sAMAccountName=jsmith)");
+        assert_eq!(user_filter("uid", "*)(uid=*"), "(uid=\\2a\\29\\28uid=\\2a)");
+
+        let config = LdapConfig { url: "http://dc01".to_string(), ..LdapConfig::default() };
+        assert!(authenticate(&config, "jsmith", "secret").is_err());
+        assert!(authenticate(&config, "jsmith", "").is_err());
+    }
+}
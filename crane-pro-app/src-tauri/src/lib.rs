@@ -1,6 +1,6 @@
-use log::info;
+use log::{info, warn};
 use std::sync::Arc;
-use tauri::Manager;
+use tauri::{Manager, RunEvent};
 
 // Module declarations
 pub mod database;
@@ -11,16 +11,53 @@ pub mod errors;
 pub mod api;
 pub mod middleware;
 pub mod commands;
+pub mod logging;
+pub mod i18n;
+pub mod units;
+pub mod scheduling;
+pub mod export;
+pub mod exporters;
+pub mod warehouse;
+pub mod shutdown;
+pub mod geo;
+pub mod inbox;
+pub mod forecast;
+pub mod totp;
+pub mod ldap;
+pub mod oidc;
+pub mod qr;
+pub mod photo;
+pub mod rate_limit;
+pub mod reports;
+pub mod json_schema;
+pub mod seed;
+pub mod activity;
+pub mod search;
+pub mod notifications;
+pub mod authz;
+pub mod trace;
+pub mod operations;
+pub mod cranepack;
 
 // Test infrastructure
 #[cfg(test)]
 pub mod test_fixtures;
 
 use crate::errors::AppResult;
-use crate::database::Database;
+use crate::database::{run_scheduled_maintenance, Database, DatabaseConfig};
 use crate::services::Services;
 use crate::middleware::auth::AuthManager;
 use crate::commands::AppState;
+use crate::logging::{LogManager, LoggingConfig};
+use crate::shutdown::{PreviousShutdown, ShutdownCoordinator};
+use crate::operations::OperationRegistry;
+use crate::security::fields::FieldCipher;
+use crate::security::secrets::Secrets;
+use crate::notifications::{
+    run_due_notifications, run_outbox_dispatcher, EmailChannel, EventChannel, DUE_NOTIFICATION_INTERVAL, OUTBOX_INTERVAL,
+};
+use crate::warehouse::run_scheduled_warehouse_exports;
+use crate::reports::store::{run_report_cleanup, REPORT_CLEANUP_INTERVAL};
 
 // Import all command handlers
 use crate::commands::{
@@ -28,21 +65,36 @@ use crate::commands::{
     create_asset_command, get_asset_command, get_assets_by_location_command,
     update_asset_command, delete_asset_command, search_assets_command,
     get_asset_components_command, create_component_command, update_component_command,
-    validate_asset_assignment_command,
+    validate_asset_assignment_command, get_asset_card_command, get_asset_cards_command,
+    get_asset_link_command, resolve_deep_link_command,
+    restore_asset_command, purge_asset_command,
+    get_component_templates_command, save_component_template_command, delete_component_template_command,
     
     // Inspection commands
     create_inspection_command, get_inspection_command, update_inspection_command,
     submit_inspection_command, get_inspections_by_asset_command, get_pending_inspections_command,
-    create_inspection_item_command, update_inspection_item_command, get_inspection_items_command,
+    create_inspection_item_command, create_inspection_items_batch_command, update_inspection_item_command,
+    get_inspection_items_command,
+    delete_inspection_command, restore_inspection_command, purge_inspection_command,
+    attach_inspection_item_photo_command, record_paper_transcription_command, get_paper_transcription_command,
     
     // Compliance commands
     create_compliance_record_command, get_compliance_record_command, get_compliance_records_by_asset_command,
     update_compliance_record_command, get_compliance_status_command, get_upcoming_requirements_command,
-    mark_compliance_complete_command,
+    mark_compliance_complete_command, generate_inspection_checklist_command, set_checklist_item_guidance_command,
+    import_standard_clauses_command, get_standard_clauses_command, delete_standard_clause_command,
     
     // User commands
     create_user_command, get_user_command, get_current_user_command, update_user_command,
-    delete_user_command, login_command, logout_command, get_users_command, change_password_command,
+    delete_user_command, login_command, oidc_login_command, refresh_token_command, logout_command, get_users_command,
+    change_password_command, request_password_reset_command, create_password_reset_command,
+    complete_password_reset_command,
+    set_user_locale_command, get_user_preferences_command, set_user_preferences_command,
+    create_user_absence_command, get_user_absences_command,
+    delete_user_absence_command, get_available_inspectors_command,
+    restore_user_command, purge_user_command, list_active_sessions_command, revoke_session_command,
+    rotate_jwt_secret_command,
+    get_account_lockout_command, unlock_user_command, get_my_login_history_command, get_login_history_command,
     
     // Media commands
     upload_file_command, get_file_command, get_files_by_inspection_command, delete_file_command,
@@ -50,12 +102,158 @@ use crate::commands::{
     
     // Report commands
     generate_inspection_report_command, generate_compliance_report_command, get_report_command,
-    list_available_reports_command,
+    list_available_reports_command, create_report_share_link_command, get_report_share_links_command,
+    revoke_report_share_link_command, open_shared_report_command, generate_paper_checklist_command,
+    generate_summary_report_csv_command, get_report_templates_command, create_report_template_command,
+    update_report_template_command, assign_report_template_command, preview_report_template_command,
+    verify_report_signature_command,
     
     // Location commands
     create_location_command, get_location_command, update_location_command,
     delete_location_command, get_location_with_assets_command, get_location_asset_summary_command,
     validate_asset_location_assignment_command, search_locations_with_asset_counts_command,
+    get_location_tree_command, get_location_path_command, get_location_rollup_command, move_location_command,
+    search_locations_geo_command, get_map_pins_command, get_location_calendar_command,
+    set_location_working_days_command, add_location_closure_command, delete_location_closure_command,
+    
+    // System commands
+    get_recent_logs_command, get_trace_command, seed_demo_data_command, run_data_quality_checks_command,
+    get_migration_status_command, run_migrations_command, rollback_to_version_command, rollback_last_upgrade_command,
+    run_db_maintenance_command, query_audit_log_command, get_security_events_command,
+
+    // Export commands
+    export_data_command, run_warehouse_export_command, get_warehouse_export_status_command,
+
+    // Team commands
+    create_team_command, get_team_command, get_teams_command, get_my_teams_command,
+    update_team_command, delete_team_command, add_team_member_command, remove_team_member_command,
+    assign_team_location_command, unassign_team_location_command,
+    get_team_pending_inspections_command, get_team_completion_stats_command,
+
+    // Comment commands
+    add_comment_command, get_comments_command, edit_comment_command, delete_comment_command,
+    get_comment_history_command, get_my_mentions_command, mark_mentions_read_command,
+
+    // Activity commands
+    get_activity_feed_command,
+
+    // Watch commands
+    watch_record_command, unwatch_record_command, get_my_watches_command,
+    get_watched_due_soon_command,
+
+    // Recycle bin commands
+    list_deleted_records_command, restore_record_command,
+
+    // Bulk commands
+    bulk_operation_command, bulk_update_assets_command,
+
+    // Search commands
+    global_search_command,
+
+    // Dashboard commands
+    get_dashboard_summary_command, get_dashboard_command, get_workload_forecast_command,
+    get_fleet_benchmarks_command, get_since_last_login_command,
+
+    // Pre-start check commands
+    get_prestart_template_command, record_prestart_check_command, get_prestart_checks_command,
+    get_missed_prestart_checks_command, set_prestart_required_command,
+
+    // History commands
+    get_entity_history_command,
+
+    // Asset record commands
+    add_asset_record_command, update_asset_record_command, delete_asset_record_command,
+    get_asset_records_command, get_expiring_asset_records_command,
+
+    // Operator authorization commands
+    grant_operator_authorization_command, revoke_operator_authorization_command,
+    get_operator_authorizations_command, check_operator_authorization_command,
+    get_authorization_matrix_command,
+
+    // Training commands
+    add_training_record_command, update_training_record_command, delete_training_record_command,
+    get_training_records_command, get_expiring_training_command,
+
+    // Work order commands
+    create_work_order_command, update_work_order_command, transition_work_order_command,
+    get_work_order_command, get_work_orders_command,
+
+    // Notification commands
+    get_notifications_command, get_unread_notification_count_command, mark_notification_read_command,
+    mark_all_notifications_read_command, get_notification_preferences_command, set_notification_preference_command,
+    get_notification_digest_settings_command, set_notification_digest_settings_command,
+    preview_notification_digest_command,
+
+    // Risk matrix commands
+    get_risk_matrix_command, update_risk_matrix_command,
+
+    // Finding SLA commands
+    acknowledge_critical_finding_command, resolve_critical_finding_command, get_finding_slas_command,
+    get_sla_targets_command, set_sla_targets_command, get_sla_performance_report_command,
+
+    // Settings commands
+    get_settings_command, update_setting_command,
+
+    // Parts commands
+    create_part_command, update_part_command, get_parts_command, set_part_compatibility_command,
+    get_compatible_parts_command, adjust_part_stock_command, record_part_consumption_command,
+    get_low_stock_parts_command, get_part_movements_command,
+
+    // Defect commands
+    get_defects_command, get_defect_command, update_defect_status_command,
+
+    // JSON schema commands
+    get_json_schemas_command, save_json_schema_command, delete_json_schema_command,
+    audit_json_schemas_command,
+
+    // Corrective action commands
+    create_corrective_action_command, update_corrective_action_command,
+    attach_corrective_action_evidence_command, start_corrective_action_command,
+    submit_corrective_action_command, verify_corrective_action_command, cancel_corrective_action_command,
+    get_corrective_action_command, get_corrective_actions_command,
+
+    // Validation rule commands
+    get_validation_rules_command, create_validation_rule_command, update_validation_rule_command,
+    delete_validation_rule_command, check_inspection_rules_command,
+
+    // Maintenance commands
+    create_maintenance_record_command, update_maintenance_record_command, start_maintenance_command,
+    complete_maintenance_command, cancel_maintenance_command, get_maintenance_record_command,
+    get_maintenance_records_by_asset_command,
+
+    // Vendor document commands
+    ingest_vendor_email_command, get_unmatched_vendor_documents_command, get_asset_vendor_documents_command,
+    file_vendor_document_command, discard_vendor_document_command,
+
+    // MFA commands
+    verify_mfa_command, enroll_mfa_command, confirm_mfa_command, get_mfa_status_command,
+    reset_mfa_command, get_mfa_policies_command, set_mfa_policy_command,
+
+    // Kiosk commands
+    kiosk_login_command, register_kiosk_terminal_command, get_kiosk_terminals_command,
+    deactivate_kiosk_terminal_command, set_kiosk_credentials_command, clear_kiosk_credentials_command,
+
+    // Trusted device commands
+    register_trusted_device_command, get_trusted_devices_command, revoke_trusted_device_command,
+    unlock_device_command,
+
+    // Legal hold commands
+    place_legal_hold_command, release_legal_hold_command, get_legal_holds_command,
+    generate_legal_hold_register_command,
+
+    // Role commands
+    get_roles_command, get_permission_catalog_command, create_role_command, update_role_command,
+    delete_role_command, assign_user_role_command, generate_permission_matrix_command,
+
+    // API key commands
+    get_api_keys_command, create_api_key_command, revoke_api_key_command,
+    
+    // Operation commands
+    list_operations_command, cancel_operation_command,
+    
+    // Checklist pack commands
+    get_pack_signing_identity_command, export_checklist_pack_command, import_checklist_pack_command,
+    get_trusted_pack_publishers_command, trust_pack_publisher_command, remove_trusted_pack_publisher_command,
 };
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
@@ -73,47 +271,123 @@ async fn health_check() -> AppResult<String> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize logging
-    env_logger::init();
-    info!("Starting CranePro Bridge Inspection Application");
-
     tauri::Builder::default()
         // Core plugins
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
-        // .plugin(tauri_plugin_stronghold::Builder::new(|_| Ok(())).build()) // Temporarily disabled
         
         // Setup handler for app initialization
         .setup(|app| {
+            // Initialize logging under the app data directory
+            let data_dir = app.path().app_data_dir()?;
+            let log_manager = LogManager::init(LoggingConfig::from_env(data_dir.join("logs")))
+                .expect("Failed to initialize logging");
+            app.manage(log_manager);
+            info!("Starting CranePro Bridge Inspection Application");
             info!("Initializing CranePro application...");
+
+            // Check how the previous run ended and mark this one as running
+            let shutdown = ShutdownCoordinator::start(&data_dir)
+                .expect("Failed to initialize shutdown coordinator");
+            if shutdown.previous_shutdown() == PreviousShutdown::Unclean {
+                warn!("Previous session did not shut down cleanly; pending writes may have been lost");
+            }
             
-            // Initialize database
+            // Load application secrets, generating them on first run
+            let secrets = Secrets::open(&data_dir).expect("Failed to open the secret store");
+            let jwt_secret = secrets.jwt_secret().expect("Failed to load the JWT secret");
+            let database_key = secrets.database_key().expect("Failed to load the database key");
+            let field_cipher = Arc::new(FieldCipher::new(&database_key));
+
+            // Open the database under the app data directory; upgrades
+            // snapshot it alongside so they can be rolled back
             let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
             let database = rt.block_on(async {
-                Database::new_in_memory().await
+                Database::new(DatabaseConfig::file(data_dir.join("crane_pro.db")).with_env_overrides()).await
                     .expect("Failed to initialize database")
             });
             let database = Arc::new(database);
             
             // Initialize services
             let services = rt.block_on(async {
-                Services::init(database.clone()).await
+                Services::init(database.clone(), field_cipher).await
                     .expect("Failed to initialize services")
             });
             let services = Arc::new(services);
             
-            // Initialize authentication manager
-            let jwt_secret = std::env::var("JWT_SECRET")
-                .unwrap_or_else(|_| "default-secret-key-change-in-production".to_string());
-            let auth_manager = Arc::new(AuthManager::new(services.clone(), &jwt_secret));
+            // Persist audit entries and security events from here on
+            crate::middleware::set_audit_sink(services.audit.clone());
+            crate::middleware::set_security_event_sink(services.security_events.clone());
+            
+            // Drop deleted records past the recycle bin retention window
+            if let Err(e) = services.recycle_bin.purge_expired() {
+                warn!("Failed to purge expired recycle bin entries: {}", e);
+            }
+            
+            // Drop security events and login history past the configured retention
+            if let Err(e) = services.security_events.purge_expired() {
+                warn!("Failed to purge expired security events: {}", e);
+            }
+            if let Err(e) = services.login_history.purge_expired() {
+                warn!("Failed to purge expired login history: {}", e);
+            }
+            
+            // Initialize authentication manager, still accepting tokens
+            // signed with a rotated-out secret during its grace window
+            let mut auth_manager = AuthManager::new(services.clone(), &jwt_secret);
+            match secrets.previous_jwt_secret(chrono::Utc::now()) {
+                Ok(Some(previous)) => {
+                    auth_manager = auth_manager.with_previous_secret(&previous.secret, previous.valid_until);
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Failed to load the previous JWT secret: {}", e),
+            }
+            let auth_manager = Arc::new(auth_manager);
+            app.manage(secrets);
+            
+            // Flush hooks run in registration order on exit
+            let checkpoint_db = database.clone();
+            shutdown.register_hook("database checkpoint", move || checkpoint_db.checkpoint());
+            
+            // Run database maintenance in the background until shutdown
+            if let Some(interval) = database.config().maintenance_interval {
+                tauri::async_runtime::spawn(run_scheduled_maintenance(
+                    database.clone(), interval, shutdown.subscribe(),
+                ));
+            }
+            
+            // Deliver notifications to the running app, and by email when a
+            // mail pickup directory is configured
+            services.notifications.register_channel(Arc::new(EventChannel::new(app.handle().clone())));
+            if let Some(email) = EmailChannel::from_env() {
+                services.notifications.register_channel(Arc::new(email));
+            }
+            tauri::async_runtime::spawn(run_due_notifications(
+                services.notifications.clone(), DUE_NOTIFICATION_INTERVAL, shutdown.subscribe(),
+            ));
+            tauri::async_runtime::spawn(run_outbox_dispatcher(
+                services.notifications.clone(), OUTBOX_INTERVAL, shutdown.subscribe(),
+            ));
+            
+            // Write data warehouse extracts when a directory is configured
+            tauri::async_runtime::spawn(run_scheduled_warehouse_exports(
+                services.warehouse.clone(), shutdown.subscribe(),
+            ));
+            
+            // Delete generated reports once they expire
+            tauri::async_runtime::spawn(run_report_cleanup(
+                services.clone(), REPORT_CLEANUP_INTERVAL, shutdown.subscribe(),
+            ));
             
             // Create app state
             let app_state = AppState::new(services, auth_manager);
             
             // Manage state
             app.manage(app_state);
+            app.manage(OperationRegistry::new());
+            app.manage(shutdown);
             
             info!("Application initialization completed");
             Ok(())
@@ -125,7 +399,7 @@ pub fn run() {
             greet,
             health_check,
             
-            // Asset management commands (10 commands)
+            // Asset management commands (19 commands)
             create_asset_command,
             get_asset_command,
             get_assets_by_location_command,
@@ -136,8 +410,17 @@ pub fn run() {
             create_component_command,
             update_component_command,
             validate_asset_assignment_command,
+            get_asset_card_command,
+            get_asset_cards_command,
+            get_asset_link_command,
+            resolve_deep_link_command,
+            restore_asset_command,
+            purge_asset_command,
+            get_component_templates_command,
+            save_component_template_command,
+            delete_component_template_command,
             
-            // Inspection management commands (9 commands)
+            // Inspection management commands (16 commands)
             create_inspection_command,
             get_inspection_command,
             update_inspection_command,
@@ -145,10 +428,17 @@ pub fn run() {
             get_inspections_by_asset_command,
             get_pending_inspections_command,
             create_inspection_item_command,
+            create_inspection_items_batch_command,
             update_inspection_item_command,
             get_inspection_items_command,
+            delete_inspection_command,
+            restore_inspection_command,
+            purge_inspection_command,
+            attach_inspection_item_photo_command,
+            record_paper_transcription_command,
+            get_paper_transcription_command,
             
-            // Compliance management commands (7 commands)
+            // Compliance management commands (12 commands)
             create_compliance_record_command,
             get_compliance_record_command,
             get_compliance_records_by_asset_command,
@@ -156,17 +446,43 @@ pub fn run() {
             get_compliance_status_command,
             get_upcoming_requirements_command,
             mark_compliance_complete_command,
+            generate_inspection_checklist_command,
+            set_checklist_item_guidance_command,
+            import_standard_clauses_command,
+            get_standard_clauses_command,
+            delete_standard_clause_command,
             
-            // User management commands (9 commands)
+            // User management commands (30 commands)
             create_user_command,
             get_user_command,
             get_current_user_command,
             update_user_command,
             delete_user_command,
             login_command,
+            oidc_login_command,
+            refresh_token_command,
             logout_command,
             get_users_command,
             change_password_command,
+            request_password_reset_command,
+            create_password_reset_command,
+            complete_password_reset_command,
+            set_user_locale_command,
+            get_user_preferences_command,
+            set_user_preferences_command,
+            create_user_absence_command,
+            get_user_absences_command,
+            delete_user_absence_command,
+            get_available_inspectors_command,
+            restore_user_command,
+            purge_user_command,
+            list_active_sessions_command,
+            revoke_session_command,
+            rotate_jwt_secret_command,
+            get_account_lockout_command,
+            unlock_user_command,
+            get_my_login_history_command,
+            get_login_history_command,
             
             // Media management commands (7 commands)
             upload_file_command,
@@ -177,13 +493,25 @@ pub fn run() {
             upload_inspection_photo_command,
             get_inspection_photos_command,
             
-            // Report generation commands (4 commands)
+            // Report generation commands (16 commands)
             generate_inspection_report_command,
             generate_compliance_report_command,
             get_report_command,
             list_available_reports_command,
+            create_report_share_link_command,
+            get_report_share_links_command,
+            revoke_report_share_link_command,
+            open_shared_report_command,
+            generate_paper_checklist_command,
+            generate_summary_report_csv_command,
+            get_report_templates_command,
+            create_report_template_command,
+            update_report_template_command,
+            assign_report_template_command,
+            preview_report_template_command,
+            verify_report_signature_command,
             
-            // Location management commands (8 commands)
+            // Location management commands (18 commands)
             create_location_command,
             get_location_command,
             update_location_command,
@@ -192,8 +520,269 @@ pub fn run() {
             get_location_asset_summary_command,
             validate_asset_location_assignment_command,
             search_locations_with_asset_counts_command,
+            get_location_tree_command,
+            get_location_path_command,
+            get_location_rollup_command,
+            move_location_command,
+            search_locations_geo_command,
+            get_map_pins_command,
+            get_location_calendar_command,
+            set_location_working_days_command,
+            add_location_closure_command,
+            delete_location_closure_command,
+            
+            // System commands (11 commands)
+            get_recent_logs_command,
+            get_trace_command,
+            seed_demo_data_command,
+            run_data_quality_checks_command,
+            get_migration_status_command,
+            run_migrations_command,
+            rollback_to_version_command,
+            rollback_last_upgrade_command,
+            run_db_maintenance_command,
+            query_audit_log_command,
+            get_security_events_command,
+            
+            // Data export commands (3 commands)
+            export_data_command,
+            run_warehouse_export_command,
+            get_warehouse_export_status_command,
+            
+            // Team management commands (12 commands)
+            create_team_command,
+            get_team_command,
+            get_teams_command,
+            get_my_teams_command,
+            update_team_command,
+            delete_team_command,
+            add_team_member_command,
+            remove_team_member_command,
+            assign_team_location_command,
+            unassign_team_location_command,
+            get_team_pending_inspections_command,
+            get_team_completion_stats_command,
+            
+            // Comment commands (7 commands)
+            add_comment_command,
+            get_comments_command,
+            edit_comment_command,
+            delete_comment_command,
+            get_comment_history_command,
+            get_my_mentions_command,
+            mark_mentions_read_command,
+            
+            // Activity feed commands (1 command)
+            get_activity_feed_command,
+            
+            // Watchlist commands (4 commands)
+            watch_record_command,
+            unwatch_record_command,
+            get_my_watches_command,
+            get_watched_due_soon_command,
+            
+            // Recycle bin commands (2 commands)
+            list_deleted_records_command,
+            restore_record_command,
+            
+            // Bulk operation commands (2 commands)
+            bulk_operation_command,
+            bulk_update_assets_command,
+            
+            // Search commands (1 command)
+            global_search_command,
+            
+            // Dashboard commands (5 commands)
+            get_dashboard_summary_command,
+            get_dashboard_command,
+            get_workload_forecast_command,
+            get_fleet_benchmarks_command,
+            get_since_last_login_command,
+            
+            // Pre-start check commands (5 commands)
+            get_prestart_template_command,
+            record_prestart_check_command,
+            get_prestart_checks_command,
+            get_missed_prestart_checks_command,
+            set_prestart_required_command,
+            
+            // Entity history commands (1 command)
+            get_entity_history_command,
+            
+            // Asset record commands (5 commands)
+            add_asset_record_command,
+            update_asset_record_command,
+            delete_asset_record_command,
+            get_asset_records_command,
+            get_expiring_asset_records_command,
+            
+            // Operator authorization commands (5 commands)
+            grant_operator_authorization_command,
+            revoke_operator_authorization_command,
+            get_operator_authorizations_command,
+            check_operator_authorization_command,
+            get_authorization_matrix_command,
+            
+            // Training commands (5 commands)
+            add_training_record_command,
+            update_training_record_command,
+            delete_training_record_command,
+            get_training_records_command,
+            get_expiring_training_command,
+            
+            // Work order commands (5 commands)
+            create_work_order_command,
+            update_work_order_command,
+            transition_work_order_command,
+            get_work_order_command,
+            get_work_orders_command,
+            
+            // Notification commands (9 commands)
+            get_notifications_command,
+            get_unread_notification_count_command,
+            mark_notification_read_command,
+            mark_all_notifications_read_command,
+            get_notification_preferences_command,
+            set_notification_preference_command,
+            get_notification_digest_settings_command,
+            set_notification_digest_settings_command,
+            preview_notification_digest_command,
+            
+            // Risk matrix commands (2 commands)
+            get_risk_matrix_command,
+            update_risk_matrix_command,
+            
+            // Finding SLA commands (6 commands)
+            acknowledge_critical_finding_command,
+            resolve_critical_finding_command,
+            get_finding_slas_command,
+            get_sla_targets_command,
+            set_sla_targets_command,
+            get_sla_performance_report_command,
+            
+            // Settings commands (2 commands)
+            get_settings_command,
+            update_setting_command,
+            
+            // Spare parts commands (9 commands)
+            create_part_command,
+            update_part_command,
+            get_parts_command,
+            set_part_compatibility_command,
+            get_compatible_parts_command,
+            adjust_part_stock_command,
+            record_part_consumption_command,
+            get_low_stock_parts_command,
+            get_part_movements_command,
+            
+            // Defect commands (3 commands)
+            get_defects_command,
+            get_defect_command,
+            update_defect_status_command,
+            
+            // JSON schema commands (4 commands)
+            get_json_schemas_command,
+            save_json_schema_command,
+            delete_json_schema_command,
+            audit_json_schemas_command,
+            
+            // Corrective action commands (9 commands)
+            create_corrective_action_command,
+            update_corrective_action_command,
+            attach_corrective_action_evidence_command,
+            start_corrective_action_command,
+            submit_corrective_action_command,
+            verify_corrective_action_command,
+            cancel_corrective_action_command,
+            get_corrective_action_command,
+            get_corrective_actions_command,
+            
+            // Validation rule commands (5 commands)
+            get_validation_rules_command,
+            create_validation_rule_command,
+            update_validation_rule_command,
+            delete_validation_rule_command,
+            check_inspection_rules_command,
+            
+            // Maintenance commands (7 commands)
+            create_maintenance_record_command,
+            update_maintenance_record_command,
+            start_maintenance_command,
+            complete_maintenance_command,
+            cancel_maintenance_command,
+            get_maintenance_record_command,
+            get_maintenance_records_by_asset_command,
+
+            // Vendor document commands (5 commands)
+            ingest_vendor_email_command,
+            get_unmatched_vendor_documents_command,
+            get_asset_vendor_documents_command,
+            file_vendor_document_command,
+            discard_vendor_document_command,
+
+            // MFA commands (7 commands)
+            verify_mfa_command,
+            enroll_mfa_command,
+            confirm_mfa_command,
+            get_mfa_status_command,
+            reset_mfa_command,
+            get_mfa_policies_command,
+            set_mfa_policy_command,
+
+            // Kiosk commands (6 commands)
+            kiosk_login_command,
+            register_kiosk_terminal_command,
+            get_kiosk_terminals_command,
+            deactivate_kiosk_terminal_command,
+            set_kiosk_credentials_command,
+            clear_kiosk_credentials_command,
+
+            // Trusted device commands (4 commands)
+            register_trusted_device_command,
+            get_trusted_devices_command,
+            revoke_trusted_device_command,
+            unlock_device_command,
+
+            // Legal hold commands (4 commands)
+            place_legal_hold_command,
+            release_legal_hold_command,
+            get_legal_holds_command,
+            generate_legal_hold_register_command,
+            
+            // Role commands (7 commands)
+            get_roles_command,
+            get_permission_catalog_command,
+            create_role_command,
+            update_role_command,
+            delete_role_command,
+            assign_user_role_command,
+            generate_permission_matrix_command,
+
+            // API key commands (3 commands)
+            get_api_keys_command,
+            create_api_key_command,
+            revoke_api_key_command,
+            
+            // Operation commands (2 commands)
+            list_operations_command,
+            cancel_operation_command,
+            
+            // Checklist pack commands (6 commands)
+            get_pack_signing_identity_command,
+            export_checklist_pack_command,
+            import_checklist_pack_command,
+            get_trusted_pack_publishers_command,
+            trust_pack_publisher_command,
+            remove_trusted_pack_publisher_command,
         ])
         
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let RunEvent::Exit = event {
+                if let Some(shutdown) = app_handle.try_state::<ShutdownCoordinator>() {
+                    shutdown.shutdown();
+                }
+            }
+        });
 }
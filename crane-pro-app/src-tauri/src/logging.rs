@@ -0,0 +1,301 @@
+//! Structured logging for CranePro Bridge Inspection Application
+//!
+//! This module configures `tracing` as the application logger. Events are
+//! written to stdout and to a daily rotating log file under the app data
+//! directory, optionally as JSON. Records emitted through the `log` crate
+//! are forwarded into `tracing`, so they pick up the active command span
+//! (request ID and user ID). Events inside a command span are also filed
+//! under the request's trace, see [`crate::trace`].
+
+use crate::errors::{AppError, AppResult};
+use crate::middleware::AuditLogEntry;
+use crate::trace::{TraceLayer, TraceStore};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{Builder as RollingBuilder, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+
+/// File name prefix for rotated log files (`cranepro.log.YYYY-MM-DD`)
+pub const LOG_FILE_PREFIX: &str = "cranepro.log";
+
+/// Default number of rotated log files kept on disk
+pub const DEFAULT_MAX_LOG_FILES: usize = 14;
+
+/// Upper bound on lines returned by [`LogManager::recent_logs`]
+pub const MAX_RECENT_LOG_LINES: usize = 2000;
+
+/// Log target for audit entries, which are written as one JSON object per event
+pub const AUDIT_LOG_TARGET: &str = "audit";
+
+/// Log output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" | "pretty" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(AppError::InvalidConfiguration {
+                key: "CRANEPRO_LOG_FORMAT".to_string(),
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Logging configuration
+#[derive(Debug, Clone)]
+pub struct LoggingConfig {
+    pub log_dir: PathBuf,
+    pub format: LogFormat,
+    pub default_level: String,
+    pub max_files: usize,
+}
+
+impl LoggingConfig {
+    /// Build configuration for the given log directory, reading overrides
+    /// from `CRANEPRO_LOG_FORMAT` (`text` or `json`). Level filtering
+    /// honours `RUST_LOG` and falls back to `info`.
+    pub fn from_env(log_dir: PathBuf) -> Self {
+        let format = std::env::var("CRANEPRO_LOG_FORMAT")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(LogFormat::Text);
+
+        Self {
+            log_dir,
+            format,
+            default_level: "info".to_string(),
+            max_files: DEFAULT_MAX_LOG_FILES,
+        }
+    }
+}
+
+/// Handle to the installed logger, kept in Tauri managed state.
+///
+/// Dropping it flushes and stops the background file writer.
+pub struct LogManager {
+    log_dir: PathBuf,
+    traces: Arc<TraceStore>,
+    _guard: WorkerGuard,
+}
+
+impl LogManager {
+    /// Install the global `tracing` subscriber
+    pub fn init(config: LoggingConfig) -> AppResult<Self> {
+        fs::create_dir_all(&config.log_dir)
+            .map_err(|e| AppError::file_system("create_dir", config.log_dir.display().to_string(), e.to_string()))?;
+
+        let file_appender = RollingBuilder::new()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .max_log_files(config.max_files)
+            .build(&config.log_dir)
+            .map_err(|e| AppError::Configuration {
+                key: "log_dir".to_string(),
+                reason: e.to_string(),
+            })?;
+        let (file_writer, guard) = tracing_appender::non_blocking(file_appender);
+
+        // The level filter applies to the log output only; traces also keep
+        // debug events such as SQL timings
+        let filter = EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| EnvFilter::new(&config.default_level));
+        let traces = Arc::new(TraceStore::new());
+        let registry = tracing_subscriber::registry()
+            .with(TraceLayer::new(traces.clone()).with_filter(LevelFilter::DEBUG));
+
+        let result = match config.format {
+            LogFormat::Json => registry
+                .with(fmt::layer().json().with_current_span(true)
+                    .and_then(fmt::layer().json().with_current_span(true).with_ansi(false).with_writer(file_writer))
+                    .with_filter(filter))
+                .try_init(),
+            LogFormat::Text => registry
+                .with(fmt::layer()
+                    .and_then(fmt::layer().with_ansi(false).with_writer(file_writer))
+                    .with_filter(filter))
+                .try_init(),
+        };
+        result.map_err(|e| AppError::Configuration {
+            key: "logging".to_string(),
+            reason: e.to_string(),
+        })?;
+
+        Ok(Self {
+            log_dir: config.log_dir,
+            traces,
+            _guard: guard,
+        })
+    }
+
+    pub fn log_dir(&self) -> &Path {
+        &self.log_dir
+    }
+
+    /// Traces of recent requests
+    pub fn traces(&self) -> &TraceStore {
+        &self.traces
+    }
+
+    /// Read the most recent log lines, newest file first
+    ///
+    /// # Arguments
+    /// * `max_lines` - Maximum number of lines to return (capped at [`MAX_RECENT_LOG_LINES`])
+    /// * `level` - Optional level (e.g. `WARN`) that lines must mention
+    ///
+    /// # Returns
+    /// * Lines in chronological order
+    pub fn recent_logs(&self, max_lines: usize, level: Option<&str>) -> AppResult<Vec<String>> {
+        read_recent_logs(&self.log_dir, max_lines, level)
+    }
+}
+
+/// Rotated log files in `log_dir`, oldest first
+fn log_files(log_dir: &Path) -> AppResult<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(log_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.starts_with(LOG_FILE_PREFIX))
+                .unwrap_or(false)
+        })
+        .collect();
+    // Date suffixes sort lexically
+    files.sort();
+    Ok(files)
+}
+
+/// Visit every audit entry in the retained log files, oldest first, reading
+/// one line at a time
+///
+/// Returns the number of entries visited. Only entries still inside the log
+/// retention window ([`DEFAULT_MAX_LOG_FILES`] days by default) are found.
+pub fn for_each_audit_entry<F>(log_dir: &Path, mut visit: F) -> AppResult<u64>
+where
+    F: FnMut(AuditLogEntry) -> AppResult<()>,
+{
+    let mut count = 0;
+    for file in log_files(log_dir)? {
+        let reader = BufReader::new(fs::File::open(&file)?);
+        for line in reader.lines() {
+            if let Some(entry) = parse_audit_line(&line?) {
+                visit(entry)?;
+                count += 1;
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// Extract an audit entry from a text or JSON formatted log line
+fn parse_audit_line(line: &str) -> Option<AuditLogEntry> {
+    if line.starts_with('{') {
+        let event: serde_json::Value = serde_json::from_str(line).ok()?;
+        if event.get("target")?.as_str()? != AUDIT_LOG_TARGET {
+            return None;
+        }
+        return serde_json::from_str(event.get("fields")?.get("message")?.as_str()?).ok();
+    }
+
+    let marker = format!(" {}: ", AUDIT_LOG_TARGET);
+    let start = line.find(&marker)? + marker.len();
+    serde_json::from_str(&line[start..]).ok()
+}
+
+/// Collect the last `max_lines` lines across rotated log files in `log_dir`
+pub fn read_recent_logs(log_dir: &Path, max_lines: usize, level: Option<&str>) -> AppResult<Vec<String>> {
+    let max_lines = max_lines.min(MAX_RECENT_LOG_LINES);
+    let level = level.map(|l| l.to_uppercase());
+
+    let mut files = log_files(log_dir)?;
+    files.reverse();
+
+    let mut lines: Vec<String> = Vec::new();
+    for file in files {
+        if lines.len() >= max_lines {
+            break;
+        }
+        let contents = fs::read_to_string(&file)?;
+        let remaining = max_lines - lines.len();
+        let mut file_lines: Vec<String> = contents
+            .lines()
+            .rev()
+            .filter(|line| match &level {
+                Some(level) => line.contains(level.as_str()),
+                None => true,
+            })
+            .take(remaining)
+            .map(|line| line.to_string())
+            .collect();
+        lines.append(&mut file_lines);
+    }
+
+    lines.reverse();
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_format_parsing() {
+        assert_eq!("json".parse::<LogFormat>().unwrap(), LogFormat::Json);
+        assert_eq!("TEXT".parse::<LogFormat>().unwrap(), LogFormat::Text);
+        assert!("xml".parse::<LogFormat>().is_err());
+    }
+
+    #[test]
+    fn test_read_recent_logs_across_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("cranepro.log.2024-01-01"), "old INFO a\nold WARN b\n").unwrap();
+        fs::write(dir.path().join("cranepro.log.2024-01-02"), "new INFO c\nnew WARN d\n").unwrap();
+        fs::write(dir.path().join("other.txt"), "ignored WARN\n").unwrap();
+
+        let lines = read_recent_logs(dir.path(), 3, None).unwrap();
+        assert_eq!(lines, vec!["old WARN b", "new INFO c", "new WARN d"]);
+
+        let warnings = read_recent_logs(dir.path(), 10, Some("warn")).unwrap();
+        assert_eq!(warnings, vec!["old WARN b", "new WARN d"]);
+    }
+
+    #[test]
+    fn test_for_each_audit_entry() {
+        let entry = AuditLogEntry::new(&crate::middleware::RequestContext::new(), "delete", "asset");
+        let json = serde_json::to_string(&entry).unwrap();
+        let json_line = serde_json::json!({
+            "level": "INFO",
+            "target": AUDIT_LOG_TARGET,
+            "fields": { "message": json },
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("cranepro.log.2024-01-01"),
+            format!("2024-01-01T00:00:00Z  INFO delete_asset{{request_id=x}}: audit: {}\nINFO other: noise\n", json),
+        ).unwrap();
+        fs::write(dir.path().join("cranepro.log.2024-01-02"), format!("{}\n", json_line)).unwrap();
+
+        let mut actions = Vec::new();
+        let count = for_each_audit_entry(dir.path(), |entry| {
+            actions.push(entry.action);
+            Ok(())
+        }).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(actions, vec!["delete", "delete"]);
+    }
+}
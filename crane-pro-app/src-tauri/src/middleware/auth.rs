@@ -4,14 +4,18 @@
 //! and permission checking for the CranePro application.
 
 use crate::errors::{AppError, AppResult};
-use crate::middleware::{UserSession, Permissions, RequestContext};
-use crate::models::User;
+use crate::i18n::Locale;
+use crate::middleware::{record_security_event, UserSession, Permissions, RequestContext};
+use crate::ldap;
+use crate::oidc;
+use crate::models::{AuthSource, DeviceUnlock, JwtKeyRotation, LdapConfig, PendingMfa, RefreshTokenRecord, SecurityEvent, SecurityEventKind, User, UserRole};
 use crate::services::Services;
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation, Algorithm};
+use jsonwebtoken::{decode, decode_header, encode, DecodingKey, EncodingKey, Header, Validation, Algorithm};
+use jsonwebtoken::errors::ErrorKind as JwtErrorKind;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
 use std::sync::{Arc, RwLock};
-use chrono::{Utc, Duration};
+use chrono::{DateTime, Utc, Duration};
 use log::{debug, warn, error};
 
 /// JWT claims structure
@@ -26,100 +30,652 @@ pub struct TokenClaims {
     pub permissions: Vec<String>, // User permissions
 }
 
-/// Authentication manager for handling sessions and tokens
-pub struct AuthManager {
+/// Tokens handed out at sign-in and on every refresh
+#[derive(Debug, Clone)]
+pub struct IssuedTokens {
+    pub session: UserSession,
+    pub access_token: String,
+    /// Single-use token that obtains the next pair of tokens
+    pub refresh_token: String,
+    pub refresh_expires_at: DateTime<Utc>,
+}
+
+/// Token handed out when signing in at a kiosk terminal. Kiosk sessions end
+/// after a short idle time and cannot be refreshed.
+#[derive(Debug, Clone)]
+pub struct KioskTokens {
+    pub session: UserSession,
+    pub access_token: String,
+}
+
+/// Returned instead of a session when a login needs a second factor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaChallenge {
+    /// Passed to `verify_mfa_command`, and to `enroll_mfa_command` when
+    /// enrolling during login
+    pub challenge_token: String,
+    pub expires_at: DateTime<Utc>,
+    /// The user's role requires a second factor but none is set up yet
+    pub enrollment_required: bool,
+}
+
+/// Result of checking a username and password
+#[derive(Debug, Clone)]
+pub enum LoginOutcome {
+    Authenticated(IssuedTokens),
+    MfaRequired(MfaChallenge),
+}
+
+/// Checks a username and password, returning the account to sign in
+pub trait AuthProvider {
+    /// Source of the accounts this provider signs in
+    fn source(&self) -> AuthSource;
+
+    fn authenticate(&self, username: &str, password: &str) -> AppResult<User>;
+}
+
+/// Accounts whose bcrypt password hash is stored locally
+pub struct LocalAuthProvider {
+    services: Arc<Services>,
+}
+
+impl LocalAuthProvider {
+    pub fn new(services: Arc<Services>) -> Self {
+        Self { services }
+    }
+}
+
+impl AuthProvider for LocalAuthProvider {
+    fn source(&self) -> AuthSource {
+        AuthSource::Local
+    }
+
+    fn authenticate(&self, username: &str, password: &str) -> AppResult<User> {
+        let user = self.services.users.get_user_by_username(username.to_string())?;
+
+        if !user.is_active {
+            warn!("Authentication failed: user {} is inactive", username);
+            return Err(AppError::authentication("User account is inactive"));
+        }
+
+        // Directory and single sign-on accounts have no usable local password
+        let source = self.services.users.get_auth_source(user.id)?;
+        if source != AuthSource::Local {
+            warn!("Authentication failed: user {} signs in through {}, not a local password", username, source);
+            return Err(AppError::authentication("Invalid credentials"));
+        }
+
+        let password_valid = self.services.users.verify_password(user.id, password.to_string())?;
+        if !password_valid {
+            warn!("Authentication failed: invalid password for user {}", username);
+            return Err(AppError::authentication("Invalid credentials"));
+        }
+        Ok(user)
+    }
+}
+
+/// Domain accounts checked by binding to an LDAP directory. The user's
+/// groups decide their role, and their local account is created or brought
+/// up to date on each sign-in.
+pub struct LdapAuthProvider {
     services: Arc<Services>,
-    active_sessions: Arc<RwLock<HashMap<String, UserSession>>>,
+    config: LdapConfig,
+}
+
+impl LdapAuthProvider {
+    pub fn new(services: Arc<Services>, config: LdapConfig) -> Self {
+        Self { services, config }
+    }
+}
+
+impl AuthProvider for LdapAuthProvider {
+    fn source(&self) -> AuthSource {
+        AuthSource::Ldap
+    }
+
+    fn authenticate(&self, username: &str, password: &str) -> AppResult<User> {
+        let directory_user = ldap::authenticate(&self.config, username, password).inspect_err(|e| {
+            warn!("Directory authentication failed for user {}: {}", username, e);
+        })?;
+
+        let role = self.config.role_for_groups(&directory_user.groups).ok_or_else(|| {
+            warn!("Directory user {} is in no group mapped to a role", username);
+            AppError::Authorization {
+                user: username.to_string(),
+                action: "sign in".to_string(),
+                resource: "application".to_string(),
+            }
+        })?;
+
+        let user = self.services.users.provision_directory_user(username, &directory_user, role)?;
+        if !user.is_active {
+            warn!("Authentication failed: user {} is inactive", username);
+            return Err(AppError::authentication("User account is inactive"));
+        }
+        Ok(user)
+    }
+}
+
+/// Key ID of a JWT secret, written to the `kid` header of the tokens it
+/// signs: the start of the secret's SHA-256, so it needs no storing
+pub fn jwt_key_id(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))[..16].to_string()
+}
+
+/// A secret tokens are signed with, and its key ID
+struct SigningKey {
+    kid: String,
     encoding_key: EncodingKey,
     decoding_key: DecodingKey,
-    token_expiry_hours: i64,
+}
+
+impl SigningKey {
+    fn new(secret: &str) -> Self {
+        Self {
+            kid: jwt_key_id(secret),
+            encoding_key: EncodingKey::from_secret(secret.as_ref()),
+            decoding_key: DecodingKey::from_secret(secret.as_ref()),
+        }
+    }
+}
+
+/// The key new tokens are signed with, and the one it replaced while tokens
+/// signed with that are still accepted
+struct SigningKeys {
+    current: SigningKey,
+    previous: Option<(SigningKey, DateTime<Utc>)>,
+}
+
+/// Authentication manager for handling sessions and tokens. Sessions,
+/// refresh tokens and pending logins are kept in the database, so every
+/// token is checked against its session, revoking the session ends it at
+/// once, and all of them survive a restart.
+pub struct AuthManager {
+    services: Arc<Services>,
+    signing_keys: RwLock<SigningKeys>,
 }
 
 impl AuthManager {
     pub fn new(services: Arc<Services>, jwt_secret: &str) -> Self {
         Self {
             services,
-            active_sessions: Arc::new(RwLock::new(HashMap::new())),
-            encoding_key: EncodingKey::from_secret(jwt_secret.as_ref()),
-            decoding_key: DecodingKey::from_secret(jwt_secret.as_ref()),
-            token_expiry_hours: 8, // 8-hour token expiry
+            signing_keys: RwLock::new(SigningKeys { current: SigningKey::new(jwt_secret), previous: None }),
+        }
+    }
+
+    /// Also accept tokens signed with `secret`, the secret replaced by the
+    /// last rotation, until `valid_until`
+    pub fn with_previous_secret(self, secret: &str, valid_until: DateTime<Utc>) -> Self {
+        self.signing_keys.write().unwrap().previous = Some((SigningKey::new(secret), valid_until));
+        self
+    }
+
+    /// Sign new tokens with `new_secret`. Tokens signed with the current
+    /// secret stay valid until `previous_valid_until`, so sessions carry on
+    /// and pick up the new key when they next refresh. A secret retired by
+    /// an earlier rotation stops being accepted.
+    pub fn rotate_signing_key(&self, new_secret: &str, previous_valid_until: DateTime<Utc>) -> JwtKeyRotation {
+        let mut keys = self.signing_keys.write().unwrap();
+        let previous = std::mem::replace(&mut keys.current, SigningKey::new(new_secret));
+        let rotation = JwtKeyRotation {
+            key_id: keys.current.kid.clone(),
+            previous_key_id: previous.kid.clone(),
+            previous_valid_until,
+        };
+        keys.previous = Some((previous, previous_valid_until));
+        warn!("Token signing key rotated to {}; key {} accepted until {}",
+              rotation.key_id, rotation.previous_key_id, previous_valid_until);
+        rotation
+    }
+
+    /// Keys a token may have been signed with, by the key ID in its header.
+    /// Tokens from before key IDs were written may be signed with either.
+    fn decoding_keys_for(&self, token: &str) -> AppResult<Vec<DecodingKey>> {
+        let header = decode_header(token)
+            .map_err(|e| AppError::authentication(format!("Invalid token: {}", e)))?;
+        let keys = self.signing_keys.read().unwrap();
+        let now = Utc::now();
+        let previous = keys.previous.as_ref()
+            .filter(|(_, valid_until)| *valid_until > now)
+            .map(|(key, _)| key);
+        let candidates: Vec<&SigningKey> = match header.kid.as_deref() {
+            None => std::iter::once(&keys.current).chain(previous).collect(),
+            Some(kid) => std::iter::once(&keys.current).chain(previous).filter(|key| key.kid == kid).collect(),
+        };
+        if candidates.is_empty() {
+            return Err(AppError::authentication("Token was signed with a retired key"));
+        }
+        Ok(candidates.into_iter().map(|key| key.decoding_key.clone()).collect())
+    }
+
+    /// Configured session length, falling back to the default if settings can't be read
+    pub(crate) fn session_duration(&self) -> Duration {
+        let hours = self.services.settings.get_settings()
+            .map(|settings| settings.session_duration_hours)
+            .unwrap_or_else(|e| {
+                warn!("Failed to read session duration setting: {}", e);
+                crate::models::DEFAULT_SESSION_DURATION_HOURS
+            });
+        Duration::hours(hours)
+    }
+
+    /// Configured refresh token lifetime, falling back to the default if settings can't be read
+    fn refresh_token_lifetime(&self) -> Duration {
+        let hours = self.services.settings.get_settings()
+            .map(|settings| settings.refresh_token_lifetime_hours)
+            .unwrap_or_else(|e| {
+                warn!("Failed to read refresh token lifetime setting: {}", e);
+                crate::models::DEFAULT_REFRESH_TOKEN_LIFETIME_HOURS
+            });
+        Duration::hours(hours)
+    }
+
+    /// Provider that checks `username`'s password. Local accounts always
+    /// use their bcrypt hash; with the directory turned on, every other
+    /// name is checked against it. Single sign-on accounts have no password
+    /// and are refused by the local provider.
+    fn provider_for(&self, username: &str) -> AppResult<Box<dyn AuthProvider + Send>> {
+        let local = Box::new(LocalAuthProvider::new(self.services.clone()));
+        let config = self.services.settings.get_settings()?.ldap_directory;
+        if !config.enabled {
+            return Ok(local);
+        }
+        match self.services.users.get_user_by_username(username.to_string()) {
+            Ok(user) if self.services.users.get_auth_source(user.id)? != AuthSource::Ldap => Ok(local),
+            _ => Ok(Box::new(LdapAuthProvider::new(self.services.clone(), config))),
         }
     }
 
     /// Authenticate user with username and password
-    pub async fn authenticate(&self, username: &str, password: &str) -> AppResult<(UserSession, String)> {
+    pub async fn authenticate(&self, username: &str, password: &str) -> AppResult<LoginOutcome> {
         debug!("Authenticating user: {}", username);
 
-        // Get user by username
-        let user = self.services.users.get_user_by_username(username.to_string())?;
+        let provider = self.provider_for(username)?;
+        let source = provider.source();
+        // Password hashing and directory binds block, so they run off the async runtime
+        let (name, secret) = (username.to_string(), password.to_string());
+        let user = tokio::task::spawn_blocking(move || provider.authenticate(&name, &secret))
+            .await
+            .map_err(|e| AppError::internal(format!("Password check task failed: {}", e)))
+            .and_then(|result| result)
+            .inspect_err(|e| record_security_event(&SecurityEvent::new(SecurityEventKind::FailedLogin, e.to_string()).with_username(username)))?;
+        debug!("User {} passed the {} password check", username, source);
+        self.complete_login(&user)
+    }
+
+    /// Sign in through the OpenID Connect provider. `open_browser` shows the
+    /// provider's sign-in page in the system browser; the account linked to
+    /// the identity that comes back is returned for `oidc_login`.
+    pub async fn oidc_user(&self, open_browser: impl FnOnce(&str) -> AppResult<()>) -> AppResult<User> {
+        let config = self.services.settings.get_settings()?.oidc_provider;
+        let identity = oidc::sign_in(&config, open_browser).await.inspect_err(|e| {
+            warn!("Single sign-on failed: {}", e);
+        })?;
+
+        let user = self.services.users.provision_oidc_user(&identity, config.default_role.clone(), config.auto_provision)
+            .inspect_err(|e| {
+                warn!("Single sign-on identity {} refused: {}", identity.subject, e);
+                record_security_event(&SecurityEvent::new(SecurityEventKind::FailedLogin, format!(
+                    "Single sign-on identity {} refused: {}", identity.subject, e
+                )));
+            })?;
+        debug!("Single sign-on identity {} belongs to user {}", identity.subject, user.username);
+        Ok(user)
+    }
 
-        // Verify user is active
+    /// Sign in the account from `oidc_user` like any other, including the
+    /// second factor its role requires
+    pub fn oidc_login(&self, user: &User) -> AppResult<LoginOutcome> {
         if !user.is_active {
-            warn!("Authentication failed: user {} is inactive", username);
+            warn!("Authentication failed: user {} is inactive", user.username);
+            record_security_event(
+                &SecurityEvent::new(SecurityEventKind::FailedLogin, "Single sign-on to an inactive account")
+                    .with_user_id(user.id)
+                    .with_username(user.username.clone()),
+            );
             return Err(AppError::authentication("User account is inactive"));
         }
+        debug!("User {} signed in through single sign-on", user.username);
+        self.complete_login(user)
+    }
 
-        // Verify password
-        let password_valid = self.services.users.verify_password(user.id, password.to_string())?;
-        if !password_valid {
-            warn!("Authentication failed: invalid password for user {}", username);
-            return Err(AppError::authentication("Invalid credentials"));
+    /// Start a session for a user who has proven who they are, or a second
+    /// factor challenge if their account needs one
+    fn complete_login(&self, user: &User) -> AppResult<LoginOutcome> {
+        let username = &user.username;
+
+        // Hold the session back until the second factor is checked
+        let mfa = self.services.mfa.get_status(user)?;
+        if mfa.enabled || mfa.required {
+            let challenge_token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+            let expires_at = Utc::now() + Duration::minutes(crate::models::MFA_CHALLENGE_MINUTES);
+            self.services.sessions.create_mfa_challenge(&refresh_token_hash(&challenge_token), &PendingMfa {
+                user_id: user.id,
+                expires_at,
+                attempts: 0,
+            })?;
+            debug!("User {} passed the first factor; awaiting second factor", username);
+            return Ok(LoginOutcome::MfaRequired(MfaChallenge {
+                challenge_token,
+                expires_at,
+                enrollment_required: !mfa.enabled,
+            }));
+        }
+
+        let issued = self.start_session(user)?;
+        debug!("User {} authenticated successfully with session {}", username, issued.session.session_id);
+        Ok(LoginOutcome::Authenticated(issued))
+    }
+
+    /// Complete a login waiting for its second factor with a code from the
+    /// authenticator app or a backup code. For users enrolling during login
+    /// the first valid code also confirms the enrollment.
+    pub fn verify_mfa(&self, challenge_token: &str, code: &str) -> AppResult<IssuedTokens> {
+        let user_id = self.mfa_challenge_user_id(challenge_token)?;
+
+        if !self.services.mfa.verify(user_id, code)? {
+            let key = refresh_token_hash(challenge_token);
+            if self.services.sessions.record_mfa_failure(&key, crate::models::MAX_MFA_ATTEMPTS)? {
+                warn!("Too many wrong MFA codes for user {}; login abandoned", user_id);
+            }
+            record_security_event(
+                &SecurityEvent::new(SecurityEventKind::FailedLogin, "Invalid verification code").with_user_id(user_id),
+            );
+            return Err(AppError::authentication("Invalid verification code"));
+        }
+        self.services.sessions.remove_mfa_challenge(&refresh_token_hash(challenge_token))?;
+
+        let user = self.services.users.get_user_by_id(user_id)?;
+        let issued = self.start_session(&user)?;
+        debug!("User {} completed MFA with session {}", user.username, issued.session.session_id);
+        Ok(issued)
+    }
+
+    /// User a pending second factor challenge belongs to
+    pub fn mfa_challenge_user_id(&self, challenge_token: &str) -> AppResult<i64> {
+        let key = refresh_token_hash(challenge_token);
+        match self.services.sessions.get_mfa_challenge(&key)? {
+            Some(pending) if pending.expires_at > Utc::now() => Ok(pending.user_id),
+            Some(_) => {
+                self.services.sessions.remove_mfa_challenge(&key)?;
+                Err(AppError::authentication("Login has expired; sign in again"))
+            }
+            None => Err(AppError::authentication("Invalid login challenge")),
+        }
+    }
+
+    /// Sign in at a registered kiosk terminal with a badge and PIN.
+    ///
+    /// The session holds only the kiosk subset of the user's permissions,
+    /// ends after the terminal's idle timeout and cannot be refreshed. Any
+    /// session still open on the terminal is ended, since one person uses
+    /// it at a time. A second factor is not asked for: kiosk sessions
+    /// cannot reach anything administrative.
+    pub fn kiosk_login(&self, terminal_key: &str, badge_id: &str, pin: &str) -> AppResult<KioskTokens> {
+        let terminal = self.services.kiosk.terminal_for_key(terminal_key)?;
+        let user_id = self.services.kiosk.user_for_badge(badge_id, pin).inspect_err(|_| {
+            warn!("Kiosk login failed on terminal {}: invalid badge or PIN", terminal.id);
+            record_security_event(&SecurityEvent::new(SecurityEventKind::FailedLogin, format!(
+                "Invalid badge or PIN at kiosk terminal {}", terminal.id
+            )));
+        })?;
+        let user = self.services.users.get_user_by_id(user_id)?;
+        if !user.is_active {
+            warn!("Kiosk login failed: user {} is inactive", user.username);
+            return Err(AppError::authentication("User account is inactive"));
+        }
+
+        let permissions = Permissions::for_kiosk(&self.services.roles.permissions_for_user(&user)?);
+        if permissions.is_empty() {
+            return Err(AppError::Authorization {
+                user: user.username.clone(),
+                action: "sign in".to_string(),
+                resource: "kiosk".to_string(),
+            });
+        }
+
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let duration = self.session_duration();
+        let mut session = UserSession::new(&user, session_id.clone(), permissions.clone());
+        session.expires_at = session.created_at + duration;
+        session.locale = self.services.users.get_user_locale(user.id)?;
+        session.kiosk_terminal_id = Some(terminal.id);
+        session.idle_timeout_minutes = Some(terminal.idle_timeout_minutes);
+        let access_token = self.generate_token(&user, &session_id, &permissions, duration)?;
+
+        self.services.sessions.revoke_for_terminal(terminal.id, "Another sign-in at the terminal")?;
+        self.services.sessions.create(&session)?;
+
+        debug!("User {} signed in at kiosk terminal {} with session {}", user.username, terminal.id, session.session_id);
+        Ok(KioskTokens { session, access_token })
+    }
+
+    /// Unlock a trusted device with a PIN or badge scan, starting a session
+    /// as if the user had signed in with their password. The password and
+    /// any second factor were checked when the device was trusted.
+    pub fn unlock_device(&self, device_key: &str, unlock: &DeviceUnlock) -> AppResult<IssuedTokens> {
+        let user_id = self.services.trusted_devices.unlock(device_key, unlock)?;
+        let user = self.services.users.get_user_by_id(user_id)?;
+        if !user.is_active {
+            warn!("Device unlock refused: user {} is inactive", user.username);
+            return Err(AppError::authentication("User account is inactive"));
+        }
+
+        let issued = self.start_session(&user)?;
+        debug!("User {} unlocked a trusted device with session {}", user.username, issued.session.session_id);
+        Ok(issued)
+    }
+
+    /// Session for one request made with an API key. The request acts as
+    /// the key's creator, with only the key's permissions that the creator
+    /// still holds. Nothing is stored; every request presents the key.
+    pub fn api_key_session(&self, secret: &str) -> AppResult<UserSession> {
+        let api_key = self.services.api_keys.authenticate(secret).inspect_err(|_| {
+            warn!("API key authentication failed");
+        })?;
+        let user = self.services.users.get_user_by_id(api_key.created_by)?;
+        if !user.is_active {
+            warn!("API key {} refused: user {} is inactive", api_key.id, user.username);
+            return Err(AppError::authentication("User account is inactive"));
         }
 
-        // Generate session and token
+        let permissions = Permissions::within(&api_key.permissions, &self.services.roles.permissions_for_user(&user)?);
+        let mut session = UserSession::new(&user, format!("api-key:{}", api_key.id), permissions);
+        if let Some(expires_at) = api_key.expires_at {
+            session.expires_at = session.expires_at.min(expires_at);
+        }
+        session.api_key_id = Some(api_key.id);
+        Ok(session)
+    }
+
+    /// Start a session for a user whose credentials have been checked
+    fn start_session(&self, user: &User) -> AppResult<IssuedTokens> {
         let session_id = uuid::Uuid::new_v4().to_string();
-        let permissions = Permissions::for_role(&user.role);
-        let session = UserSession::new(&user, session_id.clone(), permissions.clone());
-        let token = self.generate_token(&user, &session_id, &permissions)?;
+        let permissions = self.services.roles.permissions_for_user(user)?;
+        let duration = self.session_duration();
+        let mut session = UserSession::new(user, session_id.clone(), permissions.clone());
+        session.expires_at = session.created_at + duration;
+        session.locale = self.services.users.get_user_locale(user.id)?;
+        let token = self.generate_token(user, &session_id, &permissions, duration)?;
+
+        self.services.sessions.create(&session)?;
+        let refresh_expires_at = session.created_at + self.refresh_token_lifetime();
+        let family_id = uuid::Uuid::new_v4().to_string();
+        let refresh_token = self.issue_refresh_token(&session, &family_id, refresh_expires_at)?;
+
+        Ok(IssuedTokens { session, access_token: token, refresh_token, refresh_expires_at })
+    }
+
+    /// Exchange a refresh token for a new access token and refresh token.
+    ///
+    /// Each refresh token works once. A token presented a second time has
+    /// been copied, so every token of its family is revoked along with the
+    /// session and the user has to sign in again.
+    pub fn refresh_session(&self, refresh_token: &str) -> AppResult<IssuedTokens> {
+        debug!("Refreshing session");
+        let Some(record) = self.services.sessions.use_refresh_token(&refresh_token_hash(refresh_token))? else {
+            return Err(AppError::authentication("Invalid refresh token"));
+        };
+        if record.revoked_at.is_some() {
+            return Err(AppError::authentication("Refresh token has been revoked"));
+        }
+        if record.used_at.is_some() {
+            error!("Refresh token for session {} was reused; revoking the session", record.session_id);
+            record_security_event(
+                &SecurityEvent::new(SecurityEventKind::TokenAnomaly, format!(
+                    "Refresh token for session {} reused; session revoked", record.session_id
+                ))
+                .with_user_id(record.user_id),
+            );
+            self.services.sessions.revoke_refresh_family(&record.family_id)?;
+            self.end_session(&record.session_id, None, "Refresh token reused")?;
+            return Err(AppError::authentication("Refresh token has already been used"));
+        }
+        if record.expires_at <= Utc::now() {
+            self.end_session(&record.session_id, None, "Login expired")?;
+            return Err(AppError::authentication("Session expired"));
+        }
 
-        // Store session
-        {
-            let mut sessions = self.active_sessions.write().unwrap();
-            sessions.insert(session_id.clone(), session.clone());
+        let user = self.services.users.get_user_by_id(record.user_id)?;
+        if !user.is_active {
+            warn!("Refresh refused: user {} is inactive", user.username);
+            self.end_session(&record.session_id, None, "User account is inactive")?;
+            return Err(AppError::authentication("User account is inactive"));
+        }
+
+        // A revoked session stays revoked, whatever refresh tokens it had
+        let Some(mut session) = self.services.sessions.get(&record.session_id)? else {
+            self.services.sessions.revoke_refresh_family(&record.family_id)?;
+            warn!("Refresh refused: session {} has been revoked", record.session_id);
+            return Err(AppError::authentication("Session has been revoked"));
+        };
+        let permissions = self.services.roles.permissions_for_user(&user)?;
+        let duration = self.session_duration();
+        session.role = user.role.clone();
+        session.permissions = permissions.clone();
+        session.expires_at = Utc::now() + duration;
+        session.update_activity();
+        self.services.sessions.touch(&session.session_id, Some(session.expires_at))?;
+
+        let access_token = self.generate_token(&user, &session.session_id, &permissions, duration)?;
+        let refresh_token = self.issue_refresh_token(&session, &record.family_id, record.expires_at)?;
+
+        debug!("Session {} refreshed for user {}", session.session_id, user.username);
+        Ok(IssuedTokens { session, access_token, refresh_token, refresh_expires_at: record.expires_at })
+    }
+
+    /// Revoke the session a refresh token belongs to, e.g. when signing out
+    /// after the access token has already expired. Returns whether the
+    /// token was known.
+    pub fn revoke_refresh_token(&self, refresh_token: &str) -> bool {
+        let session_id = match self.services.sessions.get_refresh_token(&refresh_token_hash(refresh_token)) {
+            Ok(record) => record.filter(|record| record.revoked_at.is_none()).map(|record| record.session_id),
+            Err(e) => {
+                error!("Failed to look up refresh token: {}", e);
+                None
+            }
+        };
+        match session_id {
+            Some(session_id) => {
+                if let Err(e) = self.end_session(&session_id, None, "Signed out") {
+                    error!("Failed to revoke session {}: {}", session_id, e);
+                }
+                debug!("Revoked session {} by refresh token", session_id);
+                true
+            }
+            None => false,
         }
+    }
 
-        debug!("User {} authenticated successfully with session {}", username, session_id);
-        Ok((session, token))
+    /// Create a refresh token for `session` in `family_id`, valid until
+    /// `expires_at`
+    fn issue_refresh_token(&self, session: &UserSession, family_id: &str, expires_at: DateTime<Utc>) -> AppResult<String> {
+        let token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+        self.services.sessions.store_refresh_token(&refresh_token_hash(&token), &RefreshTokenRecord {
+            session_id: session.session_id.clone(),
+            user_id: session.user_id,
+            family_id: family_id.to_string(),
+            expires_at,
+            used_at: None,
+            revoked_at: None,
+        })?;
+        Ok(token)
+    }
+
+    /// End a session and revoke every refresh token issued for it. Returns
+    /// whether the session was still active.
+    fn end_session(&self, session_id: &str, revoked_by: Option<i64>, reason: &str) -> AppResult<bool> {
+        self.services.sessions.revoke(session_id, revoked_by, reason)
+    }
+
+    /// End another session on an administrator's behalf, e.g. one on a lost
+    /// or compromised device. Returns whether the session was still active.
+    pub fn revoke_session(&self, session_id: &str, revoked_by: i64) -> AppResult<bool> {
+        let revoked = self.end_session(session_id, Some(revoked_by), "Revoked by an administrator")?;
+        if revoked {
+            warn!("Session {} revoked by user {}", session_id, revoked_by);
+        }
+        Ok(revoked)
     }
 
     /// Validate token and return session
     pub fn validate_token(&self, token: &str) -> AppResult<UserSession> {
         debug!("Validating token");
 
-        // Decode and validate token
+        // Decode and validate token against the keys it may be signed with
         let validation = Validation::new(Algorithm::HS256);
-        let token_data = decode::<TokenClaims>(token, &self.decoding_key, &validation)
-            .map_err(|e| AppError::authentication(format!("Invalid token: {}", e)))?;
+        let mut decoded = Err(JwtErrorKind::InvalidToken.into());
+        for decoding_key in self.decoding_keys_for(token)? {
+            decoded = decode::<TokenClaims>(token, &decoding_key, &validation);
+            if decoded.is_ok() {
+                break;
+            }
+        }
+        let token_data = decoded.map_err(|e| {
+            // Access tokens running out is routine; the client refreshes them
+            if *e.kind() != JwtErrorKind::ExpiredSignature {
+                record_security_event(&SecurityEvent::new(SecurityEventKind::TokenAnomaly, format!("Invalid token: {}", e)));
+            }
+            AppError::authentication(format!("Invalid token: {}", e))
+        })?;
 
         let claims = token_data.claims;
 
-        // Check if session exists and is valid
-        let mut sessions = self.active_sessions.write().unwrap();
-        if let Some(mut session) = sessions.get(&claims.session_id).cloned() {
-            if session.is_expired() {
-                warn!("Session {} has expired", claims.session_id);
-                sessions.remove(&claims.session_id);
-                return Err(AppError::authentication("Session expired"));
+        // Check the session still exists and has not been revoked
+        let Some(mut session) = self.services.sessions.get(&claims.session_id)? else {
+            warn!("Session {} not found or revoked", claims.session_id);
+            record_token_anomaly(&claims, format!("Token for ended or revoked session {}", claims.session_id));
+            return Err(AppError::authentication("Invalid session"));
+        };
+        if session.user_id.to_string() != claims.sub {
+            warn!("Session {} does not belong to token subject {}", claims.session_id, claims.sub);
+            record_token_anomaly(&claims, format!(
+                "Token subject {} does not own session {}", claims.sub, claims.session_id
+            ));
+            return Err(AppError::authentication("Invalid session"));
+        }
+        if session.is_expired() {
+            warn!("Session {} has expired", claims.session_id);
+            // Kiosk sessions cannot be refreshed, so an idle one is over
+            if session.is_kiosk() {
+                self.end_session(&claims.session_id, None, "Idle timeout")?;
             }
-
-            // Update last activity
-            session.update_activity();
-            sessions.insert(claims.session_id.clone(), session.clone());
-            
-            debug!("Token validated successfully for user {}", claims.username);
-            Ok(session)
-        } else {
-            warn!("Session {} not found", claims.session_id);
-            Err(AppError::authentication("Invalid session"))
+            return Err(AppError::authentication("Session expired"));
         }
+
+        // Update last activity
+        session.update_activity();
+        self.services.sessions.touch(&claims.session_id, None)?;
+
+        debug!("Token validated successfully for user {}", claims.username);
+        Ok(session)
     }
 
     /// Logout user and invalidate session
     pub fn logout(&self, session_id: &str) -> AppResult<()> {
         debug!("Logging out session: {}", session_id);
 
-        let mut sessions = self.active_sessions.write().unwrap();
-        if sessions.remove(session_id).is_some() {
+        if self.end_session(session_id, None, "Signed out")? {
             debug!("Session {} logged out successfully", session_id);
             Ok(())
         } else {
@@ -133,71 +689,72 @@ impl AuthManager {
         debug!("Refreshing token");
 
         let session = self.validate_token(old_token)?;
-        
+        if session.is_kiosk() {
+            return Err(AppError::authentication("Kiosk sessions cannot be refreshed"));
+        }
+
         // Get fresh user data
         let user = self.services.users.get_user_by_id(session.user_id)?;
-        let permissions = Permissions::for_role(&user.role);
+        let permissions = self.services.roles.permissions_for_user(&user)?;
         
         // Generate new token
-        let new_token = self.generate_token(&user, &session.session_id, &permissions)?;
+        let new_token = self.generate_token(&user, &session.session_id, &permissions, self.session_duration())?;
         
         debug!("Token refreshed successfully for user {}", user.username);
         Ok(new_token)
     }
 
-    /// Clean up expired sessions
+    /// Clean up expired sessions. Sessions are kept while their login can
+    /// still be refreshed.
     pub fn cleanup_expired_sessions(&self) {
         debug!("Cleaning up expired sessions");
 
-        let mut sessions = self.active_sessions.write().unwrap();
-        let expired_sessions: Vec<String> = sessions
-            .iter()
-            .filter(|(_, session)| session.is_expired())
-            .map(|(id, _)| id.clone())
-            .collect();
-
-        for session_id in expired_sessions {
-            sessions.remove(&session_id);
-            debug!("Removed expired session: {}", session_id);
+        match self.services.sessions.purge_expired(Utc::now() - self.refresh_token_lifetime()) {
+            Ok(purged) => debug!("Removed {} expired sessions", purged),
+            Err(e) => warn!("Failed to remove expired sessions: {}", e),
         }
     }
 
     /// Get active session count
     pub fn active_session_count(&self) -> usize {
-        let sessions = self.active_sessions.read().unwrap();
-        sessions.len()
+        self.services.sessions.list_active(None)
+            .map(|sessions| sessions.len())
+            .unwrap_or_else(|e| {
+                warn!("Failed to count active sessions: {}", e);
+                0
+            })
     }
 
     /// Get session by ID
     pub fn get_session(&self, session_id: &str) -> Option<UserSession> {
-        let sessions = self.active_sessions.read().unwrap();
-        sessions.get(session_id).cloned()
+        self.services.sessions.get(session_id).ok().flatten()
     }
 
     /// Force logout user (admin function)
     pub fn force_logout_user(&self, user_id: i64) -> AppResult<usize> {
         debug!("Force logging out all sessions for user: {}", user_id);
 
-        let mut sessions = self.active_sessions.write().unwrap();
-        let user_sessions: Vec<String> = sessions
-            .iter()
-            .filter(|(_, session)| session.user_id == user_id)
-            .map(|(id, _)| id.clone())
-            .collect();
-
-        let count = user_sessions.len();
-        for session_id in user_sessions {
-            sessions.remove(&session_id);
-        }
+        let count = self.services.sessions.revoke_for_user(user_id, None, "All sessions of the user ended")?;
 
         debug!("Force logged out {} sessions for user {}", count, user_id);
         Ok(count)
     }
 
+    /// Apply a new locale to all active sessions of a user
+    pub fn update_user_locale(&self, user_id: i64, locale: Locale) -> usize {
+        let count = self.services.sessions.set_locale(user_id, locale).unwrap_or_else(|e| {
+            warn!("Failed to update session locale for user {}: {}", user_id, e);
+            0
+        });
+
+        debug!("Updated locale to {} for {} sessions of user {}", locale, count, user_id);
+        count
+    }
+
     /// Generate JWT token
-    fn generate_token(&self, user: &User, session_id: &str, permissions: &[String]) -> AppResult<String> {
+    fn generate_token(&self, user: &User, session_id: &str, permissions: &[String], duration: Duration) -> AppResult<String> {
         let now = Utc::now();
-        let expiration = now + Duration::hours(self.token_expiry_hours);
+        let expiration = now + duration;
 
         let claims = TokenClaims {
             sub: user.id.to_string(),
@@ -209,7 +766,9 @@ impl AuthManager {
             permissions: permissions.to_vec(),
         };
 
-        encode(&Header::default(), &claims, &self.encoding_key)
+        let keys = self.signing_keys.read().unwrap();
+        let header = Header { kid: Some(keys.current.kid.clone()), ..Header::default() };
+        encode(&header, &claims, &keys.current.encoding_key)
             .map_err(|e| AppError::Token {
                 operation: "generation".to_string(),
                 reason: e.to_string(),
@@ -243,6 +802,21 @@ impl AuthManager {
     }
 }
 
+/// Record a validly signed token that does not match a live session
+fn record_token_anomaly(claims: &TokenClaims, detail: String) {
+    let mut event = SecurityEvent::new(SecurityEventKind::TokenAnomaly, detail).with_username(claims.username.clone());
+    if let Ok(user_id) = claims.sub.parse() {
+        event = event.with_user_id(user_id);
+    }
+    record_security_event(&event);
+}
+
+/// SHA-256 of a refresh token or login challenge; tokens themselves are
+/// never kept
+fn refresh_token_hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.trim().as_bytes()))
+}
+
 /// Authentication helper functions for command handlers
 pub struct AuthHelper;
 
@@ -257,12 +831,29 @@ impl AuthHelper {
                     context = context.with_session(session);
                 }
                 Err(e) => {
-                    error!("Token validation failed: {}", e);
+                    context.record_in_span();
+                    error!("[{}] Token validation failed: {}", context.request_id, e);
                     return Err(e);
                 }
             }
         }
 
+        context.record_in_span();
+        Ok(context)
+    }
+
+    /// Authenticate a request from an external system by its API key
+    pub fn validate_api_key(auth_manager: &AuthManager, api_key: &str) -> AppResult<RequestContext> {
+        let mut context = RequestContext::new();
+        match auth_manager.api_key_session(api_key) {
+            Ok(session) => context = context.with_session(session),
+            Err(e) => {
+                context.record_in_span();
+                error!("[{}] API key validation failed: {}", context.request_id, e);
+                return Err(e);
+            }
+        }
+        context.record_in_span();
         Ok(context)
     }
 
@@ -271,6 +862,42 @@ impl AuthHelper {
         context.current_user()
     }
 
+    /// Require a session from a normal login. Managing the account itself,
+    /// such as its password or second factor, is refused at kiosks and to
+    /// API keys.
+    pub fn require_full_session(context: &RequestContext) -> AppResult<&UserSession> {
+        let session = context.current_user()?;
+        if session.is_kiosk() {
+            return Err(AppError::Authorization {
+                user: session.username.clone(),
+                action: "manage account".to_string(),
+                resource: "kiosk".to_string(),
+            });
+        }
+        if session.is_api_key() {
+            return Err(AppError::Authorization {
+                user: session.username.clone(),
+                action: "manage account".to_string(),
+                resource: "api key".to_string(),
+            });
+        }
+        Ok(session)
+    }
+
+    /// Require a Super Admin's own login, for changes that affect every
+    /// user such as rotating the token signing key
+    pub fn require_super_admin<'a>(context: &'a RequestContext, action: &str) -> AppResult<&'a UserSession> {
+        let session = Self::require_full_session(context)?;
+        if session.role != UserRole::SuperAdmin {
+            return Err(AppError::Authorization {
+                user: session.username.clone(),
+                action: action.to_string(),
+                resource: "system".to_string(),
+            });
+        }
+        Ok(session)
+    }
+
     /// Require specific permission
     pub fn require_permission(context: &RequestContext, permission: &str) -> AppResult<()> {
         context.require_permission(permission)
@@ -320,8 +947,8 @@ impl AuthHelper {
             entry.success = false;
         }
 
-        // In a production system, this would be logged to a database or audit service
-        debug!("Audit: {:?}", entry);
+        crate::middleware::record_audit_entry(&entry);
+        context.mark_audited();
     }
 }
 
@@ -352,6 +979,661 @@ macro_rules! require_resource_access {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::database::Database;
+    use crate::models::{LoginAttempt, LoginDevice, LoginHistoryFilter, LoginMethod, SecurityEventFilter, UserRole};
+    use crate::security::fields::FieldCipher;
+
+    /// In-memory services and an auth manager over them
+    struct TestAuth {
+        database: Arc<Database>,
+        services: Arc<Services>,
+        auth: AuthManager,
+        admin: User,
+        /// Signed in as the admin
+        context: RequestContext,
+    }
+
+    /// The admin's password is `correct horse` and no role requires a
+    /// second factor
+    async fn test_auth_manager() -> TestAuth {
+        let database = Arc::new(Database::new_in_memory().await.unwrap());
+        let conn = database.get_connection().unwrap();
+        conn.execute(
+            "UPDATE users SET password_hash = ?1 WHERE username = 'admin'",
+            [bcrypt::hash("correct horse", 4).unwrap()],
+        ).unwrap();
+        conn.execute("UPDATE mfa_policies SET required = 0", []).unwrap();
+        database.return_connection(conn);
+        let services = Arc::new(Services::init(database.clone(), Arc::new(FieldCipher::ephemeral().unwrap())).await.unwrap());
+        let auth = AuthManager::new(services.clone(), "test_secret_key_for_testing_only");
+        let admin = services.users.get_user_by_id(1).unwrap();
+        let context = RequestContext::new()
+            .with_session(UserSession::new(&admin, "setup".to_string(), Permissions::for_role(&admin.role)));
+        TestAuth { database, services, auth, admin, context }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_tokens_rotate_and_detect_reuse() {
+        let TestAuth { auth, .. } = test_auth_manager().await;
+        let signed_in = |outcome: LoginOutcome| match outcome {
+            LoginOutcome::Authenticated(issued) => issued,
+            LoginOutcome::MfaRequired(_) => panic!("second factor not expected"),
+        };
+
+        let login = signed_in(auth.authenticate("admin", "correct horse").await.unwrap());
+        let refreshed = auth.refresh_session(&login.refresh_token).unwrap();
+        assert_eq!(refreshed.session.session_id, login.session.session_id);
+        assert_eq!(refreshed.refresh_expires_at, login.refresh_expires_at);
+        assert_ne!(refreshed.refresh_token, login.refresh_token);
+        assert!(auth.validate_token(&refreshed.access_token).is_ok());
+
+        // Replaying the first refresh token revokes the whole session
+        assert!(auth.refresh_session(&login.refresh_token).is_err());
+        assert!(auth.refresh_session(&refreshed.refresh_token).is_err());
+        assert!(auth.validate_token(&refreshed.access_token).is_err());
+
+        let login = signed_in(auth.authenticate("admin", "correct horse").await.unwrap());
+        assert!(auth.revoke_refresh_token(&login.refresh_token));
+        assert!(auth.refresh_session(&login.refresh_token).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_tokens_survive_a_restart() {
+        let TestAuth { database, services, auth, .. } = test_auth_manager().await;
+        let LoginOutcome::Authenticated(login) = auth.authenticate("admin", "correct horse").await.unwrap() else {
+            panic!("second factor not expected");
+        };
+        let refreshed = auth.refresh_session(&login.refresh_token).unwrap();
+
+        // Only the hashes are stored, all in one family
+        let conn = database.get_connection().unwrap();
+        let (stored, families): (i64, i64) = conn.query_row(
+            "SELECT COUNT(*), COUNT(DISTINCT family_id) FROM refresh_tokens WHERE token_hash IN (?1, ?2)",
+            [refresh_token_hash(&login.refresh_token), refresh_token_hash(&refreshed.refresh_token)],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).unwrap();
+        database.return_connection(conn);
+        assert_eq!((stored, families), (2, 1));
+
+        let restarted = AuthManager::new(services, "test_secret_key_for_testing_only");
+        let again = restarted.refresh_session(&refreshed.refresh_token).unwrap();
+        assert_eq!(again.session.session_id, login.session.session_id);
+
+        // Reuse is still spotted, and revokes the family and the session
+        assert!(restarted.refresh_session(&login.refresh_token).is_err());
+        assert!(restarted.refresh_session(&again.refresh_token).is_err());
+        assert!(restarted.validate_token(&again.access_token).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tokens_signed_before_a_rotation_stay_valid_for_the_grace_window() {
+        let TestAuth { services, .. } = test_auth_manager().await;
+        let auth = AuthManager::new(services, "first secret");
+        let LoginOutcome::Authenticated(login) = auth.authenticate("admin", "correct horse").await.unwrap() else {
+            panic!("second factor not expected");
+        };
+        assert_eq!(decode_header(&login.access_token).unwrap().kid, Some(jwt_key_id("first secret")));
+
+        let rotation = auth.rotate_signing_key("second secret", Utc::now() + Duration::hours(1));
+        assert_eq!(rotation.previous_key_id, jwt_key_id("first secret"));
+        assert!(auth.validate_token(&login.access_token).is_ok());
+        let refreshed = auth.refresh_token(&login.access_token).unwrap();
+        assert_eq!(decode_header(&refreshed).unwrap().kid, Some(rotation.key_id));
+
+        // A second rotation retires the first secret
+        auth.rotate_signing_key("third secret", Utc::now() + Duration::hours(1));
+        assert!(auth.validate_token(&login.access_token).is_err());
+        assert!(auth.validate_token(&refreshed).is_ok());
+
+        // Past the grace window only the current secret is accepted
+        auth.rotate_signing_key("fourth secret", Utc::now() - Duration::seconds(1));
+        assert!(auth.validate_token(&refreshed).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sessions_are_stored_and_can_be_revoked() {
+        let TestAuth { services, auth, .. } = test_auth_manager().await;
+        let LoginOutcome::Authenticated(login) = auth.authenticate("admin", "correct horse").await.unwrap() else {
+            panic!("second factor not expected");
+        };
+
+        // A restarted app still knows the session
+        let restarted = AuthManager::new(services.clone(), "test_secret_key_for_testing_only");
+        assert!(restarted.validate_token(&login.access_token).is_ok());
+        let listed = services.sessions.list_active(Some(login.session.user_id)).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].session_id, login.session.session_id);
+
+        // Revoking ends both the access token and the refresh token
+        assert!(auth.revoke_session(&login.session.session_id, 1).unwrap());
+        assert!(auth.validate_token(&login.access_token).is_err());
+        assert!(auth.refresh_session(&login.refresh_token).is_err());
+        assert!(!auth.revoke_session(&login.session.session_id, 1).unwrap());
+        assert_eq!(auth.active_session_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_required_mfa_enrolls_during_login() {
+        let TestAuth { services, auth, admin, context, .. } = test_auth_manager().await;
+        services.mfa.set_policy(&context, admin.role.clone(), true).unwrap();
+
+        // The admin role requires a second factor, so no session yet
+        let LoginOutcome::MfaRequired(challenge) = auth.authenticate("admin", "correct horse").await.unwrap() else {
+            panic!("second factor expected");
+        };
+        assert!(challenge.enrollment_required);
+        assert_eq!(auth.active_session_count(), 0);
+
+        let user_id = auth.mfa_challenge_user_id(&challenge.challenge_token).unwrap();
+        let user = services.users.get_user_by_id(user_id).unwrap();
+        let enrollment = services.mfa.enroll(&user).unwrap();
+        assert!(auth.verify_mfa(&challenge.challenge_token, &enrollment.backup_codes[0]).is_err());
+
+        let step = crate::totp::time_step(Utc::now().timestamp());
+        let code = crate::totp::code_for_step(&enrollment.secret, step).unwrap();
+        let issued = auth.verify_mfa(&challenge.challenge_token, &code).unwrap();
+        assert!(auth.validate_token(&issued.access_token).is_ok());
+        assert!(services.mfa.get_status(&user).unwrap().enabled);
+        assert!(auth.verify_mfa(&challenge.challenge_token, &code).is_err());
+
+        // Once enrolled, a backup code works once
+        let LoginOutcome::MfaRequired(challenge) = auth.authenticate("admin", "correct horse").await.unwrap() else {
+            panic!("second factor expected");
+        };
+        assert!(!challenge.enrollment_required);
+        assert!(auth.verify_mfa(&challenge.challenge_token, &enrollment.backup_codes[1].to_lowercase()).is_ok());
+        assert_eq!(services.mfa.get_status(&user).unwrap().backup_codes_remaining, 9);
+    }
+
+    #[tokio::test]
+    async fn test_kiosk_sessions_are_restricted_and_idle_out() {
+        let TestAuth { database, services, auth, admin, context } = test_auth_manager().await;
+
+        let registered = services.kiosk.register_terminal(&context, crate::models::KioskTerminalInput {
+            name: "Bay 3".to_string(),
+            location_id: None,
+            idle_timeout_minutes: Some(2),
+        }).unwrap();
+        services.kiosk.set_credentials(&context, admin.id, crate::models::KioskCredentialInput {
+            badge_id: "04A1B2C3".to_string(),
+            pin: "2580".to_string(),
+        }).unwrap();
+
+        assert!(auth.kiosk_login(&registered.terminal_key, "04A1B2C3", "0000").is_err());
+        assert!(auth.kiosk_login("not-a-terminal", "04A1B2C3", "2580").is_err());
+
+        // Even a super admin only gets the kiosk subset
+        let issued = auth.kiosk_login(&registered.terminal_key, "04A1B2C3", "2580").unwrap();
+        assert!(issued.session.is_kiosk());
+        assert!(issued.session.can_access_resource("inspection", "submit"));
+        assert!(!issued.session.can_access_resource("user", "update"));
+        assert!(!issued.session.has_permission("*"));
+        let kiosk_context = RequestContext::new().with_session(issued.session.clone());
+        assert!(AuthHelper::require_full_session(&kiosk_context).is_err());
+        assert!(auth.refresh_token(&issued.access_token).is_err());
+
+        // The next sign-in on the terminal ends the previous one
+        let next = auth.kiosk_login(&registered.terminal_key, "04A1B2C3", "2580").unwrap();
+        assert!(auth.validate_token(&issued.access_token).is_err());
+
+        // Idle past the terminal's timeout
+        let conn = database.get_connection().unwrap();
+        conn.execute(
+            "UPDATE sessions SET last_activity = ?1 WHERE session_id = ?2",
+            rusqlite::params![Utc::now() - Duration::minutes(3), next.session.session_id],
+        ).unwrap();
+        database.return_connection(conn);
+        assert!(auth.validate_token(&next.access_token).is_err());
+        assert_eq!(auth.active_session_count(), 0);
+
+        services.kiosk.deactivate_terminal(&context, registered.terminal.id).unwrap();
+        assert!(auth.kiosk_login(&registered.terminal_key, "04A1B2C3", "2580").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_wrong_kiosk_pins_lock_the_badge() {
+        let TestAuth { services, auth, admin, context, .. } = test_auth_manager().await;
+
+        let registered = services.kiosk.register_terminal(&context, crate::models::KioskTerminalInput {
+            name: "Bay 3".to_string(),
+            location_id: None,
+            idle_timeout_minutes: None,
+        }).unwrap();
+        let credentials = crate::models::KioskCredentialInput {
+            badge_id: "04A1B2C3".to_string(),
+            pin: "2580".to_string(),
+        };
+        services.kiosk.set_credentials(&context, admin.id, credentials.clone()).unwrap();
+        let key = &registered.terminal_key;
+
+        // A correct PIN resets the count
+        for _ in 1..crate::models::KIOSK_BADGE_ATTEMPTS {
+            assert!(auth.kiosk_login(key, "04A1B2C3", "0000").is_err());
+        }
+        assert!(auth.kiosk_login(key, "04A1B2C3", "2580").is_ok());
+
+        for _ in 0..crate::models::KIOSK_BADGE_ATTEMPTS {
+            assert!(auth.kiosk_login(key, "04A1B2C3", "0000").is_err());
+        }
+        assert!(auth.kiosk_login(key, "04A1B2C3", "2580").is_err());
+
+        // Setting a new PIN lifts the lock
+        services.kiosk.set_credentials(&context, admin.id, credentials).unwrap();
+        assert!(auth.kiosk_login(key, "04A1B2C3", "2580").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_local_accounts_fall_back_when_directory_is_enabled() {
+        let TestAuth { services, auth, context, .. } = test_auth_manager().await;
+
+        // Nothing listens on port 1, so every directory check fails
+        let config = LdapConfig {
+            enabled: true,
+            url: "ldap://127.0.0.1:1".to_string(),
+            bind_dn_template: "{username}@corp.example.com".to_string(),
+            search_base: "DC=corp,DC=example,DC=com".to_string(),
+            default_role: Some(crate::models::UserRole::Inspector),
+            timeout_seconds: 1,
+            ..LdapConfig::default()
+        };
+        services.settings.set_setting(&context, crate::models::SettingKey::LdapDirectory,
+            serde_json::to_value(&config).unwrap()).unwrap();
+
+        assert!(auth.authenticate("admin", "correct horse").await.is_ok());
+        assert!(auth.authenticate("admin", "wrong").await.is_err());
+        assert!(auth.authenticate("jsmith", "secret").await.is_err());
+
+        // Directory accounts follow the directory's role and never sign in locally
+        let directory_user = ldap::DirectoryUser {
+            email: Some("j.smith@corp.example.com".to_string()),
+            ..Default::default()
+        };
+        let user = services.users.provision_directory_user("jsmith", &directory_user, crate::models::UserRole::Inspector).unwrap();
+        let user_again = services.users.provision_directory_user("jsmith", &directory_user, crate::models::UserRole::Supervisor).unwrap();
+        assert_eq!(user.id, user_again.id);
+        assert_eq!(user_again.role, crate::models::UserRole::Supervisor);
+        assert!(services.users.provision_directory_user("admin", &directory_user, crate::models::UserRole::Inspector).is_err());
+
+        services.settings.set_setting(&context, crate::models::SettingKey::LdapDirectory,
+            serde_json::to_value(LdapConfig::default()).unwrap()).unwrap();
+        assert!(auth.authenticate("jsmith", "!").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_single_sign_on_accounts_link_by_subject() {
+        let TestAuth { services, auth, .. } = test_auth_manager().await;
+        let identity = oidc::OidcIdentity {
+            subject: "00u1a2b3c4".to_string(),
+            username: "j.smith".to_string(),
+            email: Some("j.smith@example.com".to_string()),
+            first_name: Some("Jo".to_string()),
+            last_name: None,
+        };
+
+        assert!(services.users.provision_oidc_user(&identity, UserRole::Inspector, false).is_err());
+        let user = services.users.provision_oidc_user(&identity, UserRole::Inspector, true).unwrap();
+        assert_eq!(user.role, UserRole::Inspector);
+
+        // A renamed identity keeps its account, and its role is not reset
+        let renamed = oidc::OidcIdentity { username: "jo.smith".to_string(), ..identity.clone() };
+        let again = services.users.provision_oidc_user(&renamed, UserRole::Supervisor, true).unwrap();
+        assert_eq!(again.id, user.id);
+        assert_eq!(again.username, "j.smith");
+        assert_eq!(again.role, UserRole::Inspector);
+
+        let clash = oidc::OidcIdentity { subject: "other".to_string(), username: "admin".to_string(),
+            email: Some("someone@example.com".to_string()), ..identity.clone() };
+        assert!(services.users.provision_oidc_user(&clash, UserRole::Inspector, true).is_err());
+
+        // Single sign-on accounts have no password to sign in with
+        assert!(auth.authenticate("j.smith", "!").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_trusted_device_unlock_is_rate_limited() {
+        let TestAuth { database, services, auth, admin, context } = test_auth_manager().await;
+
+        let registered = services.trusted_devices.register(&context, crate::models::TrustedDeviceInput {
+            name: "Tablet 4".to_string(),
+            pin: "2580".to_string(),
+        }).unwrap();
+        let key = &registered.device_key;
+        let pin = |pin: &str| DeviceUnlock::Pin(pin.to_string());
+
+        let issued = auth.unlock_device(key, &pin("2580")).unwrap();
+        assert_eq!(issued.session.user_id, admin.id);
+        assert!(auth.unlock_device("unknown", &pin("2580")).is_err());
+
+        // A badge only works once the user has one
+        let badge = DeviceUnlock::Badge("04A1B2C3".to_string());
+        assert!(auth.unlock_device(key, &badge).is_err());
+        services.kiosk.set_credentials(&context, admin.id, crate::models::KioskCredentialInput {
+            badge_id: "04A1B2C3".to_string(),
+            pin: "1470".to_string(),
+        }).unwrap();
+        assert!(auth.unlock_device(key, &badge).is_ok());
+
+        // Five wrong PINs in a row lock the device, even for the right one
+        for _ in 0..crate::models::DEVICE_UNLOCK_ATTEMPTS {
+            assert!(auth.unlock_device(key, &pin("0000")).is_err());
+        }
+        assert!(auth.unlock_device(key, &pin("2580")).is_err());
+
+        // Wrong attempts continue after the lock expires until the device is revoked
+        let expire_lock = || {
+            let conn = database.get_connection().unwrap();
+            conn.execute("UPDATE trusted_devices SET locked_until = NULL", []).unwrap();
+            database.return_connection(conn);
+        };
+        expire_lock();
+        for _ in 0..crate::models::DEVICE_REVOKE_ATTEMPTS - crate::models::DEVICE_UNLOCK_ATTEMPTS {
+            assert!(auth.unlock_device(key, &pin("0000")).is_err());
+        }
+        expire_lock();
+        assert!(auth.unlock_device(key, &pin("2580")).is_err());
+        assert!(services.trusted_devices.get_devices(admin.id).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_wrong_passwords_lock_the_account() {
+        let TestAuth { database, services, auth, admin, context } = test_auth_manager().await;
+
+        for _ in 0..crate::models::DEFAULT_LOCKOUT_THRESHOLD {
+            assert!(auth.authenticate("admin", "wrong").await.is_err());
+        }
+        let lockout = services.users.get_account_lockout_info(admin.id).unwrap();
+        assert!(lockout.is_locked);
+        assert_eq!(lockout.failed_attempts as i64, crate::models::DEFAULT_LOCKOUT_THRESHOLD);
+        assert!(auth.authenticate("admin", "correct horse").await.is_err());
+
+        // An expired lock unlocks by itself and the count starts again
+        let conn = database.get_connection().unwrap();
+        conn.execute("UPDATE login_attempts SET locked_until = ?1", [Utc::now() - chrono::Duration::minutes(1)]).unwrap();
+        database.return_connection(conn);
+        assert!(!services.users.get_account_lockout_info(admin.id).unwrap().is_locked);
+        assert!(auth.authenticate("admin", "wrong").await.is_err());
+        let lockout = services.users.get_account_lockout_info(admin.id).unwrap();
+        assert_eq!((lockout.failed_attempts, lockout.locked_until), (1, None));
+
+        // An administrator can unlock before the lock runs out
+        for _ in 1..crate::models::DEFAULT_LOCKOUT_THRESHOLD {
+            assert!(auth.authenticate("admin", "wrong").await.is_err());
+        }
+        assert!(services.users.get_account_lockout_info(admin.id).unwrap().is_locked);
+        let unlocked = services.users.unlock_user_account(&context, admin.id).unwrap();
+        assert_eq!((unlocked.failed_attempts, unlocked.is_locked), (0, false));
+        assert!(auth.authenticate("admin", "correct horse").await.is_ok());
+    }
+
+    /// Security events go to one sink for the whole process, so tests that
+    /// check them install this one, which writes each event to every test
+    /// database still open. Those tests only look at events for accounts of
+    /// their own.
+    struct TestSecurityEventSink(std::sync::Mutex<Vec<std::sync::Weak<crate::services::SecurityEventService>>>);
+
+    impl crate::middleware::SecurityEventSink for TestSecurityEventSink {
+        fn record(&self, event: &SecurityEvent) -> AppResult<()> {
+            let mut sinks = self.0.lock().unwrap();
+            sinks.retain(|sink| sink.strong_count() > 0);
+            for sink in sinks.iter().filter_map(std::sync::Weak::upgrade) {
+                // A database being torn down by a finished test is skipped
+                sink.record(event).ok();
+            }
+            Ok(())
+        }
+    }
+
+    fn record_security_events(services: &Services) {
+        static SINK: std::sync::OnceLock<Arc<TestSecurityEventSink>> = std::sync::OnceLock::new();
+        let sink = SINK.get_or_init(|| {
+            let sink = Arc::new(TestSecurityEventSink(Default::default()));
+            crate::middleware::set_security_event_sink(sink.clone());
+            sink
+        });
+        sink.0.lock().unwrap().push(Arc::downgrade(&services.security_events));
+    }
+
+    /// An inspector with an ID no other test uses, since their security
+    /// events reach this test's database too
+    fn add_inspector(database: &Database, id: i64, username: &str) {
+        let conn = database.get_connection().unwrap();
+        conn.execute(
+            "INSERT INTO users (id, username, email, password_hash, role, first_name, last_name)
+             VALUES (?1, ?2, ?2 || '@example.com', ?3, 'Inspector', 'Test', 'Inspector')",
+            rusqlite::params![id, username, bcrypt::hash("correct horse", 4).unwrap()],
+        ).unwrap();
+        database.return_connection(conn);
+    }
+
+    #[tokio::test]
+    async fn test_failed_logins_and_lockouts_are_security_events() {
+        let TestAuth { database, services, auth, .. } = test_auth_manager().await;
+        record_security_events(&services);
+        add_inspector(&database, 4101, "r.okafor");
+        let events = |kind: SecurityEventKind, filter: SecurityEventFilter| {
+            services.security_events.query(&SecurityEventFilter { kind: Some(kind), ..filter }, 100, 0).unwrap()
+        };
+        let by_name = |username: &str| SecurityEventFilter { username: Some(username.to_string()), ..Default::default() };
+        let by_id = SecurityEventFilter { user_id: Some(4101), ..Default::default() };
+
+        for _ in 0..crate::models::DEFAULT_LOCKOUT_THRESHOLD {
+            assert!(auth.authenticate("r.okafor", "wrong").await.is_err());
+        }
+        assert_eq!(events(SecurityEventKind::FailedLogin, by_name("r.okafor")).len() as i64,
+                   crate::models::DEFAULT_LOCKOUT_THRESHOLD);
+        let locked = events(SecurityEventKind::AccountLocked, by_id.clone());
+        assert_eq!(locked.len(), 1);
+        assert!(locked[0].detail.contains("wrong passwords"));
+
+        // The right password while locked is refused, but does not lock again
+        assert!(auth.authenticate("r.okafor", "correct horse").await.is_err());
+        assert_eq!(events(SecurityEventKind::FailedLogin, by_name("r.okafor")).len() as i64,
+                   crate::models::DEFAULT_LOCKOUT_THRESHOLD + 1);
+        assert_eq!(events(SecurityEventKind::AccountLocked, by_id.clone()).len(), 1);
+
+        // Names that match no account are recorded as typed
+        assert!(auth.authenticate("nobody.4101", "wrong").await.is_err());
+        assert_eq!(events(SecurityEventKind::FailedLogin, by_name("nobody.4101")).len(), 1);
+
+        // Single sign-on to a deactivated account
+        let conn = database.get_connection().unwrap();
+        conn.execute("UPDATE users SET is_active = 0 WHERE id = 4101", []).unwrap();
+        database.return_connection(conn);
+        assert!(auth.oidc_login(&services.users.get_user_by_id(4101).unwrap()).is_err());
+        let failed = events(SecurityEventKind::FailedLogin, by_id);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].username.as_deref(), Some("r.okafor"));
+        assert!(failed[0].detail.contains("inactive account"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_logins_reach_the_login_history() {
+        let TestAuth { database, services, auth, context, .. } = test_auth_manager().await;
+        add_inspector(&database, 4102, "t.nguyen");
+        let history = || {
+            services.login_history.query(&LoginHistoryFilter { user_id: Some(4102), ..Default::default() }, 100, 0).unwrap()
+        };
+        // Attempts as the login commands record them
+        let record = |user_id: Option<i64>, username: &str, method: LoginMethod, failure: AppError| {
+            services.login_history.record(&LoginAttempt {
+                user_id,
+                username: username.to_string(),
+                method,
+                device: LoginDevice { client_id: Some("tablet-7".to_string()), name: Some("Bay 3 tablet".to_string()) },
+                failure_reason: Some(failure.to_string()),
+            }).unwrap();
+        };
+
+        // Wrong passwords, through to the lockout, are found by the name typed
+        for _ in 0..crate::models::DEFAULT_LOCKOUT_THRESHOLD {
+            record(None, "T.Nguyen", LoginMethod::Password, auth.authenticate("t.nguyen", "wrong").await.unwrap_err());
+        }
+        record(None, "t.nguyen", LoginMethod::Password, auth.authenticate("t.nguyen", "correct horse").await.unwrap_err());
+        let entries = history();
+        assert_eq!(entries.len() as i64, crate::models::DEFAULT_LOCKOUT_THRESHOLD + 1);
+        assert!(entries.iter().all(|entry| !entry.success && entry.method == LoginMethod::Password));
+        assert!(entries.iter().all(|entry| entry.username == "t.nguyen"));
+        assert!(entries[0].failure_reason.as_deref().is_some_and(|reason| reason.contains("locked")));
+        assert_eq!(entries[0].device_name.as_deref(), Some("Bay 3 tablet"));
+
+        // Names that match no account have no history to add to
+        record(None, "nobody.4102", LoginMethod::Password, auth.authenticate("nobody.4102", "wrong").await.unwrap_err());
+        assert_eq!(services.login_history.query(&LoginHistoryFilter::default(), 100, 0).unwrap().len(), entries.len());
+
+        // A wrong PIN goes to the history of whoever the badge is issued to
+        let registered = services.kiosk.register_terminal(&context, crate::models::KioskTerminalInput {
+            name: "Bay 3".to_string(),
+            location_id: None,
+            idle_timeout_minutes: None,
+        }).unwrap();
+        services.kiosk.set_credentials(&context, 4102, crate::models::KioskCredentialInput {
+            badge_id: "04D5E6F7".to_string(),
+            pin: "2580".to_string(),
+        }).unwrap();
+        let owner = services.kiosk.badge_owner("04D5E6F7").unwrap();
+        assert_eq!(owner, Some(4102));
+        assert_eq!(services.kiosk.badge_owner("FFFFFFFF").unwrap(), None);
+        record(owner, "", LoginMethod::Kiosk, auth.kiosk_login(&registered.terminal_key, "04D5E6F7", "0000").unwrap_err());
+        let entries = history();
+        assert_eq!((entries[0].method, entries[0].success), (LoginMethod::Kiosk, false));
+        assert_eq!(entries[0].username, "t.nguyen");
+
+        // Single sign-on to a deactivated account
+        let conn = database.get_connection().unwrap();
+        conn.execute("UPDATE users SET is_active = 0 WHERE id = 4102", []).unwrap();
+        database.return_connection(conn);
+        let user = services.users.get_user_by_id(4102).unwrap();
+        record(Some(user.id), &user.username, LoginMethod::SingleSignOn, auth.oidc_login(&user).unwrap_err());
+        let entries = history();
+        assert_eq!((entries[0].method, entries[0].success), (LoginMethod::SingleSignOn, false));
+        assert!(entries[0].failure_reason.as_deref().is_some_and(|reason| reason.contains("inactive")));
+    }
+
+    #[tokio::test]
+    async fn test_custom_roles_set_session_permissions() {
+        let TestAuth { database, services, auth, admin, .. } = test_auth_manager().await;
+        let admin_context = RequestContext::new()
+            .with_session(UserSession::new(&admin, "setup".to_string(), Permissions::for_role(&UserRole::Administrator)));
+        assert_eq!(services.roles.seed_builtin_roles().unwrap(), 0);
+
+        let auditor = services.roles.create_role(&admin_context, crate::models::RoleInput {
+            name: "Read-only auditor".to_string(),
+            description: None,
+            base_role: UserRole::Inspector,
+            permissions: vec![Permissions::ASSET_READ.to_string(), Permissions::SYSTEM_AUDIT.to_string()],
+        }).unwrap();
+
+        // Nobody can grant what they do not hold themselves
+        let escalate = crate::models::RoleInput {
+            name: "Everything".to_string(),
+            description: None,
+            base_role: UserRole::Administrator,
+            permissions: vec![Permissions::SYSTEM_ALL.to_string()],
+        };
+        assert!(matches!(
+            services.roles.create_role(&admin_context, escalate),
+            Err(AppError::Authorization { .. })
+        ));
+
+        let conn = database.get_connection().unwrap();
+        conn.execute(
+            "INSERT INTO users (username, email, password_hash, role, first_name, last_name, is_active)
+             VALUES ('ina', 'ina@example.com', 'x', 'Inspector', 'Ina', 'Spector', 1)",
+            [],
+        ).unwrap();
+        database.return_connection(conn);
+        let inspector = services.users.get_user_by_username("ina".to_string()).unwrap();
+        let issued = auth.start_session(&inspector).unwrap();
+        assert!(issued.session.permissions.contains(&Permissions::INSPECTION_CREATE.to_string()));
+
+        services.roles.assign_role(&admin_context, inspector.id, auditor.id).unwrap();
+        let session = auth.validate_token(&issued.access_token).unwrap();
+        assert!(session.can_access_resource("system", "audit"));
+        assert!(!session.can_access_resource("inspection", "create"));
+        assert!(services.roles.delete_role(&admin_context, auditor.id).is_err());
+
+        // Editing a built-in role changes its users' sessions at once
+        let builtin = services.roles.get_roles().unwrap().into_iter()
+            .find(|role| role.is_builtin && role.base_role == UserRole::Inspector).unwrap();
+        services.roles.assign_role(&admin_context, inspector.id, builtin.id).unwrap();
+        services.roles.update_role(&admin_context, builtin.id, crate::models::RoleInput {
+            name: builtin.name.clone(),
+            description: Some("Field inspectors".to_string()),
+            base_role: UserRole::Inspector,
+            permissions: vec![Permissions::INSPECTION_READ.to_string()],
+        }).unwrap();
+        let session = auth.validate_token(&issued.access_token).unwrap();
+        assert_eq!(session.permissions, vec![Permissions::INSPECTION_READ.to_string()]);
+        assert!(services.roles.delete_role(&admin_context, builtin.id).is_err());
+        services.roles.delete_role(&admin_context, auditor.id).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_password_resets_cannot_take_over_higher_roles() {
+        let TestAuth { database, services, admin, .. } = test_auth_manager().await;
+        assert_eq!(admin.role, UserRole::SuperAdmin);
+        let conn = database.get_connection().unwrap();
+        conn.execute(
+            "INSERT INTO users (username, email, password_hash, role, first_name, last_name, is_active)
+             VALUES ('ada', 'ada@example.com', 'x', 'Administrator', 'Ada', 'Min', 1),
+                    ('ina', 'ina@example.com', 'x', 'Inspector', 'Ina', 'Spector', 1)",
+            [],
+        ).unwrap();
+        database.return_connection(conn);
+        let administrator = services.users.get_user_by_username("ada".to_string()).unwrap();
+        let inspector = services.users.get_user_by_username("ina".to_string()).unwrap();
+        let context = RequestContext::new()
+            .with_session(UserSession::new(&administrator, "setup".to_string(), Permissions::for_role(&administrator.role)));
+
+        assert!(matches!(
+            services.users.create_password_reset_for(&context, admin.id),
+            Err(AppError::Authorization { .. })
+        ));
+        let reset = services.users.create_password_reset_for(&context, inspector.id).unwrap().unwrap();
+        assert_eq!(reset.user_id, inspector.id);
+    }
+
+    #[tokio::test]
+    async fn test_api_keys_act_with_their_own_permissions() {
+        let TestAuth { services, auth, admin, .. } = test_auth_manager().await;
+        let context = RequestContext::new()
+            .with_session(UserSession::new(&admin, "setup".to_string(), Permissions::for_role(&UserRole::Administrator)));
+
+        let input = |permissions: Vec<&str>| crate::models::ApiKeyInput {
+            name: "ERP sync".to_string(),
+            permissions: permissions.into_iter().map(str::to_string).collect(),
+            expires_in_days: Some(30),
+        };
+        assert!(matches!(
+            services.api_keys.create_api_key(&context, input(vec![Permissions::SYSTEM_ALL])),
+            Err(AppError::Authorization { .. })
+        ));
+        let created = services.api_keys.create_api_key(&context, input(vec![Permissions::ASSET_ALL])).unwrap();
+        assert!(created.secret.starts_with(&created.api_key.key_prefix));
+
+        let session = auth.api_key_session(&created.secret).unwrap();
+        assert_eq!(session.user_id, admin.id);
+        assert!(session.can_access_resource("asset", "delete"));
+        assert!(!session.can_access_resource("inspection", "read"));
+        let context = RequestContext::new().with_session(session);
+        assert!(AuthHelper::require_full_session(&context).is_err());
+        assert!(services.api_keys.get_api_keys().unwrap()[0].last_used_at.is_some());
+
+        assert!(auth.api_key_session("cpk_not-a-key").is_err());
+        services.api_keys.revoke_api_key(&context, created.api_key.id).unwrap();
+        assert!(auth.api_key_session(&created.secret).is_err());
+    }
+
+    #[test]
+    fn test_wildcards_narrow_to_granted_permissions() {
+        let granted = vec![Permissions::ASSET_READ.to_string(), Permissions::REPORT_ALL.to_string()];
+        let requested = vec![Permissions::ASSET_ALL.to_string(), Permissions::REPORT_ALL.to_string()];
+        assert_eq!(
+            Permissions::within(&requested, &granted),
+            vec![Permissions::ASSET_READ.to_string(), Permissions::REPORT_ALL.to_string()]
+        );
+        assert!(Permissions::within(&[Permissions::SYSTEM_ALL.to_string()], &[]).is_empty());
+    }
 
     #[tokio::test]
     async fn test_token_generation_and_validation() {
@@ -4,13 +4,18 @@
 //! authorization, logging, and request processing.
 
 pub mod auth;
+pub mod record_access;
+pub mod validation;
 
 // Re-export commonly used types
 pub use auth::*;
 
 use crate::errors::{AppError, AppResult};
-use crate::models::{User, UserRole};
+use crate::i18n::Locale;
+use crate::models::{SecurityEvent, SecurityEventKind, User, UserRole};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -25,6 +30,17 @@ pub struct UserSession {
     pub expires_at: DateTime<Utc>,
     pub last_activity: DateTime<Utc>,
     pub permissions: Vec<String>,
+    #[serde(default)]
+    pub locale: Locale,
+    /// Shared terminal the session was started on; `None` for a normal login
+    #[serde(default)]
+    pub kiosk_terminal_id: Option<i64>,
+    /// Minutes without activity after which the session ends
+    #[serde(default)]
+    pub idle_timeout_minutes: Option<i64>,
+    /// API key an external system authenticated with; `None` for a login
+    #[serde(default)]
+    pub api_key_id: Option<i64>,
 }
 
 impl UserSession {
@@ -41,11 +57,28 @@ impl UserSession {
             expires_at,
             last_activity: now,
             permissions,
+            locale: Locale::default(),
+            kiosk_terminal_id: None,
+            idle_timeout_minutes: None,
+            api_key_id: None,
         }
     }
 
     pub fn is_expired(&self) -> bool {
-        Utc::now() > self.expires_at
+        let now = Utc::now();
+        now > self.expires_at
+            || self.idle_timeout_minutes
+                .is_some_and(|minutes| now - self.last_activity > chrono::Duration::minutes(minutes))
+    }
+
+    /// Whether the session was started on a shared kiosk terminal
+    pub fn is_kiosk(&self) -> bool {
+        self.kiosk_terminal_id.is_some()
+    }
+
+    /// Whether the request came from an external system using an API key
+    pub fn is_api_key(&self) -> bool {
+        self.api_key_id.is_some()
     }
 
     pub fn has_permission(&self, permission: &str) -> bool {
@@ -93,6 +126,9 @@ impl Permissions {
     pub const USER_READ: &'static str = "user:read";
     pub const USER_UPDATE: &'static str = "user:update";
     pub const USER_DELETE: &'static str = "user:delete";
+    pub const USER_SESSIONS: &'static str = "user:sessions";
+    pub const USER_ROLES: &'static str = "user:roles";
+    pub const USER_API_KEYS: &'static str = "user:api_keys";
     pub const USER_ALL: &'static str = "user:*";
 
     // Media permissions
@@ -104,6 +140,8 @@ impl Permissions {
     // Report permissions
     pub const REPORT_GENERATE: &'static str = "report:generate";
     pub const REPORT_READ: &'static str = "report:read";
+    pub const REPORT_EXPORT: &'static str = "report:export";
+    pub const REPORT_TEMPLATES: &'static str = "report:templates";
     pub const REPORT_ALL: &'static str = "report:*";
 
     // Location permissions
@@ -113,11 +151,92 @@ impl Permissions {
     pub const LOCATION_DELETE: &'static str = "location:delete";
     pub const LOCATION_ALL: &'static str = "location:*";
 
+    // Team permissions
+    pub const TEAM_CREATE: &'static str = "team:create";
+    pub const TEAM_READ: &'static str = "team:read";
+    pub const TEAM_UPDATE: &'static str = "team:update";
+    pub const TEAM_DELETE: &'static str = "team:delete";
+    pub const TEAM_ALL: &'static str = "team:*";
+
     // System permissions
     pub const SYSTEM_ADMIN: &'static str = "system:admin";
+    pub const SYSTEM_LOGS: &'static str = "system:logs";
+    pub const SYSTEM_AUDIT: &'static str = "system:audit";
+    pub const SYSTEM_SEED: &'static str = "system:seed";
+    pub const SYSTEM_DATA_QUALITY: &'static str = "system:data_quality";
+    pub const SYSTEM_MIGRATIONS: &'static str = "system:migrations";
+    pub const SYSTEM_MAINTENANCE: &'static str = "system:maintenance";
+    pub const SYSTEM_PURGE: &'static str = "system:purge";
+    pub const SYSTEM_SETTINGS: &'static str = "system:settings";
+    pub const SYSTEM_LEGAL_HOLD: &'static str = "system:legal_hold";
     pub const SYSTEM_ALL: &'static str = "*";
 
-    /// Get default permissions for a user role
+    /// Every individual permission, the catalogue roles are built from
+    pub const ALL: [&'static str; 44] = [
+        Self::ASSET_CREATE, Self::ASSET_READ, Self::ASSET_UPDATE, Self::ASSET_DELETE,
+        Self::INSPECTION_CREATE, Self::INSPECTION_READ, Self::INSPECTION_UPDATE, Self::INSPECTION_DELETE,
+        Self::INSPECTION_SUBMIT,
+        Self::COMPLIANCE_READ, Self::COMPLIANCE_UPDATE, Self::COMPLIANCE_VERIFY,
+        Self::USER_CREATE, Self::USER_READ, Self::USER_UPDATE, Self::USER_DELETE, Self::USER_SESSIONS,
+        Self::USER_ROLES, Self::USER_API_KEYS,
+        Self::MEDIA_UPLOAD, Self::MEDIA_READ, Self::MEDIA_DELETE,
+        Self::REPORT_GENERATE, Self::REPORT_READ, Self::REPORT_EXPORT, Self::REPORT_TEMPLATES,
+        Self::LOCATION_CREATE, Self::LOCATION_READ, Self::LOCATION_UPDATE, Self::LOCATION_DELETE,
+        Self::TEAM_CREATE, Self::TEAM_READ, Self::TEAM_UPDATE, Self::TEAM_DELETE,
+        Self::SYSTEM_ADMIN, Self::SYSTEM_LOGS, Self::SYSTEM_AUDIT, Self::SYSTEM_SEED, Self::SYSTEM_DATA_QUALITY,
+        Self::SYSTEM_MIGRATIONS, Self::SYSTEM_MAINTENANCE, Self::SYSTEM_PURGE, Self::SYSTEM_SETTINGS,
+        Self::SYSTEM_LEGAL_HOLD,
+    ];
+
+    /// Whether a permission is in the catalogue, is `<resource>:*` for a
+    /// resource in it, or is the `*` wildcard
+    pub fn is_known(permission: &str) -> bool {
+        if permission == Self::SYSTEM_ALL || Self::ALL.contains(&permission) {
+            return true;
+        }
+        permission.strip_suffix(":*").is_some_and(|resource| {
+            Self::ALL.iter().any(|known| known.split(':').next() == Some(resource))
+        })
+    }
+
+    /// Whether `granted` includes `permission`, directly or by a wildcard
+    pub fn grants(granted: &[String], permission: &str) -> bool {
+        let resource_all = format!("{}:*", permission.split(':').next().unwrap_or_default());
+        granted.iter().any(|g| g == permission || g == Self::SYSTEM_ALL || *g == resource_all)
+    }
+
+    /// Permissions of a kiosk session: those of `KIOSK_PERMISSIONS` the
+    /// user's role grants. Administration is never possible from a kiosk,
+    /// whatever the role.
+    pub fn for_kiosk(granted: &[String]) -> Vec<String> {
+        crate::models::KIOSK_PERMISSIONS.iter()
+            .filter(|permission| Self::grants(granted, permission))
+            .map(|permission| permission.to_string())
+            .collect()
+    }
+
+    /// The part of `requested` that `granted` also gives. A wildcard in
+    /// `requested` that `granted` does not cover is narrowed to the
+    /// catalogued permissions both give.
+    pub fn within(requested: &[String], granted: &[String]) -> Vec<String> {
+        let mut permissions = Vec::new();
+        for permission in requested {
+            if Self::grants(granted, permission) {
+                permissions.push(permission.clone());
+            } else if permission == Self::SYSTEM_ALL || permission.ends_with(":*") {
+                permissions.extend(Self::ALL.iter()
+                    .filter(|known| Self::grants(std::slice::from_ref(permission), known) && Self::grants(granted, known))
+                    .map(|known| known.to_string()));
+            }
+        }
+        permissions.sort();
+        permissions.dedup();
+        permissions
+    }
+
+    /// Default permissions of a built-in role, seeded into its row in the
+    /// roles table. Sessions take their permissions from the table, where
+    /// administrators may have changed them.
     pub fn for_role(role: &UserRole) -> Vec<String> {
         match role {
             UserRole::Inspector => vec![
@@ -131,6 +250,7 @@ impl Permissions {
                 Self::MEDIA_READ.to_string(),
                 Self::REPORT_READ.to_string(),
                 Self::LOCATION_READ.to_string(),
+                Self::TEAM_READ.to_string(),
             ],
             UserRole::Supervisor => vec![
                 Self::ASSET_READ.to_string(),
@@ -143,6 +263,8 @@ impl Permissions {
                 Self::REPORT_ALL.to_string(),
                 Self::LOCATION_READ.to_string(),
                 Self::LOCATION_UPDATE.to_string(),
+                Self::TEAM_READ.to_string(),
+                Self::TEAM_UPDATE.to_string(),
             ],
             UserRole::Administrator => vec![
                 Self::ASSET_ALL.to_string(),
@@ -151,9 +273,22 @@ impl Permissions {
                 Self::USER_CREATE.to_string(),
                 Self::USER_READ.to_string(),
                 Self::USER_UPDATE.to_string(),
+                Self::USER_SESSIONS.to_string(),
+                Self::USER_ROLES.to_string(),
+                Self::USER_API_KEYS.to_string(),
                 Self::MEDIA_ALL.to_string(),
                 Self::REPORT_ALL.to_string(),
                 Self::LOCATION_ALL.to_string(),
+                Self::TEAM_ALL.to_string(),
+                Self::SYSTEM_LOGS.to_string(),
+                Self::SYSTEM_AUDIT.to_string(),
+                Self::SYSTEM_SEED.to_string(),
+                Self::SYSTEM_DATA_QUALITY.to_string(),
+                Self::SYSTEM_MIGRATIONS.to_string(),
+                Self::SYSTEM_MAINTENANCE.to_string(),
+                Self::SYSTEM_PURGE.to_string(),
+                Self::SYSTEM_SETTINGS.to_string(),
+                Self::SYSTEM_LEGAL_HOLD.to_string(),
             ],
             UserRole::SuperAdmin => vec![
                 Self::SYSTEM_ALL.to_string(),
@@ -170,6 +305,8 @@ pub struct RequestContext {
     pub timestamp: DateTime<Utc>,
     pub user_agent: Option<String>,
     pub ip_address: Option<String>,
+    /// Set once the handler has written its own audit entry, shared by clones
+    audited: Arc<AtomicBool>,
 }
 
 impl RequestContext {
@@ -180,6 +317,7 @@ impl RequestContext {
             timestamp: Utc::now(),
             user_agent: None,
             ip_address: None,
+            audited: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -188,6 +326,30 @@ impl RequestContext {
         self
     }
 
+    /// Record the request ID and user ID on the current command span
+    pub fn record_in_span(&self) {
+        let span = tracing::Span::current();
+        span.record("request_id", self.request_id.as_str());
+        if let Some(session) = &self.session {
+            span.record("user_id", session.user_id);
+        }
+    }
+
+    /// Note that the action of this request has been written to the audit log
+    pub fn mark_audited(&self) {
+        self.audited.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the action of this request is already in the audit log
+    pub fn is_audited(&self) -> bool {
+        self.audited.load(Ordering::Relaxed)
+    }
+
+    /// Locale of the current user, or the default for anonymous requests
+    pub fn locale(&self) -> Locale {
+        self.session.as_ref().map(|session| session.locale).unwrap_or_default()
+    }
+
     pub fn current_user(&self) -> AppResult<&UserSession> {
         self.session.as_ref().ok_or_else(|| {
             AppError::authentication("No active session")
@@ -200,6 +362,7 @@ impl RequestContext {
         if session.has_permission(permission) {
             Ok(())
         } else {
+            self.record_denial(session, permission);
             Err(AppError::Authorization {
                 user: session.username.clone(),
                 action: permission.to_string(),
@@ -214,6 +377,7 @@ impl RequestContext {
         if session.can_access_resource(resource, action) {
             Ok(())
         } else {
+            self.record_denial(session, &format!("{}:{}", resource, action));
             Err(AppError::Authorization {
                 user: session.username.clone(),
                 action: action.to_string(),
@@ -221,6 +385,37 @@ impl RequestContext {
             })
         }
     }
+
+    /// Require `action` on something linked to `record`, as decided by
+    /// [`record_access::can_access`]
+    pub fn require_record_access(&self, record: &record_access::LinkedRecord, action: record_access::RecordAction) -> AppResult<()> {
+        let session = self.current_user()?;
+
+        if record_access::can_access(session, record, action) {
+            Ok(())
+        } else {
+            let (resource, id) = record.resource();
+            let resource = match id {
+                Some(id) => format!("{} {}", resource, id),
+                None => resource.to_string(),
+            };
+            self.record_denial(session, &format!("{} on {}", action.permission_action(), resource));
+            Err(AppError::Authorization {
+                user: session.username.clone(),
+                action: action.permission_action().to_string(),
+                resource,
+            })
+        }
+    }
+
+    fn record_denial(&self, session: &UserSession, permission: &str) {
+        record_security_event(
+            &SecurityEvent::new(SecurityEventKind::PermissionDenied, format!("Missing permission {}", permission))
+                .with_user_id(session.user_id)
+                .with_username(session.username.clone())
+                .with_request_id(self.request_id.clone()),
+        );
+    }
 }
 
 impl Default for RequestContext {
@@ -233,6 +428,7 @@ impl Default for RequestContext {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditLogEntry {
     pub id: String,
+    pub request_id: String,
     pub user_id: Option<i64>,
     pub username: Option<String>,
     pub action: String,
@@ -254,6 +450,7 @@ impl AuditLogEntry {
     ) -> Self {
         Self {
             id: uuid::Uuid::new_v4().to_string(),
+            request_id: context.request_id.clone(),
             user_id: context.session.as_ref().map(|s| s.user_id),
             username: context.session.as_ref().map(|s| s.username.clone()),
             action: action.into(),
@@ -283,4 +480,69 @@ impl AuditLogEntry {
         self.error_message = Some(error.to_string());
         self
     }
-}
\ No newline at end of file
+}
+
+/// Destination that audit entries are persisted to
+pub trait AuditSink: Send + Sync {
+    fn record(&self, entry: &AuditLogEntry) -> AppResult<()>;
+}
+
+static AUDIT_SINK: OnceLock<Arc<dyn AuditSink>> = OnceLock::new();
+
+/// Install the sink audit entries are written to; only the first call takes effect
+pub fn set_audit_sink(sink: Arc<dyn AuditSink>) {
+    if AUDIT_SINK.set(sink).is_err() {
+        log::warn!("Audit sink already installed; ignoring replacement");
+    }
+}
+
+/// Log an audit entry on the audit target and persist it to the installed sink.
+///
+/// Failures are logged rather than returned so that auditing never fails the
+/// action being audited.
+pub fn record_audit_entry(entry: &AuditLogEntry) {
+    // Written as JSON on the audit target so the log files keep a copy
+    match serde_json::to_string(entry) {
+        Ok(json) => log::info!(target: crate::logging::AUDIT_LOG_TARGET, "{}", json),
+        Err(e) => log::warn!("[{}] Failed to serialize audit entry: {}", entry.request_id, e),
+    }
+
+    if let Some(sink) = AUDIT_SINK.get() {
+        if let Err(e) = sink.record(entry) {
+            log::warn!("[{}] Failed to persist audit entry {}: {}", entry.request_id, entry.id, e);
+        }
+    }
+}
+
+/// Destination that security events are persisted to
+pub trait SecurityEventSink: Send + Sync {
+    fn record(&self, event: &SecurityEvent) -> AppResult<()>;
+}
+
+static SECURITY_EVENT_SINK: OnceLock<Arc<dyn SecurityEventSink>> = OnceLock::new();
+
+/// Install the sink security events are written to; only the first call takes effect
+pub fn set_security_event_sink(sink: Arc<dyn SecurityEventSink>) {
+    if SECURITY_EVENT_SINK.set(sink).is_err() {
+        log::warn!("Security event sink already installed; ignoring replacement");
+    }
+}
+
+/// Log a security event and persist it to the installed sink.
+///
+/// Like audit entries, failures are logged rather than returned so that a
+/// refused request is refused for its own reason.
+pub fn record_security_event(event: &SecurityEvent) {
+    log::warn!(
+        "Security event {}: {} (user {})",
+        event.kind,
+        event.detail,
+        event.username.as_deref().unwrap_or("unknown")
+    );
+
+    if let Some(sink) = SECURITY_EVENT_SINK.get() {
+        if let Err(e) = sink.record(event) {
+            log::warn!("Failed to persist security event {}: {}", event.id, e);
+        }
+    }
+}
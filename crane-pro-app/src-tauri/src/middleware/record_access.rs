@@ -0,0 +1,122 @@
+//! Row-level access
+//!
+//! Permissions decide what a user may do to a kind of record; this module
+//! decides whether that reaches one particular record. Records that hang off
+//! another, such as media files, take their access from the record they are
+//! linked to rather than from a permission of their own.
+
+use super::UserSession;
+use crate::models::UserRole;
+
+/// What the user wants to do with a record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordAction {
+    Read,
+    Write,
+}
+
+impl RecordAction {
+    /// Permission action this corresponds to on the linked resource
+    pub fn permission_action(self) -> &'static str {
+        match self {
+            RecordAction::Read => "read",
+            RecordAction::Write => "update",
+        }
+    }
+}
+
+/// Record another record is attached to, with what its access depends on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkedRecord {
+    /// An inspection, open to its inspector and to the assignees of work
+    /// raised from it
+    Inspection { id: i64, inspector_id: i64, assignee_ids: Vec<i64> },
+    /// An asset, either directly or through one of its components
+    Asset { id: i64 },
+    /// Not attached to anything, so only open to whoever uploaded it
+    Unlinked { uploaded_by: Option<i64> },
+}
+
+impl LinkedRecord {
+    /// Resource name and id for audit entries and error messages
+    pub fn resource(&self) -> (&'static str, Option<i64>) {
+        match self {
+            LinkedRecord::Inspection { id, .. } => ("inspection", Some(*id)),
+            LinkedRecord::Asset { id } => ("asset", Some(*id)),
+            LinkedRecord::Unlinked { .. } => ("media", None),
+        }
+    }
+}
+
+/// Whether `session` may perform `action` on something linked to `record`.
+/// Supervisors and administrators reach every inspection and unlinked file.
+pub fn can_access(session: &UserSession, record: &LinkedRecord, action: RecordAction) -> bool {
+    let (resource, _) = record.resource();
+    let overrides = matches!(session.role, UserRole::Supervisor | UserRole::Administrator | UserRole::SuperAdmin);
+    match record {
+        LinkedRecord::Inspection { inspector_id, assignee_ids, .. } => {
+            overrides || session.user_id == *inspector_id || assignee_ids.contains(&session.user_id)
+        }
+        LinkedRecord::Asset { .. } => session.can_access_resource(resource, action.permission_action()),
+        LinkedRecord::Unlinked { uploaded_by } => overrides || *uploaded_by == Some(session.user_id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::User;
+    use chrono::Utc;
+
+    fn session(user_id: i64, role: UserRole, permissions: &[&str]) -> UserSession {
+        let user = User {
+            id: user_id,
+            username: format!("user{}", user_id),
+            email: String::new(),
+            password_hash: String::new(),
+            role,
+            first_name: String::new(),
+            last_name: String::new(),
+            phone: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            is_active: true,
+        };
+        UserSession::new(&user, "session".to_string(), permissions.iter().map(|p| p.to_string()).collect())
+    }
+
+    #[test]
+    fn test_inspection_media_follows_inspection_assignment() {
+        let inspection = LinkedRecord::Inspection { id: 5, inspector_id: 7, assignee_ids: vec![8] };
+
+        // Reading inspections in general does not reach this one's media
+        let other_inspector = session(9, UserRole::Inspector, &["media:read", "inspection:read"]);
+        assert!(!can_access(&other_inspector, &inspection, RecordAction::Read));
+
+        let own_inspector = session(7, UserRole::Inspector, &["media:read"]);
+        assert!(can_access(&own_inspector, &inspection, RecordAction::Read));
+        assert!(can_access(&own_inspector, &inspection, RecordAction::Write));
+
+        let assignee = session(8, UserRole::Inspector, &["media:read"]);
+        assert!(can_access(&assignee, &inspection, RecordAction::Write));
+
+        let supervisor = session(9, UserRole::Supervisor, &["media:read"]);
+        assert!(can_access(&supervisor, &inspection, RecordAction::Write));
+    }
+
+    #[test]
+    fn test_asset_and_unlinked_media() {
+        let asset = LinkedRecord::Asset { id: 3 };
+        assert!(can_access(&session(1, UserRole::Inspector, &["asset:*"]), &asset, RecordAction::Write));
+        assert!(can_access(&session(1, UserRole::Inspector, &["asset:read"]), &asset, RecordAction::Read));
+        assert!(!can_access(&session(1, UserRole::Inspector, &["asset:read"]), &asset, RecordAction::Write));
+        assert!(!can_access(&session(1, UserRole::Inspector, &["media:read"]), &asset, RecordAction::Read));
+        assert!(can_access(&session(1, UserRole::Inspector, &["*"]), &asset, RecordAction::Write));
+
+        let unlinked = LinkedRecord::Unlinked { uploaded_by: Some(1) };
+        assert!(can_access(&session(1, UserRole::Inspector, &[]), &unlinked, RecordAction::Write));
+        assert!(!can_access(&session(2, UserRole::Inspector, &["media:*"]), &unlinked, RecordAction::Read));
+        assert!(can_access(&session(2, UserRole::Administrator, &[]), &unlinked, RecordAction::Read));
+        assert!(!can_access(&session(2, UserRole::Inspector, &[]), &LinkedRecord::Unlinked { uploaded_by: None }, RecordAction::Read));
+    }
+}
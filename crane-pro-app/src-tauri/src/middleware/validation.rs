@@ -0,0 +1,236 @@
+//! Query parameter validation and sanitization
+//!
+//! List and search commands accept `sort_by`, `limit` and free-form filters
+//! from the frontend. Column names cannot be bound as SQL parameters, so
+//! services must only interpolate values that passed through this module:
+//! sort and filter columns are checked against a per-entity whitelist,
+//! page sizes are capped and malformed input is rejected with
+//! [`AppError::InvalidQueryParameter`].
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{QueryFilter, SortOrder};
+use rusqlite::ToSql;
+use std::collections::BTreeMap;
+
+/// Page size used when the request does not specify one
+pub const DEFAULT_PAGE_LIMIT: i64 = 50;
+
+/// Largest page size a client may request; larger values are capped
+pub const MAX_PAGE_LIMIT: i64 = 200;
+
+/// Longest accepted filter value
+pub const MAX_FILTER_VALUE_LENGTH: usize = 256;
+
+/// Sortable and filterable columns for one entity
+#[derive(Debug, Clone, Copy)]
+pub struct QuerySpec {
+    pub entity: &'static str,
+    pub sortable: &'static [&'static str],
+    pub filterable: &'static [&'static str],
+}
+
+impl QuerySpec {
+    pub const ASSETS: QuerySpec = QuerySpec {
+        entity: "asset",
+        sortable: &[
+            "asset_number", "asset_name", "asset_type", "manufacturer", "model",
+            "installation_date", "capacity", "status", "location_id", "created_at", "updated_at",
+        ],
+        filterable: &["asset_type", "manufacturer", "status", "location_id"],
+    };
+
+    pub const INSPECTIONS: QuerySpec = QuerySpec {
+        entity: "inspection",
+        sortable: &[
+            "inspection_type", "scheduled_date", "actual_date", "status",
+            "overall_condition", "created_at", "updated_at",
+        ],
+        filterable: &["inspection_type", "status", "inspector_id", "compliance_standard"],
+    };
+
+    pub const USERS: QuerySpec = QuerySpec {
+        entity: "user",
+        sortable: &["username", "role", "first_name", "last_name", "created_at", "updated_at"],
+        filterable: &["role", "is_active"],
+    };
+
+    pub const LOCATIONS: QuerySpec = QuerySpec {
+        entity: "location",
+        sortable: &["name", "address", "asset_count", "created_at", "updated_at"],
+        filterable: &["parent_location_id"],
+    };
+
+    /// Validate a query filter against this entity's whitelist
+    pub fn validate(&self, filter: &QueryFilter) -> AppResult<ValidatedQuery> {
+        let page = filter.page.unwrap_or(1);
+        if page < 1 {
+            return Err(self.invalid("page", page.to_string(), "Page must be 1 or greater", &[]));
+        }
+
+        let limit = filter.limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+        if limit < 1 {
+            return Err(self.invalid("limit", limit.to_string(), "Limit must be 1 or greater", &[]));
+        }
+        let limit = limit.min(MAX_PAGE_LIMIT);
+
+        let sort_by = match filter.sort_by.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(column) => Some(
+                self.sortable
+                    .iter()
+                    .copied()
+                    .find(|allowed| allowed.eq_ignore_ascii_case(column))
+                    .ok_or_else(|| self.invalid("sort_by", column.to_string(), "Column is not sortable", self.sortable))?,
+            ),
+        };
+
+        let mut filters = BTreeMap::new();
+        for (key, value) in &filter.filters {
+            let column = self
+                .filterable
+                .iter()
+                .copied()
+                .find(|allowed| allowed.eq_ignore_ascii_case(key.trim()))
+                .ok_or_else(|| self.invalid("filters", key.clone(), "Column is not filterable", self.filterable))?;
+
+            let value = value.trim();
+            if value.len() > MAX_FILTER_VALUE_LENGTH {
+                return Err(self.invalid(
+                    &format!("filters.{}", column),
+                    format!("{}...", value.chars().take(32).collect::<String>()),
+                    &format!("Value exceeds {} characters", MAX_FILTER_VALUE_LENGTH),
+                    &[],
+                ));
+            }
+            if value.chars().any(char::is_control) {
+                return Err(self.invalid(&format!("filters.{}", column), value.escape_debug().to_string(), "Value contains control characters", &[]));
+            }
+            filters.insert(column, value.to_string());
+        }
+
+        Ok(ValidatedQuery {
+            page,
+            limit,
+            offset: (page - 1) * limit,
+            sort_by,
+            sort_order: filter.sort_order.clone().unwrap_or(SortOrder::Desc),
+            filters,
+        })
+    }
+
+    fn invalid(&self, parameter: &str, value: String, reason: &str, allowed: &[&str]) -> AppError {
+        AppError::InvalidQueryParameter {
+            entity: self.entity.to_string(),
+            parameter: parameter.to_string(),
+            value,
+            reason: reason.to_string(),
+            allowed: allowed.iter().map(|column| column.to_string()).collect(),
+        }
+    }
+}
+
+/// Query parameters that are safe to interpolate into SQL
+#[derive(Debug, Clone)]
+pub struct ValidatedQuery {
+    pub page: i64,
+    pub limit: i64,
+    pub offset: i64,
+    /// Whitelisted sort column, if one was requested
+    pub sort_by: Option<&'static str>,
+    pub sort_order: SortOrder,
+    /// Whitelisted filter columns and their trimmed values
+    pub filters: BTreeMap<&'static str, String>,
+}
+
+impl ValidatedQuery {
+    /// `ORDER BY` clause using the requested column or `default_column`
+    pub fn order_by(&self, default_column: &'static str) -> String {
+        format!(" ORDER BY {} {}", self.sort_by.unwrap_or(default_column), self.sort_order)
+    }
+
+    /// ` AND column = ?n` for each filter, numbering parameters from
+    /// `first`; empty without filters
+    pub fn filter_condition(&self, first: usize) -> String {
+        self.filters
+            .keys()
+            .enumerate()
+            .map(|(index, column)| format!(" AND {} = ?{}", column, first + index))
+            .collect()
+    }
+
+    /// The query's own `leading` parameters followed by the values for
+    /// [`ValidatedQuery::filter_condition`]
+    pub fn sql_params<'a>(&'a self, leading: &[&'a dyn ToSql]) -> Vec<&'a dyn ToSql> {
+        leading.iter().copied().chain(self.filters.values().map(|value| value as &dyn ToSql)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(sort_by: Option<&str>, limit: Option<i64>) -> QueryFilter {
+        QueryFilter {
+            sort_by: sort_by.map(str::to_string),
+            limit,
+            ..QueryFilter::default()
+        }
+    }
+
+    #[test]
+    fn test_sort_column_whitelist() {
+        let validated = QuerySpec::ASSETS.validate(&filter(Some("Asset_Name"), None)).unwrap();
+        assert_eq!(validated.order_by("created_at"), " ORDER BY asset_name DESC");
+
+        let error = QuerySpec::ASSETS
+            .validate(&filter(Some("asset_name; DROP TABLE assets"), None))
+            .unwrap_err();
+        assert!(matches!(error, AppError::InvalidQueryParameter { ref parameter, .. } if parameter == "sort_by"));
+    }
+
+    #[test]
+    fn test_limit_is_capped_and_filters_checked() {
+        let validated = QuerySpec::USERS.validate(&filter(None, Some(10_000))).unwrap();
+        assert_eq!(validated.limit, MAX_PAGE_LIMIT);
+        assert!(QuerySpec::USERS.validate(&filter(None, Some(0))).is_err());
+
+        let mut bad_filter = QueryFilter::default();
+        bad_filter.filters.insert("password_hash".to_string(), "x".to_string());
+        assert!(QuerySpec::USERS.validate(&bad_filter).is_err());
+
+        let mut good_filter = QueryFilter::default();
+        good_filter.filters.insert("Status".to_string(), " Active ".to_string());
+        good_filter.filters.insert("asset_type".to_string(), "Gantry".to_string());
+        let validated = QuerySpec::ASSETS.validate(&good_filter).unwrap();
+        assert_eq!(validated.filter_condition(2), " AND asset_type = ?2 AND status = ?3");
+        assert_eq!(validated.sql_params(&[&1]).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_filters_narrow_the_query() {
+        use std::sync::Arc;
+
+        let database = Arc::new(crate::database::Database::new_in_memory().await.unwrap());
+        let cipher = Arc::new(crate::security::fields::FieldCipher::ephemeral().unwrap());
+        let services = crate::services::Services::init(database.clone(), cipher).await.unwrap();
+        database.with_transaction(|conn| {
+            conn.execute("INSERT INTO locations (name, created_by) VALUES ('Bay 3', 1)", [])?;
+            conn.execute(
+                "INSERT INTO assets (asset_number, asset_name, asset_type, location_id, created_by)
+                 VALUES ('OHC-1', 'Bay 3 Overhead', 'Overhead Crane', 1, 1),
+                        ('GAN-1', 'Bay 3 Gantry', 'Gantry Crane', 1, 1)",
+                [],
+            )?;
+            Ok(())
+        }).unwrap();
+
+        let mut filter = QueryFilter::default();
+        filter.filters.insert("asset_type".to_string(), "Gantry Crane".to_string());
+        let found = services.assets.get_assets_by_location(1, filter.clone()).unwrap();
+        assert_eq!(found.data.iter().map(|asset| asset.asset_number.as_str()).collect::<Vec<_>>(), vec!["GAN-1"]);
+        assert_eq!(found.total_count, 1);
+
+        let found = services.assets.search_assets("Bay 3".to_string(), filter).unwrap();
+        assert_eq!(found.total_count, 1);
+    }
+}
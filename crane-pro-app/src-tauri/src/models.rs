@@ -4,8 +4,9 @@
 //! the core entities in the bridge inspection system.
 
 use crate::errors::{AppError, AppResult};
+use crate::units::{Capacity, CapacityUnit, LengthUnit};
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc, NaiveDate};
+use chrono::{DateTime, Utc, NaiveDate, NaiveTime, Weekday};
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 
@@ -65,6 +66,18 @@ impl std::fmt::Display for UserRole {
     }
 }
 
+impl UserRole {
+    /// Position from Inspector (0) to SuperAdmin (3)
+    pub fn rank(&self) -> u8 {
+        match self {
+            UserRole::Inspector => 0,
+            UserRole::Supervisor => 1,
+            UserRole::Administrator => 2,
+            UserRole::SuperAdmin => 3,
+        }
+    }
+}
+
 impl std::str::FromStr for UserRole {
     type Err = AppError;
 
@@ -114,6 +127,175 @@ impl Validate for User {
     }
 }
 
+/// Absences of this many days or longer hand the user's scheduled work to
+/// other inspectors
+pub const LONG_ABSENCE_DAYS: i64 = 5;
+
+/// Period during which a user is not available for inspections. Dates are
+/// whole days, inclusive at both ends.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserAbsence {
+    pub id: i64,
+    pub user_id: i64,
+    pub absence_type: AbsenceType,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub notes: Option<String>,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum AbsenceType {
+    Vacation,
+    Sick,
+    Training,
+}
+
+impl std::fmt::Display for AbsenceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AbsenceType::Vacation => write!(f, "Vacation"),
+            AbsenceType::Sick => write!(f, "Sick"),
+            AbsenceType::Training => write!(f, "Training"),
+        }
+    }
+}
+
+impl std::str::FromStr for AbsenceType {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Vacation" => Ok(AbsenceType::Vacation),
+            "Sick" => Ok(AbsenceType::Sick),
+            "Training" => Ok(AbsenceType::Training),
+            _ => Err(AppError::validation("absence_type", format!("Invalid absence type: {}", s))),
+        }
+    }
+}
+
+impl UserAbsence {
+    /// Number of calendar days covered
+    pub fn days(&self) -> i64 {
+        (self.end_date - self.start_date).num_days() + 1
+    }
+
+    pub fn covers(&self, date: NaiveDate) -> bool {
+        date >= self.start_date && date <= self.end_date
+    }
+
+    pub fn is_long(&self) -> bool {
+        self.days() >= LONG_ABSENCE_DAYS
+    }
+}
+
+impl Validate for UserAbsence {
+    fn validate(&self) -> AppResult<()> {
+        if self.end_date < self.start_date {
+            return Err(AppError::validation("end_date", "End date cannot be before start date"));
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Team Models
+// =============================================================================
+
+/// Crew of inspectors led by a supervisor, responsible for a set of locations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Team {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub supervisor_id: Option<i64>,
+    pub is_active: bool,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl BaseModel for Team {
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+}
+
+impl Validate for Team {
+    fn validate(&self) -> AppResult<()> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::validation("name", "Team name cannot be empty"));
+        }
+        if self.name.len() > 100 {
+            return Err(AppError::validation("name", "Team name cannot exceed 100 characters"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamMember {
+    pub user_id: i64,
+    pub username: String,
+    pub full_name: String,
+    pub role: UserRole,
+    pub is_active: bool,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// Team with its members and directly assigned locations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamWithMembers {
+    #[serde(flatten)]
+    pub team: Team,
+    pub members: Vec<TeamMember>,
+    /// Locations assigned to the team; their sub-locations are covered too
+    pub location_ids: Vec<i64>,
+}
+
+/// Who to notify about a location: the team covering it and its people
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamRoute {
+    pub team_id: i64,
+    pub team_name: String,
+    pub supervisor_id: Option<i64>,
+    /// Active members, excluding the supervisor
+    pub member_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberCompletionStats {
+    pub user_id: i64,
+    pub username: String,
+    pub total_scheduled: i64,
+    pub total_completed: i64,
+    pub completion_rate: f64,
+}
+
+/// Inspection completion for a team's members over a period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamCompletionStats {
+    pub team_id: i64,
+    pub team_name: String,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub total_scheduled: i64,
+    pub total_completed: i64,
+    pub completion_rate: f64,
+    /// Open inspections past due, at any date
+    pub overdue_open: i64,
+    pub members: Vec<MemberCompletionStats>,
+}
+
 // =============================================================================
 // Location Models
 // =============================================================================
@@ -127,11 +309,18 @@ pub struct Location {
     pub longitude: Option<f64>,
     pub description: Option<String>,
     pub parent_location_id: Option<i64>,
+    /// IANA time zone name used for due dates at this site
+    #[serde(default = "default_time_zone")]
+    pub time_zone: String,
     pub created_by: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+fn default_time_zone() -> String {
+    crate::scheduling::DEFAULT_TIME_ZONE.to_string()
+}
+
 impl BaseModel for Location {
     fn id(&self) -> i64 {
         self.id
@@ -159,6 +348,7 @@ impl Validate for Location {
                 return Err(AppError::validation("longitude", "Longitude must be between -180 and 180"));
             }
         }
+        crate::scheduling::parse_time_zone(&self.time_zone)?;
         Ok(())
     }
 }
@@ -171,6 +361,7 @@ pub struct LocationUpdateData {
     pub longitude: Option<f64>,
     pub description: Option<String>,
     pub parent_location_id: Option<Option<i64>>,
+    pub time_zone: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -182,6 +373,9 @@ pub struct LocationWithAssets {
     pub longitude: Option<f64>,
     pub description: Option<String>,
     pub parent_location_id: Option<i64>,
+    /// Path from the root of the hierarchy down to this location
+    #[serde(default)]
+    pub path: Vec<LocationBreadcrumb>,
     pub created_by: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -197,6 +391,9 @@ pub struct LocationAssetSummary {
     pub longitude: Option<f64>,
     pub description: Option<String>,
     pub parent_location_id: Option<i64>,
+    /// Path from the root of the hierarchy down to this location
+    #[serde(default)]
+    pub path: Vec<LocationBreadcrumb>,
     pub created_by: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -213,12 +410,227 @@ pub struct LocationWithAssetCount {
     pub longitude: Option<f64>,
     pub description: Option<String>,
     pub parent_location_id: Option<i64>,
+    /// Path from the root of the hierarchy down to this location
+    #[serde(default)]
+    pub path: Vec<LocationBreadcrumb>,
     pub created_by: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub asset_count: i64,
 }
 
+/// Deepest allowed location nesting. Hierarchy queries also stop at this
+/// depth so a cycle in legacy data cannot make them run forever.
+pub const MAX_LOCATION_DEPTH: usize = 32;
+
+/// One step in a location's path from the root of its hierarchy
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LocationBreadcrumb {
+    pub id: i64,
+    pub name: String,
+}
+
+/// Location with its nested child locations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationTreeNode {
+    pub id: i64,
+    pub name: String,
+    pub parent_location_id: Option<i64>,
+    pub time_zone: String,
+    pub depth: usize,
+    /// Assets assigned directly to this location
+    pub asset_count: i64,
+    /// Assets at this location and all of its descendants
+    pub subtree_asset_count: i64,
+    pub children: Vec<LocationTreeNode>,
+}
+
+impl LocationTreeNode {
+    /// Build location trees from flat `(location, direct asset count)` rows.
+    ///
+    /// With `root_id`, only that location's subtree is returned. Otherwise
+    /// every top-level location is a root; locations whose parent is missing
+    /// are treated as top-level. Each location is attached at most once, so
+    /// a parent cycle cannot recurse.
+    pub fn build(rows: Vec<(Location, i64)>, root_id: Option<i64>) -> Vec<LocationTreeNode> {
+        let ids: std::collections::HashSet<i64> = rows.iter().map(|(location, _)| location.id).collect();
+        let mut children: HashMap<Option<i64>, Vec<(Location, i64)>> = HashMap::new();
+        let mut root = None;
+        for (location, asset_count) in rows {
+            if Some(location.id) == root_id {
+                root = Some((location.clone(), asset_count));
+            }
+            let parent = location.parent_location_id.filter(|parent| ids.contains(parent));
+            children.entry(parent).or_default().push((location, asset_count));
+        }
+
+        let roots = match root_id {
+            Some(_) => {
+                // Detach the requested root from its parent's child list
+                if let Some((location, _)) = &root {
+                    let parent = location.parent_location_id.filter(|parent| ids.contains(parent));
+                    if let Some(siblings) = children.get_mut(&parent) {
+                        siblings.retain(|(sibling, _)| sibling.id != location.id);
+                    }
+                }
+                root.into_iter().collect()
+            }
+            None => children.remove(&None).unwrap_or_default(),
+        };
+
+        let mut nodes: Vec<LocationTreeNode> = roots
+            .into_iter()
+            .map(|(location, asset_count)| Self::attach(location, asset_count, 0, &mut children))
+            .collect();
+        nodes.sort_by(|a, b| a.name.cmp(&b.name));
+        nodes
+    }
+
+    fn attach(
+        location: Location,
+        asset_count: i64,
+        depth: usize,
+        children: &mut HashMap<Option<i64>, Vec<(Location, i64)>>,
+    ) -> LocationTreeNode {
+        let mut child_nodes: Vec<LocationTreeNode> = if depth < MAX_LOCATION_DEPTH {
+            children
+                .remove(&Some(location.id))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(child, count)| Self::attach(child, count, depth + 1, children))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        child_nodes.sort_by(|a, b| a.name.cmp(&b.name));
+
+        LocationTreeNode {
+            id: location.id,
+            name: location.name,
+            parent_location_id: location.parent_location_id,
+            time_zone: location.time_zone,
+            depth,
+            asset_count,
+            subtree_asset_count: asset_count + child_nodes.iter().map(|c| c.subtree_asset_count).sum::<i64>(),
+            children: child_nodes,
+        }
+    }
+}
+
+/// Asset and compliance totals for a location and all of its descendants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationRollup {
+    pub location_id: i64,
+    pub name: String,
+    pub path: Vec<LocationBreadcrumb>,
+    /// Number of locations in the subtree, including this one
+    pub location_count: i64,
+    pub asset_count: i64,
+    pub active_assets: i64,
+    pub maintenance_assets: i64,
+    pub open_inspections: i64,
+    pub overdue_inspections: i64,
+    pub critical_findings: i64,
+    /// Average percentage of compliant items across completed inspections
+    pub compliance_score: f64,
+}
+
+/// Longest accepted name of a holiday or shutdown
+pub const MAX_CLOSURE_NAME_LENGTH: usize = 100;
+
+/// Longest a single holiday or shutdown can last
+pub const MAX_CLOSURE_DAYS: i64 = 366;
+
+/// Why a site is closed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ClosureKind {
+    Holiday,
+    Shutdown,
+}
+
+impl std::fmt::Display for ClosureKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClosureKind::Holiday => write!(f, "Holiday"),
+            ClosureKind::Shutdown => write!(f, "Shutdown"),
+        }
+    }
+}
+
+impl std::str::FromStr for ClosureKind {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Holiday" => Ok(ClosureKind::Holiday),
+            "Shutdown" => Ok(ClosureKind::Shutdown),
+            _ => Err(AppError::validation("kind", format!("Invalid closure kind: {}", s))),
+        }
+    }
+}
+
+/// Dates a location and everything beneath it is closed, in its local
+/// calendar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationClosure {
+    pub id: i64,
+    pub location_id: i64,
+    pub name: String,
+    pub kind: ClosureKind,
+    pub start_date: NaiveDate,
+    /// Last closed day, inclusive
+    pub end_date: NaiveDate,
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A new holiday or shutdown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationClosureInput {
+    pub name: String,
+    pub kind: ClosureKind,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+impl Validate for LocationClosureInput {
+    fn validate(&self) -> AppResult<()> {
+        let name = self.name.trim();
+        if name.is_empty() || name.len() > MAX_CLOSURE_NAME_LENGTH {
+            return Err(AppError::validation(
+                "name",
+                format!("Closure name must be 1-{} characters", MAX_CLOSURE_NAME_LENGTH),
+            ));
+        }
+        if self.end_date < self.start_date {
+            return Err(AppError::validation("end_date", "End date cannot be before start date"));
+        }
+        let days = (self.end_date - self.start_date).num_days() + 1;
+        if days > MAX_CLOSURE_DAYS {
+            return Err(AppError::OutOfRange {
+                field: "end_date".to_string(),
+                value: format!("{} days", days),
+                min: "1".to_string(),
+                max: MAX_CLOSURE_DAYS.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Working calendar in effect at a location: its own or inherited working
+/// days, and the closures set on it and its ancestors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationCalendar {
+    pub location_id: i64,
+    pub working_days: Vec<Weekday>,
+    /// Location the working days are set on; unset when none is, in which
+    /// case every day is a working day
+    pub working_days_location_id: Option<i64>,
+    /// Closures in start date order
+    pub closures: Vec<LocationClosure>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocationDeletionResult {
     pub success: bool,
@@ -251,6 +663,12 @@ pub struct Asset {
     pub created_by: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub criticality: AssetCriticality,
+    #[serde(default)]
+    pub service_class: Option<CmaaServiceClass>,
+    #[serde(default)]
+    pub fem_group: Option<FemGroup>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -316,10 +734,227 @@ impl Validate for Asset {
                 return Err(AppError::validation("capacity", "Capacity must be greater than 0"));
             }
         }
+        if let Some(capacity) = self.rated_capacity()? {
+            capacity.validate()?;
+        }
+        validate_classification(self.service_class, self.fem_group)?;
         Ok(())
     }
 }
 
+impl Asset {
+    /// Typed rated capacity, or `None` when the value or unit is not set
+    pub fn rated_capacity(&self) -> AppResult<Option<Capacity>> {
+        Capacity::from_parts(self.capacity, self.capacity_unit.as_deref())
+    }
+
+    /// Duty of the crane from its classification, taking the heavier of the
+    /// two when both are recorded
+    pub fn duty(&self) -> Option<DutyLevel> {
+        DutyLevel::of(self.service_class, self.fem_group)
+    }
+}
+
+// =============================================================================
+// Crane Classification Models
+// =============================================================================
+
+/// How hard a crane is worked, the common scale the CMAA service classes and
+/// FEM mechanism groups are compared on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DutyLevel {
+    Standby,
+    Light,
+    Moderate,
+    Heavy,
+    Severe,
+    Continuous,
+}
+
+impl DutyLevel {
+    /// Duty of a classification, the heavier of the two when both are set
+    pub fn of(service_class: Option<CmaaServiceClass>, fem_group: Option<FemGroup>) -> Option<DutyLevel> {
+        service_class.map(CmaaServiceClass::duty).max(fem_group.map(FemGroup::duty))
+    }
+
+    /// Share of the standard inspection interval a crane at this duty gets.
+    /// Heavy service moves periodic inspections from yearly to quarterly
+    /// territory; severe and continuous service halves that again.
+    pub fn interval_factor(self) -> f64 {
+        match self {
+            DutyLevel::Standby | DutyLevel::Light | DutyLevel::Moderate => 1.0,
+            DutyLevel::Heavy => 0.5,
+            DutyLevel::Severe | DutyLevel::Continuous => 0.25,
+        }
+    }
+
+    /// `interval_days` shortened for this duty, never below a day
+    pub fn scale_interval(self, interval_days: i64) -> i64 {
+        ((interval_days as f64 * self.interval_factor()).round() as i64).max(1)
+    }
+}
+
+impl std::fmt::Display for DutyLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DutyLevel::Standby => write!(f, "Standby"),
+            DutyLevel::Light => write!(f, "Light"),
+            DutyLevel::Moderate => write!(f, "Moderate"),
+            DutyLevel::Heavy => write!(f, "Heavy"),
+            DutyLevel::Severe => write!(f, "Severe"),
+            DutyLevel::Continuous => write!(f, "Continuous"),
+        }
+    }
+}
+
+/// CMAA 70/74 service class of an overhead crane
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum CmaaServiceClass {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+}
+
+impl CmaaServiceClass {
+    pub const ALL: [CmaaServiceClass; 6] = [
+        CmaaServiceClass::A,
+        CmaaServiceClass::B,
+        CmaaServiceClass::C,
+        CmaaServiceClass::D,
+        CmaaServiceClass::E,
+        CmaaServiceClass::F,
+    ];
+
+    pub fn duty(self) -> DutyLevel {
+        match self {
+            CmaaServiceClass::A => DutyLevel::Standby,
+            CmaaServiceClass::B => DutyLevel::Light,
+            CmaaServiceClass::C => DutyLevel::Moderate,
+            CmaaServiceClass::D => DutyLevel::Heavy,
+            CmaaServiceClass::E => DutyLevel::Severe,
+            CmaaServiceClass::F => DutyLevel::Continuous,
+        }
+    }
+}
+
+impl std::fmt::Display for CmaaServiceClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CmaaServiceClass::A => write!(f, "A"),
+            CmaaServiceClass::B => write!(f, "B"),
+            CmaaServiceClass::C => write!(f, "C"),
+            CmaaServiceClass::D => write!(f, "D"),
+            CmaaServiceClass::E => write!(f, "E"),
+            CmaaServiceClass::F => write!(f, "F"),
+        }
+    }
+}
+
+impl std::str::FromStr for CmaaServiceClass {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(CmaaServiceClass::A),
+            "B" => Ok(CmaaServiceClass::B),
+            "C" => Ok(CmaaServiceClass::C),
+            "D" => Ok(CmaaServiceClass::D),
+            "E" => Ok(CmaaServiceClass::E),
+            "F" => Ok(CmaaServiceClass::F),
+            _ => Err(AppError::validation("service_class", format!("Invalid CMAA service class: {}", s))),
+        }
+    }
+}
+
+/// FEM 9.511 mechanism group of a hoist
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum FemGroup {
+    #[serde(rename = "1Dm")]
+    Group1Dm,
+    #[serde(rename = "1Cm")]
+    Group1Cm,
+    #[serde(rename = "1Bm")]
+    Group1Bm,
+    #[serde(rename = "1Am")]
+    Group1Am,
+    #[serde(rename = "2m")]
+    Group2m,
+    #[serde(rename = "3m")]
+    Group3m,
+    #[serde(rename = "4m")]
+    Group4m,
+    #[serde(rename = "5m")]
+    Group5m,
+}
+
+impl FemGroup {
+    pub const ALL: [FemGroup; 8] = [
+        FemGroup::Group1Dm,
+        FemGroup::Group1Cm,
+        FemGroup::Group1Bm,
+        FemGroup::Group1Am,
+        FemGroup::Group2m,
+        FemGroup::Group3m,
+        FemGroup::Group4m,
+        FemGroup::Group5m,
+    ];
+
+    pub fn duty(self) -> DutyLevel {
+        match self {
+            FemGroup::Group1Dm | FemGroup::Group1Cm => DutyLevel::Standby,
+            FemGroup::Group1Bm => DutyLevel::Light,
+            FemGroup::Group1Am => DutyLevel::Moderate,
+            FemGroup::Group2m => DutyLevel::Heavy,
+            FemGroup::Group3m => DutyLevel::Severe,
+            FemGroup::Group4m | FemGroup::Group5m => DutyLevel::Continuous,
+        }
+    }
+}
+
+impl std::fmt::Display for FemGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FemGroup::Group1Dm => write!(f, "1Dm"),
+            FemGroup::Group1Cm => write!(f, "1Cm"),
+            FemGroup::Group1Bm => write!(f, "1Bm"),
+            FemGroup::Group1Am => write!(f, "1Am"),
+            FemGroup::Group2m => write!(f, "2m"),
+            FemGroup::Group3m => write!(f, "3m"),
+            FemGroup::Group4m => write!(f, "4m"),
+            FemGroup::Group5m => write!(f, "5m"),
+        }
+    }
+}
+
+impl std::str::FromStr for FemGroup {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FemGroup::ALL
+            .into_iter()
+            .find(|group| group.to_string() == s)
+            .ok_or_else(|| AppError::validation("fem_group", format!("Invalid FEM group: {}", s)))
+    }
+}
+
+/// A CMAA class and FEM group recorded for the same crane must describe
+/// roughly the same duty; more than one step apart means one of them is wrong
+pub fn validate_classification(service_class: Option<CmaaServiceClass>, fem_group: Option<FemGroup>) -> AppResult<()> {
+    if let (Some(class), Some(group)) = (service_class, fem_group) {
+        if (class.duty() as i32 - group.duty() as i32).abs() > 1 {
+            return Err(AppError::validation(
+                "fem_group",
+                format!("FEM group {} ({} duty) does not match CMAA class {} ({} duty)",
+                        group, group.duty(), class, class.duty()),
+            ));
+        }
+    }
+    Ok(())
+}
+
 // =============================================================================
 // Component Models
 // =============================================================================
@@ -399,20 +1034,67 @@ impl Validate for Component {
     }
 }
 
-// =============================================================================
-// Compliance Models
-// =============================================================================
-
+/// Component created on every new asset of an asset type, with default
+/// specifications the inspector can fill in later
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ComplianceStandard {
+pub struct ComponentTemplate {
     pub id: i64,
-    pub standard_code: String,
-    pub standard_name: String,
-    pub version: String,
-    pub requirements: Option<JsonValue>,
-    pub is_active: bool,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
+    /// Matched against the asset type ignoring case
+    pub asset_type: String,
+    pub component_name: String,
+    pub component_type: String,
+    pub default_specifications: Option<JsonValue>,
+    /// Order the components are created in
+    pub sort_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// New or replacement component template, keyed by asset type and component name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentTemplateInput {
+    pub asset_type: String,
+    pub component_name: String,
+    pub component_type: String,
+    pub default_specifications: Option<JsonValue>,
+    #[serde(default)]
+    pub sort_order: i32,
+}
+
+impl Validate for ComponentTemplateInput {
+    fn validate(&self) -> AppResult<()> {
+        if self.asset_type.trim().is_empty() {
+            return Err(AppError::validation("asset_type", "Asset type cannot be empty"));
+        }
+        if self.component_name.trim().is_empty() {
+            return Err(AppError::validation("component_name", "Component name cannot be empty"));
+        }
+        if self.component_type.trim().is_empty() {
+            return Err(AppError::validation("component_type", "Component type cannot be empty"));
+        }
+        if let Some(specifications) = &self.default_specifications {
+            if !specifications.is_object() {
+                return Err(AppError::validation("default_specifications", "Default specifications must be a JSON object"));
+            }
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Compliance Models
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceStandard {
+    pub id: i64,
+    pub standard_code: String,
+    pub standard_name: String,
+    pub version: String,
+    pub requirements: Option<JsonValue>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 impl BaseModel for ComplianceStandard {
@@ -481,6 +1163,87 @@ impl Validate for ComplianceChecklistTemplate {
     }
 }
 
+/// What an inspector should see alongside a checklist item: what a pass
+/// looks like, the clauses of the standard it checks, and reference photos
+/// from the media library
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChecklistItemGuidance {
+    pub acceptance_criteria: Option<String>,
+    #[serde(default)]
+    pub clause_references: Vec<String>,
+    #[serde(default)]
+    pub reference_media_ids: Vec<i64>,
+}
+
+impl Validate for ChecklistItemGuidance {
+    fn validate(&self) -> AppResult<()> {
+        if self.clause_references.iter().any(|clause| clause.trim().is_empty()) {
+            return Err(AppError::validation("clause_references", "Clause references cannot be empty"));
+        }
+        Ok(())
+    }
+}
+
+/// Maximum length of a clause reference such as `1910.179(j)(2)(iii)`
+pub const MAX_CLAUSE_REF_LENGTH: usize = 100;
+
+/// A clause in a standard's catalog that findings can cite
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandardClause {
+    pub id: i64,
+    pub standard_id: i64,
+    pub clause_ref: String,
+    pub title: String,
+    pub text: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A clause to add to a standard's catalog, or replace by reference
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StandardClauseInput {
+    pub clause_ref: String,
+    pub title: String,
+    pub text: Option<String>,
+}
+
+impl Validate for StandardClauseInput {
+    fn validate(&self) -> AppResult<()> {
+        let clause_ref = self.clause_ref.trim();
+        if clause_ref.is_empty() {
+            return Err(AppError::validation("clause_ref", "Clause reference cannot be empty"));
+        }
+        if clause_ref.chars().count() > MAX_CLAUSE_REF_LENGTH {
+            return Err(AppError::validation(
+                "clause_ref",
+                format!("Clause reference cannot exceed {} characters", MAX_CLAUSE_REF_LENGTH),
+            ));
+        }
+        if self.title.trim().is_empty() {
+            return Err(AppError::validation("title", "Clause title cannot be empty"));
+        }
+        Ok(())
+    }
+}
+
+/// Whether two spellings name the same standard, ignoring case, spacing and
+/// punctuation, so `OSHA 1910.179` matches the code `OSHA_1910_179`
+pub fn same_standard(a: &str, b: &str) -> bool {
+    let normalize = |s: &str| s.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect::<String>();
+    let a = normalize(a);
+    !a.is_empty() && a == normalize(b)
+}
+
+/// A reference photo resolved for a generated checklist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecklistReferenceImage {
+    pub media_id: i64,
+    pub file_name: String,
+    pub file_path: String,
+    pub mime_type: String,
+    pub description: Option<String>,
+}
+
 // =============================================================================
 // Inspection Models
 // =============================================================================
@@ -501,6 +1264,12 @@ pub struct Inspection {
     pub ai_analysis_results: Option<JsonValue>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Time zone of the asset's location when the inspection was scheduled
+    #[serde(default)]
+    pub time_zone: Option<String>,
+    /// Local midnight after the scheduled date, in UTC
+    #[serde(default)]
+    pub overdue_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -536,6 +1305,24 @@ impl std::str::FromStr for InspectionType {
     }
 }
 
+impl InspectionType {
+    /// Days until an inspection of this type is next due after the last one
+    pub fn interval_days(&self) -> i64 {
+        match self {
+            InspectionType::Frequent => 30,  // Monthly
+            InspectionType::Periodic => 365, // Yearly
+            InspectionType::Initial => 1,    // Immediate
+            InspectionType::Special => 90,   // Quarterly
+        }
+    }
+
+    /// Whether inspections of this type recur on a schedule. Initial and
+    /// special inspections are triggered by events instead.
+    pub fn is_recurring(&self) -> bool {
+        matches!(self, InspectionType::Frequent | InspectionType::Periodic)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum InspectionStatus {
     Scheduled,
@@ -590,6 +1377,19 @@ impl std::fmt::Display for Condition {
     }
 }
 
+impl Condition {
+    /// Score from Critical (1) to Excellent (5), for averaging conditions
+    pub fn score(&self) -> u8 {
+        match self {
+            Condition::Excellent => 5,
+            Condition::Good => 4,
+            Condition::Fair => 3,
+            Condition::Poor => 2,
+            Condition::Critical => 1,
+        }
+    }
+}
+
 impl std::str::FromStr for Condition {
     type Err = AppError;
 
@@ -637,6 +1437,9 @@ impl Validate for Inspection {
 // Inspection Item Models
 // =============================================================================
 
+/// Most inspection items created by one batch
+pub const MAX_INSPECTION_ITEM_BATCH: usize = 500;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InspectionItem {
     pub id: i64,
@@ -650,6 +1453,19 @@ pub struct InspectionItem {
     pub is_compliant: Option<bool>,
     pub corrective_action: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Clause of the inspection's standard the finding is raised against
+    #[serde(default)]
+    pub clause_id: Option<i64>,
+    /// Set from the risk matrix when the finding's severity is saved
+    #[serde(default)]
+    pub risk_rating: Option<RiskRating>,
+    #[serde(default)]
+    pub response_due_at: Option<DateTime<Utc>>,
+    /// Reading taken for the item, checked against measurement rules
+    #[serde(default)]
+    pub measured_value: Option<f64>,
+    #[serde(default)]
+    pub measurement_unit: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -660,6 +1476,18 @@ pub enum Severity {
     Critical,
 }
 
+impl Severity {
+    /// Position in the scale from Low (0) to Critical (3)
+    pub fn rank(&self) -> u8 {
+        match self {
+            Severity::Low => 0,
+            Severity::Medium => 1,
+            Severity::High => 2,
+            Severity::Critical => 3,
+        }
+    }
+}
+
 impl std::fmt::Display for Severity {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -685,6 +1513,16 @@ impl std::str::FromStr for Severity {
     }
 }
 
+impl InspectionItem {
+    /// Whether the item records a problem: a finding, a severity or a
+    /// compliance failure
+    pub fn has_finding(&self) -> bool {
+        self.finding.as_deref().is_some_and(|f| !f.trim().is_empty())
+            || self.severity.is_some()
+            || self.is_compliant == Some(false)
+    }
+}
+
 impl Validate for InspectionItem {
     fn validate(&self) -> AppResult<()> {
         if self.item_name.trim().is_empty() {
@@ -693,6 +1531,9 @@ impl Validate for InspectionItem {
         if self.item_category.trim().is_empty() {
             return Err(AppError::validation("item_category", "Item category cannot be empty"));
         }
+        if self.measured_value.is_some_and(|value| !value.is_finite()) {
+            return Err(AppError::validation("measured_value", "Measured value must be a finite number"));
+        }
         Ok(())
     }
 }
@@ -714,6 +1555,9 @@ pub struct MediaFile {
     pub description: Option<String>,
     pub ai_analysis_metadata: Option<JsonValue>,
     pub created_at: DateTime<Utc>,
+    /// User who uploaded the file; `None` for files from before this was kept
+    #[serde(default)]
+    pub uploaded_by: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -771,6 +1615,34 @@ impl Validate for MediaFile {
     }
 }
 
+/// Why an uploaded photo may be the wrong one. The upload still succeeds;
+/// the inspector decides whether to keep it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PhotoWarning {
+    /// The same file is attached to another inspection of the asset
+    DuplicateOfEarlierInspection {
+        media_id: i64,
+        inspection_id: i64,
+        inspection_date: DateTime<Utc>,
+    },
+    /// The camera recorded a date before the inspection's
+    TakenBeforeInspection {
+        captured_at: chrono::NaiveDateTime,
+        inspection_date: NaiveDate,
+    },
+}
+
+/// A stored upload with any warnings about it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadedMedia {
+    #[serde(flatten)]
+    pub media: MediaFile,
+    /// Set when `warnings` is not empty, so clients can check one flag
+    pub needs_review: bool,
+    pub warnings: Vec<PhotoWarning>,
+}
+
 // =============================================================================
 // AI Model Result Models
 // =============================================================================
@@ -786,6 +1658,9 @@ pub struct AiModelResult {
     pub confidence_score: f64,
     pub status: AiAnalysisStatus,
     pub processed_at: Option<DateTime<Utc>>,
+    /// Request that queued the job
+    #[serde(default)]
+    pub trace_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -944,112 +1819,5426 @@ impl Validate for MaintenanceRecord {
     }
 }
 
+/// Fields supplied when recording maintenance work
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceRecordInput {
+    pub asset_id: i64,
+    pub component_id: Option<i64>,
+    pub maintenance_type: MaintenanceType,
+    pub scheduled_date: Option<DateTime<Utc>>,
+    /// Set when logging work that is already done
+    pub completed_date: Option<DateTime<Utc>>,
+    pub performed_by: String,
+    pub description: String,
+    /// Scheduled unless given
+    pub status: Option<MaintenanceStatus>,
+    pub parts_used: Option<JsonValue>,
+    pub cost: Option<f64>,
+}
+
+impl MaintenanceRecordInput {
+    pub fn into_record(self) -> MaintenanceRecord {
+        MaintenanceRecord {
+            id: 0,
+            asset_id: self.asset_id,
+            component_id: self.component_id,
+            maintenance_type: self.maintenance_type,
+            scheduled_date: self.scheduled_date,
+            completed_date: self.completed_date,
+            performed_by: self.performed_by,
+            description: self.description,
+            status: self.status.unwrap_or(MaintenanceStatus::Scheduled),
+            parts_used: self.parts_used,
+            cost: self.cost,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Changes to an open maintenance record; `None` leaves a field as is
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaintenanceUpdateData {
+    pub component_id: Option<Option<i64>>,
+    pub maintenance_type: Option<MaintenanceType>,
+    pub scheduled_date: Option<Option<DateTime<Utc>>>,
+    pub performed_by: Option<String>,
+    pub description: Option<String>,
+    pub parts_used: Option<Option<JsonValue>>,
+    pub cost: Option<Option<f64>>,
+}
+
 // =============================================================================
-// Helper Types and Utilities
+// Spare Parts Models
 // =============================================================================
 
-/// Common query filters for database operations
+/// Spare part kept in stock, counted in whole units
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct QueryFilter {
-    pub page: Option<i64>,
-    pub limit: Option<i64>,
-    pub sort_by: Option<String>,
-    pub sort_order: Option<SortOrder>,
-    pub filters: HashMap<String, String>,
+pub struct Part {
+    pub id: i64,
+    pub part_number: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub manufacturer: Option<String>,
+    pub unit_cost: Option<f64>,
+    pub quantity_on_hand: i64,
+    /// Stock at or below this level is reported as low
+    pub reorder_level: i64,
+    pub compatibility: Vec<PartCompatibility>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
+impl Part {
+    pub fn is_low_stock(&self) -> bool {
+        self.quantity_on_hand <= self.reorder_level
+    }
+}
+
+/// New part, or the editable details of an existing one. Stock on hand only
+/// changes through stock movements.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum SortOrder {
-    Asc,
-    Desc,
+pub struct PartInput {
+    pub part_number: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub manufacturer: Option<String>,
+    pub unit_cost: Option<f64>,
+    #[serde(default)]
+    pub reorder_level: i64,
 }
 
-impl std::fmt::Display for SortOrder {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SortOrder::Asc => write!(f, "ASC"),
-            SortOrder::Desc => write!(f, "DESC"),
+impl Validate for PartInput {
+    fn validate(&self) -> AppResult<()> {
+        if self.part_number.trim().is_empty() {
+            return Err(AppError::validation("part_number", "Part number cannot be empty"));
+        }
+        if self.name.trim().is_empty() {
+            return Err(AppError::validation("name", "Part name cannot be empty"));
+        }
+        if let Some(unit_cost) = self.unit_cost {
+            if unit_cost < 0.0 {
+                return Err(AppError::validation("unit_cost", "Unit cost cannot be negative"));
+            }
+        }
+        if self.reorder_level < 0 {
+            return Err(AppError::validation("reorder_level", "Reorder level cannot be negative"));
         }
+        Ok(())
     }
 }
 
-impl Default for QueryFilter {
-    fn default() -> Self {
-        Self {
-            page: Some(1),
-            limit: Some(50),
-            sort_by: None,
-            sort_order: Some(SortOrder::Desc),
-            filters: HashMap::new(),
+/// Components a part fits: those of the component type, narrowed to a
+/// manufacturer and model when given
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PartCompatibility {
+    pub component_type: String,
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+}
+
+impl Validate for PartCompatibility {
+    fn validate(&self) -> AppResult<()> {
+        if self.component_type.trim().is_empty() {
+            return Err(AppError::validation("component_type", "Component type cannot be empty"));
         }
+        Ok(())
     }
 }
 
-/// Database operation result with pagination metadata
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PaginatedResult<T> {
-    pub data: Vec<T>,
-    pub total_count: i64,
-    pub page: i64,
-    pub limit: i64,
-    pub total_pages: i64,
+/// Why a part's stock changed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum StockMovementKind {
+    /// Stock received into the store
+    Received,
+    /// Used up by maintenance work
+    Consumed,
+    /// Correction after a stock count
+    Adjusted,
 }
 
-impl<T> PaginatedResult<T> {
-    pub fn new(data: Vec<T>, total_count: i64, page: i64, limit: i64) -> Self {
-        let total_pages = (total_count + limit - 1) / limit; // Ceiling division
-        Self {
-            data,
-            total_count,
-            page,
-            limit,
-            total_pages,
+impl std::fmt::Display for StockMovementKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StockMovementKind::Received => write!(f, "Received"),
+            StockMovementKind::Consumed => write!(f, "Consumed"),
+            StockMovementKind::Adjusted => write!(f, "Adjusted"),
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl std::str::FromStr for StockMovementKind {
+    type Err = AppError;
 
-    #[test]
-    fn test_user_role_parsing() {
-        assert_eq!("Inspector".parse::<UserRole>().unwrap(), UserRole::Inspector);
-        assert_eq!("Supervisor".parse::<UserRole>().unwrap(), UserRole::Supervisor);
-        assert!("InvalidRole".parse::<UserRole>().is_err());
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Received" => Ok(StockMovementKind::Received),
+            "Consumed" => Ok(StockMovementKind::Consumed),
+            "Adjusted" => Ok(StockMovementKind::Adjusted),
+            _ => Err(AppError::validation("kind", format!("Invalid stock movement kind: {}", s))),
+        }
     }
+}
 
-    #[test]
-    fn test_user_validation() {
-        let mut user = User {
-            id: 1,
-            username: "testuser".to_string(),
-            email: "test@example.com".to_string(),
-            password_hash: "hash".to_string(),
-            role: UserRole::Inspector,
-            first_name: "Test".to_string(),
-            last_name: "User".to_string(),
-            phone: None,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-            is_active: true,
-        };
+/// A change to a part's stock on hand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockMovement {
+    pub id: i64,
+    pub part_id: i64,
+    pub kind: StockMovementKind,
+    /// Positive when stock is added, negative when it is taken out
+    pub quantity_change: i64,
+    pub maintenance_record_id: Option<i64>,
+    pub notes: Option<String>,
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
 
-        assert!(user.validate().is_ok());
+/// Parts used by maintenance work. Maintenance records whose `parts_used`
+/// is a list of these consume the stock when they are completed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartUsage {
+    pub part_number: String,
+    pub quantity: i64,
+}
 
-        user.email = "invalid-email".to_string();
-        assert!(user.validate().is_err());
+impl Validate for PartUsage {
+    fn validate(&self) -> AppResult<()> {
+        if self.part_number.trim().is_empty() {
+            return Err(AppError::validation("part_number", "Part number cannot be empty"));
+        }
+        if self.quantity <= 0 {
+            return Err(AppError::validation("quantity", "Quantity used must be at least 1"));
+        }
+        Ok(())
     }
+}
 
-    #[test]
-    fn test_asset_status_parsing() {
-        assert_eq!("Active".parse::<AssetStatus>().unwrap(), AssetStatus::Active);
-        assert_eq!("Maintenance".parse::<AssetStatus>().unwrap(), AssetStatus::Maintenance);
-        assert!("InvalidStatus".parse::<AssetStatus>().is_err());
+// =============================================================================
+// Comment Models
+// =============================================================================
+
+/// Longest accepted comment body, in characters
+pub const MAX_COMMENT_LENGTH: usize = 10_000;
+
+/// Record a comment thread is attached to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CommentEntityType {
+    Asset,
+    Inspection,
+    /// An inspection item with a finding
+    Finding,
+    WorkOrder,
+}
+
+impl CommentEntityType {
+    /// Table holding the commented records
+    pub fn table(&self) -> &'static str {
+        match self {
+            CommentEntityType::Asset => "assets",
+            CommentEntityType::Inspection => "inspections",
+            CommentEntityType::Finding => "inspection_items",
+            CommentEntityType::WorkOrder => "work_orders",
+        }
     }
 
-    #[test]
-    fn test_paginated_result() {
+    /// Permission resource guarding the commented records
+    pub fn resource(&self) -> &'static str {
+        match self {
+            CommentEntityType::Asset => "asset",
+            CommentEntityType::Inspection | CommentEntityType::Finding => "inspection",
+            CommentEntityType::WorkOrder => "work_order",
+        }
+    }
+}
+
+impl std::fmt::Display for CommentEntityType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommentEntityType::Asset => write!(f, "Asset"),
+            CommentEntityType::Inspection => write!(f, "Inspection"),
+            CommentEntityType::Finding => write!(f, "Finding"),
+            CommentEntityType::WorkOrder => write!(f, "WorkOrder"),
+        }
+    }
+}
+
+impl std::str::FromStr for CommentEntityType {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Asset" => Ok(CommentEntityType::Asset),
+            "Inspection" => Ok(CommentEntityType::Inspection),
+            "Finding" => Ok(CommentEntityType::Finding),
+            "WorkOrder" => Ok(CommentEntityType::WorkOrder),
+            _ => Err(AppError::validation("entity_type", format!("Invalid comment entity type: {}", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: i64,
+    pub entity_type: CommentEntityType,
+    pub entity_id: i64,
+    /// Top-level comment this is a reply to
+    pub parent_id: Option<i64>,
+    pub author_id: i64,
+    pub author_name: String,
+    /// Empty once the comment has been deleted
+    pub body: String,
+    pub is_deleted: bool,
+    pub edit_count: i64,
+    /// Users mentioned with `@username`
+    pub mentioned_user_ids: Vec<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Top-level comment with its replies, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentThread {
+    pub comment: Comment,
+    pub replies: Vec<Comment>,
+}
+
+/// Earlier version of an edited comment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentEdit {
+    pub id: i64,
+    pub comment_id: i64,
+    pub previous_body: String,
+    pub edited_by: i64,
+    pub edited_at: DateTime<Utc>,
+}
+
+/// A comment that mentions the current user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentMention {
+    pub comment_id: i64,
+    pub user_id: i64,
+    pub entity_type: CommentEntityType,
+    pub entity_id: i64,
+    pub author_name: String,
+    pub excerpt: String,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+/// Validate a comment body before saving
+pub fn validate_comment_body(body: &str) -> AppResult<()> {
+    if body.trim().is_empty() {
+        return Err(AppError::validation("body", "Comment cannot be empty"));
+    }
+    if body.chars().count() > MAX_COMMENT_LENGTH {
+        return Err(AppError::validation(
+            "body",
+            format!("Comment cannot exceed {} characters", MAX_COMMENT_LENGTH),
+        ));
+    }
+    Ok(())
+}
+
+/// Usernames mentioned as `@username`, lowercased and without duplicates.
+/// An `@` inside a word (such as an email address) is not a mention.
+pub fn extract_mentions(body: &str) -> Vec<String> {
+    let is_name_char = |c: char| c.is_alphanumeric() || matches!(c, '_' | '.' | '-');
+    let mut mentions: Vec<String> = Vec::new();
+    let mut previous: Option<char> = None;
+
+    for (index, c) in body.char_indices() {
+        if c == '@' && !previous.is_some_and(is_name_char) {
+            let rest = &body[index + 1..];
+            let name: String = rest.chars().take_while(|&c| is_name_char(c)).collect();
+            // Trailing punctuation ends a sentence rather than the name
+            let name = name.trim_end_matches(['.', '-']).to_lowercase();
+            if !name.is_empty() && !mentions.contains(&name) {
+                mentions.push(name);
+            }
+        }
+        previous = Some(c);
+    }
+    mentions
+}
+
+// =============================================================================
+// Watch Models
+// =============================================================================
+
+/// Default look-ahead for due-date alerts on watched records
+pub const DEFAULT_WATCH_DUE_DAYS: i64 = 7;
+
+/// Record type a user can watch
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum WatchEntityType {
+    /// Watching an asset also covers its inspections
+    Asset,
+    Inspection,
+}
+
+impl WatchEntityType {
+    /// Table holding the watched records
+    pub fn table(&self) -> &'static str {
+        match self {
+            WatchEntityType::Asset => "assets",
+            WatchEntityType::Inspection => "inspections",
+        }
+    }
+
+    /// Permission resource guarding the watched records
+    pub fn resource(&self) -> &'static str {
+        match self {
+            WatchEntityType::Asset => "asset",
+            WatchEntityType::Inspection => "inspection",
+        }
+    }
+}
+
+impl std::fmt::Display for WatchEntityType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WatchEntityType::Asset => write!(f, "Asset"),
+            WatchEntityType::Inspection => write!(f, "Inspection"),
+        }
+    }
+}
+
+impl std::str::FromStr for WatchEntityType {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Asset" => Ok(WatchEntityType::Asset),
+            "Inspection" => Ok(WatchEntityType::Inspection),
+            _ => Err(AppError::validation("entity_type", format!("Invalid watch entity type: {}", s))),
+        }
+    }
+}
+
+/// A record starred by a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Watch {
+    pub user_id: i64,
+    pub entity_type: WatchEntityType,
+    pub entity_id: i64,
+    /// Asset name, or inspection type and asset name
+    pub label: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum WatchEventKind {
+    StatusChanged,
+    NewFinding,
+    DueSoon,
+    /// An insurance policy or registration on a watched asset is up for renewal
+    RenewalDue,
+}
+
+/// Notification for a watcher of an asset or inspection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchNotification {
+    pub user_id: i64,
+    pub kind: WatchEventKind,
+    /// Record the event concerns
+    pub entity_type: WatchEntityType,
+    pub entity_id: i64,
+    pub asset_id: i64,
+    pub message: String,
+    pub due_date: Option<DateTime<Utc>>,
+}
+
+// =============================================================================
+// Recycle Bin Models
+// =============================================================================
+
+/// Days deleted records stay restorable when no retention is configured
+pub const DEFAULT_RECYCLE_RETENTION_DAYS: i64 = 30;
+
+/// Record type kept in the recycle bin when deleted
+///
+/// Assets, inspections and users are soft deleted: the row stays in its table
+/// with `deleted_at` set, is hidden from normal queries and is kept until an
+/// administrator purges it. Locations and teams are removed, with a snapshot
+/// kept in the `recycle_bin` table for the retention window.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RecycleEntityType {
+    Asset,
+    Inspection,
+    Location,
+    User,
+    Team,
+}
+
+impl RecycleEntityType {
+    pub const ALL: [RecycleEntityType; 5] = [
+        RecycleEntityType::Asset,
+        RecycleEntityType::Inspection,
+        RecycleEntityType::Location,
+        RecycleEntityType::User,
+        RecycleEntityType::Team,
+    ];
+
+    /// Table holding the records
+    pub fn table(&self) -> &'static str {
+        match self {
+            RecycleEntityType::Asset => "assets",
+            RecycleEntityType::Inspection => "inspections",
+            RecycleEntityType::Location => "locations",
+            RecycleEntityType::User => "users",
+            RecycleEntityType::Team => "teams",
+        }
+    }
+
+    /// Permission resource guarding the records
+    pub fn resource(&self) -> &'static str {
+        match self {
+            RecycleEntityType::Asset => "asset",
+            RecycleEntityType::Inspection => "inspection",
+            RecycleEntityType::Location => "location",
+            RecycleEntityType::User => "user",
+            RecycleEntityType::Team => "team",
+        }
+    }
+
+    /// SQL expression labelling a soft-deleted record, with the table aliased
+    /// as `t`, or `None` for types that are removed from their table when
+    /// deleted
+    pub fn soft_delete_label(&self) -> Option<&'static str> {
+        match self {
+            RecycleEntityType::Asset => Some("t.asset_number || ' - ' || t.asset_name"),
+            RecycleEntityType::Inspection => Some("t.inspection_type || ' inspection #' || t.id"),
+            RecycleEntityType::User => Some("t.username"),
+            RecycleEntityType::Location | RecycleEntityType::Team => None,
+        }
+    }
+
+    /// Rows owned by a soft-deleted record, as `(table, foreign key column)`,
+    /// removed with it when it is purged
+    pub fn purged_with(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            RecycleEntityType::Inspection => &[
+                ("inspection_items", "inspection_id"),
+                ("media_files", "inspection_id"),
+                ("ai_model_results", "inspection_id"),
+            ],
+            _ => &[],
+        }
+    }
+
+    /// Rows removed with the record by `ON DELETE CASCADE`, as
+    /// `(table, foreign key column)`, restored along with it
+    pub fn dependents(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            RecycleEntityType::Asset => &[("prestart_checks", "asset_id")],
+            RecycleEntityType::Location => &[("team_locations", "location_id")],
+            RecycleEntityType::User => &[
+                ("user_absences", "user_id"),
+                ("team_members", "user_id"),
+                ("watches", "user_id"),
+                ("comment_mentions", "user_id"),
+            ],
+            RecycleEntityType::Team => &[("team_members", "team_id"), ("team_locations", "team_id")],
+            RecycleEntityType::Inspection => &[],
+        }
+    }
+}
+
+impl std::fmt::Display for RecycleEntityType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecycleEntityType::Asset => write!(f, "Asset"),
+            RecycleEntityType::Inspection => write!(f, "Inspection"),
+            RecycleEntityType::Location => write!(f, "Location"),
+            RecycleEntityType::User => write!(f, "User"),
+            RecycleEntityType::Team => write!(f, "Team"),
+        }
+    }
+}
+
+impl std::str::FromStr for RecycleEntityType {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Asset" => Ok(RecycleEntityType::Asset),
+            "Inspection" => Ok(RecycleEntityType::Inspection),
+            "Location" => Ok(RecycleEntityType::Location),
+            "User" => Ok(RecycleEntityType::User),
+            "Team" => Ok(RecycleEntityType::Team),
+            _ => Err(AppError::validation("entity_type", format!("Invalid recycle bin entity type: {}", s))),
+        }
+    }
+}
+
+/// A deleted record that can still be restored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedRecord {
+    pub entity_type: RecycleEntityType,
+    pub entity_id: i64,
+    pub label: String,
+    pub deleted_by: Option<i64>,
+    pub deleted_by_name: Option<String>,
+    pub deleted_at: DateTime<Utc>,
+    /// When the record is permanently purged; `None` for soft-deleted records,
+    /// which are kept until an administrator purges them
+    pub purge_after: Option<DateTime<Utc>>,
+}
+
+/// Outcome of restoring a deleted record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreResult {
+    pub entity_type: RecycleEntityType,
+    pub entity_id: i64,
+    pub label: String,
+    /// Related rows (team memberships, absences, ...) put back
+    pub restored_related: usize,
+    /// Related rows that could not be put back because what they point to
+    /// no longer exists
+    pub skipped_related: usize,
+}
+
+// =============================================================================
+// Bulk Operation Models
+// =============================================================================
+
+/// Most records accepted by one bulk operation
+pub const MAX_BULK_RECORDS: usize = 5_000;
+
+/// Records processed per transaction when no batch size is given
+pub const DEFAULT_BULK_BATCH_SIZE: usize = 100;
+
+/// Largest accepted batch size
+pub const MAX_BULK_BATCH_SIZE: usize = 500;
+
+/// Longest accepted tag
+pub const MAX_TAG_LENGTH: usize = 50;
+
+/// Record type a bulk operation applies to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BulkEntityType {
+    Asset,
+    Inspection,
+}
+
+impl BulkEntityType {
+    /// Table holding the records
+    pub fn table(&self) -> &'static str {
+        match self {
+            BulkEntityType::Asset => "assets",
+            BulkEntityType::Inspection => "inspections",
+        }
+    }
+
+    /// Permission resource guarding the records
+    pub fn resource(&self) -> &'static str {
+        match self {
+            BulkEntityType::Asset => "asset",
+            BulkEntityType::Inspection => "inspection",
+        }
+    }
+
+    /// Status records are moved to when archived
+    pub fn archived_status(&self) -> String {
+        match self {
+            BulkEntityType::Asset => AssetStatus::Decommissioned.to_string(),
+            BulkEntityType::Inspection => InspectionStatus::Cancelled.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for BulkEntityType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BulkEntityType::Asset => write!(f, "Asset"),
+            BulkEntityType::Inspection => write!(f, "Inspection"),
+        }
+    }
+}
+
+/// Change applied to every record of a bulk operation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum BulkOperation {
+    /// Delete the records; deleted assets go to the recycle bin
+    Delete,
+    /// Move the records to their inactive status (decommissioned assets,
+    /// cancelled inspections)
+    Archive,
+    /// Set the status, given as the status name of the entity type
+    SetStatus { status: String },
+    /// Add tags to the records
+    Tag { tags: Vec<String> },
+}
+
+impl BulkOperation {
+    /// Permission action required on the entity type's resource
+    pub fn action(&self) -> &'static str {
+        match self {
+            BulkOperation::Delete => "delete",
+            _ => "update",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BulkOperation::Delete => "delete",
+            BulkOperation::Archive => "archive",
+            BulkOperation::SetStatus { .. } => "set_status",
+            BulkOperation::Tag { .. } => "tag",
+        }
+    }
+
+    /// Check the operation can be applied to the entity type
+    pub fn validate_for(&self, entity_type: BulkEntityType) -> AppResult<()> {
+        match self {
+            BulkOperation::Delete if entity_type == BulkEntityType::Inspection => {
+                Err(AppError::validation("operation", "Inspections cannot be deleted; archive them instead"))
+            }
+            BulkOperation::SetStatus { status } => match entity_type {
+                BulkEntityType::Asset => status.parse::<AssetStatus>().map(|_| ()),
+                BulkEntityType::Inspection => status.parse::<InspectionStatus>().map(|_| ()),
+            },
+            BulkOperation::Tag { tags } => {
+                if tags.is_empty() {
+                    return Err(AppError::validation("tags", "At least one tag is required"));
+                }
+                for tag in tags {
+                    let tag = tag.trim();
+                    if tag.is_empty() || tag.chars().count() > MAX_TAG_LENGTH {
+                        return Err(AppError::validation(
+                            "tags",
+                            format!("Tags must be 1 to {} characters", MAX_TAG_LENGTH),
+                        ));
+                    }
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// How failures of individual records are handled
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum BulkMode {
+    /// Any failure rolls back every record
+    #[default]
+    AllOrNothing,
+    /// Failed records are skipped; the others are kept
+    BestEffort,
+}
+
+/// Outcome for one record of a bulk operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkRecordResult {
+    pub id: i64,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkOperationResult {
+    pub entity_type: BulkEntityType,
+    pub operation: BulkOperation,
+    pub mode: BulkMode,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Set when an all-or-nothing operation failed and nothing was changed
+    pub rolled_back: bool,
+    /// Set when the operation was stopped by an administrator; records not
+    /// reached are reported as failed
+    #[serde(default)]
+    pub cancelled: bool,
+    pub results: Vec<BulkRecordResult>,
+}
+
+/// Outcome of applying the same changes to many records
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkUpdateResult {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkRecordResult>,
+}
+
+// =============================================================================
+// Data Quality Models
+// =============================================================================
+
+/// Most issues listed per check; counts still cover every issue found
+pub const MAX_ISSUES_PER_CHECK: usize = 500;
+
+/// Kind of problem looked for by the data quality checks
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DataQualityCheck {
+    AssetWithoutLocation,
+    MissingInspector,
+    NegativeCapacity,
+    OrphanedComponent,
+    DateInconsistency,
+}
+
+/// One problem record found by a data quality check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataQualityIssue {
+    pub check: DataQualityCheck,
+    pub severity: Severity,
+    /// Table-level name of the affected record, e.g. `Asset`
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub label: String,
+    pub description: String,
+    /// How to fix the record
+    pub suggestion: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataQualityCheckSummary {
+    pub check: DataQualityCheck,
+    pub issue_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataQualityReport {
+    pub generated_at: DateTime<Utc>,
+    pub total_issues: usize,
+    pub summary: Vec<DataQualityCheckSummary>,
+    pub issues: Vec<DataQualityIssue>,
+    /// Set when some checks found more than `MAX_ISSUES_PER_CHECK` issues
+    pub truncated: bool,
+}
+
+// =============================================================================
+// Dashboard Models
+// =============================================================================
+
+/// Most records listed in each dashboard section; counts cover all of them
+pub const DASHBOARD_LIST_LIMIT: usize = 10;
+
+/// How far back completed inspections stay in a supervisor's review queue
+pub const REVIEW_QUEUE_DAYS: i64 = 14;
+
+/// Period covered by supervisor team statistics
+pub const DASHBOARD_STATS_DAYS: i64 = 30;
+
+/// Weeks of critical findings shown in the dashboard trend
+pub const DASHBOARD_TREND_WEEKS: i64 = 12;
+
+/// Days ahead covered by the dashboard workload projection
+pub const DASHBOARD_WORKLOAD_DAYS: i64 = 30;
+
+/// Everything the dashboard shows, fetched in one call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dashboard {
+    pub summary: DashboardSummary,
+    pub kpis: DashboardKpis,
+}
+
+/// Dashboard payload tailored to the caller's role
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "view")]
+pub enum DashboardSummary {
+    Inspector(InspectorDashboard),
+    Supervisor(SupervisorDashboard),
+    Administrator(AdminDashboard),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InspectorDashboard {
+    pub pending_count: i64,
+    /// Next open inspections by scheduled date
+    pub pending: Vec<Inspection>,
+    pub overdue_count: i64,
+    pub overdue: Vec<Inspection>,
+    /// Open inspections scheduled within the next seven days
+    pub due_this_week: i64,
+    pub unread_mentions: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupervisorDashboard {
+    /// Completion over the last `DASHBOARD_STATS_DAYS` days for each team the supervisor belongs to
+    pub teams: Vec<TeamCompletionStats>,
+    pub review_queue_count: i64,
+    pub review_queue: Vec<ReviewQueueItem>,
+}
+
+/// A recently completed inspection with findings for a supervisor to review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewQueueItem {
+    pub inspection_id: i64,
+    pub asset_id: i64,
+    pub asset_number: String,
+    pub inspector_id: i64,
+    pub inspector_name: String,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub overall_condition: Option<Condition>,
+    pub finding_count: i64,
+    /// Findings of high or critical severity
+    pub serious_finding_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminDashboard {
+    pub health: SystemHealth,
+    pub storage: StorageUsage,
+    pub compliance: ComplianceKpis,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemHealth {
+    pub schema_version: i32,
+    pub active_users: i64,
+    pub open_inspections: i64,
+    pub overdue_inspections: i64,
+    pub recycle_bin_entries: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageUsage {
+    pub database_bytes: i64,
+    /// Space held by free pages, reclaimable with VACUUM
+    pub free_bytes: i64,
+    pub media_files: i64,
+    pub media_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceKpis {
+    pub total_assets: i64,
+    pub compliant_assets: i64,
+    pub compliance_percentage: f64,
+    pub overdue_inspections: i64,
+    pub critical_findings: i64,
+}
+
+/// Fleet-wide KPIs, each computed by a single aggregate query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardKpis {
+    pub generated_at: DateTime<Utc>,
+    pub open_inspections: i64,
+    pub overdue_inspections: i64,
+    pub compliance_by_location: Vec<LocationCompliance>,
+    /// Critical findings recorded each week, oldest first, over the last
+    /// `DASHBOARD_TREND_WEEKS` weeks
+    pub critical_findings_trend: Vec<FindingsTrendPoint>,
+    /// Open inspections scheduled each day over the next
+    /// `DASHBOARD_WORKLOAD_DAYS` days
+    pub upcoming_workload: Vec<WorkloadDay>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationCompliance {
+    pub location_id: i64,
+    pub location_name: String,
+    pub total_assets: i64,
+    /// Assets with at least one completed inspection
+    pub inspected_assets: i64,
+    pub compliant_assets: i64,
+    /// Share of inspected assets that are compliant
+    pub compliance_percentage: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindingsTrendPoint {
+    /// Monday the week starts on
+    pub week_start: NaiveDate,
+    pub critical_findings: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadDay {
+    pub date: NaiveDate,
+    pub scheduled_inspections: i64,
+}
+
+// =============================================================================
+// Workload Forecast Models
+// =============================================================================
+
+/// Kind of work counted in a workload forecast
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WorkloadKind {
+    Inspection,
+    Maintenance,
+}
+
+/// Work expected in one month at one location, of one kind and type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadEntry {
+    /// Calendar month as `YYYY-MM`
+    pub month: String,
+    pub location_id: i64,
+    pub location_name: String,
+    pub kind: WorkloadKind,
+    /// Inspection type or maintenance type
+    pub work_type: String,
+    /// Work already on the schedule
+    pub scheduled_count: u32,
+    /// Work projected from inspection frequencies but not yet scheduled
+    pub projected_count: u32,
+    pub estimated_hours: f64,
+}
+
+/// Totals for one month of a workload forecast
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonthlyWorkload {
+    pub month: String,
+    pub count: u32,
+    pub estimated_hours: f64,
+}
+
+/// Inspection and maintenance workload expected over the coming months.
+/// Work already overdue is counted in the first month.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadForecast {
+    pub generated_at: DateTime<Utc>,
+    pub months: Vec<MonthlyWorkload>,
+    pub entries: Vec<WorkloadEntry>,
+}
+
+/// How far back the changes summary looks for a user without an earlier
+/// session
+pub const SINCE_LAST_LOGIN_DEFAULT_DAYS: i64 = 7;
+
+/// What changed for the caller since their previous session, to start a
+/// shift with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SinceLastLogin {
+    /// Last activity of the previous session, or the default look-back for
+    /// a user signing in for the first time
+    pub since: DateTime<Utc>,
+    pub previous_session: bool,
+    /// Open inspections newly assigned to the caller
+    pub new_assignments: Vec<Inspection>,
+    /// Findings recorded on assets the caller watches
+    pub watched_findings: Vec<WatchedFinding>,
+    /// Work waiting for the caller's sign-off, old and new
+    pub pending_approval_count: i64,
+    pub pending_approvals: Vec<PendingApproval>,
+    /// Caller's open inspections moved by someone else
+    pub schedule_changes: Vec<ScheduleChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchedFinding {
+    pub item_id: i64,
+    pub inspection_id: i64,
+    pub asset_id: i64,
+    pub asset_number: String,
+    pub item_name: String,
+    pub finding: Option<String>,
+    pub severity: Option<Severity>,
+    pub is_compliant: Option<bool>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// A corrective action submitted for verification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingApproval {
+    pub corrective_action_id: i64,
+    pub asset_id: i64,
+    pub asset_number: String,
+    pub description: String,
+    pub submitted_by: Option<i64>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    /// Submitted since the caller's previous session
+    pub is_new: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleChange {
+    pub inspection_id: i64,
+    pub asset_id: i64,
+    pub asset_number: String,
+    pub previous_date: Option<DateTime<Utc>>,
+    pub scheduled_date: Option<DateTime<Utc>>,
+    pub changed_by: Option<i64>,
+    pub changed_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// Fleet Benchmark Models
+// =============================================================================
+
+/// Findings rate of the assets sharing a manufacturer and model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelFindingsRate {
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    pub asset_count: i64,
+    /// Completed inspections of those assets
+    pub inspection_count: i64,
+    pub finding_count: i64,
+    pub findings_per_inspection: f64,
+}
+
+/// Average item compliance of the completed inspections at a location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationComplianceAverage {
+    pub location_id: i64,
+    pub location_name: String,
+    pub inspection_count: i64,
+    /// Mean of each inspection's share of compliant items
+    pub average_compliance_percentage: f64,
+}
+
+/// Conditions recorded on inspections of assets of a given age
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgeConditionPoint {
+    /// Whole years since installation (or manufacture) at inspection time
+    pub age_years: i64,
+    pub inspection_count: i64,
+    /// Mean of `Condition::score`, from 1 (Critical) to 5 (Excellent)
+    pub average_condition_score: f64,
+    /// Inspections rated Poor or Critical
+    pub poor_or_critical_count: i64,
+}
+
+/// Assets sharing a CMAA service class and FEM group, with the findings
+/// raised on them; both are `None` for assets not yet classified
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassificationBreakdown {
+    pub service_class: Option<CmaaServiceClass>,
+    pub fem_group: Option<FemGroup>,
+    pub duty: Option<DutyLevel>,
+    pub asset_count: i64,
+    /// Completed inspections of those assets
+    pub inspection_count: i64,
+    pub finding_count: i64,
+    /// Zero when none of the assets have been inspected
+    pub findings_per_inspection: f64,
+}
+
+/// Cross-fleet comparisons for the executive dashboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FleetBenchmarks {
+    pub generated_at: DateTime<Utc>,
+    /// Highest findings rate first
+    pub findings_by_model: Vec<ModelFindingsRate>,
+    /// Lowest compliance first
+    pub compliance_by_location: Vec<LocationComplianceAverage>,
+    /// Youngest assets first
+    pub age_condition_curve: Vec<AgeConditionPoint>,
+    /// Heaviest duty first, unclassified assets last
+    #[serde(default)]
+    pub by_classification: Vec<ClassificationBreakdown>,
+}
+
+// =============================================================================
+// Pre-start Check Models
+// =============================================================================
+
+/// Days looked back when counting missed pre-start checks
+pub const PRESTART_LOOKBACK_DAYS: i64 = 7;
+
+/// Items offered for a pre-start check when the operator supplies none
+pub const DEFAULT_PRESTART_ITEMS: [&str; 8] = [
+    "Hoist brake",
+    "Upper limit switch",
+    "Wire rope",
+    "Hook and safety latch",
+    "Pendant or radio controls",
+    "Emergency stop",
+    "Warning horn and lights",
+    "Travel path clear",
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Shift {
+    Day,
+    Afternoon,
+    Night,
+}
+
+impl std::fmt::Display for Shift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Shift::Day => write!(f, "Day"),
+            Shift::Afternoon => write!(f, "Afternoon"),
+            Shift::Night => write!(f, "Night"),
+        }
+    }
+}
+
+impl std::str::FromStr for Shift {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Day" => Ok(Shift::Day),
+            "Afternoon" => Ok(Shift::Afternoon),
+            "Night" => Ok(Shift::Night),
+            _ => Err(AppError::validation("shift", format!("Invalid shift: {}", s))),
+        }
+    }
+}
+
+/// One pass/fail line of a pre-start check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrestartCheckItem {
+    pub item: String,
+    pub passed: bool,
+    pub note: Option<String>,
+}
+
+/// A quick pre-shift check of an asset by its operator, separate from
+/// formal inspections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrestartCheck {
+    pub id: Option<i64>,
+    pub asset_id: i64,
+    pub operator_id: i64,
+    pub shift: Shift,
+    pub check_date: NaiveDate,
+    pub items: Vec<PrestartCheckItem>,
+    /// Whether every item passed
+    pub passed: bool,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A day on which an asset requiring pre-start checks had none
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissedPrestartCheck {
+    pub asset_id: i64,
+    pub asset_number: String,
+    pub asset_name: String,
+    pub date: NaiveDate,
+}
+
+// =============================================================================
+// Entity History Models
+// =============================================================================
+
+/// History entries returned when no limit is requested
+pub const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// Largest accepted history limit
+pub const MAX_HISTORY_LIMIT: usize = 500;
+
+/// Columns left out of history snapshots
+pub const HISTORY_REDACTED_COLUMNS: [&str; 1] = ["password_hash"];
+
+/// Columns that change on every write and are not reported as changes
+pub const HISTORY_IGNORED_COLUMNS: [&str; 1] = ["updated_at"];
+
+/// Record type whose changes are kept in the entity history
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum HistoryEntityType {
+    Asset,
+    Inspection,
+    User,
+}
+
+impl HistoryEntityType {
+    /// Table holding the records
+    pub fn table(&self) -> &'static str {
+        match self {
+            HistoryEntityType::Asset => "assets",
+            HistoryEntityType::Inspection => "inspections",
+            HistoryEntityType::User => "users",
+        }
+    }
+
+    /// Permission resource guarding the records
+    pub fn resource(&self) -> &'static str {
+        match self {
+            HistoryEntityType::Asset => "asset",
+            HistoryEntityType::Inspection => "inspection",
+            HistoryEntityType::User => "user",
+        }
+    }
+}
+
+impl std::fmt::Display for HistoryEntityType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryEntityType::Asset => write!(f, "Asset"),
+            HistoryEntityType::Inspection => write!(f, "Inspection"),
+            HistoryEntityType::User => write!(f, "User"),
+        }
+    }
+}
+
+impl std::str::FromStr for HistoryEntityType {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Asset" => Ok(HistoryEntityType::Asset),
+            "Inspection" => Ok(HistoryEntityType::Inspection),
+            "User" => Ok(HistoryEntityType::User),
+            _ => Err(AppError::validation("entity_type", format!("Invalid history entity type: {}", s))),
+        }
+    }
+}
+
+/// One column that differs between two snapshots of a record
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: JsonValue,
+    pub after: JsonValue,
+}
+
+impl FieldChange {
+    /// Columns that differ between two snapshots, by field name, skipping
+    /// `HISTORY_IGNORED_COLUMNS`
+    pub fn diff(
+        before: &serde_json::Map<String, JsonValue>,
+        after: &serde_json::Map<String, JsonValue>,
+    ) -> Vec<FieldChange> {
+        after
+            .iter()
+            .filter(|(field, _)| !HISTORY_IGNORED_COLUMNS.contains(&field.as_str()))
+            .filter_map(|(field, value)| {
+                let old = before.get(field).cloned().unwrap_or(JsonValue::Null);
+                (old != *value).then(|| FieldChange {
+                    field: field.clone(),
+                    before: old,
+                    after: value.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// A recorded change to a record: who made it, when, and the record before
+/// and after
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityHistoryEntry {
+    pub id: i64,
+    pub entity_type: HistoryEntityType,
+    pub entity_id: i64,
+    /// Operation that made the change, e.g. `update` or `transfer`
+    pub action: String,
+    pub changed_by: Option<i64>,
+    pub changed_by_name: Option<String>,
+    pub changed_at: DateTime<Utc>,
+    pub changes: Vec<FieldChange>,
+    pub before: JsonValue,
+    pub after: JsonValue,
+}
+
+// =============================================================================
+// Asset Registration Models
+// =============================================================================
+
+/// Days ahead a renewal counts as expiring when no window is given
+pub const DEFAULT_RENEWAL_WARNING_DAYS: i64 = 30;
+
+/// Kind of document kept against an asset that has to be renewed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AssetRecordKind {
+    /// Insurance policy covering the asset
+    Insurance,
+    /// Statutory registration with a regulator or authority
+    Registration,
+}
+
+impl std::fmt::Display for AssetRecordKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetRecordKind::Insurance => write!(f, "Insurance"),
+            AssetRecordKind::Registration => write!(f, "Registration"),
+        }
+    }
+}
+
+impl std::str::FromStr for AssetRecordKind {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Insurance" => Ok(AssetRecordKind::Insurance),
+            "Registration" => Ok(AssetRecordKind::Registration),
+            _ => Err(AppError::validation("kind", format!("Invalid asset record kind: {}", s))),
+        }
+    }
+}
+
+/// An insurance policy or statutory registration held for an asset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetRecord {
+    pub id: Option<i64>,
+    pub asset_id: i64,
+    pub kind: AssetRecordKind,
+    /// Policy or registration number
+    pub reference_number: String,
+    /// Insurer or registering authority
+    pub issuer: Option<String>,
+    pub issued_date: Option<NaiveDate>,
+    pub renewal_date: NaiveDate,
+    pub notes: Option<String>,
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Fields supplied when adding or replacing an asset record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetRecordInput {
+    pub kind: AssetRecordKind,
+    pub reference_number: String,
+    pub issuer: Option<String>,
+    pub issued_date: Option<NaiveDate>,
+    pub renewal_date: NaiveDate,
+    pub notes: Option<String>,
+}
+
+impl Validate for AssetRecordInput {
+    fn validate(&self) -> AppResult<()> {
+        if self.reference_number.trim().is_empty() {
+            return Err(AppError::validation("reference_number", "Reference number cannot be empty"));
+        }
+        if self.issued_date.is_some_and(|issued| issued > self.renewal_date) {
+            return Err(AppError::validation("renewal_date", "Renewal date cannot be before the issue date"));
+        }
+        Ok(())
+    }
+}
+
+/// An asset record due for renewal, with the asset it belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpiringAssetRecord {
+    pub record: AssetRecord,
+    pub asset_number: String,
+    pub asset_name: String,
+    pub location_id: i64,
+    /// Negative once the renewal date has passed
+    pub days_remaining: i64,
+}
+
+// =============================================================================
+// Vendor Document Models
+// =============================================================================
+
+/// A document received by email, filed against an asset or waiting in the
+/// unmatched queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VendorDocument {
+    pub id: i64,
+    /// `None` while the document waits to be filed
+    pub asset_id: Option<i64>,
+    pub sender: String,
+    pub subject: String,
+    pub received_at: DateTime<Utc>,
+    pub file_name: String,
+    /// Relative to the media storage path
+    pub file_path: String,
+    pub mime_type: String,
+    pub file_size: i64,
+    /// Subject code the asset was matched on, if filed automatically
+    pub matched_reference: Option<String>,
+    /// Who filed the document by hand
+    pub filed_by: Option<i64>,
+    pub filed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An attachment of an incoming email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboundAttachment {
+    pub file_name: String,
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+/// An email handed over by the mail bridge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboundEmail {
+    pub sender: String,
+    pub subject: String,
+    /// When the message was received; now when unset
+    pub received_at: Option<DateTime<Utc>>,
+    pub attachments: Vec<InboundAttachment>,
+}
+
+impl Validate for InboundEmail {
+    fn validate(&self) -> AppResult<()> {
+        if self.sender.trim().is_empty() {
+            return Err(AppError::validation("sender", "Sender cannot be empty"));
+        }
+        for (index, attachment) in self.attachments.iter().enumerate() {
+            if attachment.file_name.trim().is_empty() {
+                return Err(AppError::validation(
+                    format!("attachments[{}].file_name", index),
+                    "File name cannot be empty",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What became of an incoming email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailIngestResult {
+    /// Asset the documents were filed against; `None` when they were queued
+    pub asset_id: Option<i64>,
+    pub matched_reference: Option<String>,
+    pub documents: Vec<VendorDocument>,
+    /// Attachments skipped for their type or size
+    pub skipped: Vec<String>,
+}
+
+// =============================================================================
+// Operator Authorization Models
+// =============================================================================
+
+/// Days before expiry an authorization is flagged in the matrix
+pub const AUTHORIZATION_WARNING_DAYS: i64 = 30;
+
+/// Permission for an operator to run assets of one type, up to a capacity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatorAuthorization {
+    pub id: i64,
+    pub user_id: i64,
+    pub operator_name: String,
+    /// Matched against `Asset::asset_type`, ignoring case
+    pub asset_type: String,
+    /// Largest rated capacity covered; `None` covers any capacity
+    pub max_capacity: Option<Capacity>,
+    /// Last day the authorization is valid; `None` never expires
+    pub expires_at: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub granted_by: Option<i64>,
+    pub granted_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Fields supplied when granting an authorization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatorAuthorizationInput {
+    pub user_id: i64,
+    pub asset_type: String,
+    pub max_capacity: Option<Capacity>,
+    pub expires_at: Option<NaiveDate>,
+    pub notes: Option<String>,
+}
+
+impl Validate for OperatorAuthorizationInput {
+    fn validate(&self) -> AppResult<()> {
+        if self.asset_type.trim().is_empty() {
+            return Err(AppError::validation("asset_type", "Asset type cannot be empty"));
+        }
+        if let Some(capacity) = &self.max_capacity {
+            capacity.validate()?;
+        }
+        if self.expires_at.is_some_and(|expires| expires < Utc::now().date_naive()) {
+            return Err(AppError::validation("expires_at", "Authorization cannot expire in the past"));
+        }
+        Ok(())
+    }
+}
+
+/// Whether an operator may be recorded running an asset, and why not
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorizationCheck {
+    pub user_id: i64,
+    pub asset_id: i64,
+    pub authorized: bool,
+    /// Authorization that covers the asset
+    pub authorization_id: Option<i64>,
+    pub expires_at: Option<NaiveDate>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AuthorizationStatus {
+    Current,
+    /// Expires within `AUTHORIZATION_WARNING_DAYS`
+    ExpiringSoon,
+    Expired,
+}
+
+/// An operator's standing for one asset type in the authorization matrix
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorizationMatrixCell {
+    pub authorization_id: i64,
+    pub status: AuthorizationStatus,
+    pub max_capacity: Option<Capacity>,
+    pub expires_at: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorizationMatrixRow {
+    pub user_id: i64,
+    pub operator_name: String,
+    /// Keyed by asset type; types the operator holds no authorization for are absent
+    pub authorizations: std::collections::BTreeMap<String, AuthorizationMatrixCell>,
+}
+
+/// Operators against asset types, for auditors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorizationMatrix {
+    pub generated_at: DateTime<Utc>,
+    /// Asset types in use or with an authorization, sorted
+    pub asset_types: Vec<String>,
+    pub rows: Vec<AuthorizationMatrixRow>,
+}
+
+// =============================================================================
+// Training Models
+// =============================================================================
+
+/// Inspection types that may only be assigned to an inspector holding current
+/// training for them
+pub const REGULATED_INSPECTION_TYPES: [InspectionType; 2] = [InspectionType::Initial, InspectionType::Periodic];
+
+/// A course completed by a user, and the inspection types it qualifies them for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainingRecord {
+    pub id: i64,
+    pub user_id: i64,
+    pub course: String,
+    pub provider: Option<String>,
+    pub issued_date: NaiveDate,
+    /// Last day the competence is valid; `None` never expires
+    pub expiry_date: Option<NaiveDate>,
+    /// Scanned certificate in the media library
+    pub certificate_media_id: Option<i64>,
+    pub qualifies_for: Vec<InspectionType>,
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TrainingRecord {
+    /// Whether the record is in force on `date` for `inspection_type`
+    pub fn qualifies(&self, inspection_type: &InspectionType, date: NaiveDate) -> bool {
+        self.qualifies_for.contains(inspection_type)
+            && self.issued_date <= date
+            && self.expiry_date.is_none_or(|expiry| expiry >= date)
+    }
+}
+
+/// Fields supplied when adding or replacing a training record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainingRecordInput {
+    pub user_id: i64,
+    pub course: String,
+    pub provider: Option<String>,
+    pub issued_date: NaiveDate,
+    pub expiry_date: Option<NaiveDate>,
+    pub certificate_media_id: Option<i64>,
+    #[serde(default)]
+    pub qualifies_for: Vec<InspectionType>,
+}
+
+impl Validate for TrainingRecordInput {
+    fn validate(&self) -> AppResult<()> {
+        if self.course.trim().is_empty() {
+            return Err(AppError::validation("course", "Course cannot be empty"));
+        }
+        if self.issued_date > Utc::now().date_naive() {
+            return Err(AppError::validation("issued_date", "Issue date cannot be in the future"));
+        }
+        if self.expiry_date.is_some_and(|expiry| expiry < self.issued_date) {
+            return Err(AppError::validation("expiry_date", "Expiry date cannot be before the issue date"));
+        }
+        Ok(())
+    }
+}
+
+/// A training record lapsed or about to, with the user it belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpiringTraining {
+    pub record: TrainingRecord,
+    pub user_name: String,
+    /// Negative once the record has expired
+    pub days_remaining: i64,
+}
+
+// =============================================================================
+// Work Order Models
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WorkOrderPriority {
+    Low,
+    Normal,
+    High,
+    Urgent,
+}
+
+impl WorkOrderPriority {
+    /// Default priority for a work order raised from a finding of `severity`
+    pub fn for_severity(severity: Option<&Severity>) -> Self {
+        match severity {
+            Some(Severity::Critical) => WorkOrderPriority::Urgent,
+            Some(Severity::High) => WorkOrderPriority::High,
+            Some(Severity::Low) => WorkOrderPriority::Low,
+            Some(Severity::Medium) | None => WorkOrderPriority::Normal,
+        }
+    }
+}
+
+impl std::fmt::Display for WorkOrderPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkOrderPriority::Low => write!(f, "Low"),
+            WorkOrderPriority::Normal => write!(f, "Normal"),
+            WorkOrderPriority::High => write!(f, "High"),
+            WorkOrderPriority::Urgent => write!(f, "Urgent"),
+        }
+    }
+}
+
+impl std::str::FromStr for WorkOrderPriority {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Low" => Ok(WorkOrderPriority::Low),
+            "Normal" => Ok(WorkOrderPriority::Normal),
+            "High" => Ok(WorkOrderPriority::High),
+            "Urgent" => Ok(WorkOrderPriority::Urgent),
+            _ => Err(AppError::validation("priority", format!("Invalid work order priority: {}", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum WorkOrderStatus {
+    Open,
+    InProgress,
+    Completed,
+    Verified,
+    Cancelled,
+}
+
+impl WorkOrderStatus {
+    /// Whether a work order may move from this status to `next`. Work runs
+    /// Open → In Progress → Completed → Verified; a completed order that
+    /// fails verification goes back to In Progress, and only unfinished work
+    /// can be cancelled.
+    pub fn can_transition_to(&self, next: WorkOrderStatus) -> bool {
+        use WorkOrderStatus::*;
+        matches!(
+            (self, next),
+            (Open, InProgress)
+                | (InProgress, Completed)
+                | (Completed, Verified)
+                | (Completed, InProgress)
+                | (Open, Cancelled)
+                | (InProgress, Cancelled)
+        )
+    }
+
+    /// Whether the work order still needs doing
+    pub fn is_open(&self) -> bool {
+        matches!(self, WorkOrderStatus::Open | WorkOrderStatus::InProgress)
+    }
+}
+
+impl std::fmt::Display for WorkOrderStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkOrderStatus::Open => write!(f, "Open"),
+            WorkOrderStatus::InProgress => write!(f, "In Progress"),
+            WorkOrderStatus::Completed => write!(f, "Completed"),
+            WorkOrderStatus::Verified => write!(f, "Verified"),
+            WorkOrderStatus::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+impl std::str::FromStr for WorkOrderStatus {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Open" => Ok(WorkOrderStatus::Open),
+            "In Progress" => Ok(WorkOrderStatus::InProgress),
+            "Completed" => Ok(WorkOrderStatus::Completed),
+            "Verified" => Ok(WorkOrderStatus::Verified),
+            "Cancelled" => Ok(WorkOrderStatus::Cancelled),
+            _ => Err(AppError::validation("status", format!("Invalid work order status: {}", s))),
+        }
+    }
+}
+
+/// A part needed to carry out a work order
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkOrderPart {
+    pub part_number: String,
+    pub description: Option<String>,
+    pub quantity: u32,
+}
+
+/// Repair work raised from a non-compliant inspection item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkOrder {
+    pub id: i64,
+    pub inspection_item_id: i64,
+    pub inspection_id: i64,
+    pub asset_id: i64,
+    pub component_id: Option<i64>,
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: WorkOrderPriority,
+    pub status: WorkOrderStatus,
+    pub assignee_id: Option<i64>,
+    pub due_date: Option<NaiveDate>,
+    pub parts: Vec<WorkOrderPart>,
+    pub completion_notes: Option<String>,
+    pub created_by: Option<i64>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub verified_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Fields supplied when raising a work order from an inspection item. The
+/// title defaults to the item name and the priority to one matching the
+/// finding's severity.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkOrderInput {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub priority: Option<WorkOrderPriority>,
+    pub assignee_id: Option<i64>,
+    pub due_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub parts: Vec<WorkOrderPart>,
+}
+
+fn validate_work_order_parts(parts: &[WorkOrderPart]) -> AppResult<()> {
+    for part in parts {
+        if part.part_number.trim().is_empty() {
+            return Err(AppError::validation("parts", "Part number cannot be empty"));
+        }
+        if part.quantity == 0 {
+            return Err(AppError::validation("parts", format!("Quantity of part {} must be at least 1", part.part_number)));
+        }
+    }
+    Ok(())
+}
+
+impl Validate for WorkOrderInput {
+    fn validate(&self) -> AppResult<()> {
+        if self.title.as_deref().is_some_and(|title| title.trim().is_empty()) {
+            return Err(AppError::validation("title", "Title cannot be empty"));
+        }
+        validate_work_order_parts(&self.parts)
+    }
+}
+
+/// Changes to an unfinished work order; `None` leaves a field as is
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkOrderUpdateData {
+    pub title: Option<String>,
+    pub description: Option<Option<String>>,
+    pub priority: Option<WorkOrderPriority>,
+    pub assignee_id: Option<Option<i64>>,
+    pub due_date: Option<Option<NaiveDate>>,
+    pub parts: Option<Vec<WorkOrderPart>>,
+}
+
+impl Validate for WorkOrderUpdateData {
+    fn validate(&self) -> AppResult<()> {
+        if self.title.as_deref().is_some_and(|title| title.trim().is_empty()) {
+            return Err(AppError::validation("title", "Title cannot be empty"));
+        }
+        validate_work_order_parts(self.parts.as_deref().unwrap_or_default())
+    }
+}
+
+/// Filters for listing work orders; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkOrderFilter {
+    pub status: Option<WorkOrderStatus>,
+    pub asset_id: Option<i64>,
+    pub assignee_id: Option<i64>,
+    pub inspection_id: Option<i64>,
+    /// Only orders past their due date that are still open
+    #[serde(default)]
+    pub overdue_only: bool,
+}
+
+// =============================================================================
+// Notification Models
+// =============================================================================
+
+/// Look-ahead for inspection due notifications
+pub const NOTIFICATION_DUE_DAYS: i64 = 3;
+
+/// Event a user is notified of
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum NotificationKind {
+    /// An inspection assigned to the user is coming due
+    InspectionDue,
+    /// A critical finding was recorded
+    CriticalFinding,
+    /// An inspection, work order or corrective action was assigned to the user
+    Assignment,
+}
+
+impl NotificationKind {
+    pub const ALL: [NotificationKind; 3] = [
+        NotificationKind::InspectionDue,
+        NotificationKind::CriticalFinding,
+        NotificationKind::Assignment,
+    ];
+}
+
+impl std::fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotificationKind::InspectionDue => write!(f, "InspectionDue"),
+            NotificationKind::CriticalFinding => write!(f, "CriticalFinding"),
+            NotificationKind::Assignment => write!(f, "Assignment"),
+        }
+    }
+}
+
+impl std::str::FromStr for NotificationKind {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "InspectionDue" => Ok(NotificationKind::InspectionDue),
+            "CriticalFinding" => Ok(NotificationKind::CriticalFinding),
+            "Assignment" => Ok(NotificationKind::Assignment),
+            _ => Err(AppError::validation("kind", format!("Invalid notification kind: {}", s))),
+        }
+    }
+}
+
+/// Way a notification reaches a user
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum NotificationChannelKind {
+    /// The user's notification inbox
+    InApp,
+    Email,
+    /// Pushed to the running desktop app as a Tauri event
+    Event,
+}
+
+impl NotificationChannelKind {
+    pub const ALL: [NotificationChannelKind; 3] = [
+        NotificationChannelKind::InApp,
+        NotificationChannelKind::Email,
+        NotificationChannelKind::Event,
+    ];
+}
+
+impl std::fmt::Display for NotificationChannelKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotificationChannelKind::InApp => write!(f, "InApp"),
+            NotificationChannelKind::Email => write!(f, "Email"),
+            NotificationChannelKind::Event => write!(f, "Event"),
+        }
+    }
+}
+
+impl std::str::FromStr for NotificationChannelKind {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "InApp" => Ok(NotificationChannelKind::InApp),
+            "Email" => Ok(NotificationChannelKind::Email),
+            "Event" => Ok(NotificationChannelKind::Event),
+            _ => Err(AppError::validation("channel", format!("Invalid notification channel: {}", s))),
+        }
+    }
+}
+
+/// A notification recorded for a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: i64,
+    pub user_id: i64,
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+    /// Record the notification is about, such as `inspection` or `work_order`
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+/// Whether a user receives one kind of notification on one channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationPreference {
+    pub kind: NotificationKind,
+    pub channel: NotificationChannelKind,
+    pub enabled: bool,
+}
+
+/// How notification emails reach a user
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum NotificationDelivery {
+    /// One email per notification
+    #[default]
+    Immediate,
+    /// One summary email a day at the user's chosen local time
+    DailyDigest,
+}
+
+impl std::fmt::Display for NotificationDelivery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotificationDelivery::Immediate => write!(f, "Immediate"),
+            NotificationDelivery::DailyDigest => write!(f, "DailyDigest"),
+        }
+    }
+}
+
+impl std::str::FromStr for NotificationDelivery {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Immediate" => Ok(NotificationDelivery::Immediate),
+            "DailyDigest" => Ok(NotificationDelivery::DailyDigest),
+            _ => Err(AppError::validation("delivery", format!("Invalid notification delivery: {}", s))),
+        }
+    }
+}
+
+/// Local time digests are sent at unless the user picks another
+pub const DEFAULT_DIGEST_TIME: &str = "07:00";
+
+/// A user's choice between immediate notification emails and a daily digest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationDigestSettings {
+    pub delivery: NotificationDelivery,
+    /// Local time of day the digest is sent
+    pub send_at: NaiveTime,
+    /// IANA time zone `send_at` is in
+    pub time_zone: String,
+    /// When the last digest was sent; the next one covers what happened since
+    pub last_sent_at: Option<DateTime<Utc>>,
+}
+
+impl NotificationDigestSettings {
+    /// Settings of a user who has never chosen
+    pub fn defaults() -> Self {
+        Self {
+            delivery: NotificationDelivery::Immediate,
+            send_at: NaiveTime::parse_from_str(DEFAULT_DIGEST_TIME, "%H:%M").unwrap_or_default(),
+            time_zone: crate::scheduling::DEFAULT_TIME_ZONE.to_string(),
+            last_sent_at: None,
+        }
+    }
+}
+
+/// Replacement digest settings for the signed-in user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationDigestInput {
+    pub delivery: NotificationDelivery,
+    /// Defaults to [`DEFAULT_DIGEST_TIME`]
+    pub send_at: Option<NaiveTime>,
+    /// Defaults to UTC
+    pub time_zone: Option<String>,
+}
+
+impl Validate for NotificationDigestInput {
+    fn validate(&self) -> AppResult<()> {
+        if let Some(time_zone) = &self.time_zone {
+            crate::scheduling::parse_time_zone(time_zone)?;
+        }
+        Ok(())
+    }
+}
+
+/// One record in a digest. Several notifications about the same record are
+/// folded into one entry showing the latest.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DigestEntry {
+    pub summary: String,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i64>,
+    /// Notifications folded into this entry; 1 for overdue items
+    pub occurrences: usize,
+}
+
+/// Entries of one kind in a digest, such as overdue items or new findings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DigestSection {
+    pub title: String,
+    pub entries: Vec<DigestEntry>,
+}
+
+/// Summary of a user's overdue work and the notifications since their last
+/// digest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationDigest {
+    pub user_id: i64,
+    pub period_start: Option<DateTime<Utc>>,
+    pub generated_at: DateTime<Utc>,
+    /// Only sections with entries, in a fixed order
+    pub sections: Vec<DigestSection>,
+}
+
+impl NotificationDigest {
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+}
+
+// =============================================================================
+// Risk Matrix Models
+// =============================================================================
+
+/// Consequence of an asset failing, set per asset; findings on more critical
+/// assets carry more risk
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum AssetCriticality {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Critical,
+}
+
+impl AssetCriticality {
+    pub const ALL: [AssetCriticality; 4] = [
+        AssetCriticality::Low,
+        AssetCriticality::Medium,
+        AssetCriticality::High,
+        AssetCriticality::Critical,
+    ];
+}
+
+impl std::fmt::Display for AssetCriticality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetCriticality::Low => write!(f, "Low"),
+            AssetCriticality::Medium => write!(f, "Medium"),
+            AssetCriticality::High => write!(f, "High"),
+            AssetCriticality::Critical => write!(f, "Critical"),
+        }
+    }
+}
+
+impl std::str::FromStr for AssetCriticality {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Low" => Ok(AssetCriticality::Low),
+            "Medium" => Ok(AssetCriticality::Medium),
+            "High" => Ok(AssetCriticality::High),
+            "Critical" => Ok(AssetCriticality::Critical),
+            _ => Err(AppError::validation("criticality", format!("Invalid asset criticality: {}", s))),
+        }
+    }
+}
+
+/// Risk of a finding, from its severity and the criticality of the asset
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RiskRating {
+    Low,
+    Medium,
+    High,
+    Extreme,
+}
+
+impl std::fmt::Display for RiskRating {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RiskRating::Low => write!(f, "Low"),
+            RiskRating::Medium => write!(f, "Medium"),
+            RiskRating::High => write!(f, "High"),
+            RiskRating::Extreme => write!(f, "Extreme"),
+        }
+    }
+}
+
+impl std::str::FromStr for RiskRating {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Low" => Ok(RiskRating::Low),
+            "Medium" => Ok(RiskRating::Medium),
+            "High" => Ok(RiskRating::High),
+            "Extreme" => Ok(RiskRating::Extreme),
+            _ => Err(AppError::validation("rating", format!("Invalid risk rating: {}", s))),
+        }
+    }
+}
+
+/// Rating and response time for one severity and asset criticality
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskMatrixCell {
+    pub severity: Severity,
+    pub criticality: AssetCriticality,
+    pub rating: RiskRating,
+    /// Hours from the finding being recorded to the required response
+    pub response_hours: u32,
+}
+
+/// The installation's risk matrix: one cell for every severity and asset
+/// criticality
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskMatrix {
+    pub cells: Vec<RiskMatrixCell>,
+    pub updated_by: Option<i64>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl RiskMatrix {
+    pub fn cell(&self, severity: &Severity, criticality: AssetCriticality) -> Option<&RiskMatrixCell> {
+        self.cells.iter().find(|cell| &cell.severity == severity && cell.criticality == criticality)
+    }
+}
+
+impl Validate for RiskMatrix {
+    fn validate(&self) -> AppResult<()> {
+        let severities = [Severity::Low, Severity::Medium, Severity::High, Severity::Critical];
+        for severity in &severities {
+            for criticality in AssetCriticality::ALL {
+                let count = self.cells.iter()
+                    .filter(|cell| &cell.severity == severity && cell.criticality == criticality)
+                    .count();
+                if count != 1 {
+                    return Err(AppError::validation(
+                        "cells",
+                        format!("Expected one cell for {} severity on {} criticality assets, found {}",
+                                severity, criticality, count),
+                    ));
+                }
+            }
+        }
+        if self.cells.iter().any(|cell| cell.response_hours == 0) {
+            return Err(AppError::validation("response_hours", "Response time must be at least one hour"));
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Finding SLA Models
+// =============================================================================
+
+/// Stage of handling a critical finding that has a target time
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum SlaStage {
+    Acknowledge,
+    Resolve,
+}
+
+impl std::fmt::Display for SlaStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SlaStage::Acknowledge => write!(f, "Acknowledge"),
+            SlaStage::Resolve => write!(f, "Resolve"),
+        }
+    }
+}
+
+impl std::str::FromStr for SlaStage {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Acknowledge" => Ok(SlaStage::Acknowledge),
+            "Resolve" => Ok(SlaStage::Resolve),
+            _ => Err(AppError::validation("stage", format!("Invalid SLA stage: {}", s))),
+        }
+    }
+}
+
+/// Hours allowed for a stage, counted from the finding being recorded.
+/// Changes apply to findings recorded afterwards.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlaTarget {
+    pub stage: SlaStage,
+    pub target_hours: u32,
+}
+
+impl Validate for SlaTarget {
+    fn validate(&self) -> AppResult<()> {
+        if self.target_hours == 0 {
+            return Err(AppError::validation("target_hours", "SLA target must be at least one hour"));
+        }
+        Ok(())
+    }
+}
+
+/// SLA clock of a critical finding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindingSla {
+    pub item_id: i64,
+    pub inspection_id: i64,
+    pub asset_id: i64,
+    pub asset_number: String,
+    pub location_id: i64,
+    pub item_name: String,
+    pub opened_at: DateTime<Utc>,
+    pub acknowledge_due_at: DateTime<Utc>,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    pub acknowledged_by: Option<i64>,
+    pub resolve_due_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolved_by: Option<i64>,
+    pub resolution_notes: Option<String>,
+}
+
+impl FindingSla {
+    /// Whether a stage was, or is still being, completed after its due time
+    pub fn is_breached(&self, stage: SlaStage, now: DateTime<Utc>) -> bool {
+        let (due, done) = match stage {
+            SlaStage::Acknowledge => (self.acknowledge_due_at, self.acknowledged_at),
+            SlaStage::Resolve => (self.resolve_due_at, self.resolved_at),
+        };
+        done.unwrap_or(now) > due
+    }
+}
+
+/// SLA performance of the critical findings recorded at one location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationSlaPerformance {
+    pub location_id: i64,
+    pub location_name: String,
+    pub findings: i64,
+    pub acknowledged: i64,
+    pub acknowledged_within_target: i64,
+    pub average_hours_to_acknowledge: Option<f64>,
+    pub resolved: i64,
+    pub resolved_within_target: i64,
+    pub average_hours_to_resolve: Option<f64>,
+    /// Findings past a due time with that stage still outstanding
+    pub open_breaches: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlaPerformanceReport {
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub generated_at: DateTime<Utc>,
+    pub targets: Vec<SlaTarget>,
+    pub locations: Vec<LocationSlaPerformance>,
+}
+
+// =============================================================================
+// Defect Models
+// =============================================================================
+
+/// Where a defect is in its life. A defect is opened by a non-compliant
+/// inspection item and stays open across inspections until its repair is
+/// verified.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DefectStatus {
+    Open,
+    /// Repair put off to a later date with a recorded reason
+    Deferred,
+    Repaired,
+    Verified,
+}
+
+impl DefectStatus {
+    /// Whether a defect may move from this status to `next`. A repair that
+    /// fails verification reopens the defect; verified defects are closed.
+    pub fn can_transition_to(&self, next: DefectStatus) -> bool {
+        use DefectStatus::*;
+        matches!(
+            (self, next),
+            (Open, Deferred)
+                | (Deferred, Open)
+                | (Open, Repaired)
+                | (Deferred, Repaired)
+                | (Repaired, Verified)
+                | (Repaired, Open)
+        )
+    }
+
+    /// Whether the defect still needs work
+    pub fn is_active(&self) -> bool {
+        !matches!(self, DefectStatus::Verified)
+    }
+}
+
+impl std::fmt::Display for DefectStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DefectStatus::Open => write!(f, "Open"),
+            DefectStatus::Deferred => write!(f, "Deferred"),
+            DefectStatus::Repaired => write!(f, "Repaired"),
+            DefectStatus::Verified => write!(f, "Verified"),
+        }
+    }
+}
+
+impl std::str::FromStr for DefectStatus {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Open" => Ok(DefectStatus::Open),
+            "Deferred" => Ok(DefectStatus::Deferred),
+            "Repaired" => Ok(DefectStatus::Repaired),
+            "Verified" => Ok(DefectStatus::Verified),
+            _ => Err(AppError::validation("status", format!("Invalid defect status: {}", s))),
+        }
+    }
+}
+
+/// A defect on an asset, tracked from the inspection that found it through
+/// every later inspection that saw it again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Defect {
+    pub id: i64,
+    pub asset_id: i64,
+    pub component_id: Option<i64>,
+    /// Name of the inspection item the defect was found on
+    pub title: String,
+    /// Finding from the latest observation
+    pub description: Option<String>,
+    pub severity: Option<Severity>,
+    pub status: DefectStatus,
+    pub first_item_id: i64,
+    pub latest_item_id: i64,
+    pub observation_count: i64,
+    pub deferred_until: Option<DateTime<Utc>>,
+    pub deferral_reason: Option<String>,
+    pub repaired_at: Option<DateTime<Utc>>,
+    pub repaired_by: Option<i64>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub verified_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An inspection item that recorded the defect
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefectObservation {
+    pub item_id: i64,
+    pub inspection_id: i64,
+    pub finding: Option<String>,
+    pub severity: Option<Severity>,
+    pub observed_at: DateTime<Utc>,
+}
+
+/// Severity the defect was given, and the inspection item that gave it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefectSeverityChange {
+    pub severity: Option<Severity>,
+    pub item_id: Option<i64>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// A defect with the inspections that saw it and how its severity changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefectDetail {
+    pub defect: Defect,
+    pub observations: Vec<DefectObservation>,
+    pub severity_history: Vec<DefectSeverityChange>,
+}
+
+/// Filters for listing defects; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DefectFilter {
+    pub asset_id: Option<i64>,
+    pub status: Option<DefectStatus>,
+    /// Only defects not yet verified
+    #[serde(default)]
+    pub active_only: bool,
+}
+
+// =============================================================================
+// Corrective Action Models
+// =============================================================================
+
+/// Where a corrective action is in its workflow
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CorrectiveActionStatus {
+    Open,
+    InProgress,
+    /// Work done and evidence attached, waiting for a supervisor
+    PendingVerification,
+    /// Verified by a supervisor
+    Closed,
+    Cancelled,
+}
+
+impl CorrectiveActionStatus {
+    /// Whether an action may move from this status to `next`. Work is
+    /// submitted for verification and either closed or sent back; only
+    /// actions not yet submitted can be cancelled.
+    pub fn can_transition_to(&self, next: CorrectiveActionStatus) -> bool {
+        use CorrectiveActionStatus::*;
+        matches!(
+            (self, next),
+            (Open, InProgress)
+                | (InProgress, PendingVerification)
+                | (PendingVerification, Closed)
+                | (PendingVerification, InProgress)
+                | (Open, Cancelled)
+                | (InProgress, Cancelled)
+        )
+    }
+
+    /// Whether the action still needs work from its assignee
+    pub fn is_open(&self) -> bool {
+        matches!(self, CorrectiveActionStatus::Open | CorrectiveActionStatus::InProgress)
+    }
+}
+
+impl std::fmt::Display for CorrectiveActionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CorrectiveActionStatus::Open => write!(f, "Open"),
+            CorrectiveActionStatus::InProgress => write!(f, "In Progress"),
+            CorrectiveActionStatus::PendingVerification => write!(f, "Pending Verification"),
+            CorrectiveActionStatus::Closed => write!(f, "Closed"),
+            CorrectiveActionStatus::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+impl std::str::FromStr for CorrectiveActionStatus {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Open" => Ok(CorrectiveActionStatus::Open),
+            "In Progress" => Ok(CorrectiveActionStatus::InProgress),
+            "Pending Verification" => Ok(CorrectiveActionStatus::PendingVerification),
+            "Closed" => Ok(CorrectiveActionStatus::Closed),
+            "Cancelled" => Ok(CorrectiveActionStatus::Cancelled),
+            _ => Err(AppError::validation("status", format!("Invalid corrective action status: {}", s))),
+        }
+    }
+}
+
+/// Action taken to correct a finding, assigned to a user and closed only
+/// after a supervisor has verified the evidence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorrectiveAction {
+    pub id: i64,
+    pub inspection_item_id: i64,
+    pub inspection_id: i64,
+    pub asset_id: i64,
+    pub description: String,
+    pub status: CorrectiveActionStatus,
+    pub assignee_id: Option<i64>,
+    pub due_date: Option<NaiveDate>,
+    /// Photos showing the work, attached before submitting for verification
+    pub evidence_media_ids: Vec<i64>,
+    pub completion_notes: Option<String>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub submitted_by: Option<i64>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub verified_by: Option<i64>,
+    /// Supervisor's comments on closing or sending the action back
+    pub verification_notes: Option<String>,
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Fields supplied when creating a corrective action from a finding. The
+/// description defaults to the item's recorded corrective action or finding.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CorrectiveActionInput {
+    pub description: Option<String>,
+    pub assignee_id: Option<i64>,
+    pub due_date: Option<NaiveDate>,
+}
+
+impl Validate for CorrectiveActionInput {
+    fn validate(&self) -> AppResult<()> {
+        if self.description.as_deref().is_some_and(|d| d.trim().is_empty()) {
+            return Err(AppError::validation("description", "Description cannot be empty"));
+        }
+        Ok(())
+    }
+}
+
+/// Changes to an unfinished corrective action; `None` leaves a field as is
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CorrectiveActionUpdateData {
+    pub description: Option<String>,
+    pub assignee_id: Option<Option<i64>>,
+    pub due_date: Option<Option<NaiveDate>>,
+}
+
+impl Validate for CorrectiveActionUpdateData {
+    fn validate(&self) -> AppResult<()> {
+        if self.description.as_deref().is_some_and(|d| d.trim().is_empty()) {
+            return Err(AppError::validation("description", "Description cannot be empty"));
+        }
+        Ok(())
+    }
+}
+
+/// Filters for listing corrective actions; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CorrectiveActionFilter {
+    pub status: Option<CorrectiveActionStatus>,
+    pub asset_id: Option<i64>,
+    pub assignee_id: Option<i64>,
+    pub inspection_id: Option<i64>,
+    /// Only actions past their due date that are still open
+    #[serde(default)]
+    pub overdue_only: bool,
+}
+
+// =============================================================================
+// Validation Rule Models
+// =============================================================================
+
+/// What a validation rule checks on an inspection item
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ValidationRuleKind {
+    /// The measured value lies within `min_value`..=`max_value`
+    MeasurementRange,
+    /// At least one photo is attached to the item
+    PhotoRequired,
+    /// Non-compliant items say what corrective action is needed
+    CorrectiveActionRequired,
+}
+
+impl std::fmt::Display for ValidationRuleKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationRuleKind::MeasurementRange => write!(f, "MeasurementRange"),
+            ValidationRuleKind::PhotoRequired => write!(f, "PhotoRequired"),
+            ValidationRuleKind::CorrectiveActionRequired => write!(f, "CorrectiveActionRequired"),
+        }
+    }
+}
+
+impl std::str::FromStr for ValidationRuleKind {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "MeasurementRange" => Ok(ValidationRuleKind::MeasurementRange),
+            "PhotoRequired" => Ok(ValidationRuleKind::PhotoRequired),
+            "CorrectiveActionRequired" => Ok(ValidationRuleKind::CorrectiveActionRequired),
+            _ => Err(AppError::validation("kind", format!("Invalid validation rule kind: {}", s))),
+        }
+    }
+}
+
+/// When a rule is enforced. Every active rule is checked on submission;
+/// `Save` rules also reject an item as it is saved.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RuleStage {
+    Save,
+    Submission,
+}
+
+impl std::fmt::Display for RuleStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleStage::Save => write!(f, "Save"),
+            RuleStage::Submission => write!(f, "Submission"),
+        }
+    }
+}
+
+impl std::str::FromStr for RuleStage {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Save" => Ok(RuleStage::Save),
+            "Submission" => Ok(RuleStage::Submission),
+            _ => Err(AppError::validation("stage", format!("Invalid rule stage: {}", s))),
+        }
+    }
+}
+
+/// A configurable check on inspection items. Unset `item_category`,
+/// `item_name` and `min_severity` match every item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationRule {
+    pub id: i64,
+    pub name: String,
+    pub kind: ValidationRuleKind,
+    pub stage: RuleStage,
+    pub item_category: Option<String>,
+    pub item_name: Option<String>,
+    /// Only items recorded at this severity or above
+    pub min_severity: Option<Severity>,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    /// Unit the range is given in; readings in another unit are flagged
+    pub unit: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ValidationRule {
+    /// Whether the rule covers an item of this category, name and severity
+    pub fn applies_to(&self, item_category: &str, item_name: &str, severity: Option<&Severity>) -> bool {
+        let matches = |filter: &Option<String>, value: &str| {
+            filter.as_deref().is_none_or(|f| f.trim().eq_ignore_ascii_case(value.trim()))
+        };
+        let severe_enough = match &self.min_severity {
+            None => true,
+            Some(min) => severity.is_some_and(|s| s.rank() >= min.rank()),
+        };
+        matches(&self.item_category, item_category) && matches(&self.item_name, item_name) && severe_enough
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationRuleInput {
+    pub name: String,
+    pub kind: ValidationRuleKind,
+    pub stage: RuleStage,
+    pub item_category: Option<String>,
+    pub item_name: Option<String>,
+    pub min_severity: Option<Severity>,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    pub unit: Option<String>,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Validate for ValidationRuleInput {
+    fn validate(&self) -> AppResult<()> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::RequiredField { field: "name".to_string() });
+        }
+        let bounds = [("min_value", self.min_value), ("max_value", self.max_value)];
+        if let Some((field, _)) = bounds.iter().find(|(_, v)| v.is_some_and(|v| !v.is_finite())) {
+            return Err(AppError::validation(*field, "Range bounds must be finite numbers"));
+        }
+        match self.kind {
+            ValidationRuleKind::MeasurementRange => {
+                if self.min_value.is_none() && self.max_value.is_none() {
+                    return Err(AppError::validation("min_value", "A measurement range needs a minimum, a maximum or both"));
+                }
+                if let (Some(min), Some(max)) = (self.min_value, self.max_value) {
+                    if min > max {
+                        return Err(AppError::validation("min_value", "Minimum cannot be greater than maximum"));
+                    }
+                }
+            }
+            _ => {
+                if self.min_value.is_some() || self.max_value.is_some() || self.unit.is_some() {
+                    return Err(AppError::validation("kind", format!("{} rules do not take a range or unit", self.kind)));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One rule an inspection item fails
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleViolation {
+    pub rule_id: i64,
+    pub rule_name: String,
+    /// Item field the rule concerns
+    pub field: String,
+    pub message: String,
+}
+
+/// The rules one inspection item fails
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemRuleViolations {
+    pub item_id: i64,
+    pub item_name: String,
+    pub violations: Vec<RuleViolation>,
+}
+
+/// Result of checking every item of an inspection against the active rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InspectionRuleReport {
+    pub inspection_id: i64,
+    pub items_checked: usize,
+    /// Items failing at least one rule; empty when the inspection passes
+    pub items: Vec<ItemRuleViolations>,
+}
+
+impl InspectionRuleReport {
+    pub fn passed(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+// =============================================================================
+// JSON Schema Models
+// =============================================================================
+
+/// A free-form JSON field that can be checked against a schema
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum SchemaTarget {
+    /// `assets.specifications`, scoped by asset type
+    AssetSpecifications,
+    /// `components.specifications`, scoped by component type
+    ComponentSpecifications,
+    /// `inspections.checklist_data`, scoped by inspection type
+    ChecklistData,
+    /// `compliance_standards.requirements`, scoped by standard code
+    StandardRequirements,
+}
+
+impl SchemaTarget {
+    pub const ALL: [SchemaTarget; 4] = [
+        SchemaTarget::AssetSpecifications,
+        SchemaTarget::ComponentSpecifications,
+        SchemaTarget::ChecklistData,
+        SchemaTarget::StandardRequirements,
+    ];
+
+    /// Name of the field in validation errors
+    pub fn field_name(&self) -> &'static str {
+        match self {
+            SchemaTarget::AssetSpecifications | SchemaTarget::ComponentSpecifications => "specifications",
+            SchemaTarget::ChecklistData => "checklist_data",
+            SchemaTarget::StandardRequirements => "requirements",
+        }
+    }
+}
+
+impl std::fmt::Display for SchemaTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaTarget::AssetSpecifications => write!(f, "AssetSpecifications"),
+            SchemaTarget::ComponentSpecifications => write!(f, "ComponentSpecifications"),
+            SchemaTarget::ChecklistData => write!(f, "ChecklistData"),
+            SchemaTarget::StandardRequirements => write!(f, "StandardRequirements"),
+        }
+    }
+}
+
+impl std::str::FromStr for SchemaTarget {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SchemaTarget::ALL.into_iter()
+            .find(|target| target.to_string() == s)
+            .ok_or_else(|| AppError::validation("target", format!("Invalid schema target: {}", s)))
+    }
+}
+
+/// A JSON Schema applied to one field for one scope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonSchemaDefinition {
+    pub id: i64,
+    pub target: SchemaTarget,
+    /// Asset type, component type, inspection type or standard code
+    pub scope: String,
+    pub schema: JsonValue,
+    pub description: Option<String>,
+    pub updated_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonSchemaInput {
+    pub target: SchemaTarget,
+    pub scope: String,
+    pub schema: JsonValue,
+    pub description: Option<String>,
+}
+
+impl Validate for JsonSchemaInput {
+    fn validate(&self) -> AppResult<()> {
+        if self.scope.trim().is_empty() {
+            return Err(AppError::RequiredField { field: "scope".to_string() });
+        }
+        crate::json_schema::check_schema(&self.schema)
+    }
+}
+
+/// A stored record whose JSON no longer matches the current schema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaAuditFinding {
+    pub target: SchemaTarget,
+    pub scope: String,
+    pub record_id: i64,
+    /// Asset number, component name, inspection ID or standard code
+    pub record_label: String,
+    pub violations: Vec<crate::json_schema::SchemaViolation>,
+}
+
+/// Result of checking stored records against the current schemas
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchemaAuditReport {
+    pub records_checked: usize,
+    pub findings: Vec<SchemaAuditFinding>,
+}
+
+// =============================================================================
+// Report Share Link Models
+// =============================================================================
+
+/// Hours a share link stays valid when no lifetime is given
+pub const DEFAULT_SHARE_LINK_HOURS: i64 = 72;
+
+/// Longest lifetime a share link can be given
+pub const MAX_SHARE_LINK_HOURS: i64 = 24 * 30;
+
+/// Longest recipient note stored with a share link
+pub const MAX_SHARE_RECIPIENT_LENGTH: usize = 200;
+
+/// Whether `report_id` names a generated report file. Only letters, digits,
+/// `_` and `-` are allowed so the ID can never reach outside the reports
+/// directory.
+pub fn is_valid_report_id(report_id: &str) -> bool {
+    !report_id.is_empty()
+        && report_id.len() <= 200
+        && report_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Request to share one generated report with someone without a login
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareLinkInput {
+    pub report_id: String,
+    /// Who the link is for, e.g. "Regulator - J. Smith"
+    pub recipient: Option<String>,
+    /// Hours until the link expires, `DEFAULT_SHARE_LINK_HOURS` when unset
+    pub expires_in_hours: Option<i64>,
+    /// Times the report can be opened through the link; unlimited when unset
+    pub max_views: Option<i64>,
+}
+
+impl Validate for ShareLinkInput {
+    fn validate(&self) -> AppResult<()> {
+        if !is_valid_report_id(&self.report_id) {
+            return Err(AppError::validation("report_id", "Not a valid report ID"));
+        }
+        if self.recipient.as_ref().is_some_and(|r| r.len() > MAX_SHARE_RECIPIENT_LENGTH) {
+            return Err(AppError::validation(
+                "recipient",
+                format!("Recipient cannot exceed {} characters", MAX_SHARE_RECIPIENT_LENGTH),
+            ));
+        }
+        if let Some(hours) = self.expires_in_hours {
+            if !(1..=MAX_SHARE_LINK_HOURS).contains(&hours) {
+                return Err(AppError::OutOfRange {
+                    field: "expires_in_hours".to_string(),
+                    value: hours.to_string(),
+                    min: "1".to_string(),
+                    max: MAX_SHARE_LINK_HOURS.to_string(),
+                });
+            }
+        }
+        if self.max_views.is_some_and(|views| views < 1) {
+            return Err(AppError::validation("max_views", "A link must allow at least one view"));
+        }
+        Ok(())
+    }
+}
+
+/// A generated report file and what it was generated from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportArtifact {
+    pub report_id: String,
+    /// `inspection`, `compliance`, `checklist` or the summary report name
+    pub report_type: String,
+    /// Hash of the report type, format, locale and parameters
+    pub cache_key: String,
+    /// Hash of the data the report was built from
+    pub input_fingerprint: String,
+    pub file_path: String,
+    pub generated_by: Option<i64>,
+    pub generated_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl ReportArtifact {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// A read-only link to one report. Only a hash of the token is stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportShareLink {
+    pub id: i64,
+    pub report_id: String,
+    pub recipient: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub max_views: Option<i64>,
+    pub view_count: i64,
+    pub last_viewed_at: Option<DateTime<Utc>>,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ReportShareLink {
+    /// Whether the link can still be used to open the report
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none()
+            && self.expires_at > now
+            && self.max_views.is_none_or(|max| self.view_count < max)
+    }
+}
+
+/// A newly created link with its token, which is only ever shown once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedShareLink {
+    pub link: ReportShareLink,
+    pub token: String,
+}
+
+/// A report opened through a share link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedReport {
+    pub report_id: String,
+    pub file_name: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+    pub expires_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// Multi-factor Authentication Models
+// =============================================================================
+
+/// Issuer shown in authenticator apps
+pub const MFA_ISSUER: &str = "CranePro";
+
+/// Minutes a password-verified login waits for its second factor
+pub const MFA_CHALLENGE_MINUTES: i64 = 5;
+
+/// Wrong codes accepted for one login before it has to start over
+pub const MAX_MFA_ATTEMPTS: u32 = 5;
+
+/// Whether users of a role must use a second factor to sign in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaPolicy {
+    pub role: UserRole,
+    pub required: bool,
+}
+
+/// A user's second factor setup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaStatus {
+    pub user_id: i64,
+    /// An authenticator has been enrolled and confirmed with a code
+    pub enabled: bool,
+    /// A secret was issued but no code has confirmed it yet
+    pub pending_enrollment: bool,
+    /// The user's role requires a second factor
+    pub required: bool,
+    pub backup_codes_remaining: i64,
+}
+
+/// What the user needs to set up an authenticator app. Shown once only.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaEnrollment {
+    /// Base32 secret for entering by hand
+    pub secret: String,
+    /// `otpauth://` URI to render as a QR code
+    pub otpauth_uri: String,
+    pub backup_codes: Vec<String>,
+}
+
+// =============================================================================
+// User Preference Models
+// =============================================================================
+
+/// Longest landing page path stored for a user
+pub const MAX_LANDING_PAGE_LENGTH: usize = 200;
+
+/// Tables a user can keep a column layout for
+pub const MAX_TABLE_LAYOUTS: usize = 50;
+
+/// Columns kept in one table layout
+pub const MAX_TABLE_COLUMNS: usize = 100;
+
+/// Largest rows-per-page a table layout can ask for
+pub const MAX_TABLE_PAGE_SIZE: u32 = 500;
+
+/// One column of a table as the user arranged it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TableColumnLayout {
+    pub key: String,
+    /// Width in pixels; the table's own default when unset
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+/// How a user arranged one table: column order, widths, sorting and page size
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TableLayout {
+    /// Columns in display order
+    pub columns: Vec<TableColumnLayout>,
+    pub sort_by: Option<String>,
+    #[serde(default)]
+    pub sort_descending: bool,
+    pub page_size: Option<u32>,
+}
+
+impl TableLayout {
+    fn validate_for(&self, table: &str) -> AppResult<()> {
+        let field = |name: &str| format!("table_layouts.{}.{}", table, name);
+        if self.columns.len() > MAX_TABLE_COLUMNS {
+            return Err(AppError::validation(
+                field("columns"),
+                format!("A table layout cannot have more than {} columns", MAX_TABLE_COLUMNS),
+            ));
+        }
+        for (index, column) in self.columns.iter().enumerate() {
+            if column.key.trim().is_empty() || column.key.len() > 100 {
+                return Err(AppError::validation(
+                    field(&format!("columns[{}].key", index)),
+                    "Column key must be 1-100 characters",
+                ));
+            }
+            if self.columns[..index].iter().any(|c| c.key == column.key) {
+                return Err(AppError::validation(
+                    field(&format!("columns[{}].key", index)),
+                    format!("Column '{}' appears more than once", column.key),
+                ));
+            }
+            if column.width.is_some_and(|width| !(20..=2000).contains(&width)) {
+                return Err(AppError::validation(
+                    field(&format!("columns[{}].width", index)),
+                    "Column width must be between 20 and 2000 pixels",
+                ));
+            }
+        }
+        if self.sort_by.as_ref().is_some_and(|key| key.trim().is_empty() || key.len() > 100) {
+            return Err(AppError::validation(field("sort_by"), "Sort column must be 1-100 characters"));
+        }
+        if let Some(page_size) = self.page_size {
+            if !(1..=MAX_TABLE_PAGE_SIZE).contains(&page_size) {
+                return Err(AppError::OutOfRange {
+                    field: field("page_size"),
+                    value: page_size.to_string(),
+                    min: "1".to_string(),
+                    max: MAX_TABLE_PAGE_SIZE.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A user's preferences, stored with their account so they follow the user
+/// from one device to the next
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserPreferences {
+    pub user_id: i64,
+    /// Location selected when the app opens
+    pub default_location_id: Option<i64>,
+    /// App path opened after login, e.g. `/inspections`
+    pub landing_page: Option<String>,
+    pub capacity_unit: CapacityUnit,
+    pub length_unit: LengthUnit,
+    /// Channels the user receives notifications on at all; per-kind choices
+    /// are kept in the notification preferences
+    pub notification_channels: Vec<NotificationChannelKind>,
+    /// Column layouts keyed by table, e.g. `assets` or `inspection_items`
+    pub table_layouts: HashMap<String, TableLayout>,
+    /// `None` until the user first saves their preferences
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl UserPreferences {
+    /// Preferences of a user who has never saved any
+    pub fn defaults(user_id: i64) -> Self {
+        Self {
+            user_id,
+            default_location_id: None,
+            landing_page: None,
+            capacity_unit: CapacityUnit::Tonne,
+            length_unit: LengthUnit::Millimeter,
+            notification_channels: NotificationChannelKind::ALL.to_vec(),
+            table_layouts: HashMap::new(),
+            updated_at: None,
+        }
+    }
+}
+
+/// Replacement preferences for the signed-in user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserPreferencesInput {
+    pub default_location_id: Option<i64>,
+    pub landing_page: Option<String>,
+    pub capacity_unit: CapacityUnit,
+    pub length_unit: LengthUnit,
+    pub notification_channels: Vec<NotificationChannelKind>,
+    #[serde(default)]
+    pub table_layouts: HashMap<String, TableLayout>,
+}
+
+impl Validate for UserPreferencesInput {
+    fn validate(&self) -> AppResult<()> {
+        if let Some(page) = &self.landing_page {
+            // Only paths inside the app, so a stored preference can never
+            // send the user to another site
+            if !page.starts_with('/') || page.starts_with("//") || page.contains("..")
+                || page.len() > MAX_LANDING_PAGE_LENGTH || page.chars().any(char::is_whitespace)
+            {
+                return Err(AppError::validation(
+                    "landing_page",
+                    format!("Landing page must be an app path such as /inspections of at most {} characters",
+                        MAX_LANDING_PAGE_LENGTH),
+                ));
+            }
+        }
+        if self.table_layouts.len() > MAX_TABLE_LAYOUTS {
+            return Err(AppError::validation(
+                "table_layouts",
+                format!("Layouts can be kept for at most {} tables", MAX_TABLE_LAYOUTS),
+            ));
+        }
+        for (table, layout) in &self.table_layouts {
+            if table.trim().is_empty() || table.len() > 100 {
+                return Err(AppError::validation("table_layouts", "Table key must be 1-100 characters"));
+            }
+            layout.validate_for(table)?;
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Password Reset Models
+// =============================================================================
+
+/// Minutes a password reset token can be used for
+pub const PASSWORD_RESET_TOKEN_MINUTES: i64 = 60;
+
+/// Unused, unexpired reset tokens a user can have at once; further requests
+/// are ignored so the mailbox cannot be flooded
+pub const MAX_ACTIVE_PASSWORD_RESETS: i64 = 3;
+
+/// A single-use password reset token. Only its hash is stored; the token
+/// itself is sent to the user or handed over by an administrator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordReset {
+    pub user_id: i64,
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// Kiosk Models
+// =============================================================================
+
+/// Minutes without activity before a kiosk session is signed out, unless the
+/// terminal sets its own
+pub const DEFAULT_KIOSK_IDLE_MINUTES: i64 = 5;
+
+/// Longest idle timeout a kiosk terminal can be given
+pub const MAX_KIOSK_IDLE_MINUTES: i64 = 60;
+
+/// Digits in a kiosk PIN
+pub const KIOSK_PIN_LENGTH: std::ops::RangeInclusive<usize> = 4..=8;
+
+/// Wrong PINs in a row for one badge before it is locked for a while
+pub const KIOSK_BADGE_ATTEMPTS: i64 = 5;
+
+/// Minutes a badge stays locked after too many wrong PINs
+pub const KIOSK_LOCKOUT_MINUTES: i64 = 15;
+
+/// Permissions a kiosk session can hold at most: looking up equipment and
+/// recording inspections. Each user keeps only those their role grants.
+pub const KIOSK_PERMISSIONS: &[&str] = &[
+    "asset:read",
+    "inspection:create",
+    "inspection:read",
+    "inspection:update",
+    "inspection:submit",
+    "media:upload",
+    "media:read",
+    "location:read",
+];
+
+/// A shared shop-floor terminal allowed to start kiosk sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KioskTerminal {
+    pub id: i64,
+    pub name: String,
+    pub location_id: Option<i64>,
+    pub idle_timeout_minutes: i64,
+    pub is_active: bool,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: Option<DateTime<Utc>>,
+}
+
+/// Request to register a kiosk terminal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KioskTerminalInput {
+    pub name: String,
+    pub location_id: Option<i64>,
+    /// `DEFAULT_KIOSK_IDLE_MINUTES` when unset
+    pub idle_timeout_minutes: Option<i64>,
+}
+
+impl Validate for KioskTerminalInput {
+    fn validate(&self) -> AppResult<()> {
+        if self.name.trim().is_empty() || self.name.len() > 100 {
+            return Err(AppError::validation("name", "Terminal name must be 1-100 characters"));
+        }
+        if let Some(minutes) = self.idle_timeout_minutes {
+            if !(1..=MAX_KIOSK_IDLE_MINUTES).contains(&minutes) {
+                return Err(AppError::OutOfRange {
+                    field: "idle_timeout_minutes".to_string(),
+                    value: minutes.to_string(),
+                    min: "1".to_string(),
+                    max: MAX_KIOSK_IDLE_MINUTES.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A newly registered terminal with the key it signs in with. The key is
+/// shown once; only its hash is stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisteredKioskTerminal {
+    pub terminal: KioskTerminal,
+    pub terminal_key: String,
+}
+
+/// Badge and PIN a user signs in to kiosks with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KioskCredentialInput {
+    /// Value read from the user's badge
+    pub badge_id: String,
+    pub pin: String,
+}
+
+impl Validate for KioskCredentialInput {
+    fn validate(&self) -> AppResult<()> {
+        let badge = self.badge_id.trim();
+        if badge.len() < 4 || badge.len() > 128 {
+            return Err(AppError::validation("badge_id", "Badge ID must be 4-128 characters"));
+        }
+        validate_pin(&self.pin)
+    }
+}
+
+/// Check a kiosk or device PIN: `KIOSK_PIN_LENGTH` digits, not all the same
+pub fn validate_pin(pin: &str) -> AppResult<()> {
+    if !KIOSK_PIN_LENGTH.contains(&pin.len()) || !pin.chars().all(|c| c.is_ascii_digit()) {
+        return Err(AppError::validation(
+            "pin",
+            format!("PIN must be {} to {} digits", KIOSK_PIN_LENGTH.start(), KIOSK_PIN_LENGTH.end()),
+        ));
+    }
+    if pin.chars().all(|c| pin.starts_with(c)) {
+        return Err(AppError::validation("pin", "PIN cannot be the same digit repeated"));
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Trusted Device Models
+// =============================================================================
+
+/// Days a device stays trusted after it is registered
+pub const TRUSTED_DEVICE_DAYS: i64 = 30;
+
+/// Wrong PINs or badges in a row before a device is locked for a while
+pub const DEVICE_UNLOCK_ATTEMPTS: i64 = 5;
+
+/// Minutes a device stays locked after too many wrong attempts
+pub const DEVICE_LOCKOUT_MINUTES: i64 = 15;
+
+/// Wrong attempts in a row after which the device is no longer trusted and
+/// the user has to sign in with their password again
+pub const DEVICE_REVOKE_ATTEMPTS: i64 = 10;
+
+/// A device a user signed in on with their password and can now unlock with
+/// a PIN or their badge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedDevice {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub failed_attempts: i64,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Request to trust the device the user is signed in on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedDeviceInput {
+    /// Shown in the device list, e.g. "Tablet 4 - north yard"
+    pub name: String,
+    pub pin: String,
+}
+
+impl Validate for TrustedDeviceInput {
+    fn validate(&self) -> AppResult<()> {
+        if self.name.trim().is_empty() || self.name.len() > 100 {
+            return Err(AppError::validation("name", "Device name must be 1-100 characters"));
+        }
+        validate_pin(&self.pin)
+    }
+}
+
+/// A newly trusted device with the key it unlocks with. The key is kept on
+/// the device and shown once; only its hash is stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisteredTrustedDevice {
+    pub device: TrustedDevice,
+    pub device_key: String,
+}
+
+/// What the user entered to unlock a trusted device: a PIN or a badge scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "method", content = "value", rename_all = "snake_case")]
+pub enum DeviceUnlock {
+    Pin(String),
+    Badge(String),
+}
+
+// =============================================================================
+// Paper Checklist Models
+// =============================================================================
+
+/// URI scheme the app opens for links in printed material and emails
+pub const APP_LINK_SCHEME: &str = "cranepro";
+
+/// Blank rows printed for an inspection with no checklist to follow
+pub const PAPER_CHECKLIST_BLANK_ROWS: usize = 20;
+
+/// A line of a printed checklist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperChecklistItem {
+    pub name: String,
+    /// Acceptance criteria printed under the item
+    pub guidance: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperChecklistSection {
+    pub title: Option<String>,
+    pub items: Vec<PaperChecklistItem>,
+}
+
+/// What is printed on a blank checklist for an inspection in an area where
+/// tablets and phones are not allowed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperChecklist {
+    pub inspection_id: i64,
+    pub asset_number: String,
+    pub asset_name: String,
+    pub location_name: String,
+    pub inspection_type: InspectionType,
+    pub compliance_standard: String,
+    pub scheduled_date: Option<DateTime<Utc>>,
+    pub inspector_name: String,
+    pub sections: Vec<PaperChecklistSection>,
+}
+
+impl PaperChecklist {
+    /// Link printed as a QR code that opens the inspection in the app
+    pub fn record_uri(&self) -> String {
+        format!("{}://inspection/{}", APP_LINK_SCHEME, self.inspection_id)
+    }
+}
+
+/// Mark an inspection as carried out on paper and typed in afterwards
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperTranscriptionInput {
+    /// Scan or photo of the completed sheet, already uploaded to the
+    /// inspection
+    pub scan_media_id: i64,
+    /// When the inspection was done, as written on the sheet; becomes the
+    /// inspection's actual date
+    pub inspected_at: Option<DateTime<Utc>>,
+}
+
+/// An inspection whose results were typed in from a paper checklist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperTranscription {
+    pub inspection_id: i64,
+    pub scan_media_id: i64,
+    pub transcribed_by: i64,
+    pub transcribed_at: DateTime<Utc>,
+    pub inspected_at: Option<DateTime<Utc>>,
+}
+
+// =============================================================================
+// Deep Link Models
+// =============================================================================
+
+/// Hex characters in the token printed on an asset label
+pub const ASSET_LINK_TOKEN_LENGTH: usize = 32;
+
+/// Link the app was opened with, from a scanned label, a printed checklist
+/// or an email
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    /// `cranepro://asset/{token}`; labels carry a token rather than the
+    /// asset's ID so that IDs cannot be guessed from one label to the next
+    Asset { token: String },
+    /// `cranepro://inspection/{id}`, as printed on paper checklists
+    Inspection { id: i64 },
+}
+
+impl std::str::FromStr for DeepLink {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AppError::validation("uri", format!("Not a {} link: {}", APP_LINK_SCHEME, s));
+        let rest = s.trim()
+            .strip_prefix(APP_LINK_SCHEME)
+            .and_then(|rest| rest.strip_prefix("://"))
+            .ok_or_else(invalid)?;
+        // Query strings and fragments added by mail clients are ignored
+        let path = rest.split(['?', '#']).next().unwrap_or_default().trim_end_matches('/');
+        let (kind, value) = path.split_once('/').ok_or_else(invalid)?;
+
+        match kind {
+            "asset" => {
+                let token = value.to_ascii_lowercase();
+                if token.len() != ASSET_LINK_TOKEN_LENGTH || !token.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(AppError::validation("uri", "Asset link token is malformed"));
+                }
+                Ok(DeepLink::Asset { token })
+            }
+            "inspection" => value.parse()
+                .map(|id| DeepLink::Inspection { id })
+                .map_err(|_| AppError::validation("uri", "Inspection link ID is malformed")),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Kind of record a deep link opens
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkedEntityType {
+    Asset,
+    Inspection,
+}
+
+/// Screen the frontend opens a linked record on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkView {
+    /// The asset's field card with its open inspections and findings
+    AssetCard,
+    /// The inspection's checklist, to carry on or review it
+    InspectionChecklist,
+}
+
+/// Where the frontend should route a deep link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeepLinkTarget {
+    pub entity_type: LinkedEntityType,
+    pub id: i64,
+    pub default_view: LinkView,
+}
+
+/// Link printed on an asset's label
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetLink {
+    pub asset_id: i64,
+    pub uri: String,
+}
+
+impl AssetLink {
+    pub fn new(asset_id: i64, token: &str) -> Self {
+        Self { asset_id, uri: format!("{}://asset/{}", APP_LINK_SCHEME, token) }
+    }
+}
+
+// =============================================================================
+// Legal Hold Models
+// =============================================================================
+
+/// Longest accepted hold or release reason
+pub const MAX_LEGAL_HOLD_REASON_LENGTH: usize = 1000;
+
+/// Record type that can be placed under legal hold
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LegalHoldEntityType {
+    /// Covers the asset, its inspections and their media, and documents
+    /// filed against it
+    Asset,
+    /// Covers the inspection and its media
+    Inspection,
+}
+
+impl std::fmt::Display for LegalHoldEntityType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LegalHoldEntityType::Asset => write!(f, "asset"),
+            LegalHoldEntityType::Inspection => write!(f, "inspection"),
+        }
+    }
+}
+
+impl std::str::FromStr for LegalHoldEntityType {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "asset" => Ok(LegalHoldEntityType::Asset),
+            "inspection" => Ok(LegalHoldEntityType::Inspection),
+            _ => Err(AppError::validation("entity_type", format!("Invalid legal hold record type: {}", s))),
+        }
+    }
+}
+
+/// Place a record under legal hold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegalHoldInput {
+    pub entity_type: LegalHoldEntityType,
+    pub entity_id: i64,
+    pub reason: String,
+    /// Matter or case number the hold is for
+    pub reference: Option<String>,
+}
+
+impl Validate for LegalHoldInput {
+    fn validate(&self) -> AppResult<()> {
+        if self.reason.trim().is_empty() {
+            return Err(AppError::validation("reason", "A reason for the hold is required"));
+        }
+        if self.reason.len() > MAX_LEGAL_HOLD_REASON_LENGTH {
+            return Err(AppError::validation(
+                "reason",
+                format!("Reason cannot exceed {} characters", MAX_LEGAL_HOLD_REASON_LENGTH),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A legal hold exempting a record from deletion. While active, the record
+/// and everything it covers cannot be purged, have media deleted or be
+/// removed by retention jobs. Released holds stay in the register.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegalHold {
+    pub id: i64,
+    pub entity_type: LegalHoldEntityType,
+    pub entity_id: i64,
+    /// Asset number and name, or inspection type and number
+    pub label: String,
+    pub reason: String,
+    pub reference: Option<String>,
+    pub placed_by: i64,
+    pub placed_by_name: Option<String>,
+    pub placed_at: DateTime<Utc>,
+    pub released_by: Option<i64>,
+    pub released_at: Option<DateTime<Utc>>,
+    pub release_reason: Option<String>,
+    /// Inspections and media files the hold protects besides the record itself
+    pub covered_inspections: i64,
+    pub covered_media: i64,
+}
+
+impl LegalHold {
+    pub fn is_active(&self) -> bool {
+        self.released_at.is_none()
+    }
+}
+
+// =============================================================================
+// Role Models
+// =============================================================================
+
+/// Longest accepted role name
+pub const MAX_ROLE_NAME_LENGTH: usize = 100;
+
+/// A set of permissions users can be given. Each built-in role has a row
+/// seeded from its default permissions; custom roles are made by
+/// administrators, e.g. a read-only auditor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Role {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    /// For a built-in role, the role itself. For a custom role, the
+    /// built-in role whose other rules apply to its users, such as which
+    /// users are alerted of critical findings or must use a second factor.
+    pub base_role: UserRole,
+    pub is_builtin: bool,
+    pub permissions: Vec<String>,
+    /// Users currently given the role
+    pub user_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A new custom role, or replacement details for an existing role. The
+/// name and base role of a built-in role cannot be changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleInput {
+    pub name: String,
+    pub description: Option<String>,
+    pub base_role: UserRole,
+    pub permissions: Vec<String>,
+}
+
+impl Validate for RoleInput {
+    fn validate(&self) -> AppResult<()> {
+        let name = self.name.trim();
+        if name.is_empty() || name.len() > MAX_ROLE_NAME_LENGTH {
+            return Err(AppError::validation(
+                "name",
+                format!("Role name must be 1-{} characters", MAX_ROLE_NAME_LENGTH),
+            ));
+        }
+        if let Some(permission) = self.permissions.iter().find(|p| !crate::middleware::Permissions::is_known(p)) {
+            return Err(AppError::validation("permissions", format!("Unknown permission: {}", permission)));
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Permission Matrix Models
+// =============================================================================
+
+/// Whether a role can run a command
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CommandAccess {
+    Allowed,
+    /// The role can run the command, but only for some of its inputs, e.g.
+    /// exporting assets but not the audit log
+    Partial,
+    Denied,
+}
+
+impl std::fmt::Display for CommandAccess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandAccess::Allowed => write!(f, "Yes"),
+            CommandAccess::Partial => write!(f, "Partly"),
+            CommandAccess::Denied => write!(f, "No"),
+        }
+    }
+}
+
+/// What a command checks before it runs, and which roles pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandAuthorization {
+    pub command: String,
+    /// Command module, e.g. "inspection"
+    pub module: String,
+    /// Runs without signing in, e.g. logging in
+    pub public: bool,
+    /// Permissions checked on every call
+    pub required: Vec<String>,
+    /// Permissions checked only for some inputs. A resource chosen at run
+    /// time is shown as `<record>`, e.g. `<record>:update`.
+    pub conditional: Vec<String>,
+    /// Refused to kiosk sessions and API keys
+    pub full_session: bool,
+    /// Access of each role, in the order of `PermissionMatrix::roles`
+    pub access: Vec<CommandAccess>,
+}
+
+/// Every command against every role
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionMatrix {
+    pub generated_at: DateTime<Utc>,
+    pub roles: Vec<String>,
+    pub commands: Vec<CommandAuthorization>,
+}
+
+// =============================================================================
+// API Key Models
+// =============================================================================
+
+/// Longest accepted API key name
+pub const MAX_API_KEY_NAME_LENGTH: usize = 100;
+
+/// Longest an API key can be made to last
+pub const MAX_API_KEY_DAYS: i64 = 730;
+
+/// Start of every API key, followed by the key's prefix
+pub const API_KEY_MARKER: &str = "cpk_";
+
+/// A key an external system uses in place of a user login. The system acts
+/// as the user who created the key, limited to the key's permissions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: i64,
+    pub name: String,
+    /// First characters of the key, shown so keys can be told apart
+    pub key_prefix: String,
+    pub permissions: Vec<String>,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    /// Whether the key can still be used to authenticate
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+/// A new API key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyInput {
+    /// What the key is for, e.g. "ERP work order sync"
+    pub name: String,
+    pub permissions: Vec<String>,
+    /// Days until the key expires; it never expires when unset
+    pub expires_in_days: Option<i64>,
+}
+
+impl Validate for ApiKeyInput {
+    fn validate(&self) -> AppResult<()> {
+        let name = self.name.trim();
+        if name.is_empty() || name.len() > MAX_API_KEY_NAME_LENGTH {
+            return Err(AppError::validation(
+                "name",
+                format!("API key name must be 1-{} characters", MAX_API_KEY_NAME_LENGTH),
+            ));
+        }
+        if self.permissions.is_empty() {
+            return Err(AppError::validation("permissions", "An API key needs at least one permission"));
+        }
+        if let Some(permission) = self.permissions.iter().find(|p| !crate::middleware::Permissions::is_known(p)) {
+            return Err(AppError::validation("permissions", format!("Unknown permission: {}", permission)));
+        }
+        if let Some(days) = self.expires_in_days {
+            if !(1..=MAX_API_KEY_DAYS).contains(&days) {
+                return Err(AppError::OutOfRange {
+                    field: "expires_in_days".to_string(),
+                    value: days.to_string(),
+                    min: "1".to_string(),
+                    max: MAX_API_KEY_DAYS.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A newly created key with its secret, which is only ever shown once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedApiKey {
+    pub api_key: ApiKey,
+    pub secret: String,
+}
+
+// =============================================================================
+// Request Trace Models
+// =============================================================================
+
+/// What a trace event records
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TraceEventKind {
+    /// A log line written while the request was handled
+    Log,
+    /// A SQL statement and how long it took
+    Sql,
+    /// An AI job being queued
+    AiJob,
+    /// An event emitted to the frontend
+    Event,
+}
+
+/// One thing that happened while a request was handled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEvent {
+    /// Milliseconds after the request started
+    pub offset_ms: i64,
+    pub kind: TraceEventKind,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    /// Time taken, for SQL statements
+    pub duration_ms: Option<u64>,
+}
+
+/// Timeline of one request, from command entry to its response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trace {
+    /// The request ID returned in the response metadata
+    pub trace_id: String,
+    pub command: String,
+    pub user_id: Option<i64>,
+    pub started_at: DateTime<Utc>,
+    /// Unset while the command is still running
+    pub duration_ms: Option<u64>,
+    /// Whether an error was logged
+    pub failed: bool,
+    pub sql_statements: u64,
+    pub sql_ms: u64,
+    /// Events past the per-trace limit, counted but not kept
+    pub dropped_events: u64,
+    pub events: Vec<TraceEvent>,
+    /// AI jobs the request queued, as they stand now
+    pub ai_jobs: Vec<AiModelResult>,
+}
+
+// =============================================================================
+// Session Models
+// =============================================================================
+
+/// A signed-in session as listed for administrators
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveSession {
+    pub session_id: String,
+    pub user_id: i64,
+    pub username: String,
+    pub full_name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// Shared terminal the session was started on
+    pub kiosk_terminal_id: Option<i64>,
+    /// The session making the request
+    pub current: bool,
+}
+
+/// A refresh token as stored; the token itself is kept only as its SHA-256
+#[derive(Debug, Clone)]
+pub struct RefreshTokenRecord {
+    pub session_id: String,
+    pub user_id: i64,
+    /// Shared by every token rotated from the same sign-in
+    pub family_id: String,
+    /// When the login stops being refreshable; fixed at sign-in
+    pub expires_at: DateTime<Utc>,
+    /// Set once the token has been exchanged. Presenting it again means it
+    /// was copied, so the whole family is revoked.
+    pub used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// A login that passed the password check and awaits its second factor
+#[derive(Debug, Clone)]
+pub struct PendingMfa {
+    pub user_id: i64,
+    pub expires_at: DateTime<Utc>,
+    /// Wrong codes entered so far
+    pub attempts: u32,
+}
+
+/// Longest that tokens signed with a rotated-out key stay valid
+pub const MAX_JWT_ROTATION_GRACE_HOURS: i64 = 168;
+
+/// Outcome of rotating the key session tokens are signed with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtKeyRotation {
+    /// Key ID new tokens carry
+    pub key_id: String,
+    /// Key ID of the replaced key
+    pub previous_key_id: String,
+    /// When tokens signed with the replaced key stop being accepted
+    pub previous_valid_until: DateTime<Utc>,
+}
+
+// =============================================================================
+// Audit Log Models
+// =============================================================================
+
+/// Audit entries returned when no limit is requested
+pub const DEFAULT_AUDIT_LOG_LIMIT: usize = 100;
+
+/// Largest accepted audit log limit
+pub const MAX_AUDIT_LOG_LIMIT: usize = 1000;
+
+/// Filters for querying the audit log; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditLogFilter {
+    pub user_id: Option<i64>,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<String>,
+    pub action: Option<String>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    /// Only failed (`false`) or successful (`true`) actions
+    pub success: Option<bool>,
+}
+
+// =============================================================================
+// Security Event Models
+// =============================================================================
+
+/// Security events returned when no limit is requested
+pub const DEFAULT_SECURITY_EVENT_LIMIT: usize = 100;
+
+/// Largest accepted security event limit
+pub const MAX_SECURITY_EVENT_LIMIT: usize = 1000;
+
+/// Kind of security event; unlike audit entries these record what was
+/// refused rather than what was done
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityEventKind {
+    /// Wrong credentials, or a sign-in refused for an inactive or locked account
+    FailedLogin,
+    /// A signed-in user lacked the permission a request needed
+    PermissionDenied,
+    /// Too many wrong passwords locked an account
+    AccountLocked,
+    /// A token that was forged, expired, revoked or replayed
+    TokenAnomaly,
+}
+
+impl std::fmt::Display for SecurityEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecurityEventKind::FailedLogin => write!(f, "failed_login"),
+            SecurityEventKind::PermissionDenied => write!(f, "permission_denied"),
+            SecurityEventKind::AccountLocked => write!(f, "account_locked"),
+            SecurityEventKind::TokenAnomaly => write!(f, "token_anomaly"),
+        }
+    }
+}
+
+impl std::str::FromStr for SecurityEventKind {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "failed_login" => Ok(SecurityEventKind::FailedLogin),
+            "permission_denied" => Ok(SecurityEventKind::PermissionDenied),
+            "account_locked" => Ok(SecurityEventKind::AccountLocked),
+            "token_anomaly" => Ok(SecurityEventKind::TokenAnomaly),
+            _ => Err(AppError::validation("kind", format!("Invalid security event kind: {}", s))),
+        }
+    }
+}
+
+/// Refused sign-in, denied request or suspicious token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityEvent {
+    pub id: String,
+    pub kind: SecurityEventKind,
+    /// Account involved, when it is known
+    pub user_id: Option<i64>,
+    /// Name the account signed in or tried to sign in with
+    pub username: Option<String>,
+    pub request_id: Option<String>,
+    pub detail: String,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl SecurityEvent {
+    pub fn new(kind: SecurityEventKind, detail: impl Into<String>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind,
+            user_id: None,
+            username: None,
+            request_id: None,
+            detail: detail.into(),
+            occurred_at: Utc::now(),
+        }
+    }
+
+    pub fn with_user_id(mut self, user_id: i64) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    pub fn with_username(mut self, username: impl Into<String>) -> Self {
+        self.username = Some(username.into());
+        self
+    }
+
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+}
+
+/// Filters for querying security events; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SecurityEventFilter {
+    pub kind: Option<SecurityEventKind>,
+    pub user_id: Option<i64>,
+    /// Matches the name exactly, ignoring case
+    pub username: Option<String>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+}
+
+// =============================================================================
+// Login History Models
+// =============================================================================
+
+/// Login history entries returned when no limit is requested
+pub const DEFAULT_LOGIN_HISTORY_LIMIT: usize = 50;
+
+/// Largest accepted login history limit
+pub const MAX_LOGIN_HISTORY_LIMIT: usize = 500;
+
+/// Longest device name kept with a login
+pub const MAX_LOGIN_DEVICE_NAME_LENGTH: usize = 100;
+
+/// How someone signed in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginMethod {
+    Password,
+    SingleSignOn,
+    /// Authenticator or backup code after a password or single sign-on
+    SecondFactor,
+    Kiosk,
+    /// PIN or badge on a trusted device
+    DeviceUnlock,
+}
+
+impl std::fmt::Display for LoginMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoginMethod::Password => write!(f, "password"),
+            LoginMethod::SingleSignOn => write!(f, "single_sign_on"),
+            LoginMethod::SecondFactor => write!(f, "second_factor"),
+            LoginMethod::Kiosk => write!(f, "kiosk"),
+            LoginMethod::DeviceUnlock => write!(f, "device_unlock"),
+        }
+    }
+}
+
+impl std::str::FromStr for LoginMethod {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "password" => Ok(LoginMethod::Password),
+            "single_sign_on" => Ok(LoginMethod::SingleSignOn),
+            "second_factor" => Ok(LoginMethod::SecondFactor),
+            "kiosk" => Ok(LoginMethod::Kiosk),
+            "device_unlock" => Ok(LoginMethod::DeviceUnlock),
+            _ => Err(AppError::validation("method", format!("Invalid login method: {}", s))),
+        }
+    }
+}
+
+/// Device a login came from, as reported by the client
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoginDevice {
+    /// Stable identifier of the installation
+    pub client_id: Option<String>,
+    /// Name shown in the login history, e.g. "Inspection tablet 3"
+    pub name: Option<String>,
+}
+
+impl LoginDevice {
+    /// Device with blank fields dropped and the name cut to length
+    pub fn normalized(&self) -> Self {
+        let clean = |value: &Option<String>| {
+            value.as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(|value| value.chars().take(MAX_LOGIN_DEVICE_NAME_LENGTH).collect())
+        };
+        Self { client_id: clean(&self.client_id), name: clean(&self.name) }
+    }
+}
+
+/// A login attempt to record; failures leave `failure_reason` set
+#[derive(Debug, Clone)]
+pub struct LoginAttempt {
+    /// Looked up from `username` when not known
+    pub user_id: Option<i64>,
+    /// Name typed at sign-in; the account's own name is stored
+    pub username: String,
+    pub method: LoginMethod,
+    pub device: LoginDevice,
+    pub failure_reason: Option<String>,
+}
+
+/// One login to an account, successful or not
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginHistoryEntry {
+    pub id: i64,
+    pub user_id: i64,
+    pub username: String,
+    pub method: LoginMethod,
+    pub client_id: Option<String>,
+    pub device_name: Option<String>,
+    pub success: bool,
+    pub failure_reason: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Filters for querying login history; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoginHistoryFilter {
+    pub user_id: Option<i64>,
+    pub success: Option<bool>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+}
+
+// =============================================================================
+// Settings Models
+// =============================================================================
+
+/// Session length used until an administrator changes it
+pub const DEFAULT_SESSION_DURATION_HOURS: i64 = 8;
+
+/// Longest session length that can be configured
+pub const MAX_SESSION_DURATION_HOURS: i64 = 24 * 7;
+
+/// Hours a login can be kept alive with refresh tokens until an
+/// administrator changes it
+pub const DEFAULT_REFRESH_TOKEN_LIFETIME_HOURS: i64 = 24 * 14;
+
+/// Longest refresh token lifetime that can be configured
+pub const MAX_REFRESH_TOKEN_LIFETIME_HOURS: i64 = 24 * 90;
+
+/// Standard new inspections follow when none is given
+pub const DEFAULT_COMPLIANCE_STANDARD: &str = "OSHA_1910_179";
+
+/// Directory uploaded media is stored under
+pub const DEFAULT_MEDIA_STORAGE_PATH: &str = "./data";
+
+/// Days generated report files are kept
+pub const DEFAULT_REPORT_RETENTION_DAYS: i64 = 365;
+
+/// Hours between scheduled data warehouse extracts
+pub const DEFAULT_WAREHOUSE_EXPORT_INTERVAL_HOURS: i64 = 24;
+
+/// Wrong passwords in a row that lock an account
+pub const DEFAULT_LOCKOUT_THRESHOLD: i64 = 5;
+
+/// Most wrong passwords that can be allowed before an account locks
+pub const MAX_LOCKOUT_THRESHOLD: i64 = 100;
+
+/// Minutes a locked account stays locked unless an administrator unlocks it
+pub const DEFAULT_LOCKOUT_DURATION_MINUTES: i64 = 15;
+
+/// Longest lockout that can be configured
+pub const MAX_LOCKOUT_DURATION_MINUTES: i64 = 24 * 60;
+
+/// Days security events and login history are kept
+pub const DEFAULT_SECURITY_EVENT_RETENTION_DAYS: i64 = 90;
+
+/// Seconds to wait for the LDAP directory before giving up on a sign-in
+pub const DEFAULT_LDAP_TIMEOUT_SECONDS: u64 = 10;
+
+/// Longest LDAP timeout that can be configured
+pub const MAX_LDAP_TIMEOUT_SECONDS: u64 = 60;
+
+/// Where an account's password is checked
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthSource {
+    /// Bcrypt hash stored with the account
+    Local,
+    /// Bind to the LDAP directory; the account is created and kept up to
+    /// date from the directory on each sign-in
+    Ldap,
+    /// Single sign-on through an OpenID Connect provider; the account is
+    /// linked to the provider's subject identifier
+    Oidc,
+}
+
+impl std::fmt::Display for AuthSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuthSource::Local => write!(f, "local"),
+            AuthSource::Ldap => write!(f, "ldap"),
+            AuthSource::Oidc => write!(f, "oidc"),
+        }
+    }
+}
+
+impl std::str::FromStr for AuthSource {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "local" => Ok(AuthSource::Local),
+            "ldap" => Ok(AuthSource::Ldap),
+            "oidc" => Ok(AuthSource::Oidc),
+            _ => Err(AppError::validation("auth_source", format!("Invalid authentication source: {}", s))),
+        }
+    }
+}
+
+/// Directory group whose members get a role
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LdapGroupRole {
+    /// Distinguished name of the group as listed in `memberOf`
+    pub group_dn: String,
+    pub role: UserRole,
+}
+
+/// Connection to an LDAP directory such as Active Directory for signing in
+/// with domain accounts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct LdapConfig {
+    pub enabled: bool,
+    /// `ldap://host[:port]` or `ldaps://host[:port]`
+    pub url: String,
+    /// Name to bind as, with `{username}` replaced by the name typed at
+    /// sign-in, e.g. `{username}@corp.example.com` for Active Directory or
+    /// `uid={username},ou=people,dc=example,dc=com`
+    pub bind_dn_template: String,
+    /// Subtree searched for the user's entry
+    pub search_base: String,
+    /// Attribute holding the sign-in name
+    pub username_attribute: String,
+    /// Members of more than one group get the highest of their roles
+    pub group_roles: Vec<LdapGroupRole>,
+    /// Role of users in none of the mapped groups; without one they are
+    /// refused
+    pub default_role: Option<UserRole>,
+    pub timeout_seconds: u64,
+}
+
+impl Default for LdapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            bind_dn_template: String::new(),
+            search_base: String::new(),
+            username_attribute: "sAMAccountName".to_string(),
+            group_roles: Vec::new(),
+            default_role: None,
+            timeout_seconds: DEFAULT_LDAP_TIMEOUT_SECONDS,
+        }
+    }
+}
+
+impl LdapConfig {
+    /// Role for a member of `groups`, or `None` if the user may not sign in
+    pub fn role_for_groups(&self, groups: &[String]) -> Option<UserRole> {
+        self.group_roles.iter()
+            .filter(|mapping| groups.iter().any(|group| group.eq_ignore_ascii_case(mapping.group_dn.trim())))
+            .map(|mapping| mapping.role.clone())
+            .max_by_key(UserRole::rank)
+            .or_else(|| self.default_role.clone())
+    }
+}
+
+impl Validate for LdapConfig {
+    fn validate(&self) -> AppResult<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let url = self.url.trim();
+        if !(url.starts_with("ldap://") || url.starts_with("ldaps://")) {
+            return Err(AppError::validation("url", "Directory URL must start with ldap:// or ldaps://"));
+        }
+        if !self.bind_dn_template.contains("{username}") {
+            return Err(AppError::validation("bind_dn_template", "Bind name must contain {username}"));
+        }
+        if self.search_base.trim().is_empty() {
+            return Err(AppError::validation("search_base", "Search base cannot be empty"));
+        }
+        if self.username_attribute.trim().is_empty() {
+            return Err(AppError::validation("username_attribute", "Username attribute cannot be empty"));
+        }
+        if self.group_roles.iter().any(|mapping| mapping.group_dn.trim().is_empty()) {
+            return Err(AppError::validation("group_roles", "Group names cannot be empty"));
+        }
+        if self.group_roles.is_empty() && self.default_role.is_none() {
+            return Err(AppError::validation("group_roles", "Map at least one group to a role or set a default role"));
+        }
+        if !(1..=MAX_LDAP_TIMEOUT_SECONDS).contains(&self.timeout_seconds) {
+            return Err(AppError::validation("timeout_seconds", format!(
+                "Timeout must be between 1 and {} seconds", MAX_LDAP_TIMEOUT_SECONDS
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// How long the browser sign-in may take before the app stops waiting
+pub const DEFAULT_OIDC_LOGIN_TIMEOUT_SECONDS: u64 = 300;
+
+/// Largest accepted browser sign-in timeout, in seconds
+pub const MAX_OIDC_LOGIN_TIMEOUT_SECONDS: u64 = 1800;
+
+/// Single sign-on with an OpenID Connect provider such as Entra ID, Okta or
+/// Keycloak. The app signs in as a native client: the system browser opens
+/// the provider's sign-in page and the authorization code comes back to a
+/// loopback address, exchanged with PKCE.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OidcConfig {
+    pub enabled: bool,
+    /// Issuer URL; the provider's metadata is read from
+    /// `{issuer_url}/.well-known/openid-configuration`
+    pub issuer_url: String,
+    pub client_id: String,
+    /// Only for providers that insist on a secret from native clients
+    pub client_secret: Option<String>,
+    /// Scopes requested besides `openid`
+    pub scopes: Vec<String>,
+    /// Port of the loopback redirect `http://127.0.0.1:{port}/callback`.
+    /// `None` picks a free port, which providers following RFC 8252 allow.
+    pub redirect_port: Option<u16>,
+    /// Claim holding the sign-in name of new accounts; `email` is used when
+    /// it is missing
+    pub username_claim: String,
+    /// Create an account with `default_role` the first time someone signs
+    /// in. Without it, an administrator must link accounts beforehand.
+    pub auto_provision: bool,
+    pub default_role: UserRole,
+    pub login_timeout_seconds: u64,
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            issuer_url: String::new(),
+            client_id: String::new(),
+            client_secret: None,
+            scopes: vec!["profile".to_string(), "email".to_string()],
+            redirect_port: None,
+            username_claim: "preferred_username".to_string(),
+            auto_provision: true,
+            default_role: UserRole::Inspector,
+            login_timeout_seconds: DEFAULT_OIDC_LOGIN_TIMEOUT_SECONDS,
+        }
+    }
+}
+
+impl Validate for OidcConfig {
+    fn validate(&self) -> AppResult<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let issuer = self.issuer_url.trim();
+        let local = issuer.starts_with("http://localhost") || issuer.starts_with("http://127.0.0.1");
+        if !(issuer.starts_with("https://") || local) {
+            return Err(AppError::validation("issuer_url", "Issuer URL must start with https://"));
+        }
+        if self.client_id.trim().is_empty() {
+            return Err(AppError::validation("client_id", "Client ID cannot be empty"));
+        }
+        if self.scopes.iter().any(|scope| scope.trim().is_empty() || scope.contains(char::is_whitespace)) {
+            return Err(AppError::validation("scopes", "Scopes must be single words"));
+        }
+        if self.username_claim.trim().is_empty() {
+            return Err(AppError::validation("username_claim", "Username claim cannot be empty"));
+        }
+        if self.redirect_port == Some(0) {
+            return Err(AppError::validation("redirect_port", "Redirect port cannot be 0"));
+        }
+        if !(30..=MAX_OIDC_LOGIN_TIMEOUT_SECONDS).contains(&self.login_timeout_seconds) {
+            return Err(AppError::validation("login_timeout_seconds", format!(
+                "Sign-in timeout must be between 30 and {} seconds", MAX_OIDC_LOGIN_TIMEOUT_SECONDS
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Days after an inspection its condition rating is fully trusted
+pub const DEFAULT_CONDITION_GRACE_DAYS: i64 = 30;
+
+/// Days past the grace period over which trust in a rating halves
+pub const DEFAULT_CONDITION_HALF_LIFE_DAYS: i64 = 180;
+
+/// Confidence below which a condition rating is flagged stale
+pub const DEFAULT_CONDITION_STALE_BELOW: f64 = 0.5;
+
+/// How confidence in an asset's last condition rating falls as the rating
+/// ages. It stays at 1 through the grace period and then halves every
+/// half-life, so a "Good" crane last looked at two years ago is shown as
+/// a stale rating rather than a good crane.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ConditionDecayConfig {
+    pub grace_days: i64,
+    pub half_life_days: i64,
+    /// Confidence, above 0 and at most 1, below which a rating is stale
+    pub stale_below: f64,
+}
+
+impl Default for ConditionDecayConfig {
+    fn default() -> Self {
+        Self {
+            grace_days: DEFAULT_CONDITION_GRACE_DAYS,
+            half_life_days: DEFAULT_CONDITION_HALF_LIFE_DAYS,
+            stale_below: DEFAULT_CONDITION_STALE_BELOW,
+        }
+    }
+}
+
+impl ConditionDecayConfig {
+    /// Confidence from 0 to 1 in a rating given at `rated_at`; an asset
+    /// never rated has none
+    pub fn confidence(&self, rated_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> f64 {
+        let Some(rated_at) = rated_at else {
+            return 0.0;
+        };
+        let days = (now - rated_at).num_seconds() as f64 / 86_400.0 - self.grace_days as f64;
+        if days <= 0.0 {
+            return 1.0;
+        }
+        0.5_f64.powf(days / self.half_life_days as f64)
+    }
+
+    /// Whether a rating with this confidence should be taken with suspicion
+    pub fn is_stale(&self, confidence: f64) -> bool {
+        confidence < self.stale_below
+    }
+}
+
+impl Validate for ConditionDecayConfig {
+    fn validate(&self) -> AppResult<()> {
+        if self.grace_days < 0 {
+            return Err(AppError::validation("grace_days", "Grace period cannot be negative"));
+        }
+        if self.half_life_days < 1 {
+            return Err(AppError::validation("half_life_days", "Half-life must be at least one day"));
+        }
+        if !(self.stale_below > 0.0 && self.stale_below <= 1.0) {
+            return Err(AppError::validation("stale_below", "Stale threshold must be above 0 and at most 1"));
+        }
+        Ok(())
+    }
+}
+
+/// Application-wide setting that administrators can change at runtime
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingKey {
+    /// Lifetime of an access token; the session continues past it while
+    /// the client refreshes
+    SessionDurationHours,
+    /// How long after login a session can be refreshed without signing in
+    /// again
+    RefreshTokenLifetimeHours,
+    DefaultComplianceStandard,
+    /// Changing the path does not move media already uploaded
+    MediaStoragePath,
+    ReportRetentionDays,
+    /// Directory scheduled data warehouse extracts are written to; empty
+    /// turns the scheduled export off
+    WarehouseExportDirectory,
+    WarehouseExportIntervalHours,
+    /// Sign-in with directory accounts; local accounts keep working
+    LdapDirectory,
+    /// Single sign-on through an OpenID Connect provider
+    OidcProvider,
+    /// Wrong passwords in a row that lock a local account
+    LockoutThreshold,
+    /// Minutes a locked account stays locked
+    LockoutDurationMinutes,
+    /// Days security events and login history are kept
+    SecurityEventRetentionDays,
+    /// How fast confidence in asset condition ratings falls between
+    /// inspections
+    ConditionDecay,
+}
+
+impl SettingKey {
+    pub const ALL: [SettingKey; 13] = [
+        SettingKey::SessionDurationHours,
+        SettingKey::RefreshTokenLifetimeHours,
+        SettingKey::DefaultComplianceStandard,
+        SettingKey::MediaStoragePath,
+        SettingKey::ReportRetentionDays,
+        SettingKey::WarehouseExportDirectory,
+        SettingKey::WarehouseExportIntervalHours,
+        SettingKey::LdapDirectory,
+        SettingKey::OidcProvider,
+        SettingKey::LockoutThreshold,
+        SettingKey::LockoutDurationMinutes,
+        SettingKey::SecurityEventRetentionDays,
+        SettingKey::ConditionDecay,
+    ];
+
+    /// Key the setting is stored under
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SettingKey::SessionDurationHours => "session_duration_hours",
+            SettingKey::RefreshTokenLifetimeHours => "refresh_token_lifetime_hours",
+            SettingKey::DefaultComplianceStandard => "default_compliance_standard",
+            SettingKey::MediaStoragePath => "media_storage_path",
+            SettingKey::ReportRetentionDays => "report_retention_days",
+            SettingKey::WarehouseExportDirectory => "warehouse_export_directory",
+            SettingKey::WarehouseExportIntervalHours => "warehouse_export_interval_hours",
+            SettingKey::LdapDirectory => "ldap_directory",
+            SettingKey::OidcProvider => "oidc_provider",
+            SettingKey::LockoutThreshold => "lockout_threshold",
+            SettingKey::LockoutDurationMinutes => "lockout_duration_minutes",
+            SettingKey::SecurityEventRetentionDays => "security_event_retention_days",
+            SettingKey::ConditionDecay => "condition_decay",
+        }
+    }
+
+    /// Check a new value has the setting's type and range
+    pub fn validate_value(&self, value: &JsonValue) -> AppResult<()> {
+        let field = self.as_str();
+        match self {
+            SettingKey::SessionDurationHours => match value.as_i64() {
+                Some(hours) if (1..=MAX_SESSION_DURATION_HOURS).contains(&hours) => Ok(()),
+                _ => Err(AppError::validation(field, format!(
+                    "Session duration must be a whole number of hours between 1 and {}", MAX_SESSION_DURATION_HOURS
+                ))),
+            },
+            SettingKey::RefreshTokenLifetimeHours => match value.as_i64() {
+                Some(hours) if (1..=MAX_REFRESH_TOKEN_LIFETIME_HOURS).contains(&hours) => Ok(()),
+                _ => Err(AppError::validation(field, format!(
+                    "Refresh token lifetime must be a whole number of hours between 1 and {}", MAX_REFRESH_TOKEN_LIFETIME_HOURS
+                ))),
+            },
+            SettingKey::ReportRetentionDays => match value.as_i64() {
+                Some(days) if days >= 1 => Ok(()),
+                _ => Err(AppError::validation(field, "Report retention must be a whole number of days of at least 1")),
+            },
+            SettingKey::SecurityEventRetentionDays => match value.as_i64() {
+                Some(days) if days >= 1 => Ok(()),
+                _ => Err(AppError::validation(field, "Security event retention must be a whole number of days of at least 1")),
+            },
+            SettingKey::WarehouseExportIntervalHours => match value.as_i64() {
+                Some(hours) if hours >= 1 => Ok(()),
+                _ => Err(AppError::validation(field, "Export interval must be a whole number of hours of at least 1")),
+            },
+            SettingKey::LockoutThreshold => match value.as_i64() {
+                Some(attempts) if (1..=MAX_LOCKOUT_THRESHOLD).contains(&attempts) => Ok(()),
+                _ => Err(AppError::validation(field, format!(
+                    "Lockout threshold must be a whole number of attempts between 1 and {}", MAX_LOCKOUT_THRESHOLD
+                ))),
+            },
+            SettingKey::LockoutDurationMinutes => match value.as_i64() {
+                Some(minutes) if (1..=MAX_LOCKOUT_DURATION_MINUTES).contains(&minutes) => Ok(()),
+                _ => Err(AppError::validation(field, format!(
+                    "Lockout duration must be a whole number of minutes between 1 and {}", MAX_LOCKOUT_DURATION_MINUTES
+                ))),
+            },
+            SettingKey::WarehouseExportDirectory => match value.as_str() {
+                Some(_) => Ok(()),
+                None => Err(AppError::validation(field, "Value must be a string")),
+            },
+            SettingKey::DefaultComplianceStandard | SettingKey::MediaStoragePath => match value.as_str() {
+                Some(text) if !text.trim().is_empty() => Ok(()),
+                _ => Err(AppError::validation(field, "Value must be a non-empty string")),
+            },
+            SettingKey::LdapDirectory => serde_json::from_value::<LdapConfig>(value.clone())
+                .map_err(|e| AppError::validation(field, format!("Invalid directory settings: {}", e)))?
+                .validate(),
+            SettingKey::OidcProvider => serde_json::from_value::<OidcConfig>(value.clone())
+                .map_err(|e| AppError::validation(field, format!("Invalid single sign-on settings: {}", e)))?
+                .validate(),
+            SettingKey::ConditionDecay => serde_json::from_value::<ConditionDecayConfig>(value.clone())
+                .map_err(|e| AppError::validation(field, format!("Invalid condition decay settings: {}", e)))?
+                .validate(),
+        }
+    }
+}
+
+impl std::fmt::Display for SettingKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for SettingKey {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SettingKey::ALL
+            .into_iter()
+            .find(|key| key.as_str() == s)
+            .ok_or_else(|| AppError::validation("key", format!("Invalid setting key: {}", s)))
+    }
+}
+
+/// Current value of every application setting; settings never changed keep
+/// their defaults
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppSettings {
+    pub session_duration_hours: i64,
+    pub refresh_token_lifetime_hours: i64,
+    pub default_compliance_standard: String,
+    pub media_storage_path: String,
+    pub report_retention_days: i64,
+    pub warehouse_export_directory: String,
+    pub warehouse_export_interval_hours: i64,
+    pub ldap_directory: LdapConfig,
+    pub oidc_provider: OidcConfig,
+    pub lockout_threshold: i64,
+    pub lockout_duration_minutes: i64,
+    pub security_event_retention_days: i64,
+    pub condition_decay: ConditionDecayConfig,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            session_duration_hours: DEFAULT_SESSION_DURATION_HOURS,
+            refresh_token_lifetime_hours: DEFAULT_REFRESH_TOKEN_LIFETIME_HOURS,
+            default_compliance_standard: DEFAULT_COMPLIANCE_STANDARD.to_string(),
+            media_storage_path: DEFAULT_MEDIA_STORAGE_PATH.to_string(),
+            report_retention_days: DEFAULT_REPORT_RETENTION_DAYS,
+            warehouse_export_directory: String::new(),
+            warehouse_export_interval_hours: DEFAULT_WAREHOUSE_EXPORT_INTERVAL_HOURS,
+            ldap_directory: LdapConfig::default(),
+            oidc_provider: OidcConfig::default(),
+            lockout_threshold: DEFAULT_LOCKOUT_THRESHOLD,
+            lockout_duration_minutes: DEFAULT_LOCKOUT_DURATION_MINUTES,
+            security_event_retention_days: DEFAULT_SECURITY_EVENT_RETENTION_DAYS,
+            condition_decay: ConditionDecayConfig::default(),
+        }
+    }
+}
+
+impl AppSettings {
+    /// Overwrite one setting with its stored JSON value
+    pub fn apply(&mut self, key: SettingKey, value: JsonValue) -> AppResult<()> {
+        key.validate_value(&value)?;
+        match key {
+            SettingKey::SessionDurationHours => self.session_duration_hours = serde_json::from_value(value)?,
+            SettingKey::RefreshTokenLifetimeHours => self.refresh_token_lifetime_hours = serde_json::from_value(value)?,
+            SettingKey::DefaultComplianceStandard => self.default_compliance_standard = serde_json::from_value(value)?,
+            SettingKey::MediaStoragePath => self.media_storage_path = serde_json::from_value(value)?,
+            SettingKey::ReportRetentionDays => self.report_retention_days = serde_json::from_value(value)?,
+            SettingKey::WarehouseExportDirectory => self.warehouse_export_directory = serde_json::from_value(value)?,
+            SettingKey::WarehouseExportIntervalHours => self.warehouse_export_interval_hours = serde_json::from_value(value)?,
+            SettingKey::LdapDirectory => self.ldap_directory = serde_json::from_value(value)?,
+            SettingKey::OidcProvider => self.oidc_provider = serde_json::from_value(value)?,
+            SettingKey::LockoutThreshold => self.lockout_threshold = serde_json::from_value(value)?,
+            SettingKey::LockoutDurationMinutes => self.lockout_duration_minutes = serde_json::from_value(value)?,
+            SettingKey::SecurityEventRetentionDays => self.security_event_retention_days = serde_json::from_value(value)?,
+            SettingKey::ConditionDecay => self.condition_decay = serde_json::from_value(value)?,
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Custom Report Template Models
+// =============================================================================
+
+/// Longest accepted report template name
+pub const MAX_REPORT_TEMPLATE_NAME_LENGTH: usize = 100;
+
+/// Largest accepted report template, in bytes
+pub const MAX_REPORT_TEMPLATE_SIZE: usize = 256 * 1024;
+
+/// Report a custom template can lay out as HTML
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomReportType {
+    Inspection,
+    Compliance,
+}
+
+impl std::fmt::Display for CustomReportType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CustomReportType::Inspection => write!(f, "inspection"),
+            CustomReportType::Compliance => write!(f, "compliance"),
+        }
+    }
+}
+
+impl std::str::FromStr for CustomReportType {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "inspection" => Ok(CustomReportType::Inspection),
+            "compliance" => Ok(CustomReportType::Compliance),
+            _ => Err(AppError::validation("report_type", format!("Invalid report type: {}", s))),
+        }
+    }
+}
+
+/// An HTML report layout written in Handlebars. It is rendered with the
+/// same data the JSON report of its type holds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomReportTemplate {
+    pub id: i64,
+    pub name: String,
+    pub report_type: CustomReportType,
+    pub description: Option<String>,
+    pub template: String,
+    /// Whether HTML reports of its type are currently rendered with it
+    pub is_assigned: bool,
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A new or edited report template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomReportTemplateInput {
+    pub name: String,
+    pub report_type: CustomReportType,
+    pub description: Option<String>,
+    pub template: String,
+}
+
+impl Validate for CustomReportTemplateInput {
+    fn validate(&self) -> AppResult<()> {
+        let name = self.name.trim();
+        if name.is_empty() || name.len() > MAX_REPORT_TEMPLATE_NAME_LENGTH {
+            return Err(AppError::validation(
+                "name",
+                format!("Template name must be 1-{} characters", MAX_REPORT_TEMPLATE_NAME_LENGTH),
+            ));
+        }
+        if self.template.trim().is_empty() {
+            return Err(AppError::RequiredField { field: "template".to_string() });
+        }
+        if self.template.len() > MAX_REPORT_TEMPLATE_SIZE {
+            return Err(AppError::validation(
+                "template",
+                format!("Template cannot exceed {} KB", MAX_REPORT_TEMPLATE_SIZE / 1024),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Checklist Pack Models
+// =============================================================================
+
+/// Longest accepted name of a pack or trusted publisher
+pub const MAX_PACK_NAME_LENGTH: usize = 100;
+
+/// A publisher whose signed checklist packs this installation imports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedPackPublisher {
+    pub id: i64,
+    pub name: String,
+    /// Hex Ed25519 key the publisher signs packs with
+    pub public_key: String,
+    pub fingerprint: String,
+    pub added_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A publisher to trust, by the public key they gave out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedPackPublisherInput {
+    pub name: String,
+    pub public_key: String,
+}
+
+impl Validate for TrustedPackPublisherInput {
+    fn validate(&self) -> AppResult<()> {
+        let name = self.name.trim();
+        if name.is_empty() || name.len() > MAX_PACK_NAME_LENGTH {
+            return Err(AppError::validation(
+                "name",
+                format!("Publisher name must be 1-{} characters", MAX_PACK_NAME_LENGTH),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// This installation's pack signing key, for customers to trust
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackSigningIdentity {
+    pub public_key: String,
+    pub fingerprint: String,
+}
+
+/// What to put in an exported checklist pack: the standards, with their
+/// clauses, checklist templates, frequency rules and reference images
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackExportRequest {
+    pub name: String,
+    pub version: String,
+    pub publisher: String,
+    pub description: Option<String>,
+    pub standard_ids: Vec<i64>,
+}
+
+impl Validate for PackExportRequest {
+    fn validate(&self) -> AppResult<()> {
+        for (field, value) in [("name", &self.name), ("publisher", &self.publisher)] {
+            let value = value.trim();
+            if value.is_empty() || value.len() > MAX_PACK_NAME_LENGTH {
+                return Err(AppError::validation(
+                    field,
+                    format!("Pack {} must be 1-{} characters", field, MAX_PACK_NAME_LENGTH),
+                ));
+            }
+        }
+        if self.version.trim().is_empty() {
+            return Err(AppError::RequiredField { field: "version".to_string() });
+        }
+        if self.standard_ids.is_empty() {
+            return Err(AppError::validation("standard_ids", "Choose at least one standard to export"));
+        }
+        Ok(())
+    }
+}
+
+/// What importing a checklist pack added or replaced
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackImportSummary {
+    pub pack_name: String,
+    pub pack_version: String,
+    pub publisher: String,
+    pub publisher_fingerprint: String,
+    pub standards_created: usize,
+    pub standards_updated: usize,
+    pub clauses: usize,
+    pub templates: usize,
+    pub frequency_rules: usize,
+    pub reference_images: usize,
+}
+
+/// A checklist pack written to disk, and the key customers need to trust
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackExportResult {
+    pub file_path: String,
+    pub publisher_fingerprint: String,
+    pub standards: usize,
+    pub templates: usize,
+    pub frequency_rules: usize,
+    pub reference_images: usize,
+}
+
+// =============================================================================
+// Helper Types and Utilities
+// =============================================================================
+
+/// Common query filters for database operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryFilter {
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<SortOrder>,
+    pub filters: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl std::fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortOrder::Asc => write!(f, "ASC"),
+            SortOrder::Desc => write!(f, "DESC"),
+        }
+    }
+}
+
+impl Default for QueryFilter {
+    fn default() -> Self {
+        Self {
+            page: Some(1),
+            limit: Some(50),
+            sort_by: None,
+            sort_order: Some(SortOrder::Desc),
+            filters: HashMap::new(),
+        }
+    }
+}
+
+/// Database operation result with pagination metadata
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PaginatedResult<T> {
+    pub data: Vec<T>,
+    pub total_count: i64,
+    pub page: i64,
+    pub limit: i64,
+    pub total_pages: i64,
+}
+
+impl<T> PaginatedResult<T> {
+    pub fn new(data: Vec<T>, total_count: i64, page: i64, limit: i64) -> Self {
+        let total_pages = (total_count + limit - 1) / limit; // Ceiling division
+        Self {
+            data,
+            total_count,
+            page,
+            limit,
+            total_pages,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_change_diff_skips_ignored_columns() {
+        let before = serde_json::json!({"id": 1, "status": "Active", "notes": null, "updated_at": "a"});
+        let after = serde_json::json!({"id": 1, "status": "Maintenance", "notes": "Brake worn", "updated_at": "b"});
+        let changes = FieldChange::diff(before.as_object().unwrap(), after.as_object().unwrap());
+        let fields: Vec<_> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, ["notes", "status"]);
+        assert_eq!(changes[1].before, "Active");
+        assert!(FieldChange::diff(after.as_object().unwrap(), after.as_object().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_user_role_parsing() {
+        assert_eq!("Inspector".parse::<UserRole>().unwrap(), UserRole::Inspector);
+        assert_eq!("Supervisor".parse::<UserRole>().unwrap(), UserRole::Supervisor);
+        assert!("InvalidRole".parse::<UserRole>().is_err());
+    }
+
+    #[test]
+    fn test_user_validation() {
+        let mut user = User {
+            id: 1,
+            username: "testuser".to_string(),
+            email: "test@example.com".to_string(),
+            password_hash: "hash".to_string(),
+            role: UserRole::Inspector,
+            first_name: "Test".to_string(),
+            last_name: "User".to_string(),
+            phone: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            is_active: true,
+        };
+
+        assert!(user.validate().is_ok());
+
+        user.email = "invalid-email".to_string();
+        assert!(user.validate().is_err());
+    }
+
+    #[test]
+    fn test_asset_status_parsing() {
+        assert_eq!("Active".parse::<AssetStatus>().unwrap(), AssetStatus::Active);
+        assert_eq!("Maintenance".parse::<AssetStatus>().unwrap(), AssetStatus::Maintenance);
+        assert!("InvalidStatus".parse::<AssetStatus>().is_err());
+    }
+
+    #[test]
+    fn test_user_absence_period() {
+        let date = |day: u32| NaiveDate::from_ymd_opt(2024, 7, day).unwrap();
+        let mut absence = UserAbsence {
+            id: 1,
+            user_id: 2,
+            absence_type: AbsenceType::Vacation,
+            start_date: date(1),
+            end_date: date(5),
+            notes: None,
+            created_by: 2,
+            created_at: Utc::now(),
+        };
+
+        assert!(absence.validate().is_ok());
+        assert_eq!(absence.days(), 5);
+        assert!(absence.is_long());
+        assert!(absence.covers(date(5)) && !absence.covers(date(6)));
+
+        absence.end_date = date(2);
+        assert!(!absence.is_long());
+        absence.end_date = date(1) - chrono::Duration::days(1);
+        assert!(absence.validate().is_err());
+    }
+
+    #[test]
+    fn test_extract_mentions() {
+        assert_eq!(
+            extract_mentions("@Supervisor can you check this? cc @j.smith. Email ops@example.com"),
+            vec!["supervisor".to_string(), "j.smith".to_string()]
+        );
+        assert_eq!(extract_mentions("@bob @BOB and @"), vec!["bob".to_string()]);
+    }
+
+    #[test]
+    fn test_bulk_operation_validation() {
+        let operation: BulkOperation = serde_json::from_str(r#"{"type":"SetStatus","status":"In Progress"}"#).unwrap();
+        assert!(operation.validate_for(BulkEntityType::Inspection).is_ok());
+        assert!(operation.validate_for(BulkEntityType::Asset).is_err());
+
+        assert!(BulkOperation::Delete.validate_for(BulkEntityType::Asset).is_ok());
+        assert!(BulkOperation::Delete.validate_for(BulkEntityType::Inspection).is_err());
+        assert!(BulkOperation::Tag { tags: vec![" ".to_string()] }.validate_for(BulkEntityType::Asset).is_err());
+    }
+
+    #[test]
+    fn test_location_tree_build() {
+        let location = |id: i64, name: &str, parent: Option<i64>| Location {
+            id,
+            name: name.to_string(),
+            address: None,
+            latitude: None,
+            longitude: None,
+            description: None,
+            parent_location_id: parent,
+            time_zone: "UTC".to_string(),
+            created_by: 1,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let rows = vec![
+            (location(1, "Plant", None), 2),
+            (location(2, "Bay B", Some(1)), 3),
+            (location(3, "Bay A", Some(1)), 1),
+            (location(4, "Rail", Some(3)), 4),
+            // Cycle between 5 and 6 is never reachable from a root
+            (location(5, "Loop 1", Some(6)), 1),
+            (location(6, "Loop 2", Some(5)), 1),
+        ];
+
+        let forest = LocationTreeNode::build(rows.clone(), None);
+        assert_eq!(forest.len(), 1);
+        let plant = &forest[0];
+        assert_eq!(plant.subtree_asset_count, 10);
+        assert_eq!(plant.children.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["Bay A", "Bay B"]);
+        assert_eq!(plant.children[0].children[0].depth, 2);
+
+        let subtree = LocationTreeNode::build(rows, Some(3));
+        assert_eq!(subtree.len(), 1);
+        assert_eq!(subtree[0].subtree_asset_count, 5);
+    }
+
+    #[test]
+    fn test_asset_record_validation() {
+        let mut record = AssetRecordInput {
+            kind: AssetRecordKind::Insurance,
+            reference_number: "POL-1234".to_string(),
+            issuer: Some("Acme Mutual".to_string()),
+            issued_date: NaiveDate::from_ymd_opt(2026, 1, 1),
+            renewal_date: NaiveDate::from_ymd_opt(2027, 1, 1).unwrap(),
+            notes: None,
+        };
+        assert!(record.validate().is_ok());
+
+        record.renewal_date = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap();
+        assert!(record.validate().is_err());
+
+        record.renewal_date = NaiveDate::from_ymd_opt(2027, 1, 1).unwrap();
+        record.reference_number = "  ".to_string();
+        assert!(record.validate().is_err());
+        assert_eq!("Registration".parse::<AssetRecordKind>().unwrap(), AssetRecordKind::Registration);
+    }
+
+    #[test]
+    fn test_training_record_qualifies() {
+        let record = TrainingRecord {
+            id: 1,
+            user_id: 2,
+            course: "Overhead crane inspector".to_string(),
+            provider: None,
+            issued_date: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            expiry_date: NaiveDate::from_ymd_opt(2027, 1, 1),
+            certificate_media_id: None,
+            qualifies_for: vec![InspectionType::Periodic],
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let in_force = NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+        assert!(record.qualifies(&InspectionType::Periodic, in_force));
+        assert!(!record.qualifies(&InspectionType::Initial, in_force));
+        assert!(record.qualifies(&InspectionType::Periodic, NaiveDate::from_ymd_opt(2027, 1, 1).unwrap()));
+        assert!(!record.qualifies(&InspectionType::Periodic, NaiveDate::from_ymd_opt(2027, 1, 2).unwrap()));
+        assert!(!record.qualifies(&InspectionType::Periodic, NaiveDate::from_ymd_opt(2025, 12, 31).unwrap()));
+    }
+
+    #[test]
+    fn test_work_order_status_transitions() {
+        use WorkOrderStatus::*;
+        assert!(Open.can_transition_to(InProgress));
+        assert!(InProgress.can_transition_to(Completed));
+        assert!(Completed.can_transition_to(Verified));
+        assert!(Completed.can_transition_to(InProgress));
+        assert!(!Open.can_transition_to(Completed));
+        assert!(!Verified.can_transition_to(InProgress));
+        assert!(!Completed.can_transition_to(Cancelled));
+        assert_eq!("In Progress".parse::<WorkOrderStatus>().unwrap(), InProgress);
+        assert_eq!(WorkOrderPriority::for_severity(Some(&Severity::Critical)), WorkOrderPriority::Urgent);
+        assert_eq!(WorkOrderPriority::for_severity(None), WorkOrderPriority::Normal);
+    }
+
+    #[test]
+    fn test_finding_sla_breach() {
+        let opened_at = Utc::now() - chrono::Duration::hours(10);
+        let mut sla = FindingSla {
+            item_id: 1,
+            inspection_id: 1,
+            asset_id: 1,
+            asset_number: "A1".to_string(),
+            location_id: 1,
+            item_name: "Hook latch".to_string(),
+            opened_at,
+            acknowledge_due_at: opened_at + chrono::Duration::hours(4),
+            acknowledged_at: Some(opened_at + chrono::Duration::hours(1)),
+            acknowledged_by: Some(1),
+            resolve_due_at: opened_at + chrono::Duration::hours(72),
+            resolved_at: None,
+            resolved_by: None,
+            resolution_notes: None,
+        };
+        assert!(!sla.is_breached(SlaStage::Acknowledge, Utc::now()));
+        assert!(!sla.is_breached(SlaStage::Resolve, Utc::now()));
+
+        sla.acknowledged_at = None;
+        assert!(sla.is_breached(SlaStage::Acknowledge, Utc::now()));
+        sla.acknowledged_at = Some(opened_at + chrono::Duration::hours(5));
+        assert!(sla.is_breached(SlaStage::Acknowledge, Utc::now()));
+    }
+
+    #[test]
+    fn test_part_usages_from_parts_used() {
+        let usages: Vec<PartUsage> = serde_json::from_value(serde_json::json!([
+            {"part_number": "WR-16", "quantity": 2}
+        ])).unwrap();
+        assert!(usages[0].validate().is_ok());
+        // Free-form notes are not a parts list
+        assert!(serde_json::from_value::<Vec<PartUsage>>(serde_json::json!("new rope and clips")).is_err());
+
+        let usage = PartUsage { part_number: "WR-16".to_string(), quantity: 0 };
+        assert!(usage.validate().is_err());
+    }
+
+    #[test]
+    fn test_validation_rule_applies_to() {
+        let rule = ValidationRule {
+            id: 1,
+            name: "Photo for serious findings".to_string(),
+            kind: ValidationRuleKind::PhotoRequired,
+            stage: RuleStage::Submission,
+            item_category: Some("Hooks".to_string()),
+            item_name: None,
+            min_severity: Some(Severity::High),
+            min_value: None,
+            max_value: None,
+            unit: None,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        assert!(rule.applies_to("hooks", "Throat opening", Some(&Severity::Critical)));
+        assert!(!rule.applies_to("Hooks", "Throat opening", Some(&Severity::Medium)));
+        assert!(!rule.applies_to("Hooks", "Throat opening", None));
+        assert!(!rule.applies_to("Wire Rope", "Diameter", Some(&Severity::High)));
+
+        let mut input = ValidationRuleInput {
+            name: "Throat opening".to_string(),
+            kind: ValidationRuleKind::MeasurementRange,
+            stage: RuleStage::Save,
+            item_category: None,
+            item_name: Some("Throat opening".to_string()),
+            min_severity: None,
+            min_value: Some(40.0),
+            max_value: Some(30.0),
+            unit: Some("mm".to_string()),
+            is_active: true,
+        };
+        assert!(input.validate().is_err());
+        input.max_value = Some(44.0);
+        assert!(input.validate().is_ok());
+        input.kind = ValidationRuleKind::PhotoRequired;
+        assert!(input.validate().is_err());
+    }
+
+    #[test]
+    fn test_corrective_action_status_transitions() {
+        use CorrectiveActionStatus::*;
+        assert!(Open.can_transition_to(InProgress));
+        assert!(InProgress.can_transition_to(PendingVerification));
+        assert!(PendingVerification.can_transition_to(Closed));
+        assert!(PendingVerification.can_transition_to(InProgress));
+        // Closure only through verification, and submitted work cannot be cancelled
+        assert!(!InProgress.can_transition_to(Closed));
+        assert!(!PendingVerification.can_transition_to(Cancelled));
+        assert!(!Closed.can_transition_to(InProgress));
+
+        for status in [Open, InProgress, PendingVerification, Closed, Cancelled] {
+            assert_eq!(status.to_string().parse::<CorrectiveActionStatus>().unwrap(), status);
+        }
+    }
+
+    #[test]
+    fn test_defect_status_transitions() {
+        assert!(DefectStatus::Open.can_transition_to(DefectStatus::Deferred));
+        assert!(DefectStatus::Deferred.can_transition_to(DefectStatus::Repaired));
+        assert!(DefectStatus::Repaired.can_transition_to(DefectStatus::Open));
+        assert!(!DefectStatus::Open.can_transition_to(DefectStatus::Verified));
+        assert!(!DefectStatus::Verified.can_transition_to(DefectStatus::Open));
+        assert!(!DefectStatus::Verified.is_active());
+    }
+
+    #[test]
+    fn test_app_settings_apply() {
+        let mut settings = AppSettings::default();
+        settings.apply(SettingKey::SessionDurationHours, serde_json::json!(12)).unwrap();
+        settings.apply(SettingKey::MediaStoragePath, serde_json::json!("/srv/media")).unwrap();
+        assert_eq!(settings.session_duration_hours, 12);
+        assert_eq!(settings.media_storage_path, "/srv/media");
+
+        assert!(settings.apply(SettingKey::SessionDurationHours, serde_json::json!(0)).is_err());
+        assert!(settings.apply(SettingKey::ReportRetentionDays, serde_json::json!("30")).is_err());
+        assert!(settings.apply(SettingKey::DefaultComplianceStandard, serde_json::json!(" ")).is_err());
+        assert_eq!("report_retention_days".parse::<SettingKey>().unwrap(), SettingKey::ReportRetentionDays);
+    }
+
+    #[test]
+    fn test_condition_confidence_decays_after_grace_period() {
+        let decay = ConditionDecayConfig::default();
+        let now = Utc::now();
+        let days_ago = |days: i64| Some(now - chrono::Duration::days(days));
+
+        assert_eq!(decay.confidence(days_ago(10), now), 1.0);
+        assert!((decay.confidence(days_ago(30 + 180), now) - 0.5).abs() < 1e-6);
+        assert!(decay.confidence(days_ago(730), now) < 0.1);
+        assert_eq!(decay.confidence(None, now), 0.0);
+        assert!(!decay.is_stale(decay.confidence(days_ago(100), now)));
+        assert!(decay.is_stale(decay.confidence(days_ago(400), now)));
+
+        let mut settings = AppSettings::default();
+        assert!(settings.apply(SettingKey::ConditionDecay, serde_json::json!({"half_life_days": 0})).is_err());
+        settings.apply(SettingKey::ConditionDecay, serde_json::json!({"half_life_days": 90})).unwrap();
+        assert_eq!(settings.condition_decay.half_life_days, 90);
+        assert_eq!(settings.condition_decay.grace_days, DEFAULT_CONDITION_GRACE_DAYS);
+    }
+
+    #[test]
+    fn test_heavier_duty_shortens_inspection_interval() {
+        for group in FemGroup::ALL {
+            assert_eq!(group.to_string().parse::<FemGroup>().unwrap(), group);
+        }
+        assert_eq!(serde_json::to_string(&FemGroup::Group1Am).unwrap(), "\"1Am\"");
+
+        assert_eq!(DutyLevel::of(Some(CmaaServiceClass::C), None), Some(DutyLevel::Moderate));
+        assert_eq!(DutyLevel::of(Some(CmaaServiceClass::D), Some(FemGroup::Group3m)), Some(DutyLevel::Severe));
+        assert_eq!(DutyLevel::of(None, None), None);
+
+        let periodic = InspectionType::Periodic.interval_days();
+        assert_eq!(DutyLevel::Moderate.scale_interval(periodic), periodic);
+        assert!(DutyLevel::Heavy.scale_interval(periodic) < periodic);
+        assert!(DutyLevel::Severe.scale_interval(periodic) < DutyLevel::Heavy.scale_interval(periodic));
+        assert_eq!(DutyLevel::Continuous.scale_interval(1), 1);
+
+        assert!(validate_classification(Some(CmaaServiceClass::D), Some(FemGroup::Group2m)).is_ok());
+        assert!(validate_classification(Some(CmaaServiceClass::A), None).is_ok());
+        assert!(validate_classification(Some(CmaaServiceClass::A), Some(FemGroup::Group4m)).is_err());
+    }
+
+    #[test]
+    fn test_risk_matrix_needs_every_cell() {
+        let severities = [Severity::Low, Severity::Medium, Severity::High, Severity::Critical];
+        let cells: Vec<RiskMatrixCell> = severities.iter()
+            .flat_map(|severity| AssetCriticality::ALL.map(|criticality| RiskMatrixCell {
+                severity: severity.clone(),
+                criticality,
+                rating: RiskRating::Medium,
+                response_hours: 24,
+            }))
+            .collect();
+        let mut matrix = RiskMatrix { cells, updated_by: None, updated_at: None };
+        assert!(matrix.validate().is_ok());
+        assert_eq!(matrix.cell(&Severity::High, AssetCriticality::Low).unwrap().response_hours, 24);
+
+        matrix.cells[3].response_hours = 0;
+        assert!(matrix.validate().is_err());
+        matrix.cells[3].response_hours = 24;
+        let duplicate = matrix.cells[0].clone();
+        matrix.cells[1] = duplicate;
+        assert!(matrix.validate().is_err());
+    }
+
+    #[test]
+    fn test_standard_clause_matching() {
+        assert!(same_standard("OSHA 1910.179", "OSHA_1910_179"));
+        assert!(same_standard("asme b30.2", "ASME_B30_2"));
+        assert!(!same_standard("OSHA 1910.179", "CMAA_75"));
+        assert!(!same_standard("", ""));
+
+        let clause = StandardClauseInput { clause_ref: " ".to_string(), title: "Hooks".to_string(), text: None };
+        assert!(clause.validate().is_err());
+        let clause = StandardClauseInput { clause_ref: "1910.179(j)(2)(iii)".to_string(), ..clause };
+        assert!(clause.validate().is_ok());
+    }
+
+    #[test]
+    fn test_share_link_input_and_activity() {
+        let input = ShareLinkInput {
+            report_id: "../secrets".to_string(),
+            recipient: None,
+            expires_in_hours: None,
+            max_views: None,
+        };
+        assert!(input.validate().is_err());
+        let input = ShareLinkInput { report_id: "inspection_7_20261017_101500".to_string(), ..input };
+        assert!(input.validate().is_ok());
+        assert!(ShareLinkInput { expires_in_hours: Some(MAX_SHARE_LINK_HOURS + 1), ..input.clone() }.validate().is_err());
+        assert!(ShareLinkInput { max_views: Some(0), ..input }.validate().is_err());
+
+        let now = Utc::now();
+        let mut link = ReportShareLink {
+            id: 1,
+            report_id: "inspection_7_20261017_101500".to_string(),
+            recipient: None,
+            expires_at: now + chrono::Duration::hours(1),
+            max_views: Some(2),
+            view_count: 1,
+            last_viewed_at: None,
+            created_by: 1,
+            created_at: now,
+            revoked_at: None,
+        };
+        assert!(link.is_active(now));
+        link.view_count = 2;
+        assert!(!link.is_active(now));
+        link.max_views = None;
+        assert!(!link.is_active(now + chrono::Duration::hours(2)));
+        link.revoked_at = Some(now);
+        assert!(!link.is_active(now));
+    }
+
+    #[test]
+    fn test_user_preferences_input_validation() {
+        let column = |key: &str| TableColumnLayout { key: key.to_string(), width: Some(120), hidden: false };
+        let mut input = UserPreferencesInput {
+            default_location_id: None,
+            landing_page: Some("/inspections?status=Scheduled".to_string()),
+            capacity_unit: CapacityUnit::Pound,
+            length_unit: LengthUnit::Inch,
+            notification_channels: vec![NotificationChannelKind::InApp],
+            table_layouts: HashMap::from([(
+                "assets".to_string(),
+                TableLayout { columns: vec![column("asset_number"), column("name")], ..Default::default() },
+            )]),
+        };
+        assert!(input.validate().is_ok());
+
+        for page in ["https://example.com", "//example.com", "/../etc", "inspections"] {
+            input.landing_page = Some(page.to_string());
+            assert!(input.validate().is_err(), "{} should be rejected", page);
+        }
+        input.landing_page = None;
+
+        let layout = input.table_layouts.get_mut("assets").unwrap();
+        layout.columns.push(column("name"));
+        let err = input.validate().unwrap_err();
+        assert!(matches!(err, AppError::Validation { ref field, .. } if field == "table_layouts.assets.columns[2].key"));
+
+        let layout = input.table_layouts.get_mut("assets").unwrap();
+        layout.columns.pop();
+        layout.page_size = Some(0);
+        assert!(input.validate().is_err());
+    }
+
+    #[test]
+    fn test_paginated_result() {
         let data = vec![1, 2, 3, 4, 5];
         let result = PaginatedResult::new(data, 25, 1, 5);
         
@@ -1058,4 +7247,47 @@ mod tests {
         assert_eq!(result.limit, 5);
         assert_eq!(result.total_pages, 5);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_ldap_group_roles() {
+        let mut config = LdapConfig {
+            enabled: true,
+            url: "ldaps://dc01.corp.example.com".to_string(),
+            bind_dn_template: "{username}@corp.example.com".to_string(),
+            search_base: "DC=corp,DC=example,DC=com".to_string(),
+            group_roles: vec![
+                LdapGroupRole { group_dn: "CN=Crane Inspectors,OU=Groups".to_string(), role: UserRole::Inspector },
+                LdapGroupRole { group_dn: "CN=Crane Admins,OU=Groups".to_string(), role: UserRole::Administrator },
+            ],
+            ..LdapConfig::default()
+        };
+        config.validate().unwrap();
+
+        let groups = vec!["cn=crane admins,ou=groups".to_string(), "CN=Crane Inspectors,OU=Groups".to_string()];
+        assert_eq!(config.role_for_groups(&groups), Some(UserRole::Administrator));
+        assert_eq!(config.role_for_groups(&["CN=Staff".to_string()]), None);
+        config.default_role = Some(UserRole::Inspector);
+        assert_eq!(config.role_for_groups(&[]), Some(UserRole::Inspector));
+
+        config.bind_dn_template = "CORP\\jsmith".to_string();
+        assert!(config.validate().is_err());
+        assert!(SettingKey::LdapDirectory.validate_value(&serde_json::json!({"enabled": true})).is_err());
+        assert!(SettingKey::LdapDirectory.validate_value(&serde_json::json!({"enabled": false})).is_ok());
+    }
+
+    #[test]
+    fn test_deep_link_parsing() {
+        let token = "0123456789ABCDEF0123456789abcdef";
+        assert_eq!(
+            format!("cranepro://asset/{}?utm_source=email", token).parse::<DeepLink>().unwrap(),
+            DeepLink::Asset { token: token.to_ascii_lowercase() },
+        );
+        assert_eq!("cranepro://inspection/1042/".parse::<DeepLink>().unwrap(), DeepLink::Inspection { id: 1042 });
+        assert_eq!(AssetLink::new(7, "abc").uri, "cranepro://asset/abc");
+
+        assert!("cranepro://asset/1042".parse::<DeepLink>().is_err());
+        assert!("https://asset/0123456789abcdef0123456789abcdef".parse::<DeepLink>().is_err());
+        assert!("cranepro://location/3".parse::<DeepLink>().is_err());
+        assert!("cranepro://inspection/abc".parse::<DeepLink>().is_err());
+    }
+}
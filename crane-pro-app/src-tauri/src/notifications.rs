@@ -0,0 +1,514 @@
+//! Notification delivery channels
+//!
+//! Notifications are recorded by the `NotificationService`, which is the
+//! user's in-app inbox, and then handed to every registered
+//! [`NotificationChannel`] the recipient has not switched off. Channels are
+//! registered at startup: the Tauri event channel needs the app handle, and
+//! the email channel is only available when a mail pickup directory is
+//! configured.
+//!
+//! Users may instead take their email as a daily digest: per-event emails are
+//! held back, and once a day at their chosen local time they are sent one
+//! message listing their overdue work and the notifications since the last
+//! digest, grouped by kind with repeats about the same record folded
+//! together.
+//!
+//! Deliveries are not made inside the transaction that records a
+//! notification or marks a digest sent. Instead an [`OutboxMessage`] is
+//! written to the outbox in that transaction, and the outbox is drained after
+//! it commits and again by [`run_outbox_dispatcher`], which retries failed
+//! deliveries with backoff. A delivery may therefore be repeated but is
+//! never lost.
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{DigestEntry, DigestSection, Notification, NotificationChannelKind, NotificationDigest, NotificationKind};
+use crate::services::NotificationService;
+use crate::shutdown::ShutdownSignal;
+use crate::trace;
+use chrono::{DateTime, Utc};
+use log::{debug, error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// Tauri event carrying each [`Notification`] to the running app
+pub const NOTIFICATION_EVENT: &str = "notification";
+
+/// How often inspections coming due and missed finding SLAs are checked for
+pub const DUE_NOTIFICATION_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// How often the outbox is checked for deliveries to retry
+pub const OUTBOX_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Attempts at a delivery before it is given up on
+pub const MAX_OUTBOX_ATTEMPTS: i64 = 10;
+
+/// Longest wait between attempts at a delivery
+const MAX_OUTBOX_RETRY_MINUTES: i64 = 6 * 60;
+
+/// The user a notification is delivered to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationRecipient {
+    pub user_id: i64,
+    pub name: String,
+    pub email: String,
+}
+
+/// A way of delivering notifications to users
+pub trait NotificationChannel: Send + Sync {
+    fn kind(&self) -> NotificationChannelKind;
+
+    fn deliver(&self, notification: &Notification, recipient: &NotificationRecipient) -> AppResult<()>;
+
+    /// Send a one-off message that must not be kept in the user's inbox,
+    /// such as a password reset link. Returns `false` from channels that
+    /// cannot carry such messages.
+    fn send_private(&self, _recipient: &NotificationRecipient, _subject: &str, _body: &str) -> AppResult<bool> {
+        Ok(false)
+    }
+}
+
+/// The in-app inbox. Recording the notification is the delivery, so there is
+/// nothing left to do; the channel exists so users can switch it off like
+/// any other.
+pub struct InAppChannel;
+
+impl NotificationChannel for InAppChannel {
+    fn kind(&self) -> NotificationChannelKind {
+        NotificationChannelKind::InApp
+    }
+
+    fn deliver(&self, _notification: &Notification, _recipient: &NotificationRecipient) -> AppResult<()> {
+        Ok(())
+    }
+}
+
+/// Pushes notifications to the frontend as [`NOTIFICATION_EVENT`] events
+pub struct EventChannel {
+    app: AppHandle,
+}
+
+impl EventChannel {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+impl NotificationChannel for EventChannel {
+    fn kind(&self) -> NotificationChannelKind {
+        NotificationChannelKind::Event
+    }
+
+    fn deliver(&self, notification: &Notification, _recipient: &NotificationRecipient) -> AppResult<()> {
+        trace::emit(&self.app, NOTIFICATION_EVENT, notification.clone())
+            .map_err(|e| AppError::internal(format!("Failed to emit notification event: {}", e)))
+    }
+}
+
+/// Writes each notification as an RFC 5322 message into a mail pickup
+/// directory, from which the local mail transfer agent sends it
+pub struct EmailChannel {
+    pickup_dir: PathBuf,
+    from: String,
+}
+
+impl EmailChannel {
+    pub fn new(pickup_dir: impl Into<PathBuf>, from: impl Into<String>) -> Self {
+        Self { pickup_dir: pickup_dir.into(), from: from.into() }
+    }
+
+    /// Configure from `CRANEPRO_MAIL_PICKUP_DIR` and `CRANEPRO_MAIL_FROM`.
+    /// Returns `None` when no pickup directory is set.
+    pub fn from_env() -> Option<Self> {
+        let pickup_dir = std::env::var("CRANEPRO_MAIL_PICKUP_DIR").ok().filter(|dir| !dir.trim().is_empty())?;
+        let from = std::env::var("CRANEPRO_MAIL_FROM").unwrap_or_else(|_| "cranepro@localhost".to_string());
+        Some(Self::new(pickup_dir, from))
+    }
+
+    fn message(&self, message_id: &str, recipient: &NotificationRecipient, subject: &str, body: &str) -> String {
+        // Header values must stay on one line
+        let header = |value: &str| value.replace(['\r', '\n'], " ");
+        format!(
+            "From: {}\r\nTo: {} <{}>\r\nSubject: {}\r\nDate: {}\r\nMessage-ID: <{}@cranepro>\r\n\
+             MIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+            header(&self.from),
+            header(&recipient.name),
+            header(&recipient.email),
+            header(subject),
+            Utc::now().to_rfc2822(),
+            message_id,
+            body.replace('\n', "\r\n"),
+        )
+    }
+
+    /// Queue a message in the pickup directory as `<name>.eml`
+    fn queue(&self, name: &str, message: String) -> AppResult<()> {
+        std::fs::create_dir_all(&self.pickup_dir)?;
+        // Write under a temporary name so the MTA never picks up half a message
+        let partial = self.pickup_dir.join(format!("{}.tmp", name));
+        std::fs::write(&partial, message)?;
+        std::fs::rename(&partial, self.pickup_dir.join(format!("{}.eml", name)))?;
+        Ok(())
+    }
+}
+
+impl NotificationChannel for EmailChannel {
+    fn kind(&self) -> NotificationChannelKind {
+        NotificationChannelKind::Email
+    }
+
+    fn deliver(&self, notification: &Notification, recipient: &NotificationRecipient) -> AppResult<()> {
+        let message_id = format!("notification-{}", notification.id);
+        let message = self.message(&message_id, recipient, &notification.title, &notification.body);
+        self.queue(&format!("{}-{}", message_id, uuid::Uuid::new_v4()), message)?;
+        debug!("Queued notification {} email to {}", notification.id, recipient.email);
+        Ok(())
+    }
+
+    fn send_private(&self, recipient: &NotificationRecipient, subject: &str, body: &str) -> AppResult<bool> {
+        let message_id = format!("message-{}", uuid::Uuid::new_v4());
+        self.queue(&message_id, self.message(&message_id, recipient, subject, body))?;
+        debug!("Queued private email to {}", recipient.email);
+        Ok(true)
+    }
+}
+
+/// A delivery waiting in the outbox
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum OutboxMessage {
+    /// A recorded notification, to deliver on one channel
+    Notification {
+        notification_id: i64,
+        channel: NotificationChannelKind,
+        recipient: NotificationRecipient,
+    },
+    /// A message that is not kept in the inbox, such as a digest
+    Message {
+        channel: NotificationChannelKind,
+        recipient: NotificationRecipient,
+        subject: String,
+        body: String,
+    },
+}
+
+impl OutboxMessage {
+    pub fn channel(&self) -> NotificationChannelKind {
+        match self {
+            OutboxMessage::Notification { channel, .. } | OutboxMessage::Message { channel, .. } => *channel,
+        }
+    }
+}
+
+/// Wait before the next attempt at a delivery that has failed `attempts`
+/// times: a minute, doubling each time up to six hours
+pub fn outbox_retry_delay(attempts: i64) -> chrono::Duration {
+    let minutes = 1i64 << (attempts.clamp(1, 10) - 1);
+    chrono::Duration::minutes(minutes.min(MAX_OUTBOX_RETRY_MINUTES))
+}
+
+/// Digest sections for each kind of notification, in the order they appear
+/// after the overdue items
+const DIGEST_SECTIONS: [(NotificationKind, &str); 3] = [
+    (NotificationKind::CriticalFinding, "New findings"),
+    (NotificationKind::Assignment, "Assignments"),
+    (NotificationKind::InspectionDue, "Coming due"),
+];
+
+/// Assemble a digest from a user's overdue items and the notifications they
+/// were sent since `period_start`, oldest first. Notifications about the same
+/// record and of the same kind become one entry showing the latest, and
+/// records already listed as overdue are not repeated.
+pub fn build_digest(
+    user_id: i64,
+    period_start: Option<DateTime<Utc>>,
+    generated_at: DateTime<Utc>,
+    overdue: Vec<DigestEntry>,
+    notifications: &[Notification],
+) -> NotificationDigest {
+    let listed: HashSet<(String, i64)> = overdue
+        .iter()
+        .filter_map(|entry| Some((entry.entity_type.clone()?, entry.entity_id?)))
+        .collect();
+
+    let mut sections = Vec::new();
+    if !overdue.is_empty() {
+        sections.push(DigestSection { title: "Overdue".to_string(), entries: overdue });
+    }
+    for (kind, title) in DIGEST_SECTIONS {
+        let mut entries: Vec<DigestEntry> = Vec::new();
+        let mut positions: HashMap<(Option<String>, Option<i64>, String), usize> = HashMap::new();
+        for notification in notifications.iter().filter(|n| n.kind == kind) {
+            if let (Some(entity_type), Some(entity_id)) = (&notification.entity_type, notification.entity_id) {
+                if listed.contains(&(entity_type.clone(), entity_id)) {
+                    continue;
+                }
+            }
+            let summary = format!("{}: {}", notification.title, notification.body);
+            // Notifications without a record are only folded when identical
+            let key = match notification.entity_id {
+                Some(_) => (notification.entity_type.clone(), notification.entity_id, String::new()),
+                None => (notification.entity_type.clone(), None, summary.clone()),
+            };
+            match positions.get(&key) {
+                Some(&position) => {
+                    entries[position].summary = summary;
+                    entries[position].occurrences += 1;
+                }
+                None => {
+                    positions.insert(key, entries.len());
+                    entries.push(DigestEntry {
+                        summary,
+                        entity_type: notification.entity_type.clone(),
+                        entity_id: notification.entity_id,
+                        occurrences: 1,
+                    });
+                }
+            }
+        }
+        if !entries.is_empty() {
+            sections.push(DigestSection { title: title.to_string(), entries });
+        }
+    }
+
+    NotificationDigest { user_id, period_start, generated_at, sections }
+}
+
+/// Subject and plain text body of a digest email
+pub fn render_digest(digest: &NotificationDigest) -> (String, String) {
+    let total: usize = digest.sections.iter().map(|section| section.entries.len()).sum();
+    let subject = format!("CranePro daily digest: {} item{}", total, if total == 1 { "" } else { "s" });
+
+    let mut body = match digest.period_start {
+        Some(start) => format!("Since {} UTC:\n", start.format("%Y-%m-%d %H:%M")),
+        None => String::new(),
+    };
+    for section in &digest.sections {
+        body.push_str(&format!("\n{} ({})\n", section.title, section.entries.len()));
+        for entry in &section.entries {
+            body.push_str("  - ");
+            body.push_str(&entry.summary);
+            if entry.occurrences > 1 {
+                body.push_str(&format!(" ({} updates)", entry.occurrences));
+            }
+            body.push('\n');
+        }
+    }
+    (subject, body)
+}
+
+/// Background task notifying inspectors of inspections coming due, and
+/// managers of critical findings past their SLA targets, every `interval`
+/// until shutdown. Digests that have come due are sent after each check, so
+/// they include what it found.
+pub async fn run_due_notifications(service: Arc<NotificationService>, interval: Duration, mut shutdown: ShutdownSignal) {
+    info!("Checking for inspections coming due, SLA breaches and digests every {:?}", interval);
+    loop {
+        let notifications = service.clone();
+        let outcome = tokio::task::spawn_blocking(move || {
+            let due = notifications.notify_due_inspections();
+            let breaches = notifications.notify_sla_breaches();
+            (due, breaches, notifications.send_due_digests(Utc::now()))
+        }).await;
+        match outcome {
+            Ok((due, breaches, digests)) => {
+                match due {
+                    Ok(sent) => debug!("Sent {} inspection due notifications", sent.len()),
+                    Err(e) => error!("Inspection due notifications failed: {}", e),
+                }
+                match breaches {
+                    Ok(sent) => debug!("Sent {} SLA breach notifications", sent.len()),
+                    Err(e) => error!("SLA breach notifications failed: {}", e),
+                }
+                match digests {
+                    Ok(sent) => debug!("Sent {} notification digests", sent),
+                    Err(e) => error!("Notification digests failed: {}", e),
+                }
+            }
+            Err(e) => error!("Due notification task panicked: {}", e),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = shutdown.wait() => break,
+        }
+    }
+    debug!("Inspection due notifications stopped");
+}
+
+/// Background task delivering what is waiting in the outbox every
+/// `interval` until shutdown
+pub async fn run_outbox_dispatcher(service: Arc<NotificationService>, interval: Duration, mut shutdown: ShutdownSignal) {
+    info!("Dispatching the notification outbox every {:?}", interval);
+    loop {
+        let notifications = service.clone();
+        match tokio::task::spawn_blocking(move || notifications.dispatch_outbox(Utc::now())).await {
+            Ok(Ok(delivered)) if delivered > 0 => debug!("Delivered {} outbox messages", delivered),
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => error!("Outbox dispatch failed: {}", e),
+            Err(e) => error!("Outbox dispatch task panicked: {}", e),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = shutdown.wait() => break,
+        }
+    }
+    debug!("Outbox dispatcher stopped");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NotificationKind;
+    use crate::security::fields::FieldCipher;
+
+    #[test]
+    fn test_email_channel_writes_message_to_pickup_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let channel = EmailChannel::new(dir.path(), "cranepro@example.com");
+        let notification = Notification {
+            id: 7,
+            user_id: 2,
+            kind: NotificationKind::CriticalFinding,
+            title: "Critical finding\r\nBcc: someone@example.com".to_string(),
+            body: "Hook latch on A1 Crane: latch bent".to_string(),
+            entity_type: Some("inspection_item".to_string()),
+            entity_id: Some(1),
+            created_at: Utc::now(),
+            read_at: None,
+        };
+        let recipient = NotificationRecipient { user_id: 2, name: "Ina Spector".to_string(), email: "ina@example.com".to_string() };
+
+        channel.deliver(&notification, &recipient).unwrap();
+
+        let files: Vec<_> = std::fs::read_dir(dir.path()).unwrap().map(|entry| entry.unwrap().path()).collect();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].extension().unwrap(), "eml");
+        let message = std::fs::read_to_string(&files[0]).unwrap();
+        assert!(message.contains("To: Ina Spector <ina@example.com>\r\n"));
+        assert!(message.contains("Subject: Critical finding  Bcc: someone@example.com\r\n"));
+        assert!(message.ends_with("latch bent\r\n"));
+    }
+
+    #[test]
+    fn test_only_email_channel_sends_private_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let channel = EmailChannel::new(dir.path(), "cranepro@example.com");
+        let recipient = NotificationRecipient { user_id: 2, name: "Ina Spector".to_string(), email: "ina@example.com".to_string() };
+
+        assert!(!InAppChannel.send_private(&recipient, "Reset", "Code: 1234").unwrap());
+        assert!(channel.send_private(&recipient, "Reset your password", "Code: 1234").unwrap());
+
+        let files: Vec<_> = std::fs::read_dir(dir.path()).unwrap().map(|entry| entry.unwrap().path()).collect();
+        assert_eq!(files.len(), 1);
+        let message = std::fs::read_to_string(&files[0]).unwrap();
+        assert!(message.contains("Subject: Reset your password\r\n"));
+        assert!(message.ends_with("Code: 1234\r\n"));
+    }
+
+    #[test]
+    fn test_digest_groups_and_folds_notifications() {
+        let notification = |id: i64, kind: NotificationKind, title: &str, entity: (&str, i64)| Notification {
+            id,
+            user_id: 2,
+            kind,
+            title: title.to_string(),
+            body: format!("update {}", id),
+            entity_type: Some(entity.0.to_string()),
+            entity_id: Some(entity.1),
+            created_at: Utc::now(),
+            read_at: None,
+        };
+        let notifications = [
+            notification(1, NotificationKind::Assignment, "Work order assigned", ("work_order", 4)),
+            notification(2, NotificationKind::CriticalFinding, "Critical finding", ("inspection_item", 9)),
+            notification(3, NotificationKind::Assignment, "Inspection assigned", ("inspection", 5)),
+            notification(4, NotificationKind::CriticalFinding, "Critical finding not resolved", ("inspection_item", 9)),
+            notification(5, NotificationKind::Assignment, "Work order assigned", ("work_order", 6)),
+        ];
+        let overdue = vec![DigestEntry {
+            summary: "Work order: Replace hook latch was due 2026-10-01".to_string(),
+            entity_type: Some("work_order".to_string()),
+            entity_id: Some(6),
+            occurrences: 1,
+        }];
+
+        let digest = build_digest(2, None, Utc::now(), overdue, &notifications);
+        let titles: Vec<_> = digest.sections.iter().map(|section| section.title.as_str()).collect();
+        assert_eq!(titles, ["Overdue", "New findings", "Assignments"]);
+        assert_eq!(digest.sections[1].entries.len(), 1);
+        assert_eq!(digest.sections[1].entries[0].summary, "Critical finding not resolved: update 4");
+        assert_eq!(digest.sections[1].entries[0].occurrences, 2);
+        // The overdue work order is not listed again as an assignment
+        let assigned: Vec<_> = digest.sections[2].entries.iter().map(|entry| entry.entity_id).collect();
+        assert_eq!(assigned, [Some(4), Some(5)]);
+
+        let (subject, body) = render_digest(&digest);
+        assert_eq!(subject, "CranePro daily digest: 4 items");
+        assert!(body.contains("New findings (1)\n  - Critical finding not resolved: update 4 (2 updates)\n"));
+        assert!(build_digest(2, None, Utc::now(), Vec::new(), &[]).is_empty());
+    }
+
+    /// Fails as many deliveries as it is told to, then records the rest
+    struct FlakyChannel {
+        failures: std::sync::Mutex<usize>,
+        delivered: std::sync::Mutex<Vec<i64>>,
+    }
+
+    impl NotificationChannel for FlakyChannel {
+        fn kind(&self) -> NotificationChannelKind {
+            NotificationChannelKind::Event
+        }
+
+        fn deliver(&self, notification: &Notification, _recipient: &NotificationRecipient) -> AppResult<()> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(AppError::internal("channel unavailable"));
+            }
+            self.delivered.lock().unwrap().push(notification.id);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_deliveries_are_retried_from_the_outbox() {
+        let database = Arc::new(crate::database::Database::new_in_memory().await.unwrap());
+        let services = crate::services::Services::init(database, Arc::new(FieldCipher::ephemeral().unwrap())).await.unwrap();
+        let channel = Arc::new(FlakyChannel { failures: std::sync::Mutex::new(1), delivered: Default::default() });
+        services.notifications.register_channel(channel.clone());
+
+        let notification = services.notifications
+            .notify(1, NotificationKind::Assignment, "Inspection assigned", "Annual inspection of A1 Crane", None)
+            .unwrap()
+            .unwrap();
+        assert!(channel.delivered.lock().unwrap().is_empty());
+
+        // The failed delivery waits for its retry delay, then goes once
+        let now = Utc::now();
+        assert_eq!(services.notifications.dispatch_outbox(now).unwrap(), 0);
+        assert_eq!(services.notifications.dispatch_outbox(now + chrono::Duration::minutes(2)).unwrap(), 1);
+        assert_eq!(services.notifications.dispatch_outbox(now + chrono::Duration::minutes(30)).unwrap(), 0);
+        assert_eq!(*channel.delivered.lock().unwrap(), [notification.id]);
+    }
+
+    #[test]
+    fn test_outbox_retries_back_off_up_to_six_hours() {
+        let delays: Vec<_> = [1, 2, 3, 9, 10, 40].iter().map(|&attempts| outbox_retry_delay(attempts).num_minutes()).collect();
+        assert_eq!(delays, [1, 2, 4, 256, 360, 360]);
+
+        let message = OutboxMessage::Message {
+            channel: NotificationChannelKind::Email,
+            recipient: NotificationRecipient { user_id: 2, name: "Ina Spector".to_string(), email: "ina@example.com".to_string() },
+            subject: "CranePro daily digest: 1 item".to_string(),
+            body: "Overdue (1)".to_string(),
+        };
+        let stored = serde_json::to_string(&message).unwrap();
+        let restored: OutboxMessage = serde_json::from_str(&stored).unwrap();
+        assert_eq!(restored.channel(), NotificationChannelKind::Email);
+    }
+}
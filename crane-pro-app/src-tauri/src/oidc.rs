@@ -0,0 +1,421 @@
+//! OpenID Connect single sign-on
+//!
+//! The app signs in as a native client following RFC 8252: the provider's
+//! sign-in page opens in the system browser, and the provider redirects
+//! back to a one-off listener on the loopback interface with an
+//! authorization code. The code is exchanged with PKCE (RFC 7636), so no
+//! client secret needs to ship with the app. The ID token's signature is
+//! checked against the provider's published keys, and its claims become
+//! the identity the local account is linked to.
+
+use crate::errors::{AppError, AppResult};
+use crate::models::OidcConfig;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::Deserialize;
+use serde_json::{Map, Value as JsonValue};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Path the provider redirects the browser to on the loopback listener
+const REDIRECT_PATH: &str = "/callback";
+
+/// Timeout of each request to the provider
+const PROVIDER_REQUEST_TIMEOUT_SECONDS: u64 = 30;
+
+/// Largest redirect request read from the browser
+const MAX_REDIRECT_REQUEST_BYTES: usize = 16 * 1024;
+
+const BASE64URL_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Page shown in the browser once the code has been received
+const SIGNED_IN_PAGE: &str = "<!DOCTYPE html><html><head><title>CranePro</title></head>\
+    <body><p>Sign-in complete. You can close this window and return to CranePro.</p></body></html>";
+
+/// Page shown in the browser when the provider reported an error
+const FAILED_PAGE: &str = "<!DOCTYPE html><html><head><title>CranePro</title></head>\
+    <body><p>Sign-in did not complete. Return to CranePro and try again.</p></body></html>";
+
+/// The account the provider vouched for
+#[derive(Debug, Clone, PartialEq)]
+pub struct OidcIdentity {
+    /// The provider's identifier for the account, which never changes
+    pub subject: String,
+    pub username: String,
+    pub email: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+}
+
+/// The parts of the provider's discovery document that are used
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: Option<String>,
+}
+
+fn provider_error(message: impl Into<String>) -> AppError {
+    AppError::ExternalService { service: "OIDC".to_string(), message: message.into() }
+}
+
+/// Unpadded Base64 with the URL-safe alphabet
+fn base64url_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let buffer = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let bits = u32::from_be_bytes([0, buffer[0], buffer[1], buffer[2]]);
+        for i in 0..chunk.len() + 1 {
+            encoded.push(BASE64URL_ALPHABET[((bits >> (18 - i * 6)) & 0x3f) as usize] as char);
+        }
+    }
+    encoded
+}
+
+/// Random URL-safe string carrying `bytes` bytes of entropy
+fn random_token(bytes: usize) -> AppResult<String> {
+    let mut buffer = vec![0u8; bytes];
+    SystemRandom::new()
+        .fill(&mut buffer)
+        .map_err(|_| AppError::Encryption { reason: "Secure random number generator failed".to_string() })?;
+    Ok(base64url_encode(&buffer))
+}
+
+/// S256 code challenge of a PKCE code verifier
+fn code_challenge(verifier: &str) -> String {
+    base64url_encode(&Sha256::digest(verifier.as_bytes()))
+}
+
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Decode a query string value, where `+` also stands for a space
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let escaped = bytes.get(i + 1..i + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match escaped {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 3;
+                        continue;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            b'+' => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Name/value pairs of a query string
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(name), percent_decode(value))
+        })
+        .collect()
+}
+
+/// Identity described by the claims of a verified ID token
+fn identity_from_claims(config: &OidcConfig, claims: &Map<String, JsonValue>) -> AppResult<OidcIdentity> {
+    let claim = |name: &str| {
+        claims.get(name)
+            .and_then(JsonValue::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    let subject = claim("sub").ok_or_else(|| provider_error("ID token has no subject"))?;
+    let email = claim("email");
+    let username = claim(config.username_claim.trim())
+        .or_else(|| email.clone())
+        .ok_or_else(|| provider_error(format!("ID token has neither a {} nor an email claim", config.username_claim)))?;
+    Ok(OidcIdentity {
+        subject,
+        username,
+        email,
+        first_name: claim("given_name"),
+        last_name: claim("family_name"),
+    })
+}
+
+/// Browser redirects are received on a loopback port for the duration of
+/// one sign-in
+struct LoopbackRedirect {
+    listener: TcpListener,
+    redirect_uri: String,
+}
+
+impl LoopbackRedirect {
+    async fn bind(port: Option<u16>) -> AppResult<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port.unwrap_or(0))).await?;
+        let port = listener.local_addr()?.port();
+        Ok(Self { listener, redirect_uri: format!("http://127.0.0.1:{}{}", port, REDIRECT_PATH) })
+    }
+
+    /// Wait for the provider to redirect the browser back with the code for
+    /// `state`. Requests for other paths or states, such as a favicon or a
+    /// page probing the port, are answered and ignored.
+    async fn wait_for_code(&self, state: &str) -> AppResult<String> {
+        loop {
+            let (mut stream, _) = self.listener.accept().await?;
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 2048];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REDIRECT_REQUEST_BYTES {
+                // A connection dropped midway is answered like any stray request
+                let read = stream.read(&mut buffer).await.unwrap_or(0);
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+
+            let request = String::from_utf8_lossy(&request);
+            let target = request.lines().next()
+                .and_then(|line| line.strip_prefix("GET "))
+                .and_then(|line| line.split(' ').next())
+                .unwrap_or_default();
+            let (path, query) = target.split_once('?').unwrap_or((target, ""));
+            let params = parse_query(query);
+            let param = |name: &str| params.iter().find(|(key, _)| key == name).map(|(_, value)| value.clone());
+
+            if path != REDIRECT_PATH || param("state").as_deref() != Some(state) {
+                let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+                continue;
+            }
+
+            let outcome = match (param("code"), param("error")) {
+                (Some(code), None) => Ok(code),
+                (_, Some(error)) => Err(provider_error(format!(
+                    "Sign-in was refused: {}{}",
+                    error,
+                    param("error_description").map(|d| format!(" ({})", d)).unwrap_or_default()
+                ))),
+                (None, None) => Err(provider_error("Redirect carried no authorization code")),
+            };
+            let page = if outcome.is_ok() { SIGNED_IN_PAGE } else { FAILED_PAGE };
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                page.len(),
+                page
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+            return outcome;
+        }
+    }
+}
+
+/// Client for the provider configured in `OidcConfig`
+struct OidcClient<'a> {
+    config: &'a OidcConfig,
+    http: reqwest::Client,
+}
+
+impl<'a> OidcClient<'a> {
+    fn new(config: &'a OidcConfig) -> AppResult<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(PROVIDER_REQUEST_TIMEOUT_SECONDS))
+            .build()?;
+        Ok(Self { config, http })
+    }
+
+    async fn discover(&self) -> AppResult<ProviderMetadata> {
+        let issuer = self.config.issuer_url.trim().trim_end_matches('/');
+        let metadata: ProviderMetadata = self.http
+            .get(format!("{}/.well-known/openid-configuration", issuer))
+            .send().await?
+            .error_for_status()?
+            .json().await?;
+        if metadata.issuer.trim_end_matches('/') != issuer {
+            return Err(provider_error(format!(
+                "Provider identifies as {} rather than the configured issuer {}", metadata.issuer, issuer
+            )));
+        }
+        Ok(metadata)
+    }
+
+    fn authorization_url(&self, metadata: &ProviderMetadata, redirect_uri: &str, state: &str, nonce: &str, challenge: &str) -> String {
+        let scope = std::iter::once("openid")
+            .chain(self.config.scopes.iter().map(|scope| scope.trim()).filter(|scope| *scope != "openid"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let separator = if metadata.authorization_endpoint.contains('?') { '&' } else { '?' };
+        format!(
+            "{}{}response_type=code&client_id={}&redirect_uri={}&scope={}&state={}&nonce={}&code_challenge={}&code_challenge_method=S256",
+            metadata.authorization_endpoint,
+            separator,
+            percent_encode(&self.config.client_id),
+            percent_encode(redirect_uri),
+            percent_encode(&scope),
+            state,
+            nonce,
+            challenge
+        )
+    }
+
+    /// Exchange the authorization code for an ID token
+    async fn exchange_code(&self, metadata: &ProviderMetadata, code: &str, redirect_uri: &str, verifier: &str) -> AppResult<String> {
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", self.config.client_id.as_str()),
+            ("code_verifier", verifier),
+        ];
+        if let Some(secret) = self.config.client_secret.as_deref() {
+            form.push(("client_secret", secret));
+        }
+        let response = self.http.post(&metadata.token_endpoint).form(&form).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(provider_error(format!("Token request failed with {}: {}", status, body)));
+        }
+        let tokens: TokenResponse = response.json().await?;
+        tokens.id_token.ok_or_else(|| provider_error("Token response has no ID token"))
+    }
+
+    /// Check the ID token's signature, issuer, audience, expiry and nonce
+    async fn verify_id_token(&self, metadata: &ProviderMetadata, id_token: &str, nonce: &str) -> AppResult<OidcIdentity> {
+        let header = decode_header(id_token)?;
+        // A symmetric algorithm would let anyone holding the client ID forge tokens
+        if matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+            return Err(provider_error(format!("ID token is signed with unsupported algorithm {:?}", header.alg)));
+        }
+
+        let keys: JwkSet = self.http.get(&metadata.jwks_uri).send().await?.error_for_status()?.json().await?;
+        let key = match header.kid.as_deref() {
+            Some(kid) => keys.find(kid),
+            None if keys.keys.len() == 1 => keys.keys.first(),
+            None => None,
+        }.ok_or_else(|| provider_error("ID token is signed with a key the provider does not publish"))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_audience(&[self.config.client_id.as_str()]);
+        validation.set_issuer(&[metadata.issuer.as_str()]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+        let claims = decode::<Map<String, JsonValue>>(id_token, &DecodingKey::from_jwk(key)?, &validation)?.claims;
+
+        if claims.get("nonce").and_then(JsonValue::as_str) != Some(nonce) {
+            return Err(provider_error("ID token was not issued for this sign-in"));
+        }
+        identity_from_claims(self.config, &claims)
+    }
+}
+
+/// Sign in through the provider. `open_browser` is given the provider's
+/// sign-in page to show; this then waits, up to the configured timeout, for
+/// the browser to come back with the code.
+pub async fn sign_in(config: &OidcConfig, open_browser: impl FnOnce(&str) -> AppResult<()>) -> AppResult<OidcIdentity> {
+    if !config.enabled {
+        return Err(AppError::authentication("Single sign-on is not enabled"));
+    }
+    let client = OidcClient::new(config)?;
+    let metadata = client.discover().await?;
+
+    let state = random_token(16)?;
+    let nonce = random_token(16)?;
+    let verifier = random_token(32)?;
+    let redirect = LoopbackRedirect::bind(config.redirect_port).await?;
+    open_browser(&client.authorization_url(&metadata, &redirect.redirect_uri, &state, &nonce, &code_challenge(&verifier)))?;
+
+    let code = tokio::time::timeout(Duration::from_secs(config.login_timeout_seconds), redirect.wait_for_code(&state))
+        .await
+        .map_err(|_| AppError::Timeout { operation: "single sign-on".to_string(), timeout: config.login_timeout_seconds })??;
+
+    let id_token = client.exchange_code(&metadata, &code, &redirect.redirect_uri, &verifier).await?;
+    client.verify_id_token(&metadata, &id_token, &nonce).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pkce_challenge_and_query_parsing() {
+        // RFC 7636 appendix B
+        assert_eq!(
+            code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        assert_eq!(base64url_encode(b"f"), "Zg");
+        assert_eq!(base64url_encode(b"foob"), "Zm9vYg");
+        assert_eq!(random_token(32).unwrap().len(), 43);
+
+        let params = parse_query("code=a%2Fb+c&state=xyz&empty=&bad=%zz");
+        assert_eq!(params[0], ("code".to_string(), "a/b c".to_string()));
+        assert_eq!(params[2], ("empty".to_string(), String::new()));
+        assert_eq!(params[3], ("bad".to_string(), "%zz".to_string()));
+    }
+
+    #[test]
+    fn test_identity_from_claims() {
+        let config = OidcConfig::default();
+        let claims = |value: JsonValue| value.as_object().unwrap().clone();
+
+        let identity = identity_from_claims(&config, &claims(serde_json::json!({
+            "sub": "248289761001", "preferred_username": "jsmith", "email": "j.smith@example.com",
+            "given_name": "Jo", "family_name": " "
+        }))).unwrap();
+        assert_eq!(identity.username, "jsmith");
+        assert_eq!(identity.first_name.as_deref(), Some("Jo"));
+        assert_eq!(identity.last_name, None);
+
+        let by_email = identity_from_claims(&config, &claims(serde_json::json!({
+            "sub": "1", "email": "j.smith@example.com"
+        }))).unwrap();
+        assert_eq!(by_email.username, "j.smith@example.com");
+        assert!(identity_from_claims(&config, &claims(serde_json::json!({"email": "a@b.c"}))).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_loopback_redirect_waits_for_matching_state() {
+        let redirect = LoopbackRedirect::bind(None).await.unwrap();
+        let address = redirect.listener.local_addr().unwrap();
+        let browser = tokio::spawn(async move {
+            let mut responses = Vec::new();
+            for target in ["/favicon.ico", "/callback?code=stolen&state=guess", "/callback?code=abc%2B1&state=s1"] {
+                let mut stream = tokio::net::TcpStream::connect(address).await.unwrap();
+                stream.write_all(format!("GET {} HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n", target).as_bytes()).await.unwrap();
+                let mut response = String::new();
+                stream.read_to_string(&mut response).await.unwrap();
+                responses.push(response);
+            }
+            responses
+        });
+
+        assert_eq!(redirect.wait_for_code("s1").await.unwrap(), "abc+1");
+        let responses = browser.await.unwrap();
+        assert!(responses[0].starts_with("HTTP/1.1 404"));
+        assert!(responses[1].starts_with("HTTP/1.1 404"));
+        assert!(responses[2].contains("Sign-in complete"));
+    }
+}
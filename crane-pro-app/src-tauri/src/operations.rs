@@ -0,0 +1,275 @@
+//! Long-running operations
+//!
+//! Bulk changes and full exports can run for minutes. Each one registers
+//! here when it starts, reports progress as it goes and checks its
+//! cancellation token between units of work, so an administrator can see
+//! what is running and stop it. Finished operations stay listed for
+//! [`FINISHED_RETENTION_MINUTES`] so their outcome can still be seen after
+//! the command that ran them has returned.
+
+use crate::errors::{AppError, AppResult};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// How long finished operations stay in the list
+pub const FINISHED_RETENTION_MINUTES: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    BulkOperation,
+    DataExport,
+    WarehouseExport,
+}
+
+impl std::fmt::Display for OperationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OperationKind::BulkOperation => write!(f, "bulk operation"),
+            OperationKind::DataExport => write!(f, "data export"),
+            OperationKind::WarehouseExport => write!(f, "warehouse export"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationStatus {
+    Running,
+    /// Cancellation was requested and the worker has not stopped yet
+    Cancelling,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl OperationStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, OperationStatus::Completed | OperationStatus::Failed | OperationStatus::Cancelled)
+    }
+}
+
+/// What is known about one operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationInfo {
+    pub id: String,
+    pub kind: OperationKind,
+    pub description: String,
+    pub started_by: Option<i64>,
+    pub started_at: DateTime<Utc>,
+    pub status: OperationStatus,
+    /// Units of work done so far: records changed, rows written
+    pub completed: u64,
+    /// Units of work expected, when known up front
+    pub total: Option<u64>,
+    pub cancelled_by: Option<i64>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+/// Shared flag a worker checks to learn it should stop
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+struct Entry {
+    info: OperationInfo,
+    token: CancellationToken,
+}
+
+type Operations = Arc<Mutex<HashMap<String, Entry>>>;
+
+/// Operations started since the app launched, kept in Tauri managed state
+#[derive(Default)]
+pub struct OperationRegistry {
+    operations: Operations,
+}
+
+impl OperationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a new running operation; the worker reports through the
+    /// returned handle
+    pub fn start(&self, kind: OperationKind, description: impl Into<String>, started_by: Option<i64>) -> OperationHandle {
+        let id = Uuid::new_v4().to_string();
+        let token = CancellationToken::default();
+        let info = OperationInfo {
+            id: id.clone(),
+            kind,
+            description: description.into(),
+            started_by,
+            started_at: Utc::now(),
+            status: OperationStatus::Running,
+            completed: 0,
+            total: None,
+            cancelled_by: None,
+            finished_at: None,
+            error: None,
+        };
+        lock(&self.operations).insert(id.clone(), Entry { info, token: token.clone() });
+        OperationHandle { id, token, operations: self.operations.clone(), finished: false }
+    }
+
+    /// Running operations and those finished recently, newest first
+    pub fn list(&self) -> Vec<OperationInfo> {
+        let mut operations = lock(&self.operations);
+        let cutoff = Utc::now() - Duration::minutes(FINISHED_RETENTION_MINUTES);
+        operations.retain(|_, entry| !matches!(entry.info.finished_at, Some(at) if at <= cutoff));
+        let mut list: Vec<OperationInfo> = operations.values().map(|entry| entry.info.clone()).collect();
+        list.sort_by_key(|operation| std::cmp::Reverse(operation.started_at));
+        list
+    }
+
+    /// Ask a running operation to stop. It stops at its next check, so the
+    /// returned status is `Cancelling` until the worker has finished.
+    pub fn cancel(&self, id: &str, cancelled_by: Option<i64>) -> AppResult<OperationInfo> {
+        let mut operations = lock(&self.operations);
+        let entry = operations.get_mut(id).ok_or_else(|| AppError::RecordNotFound {
+            entity: "Operation".to_string(),
+            field: "id".to_string(),
+            value: id.to_string(),
+        })?;
+        if entry.info.status.is_finished() {
+            return Err(AppError::validation("id", format!("Operation {} has already finished", id)));
+        }
+        entry.token.cancel();
+        entry.info.status = OperationStatus::Cancelling;
+        entry.info.cancelled_by = cancelled_by;
+        Ok(entry.info.clone())
+    }
+}
+
+/// The worker's side of a registered operation
+pub struct OperationHandle {
+    id: String,
+    token: CancellationToken,
+    operations: Operations,
+    finished: bool,
+}
+
+impl OperationHandle {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// [`AppError::Cancelled`] once cancellation has been requested; call
+    /// between units of work
+    pub fn check_cancelled(&self) -> AppResult<()> {
+        if self.is_cancelled() {
+            let description = lock(&self.operations)
+                .get(&self.id)
+                .map(|entry| entry.info.description.clone())
+                .unwrap_or_else(|| self.id.clone());
+            return Err(AppError::Cancelled { operation: description });
+        }
+        Ok(())
+    }
+
+    pub fn set_progress(&self, completed: u64, total: Option<u64>) {
+        if let Some(entry) = lock(&self.operations).get_mut(&self.id) {
+            entry.info.completed = completed;
+            if total.is_some() {
+                entry.info.total = total;
+            }
+        }
+    }
+
+    /// Record how the operation ended
+    pub fn finish<T>(mut self, result: &AppResult<T>) {
+        let (status, error) = match result {
+            Ok(_) if self.is_cancelled() => (OperationStatus::Cancelled, None),
+            Ok(_) => (OperationStatus::Completed, None),
+            Err(AppError::Cancelled { .. }) => (OperationStatus::Cancelled, None),
+            Err(e) => (OperationStatus::Failed, Some(e.to_string())),
+        };
+        self.mark_finished(status, error);
+    }
+
+    fn mark_finished(&mut self, status: OperationStatus, error: Option<String>) {
+        if let Some(entry) = lock(&self.operations).get_mut(&self.id) {
+            entry.info.status = status;
+            entry.info.error = error;
+            entry.info.finished_at = Some(Utc::now());
+        }
+        self.finished = true;
+    }
+}
+
+impl Drop for OperationHandle {
+    /// A worker that returns early or panics without calling `finish` still
+    /// leaves the operation marked as ended
+    fn drop(&mut self) {
+        if !self.finished {
+            self.mark_finished(OperationStatus::Failed, Some("Ended without reporting an outcome".to_string()));
+        }
+    }
+}
+
+fn lock(operations: &Operations) -> std::sync::MutexGuard<'_, HashMap<String, Entry>> {
+    operations.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_and_completion() {
+        let registry = OperationRegistry::new();
+        let handle = registry.start(OperationKind::DataExport, "Export assets", Some(1));
+        handle.set_progress(500, Some(2000));
+
+        let listed = registry.list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!((listed[0].completed, listed[0].total), (500, Some(2000)));
+        assert_eq!(listed[0].status, OperationStatus::Running);
+
+        handle.finish(&Ok::<_, AppError>(()));
+        assert_eq!(registry.list()[0].status, OperationStatus::Completed);
+        assert!(registry.list()[0].finished_at.is_some());
+
+        let failing = registry.start(OperationKind::BulkOperation, "Archive assets", None);
+        let id = failing.id().to_string();
+        drop(failing);
+        let failed = registry.list().into_iter().find(|op| op.id == id).unwrap();
+        assert_eq!(failed.status, OperationStatus::Failed);
+    }
+
+    #[test]
+    fn test_cancellation() {
+        let registry = OperationRegistry::new();
+        let handle = registry.start(OperationKind::BulkOperation, "Delete inspections", Some(1));
+        assert!(handle.check_cancelled().is_ok());
+
+        let cancelling = registry.cancel(handle.id(), Some(2)).unwrap();
+        assert_eq!(cancelling.status, OperationStatus::Cancelling);
+        assert_eq!(cancelling.cancelled_by, Some(2));
+        assert!(matches!(handle.check_cancelled(), Err(AppError::Cancelled { .. })));
+
+        let id = handle.id().to_string();
+        let result = handle.check_cancelled();
+        handle.finish(&result);
+        assert_eq!(registry.list()[0].status, OperationStatus::Cancelled);
+        assert!(registry.cancel(&id, Some(2)).is_err());
+        assert!(registry.cancel("missing", Some(2)).is_err());
+    }
+}
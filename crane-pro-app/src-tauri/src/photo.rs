@@ -0,0 +1,94 @@
+//! Photo fingerprints
+//!
+//! Every uploaded file is stored with the SHA-256 of its content, and photos
+//! with the time the camera recorded in their EXIF data. Together they show
+//! when a photo was attached before, or was taken before the inspection it
+//! is attached to, which usually means last year's photo was picked by
+//! mistake.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use exif::{In, Reader, Tag, Value};
+use sha2::{Digest, Sha256};
+use std::io::Cursor;
+
+/// What is kept about an uploaded file to recognise it again
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhotoFingerprint {
+    /// Hex SHA-256 of the file content
+    pub content_hash: String,
+    /// When the photo was taken by the camera's clock, which has no time
+    /// zone; `None` for files without EXIF data
+    pub captured_at: Option<NaiveDateTime>,
+}
+
+pub fn fingerprint(data: &[u8]) -> PhotoFingerprint {
+    PhotoFingerprint {
+        content_hash: format!("{:x}", Sha256::digest(data)),
+        captured_at: capture_time(data),
+    }
+}
+
+/// EXIF `DateTimeOriginal`, falling back to `DateTime`
+fn capture_time(data: &[u8]) -> Option<NaiveDateTime> {
+    let exif = Reader::new().read_from_container(&mut Cursor::new(data)).ok()?;
+    [Tag::DateTimeOriginal, Tag::DateTime].into_iter().find_map(|tag| {
+        let field = exif.get_field(tag, In::PRIMARY)?;
+        let Value::Ascii(ref values) = field.value else {
+            return None;
+        };
+        let recorded = exif::DateTime::from_ascii(values.first()?).ok()?;
+        NaiveDate::from_ymd_opt(recorded.year.into(), recorded.month.into(), recorded.day.into())?
+            .and_hms_opt(recorded.hour.into(), recorded.minute.into(), recorded.second.into())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A JPEG holding only an APP1 segment with `DateTimeOriginal`
+    fn jpeg_taken_at(timestamp: &[u8; 19]) -> Vec<u8> {
+        let mut tiff = Vec::new();
+        tiff.extend_from_slice(b"II*\0");
+        tiff.extend_from_slice(&8u32.to_le_bytes());
+        // IFD0: one entry pointing at the Exif IFD
+        tiff.extend_from_slice(&1u16.to_le_bytes());
+        tiff.extend_from_slice(&0x8769u16.to_le_bytes());
+        tiff.extend_from_slice(&4u16.to_le_bytes());
+        tiff.extend_from_slice(&1u32.to_le_bytes());
+        tiff.extend_from_slice(&26u32.to_le_bytes());
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        // Exif IFD: DateTimeOriginal, 20 ASCII bytes stored after the IFD
+        tiff.extend_from_slice(&1u16.to_le_bytes());
+        tiff.extend_from_slice(&0x9003u16.to_le_bytes());
+        tiff.extend_from_slice(&2u16.to_le_bytes());
+        tiff.extend_from_slice(&20u32.to_le_bytes());
+        tiff.extend_from_slice(&44u32.to_le_bytes());
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        tiff.extend_from_slice(timestamp);
+        tiff.push(0);
+
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+        jpeg.extend_from_slice(b"Exif\0\0");
+        jpeg.extend_from_slice(&tiff);
+        jpeg.extend_from_slice(&[0xFF, 0xD9]);
+        jpeg
+    }
+
+    #[test]
+    fn test_fingerprint_reads_capture_time() {
+        let photo = jpeg_taken_at(b"2025:03:14 09:26:53");
+        let print = fingerprint(&photo);
+        assert_eq!(
+            print.captured_at,
+            NaiveDate::from_ymd_opt(2025, 3, 14).unwrap().and_hms_opt(9, 26, 53),
+        );
+        assert_eq!(print.content_hash.len(), 64);
+        assert_eq!(print, fingerprint(&photo));
+
+        let other = fingerprint(&jpeg_taken_at(b"2025:03:14 09:26:54"));
+        assert_ne!(other.content_hash, print.content_hash);
+        assert_eq!(fingerprint(b"not a photo").captured_at, None);
+    }
+}
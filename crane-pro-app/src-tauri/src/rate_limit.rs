@@ -0,0 +1,119 @@
+//! Sliding-window rate limiting
+//!
+//! Attempts are counted per key, e.g. a username or a device, over a window
+//! that slides with each attempt rather than resetting on the hour. Refused
+//! attempts are not counted, so a key is free again one window after its
+//! earliest counted attempt. State is kept in memory; refusals worth keeping
+//! are persisted by the caller.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Keys tracked before idle ones are swept out
+const SWEEP_THRESHOLD: usize = 1024;
+
+/// How many attempts a key may make within a window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub max_attempts: usize,
+    pub window_seconds: i64,
+}
+
+impl RateLimit {
+    fn window(&self) -> Duration {
+        Duration::seconds(self.window_seconds)
+    }
+}
+
+/// Login attempts allowed for one username, from any source
+pub const LOGIN_PER_USERNAME: RateLimit = RateLimit { max_attempts: 10, window_seconds: 5 * 60 };
+
+/// Login attempts allowed from one source, such as an app window or a kiosk
+/// terminal, for any username
+pub const LOGIN_PER_SOURCE: RateLimit = RateLimit { max_attempts: 30, window_seconds: 5 * 60 };
+
+/// An attempt refused because its key is over the limit
+#[derive(Debug, Clone, PartialEq)]
+pub struct Refusal {
+    /// Seconds until the key may try again
+    pub retry_after: u64,
+    /// Attempts counted in the window
+    pub attempts: usize,
+    /// The first refusal since the key went over the limit
+    pub new_violation: bool,
+}
+
+#[derive(Debug, Default)]
+struct Window {
+    attempts: VecDeque<DateTime<Utc>>,
+    /// Set on the first refusal; cleared once the key is under the limit
+    violating: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct SlidingWindowLimiter {
+    windows: Mutex<HashMap<String, Window>>,
+}
+
+impl SlidingWindowLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count an attempt by `key` at `now`, or refuse it if the key has
+    /// already made `limit.max_attempts` within the window
+    pub fn attempt(&self, key: &str, limit: RateLimit, now: DateTime<Utc>) -> Result<(), Refusal> {
+        let mut windows = self.windows.lock().unwrap();
+        if windows.len() >= SWEEP_THRESHOLD && !windows.contains_key(key) {
+            windows.retain(|_, window| window.attempts.back().is_some_and(|last| now - *last < limit.window()));
+        }
+
+        let window = windows.entry(key.to_string()).or_default();
+        while window.attempts.front().is_some_and(|first| now - *first >= limit.window()) {
+            window.attempts.pop_front();
+        }
+
+        if window.attempts.len() >= limit.max_attempts {
+            let free_at = window.attempts[window.attempts.len() - limit.max_attempts] + limit.window();
+            let new_violation = !window.violating;
+            window.violating = true;
+            return Err(Refusal {
+                retry_after: (free_at - now).num_seconds().max(1) as u64,
+                attempts: window.attempts.len(),
+                new_violation,
+            });
+        }
+
+        window.violating = false;
+        window.attempts.push_back(now);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_slides_and_reports_new_violations_once() {
+        let limiter = SlidingWindowLimiter::new();
+        let limit = RateLimit { max_attempts: 3, window_seconds: 60 };
+        let start = Utc::now();
+        let at = |seconds: i64| start + Duration::seconds(seconds);
+
+        assert!(limiter.attempt("alice", limit, at(0)).is_ok());
+        assert!(limiter.attempt("alice", limit, at(20)).is_ok());
+        assert!(limiter.attempt("alice", limit, at(40)).is_ok());
+        assert!(limiter.attempt("bob", limit, at(40)).is_ok());
+
+        let refused = limiter.attempt("alice", limit, at(45)).unwrap_err();
+        assert_eq!(refused.retry_after, 15);
+        assert!(refused.new_violation);
+        assert!(!limiter.attempt("alice", limit, at(50)).unwrap_err().new_violation);
+
+        // The attempt at 0 has left the window, but only that one
+        assert!(limiter.attempt("alice", limit, at(60)).is_ok());
+        assert_eq!(limiter.attempt("alice", limit, at(61)).unwrap_err().retry_after, 19);
+    }
+}
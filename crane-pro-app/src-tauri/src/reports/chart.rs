@@ -0,0 +1,318 @@
+//! Report charts
+//!
+//! Draws a [`ChartSpec`] either as inline SVG for HTML reports or onto a
+//! [`PdfPage`]. Both use the same layout, measured in points from the top
+//! left of the chart's box, so a chart looks the same in either format.
+
+use super::pdf::{text_width, Font, PdfPage};
+use crate::api::{ChartKind, ChartSpec};
+use std::fmt::Write as _;
+
+const TITLE_HEIGHT: f32 = 20.0;
+const AXIS_LABEL_WIDTH: f32 = 40.0;
+const CATEGORY_LABEL_HEIGHT: f32 = 16.0;
+const RIGHT_PADDING: f32 = 8.0;
+const TITLE_FONT_SIZE: f32 = 10.0;
+const LABEL_FONT_SIZE: f32 = 7.0;
+
+/// Horizontal grid lines, including the one at zero
+const GRID_LINES: usize = 5;
+
+/// Most category labels printed before they start to overlap
+const MAX_CATEGORY_LABELS: usize = 12;
+
+/// Share of each category's slot a bar fills
+const BAR_FILL: f32 = 0.6;
+const MARKER_SIZE: f32 = 3.0;
+
+/// Colour of bars and lines in SVG output; PDF output is greyscale
+const SERIES_COLOR: &str = "#2f6fab";
+const SERIES_GRAY: f32 = 0.35;
+const GRID_GRAY: f32 = 0.85;
+
+/// Positions of everything on a chart `width` by `height` points
+struct Layout {
+    plot_left: f32,
+    plot_top: f32,
+    plot_width: f32,
+    plot_height: f32,
+    axis_max: f64,
+}
+
+impl Layout {
+    fn new(chart: &ChartSpec, width: f32, height: f32) -> Self {
+        let largest = chart.points.iter().map(|point| point.value).fold(0.0, f64::max);
+        let axis_max = chart.max_value.unwrap_or(largest);
+        Self {
+            plot_left: AXIS_LABEL_WIDTH,
+            plot_top: TITLE_HEIGHT,
+            plot_width: (width - AXIS_LABEL_WIDTH - RIGHT_PADDING).max(1.0),
+            plot_height: (height - TITLE_HEIGHT - CATEGORY_LABEL_HEIGHT).max(1.0),
+            axis_max: if axis_max > 0.0 { axis_max } else { 1.0 },
+        }
+    }
+
+    fn plot_bottom(&self) -> f32 {
+        self.plot_top + self.plot_height
+    }
+
+    fn slot_width(&self, count: usize) -> f32 {
+        self.plot_width / count.max(1) as f32
+    }
+
+    /// Horizontal centre of the `index`th category
+    fn x(&self, index: usize, count: usize) -> f32 {
+        self.plot_left + self.slot_width(count) * (index as f32 + 0.5)
+    }
+
+    /// Height of `value` on the plot, clamped to the axis
+    fn y(&self, value: f64) -> f32 {
+        let share = (value / self.axis_max).clamp(0.0, 1.0) as f32;
+        self.plot_bottom() - share * self.plot_height
+    }
+
+    /// Value and height of each grid line, from zero up
+    fn grid(&self) -> impl Iterator<Item = (f64, f32)> + '_ {
+        (0..GRID_LINES).map(move |step| {
+            let value = self.axis_max * step as f64 / (GRID_LINES - 1) as f64;
+            (value, self.y(value))
+        })
+    }
+
+    /// Whether the `index`th category label is printed
+    fn shows_label(index: usize, count: usize) -> bool {
+        index.is_multiple_of(count.div_ceil(MAX_CATEGORY_LABELS).max(1))
+    }
+}
+
+fn format_value(value: f64, unit: Option<&str>) -> String {
+    let number = if value.fract().abs() < 0.05 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.1}", value)
+    };
+    format!("{}{}", number, unit.unwrap_or(""))
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// `chart` as a standalone `<svg>` element `width` by `height` pixels
+pub fn to_svg(chart: &ChartSpec, width: f32, height: f32) -> String {
+    let layout = Layout::new(chart, width, height);
+    let count = chart.points.len();
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}" height="{h:.0}" viewBox="0 0 {w:.0} {h:.0}" font-family="Helvetica, Arial, sans-serif" role="img">"#,
+        w = width,
+        h = height
+    );
+    let _ = write!(svg, "<title>{}</title>", escape_xml(&chart.title));
+    let _ = write!(
+        svg,
+        r#"<text x="0" y="{:.1}" font-size="{}" font-weight="bold">{}</text>"#,
+        TITLE_FONT_SIZE + 2.0,
+        TITLE_FONT_SIZE,
+        escape_xml(&chart.title)
+    );
+
+    for (value, y) in layout.grid() {
+        let _ = write!(
+            svg,
+            r##"<line x1="{:.1}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" stroke="#d9d9d9" stroke-width="0.5"/><text x="{:.1}" y="{:.1}" font-size="{}" text-anchor="end">{}</text>"##,
+            layout.plot_left,
+            layout.plot_left + layout.plot_width,
+            layout.plot_left - 4.0,
+            y + LABEL_FONT_SIZE / 3.0,
+            LABEL_FONT_SIZE,
+            escape_xml(&format_value(value, chart.unit.as_deref())),
+            y = y
+        );
+    }
+
+    match chart.kind {
+        ChartKind::Bar => {
+            let bar_width = layout.slot_width(count) * BAR_FILL;
+            for (index, point) in chart.points.iter().enumerate() {
+                let top = layout.y(point.value);
+                let _ = write!(
+                    svg,
+                    r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"><title>{}: {}</title></rect>"#,
+                    layout.x(index, count) - bar_width / 2.0,
+                    top,
+                    bar_width,
+                    layout.plot_bottom() - top,
+                    SERIES_COLOR,
+                    escape_xml(&point.label),
+                    escape_xml(&format_value(point.value, chart.unit.as_deref()))
+                );
+            }
+        }
+        ChartKind::Line => {
+            let path = chart.points.iter().enumerate()
+                .map(|(index, point)| format!("{:.1},{:.1}", layout.x(index, count), layout.y(point.value)))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let _ = write!(
+                svg,
+                r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="1.5"/>"#,
+                path, SERIES_COLOR
+            );
+            for (index, point) in chart.points.iter().enumerate() {
+                let _ = write!(
+                    svg,
+                    r#"<circle cx="{:.1}" cy="{:.1}" r="{}" fill="{}"><title>{}: {}</title></circle>"#,
+                    layout.x(index, count),
+                    layout.y(point.value),
+                    MARKER_SIZE / 2.0 + 0.5,
+                    SERIES_COLOR,
+                    escape_xml(&point.label),
+                    escape_xml(&format_value(point.value, chart.unit.as_deref()))
+                );
+            }
+        }
+    }
+
+    for (index, point) in chart.points.iter().enumerate() {
+        if Layout::shows_label(index, count) {
+            let _ = write!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" font-size="{}" text-anchor="middle">{}</text>"#,
+                layout.x(index, count),
+                layout.plot_bottom() + LABEL_FONT_SIZE + 4.0,
+                LABEL_FONT_SIZE,
+                escape_xml(&point.label)
+            );
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Draw `chart` on `page` in the `width` by `height` box whose top left
+/// corner is (`x`, `y`)
+pub fn draw(page: &mut PdfPage, chart: &ChartSpec, x: f32, y: f32, width: f32, height: f32) {
+    let layout = Layout::new(chart, width, height);
+    let count = chart.points.len();
+
+    page.fill_gray(0.0);
+    page.text(x, y + TITLE_FONT_SIZE + 2.0, Font::Bold, TITLE_FONT_SIZE, &chart.title);
+
+    for (value, line_y) in layout.grid() {
+        page.fill_gray(GRID_GRAY);
+        page.fill_rect(x + layout.plot_left, y + line_y - 0.25, layout.plot_width, 0.5);
+        page.fill_gray(0.0);
+        page.text_right(
+            x + layout.plot_left - 4.0,
+            y + line_y + LABEL_FONT_SIZE / 3.0,
+            Font::Regular,
+            LABEL_FONT_SIZE,
+            &format_value(value, chart.unit.as_deref()),
+        );
+    }
+
+    page.fill_gray(SERIES_GRAY);
+    match chart.kind {
+        ChartKind::Bar => {
+            let bar_width = layout.slot_width(count) * BAR_FILL;
+            for (index, point) in chart.points.iter().enumerate() {
+                let top = layout.y(point.value);
+                page.fill_rect(
+                    x + layout.x(index, count) - bar_width / 2.0,
+                    y + top,
+                    bar_width,
+                    layout.plot_bottom() - top,
+                );
+            }
+        }
+        ChartKind::Line => {
+            let positions: Vec<(f32, f32)> = chart.points.iter().enumerate()
+                .map(|(index, point)| (x + layout.x(index, count), y + layout.y(point.value)))
+                .collect();
+            for pair in positions.windows(2) {
+                page.line(pair[0].0, pair[0].1, pair[1].0, pair[1].1, 1.5);
+            }
+            for (point_x, point_y) in positions {
+                page.fill_rect(point_x - MARKER_SIZE / 2.0, point_y - MARKER_SIZE / 2.0, MARKER_SIZE, MARKER_SIZE);
+            }
+        }
+    }
+
+    page.fill_gray(0.0);
+    for (index, point) in chart.points.iter().enumerate() {
+        if Layout::shows_label(index, count) {
+            let center = x + layout.x(index, count);
+            let label_width = text_width(&point.label, Font::Regular, LABEL_FONT_SIZE);
+            page.text(
+                center - label_width / 2.0,
+                y + layout.plot_bottom() + LABEL_FONT_SIZE + 4.0,
+                Font::Regular,
+                LABEL_FONT_SIZE,
+                &point.label,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ChartPoint;
+    use crate::reports::pdf::{PdfDocument, RenderedPdf};
+    use chrono::Utc;
+
+    fn chart(kind: ChartKind, values: &[f64]) -> ChartSpec {
+        ChartSpec {
+            title: "Compliance <trend>".to_string(),
+            kind,
+            unit: Some("%".to_string()),
+            max_value: Some(100.0),
+            points: values.iter().enumerate()
+                .map(|(index, value)| ChartPoint { label: format!("2026-{:02}", index + 1), value: *value })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_svg_has_one_mark_per_point_and_escapes_text() {
+        let bars = to_svg(&chart(ChartKind::Bar, &[40.0, 80.0, 100.0]), 400.0, 200.0);
+        assert!(bars.starts_with("<svg") && bars.ends_with("</svg>"));
+        assert_eq!(bars.matches("<rect").count(), 3);
+        assert!(bars.contains("Compliance &lt;trend&gt;"));
+        assert!(bars.contains(">100%<"));
+
+        let line = to_svg(&chart(ChartKind::Line, &[40.0, 80.0]), 400.0, 200.0);
+        assert_eq!(line.matches("<circle").count(), 2);
+        assert_eq!(line.matches("<polyline").count(), 1);
+    }
+
+    #[test]
+    fn test_layout_scales_values_and_thins_labels() {
+        let spec = chart(ChartKind::Bar, &[50.0]);
+        let layout = Layout::new(&spec, 400.0, 200.0);
+        assert_eq!(layout.y(0.0), layout.plot_bottom());
+        assert_eq!(layout.y(100.0), layout.plot_top);
+        assert_eq!(layout.y(250.0), layout.plot_top);
+        assert!((layout.y(50.0) - (layout.plot_top + layout.plot_height / 2.0)).abs() < 0.01);
+
+        let empty = ChartSpec { max_value: None, points: Vec::new(), ..spec };
+        assert_eq!(Layout::new(&empty, 400.0, 200.0).axis_max, 1.0);
+
+        assert_eq!((0..24).filter(|&index| Layout::shows_label(index, 24)).count(), 12);
+        assert_eq!((0..5).filter(|&index| Layout::shows_label(index, 5)).count(), 5);
+    }
+
+    #[test]
+    fn test_chart_draws_into_pdf() {
+        let mut document = PdfDocument::new("Charts", Utc::now());
+        let page = document.add_page();
+        draw(page, &chart(ChartKind::Line, &[40.0, 80.0, 60.0]), 40.0, 40.0, 500.0, 180.0);
+        let pdf = RenderedPdf::read(&document.to_bytes().unwrap());
+        assert!(pdf.text.contains("2026-03\n"));
+        assert_eq!(pdf.count("l"), 2);
+    }
+}
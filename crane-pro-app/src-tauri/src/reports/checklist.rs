@@ -0,0 +1,304 @@
+//! Blank inspection checklist for printing
+//!
+//! Used where phones and tablets are not allowed on site. The sheet carries
+//! a QR code linking back to the inspection so that, once the inspector is
+//! back at a device, the scan opens the right record to type results into.
+
+use super::pdf::{text_width, wrap_text, Font, PdfDocument, PdfPage, A4_HEIGHT, A4_WIDTH};
+use crate::errors::AppResult;
+use crate::models::{PaperChecklist, PaperChecklistItem, PAPER_CHECKLIST_BLANK_ROWS};
+use crate::qr;
+use chrono::{DateTime, Utc};
+
+const MARGIN: f32 = 40.0;
+const CONTENT_WIDTH: f32 = A4_WIDTH - 2.0 * MARGIN;
+const QR_SIZE: f32 = 84.0;
+
+/// Lowest point content may reach before the footer
+const BODY_BOTTOM: f32 = A4_HEIGHT - MARGIN - 24.0;
+
+const CHECK_COLUMN_WIDTH: f32 = 36.0;
+const NOTES_COLUMN_WIDTH: f32 = 130.0;
+const ITEM_COLUMN_WIDTH: f32 = CONTENT_WIDTH - 3.0 * CHECK_COLUMN_WIDTH - NOTES_COLUMN_WIDTH;
+const CHECK_COLUMNS: [&str; 3] = ["OK", "Defect", "N/A"];
+
+const HEADER_ROW_HEIGHT: f32 = 18.0;
+const SECTION_ROW_HEIGHT: f32 = 16.0;
+const ITEM_FONT_SIZE: f32 = 9.0;
+const GUIDANCE_FONT_SIZE: f32 = 7.0;
+const MIN_ITEM_ROW_HEIGHT: f32 = 22.0;
+const CHECKBOX_SIZE: f32 = 9.0;
+
+/// Height of the condition, findings and signature block at the end
+const SIGN_OFF_HEIGHT: f32 = 190.0;
+
+const CONDITIONS: [&str; 5] = ["Excellent", "Good", "Fair", "Poor", "Critical"];
+
+/// Render `checklist` as a PDF ready to print
+pub fn render_paper_checklist(checklist: &PaperChecklist, generated_at: DateTime<Utc>) -> AppResult<Vec<u8>> {
+    let code = qr::encode(&checklist.record_uri())?;
+    let mut document = PdfDocument::new(
+        &format!("Inspection checklist - {} (inspection #{})", checklist.asset_number, checklist.inspection_id),
+        generated_at,
+    );
+
+    let page = document.add_page();
+    let mut y = draw_heading(page, checklist);
+    page.qr_code(&code, A4_WIDTH - MARGIN - QR_SIZE, MARGIN - 8.0, QR_SIZE);
+    page.text_right(
+        A4_WIDTH - MARGIN,
+        MARGIN + QR_SIZE - 2.0,
+        Font::Regular,
+        7.0,
+        &format!("Inspection #{}", checklist.inspection_id),
+    );
+    y = draw_details(page, checklist, y);
+    y = draw_table_header(page, y);
+
+    let blank_rows;
+    let sections: Vec<(Option<&str>, &[PaperChecklistItem])> = if checklist.sections.is_empty() {
+        blank_rows = vec![PaperChecklistItem { name: String::new(), guidance: None }; PAPER_CHECKLIST_BLANK_ROWS];
+        vec![(None, blank_rows.as_slice())]
+    } else {
+        checklist.sections.iter().map(|s| (s.title.as_deref(), s.items.as_slice())).collect()
+    };
+
+    for (title, items) in sections {
+        if let Some(title) = title {
+            // Keep a section title with at least its first item
+            if y + SECTION_ROW_HEIGHT + MIN_ITEM_ROW_HEIGHT > BODY_BOTTOM {
+                y = continue_on_new_page(&mut document, checklist);
+            }
+            let page = current_page(&mut document);
+            page.fill_gray(0.88);
+            page.fill_rect(MARGIN, y, CONTENT_WIDTH, SECTION_ROW_HEIGHT);
+            page.fill_gray(0.0);
+            page.rect(MARGIN, y, CONTENT_WIDTH, SECTION_ROW_HEIGHT, 0.5);
+            page.text(MARGIN + 4.0, y + 11.5, Font::Bold, 9.0, title);
+            y += SECTION_ROW_HEIGHT;
+        }
+        for item in items {
+            let name_lines = wrap_text(&item.name, Font::Regular, ITEM_FONT_SIZE, ITEM_COLUMN_WIDTH - 8.0);
+            let guidance_lines = item.guidance.as_deref()
+                .map(|g| wrap_text(g, Font::Regular, GUIDANCE_FONT_SIZE, ITEM_COLUMN_WIDTH - 8.0))
+                .unwrap_or_default();
+            let height = (8.0 + name_lines.len() as f32 * 11.0 + guidance_lines.len() as f32 * 8.5)
+                .max(MIN_ITEM_ROW_HEIGHT);
+            if y + height > BODY_BOTTOM {
+                y = continue_on_new_page(&mut document, checklist);
+            }
+            draw_item_row(current_page(&mut document), y, height, &name_lines, &guidance_lines);
+            y += height;
+        }
+    }
+
+    if y + 16.0 + SIGN_OFF_HEIGHT > BODY_BOTTOM {
+        y = continue_on_new_page(&mut document, checklist);
+    } else {
+        y += 16.0;
+    }
+    draw_sign_off(current_page(&mut document), y);
+
+    let page_count = document.page_count();
+    for (index, page) in document.pages_mut().enumerate() {
+        draw_footer(page, index + 1, page_count);
+    }
+    document.to_bytes()
+}
+
+fn current_page(document: &mut PdfDocument) -> &mut PdfPage {
+    document.pages_mut().last().expect("the first page is added before drawing")
+}
+
+/// Title lines; returns where the next block starts
+fn draw_heading(page: &mut PdfPage, checklist: &PaperChecklist) -> f32 {
+    page.text(MARGIN, MARGIN + 16.0, Font::Bold, 18.0, "Inspection Checklist");
+    page.text(
+        MARGIN,
+        MARGIN + 34.0,
+        Font::Regular,
+        10.0,
+        &format!("{} inspection - {}", checklist.inspection_type, checklist.compliance_standard),
+    );
+    MARGIN + 50.0
+}
+
+fn draw_details(page: &mut PdfPage, checklist: &PaperChecklist, top: f32) -> f32 {
+    let scheduled = checklist.scheduled_date
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    let rows = [
+        ("Asset", checklist.asset_name.as_str(), "Asset number", checklist.asset_number.as_str()),
+        ("Location", checklist.location_name.as_str(), "Scheduled", scheduled.as_str()),
+        ("Inspector", checklist.inspector_name.as_str(), "Standard", checklist.compliance_standard.as_str()),
+        ("Date inspected", "", "Time", ""),
+    ];
+    // Leave room for the QR code on the right of the first rows
+    let column_width = (CONTENT_WIDTH - QR_SIZE - 12.0) / 2.0;
+    let mut y = top;
+    for (left_label, left_value, right_label, right_value) in rows {
+        for (x, label, value) in [
+            (MARGIN, left_label, left_value),
+            (MARGIN + column_width, right_label, right_value),
+        ] {
+            page.text(x, y + 10.0, Font::Bold, 8.0, label);
+            let value_x = x + 72.0;
+            let width = column_width - 80.0;
+            let value = fit_text(value, Font::Regular, 9.0, width);
+            page.text(value_x, y + 10.0, Font::Regular, 9.0, &value);
+            page.line(value_x, y + 13.0, value_x + width, y + 13.0, 0.4);
+        }
+        y += 18.0;
+    }
+    y.max(MARGIN + QR_SIZE + 4.0) + 10.0
+}
+
+/// `text` shortened with an ellipsis to fit in `width`
+fn fit_text(text: &str, font: Font, size: f32, width: f32) -> String {
+    if text_width(text, font, size) <= width {
+        return text.to_string();
+    }
+    let mut shortened = text.to_string();
+    while !shortened.is_empty() && text_width(&format!("{}…", shortened), font, size) > width {
+        shortened.pop();
+    }
+    format!("{}…", shortened.trim_end())
+}
+
+fn draw_table_header(page: &mut PdfPage, y: f32) -> f32 {
+    page.fill_gray(0.75);
+    page.fill_rect(MARGIN, y, CONTENT_WIDTH, HEADER_ROW_HEIGHT);
+    page.fill_gray(0.0);
+    page.rect(MARGIN, y, CONTENT_WIDTH, HEADER_ROW_HEIGHT, 0.5);
+    page.text(MARGIN + 4.0, y + 12.5, Font::Bold, 9.0, "Item");
+    let mut x = MARGIN + ITEM_COLUMN_WIDTH;
+    for label in CHECK_COLUMNS {
+        let width = text_width(label, Font::Bold, 8.0);
+        page.text(x + (CHECK_COLUMN_WIDTH - width) / 2.0, y + 12.5, Font::Bold, 8.0, label);
+        x += CHECK_COLUMN_WIDTH;
+    }
+    page.text(x + 4.0, y + 12.5, Font::Bold, 9.0, "Notes");
+    y + HEADER_ROW_HEIGHT
+}
+
+fn draw_item_row(page: &mut PdfPage, y: f32, height: f32, name_lines: &[String], guidance_lines: &[String]) {
+    page.rect(MARGIN, y, CONTENT_WIDTH, height, 0.5);
+    let mut text_y = y + 13.0;
+    for line in name_lines {
+        page.text(MARGIN + 4.0, text_y, Font::Regular, ITEM_FONT_SIZE, line);
+        text_y += 11.0;
+    }
+    if !guidance_lines.is_empty() {
+        page.fill_gray(0.35);
+        text_y -= 2.0;
+        for line in guidance_lines {
+            page.text(MARGIN + 4.0, text_y, Font::Regular, GUIDANCE_FONT_SIZE, line);
+            text_y += 8.5;
+        }
+        page.fill_gray(0.0);
+    }
+
+    let mut x = MARGIN + ITEM_COLUMN_WIDTH;
+    for _ in CHECK_COLUMNS {
+        page.line(x, y, x, y + height, 0.5);
+        page.rect(
+            x + (CHECK_COLUMN_WIDTH - CHECKBOX_SIZE) / 2.0,
+            y + (height - CHECKBOX_SIZE) / 2.0,
+            CHECKBOX_SIZE,
+            CHECKBOX_SIZE,
+            0.6,
+        );
+        x += CHECK_COLUMN_WIDTH;
+    }
+    page.line(x, y, x, y + height, 0.5);
+}
+
+/// Start another page with the heading and table header repeated
+fn continue_on_new_page(document: &mut PdfDocument, checklist: &PaperChecklist) -> f32 {
+    let page = document.add_page();
+    page.text(MARGIN, MARGIN + 12.0, Font::Bold, 11.0, "Inspection Checklist (continued)");
+    page.text_right(
+        A4_WIDTH - MARGIN,
+        MARGIN + 12.0,
+        Font::Regular,
+        9.0,
+        &format!("{} - inspection #{}", checklist.asset_number, checklist.inspection_id),
+    );
+    draw_table_header(page, MARGIN + 24.0)
+}
+
+fn draw_sign_off(page: &mut PdfPage, top: f32) {
+    page.text(MARGIN, top + 10.0, Font::Bold, 10.0, "Overall condition");
+    let mut x = MARGIN;
+    for condition in CONDITIONS {
+        page.rect(x, top + 18.0, CHECKBOX_SIZE, CHECKBOX_SIZE, 0.6);
+        page.text(x + CHECKBOX_SIZE + 4.0, top + 26.0, Font::Regular, 9.0, condition);
+        x += CHECKBOX_SIZE + 12.0 + text_width(condition, Font::Regular, 9.0);
+    }
+
+    page.text(MARGIN, top + 48.0, Font::Bold, 10.0, "Findings and recommendations");
+    page.rect(MARGIN, top + 54.0, CONTENT_WIDTH, 80.0, 0.5);
+
+    let signature_y = top + 172.0;
+    let fields = [("Inspector signature", 200.0), ("Date", 100.0), ("Reviewed by", 175.0)];
+    let gap = (CONTENT_WIDTH - fields.iter().map(|(_, width)| width).sum::<f32>()) / (fields.len() - 1) as f32;
+    let mut x = MARGIN;
+    for (label, width) in fields {
+        page.line(x, signature_y, x + width, signature_y, 0.5);
+        page.text(x, signature_y + 10.0, Font::Regular, 8.0, label);
+        x += width + gap;
+    }
+}
+
+fn draw_footer(page: &mut PdfPage, number: usize, count: usize) {
+    let y = A4_HEIGHT - MARGIN + 4.0;
+    page.fill_gray(0.35);
+    page.text(
+        MARGIN,
+        y,
+        Font::Regular,
+        7.0,
+        "Enter these results in CranePro against this inspection and attach a scan of every page.",
+    );
+    page.text_right(A4_WIDTH - MARGIN, y, Font::Regular, 7.0, &format!("Page {} of {}", number, count));
+    page.fill_gray(0.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{InspectionType, PaperChecklistSection};
+    use crate::reports::pdf::RenderedPdf;
+
+    fn checklist(items: usize) -> PaperChecklist {
+        PaperChecklist {
+            inspection_id: 42,
+            asset_number: "CR-0042".to_string(),
+            asset_name: "Bay 3 overhead crane".to_string(),
+            location_name: "Plant 1".to_string(),
+            inspection_type: InspectionType::Periodic,
+            compliance_standard: "OSHA 1910.179".to_string(),
+            scheduled_date: None,
+            inspector_name: "Jordan Lee".to_string(),
+            sections: vec![PaperChecklistSection {
+                title: Some("Hoist".to_string()),
+                items: (0..items)
+                    .map(|i| PaperChecklistItem {
+                        name: format!("Check item {}", i),
+                        guidance: Some("No cracks, wear within limits".to_string()),
+                    })
+                    .collect(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_long_checklists_continue_on_more_pages() {
+        let short = RenderedPdf::read(&render_paper_checklist(&checklist(5), Utc::now()).unwrap());
+        assert_eq!(short.pages, 1);
+
+        let long = RenderedPdf::read(&render_paper_checklist(&checklist(60), Utc::now()).unwrap());
+        assert!(long.pages > 1);
+        assert!(long.text.contains("Page 2 of "));
+    }
+}
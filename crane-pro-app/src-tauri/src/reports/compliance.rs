@@ -0,0 +1,249 @@
+//! Compliance report
+//!
+//! The PDF counterpart of the HTML compliance report: the asset and period
+//! covered, the compliance summary for its location, insurance and
+//! registration records, and the trend charts.
+
+use super::chart;
+use super::pdf::{wrap_text, Font, PdfDocument, PdfPage, A4_HEIGHT, A4_WIDTH};
+use crate::api::{ChartSpec, DateRange};
+use crate::errors::AppResult;
+use crate::i18n::{translate, translate_with, Locale, Localize};
+use crate::models::{Asset, AssetRecord};
+use crate::services::ComplianceStatusReport;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+const MARGIN: f32 = 40.0;
+const CONTENT_WIDTH: f32 = A4_WIDTH - 2.0 * MARGIN;
+
+/// Lowest point content may reach before the footer
+const BODY_BOTTOM: f32 = A4_HEIGHT - MARGIN - 24.0;
+
+const ROW_HEIGHT: f32 = 15.0;
+const CHART_HEIGHT: f32 = 170.0;
+const CHART_GAP: f32 = 16.0;
+
+/// Everything printed on a compliance report
+#[derive(Debug, Clone)]
+pub struct ComplianceReport<'a> {
+    pub asset: &'a Asset,
+    pub date_range: &'a DateRange,
+    pub status: &'a ComplianceStatusReport,
+    pub records: &'a [AssetRecord],
+    pub charts: &'a [ChartSpec],
+}
+
+/// Render `report` as a PDF with labels in `locale`
+pub fn render_compliance_report(report: &ComplianceReport, locale: Locale, generated_at: DateTime<Utc>) -> AppResult<Vec<u8>> {
+    let t = |key: &str| translate(locale, &format!("report.{}", key));
+    let mut document = PdfDocument::new(
+        &format!("{} - {}", t("compliance_report"), report.asset.asset_number),
+        generated_at,
+    );
+
+    let page = document.add_page();
+    page.text(MARGIN, MARGIN + 16.0, Font::Bold, 18.0, &t("compliance_report"));
+    page.text(
+        MARGIN,
+        MARGIN + 34.0,
+        Font::Regular,
+        10.0,
+        &format!("{} - {}", report.asset.asset_name, report.asset.asset_number),
+    );
+    let mut y = draw_summary(page, report, locale, MARGIN + 50.0);
+
+    if !report.records.is_empty() {
+        if y + 16.0 + 2.0 * ROW_HEIGHT > BODY_BOTTOM {
+            y = continue_on_new_page(&mut document, report, locale);
+        }
+        current_page(&mut document).text(MARGIN, y + 10.0, Font::Bold, 12.0, &t("asset_records"));
+        y = draw_records_header(current_page(&mut document), locale, y + 16.0);
+        for record in report.records {
+            if y + ROW_HEIGHT > BODY_BOTTOM {
+                y = continue_on_new_page(&mut document, report, locale);
+                y = draw_records_header(current_page(&mut document), locale, y);
+            }
+            draw_record(current_page(&mut document), record, locale, y);
+            y += ROW_HEIGHT;
+        }
+        y += 16.0;
+    }
+
+    let mut title_drawn = false;
+    for spec in report.charts {
+        let title_height = if title_drawn { 0.0 } else { 16.0 };
+        if y + title_height + CHART_HEIGHT > BODY_BOTTOM {
+            y = continue_on_new_page(&mut document, report, locale);
+        }
+        let page = current_page(&mut document);
+        if !title_drawn {
+            page.text(MARGIN, y + 10.0, Font::Bold, 12.0, &t("charts"));
+            y += title_height;
+            title_drawn = true;
+        }
+        chart::draw(page, spec, MARGIN, y, CONTENT_WIDTH, CHART_HEIGHT);
+        y += CHART_HEIGHT + CHART_GAP;
+    }
+
+    let page_count = document.page_count();
+    let generated = format!("{}: {}", t("generated_on"), generated_at.format("%Y-%m-%d %H:%M UTC"));
+    for (index, page) in document.pages_mut().enumerate() {
+        let page_label = translate_with(locale, "report.page", &HashMap::from([
+            ("page".to_string(), (index + 1).to_string()),
+            ("count".to_string(), page_count.to_string()),
+        ]));
+        draw_footer(page, &generated, &page_label);
+    }
+    document.to_bytes()
+}
+
+fn current_page(document: &mut PdfDocument) -> &mut PdfPage {
+    document.pages_mut().last().expect("the first page is added before drawing")
+}
+
+/// Report period and compliance figures in two columns
+fn draw_summary(page: &mut PdfPage, report: &ComplianceReport, locale: Locale, top: f32) -> f32 {
+    let t = |key: &str| translate(locale, &format!("report.{}", key));
+    let status = report.status;
+    let rows = [
+        (
+            t("from"),
+            report.date_range.start_date.format("%Y-%m-%d").to_string(),
+            t("to"),
+            report.date_range.end_date.format("%Y-%m-%d").to_string(),
+        ),
+        (t("total_assets"), status.total_assets.to_string(), t("compliance_percentage"), format!("{:.1}%", status.compliance_percentage)),
+        (t("compliant_assets"), status.compliant_assets.to_string(), t("non_compliant_assets"), status.non_compliant_assets.to_string()),
+        (t("critical_findings"), status.critical_findings.to_string(), t("overdue_inspections"), status.overdue_inspections.to_string()),
+        (t("expiring_records"), status.expiring_records.to_string(), String::new(), String::new()),
+    ];
+    let column_width = CONTENT_WIDTH / 2.0;
+    let mut y = top;
+    for (left_label, left_value, right_label, right_value) in rows {
+        for (x, label, value) in [
+            (MARGIN, left_label, left_value),
+            (MARGIN + column_width, right_label, right_value),
+        ] {
+            let label = wrap_text(&label, Font::Bold, 8.0, 150.0).into_iter().next().unwrap_or_default();
+            page.text(x, y + 10.0, Font::Bold, 8.0, &label);
+            page.text(x + 160.0, y + 10.0, Font::Regular, 9.0, &value);
+        }
+        y += ROW_HEIGHT;
+    }
+    page.line(MARGIN, y + 4.0, MARGIN + CONTENT_WIDTH, y + 4.0, 0.5);
+    y + 14.0
+}
+
+fn record_columns(locale: Locale) -> [(String, f32); 4] {
+    let t = |key: &str| translate(locale, &format!("report.{}", key));
+    let width = CONTENT_WIDTH / 4.0;
+    [
+        (t("record_kind"), width),
+        (t("reference_number"), width),
+        (t("issuer"), width),
+        (t("renewal_date"), width),
+    ]
+}
+
+fn draw_records_header(page: &mut PdfPage, locale: Locale, y: f32) -> f32 {
+    page.fill_gray(0.92);
+    page.fill_rect(MARGIN, y, CONTENT_WIDTH, ROW_HEIGHT);
+    page.fill_gray(0.0);
+    let mut x = MARGIN;
+    for (label, width) in record_columns(locale) {
+        page.text(x + 3.0, y + 10.5, Font::Bold, 8.0, &label);
+        x += width;
+    }
+    y + ROW_HEIGHT
+}
+
+fn draw_record(page: &mut PdfPage, record: &AssetRecord, locale: Locale, y: f32) {
+    let values = [
+        record.kind.localize(locale),
+        record.reference_number.clone(),
+        record.issuer.clone().unwrap_or_else(|| translate(locale, "common.not_available")),
+        record.renewal_date.format("%Y-%m-%d").to_string(),
+    ];
+    let mut x = MARGIN;
+    for ((_, width), value) in record_columns(locale).into_iter().zip(values) {
+        let value = wrap_text(&value, Font::Regular, 8.5, width - 6.0).into_iter().next().unwrap_or_default();
+        page.text(x + 3.0, y + 10.5, Font::Regular, 8.5, &value);
+        x += width;
+    }
+    page.line(MARGIN, y + ROW_HEIGHT, MARGIN + CONTENT_WIDTH, y + ROW_HEIGHT, 0.3);
+}
+
+/// Start another page with a short heading
+fn continue_on_new_page(document: &mut PdfDocument, report: &ComplianceReport, locale: Locale) -> f32 {
+    let page = document.add_page();
+    page.text(
+        MARGIN,
+        MARGIN + 12.0,
+        Font::Bold,
+        11.0,
+        &translate(locale, "report.compliance_report"),
+    );
+    page.text_right(A4_WIDTH - MARGIN, MARGIN + 12.0, Font::Regular, 9.0, &report.asset.asset_number);
+    MARGIN + 24.0
+}
+
+fn draw_footer(page: &mut PdfPage, generated: &str, page_label: &str) {
+    let y = A4_HEIGHT - MARGIN + 4.0;
+    page.fill_gray(0.35);
+    page.text(MARGIN, y, Font::Regular, 7.0, generated);
+    page.text_right(A4_WIDTH - MARGIN, y, Font::Regular, 7.0, page_label);
+    page.fill_gray(0.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{ChartKind, ChartPoint};
+    use crate::reports::pdf::RenderedPdf;
+    use crate::test_fixtures::test_asset;
+
+    fn status() -> ComplianceStatusReport {
+        ComplianceStatusReport {
+            location_id: Some(1),
+            total_assets: 4,
+            compliant_assets: 3,
+            non_compliant_assets: 1,
+            overdue_inspections: 0,
+            compliance_percentage: 75.0,
+            critical_findings: 2,
+            expiring_records: 0,
+            by_standard: HashMap::new(),
+        }
+    }
+
+    fn chart(title: &str) -> ChartSpec {
+        ChartSpec {
+            title: title.to_string(),
+            kind: ChartKind::Line,
+            unit: Some("%".to_string()),
+            max_value: Some(100.0),
+            points: vec![
+                ChartPoint { label: "2026-01".to_string(), value: 70.0 },
+                ChartPoint { label: "2026-02".to_string(), value: 85.0 },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_report_prints_summary_and_charts() {
+        let asset = test_asset();
+        let status = status();
+        let date_range = DateRange { start_date: Utc::now() - chrono::Duration::days(90), end_date: Utc::now() };
+        let charts: Vec<_> = ["Score trend", "Findings", "Completion", "Backlog"].into_iter().map(chart).collect();
+        let report = ComplianceReport { asset: &asset, date_range: &date_range, status: &status, records: &[], charts: &charts };
+
+        let pdf = RenderedPdf::read(&render_compliance_report(&report, Locale::En, Utc::now()).unwrap());
+
+        assert!(pdf.text.contains("75.0%"));
+        assert!(pdf.text.contains("Score trend"));
+        assert!(pdf.text.contains("Completion"));
+        assert!(pdf.pages >= 2);
+        assert!(pdf.text.contains("Page 2 of "));
+    }
+}
@@ -0,0 +1,489 @@
+//! Completed inspection report
+//!
+//! The PDF counterpart of the HTML inspection report: asset and inspection
+//! details, every checklist item with its condition, severity and finding,
+//! the photos taken, and a sign-off block for the inspector and reviewer.
+
+use super::pdf::{wrap_text, Font, PdfDocument, PdfImage, PdfPage, A4_HEIGHT, A4_WIDTH};
+use crate::errors::AppResult;
+use crate::i18n::{translate, translate_with, Locale, Localize};
+use crate::models::{Asset, Inspection, InspectionItem, Severity, StandardClause};
+use crate::units;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+const MARGIN: f32 = 40.0;
+const CONTENT_WIDTH: f32 = A4_WIDTH - 2.0 * MARGIN;
+
+/// Lowest point content may reach before the footer
+const BODY_BOTTOM: f32 = A4_HEIGHT - MARGIN - 24.0;
+
+const CONDITION_COLUMN_WIDTH: f32 = 62.0;
+const SEVERITY_COLUMN_WIDTH: f32 = 58.0;
+const COMPLIANT_COLUMN_WIDTH: f32 = 56.0;
+const CLAUSE_COLUMN_WIDTH: f32 = 64.0;
+const ITEM_COLUMN_WIDTH: f32 = CONTENT_WIDTH
+    - CONDITION_COLUMN_WIDTH
+    - SEVERITY_COLUMN_WIDTH
+    - COMPLIANT_COLUMN_WIDTH
+    - CLAUSE_COLUMN_WIDTH;
+
+const HEADER_ROW_HEIGHT: f32 = 18.0;
+const ITEM_FONT_SIZE: f32 = 9.0;
+const DETAIL_FONT_SIZE: f32 = 7.5;
+const LINE_HEIGHT: f32 = 11.0;
+const DETAIL_LINE_HEIGHT: f32 = 9.0;
+
+const PHOTOS_PER_ROW: usize = 2;
+const PHOTO_GAP: f32 = 16.0;
+const PHOTO_MAX_HEIGHT: f32 = 200.0;
+const CAPTION_HEIGHT: f32 = 14.0;
+
+/// Height of the signature block at the end
+const SIGN_OFF_HEIGHT: f32 = 90.0;
+
+/// A photo to print with the report
+#[derive(Debug, Clone)]
+pub struct ReportPhoto {
+    pub caption: String,
+    pub image: PdfImage,
+}
+
+/// Everything printed on an inspection report
+#[derive(Debug, Clone)]
+pub struct InspectionReport<'a> {
+    pub inspection: &'a Inspection,
+    pub asset: &'a Asset,
+    pub inspector_name: String,
+    pub items: &'a [InspectionItem],
+    /// Clauses cited by the items' findings
+    pub clauses: &'a [StandardClause],
+    pub photos: Vec<ReportPhoto>,
+}
+
+/// Render `report` as a PDF with labels in `locale`
+pub fn render_inspection_report(report: &InspectionReport, locale: Locale, generated_at: DateTime<Utc>) -> AppResult<Vec<u8>> {
+    let t = |key: &str| translate(locale, &format!("report.{}", key));
+    let mut document = PdfDocument::new(
+        &format!("{} - {} (#{})", t("inspection_report"), report.asset.asset_number, report.inspection.id),
+        generated_at,
+    );
+    let photos: Vec<_> = report.photos.iter()
+        .map(|photo| (document.add_image(photo.image.clone()), photo))
+        .collect();
+
+    let page = document.add_page();
+    page.text(MARGIN, MARGIN + 16.0, Font::Bold, 18.0, &t("inspection_report"));
+    page.text(
+        MARGIN,
+        MARGIN + 34.0,
+        Font::Regular,
+        10.0,
+        &format!("{} - {}", report.asset.asset_name, report.asset.asset_number),
+    );
+    let mut y = draw_details(page, report, locale, MARGIN + 50.0);
+
+    if let Some(notes) = report.inspection.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        let lines = wrap_text(notes, Font::Regular, ITEM_FONT_SIZE, CONTENT_WIDTH);
+        if y + 16.0 + lines.len() as f32 * LINE_HEIGHT > BODY_BOTTOM {
+            y = continue_on_new_page(&mut document, report, locale);
+        }
+        let page = current_page(&mut document);
+        page.text(MARGIN, y + 10.0, Font::Bold, 10.0, &t("notes"));
+        y += 16.0;
+        for line in lines {
+            if y + LINE_HEIGHT > BODY_BOTTOM {
+                y = continue_on_new_page(&mut document, report, locale);
+            }
+            current_page(&mut document).text(MARGIN, y + 9.0, Font::Regular, ITEM_FONT_SIZE, &line);
+            y += LINE_HEIGHT;
+        }
+        y += 10.0;
+    }
+
+    if !report.items.is_empty() {
+        if y + 16.0 + HEADER_ROW_HEIGHT + 2.0 * LINE_HEIGHT > BODY_BOTTOM {
+            y = continue_on_new_page(&mut document, report, locale);
+        }
+        current_page(&mut document).text(MARGIN, y + 10.0, Font::Bold, 12.0, &t("inspection_items"));
+        y = draw_table_header(current_page(&mut document), locale, y + 16.0);
+        for item in report.items {
+            let row = ItemRow::new(item, report.clauses, locale);
+            if y + row.height() > BODY_BOTTOM {
+                y = continue_on_new_page(&mut document, report, locale);
+                y = draw_table_header(current_page(&mut document), locale, y);
+            }
+            row.draw(current_page(&mut document), y);
+            y += row.height();
+        }
+        y += 16.0;
+    }
+
+    if !photos.is_empty() {
+        let photo_width = (CONTENT_WIDTH - PHOTO_GAP * (PHOTOS_PER_ROW - 1) as f32) / PHOTOS_PER_ROW as f32;
+        let mut title_drawn = false;
+        for row in photos.chunks(PHOTOS_PER_ROW) {
+            let row_height = row.iter()
+                .map(|(_, photo)| photo.image.height_at(photo_width).min(PHOTO_MAX_HEIGHT))
+                .fold(0.0, f32::max) + CAPTION_HEIGHT;
+            let title_height = if title_drawn { 0.0 } else { 16.0 };
+            if y + title_height + row_height > BODY_BOTTOM {
+                y = continue_on_new_page(&mut document, report, locale);
+            }
+            let page = current_page(&mut document);
+            if !title_drawn {
+                page.text(MARGIN, y + 10.0, Font::Bold, 12.0, &t("photos"));
+                y += title_height;
+                title_drawn = true;
+            }
+            let mut x = MARGIN;
+            for (id, photo) in row {
+                // Fit within the cell, keeping the aspect ratio
+                let mut width = photo_width;
+                let mut height = photo.image.height_at(width);
+                if height > PHOTO_MAX_HEIGHT {
+                    width *= PHOTO_MAX_HEIGHT / height;
+                    height = PHOTO_MAX_HEIGHT;
+                }
+                page.image(*id, x, y, width, height);
+                page.rect(x, y, width, height, 0.4);
+                let caption = wrap_text(&photo.caption, Font::Regular, DETAIL_FONT_SIZE, photo_width)
+                    .into_iter()
+                    .next()
+                    .unwrap_or_default();
+                page.text(x, y + height + 9.0, Font::Regular, DETAIL_FONT_SIZE, &caption);
+                x += photo_width + PHOTO_GAP;
+            }
+            y += row_height + 8.0;
+        }
+        y += 8.0;
+    }
+
+    if y + SIGN_OFF_HEIGHT > BODY_BOTTOM {
+        y = continue_on_new_page(&mut document, report, locale);
+    }
+    draw_sign_off(current_page(&mut document), report, locale, y);
+
+    let page_count = document.page_count();
+    let generated = format!("{}: {}", t("generated_on"), generated_at.format("%Y-%m-%d %H:%M UTC"));
+    for (index, page) in document.pages_mut().enumerate() {
+        let page_label = translate_with(locale, "report.page", &HashMap::from([
+            ("page".to_string(), (index + 1).to_string()),
+            ("count".to_string(), page_count.to_string()),
+        ]));
+        draw_footer(page, &generated, &page_label);
+    }
+    document.to_bytes()
+}
+
+fn current_page(document: &mut PdfDocument) -> &mut PdfPage {
+    document.pages_mut().last().expect("the first page is added before drawing")
+}
+
+fn format_date(date: Option<DateTime<Utc>>, locale: Locale) -> String {
+    date.map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| translate(locale, "common.not_available"))
+}
+
+fn draw_details(page: &mut PdfPage, report: &InspectionReport, locale: Locale, top: f32) -> f32 {
+    let t = |key: &str| translate(locale, &format!("report.{}", key));
+    let not_available = translate(locale, "common.not_available");
+    let inspection = report.inspection;
+    let asset = report.asset;
+    // Unrecognised units fall back to the stored text
+    let capacity = match asset.rated_capacity() {
+        Ok(Some(capacity)) => capacity.format_dual(),
+        _ => asset.capacity
+            .map(|value| {
+                format!("{} {}", units::format_number(value), asset.capacity_unit.as_deref().unwrap_or_default())
+                    .trim_end()
+                    .to_string()
+            })
+            .unwrap_or_else(|| not_available.clone()),
+    };
+    let rows = [
+        (t("asset_type"), asset.asset_type.clone(), t("inspection_id"), inspection.id.to_string()),
+        (t("capacity"), capacity, t("inspection_type"), inspection.inspection_type.localize(locale)),
+        (t("inspector"), report.inspector_name.clone(), t("status"), inspection.status.localize(locale)),
+        (
+            t("scheduled_date"),
+            format_date(inspection.scheduled_date, locale),
+            t("actual_date"),
+            format_date(inspection.actual_date, locale),
+        ),
+        (
+            t("overall_condition"),
+            inspection.overall_condition.as_ref().map(|c| c.localize(locale)).unwrap_or_else(|| not_available.clone()),
+            t("compliance_standard"),
+            inspection.compliance_standard.clone(),
+        ),
+    ];
+    let column_width = CONTENT_WIDTH / 2.0;
+    let mut y = top;
+    for (left_label, left_value, right_label, right_value) in rows {
+        for (x, label, value) in [
+            (MARGIN, left_label, left_value),
+            (MARGIN + column_width, right_label, right_value),
+        ] {
+            page.text(x, y + 10.0, Font::Bold, 8.0, &label);
+            let value = wrap_text(&value, Font::Regular, 9.0, column_width - 100.0)
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+            page.text(x + 92.0, y + 10.0, Font::Regular, 9.0, &value);
+        }
+        y += 15.0;
+    }
+    page.line(MARGIN, y + 4.0, MARGIN + CONTENT_WIDTH, y + 4.0, 0.5);
+    y + 14.0
+}
+
+fn table_columns(locale: Locale) -> [(String, f32); 5] {
+    let t = |key: &str| translate(locale, &format!("report.{}", key));
+    [
+        (t("item_name"), ITEM_COLUMN_WIDTH),
+        (t("condition"), CONDITION_COLUMN_WIDTH),
+        (t("severity"), SEVERITY_COLUMN_WIDTH),
+        (t("compliant"), COMPLIANT_COLUMN_WIDTH),
+        (t("clause"), CLAUSE_COLUMN_WIDTH),
+    ]
+}
+
+fn draw_table_header(page: &mut PdfPage, locale: Locale, y: f32) -> f32 {
+    page.fill_gray(0.75);
+    page.fill_rect(MARGIN, y, CONTENT_WIDTH, HEADER_ROW_HEIGHT);
+    page.fill_gray(0.0);
+    page.rect(MARGIN, y, CONTENT_WIDTH, HEADER_ROW_HEIGHT, 0.5);
+    let mut x = MARGIN;
+    for (label, width) in table_columns(locale) {
+        let label = wrap_text(&label, Font::Bold, 8.0, width - 8.0).into_iter().next().unwrap_or_default();
+        page.text(x + 4.0, y + 12.5, Font::Bold, 8.0, &label);
+        x += width;
+    }
+    y + HEADER_ROW_HEIGHT
+}
+
+/// One checklist item laid out for the table
+struct ItemRow {
+    name: Vec<String>,
+    /// Category, finding and corrective action under the name
+    details: Vec<String>,
+    /// Condition, severity, compliance and clause columns
+    cells: [Vec<String>; 4],
+    /// Shaded to stand out: non-compliant, or high or critical severity
+    flagged: bool,
+}
+
+impl ItemRow {
+    fn new(item: &InspectionItem, clauses: &[StandardClause], locale: Locale) -> Self {
+        let t = |key: &str| translate(locale, &format!("report.{}", key));
+        let not_available = translate(locale, "common.not_available");
+        let detail_width = ITEM_COLUMN_WIDTH - 8.0;
+
+        let mut details = wrap_text(&item.item_category, Font::Regular, DETAIL_FONT_SIZE, detail_width);
+        for (label, text) in [(t("finding"), &item.finding), (t("corrective_action"), &item.corrective_action)] {
+            if let Some(text) = text.as_deref().filter(|text| !text.trim().is_empty()) {
+                details.extend(wrap_text(&format!("{}: {}", label, text), Font::Regular, DETAIL_FONT_SIZE, detail_width));
+            }
+        }
+
+        let clause = item.clause_id
+            .and_then(|id| clauses.iter().find(|c| c.id == id))
+            .map(|c| c.clause_ref.clone());
+        let values = [
+            (item.condition.as_ref().map(|c| c.localize(locale)), CONDITION_COLUMN_WIDTH),
+            (item.severity.as_ref().map(|s| s.localize(locale)), SEVERITY_COLUMN_WIDTH),
+            (
+                item.is_compliant.map(|c| translate(locale, if c { "common.yes" } else { "common.no" })),
+                COMPLIANT_COLUMN_WIDTH,
+            ),
+            (clause, CLAUSE_COLUMN_WIDTH),
+        ];
+        let cells = values.map(|(value, width)| {
+            wrap_text(&value.unwrap_or_else(|| not_available.clone()), Font::Regular, ITEM_FONT_SIZE, width - 8.0)
+        });
+
+        Self {
+            name: wrap_text(&item.item_name, Font::Bold, ITEM_FONT_SIZE, detail_width),
+            details,
+            cells,
+            flagged: item.is_compliant == Some(false)
+                || matches!(item.severity, Some(Severity::High | Severity::Critical)),
+        }
+    }
+
+    fn height(&self) -> f32 {
+        let item = self.name.len() as f32 * LINE_HEIGHT + self.details.len() as f32 * DETAIL_LINE_HEIGHT;
+        let cells = self.cells.iter().map(|lines| lines.len()).max().unwrap_or(1) as f32 * LINE_HEIGHT;
+        item.max(cells) + 8.0
+    }
+
+    fn draw(&self, page: &mut PdfPage, y: f32) {
+        let height = self.height();
+        if self.flagged {
+            page.fill_gray(0.92);
+            page.fill_rect(MARGIN, y, CONTENT_WIDTH, height);
+            page.fill_gray(0.0);
+        }
+        page.rect(MARGIN, y, CONTENT_WIDTH, height, 0.5);
+
+        let mut text_y = y + 13.0;
+        for line in &self.name {
+            page.text(MARGIN + 4.0, text_y, Font::Bold, ITEM_FONT_SIZE, line);
+            text_y += LINE_HEIGHT;
+        }
+        page.fill_gray(0.3);
+        text_y -= 1.0;
+        for line in &self.details {
+            page.text(MARGIN + 4.0, text_y, Font::Regular, DETAIL_FONT_SIZE, line);
+            text_y += DETAIL_LINE_HEIGHT;
+        }
+        page.fill_gray(0.0);
+
+        let mut x = MARGIN + ITEM_COLUMN_WIDTH;
+        let widths = [CONDITION_COLUMN_WIDTH, SEVERITY_COLUMN_WIDTH, COMPLIANT_COLUMN_WIDTH, CLAUSE_COLUMN_WIDTH];
+        for (lines, width) in self.cells.iter().zip(widths) {
+            page.line(x, y, x, y + height, 0.5);
+            for (index, line) in lines.iter().enumerate() {
+                page.text(x + 4.0, y + 13.0 + index as f32 * LINE_HEIGHT, Font::Regular, ITEM_FONT_SIZE, line);
+            }
+            x += width;
+        }
+    }
+}
+
+/// Start another page with a short heading
+fn continue_on_new_page(document: &mut PdfDocument, report: &InspectionReport, locale: Locale) -> f32 {
+    let page = document.add_page();
+    page.text(
+        MARGIN,
+        MARGIN + 12.0,
+        Font::Bold,
+        11.0,
+        &translate(locale, "report.inspection_report"),
+    );
+    page.text_right(
+        A4_WIDTH - MARGIN,
+        MARGIN + 12.0,
+        Font::Regular,
+        9.0,
+        &format!("{} - #{}", report.asset.asset_number, report.inspection.id),
+    );
+    MARGIN + 24.0
+}
+
+/// Inspector and reviewer signature lines, the inspector's printed under
+/// their name with the date the inspection was carried out
+fn draw_sign_off(page: &mut PdfPage, report: &InspectionReport, locale: Locale, top: f32) {
+    let t = |key: &str| translate(locale, &format!("report.{}", key));
+    page.text(MARGIN, top + 10.0, Font::Bold, 12.0, &t("sign_off"));
+
+    let signature_y = top + 56.0;
+    let blocks = [
+        (t("inspector"), Some(report.inspector_name.as_str()), report.inspection.actual_date),
+        (t("reviewed_by"), None, None),
+    ];
+    let block_width = (CONTENT_WIDTH - 40.0) / 2.0;
+    let mut x = MARGIN;
+    for (role, name, date) in blocks {
+        page.text(x, top + 30.0, Font::Bold, 9.0, &role);
+        if let Some(name) = name {
+            page.text(x, top + 42.0, Font::Regular, 9.0, name);
+        }
+        page.line(x, signature_y, x + block_width * 0.6, signature_y, 0.5);
+        page.text(x, signature_y + 10.0, Font::Regular, 8.0, &t("signature"));
+        let date_x = x + block_width * 0.65;
+        if let Some(date) = date {
+            page.text(date_x, signature_y - 3.0, Font::Regular, 9.0, &date.format("%Y-%m-%d").to_string());
+        }
+        page.line(date_x, signature_y, x + block_width, signature_y, 0.5);
+        page.text(date_x, signature_y + 10.0, Font::Regular, 8.0, &t("date"));
+        x += block_width + 40.0;
+    }
+}
+
+fn draw_footer(page: &mut PdfPage, generated: &str, page_label: &str) {
+    let y = A4_HEIGHT - MARGIN + 4.0;
+    page.fill_gray(0.35);
+    page.text(MARGIN, y, Font::Regular, 7.0, generated);
+    page.text_right(A4_WIDTH - MARGIN, y, Font::Regular, 7.0, page_label);
+    page.fill_gray(0.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Condition;
+    use crate::reports::pdf::RenderedPdf;
+    use crate::test_fixtures::{test_asset, test_inspection};
+
+    fn item(index: usize) -> InspectionItem {
+        InspectionItem {
+            id: index as i64,
+            inspection_id: 1,
+            component_id: None,
+            item_name: format!("Wire rope section {}", index),
+            item_category: "Hoist".to_string(),
+            condition: Some(Condition::Poor),
+            finding: Some("Broken wires in one lay length exceed the discard criteria".to_string()),
+            severity: Some(Severity::Critical),
+            is_compliant: Some(false),
+            corrective_action: Some("Replace the rope before further use".to_string()),
+            created_at: Utc::now(),
+            clause_id: None,
+            risk_rating: None,
+            response_due_at: None,
+            measured_value: None,
+            measurement_unit: None,
+        }
+    }
+
+    fn photo() -> ReportPhoto {
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgb8(image::RgbImage::new(40, 30))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .unwrap();
+        ReportPhoto { caption: "Hook throat".to_string(), image: PdfImage::from_file_bytes(&png).unwrap() }
+    }
+
+    #[test]
+    fn test_report_prints_items_photos_and_sign_off() {
+        let inspection = test_inspection();
+        let asset = test_asset();
+        let items: Vec<_> = (0..3).map(item).collect();
+        let report = InspectionReport {
+            inspection: &inspection,
+            asset: &asset,
+            inspector_name: "Jordan Lee".to_string(),
+            items: &items,
+            clauses: &[],
+            photos: vec![photo()],
+        };
+        let pdf = RenderedPdf::read(&render_inspection_report(&report, Locale::En, Utc::now()).unwrap());
+
+        assert!(pdf.text.contains("Wire rope section 2"));
+        assert!(pdf.text.contains("Critical"));
+        assert_eq!(pdf.count("Do"), 1);
+        assert!(pdf.text.contains("Jordan Lee"));
+        assert!(pdf.text.contains("Page 1 of 1"));
+    }
+
+    #[test]
+    fn test_long_reports_continue_on_more_pages() {
+        let inspection = test_inspection();
+        let asset = test_asset();
+        let items: Vec<_> = (0..40).map(item).collect();
+        let report = InspectionReport {
+            inspection: &inspection,
+            asset: &asset,
+            inspector_name: "Jordan Lee".to_string(),
+            items: &items,
+            clauses: &[],
+            photos: (0..6).map(|_| photo()).collect(),
+        };
+        let pdf = RenderedPdf::read(&render_inspection_report(&report, Locale::De, Utc::now()).unwrap());
+
+        assert!(pdf.pages > 2);
+        assert!(pdf.text.contains("Seite 2 von "));
+    }
+}
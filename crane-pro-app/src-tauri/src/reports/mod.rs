@@ -0,0 +1,19 @@
+//! Printable documents
+//!
+//! PDF output is laid out in [`pdf`] and written with the `printpdf` crate,
+//! using the standard Helvetica fonts every PDF reader has, so no fonts need
+//! to be bundled. Layouts for particular documents live in their own modules,
+//! charts for both PDF and HTML output are drawn by [`chart`], and sites can
+//! supply their own HTML layouts through [`template`]. Generated PDFs are
+//! signed by [`signature`], and generated files of every format are kept,
+//! reused and expired by [`store`].
+
+pub mod pdf;
+pub mod chart;
+pub mod checklist;
+pub mod compliance;
+pub mod inspection;
+pub mod permission_matrix;
+pub mod template;
+pub mod signature;
+pub mod store;
@@ -0,0 +1,447 @@
+//! PDF documents for reports
+//!
+//! Reports are laid out as text, lines, boxes, QR codes and photos on A4
+//! pages, and written out with `printpdf`. Photos are re-encoded as JPEG,
+//! which PDF readers decode natively. Text is set in Helvetica or
+//! Helvetica-Bold with WinAnsi encoding, so characters outside Western
+//! European scripts are left out. Coordinates are in points (1/72 inch)
+//! measured from the top left of the page.
+
+use crate::errors::{AppError, AppResult};
+use crate::qr::{QrCode, QR_QUIET_ZONE};
+use chrono::{DateTime, Utc};
+use image::codecs::jpeg::JpegEncoder;
+use image::ColorType;
+use printpdf::path::{PaintMode, WindingOrder};
+use printpdf::{
+    BuiltinFont, Color, ColorBits, ColorSpace, CustomPdfConformance, Greyscale, Image, ImageFilter, ImageTransform,
+    ImageXObject, IndirectFontRef, Line, Mm, OffsetDateTime, PdfConformance, PdfLayerReference, Point, Pt, Px, Rect,
+};
+
+/// A4 portrait, in points
+pub const A4_WIDTH: f32 = 595.0;
+pub const A4_HEIGHT: f32 = 842.0;
+
+/// Glyph widths of Helvetica for characters 32 to 126, in 1/1000 em
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Glyph widths of Helvetica-Bold for characters 32 to 126, in 1/1000 em
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+/// Width used for characters outside the tables
+const DEFAULT_GLYPH_WIDTH: u16 = 556;
+
+/// Longest side of an embedded image in pixels; enough for print at the
+/// sizes reports use them, and keeps a report of many photos small
+const MAX_IMAGE_PIXELS: u32 = 1200;
+const JPEG_QUALITY: u8 = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn builtin(&self) -> BuiltinFont {
+        match self {
+            Font::Regular => BuiltinFont::Helvetica,
+            Font::Bold => BuiltinFont::HelveticaBold,
+        }
+    }
+
+    fn glyph_width(&self, c: char) -> u16 {
+        let table = match self {
+            Font::Regular => &HELVETICA_WIDTHS,
+            Font::Bold => &HELVETICA_BOLD_WIDTHS,
+        };
+        match c as u32 {
+            code @ 32..=126 => table[(code - 32) as usize],
+            _ => DEFAULT_GLYPH_WIDTH,
+        }
+    }
+}
+
+/// Width of `text` set in `font` at `size` points
+pub fn text_width(text: &str, font: Font, size: f32) -> f32 {
+    text.chars().map(|c| font.glyph_width(c) as f32).sum::<f32>() * size / 1000.0
+}
+
+/// Break `text` into lines no wider than `max_width`, at spaces where
+/// possible. Words too long for a line are split.
+pub fn wrap_text(text: &str, font: Font, size: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+            if text_width(&candidate, font, size) <= max_width {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            for c in word.chars() {
+                if !line.is_empty() && text_width(&format!("{}{}", line, c), font, size) > max_width {
+                    lines.push(std::mem::take(&mut line));
+                }
+                line.push(c);
+            }
+        }
+        lines.push(line);
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+/// An RGB JPEG ready to embed
+#[derive(Debug, Clone)]
+pub struct PdfImage {
+    pub width: u32,
+    pub height: u32,
+    data: Vec<u8>,
+}
+
+impl PdfImage {
+    /// Decode an image file in any format the `image` crate reads, scaling
+    /// it down to at most [`MAX_IMAGE_PIXELS`] on its longest side
+    pub fn from_file_bytes(data: &[u8]) -> AppResult<Self> {
+        let decoded = image::load_from_memory(data)?;
+        let decoded = if decoded.width().max(decoded.height()) > MAX_IMAGE_PIXELS {
+            decoded.thumbnail(MAX_IMAGE_PIXELS, MAX_IMAGE_PIXELS)
+        } else {
+            decoded
+        };
+        let rgb = decoded.to_rgb8();
+        let mut jpeg = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+            .encode(rgb.as_raw(), rgb.width(), rgb.height(), ColorType::Rgb8)?;
+        Ok(Self { width: rgb.width(), height: rgb.height(), data: jpeg })
+    }
+
+    /// Height of the image drawn `width` points wide
+    pub fn height_at(&self, width: f32) -> f32 {
+        width * self.height as f32 / self.width.max(1) as f32
+    }
+}
+
+/// An image added to a [`PdfDocument`], for drawing on any of its pages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageId(usize);
+
+/// A drawing operation, in report coordinates
+#[derive(Debug, Clone)]
+enum Operation {
+    Text { x: f32, y: f32, font: Font, size: f32, text: String },
+    FillGray(f32),
+    Line { x1: f32, y1: f32, x2: f32, y2: f32, width: f32 },
+    Rect { x: f32, y: f32, width: f32, height: f32, line_width: Option<f32> },
+    Image { id: ImageId, x: f32, y: f32, width: f32, height: f32 },
+}
+
+/// One page's drawing operations
+#[derive(Debug, Clone, Default)]
+pub struct PdfPage {
+    operations: Vec<Operation>,
+}
+
+impl PdfPage {
+    /// Text with its baseline `y` points from the top
+    pub fn text(&mut self, x: f32, y: f32, font: Font, size: f32, text: &str) {
+        self.operations.push(Operation::Text { x, y, font, size, text: text.to_string() });
+    }
+
+    /// Text ending at `right`
+    pub fn text_right(&mut self, right: f32, y: f32, font: Font, size: f32, text: &str) {
+        self.text(right - text_width(text, font, size), y, font, size, text);
+    }
+
+    /// Grey level of text and filled shapes drawn after this, from 0 (black)
+    /// to 1 (white)
+    pub fn fill_gray(&mut self, gray: f32) {
+        self.operations.push(Operation::FillGray(gray.clamp(0.0, 1.0)));
+    }
+
+    pub fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, width: f32) {
+        self.operations.push(Operation::Line { x1, y1, x2, y2, width });
+    }
+
+    /// Outline of the box whose top left corner is (`x`, `y`)
+    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, line_width: f32) {
+        self.operations.push(Operation::Rect { x, y, width, height, line_width: Some(line_width) });
+    }
+
+    /// Box filled with the current fill grey
+    pub fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.operations.push(Operation::Rect { x, y, width, height, line_width: None });
+    }
+
+    /// Image `id` scaled to `width` by `height` points with its top left
+    /// corner at (`x`, `y`)
+    pub fn image(&mut self, id: ImageId, x: f32, y: f32, width: f32, height: f32) {
+        self.operations.push(Operation::Image { id, x, y, width, height });
+    }
+
+    /// A QR code `size` points square, including its quiet zone, with its
+    /// top left corner at (`x`, `y`)
+    pub fn qr_code(&mut self, code: &QrCode, x: f32, y: f32, size: f32) {
+        let module = size / (code.size + 2 * QR_QUIET_ZONE) as f32;
+        let origin = QR_QUIET_ZONE as f32 * module;
+        self.fill_gray(0.0);
+        for row in 0..code.size {
+            for column in 0..code.size {
+                if code.is_dark(column, row) {
+                    self.fill_rect(x + origin + column as f32 * module, y + origin + row as f32 * module, module, module);
+                }
+            }
+        }
+    }
+}
+
+/// A point `y` points from the top of the page
+fn point(x: f32, y: f32) -> Point {
+    Point { x: Pt(x), y: Pt(A4_HEIGHT - y) }
+}
+
+fn pdf_error(error: printpdf::Error) -> AppError {
+    AppError::ReportGeneration { report_type: "pdf".to_string(), reason: error.to_string() }
+}
+
+/// A document built page by page and written out with [`PdfDocument::to_bytes`]
+#[derive(Debug, Clone)]
+pub struct PdfDocument {
+    title: String,
+    created_at: DateTime<Utc>,
+    pages: Vec<PdfPage>,
+    images: Vec<PdfImage>,
+}
+
+impl PdfDocument {
+    pub fn new(title: &str, created_at: DateTime<Utc>) -> Self {
+        Self { title: title.to_string(), created_at, pages: Vec::new(), images: Vec::new() }
+    }
+
+    /// Embed `image` once, to be drawn with [`PdfPage::image`]
+    pub fn add_image(&mut self, image: PdfImage) -> ImageId {
+        self.images.push(image);
+        ImageId(self.images.len() - 1)
+    }
+
+    /// Start a new A4 page and return it for drawing
+    pub fn add_page(&mut self) -> &mut PdfPage {
+        self.pages.push(PdfPage::default());
+        self.pages.last_mut().expect("page was just added")
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn pages_mut(&mut self) -> impl Iterator<Item = &mut PdfPage> {
+        self.pages.iter_mut()
+    }
+
+    /// The finished file
+    pub fn to_bytes(&self) -> AppResult<Vec<u8>> {
+        let created_at = OffsetDateTime::from_unix_timestamp(self.created_at.timestamp())
+            .map_err(|e| AppError::ReportGeneration { report_type: "pdf".to_string(), reason: e.to_string() })?;
+        let document = printpdf::PdfDocument::empty(self.title.as_str())
+            .with_producer("CranePro")
+            // Plain PDF: the builtin fonts rule out PDF/A, which would also embed an ICC profile
+            .with_conformance(PdfConformance::Custom(CustomPdfConformance {
+                allows_default_fonts: true,
+                ..CustomPdfConformance::default()
+            }))
+            .with_creation_date(created_at)
+            .with_mod_date(created_at);
+        let regular = document.add_builtin_font(Font::Regular.builtin()).map_err(pdf_error)?;
+        let bold = document.add_builtin_font(Font::Bold.builtin()).map_err(pdf_error)?;
+
+        for page in &self.pages {
+            let (page_index, layer_index) = document.add_page(Mm::from(Pt(A4_WIDTH)), Mm::from(Pt(A4_HEIGHT)), "Content");
+            let layer = document.get_page(page_index).get_layer(layer_index);
+            for operation in &page.operations {
+                let font = |font: Font| match font {
+                    Font::Regular => &regular,
+                    Font::Bold => &bold,
+                };
+                self.draw(&layer, operation, font);
+            }
+        }
+        document.save_to_bytes().map_err(pdf_error)
+    }
+
+    fn draw<'a>(&self, layer: &PdfLayerReference, operation: &Operation, font: impl Fn(Font) -> &'a IndirectFontRef) {
+        match operation {
+            Operation::Text { x, y, font: text_font, size, text } => {
+                layer.use_text(text.as_str(), *size, Mm::from(Pt(*x)), Mm::from(Pt(A4_HEIGHT - y)), font(*text_font));
+            }
+            Operation::FillGray(gray) => {
+                layer.set_fill_color(Color::Greyscale(Greyscale::new(*gray, None)));
+            }
+            Operation::Line { x1, y1, x2, y2, width } => {
+                layer.set_outline_thickness(*width);
+                layer.add_line(Line { points: vec![(point(*x1, *y1), false), (point(*x2, *y2), false)], is_closed: false });
+            }
+            Operation::Rect { x, y, width, height, line_width } => {
+                let mode = match line_width {
+                    Some(line_width) => {
+                        layer.set_outline_thickness(*line_width);
+                        PaintMode::Stroke
+                    }
+                    None => PaintMode::Fill,
+                };
+                layer.add_rect(Rect {
+                    ll: point(*x, y + height),
+                    ur: point(x + width, *y),
+                    mode,
+                    winding: WindingOrder::NonZero,
+                });
+            }
+            Operation::Image { id, x, y, width, height } => {
+                let image = &self.images[id.0];
+                let xobject = ImageXObject {
+                    width: Px(image.width as usize),
+                    height: Px(image.height as usize),
+                    color_space: ColorSpace::Rgb,
+                    bits_per_component: ColorBits::Bit8,
+                    interpolate: true,
+                    image_data: image.data.clone(),
+                    image_filter: Some(ImageFilter::DCT),
+                    smask: None,
+                    clipping_bbox: None,
+                };
+                // At 72 dpi one pixel is one point
+                Image::from(xobject).add_to_layer(layer.clone(), ImageTransform {
+                    translate_x: Some(Mm::from(Pt(*x))),
+                    translate_y: Some(Mm::from(Pt(A4_HEIGHT - y - height))),
+                    scale_x: Some(width / image.width.max(1) as f32),
+                    scale_y: Some(height / image.height.max(1) as f32),
+                    dpi: Some(72.0),
+                    ..ImageTransform::default()
+                });
+            }
+        }
+    }
+}
+
+/// A finished file read back, for checking rendered reports in tests
+#[cfg(test)]
+pub(crate) struct RenderedPdf {
+    pub pages: usize,
+    /// Text of every page, one line per text run
+    pub text: String,
+    /// Content stream operators of every page, e.g. `Tj` or `Do`
+    pub operators: Vec<String>,
+    document: printpdf::lopdf::Document,
+}
+
+#[cfg(test)]
+impl RenderedPdf {
+    pub fn read(bytes: &[u8]) -> Self {
+        use printpdf::lopdf::{content::Content, Document};
+
+        assert!(bytes.starts_with(b"%PDF-"));
+        let document = Document::load_mem(bytes).expect("a readable PDF");
+        let pages = document.get_pages();
+        let operations: Vec<_> = pages
+            .values()
+            .flat_map(|&id| Content::decode(&document.get_page_content(id).unwrap()).unwrap().operations)
+            .collect();
+        // Builtin fonts are WinAnsi encoded; extract_text would assume StandardEncoding
+        let text = operations
+            .iter()
+            .filter(|operation| operation.operator == "Tj")
+            .filter_map(|operation| operation.operands.first()?.as_str().ok())
+            .map(|bytes| Document::decode_text(Some("WinAnsiEncoding"), bytes) + "\n")
+            .collect();
+        let operators = operations.into_iter().map(|operation| operation.operator).collect();
+        Self { pages: pages.len(), text, operators, document }
+    }
+
+    pub fn count(&self, operator: &str) -> usize {
+        self.operators.iter().filter(|op| *op == operator).count()
+    }
+
+    /// Dictionaries of the embedded images
+    pub fn images(&self) -> Vec<&printpdf::lopdf::Dictionary> {
+        self.document
+            .objects
+            .values()
+            .filter_map(|object| object.as_stream().ok())
+            .filter(|stream| stream.dict.get(b"Subtype").and_then(|s| s.as_name()).ok() == Some(b"Image".as_slice()))
+            .map(|stream| &stream.dict)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_and_measure_text() {
+        assert_eq!(text_width("Hi", Font::Regular, 10.0), (722.0 + 222.0) / 100.0);
+        assert!(text_width("Hi", Font::Bold, 10.0) > text_width("Hi", Font::Regular, 10.0));
+
+        let lines = wrap_text("Check hook throat opening against the last recorded value", Font::Regular, 10.0, 120.0);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|line| text_width(line, Font::Regular, 10.0) <= 120.0));
+        assert_eq!(wrap_text("", Font::Regular, 10.0, 100.0), vec![String::new()]);
+        assert_eq!(wrap_text("WWWWWWWW", Font::Regular, 10.0, 30.0).len(), 3);
+    }
+
+    #[test]
+    fn test_document_structure() {
+        let mut document = PdfDocument::new("Checklist (draft)", Utc::now());
+        document.add_page().text(40.0, 40.0, Font::Bold, 12.0, "Hoist – «A»");
+        document.add_page().rect(40.0, 40.0, 10.0, 10.0, 0.5);
+        let rendered = RenderedPdf::read(&document.to_bytes().unwrap());
+
+        assert_eq!(rendered.pages, 2);
+        // En dash and guillemets survive WinAnsi encoding
+        assert_eq!(rendered.text, "Hoist – «A»\n");
+        assert_eq!(rendered.count("re"), 1);
+    }
+
+    #[test]
+    fn test_embedded_images() {
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgb8(image::RgbImage::new(2400, 1200))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .unwrap();
+        let image = PdfImage::from_file_bytes(&png).unwrap();
+        assert_eq!((image.width, image.height), (MAX_IMAGE_PIXELS, MAX_IMAGE_PIXELS / 2));
+        assert_eq!(image.height_at(100.0), 50.0);
+        assert!(PdfImage::from_file_bytes(b"not an image").is_err());
+
+        let mut document = PdfDocument::new("Photos", Utc::now());
+        let id = document.add_image(image);
+        document.add_page().image(id, 40.0, 40.0, 200.0, 100.0);
+        let rendered = RenderedPdf::read(&document.to_bytes().unwrap());
+
+        assert_eq!(rendered.count("Do"), 1);
+        let images = rendered.images();
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].get(b"Width").unwrap().as_i64().unwrap(), 1200);
+        assert_eq!(images[0].get(b"Height").unwrap().as_i64().unwrap(), 600);
+        assert!(format!("{:?}", images[0].get(b"Filter").unwrap()).contains("DCTDecode"));
+    }
+}
@@ -0,0 +1,169 @@
+//! Permission matrix for auditors
+//!
+//! One row per command, grouped by module, with the permissions it checks
+//! under its name and a column per role saying whether the role can run it.
+
+use super::pdf::{text_width, wrap_text, Font, PdfDocument, PdfPage, A4_HEIGHT, A4_WIDTH};
+use crate::authz::sign_in_label;
+use crate::errors::AppResult;
+use crate::models::{CommandAccess, CommandAuthorization, PermissionMatrix};
+
+const MARGIN: f32 = 40.0;
+const CONTENT_WIDTH: f32 = A4_WIDTH - 2.0 * MARGIN;
+
+/// Lowest point content may reach before the footer
+const BODY_BOTTOM: f32 = A4_HEIGHT - MARGIN - 24.0;
+
+const COMMAND_COLUMN_WIDTH: f32 = 215.0;
+const MAX_ROLE_COLUMN_WIDTH: f32 = 60.0;
+
+const NAME_FONT_SIZE: f32 = 8.0;
+const DETAIL_FONT_SIZE: f32 = 6.5;
+const ROLE_FONT_SIZE: f32 = 7.0;
+const MODULE_ROW_HEIGHT: f32 = 14.0;
+
+pub fn render_permission_matrix(matrix: &PermissionMatrix) -> AppResult<Vec<u8>> {
+    let mut document = PdfDocument::new("Permission matrix", matrix.generated_at);
+    let role_width = if matrix.roles.is_empty() {
+        MAX_ROLE_COLUMN_WIDTH
+    } else {
+        ((CONTENT_WIDTH - COMMAND_COLUMN_WIDTH) / matrix.roles.len() as f32).min(MAX_ROLE_COLUMN_WIDTH)
+    };
+
+    let page = document.add_page();
+    page.text(MARGIN, MARGIN + 16.0, Font::Bold, 18.0, "Permission Matrix");
+    page.text(
+        MARGIN,
+        MARGIN + 32.0,
+        Font::Regular,
+        9.0,
+        &format!(
+            "Who can run each command, from the checks the commands make. Generated {} UTC.",
+            matrix.generated_at.format("%Y-%m-%d %H:%M")
+        ),
+    );
+    let mut y = draw_header(page, matrix, role_width, MARGIN + 46.0);
+
+    let mut module = None;
+    for command in &matrix.commands {
+        let details = wrap_text(&requirement_text(command), Font::Regular, DETAIL_FONT_SIZE, COMMAND_COLUMN_WIDTH - 8.0);
+        let height = 8.0 + NAME_FONT_SIZE + details.len() as f32 * (DETAIL_FONT_SIZE + 1.5);
+        let new_module = module != Some(command.module.as_str());
+        let needed = height + if new_module { MODULE_ROW_HEIGHT } else { 0.0 };
+        if y + needed > BODY_BOTTOM {
+            let page = document.add_page();
+            y = draw_header(page, matrix, role_width, MARGIN);
+        }
+        let page = document.pages_mut().last().expect("the first page is added before drawing");
+        if new_module {
+            page.fill_gray(0.88);
+            page.fill_rect(MARGIN, y, CONTENT_WIDTH, MODULE_ROW_HEIGHT);
+            page.fill_gray(0.0);
+            page.text(MARGIN + 4.0, y + 10.0, Font::Bold, 8.0, &command.module.replace('_', " "));
+            y += MODULE_ROW_HEIGHT;
+            module = Some(command.module.as_str());
+        }
+        draw_row(page, command, &details, role_width, y, height);
+        y += height;
+    }
+
+    let page_count = document.page_count();
+    for (index, page) in document.pages_mut().enumerate() {
+        page.line(MARGIN, A4_HEIGHT - MARGIN - 14.0, A4_WIDTH - MARGIN, A4_HEIGHT - MARGIN - 14.0, 0.5);
+        page.text(MARGIN, A4_HEIGHT - MARGIN, Font::Regular, 7.0, "Partly: only for some inputs of the command");
+        page.text_right(
+            A4_WIDTH - MARGIN,
+            A4_HEIGHT - MARGIN,
+            Font::Regular,
+            7.0,
+            &format!("Page {} of {}", index + 1, page_count),
+        );
+    }
+    document.to_bytes()
+}
+
+/// Permissions and session a command needs, in a sentence
+fn requirement_text(command: &CommandAuthorization) -> String {
+    let mut parts = Vec::new();
+    if !command.required.is_empty() {
+        parts.push(format!("Needs {}", command.required.join(", ")));
+    }
+    if !command.conditional.is_empty() {
+        parts.push(format!("for some inputs {}", command.conditional.join(", ")));
+    }
+    parts.push(format!("sign-in: {}", sign_in_label(command).to_lowercase()));
+    parts.join("; ")
+}
+
+/// Column headings; returns where the first row starts
+fn draw_header(page: &mut PdfPage, matrix: &PermissionMatrix, role_width: f32, top: f32) -> f32 {
+    let role_lines: Vec<Vec<String>> = matrix.roles.iter()
+        .map(|role| wrap_text(role, Font::Bold, ROLE_FONT_SIZE, role_width - 4.0))
+        .collect();
+    let lines = role_lines.iter().map(Vec::len).max().unwrap_or(1).max(1);
+    let height = 8.0 + lines as f32 * (ROLE_FONT_SIZE + 1.5);
+
+    page.rect(MARGIN, top, CONTENT_WIDTH, height, 0.5);
+    page.text(MARGIN + 4.0, top + 4.0 + ROLE_FONT_SIZE, Font::Bold, ROLE_FONT_SIZE, "Command");
+    for (index, lines) in role_lines.iter().enumerate() {
+        let x = MARGIN + COMMAND_COLUMN_WIDTH + index as f32 * role_width;
+        page.line(x, top, x, top + height, 0.5);
+        for (line_index, line) in lines.iter().enumerate() {
+            let width = text_width(line, Font::Bold, ROLE_FONT_SIZE);
+            page.text(
+                x + (role_width - width) / 2.0,
+                top + 4.0 + ROLE_FONT_SIZE + line_index as f32 * (ROLE_FONT_SIZE + 1.5),
+                Font::Bold,
+                ROLE_FONT_SIZE,
+                line,
+            );
+        }
+    }
+    top + height
+}
+
+fn draw_row(page: &mut PdfPage, command: &CommandAuthorization, details: &[String], role_width: f32, y: f32, height: f32) {
+    page.rect(MARGIN, y, CONTENT_WIDTH, height, 0.25);
+    page.text(MARGIN + 4.0, y + 3.0 + NAME_FONT_SIZE, Font::Regular, NAME_FONT_SIZE, &command.command);
+    for (index, line) in details.iter().enumerate() {
+        page.text(
+            MARGIN + 4.0,
+            y + 4.0 + NAME_FONT_SIZE + (index + 1) as f32 * (DETAIL_FONT_SIZE + 1.5),
+            Font::Regular,
+            DETAIL_FONT_SIZE,
+            line,
+        );
+    }
+    for (index, access) in command.access.iter().enumerate() {
+        let x = MARGIN + COMMAND_COLUMN_WIDTH + index as f32 * role_width;
+        page.line(x, y, x, y + height, 0.25);
+        let label = access.to_string();
+        let font = if *access == CommandAccess::Denied { Font::Regular } else { Font::Bold };
+        let width = text_width(&label, font, NAME_FONT_SIZE);
+        page.text(x + (role_width - width) / 2.0, y + 3.0 + NAME_FONT_SIZE, font, NAME_FONT_SIZE, &label);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reports::pdf::RenderedPdf;
+    use chrono::Utc;
+
+    #[test]
+    fn test_matrix_renders_across_pages() {
+        let matrix = PermissionMatrix {
+            generated_at: Utc::now(),
+            roles: vec!["Inspector".to_string(), "Read-only auditor".to_string()],
+            commands: crate::authz::command_requirements().into_iter()
+                .map(|mut command| {
+                    command.access = vec![CommandAccess::Denied, CommandAccess::Partial];
+                    command
+                })
+                .collect(),
+        };
+        let pdf = RenderedPdf::read(&render_permission_matrix(&matrix).unwrap());
+        assert!(pdf.text.contains("delete_inspection_command"));
+        assert!(pdf.text.contains("Page 2 of "));
+    }
+}
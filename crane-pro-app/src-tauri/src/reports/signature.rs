@@ -0,0 +1,143 @@
+//! Tamper-evident report files
+//!
+//! A generated PDF is hashed with SHA-256 and the hash, with the time of
+//! signing, is signed with the installation's report key. The signature is
+//! appended after the file's `%%EOF` as a PDF comment, which readers skip,
+//! so the report opens as before and carries its own proof. Changing any
+//! byte before the comment breaks the hash; re-signing a changed file takes
+//! the installation's key.
+
+use crate::errors::{AppError, AppResult};
+use crate::security::signing;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Start of the comment line carrying the signature
+const SIGNATURE_MARKER: &[u8] = b"%CranePro-Signature ";
+
+/// The signature embedded in a report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportSignature {
+    /// Hex SHA-256 of the report as generated
+    pub sha256: String,
+    pub signed_at: DateTime<Utc>,
+    /// Hex Ed25519 key of the installation that signed it
+    pub public_key: String,
+    pub signature: String,
+}
+
+impl ReportSignature {
+    /// The bytes the signature is made over
+    fn message(sha256: &str, signed_at: DateTime<Utc>) -> Vec<u8> {
+        format!("{}\n{}", sha256, signed_at.to_rfc3339()).into_bytes()
+    }
+}
+
+/// What checking a report's signature found
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportVerification {
+    /// The report is unchanged since this installation signed it
+    pub valid: bool,
+    pub signature: Option<ReportSignature>,
+    pub key_fingerprint: Option<String>,
+    /// Why the report does not verify, when it does not
+    pub problem: Option<String>,
+}
+
+impl ReportVerification {
+    fn failed(signature: Option<ReportSignature>, problem: &str) -> Self {
+        Self {
+            key_fingerprint: signature.as_ref().map(|s| signing::key_fingerprint(&s.public_key)),
+            valid: false,
+            signature,
+            problem: Some(problem.to_string()),
+        }
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// `pdf` with its signature appended
+pub fn sign_pdf(mut pdf: Vec<u8>, signing_key: &[u8], signed_at: DateTime<Utc>) -> AppResult<Vec<u8>> {
+    if !pdf.ends_with(b"\n") {
+        pdf.push(b'\n');
+    }
+    let sha256 = sha256_hex(&pdf);
+    let signature = ReportSignature {
+        signature: signing::sign(signing_key, &ReportSignature::message(&sha256, signed_at))?,
+        public_key: signing::public_key(signing_key)?,
+        sha256,
+        signed_at,
+    };
+    let line = serde_json::to_vec(&signature)
+        .map_err(|e| AppError::internal(format!("Failed to serialize report signature: {}", e)))?;
+    pdf.extend_from_slice(SIGNATURE_MARKER);
+    pdf.extend(line);
+    pdf.push(b'\n');
+    Ok(pdf)
+}
+
+/// Check a report against its embedded signature and the public key of the
+/// installation that should have signed it
+pub fn verify_pdf(file: &[u8], expected_public_key: &str) -> ReportVerification {
+    let Some(start) = file.windows(SIGNATURE_MARKER.len()).rposition(|window| window == SIGNATURE_MARKER) else {
+        return ReportVerification::failed(None, "The report is not signed");
+    };
+    let (signed, line) = file.split_at(start);
+    let Ok(signature) = serde_json::from_slice::<ReportSignature>(line[SIGNATURE_MARKER.len()..].trim_ascii()) else {
+        return ReportVerification::failed(None, "The report's signature is unreadable");
+    };
+
+    if sha256_hex(signed) != signature.sha256 {
+        return ReportVerification::failed(Some(signature), "The report was changed after it was signed");
+    }
+    let message = ReportSignature::message(&signature.sha256, signature.signed_at);
+    if !signing::verify(&signature.public_key, &message, &signature.signature) {
+        return ReportVerification::failed(Some(signature), "The signature does not match the report");
+    }
+    if !signature.public_key.eq_ignore_ascii_case(expected_public_key.trim()) {
+        return ReportVerification::failed(Some(signature), "The report was signed by another installation's key");
+    }
+
+    ReportVerification {
+        key_fingerprint: Some(signing::key_fingerprint(&signature.public_key)),
+        valid: true,
+        signature: Some(signature),
+        problem: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::Ed25519KeyPair;
+
+    fn signing_key() -> Vec<u8> {
+        Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap().as_ref().to_vec()
+    }
+
+    #[test]
+    fn test_signed_report_verifies_until_changed() {
+        let key = signing_key();
+        let public_key = signing::public_key(&key).unwrap();
+        let pdf = b"%PDF-1.4\n1 0 obj\n<< >>\nendobj\n%%EOF\n".to_vec();
+        let signed = sign_pdf(pdf.clone(), &key, Utc::now()).unwrap();
+        assert!(signed.starts_with(&pdf));
+
+        let verification = verify_pdf(&signed, &public_key);
+        assert!(verification.valid, "{:?}", verification.problem);
+        assert_eq!(verification.signature.unwrap().sha256, sha256_hex(&pdf));
+
+        let mut tampered = signed.clone();
+        tampered[10] = b'2';
+        assert!(!verify_pdf(&tampered, &public_key).valid);
+        assert!(!verify_pdf(&pdf, &public_key).valid);
+
+        let other = signing::public_key(&signing_key()).unwrap();
+        assert!(!verify_pdf(&signed, &other).valid);
+    }
+}
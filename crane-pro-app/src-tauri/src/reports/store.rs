@@ -0,0 +1,162 @@
+//! Generated report files
+//!
+//! Reports are written under [`REPORTS_DIR`] and recorded with a key made
+//! from the parameters they were asked for and a fingerprint of the data they
+//! were built from. Asking for the same report again while its data is
+//! unchanged hands back the file already written instead of building it
+//! again. A background job deletes recorded reports, file and row, once they
+//! expire, and files nothing records once they are older than the report
+//! retention setting. Reports about records under legal hold are kept.
+
+use crate::api::ReportFormat;
+use crate::errors::{AppError, AppResult};
+use crate::i18n::Locale;
+use crate::services::Services;
+use crate::shutdown::ShutdownSignal;
+use chrono::{DateTime, Utc};
+use log::{debug, error, info, warn};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Directory generated report files are written to
+pub const REPORTS_DIR: &str = "./data/reports";
+
+/// How often expired reports are cleaned up
+pub const REPORT_CLEANUP_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Hex SHA-256 of `value` serialized as JSON. Maps are written with their
+/// keys in order, so equal values hash the same however they were built.
+pub fn fingerprint<T: Serialize + ?Sized>(value: &T) -> AppResult<String> {
+    let value = serde_json::to_value(value)
+        .map_err(|e| AppError::internal(format!("Failed to serialize report inputs: {}", e)))?;
+    let bytes = serde_json::to_vec(&value)
+        .map_err(|e| AppError::internal(format!("Failed to serialize report inputs: {}", e)))?;
+    Ok(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+/// What a generated report is looked up by
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportKey {
+    pub report_type: String,
+    /// Hash of the report type, format, locale and parameters
+    pub cache_key: String,
+    /// Hash of the data the report is built from
+    pub input_fingerprint: String,
+}
+
+impl ReportKey {
+    pub fn new<P, I>(report_type: &str, format: &ReportFormat, locale: Locale, parameters: &P, inputs: &I) -> AppResult<Self>
+    where
+        P: Serialize + ?Sized,
+        I: Serialize + ?Sized,
+    {
+        Ok(Self {
+            report_type: report_type.to_string(),
+            cache_key: fingerprint(&serde_json::json!({
+                "report_type": report_type,
+                "format": format,
+                "locale": locale,
+                "parameters": parameters,
+            }))?,
+            input_fingerprint: fingerprint(inputs)?,
+        })
+    }
+}
+
+/// Delete generated report files last modified more than `retention_days`
+/// ago, except those whose names start with one of `held_prefixes`.
+/// Returns the number of files deleted.
+pub fn purge_expired_reports(reports_dir: &Path, retention_days: i64, held_prefixes: &[String]) -> AppResult<usize> {
+    if !reports_dir.exists() {
+        return Ok(0);
+    }
+
+    let retention = std::time::Duration::from_secs(retention_days.max(0) as u64 * 24 * 60 * 60);
+    let mut purged = 0;
+    for entry in fs::read_dir(reports_dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        let expired = metadata.modified()?.elapsed().map(|age| age > retention).unwrap_or(false);
+        let name = entry.file_name();
+        let held = held_prefixes.iter().any(|prefix| name.to_string_lossy().starts_with(prefix.as_str()));
+        if metadata.is_file() && expired && !held {
+            fs::remove_file(entry.path())?;
+            purged += 1;
+        }
+    }
+
+    if purged > 0 {
+        info!("Purged {} report files older than {} days", purged, retention_days);
+    }
+    Ok(purged)
+}
+
+/// Delete expired recorded reports, then report files past the retention
+/// setting. Returns the number of files deleted.
+pub fn cleanup_reports(services: &Services, reports_dir: &Path, now: DateTime<Utc>) -> AppResult<usize> {
+    let settings = services.settings.get_settings()?;
+    // Without the hold list nothing is purged, so held reports survive
+    let held_prefixes = services.legal_holds.held_report_prefixes()?;
+
+    let expired = services.reports.purge_expired_artifacts(now, &held_prefixes)?;
+    let aged = purge_expired_reports(reports_dir, settings.report_retention_days, &held_prefixes)?;
+    Ok(expired + aged)
+}
+
+/// Background task cleaning up expired reports every `interval` until
+/// shutdown
+pub async fn run_report_cleanup(services: Arc<Services>, interval: Duration, mut shutdown: ShutdownSignal) {
+    info!("Cleaning up expired reports every {:?}", interval);
+    loop {
+        let cleanup = services.clone();
+        let outcome = tokio::task::spawn_blocking(move || {
+            cleanup_reports(&cleanup, Path::new(REPORTS_DIR), Utc::now())
+        }).await;
+        match outcome {
+            Ok(Ok(purged)) => debug!("Report cleanup deleted {} files", purged),
+            Ok(Err(e)) => warn!("Report cleanup failed: {}", e),
+            Err(e) => error!("Report cleanup task panicked: {}", e),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = shutdown.wait() => break,
+        }
+    }
+    debug!("Report cleanup stopped");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_report_key_follows_parameters_and_inputs() {
+        let mut first = HashMap::new();
+        let mut second = HashMap::new();
+        for (key, value) in [("a", 1), ("b", 2), ("c", 3)] {
+            first.insert(key, value);
+        }
+        for (key, value) in [("c", 3), ("a", 1), ("b", 2)] {
+            second.insert(key, value);
+        }
+        assert_eq!(fingerprint(&first).unwrap(), fingerprint(&second).unwrap());
+
+        let key = ReportKey::new("inspection", &ReportFormat::Pdf, Locale::default(), &7, &first).unwrap();
+        assert_eq!(key, ReportKey::new("inspection", &ReportFormat::Pdf, Locale::default(), &7, &second).unwrap());
+
+        let other_format = ReportKey::new("inspection", &ReportFormat::Html, Locale::default(), &7, &first).unwrap();
+        assert_ne!(key.cache_key, other_format.cache_key);
+        assert_eq!(key.input_fingerprint, other_format.input_fingerprint);
+
+        second.insert("c", 4);
+        let changed = ReportKey::new("inspection", &ReportFormat::Pdf, Locale::default(), &7, &second).unwrap();
+        assert_eq!(key.cache_key, changed.cache_key);
+        assert_ne!(key.input_fingerprint, changed.input_fingerprint);
+    }
+}
@@ -0,0 +1,52 @@
+//! Custom report templates
+//!
+//! Sites lay out HTML reports in their own corporate format with Handlebars
+//! templates. A template sees the same data as the JSON report of its type,
+//! so `{{inspection.asset_name}}` or `{{#each items}}` work as they read.
+//! Values are HTML-escaped unless written in triple braces.
+
+use crate::errors::{AppError, AppResult};
+use handlebars::{Handlebars, Template};
+use serde::Serialize;
+
+/// Check that `template` parses, reporting where it does not
+pub fn check_template(template: &str) -> AppResult<()> {
+    Template::compile(template)
+        .map(|_| ())
+        .map_err(|e| AppError::validation("template", format!("Invalid template: {}", e)))
+}
+
+/// Render `template` with `data`. Missing values render as empty text.
+pub fn render_template<T: Serialize>(template: &str, data: &T) -> AppResult<String> {
+    check_template(template)?;
+    let mut engine = Handlebars::new();
+    engine.set_strict_mode(false);
+    engine
+        .render_template(template, data)
+        .map_err(|e| AppError::validation("template", format!("Template could not be rendered: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_escapes_and_iterates() {
+        let data = serde_json::json!({
+            "inspection": { "asset_name": "Crane <A>" },
+            "items": [{ "item_name": "Hook" }, { "item_name": "Rope" }],
+        });
+        let html = render_template(
+            "<h1>{{inspection.asset_name}}</h1>{{#each items}}<li>{{item_name}}</li>{{/each}}{{missing}}",
+            &data,
+        ).unwrap();
+        assert_eq!(html, "<h1>Crane &lt;A&gt;</h1><li>Hook</li><li>Rope</li>");
+    }
+
+    #[test]
+    fn test_invalid_template_is_rejected() {
+        assert!(check_template("{{#each items}}<li>{{item_name}}</li>").is_err());
+        assert!(matches!(render_template("{{#if}}", &serde_json::json!({})), Err(AppError::Validation { .. })));
+        assert!(check_template("<p>{{inspection.notes}}</p>").is_ok());
+    }
+}
@@ -0,0 +1,220 @@
+//! Time-zone aware scheduling helpers
+//!
+//! Timestamps are stored in UTC, but due dates belong to the site where the
+//! crane is installed. Each location carries an IANA time zone name; these
+//! helpers do calendar arithmetic in that zone so that "due in 30 days" keeps
+//! the local time of day across DST changes and an inspection becomes
+//! overdue at local midnight after its due date rather than at UTC midnight.
+//!
+//! A site may also have a working calendar: the weekdays it works and the
+//! holidays and shutdowns it is closed for. Due dates that land on a day off
+//! move to the next working day, and so does the day they become overdue.
+
+use crate::errors::{AppError, AppResult};
+use chrono::{DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+
+/// Time zone used for locations that have not been configured
+pub const DEFAULT_TIME_ZONE: &str = "UTC";
+
+/// Every day of the week, the working days of a site without a calendar
+pub const ALL_WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun,
+];
+
+/// Furthest a date is moved looking for a working day; past this the date
+/// is left as it is rather than deferred indefinitely
+const MAX_WORKING_DAY_SEARCH_DAYS: i64 = 400;
+
+/// Parse an IANA time zone name such as `America/Chicago`
+pub fn parse_time_zone(name: &str) -> AppResult<Tz> {
+    name.trim()
+        .parse::<Tz>()
+        .map_err(|_| AppError::validation("time_zone", format!("Unknown time zone: {}", name)))
+}
+
+/// Parse a stored time zone name, falling back to UTC for missing or
+/// unrecognised values so legacy rows keep working
+pub fn time_zone_or_default(name: Option<&str>) -> Tz {
+    name.and_then(|name| parse_time_zone(name).ok()).unwrap_or(Tz::UTC)
+}
+
+/// Convert a local wall-clock time to UTC.
+///
+/// Ambiguous times (DST fall-back) resolve to the earlier instant; times that
+/// do not exist (DST spring-forward gap) are moved forward by an hour.
+fn resolve_local(tz: Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    let resolved = match tz.from_local_datetime(&local) {
+        LocalResult::Single(dt) => dt,
+        LocalResult::Ambiguous(earliest, _) => earliest,
+        LocalResult::None => tz
+            .from_local_datetime(&(local + Duration::hours(1)))
+            .earliest()
+            .unwrap_or_else(|| tz.from_utc_datetime(&local)),
+    };
+    resolved.with_timezone(&Utc)
+}
+
+/// Calendar date of an instant at the given location
+pub fn local_date(instant: DateTime<Utc>, tz: Tz) -> NaiveDate {
+    instant.with_timezone(&tz).date_naive()
+}
+
+/// UTC instant of local midnight at the start of `date`
+pub fn start_of_local_day(date: NaiveDate, tz: Tz) -> DateTime<Utc> {
+    resolve_local(tz, date.and_hms_opt(0, 0, 0).unwrap_or_default())
+}
+
+/// Instant at which something due at `due` becomes overdue: local midnight
+/// at the end of its due day
+pub fn overdue_at(due: DateTime<Utc>, tz: Tz) -> DateTime<Utc> {
+    let due_date = local_date(due, tz);
+    let next_day = due_date.succ_opt().unwrap_or(due_date);
+    start_of_local_day(next_day, tz)
+}
+
+/// Whether something due at `due` is overdue at `now`
+pub fn is_overdue(due: DateTime<Utc>, tz: Tz, now: DateTime<Utc>) -> bool {
+    now >= overdue_at(due, tz)
+}
+
+/// Add whole calendar days in local time, keeping the local time of day
+pub fn add_local_days(base: DateTime<Utc>, days: i64, tz: Tz) -> DateTime<Utc> {
+    let local = base.with_timezone(&tz).naive_local();
+    resolve_local(tz, local + Duration::days(days))
+}
+
+/// The latest instant at or before `now` when the local clock read `time`,
+/// such as this morning's 07:00 or, before 07:00, yesterday's
+pub fn latest_local_time(now: DateTime<Utc>, time: NaiveTime, tz: Tz) -> DateTime<Utc> {
+    let today = local_date(now, tz);
+    let at = resolve_local(tz, today.and_time(time));
+    if at <= now {
+        return at;
+    }
+    resolve_local(tz, today.pred_opt().unwrap_or(today).and_time(time))
+}
+
+/// Days a site works, and the date ranges it is closed for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkingCalendar {
+    working_days: Vec<Weekday>,
+    /// Inclusive date ranges in the site's local calendar
+    closures: Vec<(NaiveDate, NaiveDate)>,
+}
+
+impl Default for WorkingCalendar {
+    /// Every day is a working day
+    fn default() -> Self {
+        Self::new(ALL_WEEKDAYS.to_vec(), Vec::new())
+    }
+}
+
+impl WorkingCalendar {
+    pub fn new(working_days: Vec<Weekday>, closures: Vec<(NaiveDate, NaiveDate)>) -> Self {
+        Self { working_days, closures }
+    }
+
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        self.working_days.contains(&date.weekday())
+            && !self.closures.iter().any(|(start, end)| (*start..=*end).contains(&date))
+    }
+
+    /// `date` if the site works that day, else the next day it does
+    pub fn next_working_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut day = date;
+        for _ in 0..MAX_WORKING_DAY_SEARCH_DAYS {
+            if self.is_working_day(day) {
+                return day;
+            }
+            match day.succ_opt() {
+                Some(next) => day = next,
+                None => break,
+            }
+        }
+        date
+    }
+
+    /// Move an instant to the same local time on the next working day, if
+    /// it falls on a day off
+    pub fn shift_to_working_day(&self, instant: DateTime<Utc>, tz: Tz) -> DateTime<Utc> {
+        let date = local_date(instant, tz);
+        let working_day = self.next_working_day(date);
+        if working_day == date {
+            return instant;
+        }
+        add_local_days(instant, (working_day - date).num_days(), tz)
+    }
+
+    /// Instant at which something due at `due` becomes overdue: local
+    /// midnight at the end of the first working day on or after its due day
+    pub fn overdue_at(&self, due: DateTime<Utc>, tz: Tz) -> DateTime<Utc> {
+        let last_day = self.next_working_day(local_date(due, tz));
+        start_of_local_day(last_day.succ_opt().unwrap_or(last_day), tz)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overdue_at_local_midnight() {
+        let tz = parse_time_zone("Australia/Sydney").unwrap();
+        // 2024-06-10 20:00 UTC is 2024-06-11 06:00 in Sydney (UTC+10)
+        let due = Utc.with_ymd_and_hms(2024, 6, 10, 20, 0, 0).unwrap();
+        assert_eq!(overdue_at(due, tz), Utc.with_ymd_and_hms(2024, 6, 11, 14, 0, 0).unwrap());
+        assert!(!is_overdue(due, tz, Utc.with_ymd_and_hms(2024, 6, 11, 13, 59, 0).unwrap()));
+        assert!(is_overdue(due, tz, Utc.with_ymd_and_hms(2024, 6, 11, 14, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_add_local_days_across_dst() {
+        let tz = parse_time_zone("America/New_York").unwrap();
+        // 09:00 EST on 2024-03-01 is 14:00 UTC; 30 days later is 09:00 EDT (13:00 UTC)
+        let base = Utc.with_ymd_and_hms(2024, 3, 1, 14, 0, 0).unwrap();
+        assert_eq!(add_local_days(base, 30, tz), Utc.with_ymd_and_hms(2024, 3, 31, 13, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_working_calendar_moves_due_dates_past_days_off() {
+        let tz = parse_time_zone("Europe/Berlin").unwrap();
+        let weekdays = vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri];
+        // Shut down for the week of 2024-07-22
+        let shutdown = (NaiveDate::from_ymd_opt(2024, 7, 22).unwrap(), NaiveDate::from_ymd_opt(2024, 7, 26).unwrap());
+        let calendar = WorkingCalendar::new(weekdays, vec![shutdown]);
+
+        // Due Saturday 2024-07-20 10:00 CEST: the site reopens Monday 2024-07-29
+        let due = Utc.with_ymd_and_hms(2024, 7, 20, 8, 0, 0).unwrap();
+        assert_eq!(calendar.shift_to_working_day(due, tz), Utc.with_ymd_and_hms(2024, 7, 29, 8, 0, 0).unwrap());
+        assert_eq!(calendar.overdue_at(due, tz), Utc.with_ymd_and_hms(2024, 7, 29, 22, 0, 0).unwrap());
+
+        // A working day is left alone, and without a calendar nothing moves
+        let wednesday = Utc.with_ymd_and_hms(2024, 7, 17, 8, 0, 0).unwrap();
+        assert_eq!(calendar.shift_to_working_day(wednesday, tz), wednesday);
+        assert_eq!(WorkingCalendar::default().overdue_at(due, tz), overdue_at(due, tz));
+
+        // A calendar with no working days leaves dates as they are
+        let closed = WorkingCalendar::new(Vec::new(), Vec::new());
+        assert_eq!(closed.shift_to_working_day(due, tz), due);
+    }
+
+    #[test]
+    fn test_time_zone_parsing() {
+        assert!(parse_time_zone("Europe/Berlin").is_ok());
+        assert!(parse_time_zone("Mars/Olympus").is_err());
+        assert_eq!(time_zone_or_default(None), Tz::UTC);
+        assert_eq!(time_zone_or_default(Some("bogus")), Tz::UTC);
+    }
+
+    #[test]
+    fn test_latest_local_time() {
+        let tz = parse_time_zone("America/Chicago").unwrap();
+        let seven = NaiveTime::from_hms_opt(7, 0, 0).unwrap();
+        // 07:00 CDT is 12:00 UTC
+        let after = Utc.with_ymd_and_hms(2024, 7, 2, 15, 0, 0).unwrap();
+        assert_eq!(latest_local_time(after, seven, tz), Utc.with_ymd_and_hms(2024, 7, 2, 12, 0, 0).unwrap());
+        let before = Utc.with_ymd_and_hms(2024, 7, 2, 11, 59, 0).unwrap();
+        assert_eq!(latest_local_time(before, seven, tz), Utc.with_ymd_and_hms(2024, 7, 1, 12, 0, 0).unwrap());
+    }
+}
@@ -0,0 +1,91 @@
+//! Full-text search support
+//!
+//! Assets, components, inspections and inspection items each have an FTS5
+//! index kept current by triggers. Free text typed by a user is never passed
+//! to `MATCH` directly: it is split into terms, each quoted and made a prefix
+//! match, so punctuation cannot form FTS5 query syntax. All terms must match.
+
+use serde::{Deserialize, Serialize};
+
+/// Results returned when no limit is requested
+pub const DEFAULT_SEARCH_LIMIT: usize = 25;
+
+/// Largest accepted result limit
+pub const MAX_SEARCH_LIMIT: usize = 100;
+
+/// Most terms taken from a query; the rest are ignored
+const MAX_SEARCH_TERMS: usize = 8;
+
+/// Kind of record a search hit refers to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum SearchEntityType {
+    Asset,
+    Component,
+    Inspection,
+    InspectionItem,
+}
+
+impl SearchEntityType {
+    pub const ALL: [SearchEntityType; 4] = [
+        SearchEntityType::Asset,
+        SearchEntityType::Component,
+        SearchEntityType::Inspection,
+        SearchEntityType::InspectionItem,
+    ];
+
+    /// Permission resource guarding records of this kind
+    pub fn resource(&self) -> &'static str {
+        match self {
+            SearchEntityType::Asset | SearchEntityType::Component => "asset",
+            SearchEntityType::Inspection | SearchEntityType::InspectionItem => "inspection",
+        }
+    }
+}
+
+/// A ranked search result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub entity_type: SearchEntityType,
+    pub entity_id: i64,
+    /// Asset the record belongs to, for navigation
+    pub asset_id: i64,
+    pub title: String,
+    /// Matching text with the matched terms wrapped in `[` and `]`
+    pub snippet: String,
+    /// Relevance, higher is better
+    pub score: f64,
+}
+
+/// Clamp a requested result count to `1..=MAX_SEARCH_LIMIT`
+pub fn search_limit(requested: Option<usize>) -> usize {
+    requested.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT)
+}
+
+/// Build an FTS5 query from user input, or `None` if it has no searchable terms
+pub fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .take(MAX_SEARCH_TERMS)
+        .map(|term| format!("\"{}\"*", term.to_lowercase()))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fts_query_quotes_terms_and_drops_syntax() {
+        assert_eq!(fts_query("Hoist CR-102").as_deref(), Some("\"hoist\"* \"cr\"* \"102\"*"));
+        assert_eq!(fts_query("wire NOT \"rope\"").as_deref(), Some("\"wire\"* \"not\"* \"rope\"*"));
+        assert_eq!(fts_query("  *:^() "), None);
+        assert_eq!(search_limit(Some(0)), 1);
+        assert_eq!(search_limit(Some(1000)), MAX_SEARCH_LIMIT);
+    }
+}
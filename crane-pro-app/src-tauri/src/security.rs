@@ -1,28 +0,0 @@
-//! Security module for CranePro Bridge Inspection Application
-//!
-//! This module will handle authentication, authorization, encryption,
-//! and other security-related functionality. Currently a placeholder
-//! for future implementation.
-
-use crate::errors::AppResult;
-
-/// Security module placeholder
-/// 
-/// This module will be implemented in subsequent tasks to include:
-/// - User authentication and session management
-/// - JWT token generation and validation
-/// - Password hashing and verification
-/// - Role-based access control (RBAC)
-/// - Data encryption/decryption utilities
-/// - Security audit logging
-/// - Secure storage integration with Stronghold
-pub struct Security;
-
-impl Security {
-    /// Initialize security subsystem
-    /// This is a placeholder implementation
-    pub async fn init() -> AppResult<Self> {
-        log::info!("Security module initialized (placeholder)");
-        Ok(Security)
-    }
-}
\ No newline at end of file
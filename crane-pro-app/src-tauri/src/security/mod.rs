@@ -0,0 +1,29 @@
+//! Secrets, encryption and signing
+//!
+//! - [`secrets`] keeps the JWT secret, the database key, signing keys and
+//!   SMTP credentials in a Stronghold snapshot or the OS keychain, creating
+//!   them on first run.
+//! - [`fields`] encrypts personal data such as email addresses and phone
+//!   numbers column by column, with blind indexes for lookups.
+//! - [`signing`] signs and verifies checklist packs and reports with
+//!   Ed25519 keys, and holds the hex helpers the others share.
+//!
+//! Sign-in, sessions and permission checks live in `middleware::auth`.
+
+use crate::errors::AppResult;
+
+pub mod fields;
+pub mod secrets;
+pub mod signing;
+
+/// Startup hook for the security subsystem. The submodules need no setup
+/// of their own; secrets are opened by [`secrets::Secrets::open`].
+pub struct Security;
+
+impl Security {
+    /// Initialize security subsystem
+    pub async fn init() -> AppResult<Self> {
+        log::info!("Security module initialized");
+        Ok(Security)
+    }
+}
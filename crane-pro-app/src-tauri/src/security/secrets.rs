@@ -0,0 +1,436 @@
+//! Application secrets
+//!
+//! Keeps the JWT signing secret, the database encryption key, the keys
+//! checklist packs and reports are signed with and the SMTP credentials out
+//! of the source, the database and the environment. They live in an encrypted
+//! Stronghold snapshot under the app data directory when
+//! `CRANEPRO_SECRETS_PASSWORD` is set, and in the OS keychain otherwise.
+//! Generated secrets are created on first run and kept from then on, so
+//! sessions survive a restart. A rotated JWT secret is kept as the
+//! previous secret until its grace window ends. With neither store available
+//! the app does not start: a database key made up for one run would leave
+//! every field encrypted under the real key unreadable.
+
+use crate::errors::{AppError, AppResult};
+use crate::security::signing::{decode_hex, encode_hex};
+use chrono::{DateTime, Utc};
+use iota_stronghold::{Client, ClientError, KeyProvider, SnapshotPath, Stronghold};
+use log::info;
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::Ed25519KeyPair;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Password of the Stronghold snapshot; without it the OS keychain is used
+pub const SECRETS_PASSWORD_ENV: &str = "CRANEPRO_SECRETS_PASSWORD";
+
+/// JWT secret imported on first run, so tokens issued before secrets were
+/// stored stay valid
+pub const JWT_SECRET_ENV: &str = "JWT_SECRET";
+
+/// Snapshot file under the app data directory
+pub const SNAPSHOT_FILE: &str = "secrets.stronghold";
+
+/// Service the keychain entries are filed under
+const KEYCHAIN_SERVICE: &str = "CranePro";
+
+/// Stronghold client holding the secrets
+const CLIENT_PATH: &[u8] = b"cranepro-secrets";
+
+/// Bytes of a generated secret; 256 bits
+const GENERATED_SECRET_BYTES: usize = 32;
+
+/// A secret the application keeps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SecretName {
+    JwtSecret,
+    PreviousJwtSecret,
+    DatabaseKey,
+    SmtpCredentials,
+    PackSigningKey,
+    ReportSigningKey,
+}
+
+impl SecretName {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecretName::JwtSecret => "jwt_secret",
+            SecretName::PreviousJwtSecret => "previous_jwt_secret",
+            SecretName::DatabaseKey => "database_key",
+            SecretName::SmtpCredentials => "smtp_credentials",
+            SecretName::PackSigningKey => "pack_signing_key",
+            SecretName::ReportSigningKey => "report_signing_key",
+        }
+    }
+}
+
+/// Username and password for the outgoing mail server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmtpCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+/// A JWT secret replaced by rotation, still accepted until `valid_until`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetiredSecret {
+    pub secret: String,
+    pub valid_until: DateTime<Utc>,
+}
+
+/// Where secrets are kept
+pub trait SecretStore: Send + Sync {
+    /// Name of the backend, for the logs
+    fn backend(&self) -> &'static str;
+
+    /// Whether what is stored survives a restart
+    fn is_persistent(&self) -> bool {
+        true
+    }
+
+    fn get(&self, name: SecretName) -> AppResult<Option<String>>;
+
+    fn set(&self, name: SecretName, value: &str) -> AppResult<()>;
+
+    fn delete(&self, name: SecretName) -> AppResult<()>;
+}
+
+fn stronghold_error(error: ClientError) -> AppError {
+    AppError::Encryption { reason: format!("Stronghold: {}", error) }
+}
+
+/// Secrets in a Stronghold snapshot, encrypted with a key derived from a
+/// password. Every change is written to the snapshot straight away.
+pub struct StrongholdStore {
+    stronghold: Stronghold,
+    client: Client,
+    snapshot_path: SnapshotPath,
+    key_provider: KeyProvider,
+    /// Serializes writes so commits do not interleave
+    write_lock: Mutex<()>,
+}
+
+impl StrongholdStore {
+    /// Open the snapshot at `path`, or start a new one if there is none.
+    /// Fails if the snapshot exists and `password` does not open it.
+    pub fn open(path: &Path, password: &str) -> AppResult<Self> {
+        let stronghold = Stronghold::default();
+        let snapshot_path = SnapshotPath::from_path(path);
+        let key_provider = KeyProvider::with_passphrase_hashed_blake2b(password.as_bytes().to_vec())
+            .map_err(stronghold_error)?;
+        let client = if snapshot_path.exists() {
+            stronghold.load_client_from_snapshot(CLIENT_PATH, &key_provider, &snapshot_path)
+        } else {
+            stronghold.create_client(CLIENT_PATH)
+        }.map_err(stronghold_error)?;
+        Ok(Self { stronghold, client, snapshot_path, key_provider, write_lock: Mutex::new(()) })
+    }
+
+    fn commit(&self) -> AppResult<()> {
+        self.stronghold.write_client(CLIENT_PATH).map_err(stronghold_error)?;
+        self.stronghold.commit_with_keyprovider(&self.snapshot_path, &self.key_provider)
+            .map_err(stronghold_error)
+    }
+}
+
+impl SecretStore for StrongholdStore {
+    fn backend(&self) -> &'static str {
+        "Stronghold"
+    }
+
+    fn get(&self, name: SecretName) -> AppResult<Option<String>> {
+        let value = self.client.store().get(name.as_str().as_bytes()).map_err(stronghold_error)?;
+        value.map(|bytes| String::from_utf8(bytes)
+            .map_err(|_| AppError::Decryption { reason: format!("Secret {} is not valid UTF-8", name.as_str()) }))
+            .transpose()
+    }
+
+    fn set(&self, name: SecretName, value: &str) -> AppResult<()> {
+        let _guard = self.write_lock.lock().map_err(|_| AppError::internal("Secret store lock poisoned"))?;
+        self.client.store()
+            .insert(name.as_str().as_bytes().to_vec(), value.as_bytes().to_vec(), None)
+            .map_err(stronghold_error)?;
+        self.commit()
+    }
+
+    fn delete(&self, name: SecretName) -> AppResult<()> {
+        let _guard = self.write_lock.lock().map_err(|_| AppError::internal("Secret store lock poisoned"))?;
+        self.client.store().delete(name.as_str().as_bytes()).map_err(stronghold_error)?;
+        self.commit()
+    }
+}
+
+fn keychain_error(error: keyring::Error) -> AppError {
+    AppError::ExternalService { service: "OS keychain".to_string(), message: error.to_string() }
+}
+
+/// Secrets in the OS keychain: Keychain on macOS, Credential Manager on
+/// Windows and the Secret Service on Linux
+pub struct KeychainStore {
+    service: String,
+}
+
+impl KeychainStore {
+    /// The keychain, if one is reachable
+    pub fn open() -> AppResult<Self> {
+        let store = Self { service: KEYCHAIN_SERVICE.to_string() };
+        store.get(SecretName::JwtSecret)?;
+        Ok(store)
+    }
+
+    fn entry(&self, name: SecretName) -> AppResult<keyring::Entry> {
+        keyring::Entry::new(&self.service, name.as_str()).map_err(keychain_error)
+    }
+}
+
+impl SecretStore for KeychainStore {
+    fn backend(&self) -> &'static str {
+        "OS keychain"
+    }
+
+    fn get(&self, name: SecretName) -> AppResult<Option<String>> {
+        match self.entry(name)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(keychain_error(e)),
+        }
+    }
+
+    fn set(&self, name: SecretName, value: &str) -> AppResult<()> {
+        self.entry(name)?.set_password(value).map_err(keychain_error)
+    }
+
+    fn delete(&self, name: SecretName) -> AppResult<()> {
+        match self.entry(name)?.delete_password() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(keychain_error(e)),
+        }
+    }
+}
+
+/// Secrets held only for the life of the process, for tests. Keys that
+/// must outlive the process are never generated into it.
+#[derive(Default)]
+pub struct MemoryStore {
+    values: Mutex<HashMap<SecretName, String>>,
+}
+
+impl SecretStore for MemoryStore {
+    fn backend(&self) -> &'static str {
+        "memory"
+    }
+
+    fn is_persistent(&self) -> bool {
+        false
+    }
+
+    fn get(&self, name: SecretName) -> AppResult<Option<String>> {
+        let values = self.values.lock().map_err(|_| AppError::internal("Secret store lock poisoned"))?;
+        Ok(values.get(&name).cloned())
+    }
+
+    fn set(&self, name: SecretName, value: &str) -> AppResult<()> {
+        let mut values = self.values.lock().map_err(|_| AppError::internal("Secret store lock poisoned"))?;
+        values.insert(name, value.to_string());
+        Ok(())
+    }
+
+    fn delete(&self, name: SecretName) -> AppResult<()> {
+        let mut values = self.values.lock().map_err(|_| AppError::internal("Secret store lock poisoned"))?;
+        values.remove(&name);
+        Ok(())
+    }
+}
+
+/// 256 random bits, hex encoded
+fn generate_secret() -> AppResult<String> {
+    let mut bytes = [0u8; GENERATED_SECRET_BYTES];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| AppError::Encryption { reason: "Secure random number generator failed".to_string() })?;
+    Ok(encode_hex(&bytes))
+}
+
+/// The application's secrets, over whichever store is available
+pub struct Secrets {
+    store: Box<dyn SecretStore>,
+}
+
+impl Secrets {
+    pub fn new(store: Box<dyn SecretStore>) -> Self {
+        Self { store }
+    }
+
+    /// The Stronghold snapshot in `data_dir` if a snapshot password is set,
+    /// else the OS keychain. A wrong snapshot password or an unreachable
+    /// keychain is an error rather than a silent fallback.
+    pub fn open(data_dir: &Path) -> AppResult<Self> {
+        let store: Box<dyn SecretStore> = match std::env::var(SECRETS_PASSWORD_ENV) {
+            Ok(password) if !password.is_empty() => {
+                Box::new(StrongholdStore::open(&data_dir.join(SNAPSHOT_FILE), &password)?)
+            }
+            _ => Box::new(KeychainStore::open().map_err(|e| AppError::Configuration {
+                key: SECRETS_PASSWORD_ENV.to_string(),
+                reason: format!("The OS keychain is not available ({}); set {} to keep secrets in a Stronghold snapshot",
+                                e, SECRETS_PASSWORD_ENV),
+            })?),
+        };
+        info!("Application secrets kept in {}", store.backend());
+        Ok(Self::new(store))
+    }
+
+    pub fn backend(&self) -> &'static str {
+        self.store.backend()
+    }
+
+    /// The stored value of `name`, storing `initial()` first if there is none
+    fn get_or_insert_with(&self, name: SecretName, initial: impl FnOnce() -> AppResult<String>) -> AppResult<String> {
+        if let Some(value) = self.store.get(name)? {
+            return Ok(value);
+        }
+        let value = initial()?;
+        if !self.store.is_persistent() {
+            return Err(AppError::Configuration {
+                key: name.as_str().to_string(),
+                reason: format!("The {} would not survive a restart in the {} secret store", name.as_str(), self.store.backend()),
+            });
+        }
+        self.store.set(name, &value)?;
+        info!("Stored new {} in {}", name.as_str(), self.store.backend());
+        Ok(value)
+    }
+
+    /// Key tokens are signed with. On first run `JWT_SECRET` is taken over
+    /// if set, else a new secret is generated.
+    pub fn jwt_secret(&self) -> AppResult<String> {
+        self.get_or_insert_with(SecretName::JwtSecret, || match std::env::var(JWT_SECRET_ENV) {
+            Ok(secret) if !secret.is_empty() => Ok(secret),
+            _ => generate_secret(),
+        })
+    }
+
+    /// The JWT secret replaced by the last rotation, while it is still valid
+    pub fn previous_jwt_secret(&self, now: DateTime<Utc>) -> AppResult<Option<RetiredSecret>> {
+        let Some(value) = self.store.get(SecretName::PreviousJwtSecret)? else {
+            return Ok(None);
+        };
+        let retired: RetiredSecret = serde_json::from_str(&value)
+            .map_err(|e| AppError::Decryption { reason: format!("Stored previous JWT secret is unreadable: {}", e) })?;
+        Ok(Some(retired).filter(|retired| retired.valid_until > now))
+    }
+
+    /// Replace the JWT secret with a new one, keeping the current one as the
+    /// previous secret until `previous_valid_until`. A secret retired by an
+    /// earlier rotation is forgotten. Returns the new secret.
+    pub fn rotate_jwt_secret(&self, previous_valid_until: DateTime<Utc>) -> AppResult<String> {
+        let retired = RetiredSecret { secret: self.jwt_secret()?, valid_until: previous_valid_until };
+        let value = serde_json::to_string(&retired)
+            .map_err(|e| AppError::internal(format!("Failed to serialize previous JWT secret: {}", e)))?;
+        // Kept first, so a failure in between leaves the current secret usable
+        self.store.set(SecretName::PreviousJwtSecret, &value)?;
+        let secret = generate_secret()?;
+        self.store.set(SecretName::JwtSecret, &secret)?;
+        info!("JWT secret rotated; the previous secret is accepted until {}", previous_valid_until);
+        Ok(secret)
+    }
+
+    /// Key for encrypting data at rest, generated on first run
+    pub fn database_key(&self) -> AppResult<String> {
+        self.get_or_insert_with(SecretName::DatabaseKey, generate_secret)
+    }
+
+    /// PKCS#8 document of the Ed25519 key this installation signs checklist
+    /// packs with, generated on first use
+    pub fn pack_signing_key(&self) -> AppResult<Vec<u8>> {
+        self.signing_key(SecretName::PackSigningKey)
+    }
+
+    /// PKCS#8 document of the Ed25519 key generated reports are signed
+    /// with, generated on first use
+    pub fn report_signing_key(&self) -> AppResult<Vec<u8>> {
+        self.signing_key(SecretName::ReportSigningKey)
+    }
+
+    fn signing_key(&self, name: SecretName) -> AppResult<Vec<u8>> {
+        let value = self.get_or_insert_with(name, || {
+            let document = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+                .map_err(|_| AppError::Encryption { reason: format!("Failed to generate the {}", name.as_str()) })?;
+            Ok(encode_hex(document.as_ref()))
+        })?;
+        decode_hex(&value)
+            .ok_or_else(|| AppError::Decryption { reason: format!("Stored {} is unreadable", name.as_str()) })
+    }
+
+    /// Credentials for the outgoing mail server, if any were saved
+    pub fn smtp_credentials(&self) -> AppResult<Option<SmtpCredentials>> {
+        self.store.get(SecretName::SmtpCredentials)?
+            .map(|value| serde_json::from_str(&value)
+                .map_err(|e| AppError::Decryption { reason: format!("Stored SMTP credentials are unreadable: {}", e) }))
+            .transpose()
+    }
+
+    /// Save credentials for the outgoing mail server, or forget them
+    pub fn set_smtp_credentials(&self, credentials: Option<&SmtpCredentials>) -> AppResult<()> {
+        match credentials {
+            Some(credentials) => {
+                let value = serde_json::to_string(credentials)
+                    .map_err(|e| AppError::internal(format!("Failed to serialize SMTP credentials: {}", e)))?;
+                self.store.set(SecretName::SmtpCredentials, &value)
+            }
+            None => self.store.delete(SecretName::SmtpCredentials),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secrets_are_generated_once_and_survive_reopening() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SNAPSHOT_FILE);
+
+        let secrets = Secrets::new(Box::new(StrongholdStore::open(&path, "correct horse").unwrap()));
+        let database_key = secrets.database_key().unwrap();
+        assert_eq!(database_key.len(), GENERATED_SECRET_BYTES * 2);
+        assert_eq!(secrets.database_key().unwrap(), database_key);
+        let credentials = SmtpCredentials { username: "mailer".to_string(), password: "s3cret".to_string() };
+        secrets.set_smtp_credentials(Some(&credentials)).unwrap();
+        drop(secrets);
+
+        let reopened = Secrets::new(Box::new(StrongholdStore::open(&path, "correct horse").unwrap()));
+        assert_eq!(reopened.database_key().unwrap(), database_key);
+        assert_eq!(reopened.smtp_credentials().unwrap(), Some(credentials));
+        reopened.set_smtp_credentials(None).unwrap();
+        assert_eq!(reopened.smtp_credentials().unwrap(), None);
+
+        assert!(StrongholdStore::open(&path, "wrong password").is_err());
+    }
+
+    #[test]
+    fn test_rotation_keeps_the_previous_jwt_secret_for_its_grace_window() {
+        let store = MemoryStore::default();
+        store.set(SecretName::JwtSecret, "first").unwrap();
+        let secrets = Secrets::new(Box::new(store));
+        let first = secrets.jwt_secret().unwrap();
+        let now = Utc::now();
+        assert_eq!(secrets.previous_jwt_secret(now).unwrap(), None);
+
+        let second = secrets.rotate_jwt_secret(now + chrono::Duration::hours(8)).unwrap();
+        assert_ne!(second, first);
+        assert_eq!(secrets.jwt_secret().unwrap(), second);
+        assert_eq!(secrets.previous_jwt_secret(now).unwrap().unwrap().secret, first);
+        assert_eq!(secrets.previous_jwt_secret(now + chrono::Duration::hours(9)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_keys_are_not_generated_into_a_store_lost_on_restart() {
+        let secrets = Secrets::new(Box::new(MemoryStore::default()));
+        assert!(secrets.database_key().is_err());
+        assert!(secrets.report_signing_key().is_err());
+        assert!(secrets.jwt_secret().is_err());
+    }
+}
@@ -0,0 +1,73 @@
+//! Ed25519 signatures
+//!
+//! Checklist packs and generated reports are signed with keys kept by
+//! [`super::secrets`]. Keys and signatures travel as hex so they can be
+//! pasted into settings and embedded in text formats.
+
+use crate::errors::{AppError, AppResult};
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use sha2::{Digest, Sha256};
+
+/// Lowercase hex of `bytes`
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Bytes of a lowercase or uppercase hex string, `None` if it is not one
+pub fn decode_hex(encoded: &str) -> Option<Vec<u8>> {
+    // `from_str_radix` alone would also take a sign, as in "+f"
+    if !encoded.len().is_multiple_of(2) || !encoded.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    encoded.as_bytes()
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+fn key_pair(signing_key: &[u8]) -> AppResult<Ed25519KeyPair> {
+    Ed25519KeyPair::from_pkcs8(signing_key)
+        .map_err(|_| AppError::Encryption { reason: "Signing key is not a valid Ed25519 key".to_string() })
+}
+
+/// Hex public key of a PKCS#8 signing key, as given out for others to trust
+pub fn public_key(signing_key: &[u8]) -> AppResult<String> {
+    Ok(encode_hex(key_pair(signing_key)?.public_key().as_ref()))
+}
+
+/// Short form of a public key for people to compare by eye
+pub fn key_fingerprint(public_key: &str) -> String {
+    let digest = Sha256::digest(public_key.trim().to_lowercase().as_bytes());
+    digest.iter().take(8).map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(":")
+}
+
+/// Hex signature over `message` with a PKCS#8 signing key
+pub fn sign(signing_key: &[u8], message: &[u8]) -> AppResult<String> {
+    Ok(encode_hex(key_pair(signing_key)?.sign(message).as_ref()))
+}
+
+/// Whether `signature` over `message` was made with `public_key`, both hex
+pub fn verify(public_key: &str, message: &[u8], signature: &str) -> bool {
+    match (decode_hex(public_key), decode_hex(signature)) {
+        (Some(public_key), Some(signature)) => UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(message, &signature)
+            .is_ok(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_hex_round_trips_and_rejects_malformed_input() {
+        assert_eq!(decode_hex(&encode_hex(&[0x00, 0xab, 0xff])), Some(vec![0x00, 0xab, 0xff]));
+        assert_eq!(decode_hex("ABff"), Some(vec![0xab, 0xff]));
+        assert_eq!(decode_hex(""), Some(Vec::new()));
+
+        for malformed in ["+f", "-1", "abc", "0g", " a"] {
+            assert_eq!(decode_hex(malformed), None, "{:?}", malformed);
+        }
+    }
+}
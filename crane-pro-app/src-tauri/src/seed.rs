@@ -0,0 +1,432 @@
+//! Demo data generation
+//!
+//! Fills an empty database with a plausible crane fleet so prospects can
+//! explore the app: sites with coordinates and time zones, cranes at each
+//! site, an inspection history per crane with findings on the worn parts,
+//! and placeholder photo records. Generation is deterministic for a given
+//! seed, so a demo can be rebuilt identically.
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{AssetStatus, Condition, InspectionStatus, InspectionType, Severity};
+use crate::scheduling;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+/// Upper bounds on requested volumes, to keep a demo build to seconds
+pub const MAX_SEED_LOCATIONS: u32 = 200;
+pub const MAX_SEED_ASSETS_PER_LOCATION: u32 = 100;
+pub const MAX_SEED_INSPECTIONS_PER_ASSET: u32 = 50;
+pub const MAX_SEED_FINDINGS_PER_INSPECTION: u32 = 20;
+pub const MAX_SEED_MEDIA_PER_INSPECTION: u32 = 10;
+
+/// Prefix of generated asset numbers
+pub const DEMO_ASSET_PREFIX: &str = "DEMO";
+
+/// Path recorded on placeholder media rows; no file is written
+pub const DEMO_MEDIA_PLACEHOLDER_PATH: &str = "demo/placeholder.jpg";
+
+/// Standard recorded on generated inspections
+const DEMO_COMPLIANCE_STANDARD: &str = "ASME B30.2";
+
+/// Requested demo data volumes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeedOptions {
+    pub locations: u32,
+    pub assets_per_location: u32,
+    /// Inspection history per asset, including the next scheduled one
+    pub inspections_per_asset: u32,
+    /// Checklist items recorded per completed inspection
+    pub findings_per_inspection: u32,
+    /// Placeholder photos per completed inspection
+    pub media_per_inspection: u32,
+    /// Random seed; the same seed and volumes give the same data
+    pub seed: u64,
+}
+
+impl Default for SeedOptions {
+    fn default() -> Self {
+        Self {
+            locations: 5,
+            assets_per_location: 4,
+            inspections_per_asset: 6,
+            findings_per_inspection: 5,
+            media_per_inspection: 2,
+            seed: 42,
+        }
+    }
+}
+
+impl SeedOptions {
+    pub fn validate(&self) -> AppResult<()> {
+        let limits = [
+            ("locations", self.locations, 1, MAX_SEED_LOCATIONS),
+            ("assets_per_location", self.assets_per_location, 1, MAX_SEED_ASSETS_PER_LOCATION),
+            ("inspections_per_asset", self.inspections_per_asset, 0, MAX_SEED_INSPECTIONS_PER_ASSET),
+            ("findings_per_inspection", self.findings_per_inspection, 0, MAX_SEED_FINDINGS_PER_INSPECTION),
+            ("media_per_inspection", self.media_per_inspection, 0, MAX_SEED_MEDIA_PER_INSPECTION),
+        ];
+        for (field, value, min, max) in limits {
+            if value < min || value > max {
+                return Err(AppError::OutOfRange {
+                    field: field.to_string(),
+                    value: value.to_string(),
+                    min: min.to_string(),
+                    max: max.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rows written by a seeding run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SeedSummary {
+    pub locations: u64,
+    pub assets: u64,
+    pub inspections: u64,
+    pub inspection_items: u64,
+    pub media_files: u64,
+}
+
+/// Small deterministic generator (SplitMix64); demo data does not need
+/// cryptographic randomness
+struct DemoRng(u64);
+
+impl DemoRng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0..n`
+    fn below(&mut self, n: u64) -> u64 {
+        if n == 0 { 0 } else { self.next_u64() % n }
+    }
+
+    /// Uniform value in `min..=max`
+    fn between(&mut self, min: i64, max: i64) -> i64 {
+        min + self.below((max - min + 1) as u64) as i64
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+/// Demo sites: city, latitude, longitude, IANA time zone
+const SITES: &[(&str, f64, f64, &str)] = &[
+    ("Houston", 29.7604, -95.3698, "America/Chicago"),
+    ("Pittsburgh", 40.4406, -79.9959, "America/New_York"),
+    ("Gary", 41.5934, -87.3464, "America/Chicago"),
+    ("Long Beach", 33.7701, -118.1937, "America/Los_Angeles"),
+    ("Rotterdam", 51.9244, 4.4777, "Europe/Amsterdam"),
+    ("Hamburg", 53.5511, 9.9937, "Europe/Berlin"),
+    ("Sheffield", 53.3811, -1.4701, "Europe/London"),
+    ("Brisbane", -27.4698, 153.0251, "Australia/Brisbane"),
+];
+
+const SITE_KINDS: &[&str] = &["Fabrication Plant", "Steel Mill", "Distribution Center", "Port Terminal", "Maintenance Depot"];
+const STREETS: &[&str] = &["Industrial Way", "Foundry Road", "Harbor Drive", "Mill Street", "Commerce Parkway"];
+
+const CRANE_TYPES: &[&str] = &["Overhead Bridge Crane", "Gantry Crane", "Semi-Gantry Crane", "Jib Crane", "Monorail Hoist"];
+const MANUFACTURERS: &[(&str, &[&str])] = &[
+    ("Konecranes", &["CXT", "SMARTON", "CLX"]),
+    ("Demag", &["DR-Pro", "DMR", "EKKE"]),
+    ("ABUS", &["GM 6000", "ZLK", "EHB"]),
+    ("Street Crane", &["ZX", "VX", "SX"]),
+    ("Gorbel", &["GS Series", "FS Series", "Tether Track"]),
+];
+const CAPACITIES_TONNES: &[f64] = &[2.0, 5.0, 10.0, 16.0, 20.0, 32.0, 50.0];
+
+/// Checklist items: name, category, finding text when defective
+const CHECKLIST_ITEMS: &[(&str, &str, &str)] = &[
+    ("Hoist rope", "Hoist", "Broken wires and reduced diameter at drum end"),
+    ("Hook and latch", "Hoist", "Latch spring weak, throat opening increased"),
+    ("Hoist brake", "Hoist", "Brake lining worn, slipping under test load"),
+    ("Upper limit switch", "Safety", "Limit switch trips late"),
+    ("Pendant controls", "Controls", "Pendant cable jacket damaged"),
+    ("Runway rails", "Structure", "Rail clip loose at column line"),
+    ("End truck wheels", "Structure", "Wheel flange wear beyond tolerance"),
+    ("Sheaves", "Hoist", "Sheave groove worn"),
+    ("Festoon cables", "Electrical", "Festoon trolley binding"),
+    ("Capacity markings", "Safety", "Rated load marking not legible from floor"),
+];
+
+const INSPECTION_NOTES: &[&str] = &[
+    "Routine inspection, no operational issues reported.",
+    "Operator reported occasional noise from hoist gearbox.",
+    "Inspection performed during scheduled shutdown.",
+    "Load test witnessed by site supervisor.",
+];
+
+/// Populate the database. The caller is expected to run this inside a
+/// transaction on an empty database.
+pub fn populate(
+    conn: &Connection,
+    options: &SeedOptions,
+    created_by: i64,
+    inspectors: &[i64],
+    today: NaiveDate,
+) -> AppResult<SeedSummary> {
+    options.validate()?;
+    let inspectors: &[i64] = if inspectors.is_empty() { &[created_by] } else { inspectors };
+    let mut rng = DemoRng(options.seed);
+    let mut summary = SeedSummary::default();
+    let now = scheduling::start_of_local_day(today, chrono_tz::Tz::UTC) + Duration::hours(12);
+
+    for location_index in 0..options.locations {
+        let (city, latitude, longitude, time_zone) = *rng.pick(SITES);
+        let name = format!("{} {} {}", city, rng.pick(SITE_KINDS), location_index + 1);
+        let address = format!("{} {}, {}", rng.between(100, 9999), rng.pick(STREETS), city);
+        // Spread sites a few kilometres around the city centre
+        let latitude = latitude + (rng.between(-300, 300) as f64) / 10_000.0;
+        let longitude = longitude + (rng.between(-300, 300) as f64) / 10_000.0;
+
+        let location_id: i64 = conn.query_row(
+            "INSERT INTO locations (name, address, latitude, longitude, description, time_zone, created_by)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             RETURNING id",
+            params![name, address, latitude, longitude, "Demo site", time_zone, created_by],
+            |row| row.get(0),
+        )?;
+        summary.locations += 1;
+        let tz = scheduling::time_zone_or_default(Some(time_zone));
+
+        for _ in 0..options.assets_per_location {
+            let asset_id = insert_asset(conn, &mut rng, &summary, location_id, created_by, today)?;
+            summary.assets += 1;
+
+            // History runs from oldest to newest; the last one is still open
+            let interval_days = rng.between(30, 60);
+            for step in (0..options.inspections_per_asset as i64).rev() {
+                let scheduled = scheduling::add_local_days(now, -step * interval_days + rng.between(-3, 3), tz);
+                let is_open = step == 0;
+                let inspector_id = *rng.pick(inspectors);
+                let inspection_type = if rng.chance(20) { InspectionType::Periodic } else { InspectionType::Frequent };
+
+                let (inspection_id, completed) = insert_inspection(
+                    conn, &mut rng, asset_id, inspector_id, inspection_type, scheduled, is_open, time_zone, tz,
+                )?;
+                summary.inspections += 1;
+                if !completed {
+                    continue;
+                }
+
+                let worst = insert_findings(conn, &mut rng, inspection_id, options.findings_per_inspection)?;
+                summary.inspection_items += options.findings_per_inspection as u64;
+                conn.execute(
+                    "UPDATE inspections SET overall_condition = ?1 WHERE id = ?2",
+                    params![worst.to_string(), inspection_id],
+                )?;
+
+                for photo in 0..options.media_per_inspection {
+                    conn.execute(
+                        "INSERT INTO media_files (inspection_id, file_name, file_path, file_type, mime_type, file_size, description)
+                         VALUES (?1, ?2, ?3, 'image', 'image/jpeg', 0, ?4)",
+                        params![
+                            inspection_id,
+                            format!("demo-inspection-{}-photo-{}.jpg", inspection_id, photo + 1),
+                            DEMO_MEDIA_PLACEHOLDER_PATH,
+                            "Demo placeholder photo",
+                        ],
+                    )?;
+                    summary.media_files += 1;
+                }
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+fn insert_asset(
+    conn: &Connection,
+    rng: &mut DemoRng,
+    summary: &SeedSummary,
+    location_id: i64,
+    created_by: i64,
+    today: NaiveDate,
+) -> AppResult<i64> {
+    let number = summary.assets + 1;
+    let crane_type = *rng.pick(CRANE_TYPES);
+    let (manufacturer, models) = *rng.pick(MANUFACTURERS);
+    let model = *rng.pick(models);
+    let capacity = *rng.pick(CAPACITIES_TONNES);
+    let installed = today - Duration::days(rng.between(365, 20 * 365));
+    let manufactured = installed - Duration::days(rng.between(30, 365));
+    let status = match rng.below(20) {
+        0 => AssetStatus::Inactive,
+        1 | 2 => AssetStatus::Maintenance,
+        _ => AssetStatus::Active,
+    };
+
+    let asset_id = conn.query_row(
+        "INSERT INTO assets (asset_number, asset_name, asset_type, manufacturer, model, serial_number,
+         manufacture_date, installation_date, capacity, capacity_unit, location_id, status, description, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 't', ?10, ?11, ?12, ?13)
+         RETURNING id",
+        params![
+            format!("{}-{:05}", DEMO_ASSET_PREFIX, number),
+            format!("{} {}t #{}", crane_type, capacity, number),
+            crane_type,
+            manufacturer,
+            model,
+            format!("{}{:08}", &manufacturer[..2].to_uppercase(), rng.below(100_000_000)),
+            manufactured,
+            installed,
+            capacity,
+            location_id,
+            status.to_string(),
+            "Demo asset",
+            created_by,
+        ],
+        |row| row.get(0),
+    )?;
+    Ok(asset_id)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn insert_inspection(
+    conn: &Connection,
+    rng: &mut DemoRng,
+    asset_id: i64,
+    inspector_id: i64,
+    inspection_type: InspectionType,
+    scheduled: DateTime<Utc>,
+    is_open: bool,
+    time_zone: &str,
+    tz: chrono_tz::Tz,
+) -> AppResult<(i64, bool)> {
+    // Open inspections are mostly upcoming, with a few already overdue
+    let (scheduled, status, actual) = if is_open {
+        let scheduled = if rng.chance(15) {
+            scheduled - Duration::days(rng.between(5, 20))
+        } else {
+            scheduled + Duration::days(rng.between(5, 45))
+        };
+        (scheduled, InspectionStatus::Scheduled, None)
+    } else if rng.chance(3) {
+        (scheduled, InspectionStatus::Cancelled, None)
+    } else {
+        let actual = scheduled + Duration::hours(rng.between(-24, 72));
+        (scheduled, InspectionStatus::Completed, Some(actual))
+    };
+
+    let id = conn.query_row(
+        "INSERT INTO inspections (asset_id, inspector_id, inspection_type, compliance_standard,
+         scheduled_date, actual_date, status, notes, time_zone, overdue_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+         RETURNING id",
+        params![
+            asset_id,
+            inspector_id,
+            inspection_type.to_string(),
+            DEMO_COMPLIANCE_STANDARD,
+            scheduled,
+            actual,
+            status.to_string(),
+            actual.map(|_| *rng.pick(INSPECTION_NOTES)),
+            time_zone,
+            scheduling::overdue_at(scheduled, tz),
+        ],
+        |row| row.get(0),
+    )?;
+    Ok((id, status == InspectionStatus::Completed))
+}
+
+/// Record checklist items and return the worst condition found
+fn insert_findings(conn: &Connection, rng: &mut DemoRng, inspection_id: i64, count: u32) -> AppResult<Condition> {
+    let mut worst = Condition::Excellent;
+    for index in 0..count as usize {
+        let (item_name, category, defect) = CHECKLIST_ITEMS[index % CHECKLIST_ITEMS.len()];
+        let (condition, severity) = match rng.below(100) {
+            0..=1 => (Condition::Critical, Some(Severity::Critical)),
+            2..=7 => (Condition::Poor, Some(Severity::High)),
+            8..=24 => (Condition::Fair, Some(Severity::Low)),
+            25..=69 => (Condition::Good, None),
+            _ => (Condition::Excellent, None),
+        };
+        let defective = matches!(condition, Condition::Poor | Condition::Critical);
+        if condition_rank(&condition) > condition_rank(&worst) {
+            worst = condition.clone();
+        }
+
+        conn.execute(
+            "INSERT INTO inspection_items (inspection_id, item_name, item_category, condition, finding,
+             severity, is_compliant, corrective_action)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                inspection_id,
+                item_name,
+                category,
+                condition.to_string(),
+                severity.as_ref().map(|_| defect),
+                severity.as_ref().map(|s| s.to_string()),
+                !defective,
+                defective.then_some("Repair or replace before next shift"),
+            ],
+        )?;
+    }
+    Ok(worst)
+}
+
+fn condition_rank(condition: &Condition) -> u8 {
+    match condition {
+        Condition::Excellent => 0,
+        Condition::Good => 1,
+        Condition::Fair => 2,
+        Condition::Poor => 3,
+        Condition::Critical => 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng_is_deterministic() {
+        let mut a = DemoRng(7);
+        let mut b = DemoRng(7);
+        let first: Vec<u64> = (0..5).map(|_| a.below(1000)).collect();
+        assert_eq!(first, (0..5).map(|_| b.below(1000)).collect::<Vec<_>>());
+        assert!((0..100).all(|_| (3..=9).contains(&a.between(3, 9))));
+    }
+
+    #[test]
+    fn test_populate_demo_database() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::LegacyMigrationManager::new().run_migrations(&conn, 0, i32::MAX).unwrap();
+        // The initial schema creates the default administrator
+        let admin_id: i64 = conn.query_row("SELECT MIN(id) FROM users", [], |row| row.get(0)).unwrap();
+        let options = SeedOptions { locations: 2, assets_per_location: 3, inspections_per_asset: 4, ..SeedOptions::default() };
+
+        let summary = populate(&conn, &options, admin_id, &[], Utc::now().date_naive()).unwrap();
+        assert_eq!(summary.locations, 2);
+        assert_eq!(summary.assets, 6);
+        assert_eq!(summary.inspections, 24);
+        let open: i64 = conn
+            .query_row("SELECT COUNT(*) FROM inspections WHERE status = 'Scheduled'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(open, 6);
+    }
+
+    #[test]
+    fn test_seed_options_limits() {
+        assert!(SeedOptions::default().validate().is_ok());
+        let too_many = SeedOptions { locations: MAX_SEED_LOCATIONS + 1, ..SeedOptions::default() };
+        assert!(too_many.validate().is_err());
+        let none = SeedOptions { assets_per_location: 0, ..SeedOptions::default() };
+        assert!(none.validate().is_err());
+    }
+}
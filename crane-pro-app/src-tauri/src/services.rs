@@ -3,16 +3,50 @@
 //! This module implements the repository pattern with comprehensive
 //! business logic, CRUD operations, and transaction management.
 
-use crate::database::Database;
+use crate::database::{Database, MaintenanceRun, MaintenanceTask, MigrationResult, MigrationStatus, UpgradeSnapshot};
 use crate::errors::{AppError, AppResult};
+use crate::middleware::{record_security_event, AuditLogEntry, AuditSink, Permissions, RequestContext, SecurityEventSink, UserSession};
+use crate::middleware::record_access::{LinkedRecord, RecordAction};
+use crate::middleware::validation::QuerySpec;
+use crate::i18n::{translate, Locale, Localize};
+use crate::api::{ChartKind, ChartPoint, ChartSpec};
+use crate::units::{self, Capacity};
+use crate::scheduling;
+use crate::seed::{self, SeedOptions, SeedSummary};
+use crate::search::{self, SearchEntityType, SearchHit};
+use crate::activity::{ActivityCursor, ActivityFilter, ActivityItem, ActivityKind, ActivityScope};
+use crate::json_schema;
+use crate::forecast;
+use crate::inbox;
+use crate::totp;
+use crate::ldap::DirectoryUser;
+use crate::oidc::OidcIdentity;
+use crate::photo::PhotoFingerprint;
+use crate::rate_limit::{self, RateLimit, Refusal, SlidingWindowLimiter};
+use crate::export::{export_to_file, ExportFormat};
+use crate::exporters::ExportTable;
+use crate::exporters::csv::{render_csv, CsvOptions};
+use crate::reports::store::ReportKey;
+use crate::reports::template;
+use crate::cranepack::{self, CranePack, PackContents, PackFrequencyRule, PackImage, PackManifest, PackStandard, PackTemplate};
+use crate::warehouse::{self, AssetDimension, DateDimension, FindingFact, InspectionFact, WarehouseManifest,
+                       WarehouseTable, WAREHOUSE_EXTRACTS_KEPT};
+use crate::geo::{self, GeoPoint, GeoQuery, LocationDistance, MapPin, PinColor};
+use crate::operations::OperationHandle;
+use crate::notifications::{self, InAppChannel, NotificationChannel, NotificationRecipient, OutboxMessage, MAX_OUTBOX_ATTEMPTS};
+use crate::trace::AI_JOB_TARGET;
+use crate::security::fields::{FieldCipher, ENCRYPTED_PREFIX};
+use crate::security::signing;
 use crate::models::*;
-use rusqlite::{params, Row};
-use chrono::{DateTime, Utc, NaiveDate};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use chrono::{DateTime, Datelike, Utc, NaiveDate, NaiveTime, Weekday};
 use serde_json::Value as JsonValue;
-use log::{info, debug};
-use std::sync::Arc;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use log::{info, debug, error, warn};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 
 // =============================================================================
 // Data Transfer Objects (DTOs)
@@ -33,6 +67,12 @@ pub struct AssetUpdateData {
     pub status: Option<AssetStatus>,
     pub description: Option<String>,
     pub specifications: Option<JsonValue>,
+    #[serde(default)]
+    pub criticality: Option<AssetCriticality>,
+    #[serde(default)]
+    pub service_class: Option<CmaaServiceClass>,
+    #[serde(default)]
+    pub fem_group: Option<FemGroup>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,6 +111,12 @@ pub struct InspectionItemUpdateData {
     pub severity: Option<Severity>,
     pub is_compliant: Option<bool>,
     pub corrective_action: Option<String>,
+    #[serde(default)]
+    pub clause_id: Option<i64>,
+    #[serde(default)]
+    pub measured_value: Option<f64>,
+    #[serde(default)]
+    pub measurement_unit: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,6 +130,14 @@ pub struct UserUpdateData {
     pub is_active: Option<bool>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamUpdateData {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub supervisor_id: Option<i64>,
+    pub is_active: Option<bool>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserSearchCriteria {
     pub username: Option<String>,
@@ -161,6 +215,9 @@ pub struct ComplianceStatusReport {
     pub overdue_inspections: i64,
     pub compliance_percentage: f64,
     pub critical_findings: i64,
+    /// Insurance policies and registrations lapsed or due for renewal within
+    /// `DEFAULT_RENEWAL_WARNING_DAYS`
+    pub expiring_records: i64,
     pub by_standard: HashMap<String, ComplianceStandardStatus>,
 }
 
@@ -172,6 +229,116 @@ pub struct ComplianceStandardStatus {
     pub compliance_rate: f64,
 }
 
+fn csv_date(date: Option<DateTime<Utc>>) -> String {
+    date.map(|d| d.to_rfc3339()).unwrap_or_default()
+}
+
+impl AssetSummaryReport {
+    /// One row holding the whole summary
+    pub fn to_table(&self, generated_at: DateTime<Utc>) -> ExportTable {
+        let mut table = ExportTable::new(
+            "Asset summary",
+            generated_at,
+            &[
+                "asset_id", "asset_name", "total_inspections", "completed_inspections", "pending_inspections",
+                "last_inspection_date", "next_inspection_date", "overall_condition", "maintenance_records",
+                "compliance_score",
+            ],
+        );
+        table.push_row(vec![
+            self.asset_id.to_string(),
+            self.asset_name.clone(),
+            self.total_inspections.to_string(),
+            self.completed_inspections.to_string(),
+            self.pending_inspections.to_string(),
+            csv_date(self.last_inspection_date),
+            csv_date(self.next_inspection_date),
+            self.overall_condition.as_ref().map(|c| c.to_string()).unwrap_or_default(),
+            self.maintenance_records.to_string(),
+            format!("{:.1}", self.compliance_score),
+        ]);
+        table
+    }
+}
+
+impl InspectionCompletionReport {
+    /// One row per inspector and per asset type, each repeating the period
+    /// totals so the file pivots cleanly in a spreadsheet
+    pub fn to_table(&self, generated_at: DateTime<Utc>) -> ExportTable {
+        let mut table = ExportTable::new(
+            "Inspection completion",
+            generated_at,
+            &[
+                "period_start", "period_end", "total_scheduled", "total_completed", "completion_rate",
+                "average_completion_time_hours", "breakdown", "name", "completed",
+            ],
+        );
+        let totals = [
+            self.period_start.to_rfc3339(),
+            self.period_end.to_rfc3339(),
+            self.total_scheduled.to_string(),
+            self.total_completed.to_string(),
+            format!("{:.1}", self.completion_rate),
+            format!("{:.1}", self.average_completion_time_hours),
+        ];
+        let mut breakdowns: Vec<(&str, &String, &i64)> = self.by_inspector.iter()
+            .map(|(name, count)| ("inspector", name, count))
+            .chain(self.by_asset_type.iter().map(|(name, count)| ("asset_type", name, count)))
+            .collect();
+        breakdowns.sort();
+        if breakdowns.is_empty() {
+            table.push_row(totals.to_vec());
+        }
+        for (breakdown, name, count) in breakdowns {
+            let mut row = totals.to_vec();
+            row.extend([breakdown.to_string(), name.clone(), count.to_string()]);
+            table.push_row(row);
+        }
+        table
+    }
+}
+
+impl ComplianceStatusReport {
+    /// One row per compliance standard, each repeating the overall figures
+    pub fn to_table(&self, generated_at: DateTime<Utc>) -> ExportTable {
+        let mut table = ExportTable::new(
+            "Compliance status",
+            generated_at,
+            &[
+                "location_id", "total_assets", "compliant_assets", "non_compliant_assets", "overdue_inspections",
+                "compliance_percentage", "critical_findings", "expiring_records", "standard_code",
+                "standard_total_assets", "standard_compliant", "standard_compliance_rate",
+            ],
+        );
+        let totals = [
+            self.location_id.map(|id| id.to_string()).unwrap_or_default(),
+            self.total_assets.to_string(),
+            self.compliant_assets.to_string(),
+            self.non_compliant_assets.to_string(),
+            self.overdue_inspections.to_string(),
+            format!("{:.1}", self.compliance_percentage),
+            self.critical_findings.to_string(),
+            self.expiring_records.to_string(),
+        ];
+        let mut standards: Vec<&ComplianceStandardStatus> = self.by_standard.values().collect();
+        standards.sort_by(|a, b| a.standard_code.cmp(&b.standard_code));
+        if standards.is_empty() {
+            table.push_row(totals.to_vec());
+        }
+        for standard in standards {
+            let mut row = totals.to_vec();
+            row.extend([
+                standard.standard_code.clone(),
+                standard.total_assets.to_string(),
+                standard.compliant.to_string(),
+                format!("{:.1}", standard.compliance_rate),
+            ]);
+            table.push_row(row);
+        }
+        table
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MaintenanceHistoryReport {
     pub asset_id: i64,
@@ -207,6 +374,41 @@ pub struct AssetSummary {
     pub critical_findings_count: i64,
 }
 
+/// Compact asset summary for field screens, read from the `asset_cards` view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetCardDto {
+    pub asset_id: i64,
+    pub asset_number: String,
+    pub asset_name: String,
+    pub asset_type: String,
+    pub status: AssetStatus,
+    pub location_id: i64,
+    pub location_name: String,
+    pub last_inspection_id: Option<i64>,
+    pub last_inspection_date: Option<DateTime<Utc>>,
+    pub last_condition: Option<Condition>,
+    /// Earliest scheduled date of an open inspection
+    pub next_due_date: Option<DateTime<Utc>>,
+    pub is_overdue: bool,
+    /// Problem items recorded by the latest completed inspection
+    pub open_findings: i64,
+    /// 0 (low) to 100 (high); see the `asset_cards` view for the weighting
+    pub risk_score: i64,
+    /// Trust from 0 to 1 in `last_condition`, falling as it ages
+    pub condition_confidence: f64,
+    /// The last rating is too old to rely on, whatever it says
+    pub condition_stale: bool,
+}
+
+impl AssetCardDto {
+    /// Fill in how far the last condition rating can still be trusted
+    fn with_condition_decay(mut self, decay: &ConditionDecayConfig, now: DateTime<Utc>) -> Self {
+        self.condition_confidence = decay.confidence(self.last_inspection_date, now);
+        self.condition_stale = decay.is_stale(self.condition_confidence);
+        self
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BulkImportResult {
     pub total_processed: i64,
@@ -238,6 +440,8 @@ pub struct AssetComplianceSummary {
     pub next_required_inspection: Option<DateTime<Utc>>,
     pub critical_findings: i64,
     pub overdue_inspections: i64,
+    /// Days in the last week without a required pre-start check
+    pub missed_prestart_checks: i64,
     pub compliance_status: String, // "Compliant", "Non-Compliant", "Overdue", "No Data"
 }
 
@@ -262,6 +466,36 @@ pub struct MaintenanceHistoryEntry {
     pub status: String,
 }
 
+const COMPONENT_TEMPLATE_COLUMNS: &str =
+    "id, asset_type, component_name, component_type, default_specifications, sort_order, created_at, updated_at";
+
+fn row_to_component_template(row: &Row) -> rusqlite::Result<ComponentTemplate> {
+    Ok(ComponentTemplate {
+        id: row.get(0)?,
+        asset_type: row.get(1)?,
+        component_name: row.get(2)?,
+        component_type: row.get(3)?,
+        default_specifications: row.get::<_, Option<String>>(4)?
+            .and_then(|s| serde_json::from_str(&s).ok()),
+        sort_order: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+/// Create the template components for a new asset, each with a copy of the
+/// template's default specifications. Returns the number created.
+fn apply_component_templates(conn: &Connection, asset_id: i64, asset_type: &str) -> AppResult<usize> {
+    let created = conn.execute(
+        "INSERT INTO components (asset_id, component_name, component_type, specifications, status)
+         SELECT ?1, component_name, component_type, default_specifications, 'Active'
+         FROM component_templates WHERE asset_type = ?2
+         ORDER BY sort_order, id",
+        params![asset_id, asset_type.trim()],
+    )?;
+    Ok(created)
+}
+
 // =============================================================================
 // Asset Service
 // =============================================================================
@@ -275,34 +509,161 @@ impl AssetService {
         Self { database }
     }
 
-    pub fn create_asset(&self, asset: Asset) -> AppResult<Asset> {
-        info!("Creating new asset: {}", asset.asset_number);
+    pub fn create_asset(&self, context: &RequestContext, asset: Asset) -> AppResult<Asset> {
+        info!("[{}] Creating new asset: {}", context.request_id, asset.asset_number);
         asset.validate()?;
 
+        // Store capacity units under their canonical symbol
+        let capacity_unit = match asset.rated_capacity()? {
+            Some(capacity) => Some(capacity.unit.symbol().to_string()),
+            None => asset.capacity_unit.clone(),
+        };
+
         self.database.with_transaction(|conn| {
+            ensure_matches_schema(conn, SchemaTarget::AssetSpecifications, &asset.asset_type, asset.specifications.as_ref())?;
             let id = conn.query_row(
                 "INSERT INTO assets (asset_number, asset_name, asset_type, manufacturer, model, 
                  serial_number, manufacture_date, installation_date, capacity, capacity_unit, 
-                 location_id, status, description, specifications, created_by)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+                 location_id, status, description, specifications, created_by, criticality,
+                 service_class, fem_group)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
                  RETURNING id",
                 params![
                     asset.asset_number, asset.asset_name, asset.asset_type,
                     asset.manufacturer, asset.model, asset.serial_number,
                     asset.manufacture_date, asset.installation_date,
-                    asset.capacity, asset.capacity_unit, asset.location_id,
+                    asset.capacity, capacity_unit, asset.location_id,
                     asset.status.to_string(), asset.description,
                     asset.specifications.as_ref().map(|s| s.to_string()),
-                    asset.created_by
+                    asset.created_by, asset.criticality.to_string(),
+                    asset.service_class.map(|c| c.to_string()), asset.fem_group.map(|g| g.to_string())
                 ],
                 |row| row.get::<_, i64>(0),
             )?;
+            let components = apply_component_templates(conn, id, &asset.asset_type)?;
 
-            debug!("Asset created with ID: {}", id);
+            debug!("Asset created with ID: {} and {} template components", id, components);
             self.get_asset_by_id(id)
         })
     }
 
+    /// Assets to show on the map, placed at their location's coordinates and
+    /// colour coded by status and inspection state. `query` limits the pins
+    /// to a viewport or radius; assets at locations without coordinates are
+    /// left out.
+    pub fn get_map_pins(&self, query: Option<GeoQuery>) -> AppResult<Vec<MapPin>> {
+        debug!("Fetching map pins for {:?}", query);
+        if let Some(query) = &query {
+            query.validate()?;
+        }
+        let bounds = query.map(|q| q.bounding_box());
+        let conn = self.database.get_connection()?;
+
+        let sql = format!(
+            "SELECT a.id, a.asset_number, a.asset_name, a.asset_type, a.status,
+                    l.id, l.name, l.latitude, l.longitude,
+                    (SELECT AVG(score) FROM (
+                        SELECT 100.0 * COUNT(CASE WHEN ii.is_compliant = 1 THEN 1 END) / COUNT(*) AS score
+                        FROM inspection_items ii JOIN inspections i ON ii.inspection_id = i.id
+                        WHERE i.asset_id = a.id AND i.status = 'Completed'
+                        GROUP BY i.id
+                    )) AS compliance_score,
+                    (SELECT COUNT(*) FROM inspections i
+                     WHERE i.asset_id = a.id AND i.status NOT IN ('Completed', 'Cancelled')
+                       AND COALESCE(i.overdue_at, i.scheduled_date) < datetime('now')) AS overdue_inspections,
+                    (SELECT COUNT(*) FROM inspection_items ii
+                     WHERE ii.severity = 'Critical' AND ii.inspection_id = (
+                         SELECT i.id FROM inspections i
+                         WHERE i.asset_id = a.id AND i.status = 'Completed'
+                         ORDER BY i.actual_date DESC, i.id DESC LIMIT 1
+                     )) AS critical_findings
+             FROM assets a JOIN locations l ON a.location_id = l.id
+             WHERE a.deleted_at IS NULL AND l.latitude IS NOT NULL AND l.longitude IS NOT NULL{}
+             ORDER BY a.asset_name",
+            bounds.map(|b| format!(" AND {}", b.sql_condition("l.latitude", "l.longitude", 1))).unwrap_or_default()
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map(params_from_iter(bounds.map(|b| b.sql_params().to_vec()).unwrap_or_default()), |row| {
+                let status: AssetStatus = row.get::<_, String>(4)?.parse().unwrap_or(AssetStatus::Active);
+                let compliance_score: Option<f64> = row.get(9)?;
+                let overdue_inspections: i64 = row.get(10)?;
+                let critical_findings: i64 = row.get(11)?;
+                Ok(MapPin {
+                    asset_id: row.get(0)?,
+                    asset_number: row.get(1)?,
+                    asset_name: row.get(2)?,
+                    asset_type: row.get(3)?,
+                    color: PinColor::for_asset(&status, compliance_score, overdue_inspections, critical_findings),
+                    status,
+                    location_id: row.get(5)?,
+                    location_name: row.get(6)?,
+                    latitude: row.get(7)?,
+                    longitude: row.get(8)?,
+                    distance_km: None,
+                    compliance_score,
+                    overdue_inspections,
+                    critical_findings,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        drop(stmt);
+        self.database.return_connection(conn);
+
+        // Refine the SQL bounding box to the exact viewport or radius
+        let mut pins: Vec<MapPin> = rows
+            .into_iter()
+            .filter_map(|mut pin| match &query {
+                Some(query) => {
+                    let point = GeoPoint { latitude: pin.latitude, longitude: pin.longitude };
+                    query.matches(&point).map(|distance| {
+                        pin.distance_km = distance;
+                        pin
+                    })
+                }
+                None => Some(pin),
+            })
+            .collect();
+        if matches!(query, Some(GeoQuery::Radius { .. })) {
+            pins.sort_by(|a, b| a.distance_km.partial_cmp(&b.distance_km).unwrap_or(std::cmp::Ordering::Equal));
+        }
+        Ok(pins)
+    }
+
+    /// Visit every asset in ID order, one row at a time, without loading the
+    /// whole fleet into memory
+    ///
+    /// Returns the number of assets visited.
+    pub fn stream_assets<F>(&self, mut visit: F) -> AppResult<u64>
+    where
+        F: FnMut(Asset) -> AppResult<()>,
+    {
+        debug!("Streaming all assets");
+        let conn = self.database.get_read_connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, asset_number, asset_name, asset_type, manufacturer, model,
+             serial_number, manufacture_date, installation_date, capacity, capacity_unit,
+             location_id, status, description, specifications, created_by, created_at, updated_at, criticality,
+             service_class, fem_group
+             FROM assets WHERE deleted_at IS NULL ORDER BY id"
+        )?;
+        let mut count = 0;
+        for asset in stmt.query_map([], |row| self.row_to_asset(row))? {
+            visit(asset?)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    pub fn count_assets(&self) -> AppResult<u64> {
+        let conn = self.database.get_read_connection()?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM assets WHERE deleted_at IS NULL", [], |row| row.get(0))?;
+        Ok(count as u64)
+    }
+
     pub fn get_asset_by_id(&self, id: i64) -> AppResult<Asset> {
         debug!("Fetching asset by ID: {}", id);
         let conn = self.database.get_connection()?;
@@ -310,8 +671,9 @@ impl AssetService {
         let asset = conn.query_row(
             "SELECT id, asset_number, asset_name, asset_type, manufacturer, model,
              serial_number, manufacture_date, installation_date, capacity, capacity_unit,
-             location_id, status, description, specifications, created_by, created_at, updated_at
-             FROM assets WHERE id = ?1",
+             location_id, status, description, specifications, created_by, created_at, updated_at, criticality,
+             service_class, fem_group
+             FROM assets WHERE id = ?1 AND deleted_at IS NULL",
             params![id],
             |row| self.row_to_asset(row),
         ).map_err(|_| AppError::RecordNotFound {
@@ -326,26 +688,25 @@ impl AssetService {
 
     pub fn get_assets_by_location(&self, location_id: i64, filter: QueryFilter) -> AppResult<PaginatedResult<Asset>> {
         info!("Fetching assets for location: {} with filter: {:?}", location_id, filter);
+        let paging = QuerySpec::ASSETS.validate(&filter)?;
         let conn = self.database.get_connection()?;
 
-        let offset = ((filter.page.unwrap_or(1) - 1) * filter.limit.unwrap_or(50)).max(0);
-        let limit = filter.limit.unwrap_or(50);
-        let sort_order = filter.sort_order.unwrap_or(SortOrder::Desc);
+        let (offset, limit) = (paging.offset, paging.limit);
 
-        // Simple implementation without dynamic filters for now
-        let order_by = format!(" ORDER BY {} {}",
-            filter.sort_by.unwrap_or("created_at".to_string()), sort_order);
+        let where_clause = format!("WHERE location_id = ?1 AND deleted_at IS NULL{}", paging.filter_condition(2));
+        let order_by = paging.order_by("created_at");
 
         let query = format!(
             "SELECT id, asset_number, asset_name, asset_type, manufacturer, model,
              serial_number, manufacture_date, installation_date, capacity, capacity_unit,
-             location_id, status, description, specifications, created_by, created_at, updated_at
-             FROM assets WHERE location_id = ?1 {} LIMIT {} OFFSET {}",
-            order_by, limit, offset
+             location_id, status, description, specifications, created_by, created_at, updated_at, criticality,
+             service_class, fem_group
+             FROM assets {} {} LIMIT {} OFFSET {}",
+            where_clause, order_by, limit, offset
         );
 
         let mut stmt = conn.prepare(&query)?;
-        let asset_iter = stmt.query_map([location_id], |row| self.row_to_asset(row))?;
+        let asset_iter = stmt.query_map(paging.sql_params(&[&location_id]).as_slice(), |row| self.row_to_asset(row))?;
 
         let mut assets = Vec::new();
         for asset in asset_iter {
@@ -354,84 +715,145 @@ impl AssetService {
 
         // Get total count
         let total_count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM assets WHERE location_id = ?1",
-            [location_id],
+            &format!("SELECT COUNT(*) FROM assets {}", where_clause),
+            paging.sql_params(&[&location_id]).as_slice(),
             |row| row.get(0),
         )?;
 
         drop(stmt);
         self.database.return_connection(conn);
-        Ok(PaginatedResult::new(assets, total_count, filter.page.unwrap_or(1), limit))
+        Ok(PaginatedResult::new(assets, total_count, paging.page, limit))
     }
 
-    pub fn update_asset(&self, id: i64, updates: AssetUpdateData) -> AppResult<Asset> {
-        info!("Updating asset: {}", id);
+    pub fn update_asset(&self, context: &RequestContext, id: i64, updates: AssetUpdateData) -> AppResult<Asset> {
+        info!("[{}] Updating asset: {}", context.request_id, id);
         
         self.database.with_transaction(|conn| {
-            // Simple implementation - update individual fields
-            if let Some(asset_name) = &updates.asset_name {
-                conn.execute("UPDATE assets SET asset_name = ?1 WHERE id = ?2", params![asset_name, id])?;
-            }
-            if let Some(asset_type) = &updates.asset_type {
-                conn.execute("UPDATE assets SET asset_type = ?1 WHERE id = ?2", params![asset_type, id])?;
-            }
-            if let Some(manufacturer) = &updates.manufacturer {
-                conn.execute("UPDATE assets SET manufacturer = ?1 WHERE id = ?2", params![manufacturer, id])?;
-            }
-            if let Some(model) = &updates.model {
-                conn.execute("UPDATE assets SET model = ?1 WHERE id = ?2", params![model, id])?;
-            }
-            if let Some(status) = &updates.status {
-                conn.execute("UPDATE assets SET status = ?1 WHERE id = ?2", params![status.to_string(), id])?;
+            apply_asset_update(conn, context, id, &updates)?;
+            debug!("Asset {} updated successfully", id);
+            self.get_asset_by_id(id)
+        })
+    }
+
+    /// Apply the same changes to many assets in one transaction, e.g. to put
+    /// every crane in a building into maintenance. Each asset is updated
+    /// under its own savepoint, so an asset that fails is reported and left
+    /// unchanged without undoing the others.
+    pub fn bulk_update_assets(&self, context: &RequestContext, ids: &[i64], updates: AssetUpdateData) -> AppResult<BulkUpdateResult> {
+        let mut unique_ids = Vec::with_capacity(ids.len());
+        for id in ids {
+            if !unique_ids.contains(id) {
+                unique_ids.push(*id);
             }
-            if let Some(description) = &updates.description {
-                conn.execute("UPDATE assets SET description = ?1 WHERE id = ?2", params![description, id])?;
+        }
+        if unique_ids.is_empty() {
+            return Err(AppError::validation("ids", "At least one asset ID is required"));
+        }
+        if unique_ids.len() > MAX_BULK_RECORDS {
+            return Err(AppError::OutOfRange {
+                field: "ids".to_string(),
+                value: unique_ids.len().to_string(),
+                min: "1".to_string(),
+                max: MAX_BULK_RECORDS.to_string(),
+            });
+        }
+        info!("[{}] Bulk updating {} assets", context.request_id, unique_ids.len());
+
+        let results = self.database.with_transaction(|conn| {
+            let mut results = Vec::with_capacity(unique_ids.len());
+            for &id in &unique_ids {
+                conn.execute_batch("SAVEPOINT bulk_asset_update")?;
+                match apply_asset_update(conn, context, id, &updates) {
+                    Ok(()) => {
+                        conn.execute_batch("RELEASE bulk_asset_update")?;
+                        results.push(BulkRecordResult { id, success: true, error: None });
+                    }
+                    Err(e) => {
+                        conn.execute_batch("ROLLBACK TO bulk_asset_update; RELEASE bulk_asset_update")?;
+                        debug!("Bulk update skipped asset {}: {}", id, e);
+                        results.push(BulkRecordResult { id, success: false, error: Some(e.to_string()) });
+                    }
+                }
             }
+            Ok(results)
+        })?;
 
-            debug!("Asset {} updated successfully", id);
-            self.get_asset_by_id(id)
+        let succeeded = results.iter().filter(|r| r.success).count();
+        Ok(BulkUpdateResult {
+            total: results.len(),
+            succeeded,
+            failed: results.len() - succeeded,
+            results,
         })
     }
 
-    pub fn delete_asset(&self, id: i64) -> AppResult<()> {
-        info!("Deleting asset: {}", id);
+    /// Soft delete an asset. It stays in the database with its history,
+    /// hidden from normal queries, until restored or purged.
+    pub fn delete_asset(&self, context: &RequestContext, id: i64) -> AppResult<()> {
+        info!("[{}] Deleting asset: {}", context.request_id, id);
         
         self.database.with_transaction(|conn| {
-            let rows_affected = conn.execute("DELETE FROM assets WHERE id = ?1", params![id])?;
-            
-            if rows_affected == 0 {
-                return Err(AppError::RecordNotFound {
-                    entity: "Asset".to_string(),
-                    field: "id".to_string(),
-                    value: id.to_string(),
-                });
-            }
-            
+            soft_delete(conn, context, RecycleEntityType::Asset, id)?;
             debug!("Asset {} deleted successfully", id);
             Ok(())
         })
     }
 
+    /// Undo the soft delete of an asset
+    pub fn restore_asset(&self, context: &RequestContext, id: i64) -> AppResult<Asset> {
+        info!("[{}] Restoring asset: {}", context.request_id, id);
+        self.database.with_transaction(|conn| restore_soft_deleted(conn, RecycleEntityType::Asset, id))?;
+        self.get_asset_by_id(id)
+    }
+
+    /// Permanently remove a soft-deleted asset. Refused while inspections or
+    /// other history still refer to it.
+    pub fn purge_asset(&self, context: &RequestContext, id: i64) -> AppResult<()> {
+        warn!("[{}] Purging asset: {}", context.request_id, id);
+        self.database.with_transaction(|conn| purge_soft_deleted(conn, RecycleEntityType::Asset, id))?;
+        Ok(())
+    }
+
+    /// Search assets by text, with optional capacity conditions
+    ///
+    /// Phrases such as "over 10 t" or "at most 5,000 lbs" are extracted from
+    /// the query and compared against each asset's capacity converted to
+    /// kilograms, so units do not need to match. The rest of the query is
+    /// matched against name, number, type and manufacturer.
     pub fn search_assets(&self, query: String, filter: QueryFilter) -> AppResult<PaginatedResult<Asset>> {
         info!("Searching assets with query: {}", query);
+        let paging = QuerySpec::ASSETS.validate(&filter)?;
         let conn = self.database.get_connection()?;
 
-        let search_term = format!("%{}%", query);
-        let offset = ((filter.page.unwrap_or(1) - 1) * filter.limit.unwrap_or(50)).max(0);
-        let limit = filter.limit.unwrap_or(50);
+        let (capacity_filter, text) = units::extract_capacity_filter(&query);
+        let search_term = format!("%{}%", text);
+        let capacity_kg = capacity_filter.map(|f| f.threshold_kg());
+        let comparison = capacity_filter
+            .map(|f| f.comparison.sql_operator())
+            .unwrap_or(">=");
+        let (offset, limit) = (paging.offset, paging.limit);
+
+        let where_clause = format!(
+            "WHERE (asset_name LIKE ?1 OR asset_number LIKE ?1 OR asset_type LIKE ?1 OR manufacturer LIKE ?1)
+             AND deleted_at IS NULL AND (?2 IS NULL OR ({}) {} ?2){}",
+            units::capacity_kg_sql("capacity", "capacity_unit"),
+            comparison,
+            paging.filter_condition(3)
+        );
 
         let search_query = format!(
             "SELECT id, asset_number, asset_name, asset_type, manufacturer, model,
              serial_number, manufacture_date, installation_date, capacity, capacity_unit,
-             location_id, status, description, specifications, created_by, created_at, updated_at
+             location_id, status, description, specifications, created_by, created_at, updated_at, criticality,
+             service_class, fem_group
              FROM assets
-             WHERE asset_name LIKE ?1 OR asset_number LIKE ?1 OR asset_type LIKE ?1 OR manufacturer LIKE ?1
-             ORDER BY created_at DESC LIMIT {} OFFSET {}",
-            limit, offset
+             {}
+             {} LIMIT {} OFFSET {}",
+            where_clause, paging.order_by("created_at"), limit, offset
         );
 
         let mut stmt = conn.prepare(&search_query)?;
-        let asset_iter = stmt.query_map([&search_term], |row| self.row_to_asset(row))?;
+        let asset_iter = stmt.query_map(paging.sql_params(&[&search_term, &capacity_kg]).as_slice(), |row| self.row_to_asset(row))?;
 
         let mut assets = Vec::new();
         for asset in asset_iter {
@@ -439,15 +861,14 @@ impl AssetService {
         }
 
         let total_count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM assets
-             WHERE asset_name LIKE ?1 OR asset_number LIKE ?1 OR asset_type LIKE ?1 OR manufacturer LIKE ?1",
-            [&search_term],
+            &format!("SELECT COUNT(*) FROM assets {}", where_clause),
+            paging.sql_params(&[&search_term, &capacity_kg]).as_slice(),
             |row| row.get(0),
         )?;
 
         drop(stmt);
         self.database.return_connection(conn);
-        Ok(PaginatedResult::new(assets, total_count, filter.page.unwrap_or(1), limit))
+        Ok(PaginatedResult::new(assets, total_count, paging.page, limit))
     }
 
     pub fn get_asset_components(&self, asset_id: i64) -> AppResult<Vec<Component>> {
@@ -472,11 +893,12 @@ impl AssetService {
         Ok(components)
     }
 
-    pub fn create_component(&self, component: Component) -> AppResult<Component> {
-        info!("Creating new component: {}", component.component_name);
+    pub fn create_component(&self, context: &RequestContext, component: Component) -> AppResult<Component> {
+        info!("[{}] Creating new component: {}", context.request_id, component.component_name);
         component.validate()?;
 
         self.database.with_transaction(|conn| {
+            ensure_matches_schema(conn, SchemaTarget::ComponentSpecifications, &component.component_type, component.specifications.as_ref())?;
             let id = conn.query_row(
                 "INSERT INTO components (asset_id, component_name, component_type, manufacturer,
                  model, serial_number, parent_component_id, specifications, status)
@@ -497,8 +919,8 @@ impl AssetService {
         })
     }
 
-    pub fn update_component(&self, id: i64, updates: ComponentUpdateData) -> AppResult<Component> {
-        info!("Updating component: {}", id);
+    pub fn update_component(&self, context: &RequestContext, id: i64, updates: ComponentUpdateData) -> AppResult<Component> {
+        info!("[{}] Updating component: {}", context.request_id, id);
         
         self.database.with_transaction(|conn| {
             if let Some(component_name) = &updates.component_name {
@@ -510,12 +932,96 @@ impl AssetService {
             if let Some(status) = &updates.status {
                 conn.execute("UPDATE components SET status = ?1 WHERE id = ?2", params![status.to_string(), id])?;
             }
+            if updates.specifications.is_some() || updates.component_type.is_some() {
+                if let Some(specifications) = &updates.specifications {
+                    conn.execute("UPDATE components SET specifications = ?1 WHERE id = ?2", params![specifications.to_string(), id])?;
+                }
+                let (component_type, specifications): (String, Option<JsonValue>) = conn.query_row(
+                    "SELECT component_type, specifications FROM components WHERE id = ?1",
+                    params![id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )?;
+                ensure_matches_schema(conn, SchemaTarget::ComponentSpecifications, &component_type, specifications.as_ref())?;
+            }
 
             debug!("Component {} updated successfully", id);
             self.get_component_by_id(id)
         })
     }
 
+    /// Component templates, for one asset type or all of them
+    pub fn get_component_templates(&self, asset_type: Option<String>) -> AppResult<Vec<ComponentTemplate>> {
+        debug!("Fetching component templates for {:?}", asset_type);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<ComponentTemplate>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM component_templates
+                 WHERE ?1 IS NULL OR asset_type = ?1
+                 ORDER BY asset_type, sort_order, id",
+                COMPONENT_TEMPLATE_COLUMNS
+            ))?;
+            let templates = stmt.query_map(params![asset_type], row_to_component_template)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(templates)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Add a component template, or replace the one with the same asset type
+    /// and component name. Assets already created are not changed.
+    pub fn save_component_template(&self, context: &RequestContext, input: ComponentTemplateInput) -> AppResult<ComponentTemplate> {
+        info!("[{}] Saving component template {} for {}", context.request_id, input.component_name, input.asset_type);
+        input.validate()?;
+
+        self.database.with_transaction(|conn| {
+            // Template specifications are copied onto new components, so hold
+            // them to the component type's schema up front
+            ensure_matches_schema(
+                conn, SchemaTarget::ComponentSpecifications, input.component_type.trim(),
+                input.default_specifications.as_ref(),
+            )?;
+            let template = conn.query_row(
+                &format!(
+                    "INSERT INTO component_templates (asset_type, component_name, component_type, default_specifications, sort_order)
+                     VALUES (?1, ?2, ?3, ?4, ?5)
+                     ON CONFLICT(asset_type, component_name) DO UPDATE SET
+                         component_type = excluded.component_type,
+                         default_specifications = excluded.default_specifications,
+                         sort_order = excluded.sort_order,
+                         updated_at = CURRENT_TIMESTAMP
+                     RETURNING {}",
+                    COMPONENT_TEMPLATE_COLUMNS
+                ),
+                params![
+                    input.asset_type.trim(), input.component_name.trim(), input.component_type.trim(),
+                    input.default_specifications.as_ref().map(|s| s.to_string()),
+                    input.sort_order
+                ],
+                row_to_component_template,
+            )?;
+            Ok(template)
+        })
+    }
+
+    pub fn delete_component_template(&self, context: &RequestContext, id: i64) -> AppResult<()> {
+        info!("[{}] Deleting component template {}", context.request_id, id);
+
+        self.database.with_transaction(|conn| {
+            let deleted = conn.execute("DELETE FROM component_templates WHERE id = ?1", params![id])?;
+            if deleted == 0 {
+                return Err(AppError::RecordNotFound {
+                    entity: "ComponentTemplate".to_string(),
+                    field: "id".to_string(),
+                    value: id.to_string(),
+                });
+            }
+            Ok(())
+        })
+    }
+
     fn get_component_by_id(&self, id: i64) -> AppResult<Component> {
         let conn = self.database.get_connection()?;
         let component = conn.query_row(
@@ -555,9 +1061,111 @@ impl AssetService {
             created_by: row.get(15)?,
             created_at: row.get(16)?,
             updated_at: row.get(17)?,
+            criticality: row.get::<_, String>(18)?.parse().unwrap_or_default(),
+            service_class: row.get::<_, Option<String>>(19)?.and_then(|c| c.parse().ok()),
+            fem_group: row.get::<_, Option<String>>(20)?.and_then(|g| g.parse().ok()),
+        })
+    }
+
+    /// Get the field screen card of an asset
+    pub fn get_asset_card(&self, id: i64) -> AppResult<AssetCardDto> {
+        debug!("Fetching asset card: {}", id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Option<AssetCardDto>> {
+            let decay = read_app_settings(&conn)?.condition_decay;
+            let card = conn.query_row(
+                &format!("SELECT {} FROM asset_cards WHERE asset_id = ?1", ASSET_CARD_COLUMNS),
+                params![id],
+                row_to_asset_card,
+            ).optional()?;
+            Ok(card.map(|card| card.with_condition_decay(&decay, Utc::now())))
+        })();
+
+        self.database.return_connection(conn);
+        result?.ok_or_else(|| AppError::RecordNotFound {
+            entity: "Asset".to_string(),
+            field: "id".to_string(),
+            value: id.to_string(),
+        })
+    }
+
+    /// Link printed on the asset's label, issuing its token the first time
+    pub fn get_asset_link(&self, context: &RequestContext, asset_id: i64) -> AppResult<AssetLink> {
+        debug!("[{}] Fetching label link for asset {}", context.request_id, asset_id);
+
+        self.database.with_transaction(|conn| {
+            let token: Option<String> = conn.query_row(
+                "SELECT link_token FROM assets WHERE id = ?1 AND deleted_at IS NULL",
+                params![asset_id],
+                |row| row.get(0),
+            ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+                entity: "Asset".to_string(),
+                field: "id".to_string(),
+                value: asset_id.to_string(),
+            })?;
+
+            let token = match token {
+                Some(token) => token,
+                None => {
+                    let token = uuid::Uuid::new_v4().simple().to_string();
+                    conn.execute("UPDATE assets SET link_token = ?2 WHERE id = ?1", params![asset_id, token])?;
+                    info!("[{}] Issued label link token for asset {}", context.request_id, asset_id);
+                    token
+                }
+            };
+            Ok(AssetLink::new(asset_id, &token))
+        })
+    }
+
+    /// Asset a label link token belongs to
+    pub fn asset_id_for_link_token(&self, token: &str) -> AppResult<i64> {
+        let conn = self.database.get_connection()?;
+        let asset_id = conn.query_row(
+            "SELECT id FROM assets WHERE link_token = ?1 AND deleted_at IS NULL",
+            params![token],
+            |row| row.get(0),
+        ).optional();
+        self.database.return_connection(conn);
+
+        asset_id?.ok_or_else(|| AppError::RecordNotFound {
+            entity: "Asset".to_string(),
+            field: "link_token".to_string(),
+            value: token.to_string(),
         })
     }
 
+    /// Field screen cards for the assets under a location (including
+    /// sub-locations) and/or with open inspections assigned to an inspector,
+    /// highest risk first
+    pub fn get_asset_cards(&self, location_id: Option<i64>, assigned_inspector_id: Option<i64>) -> AppResult<Vec<AssetCardDto>> {
+        debug!("Fetching asset cards (location: {:?}, inspector: {:?})", location_id, assigned_inspector_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<AssetCardDto>> {
+            let mut stmt = conn.prepare(&format!(
+                "{}
+                 SELECT {} FROM asset_cards c
+                 WHERE (?1 IS NULL OR c.location_id IN (SELECT id FROM subtree))
+                   AND (?3 IS NULL OR EXISTS (
+                        SELECT 1 FROM inspections i
+                        WHERE i.asset_id = c.asset_id AND i.inspector_id = ?3
+                          AND i.status IN ('Scheduled', 'In Progress')))
+                 ORDER BY c.risk_score DESC, c.next_due_date IS NULL, c.next_due_date, c.asset_name",
+                LOCATION_SUBTREE_CTE, ASSET_CARD_COLUMNS
+            ))?;
+            let cards = stmt
+                .query_map(params![location_id, MAX_LOCATION_DEPTH as i64, assigned_inspector_id], row_to_asset_card)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            let decay = read_app_settings(&conn)?.condition_decay;
+            let now = Utc::now();
+            Ok(cards.into_iter().map(|card| card.with_condition_decay(&decay, now)).collect())
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
     /// Get comprehensive asset summary including inspections, maintenance, and compliance data
     ///
     /// # Arguments
@@ -567,14 +1175,14 @@ impl AssetService {
     /// * `AssetSummary` with comprehensive asset data
     pub fn get_asset_summary(&self, asset_id: i64) -> AppResult<AssetSummary> {
         info!("Getting asset summary for asset: {}", asset_id);
-        let conn = self.database.get_connection()?;
+        let conn = self.database.get_read_connection()?;
 
         // Get basic asset information with location name
         let (asset_name, asset_number, asset_type, location_name, status): (String, String, String, String, String) = conn.query_row(
             "SELECT a.asset_name, a.asset_number, a.asset_type, l.name, a.status
              FROM assets a
              JOIN locations l ON a.location_id = l.id
-             WHERE a.id = ?1",
+             WHERE a.id = ?1 AND a.deleted_at IS NULL",
             params![asset_id],
             |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
         ).map_err(|_| AppError::RecordNotFound {
@@ -667,8 +1275,6 @@ impl AssetService {
             |row| row.get(0),
         )?;
 
-        self.database.return_connection(conn);
-
         debug!("Asset summary generated for asset: {}", asset_id);
         Ok(AssetSummary {
             asset_id,
@@ -694,12 +1300,13 @@ impl AssetService {
     /// Bulk import assets with validation and transaction handling
     ///
     /// # Arguments
+    /// * `context` - Request context of the calling command
     /// * `assets` - Vector of Asset objects to import
     ///
     /// # Returns
     /// * `BulkImportResult` with detailed results for each asset
-    pub fn bulk_import_assets(&self, assets: Vec<Asset>) -> AppResult<BulkImportResult> {
-        info!("Starting bulk import of {} assets", assets.len());
+    pub fn bulk_import_assets(&self, context: &RequestContext, assets: Vec<Asset>) -> AppResult<BulkImportResult> {
+        info!("[{}] Starting bulk import of {} assets", context.request_id, assets.len());
         let mut results = Vec::new();
         let mut successful_imports = 0i64;
         let mut failed_imports = 0i64;
@@ -734,7 +1341,7 @@ impl AssetService {
                     }
 
                     // Try to create the asset
-                    match self.create_asset(asset.clone()) {
+                    match self.create_asset(context, asset.clone()) {
                         Ok(created_asset) => {
                             successful_imports += 1;
                             results.push(AssetImportResult {
@@ -871,32 +1478,33 @@ impl AssetService {
     /// * `PaginatedResult<Asset>` with assets matching the status filter
     pub fn get_assets_by_status(&self, status_filter: AssetStatusFilter, filter: QueryFilter) -> AppResult<PaginatedResult<Asset>> {
         info!("Fetching assets by status: {:?}", status_filter);
+        let paging = QuerySpec::ASSETS.validate(&filter)?;
         let conn = self.database.get_connection()?;
 
-        let offset = ((filter.page.unwrap_or(1) - 1) * filter.limit.unwrap_or(50)).max(0);
-        let limit = filter.limit.unwrap_or(50);
-        let sort_order = filter.sort_order.unwrap_or(SortOrder::Desc);
-        let sort_by = filter.sort_by.unwrap_or("created_at".to_string());
+        let (offset, limit) = (paging.offset, paging.limit);
 
         // Build WHERE conditions
         let where_clause = if status_filter.include_inactive {
-            "WHERE status = ?"
+            "WHERE status = ?1 AND deleted_at IS NULL"
         } else {
-            "WHERE status = ? AND status != 'Inactive'"
+            "WHERE status = ?1 AND status != 'Inactive' AND deleted_at IS NULL"
         };
+        let where_clause = format!("{}{}", where_clause, paging.filter_condition(2));
+        let status = status_filter.status.to_string();
 
-        let order_by = format!(" ORDER BY {} {}", sort_by, sort_order);
+        let order_by = paging.order_by("created_at");
 
         let query = format!(
             "SELECT id, asset_number, asset_name, asset_type, manufacturer, model,
              serial_number, manufacture_date, installation_date, capacity, capacity_unit,
-             location_id, status, description, specifications, created_by, created_at, updated_at
+             location_id, status, description, specifications, created_by, created_at, updated_at, criticality,
+             service_class, fem_group
              FROM assets {} {} LIMIT {} OFFSET {}",
             where_clause, order_by, limit, offset
         );
 
         let mut stmt = conn.prepare(&query)?;
-        let asset_iter = stmt.query_map([status_filter.status.to_string()], |row| self.row_to_asset(row))?;
+        let asset_iter = stmt.query_map(paging.sql_params(&[&status]).as_slice(), |row| self.row_to_asset(row))?;
 
         let mut assets = Vec::new();
         for asset in asset_iter {
@@ -906,13 +1514,13 @@ impl AssetService {
         // Get total count
         let total_count: i64 = conn.query_row(
             &format!("SELECT COUNT(*) FROM assets {}", where_clause),
-            [status_filter.status.to_string()],
+            paging.sql_params(&[&status]).as_slice(),
             |row| row.get(0),
         )?;
 
         drop(stmt);
         self.database.return_connection(conn);
-        Ok(PaginatedResult::new(assets, total_count, filter.page.unwrap_or(1), limit))
+        Ok(PaginatedResult::new(assets, total_count, paging.page, limit))
     }
 
     /// Get compliance summary for a specific asset
@@ -924,11 +1532,11 @@ impl AssetService {
     /// * `AssetComplianceSummary` with compliance status and critical findings
     pub fn get_asset_compliance_summary(&self, asset_id: i64) -> AppResult<AssetComplianceSummary> {
         info!("Getting compliance summary for asset: {}", asset_id);
-        let conn = self.database.get_connection()?;
+        let conn = self.database.get_read_connection()?;
 
         // Get asset name
         let asset_name: String = conn.query_row(
-            "SELECT asset_name FROM assets WHERE id = ?1",
+            "SELECT asset_name FROM assets WHERE id = ?1 AND deleted_at IS NULL",
             params![asset_id],
             |row| row.get(0),
         ).map_err(|_| AppError::RecordNotFound {
@@ -998,24 +1606,31 @@ impl AssetService {
         // Get overdue inspections count
         let overdue_inspections: i64 = conn.query_row(
             "SELECT COUNT(*) FROM inspections
-             WHERE asset_id = ?1 AND scheduled_date < datetime('now') AND status NOT IN ('Completed', 'Cancelled')",
+             WHERE asset_id = ?1 AND COALESCE(overdue_at, scheduled_date) < datetime('now') AND status NOT IN ('Completed', 'Cancelled')",
             params![asset_id],
             |row| row.get(0),
         )?;
 
+        // Count days without a required pre-start check
+        let missed_prestart_checks: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM ({})", MISSED_PRESTART_CHECKS_SQL),
+            params![asset_id, PRESTART_LOOKBACK_DAYS],
+            |row| row.get(0),
+        )?;
+
         // Determine compliance status
         let compliance_status = if overall_compliance_score == 0.0 {
             "No Data".to_string()
         } else if overdue_inspections > 0 {
             "Overdue".to_string()
+        } else if missed_prestart_checks > 0 {
+            "Non-Compliant".to_string()
         } else if overall_compliance_score >= 80.0 {
             "Compliant".to_string()
         } else {
             "Non-Compliant".to_string()
         };
 
-        self.database.return_connection(conn);
-
         debug!("Compliance summary generated for asset: {}", asset_id);
         Ok(AssetComplianceSummary {
             asset_id,
@@ -1025,6 +1640,7 @@ impl AssetService {
             next_required_inspection,
             critical_findings,
             overdue_inspections,
+            missed_prestart_checks,
             compliance_status,
         })
     }
@@ -1032,18 +1648,21 @@ impl AssetService {
     /// Transfer asset from one location to another with validation and audit logging
     ///
     /// # Arguments
+    /// * `context` - Request context of the calling command
     /// * `transfer_request` - The transfer request details
     ///
     /// # Returns
     /// * `AppResult<Asset>` the updated asset
-    pub fn transfer_asset_location(&self, transfer_request: AssetTransferRequest) -> AppResult<Asset> {
-        info!("Transferring asset {} from location {} to location {}",
+    pub fn transfer_asset_location(&self, context: &RequestContext, transfer_request: AssetTransferRequest) -> AppResult<Asset> {
+        info!("[{}] Transferring asset {} from location {} to location {}", context.request_id,
               transfer_request.asset_id, transfer_request.from_location_id, transfer_request.to_location_id);
 
         self.database.with_transaction(|conn| {
+            let before = history_snapshot(conn, HistoryEntityType::Asset, transfer_request.asset_id)?;
+
             // Validate asset exists and is at the source location
             let current_location_id: i64 = conn.query_row(
-                "SELECT location_id FROM assets WHERE id = ?1",
+                "SELECT location_id FROM assets WHERE id = ?1 AND deleted_at IS NULL",
                 params![transfer_request.asset_id],
                 |row| row.get(0),
             ).map_err(|_| AppError::RecordNotFound {
@@ -1108,6 +1727,7 @@ impl AssetService {
                   transfer_request.asset_id, transfer_request.from_location_id,
                   transfer_request.to_location_id, transfer_request.transferred_by, transfer_request.transfer_reason);
 
+            record_history(conn, context, HistoryEntityType::Asset, transfer_request.asset_id, "transfer", before)?;
             debug!("Asset {} transferred successfully", transfer_request.asset_id);
             self.get_asset_by_id(transfer_request.asset_id)
         })
@@ -1132,6 +1752,165 @@ impl AssetService {
     }
 }
 
+const ASSET_CARD_COLUMNS: &str =
+    "asset_id, asset_number, asset_name, asset_type, status, location_id, location_name,
+     last_inspection_id, last_inspection_date, last_condition, next_due_date, is_overdue,
+     open_findings, risk_score";
+
+/// Apply `updates` to one asset and record the change in its history
+fn apply_asset_update(conn: &Connection, context: &RequestContext, id: i64, updates: &AssetUpdateData) -> AppResult<()> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM assets WHERE id = ?1 AND deleted_at IS NULL)",
+        params![id],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Err(AppError::RecordNotFound {
+            entity: "Asset".to_string(),
+            field: "id".to_string(),
+            value: id.to_string(),
+        });
+    }
+    let before = history_snapshot(conn, HistoryEntityType::Asset, id)?;
+
+    // Simple implementation - update individual fields
+    if let Some(asset_name) = &updates.asset_name {
+        conn.execute("UPDATE assets SET asset_name = ?1 WHERE id = ?2", params![asset_name, id])?;
+    }
+    if let Some(asset_type) = &updates.asset_type {
+        conn.execute("UPDATE assets SET asset_type = ?1 WHERE id = ?2", params![asset_type, id])?;
+    }
+    if let Some(manufacturer) = &updates.manufacturer {
+        conn.execute("UPDATE assets SET manufacturer = ?1 WHERE id = ?2", params![manufacturer, id])?;
+    }
+    if let Some(model) = &updates.model {
+        conn.execute("UPDATE assets SET model = ?1 WHERE id = ?2", params![model, id])?;
+    }
+    if let Some(status) = &updates.status {
+        conn.execute("UPDATE assets SET status = ?1 WHERE id = ?2", params![status.to_string(), id])?;
+    }
+    if let Some(criticality) = &updates.criticality {
+        conn.execute("UPDATE assets SET criticality = ?1 WHERE id = ?2", params![criticality.to_string(), id])?;
+    }
+    if updates.service_class.is_some() || updates.fem_group.is_some() {
+        let (current_class, current_group): (Option<String>, Option<String>) = conn.query_row(
+            "SELECT service_class, fem_group FROM assets WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let service_class = match updates.service_class {
+            Some(class) => Some(class),
+            None => current_class.map(|c| c.parse::<CmaaServiceClass>()).transpose()?,
+        };
+        let fem_group = match updates.fem_group {
+            Some(group) => Some(group),
+            None => current_group.map(|g| g.parse::<FemGroup>()).transpose()?,
+        };
+
+        validate_classification(service_class, fem_group)?;
+        conn.execute(
+            "UPDATE assets SET service_class = ?1, fem_group = ?2 WHERE id = ?3",
+            params![service_class.map(|c| c.to_string()), fem_group.map(|g| g.to_string()), id],
+        )?;
+    }
+    if let Some(description) = &updates.description {
+        conn.execute("UPDATE assets SET description = ?1 WHERE id = ?2", params![description, id])?;
+    }
+    if updates.capacity.is_some() || updates.capacity_unit.is_some() {
+        let (current_capacity, current_unit): (Option<f64>, Option<String>) = conn.query_row(
+            "SELECT capacity, capacity_unit FROM assets WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let capacity = updates.capacity.or(current_capacity);
+        let mut capacity_unit = updates.capacity_unit.clone().or(current_unit);
+
+        // A stored unit from before units were typed is kept as written when
+        // only the value changes; a unit sent with the update must be known
+        let typed = match Capacity::from_parts(capacity, capacity_unit.as_deref()) {
+            Err(_) if updates.capacity_unit.is_none() => None,
+            result => result?,
+        };
+        match typed {
+            Some(typed) => {
+                typed.validate()?;
+                capacity_unit = Some(typed.unit.symbol().to_string());
+            }
+            None if capacity.is_some_and(|value| value <= 0.0) => {
+                return Err(AppError::validation("capacity", "Capacity must be greater than 0"));
+            }
+            None => {}
+        }
+        conn.execute(
+            "UPDATE assets SET capacity = ?1, capacity_unit = ?2 WHERE id = ?3",
+            params![capacity, capacity_unit, id],
+        )?;
+    }
+    if updates.specifications.is_some() || updates.asset_type.is_some() {
+        if let Some(specifications) = &updates.specifications {
+            conn.execute("UPDATE assets SET specifications = ?1 WHERE id = ?2", params![specifications.to_string(), id])?;
+        }
+        // Specifications must match the schema for the (possibly new) asset type
+        let (asset_type, specifications): (String, Option<JsonValue>) = conn.query_row(
+            "SELECT asset_type, specifications FROM assets WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        ensure_matches_schema(conn, SchemaTarget::AssetSpecifications, &asset_type, specifications.as_ref())?;
+    }
+
+    record_history(conn, context, HistoryEntityType::Asset, id, "update", before)
+}
+
+/// Insert a validated inspection item and apply what follows from it: save
+/// rules, the risk matrix, the finding SLA and defect tracking
+fn insert_inspection_item(conn: &Connection, item: &InspectionItem) -> AppResult<i64> {
+    if let Some(clause_id) = item.clause_id {
+        ensure_clause_applies(conn, item.inspection_id, clause_id)?;
+    }
+    let id = conn.query_row(
+        "INSERT INTO inspection_items (inspection_id, component_id, item_name, item_category,
+         condition, finding, severity, is_compliant, corrective_action, clause_id,
+         measured_value, measurement_unit)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+         RETURNING id",
+        params![
+            item.inspection_id, item.component_id, item.item_name, item.item_category,
+            item.condition.as_ref().map(|c| c.to_string()), item.finding,
+            item.severity.as_ref().map(|s| s.to_string()), item.is_compliant,
+            item.corrective_action, item.clause_id,
+            item.measured_value, item.measurement_unit
+        ],
+        |row| row.get::<_, i64>(0),
+    )?;
+    ensure_item_passes_save_rules(conn, id)?;
+    apply_risk_matrix(conn, id)?;
+    open_finding_sla(conn, id)?;
+    track_defect(conn, id)?;
+    Ok(id)
+}
+
+fn row_to_asset_card(row: &Row) -> rusqlite::Result<AssetCardDto> {
+    Ok(AssetCardDto {
+        asset_id: row.get(0)?,
+        asset_number: row.get(1)?,
+        asset_name: row.get(2)?,
+        asset_type: row.get(3)?,
+        status: row.get::<_, String>(4)?.parse().unwrap_or(AssetStatus::Active),
+        location_id: row.get(5)?,
+        location_name: row.get(6)?,
+        last_inspection_id: row.get(7)?,
+        last_inspection_date: row.get(8)?,
+        last_condition: row.get::<_, Option<String>>(9)?.and_then(|c| c.parse().ok()),
+        next_due_date: row.get(10)?,
+        is_overdue: row.get(11)?,
+        open_findings: row.get(12)?,
+        risk_score: row.get(13)?,
+        condition_confidence: 0.0,
+        condition_stale: true,
+    })
+}
+
 // =============================================================================
 // Inspection Service
 // =============================================================================
@@ -1145,15 +1924,33 @@ impl InspectionService {
         Self { database }
     }
 
-    pub fn create_inspection(&self, inspection: Inspection) -> AppResult<Inspection> {
-        info!("Creating new inspection for asset: {}", inspection.asset_id);
+    pub fn create_inspection(&self, context: &RequestContext, inspection: Inspection) -> AppResult<Inspection> {
+        info!("[{}] Creating new inspection for asset: {}", context.request_id, inspection.asset_id);
         inspection.validate()?;
 
         self.database.with_transaction(|conn| {
+            // Due dates follow the local calendar of the asset's site
+            let time_zone = self.asset_time_zone(conn, inspection.asset_id)?;
+            if let Some(scheduled_date) = inspection.scheduled_date {
+                ensure_inspector_available(conn, "new", inspection.inspector_id, scheduled_date, &time_zone)?;
+            }
+            let due_date = inspection.scheduled_date
+                .map(|date| scheduling::local_date(date, scheduling::time_zone_or_default(Some(&time_zone))))
+                .unwrap_or_else(|| Utc::now().date_naive());
+            ensure_inspector_qualified(conn, "new", inspection.inspector_id, &inspection.inspection_type, due_date)?;
+            let calendar = asset_working_calendar(conn, inspection.asset_id)?;
+            let overdue_at = inspection.scheduled_date
+                .map(|date| calendar.overdue_at(date, scheduling::time_zone_or_default(Some(&time_zone))));
+            ensure_matches_schema(
+                conn, SchemaTarget::ChecklistData, &inspection.inspection_type.to_string(),
+                inspection.checklist_data.as_ref(),
+            )?;
+
             let id = conn.query_row(
                 "INSERT INTO inspections (asset_id, inspector_id, inspection_type, compliance_standard,
-                 scheduled_date, actual_date, status, overall_condition, checklist_data, notes, ai_analysis_results)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                 scheduled_date, actual_date, status, overall_condition, checklist_data, notes, ai_analysis_results,
+                 time_zone, overdue_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
                  RETURNING id",
                 params![
                     inspection.asset_id, inspection.inspector_id, inspection.inspection_type.to_string(),
@@ -1162,7 +1959,8 @@ impl InspectionService {
                     inspection.overall_condition.as_ref().map(|c| c.to_string()),
                     inspection.checklist_data.as_ref().map(|d| d.to_string()),
                     inspection.notes,
-                    inspection.ai_analysis_results.as_ref().map(|r| r.to_string())
+                    inspection.ai_analysis_results.as_ref().map(|r| r.to_string()),
+                    time_zone, overdue_at
                 ],
                 |row| row.get::<_, i64>(0),
             )?;
@@ -1172,6 +1970,36 @@ impl InspectionService {
         })
     }
 
+    /// Visit every inspection in ID order, one row at a time
+    ///
+    /// Returns the number of inspections visited.
+    pub fn stream_inspections<F>(&self, mut visit: F) -> AppResult<u64>
+    where
+        F: FnMut(Inspection) -> AppResult<()>,
+    {
+        debug!("Streaming all inspections");
+        let conn = self.database.get_read_connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, asset_id, inspector_id, inspection_type, compliance_standard,
+             scheduled_date, actual_date, status, overall_condition, checklist_data, notes,
+             ai_analysis_results, created_at, updated_at, time_zone, overdue_at
+             FROM inspections WHERE deleted_at IS NULL ORDER BY id"
+        )?;
+        let mut count = 0;
+        for inspection in stmt.query_map([], |row| self.row_to_inspection(row))? {
+            visit(inspection?)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    pub fn count_inspections(&self) -> AppResult<u64> {
+        let conn = self.database.get_read_connection()?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM inspections WHERE deleted_at IS NULL", [], |row| row.get(0))?;
+        Ok(count as u64)
+    }
+
     pub fn get_inspection_by_id(&self, id: i64) -> AppResult<Inspection> {
         debug!("Fetching inspection by ID: {}", id);
         let conn = self.database.get_connection()?;
@@ -1179,8 +2007,8 @@ impl InspectionService {
         let inspection = conn.query_row(
             "SELECT id, asset_id, inspector_id, inspection_type, compliance_standard,
              scheduled_date, actual_date, status, overall_condition, checklist_data, notes,
-             ai_analysis_results, created_at, updated_at
-             FROM inspections WHERE id = ?1",
+             ai_analysis_results, created_at, updated_at, time_zone, overdue_at
+             FROM inspections WHERE id = ?1 AND deleted_at IS NULL",
             params![id],
             |row| self.row_to_inspection(row),
         ).map_err(|_| AppError::RecordNotFound {
@@ -1193,14 +2021,63 @@ impl InspectionService {
         Ok(inspection)
     }
 
-    pub fn update_inspection(&self, id: i64, updates: InspectionUpdateData) -> AppResult<Inspection> {
-        info!("Updating inspection: {}", id);
+    pub fn update_inspection(&self, context: &RequestContext, id: i64, updates: InspectionUpdateData) -> AppResult<Inspection> {
+        info!("[{}] Updating inspection: {}", context.request_id, id);
         
         self.database.with_transaction(|conn| {
-            if let Some(status) = &updates.status {
-                conn.execute("UPDATE inspections SET status = ?1 WHERE id = ?2", params![status.to_string(), id])?;
+            let before = history_snapshot(conn, HistoryEntityType::Inspection, id)?;
+
+            if updates.inspector_id.is_some() || updates.scheduled_date.is_some() {
+                // The assignee must be available on the (possibly new) due date
+                let (asset_id, inspector_id, scheduled_date, stored_zone): (i64, i64, Option<DateTime<Utc>>, Option<String>) = conn.query_row(
+                    "SELECT asset_id, inspector_id, scheduled_date, time_zone FROM inspections WHERE id = ?1",
+                    params![id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+                )?;
+                let inspector_id = updates.inspector_id.unwrap_or(inspector_id);
+                if let Some(scheduled_date) = updates.scheduled_date.or(scheduled_date) {
+                    let time_zone = match stored_zone {
+                        Some(zone) => zone,
+                        None => self.asset_time_zone(conn, asset_id)?,
+                    };
+                    ensure_inspector_available(conn, &id.to_string(), inspector_id, scheduled_date, &time_zone)?;
+                }
             }
-            if let Some(actual_date) = &updates.actual_date {
+            if let Some(inspector_id) = updates.inspector_id {
+                // A new assignee must be trained for the inspection on its due date
+                let (inspection_type, scheduled_date, time_zone): (String, Option<DateTime<Utc>>, Option<String>) = conn.query_row(
+                    "SELECT inspection_type, scheduled_date, time_zone FROM inspections WHERE id = ?1",
+                    params![id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )?;
+                let due_date = updates.scheduled_date.or(scheduled_date)
+                    .map(|date| scheduling::local_date(date, scheduling::time_zone_or_default(time_zone.as_deref())))
+                    .unwrap_or_else(|| Utc::now().date_naive());
+                ensure_inspector_qualified(conn, &id.to_string(), inspector_id, &inspection_type.parse()?, due_date)?;
+                conn.execute("UPDATE inspections SET inspector_id = ?1 WHERE id = ?2", params![inspector_id, id])?;
+            }
+            if let Some(scheduled_date) = &updates.scheduled_date {
+                // Keep the time zone captured at scheduling time, if any
+                let (asset_id, stored_zone): (i64, Option<String>) = conn.query_row(
+                    "SELECT asset_id, time_zone FROM inspections WHERE id = ?1",
+                    params![id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )?;
+                let time_zone = match stored_zone {
+                    Some(zone) => zone,
+                    None => self.asset_time_zone(conn, asset_id)?,
+                };
+                let calendar = asset_working_calendar(conn, asset_id)?;
+                let overdue_at = calendar.overdue_at(*scheduled_date, scheduling::time_zone_or_default(Some(&time_zone)));
+                conn.execute(
+                    "UPDATE inspections SET scheduled_date = ?1, time_zone = ?2, overdue_at = ?3 WHERE id = ?4",
+                    params![scheduled_date, time_zone, overdue_at, id],
+                )?;
+            }
+            if let Some(status) = &updates.status {
+                conn.execute("UPDATE inspections SET status = ?1 WHERE id = ?2", params![status.to_string(), id])?;
+            }
+            if let Some(actual_date) = &updates.actual_date {
                 conn.execute("UPDATE inspections SET actual_date = ?1 WHERE id = ?2", params![actual_date, id])?;
             }
             if let Some(overall_condition) = &updates.overall_condition {
@@ -1209,42 +2086,77 @@ impl InspectionService {
             if let Some(notes) = &updates.notes {
                 conn.execute("UPDATE inspections SET notes = ?1 WHERE id = ?2", params![notes, id])?;
             }
+            if let Some(checklist_data) = &updates.checklist_data {
+                let inspection_type: String = conn.query_row(
+                    "SELECT inspection_type FROM inspections WHERE id = ?1",
+                    params![id],
+                    |row| row.get(0),
+                )?;
+                ensure_matches_schema(conn, SchemaTarget::ChecklistData, &inspection_type, Some(checklist_data))?;
+                conn.execute("UPDATE inspections SET checklist_data = ?1 WHERE id = ?2", params![checklist_data.to_string(), id])?;
+            }
 
+            record_history(conn, context, HistoryEntityType::Inspection, id, "update", before)?;
             debug!("Inspection {} updated successfully", id);
             self.get_inspection_by_id(id)
         })
     }
 
-    pub fn submit_inspection(&self, id: i64) -> AppResult<Inspection> {
-        info!("Submitting inspection: {}", id);
+    pub fn submit_inspection(&self, context: &RequestContext, id: i64) -> AppResult<Inspection> {
+        info!("[{}] Submitting inspection: {}", context.request_id, id);
         
         self.database.with_transaction(|conn| {
+            let before = history_snapshot(conn, HistoryEntityType::Inspection, id)?;
+            ensure_inspection_passes_rules(conn, id)?;
             conn.execute(
                 "UPDATE inspections SET status = 'Completed', actual_date = CURRENT_TIMESTAMP WHERE id = ?1",
                 params![id]
             )?;
+            record_history(conn, context, HistoryEntityType::Inspection, id, "submit", before)?;
             
             debug!("Inspection {} submitted successfully", id);
             self.get_inspection_by_id(id)
         })
     }
 
+    /// Soft delete an inspection, keeping it and its items for audit
+    pub fn delete_inspection(&self, context: &RequestContext, id: i64) -> AppResult<()> {
+        info!("[{}] Deleting inspection: {}", context.request_id, id);
+        self.database.with_transaction(|conn| soft_delete(conn, context, RecycleEntityType::Inspection, id))
+    }
+
+    /// Undo the soft delete of an inspection. Its asset must not be deleted.
+    pub fn restore_inspection(&self, context: &RequestContext, id: i64) -> AppResult<Inspection> {
+        info!("[{}] Restoring inspection: {}", context.request_id, id);
+        self.database.with_transaction(|conn| restore_soft_deleted(conn, RecycleEntityType::Inspection, id))?;
+        self.get_inspection_by_id(id)
+    }
+
+    /// Permanently remove a soft-deleted inspection with its items, media
+    /// records and analysis results
+    pub fn purge_inspection(&self, context: &RequestContext, id: i64) -> AppResult<()> {
+        warn!("[{}] Purging inspection: {}", context.request_id, id);
+        self.database.with_transaction(|conn| purge_soft_deleted(conn, RecycleEntityType::Inspection, id))?;
+        Ok(())
+    }
+
     pub fn get_inspections_by_asset(&self, asset_id: i64, filter: QueryFilter) -> AppResult<PaginatedResult<Inspection>> {
         info!("Fetching inspections for asset: {}", asset_id);
+        let paging = QuerySpec::INSPECTIONS.validate(&filter)?;
         let conn = self.database.get_connection()?;
 
-        let offset = ((filter.page.unwrap_or(1) - 1) * filter.limit.unwrap_or(50)).max(0);
-        let limit = filter.limit.unwrap_or(50);
+        let (offset, limit) = (paging.offset, paging.limit);
+        let where_clause = format!("WHERE asset_id = ?1 AND deleted_at IS NULL{}", paging.filter_condition(2));
 
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT id, asset_id, inspector_id, inspection_type, compliance_standard,
              scheduled_date, actual_date, status, overall_condition, checklist_data, notes,
-             ai_analysis_results, created_at, updated_at
-             FROM inspections WHERE asset_id = ?1 
-             ORDER BY created_at DESC LIMIT ?2 OFFSET ?3"
-        )?;
+             ai_analysis_results, created_at, updated_at, time_zone, overdue_at
+             FROM inspections {} {} LIMIT {} OFFSET {}",
+            where_clause, paging.order_by("created_at"), limit, offset
+        ))?;
 
-        let inspection_iter = stmt.query_map(params![asset_id, limit, offset], |row| self.row_to_inspection(row))?;
+        let inspection_iter = stmt.query_map(paging.sql_params(&[&asset_id]).as_slice(), |row| self.row_to_inspection(row))?;
 
         let mut inspections = Vec::new();
         for inspection in inspection_iter {
@@ -1252,14 +2164,14 @@ impl InspectionService {
         }
 
         let total_count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM inspections WHERE asset_id = ?1",
-            params![asset_id],
+            &format!("SELECT COUNT(*) FROM inspections {}", where_clause),
+            paging.sql_params(&[&asset_id]).as_slice(),
             |row| row.get(0),
         )?;
 
         drop(stmt);
         self.database.return_connection(conn);
-        Ok(PaginatedResult::new(inspections, total_count, filter.page.unwrap_or(1), limit))
+        Ok(PaginatedResult::new(inspections, total_count, paging.page, limit))
     }
 
     pub fn get_pending_inspections(&self, inspector_id: Option<i64>) -> AppResult<Vec<Inspection>> {
@@ -1269,14 +2181,16 @@ impl InspectionService {
         let query = if let Some(_inspector_id) = inspector_id {
             "SELECT id, asset_id, inspector_id, inspection_type, compliance_standard,
              scheduled_date, actual_date, status, overall_condition, checklist_data, notes,
-             ai_analysis_results, created_at, updated_at
+             ai_analysis_results, created_at, updated_at, time_zone, overdue_at
              FROM inspections WHERE status IN ('Scheduled', 'In Progress') AND inspector_id = ?1
+               AND deleted_at IS NULL AND asset_id IN (SELECT id FROM assets WHERE deleted_at IS NULL)
              ORDER BY scheduled_date ASC"
         } else {
             "SELECT id, asset_id, inspector_id, inspection_type, compliance_standard,
              scheduled_date, actual_date, status, overall_condition, checklist_data, notes,
-             ai_analysis_results, created_at, updated_at
+             ai_analysis_results, created_at, updated_at, time_zone, overdue_at
              FROM inspections WHERE status IN ('Scheduled', 'In Progress')
+               AND deleted_at IS NULL AND asset_id IN (SELECT id FROM assets WHERE deleted_at IS NULL)
              ORDER BY scheduled_date ASC"
         };
 
@@ -1298,32 +2212,66 @@ impl InspectionService {
         Ok(inspections)
     }
 
-    pub fn create_inspection_item(&self, item: InspectionItem) -> AppResult<InspectionItem> {
-        info!("Creating inspection item: {}", item.item_name);
+    pub fn create_inspection_item(&self, context: &RequestContext, item: InspectionItem) -> AppResult<InspectionItem> {
+        info!("[{}] Creating inspection item: {}", context.request_id, item.item_name);
         item.validate()?;
 
         self.database.with_transaction(|conn| {
-            let id = conn.query_row(
-                "INSERT INTO inspection_items (inspection_id, component_id, item_name, item_category,
-                 condition, finding, severity, is_compliant, corrective_action)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
-                 RETURNING id",
-                params![
-                    item.inspection_id, item.component_id, item.item_name, item.item_category,
-                    item.condition.as_ref().map(|c| c.to_string()), item.finding,
-                    item.severity.as_ref().map(|s| s.to_string()), item.is_compliant,
-                    item.corrective_action
-                ],
-                |row| row.get::<_, i64>(0),
-            )?;
-
+            let id = insert_inspection_item(conn, &item)?;
             debug!("Inspection item created with ID: {}", id);
             self.get_inspection_item_by_id(id)
         })
     }
 
-    pub fn update_inspection_item(&self, id: i64, updates: InspectionItemUpdateData) -> AppResult<InspectionItem> {
-        info!("Updating inspection item: {}", id);
+    /// Create many checklist items in one transaction. Every item is
+    /// validated first; if any item fails, none are created and the error
+    /// names the item by its position, e.g. `items[12].item_name`.
+    pub fn create_items_batch(&self, context: &RequestContext, items: Vec<InspectionItem>) -> AppResult<Vec<InspectionItem>> {
+        info!("[{}] Creating batch of {} inspection items", context.request_id, items.len());
+        if items.is_empty() {
+            return Err(AppError::validation("items", "At least one inspection item is required"));
+        }
+        if items.len() > MAX_INSPECTION_ITEM_BATCH {
+            return Err(AppError::OutOfRange {
+                field: "items".to_string(),
+                value: items.len().to_string(),
+                min: "1".to_string(),
+                max: MAX_INSPECTION_ITEM_BATCH.to_string(),
+            });
+        }
+        let in_batch = |index: usize, e: AppError| match e {
+            AppError::Validation { field, message } => {
+                AppError::validation(format!("items[{}].{}", index, field), message)
+            }
+            other => other,
+        };
+        for (index, item) in items.iter().enumerate() {
+            item.validate().map_err(|e| in_batch(index, e))?;
+        }
+
+        self.database.with_transaction(|conn| {
+            let mut ids = Vec::with_capacity(items.len());
+            for (index, item) in items.iter().enumerate() {
+                ids.push(insert_inspection_item(conn, item).map_err(|e| in_batch(index, e))?);
+            }
+
+            let mut stmt = conn.prepare(
+                "SELECT id, inspection_id, component_id, item_name, item_category, condition,
+                 finding, severity, is_compliant, corrective_action, created_at, clause_id,
+                 risk_rating, response_due_at, measured_value, measurement_unit
+                 FROM inspection_items WHERE id IN (SELECT value FROM json_each(?1))
+                 ORDER BY id"
+            )?;
+            let created = stmt
+                .query_map(params![serde_json::to_string(&ids)?], |row| self.row_to_inspection_item(row))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            debug!("Created {} inspection items", created.len());
+            Ok(created)
+        })
+    }
+
+    pub fn update_inspection_item(&self, context: &RequestContext, id: i64, updates: InspectionItemUpdateData) -> AppResult<InspectionItem> {
+        info!("[{}] Updating inspection item: {}", context.request_id, id);
         
         self.database.with_transaction(|conn| {
             // Simple implementation - update individual fields
@@ -1346,11 +2294,43 @@ impl InspectionService {
                 conn.execute("UPDATE inspection_items SET item_category = ?1 WHERE id = ?2", params![item_category, id])?;
             }
             if let Some(severity) = &updates.severity {
-                conn.execute("UPDATE inspection_items SET severity = ?1 WHERE id = ?2", params![severity.to_string(), id])?;
+                // Only a change of severity restarts the response clock
+                let changed = conn.execute(
+                    "UPDATE inspection_items SET severity = ?1 WHERE id = ?2 AND severity IS NOT ?1",
+                    params![severity.to_string(), id],
+                )?;
+                if changed > 0 {
+                    apply_risk_matrix(conn, id)?;
+                    open_finding_sla(conn, id)?;
+                }
             }
             if let Some(corrective_action) = &updates.corrective_action {
                 conn.execute("UPDATE inspection_items SET corrective_action = ?1 WHERE id = ?2", params![corrective_action, id])?;
             }
+            if let Some(clause_id) = updates.clause_id {
+                let inspection_id: i64 = conn.query_row(
+                    "SELECT inspection_id FROM inspection_items WHERE id = ?1",
+                    params![id],
+                    |row| row.get(0),
+                ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+                    entity: "InspectionItem".to_string(),
+                    field: "id".to_string(),
+                    value: id.to_string(),
+                })?;
+                ensure_clause_applies(conn, inspection_id, clause_id)?;
+                conn.execute("UPDATE inspection_items SET clause_id = ?1 WHERE id = ?2", params![clause_id, id])?;
+            }
+            if let Some(measured_value) = updates.measured_value {
+                if !measured_value.is_finite() {
+                    return Err(AppError::validation("measured_value", "Measured value must be a finite number"));
+                }
+                conn.execute("UPDATE inspection_items SET measured_value = ?1 WHERE id = ?2", params![measured_value, id])?;
+            }
+            if let Some(measurement_unit) = &updates.measurement_unit {
+                conn.execute("UPDATE inspection_items SET measurement_unit = ?1 WHERE id = ?2", params![measurement_unit, id])?;
+            }
+            ensure_item_passes_save_rules(conn, id)?;
+            track_defect(conn, id)?;
 
             debug!("Inspection item {} updated successfully", id);
             self.get_inspection_item_by_id(id)
@@ -1363,7 +2343,8 @@ impl InspectionService {
 
         let mut stmt = conn.prepare(
             "SELECT id, inspection_id, component_id, item_name, item_category, condition,
-             finding, severity, is_compliant, corrective_action, created_at
+             finding, severity, is_compliant, corrective_action, created_at, clause_id,
+             risk_rating, response_due_at, measured_value, measurement_unit
              FROM inspection_items WHERE inspection_id = ?1 ORDER BY item_name"
         )?;
 
@@ -1379,11 +2360,222 @@ impl InspectionService {
         Ok(items)
     }
 
+    /// Attach an uploaded photo to an inspection item and return the IDs of
+    /// all photos now attached to it
+    pub fn attach_item_photo(&self, context: &RequestContext, item_id: i64, media_id: i64) -> AppResult<Vec<i64>> {
+        info!("[{}] Attaching media {} to inspection item {}", context.request_id, media_id, item_id);
+
+        self.database.with_transaction(|conn| {
+            let exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM inspection_items WHERE id = ?1)",
+                params![item_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Err(AppError::RecordNotFound {
+                    entity: "InspectionItem".to_string(),
+                    field: "id".to_string(),
+                    value: item_id.to_string(),
+                });
+            }
+            let file_type: Option<String> = conn.query_row(
+                "SELECT file_type FROM media_files WHERE id = ?1",
+                params![media_id],
+                |row| row.get(0),
+            ).optional()?;
+            match file_type.as_deref() {
+                None => return Err(AppError::RecordNotFound {
+                    entity: "MediaFile".to_string(),
+                    field: "id".to_string(),
+                    value: media_id.to_string(),
+                }),
+                Some("image") => {}
+                Some(other) => return Err(AppError::validation(
+                    "media_id",
+                    format!("Only photos can be attached to an item, media {} is {}", media_id, other),
+                )),
+            }
+
+            conn.execute(
+                "INSERT OR IGNORE INTO inspection_item_media (item_id, media_id) VALUES (?1, ?2)",
+                params![item_id, media_id],
+            )?;
+            let mut stmt = conn.prepare(
+                "SELECT media_id FROM inspection_item_media WHERE item_id = ?1 ORDER BY attached_at, media_id"
+            )?;
+            let photos = stmt.query_map(params![item_id], |row| row.get(0))?
+                .collect::<rusqlite::Result<Vec<i64>>>()?;
+            Ok(photos)
+        })
+    }
+
+    /// What to print on a blank checklist for an inspection. Items already
+    /// recorded against the inspection are printed by category; otherwise
+    /// the checklist template for its standard and type is followed.
+    pub fn get_paper_checklist(&self, inspection_id: i64) -> AppResult<PaperChecklist> {
+        debug!("Assembling paper checklist for inspection: {}", inspection_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<PaperChecklist> {
+            let mut checklist = conn.query_row(
+                "SELECT a.asset_number, a.asset_name, l.name, i.inspection_type, i.compliance_standard,
+                 i.scheduled_date, TRIM(u.first_name || ' ' || u.last_name)
+                 FROM inspections i
+                 JOIN assets a ON a.id = i.asset_id
+                 JOIN locations l ON l.id = a.location_id
+                 JOIN users u ON u.id = i.inspector_id
+                 WHERE i.id = ?1 AND i.deleted_at IS NULL",
+                params![inspection_id],
+                |row| Ok(PaperChecklist {
+                    inspection_id,
+                    asset_number: row.get(0)?,
+                    asset_name: row.get(1)?,
+                    location_name: row.get(2)?,
+                    inspection_type: row.get::<_, String>(3)?.parse().unwrap_or(InspectionType::Frequent),
+                    compliance_standard: row.get(4)?,
+                    scheduled_date: row.get(5)?,
+                    inspector_name: row.get(6)?,
+                    sections: Vec::new(),
+                }),
+            ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+                entity: "Inspection".to_string(),
+                field: "id".to_string(),
+                value: inspection_id.to_string(),
+            })?;
+
+            let mut stmt = conn.prepare(
+                "SELECT item_category, item_name FROM inspection_items
+                 WHERE inspection_id = ?1 ORDER BY item_category, item_name"
+            )?;
+            let recorded = stmt.query_map(params![inspection_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            for (category, name) in recorded {
+                let item = PaperChecklistItem { name, guidance: None };
+                match checklist.sections.last_mut() {
+                    Some(section) if section.title.as_deref() == Some(category.as_str()) => section.items.push(item),
+                    _ => checklist.sections.push(PaperChecklistSection { title: Some(category), items: vec![item] }),
+                }
+            }
+
+            if checklist.sections.is_empty() {
+                let mut stmt = conn.prepare(
+                    "SELECT s.standard_code, s.standard_name, t.checklist_structure
+                     FROM compliance_checklist_templates t
+                     JOIN compliance_standards s ON s.id = t.standard_id
+                     WHERE t.inspection_type = ?1"
+                )?;
+                let templates = stmt.query_map(params![checklist.inspection_type.to_string()], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+                })?.collect::<rusqlite::Result<Vec<_>>>()?;
+                let template = templates.into_iter().find(|(code, name, _)| {
+                    same_standard(&checklist.compliance_standard, code) || same_standard(&checklist.compliance_standard, name)
+                });
+                if let Some((_, _, structure)) = template {
+                    let structure: JsonValue = serde_json::from_str(&structure).map_err(|e| AppError::InvalidFormat {
+                        field: "checklist_structure".to_string(),
+                        expected: "valid JSON".to_string(),
+                        actual: e.to_string(),
+                    })?;
+                    collect_paper_checklist_sections(&structure, None, &mut checklist.sections);
+                }
+            }
+            Ok(checklist)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Mark an inspection as carried out on paper, attaching the scan of
+    /// the completed sheet. Results are then typed in as inspection items
+    /// as usual; a scheduled inspection moves to in progress.
+    pub fn record_paper_transcription(
+        &self,
+        context: &RequestContext,
+        inspection_id: i64,
+        input: PaperTranscriptionInput,
+    ) -> AppResult<PaperTranscription> {
+        info!("[{}] Recording paper transcription of inspection {}", context.request_id, inspection_id);
+        let user_id = context.current_user()?.user_id;
+        if input.inspected_at.is_some_and(|at| at > Utc::now()) {
+            return Err(AppError::validation("inspected_at", "Inspection date cannot be in the future"));
+        }
+
+        self.database.with_transaction(|conn| {
+            let status: Option<String> = conn.query_row(
+                "SELECT status FROM inspections WHERE id = ?1 AND deleted_at IS NULL",
+                params![inspection_id],
+                |row| row.get(0),
+            ).optional()?;
+            match status.as_deref() {
+                None => return Err(AppError::RecordNotFound {
+                    entity: "Inspection".to_string(),
+                    field: "id".to_string(),
+                    value: inspection_id.to_string(),
+                }),
+                Some("Cancelled") => return Err(AppError::validation(
+                    "inspection_id",
+                    "A cancelled inspection cannot be transcribed",
+                )),
+                Some(_) => {}
+            }
+
+            let media: Option<(Option<i64>, String)> = conn.query_row(
+                "SELECT inspection_id, file_type FROM media_files WHERE id = ?1",
+                params![input.scan_media_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            ).optional()?;
+            match media {
+                None => return Err(AppError::RecordNotFound {
+                    entity: "MediaFile".to_string(),
+                    field: "id".to_string(),
+                    value: input.scan_media_id.to_string(),
+                }),
+                Some((linked, _)) if linked != Some(inspection_id) => return Err(AppError::validation(
+                    "scan_media_id",
+                    format!("Media {} is not attached to inspection {}", input.scan_media_id, inspection_id),
+                )),
+                Some((_, file_type)) if file_type != "image" && file_type != "document" => return Err(AppError::validation(
+                    "scan_media_id",
+                    format!("The scan must be an image or document, media {} is {}", input.scan_media_id, file_type),
+                )),
+                Some(_) => {}
+            }
+
+            let before = history_snapshot(conn, HistoryEntityType::Inspection, inspection_id)?;
+            conn.execute(
+                "UPDATE inspections SET
+                     paper_scan_media_id = ?1,
+                     paper_transcribed_by = ?2,
+                     paper_transcribed_at = CURRENT_TIMESTAMP,
+                     actual_date = COALESCE(?3, actual_date),
+                     status = CASE WHEN status = 'Scheduled' THEN 'In Progress' ELSE status END,
+                     updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?4",
+                params![input.scan_media_id, user_id, input.inspected_at, inspection_id],
+            )?;
+            record_history(conn, context, HistoryEntityType::Inspection, inspection_id, "paper_transcription", before)?;
+
+            read_paper_transcription(conn, inspection_id)?.ok_or_else(|| AppError::Internal {
+                message: format!("Transcription of inspection {} was not saved", inspection_id),
+            })
+        })
+    }
+
+    /// How an inspection's paper checklist was transcribed, if it was
+    pub fn get_paper_transcription(&self, inspection_id: i64) -> AppResult<Option<PaperTranscription>> {
+        let conn = self.database.get_connection()?;
+        let result = read_paper_transcription(&conn, inspection_id);
+        self.database.return_connection(conn);
+        result
+    }
+
     fn get_inspection_item_by_id(&self, id: i64) -> AppResult<InspectionItem> {
         let conn = self.database.get_connection()?;
         let item = conn.query_row(
             "SELECT id, inspection_id, component_id, item_name, item_category, condition,
-             finding, severity, is_compliant, corrective_action, created_at
+             finding, severity, is_compliant, corrective_action, created_at, clause_id,
+             risk_rating, response_due_at, measured_value, measurement_unit
              FROM inspection_items WHERE id = ?1",
             params![id],
             |row| self.row_to_inspection_item(row),
@@ -1416,6 +2608,22 @@ impl InspectionService {
                 .and_then(|s| serde_json::from_str(&s).ok()),
             created_at: row.get(12)?,
             updated_at: row.get(13)?,
+            time_zone: row.get(14)?,
+            overdue_at: row.get(15)?,
+        })
+    }
+
+    /// Time zone of the location an asset is installed at
+    fn asset_time_zone(&self, conn: &Connection, asset_id: i64) -> AppResult<String> {
+        conn.query_row(
+            "SELECT l.time_zone FROM assets a JOIN locations l ON a.location_id = l.id
+             WHERE a.id = ?1 AND a.deleted_at IS NULL",
+            params![asset_id],
+            |row| row.get(0),
+        ).map_err(|_| AppError::RecordNotFound {
+            entity: "Asset".to_string(),
+            field: "id".to_string(),
+            value: asset_id.to_string(),
         })
     }
 
@@ -1434,6 +2642,11 @@ impl InspectionService {
             is_compliant: row.get(8)?,
             corrective_action: row.get(9)?,
             created_at: row.get(10)?,
+            clause_id: row.get(11)?,
+            risk_rating: row.get::<_, Option<String>>(12)?.and_then(|r| r.parse().ok()),
+            response_due_at: row.get(13)?,
+            measured_value: row.get(14)?,
+            measurement_unit: row.get(15)?,
         })
     }
 }
@@ -1512,27 +2725,219 @@ impl ComplianceService {
         Ok(templates)
     }
 
+    /// The checklist for an inspection, with each item's acceptance criteria,
+    /// clause references and reference photos filled in
     pub fn generate_inspection_checklist(&self, standard_id: i64, inspection_type: InspectionType) -> AppResult<JsonValue> {
         info!("Generating inspection checklist for standard: {} and type: {}", standard_id, inspection_type);
         let conn = self.database.get_connection()?;
 
-        let template = conn.query_row(
-            "SELECT checklist_structure FROM compliance_checklist_templates 
-             WHERE standard_id = ?1 AND inspection_type = ?2",
-            params![standard_id, inspection_type.to_string()],
-            |row| row.get::<_, String>(0),
-        ).map_err(|_| AppError::RecordNotFound {
-            entity: "ChecklistTemplate".to_string(),
-            field: "standard_id_inspection_type".to_string(),
-            value: format!("{}_{}", standard_id, inspection_type),
-        })?;
+        let result = (|| -> AppResult<JsonValue> {
+            let template = conn.query_row(
+                "SELECT checklist_structure FROM compliance_checklist_templates 
+                 WHERE standard_id = ?1 AND inspection_type = ?2",
+                params![standard_id, inspection_type.to_string()],
+                |row| row.get::<_, String>(0),
+            ).map_err(|_| AppError::RecordNotFound {
+                entity: "ChecklistTemplate".to_string(),
+                field: "standard_id_inspection_type".to_string(),
+                value: format!("{}_{}", standard_id, inspection_type),
+            })?;
+
+            let mut checklist: JsonValue = serde_json::from_str(&template).map_err(|e| AppError::InvalidFormat {
+                field: "checklist_structure".to_string(),
+                expected: "valid JSON".to_string(),
+                actual: e.to_string(),
+            })?;
+            resolve_checklist_guidance(&conn, &mut checklist)?;
+            Ok(checklist)
+        })();
 
         self.database.return_connection(conn);
-        
-        serde_json::from_str(&template).map_err(|e| AppError::InvalidFormat {
-            field: "checklist_structure".to_string(),
-            expected: "valid JSON".to_string(),
-            actual: e.to_string(),
+        result
+    }
+
+    /// Set the guidance carried by a named item of a checklist template,
+    /// replacing any it had
+    pub fn set_checklist_item_guidance(
+        &self,
+        context: &RequestContext,
+        template_id: i64,
+        item_name: &str,
+        guidance: ChecklistItemGuidance,
+    ) -> AppResult<ComplianceChecklistTemplate> {
+        info!("[{}] Setting guidance for item '{}' of checklist template {}", context.request_id, item_name, template_id);
+        guidance.validate()?;
+
+        self.database.with_transaction(|conn| {
+            let select = "SELECT id, standard_id, template_name, inspection_type, checklist_structure, created_at, updated_at
+                          FROM compliance_checklist_templates WHERE id = ?1";
+            let mut template = conn.query_row(select, params![template_id], |row| self.row_to_checklist_template(row))
+                .optional()?
+                .ok_or_else(|| AppError::RecordNotFound {
+                    entity: "ChecklistTemplate".to_string(),
+                    field: "id".to_string(),
+                    value: template_id.to_string(),
+                })?;
+
+            for media_id in &guidance.reference_media_ids {
+                let is_image: Option<bool> = conn.query_row(
+                    "SELECT file_type = 'image' FROM media_files WHERE id = ?1",
+                    params![media_id],
+                    |row| row.get(0),
+                ).optional()?;
+                match is_image {
+                    None => return Err(AppError::RecordNotFound {
+                        entity: "MediaFile".to_string(),
+                        field: "id".to_string(),
+                        value: media_id.to_string(),
+                    }),
+                    Some(false) => return Err(AppError::validation(
+                        "reference_media_ids",
+                        format!("Media file {} is not an image", media_id),
+                    )),
+                    Some(true) => {}
+                }
+            }
+
+            let mut matched = 0;
+            for_each_checklist_item(&mut template.checklist_structure, &mut |item| {
+                if checklist_item_name(item).is_some_and(|name| name.trim().eq_ignore_ascii_case(item_name.trim())) {
+                    item.insert("acceptance_criteria".to_string(), serde_json::json!(guidance.acceptance_criteria));
+                    item.insert("clause_references".to_string(), serde_json::json!(guidance.clause_references));
+                    item.insert("reference_media_ids".to_string(), serde_json::json!(guidance.reference_media_ids));
+                    matched += 1;
+                }
+            });
+            if matched == 0 {
+                return Err(AppError::RecordNotFound {
+                    entity: "ChecklistItem".to_string(),
+                    field: "name".to_string(),
+                    value: item_name.to_string(),
+                });
+            }
+
+            conn.execute(
+                "UPDATE compliance_checklist_templates SET checklist_structure = ?1 WHERE id = ?2",
+                params![template.checklist_structure.to_string(), template_id],
+            )?;
+            conn.query_row(select, params![template_id], |row| self.row_to_checklist_template(row))
+                .map_err(AppError::from)
+        })
+    }
+
+    /// Add clauses to a standard's catalog, replacing any with the same
+    /// reference
+    pub fn import_standard_clauses(
+        &self,
+        context: &RequestContext,
+        standard_id: i64,
+        clauses: Vec<StandardClauseInput>,
+    ) -> AppResult<Vec<StandardClause>> {
+        info!("[{}] Importing {} clauses for standard {}", context.request_id, clauses.len(), standard_id);
+        for clause in &clauses {
+            clause.validate()?;
+        }
+
+        self.database.with_transaction(|conn| {
+            let exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM compliance_standards WHERE id = ?1)",
+                params![standard_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Err(AppError::RecordNotFound {
+                    entity: "ComplianceStandard".to_string(),
+                    field: "id".to_string(),
+                    value: standard_id.to_string(),
+                });
+            }
+
+            let mut stmt = conn.prepare(&format!(
+                "INSERT INTO standard_clauses (standard_id, clause_ref, title, text)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(standard_id, clause_ref) DO UPDATE SET
+                     title = excluded.title, text = excluded.text, updated_at = CURRENT_TIMESTAMP
+                 RETURNING {}",
+                STANDARD_CLAUSE_COLUMNS
+            ))?;
+            let mut imported = Vec::with_capacity(clauses.len());
+            for clause in &clauses {
+                imported.push(stmt.query_row(
+                    params![standard_id, clause.clause_ref.trim(), clause.title.trim(), clause.text],
+                    row_to_standard_clause,
+                )?);
+            }
+            Ok(imported)
+        })
+    }
+
+    /// The clause catalog of a standard, in reference order
+    pub fn get_standard_clauses(&self, standard_id: i64) -> AppResult<Vec<StandardClause>> {
+        debug!("Fetching clauses for standard: {}", standard_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<StandardClause>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM standard_clauses WHERE standard_id = ?1 ORDER BY clause_ref",
+                STANDARD_CLAUSE_COLUMNS
+            ))?;
+            let clauses = stmt.query_map(params![standard_id], row_to_standard_clause)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(clauses)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// The clauses cited by an inspection's findings, for printing on reports
+    pub fn get_inspection_clauses(&self, inspection_id: i64) -> AppResult<Vec<StandardClause>> {
+        debug!("Fetching clauses cited by inspection: {}", inspection_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<StandardClause>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM standard_clauses
+                 WHERE id IN (SELECT clause_id FROM inspection_items WHERE inspection_id = ?1)
+                 ORDER BY clause_ref",
+                STANDARD_CLAUSE_COLUMNS
+            ))?;
+            let clauses = stmt.query_map(params![inspection_id], row_to_standard_clause)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(clauses)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Remove a clause from its catalog. Clauses cited by findings are kept
+    /// so reports stay traceable.
+    pub fn delete_standard_clause(&self, context: &RequestContext, clause_id: i64) -> AppResult<()> {
+        info!("[{}] Deleting standard clause {}", context.request_id, clause_id);
+
+        self.database.with_transaction(|conn| {
+            let cited: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM inspection_items WHERE clause_id = ?1",
+                params![clause_id],
+                |row| row.get(0),
+            )?;
+            if cited > 0 {
+                return Err(AppError::validation(
+                    "clause_id",
+                    format!("Clause {} is cited by {} inspection items", clause_id, cited),
+                ));
+            }
+
+            let deleted = conn.execute("DELETE FROM standard_clauses WHERE id = ?1", params![clause_id])?;
+            if deleted == 0 {
+                return Err(AppError::RecordNotFound {
+                    entity: "StandardClause".to_string(),
+                    field: "id".to_string(),
+                    value: clause_id.to_string(),
+                });
+            }
+            Ok(())
         })
     }
 
@@ -1591,6 +2996,13 @@ impl ComplianceService {
             warnings.push(format!("{} items missing compliance status", incomplete_items));
         }
 
+        let rules = inspection_rule_report(&conn, inspection_id)?;
+        for item in &rules.items {
+            for violation in &item.violations {
+                errors.push(format!("{}: {}", item.item_name, violation.message));
+            }
+        }
+
         // Calculate compliance score
         let compliance_score = self.calculate_compliance_score(inspection_id)?;
 
@@ -1638,19 +3050,43 @@ impl ComplianceService {
             |row| row.get(0),
         ).unwrap_or(None);
 
+        let (time_zone, service_class, fem_group): (Option<String>, Option<String>, Option<String>) = conn.query_row(
+            "SELECT l.time_zone, a.service_class, a.fem_group
+             FROM assets a JOIN locations l ON a.location_id = l.id WHERE a.id = ?1",
+            params![asset_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).unwrap_or((None, None, None));
+        let duty = DutyLevel::of(
+            service_class.and_then(|c| c.parse().ok()),
+            fem_group.and_then(|g| g.parse().ok()),
+        );
+        let calendar = asset_working_calendar(&conn, asset_id);
+
+        // An interval set for the standard the asset was last inspected
+        // against, as imported from a checklist pack, wins over the default
+        let interval_days: Option<i64> = conn.query_row(
+            "SELECT r.interval_days FROM inspection_frequency_rules r
+             JOIN compliance_standards s ON s.id = r.standard_id
+             WHERE r.inspection_type = ?2 AND s.standard_code = (
+                 SELECT compliance_standard FROM inspections WHERE asset_id = ?1 AND inspection_type = ?2
+                 ORDER BY COALESCE(actual_date, scheduled_date) DESC LIMIT 1
+             )",
+            params![asset_id, inspection_type.to_string()],
+            |row| row.get(0),
+        ).optional().unwrap_or(None);
+
         self.database.return_connection(conn);
 
         let base_date = last_inspection.unwrap_or_else(Utc::now);
+        let tz = scheduling::time_zone_or_default(time_zone.as_deref());
         
-        // Calculate next inspection based on type
-        let next_date = match inspection_type {
-            InspectionType::Frequent => base_date + chrono::Duration::days(30),  // Monthly
-            InspectionType::Periodic => base_date + chrono::Duration::days(365), // Yearly
-            InspectionType::Initial => base_date + chrono::Duration::days(1),    // Immediate
-            InspectionType::Special => base_date + chrono::Duration::days(90),   // Quarterly
-        };
-
-        Ok(next_date)
+        // Calculate next inspection based on type, shortened for cranes in
+        // heavy service, in the site's local calendar, and move it off days
+        // the site is closed
+        let interval_days = interval_days.unwrap_or_else(|| inspection_type.interval_days());
+        let interval_days = duty.map_or(interval_days, |duty| duty.scale_interval(interval_days));
+        let next_date = scheduling::add_local_days(base_date, interval_days, tz);
+        Ok(calendar?.shift_to_working_day(next_date, tz))
     }
 
     fn row_to_compliance_standard(&self, row: &Row) -> rusqlite::Result<ComplianceStandard> {
@@ -1681,27 +3117,724 @@ impl ComplianceService {
     }
 }
 
+const STANDARD_CLAUSE_COLUMNS: &str =
+    "id, standard_id, clause_ref, title, text, created_at, updated_at";
+
+fn row_to_standard_clause(row: &Row) -> rusqlite::Result<StandardClause> {
+    Ok(StandardClause {
+        id: row.get(0)?,
+        standard_id: row.get(1)?,
+        clause_ref: row.get(2)?,
+        title: row.get(3)?,
+        text: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+/// Reject citing a clause that does not exist or belongs to a standard other
+/// than the one the inspection is carried out against
+fn ensure_clause_applies(conn: &Connection, inspection_id: i64, clause_id: i64) -> AppResult<()> {
+    let clause = conn.query_row(
+        "SELECT s.standard_code, s.standard_name, c.clause_ref
+         FROM standard_clauses c JOIN compliance_standards s ON s.id = c.standard_id
+         WHERE c.id = ?1",
+        params![clause_id],
+        |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)),
+    ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+        entity: "StandardClause".to_string(),
+        field: "id".to_string(),
+        value: clause_id.to_string(),
+    })?;
+    let standard: Option<String> = conn.query_row(
+        "SELECT compliance_standard FROM inspections WHERE id = ?1",
+        params![inspection_id],
+        |row| row.get(0),
+    ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+        entity: "Inspection".to_string(),
+        field: "id".to_string(),
+        value: inspection_id.to_string(),
+    })?;
+
+    let (code, name, clause_ref) = clause;
+    match standard {
+        Some(standard) if same_standard(&standard, &code) || same_standard(&standard, &name) => Ok(()),
+        Some(standard) => Err(AppError::validation(
+            "clause_id",
+            format!("Clause {} of {} does not apply to an inspection against {}", clause_ref, code, standard),
+        )),
+        None => Err(AppError::validation(
+            "clause_id",
+            format!("Inspection {} has no compliance standard to cite clauses from", inspection_id),
+        )),
+    }
+}
+
+/// Sections of a checklist template with items, in order. A section takes
+/// its title from its own `title`, `name` or `section` field, or else from
+/// the section it is nested in.
+fn collect_paper_checklist_sections(value: &JsonValue, title: Option<&str>, sections: &mut Vec<PaperChecklistSection>) {
+    match value {
+        JsonValue::Object(map) => {
+            let title = ["title", "name", "section"].iter()
+                .find_map(|key| map.get(*key).and_then(JsonValue::as_str))
+                .or(title);
+            if let Some(JsonValue::Array(items)) = map.get("items") {
+                let items: Vec<PaperChecklistItem> = items.iter()
+                    .filter_map(JsonValue::as_object)
+                    .filter_map(|item| Some(PaperChecklistItem {
+                        name: checklist_item_name(item)?.trim().to_string(),
+                        guidance: item.get("acceptance_criteria").and_then(JsonValue::as_str).map(str::to_string),
+                    }))
+                    .collect();
+                if !items.is_empty() {
+                    sections.push(PaperChecklistSection { title: title.map(str::to_string), items });
+                }
+            }
+            for (key, child) in map {
+                if key != "items" {
+                    collect_paper_checklist_sections(child, title, sections);
+                }
+            }
+        }
+        JsonValue::Array(values) => {
+            for child in values {
+                collect_paper_checklist_sections(child, title, sections);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn read_paper_transcription(conn: &Connection, inspection_id: i64) -> AppResult<Option<PaperTranscription>> {
+    conn.query_row(
+        "SELECT id, paper_scan_media_id, paper_transcribed_by, paper_transcribed_at, actual_date
+         FROM inspections WHERE id = ?1 AND paper_transcribed_at IS NOT NULL",
+        params![inspection_id],
+        |row| Ok(PaperTranscription {
+            inspection_id: row.get(0)?,
+            scan_media_id: row.get(1)?,
+            transcribed_by: row.get(2)?,
+            transcribed_at: row.get(3)?,
+            inspected_at: row.get(4)?,
+        }),
+    ).optional().map_err(AppError::from)
+}
+
+/// Call `f` on every checklist item: the objects in any `items` array of the
+/// structure, however deeply sections are nested
+fn for_each_checklist_item(value: &mut JsonValue, f: &mut impl FnMut(&mut serde_json::Map<String, JsonValue>)) {
+    match value {
+        JsonValue::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if key == "items" {
+                    if let JsonValue::Array(items) = child {
+                        for item in items.iter_mut() {
+                            if let JsonValue::Object(item) = item {
+                                f(item);
+                            }
+                        }
+                    }
+                }
+                for_each_checklist_item(child, f);
+            }
+        }
+        JsonValue::Array(values) => {
+            for child in values {
+                for_each_checklist_item(child, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn checklist_item_name(item: &serde_json::Map<String, JsonValue>) -> Option<&str> {
+    item.get("name").or_else(|| item.get("item_name")).and_then(JsonValue::as_str)
+}
+
+/// Give every checklist item its guidance fields, resolving reference media
+/// IDs into the images inspectors are shown. Images deleted from the media
+/// library since are left out.
+fn resolve_checklist_guidance(conn: &Connection, checklist: &mut JsonValue) -> AppResult<()> {
+    let mut stmt = conn.prepare(
+        "SELECT id, file_name, file_path, mime_type, description FROM media_files WHERE id = ?1"
+    )?;
+    let mut error = None;
+    for_each_checklist_item(checklist, &mut |item| {
+        if error.is_some() {
+            return;
+        }
+        let media_ids: Vec<i64> = item.get("reference_media_ids")
+            .and_then(|ids| serde_json::from_value(ids.clone()).ok())
+            .unwrap_or_default();
+        let mut images = Vec::new();
+        for media_id in media_ids {
+            let image = stmt.query_row(params![media_id], |row| {
+                Ok(ChecklistReferenceImage {
+                    media_id: row.get(0)?,
+                    file_name: row.get(1)?,
+                    file_path: row.get(2)?,
+                    mime_type: row.get(3)?,
+                    description: row.get(4)?,
+                })
+            }).optional();
+            match image {
+                Ok(Some(image)) => images.push(image),
+                Ok(None) => {}
+                Err(e) => {
+                    error = Some(e);
+                    return;
+                }
+            }
+        }
+
+        item.entry("acceptance_criteria").or_insert(JsonValue::Null);
+        item.entry("clause_references").or_insert_with(|| serde_json::json!([]));
+        item.insert("reference_images".to_string(), serde_json::json!(images));
+    });
+    match error {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
+}
+
 // =============================================================================
-// User Service
+// Checklist Pack Service
 // =============================================================================
 
-pub struct UserService {
+/// Directory under the media root reference images from imported packs are
+/// stored in
+const PACK_IMAGE_DIR: &str = "uploads/image/packs";
+
+const TRUSTED_PUBLISHER_COLUMNS: &str = "id, name, public_key, added_by, created_at";
+
+fn row_to_trusted_publisher(row: &Row) -> rusqlite::Result<TrustedPackPublisher> {
+    let public_key: String = row.get(2)?;
+    Ok(TrustedPackPublisher {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        fingerprint: signing::key_fingerprint(&public_key),
+        public_key,
+        added_by: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+/// Pack key of a media library image
+fn pack_image_key(media_id: i64) -> String {
+    format!("image-{}", media_id)
+}
+
+/// Exports compliance standards with everything written against them as
+/// checklist packs, and imports packs from trusted publishers
+pub struct ChecklistPackService {
     database: Arc<Database>,
 }
 
-impl UserService {
+impl ChecklistPackService {
     pub fn new(database: Arc<Database>) -> Self {
         Self { database }
     }
 
-    /// Create a new user with plain text password that will be hashed
-    ///
-    /// # Arguments
-    /// * `user` - User object with plain text password in password_hash field
-    /// * `plain_password` - The plain text password to hash
-    pub fn create_user(&self, mut user: User, plain_password: String) -> AppResult<User> {
-        info!("Creating new user: {}", user.username);
-        user.validate()?;
+    /// Publishers whose signed packs are imported, by name
+    pub fn get_trusted_publishers(&self) -> AppResult<Vec<TrustedPackPublisher>> {
+        let conn = self.database.get_connection()?;
+        let result = (|| -> AppResult<Vec<TrustedPackPublisher>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM trusted_pack_publishers ORDER BY name COLLATE NOCASE",
+                TRUSTED_PUBLISHER_COLUMNS
+            ))?;
+            let publishers = stmt.query_map([], row_to_trusted_publisher)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(publishers)
+        })();
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Accept packs signed with a publisher's key from now on
+    pub fn trust_publisher(&self, context: &RequestContext, input: TrustedPackPublisherInput) -> AppResult<TrustedPackPublisher> {
+        info!("[{}] Trusting checklist pack publisher '{}'", context.request_id, input.name.trim());
+        input.validate()?;
+        let public_key = cranepack::validate_public_key(&input.public_key)?;
+        let user_id = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            let trusted: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM trusted_pack_publishers WHERE public_key = ?1)",
+                params![public_key],
+                |row| row.get(0),
+            )?;
+            if trusted {
+                return Err(AppError::DuplicateRecord {
+                    entity: "TrustedPackPublisher".to_string(),
+                    field: "public_key".to_string(),
+                    value: signing::key_fingerprint(&public_key),
+                });
+            }
+            conn.query_row(
+                &format!(
+                    "INSERT INTO trusted_pack_publishers (name, public_key, added_by, created_at)
+                     VALUES (?1, ?2, ?3, ?4)
+                     RETURNING {}",
+                    TRUSTED_PUBLISHER_COLUMNS
+                ),
+                params![input.name.trim(), public_key, user_id, Utc::now()],
+                row_to_trusted_publisher,
+            ).map_err(AppError::from)
+        })
+    }
+
+    /// Stop accepting packs from a publisher. Packs already imported stay.
+    pub fn remove_trusted_publisher(&self, context: &RequestContext, id: i64) -> AppResult<()> {
+        info!("[{}] Removing trusted checklist pack publisher {}", context.request_id, id);
+
+        self.database.with_transaction(|conn| {
+            let deleted = conn.execute("DELETE FROM trusted_pack_publishers WHERE id = ?1", params![id])?;
+            if deleted == 0 {
+                return Err(AppError::RecordNotFound {
+                    entity: "TrustedPackPublisher".to_string(),
+                    field: "id".to_string(),
+                    value: id.to_string(),
+                });
+            }
+            Ok(())
+        })
+    }
+
+    /// Gather the requested standards with their clauses, checklist
+    /// templates, frequency rules and the reference images the templates
+    /// show, ready to sign. Images deleted from the media library since are
+    /// left out.
+    pub fn build_pack(&self, context: &RequestContext, request: &PackExportRequest, media_root: &Path) -> AppResult<PackContents> {
+        info!("[{}] Building checklist pack '{}' of {} standards", context.request_id, request.name.trim(), request.standard_ids.len());
+        request.validate()?;
+
+        let conn = self.database.get_read_connection()?;
+        let mut contents = PackContents::new(PackManifest {
+            name: request.name.trim().to_string(),
+            version: request.version.trim().to_string(),
+            publisher: request.publisher.trim().to_string(),
+            description: request.description.clone(),
+            created_at: Utc::now(),
+        });
+
+        let mut media_ids = std::collections::BTreeSet::new();
+        for &standard_id in &request.standard_ids {
+            let mut standard = conn.query_row(
+                "SELECT standard_code, standard_name, version, requirements FROM compliance_standards WHERE id = ?1",
+                params![standard_id],
+                |row| Ok(PackStandard {
+                    standard_code: row.get(0)?,
+                    standard_name: row.get(1)?,
+                    version: row.get(2)?,
+                    requirements: row.get::<_, Option<String>>(3)?.and_then(|s| serde_json::from_str(&s).ok()),
+                    clauses: Vec::new(),
+                }),
+            ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+                entity: "ComplianceStandard".to_string(),
+                field: "id".to_string(),
+                value: standard_id.to_string(),
+            })?;
+
+            let mut stmt = conn.prepare(
+                "SELECT clause_ref, title, text FROM standard_clauses WHERE standard_id = ?1 ORDER BY clause_ref"
+            )?;
+            standard.clauses = stmt.query_map(params![standard_id], |row| Ok(StandardClauseInput {
+                clause_ref: row.get(0)?,
+                title: row.get(1)?,
+                text: row.get(2)?,
+            }))?.collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut stmt = conn.prepare(
+                "SELECT template_name, inspection_type, checklist_structure
+                 FROM compliance_checklist_templates WHERE standard_id = ?1 ORDER BY template_name"
+            )?;
+            let templates = stmt.query_map(params![standard_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+            })?.collect::<rusqlite::Result<Vec<_>>>()?;
+            for (template_name, inspection_type, checklist) in templates {
+                let mut checklist_structure: JsonValue = serde_json::from_str(&checklist).map_err(|e| AppError::InvalidFormat {
+                    field: "checklist_structure".to_string(),
+                    expected: "valid JSON".to_string(),
+                    actual: e.to_string(),
+                })?;
+                for_each_checklist_item(&mut checklist_structure, &mut |item| {
+                    if let Some(ids) = item.get("reference_media_ids").and_then(|ids| serde_json::from_value::<Vec<i64>>(ids.clone()).ok()) {
+                        media_ids.extend(ids);
+                    }
+                });
+                contents.templates.push(PackTemplate {
+                    standard_code: standard.standard_code.clone(),
+                    template_name,
+                    inspection_type: inspection_type.parse()?,
+                    checklist_structure,
+                });
+            }
+
+            let mut stmt = conn.prepare(
+                "SELECT inspection_type, interval_days FROM inspection_frequency_rules
+                 WHERE standard_id = ?1 ORDER BY inspection_type"
+            )?;
+            let rules = stmt.query_map(params![standard_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            for (inspection_type, interval_days) in rules {
+                contents.frequency_rules.push(PackFrequencyRule {
+                    standard_code: standard.standard_code.clone(),
+                    inspection_type: inspection_type.parse()?,
+                    interval_days,
+                });
+            }
+
+            contents.standards.push(standard);
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT file_name, file_path, mime_type, description FROM media_files WHERE id = ?1 AND file_type = 'image'"
+        )?;
+        for media_id in media_ids {
+            let image = stmt.query_row(params![media_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, Option<String>>(3)?))
+            }).optional()?;
+            let Some((file_name, file_path, mime_type, description)) = image else {
+                continue;
+            };
+            let full_path = media_root.join(&file_path);
+            let data = std::fs::read(&full_path)
+                .map_err(|e| AppError::file_system("read", full_path.display().to_string(), e.to_string()))?;
+            contents.reference_images.push(PackImage {
+                key: pack_image_key(media_id),
+                file_name,
+                mime_type,
+                description,
+                data: cranepack::encode_image(&data),
+            });
+        }
+
+        // Point items at the packed images instead of this installation's media IDs
+        let packed: HashSet<String> = contents.reference_images.iter().map(|image| image.key.clone()).collect();
+        for template in &mut contents.templates {
+            for_each_checklist_item(&mut template.checklist_structure, &mut |item| {
+                let Some(ids) = item.remove("reference_media_ids") else {
+                    return;
+                };
+                let keys: Vec<String> = serde_json::from_value::<Vec<i64>>(ids).unwrap_or_default()
+                    .into_iter()
+                    .map(pack_image_key)
+                    .filter(|key| packed.contains(key))
+                    .collect();
+                item.insert(cranepack::IMAGE_KEYS_FIELD.to_string(), serde_json::json!(keys));
+            });
+        }
+        Ok(contents)
+    }
+
+    /// Verify a pack came unaltered from a trusted publisher and add its
+    /// contents. Standards, clauses, templates and frequency rules replace
+    /// those with the same code, reference or inspection type; reference
+    /// images are added to the media library.
+    pub fn import_pack(&self, context: &RequestContext, pack: &CranePack, media_root: &Path) -> AppResult<PackImportSummary> {
+        let manifest = &pack.contents.manifest;
+        info!("[{}] Importing checklist pack '{}' {} from {}", context.request_id, manifest.name, manifest.version, manifest.publisher);
+        cranepack::verify(pack)?;
+        for standard in &pack.contents.standards {
+            for clause in &standard.clauses {
+                clause.validate()?;
+            }
+        }
+        for image in &pack.contents.reference_images {
+            if !image.mime_type.starts_with("image/") {
+                return Err(AppError::validation(
+                    "reference_images",
+                    format!("Reference image {} is {}, not an image", image.key, image.mime_type),
+                ));
+            }
+        }
+
+        let public_key = pack.signature.public_key.to_lowercase();
+        let fingerprint = signing::key_fingerprint(&public_key);
+        let trusted: bool = {
+            let conn = self.database.get_connection()?;
+            let trusted = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM trusted_pack_publishers WHERE public_key = ?1)",
+                params![public_key],
+                |row| row.get(0),
+            );
+            self.database.return_connection(conn);
+            trusted?
+        };
+        if !trusted {
+            return Err(AppError::authentication(format!(
+                "Checklist pack publisher '{}' (key {}) is not trusted", manifest.publisher, fingerprint
+            )));
+        }
+
+        // Write the images first; they are removed again if the import fails
+        let image_dir = media_root.join(PACK_IMAGE_DIR);
+        std::fs::create_dir_all(&image_dir)
+            .map_err(|e| AppError::file_system("create", image_dir.display().to_string(), e.to_string()))?;
+        let mut written = Vec::new();
+        let mut images = Vec::new();
+        for image in &pack.contents.reference_images {
+            let data = cranepack::decode_image(image)?;
+            let extension = Path::new(&image.file_name).extension().and_then(|ext| ext.to_str()).unwrap_or("bin");
+            let file_path = format!("{}/{}.{}", PACK_IMAGE_DIR, uuid::Uuid::new_v4(), extension);
+            let full_path = media_root.join(&file_path);
+            if let Err(e) = std::fs::write(&full_path, &data) {
+                written.iter().for_each(|path| { let _ = std::fs::remove_file(path); });
+                return Err(AppError::file_system("write", full_path.display().to_string(), e.to_string()));
+            }
+            written.push(full_path);
+            images.push((image, file_path, data));
+        }
+
+        let result = self.database.with_transaction(|conn| {
+            let mut summary = PackImportSummary {
+                pack_name: manifest.name.clone(),
+                pack_version: manifest.version.clone(),
+                publisher: manifest.publisher.clone(),
+                publisher_fingerprint: fingerprint.clone(),
+                ..Default::default()
+            };
+
+            let mut media_ids = HashMap::new();
+            for (image, file_path, data) in &images {
+                let photo = crate::photo::fingerprint(data);
+                let id = conn.query_row(
+                    "INSERT INTO media_files (file_name, file_path, file_type, mime_type, file_size, description, content_hash,
+                     uploaded_by)
+                     VALUES (?1, ?2, 'image', ?3, ?4, ?5, ?6, ?7)
+                     RETURNING id",
+                    params![image.file_name, file_path, image.mime_type, data.len() as i64, image.description, photo.content_hash,
+                            context.current_user().map(|u| u.user_id).ok()],
+                    |row| row.get::<_, i64>(0),
+                )?;
+                media_ids.insert(image.key.clone(), id);
+                summary.reference_images += 1;
+            }
+
+            let mut standard_ids = HashMap::new();
+            for standard in &pack.contents.standards {
+                let existing: Option<i64> = conn.query_row(
+                    "SELECT id FROM compliance_standards WHERE standard_code = ?1",
+                    params![standard.standard_code],
+                    |row| row.get(0),
+                ).optional()?;
+                let requirements = standard.requirements.as_ref().map(|r| r.to_string());
+                let id = match existing {
+                    Some(id) => {
+                        conn.execute(
+                            "UPDATE compliance_standards SET standard_name = ?2, version = ?3, requirements = ?4,
+                             is_active = 1, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+                            params![id, standard.standard_name, standard.version, requirements],
+                        )?;
+                        summary.standards_updated += 1;
+                        id
+                    }
+                    None => {
+                        summary.standards_created += 1;
+                        conn.query_row(
+                            "INSERT INTO compliance_standards (standard_code, standard_name, version, requirements)
+                             VALUES (?1, ?2, ?3, ?4)
+                             RETURNING id",
+                            params![standard.standard_code, standard.standard_name, standard.version, requirements],
+                            |row| row.get::<_, i64>(0),
+                        )?
+                    }
+                };
+                standard_ids.insert(standard.standard_code.as_str(), id);
+
+                for clause in &standard.clauses {
+                    conn.execute(
+                        "INSERT INTO standard_clauses (standard_id, clause_ref, title, text)
+                         VALUES (?1, ?2, ?3, ?4)
+                         ON CONFLICT(standard_id, clause_ref) DO UPDATE SET
+                             title = excluded.title, text = excluded.text, updated_at = CURRENT_TIMESTAMP",
+                        params![id, clause.clause_ref.trim(), clause.title.trim(), clause.text],
+                    )?;
+                    summary.clauses += 1;
+                }
+            }
+
+            for template in &pack.contents.templates {
+                let standard_id = standard_ids[template.standard_code.as_str()];
+                let mut checklist = template.checklist_structure.clone();
+                for_each_checklist_item(&mut checklist, &mut |item| {
+                    if let Some(keys) = item.remove(cranepack::IMAGE_KEYS_FIELD) {
+                        let ids: Vec<i64> = serde_json::from_value::<Vec<String>>(keys).unwrap_or_default()
+                            .iter()
+                            .filter_map(|key| media_ids.get(key).copied())
+                            .collect();
+                        item.insert("reference_media_ids".to_string(), serde_json::json!(ids));
+                    }
+                });
+
+                let replaced = conn.execute(
+                    "UPDATE compliance_checklist_templates SET template_name = ?3, checklist_structure = ?4
+                     WHERE standard_id = ?1 AND inspection_type = ?2",
+                    params![standard_id, template.inspection_type.to_string(), template.template_name, checklist.to_string()],
+                )?;
+                if replaced == 0 {
+                    conn.execute(
+                        "INSERT INTO compliance_checklist_templates (standard_id, template_name, inspection_type, checklist_structure)
+                         VALUES (?1, ?2, ?3, ?4)",
+                        params![standard_id, template.template_name, template.inspection_type.to_string(), checklist.to_string()],
+                    )?;
+                }
+                summary.templates += 1;
+            }
+
+            for rule in &pack.contents.frequency_rules {
+                conn.execute(
+                    "INSERT INTO inspection_frequency_rules (standard_id, inspection_type, interval_days, updated_at)
+                     VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT(standard_id, inspection_type) DO UPDATE SET
+                         interval_days = excluded.interval_days, updated_at = excluded.updated_at",
+                    params![standard_ids[rule.standard_code.as_str()], rule.inspection_type.to_string(), rule.interval_days, Utc::now()],
+                )?;
+                summary.frequency_rules += 1;
+            }
+
+            Ok(summary)
+        });
+
+        if result.is_err() {
+            written.iter().for_each(|path| { let _ = std::fs::remove_file(path); });
+        }
+        result
+    }
+}
+
+// =============================================================================
+// User Service
+// =============================================================================
+
+/// A user's saved preferences, or the defaults. Stored values that no longer
+/// parse fall back to their default rather than failing the read.
+fn user_preferences(conn: &Connection, user_id: i64) -> AppResult<UserPreferences> {
+    let defaults = UserPreferences::defaults(user_id);
+    let stored = conn.query_row(
+        "SELECT default_location_id, landing_page, capacity_unit, length_unit,
+                notification_channels, table_layouts, updated_at
+         FROM user_preferences WHERE user_id = ?1",
+        params![user_id],
+        |row| {
+            Ok((
+                row.get::<_, Option<i64>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, DateTime<Utc>>(6)?,
+            ))
+        },
+    ).optional()?;
+    let Some((default_location_id, landing_page, capacity_unit, length_unit, channels, layouts, updated_at)) = stored else {
+        return Ok(defaults);
+    };
+
+    Ok(UserPreferences {
+        user_id,
+        default_location_id,
+        landing_page,
+        capacity_unit: serde_json::from_value(JsonValue::String(capacity_unit)).unwrap_or(defaults.capacity_unit),
+        length_unit: serde_json::from_value(JsonValue::String(length_unit)).unwrap_or(defaults.length_unit),
+        notification_channels: serde_json::from_str(&channels).unwrap_or(defaults.notification_channels),
+        table_layouts: serde_json::from_str(&layouts).unwrap_or_default(),
+        updated_at: Some(updated_at),
+    })
+}
+
+fn account_lockout(conn: &Connection, user_id: i64, now: DateTime<Utc>) -> AppResult<AccountLockoutInfo> {
+    let stored = conn.query_row(
+        "SELECT failed_attempts, locked_at, locked_until FROM login_attempts WHERE user_id = ?1",
+        params![user_id],
+        |row| Ok((row.get::<_, i32>(0)?, row.get::<_, Option<DateTime<Utc>>>(1)?, row.get::<_, Option<DateTime<Utc>>>(2)?)),
+    ).optional()?;
+    let (failed_attempts, locked_at, locked_until) = stored.unwrap_or((0, None, None));
+    Ok(AccountLockoutInfo {
+        user_id,
+        failed_attempts,
+        locked_at,
+        locked_until,
+        is_locked: locked_until.is_some_and(|until| until > now),
+    })
+}
+
+/// SHA-256 of a password reset token; tokens themselves are never stored
+fn reset_token_hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.trim().as_bytes()))
+}
+
+/// Encrypted user columns, also the associated data they are sealed with
+const USER_EMAIL_COLUMN: &str = "email";
+const USER_PHONE_COLUMN: &str = "phone";
+
+/// SQL matching users by email address: by blind index `?{index}`, or, for
+/// rows not yet encrypted, by the plain address `?{plain}` ignoring case
+fn user_email_matches(index: usize, plain: usize) -> String {
+    format!(
+        "(email_index = ?{index} OR (email NOT LIKE '{prefix}%' AND LOWER(email) = LOWER(?{plain})))",
+        index = index, plain = plain, prefix = ENCRYPTED_PREFIX,
+    )
+}
+
+pub struct UserService {
+    database: Arc<Database>,
+    rate_limiter: SlidingWindowLimiter,
+    cipher: Arc<FieldCipher>,
+}
+
+impl UserService {
+    pub fn new(database: Arc<Database>, cipher: Arc<FieldCipher>) -> Self {
+        Self { database, rate_limiter: SlidingWindowLimiter::new(), cipher }
+    }
+
+    /// Encrypt and index email addresses and phone numbers written before
+    /// field encryption, or by tools that bypass the service. Returns how
+    /// many users were updated.
+    pub fn encrypt_plaintext_fields(&self) -> AppResult<usize> {
+        self.database.with_transaction(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT id, email, phone FROM users
+                 WHERE email NOT LIKE '{prefix}%' OR (phone IS NOT NULL AND phone NOT LIKE '{prefix}%')",
+                prefix = ENCRYPTED_PREFIX,
+            ))?;
+            let plaintext = stmt
+                .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            drop(stmt);
+
+            for (id, email, phone) in &plaintext {
+                let email = self.cipher.decrypt(USER_EMAIL_COLUMN, email)?;
+                let phone = self.cipher.decrypt_opt(USER_PHONE_COLUMN, phone.as_deref())?;
+                conn.execute(
+                    "UPDATE users SET email = ?1, email_index = ?2, phone = ?3 WHERE id = ?4",
+                    params![
+                        self.cipher.encrypt(USER_EMAIL_COLUMN, &email)?,
+                        self.cipher.blind_index(USER_EMAIL_COLUMN, &email),
+                        self.cipher.encrypt_opt(USER_PHONE_COLUMN, phone.as_deref())?,
+                        id,
+                    ],
+                )?;
+            }
+            if !plaintext.is_empty() {
+                info!("Encrypted contact details of {} users", plaintext.len());
+            }
+            Ok(plaintext.len())
+        })
+    }
+
+    /// Create a new user with plain text password that will be hashed
+    ///
+    /// # Arguments
+    /// * `context` - Request context of the calling command
+    /// * `user` - User object with plain text password in password_hash field
+    /// * `plain_password` - The plain text password to hash
+    pub fn create_user(&self, context: &RequestContext, mut user: User, plain_password: String) -> AppResult<User> {
+        info!("[{}] Creating new user: {}", context.request_id, user.username);
+        user.validate()?;
 
         // Validate password strength before proceeding
         let password_validation = self.validate_password_strength(&plain_password)?;
@@ -1737,12 +3870,16 @@ impl UserService {
 
         self.database.with_transaction(|conn| {
             let id = conn.query_row(
-                "INSERT INTO users (username, email, password_hash, role, first_name, last_name, phone, is_active)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                "INSERT INTO users (username, email, email_index, password_hash, role, first_name, last_name, phone, is_active)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
                  RETURNING id",
                 params![
-                    user.username, user.email, user.password_hash, user.role.to_string(),
-                    user.first_name, user.last_name, user.phone, user.is_active
+                    user.username,
+                    self.cipher.encrypt(USER_EMAIL_COLUMN, &user.email)?,
+                    self.cipher.blind_index(USER_EMAIL_COLUMN, &user.email),
+                    user.password_hash, user.role.to_string(), user.first_name, user.last_name,
+                    self.cipher.encrypt_opt(USER_PHONE_COLUMN, user.phone.as_deref())?,
+                    user.is_active
                 ],
                 |row| row.get::<_, i64>(0),
             )?;
@@ -1760,7 +3897,7 @@ impl UserService {
         let user = conn.query_row(
             "SELECT id, username, email, password_hash, role, first_name, last_name, phone,
              created_at, updated_at, is_active
-             FROM users WHERE id = ?1",
+             FROM users WHERE id = ?1 AND deleted_at IS NULL",
             params![id],
             |row| self.row_to_user(row),
         ).map_err(|_| AppError::RecordNotFound {
@@ -1780,7 +3917,7 @@ impl UserService {
         let user = conn.query_row(
             "SELECT id, username, email, password_hash, role, first_name, last_name, phone,
              created_at, updated_at, is_active
-             FROM users WHERE username = ?1",
+             FROM users WHERE username = ?1 AND deleted_at IS NULL",
             params![username],
             |row| self.row_to_user(row),
         ).map_err(|_| AppError::RecordNotFound {
@@ -1798,10 +3935,13 @@ impl UserService {
         let conn = self.database.get_connection()?;
         
         let user = conn.query_row(
-            "SELECT id, username, email, password_hash, role, first_name, last_name, phone,
-             created_at, updated_at, is_active
-             FROM users WHERE email = ?1",
-            params![email],
+            &format!(
+                "SELECT id, username, email, password_hash, role, first_name, last_name, phone,
+                 created_at, updated_at, is_active
+                 FROM users WHERE {} AND deleted_at IS NULL",
+                user_email_matches(1, 2)
+            ),
+            params![self.cipher.blind_index(USER_EMAIL_COLUMN, &email), email],
             |row| self.row_to_user(row),
         ).map_err(|_| AppError::RecordNotFound {
             entity: "User".to_string(),
@@ -1813,19 +3953,32 @@ impl UserService {
         Ok(user)
     }
 
-    pub fn update_user(&self, id: i64, updates: UserUpdateData) -> AppResult<User> {
-        info!("Updating user: {}", id);
+    pub fn update_user(&self, context: &RequestContext, id: i64, updates: UserUpdateData) -> AppResult<User> {
+        info!("[{}] Updating user: {}", context.request_id, id);
         
         self.database.with_transaction(|conn| {
+            let before = history_snapshot(conn, HistoryEntityType::User, id)?;
+
             // Simple implementation - update individual fields
             if let Some(username) = &updates.username {
                 conn.execute("UPDATE users SET username = ?1 WHERE id = ?2", params![username, id])?;
             }
             if let Some(email) = &updates.email {
-                conn.execute("UPDATE users SET email = ?1 WHERE id = ?2", params![email, id])?;
+                conn.execute(
+                    "UPDATE users SET email = ?1, email_index = ?2 WHERE id = ?3",
+                    params![
+                        self.cipher.encrypt(USER_EMAIL_COLUMN, email)?,
+                        self.cipher.blind_index(USER_EMAIL_COLUMN, email),
+                        id
+                    ],
+                )?;
             }
             if let Some(role) = &updates.role {
-                conn.execute("UPDATE users SET role = ?1 WHERE id = ?2", params![role.to_string(), id])?;
+                // A built-in role replaces any custom role
+                conn.execute(
+                    "UPDATE users SET role = ?1, custom_role_id = NULL WHERE id = ?2",
+                    params![role.to_string(), id],
+                )?;
             }
             if let Some(first_name) = &updates.first_name {
                 conn.execute("UPDATE users SET first_name = ?1 WHERE id = ?2", params![first_name, id])?;
@@ -1834,37 +3987,51 @@ impl UserService {
                 conn.execute("UPDATE users SET last_name = ?1 WHERE id = ?2", params![last_name, id])?;
             }
             if let Some(phone) = &updates.phone {
-                conn.execute("UPDATE users SET phone = ?1 WHERE id = ?2", params![phone, id])?;
+                conn.execute(
+                    "UPDATE users SET phone = ?1 WHERE id = ?2",
+                    params![self.cipher.encrypt(USER_PHONE_COLUMN, phone)?, id],
+                )?;
             }
             if let Some(is_active) = &updates.is_active {
                 conn.execute("UPDATE users SET is_active = ?1 WHERE id = ?2", params![is_active, id])?;
             }
 
+            record_history(conn, context, HistoryEntityType::User, id, "update", before)?;
             debug!("User {} updated successfully", id);
             self.get_user_by_id(id)
         })
     }
 
-    pub fn delete_user(&self, id: i64) -> AppResult<()> {
-        info!("Deleting user: {}", id);
+    /// Soft delete a user. The account can no longer sign in; records that
+    /// name the user keep pointing at it.
+    pub fn delete_user(&self, context: &RequestContext, id: i64) -> AppResult<()> {
+        info!("[{}] Deleting user: {}", context.request_id, id);
         
         self.database.with_transaction(|conn| {
-            let rows_affected = conn.execute("DELETE FROM users WHERE id = ?1", params![id])?;
-            
-            if rows_affected == 0 {
-                return Err(AppError::RecordNotFound {
-                    entity: "User".to_string(),
-                    field: "id".to_string(),
-                    value: id.to_string(),
-                });
-            }
-            
+            soft_delete(conn, context, RecycleEntityType::User, id)?;
             debug!("User {} deleted successfully", id);
             Ok(())
         })
     }
 
-    /// Verify a user's password using bcrypt
+    /// Undo the soft delete of a user
+    pub fn restore_user(&self, context: &RequestContext, id: i64) -> AppResult<User> {
+        info!("[{}] Restoring user: {}", context.request_id, id);
+        self.database.with_transaction(|conn| restore_soft_deleted(conn, RecycleEntityType::User, id))?;
+        self.get_user_by_id(id)
+    }
+
+    /// Permanently remove a soft-deleted user. Refused while inspections,
+    /// assets or other records still name the user.
+    pub fn purge_user(&self, context: &RequestContext, id: i64) -> AppResult<()> {
+        warn!("[{}] Purging user: {}", context.request_id, id);
+        self.database.with_transaction(|conn| purge_soft_deleted(conn, RecycleEntityType::User, id))?;
+        Ok(())
+    }
+
+    /// Verify a user's password using bcrypt. Wrong passwords are counted,
+    /// and after the configured number in a row the account is locked for
+    /// the configured time; a correct one clears the count.
     ///
     /// # Arguments
     /// * `user_id` - The user's ID
@@ -1873,22 +4040,27 @@ impl UserService {
     /// # Returns
     /// * `Ok(true)` if password matches
     /// * `Ok(false)` if password doesn't match
-    /// * `Err` if user not found or other error
+    /// * `Err` if user not found, the account is inactive or locked, or other error
     pub fn verify_password(&self, user_id: i64, password: String) -> AppResult<bool> {
         debug!("Verifying password for user: {}", user_id);
+        let now = Utc::now();
         let conn = self.database.get_connection()?;
         
-        let (password_hash, is_active): (String, bool) = conn.query_row(
-            "SELECT password_hash, is_active FROM users WHERE id = ?1",
-            params![user_id],
-            |row| Ok((row.get(0)?, row.get(1)?)),
-        ).map_err(|_| AppError::RecordNotFound {
-            entity: "User".to_string(),
-            field: "id".to_string(),
-            value: user_id.to_string(),
-        })?;
+        let stored = (|| -> AppResult<(String, bool, AccountLockoutInfo)> {
+            let (password_hash, is_active): (String, bool) = conn.query_row(
+                "SELECT password_hash, is_active FROM users WHERE id = ?1",
+                params![user_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            ).map_err(|_| AppError::RecordNotFound {
+                entity: "User".to_string(),
+                field: "id".to_string(),
+                value: user_id.to_string(),
+            })?;
+            Ok((password_hash, is_active, account_lockout(&conn, user_id, now)?))
+        })();
 
         self.database.return_connection(conn);
+        let (password_hash, is_active, lockout) = stored?;
         
         // Check if user account is active
         if !is_active {
@@ -1896,16 +4068,29 @@ impl UserService {
                 reason: "User account is inactive".to_string(),
             });
         }
+        if let Some(locked_until) = lockout.locked_until.filter(|_| lockout.is_locked) {
+            return Err(AppError::authentication(format!(
+                "Account locked after too many wrong passwords; try again after {} UTC",
+                locked_until.format("%H:%M")
+            )));
+        }
 
         // Verify password using bcrypt
         match bcrypt::verify(&password, &password_hash) {
-            Ok(is_valid) => {
-                if is_valid {
-                    debug!("Password verification successful for user: {}", user_id);
-                } else {
-                    debug!("Password verification failed for user: {}", user_id);
+            Ok(true) => {
+                debug!("Password verification successful for user: {}", user_id);
+                if lockout.failed_attempts > 0 {
+                    self.database.with_transaction(|conn| {
+                        conn.execute("DELETE FROM login_attempts WHERE user_id = ?1", params![user_id])?;
+                        Ok(())
+                    })?;
                 }
-                Ok(is_valid)
+                Ok(true)
+            }
+            Ok(false) => {
+                debug!("Password verification failed for user: {}", user_id);
+                self.record_failed_password(user_id, now)?;
+                Ok(false)
             }
             Err(e) => {
                 debug!("Password verification error for user {}: {}", user_id, e);
@@ -1916,13 +4101,234 @@ impl UserService {
         }
     }
 
+    /// Count a wrong password, locking the account once the count reaches
+    /// the threshold. A lock that has run out starts the count again.
+    fn record_failed_password(&self, user_id: i64, now: DateTime<Utc>) -> AppResult<()> {
+        let lockout = self.database.with_transaction(|conn| {
+            let settings = read_app_settings(conn)?;
+            let failed_attempts: i64 = conn.query_row(
+                "INSERT INTO login_attempts (user_id, failed_attempts, last_failed_at) VALUES (?1, 1, ?2)
+                 ON CONFLICT(user_id) DO UPDATE SET
+                     failed_attempts = CASE WHEN locked_until <= ?2 THEN 1 ELSE failed_attempts + 1 END,
+                     locked_at = CASE WHEN locked_until <= ?2 THEN NULL ELSE locked_at END,
+                     locked_until = CASE WHEN locked_until <= ?2 THEN NULL ELSE locked_until END,
+                     last_failed_at = ?2
+                 RETURNING failed_attempts",
+                params![user_id, now],
+                |row| row.get(0),
+            )?;
+            if failed_attempts >= settings.lockout_threshold {
+                let locked_until = now + chrono::Duration::minutes(settings.lockout_duration_minutes);
+                let locked = conn.execute(
+                    "UPDATE login_attempts SET locked_at = ?2, locked_until = ?3
+                     WHERE user_id = ?1 AND locked_until IS NULL",
+                    params![user_id, now, locked_until],
+                )?;
+                if locked > 0 {
+                    warn!("User {} locked out until {} after {} wrong passwords", user_id, locked_until, failed_attempts);
+                    return Ok(Some((locked_until, failed_attempts)));
+                }
+            }
+            Ok(None)
+        })?;
+
+        // Recorded once the transaction has committed, since the event is
+        // written on a connection of its own
+        if let Some((locked_until, failed_attempts)) = lockout {
+            record_security_event(
+                &SecurityEvent::new(SecurityEventKind::AccountLocked, format!(
+                    "Locked until {} after {} wrong passwords", locked_until.format("%Y-%m-%d %H:%M UTC"), failed_attempts
+                ))
+                .with_user_id(user_id),
+            );
+        }
+        Ok(())
+    }
+
+    /// Where the account's password is checked
+    pub fn get_auth_source(&self, user_id: i64) -> AppResult<AuthSource> {
+        let conn = self.database.get_connection()?;
+        let source: Option<String> = conn.query_row(
+            "SELECT auth_source FROM users WHERE id = ?1",
+            params![user_id],
+            |row| row.get(0),
+        ).optional()?;
+        self.database.return_connection(conn);
+
+        source
+            .ok_or_else(|| AppError::RecordNotFound {
+                entity: "User".to_string(),
+                field: "id".to_string(),
+                value: user_id.to_string(),
+            })?
+            .parse()
+    }
+
+    /// Create or update the local account of a directory user who has just
+    /// signed in, so inspections and audit entries can name them.
+    ///
+    /// The role, name and email address follow the directory on every
+    /// sign-in. Whether the account is active stays under local control,
+    /// and the account never gets a usable password hash.
+    pub fn provision_directory_user(&self, username: &str, directory: &DirectoryUser, role: UserRole) -> AppResult<User> {
+        let email = directory.email.clone().filter(|email| !email.trim().is_empty())
+            .ok_or_else(|| AppError::validation("email", "Directory account has no email address"))?;
+        let first_name = directory.first_name.clone().unwrap_or_default();
+        let last_name = directory.last_name.clone().unwrap_or_default();
+
+        self.database.with_transaction(|conn| {
+            let existing: Option<(i64, String, bool)> = conn.query_row(
+                "SELECT id, auth_source, deleted_at IS NOT NULL FROM users WHERE username = ?1",
+                params![username],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            ).optional()?;
+
+            let email_index = self.cipher.blind_index(USER_EMAIL_COLUMN, &email);
+            let encrypted_email = self.cipher.encrypt(USER_EMAIL_COLUMN, &email)?;
+            let email_taken: bool = conn.query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM users WHERE {} AND username <> ?3)", user_email_matches(1, 2)),
+                params![email_index, email, username],
+                |row| row.get(0),
+            )?;
+            if email_taken {
+                return Err(AppError::DuplicateRecord {
+                    entity: "User".to_string(),
+                    field: "email".to_string(),
+                    value: email.clone(),
+                });
+            }
+
+            let id = match existing {
+                Some((_, _, true)) => return Err(AppError::authentication("User account has been removed")),
+                Some((_, source, _)) if source != AuthSource::Ldap.to_string() => {
+                    return Err(AppError::authentication("Account does not sign in through the directory"));
+                }
+                Some((id, _, _)) => {
+                    conn.execute(
+                        "UPDATE users SET email = ?1, email_index = ?2, role = ?3, first_name = ?4, last_name = ?5,
+                         updated_at = CURRENT_TIMESTAMP WHERE id = ?6",
+                        params![encrypted_email, email_index, role.to_string(), first_name, last_name, id],
+                    )?;
+                    id
+                }
+                None => {
+                    // "!" is never produced by bcrypt, so no password matches it
+                    let id = conn.query_row(
+                        "INSERT INTO users (username, email, email_index, password_hash, role, first_name, last_name,
+                         is_active, auth_source)
+                         VALUES (?1, ?2, ?3, '!', ?4, ?5, ?6, 1, ?7)
+                         RETURNING id",
+                        params![username, encrypted_email, email_index, role.to_string(), first_name, last_name,
+                                AuthSource::Ldap.to_string()],
+                        |row| row.get::<_, i64>(0),
+                    )?;
+                    info!("Created account for directory user {}", username);
+                    id
+                }
+            };
+            conn.query_row(
+                "SELECT id, username, email, password_hash, role, first_name, last_name, phone,
+                 created_at, updated_at, is_active
+                 FROM users WHERE id = ?1",
+                params![id],
+                |row| self.row_to_user(row),
+            ).map_err(AppError::from)
+        })
+    }
+
+    /// The account linked to an identity from the single sign-on provider,
+    /// with its email and name brought up to date. Accounts are linked by
+    /// the provider's subject, so a renamed user keeps their account. With
+    /// `auto_provision`, an unknown identity gets a new account with
+    /// `default_role`; roles of existing accounts are left to
+    /// administrators.
+    pub fn provision_oidc_user(&self, identity: &OidcIdentity, default_role: UserRole, auto_provision: bool) -> AppResult<User> {
+        let email = identity.email.clone().filter(|email| !email.trim().is_empty())
+            .ok_or_else(|| AppError::validation("email", "Single sign-on account has no email address"))?;
+        let first_name = identity.first_name.clone().unwrap_or_default();
+        let last_name = identity.last_name.clone().unwrap_or_default();
+        let source = AuthSource::Oidc.to_string();
+
+        self.database.with_transaction(|conn| {
+            let linked: Option<(i64, bool)> = conn.query_row(
+                "SELECT id, deleted_at IS NOT NULL FROM users WHERE auth_source = ?1 AND external_subject = ?2",
+                params![source, identity.subject],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            ).optional()?;
+
+            let email_index = self.cipher.blind_index(USER_EMAIL_COLUMN, &email);
+            let encrypted_email = self.cipher.encrypt(USER_EMAIL_COLUMN, &email)?;
+            let email_taken: bool = conn.query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM users WHERE {} AND id IS NOT ?3)", user_email_matches(1, 2)),
+                params![email_index, email, linked.map(|(id, _)| id)],
+                |row| row.get(0),
+            )?;
+            if email_taken {
+                return Err(AppError::DuplicateRecord {
+                    entity: "User".to_string(),
+                    field: "email".to_string(),
+                    value: email.clone(),
+                });
+            }
+
+            let id = match linked {
+                Some((_, true)) => return Err(AppError::authentication("User account has been removed")),
+                Some((id, false)) => {
+                    conn.execute(
+                        "UPDATE users SET email = ?1, email_index = ?2, first_name = ?3, last_name = ?4,
+                         updated_at = CURRENT_TIMESTAMP WHERE id = ?5",
+                        params![encrypted_email, email_index, first_name, last_name, id],
+                    )?;
+                    id
+                }
+                None if !auto_provision => {
+                    return Err(AppError::authentication("No account is linked to this sign-on identity"));
+                }
+                None => {
+                    let username_taken: bool = conn.query_row(
+                        "SELECT EXISTS(SELECT 1 FROM users WHERE username = ?1)",
+                        params![identity.username],
+                        |row| row.get(0),
+                    )?;
+                    if username_taken {
+                        return Err(AppError::DuplicateRecord {
+                            entity: "User".to_string(),
+                            field: "username".to_string(),
+                            value: identity.username.clone(),
+                        });
+                    }
+                    // "!" is never produced by bcrypt, so no password matches it
+                    let id = conn.query_row(
+                        "INSERT INTO users (username, email, email_index, password_hash, role, first_name, last_name,
+                         is_active, auth_source, external_subject)
+                         VALUES (?1, ?2, ?3, '!', ?4, ?5, ?6, 1, ?7, ?8)
+                         RETURNING id",
+                        params![identity.username, encrypted_email, email_index, default_role.to_string(),
+                                first_name, last_name, source, identity.subject],
+                        |row| row.get::<_, i64>(0),
+                    )?;
+                    info!("Created account {} for single sign-on user", identity.username);
+                    id
+                }
+            };
+            conn.query_row(
+                "SELECT id, username, email, password_hash, role, first_name, last_name, phone,
+                 created_at, updated_at, is_active
+                 FROM users WHERE id = ?1",
+                params![id],
+                |row| self.row_to_user(row),
+            ).map_err(AppError::from)
+        })
+    }
+
     /// Update a user's password with validation and proper hashing
     ///
     /// # Arguments
+    /// * `context` - Request context of the calling command
     /// * `user_id` - The user's ID
     /// * `new_password` - The new plain text password
-    pub fn update_password(&self, user_id: i64, new_password: String) -> AppResult<()> {
-        info!("Updating password for user: {}", user_id);
+    pub fn update_password(&self, context: &RequestContext, user_id: i64, new_password: String) -> AppResult<()> {
+        info!("[{}] Updating password for user: {}", context.request_id, user_id);
         
         // Validate password strength
         let password_validation = self.validate_password_strength(&new_password)?;
@@ -1976,36 +4382,287 @@ impl UserService {
         })
     }
 
-    pub fn get_users_by_role(&self, role: UserRole, filter: QueryFilter) -> AppResult<PaginatedResult<User>> {
-        info!("Fetching users by role: {}", role);
-        let conn = self.database.get_connection()?;
+    /// Create a single-use password reset token for the active user with
+    /// the given username or email address.
+    ///
+    /// Returns `None` when there is no such user or they already have
+    /// `MAX_ACTIVE_PASSWORD_RESETS` outstanding tokens, so callers can
+    /// answer every request the same way without revealing which accounts
+    /// exist.
+    ///
+    /// # Arguments
+    /// * `context` - Request context of the calling command
+    /// * `identifier` - Username or email address
+    pub fn initiate_password_reset(&self, context: &RequestContext, identifier: &str) -> AppResult<Option<PasswordReset>> {
+        info!("[{}] Password reset requested", context.request_id);
+        let requested_by = context.current_user().map(|u| u.user_id).ok();
+        let token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+        let expires_at = Utc::now() + chrono::Duration::minutes(PASSWORD_RESET_TOKEN_MINUTES);
 
-        let offset = ((filter.page.unwrap_or(1) - 1) * filter.limit.unwrap_or(50)).max(0);
-        let limit = filter.limit.unwrap_or(50);
+        self.database.with_transaction(|conn| {
+            let user_id: Option<i64> = conn.query_row(
+                &format!(
+                    "SELECT id FROM users
+                     WHERE (username = ?2 OR {}) AND is_active = 1 AND deleted_at IS NULL",
+                    user_email_matches(1, 2)
+                ),
+                params![self.cipher.blind_index(USER_EMAIL_COLUMN, identifier), identifier.trim()],
+                |row| row.get(0),
+            ).optional()?;
+            let Some(user_id) = user_id else {
+                debug!("[{}] No active user matches the reset request", context.request_id);
+                return Ok(None);
+            };
+
+            let outstanding: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM password_reset_tokens
+                 WHERE user_id = ?1 AND used_at IS NULL AND expires_at > ?2",
+                params![user_id, Utc::now()],
+                |row| row.get(0),
+            )?;
+            if outstanding >= MAX_ACTIVE_PASSWORD_RESETS {
+                warn!("[{}] User {} already has {} outstanding password resets", context.request_id, user_id, outstanding);
+                return Ok(None);
+            }
 
-        let mut stmt = conn.prepare(
-            "SELECT id, username, email, password_hash, role, first_name, last_name, phone,
-             created_at, updated_at, is_active
-             FROM users WHERE role = ?1 AND is_active = 1
-             ORDER BY last_name, first_name LIMIT ?2 OFFSET ?3"
-        )?;
+            conn.execute(
+                "INSERT INTO password_reset_tokens (user_id, token_hash, expires_at, requested_by) VALUES (?1, ?2, ?3, ?4)",
+                params![user_id, reset_token_hash(&token), expires_at, requested_by],
+            )?;
+            Ok(Some(PasswordReset { user_id, token: token.clone(), expires_at }))
+        })
+    }
 
-        let user_iter = stmt.query_map(params![role.to_string(), limit, offset], |row| self.row_to_user(row))?;
+    /// Create a password reset token for another user on an administrator's
+    /// behalf. The administrator must hold every permission the user has, so
+    /// a reset cannot be used to take over a higher-ranked account.
+    pub fn create_password_reset_for(&self, context: &RequestContext, user_id: i64) -> AppResult<Option<PasswordReset>> {
+        let user = self.get_user_by_id(user_id)?;
+        let conn = self.database.get_connection()?;
+        let permissions = user_permissions(&conn, user.id, &user.role);
+        self.database.return_connection(conn);
+        ensure_grantable(context, &permissions?)?;
+        self.initiate_password_reset(context, &user.username)
+    }
 
-        let mut users = Vec::new();
-        for user in user_iter {
-            users.push(user?);
-        }
+    /// Set a new password with a reset token. The token is used up, along
+    /// with any other outstanding tokens of the user. Returns the user's ID.
+    ///
+    /// # Arguments
+    /// * `context` - Request context of the calling command
+    /// * `token` - Token from `initiate_password_reset`
+    /// * `new_password` - The new plain text password
+    pub fn complete_password_reset(&self, context: &RequestContext, token: &str, new_password: String) -> AppResult<i64> {
+        info!("[{}] Completing password reset", context.request_id);
+
+        let password_validation = self.validate_password_strength(&new_password)?;
+        if !password_validation.is_valid {
+            return Err(AppError::validation(
+                "password",
+                format!("New password does not meet strength requirements: {}",
+                    password_validation.issues.join(", "))
+            ));
+        }
+        let password_hash = bcrypt::hash(&new_password, bcrypt::DEFAULT_COST)?;
+
+        self.database.with_transaction(|conn| {
+            let stored = conn.query_row(
+                "SELECT t.id, t.user_id, t.expires_at, t.used_at, u.is_active AND u.deleted_at IS NULL
+                 FROM password_reset_tokens t JOIN users u ON u.id = t.user_id
+                 WHERE t.token_hash = ?1",
+                params![reset_token_hash(token)],
+                |row| Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, DateTime<Utc>>(2)?,
+                    row.get::<_, Option<DateTime<Utc>>>(3)?,
+                    row.get::<_, bool>(4)?,
+                )),
+            ).optional()?;
+
+            let Some((token_id, user_id, expires_at, used_at, user_active)) = stored else {
+                return Err(AppError::authentication("Invalid password reset token"));
+            };
+            if used_at.is_some() {
+                warn!("[{}] Password reset token {} was presented again", context.request_id, token_id);
+                return Err(AppError::authentication("Password reset token has already been used"));
+            }
+            if expires_at <= Utc::now() {
+                return Err(AppError::authentication("Password reset token has expired"));
+            }
+            if !user_active {
+                return Err(AppError::authentication("User account is inactive"));
+            }
+
+            conn.execute(
+                "UPDATE users SET password_hash = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+                params![password_hash, user_id],
+            )?;
+            conn.execute(
+                "UPDATE password_reset_tokens SET used_at = CURRENT_TIMESTAMP WHERE user_id = ?1 AND used_at IS NULL",
+                params![user_id],
+            )?;
+
+            info!("[{}] Password reset for user {}", context.request_id, user_id);
+            Ok(user_id)
+        })
+    }
+
+    /// Get a user's preferred locale
+    ///
+    /// # Arguments
+    /// * `user_id` - The user's ID
+    pub fn get_user_locale(&self, user_id: i64) -> AppResult<Locale> {
+        debug!("Fetching locale for user: {}", user_id);
+        let conn = self.database.get_connection()?;
+
+        let locale: String = conn.query_row(
+            "SELECT locale FROM users WHERE id = ?1",
+            params![user_id],
+            |row| row.get(0),
+        ).map_err(|_| AppError::RecordNotFound {
+            entity: "User".to_string(),
+            field: "id".to_string(),
+            value: user_id.to_string(),
+        })?;
+        self.database.return_connection(conn);
+
+        Ok(locale.parse().unwrap_or_default())
+    }
+
+    /// Set a user's preferred locale
+    ///
+    /// # Arguments
+    /// * `context` - Request context of the calling command
+    /// * `user_id` - The user's ID
+    /// * `locale` - The new locale
+    pub fn set_user_locale(&self, context: &RequestContext, user_id: i64, locale: Locale) -> AppResult<()> {
+        info!("[{}] Setting locale for user {} to {}", context.request_id, user_id, locale);
+
+        self.database.with_transaction(|conn| {
+            let before = history_snapshot(conn, HistoryEntityType::User, user_id)?;
+            let rows_affected = conn.execute(
+                "UPDATE users SET locale = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+                params![locale.code(), user_id]
+            )?;
+
+            if rows_affected == 0 {
+                return Err(AppError::RecordNotFound {
+                    entity: "User".to_string(),
+                    field: "id".to_string(),
+                    value: user_id.to_string(),
+                });
+            }
+
+            record_history(conn, context, HistoryEntityType::User, user_id, "update", before)?;
+            debug!("Locale updated successfully for user: {}", user_id);
+            Ok(())
+        })
+    }
+
+    /// Get a user's preferences, or the defaults if they never saved any
+    ///
+    /// # Arguments
+    /// * `user_id` - The user's ID
+    pub fn get_preferences(&self, user_id: i64) -> AppResult<UserPreferences> {
+        debug!("Fetching preferences for user: {}", user_id);
+        let conn = self.database.get_connection()?;
+        let result = user_preferences(&conn, user_id);
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Replace the signed-in user's preferences
+    ///
+    /// # Arguments
+    /// * `context` - Request context of the calling command
+    /// * `input` - The new preferences
+    pub fn set_preferences(&self, context: &RequestContext, input: UserPreferencesInput) -> AppResult<UserPreferences> {
+        let user_id = context.current_user()?.user_id;
+        info!("[{}] Saving preferences for user {}", context.request_id, user_id);
+        input.validate()?;
+
+        self.database.with_transaction(|conn| {
+            if let Some(location_id) = input.default_location_id {
+                let location_exists: bool = conn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM locations WHERE id = ?1)",
+                    params![location_id],
+                    |row| row.get(0),
+                )?;
+                if !location_exists {
+                    return Err(AppError::RecordNotFound {
+                        entity: "Location".to_string(),
+                        field: "id".to_string(),
+                        value: location_id.to_string(),
+                    });
+                }
+            }
+
+            let mut channels = input.notification_channels.clone();
+            channels.dedup();
+            conn.execute(
+                "INSERT INTO user_preferences
+                    (user_id, default_location_id, landing_page, capacity_unit, length_unit,
+                     notification_channels, table_layouts, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP)
+                 ON CONFLICT(user_id) DO UPDATE SET
+                    default_location_id = excluded.default_location_id,
+                    landing_page = excluded.landing_page,
+                    capacity_unit = excluded.capacity_unit,
+                    length_unit = excluded.length_unit,
+                    notification_channels = excluded.notification_channels,
+                    table_layouts = excluded.table_layouts,
+                    updated_at = excluded.updated_at",
+                params![
+                    user_id,
+                    input.default_location_id,
+                    input.landing_page,
+                    serde_json::to_value(input.capacity_unit)?.as_str(),
+                    serde_json::to_value(input.length_unit)?.as_str(),
+                    serde_json::to_string(&channels)?,
+                    serde_json::to_string(&input.table_layouts)?,
+                ],
+            )?;
+            user_preferences(conn, user_id)
+        })
+    }
+
+    pub fn get_users_by_role(&self, role: UserRole, filter: QueryFilter) -> AppResult<PaginatedResult<User>> {
+        info!("Fetching users by role: {}", role);
+        let paging = QuerySpec::USERS.validate(&filter)?;
+        let conn = self.database.get_connection()?;
+
+        let (offset, limit) = (paging.offset, paging.limit);
+        let role = role.to_string();
+        let where_clause = format!("WHERE role = ?1 AND is_active = 1 AND deleted_at IS NULL{}", paging.filter_condition(2));
+        let order_by = match paging.sort_by {
+            Some(_) => paging.order_by("last_name"),
+            None => " ORDER BY last_name, first_name".to_string(),
+        };
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, username, email, password_hash, role, first_name, last_name, phone,
+             created_at, updated_at, is_active
+             FROM users {} {} LIMIT {} OFFSET {}",
+            where_clause, order_by, limit, offset
+        ))?;
+
+        let user_iter = stmt.query_map(paging.sql_params(&[&role]).as_slice(), |row| self.row_to_user(row))?;
+
+        let mut users = Vec::new();
+        for user in user_iter {
+            users.push(user?);
+        }
 
         let total_count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM users WHERE role = ?1 AND is_active = 1",
-            params![role.to_string()],
+            &format!("SELECT COUNT(*) FROM users {}", where_clause),
+            paging.sql_params(&[&role]).as_slice(),
             |row| row.get(0),
         )?;
 
         drop(stmt);
         self.database.return_connection(conn);
-        Ok(PaginatedResult::new(users, total_count, filter.page.unwrap_or(1), limit))
+        Ok(PaginatedResult::new(users, total_count, paging.page, limit))
     }
 
     /// Validate password strength according to security requirements
@@ -2093,8 +4750,8 @@ impl UserService {
     pub fn email_exists(&self, email: &str) -> AppResult<bool> {
         let conn = self.database.get_connection()?;
         let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM users WHERE LOWER(email) = LOWER(?1)",
-            params![email],
+            &format!("SELECT COUNT(*) FROM users WHERE {}", user_email_matches(1, 2)),
+            params![self.cipher.blind_index(USER_EMAIL_COLUMN, email), email],
             |row| row.get(0),
         )?;
         self.database.return_connection(conn);
@@ -2128,25 +4785,25 @@ impl UserService {
     /// * `PaginatedResult<User>` with users and pagination metadata
     pub fn get_all_users(&self, filter: QueryFilter) -> AppResult<PaginatedResult<User>> {
         info!("Fetching all users with filter: {:?}", filter);
+        let paging = QuerySpec::USERS.validate(&filter)?;
         let conn = self.database.get_connection()?;
 
-        let offset = ((filter.page.unwrap_or(1) - 1) * filter.limit.unwrap_or(50)).max(0);
-        let limit = filter.limit.unwrap_or(50);
-        let sort_order = filter.sort_order.unwrap_or(SortOrder::Desc);
-        let sort_by = filter.sort_by.unwrap_or("created_at".to_string());
+        let (offset, limit) = (paging.offset, paging.limit);
+
+        let where_clause = format!("WHERE deleted_at IS NULL{}", paging.filter_condition(1));
 
         // Build the ORDER BY clause
-        let order_by = format!(" ORDER BY {} {}", sort_by, sort_order);
+        let order_by = paging.order_by("created_at");
 
         let query = format!(
             "SELECT id, username, email, password_hash, role, first_name, last_name, phone,
              created_at, updated_at, is_active
-             FROM users {} LIMIT {} OFFSET {}",
-            order_by, limit, offset
+             FROM users {} {} LIMIT {} OFFSET {}",
+            where_clause, order_by, limit, offset
         );
 
         let mut stmt = conn.prepare(&query)?;
-        let user_iter = stmt.query_map([], |row| self.row_to_user(row))?;
+        let user_iter = stmt.query_map(paging.sql_params(&[]).as_slice(), |row| self.row_to_user(row))?;
 
         let mut users = Vec::new();
         for user in user_iter {
@@ -2155,14 +4812,14 @@ impl UserService {
 
         // Get total count
         let total_count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM users",
-            [],
+            &format!("SELECT COUNT(*) FROM users {}", where_clause),
+            paging.sql_params(&[]).as_slice(),
             |row| row.get(0),
         )?;
 
         drop(stmt);
         self.database.return_connection(conn);
-        Ok(PaginatedResult::new(users, total_count, filter.page.unwrap_or(1), limit))
+        Ok(PaginatedResult::new(users, total_count, paging.page, limit))
     }
 
     /// Search users by various criteria
@@ -2175,12 +4832,10 @@ impl UserService {
     /// * `PaginatedResult<User>` with matching users and pagination metadata
     pub fn search_users(&self, criteria: UserSearchCriteria, filter: QueryFilter) -> AppResult<PaginatedResult<User>> {
         info!("Searching users with criteria: {:?}", criteria);
+        let paging = QuerySpec::USERS.validate(&filter)?;
         let conn = self.database.get_connection()?;
 
-        let offset = ((filter.page.unwrap_or(1) - 1) * filter.limit.unwrap_or(50)).max(0);
-        let limit = filter.limit.unwrap_or(50);
-        let sort_order = filter.sort_order.unwrap_or(SortOrder::Desc);
-        let sort_by = filter.sort_by.unwrap_or("created_at".to_string());
+        let (offset, limit) = (paging.offset, paging.limit);
 
         // Build WHERE conditions
         let mut where_conditions = Vec::new();
@@ -2194,8 +4849,9 @@ impl UserService {
             owned_params.push(format!("%{}%", username));
         }
         if let Some(ref email) = criteria.email {
-            where_conditions.push("email LIKE ?");
-            owned_params.push(format!("%{}%", email));
+            // Encrypted addresses only match in full, through the blind index
+            where_conditions.push("email_index = ?");
+            owned_params.push(self.cipher.blind_index(USER_EMAIL_COLUMN, email));
         }
         if let Some(ref first_name) = criteria.first_name {
             where_conditions.push("first_name LIKE ?");
@@ -2214,18 +4870,18 @@ impl UserService {
             owned_params.push(is_active.to_string());
         }
         
+        where_conditions.push("deleted_at IS NULL");
+        let filter_condition = paging.filter_condition(owned_params.len() + 1);
+        owned_params.extend(paging.filters.values().cloned());
+
         // Now create references to the owned values
         for param in &owned_params {
             params.push(param);
         }
 
-        let where_clause = if where_conditions.is_empty() {
-            String::new()
-        } else {
-            format!(" WHERE {}", where_conditions.join(" AND "))
-        };
+        let where_clause = format!(" WHERE {}{}", where_conditions.join(" AND "), filter_condition);
 
-        let order_by = format!(" ORDER BY {} {}", sort_by, sort_order);
+        let order_by = paging.order_by("created_at");
 
         let query = format!(
             "SELECT id, username, email, password_hash, role, first_name, last_name, phone,
@@ -2250,7 +4906,7 @@ impl UserService {
         drop(stmt);
         drop(count_stmt);
         self.database.return_connection(conn);
-        Ok(PaginatedResult::new(users, total_count, filter.page.unwrap_or(1), limit))
+        Ok(PaginatedResult::new(users, total_count, paging.page, limit))
     }
 
     /// Enhanced get_users_by_role with better filtering
@@ -2264,20 +4920,20 @@ impl UserService {
     /// * `PaginatedResult<User>` with users of the specified role
     pub fn get_users_by_role_enhanced(&self, role: UserRole, filter: QueryFilter, include_inactive: bool) -> AppResult<PaginatedResult<User>> {
         info!("Fetching users by role: {} (include_inactive: {})", role, include_inactive);
+        let paging = QuerySpec::USERS.validate(&filter)?;
         let conn = self.database.get_connection()?;
 
-        let offset = ((filter.page.unwrap_or(1) - 1) * filter.limit.unwrap_or(50)).max(0);
-        let limit = filter.limit.unwrap_or(50);
-        let sort_order = filter.sort_order.unwrap_or(SortOrder::Desc);
-        let sort_by = filter.sort_by.unwrap_or("last_name".to_string());
+        let (offset, limit) = (paging.offset, paging.limit);
 
         let where_clause = if include_inactive {
-            "WHERE role = ?"
+            "WHERE role = ?1 AND deleted_at IS NULL"
         } else {
-            "WHERE role = ? AND is_active = 1"
+            "WHERE role = ?1 AND is_active = 1 AND deleted_at IS NULL"
         };
+        let where_clause = format!("{}{}", where_clause, paging.filter_condition(2));
+        let role = role.to_string();
 
-        let order_by = format!(" ORDER BY {} {}", sort_by, sort_order);
+        let order_by = paging.order_by("last_name");
 
         let query = format!(
             "SELECT id, username, email, password_hash, role, first_name, last_name, phone,
@@ -2287,7 +4943,7 @@ impl UserService {
         );
 
         let mut stmt = conn.prepare(&query)?;
-        let user_iter = stmt.query_map([role.to_string()], |row| self.row_to_user(row))?;
+        let user_iter = stmt.query_map(paging.sql_params(&[&role]).as_slice(), |row| self.row_to_user(row))?;
 
         let mut users = Vec::new();
         for user in user_iter {
@@ -2296,117 +4952,10287 @@ impl UserService {
 
         let total_count: i64 = conn.query_row(
             &format!("SELECT COUNT(*) FROM users {}", where_clause),
-            [role.to_string()],
+            paging.sql_params(&[&role]).as_slice(),
             |row| row.get(0),
         )?;
 
         drop(stmt);
         self.database.return_connection(conn);
-        Ok(PaginatedResult::new(users, total_count, filter.page.unwrap_or(1), limit))
+        Ok(PaginatedResult::new(users, total_count, paging.page, limit))
     }
 
     // =============================================================================
     // Security Enhancement Methods (Placeholder implementations for future use)
     // =============================================================================
 
-    /// Initialize rate limiting tracking for a user (placeholder)
-    ///
-    /// Note: This is a placeholder implementation for future rate limiting functionality
-    pub fn init_rate_limiting(&self, user_id: i64) -> AppResult<()> {
-        debug!("Initializing rate limiting for user: {} (placeholder)", user_id);
-        // TODO: Implement actual rate limiting logic with Redis or in-memory store
-        Ok(())
+    /// Count a login attempt for `username` from `source`, refusing it when
+    /// either has made too many attempts recently. The first refusal of a
+    /// run is recorded in `rate_limit_violations`.
+    pub fn check_login_rate_limit(&self, username: &str, source: &str) -> AppResult<()> {
+        let now = Utc::now();
+        let username = username.trim().to_lowercase();
+        let checks = [
+            ("source", source.trim(), rate_limit::LOGIN_PER_SOURCE),
+            ("username", username.as_str(), rate_limit::LOGIN_PER_USERNAME),
+        ];
+        for (scope, subject, limit) in checks {
+            let Err(refusal) = self.rate_limiter.attempt(&format!("login:{}:{}", scope, subject), limit, now) else {
+                continue;
+            };
+            if refusal.new_violation {
+                warn!("Login rate limit exceeded for {} {} ({} attempts)", scope, subject, refusal.attempts);
+                self.record_rate_limit_violation("login", scope, subject, limit, &refusal)?;
+            }
+            return Err(AppError::RateLimited {
+                action: "login".to_string(),
+                retry_after: refusal.retry_after,
+            });
+        }
+        Ok(())
+    }
+
+    fn record_rate_limit_violation(
+        &self,
+        action: &str,
+        scope: &str,
+        subject: &str,
+        limit: RateLimit,
+        refusal: &Refusal,
+    ) -> AppResult<()> {
+        self.database.with_transaction(|conn| {
+            conn.execute(
+                "INSERT INTO rate_limit_violations (action, scope, subject, attempts, window_seconds, retry_after_seconds)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![action, scope, subject, refusal.attempts as i64, limit.window_seconds, refusal.retry_after as i64],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Log user activity for audit purposes (placeholder)
+    ///
+    /// Note: This is a placeholder implementation for future audit functionality
+    pub fn log_user_activity(&self, user_id: i64, activity: &str, metadata: Option<&str>) -> AppResult<()> {
+        info!("User activity - ID: {}, Action: {}, Metadata: {:?} (placeholder)",
+              user_id, activity, metadata);
+        // TODO: Implement actual activity logging to database or external service
+        Ok(())
+    }
+
+    /// Get user activity history (placeholder)
+    ///
+    /// Note: This is a placeholder implementation for future audit functionality
+    pub fn get_user_activity_history(&self, user_id: i64, _filter: QueryFilter) -> AppResult<Vec<String>> {
+        debug!("Getting activity history for user: {} (placeholder)", user_id);
+        // TODO: Implement actual activity history retrieval
+        Ok(vec!["Activity logging not yet implemented".to_string()])
+    }
+
+    /// Wrong passwords since the user's last sign-in and whether the account
+    /// is locked. A lock that has run out is reported as unlocked.
+    pub fn get_account_lockout_info(&self, user_id: i64) -> AppResult<AccountLockoutInfo> {
+        debug!("Getting account lockout info for user: {}", user_id);
+        let conn = self.database.get_connection()?;
+        let result = account_lockout(&conn, user_id, Utc::now());
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Unlock an account before its lock runs out and clear its count of
+    /// wrong passwords
+    pub fn unlock_user_account(&self, context: &RequestContext, user_id: i64) -> AppResult<AccountLockoutInfo> {
+        info!("[{}] Unlocking user account: {}", context.request_id, user_id);
+        self.database.with_transaction(|conn| {
+            let exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM users WHERE id = ?1 AND deleted_at IS NULL)",
+                params![user_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Err(AppError::RecordNotFound {
+                    entity: "User".to_string(),
+                    field: "id".to_string(),
+                    value: user_id.to_string(),
+                });
+            }
+            conn.execute("DELETE FROM login_attempts WHERE user_id = ?1", params![user_id])?;
+            account_lockout(conn, user_id, Utc::now())
+        })
+    }
+
+    fn row_to_user(&self, row: &Row) -> rusqlite::Result<User> {
+        let decrypt = |index: usize, column: &str, stored: Option<String>| {
+            self.cipher.decrypt_opt(column, stored.as_deref()).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+            })
+        };
+        Ok(User {
+            id: row.get(0)?,
+            username: row.get(1)?,
+            email: decrypt(2, USER_EMAIL_COLUMN, row.get(2)?)?.unwrap_or_default(),
+            password_hash: row.get(3)?,
+            role: row.get::<_, String>(4)?.parse().unwrap_or(UserRole::Inspector),
+            first_name: row.get(5)?,
+            last_name: row.get(6)?,
+            phone: decrypt(7, USER_PHONE_COLUMN, row.get(7)?)?,
+            created_at: row.get(8)?,
+            updated_at: row.get(9)?,
+            is_active: row.get(10)?,
+        })
+    }
+}
+
+// =============================================================================
+// MFA Service
+// =============================================================================
+
+/// SHA-256 of a backup code, salted with the user so equal codes of two
+/// users never share a hash
+fn backup_code_hash(user_id: i64, code: &str) -> String {
+    format!("{:x}", Sha256::digest(format!("{}:{}", user_id, totp::normalize_backup_code(code)).as_bytes()))
+}
+
+/// TOTP second factors, backup codes and which roles must use them
+pub struct MfaService {
+    database: Arc<Database>,
+}
+
+impl MfaService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Whether users of `role` must sign in with a second factor
+    pub fn is_required_for_role(&self, role: &UserRole) -> AppResult<bool> {
+        let conn = self.database.get_connection()?;
+        let required = conn.query_row(
+            "SELECT required FROM mfa_policies WHERE role = ?1",
+            params![role.to_string()],
+            |row| row.get::<_, bool>(0),
+        ).optional();
+        self.database.return_connection(conn);
+        Ok(required?.unwrap_or(false))
+    }
+
+    pub fn get_policies(&self) -> AppResult<Vec<MfaPolicy>> {
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<MfaPolicy>> {
+            let mut stmt = conn.prepare("SELECT role, required FROM mfa_policies")?;
+            let mut policies = stmt
+                .query_map([], |row| Ok(MfaPolicy {
+                    role: row.get::<_, String>(0)?.parse().unwrap_or(UserRole::Inspector),
+                    required: row.get(1)?,
+                }))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            policies.sort_by_key(|policy| match policy.role {
+                UserRole::Inspector => 0,
+                UserRole::Supervisor => 1,
+                UserRole::Administrator => 2,
+                UserRole::SuperAdmin => 3,
+            });
+            Ok(policies)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Require or stop requiring a second factor for a role. Users of the
+    /// role who have not enrolled are asked to at their next sign-in.
+    pub fn set_policy(&self, context: &RequestContext, role: UserRole, required: bool) -> AppResult<MfaPolicy> {
+        info!("[{}] Setting MFA requirement for {} to {}", context.request_id, role, required);
+        let updated_by = context.current_user().map(|u| u.user_id).ok();
+        self.database.with_transaction(|conn| {
+            conn.execute(
+                "INSERT INTO mfa_policies (role, required, updated_by, updated_at) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(role) DO UPDATE SET required = ?2, updated_by = ?3, updated_at = ?4",
+                params![role.to_string(), required, updated_by, Utc::now()],
+            )?;
+            Ok(())
+        })?;
+        Ok(MfaPolicy { role, required })
+    }
+
+    pub fn get_status(&self, user: &User) -> AppResult<MfaStatus> {
+        let required = self.is_required_for_role(&user.role)?;
+        let conn = self.database.get_connection()?;
+        let result = (|| -> AppResult<MfaStatus> {
+            let enabled: Option<bool> = conn.query_row(
+                "SELECT enabled_at IS NOT NULL FROM user_mfa WHERE user_id = ?1",
+                params![user.id],
+                |row| row.get(0),
+            ).optional()?;
+            let backup_codes_remaining: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM mfa_backup_codes WHERE user_id = ?1 AND used_at IS NULL",
+                params![user.id],
+                |row| row.get(0),
+            )?;
+            Ok(MfaStatus {
+                user_id: user.id,
+                enabled: enabled == Some(true),
+                pending_enrollment: enabled == Some(false),
+                required,
+                backup_codes_remaining,
+            })
+        })();
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Whether signing in as `user` needs a second factor: always once one
+    /// is enrolled, and for roles that require one so the user enrolls
+    /// before their first session starts
+    pub fn is_needed_at_login(&self, user: &User) -> AppResult<bool> {
+        let status = self.get_status(user)?;
+        Ok(status.enabled || status.required)
+    }
+
+    /// Issue a new secret and backup codes. The enrollment takes effect once
+    /// a code from the authenticator app confirms it. An enabled second
+    /// factor has to be reset before enrolling again.
+    pub fn enroll(&self, user: &User) -> AppResult<MfaEnrollment> {
+        info!("Enrolling MFA for user {}", user.username);
+        let secret = totp::generate_secret()?;
+        let backup_codes = totp::generate_backup_codes()?;
+
+        self.database.with_transaction(|conn| {
+            let enabled: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM user_mfa WHERE user_id = ?1 AND enabled_at IS NOT NULL)",
+                params![user.id],
+                |row| row.get(0),
+            )?;
+            if enabled {
+                return Err(AppError::validation(
+                    "mfa",
+                    "Multi-factor authentication is already enabled; reset it before enrolling again",
+                ));
+            }
+            conn.execute(
+                "INSERT INTO user_mfa (user_id, secret) VALUES (?1, ?2)
+                 ON CONFLICT(user_id) DO UPDATE SET secret = ?2, enabled_at = NULL, last_used_step = NULL,
+                 created_at = CURRENT_TIMESTAMP",
+                params![user.id, secret],
+            )?;
+            conn.execute("DELETE FROM mfa_backup_codes WHERE user_id = ?1", params![user.id])?;
+            for code in &backup_codes {
+                conn.execute(
+                    "INSERT INTO mfa_backup_codes (user_id, code_hash) VALUES (?1, ?2)",
+                    params![user.id, backup_code_hash(user.id, code)],
+                )?;
+            }
+            Ok(())
+        })?;
+
+        Ok(MfaEnrollment {
+            otpauth_uri: totp::provisioning_uri(MFA_ISSUER, &user.username, &secret),
+            secret,
+            backup_codes,
+        })
+    }
+
+    /// Check a code from the authenticator app, or an unused backup code
+    /// once enrollment is confirmed. A valid app code confirms a pending
+    /// enrollment. Each code works only once.
+    pub fn verify(&self, user_id: i64, code: &str) -> AppResult<bool> {
+        let now = Utc::now();
+        self.database.with_transaction(|conn| {
+            let enrollment: Option<(String, bool, Option<i64>)> = conn.query_row(
+                "SELECT secret, enabled_at IS NOT NULL, last_used_step FROM user_mfa WHERE user_id = ?1",
+                params![user_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            ).optional()?;
+            let Some((secret, enabled, last_used_step)) = enrollment else {
+                return Ok(false);
+            };
+
+            if let Some(step) = totp::verify_code(&secret, code, now.timestamp(), last_used_step)? {
+                conn.execute(
+                    "UPDATE user_mfa SET last_used_step = ?2, enabled_at = COALESCE(enabled_at, ?3) WHERE user_id = ?1",
+                    params![user_id, step, now],
+                )?;
+                if !enabled {
+                    info!("MFA enrollment confirmed for user {}", user_id);
+                }
+                return Ok(true);
+            }
+
+            if enabled {
+                let used = conn.execute(
+                    "UPDATE mfa_backup_codes SET used_at = ?3
+                     WHERE user_id = ?1 AND code_hash = ?2 AND used_at IS NULL",
+                    params![user_id, backup_code_hash(user_id, code), now],
+                )?;
+                if used == 1 {
+                    warn!("Backup code used by user {}", user_id);
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        })
+    }
+
+    /// Remove a user's second factor and backup codes, e.g. after a lost
+    /// phone. Users whose role requires one enroll again at next sign-in.
+    pub fn reset(&self, context: &RequestContext, user_id: i64) -> AppResult<()> {
+        info!("[{}] Resetting MFA for user {}", context.request_id, user_id);
+        self.database.with_transaction(|conn| {
+            conn.execute("DELETE FROM mfa_backup_codes WHERE user_id = ?1", params![user_id])?;
+            conn.execute("DELETE FROM user_mfa WHERE user_id = ?1", params![user_id])?;
+            Ok(())
+        })
+    }
+}
+
+// =============================================================================
+// Kiosk Service
+// =============================================================================
+
+const KIOSK_TERMINAL_COLUMNS: &str =
+    "id, name, location_id, idle_timeout_minutes, is_active, created_by, created_at, last_seen_at";
+
+fn row_to_kiosk_terminal(row: &Row) -> rusqlite::Result<KioskTerminal> {
+    Ok(KioskTerminal {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        location_id: row.get(2)?,
+        idle_timeout_minutes: row.get(3)?,
+        is_active: row.get(4)?,
+        created_by: row.get(5)?,
+        created_at: row.get(6)?,
+        last_seen_at: row.get(7)?,
+    })
+}
+
+/// SHA-256 of a terminal key, device key or badge value; none is stored as given
+fn kiosk_secret_hash(value: &str) -> String {
+    format!("{:x}", Sha256::digest(value.trim().as_bytes()))
+}
+
+/// Name a badge or terminal key is rate limited and logged under, without
+/// revealing the value itself
+pub fn kiosk_secret_label(kind: &str, value: &str) -> String {
+    format!("{}:{}", kind, &kiosk_secret_hash(value)[..12])
+}
+
+/// Shared shop-floor terminals and the badges and PINs used to sign in to them
+pub struct KioskService {
+    database: Arc<Database>,
+}
+
+impl KioskService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Register a terminal. The returned key is configured on the terminal
+    /// and cannot be retrieved again.
+    pub fn register_terminal(&self, context: &RequestContext, input: KioskTerminalInput) -> AppResult<RegisteredKioskTerminal> {
+        let user_id = context.current_user()?.user_id;
+        info!("[{}] Registering kiosk terminal '{}'", context.request_id, input.name);
+        input.validate()?;
+
+        let terminal_key = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+        let terminal = self.database.with_transaction(|conn| {
+            if let Some(location_id) = input.location_id {
+                let location_exists: bool = conn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM locations WHERE id = ?1)",
+                    params![location_id],
+                    |row| row.get(0),
+                )?;
+                if !location_exists {
+                    return Err(AppError::RecordNotFound {
+                        entity: "Location".to_string(),
+                        field: "id".to_string(),
+                        value: location_id.to_string(),
+                    });
+                }
+            }
+            Ok(conn.query_row(
+                &format!(
+                    "INSERT INTO kiosk_terminals (name, location_id, key_hash, idle_timeout_minutes, created_by)
+                     VALUES (?1, ?2, ?3, ?4, ?5) RETURNING {}",
+                    KIOSK_TERMINAL_COLUMNS
+                ),
+                params![
+                    input.name.trim(),
+                    input.location_id,
+                    kiosk_secret_hash(&terminal_key),
+                    input.idle_timeout_minutes.unwrap_or(DEFAULT_KIOSK_IDLE_MINUTES),
+                    user_id,
+                ],
+                row_to_kiosk_terminal,
+            )?)
+        })?;
+
+        Ok(RegisteredKioskTerminal { terminal, terminal_key })
+    }
+
+    pub fn get_terminals(&self) -> AppResult<Vec<KioskTerminal>> {
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<KioskTerminal>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM kiosk_terminals ORDER BY is_active DESC, name",
+                KIOSK_TERMINAL_COLUMNS
+            ))?;
+            let terminals = stmt
+                .query_map([], row_to_kiosk_terminal)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(terminals)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Stop a terminal from starting new sessions, e.g. when it is lost or
+    /// retired. Sessions already open run until they time out.
+    pub fn deactivate_terminal(&self, context: &RequestContext, terminal_id: i64) -> AppResult<KioskTerminal> {
+        info!("[{}] Deactivating kiosk terminal {}", context.request_id, terminal_id);
+        self.database.with_transaction(|conn| {
+            conn.query_row(
+                &format!(
+                    "UPDATE kiosk_terminals SET is_active = 0 WHERE id = ?1 RETURNING {}",
+                    KIOSK_TERMINAL_COLUMNS
+                ),
+                params![terminal_id],
+                row_to_kiosk_terminal,
+            ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+                entity: "KioskTerminal".to_string(),
+                field: "id".to_string(),
+                value: terminal_id.to_string(),
+            })
+        })
+    }
+
+    /// Set the badge and PIN a user signs in to kiosks with, replacing any
+    /// set before
+    pub fn set_credentials(&self, context: &RequestContext, user_id: i64, input: KioskCredentialInput) -> AppResult<()> {
+        info!("[{}] Setting kiosk credentials for user {}", context.request_id, user_id);
+        input.validate()?;
+
+        let pin_hash = bcrypt::hash(&input.pin, bcrypt::DEFAULT_COST)?;
+        self.database.with_transaction(|conn| {
+            let user_exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM users WHERE id = ?1 AND deleted_at IS NULL)",
+                params![user_id],
+                |row| row.get(0),
+            )?;
+            if !user_exists {
+                return Err(AppError::RecordNotFound {
+                    entity: "User".to_string(),
+                    field: "id".to_string(),
+                    value: user_id.to_string(),
+                });
+            }
+            let badge_hash = kiosk_secret_hash(&input.badge_id);
+            let taken: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM kiosk_credentials WHERE badge_hash = ?1 AND user_id != ?2)",
+                params![badge_hash, user_id],
+                |row| row.get(0),
+            )?;
+            if taken {
+                return Err(AppError::validation("badge_id", "This badge is assigned to another user"));
+            }
+            conn.execute(
+                "INSERT INTO kiosk_credentials (user_id, badge_hash, pin_hash) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(user_id) DO UPDATE SET
+                    badge_hash = excluded.badge_hash,
+                    pin_hash = excluded.pin_hash,
+                    failed_attempts = 0,
+                    locked_until = NULL,
+                    updated_at = CURRENT_TIMESTAMP",
+                params![user_id, badge_hash, pin_hash],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Remove a user's badge and PIN. Returns whether they had any.
+    pub fn clear_credentials(&self, context: &RequestContext, user_id: i64) -> AppResult<bool> {
+        info!("[{}] Clearing kiosk credentials for user {}", context.request_id, user_id);
+        self.database.with_transaction(|conn| {
+            Ok(conn.execute("DELETE FROM kiosk_credentials WHERE user_id = ?1", params![user_id])? > 0)
+        })
+    }
+
+    /// The active terminal a key belongs to, recording that it was seen
+    pub fn terminal_for_key(&self, terminal_key: &str) -> AppResult<KioskTerminal> {
+        self.database.with_transaction(|conn| {
+            conn.query_row(
+                &format!(
+                    "UPDATE kiosk_terminals SET last_seen_at = CURRENT_TIMESTAMP
+                     WHERE key_hash = ?1 AND is_active = 1 RETURNING {}",
+                    KIOSK_TERMINAL_COLUMNS
+                ),
+                params![kiosk_secret_hash(terminal_key)],
+                row_to_kiosk_terminal,
+            ).optional()?.ok_or_else(|| AppError::authentication("Unknown or inactive kiosk terminal"))
+        })
+    }
+
+    /// ID of the user a badge is issued to, whatever the PIN, so a failed
+    /// sign-in can be added to their login history
+    pub fn badge_owner(&self, badge_id: &str) -> AppResult<Option<i64>> {
+        let conn = self.database.get_connection()?;
+        let owner = conn.query_row(
+            "SELECT user_id FROM kiosk_credentials WHERE badge_hash = ?1",
+            params![kiosk_secret_hash(badge_id)],
+            |row| row.get(0),
+        ).optional();
+        self.database.return_connection(conn);
+        Ok(owner?)
+    }
+
+    /// ID of the user a badge belongs to, if the PIN matches.
+    ///
+    /// Wrong PINs are counted per badge: every `KIOSK_BADGE_ATTEMPTS` in a
+    /// row lock it for `KIOSK_LOCKOUT_MINUTES`, and a correct PIN resets the
+    /// count.
+    pub fn user_for_badge(&self, badge_id: &str, pin: &str) -> AppResult<i64> {
+        let now = Utc::now();
+        let outcome = self.database.with_transaction(|conn| {
+            let stored = conn.query_row(
+                "SELECT user_id, pin_hash, failed_attempts, locked_until FROM kiosk_credentials WHERE badge_hash = ?1",
+                params![kiosk_secret_hash(badge_id)],
+                |row| Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, Option<DateTime<Utc>>>(3)?,
+                )),
+            ).optional()?;
+            let Some((user_id, pin_hash, failed_attempts, locked_until)) = stored else {
+                return Ok(Err(AppError::authentication("Invalid badge or PIN")));
+            };
+            if let Some(locked_until) = locked_until.filter(|until| *until > now) {
+                return Ok(Err(AppError::authentication(format!(
+                    "Too many wrong PINs for this badge; try again after {} UTC",
+                    locked_until.format("%H:%M")
+                ))));
+            }
+
+            if bcrypt::verify(pin, &pin_hash).unwrap_or(false) {
+                conn.execute(
+                    "UPDATE kiosk_credentials SET failed_attempts = 0, locked_until = NULL WHERE user_id = ?1",
+                    params![user_id],
+                )?;
+                return Ok(Ok(user_id));
+            }
+
+            let failed_attempts = failed_attempts + 1;
+            let locked_until = (failed_attempts % KIOSK_BADGE_ATTEMPTS == 0)
+                .then(|| now + chrono::Duration::minutes(KIOSK_LOCKOUT_MINUTES));
+            conn.execute(
+                "UPDATE kiosk_credentials SET failed_attempts = ?2, locked_until = COALESCE(?3, locked_until)
+                 WHERE user_id = ?1",
+                params![user_id, failed_attempts, locked_until],
+            )?;
+            if locked_until.is_some() {
+                warn!("Kiosk badge of user {} locked after {} wrong PINs", user_id, failed_attempts);
+            }
+            Ok(Err(AppError::authentication("Invalid badge or PIN")))
+        })?;
+        outcome
+    }
+}
+
+// =============================================================================
+// Trusted Device Service
+// =============================================================================
+
+const TRUSTED_DEVICE_COLUMNS: &str =
+    "id, user_id, name, failed_attempts, locked_until, expires_at, last_used_at, created_at, revoked_at";
+
+fn row_to_trusted_device(row: &Row) -> rusqlite::Result<TrustedDevice> {
+    Ok(TrustedDevice {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        name: row.get(2)?,
+        failed_attempts: row.get(3)?,
+        locked_until: row.get(4)?,
+        expires_at: row.get(5)?,
+        last_used_at: row.get(6)?,
+        created_at: row.get(7)?,
+        revoked_at: row.get(8)?,
+    })
+}
+
+/// Devices a user signed in on with their password and can unlock later
+/// with a PIN or a badge scan
+pub struct TrustedDeviceService {
+    database: Arc<Database>,
+}
+
+impl TrustedDeviceService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Trust the device the signed-in user is on. The returned key is kept
+    /// on the device and cannot be retrieved again.
+    pub fn register(&self, context: &RequestContext, input: TrustedDeviceInput) -> AppResult<RegisteredTrustedDevice> {
+        let user_id = context.current_user()?.user_id;
+        info!("[{}] Trusting device '{}' for user {}", context.request_id, input.name, user_id);
+        input.validate()?;
+
+        let device_key = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+        let pin_hash = bcrypt::hash(&input.pin, bcrypt::DEFAULT_COST)?;
+        let expires_at = Utc::now() + chrono::Duration::days(TRUSTED_DEVICE_DAYS);
+        let device = self.database.with_transaction(|conn| {
+            Ok(conn.query_row(
+                &format!(
+                    "INSERT INTO trusted_devices (user_id, name, device_key_hash, pin_hash, expires_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5) RETURNING {}",
+                    TRUSTED_DEVICE_COLUMNS
+                ),
+                params![user_id, input.name.trim(), kiosk_secret_hash(&device_key), pin_hash, expires_at],
+                row_to_trusted_device,
+            )?)
+        })?;
+
+        Ok(RegisteredTrustedDevice { device, device_key })
+    }
+
+    pub fn get_device(&self, device_id: i64) -> AppResult<TrustedDevice> {
+        let conn = self.database.get_connection()?;
+        let device = conn.query_row(
+            &format!("SELECT {} FROM trusted_devices WHERE id = ?1", TRUSTED_DEVICE_COLUMNS),
+            params![device_id],
+            row_to_trusted_device,
+        ).optional();
+        self.database.return_connection(conn);
+
+        device?.ok_or_else(|| AppError::RecordNotFound {
+            entity: "TrustedDevice".to_string(),
+            field: "id".to_string(),
+            value: device_id.to_string(),
+        })
+    }
+
+    /// ID of the user a device key was issued to, so a failed unlock can be
+    /// added to their login history
+    pub fn device_owner(&self, device_key: &str) -> AppResult<Option<i64>> {
+        let conn = self.database.get_connection()?;
+        let owner = conn.query_row(
+            "SELECT user_id FROM trusted_devices WHERE device_key_hash = ?1",
+            params![kiosk_secret_hash(device_key)],
+            |row| row.get(0),
+        ).optional();
+        self.database.return_connection(conn);
+        Ok(owner?)
+    }
+
+    /// A user's devices that are still trusted, most recently used first
+    pub fn get_devices(&self, user_id: i64) -> AppResult<Vec<TrustedDevice>> {
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<TrustedDevice>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM trusted_devices
+                 WHERE user_id = ?1 AND revoked_at IS NULL AND expires_at > ?2
+                 ORDER BY COALESCE(last_used_at, created_at) DESC",
+                TRUSTED_DEVICE_COLUMNS
+            ))?;
+            let devices = stmt
+                .query_map(params![user_id, Utc::now()], row_to_trusted_device)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(devices)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Stop trusting a device, e.g. when it is lost
+    pub fn revoke(&self, context: &RequestContext, device_id: i64) -> AppResult<TrustedDevice> {
+        info!("[{}] Revoking trusted device {}", context.request_id, device_id);
+        self.database.with_transaction(|conn| {
+            conn.query_row(
+                &format!(
+                    "UPDATE trusted_devices SET revoked_at = COALESCE(revoked_at, CURRENT_TIMESTAMP)
+                     WHERE id = ?1 RETURNING {}",
+                    TRUSTED_DEVICE_COLUMNS
+                ),
+                params![device_id],
+                row_to_trusted_device,
+            ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+                entity: "TrustedDevice".to_string(),
+                field: "id".to_string(),
+                value: device_id.to_string(),
+            })
+        })
+    }
+
+    /// Check a PIN or badge against a trusted device, returning the ID of
+    /// the user it belongs to.
+    ///
+    /// Wrong attempts are counted per device: every `DEVICE_UNLOCK_ATTEMPTS`
+    /// in a row lock it for `DEVICE_LOCKOUT_MINUTES`, and after
+    /// `DEVICE_REVOKE_ATTEMPTS` it is no longer trusted at all.
+    pub fn unlock(&self, device_key: &str, unlock: &DeviceUnlock) -> AppResult<i64> {
+        let now = Utc::now();
+        let outcome = self.database.with_transaction(|conn| {
+            let stored = conn.query_row(
+                "SELECT d.id, d.user_id, d.pin_hash, d.failed_attempts, d.locked_until, d.expires_at, d.revoked_at,
+                        k.badge_hash
+                 FROM trusted_devices d LEFT JOIN kiosk_credentials k ON k.user_id = d.user_id
+                 WHERE d.device_key_hash = ?1",
+                params![kiosk_secret_hash(device_key)],
+                |row| Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, Option<DateTime<Utc>>>(4)?,
+                    row.get::<_, DateTime<Utc>>(5)?,
+                    row.get::<_, Option<DateTime<Utc>>>(6)?,
+                    row.get::<_, Option<String>>(7)?,
+                )),
+            ).optional()?;
+            let Some((device_id, user_id, pin_hash, failed_attempts, locked_until, expires_at, revoked_at, badge_hash)) = stored else {
+                return Ok(Err(AppError::authentication("Unknown device")));
+            };
+            if revoked_at.is_some() || expires_at <= now {
+                return Ok(Err(AppError::authentication("This device is no longer trusted; sign in with your password")));
+            }
+            if let Some(locked_until) = locked_until.filter(|until| *until > now) {
+                return Ok(Err(AppError::authentication(format!(
+                    "Too many wrong attempts; try again after {} UTC",
+                    locked_until.format("%H:%M")
+                ))));
+            }
+
+            let valid = match unlock {
+                DeviceUnlock::Pin(pin) => bcrypt::verify(pin, &pin_hash).unwrap_or(false),
+                DeviceUnlock::Badge(badge_id) => badge_hash.is_some_and(|hash| hash == kiosk_secret_hash(badge_id)),
+            };
+            if valid {
+                conn.execute(
+                    "UPDATE trusted_devices SET failed_attempts = 0, locked_until = NULL, last_used_at = ?2 WHERE id = ?1",
+                    params![device_id, now],
+                )?;
+                return Ok(Ok(user_id));
+            }
+
+            let failed_attempts = failed_attempts + 1;
+            let revoked_at = (failed_attempts >= DEVICE_REVOKE_ATTEMPTS).then_some(now);
+            let locked_until = (failed_attempts % DEVICE_UNLOCK_ATTEMPTS == 0)
+                .then(|| now + chrono::Duration::minutes(DEVICE_LOCKOUT_MINUTES));
+            conn.execute(
+                "UPDATE trusted_devices SET failed_attempts = ?2, locked_until = COALESCE(?3, locked_until),
+                        revoked_at = COALESCE(?4, revoked_at)
+                 WHERE id = ?1",
+                params![device_id, failed_attempts, locked_until, revoked_at],
+            )?;
+            if revoked_at.is_some() {
+                warn!("Trusted device {} of user {} revoked after {} wrong attempts", device_id, user_id, failed_attempts);
+            } else if locked_until.is_some() {
+                warn!("Trusted device {} of user {} locked after {} wrong attempts", device_id, user_id, failed_attempts);
+            }
+            Ok(Err(AppError::authentication("Invalid PIN or badge")))
+        })?;
+        outcome
+    }
+}
+
+// =============================================================================
+// Role Service
+// =============================================================================
+
+const ROLE_SELECT: &str =
+    "SELECT r.id, r.name, r.description, r.base_role, r.is_builtin, r.created_at, r.updated_at,
+            (SELECT COUNT(*) FROM users u
+             WHERE u.deleted_at IS NULL
+               AND (u.custom_role_id = r.id OR (r.is_builtin AND u.custom_role_id IS NULL AND u.role = r.base_role)))
+     FROM roles r";
+
+fn row_to_role(row: &Row) -> rusqlite::Result<Role> {
+    Ok(Role {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        base_role: row.get::<_, String>(3)?.parse().unwrap_or(UserRole::Inspector),
+        is_builtin: row.get(4)?,
+        // Filled in by `read_role`
+        permissions: Vec::new(),
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+        user_count: row.get(7)?,
+    })
+}
+
+fn role_permissions(conn: &Connection, role_id: i64) -> AppResult<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT permission FROM role_permissions WHERE role_id = ?1 ORDER BY permission")?;
+    let permissions = stmt
+        .query_map(params![role_id], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(permissions)
+}
+
+fn read_role(conn: &Connection, id: i64) -> AppResult<Role> {
+    let mut role = conn.query_row(&format!("{} WHERE r.id = ?1", ROLE_SELECT), params![id], row_to_role)
+        .optional()?
+        .ok_or_else(|| AppError::RecordNotFound {
+            entity: "Role".to_string(),
+            field: "id".to_string(),
+            value: id.to_string(),
+        })?;
+    role.permissions = role_permissions(conn, id)?;
+    Ok(role)
+}
+
+fn replace_role_permissions(conn: &Connection, role_id: i64, permissions: &[String]) -> AppResult<()> {
+    conn.execute("DELETE FROM role_permissions WHERE role_id = ?1", params![role_id])?;
+    for permission in permissions {
+        conn.execute(
+            "INSERT OR IGNORE INTO role_permissions (role_id, permission) VALUES (?1, ?2)",
+            params![role_id, permission],
+        )?;
+    }
+    Ok(())
+}
+
+/// Permissions a user's role gives them: those of their custom role, or of
+/// their built-in role's row. Before the built-in rows are seeded the
+/// defaults in `Permissions::for_role` apply.
+fn user_permissions(conn: &Connection, user_id: i64, role: &UserRole) -> AppResult<Vec<String>> {
+    let role_id: Option<i64> = conn.query_row(
+        "SELECT COALESCE(u.custom_role_id, (SELECT r.id FROM roles r WHERE r.is_builtin AND r.base_role = u.role))
+         FROM users u WHERE u.id = ?1",
+        params![user_id],
+        |row| row.get(0),
+    ).optional()?.flatten();
+    match role_id {
+        Some(role_id) => role_permissions(conn, role_id),
+        None => Ok(Permissions::for_role(role)),
+    }
+}
+
+/// Refuse to grant permissions the acting user does not hold themselves, so
+/// roles cannot be used to raise anyone above the administrator editing them
+fn ensure_grantable(context: &RequestContext, permissions: &[String]) -> AppResult<()> {
+    let actor = context.current_user()?;
+    match permissions.iter().find(|permission| !Permissions::grants(&actor.permissions, permission)) {
+        Some(permission) => Err(AppError::Authorization {
+            user: actor.username.clone(),
+            action: "grant".to_string(),
+            resource: permission.clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Roles and the permissions they give. Built-in roles can have their
+/// permissions changed but cannot be renamed or deleted; SuperAdmin always
+/// keeps every permission.
+pub struct RoleService {
+    database: Arc<Database>,
+}
+
+impl RoleService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Add the row of each built-in role that has none yet, with its default
+    /// permissions. Rows already present keep any changes made to them.
+    /// Returns how many were added.
+    pub fn seed_builtin_roles(&self) -> AppResult<usize> {
+        self.database.with_transaction(|conn| {
+            let mut seeded = 0;
+            for role in [UserRole::Inspector, UserRole::Supervisor, UserRole::Administrator, UserRole::SuperAdmin] {
+                let id: Option<i64> = conn.query_row(
+                    "INSERT INTO roles (name, base_role, is_builtin) VALUES (?1, ?1, 1)
+                     ON CONFLICT DO NOTHING
+                     RETURNING id",
+                    params![role.to_string()],
+                    |row| row.get(0),
+                ).optional()?;
+                if let Some(id) = id {
+                    replace_role_permissions(conn, id, &Permissions::for_role(&role))?;
+                    seeded += 1;
+                }
+            }
+            if seeded > 0 {
+                info!("Seeded {} built-in roles", seeded);
+            }
+            Ok(seeded)
+        })
+    }
+
+    /// Every role, built-in roles first
+    pub fn get_roles(&self) -> AppResult<Vec<Role>> {
+        let conn = self.database.get_connection()?;
+        let result = (|| -> AppResult<Vec<Role>> {
+            let mut stmt = conn.prepare(&format!("{} ORDER BY r.is_builtin DESC, r.name", ROLE_SELECT))?;
+            let mut roles = stmt.query_map([], row_to_role)?.collect::<rusqlite::Result<Vec<_>>>()?;
+            for role in &mut roles {
+                role.permissions = role_permissions(&conn, role.id)?;
+            }
+            Ok(roles)
+        })();
+        self.database.return_connection(conn);
+        result
+    }
+
+    pub fn get_role(&self, id: i64) -> AppResult<Role> {
+        let conn = self.database.get_connection()?;
+        let result = read_role(&conn, id);
+        self.database.return_connection(conn);
+        result
+    }
+
+    pub fn create_role(&self, context: &RequestContext, input: RoleInput) -> AppResult<Role> {
+        info!("[{}] Creating role: {}", context.request_id, input.name);
+        input.validate()?;
+        if input.base_role == UserRole::SuperAdmin {
+            return Err(AppError::validation("base_role", "Custom roles cannot be based on SuperAdmin"));
+        }
+        ensure_grantable(context, &input.permissions)?;
+        let user_id = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            ensure_role_name_free(conn, input.name.trim(), None)?;
+            let id: i64 = conn.query_row(
+                "INSERT INTO roles (name, description, base_role, created_by) VALUES (?1, ?2, ?3, ?4) RETURNING id",
+                params![input.name.trim(), input.description, input.base_role.to_string(), user_id],
+                |row| row.get(0),
+            )?;
+            replace_role_permissions(conn, id, &input.permissions)?;
+            read_role(conn, id)
+        })
+    }
+
+    /// Replace a role's details and permissions. Users given a custom role
+    /// move with it if its base role changes. Sessions pick up the new
+    /// permissions on their next request.
+    pub fn update_role(&self, context: &RequestContext, id: i64, input: RoleInput) -> AppResult<Role> {
+        info!("[{}] Updating role: {}", context.request_id, id);
+        input.validate()?;
+        ensure_grantable(context, &input.permissions)?;
+
+        self.database.with_transaction(|conn| {
+            let existing = read_role(conn, id)?;
+            if existing.is_builtin {
+                if existing.base_role == UserRole::SuperAdmin {
+                    return Err(AppError::validation("permissions", "SuperAdmin always has every permission"));
+                }
+                if !input.name.trim().eq_ignore_ascii_case(&existing.name) || input.base_role != existing.base_role {
+                    return Err(AppError::validation("name", "Built-in roles cannot be renamed or rebased"));
+                }
+            } else if input.base_role == UserRole::SuperAdmin {
+                return Err(AppError::validation("base_role", "Custom roles cannot be based on SuperAdmin"));
+            }
+            // Taking a permission away must also be within the editor's reach
+            ensure_grantable(context, &existing.permissions)?;
+            ensure_role_name_free(conn, input.name.trim(), Some(id))?;
+
+            conn.execute(
+                "UPDATE roles SET name = ?1, description = ?2, base_role = ?3, updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?4",
+                params![
+                    if existing.is_builtin { existing.name.as_str() } else { input.name.trim() },
+                    input.description, input.base_role.to_string(), id,
+                ],
+            )?;
+            conn.execute(
+                "UPDATE users SET role = ?1, updated_at = CURRENT_TIMESTAMP WHERE custom_role_id = ?2",
+                params![input.base_role.to_string(), id],
+            )?;
+            replace_role_permissions(conn, id, &input.permissions)?;
+            read_role(conn, id)
+        })
+    }
+
+    /// Delete a custom role. Refused for built-in roles and while any user,
+    /// including a deleted one, still has the role.
+    pub fn delete_role(&self, context: &RequestContext, id: i64) -> AppResult<()> {
+        warn!("[{}] Deleting role: {}", context.request_id, id);
+        self.database.with_transaction(|conn| {
+            let role = read_role(conn, id)?;
+            if role.is_builtin {
+                return Err(AppError::validation("id", "Built-in roles cannot be deleted"));
+            }
+            let assigned: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM users WHERE custom_role_id = ?1",
+                params![id],
+                |row| row.get(0),
+            )?;
+            if assigned > 0 {
+                return Err(AppError::validation(
+                    "id",
+                    format!("Role {} is still given to {} users", role.name, assigned),
+                ));
+            }
+            conn.execute("DELETE FROM roles WHERE id = ?1", params![id])?;
+            Ok(())
+        })
+    }
+
+    /// Give a user a role. A custom role also sets the user's built-in role
+    /// to its base role; a built-in role clears any custom role.
+    pub fn assign_role(&self, context: &RequestContext, user_id: i64, role_id: i64) -> AppResult<Role> {
+        info!("[{}] Giving user {} role {}", context.request_id, user_id, role_id);
+        self.database.with_transaction(|conn| {
+            let role = read_role(conn, role_id)?;
+            ensure_grantable(context, &role.permissions)?;
+            let current: Vec<String> = match conn.query_row(
+                "SELECT role FROM users WHERE id = ?1 AND deleted_at IS NULL",
+                params![user_id],
+                |row| row.get::<_, String>(0),
+            ).optional()? {
+                Some(current) => user_permissions(conn, user_id, &current.parse().unwrap_or(UserRole::Inspector))?,
+                None => {
+                    return Err(AppError::RecordNotFound {
+                        entity: "User".to_string(),
+                        field: "id".to_string(),
+                        value: user_id.to_string(),
+                    });
+                }
+            };
+            // Nor can a user be moved off a role the editor could not grant
+            ensure_grantable(context, &current)?;
+
+            conn.execute(
+                "UPDATE users SET role = ?1, custom_role_id = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
+                params![role.base_role.to_string(), (!role.is_builtin).then_some(role.id), user_id],
+            )?;
+            read_role(conn, role_id)
+        })
+    }
+
+    /// Permissions the user's role currently gives them
+    pub fn permissions_for_user(&self, user: &User) -> AppResult<Vec<String>> {
+        let conn = self.database.get_connection()?;
+        let result = user_permissions(&conn, user.id, &user.role);
+        self.database.return_connection(conn);
+        result
+    }
+}
+
+fn ensure_role_name_free(conn: &Connection, name: &str, except_id: Option<i64>) -> AppResult<()> {
+    let taken: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM roles WHERE name = ?1 AND (?2 IS NULL OR id != ?2))",
+        params![name, except_id],
+        |row| row.get(0),
+    )?;
+    if taken {
+        return Err(AppError::DuplicateRecord {
+            entity: "Role".to_string(),
+            field: "name".to_string(),
+            value: name.to_string(),
+        });
+    }
+    Ok(())
+}
+
+// =============================================================================
+// API Key Service
+// =============================================================================
+
+const API_KEY_COLUMNS: &str =
+    "id, name, key_prefix, created_by, created_at, expires_at, last_used_at, revoked_at";
+
+/// Characters of a key kept in the clear, the marker included
+const API_KEY_PREFIX_LENGTH: usize = 12;
+
+fn row_to_api_key(row: &Row) -> rusqlite::Result<ApiKey> {
+    Ok(ApiKey {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        key_prefix: row.get(2)?,
+        // Filled in by `read_api_key`
+        permissions: Vec::new(),
+        created_by: row.get(3)?,
+        created_at: row.get(4)?,
+        expires_at: row.get(5)?,
+        last_used_at: row.get(6)?,
+        revoked_at: row.get(7)?,
+    })
+}
+
+fn api_key_permissions(conn: &Connection, api_key_id: i64) -> AppResult<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT permission FROM api_key_permissions WHERE api_key_id = ?1 ORDER BY permission")?;
+    let permissions = stmt
+        .query_map(params![api_key_id], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(permissions)
+}
+
+fn read_api_key(conn: &Connection, id: i64) -> AppResult<ApiKey> {
+    let mut api_key = conn.query_row(
+        &format!("SELECT {} FROM api_keys WHERE id = ?1", API_KEY_COLUMNS),
+        params![id],
+        row_to_api_key,
+    ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+        entity: "ApiKey".to_string(),
+        field: "id".to_string(),
+        value: id.to_string(),
+    })?;
+    api_key.permissions = api_key_permissions(conn, id)?;
+    Ok(api_key)
+}
+
+/// SHA-256 of an API key; keys themselves are never stored
+fn api_key_hash(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.trim().as_bytes()))
+}
+
+/// Keys external systems use instead of a user login, e.g. through the
+/// planned local HTTP interface. A key can only be given permissions its
+/// creator holds, and is used as its creator.
+pub struct ApiKeyService {
+    database: Arc<Database>,
+}
+
+impl ApiKeyService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Create a key. Its secret is returned here and cannot be read again.
+    pub fn create_api_key(&self, context: &RequestContext, input: ApiKeyInput) -> AppResult<CreatedApiKey> {
+        info!("[{}] Creating API key '{}'", context.request_id, input.name.trim());
+        input.validate()?;
+        ensure_grantable(context, &input.permissions)?;
+        let user_id = context.current_user()?.user_id;
+
+        let secret = format!("{}{}{}", API_KEY_MARKER, uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+        let expires_at = input.expires_in_days.map(|days| Utc::now() + chrono::Duration::days(days));
+
+        let api_key = self.database.with_transaction(|conn| {
+            let id = conn.query_row(
+                "INSERT INTO api_keys (name, key_prefix, key_hash, created_by, expires_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 RETURNING id",
+                params![input.name.trim(), &secret[..API_KEY_PREFIX_LENGTH], api_key_hash(&secret), user_id, expires_at],
+                |row| row.get::<_, i64>(0),
+            )?;
+            for permission in &input.permissions {
+                conn.execute(
+                    "INSERT OR IGNORE INTO api_key_permissions (api_key_id, permission) VALUES (?1, ?2)",
+                    params![id, permission],
+                )?;
+            }
+            read_api_key(conn, id)
+        })?;
+
+        debug!("API key {} created by user {}", api_key.id, user_id);
+        Ok(CreatedApiKey { api_key, secret })
+    }
+
+    /// Every API key, newest first
+    pub fn get_api_keys(&self) -> AppResult<Vec<ApiKey>> {
+        let conn = self.database.get_connection()?;
+        let result = (|| -> AppResult<Vec<ApiKey>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM api_keys ORDER BY created_at DESC, id DESC", API_KEY_COLUMNS
+            ))?;
+            let mut api_keys = stmt.query_map([], row_to_api_key)?.collect::<rusqlite::Result<Vec<_>>>()?;
+            for api_key in &mut api_keys {
+                api_key.permissions = api_key_permissions(&conn, api_key.id)?;
+            }
+            Ok(api_keys)
+        })();
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Stop a key from authenticating. Revoking a revoked key keeps the
+    /// time it was first revoked.
+    pub fn revoke_api_key(&self, context: &RequestContext, id: i64) -> AppResult<ApiKey> {
+        info!("[{}] Revoking API key {}", context.request_id, id);
+        let user_id = context.current_user()?.user_id;
+        self.database.with_transaction(|conn| {
+            read_api_key(conn, id)?;
+            conn.execute(
+                "UPDATE api_keys SET revoked_at = ?2, revoked_by = ?3 WHERE id = ?1 AND revoked_at IS NULL",
+                params![id, Utc::now(), user_id],
+            )?;
+            read_api_key(conn, id)
+        })
+    }
+
+    /// The key with `secret`, recording that it was used.
+    ///
+    /// Unknown, expired and revoked keys are all rejected with the same
+    /// error so a key cannot be probed for its state.
+    pub fn authenticate(&self, secret: &str) -> AppResult<ApiKey> {
+        let now = Utc::now();
+        self.database.with_transaction(|conn| {
+            let id: Option<i64> = conn.query_row(
+                "SELECT id FROM api_keys WHERE key_hash = ?1",
+                params![api_key_hash(secret)],
+                |row| row.get(0),
+            ).optional()?;
+            let api_key = match id {
+                Some(id) => read_api_key(conn, id)?,
+                None => return Err(AppError::authentication("Invalid API key")),
+            };
+            if !api_key.is_active(now) {
+                return Err(AppError::authentication("Invalid API key"));
+            }
+            conn.execute("UPDATE api_keys SET last_used_at = ?2 WHERE id = ?1", params![api_key.id, now])?;
+            Ok(ApiKey { last_used_at: Some(now), ..api_key })
+        })
+    }
+}
+
+// =============================================================================
+// Session Service
+// =============================================================================
+
+const SESSION_SELECT: &str =
+    "SELECT s.session_id, s.user_id, u.username, u.role, s.created_at, s.expires_at, s.last_activity,
+            s.locale, s.kiosk_terminal_id, s.idle_timeout_minutes
+     FROM sessions s JOIN users u ON u.id = s.user_id";
+
+fn row_to_session(row: &Row) -> rusqlite::Result<UserSession> {
+    // Permissions are filled in from the user's role once the row is read
+    Ok(UserSession {
+        user_id: row.get(1)?,
+        username: row.get(2)?,
+        role: row.get::<_, String>(3)?.parse().unwrap_or(UserRole::Inspector),
+        session_id: row.get(0)?,
+        created_at: row.get(4)?,
+        expires_at: row.get(5)?,
+        last_activity: row.get(6)?,
+        permissions: Vec::new(),
+        locale: row.get::<_, String>(7)?.parse().unwrap_or_default(),
+        kiosk_terminal_id: row.get(8)?,
+        idle_timeout_minutes: row.get(9)?,
+        api_key_id: None,
+    })
+}
+
+/// Signed-in sessions. Tokens only name their session, so a session ended
+/// here ends for every token issued for it.
+pub struct SessionService {
+    database: Arc<Database>,
+}
+
+impl SessionService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    pub fn create(&self, session: &UserSession) -> AppResult<()> {
+        self.database.with_transaction(|conn| {
+            conn.execute(
+                "INSERT INTO sessions (session_id, user_id, created_at, expires_at, last_activity, locale,
+                                       kiosk_terminal_id, idle_timeout_minutes)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    session.session_id,
+                    session.user_id,
+                    session.created_at,
+                    session.expires_at,
+                    session.last_activity,
+                    session.locale.to_string(),
+                    session.kiosk_terminal_id,
+                    session.idle_timeout_minutes,
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// A session that has not been revoked, whether or not it has expired.
+    /// Permissions are those of the user's current role.
+    pub fn get(&self, session_id: &str) -> AppResult<Option<UserSession>> {
+        let conn = self.database.get_connection()?;
+        let result = (|| -> AppResult<Option<UserSession>> {
+            let session = conn.query_row(
+                &format!("{} WHERE s.session_id = ?1 AND s.revoked_at IS NULL AND u.deleted_at IS NULL", SESSION_SELECT),
+                params![session_id],
+                row_to_session,
+            ).optional()?;
+            let Some(mut session) = session else {
+                return Ok(None);
+            };
+            let granted = user_permissions(&conn, session.user_id, &session.role)?;
+            session.permissions = match session.kiosk_terminal_id {
+                Some(_) => Permissions::for_kiosk(&granted),
+                None => granted,
+            };
+            Ok(Some(session))
+        })();
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Record activity on a session, moving its expiry to `expires_at` if given
+    pub fn touch(&self, session_id: &str, expires_at: Option<DateTime<Utc>>) -> AppResult<()> {
+        self.database.with_transaction(|conn| {
+            conn.execute(
+                "UPDATE sessions SET last_activity = ?1, expires_at = COALESCE(?2, expires_at)
+                 WHERE session_id = ?3",
+                params![Utc::now(), expires_at, session_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// End a session and revoke its refresh tokens. Returns whether it was
+    /// still active.
+    pub fn revoke(&self, session_id: &str, revoked_by: Option<i64>, reason: &str) -> AppResult<bool> {
+        self.database.with_transaction(|conn| {
+            let now = Utc::now();
+            let rows = conn.execute(
+                "UPDATE sessions SET revoked_at = ?1, revoked_by = ?2, revoke_reason = ?3
+                 WHERE session_id = ?4 AND revoked_at IS NULL",
+                params![now, revoked_by, reason, session_id],
+            )?;
+            conn.execute(
+                "UPDATE refresh_tokens SET revoked_at = ?1 WHERE session_id = ?2 AND revoked_at IS NULL",
+                params![now, session_id],
+            )?;
+            Ok(rows > 0)
+        })
+    }
+
+    /// End every session of a user and revoke their refresh tokens,
+    /// returning how many sessions were active
+    pub fn revoke_for_user(&self, user_id: i64, revoked_by: Option<i64>, reason: &str) -> AppResult<usize> {
+        self.database.with_transaction(|conn| {
+            let now = Utc::now();
+            let rows = conn.execute(
+                "UPDATE sessions SET revoked_at = ?1, revoked_by = ?2, revoke_reason = ?3
+                 WHERE user_id = ?4 AND revoked_at IS NULL",
+                params![now, revoked_by, reason, user_id],
+            )?;
+            conn.execute(
+                "UPDATE refresh_tokens SET revoked_at = ?1 WHERE user_id = ?2 AND revoked_at IS NULL",
+                params![now, user_id],
+            )?;
+            Ok(rows)
+        })
+    }
+
+    /// End every session started on a kiosk terminal
+    pub fn revoke_for_terminal(&self, terminal_id: i64, reason: &str) -> AppResult<usize> {
+        self.database.with_transaction(|conn| {
+            Ok(conn.execute(
+                "UPDATE sessions SET revoked_at = ?1, revoke_reason = ?2
+                 WHERE kiosk_terminal_id = ?3 AND revoked_at IS NULL",
+                params![Utc::now(), reason, terminal_id],
+            )?)
+        })
+    }
+
+    /// Apply a new locale to the active sessions of a user
+    pub fn set_locale(&self, user_id: i64, locale: Locale) -> AppResult<usize> {
+        self.database.with_transaction(|conn| {
+            Ok(conn.execute(
+                "UPDATE sessions SET locale = ?1 WHERE user_id = ?2 AND revoked_at IS NULL",
+                params![locale.to_string(), user_id],
+            )?)
+        })
+    }
+
+    /// Sessions that are neither revoked nor expired, optionally of one
+    /// user, most recently active first
+    pub fn list_active(&self, user_id: Option<i64>) -> AppResult<Vec<ActiveSession>> {
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<ActiveSession>> {
+            let mut stmt = conn.prepare(
+                "SELECT s.session_id, s.user_id, u.username, TRIM(u.first_name || ' ' || u.last_name),
+                        s.created_at, s.last_activity, s.expires_at, s.kiosk_terminal_id, s.idle_timeout_minutes
+                 FROM sessions s JOIN users u ON u.id = s.user_id
+                 WHERE s.revoked_at IS NULL AND s.expires_at > ?1 AND u.deleted_at IS NULL
+                   AND (?2 IS NULL OR s.user_id = ?2)
+                 ORDER BY s.last_activity DESC"
+            )?;
+            let now = Utc::now();
+            let rows = stmt.query_map(params![now, user_id], |row| {
+                Ok((ActiveSession {
+                    session_id: row.get(0)?,
+                    user_id: row.get(1)?,
+                    username: row.get(2)?,
+                    full_name: row.get(3)?,
+                    created_at: row.get(4)?,
+                    last_activity: row.get(5)?,
+                    expires_at: row.get(6)?,
+                    kiosk_terminal_id: row.get(7)?,
+                    current: false,
+                }, row.get::<_, Option<i64>>(8)?))
+            })?;
+
+            let mut sessions = Vec::new();
+            for row in rows {
+                let (session, idle_timeout_minutes) = row?;
+                let idled_out = idle_timeout_minutes
+                    .is_some_and(|minutes| now - session.last_activity > chrono::Duration::minutes(minutes));
+                if !idled_out {
+                    sessions.push(session);
+                }
+            }
+            Ok(sessions)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Delete sessions that expired before `before`, revoked or not, with
+    /// their refresh tokens, and login challenges that have run out
+    pub fn purge_expired(&self, before: DateTime<Utc>) -> AppResult<usize> {
+        self.database.with_transaction(|conn| {
+            conn.execute("DELETE FROM mfa_challenges WHERE expires_at <= ?1", params![Utc::now()])?;
+            conn.execute("DELETE FROM refresh_tokens WHERE expires_at < ?1", params![before])?;
+            Ok(conn.execute("DELETE FROM sessions WHERE expires_at < ?1", params![before])?)
+        })
+    }
+
+    /// Store a refresh token under the SHA-256 of the token
+    pub fn store_refresh_token(&self, token_hash: &str, token: &RefreshTokenRecord) -> AppResult<()> {
+        self.database.with_transaction(|conn| {
+            conn.execute(
+                "INSERT INTO refresh_tokens (token_hash, session_id, user_id, family_id, expires_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![token_hash, token.session_id, token.user_id, token.family_id, token.expires_at],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn get_refresh_token(&self, token_hash: &str) -> AppResult<Option<RefreshTokenRecord>> {
+        let conn = self.database.get_connection()?;
+        let result = read_refresh_token(&conn, token_hash);
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Exchange a refresh token: mark it used if it is neither used,
+    /// revoked nor expired. Returns the token as it stood before, so a
+    /// caller can tell a token accepted now from one seen before.
+    pub fn use_refresh_token(&self, token_hash: &str) -> AppResult<Option<RefreshTokenRecord>> {
+        self.database.with_transaction(|conn| {
+            let token = read_refresh_token(conn, token_hash)?;
+            let now = Utc::now();
+            if let Some(token) = token.as_ref().filter(|t| t.used_at.is_none() && t.revoked_at.is_none() && t.expires_at > now) {
+                conn.execute(
+                    "UPDATE refresh_tokens SET used_at = ?1 WHERE token_hash = ?2",
+                    params![now, token_hash],
+                )?;
+                debug!("Refresh token of session {} used", token.session_id);
+            }
+            Ok(token)
+        })
+    }
+
+    /// Revoke every refresh token rotated from the same sign-in, returning
+    /// how many were still live
+    pub fn revoke_refresh_family(&self, family_id: &str) -> AppResult<usize> {
+        self.database.with_transaction(|conn| {
+            Ok(conn.execute(
+                "UPDATE refresh_tokens SET revoked_at = ?1 WHERE family_id = ?2 AND revoked_at IS NULL",
+                params![Utc::now(), family_id],
+            )?)
+        })
+    }
+
+    /// Hold a login until its second factor is checked, under the SHA-256
+    /// of the challenge token
+    pub fn create_mfa_challenge(&self, challenge_hash: &str, pending: &PendingMfa) -> AppResult<()> {
+        self.database.with_transaction(|conn| {
+            conn.execute(
+                "INSERT INTO mfa_challenges (challenge_hash, user_id, expires_at, attempts) VALUES (?1, ?2, ?3, ?4)",
+                params![challenge_hash, pending.user_id, pending.expires_at, pending.attempts],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn get_mfa_challenge(&self, challenge_hash: &str) -> AppResult<Option<PendingMfa>> {
+        let conn = self.database.get_connection()?;
+        let result = conn.query_row(
+            "SELECT user_id, expires_at, attempts FROM mfa_challenges WHERE challenge_hash = ?1",
+            params![challenge_hash],
+            |row| Ok(PendingMfa { user_id: row.get(0)?, expires_at: row.get(1)?, attempts: row.get(2)? }),
+        ).optional();
+        self.database.return_connection(conn);
+        Ok(result?)
+    }
+
+    /// Count a wrong code against a challenge, dropping the challenge once
+    /// `max_attempts` have been used. Returns whether it was dropped.
+    pub fn record_mfa_failure(&self, challenge_hash: &str, max_attempts: u32) -> AppResult<bool> {
+        self.database.with_transaction(|conn| {
+            conn.execute(
+                "UPDATE mfa_challenges SET attempts = attempts + 1 WHERE challenge_hash = ?1",
+                params![challenge_hash],
+            )?;
+            Ok(conn.execute(
+                "DELETE FROM mfa_challenges WHERE challenge_hash = ?1 AND attempts >= ?2",
+                params![challenge_hash, max_attempts],
+            )? > 0)
+        })
+    }
+
+    pub fn remove_mfa_challenge(&self, challenge_hash: &str) -> AppResult<()> {
+        self.database.with_transaction(|conn| {
+            conn.execute("DELETE FROM mfa_challenges WHERE challenge_hash = ?1", params![challenge_hash])?;
+            Ok(())
+        })
+    }
+}
+
+fn read_refresh_token(conn: &Connection, token_hash: &str) -> AppResult<Option<RefreshTokenRecord>> {
+    Ok(conn.query_row(
+        "SELECT session_id, user_id, family_id, expires_at, used_at, revoked_at
+         FROM refresh_tokens WHERE token_hash = ?1",
+        params![token_hash],
+        |row| Ok(RefreshTokenRecord {
+            session_id: row.get(0)?,
+            user_id: row.get(1)?,
+            family_id: row.get(2)?,
+            expires_at: row.get(3)?,
+            used_at: row.get(4)?,
+            revoked_at: row.get(5)?,
+        }),
+    ).optional()?)
+}
+
+// =============================================================================
+// Availability Service
+// =============================================================================
+
+/// Inspector who can take work on a given day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvailableInspector {
+    pub user_id: i64,
+    pub username: String,
+    pub full_name: String,
+    /// Scheduled and in-progress inspections currently assigned
+    pub open_inspections: i64,
+}
+
+/// Inspection handed to another inspector because of an absence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InspectionReassignment {
+    pub inspection_id: i64,
+    pub asset_id: i64,
+    pub scheduled_date: DateTime<Utc>,
+    pub from_inspector_id: i64,
+    /// `None` when no inspector was available that day; the inspection
+    /// stays with the absent user and needs manual attention
+    pub to_inspector_id: Option<i64>,
+}
+
+/// Recorded absence and any work moved because of it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbsenceRecordResult {
+    pub absence: UserAbsence,
+    pub reassignments: Vec<InspectionReassignment>,
+}
+
+const ABSENCE_COLUMNS: &str =
+    "id, user_id, absence_type, start_date, end_date, notes, created_by, created_at";
+
+pub struct AvailabilityService {
+    database: Arc<Database>,
+}
+
+impl AvailabilityService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Record an absence. Long absences (see [`LONG_ABSENCE_DAYS`]) move the
+    /// user's scheduled inspections in the period to available inspectors.
+    pub fn create_absence(&self, context: &RequestContext, absence: UserAbsence) -> AppResult<AbsenceRecordResult> {
+        info!("[{}] Recording {} absence for user {}: {} to {}", context.request_id,
+              absence.absence_type, absence.user_id, absence.start_date, absence.end_date);
+        absence.validate()?;
+        let created_by = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            ensure_user_exists(conn, absence.user_id)?;
+
+            let overlapping: Option<i64> = conn.query_row(
+                "SELECT id FROM user_absences
+                 WHERE user_id = ?1 AND start_date <= ?3 AND end_date >= ?2 LIMIT 1",
+                params![absence.user_id, absence.start_date, absence.end_date],
+                |row| row.get(0),
+            ).optional()?;
+            if let Some(existing) = overlapping {
+                return Err(AppError::validation(
+                    "start_date",
+                    format!("Absence overlaps existing absence {} for this user", existing),
+                ));
+            }
+
+            let id = conn.query_row(
+                "INSERT INTO user_absences (user_id, absence_type, start_date, end_date, notes, created_by)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 RETURNING id",
+                params![
+                    absence.user_id, absence.absence_type.to_string(), absence.start_date,
+                    absence.end_date, absence.notes, created_by
+                ],
+                |row| row.get::<_, i64>(0),
+            )?;
+            let absence = conn.query_row(
+                &format!("SELECT {} FROM user_absences WHERE id = ?1", ABSENCE_COLUMNS),
+                params![id],
+                row_to_absence,
+            )?;
+            debug!("Absence created with ID: {}", id);
+
+            let reassignments = if absence.is_long() {
+                reassign_absent_work(conn, context, &absence)?
+            } else {
+                Vec::new()
+            };
+            Ok(AbsenceRecordResult { absence, reassignments })
+        })
+    }
+
+    pub fn get_absence_by_id(&self, id: i64) -> AppResult<UserAbsence> {
+        debug!("Fetching absence by ID: {}", id);
+        let conn = self.database.get_connection()?;
+
+        let absence = conn.query_row(
+            &format!("SELECT {} FROM user_absences WHERE id = ?1", ABSENCE_COLUMNS),
+            params![id],
+            row_to_absence,
+        ).map_err(|_| AppError::RecordNotFound {
+            entity: "UserAbsence".to_string(),
+            field: "id".to_string(),
+            value: id.to_string(),
+        });
+
+        self.database.return_connection(conn);
+        absence
+    }
+
+    /// Absences, optionally for one user and/or overlapping a date range
+    pub fn get_absences(
+        &self,
+        user_id: Option<i64>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> AppResult<Vec<UserAbsence>> {
+        debug!("Fetching absences for user {:?} between {:?} and {:?}", user_id, from, to);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<UserAbsence>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM user_absences
+                 WHERE (?1 IS NULL OR user_id = ?1)
+                   AND (?2 IS NULL OR end_date >= ?2)
+                   AND (?3 IS NULL OR start_date <= ?3)
+                 ORDER BY start_date, user_id",
+                ABSENCE_COLUMNS
+            ))?;
+            let absences = stmt
+                .query_map(params![user_id, from, to], row_to_absence)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(absences)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    pub fn delete_absence(&self, context: &RequestContext, id: i64) -> AppResult<()> {
+        info!("[{}] Deleting absence: {}", context.request_id, id);
+
+        self.database.with_transaction(|conn| {
+            let deleted = conn.execute("DELETE FROM user_absences WHERE id = ?1", params![id])?;
+            if deleted == 0 {
+                return Err(AppError::RecordNotFound {
+                    entity: "UserAbsence".to_string(),
+                    field: "id".to_string(),
+                    value: id.to_string(),
+                });
+            }
+            Ok(())
+        })
+    }
+
+    pub fn is_available(&self, user_id: i64, date: NaiveDate) -> AppResult<bool> {
+        let conn = self.database.get_connection()?;
+        let result = is_absent_on(&conn, user_id, date).map(|absent| !absent);
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Active inspectors not absent on `date`, least loaded first
+    pub fn get_available_inspectors(&self, date: NaiveDate) -> AppResult<Vec<AvailableInspector>> {
+        debug!("Fetching inspectors available on {}", date);
+        let conn = self.database.get_connection()?;
+        let result = available_inspectors(&conn, date, None);
+        self.database.return_connection(conn);
+        result
+    }
+}
+
+fn row_to_absence(row: &Row) -> rusqlite::Result<UserAbsence> {
+    Ok(UserAbsence {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        absence_type: row.get::<_, String>(2)?.parse().unwrap_or(AbsenceType::Vacation),
+        start_date: row.get(3)?,
+        end_date: row.get(4)?,
+        notes: row.get(5)?,
+        created_by: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+fn is_absent_on(conn: &Connection, user_id: i64, date: NaiveDate) -> AppResult<bool> {
+    let absent = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM user_absences WHERE user_id = ?1 AND start_date <= ?2 AND end_date >= ?2)",
+        params![user_id, date],
+        |row| row.get(0),
+    )?;
+    Ok(absent)
+}
+
+fn available_inspectors(conn: &Connection, date: NaiveDate, exclude: Option<i64>) -> AppResult<Vec<AvailableInspector>> {
+    let mut stmt = conn.prepare(
+        "SELECT u.id, u.username, u.first_name || ' ' || u.last_name,
+                (SELECT COUNT(*) FROM inspections i
+                 WHERE i.inspector_id = u.id AND i.status IN ('Scheduled', 'In Progress')) AS open_inspections
+         FROM users u
+         WHERE u.is_active = 1 AND u.role = 'Inspector' AND u.deleted_at IS NULL
+           AND (?2 IS NULL OR u.id != ?2)
+           AND NOT EXISTS (SELECT 1 FROM user_absences a
+                           WHERE a.user_id = u.id AND a.start_date <= ?1 AND a.end_date >= ?1)
+         ORDER BY open_inspections ASC, u.id ASC"
+    )?;
+    let inspectors = stmt
+        .query_map(params![date, exclude], |row| {
+            Ok(AvailableInspector {
+                user_id: row.get(0)?,
+                username: row.get(1)?,
+                full_name: row.get(2)?,
+                open_inspections: row.get(3)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(inspectors)
+}
+
+/// Reject assigning an inspection to someone who is absent on its local due date
+fn ensure_inspector_available(
+    conn: &Connection,
+    inspection_id: &str,
+    inspector_id: i64,
+    scheduled_date: DateTime<Utc>,
+    time_zone: &str,
+) -> AppResult<()> {
+    let date = scheduling::local_date(scheduled_date, scheduling::time_zone_or_default(Some(time_zone)));
+    if is_absent_on(conn, inspector_id, date)? {
+        return Err(AppError::ScheduleConflict {
+            inspection_id: inspection_id.to_string(),
+            reason: format!("Inspector {} is unavailable on {}", inspector_id, date),
+        });
+    }
+    Ok(())
+}
+
+/// Whether a user holds training in force on `date` for `inspection_type`.
+/// Types outside `REGULATED_INSPECTION_TYPES` need no training.
+fn inspector_qualified(
+    conn: &Connection,
+    inspector_id: i64,
+    inspection_type: &InspectionType,
+    date: NaiveDate,
+) -> AppResult<bool> {
+    if !REGULATED_INSPECTION_TYPES.contains(inspection_type) {
+        return Ok(true);
+    }
+    let qualified = conn.query_row(
+        "SELECT EXISTS(
+             SELECT 1 FROM training_records t
+             WHERE t.user_id = ?1 AND t.issued_date <= ?3
+               AND (t.expiry_date IS NULL OR t.expiry_date >= ?3)
+               AND EXISTS (SELECT 1 FROM json_each(t.qualifies_for) WHERE value = ?2))",
+        params![inspector_id, inspection_type.to_string(), date],
+        |row| row.get(0),
+    )?;
+    Ok(qualified)
+}
+
+/// Reject assigning a regulated inspection to someone without training in
+/// force on its local due date
+fn ensure_inspector_qualified(
+    conn: &Connection,
+    inspection_id: &str,
+    inspector_id: i64,
+    inspection_type: &InspectionType,
+    date: NaiveDate,
+) -> AppResult<()> {
+    if !inspector_qualified(conn, inspector_id, inspection_type, date)? {
+        return Err(AppError::ScheduleConflict {
+            inspection_id: inspection_id.to_string(),
+            reason: format!("Inspector {} has no current training for {} inspections on {}",
+                            inspector_id, inspection_type, date),
+        });
+    }
+    Ok(())
+}
+
+/// Move the absent user's scheduled inspections within the absence to the
+/// least loaded available inspector for each day
+fn reassign_absent_work(
+    conn: &Connection,
+    context: &RequestContext,
+    absence: &UserAbsence,
+) -> AppResult<Vec<InspectionReassignment>> {
+    // Widen the UTC window by a day each side; local dates are checked below
+    let window_start = scheduling::start_of_local_day(absence.start_date, chrono_tz::Tz::UTC) - chrono::Duration::days(1);
+    let window_end = scheduling::start_of_local_day(absence.end_date, chrono_tz::Tz::UTC) + chrono::Duration::days(2);
+
+    let candidates = {
+        let mut stmt = conn.prepare(
+            "SELECT id, asset_id, scheduled_date, time_zone, inspection_type FROM inspections
+             WHERE inspector_id = ?1 AND status = 'Scheduled'
+               AND scheduled_date >= ?2 AND scheduled_date < ?3
+             ORDER BY scheduled_date"
+        )?;
+        let rows = stmt
+            .query_map(params![absence.user_id, window_start, window_end], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, DateTime<Utc>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, String>(4)?.parse().unwrap_or(InspectionType::Periodic),
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rows
+    };
+
+    let mut reassignments = Vec::new();
+    for (inspection_id, asset_id, scheduled_date, time_zone, inspection_type) in candidates {
+        let date = scheduling::local_date(scheduled_date, scheduling::time_zone_or_default(time_zone.as_deref()));
+        if !absence.covers(date) {
+            continue;
+        }
+
+        let mut to_inspector_id = None;
+        for inspector in available_inspectors(conn, date, Some(absence.user_id))? {
+            if inspector_qualified(conn, inspector.user_id, &inspection_type, date)? {
+                to_inspector_id = Some(inspector.user_id);
+                break;
+            }
+        }
+        match to_inspector_id {
+            Some(to) => {
+                conn.execute(
+                    "UPDATE inspections SET inspector_id = ?1 WHERE id = ?2",
+                    params![to, inspection_id],
+                )?;
+                info!("[{}] Reassigned inspection {} from inspector {} to {} during absence {}",
+                      context.request_id, inspection_id, absence.user_id, to, absence.id);
+            }
+            None => warn!("[{}] No inspector available on {} to take inspection {} from absent inspector {}",
+                          context.request_id, date, inspection_id, absence.user_id),
+        }
+        reassignments.push(InspectionReassignment {
+            inspection_id,
+            asset_id,
+            scheduled_date,
+            from_inspector_id: absence.user_id,
+            to_inspector_id,
+        });
+    }
+    Ok(reassignments)
+}
+
+// =============================================================================
+// Team Service
+// =============================================================================
+
+const TEAM_COLUMNS: &str =
+    "id, name, description, supervisor_id, is_active, created_by, created_at, updated_at";
+
+/// Recursive CTE selecting every location covered by team `?1` as
+/// `covered(id, depth)`: its assigned locations and their descendants down to
+/// depth `?2`, except sub-trees assigned to another team
+const TEAM_COVERAGE_CTE: &str = "WITH RECURSIVE covered(id, depth) AS (
+    SELECT location_id, 0 FROM team_locations WHERE team_id = ?1
+    UNION
+    SELECT l.id, c.depth + 1 FROM locations l JOIN covered c ON l.parent_location_id = c.id
+    WHERE c.depth < ?2
+      AND l.id NOT IN (SELECT location_id FROM team_locations WHERE team_id != ?1)
+)";
+
+pub struct TeamService {
+    database: Arc<Database>,
+    inspection_service: Arc<InspectionService>,
+}
+
+impl TeamService {
+    pub fn new(database: Arc<Database>, inspection_service: Arc<InspectionService>) -> Self {
+        Self { database, inspection_service }
+    }
+
+    pub fn create_team(&self, context: &RequestContext, team: Team) -> AppResult<Team> {
+        info!("[{}] Creating new team: {}", context.request_id, team.name);
+        team.validate()?;
+        let created_by = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            if let Some(supervisor_id) = team.supervisor_id {
+                ensure_user_exists(conn, supervisor_id)?;
+            }
+
+            let id = conn.query_row(
+                "INSERT INTO teams (name, description, supervisor_id, is_active, created_by)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 RETURNING id",
+                params![team.name.trim(), team.description, team.supervisor_id, team.is_active, created_by],
+                |row| row.get::<_, i64>(0),
+            ).map_err(|e| unique_team_name(e, &team.name))?;
+
+            debug!("Team created with ID: {}", id);
+            self.team_by_id(conn, id)
+        })
+    }
+
+    pub fn get_team(&self, id: i64) -> AppResult<TeamWithMembers> {
+        debug!("Fetching team by ID: {}", id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<TeamWithMembers> {
+            let team = self.team_by_id(&conn, id)?;
+
+            let mut stmt = conn.prepare(
+                "SELECT u.id, u.username, u.first_name || ' ' || u.last_name, u.role, u.is_active, tm.joined_at
+                 FROM team_members tm JOIN users u ON tm.user_id = u.id
+                 WHERE tm.team_id = ?1
+                 ORDER BY u.last_name, u.first_name"
+            )?;
+            let members = stmt
+                .query_map(params![id], |row| {
+                    Ok(TeamMember {
+                        user_id: row.get(0)?,
+                        username: row.get(1)?,
+                        full_name: row.get(2)?,
+                        role: row.get::<_, String>(3)?.parse().unwrap_or(UserRole::Inspector),
+                        is_active: row.get(4)?,
+                        joined_at: row.get(5)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut stmt = conn.prepare("SELECT location_id FROM team_locations WHERE team_id = ?1 ORDER BY location_id")?;
+            let location_ids = stmt
+                .query_map(params![id], |row| row.get(0))?
+                .collect::<rusqlite::Result<Vec<i64>>>()?;
+
+            Ok(TeamWithMembers { team, members, location_ids })
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    pub fn get_teams(&self, include_inactive: bool) -> AppResult<Vec<Team>> {
+        debug!("Fetching teams");
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<Team>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM teams WHERE (?1 OR is_active = 1) ORDER BY name",
+                TEAM_COLUMNS
+            ))?;
+            let teams = stmt
+                .query_map(params![include_inactive], row_to_team)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(teams)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Active teams the user belongs to or supervises
+    pub fn get_user_teams(&self, user_id: i64) -> AppResult<Vec<Team>> {
+        debug!("Fetching teams for user: {}", user_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<Team>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM teams
+                 WHERE is_active = 1
+                   AND (supervisor_id = ?1 OR id IN (SELECT team_id FROM team_members WHERE user_id = ?1))
+                 ORDER BY name",
+                TEAM_COLUMNS
+            ))?;
+            let teams = stmt
+                .query_map(params![user_id], row_to_team)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(teams)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    pub fn update_team(&self, context: &RequestContext, id: i64, updates: TeamUpdateData) -> AppResult<Team> {
+        info!("[{}] Updating team: {}", context.request_id, id);
+
+        self.database.with_transaction(|conn| {
+            let mut team = self.team_by_id(conn, id)?;
+            if let Some(name) = updates.name {
+                team.name = name.trim().to_string();
+            }
+            if let Some(description) = updates.description {
+                team.description = Some(description);
+            }
+            if let Some(supervisor_id) = updates.supervisor_id {
+                ensure_user_exists(conn, supervisor_id)?;
+                team.supervisor_id = Some(supervisor_id);
+            }
+            if let Some(is_active) = updates.is_active {
+                team.is_active = is_active;
+            }
+            team.validate()?;
+
+            conn.execute(
+                "UPDATE teams SET name = ?1, description = ?2, supervisor_id = ?3, is_active = ?4 WHERE id = ?5",
+                params![team.name, team.description, team.supervisor_id, team.is_active, id],
+            ).map_err(|e| unique_team_name(e, &team.name))?;
+
+            debug!("Team {} updated successfully", id);
+            self.team_by_id(conn, id)
+        })
+    }
+
+    pub fn delete_team(&self, context: &RequestContext, id: i64) -> AppResult<()> {
+        info!("[{}] Deleting team: {}", context.request_id, id);
+
+        self.database.with_transaction(|conn| {
+            // Members and location assignments are removed by cascade; the
+            // recycle bin snapshot keeps them for a restore
+            move_to_recycle_bin(conn, context, RecycleEntityType::Team, id)?;
+            let deleted = conn.execute("DELETE FROM teams WHERE id = ?1", params![id])?;
+            if deleted == 0 {
+                return Err(team_not_found(id));
+            }
+            Ok(())
+        })
+    }
+
+    pub fn add_member(&self, context: &RequestContext, team_id: i64, user_id: i64) -> AppResult<()> {
+        info!("[{}] Adding user {} to team {}", context.request_id, user_id, team_id);
+
+        self.database.with_transaction(|conn| {
+            self.team_by_id(conn, team_id)?;
+            ensure_user_exists(conn, user_id)?;
+            let inserted = conn.execute(
+                "INSERT OR IGNORE INTO team_members (team_id, user_id) VALUES (?1, ?2)",
+                params![team_id, user_id],
+            )?;
+            if inserted == 0 {
+                return Err(AppError::DuplicateRecord {
+                    entity: "TeamMember".to_string(),
+                    field: "user_id".to_string(),
+                    value: user_id.to_string(),
+                });
+            }
+            Ok(())
+        })
+    }
+
+    pub fn remove_member(&self, context: &RequestContext, team_id: i64, user_id: i64) -> AppResult<()> {
+        info!("[{}] Removing user {} from team {}", context.request_id, user_id, team_id);
+
+        self.database.with_transaction(|conn| {
+            let deleted = conn.execute(
+                "DELETE FROM team_members WHERE team_id = ?1 AND user_id = ?2",
+                params![team_id, user_id],
+            )?;
+            if deleted == 0 {
+                return Err(AppError::RecordNotFound {
+                    entity: "TeamMember".to_string(),
+                    field: "user_id".to_string(),
+                    value: user_id.to_string(),
+                });
+            }
+            Ok(())
+        })
+    }
+
+    /// Make `team_id` responsible for a location and its sub-locations,
+    /// replacing any previous assignment of that location
+    pub fn assign_location(&self, context: &RequestContext, team_id: i64, location_id: i64) -> AppResult<()> {
+        info!("[{}] Assigning location {} to team {}", context.request_id, location_id, team_id);
+
+        self.database.with_transaction(|conn| {
+            self.team_by_id(conn, team_id)?;
+            let location_exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM locations WHERE id = ?1)",
+                params![location_id],
+                |row| row.get(0),
+            )?;
+            if !location_exists {
+                return Err(AppError::RecordNotFound {
+                    entity: "Location".to_string(),
+                    field: "id".to_string(),
+                    value: location_id.to_string(),
+                });
+            }
+            conn.execute(
+                "INSERT INTO team_locations (location_id, team_id) VALUES (?1, ?2)
+                 ON CONFLICT(location_id) DO UPDATE SET team_id = excluded.team_id, assigned_at = CURRENT_TIMESTAMP",
+                params![location_id, team_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn unassign_location(&self, context: &RequestContext, location_id: i64) -> AppResult<()> {
+        info!("[{}] Removing team assignment from location {}", context.request_id, location_id);
+
+        self.database.with_transaction(|conn| {
+            let deleted = conn.execute("DELETE FROM team_locations WHERE location_id = ?1", params![location_id])?;
+            if deleted == 0 {
+                return Err(AppError::RecordNotFound {
+                    entity: "TeamLocation".to_string(),
+                    field: "location_id".to_string(),
+                    value: location_id.to_string(),
+                });
+            }
+            Ok(())
+        })
+    }
+
+    /// Scheduled and in-progress inspections for a team: those assigned to
+    /// its members plus any at locations the team covers
+    pub fn get_team_pending_inspections(&self, team_id: i64) -> AppResult<Vec<Inspection>> {
+        info!("Fetching pending inspections for team: {}", team_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<Inspection>> {
+            self.team_by_id(&conn, team_id)?;
+            let mut stmt = conn.prepare(&format!(
+                "{}
+                 SELECT i.id, i.asset_id, i.inspector_id, i.inspection_type, i.compliance_standard,
+                 i.scheduled_date, i.actual_date, i.status, i.overall_condition, i.checklist_data, i.notes,
+                 i.ai_analysis_results, i.created_at, i.updated_at, i.time_zone, i.overdue_at
+                 FROM inspections i JOIN assets a ON i.asset_id = a.id
+                 WHERE i.status IN ('Scheduled', 'In Progress')
+                   AND i.deleted_at IS NULL AND a.deleted_at IS NULL
+                   AND (i.inspector_id IN (SELECT user_id FROM team_members WHERE team_id = ?1)
+                        OR a.location_id IN (SELECT id FROM covered))
+                 ORDER BY i.scheduled_date ASC",
+                TEAM_COVERAGE_CTE
+            ))?;
+            let inspections = stmt
+                .query_map(params![team_id, MAX_LOCATION_DEPTH as i64], |row| {
+                    self.inspection_service.row_to_inspection(row)
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(inspections)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Completion of inspections scheduled in the period, per team member
+    pub fn get_team_completion_stats(
+        &self,
+        team_id: i64,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+    ) -> AppResult<TeamCompletionStats> {
+        info!("Generating completion stats for team {} from {} to {}", team_id, start_date, end_date);
+        if end_date < start_date {
+            return Err(AppError::validation("end_date", "End date cannot be before start date"));
+        }
+        let conn = self.database.get_read_connection()?;
+
+        let team = self.team_by_id(&conn, team_id)?;
+
+        let mut stmt = conn.prepare(
+            "SELECT u.id, u.username,
+                    COUNT(i.id),
+                    COUNT(CASE WHEN i.status = 'Completed' THEN 1 END)
+             FROM team_members tm
+             JOIN users u ON tm.user_id = u.id
+             LEFT JOIN inspections i ON i.inspector_id = u.id AND i.scheduled_date BETWEEN ?2 AND ?3
+             WHERE tm.team_id = ?1
+             GROUP BY u.id, u.username
+             ORDER BY u.username"
+        )?;
+        let members = stmt
+            .query_map(params![team_id, start_date, end_date], |row| {
+                let total_scheduled: i64 = row.get(2)?;
+                let total_completed: i64 = row.get(3)?;
+                Ok(MemberCompletionStats {
+                    user_id: row.get(0)?,
+                    username: row.get(1)?,
+                    total_scheduled,
+                    total_completed,
+                    completion_rate: completion_rate(total_completed, total_scheduled),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let overdue_open: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM inspections
+             WHERE inspector_id IN (SELECT user_id FROM team_members WHERE team_id = ?1)
+               AND status NOT IN ('Completed', 'Cancelled')
+               AND COALESCE(overdue_at, scheduled_date) < datetime('now')",
+            params![team_id],
+            |row| row.get(0),
+        )?;
+
+        let total_scheduled = members.iter().map(|m| m.total_scheduled).sum();
+        let total_completed = members.iter().map(|m| m.total_completed).sum();
+        Ok(TeamCompletionStats {
+            team_id,
+            team_name: team.name,
+            start_date,
+            end_date,
+            total_scheduled,
+            total_completed,
+            completion_rate: completion_rate(total_completed, total_scheduled),
+            overdue_open,
+            members,
+        })
+    }
+
+    /// Team responsible for a location: the nearest team assigned to it or
+    /// one of its ancestors. Used to route notifications about the location.
+    pub fn route_for_location(&self, location_id: i64) -> AppResult<Option<TeamRoute>> {
+        debug!("Resolving responsible team for location: {}", location_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Option<TeamRoute>> {
+            let team_id: Option<i64> = conn.query_row(
+                "WITH RECURSIVE ancestors(id, depth) AS (
+                     SELECT id, 0 FROM locations WHERE id = ?1
+                     UNION ALL
+                     SELECT l.parent_location_id, a.depth + 1 FROM locations l JOIN ancestors a ON l.id = a.id
+                     WHERE l.parent_location_id IS NOT NULL AND a.depth < ?2
+                 )
+                 SELECT tl.team_id FROM ancestors a
+                 JOIN team_locations tl ON tl.location_id = a.id
+                 JOIN teams t ON t.id = tl.team_id AND t.is_active = 1
+                 ORDER BY a.depth LIMIT 1",
+                params![location_id, MAX_LOCATION_DEPTH as i64],
+                |row| row.get(0),
+            ).optional()?;
+            let Some(team_id) = team_id else {
+                return Ok(None);
+            };
+
+            let team = self.team_by_id(&conn, team_id)?;
+            let mut stmt = conn.prepare(
+                "SELECT u.id FROM team_members tm JOIN users u ON tm.user_id = u.id
+                 WHERE tm.team_id = ?1 AND u.is_active = 1 AND u.id IS NOT ?2
+                 ORDER BY u.id"
+            )?;
+            let member_ids = stmt
+                .query_map(params![team_id, team.supervisor_id], |row| row.get(0))?
+                .collect::<rusqlite::Result<Vec<i64>>>()?;
+
+            Ok(Some(TeamRoute {
+                team_id,
+                team_name: team.name,
+                supervisor_id: team.supervisor_id,
+                member_ids,
+            }))
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    fn team_by_id(&self, conn: &Connection, id: i64) -> AppResult<Team> {
+        conn.query_row(
+            &format!("SELECT {} FROM teams WHERE id = ?1", TEAM_COLUMNS),
+            params![id],
+            row_to_team,
+        ).map_err(|_| team_not_found(id))
+    }
+}
+
+fn row_to_team(row: &Row) -> rusqlite::Result<Team> {
+    Ok(Team {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        supervisor_id: row.get(3)?,
+        is_active: row.get(4)?,
+        created_by: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+fn team_not_found(id: i64) -> AppError {
+    AppError::RecordNotFound {
+        entity: "Team".to_string(),
+        field: "id".to_string(),
+        value: id.to_string(),
+    }
+}
+
+fn unique_team_name(error: rusqlite::Error, name: &str) -> AppError {
+    match &error {
+        rusqlite::Error::SqliteFailure(e, _) if e.code == rusqlite::ErrorCode::ConstraintViolation => {
+            AppError::DuplicateRecord {
+                entity: "Team".to_string(),
+                field: "name".to_string(),
+                value: name.to_string(),
+            }
+        }
+        _ => error.into(),
+    }
+}
+
+fn ensure_user_exists(conn: &Connection, user_id: i64) -> AppResult<()> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM users WHERE id = ?1)",
+        params![user_id],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Err(AppError::RecordNotFound {
+            entity: "User".to_string(),
+            field: "id".to_string(),
+            value: user_id.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn completion_rate(completed: i64, scheduled: i64) -> f64 {
+    if scheduled > 0 {
+        (completed as f64 / scheduled as f64) * 100.0
+    } else {
+        0.0
+    }
+}
+
+// =============================================================================
+// Comment Service
+// =============================================================================
+
+/// Saved comment and the users newly mentioned by this save, who should
+/// be notified
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedComment {
+    pub comment: Comment,
+    pub notified_user_ids: Vec<i64>,
+}
+
+/// Characters of the comment body included in mention notifications
+const MENTION_EXCERPT_CHARS: usize = 140;
+
+const COMMENT_SELECT: &str =
+    "SELECT c.id, c.entity_type, c.entity_id, c.parent_id, c.author_id,
+            u.first_name || ' ' || u.last_name, c.body, c.is_deleted, c.edit_count, c.created_at, c.updated_at
+     FROM comments c JOIN users u ON c.author_id = u.id";
+
+pub struct CommentService {
+    database: Arc<Database>,
+}
+
+impl CommentService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Add a comment, or a reply when `parent_id` is given. Replies to a
+    /// reply are attached to its top-level comment.
+    pub fn add_comment(
+        &self,
+        context: &RequestContext,
+        entity_type: CommentEntityType,
+        entity_id: i64,
+        parent_id: Option<i64>,
+        body: &str,
+    ) -> AppResult<SavedComment> {
+        info!("[{}] Adding comment on {} {}", context.request_id, entity_type, entity_id);
+        validate_comment_body(body)?;
+        let author_id = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            let exists: bool = conn.query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?1)", entity_type.table()),
+                params![entity_id],
+                |row| row.get(0),
+            ).unwrap_or(false);
+            if !exists {
+                return Err(AppError::RecordNotFound {
+                    entity: entity_type.to_string(),
+                    field: "id".to_string(),
+                    value: entity_id.to_string(),
+                });
+            }
+
+            let thread_id = match parent_id {
+                Some(parent_id) => {
+                    let parent = comment_by_id(conn, parent_id)?;
+                    if parent.entity_type != entity_type || parent.entity_id != entity_id {
+                        return Err(AppError::validation("parent_id", "Reply must be on the same record as its parent"));
+                    }
+                    Some(parent.parent_id.unwrap_or(parent.id))
+                }
+                None => None,
+            };
+
+            let id = conn.query_row(
+                "INSERT INTO comments (entity_type, entity_id, parent_id, author_id, body)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 RETURNING id",
+                params![entity_type.to_string(), entity_id, thread_id, author_id, body],
+                |row| row.get::<_, i64>(0),
+            )?;
+            let notified_user_ids = record_mentions(conn, id, author_id, body)?;
+
+            debug!("Comment created with ID: {} ({} mentions)", id, notified_user_ids.len());
+            Ok(SavedComment { comment: comment_by_id(conn, id)?, notified_user_ids })
+        })
+    }
+
+    pub fn get_comment(&self, id: i64) -> AppResult<Comment> {
+        let conn = self.database.get_connection()?;
+        let result = comment_by_id(&conn, id);
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Comment threads on a record, oldest first
+    pub fn get_comments(&self, entity_type: CommentEntityType, entity_id: i64) -> AppResult<Vec<CommentThread>> {
+        debug!("Fetching comments for {} {}", entity_type, entity_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<CommentThread>> {
+            let mut mentions: HashMap<i64, Vec<i64>> = HashMap::new();
+            let mut stmt = conn.prepare(
+                "SELECT cm.comment_id, cm.user_id FROM comment_mentions cm
+                 JOIN comments c ON cm.comment_id = c.id
+                 WHERE c.entity_type = ?1 AND c.entity_id = ?2
+                 ORDER BY cm.user_id"
+            )?;
+            for row in stmt.query_map(params![entity_type.to_string(), entity_id], |row| Ok((row.get(0)?, row.get(1)?)))? {
+                let (comment_id, user_id): (i64, i64) = row?;
+                mentions.entry(comment_id).or_default().push(user_id);
+            }
+
+            let mut stmt = conn.prepare(&format!(
+                "{} WHERE c.entity_type = ?1 AND c.entity_id = ?2 ORDER BY c.created_at, c.id",
+                COMMENT_SELECT
+            ))?;
+            let comments = stmt
+                .query_map(params![entity_type.to_string(), entity_id], row_to_comment)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut threads: Vec<CommentThread> = Vec::new();
+            let mut replies: Vec<Comment> = Vec::new();
+            for mut comment in comments {
+                comment.mentioned_user_ids = mentions.remove(&comment.id).unwrap_or_default();
+                match comment.parent_id {
+                    None => threads.push(CommentThread { comment, replies: Vec::new() }),
+                    Some(_) => replies.push(comment),
+                }
+            }
+            for reply in replies {
+                if let Some(thread) = threads.iter_mut().find(|t| Some(t.comment.id) == reply.parent_id) {
+                    thread.replies.push(reply);
+                }
+            }
+            Ok(threads)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Replace the comment text, keeping the previous version in the edit
+    /// history. Only the author may edit.
+    pub fn edit_comment(&self, context: &RequestContext, id: i64, body: &str) -> AppResult<SavedComment> {
+        info!("[{}] Editing comment: {}", context.request_id, id);
+        validate_comment_body(body)?;
+        let session = context.current_user()?;
+
+        self.database.with_transaction(|conn| {
+            let comment = comment_by_id(conn, id)?;
+            if comment.author_id != session.user_id {
+                return Err(AppError::Authorization {
+                    user: session.username.clone(),
+                    action: "edit".to_string(),
+                    resource: format!("comment {}", id),
+                });
+            }
+            if comment.is_deleted {
+                return Err(AppError::validation("id", "Deleted comments cannot be edited"));
+            }
+            if comment.body == body {
+                return Ok(SavedComment { comment, notified_user_ids: Vec::new() });
+            }
+
+            conn.execute(
+                "INSERT INTO comment_edits (comment_id, previous_body, edited_by) VALUES (?1, ?2, ?3)",
+                params![id, comment.body, session.user_id],
+            )?;
+            conn.execute(
+                "UPDATE comments SET body = ?1, edit_count = edit_count + 1, updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?2",
+                params![body, id],
+            )?;
+            let notified_user_ids = record_mentions(conn, id, comment.author_id, body)?;
+
+            debug!("Comment {} edited", id);
+            Ok(SavedComment { comment: comment_by_id(conn, id)?, notified_user_ids })
+        })
+    }
+
+    /// Blank a comment while keeping its place in the thread. The edit
+    /// history keeps the removed text.
+    pub fn delete_comment(&self, context: &RequestContext, id: i64) -> AppResult<()> {
+        info!("[{}] Deleting comment: {}", context.request_id, id);
+        let deleted_by = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            let comment = comment_by_id(conn, id)?;
+            if comment.is_deleted {
+                return Ok(());
+            }
+            conn.execute(
+                "INSERT INTO comment_edits (comment_id, previous_body, edited_by) VALUES (?1, ?2, ?3)",
+                params![id, comment.body, deleted_by],
+            )?;
+            conn.execute(
+                "UPDATE comments SET body = '', is_deleted = 1, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+                params![id],
+            )?;
+            conn.execute("DELETE FROM comment_mentions WHERE comment_id = ?1", params![id])?;
+            Ok(())
+        })
+    }
+
+    /// Previous versions of a comment, newest first
+    pub fn get_comment_history(&self, id: i64) -> AppResult<Vec<CommentEdit>> {
+        debug!("Fetching edit history for comment: {}", id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<CommentEdit>> {
+            comment_by_id(&conn, id)?;
+            let mut stmt = conn.prepare(
+                "SELECT id, comment_id, previous_body, edited_by, edited_at FROM comment_edits
+                 WHERE comment_id = ?1 ORDER BY edited_at DESC, id DESC"
+            )?;
+            let edits = stmt
+                .query_map(params![id], |row| {
+                    Ok(CommentEdit {
+                        id: row.get(0)?,
+                        comment_id: row.get(1)?,
+                        previous_body: row.get(2)?,
+                        edited_by: row.get(3)?,
+                        edited_at: row.get(4)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(edits)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Comments mentioning a user, newest first
+    pub fn get_mentions(&self, user_id: i64, unread_only: bool) -> AppResult<Vec<CommentMention>> {
+        debug!("Fetching mentions for user: {}", user_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<CommentMention>> {
+            let mut stmt = conn.prepare(
+                "SELECT cm.comment_id, cm.user_id, c.entity_type, c.entity_id,
+                        u.first_name || ' ' || u.last_name, c.body, cm.created_at, cm.read_at
+                 FROM comment_mentions cm
+                 JOIN comments c ON cm.comment_id = c.id
+                 JOIN users u ON c.author_id = u.id
+                 WHERE cm.user_id = ?1 AND (?2 = 0 OR cm.read_at IS NULL)
+                 ORDER BY cm.created_at DESC, cm.comment_id DESC"
+            )?;
+            let mentions = stmt
+                .query_map(params![user_id, unread_only], |row| {
+                    let body: String = row.get(5)?;
+                    Ok(CommentMention {
+                        comment_id: row.get(0)?,
+                        user_id: row.get(1)?,
+                        entity_type: row.get::<_, String>(2)?.parse().unwrap_or(CommentEntityType::Asset),
+                        entity_id: row.get(3)?,
+                        author_name: row.get(4)?,
+                        excerpt: body.chars().take(MENTION_EXCERPT_CHARS).collect(),
+                        created_at: row.get(6)?,
+                        read_at: row.get(7)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(mentions)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Mark the given mentions, or all of the user's mentions, as read.
+    /// Returns the number of mentions updated.
+    pub fn mark_mentions_read(&self, context: &RequestContext, comment_ids: Option<&[i64]>) -> AppResult<usize> {
+        let user_id = context.current_user()?.user_id;
+        debug!("[{}] Marking mentions read for user {}", context.request_id, user_id);
+
+        self.database.with_transaction(|conn| {
+            let updated = match comment_ids {
+                None => conn.execute(
+                    "UPDATE comment_mentions SET read_at = CURRENT_TIMESTAMP WHERE user_id = ?1 AND read_at IS NULL",
+                    params![user_id],
+                )?,
+                Some(ids) => {
+                    let mut updated = 0;
+                    for comment_id in ids {
+                        updated += conn.execute(
+                            "UPDATE comment_mentions SET read_at = CURRENT_TIMESTAMP
+                             WHERE user_id = ?1 AND comment_id = ?2 AND read_at IS NULL",
+                            params![user_id, comment_id],
+                        )?;
+                    }
+                    updated
+                }
+            };
+            Ok(updated)
+        })
+    }
+}
+
+fn row_to_comment(row: &Row) -> rusqlite::Result<Comment> {
+    Ok(Comment {
+        id: row.get(0)?,
+        entity_type: row.get::<_, String>(1)?.parse().unwrap_or(CommentEntityType::Asset),
+        entity_id: row.get(2)?,
+        parent_id: row.get(3)?,
+        author_id: row.get(4)?,
+        author_name: row.get(5)?,
+        body: row.get(6)?,
+        is_deleted: row.get(7)?,
+        edit_count: row.get(8)?,
+        mentioned_user_ids: Vec::new(),
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    })
+}
+
+fn comment_by_id(conn: &Connection, id: i64) -> AppResult<Comment> {
+    let mut comment = conn.query_row(
+        &format!("{} WHERE c.id = ?1", COMMENT_SELECT),
+        params![id],
+        row_to_comment,
+    ).map_err(|_| AppError::RecordNotFound {
+        entity: "Comment".to_string(),
+        field: "id".to_string(),
+        value: id.to_string(),
+    })?;
+
+    let mut stmt = conn.prepare("SELECT user_id FROM comment_mentions WHERE comment_id = ?1 ORDER BY user_id")?;
+    comment.mentioned_user_ids = stmt
+        .query_map(params![id], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<i64>>>()?;
+    Ok(comment)
+}
+
+/// Store mentions of active users in `body`, skipping the author and users
+/// already mentioned. Returns the newly mentioned user IDs.
+fn record_mentions(conn: &Connection, comment_id: i64, author_id: i64, body: &str) -> AppResult<Vec<i64>> {
+    let mut mentioned = Vec::new();
+    for username in extract_mentions(body) {
+        let user_id: Option<i64> = conn.query_row(
+            "SELECT id FROM users WHERE lower(username) = ?1 AND is_active = 1",
+            params![username],
+            |row| row.get(0),
+        ).optional()?;
+        let Some(user_id) = user_id else {
+            continue;
+        };
+        if user_id == author_id {
+            continue;
+        }
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO comment_mentions (comment_id, user_id) VALUES (?1, ?2)",
+            params![comment_id, user_id],
+        )?;
+        if inserted == 1 {
+            mentioned.push(user_id);
+        }
+    }
+    Ok(mentioned)
+}
+
+// =============================================================================
+// Watch Service
+// =============================================================================
+
+pub struct WatchService {
+    database: Arc<Database>,
+}
+
+impl WatchService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Star a record for the current user; watching twice is a no-op
+    pub fn watch(&self, context: &RequestContext, entity_type: WatchEntityType, entity_id: i64) -> AppResult<Watch> {
+        let user_id = context.current_user()?.user_id;
+        info!("[{}] User {} watching {} {}", context.request_id, user_id, entity_type, entity_id);
+
+        self.database.with_transaction(|conn| {
+            let exists: bool = conn.query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?1)", entity_type.table()),
+                params![entity_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Err(AppError::RecordNotFound {
+                    entity: entity_type.to_string(),
+                    field: "id".to_string(),
+                    value: entity_id.to_string(),
+                });
+            }
+
+            conn.execute(
+                "INSERT OR IGNORE INTO watches (user_id, entity_type, entity_id) VALUES (?1, ?2, ?3)",
+                params![user_id, entity_type.to_string(), entity_id],
+            )?;
+            conn.query_row(
+                &format!("{} WHERE w.user_id = ?1 AND w.entity_type = ?2 AND w.entity_id = ?3", WATCH_SELECT),
+                params![user_id, entity_type.to_string(), entity_id],
+                row_to_watch,
+            ).map_err(AppError::from)
+        })
+    }
+
+    /// Remove a star. Returns whether the record was being watched.
+    pub fn unwatch(&self, context: &RequestContext, entity_type: WatchEntityType, entity_id: i64) -> AppResult<bool> {
+        let user_id = context.current_user()?.user_id;
+        info!("[{}] User {} no longer watching {} {}", context.request_id, user_id, entity_type, entity_id);
+
+        self.database.with_transaction(|conn| {
+            let removed = conn.execute(
+                "DELETE FROM watches WHERE user_id = ?1 AND entity_type = ?2 AND entity_id = ?3",
+                params![user_id, entity_type.to_string(), entity_id],
+            )?;
+            Ok(removed > 0)
+        })
+    }
+
+    /// Records watched by a user, most recently starred first. Watches on
+    /// deleted records are left out.
+    pub fn get_watches(&self, user_id: i64) -> AppResult<Vec<Watch>> {
+        debug!("Fetching watches for user: {}", user_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<Watch>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT * FROM ({} WHERE w.user_id = ?1) WHERE label IS NOT NULL ORDER BY created_at DESC",
+                WATCH_SELECT
+            ))?;
+            let watches = stmt
+                .query_map(params![user_id], row_to_watch)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(watches)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Notifications for watchers of an inspection, or of its asset, after
+    /// its status changed from `previous`
+    pub fn inspection_status_changed(
+        &self,
+        context: &RequestContext,
+        inspection: &Inspection,
+        previous: &InspectionStatus,
+    ) -> AppResult<Vec<WatchNotification>> {
+        if inspection.status == *previous {
+            return Ok(Vec::new());
+        }
+        let message = format!(
+            "{} inspection {} changed from {} to {}",
+            inspection.inspection_type, inspection.id, previous, inspection.status
+        );
+        self.inspection_notifications(context, inspection.id, inspection.asset_id, WatchEventKind::StatusChanged, message)
+    }
+
+    /// Notifications for watchers of an asset after its status changed from
+    /// `previous`
+    pub fn asset_status_changed(
+        &self,
+        context: &RequestContext,
+        asset: &Asset,
+        previous: &AssetStatus,
+    ) -> AppResult<Vec<WatchNotification>> {
+        if asset.status == *previous {
+            return Ok(Vec::new());
+        }
+        let conn = self.database.get_connection()?;
+        let result = watchers(&conn, "entity_type = 'Asset' AND entity_id = ?1", params![asset.id]);
+        self.database.return_connection(conn);
+
+        let actor_id = context.current_user().map(|u| u.user_id).ok();
+        Ok(result?
+            .into_iter()
+            .filter(|user_id| Some(*user_id) != actor_id)
+            .map(|user_id| WatchNotification {
+                user_id,
+                kind: WatchEventKind::StatusChanged,
+                entity_type: WatchEntityType::Asset,
+                entity_id: asset.id,
+                asset_id: asset.id,
+                message: format!("{} changed from {} to {}", asset.asset_name, previous, asset.status),
+                due_date: None,
+            })
+            .collect())
+    }
+
+    /// Notifications for watchers of an inspection, or of its asset, when an
+    /// item with a finding is recorded
+    pub fn finding_recorded(&self, context: &RequestContext, item: &InspectionItem) -> AppResult<Vec<WatchNotification>> {
+        if !item.has_finding() {
+            return Ok(Vec::new());
+        }
+        let conn = self.database.get_connection()?;
+        let asset_id: AppResult<i64> = conn.query_row(
+            "SELECT asset_id FROM inspections WHERE id = ?1",
+            params![item.inspection_id],
+            |row| row.get(0),
+        ).map_err(AppError::from);
+        self.database.return_connection(conn);
+
+        let message = match &item.severity {
+            Some(severity) => format!("New {} finding on {}: {}", severity, item.item_name,
+                                      item.finding.as_deref().unwrap_or_default()),
+            None => format!("New finding on {}: {}", item.item_name, item.finding.as_deref().unwrap_or_default()),
+        };
+        self.inspection_notifications(context, item.inspection_id, asset_id?, WatchEventKind::NewFinding, message)
+    }
+
+    /// Open inspections on the user's watched records, and insurance or
+    /// registration renewals on watched assets, that are due within `days`,
+    /// including overdue ones, soonest first
+    pub fn get_due_soon(&self, user_id: i64, days: i64) -> AppResult<Vec<WatchNotification>> {
+        debug!("Fetching watched inspections due within {} days for user: {}", days, user_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<WatchNotification>> {
+            let now = Utc::now();
+            let mut stmt = conn.prepare(
+                "SELECT DISTINCT i.id, i.asset_id, i.inspection_type, a.asset_name, i.scheduled_date
+                 FROM watches w
+                 JOIN inspections i ON (w.entity_type = 'Inspection' AND i.id = w.entity_id)
+                                    OR (w.entity_type = 'Asset' AND i.asset_id = w.entity_id)
+                 JOIN assets a ON i.asset_id = a.id
+                 WHERE w.user_id = ?1
+                   AND i.status IN ('Scheduled', 'In Progress')
+                   AND i.scheduled_date IS NOT NULL
+                   AND datetime(i.scheduled_date) <= datetime(?2)
+                 ORDER BY datetime(i.scheduled_date), i.id"
+            )?;
+            let due = stmt
+                .query_map(params![user_id, now + chrono::Duration::days(days)], |row| {
+                    let id: i64 = row.get(0)?;
+                    let inspection_type: String = row.get(2)?;
+                    let asset_name: String = row.get(3)?;
+                    let due_date: DateTime<Utc> = row.get(4)?;
+                    let message = if due_date < now {
+                        format!("{} inspection of {} is overdue", inspection_type, asset_name)
+                    } else {
+                        format!("{} inspection of {} is due {}", inspection_type, asset_name, due_date.format("%Y-%m-%d"))
+                    };
+                    Ok(WatchNotification {
+                        user_id,
+                        kind: WatchEventKind::DueSoon,
+                        entity_type: WatchEntityType::Inspection,
+                        entity_id: id,
+                        asset_id: row.get(1)?,
+                        message,
+                        due_date: Some(due_date),
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let today = now.date_naive();
+            let mut stmt = conn.prepare(
+                "SELECT r.asset_id, r.kind, r.reference_number, r.renewal_date, a.asset_name
+                 FROM watches w
+                 JOIN asset_records r ON w.entity_type = 'Asset' AND r.asset_id = w.entity_id
+                 JOIN assets a ON r.asset_id = a.id
+                 WHERE w.user_id = ?1
+                   AND a.deleted_at IS NULL
+                   AND r.renewal_date <= ?2"
+            )?;
+            let renewals = stmt
+                .query_map(params![user_id, today + chrono::Duration::days(days)], |row| {
+                    let asset_id: i64 = row.get(0)?;
+                    let kind: String = row.get(1)?;
+                    let reference_number: String = row.get(2)?;
+                    let renewal_date: NaiveDate = row.get(3)?;
+                    let asset_name: String = row.get(4)?;
+                    let message = if renewal_date < today {
+                        format!("{} {} for {} has lapsed", kind, reference_number, asset_name)
+                    } else {
+                        format!("{} {} for {} is due for renewal {}", kind, reference_number, asset_name, renewal_date)
+                    };
+                    Ok(WatchNotification {
+                        user_id,
+                        kind: WatchEventKind::RenewalDue,
+                        entity_type: WatchEntityType::Asset,
+                        entity_id: asset_id,
+                        asset_id,
+                        message,
+                        due_date: renewal_date.and_hms_opt(0, 0, 0).map(|d| d.and_utc()),
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut due: Vec<WatchNotification> = due.into_iter().chain(renewals).collect();
+            due.sort_by_key(|notification| notification.due_date);
+            Ok(due)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    fn inspection_notifications(
+        &self,
+        context: &RequestContext,
+        inspection_id: i64,
+        asset_id: i64,
+        kind: WatchEventKind,
+        message: String,
+    ) -> AppResult<Vec<WatchNotification>> {
+        let conn = self.database.get_connection()?;
+        let result = watchers(
+            &conn,
+            "(entity_type = 'Inspection' AND entity_id = ?1) OR (entity_type = 'Asset' AND entity_id = ?2)",
+            params![inspection_id, asset_id],
+        );
+        self.database.return_connection(conn);
+
+        let actor_id = context.current_user().map(|u| u.user_id).ok();
+        Ok(result?
+            .into_iter()
+            .filter(|user_id| Some(*user_id) != actor_id)
+            .map(|user_id| WatchNotification {
+                user_id,
+                kind,
+                entity_type: WatchEntityType::Inspection,
+                entity_id: inspection_id,
+                asset_id,
+                message: message.clone(),
+                due_date: None,
+            })
+            .collect())
+    }
+}
+
+const WATCH_SELECT: &str =
+    "SELECT w.user_id, w.entity_type, w.entity_id,
+            CASE w.entity_type
+                WHEN 'Asset' THEN (SELECT asset_name FROM assets WHERE id = w.entity_id)
+                ELSE (SELECT i.inspection_type || ' inspection of ' || a.asset_name
+                      FROM inspections i JOIN assets a ON i.asset_id = a.id WHERE i.id = w.entity_id)
+            END AS label,
+            w.created_at
+     FROM watches w";
+
+fn row_to_watch(row: &Row) -> rusqlite::Result<Watch> {
+    Ok(Watch {
+        user_id: row.get(0)?,
+        entity_type: row.get::<_, String>(1)?.parse().unwrap_or(WatchEntityType::Asset),
+        entity_id: row.get(2)?,
+        label: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+/// Active users with a watch matching `condition`
+fn watchers(conn: &Connection, condition: &str, params: &[&dyn rusqlite::ToSql]) -> AppResult<Vec<i64>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT DISTINCT w.user_id FROM watches w JOIN users u ON w.user_id = u.id
+         WHERE u.is_active = 1 AND ({}) ORDER BY w.user_id",
+        condition
+    ))?;
+    let user_ids = stmt
+        .query_map(params, |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<i64>>>()?;
+    Ok(user_ids)
+}
+
+// =============================================================================
+// Activity Service
+// =============================================================================
+
+/// Inspection, maintenance and comment events as rows of
+/// `(id, kind, action, occurred_at, actor_id, actor_name, entity_type,
+/// entity_id, asset_id, summary)`. IDs zero-pad the record ID so that they
+/// sort the same way in SQL and in Rust.
+const ACTIVITY_EVENTS_SQL: &str = "
+    SELECT 'inspection:' || printf('%010d', i.id) || ':created', 'Inspection', 'created', i.created_at,
+           i.inspector_id, u.first_name || ' ' || u.last_name, 'inspection', i.id, i.asset_id,
+           i.inspection_type || ' inspection scheduled for ' || a.asset_name
+    FROM inspections i JOIN assets a ON i.asset_id = a.id LEFT JOIN users u ON i.inspector_id = u.id
+    UNION ALL
+    SELECT 'inspection:' || printf('%010d', i.id) || ':completed', 'Inspection', 'completed', i.actual_date,
+           i.inspector_id, u.first_name || ' ' || u.last_name, 'inspection', i.id, i.asset_id,
+           i.inspection_type || ' inspection completed for ' || a.asset_name
+    FROM inspections i JOIN assets a ON i.asset_id = a.id LEFT JOIN users u ON i.inspector_id = u.id
+    WHERE i.status = 'Completed' AND i.actual_date IS NOT NULL
+    UNION ALL
+    SELECT 'maintenance:' || printf('%010d', m.id) || ':created', 'Maintenance', 'created', m.created_at,
+           NULL, m.performed_by, 'maintenance', m.id, m.asset_id,
+           m.maintenance_type || ' maintenance on ' || a.asset_name || ': ' || m.description
+    FROM maintenance_records m JOIN assets a ON m.asset_id = a.id
+    UNION ALL
+    SELECT 'maintenance:' || printf('%010d', m.id) || ':completed', 'Maintenance', 'completed', m.completed_date,
+           NULL, m.performed_by, 'maintenance', m.id, m.asset_id,
+           m.maintenance_type || ' maintenance completed on ' || a.asset_name
+    FROM maintenance_records m JOIN assets a ON m.asset_id = a.id
+    WHERE m.status = 'Completed' AND m.completed_date IS NOT NULL
+    UNION ALL
+    SELECT 'comment:' || printf('%010d', c.id), 'Comment',
+           CASE WHEN c.parent_id IS NULL THEN 'commented' ELSE 'replied' END, c.created_at,
+           c.author_id, u.first_name || ' ' || u.last_name, c.entity_type, c.entity_id,
+           CASE c.entity_type
+               WHEN 'Asset' THEN c.entity_id
+               WHEN 'Inspection' THEN (SELECT asset_id FROM inspections WHERE id = c.entity_id)
+               WHEN 'Finding' THEN (SELECT i.asset_id FROM inspection_items ii
+                                    JOIN inspections i ON ii.inspection_id = i.id WHERE ii.id = c.entity_id)
+           END,
+           c.entity_type || ' ' || c.entity_id || ': ' || substr(c.body, 1, 140)
+    FROM comments c JOIN users u ON c.author_id = u.id
+    WHERE c.is_deleted = 0";
+
+pub struct ActivityService {
+    database: Arc<Database>,
+}
+
+impl ActivityService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Resolve a feed filter into the asset and location IDs it covers. A
+    /// location covers its whole subtree.
+    pub fn resolve_scope(&self, filter: &ActivityFilter) -> AppResult<ActivityScope> {
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<ActivityScope> {
+            let mut scope = ActivityScope {
+                user_id: filter.user_id,
+                ..ActivityScope::default()
+            };
+
+            if let Some(asset_id) = filter.asset_id {
+                let exists: bool = conn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM assets WHERE id = ?1)",
+                    params![asset_id],
+                    |row| row.get(0),
+                )?;
+                if !exists {
+                    return Err(AppError::RecordNotFound {
+                        entity: "Asset".to_string(),
+                        field: "id".to_string(),
+                        value: asset_id.to_string(),
+                    });
+                }
+                scope.asset_ids = Some(HashSet::from([asset_id]));
+            }
+
+            if let Some(location_id) = filter.location_id {
+                let mut stmt = conn.prepare(&format!("{} SELECT id FROM subtree", LOCATION_SUBTREE_CTE))?;
+                let location_ids = stmt
+                    .query_map(params![location_id, MAX_LOCATION_DEPTH as i64], |row| row.get(0))?
+                    .collect::<rusqlite::Result<HashSet<i64>>>()?;
+                if location_ids.is_empty() {
+                    return Err(AppError::RecordNotFound {
+                        entity: "Location".to_string(),
+                        field: "id".to_string(),
+                        value: location_id.to_string(),
+                    });
+                }
+
+                let mut stmt = conn.prepare(&format!(
+                    "{} SELECT a.id FROM assets a JOIN subtree s ON a.location_id = s.id",
+                    LOCATION_SUBTREE_CTE
+                ))?;
+                let location_assets = stmt
+                    .query_map(params![location_id, MAX_LOCATION_DEPTH as i64], |row| row.get(0))?
+                    .collect::<rusqlite::Result<HashSet<i64>>>()?;
+
+                scope.asset_ids = Some(match scope.asset_ids.take() {
+                    Some(asset_ids) => asset_ids.intersection(&location_assets).copied().collect(),
+                    None => location_assets,
+                });
+                scope.location_ids = Some(location_ids);
+            }
+
+            Ok(scope)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Newest inspection, maintenance and comment events in scope that come
+    /// after `cursor`, at most `limit`
+    pub fn get_events(
+        &self,
+        scope: &ActivityScope,
+        cursor: Option<&ActivityCursor>,
+        limit: usize,
+    ) -> AppResult<Vec<ActivityItem>> {
+        debug!("Fetching activity events (limit {})", limit);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<ActivityItem>> {
+            let asset_ids = scope.asset_ids.as_ref()
+                .map(|ids| serde_json::to_string(&ids.iter().collect::<Vec<_>>()))
+                .transpose()?;
+
+            let mut stmt = conn.prepare(&format!(
+                "WITH e (id, kind, action, occurred_at, actor_id, actor_name, entity_type, entity_id, asset_id, summary)
+                 AS ({})
+                 SELECT * FROM e
+                 WHERE e.occurred_at IS NOT NULL
+                   AND (?1 IS NULL OR e.asset_id IN (SELECT value FROM json_each(?1)))
+                   AND (?2 IS NULL OR e.actor_id = ?2
+                        OR (e.kind = 'Maintenance'
+                            AND e.actor_name IN (SELECT first_name || ' ' || last_name FROM users WHERE id = ?2
+                                                 UNION SELECT username FROM users WHERE id = ?2)))
+                   AND (?3 IS NULL
+                        OR CAST(strftime('%s', e.occurred_at) AS INTEGER) < ?3
+                        OR (CAST(strftime('%s', e.occurred_at) AS INTEGER) = ?3 AND e.id < ?4))
+                 ORDER BY CAST(strftime('%s', e.occurred_at) AS INTEGER) DESC, e.id DESC
+                 LIMIT ?5",
+                ACTIVITY_EVENTS_SQL
+            ))?;
+
+            let items = stmt
+                .query_map(
+                    params![
+                        asset_ids,
+                        scope.user_id,
+                        cursor.map(|c| c.timestamp),
+                        cursor.map(|c| c.id.as_str()),
+                        limit as i64,
+                    ],
+                    |row| {
+                        let kind = match row.get::<_, String>(1)?.as_str() {
+                            "Inspection" => ActivityKind::Inspection,
+                            "Maintenance" => ActivityKind::Maintenance,
+                            _ => ActivityKind::Comment,
+                        };
+                        Ok(ActivityItem {
+                            id: row.get(0)?,
+                            kind,
+                            action: row.get(2)?,
+                            occurred_at: row.get(3)?,
+                            actor_id: row.get(4)?,
+                            actor_name: row.get(5)?,
+                            entity_type: row.get(6)?,
+                            entity_id: row.get(7)?,
+                            asset_id: row.get(8)?,
+                            summary: row.get(9)?,
+                        })
+                    },
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(items)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+}
+
+// =============================================================================
+// Recycle Bin Service
+// =============================================================================
+
+/// Environment variable overriding how many days deleted records stay
+/// restorable
+pub const RECYCLE_RETENTION_ENV: &str = "CRANEPRO_RECYCLE_RETENTION_DAYS";
+
+/// Rows of one table captured when a record was deleted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SnapshotTable {
+    table: String,
+    rows: Vec<serde_json::Map<String, JsonValue>>,
+}
+
+pub struct RecycleBinService {
+    database: Arc<Database>,
+    retention_days: i64,
+}
+
+impl RecycleBinService {
+    pub fn new(database: Arc<Database>, retention_days: i64) -> Self {
+        Self { database, retention_days: retention_days.max(0) }
+    }
+
+    pub fn from_env(database: Arc<Database>) -> Self {
+        let retention_days = std::env::var(RECYCLE_RETENTION_ENV)
+            .ok()
+            .and_then(|days| days.trim().parse().ok())
+            .unwrap_or(DEFAULT_RECYCLE_RETENTION_DAYS);
+        Self::new(database, retention_days)
+    }
+
+    pub fn retention_days(&self) -> i64 {
+        self.retention_days
+    }
+
+    /// Soft-deleted records and removed records still within the retention
+    /// window, newest first
+    pub fn list_deleted(&self, entity_types: &[RecycleEntityType]) -> AppResult<Vec<DeletedRecord>> {
+        debug!("Listing deleted records for {} entity types", entity_types.len());
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<DeletedRecord>> {
+            let cutoff = Utc::now() - chrono::Duration::days(self.retention_days);
+            let types = serde_json::to_string(&entity_types.iter().map(|t| t.to_string()).collect::<Vec<_>>())?;
+            let mut stmt = conn.prepare(
+                "SELECT r.entity_type, r.entity_id, r.label, r.deleted_by,
+                        u.first_name || ' ' || u.last_name, r.deleted_at
+                 FROM recycle_bin r LEFT JOIN users u ON r.deleted_by = u.id
+                 WHERE r.entity_type IN (SELECT value FROM json_each(?1))
+                   AND datetime(r.deleted_at) > datetime(?2)
+                 ORDER BY r.deleted_at DESC, r.id DESC"
+            )?;
+            let mut records = stmt
+                .query_map(params![types, cutoff], |row| {
+                    let deleted_at: DateTime<Utc> = row.get(5)?;
+                    Ok(DeletedRecord {
+                        entity_type: row.get::<_, String>(0)?.parse().unwrap_or(RecycleEntityType::Asset),
+                        entity_id: row.get(1)?,
+                        label: row.get(2)?,
+                        deleted_by: row.get(3)?,
+                        deleted_by_name: row.get(4)?,
+                        deleted_at,
+                        purge_after: Some(deleted_at + chrono::Duration::days(self.retention_days)),
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            drop(stmt);
+
+            for &entity_type in entity_types {
+                let Some(label) = entity_type.soft_delete_label() else { continue };
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT t.id, {}, t.deleted_by, u.first_name || ' ' || u.last_name, t.deleted_at
+                     FROM {} t LEFT JOIN users u ON t.deleted_by = u.id
+                     WHERE t.deleted_at IS NOT NULL",
+                    label, entity_type.table()
+                ))?;
+                let deleted = stmt.query_map([], |row| {
+                    Ok(DeletedRecord {
+                        entity_type,
+                        entity_id: row.get(0)?,
+                        label: row.get(1)?,
+                        deleted_by: row.get(2)?,
+                        deleted_by_name: row.get(3)?,
+                        deleted_at: row.get(4)?,
+                        purge_after: None,
+                    })
+                })?;
+                for record in deleted {
+                    records.push(record?);
+                }
+            }
+            records.sort_by_key(|record| std::cmp::Reverse(record.deleted_at));
+            Ok(records)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Put a deleted record back: a soft-deleted record is undeleted in
+    /// place, anything else is rebuilt from its most recent snapshot with the
+    /// related rows that were removed along with it
+    pub fn restore(&self, context: &RequestContext, entity_type: RecycleEntityType, entity_id: i64) -> AppResult<RestoreResult> {
+        info!("[{}] Restoring {} {}", context.request_id, entity_type, entity_id);
+        let cutoff = Utc::now() - chrono::Duration::days(self.retention_days);
+
+        self.database.with_transaction(|conn| {
+            if let Some(label) = soft_deleted_label(conn, entity_type, entity_id)? {
+                restore_soft_deleted(conn, entity_type, entity_id)?;
+                info!("[{}] Restored {} {}", context.request_id, entity_type, entity_id);
+                return Ok(RestoreResult { entity_type, entity_id, label, restored_related: 0, skipped_related: 0 });
+            }
+
+            let entry: Option<(i64, String, String)> = conn.query_row(
+                "SELECT id, label, snapshot FROM recycle_bin
+                 WHERE entity_type = ?1 AND entity_id = ?2 AND datetime(deleted_at) > datetime(?3)
+                 ORDER BY deleted_at DESC, id DESC LIMIT 1",
+                params![entity_type.to_string(), entity_id, cutoff],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            ).optional()?;
+            let Some((bin_id, label, snapshot)) = entry else {
+                return Err(AppError::RecordNotFound {
+                    entity: format!("Deleted {}", entity_type),
+                    field: "id".to_string(),
+                    value: entity_id.to_string(),
+                });
+            };
+
+            let exists: bool = conn.query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?1)", entity_type.table()),
+                params![entity_id],
+                |row| row.get(0),
+            )?;
+            if exists {
+                return Err(AppError::DuplicateRecord {
+                    entity: entity_type.to_string(),
+                    field: "id".to_string(),
+                    value: entity_id.to_string(),
+                });
+            }
+
+            let tables: Vec<SnapshotTable> = serde_json::from_str(&snapshot)?;
+            let mut tables = tables.into_iter();
+            if let Some(record) = tables.next() {
+                for row in &record.rows {
+                    insert_snapshot_row(conn, &record.table, row).map_err(|e| AppError::validation(
+                        "entity_id",
+                        format!("{} {} cannot be restored: {}", entity_type, entity_id, e),
+                    ))?;
+                }
+            }
+
+            let (mut restored_related, mut skipped_related) = (0, 0);
+            for related in tables {
+                for row in &related.rows {
+                    match insert_snapshot_row(conn, &related.table, row) {
+                        Ok(()) => restored_related += 1,
+                        Err(e) => {
+                            debug!("Skipping {} row while restoring {} {}: {}", related.table, entity_type, entity_id, e);
+                            skipped_related += 1;
+                        }
+                    }
+                }
+            }
+
+            conn.execute("DELETE FROM recycle_bin WHERE id = ?1", params![bin_id])?;
+            info!("[{}] Restored {} {} ({} related rows, {} skipped)", context.request_id,
+                  entity_type, entity_id, restored_related, skipped_related);
+
+            Ok(RestoreResult { entity_type, entity_id, label, restored_related, skipped_related })
+        })
+    }
+
+    /// Permanently drop entries older than the retention window. Returns the
+    /// number of records purged.
+    pub fn purge_expired(&self) -> AppResult<usize> {
+        let cutoff = Utc::now() - chrono::Duration::days(self.retention_days);
+        let purged = self.database.with_transaction(|conn| {
+            Ok(conn.execute(
+                "DELETE FROM recycle_bin WHERE datetime(deleted_at) <= datetime(?1)",
+                params![cutoff],
+            )?)
+        })?;
+        if purged > 0 {
+            info!("Purged {} records from the recycle bin older than {} days", purged, self.retention_days);
+        }
+        Ok(purged)
+    }
+}
+
+/// Snapshot a record and its cascaded rows into the recycle bin. Call inside
+/// the deleting transaction, before the DELETE.
+fn move_to_recycle_bin(
+    conn: &Connection,
+    context: &RequestContext,
+    entity_type: RecycleEntityType,
+    entity_id: i64,
+) -> AppResult<()> {
+    let record = snapshot_rows(conn, entity_type.table(), "id", entity_id)?;
+    if record.rows.is_empty() {
+        return Err(AppError::RecordNotFound {
+            entity: entity_type.to_string(),
+            field: "id".to_string(),
+            value: entity_id.to_string(),
+        });
+    }
+
+    let text = |column: &str| record.rows[0].get(column).and_then(JsonValue::as_str).unwrap_or_default().to_string();
+    let label = match entity_type {
+        RecycleEntityType::Asset => format!("{} - {}", text("asset_number"), text("asset_name")),
+        RecycleEntityType::Inspection => format!("{} inspection #{}", text("inspection_type"), entity_id),
+        RecycleEntityType::Location | RecycleEntityType::Team => text("name"),
+        RecycleEntityType::User => text("username"),
+    };
+
+    let mut tables = vec![record];
+    for (table, column) in entity_type.dependents() {
+        tables.push(snapshot_rows(conn, table, column, entity_id)?);
+    }
+
+    conn.execute(
+        "INSERT INTO recycle_bin (entity_type, entity_id, label, snapshot, deleted_by) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            entity_type.to_string(),
+            entity_id,
+            label,
+            serde_json::to_string(&tables)?,
+            context.current_user().map(|u| u.user_id).ok(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Mark a record deleted, hiding it from normal queries. Call inside a
+/// transaction.
+fn soft_delete(
+    conn: &Connection,
+    context: &RequestContext,
+    entity_type: RecycleEntityType,
+    entity_id: i64,
+) -> AppResult<()> {
+    let updated = conn.execute(
+        &format!(
+            "UPDATE {} SET deleted_at = CURRENT_TIMESTAMP, deleted_by = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            entity_type.table()
+        ),
+        params![context.current_user().map(|u| u.user_id).ok(), entity_id],
+    )?;
+    if updated == 0 {
+        return Err(AppError::RecordNotFound {
+            entity: entity_type.to_string(),
+            field: "id".to_string(),
+            value: entity_id.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Label of a soft-deleted record, or `None` if the record is not soft deleted
+fn soft_deleted_label(conn: &Connection, entity_type: RecycleEntityType, entity_id: i64) -> AppResult<Option<String>> {
+    let Some(label) = entity_type.soft_delete_label() else {
+        return Ok(None);
+    };
+    Ok(conn.query_row(
+        &format!("SELECT {} FROM {} t WHERE t.id = ?1 AND t.deleted_at IS NOT NULL", label, entity_type.table()),
+        params![entity_id],
+        |row| row.get(0),
+    ).optional()?)
+}
+
+fn require_soft_deleted(conn: &Connection, entity_type: RecycleEntityType, entity_id: i64) -> AppResult<String> {
+    soft_deleted_label(conn, entity_type, entity_id)?.ok_or_else(|| AppError::RecordNotFound {
+        entity: format!("Deleted {}", entity_type),
+        field: "id".to_string(),
+        value: entity_id.to_string(),
+    })
+}
+
+/// Clear the soft delete of a record. An inspection can only come back while
+/// its asset is not deleted.
+fn restore_soft_deleted(conn: &Connection, entity_type: RecycleEntityType, entity_id: i64) -> AppResult<String> {
+    let label = require_soft_deleted(conn, entity_type, entity_id)?;
+    if entity_type == RecycleEntityType::Inspection {
+        let asset_deleted: bool = conn.query_row(
+            "SELECT a.deleted_at IS NOT NULL FROM inspections i JOIN assets a ON i.asset_id = a.id WHERE i.id = ?1",
+            params![entity_id],
+            |row| row.get(0),
+        )?;
+        if asset_deleted {
+            return Err(AppError::validation(
+                "entity_id",
+                format!("Inspection {} belongs to a deleted asset; restore the asset first", entity_id),
+            ));
+        }
+    }
+
+    conn.execute(
+        &format!("UPDATE {} SET deleted_at = NULL, deleted_by = NULL WHERE id = ?1", entity_type.table()),
+        params![entity_id],
+    )?;
+    Ok(label)
+}
+
+/// Permanently delete a soft-deleted record and the rows it owns. Foreign
+/// keys from other records and legal holds block the purge.
+fn purge_soft_deleted(conn: &Connection, entity_type: RecycleEntityType, entity_id: i64) -> AppResult<String> {
+    let label = require_soft_deleted(conn, entity_type, entity_id)?;
+    match entity_type {
+        RecycleEntityType::Asset => ensure_not_on_legal_hold(conn, LegalHoldEntityType::Asset, entity_id)?,
+        RecycleEntityType::Inspection => ensure_not_on_legal_hold(conn, LegalHoldEntityType::Inspection, entity_id)?,
+        RecycleEntityType::Location | RecycleEntityType::User | RecycleEntityType::Team => {}
+    }
+    let referenced = |e: rusqlite::Error| match e {
+        rusqlite::Error::SqliteFailure(err, _) if err.code == rusqlite::ErrorCode::ConstraintViolation => {
+            AppError::validation(
+                "entity_id",
+                format!("{} {} is still referenced by other records and cannot be purged", entity_type, entity_id),
+            )
+        }
+        e => e.into(),
+    };
+
+    for (table, column) in entity_type.purged_with() {
+        conn.execute(&format!("DELETE FROM {} WHERE {} = ?1", table, column), params![entity_id])
+            .map_err(referenced)?;
+    }
+    conn.execute(&format!("DELETE FROM {} WHERE id = ?1", entity_type.table()), params![entity_id])
+        .map_err(referenced)?;
+    Ok(label)
+}
+
+fn snapshot_rows(conn: &Connection, table: &str, column: &str, id: i64) -> AppResult<SnapshotTable> {
+    let mut stmt = conn.prepare(&format!("SELECT * FROM {} WHERE {} = ?1", table, column))?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let rows = stmt
+        .query_map(params![id], |row| {
+            let mut values = serde_json::Map::new();
+            for (index, name) in columns.iter().enumerate() {
+                let value = match row.get_ref(index)? {
+                    rusqlite::types::ValueRef::Null => JsonValue::Null,
+                    rusqlite::types::ValueRef::Integer(i) => JsonValue::from(i),
+                    rusqlite::types::ValueRef::Real(f) => JsonValue::from(f),
+                    rusqlite::types::ValueRef::Text(t) => JsonValue::from(String::from_utf8_lossy(t).into_owned()),
+                    rusqlite::types::ValueRef::Blob(b) => JsonValue::from(b.to_vec()),
+                };
+                values.insert(name.clone(), value);
+            }
+            Ok(values)
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(SnapshotTable { table: table.to_string(), rows })
+}
+
+fn insert_snapshot_row(conn: &Connection, table: &str, row: &serde_json::Map<String, JsonValue>) -> rusqlite::Result<()> {
+    let columns: Vec<String> = row.keys().map(|column| format!("\"{}\"", column)).collect();
+    let values = row.values().map(|value| match value {
+        JsonValue::Null => rusqlite::types::Value::Null,
+        JsonValue::Bool(b) => rusqlite::types::Value::Integer(*b as i64),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => rusqlite::types::Value::Integer(i),
+            None => rusqlite::types::Value::Real(n.as_f64().unwrap_or_default()),
+        },
+        JsonValue::String(s) => rusqlite::types::Value::Text(s.clone()),
+        JsonValue::Array(bytes) => rusqlite::types::Value::Blob(
+            bytes.iter().filter_map(|b| b.as_u64().map(|b| b as u8)).collect()
+        ),
+        JsonValue::Object(_) => rusqlite::types::Value::Text(value.to_string()),
+    });
+    let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
+    conn.execute(
+        &format!("INSERT INTO {} ({}) VALUES ({})", table, columns.join(", "), placeholders.join(", ")),
+        params_from_iter(values),
+    )?;
+    Ok(())
+}
+
+// =============================================================================
+// Legal Hold Service
+// =============================================================================
+
+const LEGAL_HOLD_SELECT: &str =
+    "SELECT h.id, h.entity_type, h.entity_id,
+            COALESCE(CASE h.entity_type
+                WHEN 'asset' THEN (SELECT a.asset_number || ' - ' || a.asset_name FROM assets a WHERE a.id = h.entity_id)
+                ELSE (SELECT i.inspection_type || ' inspection #' || i.id FROM inspections i WHERE i.id = h.entity_id)
+            END, h.entity_type || ' #' || h.entity_id),
+            h.reason, h.reference, h.placed_by, TRIM(u.first_name || ' ' || u.last_name), h.placed_at,
+            h.released_by, h.released_at, h.release_reason,
+            CASE h.entity_type
+                WHEN 'asset' THEN (SELECT COUNT(*) FROM inspections i WHERE i.asset_id = h.entity_id)
+                ELSE 0
+            END,
+            CASE h.entity_type
+                WHEN 'asset' THEN (SELECT COUNT(*) FROM media_files m JOIN inspections i ON m.inspection_id = i.id
+                                   WHERE i.asset_id = h.entity_id)
+                ELSE (SELECT COUNT(*) FROM media_files m WHERE m.inspection_id = h.entity_id)
+            END
+     FROM legal_holds h LEFT JOIN users u ON u.id = h.placed_by";
+
+pub struct LegalHoldService {
+    database: Arc<Database>,
+}
+
+impl LegalHoldService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Place a record under legal hold. A record can have one active hold;
+    /// soft-deleted records can be held too, which stops them being purged.
+    pub fn place_hold(&self, context: &RequestContext, input: LegalHoldInput) -> AppResult<LegalHold> {
+        info!("[{}] Placing legal hold on {} {}", context.request_id, input.entity_type, input.entity_id);
+        input.validate()?;
+        let user_id = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            let table = match input.entity_type {
+                LegalHoldEntityType::Asset => "assets",
+                LegalHoldEntityType::Inspection => "inspections",
+            };
+            let exists: bool = conn.query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?1)", table),
+                params![input.entity_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Err(AppError::RecordNotFound {
+                    entity: input.entity_type.to_string(),
+                    field: "id".to_string(),
+                    value: input.entity_id.to_string(),
+                });
+            }
+
+            let id = conn.query_row(
+                "INSERT INTO legal_holds (entity_type, entity_id, reason, reference, placed_by)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 RETURNING id",
+                params![
+                    input.entity_type.to_string(),
+                    input.entity_id,
+                    input.reason.trim(),
+                    input.reference.as_deref().map(str::trim).filter(|r| !r.is_empty()),
+                    user_id,
+                ],
+                |row| row.get::<_, i64>(0),
+            ).map_err(|e| match e {
+                rusqlite::Error::SqliteFailure(err, _) if err.code == rusqlite::ErrorCode::ConstraintViolation => {
+                    AppError::DuplicateRecord {
+                        entity: "LegalHold".to_string(),
+                        field: "entity_id".to_string(),
+                        value: format!("{} {}", input.entity_type, input.entity_id),
+                    }
+                }
+                e => e.into(),
+            })?;
+            legal_hold_by_id(conn, id)
+        })
+    }
+
+    /// Release an active hold. The hold stays in the register with who
+    /// released it and why.
+    pub fn release_hold(&self, context: &RequestContext, id: i64, reason: String) -> AppResult<LegalHold> {
+        info!("[{}] Releasing legal hold {}", context.request_id, id);
+        if reason.trim().is_empty() {
+            return Err(AppError::validation("reason", "A reason for releasing the hold is required"));
+        }
+        if reason.len() > MAX_LEGAL_HOLD_REASON_LENGTH {
+            return Err(AppError::validation(
+                "reason",
+                format!("Reason cannot exceed {} characters", MAX_LEGAL_HOLD_REASON_LENGTH),
+            ));
+        }
+        let user_id = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            let hold = legal_hold_by_id(conn, id)?;
+            if !hold.is_active() {
+                return Err(AppError::validation("id", format!("Legal hold {} has already been released", id)));
+            }
+            conn.execute(
+                "UPDATE legal_holds SET released_by = ?1, released_at = CURRENT_TIMESTAMP, release_reason = ?2
+                 WHERE id = ?3",
+                params![user_id, reason.trim(), id],
+            )?;
+            legal_hold_by_id(conn, id)
+        })
+    }
+
+    /// The hold register: active holds, and released ones if asked for,
+    /// newest first
+    pub fn get_holds(&self, include_released: bool) -> AppResult<Vec<LegalHold>> {
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<LegalHold>> {
+            let mut stmt = conn.prepare(&format!(
+                "{} WHERE ?1 OR h.released_at IS NULL ORDER BY h.placed_at DESC, h.id DESC",
+                LEGAL_HOLD_SELECT
+            ))?;
+            let holds = stmt.query_map(params![include_released], row_to_legal_hold)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(holds)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// File name prefixes of generated reports about held records, which
+    /// report retention must keep
+    pub fn held_report_prefixes(&self) -> AppResult<Vec<String>> {
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<String>> {
+            let mut prefixes: Vec<String> = conn.prepare(
+                "SELECT entity_id FROM legal_holds WHERE entity_type = 'asset' AND released_at IS NULL"
+            )?
+                .query_map([], |row| row.get::<_, i64>(0))?
+                .map(|id| id.map(|id| format!("compliance_{}_", id)))
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut stmt = conn.prepare(
+                "SELECT i.id FROM inspections i
+                 WHERE EXISTS(SELECT 1 FROM legal_holds h WHERE h.released_at IS NULL AND (
+                     (h.entity_type = 'inspection' AND h.entity_id = i.id)
+                     OR (h.entity_type = 'asset' AND h.entity_id = i.asset_id)))"
+            )?;
+            for id in stmt.query_map([], |row| row.get::<_, i64>(0))? {
+                let id = id?;
+                prefixes.push(format!("inspection_{}_", id));
+                prefixes.push(format!("checklist_{}_", id));
+            }
+            Ok(prefixes)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+}
+
+fn row_to_legal_hold(row: &Row) -> rusqlite::Result<LegalHold> {
+    Ok(LegalHold {
+        id: row.get(0)?,
+        entity_type: row.get::<_, String>(1)?.parse().unwrap_or(LegalHoldEntityType::Asset),
+        entity_id: row.get(2)?,
+        label: row.get(3)?,
+        reason: row.get(4)?,
+        reference: row.get(5)?,
+        placed_by: row.get(6)?,
+        placed_by_name: row.get(7)?,
+        placed_at: row.get(8)?,
+        released_by: row.get(9)?,
+        released_at: row.get(10)?,
+        release_reason: row.get(11)?,
+        covered_inspections: row.get(12)?,
+        covered_media: row.get(13)?,
+    })
+}
+
+fn legal_hold_by_id(conn: &Connection, id: i64) -> AppResult<LegalHold> {
+    conn.query_row(&format!("{} WHERE h.id = ?1", LEGAL_HOLD_SELECT), params![id], row_to_legal_hold)
+        .optional()?
+        .ok_or_else(|| AppError::RecordNotFound {
+            entity: "LegalHold".to_string(),
+            field: "id".to_string(),
+            value: id.to_string(),
+        })
+}
+
+/// Refuse to delete a record covered by an active legal hold: an asset held
+/// itself, or an inspection held itself or through its asset. Call inside
+/// the deleting transaction.
+fn ensure_not_on_legal_hold(conn: &Connection, entity_type: LegalHoldEntityType, entity_id: i64) -> AppResult<()> {
+    let sql = match entity_type {
+        LegalHoldEntityType::Asset =>
+            "SELECT reason FROM legal_holds
+             WHERE released_at IS NULL AND entity_type = 'asset' AND entity_id = ?1",
+        LegalHoldEntityType::Inspection =>
+            "SELECT reason FROM legal_holds
+             WHERE released_at IS NULL AND (
+                 (entity_type = 'inspection' AND entity_id = ?1)
+                 OR (entity_type = 'asset' AND entity_id = (SELECT asset_id FROM inspections WHERE id = ?1)))",
+    };
+    let reason: Option<String> = conn.query_row(&format!("{} LIMIT 1", sql), params![entity_id], |row| row.get(0))
+        .optional()?;
+    match reason {
+        Some(reason) => Err(AppError::validation(
+            "entity_id",
+            format!("{} {} is under legal hold and cannot be deleted: {}", entity_type, entity_id, reason),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Refuse to delete a media file belonging to a held inspection, or to a
+/// component of a held asset
+fn ensure_media_not_on_legal_hold(conn: &Connection, media_id: i64) -> AppResult<()> {
+    let owner: Option<(Option<i64>, Option<i64>)> = conn.query_row(
+        "SELECT m.inspection_id, c.asset_id FROM media_files m LEFT JOIN components c ON c.id = m.component_id
+         WHERE m.id = ?1",
+        params![media_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).optional()?;
+    let Some((inspection_id, asset_id)) = owner else {
+        return Ok(());
+    };
+    if let Some(inspection_id) = inspection_id {
+        ensure_not_on_legal_hold(conn, LegalHoldEntityType::Inspection, inspection_id)?;
+    }
+    if let Some(asset_id) = asset_id {
+        ensure_not_on_legal_hold(conn, LegalHoldEntityType::Asset, asset_id)?;
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Bulk Operation Service
+// =============================================================================
+
+pub struct BulkOperationService {
+    database: Arc<Database>,
+}
+
+impl BulkOperationService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Apply one operation to many records in batches. In all-or-nothing
+    /// mode the first failure rolls back every batch; in best-effort mode
+    /// each record is applied on its own and failures are only reported.
+    /// Progress is reported to `tracker` after each batch, and cancellation
+    /// is checked before each one: an all-or-nothing run is then rolled
+    /// back, a best-effort run keeps the batches already applied.
+    #[allow(clippy::too_many_arguments)]
+    pub fn run(
+        &self,
+        context: &RequestContext,
+        entity_type: BulkEntityType,
+        ids: &[i64],
+        operation: BulkOperation,
+        mode: BulkMode,
+        batch_size: Option<usize>,
+        tracker: &OperationHandle,
+    ) -> AppResult<BulkOperationResult> {
+        operation.validate_for(entity_type)?;
+        let mut unique_ids = Vec::with_capacity(ids.len());
+        for id in ids {
+            if !unique_ids.contains(id) {
+                unique_ids.push(*id);
+            }
+        }
+        if unique_ids.is_empty() {
+            return Err(AppError::validation("ids", "At least one record ID is required"));
+        }
+        if unique_ids.len() > MAX_BULK_RECORDS {
+            return Err(AppError::OutOfRange {
+                field: "ids".to_string(),
+                value: unique_ids.len().to_string(),
+                min: "1".to_string(),
+                max: MAX_BULK_RECORDS.to_string(),
+            });
+        }
+        let batch_size = batch_size.unwrap_or(DEFAULT_BULK_BATCH_SIZE).clamp(1, MAX_BULK_BATCH_SIZE);
+        info!("[{}] Bulk {} of {} {} records ({:?}, batches of {})", context.request_id,
+              operation.name(), unique_ids.len(), entity_type, mode, batch_size);
+
+        let total = Some(unique_ids.len() as u64);
+        let mut results = Vec::with_capacity(unique_ids.len());
+        let mut rolled_back = false;
+        let mut cancelled = false;
+        match mode {
+            BulkMode::AllOrNothing => {
+                let mut failure: Option<(i64, String)> = None;
+                let outcome = self.database.with_transaction(|conn| {
+                    for (batch, ids) in unique_ids.chunks(batch_size).enumerate() {
+                        tracker.check_cancelled()?;
+                        for &id in ids {
+                            if let Err(e) = apply_bulk_operation(conn, context, entity_type, &operation, id) {
+                                failure = Some((id, e.to_string()));
+                                return Err(e);
+                            }
+                        }
+                        debug!("[{}] Bulk batch {} applied ({} records)", context.request_id, batch + 1, ids.len());
+                        tracker.set_progress(((batch + 1) * batch_size).min(unique_ids.len()) as u64, total);
+                    }
+                    Ok(())
+                });
+
+                match (outcome, failure) {
+                    (Ok(()), _) => results.extend(unique_ids.iter().map(|&id| BulkRecordResult { id, success: true, error: None })),
+                    (Err(AppError::Cancelled { .. }), None) => {
+                        warn!("[{}] Bulk {} cancelled and rolled back", context.request_id, operation.name());
+                        rolled_back = true;
+                        cancelled = true;
+                        results.extend(unique_ids.iter().map(|&id| BulkRecordResult {
+                            id,
+                            success: false,
+                            error: Some("Cancelled; nothing was changed".to_string()),
+                        }));
+                    }
+                    (Err(_), Some((failed_id, error))) => {
+                        warn!("[{}] Bulk {} rolled back: record {} failed: {}", context.request_id,
+                              operation.name(), failed_id, error);
+                        rolled_back = true;
+                        results.extend(unique_ids.iter().map(|&id| BulkRecordResult {
+                            id,
+                            success: false,
+                            error: Some(if id == failed_id {
+                                error.clone()
+                            } else {
+                                format!("Rolled back because record {} failed", failed_id)
+                            }),
+                        }));
+                    }
+                    (Err(e), None) => return Err(e),
+                }
+            }
+            BulkMode::BestEffort => {
+                for (batch, ids) in unique_ids.chunks(batch_size).enumerate() {
+                    if tracker.is_cancelled() {
+                        warn!("[{}] Bulk {} cancelled after {} of {} records", context.request_id,
+                              operation.name(), results.len(), unique_ids.len());
+                        cancelled = true;
+                        results.extend(unique_ids[results.len()..].iter().map(|&id| BulkRecordResult {
+                            id,
+                            success: false,
+                            error: Some("Cancelled before this record was processed".to_string()),
+                        }));
+                        break;
+                    }
+                    self.database.with_transaction(|conn| {
+                        for &id in ids {
+                            conn.execute_batch("SAVEPOINT bulk_record")?;
+                            match apply_bulk_operation(conn, context, entity_type, &operation, id) {
+                                Ok(()) => {
+                                    conn.execute_batch("RELEASE bulk_record")?;
+                                    results.push(BulkRecordResult { id, success: true, error: None });
+                                }
+                                Err(e) => {
+                                    conn.execute_batch("ROLLBACK TO bulk_record; RELEASE bulk_record")?;
+                                    debug!("Bulk {} skipped record {}: {}", operation.name(), id, e);
+                                    results.push(BulkRecordResult { id, success: false, error: Some(e.to_string()) });
+                                }
+                            }
+                        }
+                        Ok(())
+                    })?;
+                    debug!("[{}] Bulk batch {} applied ({} records)", context.request_id, batch + 1, ids.len());
+                    tracker.set_progress(results.len() as u64, total);
+                }
+            }
+        }
+
+        let succeeded = results.iter().filter(|r| r.success).count();
+        Ok(BulkOperationResult {
+            entity_type,
+            operation,
+            mode,
+            total: results.len(),
+            succeeded,
+            failed: results.len() - succeeded,
+            rolled_back,
+            cancelled,
+            results,
+        })
+    }
+}
+
+fn apply_bulk_operation(
+    conn: &Connection,
+    context: &RequestContext,
+    entity_type: BulkEntityType,
+    operation: &BulkOperation,
+    id: i64,
+) -> AppResult<()> {
+    let not_found = || AppError::RecordNotFound {
+        entity: entity_type.to_string(),
+        field: "id".to_string(),
+        value: id.to_string(),
+    };
+
+    match operation {
+        BulkOperation::Delete => {
+            soft_delete(conn, context, RecycleEntityType::Asset, id)?;
+        }
+        BulkOperation::Archive | BulkOperation::SetStatus { .. } => {
+            let status = match operation {
+                BulkOperation::SetStatus { status } => status.clone(),
+                _ => entity_type.archived_status(),
+            };
+            let updated = conn.execute(
+                &format!("UPDATE {} SET status = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2", entity_type.table()),
+                params![status, id],
+            )?;
+            if updated == 0 {
+                return Err(not_found());
+            }
+        }
+        BulkOperation::Tag { tags } => {
+            let exists: bool = conn.query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?1)", entity_type.table()),
+                params![id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Err(not_found());
+            }
+            let created_by = context.current_user().map(|u| u.user_id).ok();
+            for tag in tags {
+                conn.execute(
+                    "INSERT OR IGNORE INTO record_tags (entity_type, entity_id, tag, created_by) VALUES (?1, ?2, ?3, ?4)",
+                    params![entity_type.to_string(), id, tag.trim(), created_by],
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Data Quality Service
+// =============================================================================
+
+/// Data quality checks as `(check, entity type, query)`. Each query selects
+/// `(id, label, description, suggestion, severity)` for every problem record.
+const DATA_QUALITY_QUERIES: &[(DataQualityCheck, &str, &str)] = &[
+    (DataQualityCheck::AssetWithoutLocation, "Asset",
+     "SELECT a.id, a.asset_number || ' - ' || a.asset_name,
+             'Location ' || COALESCE(a.location_id, '(none)') || ' does not exist',
+             'Assign the asset to an existing location', 'High'
+      FROM assets a LEFT JOIN locations l ON a.location_id = l.id
+      WHERE l.id IS NULL
+      ORDER BY a.id"),
+    (DataQualityCheck::MissingInspector, "Inspection",
+     "SELECT i.id, i.inspection_type || ' inspection of asset ' || i.asset_id,
+             CASE WHEN u.id IS NULL THEN 'Inspector ' || i.inspector_id || ' does not exist'
+                  ELSE 'Inspector ' || u.username || ' is deactivated but the inspection is still ' || i.status END,
+             'Reassign the inspection to an active inspector',
+             CASE WHEN u.id IS NULL THEN 'High' ELSE 'Medium' END
+      FROM inspections i LEFT JOIN users u ON i.inspector_id = u.id
+      WHERE u.id IS NULL OR (u.is_active = 0 AND i.status IN ('Scheduled', 'In Progress'))
+      ORDER BY i.id"),
+    (DataQualityCheck::NegativeCapacity, "Asset",
+     "SELECT id, asset_number || ' - ' || asset_name,
+             'Capacity is ' || capacity || COALESCE(' ' || capacity_unit, ''),
+             'Enter the rated capacity from the nameplate', 'Medium'
+      FROM assets WHERE capacity < 0
+      ORDER BY id"),
+    (DataQualityCheck::OrphanedComponent, "Component",
+     "SELECT c.id, c.component_name,
+             CASE WHEN a.id IS NULL THEN 'Asset ' || c.asset_id || ' does not exist'
+                  WHEN p.id IS NULL THEN 'Parent component ' || c.parent_component_id || ' does not exist'
+                  ELSE 'Parent component ' || p.id || ' belongs to asset ' || p.asset_id || ', not ' || c.asset_id END,
+             CASE WHEN a.id IS NULL THEN 'Attach the component to an existing asset or delete it'
+                  ELSE 'Clear the parent component or choose one on the same asset' END,
+             CASE WHEN a.id IS NULL THEN 'High' ELSE 'Medium' END
+      FROM components c
+      LEFT JOIN assets a ON c.asset_id = a.id
+      LEFT JOIN components p ON c.parent_component_id = p.id
+      WHERE a.id IS NULL
+         OR (c.parent_component_id IS NOT NULL AND (p.id IS NULL OR p.asset_id != c.asset_id))
+      ORDER BY c.id"),
+    (DataQualityCheck::DateInconsistency, "Asset",
+     "SELECT id, asset_number || ' - ' || asset_name,
+             CASE WHEN date(installation_date) < date(manufacture_date)
+                      THEN 'Installed ' || installation_date || ' before it was manufactured ' || manufacture_date
+                  WHEN date(manufacture_date) > date('now') THEN 'Manufacture date ' || manufacture_date || ' is in the future'
+                  ELSE 'Installation date ' || installation_date || ' is in the future' END,
+             'Correct the manufacture and installation dates from the asset records', 'Low'
+      FROM assets
+      WHERE date(installation_date) < date(manufacture_date)
+         OR date(manufacture_date) > date('now')
+         OR date(installation_date) > date('now')
+      ORDER BY id"),
+    (DataQualityCheck::DateInconsistency, "Inspection",
+     "SELECT i.id, i.inspection_type || ' inspection of asset ' || i.asset_id,
+             CASE WHEN i.actual_date IS NULL THEN 'Completed without an inspection date'
+                  ELSE 'Inspection date ' || i.actual_date || ' is in the future' END,
+             'Enter the date the inspection was carried out', 'Medium'
+      FROM inspections i
+      WHERE (i.status = 'Completed' AND i.actual_date IS NULL)
+         OR datetime(i.actual_date) > datetime('now')
+      ORDER BY i.id"),
+    (DataQualityCheck::DateInconsistency, "MaintenanceRecord",
+     "SELECT m.id, m.maintenance_type || ' maintenance of asset ' || m.asset_id,
+             CASE WHEN m.completed_date IS NULL THEN 'Completed without a completion date'
+                  ELSE 'Completion date ' || m.completed_date || ' is in the future' END,
+             'Enter the date the work was completed', 'Low'
+      FROM maintenance_records m
+      WHERE (m.status = 'Completed' AND m.completed_date IS NULL)
+         OR datetime(m.completed_date) > datetime('now')
+      ORDER BY m.id"),
+];
+
+pub struct DataQualityService {
+    database: Arc<Database>,
+}
+
+impl DataQualityService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Look for inconsistent records, typically left behind by legacy
+    /// imports, each with a suggested fix
+    pub fn run_checks(&self, context: &RequestContext) -> AppResult<DataQualityReport> {
+        info!("[{}] Running data quality checks", context.request_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<DataQualityReport> {
+            let mut summary: Vec<DataQualityCheckSummary> = Vec::new();
+            let mut issues: Vec<DataQualityIssue> = Vec::new();
+            let mut truncated = false;
+
+            for (check, entity_type, sql) in DATA_QUALITY_QUERIES {
+                let mut stmt = conn.prepare(sql)?;
+                let mut rows = stmt.query([])?;
+                let mut listed = issues.iter().filter(|issue| issue.check == *check).count();
+                let mut found = 0;
+                while let Some(row) = rows.next()? {
+                    found += 1;
+                    if listed >= MAX_ISSUES_PER_CHECK {
+                        truncated = true;
+                        continue;
+                    }
+                    listed += 1;
+                    issues.push(DataQualityIssue {
+                        check: *check,
+                        severity: row.get::<_, String>(4)?.parse().unwrap_or(Severity::Medium),
+                        entity_type: entity_type.to_string(),
+                        entity_id: row.get(0)?,
+                        label: row.get(1)?,
+                        description: row.get(2)?,
+                        suggestion: row.get(3)?,
+                    });
+                }
+
+                match summary.iter_mut().find(|s| s.check == *check) {
+                    Some(entry) => entry.issue_count += found,
+                    None => summary.push(DataQualityCheckSummary { check: *check, issue_count: found }),
+                }
+            }
+
+            let total_issues = summary.iter().map(|s| s.issue_count).sum();
+            info!("[{}] Data quality checks found {} issues", context.request_id, total_issues);
+            Ok(DataQualityReport {
+                generated_at: Utc::now(),
+                total_issues,
+                summary,
+                issues,
+                truncated,
+            })
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+}
+
+// =============================================================================
+// Search Service
+// =============================================================================
+
+/// Ranked full-text query per entity type. Each returns entity id, asset id,
+/// title, snippet and score (negated `bm25`, so higher is better) for
+/// `MATCH ?1`, limited to `?2` rows; column weights favour identifiers and
+/// names over free text.
+fn search_sql(entity_type: SearchEntityType) -> &'static str {
+    match entity_type {
+        SearchEntityType::Asset => {
+            "SELECT a.id, a.id, a.asset_number || ' - ' || a.asset_name,
+                    snippet(assets_fts, -1, '[', ']', '...', 12),
+                    -bm25(assets_fts, 10.0, 8.0, 3.0, 2.0, 2.0, 5.0, 1.0) AS score
+             FROM assets_fts JOIN assets a ON a.id = assets_fts.rowid
+             WHERE assets_fts MATCH ?1 AND a.deleted_at IS NULL ORDER BY score DESC LIMIT ?2"
+        }
+        SearchEntityType::Component => {
+            "SELECT c.id, c.asset_id, c.component_name || ' (' || a.asset_number || ')',
+                    snippet(components_fts, -1, '[', ']', '...', 12),
+                    -bm25(components_fts, 8.0, 3.0, 2.0, 2.0, 5.0) AS score
+             FROM components_fts
+             JOIN components c ON c.id = components_fts.rowid
+             JOIN assets a ON a.id = c.asset_id
+             WHERE components_fts MATCH ?1 AND a.deleted_at IS NULL ORDER BY score DESC LIMIT ?2"
+        }
+        SearchEntityType::Inspection => {
+            "SELECT i.id, i.asset_id, i.inspection_type || ' inspection of ' || a.asset_number,
+                    snippet(inspections_fts, -1, '[', ']', '...', 12),
+                    -bm25(inspections_fts, 2.0, 2.0, 1.0) AS score
+             FROM inspections_fts
+             JOIN inspections i ON i.id = inspections_fts.rowid
+             JOIN assets a ON a.id = i.asset_id
+             WHERE inspections_fts MATCH ?1 AND i.deleted_at IS NULL AND a.deleted_at IS NULL ORDER BY score DESC LIMIT ?2"
+        }
+        SearchEntityType::InspectionItem => {
+            "SELECT ii.id, i.asset_id, ii.item_name || ' (' || a.asset_number || ')',
+                    snippet(inspection_items_fts, -1, '[', ']', '...', 12),
+                    -bm25(inspection_items_fts, 5.0, 2.0, 3.0, 1.0) AS score
+             FROM inspection_items_fts
+             JOIN inspection_items ii ON ii.id = inspection_items_fts.rowid
+             JOIN inspections i ON i.id = ii.inspection_id
+             JOIN assets a ON a.id = i.asset_id
+             WHERE inspection_items_fts MATCH ?1 AND i.deleted_at IS NULL AND a.deleted_at IS NULL ORDER BY score DESC LIMIT ?2"
+        }
+    }
+}
+
+pub struct SearchService {
+    database: Arc<Database>,
+}
+
+impl SearchService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Search the given entity types, best matches first across all of them
+    pub fn search(&self, text: &str, entity_types: &[SearchEntityType], limit: usize) -> AppResult<Vec<SearchHit>> {
+        debug!("Searching {:?} for: {}", entity_types, text);
+        let Some(query) = search::fts_query(text) else {
+            return Ok(Vec::new());
+        };
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<SearchHit>> {
+            let mut hits = Vec::new();
+            for &entity_type in entity_types {
+                let mut stmt = conn.prepare(search_sql(entity_type))?;
+                let rows = stmt.query_map(params![query, limit as i64], |row| {
+                    Ok(SearchHit {
+                        entity_type,
+                        entity_id: row.get(0)?,
+                        asset_id: row.get(1)?,
+                        title: row.get(2)?,
+                        snippet: row.get(3)?,
+                        score: row.get(4)?,
+                    })
+                })?;
+                for hit in rows {
+                    hits.push(hit?);
+                }
+            }
+            hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+            hits.truncate(limit);
+            Ok(hits)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+}
+
+// =============================================================================
+// Dashboard Service
+// =============================================================================
+
+const OPEN_INSPECTION_COLUMNS: &str =
+    "id, asset_id, inspector_id, inspection_type, compliance_standard, scheduled_date, actual_date,
+     status, overall_condition, checklist_data, notes, ai_analysis_results, created_at, updated_at,
+     time_zone, overdue_at";
+
+/// Condition on an `inspections` row (unaliased) for an open inspection past its due date
+const OVERDUE_CONDITION: &str =
+    "status NOT IN ('Completed', 'Cancelled') AND COALESCE(overdue_at, scheduled_date) < datetime('now')";
+
+pub struct DashboardService {
+    database: Arc<Database>,
+    inspection_service: Arc<InspectionService>,
+    team_service: Arc<TeamService>,
+    report_service: Arc<ReportService>,
+}
+
+impl DashboardService {
+    pub fn new(
+        database: Arc<Database>,
+        inspection_service: Arc<InspectionService>,
+        team_service: Arc<TeamService>,
+        report_service: Arc<ReportService>,
+    ) -> Self {
+        Self { database, inspection_service, team_service, report_service }
+    }
+
+    /// Dashboard for the current user's role
+    pub fn get_summary(&self, context: &RequestContext) -> AppResult<DashboardSummary> {
+        let session = context.current_user()?;
+        debug!("[{}] Building {} dashboard", context.request_id, session.role);
+        match session.role {
+            UserRole::Inspector => self.inspector_dashboard(session.user_id).map(DashboardSummary::Inspector),
+            UserRole::Supervisor => self.supervisor_dashboard(session.user_id).map(DashboardSummary::Supervisor),
+            UserRole::Administrator | UserRole::SuperAdmin => self.admin_dashboard().map(DashboardSummary::Administrator),
+        }
+    }
+
+    /// The caller's role dashboard together with the fleet KPIs
+    pub fn get_dashboard(&self, context: &RequestContext) -> AppResult<Dashboard> {
+        Ok(Dashboard {
+            summary: self.get_summary(context)?,
+            kpis: self.get_kpis()?,
+        })
+    }
+
+    /// What changed for the caller since their previous session: newly
+    /// assigned inspections, findings on watched assets, corrective actions
+    /// waiting for their verification and open inspections someone else
+    /// rescheduled
+    pub fn get_since_last_login(&self, context: &RequestContext) -> AppResult<SinceLastLogin> {
+        let session = context.current_user()?;
+        let user_id = session.user_id;
+        let can_verify = session.can_access_resource("compliance", "update");
+        let now = Utc::now();
+        debug!("[{}] Building changes since last login for user {}", context.request_id, user_id);
+
+        let conn = self.database.get_read_connection()?;
+        // Sessions still running on another device count only up to the
+        // start of this one
+        let current_started: DateTime<Utc> = conn.query_row(
+            "SELECT created_at FROM sessions WHERE session_id = ?1",
+            params![session.session_id],
+            |row| row.get(0),
+        ).optional()?.unwrap_or(now);
+        let previous_activity: Option<DateTime<Utc>> = conn.query_row(
+            "SELECT MAX(last_activity) FROM sessions WHERE user_id = ?1 AND session_id <> ?2 AND created_at < ?3",
+            params![user_id, session.session_id, current_started],
+            |row| row.get(0),
+        )?;
+        let since = match previous_activity {
+            Some(last_activity) => last_activity.min(current_started),
+            None => now - chrono::Duration::days(SINCE_LAST_LOGIN_DEFAULT_DAYS),
+        };
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM inspections i
+             WHERE inspector_id = ?1 AND status IN ('Scheduled', 'In Progress')
+               AND (created_at > ?2 OR EXISTS(
+                    SELECT 1 FROM entity_history h
+                    WHERE h.entity_type = 'Inspection' AND h.entity_id = i.id AND h.changed_at > ?2
+                      AND json_extract(h.after_json, '$.inspector_id') = ?1
+                      AND json_extract(h.before_json, '$.inspector_id') IS NOT ?1))
+             ORDER BY scheduled_date IS NULL, scheduled_date, id LIMIT ?3",
+            OPEN_INSPECTION_COLUMNS
+        ))?;
+        let new_assignments = stmt
+            .query_map(params![user_id, since, DASHBOARD_LIST_LIMIT as i64], |row| {
+                self.inspection_service.row_to_inspection(row)
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT ii.id, ii.inspection_id, i.asset_id, a.asset_number, ii.item_name, ii.finding,
+                    ii.severity, ii.is_compliant, ii.created_at
+             FROM inspection_items ii
+             JOIN inspections i ON i.id = ii.inspection_id
+             JOIN assets a ON a.id = i.asset_id
+             JOIN watches w ON w.user_id = ?1 AND w.entity_type = 'Asset' AND w.entity_id = i.asset_id
+             WHERE ii.created_at > ?2 AND i.inspector_id IS NOT ?1
+               AND (TRIM(COALESCE(ii.finding, '')) != '' OR ii.severity IS NOT NULL OR ii.is_compliant = 0)
+             ORDER BY ii.created_at DESC, ii.id DESC LIMIT ?3"
+        )?;
+        let watched_findings = stmt
+            .query_map(params![user_id, since, DASHBOARD_LIST_LIMIT as i64], |row| {
+                Ok(WatchedFinding {
+                    item_id: row.get(0)?,
+                    inspection_id: row.get(1)?,
+                    asset_id: row.get(2)?,
+                    asset_number: row.get(3)?,
+                    item_name: row.get(4)?,
+                    finding: row.get(5)?,
+                    severity: row.get::<_, Option<String>>(6)?.and_then(|s| s.parse().ok()),
+                    is_compliant: row.get(7)?,
+                    recorded_at: row.get(8)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        // Only those who may verify corrective actions have approvals
+        // waiting, and never for work they submitted themselves
+        let (pending_approval_count, pending_approvals) = if can_verify {
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM corrective_actions
+                 WHERE status = 'Pending Verification' AND submitted_by IS NOT ?1",
+                params![user_id],
+                |row| row.get(0),
+            )?;
+            let mut stmt = conn.prepare(
+                "SELECT ca.id, ca.asset_id, a.asset_number, ca.description, ca.submitted_by, ca.submitted_at
+                 FROM corrective_actions ca
+                 JOIN assets a ON a.id = ca.asset_id
+                 WHERE ca.status = 'Pending Verification' AND ca.submitted_by IS NOT ?1
+                 ORDER BY ca.submitted_at DESC, ca.id DESC LIMIT ?2"
+            )?;
+            let approvals = stmt
+                .query_map(params![user_id, DASHBOARD_LIST_LIMIT as i64], |row| {
+                    let submitted_at: Option<DateTime<Utc>> = row.get(5)?;
+                    Ok(PendingApproval {
+                        corrective_action_id: row.get(0)?,
+                        asset_id: row.get(1)?,
+                        asset_number: row.get(2)?,
+                        description: row.get(3)?,
+                        submitted_by: row.get(4)?,
+                        submitted_at,
+                        is_new: submitted_at.is_some_and(|at| at > since),
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            (count, approvals)
+        } else {
+            (0, Vec::new())
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT i.id, i.asset_id, a.asset_number,
+                    json_extract(h.before_json, '$.scheduled_date'), i.scheduled_date,
+                    h.changed_by, h.changed_at
+             FROM entity_history h
+             JOIN inspections i ON h.entity_type = 'Inspection' AND i.id = h.entity_id
+             JOIN assets a ON a.id = i.asset_id
+             WHERE i.inspector_id = ?1 AND i.status IN ('Scheduled', 'In Progress')
+               AND h.changed_at > ?2 AND h.changed_by IS NOT ?1
+               AND json_extract(h.before_json, '$.scheduled_date') IS NOT json_extract(h.after_json, '$.scheduled_date')
+             ORDER BY h.changed_at DESC, h.id DESC LIMIT ?3"
+        )?;
+        let schedule_changes = stmt
+            .query_map(params![user_id, since, DASHBOARD_LIST_LIMIT as i64], |row| {
+                Ok(ScheduleChange {
+                    inspection_id: row.get(0)?,
+                    asset_id: row.get(1)?,
+                    asset_number: row.get(2)?,
+                    previous_date: row.get(3)?,
+                    scheduled_date: row.get(4)?,
+                    changed_by: row.get(5)?,
+                    changed_at: row.get(6)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(SinceLastLogin {
+            since,
+            previous_session: previous_activity.is_some(),
+            new_assignments,
+            watched_findings,
+            pending_approval_count,
+            pending_approvals,
+            schedule_changes,
+        })
+    }
+
+    /// Fleet KPIs. Each figure is one aggregate query on a read connection,
+    /// so the cost does not grow with the number of widgets.
+    pub fn get_kpis(&self) -> AppResult<DashboardKpis> {
+        let conn = self.database.get_read_connection()?;
+        let today = Utc::now().date_naive();
+
+        let (open_inspections, overdue_inspections): (i64, i64) = conn.query_row(
+            &format!(
+                "SELECT COUNT(CASE WHEN status IN ('Scheduled', 'In Progress') THEN 1 END),
+                        COUNT(CASE WHEN {} THEN 1 END)
+                 FROM inspections",
+                OVERDUE_CONDITION
+            ),
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        // Compliant means at least 80% of items compliant across the
+        // asset's completed inspections, as in the compliance report
+        let mut stmt = conn.prepare(
+            "SELECT l.id, l.name, COUNT(a.id),
+                    COUNT(scores.asset_id),
+                    COUNT(CASE WHEN scores.score >= 80 THEN 1 END)
+             FROM locations l
+             JOIN assets a ON a.location_id = l.id
+             LEFT JOIN (
+                 SELECT i.asset_id, AVG(items.score) AS score
+                 FROM inspections i
+                 JOIN (
+                     SELECT inspection_id,
+                            COUNT(CASE WHEN is_compliant = 1 THEN 1 END) * 100.0 / COUNT(*) AS score
+                     FROM inspection_items
+                     GROUP BY inspection_id
+                 ) items ON items.inspection_id = i.id
+                 WHERE i.status = 'Completed'
+                 GROUP BY i.asset_id
+             ) scores ON scores.asset_id = a.id
+             GROUP BY l.id
+             ORDER BY l.name"
+        )?;
+        let compliance_by_location = stmt
+            .query_map([], |row| {
+                let inspected_assets: i64 = row.get(3)?;
+                let compliant_assets: i64 = row.get(4)?;
+                Ok(LocationCompliance {
+                    location_id: row.get(0)?,
+                    location_name: row.get(1)?,
+                    total_assets: row.get(2)?,
+                    inspected_assets,
+                    compliant_assets,
+                    compliance_percentage: if inspected_assets > 0 {
+                        compliant_assets as f64 * 100.0 / inspected_assets as f64
+                    } else {
+                        0.0
+                    },
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        drop(stmt);
+
+        // Weeks start on Monday; weeks without findings are filled in
+        let first_week = today - chrono::Duration::days(
+            today.weekday().num_days_from_monday() as i64 + 7 * (DASHBOARD_TREND_WEEKS - 1)
+        );
+        let mut stmt = conn.prepare(
+            "SELECT date(created_at, '-6 days', 'weekday 1'), COUNT(*)
+             FROM inspection_items
+             WHERE severity = 'Critical' AND created_at >= ?1
+             GROUP BY 1"
+        )?;
+        let weekly: HashMap<NaiveDate, i64> = stmt
+            .query_map(params![first_week.to_string()], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        drop(stmt);
+        let critical_findings_trend = (0..DASHBOARD_TREND_WEEKS)
+            .map(|week| {
+                let week_start = first_week + chrono::Duration::weeks(week);
+                FindingsTrendPoint {
+                    week_start,
+                    critical_findings: weekly.get(&week_start).copied().unwrap_or(0),
+                }
+            })
+            .collect();
+
+        let mut stmt = conn.prepare(
+            "SELECT date(scheduled_date), COUNT(*)
+             FROM inspections
+             WHERE status IN ('Scheduled', 'In Progress')
+               AND scheduled_date >= ?1 AND scheduled_date < ?2
+             GROUP BY 1"
+        )?;
+        let daily: HashMap<NaiveDate, i64> = stmt
+            .query_map(
+                params![today.to_string(), (today + chrono::Duration::days(DASHBOARD_WORKLOAD_DAYS)).to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?
+            .collect::<rusqlite::Result<_>>()?;
+        drop(stmt);
+        let upcoming_workload = (0..DASHBOARD_WORKLOAD_DAYS)
+            .map(|day| {
+                let date = today + chrono::Duration::days(day);
+                WorkloadDay { date, scheduled_inspections: daily.get(&date).copied().unwrap_or(0) }
+            })
+            .collect();
+
+        Ok(DashboardKpis {
+            generated_at: Utc::now(),
+            open_inspections,
+            overdue_inspections,
+            compliance_by_location,
+            critical_findings_trend,
+            upcoming_workload,
+        })
+    }
+
+    /// Inspection and maintenance workload expected over the next `months`
+    /// months, by month, location and type. Scheduled work is counted where
+    /// it falls; frequent and periodic inspections of in-service assets are
+    /// projected from their frequency rules beyond what is scheduled.
+    pub fn get_workload_forecast(&self, months: Option<u32>) -> AppResult<WorkloadForecast> {
+        let now = Utc::now();
+        let window = forecast::ForecastWindow::new(
+            now.date_naive(),
+            months.unwrap_or(forecast::DEFAULT_FORECAST_MONTHS),
+        )?;
+        let conn = self.database.get_read_connection()?;
+
+        let mut builder = forecast::WorkloadBuilder::new(window);
+
+        let mut stmt = conn.prepare(
+            "SELECT i.inspection_type, i.scheduled_date, l.id, l.name, l.time_zone
+             FROM inspections i
+             JOIN assets a ON i.asset_id = a.id
+             JOIN locations l ON a.location_id = l.id
+             WHERE i.status IN ('Scheduled', 'In Progress') AND i.deleted_at IS NULL
+               AND i.scheduled_date IS NOT NULL AND a.deleted_at IS NULL"
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, DateTime<Utc>>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?;
+        for row in rows {
+            let (inspection_type, scheduled_date, location_id, location_name, time_zone) = row?;
+            let inspection_type: InspectionType = inspection_type.parse()?;
+            let due = scheduling::local_date(scheduled_date, scheduling::time_zone_or_default(time_zone.as_deref()));
+            builder.add(
+                due, location_id, &location_name, WorkloadKind::Inspection,
+                &inspection_type.to_string(), forecast::inspection_hours(&inspection_type), true,
+            );
+        }
+        drop(stmt);
+
+        let mut stmt = conn.prepare(
+            "SELECT m.maintenance_type, m.scheduled_date, l.id, l.name, l.time_zone
+             FROM maintenance_records m
+             JOIN assets a ON m.asset_id = a.id
+             JOIN locations l ON a.location_id = l.id
+             WHERE m.status IN ('Scheduled', 'In Progress') AND m.scheduled_date IS NOT NULL
+               AND a.deleted_at IS NULL"
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, DateTime<Utc>>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?;
+        for row in rows {
+            let (maintenance_type, scheduled_date, location_id, location_name, time_zone) = row?;
+            let maintenance_type: MaintenanceType = maintenance_type.parse()?;
+            let due = scheduling::local_date(scheduled_date, scheduling::time_zone_or_default(time_zone.as_deref()));
+            builder.add(
+                due, location_id, &location_name, WorkloadKind::Maintenance,
+                &maintenance_type.to_string(), forecast::maintenance_hours(&maintenance_type), true,
+            );
+        }
+        drop(stmt);
+
+        // Recurring inspections continue from the latest open one, or
+        // from the last completed one when none is scheduled
+        let mut stmt = conn.prepare(
+            "SELECT l.id, l.name, l.time_zone,
+                    (SELECT MAX(i.scheduled_date) FROM inspections i
+                     WHERE i.asset_id = a.id AND i.inspection_type = ?1 AND i.deleted_at IS NULL
+                       AND i.status IN ('Scheduled', 'In Progress')),
+                    (SELECT MAX(i.actual_date) FROM inspections i
+                     WHERE i.asset_id = a.id AND i.inspection_type = ?1 AND i.deleted_at IS NULL
+                       AND i.status = 'Completed'),
+                    a.service_class, a.fem_group
+             FROM assets a
+             JOIN locations l ON a.location_id = l.id
+             WHERE a.deleted_at IS NULL AND a.status IN ('Active', 'Maintenance')"
+        )?;
+        for inspection_type in [InspectionType::Frequent, InspectionType::Periodic] {
+            let rows = stmt.query_map(params![inspection_type.to_string()], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<DateTime<Utc>>>(3)?,
+                    row.get::<_, Option<DateTime<Utc>>>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                    row.get::<_, Option<String>>(6)?,
+                ))
+            })?;
+            for row in rows {
+                let (location_id, location_name, time_zone, scheduled, completed, service_class, fem_group) = row?;
+                let duty = DutyLevel::of(
+                    service_class.and_then(|c| c.parse().ok()),
+                    fem_group.and_then(|g| g.parse().ok()),
+                );
+                let interval_days = duty.map_or(inspection_type.interval_days(), |duty| {
+                    duty.scale_interval(inspection_type.interval_days())
+                });
+                let tz = scheduling::time_zone_or_default(time_zone.as_deref());
+                // An overdue open inspection is counted as due today,
+                // so the next one follows a full interval after that
+                let anchor = match scheduled {
+                    Some(date) => Some(scheduling::local_date(date, tz).max(now.date_naive())),
+                    None => completed.map(|date| scheduling::local_date(date, tz)),
+                };
+                for due in window.recurrences(anchor, interval_days) {
+                    builder.add(
+                        due, location_id, &location_name, WorkloadKind::Inspection,
+                        &inspection_type.to_string(), forecast::inspection_hours(&inspection_type), false,
+                    );
+                }
+            }
+        }
+        drop(stmt);
+
+        Ok(builder.finish(now))
+    }
+
+    /// Cross-fleet comparisons over completed inspections: findings per
+    /// inspection by manufacturer and model, average item compliance by
+    /// location, and average condition by asset age
+    pub fn get_fleet_benchmarks(&self) -> AppResult<FleetBenchmarks> {
+        let conn = self.database.get_read_connection()?;
+
+        // A finding is any item with a finding, a severity or a
+        // non-compliant result, as in the review queue
+        let mut stmt = conn.prepare(
+            "SELECT a.manufacturer, a.model, COUNT(DISTINCT a.id), COUNT(DISTINCT i.id), COUNT(ii.id)
+             FROM assets a
+             JOIN inspections i ON i.asset_id = a.id
+                  AND i.status = 'Completed' AND i.deleted_at IS NULL
+             LEFT JOIN inspection_items ii ON ii.inspection_id = i.id
+                  AND (TRIM(COALESCE(ii.finding, '')) != '' OR ii.severity IS NOT NULL OR ii.is_compliant = 0)
+             WHERE a.deleted_at IS NULL
+             GROUP BY a.manufacturer, a.model
+             ORDER BY COUNT(ii.id) * 1.0 / COUNT(DISTINCT i.id) DESC, a.manufacturer, a.model"
+        )?;
+        let findings_by_model = stmt
+            .query_map([], |row| {
+                let inspection_count: i64 = row.get(3)?;
+                let finding_count: i64 = row.get(4)?;
+                Ok(ModelFindingsRate {
+                    manufacturer: row.get(0)?,
+                    model: row.get(1)?,
+                    asset_count: row.get(2)?,
+                    inspection_count,
+                    finding_count,
+                    findings_per_inspection: finding_count as f64 / inspection_count as f64,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        drop(stmt);
+
+        let mut stmt = conn.prepare(
+            "SELECT l.id, l.name, COUNT(*), AVG(items.score)
+             FROM inspections i
+             JOIN (
+                 SELECT inspection_id,
+                        COUNT(CASE WHEN is_compliant = 1 THEN 1 END) * 100.0 / COUNT(*) AS score
+                 FROM inspection_items
+                 GROUP BY inspection_id
+             ) items ON items.inspection_id = i.id
+             JOIN assets a ON a.id = i.asset_id
+             JOIN locations l ON l.id = a.location_id
+             WHERE i.status = 'Completed' AND i.deleted_at IS NULL AND a.deleted_at IS NULL
+             GROUP BY l.id
+             ORDER BY 4, l.name"
+        )?;
+        let compliance_by_location = stmt
+            .query_map([], |row| {
+                Ok(LocationComplianceAverage {
+                    location_id: row.get(0)?,
+                    location_name: row.get(1)?,
+                    inspection_count: row.get(2)?,
+                    average_compliance_percentage: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        drop(stmt);
+
+        let mut stmt = conn.prepare(
+            "SELECT CAST((julianday(i.actual_date) - julianday(COALESCE(a.installation_date, a.manufacture_date))) / 365.25 AS INTEGER),
+                    i.overall_condition
+             FROM inspections i
+             JOIN assets a ON a.id = i.asset_id
+             WHERE i.status = 'Completed' AND i.deleted_at IS NULL AND a.deleted_at IS NULL
+               AND i.overall_condition IS NOT NULL AND i.actual_date IS NOT NULL
+               AND julianday(COALESCE(a.installation_date, a.manufacture_date)) <= julianday(i.actual_date)"
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+        let mut by_age: std::collections::BTreeMap<i64, (i64, i64, i64)> = std::collections::BTreeMap::new();
+        for row in rows {
+            let (age_years, condition) = row?;
+            let condition: Condition = condition.parse()?;
+            let (count, score, poor) = by_age.entry(age_years).or_default();
+            *count += 1;
+            *score += condition.score() as i64;
+            if matches!(condition, Condition::Poor | Condition::Critical) {
+                *poor += 1;
+            }
+        }
+        drop(stmt);
+        let age_condition_curve = by_age.into_iter()
+            .map(|(age_years, (inspection_count, score, poor_or_critical_count))| AgeConditionPoint {
+                age_years,
+                inspection_count,
+                average_condition_score: score as f64 / inspection_count as f64,
+                poor_or_critical_count,
+            })
+            .collect();
+
+        let mut stmt = conn.prepare(
+            "SELECT a.service_class, a.fem_group, COUNT(DISTINCT a.id), COUNT(DISTINCT i.id), COUNT(ii.id)
+             FROM assets a
+             LEFT JOIN inspections i ON i.asset_id = a.id
+                  AND i.status = 'Completed' AND i.deleted_at IS NULL
+             LEFT JOIN inspection_items ii ON ii.inspection_id = i.id
+                  AND (TRIM(COALESCE(ii.finding, '')) != '' OR ii.severity IS NOT NULL OR ii.is_compliant = 0)
+             WHERE a.deleted_at IS NULL
+             GROUP BY a.service_class, a.fem_group"
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })?;
+        let mut by_classification = Vec::new();
+        for row in rows {
+            let (service_class, fem_group, asset_count, inspection_count, finding_count) = row?;
+            let service_class = service_class.map(|c| c.parse::<CmaaServiceClass>()).transpose()?;
+            let fem_group = fem_group.map(|g| g.parse::<FemGroup>()).transpose()?;
+            by_classification.push(ClassificationBreakdown {
+                duty: DutyLevel::of(service_class, fem_group),
+                service_class,
+                fem_group,
+                asset_count,
+                inspection_count,
+                finding_count,
+                findings_per_inspection: if inspection_count > 0 {
+                    finding_count as f64 / inspection_count as f64
+                } else {
+                    0.0
+                },
+            });
+        }
+        drop(stmt);
+        by_classification.sort_by(|a, b| b.duty.cmp(&a.duty)
+            .then_with(|| b.findings_per_inspection.total_cmp(&a.findings_per_inspection)));
+
+        Ok(FleetBenchmarks {
+            generated_at: Utc::now(),
+            findings_by_model,
+            compliance_by_location,
+            age_condition_curve,
+            by_classification,
+        })
+    }
+
+    fn inspector_dashboard(&self, user_id: i64) -> AppResult<InspectorDashboard> {
+        let conn = self.database.get_read_connection()?;
+
+        let list = |condition: &str| -> AppResult<Vec<Inspection>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM inspections WHERE inspector_id = ?1 AND {}
+                 ORDER BY scheduled_date IS NULL, scheduled_date, id LIMIT ?2",
+                OPEN_INSPECTION_COLUMNS, condition
+            ))?;
+            let inspections = stmt
+                .query_map(params![user_id, DASHBOARD_LIST_LIMIT as i64], |row| {
+                    self.inspection_service.row_to_inspection(row)
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(inspections)
+        };
+        let count = |condition: &str| -> AppResult<i64> {
+            Ok(conn.query_row(
+                &format!("SELECT COUNT(*) FROM inspections WHERE inspector_id = ?1 AND {}", condition),
+                params![user_id],
+                |row| row.get(0),
+            )?)
+        };
+
+        let pending = "status IN ('Scheduled', 'In Progress')";
+        let due_this_week = "status IN ('Scheduled', 'In Progress')
+             AND scheduled_date >= datetime('now') AND scheduled_date < datetime('now', '+7 days')";
+        let unread_mentions: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM comment_mentions WHERE user_id = ?1 AND read_at IS NULL",
+            params![user_id],
+            |row| row.get(0),
+        )?;
+
+        Ok(InspectorDashboard {
+            pending_count: count(pending)?,
+            pending: list(pending)?,
+            overdue_count: count(OVERDUE_CONDITION)?,
+            overdue: list(OVERDUE_CONDITION)?,
+            due_this_week: count(due_this_week)?,
+            unread_mentions,
+        })
+    }
+
+    fn supervisor_dashboard(&self, user_id: i64) -> AppResult<SupervisorDashboard> {
+        let end_date = Utc::now();
+        let start_date = end_date - chrono::Duration::days(DASHBOARD_STATS_DAYS);
+        let teams = self.team_service.get_user_teams(user_id)?
+            .into_iter()
+            .filter(|team| team.is_active)
+            .map(|team| self.team_service.get_team_completion_stats(team.id, start_date, end_date))
+            .collect::<AppResult<Vec<_>>>()?;
+        let team_ids: Vec<i64> = teams.iter().map(|stats| stats.team_id).collect();
+
+        let conn = self.database.get_read_connection()?;
+        // Review completed inspections by members of the supervisor's
+        // teams, or all of them for a supervisor without a team
+        let team_ids = serde_json::to_string(&team_ids)?;
+        let review_sql = format!(
+            "SELECT i.id, i.asset_id, a.asset_number, i.inspector_id,
+                    u.first_name || ' ' || u.last_name, i.actual_date, i.overall_condition,
+                    COUNT(ii.id),
+                    COUNT(CASE WHEN ii.severity IN ('High', 'Critical') THEN 1 END)
+             FROM inspections i
+             JOIN assets a ON a.id = i.asset_id
+             JOIN users u ON u.id = i.inspector_id
+             JOIN inspection_items ii ON ii.inspection_id = i.id
+                  AND (TRIM(COALESCE(ii.finding, '')) != '' OR ii.severity IS NOT NULL OR ii.is_compliant = 0)
+             WHERE i.status = 'Completed'
+               AND COALESCE(i.actual_date, i.updated_at) >= datetime('now', '-{} days')
+               AND (json_array_length(?1) = 0 OR i.inspector_id IN (
+                    SELECT tm.user_id FROM team_members tm
+                    WHERE tm.team_id IN (SELECT value FROM json_each(?1))))
+             GROUP BY i.id",
+            REVIEW_QUEUE_DAYS
+        );
+
+        let review_queue_count: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM ({})", review_sql),
+            params![team_ids],
+            |row| row.get(0),
+        )?;
+        let mut stmt = conn.prepare(&format!(
+            "{} ORDER BY 9 DESC, 6 DESC LIMIT ?2",
+            review_sql
+        ))?;
+        let review_queue = stmt
+            .query_map(params![team_ids, DASHBOARD_LIST_LIMIT as i64], |row| {
+                Ok(ReviewQueueItem {
+                    inspection_id: row.get(0)?,
+                    asset_id: row.get(1)?,
+                    asset_number: row.get(2)?,
+                    inspector_id: row.get(3)?,
+                    inspector_name: row.get(4)?,
+                    completed_at: row.get(5)?,
+                    overall_condition: row.get::<_, Option<String>>(6)?.and_then(|c| c.parse().ok()),
+                    finding_count: row.get(7)?,
+                    serious_finding_count: row.get(8)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(SupervisorDashboard { teams, review_queue_count, review_queue })
+    }
+
+    fn admin_dashboard(&self) -> AppResult<AdminDashboard> {
+        let compliance = self.report_service.generate_compliance_status_report(None)?;
+        let conn = self.database.get_read_connection()?;
+
+        let count = |sql: &str| -> AppResult<i64> { Ok(conn.query_row(sql, [], |row| row.get(0))?) };
+
+        let health = SystemHealth {
+            schema_version: conn.query_row("SELECT version FROM schema_version LIMIT 1", [], |row| row.get(0))?,
+            active_users: count("SELECT COUNT(*) FROM users WHERE is_active = 1")?,
+            open_inspections: count("SELECT COUNT(*) FROM inspections WHERE status IN ('Scheduled', 'In Progress')")?,
+            overdue_inspections: count(&format!("SELECT COUNT(*) FROM inspections WHERE {}", OVERDUE_CONDITION))?,
+            recycle_bin_entries: count("SELECT COUNT(*) FROM recycle_bin")?,
+        };
+
+        let page_size = count("PRAGMA page_size")?;
+        let (media_files, media_bytes): (i64, i64) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(file_size), 0) FROM media_files",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let storage = StorageUsage {
+            database_bytes: count("PRAGMA page_count")? * page_size,
+            free_bytes: count("PRAGMA freelist_count")? * page_size,
+            media_files,
+            media_bytes,
+        };
+
+        Ok(AdminDashboard {
+            health,
+            storage,
+            compliance: ComplianceKpis {
+                total_assets: compliance.total_assets,
+                compliant_assets: compliance.compliant_assets,
+                compliance_percentage: compliance.compliance_percentage,
+                overdue_inspections: compliance.overdue_inspections,
+                critical_findings: compliance.critical_findings,
+            },
+        })
+    }
+}
+
+// =============================================================================
+// Migration Service
+// =============================================================================
+
+pub struct MigrationService {
+    database: Arc<Database>,
+}
+
+impl MigrationService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    pub fn get_status(&self) -> AppResult<MigrationStatus> {
+        self.database.migration_status()
+    }
+
+    /// Apply pending migrations
+    pub fn run_pending(&self, context: &RequestContext) -> AppResult<Vec<MigrationResult>> {
+        info!("[{}] Running pending migrations", context.request_id);
+        let results = self.database.run_pending_migrations()?;
+        log_migration_results(context, &results);
+        Ok(results)
+    }
+
+    /// Roll the schema back to `version`
+    pub fn rollback_to_version(&self, context: &RequestContext, version: i32) -> AppResult<Vec<MigrationResult>> {
+        warn!("[{}] Rolling back schema to version {}", context.request_id, version);
+        let results = self.database.rollback_to_version(version)?;
+        log_migration_results(context, &results);
+        Ok(results)
+    }
+
+    /// Undo the latest startup upgrade from the snapshot taken before it
+    pub fn rollback_last_upgrade(&self, context: &RequestContext) -> AppResult<UpgradeSnapshot> {
+        warn!("[{}] Rolling back the latest upgrade from its snapshot", context.request_id);
+        self.database.rollback_last_upgrade()
+    }
+}
+
+fn log_migration_results(context: &RequestContext, results: &[MigrationResult]) {
+    for result in results {
+        match &result.error_message {
+            None => info!("[{}] Migration {} ({}) succeeded in {} ms", context.request_id,
+                          result.version, result.name, result.execution_time_ms),
+            Some(error) => warn!("[{}] Migration {} ({}) failed: {}", context.request_id,
+                                 result.version, result.name, error),
+        }
+    }
+}
+
+// =============================================================================
+// Database Maintenance Service
+// =============================================================================
+
+/// Maintenance runs returned by `get_history`
+const MAINTENANCE_HISTORY_LIMIT: usize = 20;
+
+pub struct DatabaseMaintenanceService {
+    database: Arc<Database>,
+}
+
+impl DatabaseMaintenanceService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Run the given tasks now, or all of them when none are given
+    pub fn run(&self, context: &RequestContext, tasks: &[MaintenanceTask]) -> AppResult<Vec<MaintenanceRun>> {
+        let tasks = if tasks.is_empty() { &MaintenanceTask::ALL[..] } else { tasks };
+        let session = context.current_user()?;
+        info!("[{}] Running database maintenance: {:?}", context.request_id, tasks);
+        self.database.run_maintenance(tasks, Some(session.user_id))
+    }
+
+    /// Most recent maintenance runs, newest first
+    pub fn get_history(&self) -> AppResult<Vec<MaintenanceRun>> {
+        self.database.maintenance_history(MAINTENANCE_HISTORY_LIMIT)
+    }
+}
+
+// =============================================================================
+// Pre-start Check Service
+// =============================================================================
+
+const PRESTART_CHECK_COLUMNS: &str =
+    "id, asset_id, operator_id, shift, check_date, items, passed, notes, created_at";
+
+fn row_to_prestart_check(row: &Row) -> rusqlite::Result<PrestartCheck> {
+    let items: String = row.get(5)?;
+    Ok(PrestartCheck {
+        id: Some(row.get(0)?),
+        asset_id: row.get(1)?,
+        operator_id: row.get(2)?,
+        shift: row.get::<_, String>(3)?.parse().unwrap_or(Shift::Day),
+        check_date: row.get(4)?,
+        items: serde_json::from_str(&items).unwrap_or_default(),
+        passed: row.get(6)?,
+        notes: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+pub struct PrestartCheckService {
+    database: Arc<Database>,
+}
+
+impl PrestartCheckService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Record a pre-start check by the current user, who must be authorized
+    /// to operate the asset. The check passes only if every item passes; a
+    /// failed item must say what is wrong.
+    pub fn record_check(
+        &self,
+        context: &RequestContext,
+        asset_id: i64,
+        shift: Shift,
+        check_date: Option<NaiveDate>,
+        items: Vec<PrestartCheckItem>,
+        notes: Option<String>,
+    ) -> AppResult<PrestartCheck> {
+        let operator_id = context.current_user()?.user_id;
+        let check_date = check_date.unwrap_or_else(|| Utc::now().date_naive());
+        info!("[{}] Recording {} shift pre-start check for asset {} on {}",
+              context.request_id, shift, asset_id, check_date);
+
+        if items.is_empty() {
+            return Err(AppError::validation("items", "A pre-start check needs at least one item"));
+        }
+        if items.iter().any(|item| item.item.trim().is_empty()) {
+            return Err(AppError::validation("items", "Every check item needs a name"));
+        }
+        if items.iter().any(|item| !item.passed && item.note.as_deref().is_none_or(|n| n.trim().is_empty())) {
+            return Err(AppError::validation("items", "Failed check items need a note describing the problem"));
+        }
+        if check_date > Utc::now().date_naive() {
+            return Err(AppError::validation("check_date", "Pre-start checks cannot be recorded in advance"));
+        }
+        let passed = items.iter().all(|item| item.passed);
+
+        self.database.with_transaction(|conn| {
+            let status: String = conn.query_row(
+                "SELECT status FROM assets WHERE id = ?1",
+                params![asset_id],
+                |row| row.get(0),
+            ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+                entity: "Asset".to_string(),
+                field: "id".to_string(),
+                value: asset_id.to_string(),
+            })?;
+            if status == AssetStatus::Decommissioned.to_string() {
+                return Err(AppError::CraneOperation {
+                    crane_id: asset_id.to_string(),
+                    operation: "prestart_check".to_string(),
+                    reason: "Asset is decommissioned".to_string(),
+                });
+            }
+            let authorization = check_operator_authorization(conn, operator_id, asset_id)?;
+            if !authorization.authorized {
+                return Err(AppError::CraneOperation {
+                    crane_id: asset_id.to_string(),
+                    operation: "prestart_check".to_string(),
+                    reason: format!(
+                        "Operator is not authorized: {}",
+                        authorization.reason.unwrap_or_default()
+                    ),
+                });
+            }
+
+            let check = conn.query_row(
+                &format!(
+                    "INSERT INTO prestart_checks (asset_id, operator_id, shift, check_date, items, passed, notes)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                     RETURNING {}",
+                    PRESTART_CHECK_COLUMNS
+                ),
+                params![
+                    asset_id, operator_id, shift.to_string(), check_date,
+                    serde_json::to_string(&items)?, passed, notes,
+                ],
+                row_to_prestart_check,
+            )?;
+            if !passed {
+                warn!("[{}] Pre-start check {:?} for asset {} failed", context.request_id, check.id, asset_id);
+            }
+            Ok(check)
+        })
+    }
+
+    /// Pre-start checks of an asset between two dates inclusive, newest first
+    pub fn get_checks(&self, asset_id: i64, from: NaiveDate, to: NaiveDate) -> AppResult<Vec<PrestartCheck>> {
+        debug!("Fetching pre-start checks for asset {} from {} to {}", asset_id, from, to);
+        if to < from {
+            return Err(AppError::validation("to", "End date cannot be before start date"));
+        }
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<PrestartCheck>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM prestart_checks
+                 WHERE asset_id = ?1 AND check_date BETWEEN ?2 AND ?3
+                 ORDER BY check_date DESC, id DESC",
+                PRESTART_CHECK_COLUMNS
+            ))?;
+            let checks = stmt
+                .query_map(params![asset_id, from, to], row_to_prestart_check)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(checks)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Days in the last `PRESTART_LOOKBACK_DAYS` days, before today, on which an
+    /// active asset requiring pre-start checks had none
+    pub fn get_missed_checks(&self, asset_id: Option<i64>) -> AppResult<Vec<MissedPrestartCheck>> {
+        debug!("Fetching missed pre-start checks (asset: {:?})", asset_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<MissedPrestartCheck>> {
+            let mut stmt = conn.prepare(MISSED_PRESTART_CHECKS_SQL)?;
+            let missed = stmt
+                .query_map(params![asset_id, PRESTART_LOOKBACK_DAYS], |row| {
+                    Ok(MissedPrestartCheck {
+                        asset_id: row.get(0)?,
+                        asset_number: row.get(1)?,
+                        asset_name: row.get(2)?,
+                        date: row.get(3)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(missed)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Require, or stop requiring, daily pre-start checks for an asset
+    pub fn set_required(&self, context: &RequestContext, asset_id: i64, required: bool) -> AppResult<()> {
+        info!("[{}] Setting pre-start checks required={} for asset {}", context.request_id, required, asset_id);
+        self.database.with_transaction(|conn| {
+            let updated = conn.execute(
+                "UPDATE assets SET prestart_required = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+                params![required, asset_id],
+            )?;
+            if updated == 0 {
+                return Err(AppError::RecordNotFound {
+                    entity: "Asset".to_string(),
+                    field: "id".to_string(),
+                    value: asset_id.to_string(),
+                });
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Asset days without a pre-start check: `?1` optional asset id, `?2` days to look back
+const MISSED_PRESTART_CHECKS_SQL: &str = "
+    WITH RECURSIVE days(day) AS (
+        SELECT date('now', '-' || ?2 || ' days')
+        UNION ALL
+        SELECT date(day, '+1 day') FROM days WHERE day < date('now', '-1 day')
+    )
+    SELECT a.id, a.asset_number, a.asset_name, d.day
+    FROM assets a CROSS JOIN days d
+    WHERE a.prestart_required = 1 AND a.status = 'Active'
+      AND (?1 IS NULL OR a.id = ?1)
+      AND d.day > date(a.created_at)
+      AND NOT EXISTS (
+          SELECT 1 FROM prestart_checks p WHERE p.asset_id = a.id AND p.check_date = d.day)
+    ORDER BY a.asset_number, d.day";
+
+// =============================================================================
+// Entity History Service
+// =============================================================================
+
+/// Snapshot of a record for its change history, without redacted columns, or
+/// `None` if the record does not exist. Take it inside the updating
+/// transaction, before the first write.
+fn history_snapshot(
+    conn: &Connection,
+    entity_type: HistoryEntityType,
+    entity_id: i64,
+) -> AppResult<Option<serde_json::Map<String, JsonValue>>> {
+    let mut snapshot = snapshot_rows(conn, entity_type.table(), "id", entity_id)?;
+    Ok(snapshot.rows.pop().map(|mut row| {
+        for column in HISTORY_REDACTED_COLUMNS {
+            row.remove(column);
+        }
+        row
+    }))
+}
+
+/// Record how a record changed since `before` was taken. Nothing is recorded
+/// when the record did not exist or no column changed.
+fn record_history(
+    conn: &Connection,
+    context: &RequestContext,
+    entity_type: HistoryEntityType,
+    entity_id: i64,
+    action: &str,
+    before: Option<serde_json::Map<String, JsonValue>>,
+) -> AppResult<()> {
+    let (Some(before), Some(after)) = (before, history_snapshot(conn, entity_type, entity_id)?) else {
+        return Ok(());
+    };
+    if FieldChange::diff(&before, &after).is_empty() {
+        return Ok(());
+    }
+
+    conn.execute(
+        "INSERT INTO entity_history (entity_type, entity_id, action, changed_by, before_json, after_json)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            entity_type.to_string(),
+            entity_id,
+            action,
+            context.current_user().map(|u| u.user_id).ok(),
+            serde_json::to_string(&before)?,
+            serde_json::to_string(&after)?,
+        ],
+    )?;
+    Ok(())
+}
+
+pub struct EntityHistoryService {
+    database: Arc<Database>,
+}
+
+impl EntityHistoryService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Recorded changes to a record, newest first
+    pub fn get_history(&self, entity_type: HistoryEntityType, entity_id: i64, limit: usize) -> AppResult<Vec<EntityHistoryEntry>> {
+        debug!("Fetching history of {} {}", entity_type, entity_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<EntityHistoryEntry>> {
+            let mut stmt = conn.prepare(
+                "SELECT h.id, h.action, h.changed_by, u.first_name || ' ' || u.last_name,
+                        h.changed_at, h.before_json, h.after_json
+                 FROM entity_history h LEFT JOIN users u ON h.changed_by = u.id
+                 WHERE h.entity_type = ?1 AND h.entity_id = ?2
+                 ORDER BY h.changed_at DESC, h.id DESC LIMIT ?3"
+            )?;
+            let rows = stmt
+                .query_map(params![entity_type.to_string(), entity_id, limit as i64], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<i64>>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                        row.get::<_, DateTime<Utc>>(4)?,
+                        row.get::<_, String>(5)?,
+                        row.get::<_, String>(6)?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut entries = Vec::with_capacity(rows.len());
+            for (id, action, changed_by, changed_by_name, changed_at, before, after) in rows {
+                let before: serde_json::Map<String, JsonValue> = serde_json::from_str(&before)?;
+                let after: serde_json::Map<String, JsonValue> = serde_json::from_str(&after)?;
+                entries.push(EntityHistoryEntry {
+                    id,
+                    entity_type,
+                    entity_id,
+                    action,
+                    changed_by,
+                    changed_by_name,
+                    changed_at,
+                    changes: FieldChange::diff(&before, &after),
+                    before: JsonValue::Object(before),
+                    after: JsonValue::Object(after),
+                });
+            }
+            Ok(entries)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+}
+
+// =============================================================================
+// Asset Record Service
+// =============================================================================
+
+const ASSET_RECORD_COLUMNS: &str =
+    "r.id, r.asset_id, r.kind, r.reference_number, r.issuer, r.issued_date, r.renewal_date,
+     r.notes, r.created_by, r.created_at, r.updated_at";
+
+fn row_to_asset_record(row: &Row) -> rusqlite::Result<AssetRecord> {
+    Ok(AssetRecord {
+        id: Some(row.get(0)?),
+        asset_id: row.get(1)?,
+        kind: row.get::<_, String>(2)?.parse().unwrap_or(AssetRecordKind::Insurance),
+        reference_number: row.get(3)?,
+        issuer: row.get(4)?,
+        issued_date: row.get(5)?,
+        renewal_date: row.get(6)?,
+        notes: row.get(7)?,
+        created_by: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    })
+}
+
+fn asset_record_not_found(id: i64) -> AppError {
+    AppError::RecordNotFound {
+        entity: "AssetRecord".to_string(),
+        field: "id".to_string(),
+        value: id.to_string(),
+    }
+}
+
+fn unique_asset_record(error: rusqlite::Error, input: &AssetRecordInput) -> AppError {
+    match &error {
+        rusqlite::Error::SqliteFailure(e, _) if e.code == rusqlite::ErrorCode::ConstraintViolation => {
+            AppError::DuplicateRecord {
+                entity: format!("{} record", input.kind),
+                field: "reference_number".to_string(),
+                value: input.reference_number.clone(),
+            }
+        }
+        _ => error.into(),
+    }
+}
+
+/// Insurance policies and statutory registrations held for assets, and the
+/// renewals coming due
+pub struct AssetRecordService {
+    database: Arc<Database>,
+}
+
+impl AssetRecordService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Add an insurance policy or registration to an asset
+    pub fn add_record(&self, context: &RequestContext, asset_id: i64, input: AssetRecordInput) -> AppResult<AssetRecord> {
+        info!("[{}] Adding {} record {} to asset {}",
+              context.request_id, input.kind, input.reference_number, asset_id);
+        input.validate()?;
+        let created_by = context.current_user().map(|u| u.user_id).ok();
+
+        self.database.with_transaction(|conn| {
+            let exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM assets WHERE id = ?1 AND deleted_at IS NULL)",
+                params![asset_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Err(AppError::RecordNotFound {
+                    entity: "Asset".to_string(),
+                    field: "id".to_string(),
+                    value: asset_id.to_string(),
+                });
+            }
+
+            let id: i64 = conn.query_row(
+                "INSERT INTO asset_records
+                    (asset_id, kind, reference_number, issuer, issued_date, renewal_date, notes, created_by)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                 RETURNING id",
+                params![
+                    asset_id, input.kind.to_string(), input.reference_number.trim(), input.issuer,
+                    input.issued_date, input.renewal_date, input.notes, created_by,
+                ],
+                |row| row.get(0),
+            ).map_err(|e| unique_asset_record(e, &input))?;
+            asset_record_by_id(conn, id)
+        })
+    }
+
+    /// Replace the details of an asset record, e.g. after a renewal
+    pub fn update_record(&self, context: &RequestContext, record_id: i64, input: AssetRecordInput) -> AppResult<AssetRecord> {
+        info!("[{}] Updating asset record {}", context.request_id, record_id);
+        input.validate()?;
+
+        self.database.with_transaction(|conn| {
+            let updated = conn.execute(
+                "UPDATE asset_records
+                 SET kind = ?1, reference_number = ?2, issuer = ?3, issued_date = ?4,
+                     renewal_date = ?5, notes = ?6, updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?7",
+                params![
+                    input.kind.to_string(), input.reference_number.trim(), input.issuer,
+                    input.issued_date, input.renewal_date, input.notes, record_id,
+                ],
+            ).map_err(|e| unique_asset_record(e, &input))?;
+            if updated == 0 {
+                return Err(asset_record_not_found(record_id));
+            }
+            asset_record_by_id(conn, record_id)
+        })
+    }
+
+    /// Remove an asset record. Returns the asset it belonged to.
+    pub fn delete_record(&self, context: &RequestContext, record_id: i64) -> AppResult<i64> {
+        info!("[{}] Deleting asset record {}", context.request_id, record_id);
+
+        self.database.with_transaction(|conn| {
+            conn.query_row(
+                "DELETE FROM asset_records WHERE id = ?1 RETURNING asset_id",
+                params![record_id],
+                |row| row.get(0),
+            ).optional()?.ok_or_else(|| asset_record_not_found(record_id))
+        })
+    }
+
+    /// Records held for an asset, soonest renewal first
+    pub fn get_records(&self, asset_id: i64) -> AppResult<Vec<AssetRecord>> {
+        debug!("Fetching insurance and registration records for asset: {}", asset_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<AssetRecord>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM asset_records r WHERE r.asset_id = ?1 ORDER BY r.renewal_date, r.id",
+                ASSET_RECORD_COLUMNS
+            ))?;
+            let records = stmt
+                .query_map(params![asset_id], row_to_asset_record)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(records)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Records on assets in service that are due for renewal within `days`,
+    /// including lapsed ones, soonest first
+    pub fn get_expiring(&self, days: i64, location_id: Option<i64>) -> AppResult<Vec<ExpiringAssetRecord>> {
+        debug!("Fetching asset records expiring within {} days (location: {:?})", days, location_id);
+        if days < 0 {
+            return Err(AppError::validation("days", "Days cannot be negative"));
+        }
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<ExpiringAssetRecord>> {
+            let today = Utc::now().date_naive();
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {}, a.asset_number, a.asset_name, a.location_id
+                 FROM asset_records r
+                 JOIN assets a ON r.asset_id = a.id
+                 WHERE a.deleted_at IS NULL
+                   AND a.status != 'Decommissioned'
+                   AND r.renewal_date <= ?1
+                   AND (?2 IS NULL OR a.location_id = ?2)
+                 ORDER BY r.renewal_date, r.id",
+                ASSET_RECORD_COLUMNS
+            ))?;
+            let expiring = stmt
+                .query_map(params![today + chrono::Duration::days(days), location_id], |row| {
+                    let record = row_to_asset_record(row)?;
+                    Ok(ExpiringAssetRecord {
+                        days_remaining: (record.renewal_date - today).num_days(),
+                        record,
+                        asset_number: row.get(11)?,
+                        asset_name: row.get(12)?,
+                        location_id: row.get(13)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(expiring)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+}
+
+fn asset_record_by_id(conn: &Connection, record_id: i64) -> AppResult<AssetRecord> {
+    conn.query_row(
+        &format!("SELECT {} FROM asset_records r WHERE r.id = ?1", ASSET_RECORD_COLUMNS),
+        params![record_id],
+        row_to_asset_record,
+    ).optional()?.ok_or_else(|| asset_record_not_found(record_id))
+}
+
+// =============================================================================
+// Vendor Document Service
+// =============================================================================
+
+const VENDOR_DOCUMENT_COLUMNS: &str =
+    "id, asset_id, sender, subject, received_at, file_name, file_path, mime_type, file_size,
+     matched_reference, filed_by, filed_at, created_at";
+
+fn row_to_vendor_document(row: &Row) -> rusqlite::Result<VendorDocument> {
+    Ok(VendorDocument {
+        id: row.get(0)?,
+        asset_id: row.get(1)?,
+        sender: row.get(2)?,
+        subject: row.get(3)?,
+        received_at: row.get(4)?,
+        file_name: row.get(5)?,
+        file_path: row.get(6)?,
+        mime_type: row.get(7)?,
+        file_size: row.get(8)?,
+        matched_reference: row.get(9)?,
+        filed_by: row.get(10)?,
+        filed_at: row.get(11)?,
+        created_at: row.get(12)?,
+    })
+}
+
+fn vendor_document_by_id(conn: &Connection, id: i64) -> AppResult<VendorDocument> {
+    conn.query_row(
+        &format!("SELECT {} FROM vendor_documents WHERE id = ?1", VENDOR_DOCUMENT_COLUMNS),
+        params![id],
+        row_to_vendor_document,
+    ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+        entity: "VendorDocument".to_string(),
+        field: "id".to_string(),
+        value: id.to_string(),
+    })
+}
+
+/// The one asset whose number appears in `subject`, with the code it matched
+/// on. Subjects naming no asset or several are left for manual filing.
+fn match_subject_to_asset(conn: &Connection, subject: &str) -> AppResult<Option<(i64, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id FROM assets WHERE asset_number = ?1 COLLATE NOCASE AND deleted_at IS NULL"
+    )?;
+    let mut matched: Option<(i64, String)> = None;
+    for reference in inbox::subject_references(subject) {
+        let ids = stmt.query_map(params![reference], |row| row.get::<_, i64>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        for id in ids {
+            match &matched {
+                Some((matched_id, _)) if *matched_id != id => return Ok(None),
+                Some(_) => {}
+                None => matched = Some((id, reference.clone())),
+            }
+        }
+    }
+    Ok(matched)
+}
+
+/// Documents received by email from inspection vendors and the queue of
+/// those not yet filed against an asset
+pub struct VendorDocumentService {
+    database: Arc<Database>,
+}
+
+impl VendorDocumentService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Store the attachments of an incoming email under `media_root` and
+    /// file them against the asset named in the subject, or queue them when
+    /// no single asset matches. Attachments of unsupported types or over the
+    /// size limit are skipped.
+    pub fn ingest_email(&self, context: &RequestContext, media_root: &Path, email: InboundEmail) -> AppResult<EmailIngestResult> {
+        info!("[{}] Ingesting email from {}: {}", context.request_id, email.sender, email.subject);
+        email.validate()?;
+        let received_at = email.received_at.unwrap_or_else(Utc::now);
+
+        let (accepted, skipped): (Vec<InboundAttachment>, Vec<InboundAttachment>) = email.attachments
+            .into_iter()
+            .partition(|a| {
+                !a.data.is_empty()
+                    && a.data.len() <= inbox::MAX_INBOUND_ATTACHMENT_BYTES
+                    && inbox::INBOUND_DOCUMENT_TYPES.contains(&a.mime_type.to_ascii_lowercase().as_str())
+            });
+        let skipped: Vec<String> = skipped.into_iter().map(|a| a.file_name).collect();
+        for file_name in &skipped {
+            warn!("[{}] Skipped attachment {} from {}", context.request_id, file_name, email.sender);
+        }
+
+        let conn = self.database.get_read_connection()?;
+        let matched = match_subject_to_asset(&conn, &email.subject)?;
+        let (asset_id, matched_reference) = match matched {
+            Some((asset_id, reference)) => (Some(asset_id), Some(reference)),
+            None => (None, None),
+        };
+
+        // Write the files first so a row never points at a missing file
+        let mut written = Vec::with_capacity(accepted.len());
+        let stored: AppResult<()> = accepted.iter().try_for_each(|attachment| {
+            let file_path = inbox::inbox_file_path(&attachment.file_name, received_at);
+            let full_path = media_root.join(&file_path);
+            if let Some(parent) = full_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&full_path, &attachment.data)?;
+            written.push((file_path, full_path));
+            Ok(())
+        });
+        let remove_written = |written: &[(String, std::path::PathBuf)]| {
+            for (_, full_path) in written {
+                let _ = std::fs::remove_file(full_path);
+            }
+        };
+        if let Err(e) = stored {
+            remove_written(&written);
+            return Err(e);
+        }
+
+        let documents = self.database.with_transaction(|conn| {
+            let mut documents = Vec::with_capacity(accepted.len());
+            for (attachment, (file_path, _)) in accepted.iter().zip(&written) {
+                let id = conn.query_row(
+                    "INSERT INTO vendor_documents (asset_id, sender, subject, received_at, file_name,
+                     file_path, mime_type, file_size, matched_reference)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                     RETURNING id",
+                    params![
+                        asset_id, email.sender.trim(), email.subject, received_at, attachment.file_name,
+                        file_path, attachment.mime_type.to_ascii_lowercase(), attachment.data.len() as i64,
+                        matched_reference
+                    ],
+                    |row| row.get::<_, i64>(0),
+                )?;
+                documents.push(vendor_document_by_id(conn, id)?);
+            }
+            Ok(documents)
+        });
+        let documents = documents.inspect_err(|_| remove_written(&written))?;
+
+        match asset_id {
+            Some(asset_id) => info!("[{}] Filed {} documents against asset {} ({})", context.request_id,
+                                    documents.len(), asset_id, matched_reference.as_deref().unwrap_or_default()),
+            None => info!("[{}] Queued {} unmatched documents", context.request_id, documents.len()),
+        }
+        Ok(EmailIngestResult { asset_id, matched_reference, documents, skipped })
+    }
+
+    /// Documents waiting to be filed, oldest first
+    pub fn get_unmatched_documents(&self) -> AppResult<Vec<VendorDocument>> {
+        debug!("Fetching unmatched vendor documents");
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<VendorDocument>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM vendor_documents WHERE asset_id IS NULL ORDER BY received_at, id",
+                VENDOR_DOCUMENT_COLUMNS
+            ))?;
+            let documents = stmt
+                .query_map([], row_to_vendor_document)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(documents)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Documents filed against an asset, newest first
+    pub fn get_asset_documents(&self, asset_id: i64) -> AppResult<Vec<VendorDocument>> {
+        debug!("Fetching vendor documents for asset: {}", asset_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<VendorDocument>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM vendor_documents WHERE asset_id = ?1 ORDER BY received_at DESC, id DESC",
+                VENDOR_DOCUMENT_COLUMNS
+            ))?;
+            let documents = stmt
+                .query_map(params![asset_id], row_to_vendor_document)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(documents)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// File a document against an asset by hand, or move a document that
+    /// was matched to the wrong asset
+    pub fn file_document(&self, context: &RequestContext, id: i64, asset_id: i64) -> AppResult<VendorDocument> {
+        info!("[{}] Filing vendor document {} against asset {}", context.request_id, id, asset_id);
+        let user_id = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            vendor_document_by_id(conn, id)?;
+            let asset_exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM assets WHERE id = ?1 AND deleted_at IS NULL)",
+                params![asset_id],
+                |row| row.get(0),
+            )?;
+            if !asset_exists {
+                return Err(AppError::RecordNotFound {
+                    entity: "Asset".to_string(),
+                    field: "id".to_string(),
+                    value: asset_id.to_string(),
+                });
+            }
+            conn.execute(
+                "UPDATE vendor_documents SET asset_id = ?2, filed_by = ?3, filed_at = ?4 WHERE id = ?1",
+                params![id, asset_id, user_id, Utc::now()],
+            )?;
+            vendor_document_by_id(conn, id)
+        })
+    }
+
+    /// Delete a document that should not be kept, such as spam in the
+    /// unmatched queue, along with its file under `media_root`
+    pub fn discard_document(&self, context: &RequestContext, media_root: &Path, id: i64) -> AppResult<VendorDocument> {
+        info!("[{}] Discarding vendor document {}", context.request_id, id);
+        let document = self.database.with_transaction(|conn| {
+            let document = vendor_document_by_id(conn, id)?;
+            if let Some(asset_id) = document.asset_id {
+                ensure_not_on_legal_hold(conn, LegalHoldEntityType::Asset, asset_id)?;
+            }
+            conn.execute("DELETE FROM vendor_documents WHERE id = ?1", params![id])?;
+            Ok(document)
+        })?;
+        if let Err(e) = std::fs::remove_file(media_root.join(&document.file_path)) {
+            warn!("[{}] Failed to delete file {}: {}", context.request_id, document.file_path, e);
+        }
+        Ok(document)
+    }
+}
+
+// =============================================================================
+// Maintenance Service
+// =============================================================================
+
+const MAINTENANCE_RECORD_COLUMNS: &str =
+    "id, asset_id, component_id, maintenance_type, scheduled_date, completed_date, performed_by,
+     description, status, parts_used, cost, created_at";
+
+fn row_to_maintenance_record(row: &Row) -> rusqlite::Result<MaintenanceRecord> {
+    Ok(MaintenanceRecord {
+        id: row.get(0)?,
+        asset_id: row.get(1)?,
+        component_id: row.get(2)?,
+        maintenance_type: row.get::<_, String>(3)?.parse().unwrap_or(MaintenanceType::Corrective),
+        scheduled_date: row.get(4)?,
+        completed_date: row.get(5)?,
+        performed_by: row.get(6)?,
+        description: row.get(7)?,
+        status: row.get::<_, String>(8)?.parse().unwrap_or(MaintenanceStatus::Scheduled),
+        parts_used: row.get::<_, Option<String>>(9)?.and_then(|s| serde_json::from_str(&s).ok()),
+        cost: row.get(10)?,
+        created_at: row.get(11)?,
+    })
+}
+
+fn maintenance_record_by_id(conn: &Connection, id: i64) -> AppResult<MaintenanceRecord> {
+    conn.query_row(
+        &format!("SELECT {} FROM maintenance_records WHERE id = ?1", MAINTENANCE_RECORD_COLUMNS),
+        params![id],
+        row_to_maintenance_record,
+    ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+        entity: "MaintenanceRecord".to_string(),
+        field: "id".to_string(),
+        value: id.to_string(),
+    })
+}
+
+/// Scheduling, carrying out and closing maintenance work on assets and their
+/// components. A component under active maintenance is marked `Maintenance`
+/// and returned to `Active` once no open work holds it.
+pub struct MaintenanceService {
+    database: Arc<Database>,
+}
+
+impl MaintenanceService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Record maintenance work. New records are scheduled or in progress, or
+    /// completed when logged after the fact with a completion date.
+    pub fn create_record(&self, context: &RequestContext, record: MaintenanceRecord) -> AppResult<MaintenanceRecord> {
+        info!("[{}] Creating {} maintenance record for asset {}",
+              context.request_id, record.maintenance_type, record.asset_id);
+        record.validate()?;
+        match record.status {
+            MaintenanceStatus::Cancelled => {
+                return Err(AppError::validation("status", "Maintenance cannot be created cancelled"));
+            }
+            MaintenanceStatus::Completed if record.completed_date.is_none() => {
+                return Err(AppError::validation("completed_date", "Completed maintenance needs a completion date"));
+            }
+            _ => {}
+        }
+
+        self.database.with_transaction(|conn| {
+            let time_zone = maintenance_asset_time_zone(conn, record.asset_id)?;
+            if let Some(component_id) = record.component_id {
+                ensure_component_on_asset(conn, component_id, record.asset_id)?;
+            }
+
+            let id: i64 = conn.query_row(
+                "INSERT INTO maintenance_records
+                    (asset_id, component_id, maintenance_type, scheduled_date, completed_date, performed_by,
+                     description, status, parts_used, cost, time_zone)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                 RETURNING id",
+                params![
+                    record.asset_id, record.component_id, record.maintenance_type.to_string(),
+                    record.scheduled_date, record.completed_date, record.performed_by.trim(),
+                    record.description.trim(), record.status.to_string(),
+                    record.parts_used.as_ref().map(serde_json::to_string).transpose()?,
+                    record.cost, time_zone,
+                ],
+                |row| row.get(0),
+            )?;
+            if record.status == MaintenanceStatus::InProgress {
+                if let Some(component_id) = record.component_id {
+                    hold_component(conn, component_id)?;
+                }
+            }
+
+            debug!("Maintenance record created with ID: {}", id);
+            maintenance_record_by_id(conn, id)
+        })
+    }
+
+    /// Change the details of maintenance that is not yet completed or cancelled
+    pub fn update_record(&self, context: &RequestContext, id: i64, updates: MaintenanceUpdateData) -> AppResult<MaintenanceRecord> {
+        info!("[{}] Updating maintenance record: {}", context.request_id, id);
+
+        self.database.with_transaction(|conn| {
+            let mut record = maintenance_record_by_id(conn, id)?;
+            ensure_maintenance_open(&record, "update")?;
+            let previous_component = record.component_id;
+
+            if let Some(component_id) = updates.component_id {
+                record.component_id = component_id;
+            }
+            if let Some(maintenance_type) = updates.maintenance_type {
+                record.maintenance_type = maintenance_type;
+            }
+            if let Some(scheduled_date) = updates.scheduled_date {
+                record.scheduled_date = scheduled_date;
+            }
+            if let Some(performed_by) = updates.performed_by {
+                record.performed_by = performed_by;
+            }
+            if let Some(description) = updates.description {
+                record.description = description;
+            }
+            if let Some(parts_used) = updates.parts_used {
+                record.parts_used = parts_used;
+            }
+            if let Some(cost) = updates.cost {
+                record.cost = cost;
+            }
+            record.validate()?;
+            if let Some(component_id) = record.component_id {
+                ensure_component_on_asset(conn, component_id, record.asset_id)?;
+            }
+
+            conn.execute(
+                "UPDATE maintenance_records
+                 SET component_id = ?1, maintenance_type = ?2, scheduled_date = ?3, performed_by = ?4,
+                     description = ?5, parts_used = ?6, cost = ?7
+                 WHERE id = ?8",
+                params![
+                    record.component_id, record.maintenance_type.to_string(), record.scheduled_date,
+                    record.performed_by.trim(), record.description.trim(),
+                    record.parts_used.as_ref().map(serde_json::to_string).transpose()?,
+                    record.cost, id,
+                ],
+            )?;
+
+            // Move the hold on a component along with the work
+            if record.status == MaintenanceStatus::InProgress && previous_component != record.component_id {
+                if let Some(component_id) = previous_component {
+                    release_component(conn, component_id)?;
+                }
+                if let Some(component_id) = record.component_id {
+                    hold_component(conn, component_id)?;
+                }
+            }
+
+            maintenance_record_by_id(conn, id)
+        })
+    }
+
+    /// Mark scheduled maintenance as in progress
+    pub fn start_record(&self, context: &RequestContext, id: i64) -> AppResult<MaintenanceRecord> {
+        info!("[{}] Starting maintenance record: {}", context.request_id, id);
+
+        self.database.with_transaction(|conn| {
+            let record = maintenance_record_by_id(conn, id)?;
+            if record.status != MaintenanceStatus::Scheduled {
+                return Err(AppError::validation(
+                    "status",
+                    format!("Only scheduled maintenance can be started, this is {}", record.status),
+                ));
+            }
+
+            conn.execute(
+                "UPDATE maintenance_records SET status = ?1 WHERE id = ?2",
+                params![MaintenanceStatus::InProgress.to_string(), id],
+            )?;
+            if let Some(component_id) = record.component_id {
+                hold_component(conn, component_id)?;
+            }
+            maintenance_record_by_id(conn, id)
+        })
+    }
+
+    /// Close maintenance as done, now unless a completion date is given, with
+    /// the parts and cost it took. Parts listed by part number and quantity
+    /// are taken out of stock.
+    pub fn complete_record(
+        &self,
+        context: &RequestContext,
+        id: i64,
+        completed_date: Option<DateTime<Utc>>,
+        parts_used: Option<JsonValue>,
+        cost: Option<f64>,
+    ) -> AppResult<MaintenanceRecord> {
+        info!("[{}] Completing maintenance record: {}", context.request_id, id);
+
+        self.database.with_transaction(|conn| {
+            let mut record = maintenance_record_by_id(conn, id)?;
+            ensure_maintenance_open(&record, "complete")?;
+
+            record.status = MaintenanceStatus::Completed;
+            record.completed_date = Some(completed_date.unwrap_or_else(Utc::now));
+            if parts_used.is_some() {
+                record.parts_used = parts_used;
+            }
+            if cost.is_some() {
+                record.cost = cost;
+            }
+            record.validate()?;
+            if record.completed_date > Some(Utc::now()) {
+                return Err(AppError::validation("completed_date", "Completion date cannot be in the future"));
+            }
+
+            conn.execute(
+                "UPDATE maintenance_records
+                 SET status = ?1, completed_date = ?2, parts_used = ?3, cost = ?4
+                 WHERE id = ?5",
+                params![
+                    record.status.to_string(), record.completed_date,
+                    record.parts_used.as_ref().map(serde_json::to_string).transpose()?,
+                    record.cost, id,
+                ],
+            )?;
+            if let Some(usages) = maintenance_part_usages(record.parts_used.as_ref()) {
+                consume_parts(conn, id, &usages, context.session.as_ref().map(|s| s.user_id))?;
+            }
+            if let Some(component_id) = record.component_id {
+                release_component(conn, component_id)?;
+            }
+            maintenance_record_by_id(conn, id)
+        })
+    }
+
+    /// Call off maintenance that has not been completed
+    pub fn cancel_record(&self, context: &RequestContext, id: i64) -> AppResult<MaintenanceRecord> {
+        info!("[{}] Cancelling maintenance record: {}", context.request_id, id);
+
+        self.database.with_transaction(|conn| {
+            let record = maintenance_record_by_id(conn, id)?;
+            ensure_maintenance_open(&record, "cancel")?;
+
+            conn.execute(
+                "UPDATE maintenance_records SET status = ?1 WHERE id = ?2",
+                params![MaintenanceStatus::Cancelled.to_string(), id],
+            )?;
+            if let Some(component_id) = record.component_id {
+                release_component(conn, component_id)?;
+            }
+            maintenance_record_by_id(conn, id)
+        })
+    }
+
+    pub fn get_record_by_id(&self, id: i64) -> AppResult<MaintenanceRecord> {
+        debug!("Fetching maintenance record by ID: {}", id);
+        let conn = self.database.get_connection()?;
+        let result = maintenance_record_by_id(&conn, id);
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Maintenance records of an asset, optionally only those on one
+    /// component, newest first
+    pub fn get_records_by_asset(&self, asset_id: i64, component_id: Option<i64>) -> AppResult<Vec<MaintenanceRecord>> {
+        debug!("Fetching maintenance records for asset {} (component: {:?})", asset_id, component_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<MaintenanceRecord>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM maintenance_records
+                 WHERE asset_id = ?1 AND (?2 IS NULL OR component_id = ?2)
+                 ORDER BY COALESCE(completed_date, scheduled_date, created_at) DESC, id DESC",
+                MAINTENANCE_RECORD_COLUMNS
+            ))?;
+            let records = stmt
+                .query_map(params![asset_id, component_id], row_to_maintenance_record)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(records)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+}
+
+fn ensure_maintenance_open(record: &MaintenanceRecord, operation: &str) -> AppResult<()> {
+    if matches!(record.status, MaintenanceStatus::Completed | MaintenanceStatus::Cancelled) {
+        return Err(AppError::validation(
+            "status",
+            format!("Cannot {} maintenance that is already {}", operation, record.status),
+        ));
+    }
+    Ok(())
+}
+
+/// Site time zone of an asset that can take maintenance
+fn maintenance_asset_time_zone(conn: &Connection, asset_id: i64) -> AppResult<String> {
+    let (status, time_zone): (String, String) = conn.query_row(
+        "SELECT a.status, l.time_zone FROM assets a JOIN locations l ON a.location_id = l.id
+         WHERE a.id = ?1 AND a.deleted_at IS NULL",
+        params![asset_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+        entity: "Asset".to_string(),
+        field: "id".to_string(),
+        value: asset_id.to_string(),
+    })?;
+    if status == AssetStatus::Decommissioned.to_string() {
+        return Err(AppError::CraneOperation {
+            crane_id: asset_id.to_string(),
+            operation: "maintenance".to_string(),
+            reason: "Asset is decommissioned".to_string(),
+        });
+    }
+    Ok(time_zone)
+}
+
+fn ensure_component_on_asset(conn: &Connection, component_id: i64, asset_id: i64) -> AppResult<()> {
+    let owner: i64 = conn.query_row(
+        "SELECT asset_id FROM components WHERE id = ?1",
+        params![component_id],
+        |row| row.get(0),
+    ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+        entity: "Component".to_string(),
+        field: "id".to_string(),
+        value: component_id.to_string(),
+    })?;
+    if owner != asset_id {
+        return Err(AppError::validation(
+            "component_id",
+            format!("Component {} does not belong to asset {}", component_id, asset_id),
+        ));
+    }
+    Ok(())
+}
+
+/// Mark an active component as under maintenance
+fn hold_component(conn: &Connection, component_id: i64) -> AppResult<()> {
+    conn.execute(
+        "UPDATE components SET status = 'Maintenance', updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?1 AND status = 'Active'",
+        params![component_id],
+    )?;
+    Ok(())
+}
+
+/// Return a component to service once no maintenance is in progress on it
+fn release_component(conn: &Connection, component_id: i64) -> AppResult<()> {
+    conn.execute(
+        "UPDATE components SET status = 'Active', updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?1 AND status = 'Maintenance'
+           AND NOT EXISTS (SELECT 1 FROM maintenance_records
+                           WHERE component_id = ?1 AND status = 'In Progress')",
+        params![component_id],
+    )?;
+    Ok(())
+}
+
+// =============================================================================
+// Operator Authorization Service
+// =============================================================================
+
+const OPERATOR_AUTHORIZATION_SELECT: &str =
+    "SELECT o.id, o.user_id, u.first_name || ' ' || u.last_name, o.asset_type, o.max_capacity,
+            o.max_capacity_unit, o.expires_at, o.notes, o.granted_by, o.granted_at, o.revoked_at
+     FROM operator_authorizations o
+     JOIN users u ON o.user_id = u.id";
+
+fn row_to_operator_authorization(row: &Row) -> rusqlite::Result<OperatorAuthorization> {
+    let unit: Option<String> = row.get(5)?;
+    Ok(OperatorAuthorization {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        operator_name: row.get(2)?,
+        asset_type: row.get(3)?,
+        max_capacity: Capacity::from_parts(row.get(4)?, unit.as_deref()).unwrap_or(None),
+        expires_at: row.get(6)?,
+        notes: row.get(7)?,
+        granted_by: row.get(8)?,
+        granted_at: row.get(9)?,
+        revoked_at: row.get(10)?,
+    })
+}
+
+fn operator_authorization_by_id(conn: &Connection, id: i64) -> AppResult<OperatorAuthorization> {
+    conn.query_row(
+        &format!("{} WHERE o.id = ?1", OPERATOR_AUTHORIZATION_SELECT),
+        params![id],
+        row_to_operator_authorization,
+    ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+        entity: "OperatorAuthorization".to_string(),
+        field: "id".to_string(),
+        value: id.to_string(),
+    })
+}
+
+/// Whether `user_id` holds a current authorization covering the type and
+/// rated capacity of `asset_id`
+fn check_operator_authorization(conn: &Connection, user_id: i64, asset_id: i64) -> AppResult<AuthorizationCheck> {
+    let (asset_type, capacity, capacity_unit): (String, Option<f64>, Option<String>) = conn.query_row(
+        "SELECT asset_type, capacity, capacity_unit FROM assets WHERE id = ?1 AND deleted_at IS NULL",
+        params![asset_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+        entity: "Asset".to_string(),
+        field: "id".to_string(),
+        value: asset_id.to_string(),
+    })?;
+    let rated = Capacity::from_parts(capacity, capacity_unit.as_deref()).unwrap_or(None);
+
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE o.user_id = ?1 AND o.revoked_at IS NULL AND LOWER(o.asset_type) = LOWER(?2)
+         ORDER BY o.expires_at IS NULL DESC, o.expires_at DESC",
+        OPERATOR_AUTHORIZATION_SELECT
+    ))?;
+    let held = stmt
+        .query_map(params![user_id, asset_type], row_to_operator_authorization)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let today = Utc::now().date_naive();
+    let covers_capacity = |authorization: &OperatorAuthorization| match (&authorization.max_capacity, &rated) {
+        (None, _) => true,
+        (Some(max), Some(rated)) => {
+            rated.to_kilograms() <= max.to_kilograms() * (1.0 + units::CAPACITY_MATCH_TOLERANCE)
+        }
+        (Some(_), None) => false,
+    };
+    let current: Vec<&OperatorAuthorization> = held
+        .iter()
+        .filter(|authorization| authorization.expires_at.is_none_or(|expires| expires >= today))
+        .collect();
+    let covering = current.iter().find(|authorization| covers_capacity(authorization));
+
+    let reason = match covering {
+        Some(_) => None,
+        None if held.is_empty() => Some(format!("No authorization for {} assets", asset_type)),
+        None if current.is_empty() => Some(format!("Authorization for {} assets has expired", asset_type)),
+        None if rated.is_none() => Some("Asset has no rated capacity to check against the authorized limit".to_string()),
+        None => Some(format!(
+            "Authorized capacity for {} assets is below the rated {}",
+            asset_type,
+            rated.map(|c| c.to_string()).unwrap_or_default()
+        )),
+    };
+
+    Ok(AuthorizationCheck {
+        user_id,
+        asset_id,
+        authorized: covering.is_some(),
+        authorization_id: covering.map(|authorization| authorization.id),
+        expires_at: covering.and_then(|authorization| authorization.expires_at),
+        reason,
+    })
+}
+
+/// Which operators may run which asset types and capacities, and until when
+pub struct OperatorAuthorizationService {
+    database: Arc<Database>,
+}
+
+impl OperatorAuthorizationService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Authorize an operator for an asset type, optionally up to a capacity
+    /// and until an expiry date
+    pub fn grant(&self, context: &RequestContext, input: OperatorAuthorizationInput) -> AppResult<OperatorAuthorization> {
+        info!("[{}] Authorizing user {} for {} assets", context.request_id, input.user_id, input.asset_type);
+        input.validate()?;
+        let granted_by = context.current_user().map(|u| u.user_id).ok();
+
+        self.database.with_transaction(|conn| {
+            let active: bool = conn.query_row(
+                "SELECT is_active FROM users WHERE id = ?1 AND deleted_at IS NULL",
+                params![input.user_id],
+                |row| row.get(0),
+            ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+                entity: "User".to_string(),
+                field: "id".to_string(),
+                value: input.user_id.to_string(),
+            })?;
+            if !active {
+                return Err(AppError::validation("user_id", "Inactive users cannot be authorized"));
+            }
+
+            let id: i64 = conn.query_row(
+                "INSERT INTO operator_authorizations
+                    (user_id, asset_type, max_capacity, max_capacity_unit, expires_at, notes, granted_by)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 RETURNING id",
+                params![
+                    input.user_id, input.asset_type.trim(),
+                    input.max_capacity.map(|c| c.value), input.max_capacity.map(|c| c.unit.symbol()),
+                    input.expires_at, input.notes, granted_by,
+                ],
+                |row| row.get(0),
+            )?;
+            operator_authorization_by_id(conn, id)
+        })
+    }
+
+    /// Withdraw an authorization before it expires
+    pub fn revoke(&self, context: &RequestContext, id: i64) -> AppResult<OperatorAuthorization> {
+        info!("[{}] Revoking operator authorization {}", context.request_id, id);
+        let revoked_by = context.current_user().map(|u| u.user_id).ok();
+
+        self.database.with_transaction(|conn| {
+            let authorization = operator_authorization_by_id(conn, id)?;
+            if authorization.revoked_at.is_some() {
+                return Err(AppError::validation("id", format!("Authorization {} is already revoked", id)));
+            }
+            conn.execute(
+                "UPDATE operator_authorizations SET revoked_at = CURRENT_TIMESTAMP, revoked_by = ?1 WHERE id = ?2",
+                params![revoked_by, id],
+            )?;
+            operator_authorization_by_id(conn, id)
+        })
+    }
+
+    /// Authorizations not revoked, for one operator or all, including expired
+    /// ones when asked
+    pub fn get_authorizations(&self, user_id: Option<i64>, include_expired: bool) -> AppResult<Vec<OperatorAuthorization>> {
+        debug!("Fetching operator authorizations (user: {:?}, expired: {})", user_id, include_expired);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<OperatorAuthorization>> {
+            let mut stmt = conn.prepare(&format!(
+                "{} WHERE o.revoked_at IS NULL
+                   AND (?1 IS NULL OR o.user_id = ?1)
+                   AND (?2 OR o.expires_at IS NULL OR o.expires_at >= ?3)
+                 ORDER BY u.last_name, u.first_name, o.asset_type",
+                OPERATOR_AUTHORIZATION_SELECT
+            ))?;
+            let authorizations = stmt
+                .query_map(params![user_id, include_expired, Utc::now().date_naive()], row_to_operator_authorization)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(authorizations)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Whether an operator may be recorded running an asset
+    pub fn check(&self, user_id: i64, asset_id: i64) -> AppResult<AuthorizationCheck> {
+        debug!("Checking authorization of user {} for asset {}", user_id, asset_id);
+        let conn = self.database.get_connection()?;
+        let result = check_operator_authorization(&conn, user_id, asset_id);
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Every operator holding an authorization against every asset type, with
+    /// the best standing per type
+    pub fn get_matrix(&self) -> AppResult<AuthorizationMatrix> {
+        info!("Generating operator authorization matrix");
+        let authorizations = self.get_authorizations(None, true)?;
+        let conn = self.database.get_read_connection()?;
+
+        // Asset types keyed by lowercase name, spelled as on the assets
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT asset_type FROM assets WHERE deleted_at IS NULL ORDER BY asset_type"
+        )?;
+        let mut asset_types: HashMap<String, String> = HashMap::new();
+        for asset_type in stmt.query_map([], |row| row.get::<_, String>(0))? {
+            let asset_type = asset_type?;
+            asset_types.entry(asset_type.to_lowercase()).or_insert(asset_type);
+        }
+        for authorization in &authorizations {
+            asset_types
+                .entry(authorization.asset_type.to_lowercase())
+                .or_insert_with(|| authorization.asset_type.clone());
+        }
+
+        let today = Utc::now().date_naive();
+        let status_of = |expires_at: Option<NaiveDate>| match expires_at {
+            Some(expires) if expires < today => AuthorizationStatus::Expired,
+            Some(expires) if (expires - today).num_days() <= AUTHORIZATION_WARNING_DAYS => AuthorizationStatus::ExpiringSoon,
+            _ => AuthorizationStatus::Current,
+        };
+
+        let mut rows: Vec<AuthorizationMatrixRow> = Vec::new();
+        for authorization in authorizations {
+            let index = match rows.iter().position(|row| row.user_id == authorization.user_id) {
+                Some(index) => index,
+                None => {
+                    rows.push(AuthorizationMatrixRow {
+                        user_id: authorization.user_id,
+                        operator_name: authorization.operator_name.clone(),
+                        authorizations: std::collections::BTreeMap::new(),
+                    });
+                    rows.len() - 1
+                }
+            };
+            let asset_type = asset_types[&authorization.asset_type.to_lowercase()].clone();
+            let cell = AuthorizationMatrixCell {
+                authorization_id: authorization.id,
+                status: status_of(authorization.expires_at),
+                max_capacity: authorization.max_capacity,
+                expires_at: authorization.expires_at,
+            };
+            // Keep the authorization that lasts longest
+            let outlasts = |held: &AuthorizationMatrixCell| match (cell.expires_at, held.expires_at) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (Some(new), Some(old)) => new > old,
+            };
+            let cells = &mut rows[index].authorizations;
+            if cells.get(&asset_type).is_none_or(outlasts) {
+                cells.insert(asset_type, cell);
+            }
+        }
+
+        let mut asset_types: Vec<String> = asset_types.into_values().collect();
+        asset_types.sort();
+        Ok(AuthorizationMatrix {
+            generated_at: Utc::now(),
+            asset_types,
+            rows,
+        })
+    }
+}
+
+// =============================================================================
+// Training Service
+// =============================================================================
+
+const TRAINING_RECORD_COLUMNS: &str =
+    "t.id, t.user_id, t.course, t.provider, t.issued_date, t.expiry_date, t.certificate_media_id,
+     t.qualifies_for, t.created_by, t.created_at, t.updated_at";
+
+fn row_to_training_record(row: &Row) -> rusqlite::Result<TrainingRecord> {
+    let qualifies_for: String = row.get(7)?;
+    Ok(TrainingRecord {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        course: row.get(2)?,
+        provider: row.get(3)?,
+        issued_date: row.get(4)?,
+        expiry_date: row.get(5)?,
+        certificate_media_id: row.get(6)?,
+        qualifies_for: serde_json::from_str(&qualifies_for).unwrap_or_default(),
+        created_by: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    })
+}
+
+fn training_record_by_id(conn: &Connection, id: i64) -> AppResult<TrainingRecord> {
+    conn.query_row(
+        &format!("SELECT {} FROM training_records t WHERE t.id = ?1", TRAINING_RECORD_COLUMNS),
+        params![id],
+        row_to_training_record,
+    ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+        entity: "TrainingRecord".to_string(),
+        field: "id".to_string(),
+        value: id.to_string(),
+    })
+}
+
+fn ensure_certificate_exists(conn: &Connection, media_id: Option<i64>) -> AppResult<()> {
+    let Some(media_id) = media_id else {
+        return Ok(());
+    };
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM media_files WHERE id = ?1)",
+        params![media_id],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Err(AppError::RecordNotFound {
+            entity: "MediaFile".to_string(),
+            field: "id".to_string(),
+            value: media_id.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Courses completed by users and the inspection types they qualify them for
+pub struct TrainingService {
+    database: Arc<Database>,
+}
+
+impl TrainingService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Record a completed course for a user
+    pub fn add_record(&self, context: &RequestContext, input: TrainingRecordInput) -> AppResult<TrainingRecord> {
+        info!("[{}] Adding training record '{}' for user {}", context.request_id, input.course, input.user_id);
+        input.validate()?;
+        let created_by = context.current_user().map(|u| u.user_id).ok();
+
+        self.database.with_transaction(|conn| {
+            let exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM users WHERE id = ?1 AND deleted_at IS NULL)",
+                params![input.user_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Err(AppError::RecordNotFound {
+                    entity: "User".to_string(),
+                    field: "id".to_string(),
+                    value: input.user_id.to_string(),
+                });
+            }
+            ensure_certificate_exists(conn, input.certificate_media_id)?;
+
+            let id: i64 = conn.query_row(
+                "INSERT INTO training_records
+                    (user_id, course, provider, issued_date, expiry_date, certificate_media_id, qualifies_for, created_by)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                 RETURNING id",
+                params![
+                    input.user_id, input.course.trim(), input.provider, input.issued_date, input.expiry_date,
+                    input.certificate_media_id, serde_json::to_string(&input.qualifies_for)?, created_by,
+                ],
+                |row| row.get(0),
+            )?;
+            training_record_by_id(conn, id)
+        })
+    }
+
+    /// Replace the details of a training record, e.g. after a refresher course
+    pub fn update_record(&self, context: &RequestContext, id: i64, input: TrainingRecordInput) -> AppResult<TrainingRecord> {
+        info!("[{}] Updating training record {}", context.request_id, id);
+        input.validate()?;
+
+        self.database.with_transaction(|conn| {
+            let record = training_record_by_id(conn, id)?;
+            if record.user_id != input.user_id {
+                return Err(AppError::validation("user_id", "A training record cannot be moved to another user"));
+            }
+            ensure_certificate_exists(conn, input.certificate_media_id)?;
+
+            conn.execute(
+                "UPDATE training_records
+                 SET course = ?1, provider = ?2, issued_date = ?3, expiry_date = ?4, certificate_media_id = ?5,
+                     qualifies_for = ?6, updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?7",
+                params![
+                    input.course.trim(), input.provider, input.issued_date, input.expiry_date,
+                    input.certificate_media_id, serde_json::to_string(&input.qualifies_for)?, id,
+                ],
+            )?;
+            training_record_by_id(conn, id)
+        })
+    }
+
+    /// Remove a training record. Returns the user it belonged to.
+    pub fn delete_record(&self, context: &RequestContext, id: i64) -> AppResult<i64> {
+        info!("[{}] Deleting training record {}", context.request_id, id);
+
+        self.database.with_transaction(|conn| {
+            conn.query_row(
+                "DELETE FROM training_records WHERE id = ?1 RETURNING user_id",
+                params![id],
+                |row| row.get(0),
+            ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+                entity: "TrainingRecord".to_string(),
+                field: "id".to_string(),
+                value: id.to_string(),
+            })
+        })
+    }
+
+    /// A user's training, latest first
+    pub fn get_records(&self, user_id: i64) -> AppResult<Vec<TrainingRecord>> {
+        debug!("Fetching training records for user: {}", user_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<TrainingRecord>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM training_records t WHERE t.user_id = ?1 ORDER BY t.issued_date DESC, t.id DESC",
+                TRAINING_RECORD_COLUMNS
+            ))?;
+            let records = stmt
+                .query_map(params![user_id], row_to_training_record)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(records)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Training of active users that expires within `days`, including lapsed
+    /// training not yet superseded by a later record for the same course,
+    /// soonest first
+    pub fn get_expiring(&self, days: i64) -> AppResult<Vec<ExpiringTraining>> {
+        debug!("Fetching training expiring within {} days", days);
+        if days < 0 {
+            return Err(AppError::validation("days", "Days cannot be negative"));
+        }
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<ExpiringTraining>> {
+            let today = Utc::now().date_naive();
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {}, u.first_name || ' ' || u.last_name
+                 FROM training_records t
+                 JOIN users u ON t.user_id = u.id
+                 WHERE u.is_active = 1 AND u.deleted_at IS NULL
+                   AND t.expiry_date IS NOT NULL AND t.expiry_date <= ?1
+                   AND NOT EXISTS (
+                       SELECT 1 FROM training_records later
+                       WHERE later.user_id = t.user_id AND LOWER(later.course) = LOWER(t.course)
+                         AND (later.expiry_date IS NULL OR later.expiry_date > ?1)
+                   )
+                 ORDER BY t.expiry_date, t.id",
+                TRAINING_RECORD_COLUMNS
+            ))?;
+            let expiring = stmt
+                .query_map(params![today + chrono::Duration::days(days)], |row| {
+                    let record = row_to_training_record(row)?;
+                    Ok(ExpiringTraining {
+                        days_remaining: record.expiry_date.map(|expiry| (expiry - today).num_days()).unwrap_or_default(),
+                        record,
+                        user_name: row.get(11)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(expiring)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+}
+
+// =============================================================================
+// Work Order Service
+// =============================================================================
+
+const WORK_ORDER_COLUMNS: &str =
+    "id, inspection_item_id, inspection_id, asset_id, component_id, title, description, priority, status,
+     assignee_id, due_date, parts, completion_notes, created_by, started_at, completed_at, verified_at,
+     verified_by, created_at, updated_at";
+
+fn row_to_work_order(row: &Row) -> rusqlite::Result<WorkOrder> {
+    let parts: String = row.get(11)?;
+    Ok(WorkOrder {
+        id: row.get(0)?,
+        inspection_item_id: row.get(1)?,
+        inspection_id: row.get(2)?,
+        asset_id: row.get(3)?,
+        component_id: row.get(4)?,
+        title: row.get(5)?,
+        description: row.get(6)?,
+        priority: row.get::<_, String>(7)?.parse().unwrap_or(WorkOrderPriority::Normal),
+        status: row.get::<_, String>(8)?.parse().unwrap_or(WorkOrderStatus::Open),
+        assignee_id: row.get(9)?,
+        due_date: row.get(10)?,
+        parts: serde_json::from_str(&parts).unwrap_or_default(),
+        completion_notes: row.get(12)?,
+        created_by: row.get(13)?,
+        started_at: row.get(14)?,
+        completed_at: row.get(15)?,
+        verified_at: row.get(16)?,
+        verified_by: row.get(17)?,
+        created_at: row.get(18)?,
+        updated_at: row.get(19)?,
+    })
+}
+
+fn work_order_by_id(conn: &Connection, id: i64) -> AppResult<WorkOrder> {
+    conn.query_row(
+        &format!("SELECT {} FROM work_orders WHERE id = ?1", WORK_ORDER_COLUMNS),
+        params![id],
+        row_to_work_order,
+    ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+        entity: "WorkOrder".to_string(),
+        field: "id".to_string(),
+        value: id.to_string(),
+    })
+}
+
+fn ensure_assignable(conn: &Connection, user_id: Option<i64>) -> AppResult<()> {
+    let Some(user_id) = user_id else {
+        return Ok(());
+    };
+    let active: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM users WHERE id = ?1 AND is_active = 1 AND deleted_at IS NULL)",
+        params![user_id],
+        |row| row.get(0),
+    )?;
+    if !active {
+        return Err(AppError::validation("assignee_id", format!("User {} is not an active user", user_id)));
+    }
+    Ok(())
+}
+
+/// Repair work raised from non-compliant inspection items and tracked
+/// through to verification
+pub struct WorkOrderService {
+    database: Arc<Database>,
+}
+
+impl WorkOrderService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Raise a work order for a non-compliant inspection item. An item has at
+    /// most one work order that is not cancelled.
+    pub fn create_from_item(&self, context: &RequestContext, item_id: i64, input: WorkOrderInput) -> AppResult<WorkOrder> {
+        info!("[{}] Raising work order for inspection item: {}", context.request_id, item_id);
+        input.validate()?;
+        let created_by = context.current_user().map(|u| u.user_id).ok();
+
+        self.database.with_transaction(|conn| {
+            let (item, asset_id) = conn.query_row(
+                "SELECT ii.inspection_id, ii.component_id, ii.item_name, ii.item_category, ii.finding, ii.severity,
+                        ii.is_compliant, ii.created_at, i.asset_id
+                 FROM inspection_items ii
+                 JOIN inspections i ON ii.inspection_id = i.id
+                 WHERE ii.id = ?1",
+                params![item_id],
+                |row| {
+                    let item = InspectionItem {
+                        id: item_id,
+                        inspection_id: row.get(0)?,
+                        component_id: row.get(1)?,
+                        item_name: row.get(2)?,
+                        item_category: row.get(3)?,
+                        condition: None,
+                        finding: row.get(4)?,
+                        severity: row.get::<_, Option<String>>(5)?.and_then(|s| s.parse().ok()),
+                        is_compliant: row.get(6)?,
+                        corrective_action: None,
+                        created_at: row.get(7)?,
+                        clause_id: None,
+                        risk_rating: None,
+                        response_due_at: None,
+                        measured_value: None,
+                        measurement_unit: None,
+                    };
+                    Ok((item, row.get::<_, i64>(8)?))
+                },
+            ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+                entity: "InspectionItem".to_string(),
+                field: "id".to_string(),
+                value: item_id.to_string(),
+            })?;
+
+            if item.is_compliant != Some(false) {
+                return Err(AppError::validation(
+                    "inspection_item_id",
+                    format!("Inspection item {} is not marked non-compliant", item_id),
+                ));
+            }
+            let existing: Option<i64> = conn.query_row(
+                "SELECT id FROM work_orders WHERE inspection_item_id = ?1 AND status != 'Cancelled'",
+                params![item_id],
+                |row| row.get(0),
+            ).optional()?;
+            if existing.is_some() {
+                return Err(AppError::DuplicateRecord {
+                    entity: "WorkOrder".to_string(),
+                    field: "inspection_item_id".to_string(),
+                    value: item_id.to_string(),
+                });
+            }
+            ensure_assignable(conn, input.assignee_id)?;
+
+            let priority = input.priority.unwrap_or_else(|| WorkOrderPriority::for_severity(item.severity.as_ref()));
+            let title = input.title.as_deref().map(str::trim).unwrap_or(&item.item_name);
+            let description = input.description.clone().or(item.finding);
+
+            let id: i64 = conn.query_row(
+                "INSERT INTO work_orders
+                    (inspection_item_id, inspection_id, asset_id, component_id, title, description, priority,
+                     status, assignee_id, due_date, parts, created_by)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'Open', ?8, ?9, ?10, ?11)
+                 RETURNING id",
+                params![
+                    item_id, item.inspection_id, asset_id, item.component_id, title, description, priority.to_string(),
+                    input.assignee_id, input.due_date, serde_json::to_string(&input.parts)?, created_by,
+                ],
+                |row| row.get(0),
+            )?;
+            acknowledge_finding_sla(conn, item_id, created_by)?;
+            work_order_by_id(conn, id)
+        })
+    }
+
+    /// Change the details of a work order that is not yet finished
+    pub fn update_work_order(&self, context: &RequestContext, id: i64, updates: WorkOrderUpdateData) -> AppResult<WorkOrder> {
+        info!("[{}] Updating work order: {}", context.request_id, id);
+        updates.validate()?;
+
+        self.database.with_transaction(|conn| {
+            let mut order = work_order_by_id(conn, id)?;
+            if !order.status.is_open() {
+                return Err(AppError::validation(
+                    "status",
+                    format!("Only open work orders can be changed, this is {}", order.status),
+                ));
+            }
+
+            if let Some(title) = updates.title {
+                order.title = title.trim().to_string();
+            }
+            if let Some(description) = updates.description {
+                order.description = description;
+            }
+            if let Some(priority) = updates.priority {
+                order.priority = priority;
+            }
+            if let Some(assignee_id) = updates.assignee_id {
+                ensure_assignable(conn, assignee_id)?;
+                order.assignee_id = assignee_id;
+            }
+            if let Some(due_date) = updates.due_date {
+                order.due_date = due_date;
+            }
+            if let Some(parts) = updates.parts {
+                order.parts = parts;
+            }
+
+            conn.execute(
+                "UPDATE work_orders
+                 SET title = ?1, description = ?2, priority = ?3, assignee_id = ?4, due_date = ?5, parts = ?6,
+                     updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?7",
+                params![
+                    order.title, order.description, order.priority.to_string(), order.assignee_id,
+                    order.due_date, serde_json::to_string(&order.parts)?, id,
+                ],
+            )?;
+            work_order_by_id(conn, id)
+        })
+    }
+
+    /// Move a work order to its next status, with notes on the work done or
+    /// why it was cancelled. Verification must be done by someone other than
+    /// the assignee.
+    pub fn transition(
+        &self,
+        context: &RequestContext,
+        id: i64,
+        status: WorkOrderStatus,
+        notes: Option<String>,
+    ) -> AppResult<WorkOrder> {
+        info!("[{}] Moving work order {} to {}", context.request_id, id, status);
+        let user_id = context.current_user().map(|u| u.user_id).ok();
+
+        self.database.with_transaction(|conn| {
+            let order = work_order_by_id(conn, id)?;
+            if !order.status.can_transition_to(status) {
+                return Err(AppError::validation(
+                    "status",
+                    format!("Work order {} cannot move from {} to {}", id, order.status, status),
+                ));
+            }
+
+            if status == WorkOrderStatus::Verified && user_id.is_some() && user_id == order.assignee_id {
+                return Err(AppError::validation(
+                    "verified_by",
+                    "A work order must be verified by someone other than its assignee",
+                ));
+            }
+
+            // Reopening clears the completion; notes are kept from the last
+            // status that gave them
+            conn.execute(
+                "UPDATE work_orders
+                 SET status = ?1,
+                     started_at = CASE WHEN ?1 = 'In Progress' THEN COALESCE(started_at, ?2) ELSE started_at END,
+                     completed_at = CASE ?1 WHEN 'In Progress' THEN NULL WHEN 'Completed' THEN ?2 ELSE completed_at END,
+                     verified_at = CASE WHEN ?1 = 'Verified' THEN ?2 ELSE verified_at END,
+                     verified_by = CASE WHEN ?1 = 'Verified' THEN ?3 ELSE verified_by END,
+                     completion_notes = COALESCE(?4, completion_notes),
+                     updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?5",
+                params![status.to_string(), Utc::now(), user_id, notes, id],
+            )?;
+            if status == WorkOrderStatus::Verified {
+                let resolution = notes.as_deref().or(order.completion_notes.as_deref());
+                resolve_finding_sla(conn, order.inspection_item_id, user_id, resolution)?;
+            }
+            sync_defect_with_work_order(conn, order.inspection_item_id, status, user_id)?;
+            work_order_by_id(conn, id)
+        })
+    }
+
+    pub fn get_work_order(&self, id: i64) -> AppResult<WorkOrder> {
+        debug!("Fetching work order: {}", id);
+        let conn = self.database.get_connection()?;
+        let result = work_order_by_id(&conn, id);
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Work orders matching the filter, most urgent and soonest due first
+    pub fn get_work_orders(&self, filter: &WorkOrderFilter) -> AppResult<Vec<WorkOrder>> {
+        debug!("Fetching work orders: {:?}", filter);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<WorkOrder>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM work_orders
+                 WHERE (?1 IS NULL OR status = ?1)
+                   AND (?2 IS NULL OR asset_id = ?2)
+                   AND (?3 IS NULL OR assignee_id = ?3)
+                   AND (?4 IS NULL OR inspection_id = ?4)
+                   AND (NOT ?5 OR (status IN ('Open', 'In Progress') AND due_date < ?6))
+                 ORDER BY CASE priority WHEN 'Urgent' THEN 0 WHEN 'High' THEN 1 WHEN 'Normal' THEN 2 ELSE 3 END,
+                          due_date IS NULL, due_date, id",
+                WORK_ORDER_COLUMNS
+            ))?;
+            let orders = stmt
+                .query_map(
+                    params![
+                        filter.status.map(|s| s.to_string()), filter.asset_id, filter.assignee_id,
+                        filter.inspection_id, filter.overdue_only, Utc::now().date_naive(),
+                    ],
+                    row_to_work_order,
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(orders)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+}
+
+// =============================================================================
+// Notification Service
+// =============================================================================
+
+const NOTIFICATION_COLUMNS: &str = "id, user_id, kind, title, body, entity_type, entity_id, created_at, read_at";
+
+fn row_to_notification(row: &Row) -> rusqlite::Result<Notification> {
+    Ok(Notification {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        kind: row.get::<_, String>(2)?.parse().unwrap_or(NotificationKind::Assignment),
+        title: row.get(3)?,
+        body: row.get(4)?,
+        entity_type: row.get(5)?,
+        entity_id: row.get(6)?,
+        created_at: row.get(7)?,
+        read_at: row.get(8)?,
+    })
+}
+
+/// Add a delivery to the outbox. Called in the transaction making the change
+/// the delivery is about, so it is sent if and only if the change commits.
+pub fn enqueue_outbox(conn: &Connection, message: &OutboxMessage, now: DateTime<Utc>) -> AppResult<i64> {
+    let id = conn.query_row(
+        "INSERT INTO outbox (message, next_attempt_at) VALUES (?1, ?2) RETURNING id",
+        params![serde_json::to_string(message)?, now],
+        |row| row.get(0),
+    )?;
+    Ok(id)
+}
+
+/// Minutes a claimed outbox delivery is left to its dispatcher before
+/// another may try it
+const OUTBOX_LEASE_MINUTES: i64 = 5;
+
+/// Days delivered outbox rows are kept
+const OUTBOX_RETENTION_DAYS: i64 = 7;
+
+fn digest_settings(conn: &Connection, user_id: i64) -> AppResult<NotificationDigestSettings> {
+    let defaults = NotificationDigestSettings::defaults();
+    let stored = conn.query_row(
+        "SELECT delivery, send_at, time_zone, last_sent_at FROM notification_digests WHERE user_id = ?1",
+        params![user_id],
+        |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get(3)?)),
+    ).optional()?;
+    let Some((delivery, send_at, time_zone, last_sent_at)) = stored else {
+        return Ok(defaults);
+    };
+    Ok(NotificationDigestSettings {
+        delivery: delivery.parse().unwrap_or(defaults.delivery),
+        send_at: NaiveTime::parse_from_str(&send_at, "%H:%M").unwrap_or(defaults.send_at),
+        time_zone,
+        last_sent_at: Some(last_sent_at),
+    })
+}
+
+/// Build a user's digest: their overdue inspections, work orders and
+/// corrective actions as of `now`, and the notifications since `since` of
+/// the kinds they take by email
+fn user_digest(
+    conn: &Connection,
+    user_id: i64,
+    since: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    tz: chrono_tz::Tz,
+) -> AppResult<NotificationDigest> {
+    let mut stmt = conn.prepare(
+        "SELECT 'inspection', i.id,
+                i.inspection_type || ' inspection of ' || a.asset_number || ' ' || a.asset_name
+                    || ' was due ' || date(i.scheduled_date)
+         FROM inspections i
+         JOIN assets a ON a.id = i.asset_id
+         WHERE i.inspector_id = ?1 AND i.deleted_at IS NULL AND i.status NOT IN ('Completed', 'Cancelled')
+           AND COALESCE(i.overdue_at, i.scheduled_date) < ?2
+         UNION ALL
+         SELECT 'work_order', id, 'Work order: ' || title || ' was due ' || due_date
+         FROM work_orders
+         WHERE assignee_id = ?1 AND status IN ('Open', 'In Progress') AND due_date < ?3
+         UNION ALL
+         SELECT 'corrective_action', id, 'Corrective action: ' || description || ' was due ' || due_date
+         FROM corrective_actions
+         WHERE assignee_id = ?1 AND status IN ('Open', 'In Progress') AND due_date < ?3"
+    )?;
+    let overdue = stmt
+        .query_map(params![user_id, now, scheduling::local_date(now, tz)], |row| {
+            Ok(DigestEntry {
+                entity_type: Some(row.get(0)?),
+                entity_id: Some(row.get(1)?),
+                summary: row.get(2)?,
+                occurrences: 1,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM notifications n
+         WHERE n.user_id = ?1 AND (?2 IS NULL OR n.created_at > ?2)
+           AND NOT EXISTS (
+               SELECT 1 FROM notification_preferences p
+               WHERE p.user_id = n.user_id AND p.kind = n.kind AND p.channel = 'Email' AND NOT p.enabled
+           )
+         ORDER BY n.created_at, n.id",
+        NOTIFICATION_COLUMNS
+    ))?;
+    let notifications = stmt
+        .query_map(params![user_id, since], row_to_notification)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(notifications::build_digest(user_id, since, now, overdue, &notifications))
+}
+
+/// Records notifications as each user's in-app inbox and hands them to the
+/// registered delivery channels
+pub struct NotificationService {
+    database: Arc<Database>,
+    channels: RwLock<Vec<Arc<dyn NotificationChannel>>>,
+    cipher: Arc<FieldCipher>,
+}
+
+impl NotificationService {
+    pub fn new(database: Arc<Database>, cipher: Arc<FieldCipher>) -> Self {
+        Self {
+            database,
+            channels: RwLock::new(vec![Arc::new(InAppChannel)]),
+            cipher,
+        }
+    }
+
+    /// Recipient from `id, name, email` columns, with the address decrypted
+    fn row_to_recipient(&self, row: &Row) -> rusqlite::Result<NotificationRecipient> {
+        let email = self.cipher.decrypt(USER_EMAIL_COLUMN, &row.get::<_, String>(2)?).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+        Ok(NotificationRecipient { user_id: row.get(0)?, name: row.get(1)?, email })
+    }
+
+    /// Add a delivery channel, replacing any registered of the same kind
+    pub fn register_channel(&self, channel: Arc<dyn NotificationChannel>) {
+        info!("Registering {} notification channel", channel.kind());
+        let mut channels = self.channels.write().unwrap_or_else(|e| e.into_inner());
+        channels.retain(|existing| existing.kind() != channel.kind());
+        channels.push(channel);
+    }
+
+    /// Record a notification for a user and deliver it on every channel they
+    /// have not switched off for its kind, holding email back for users who
+    /// take a daily digest. Inactive users are skipped.
+    /// Deliveries go through the outbox with the notification, so a failed
+    /// one is retried by the dispatcher rather than returned: the
+    /// notification is already in the user's inbox.
+    pub fn notify(
+        &self,
+        user_id: i64,
+        kind: NotificationKind,
+        title: &str,
+        body: &str,
+        entity: Option<(&str, i64)>,
+    ) -> AppResult<Option<Notification>> {
+        let now = Utc::now();
+        let registered = self.channel_kinds();
+        let recorded = self.database.with_transaction(|conn| {
+            let recipient = conn.query_row(
+                "SELECT id, first_name || ' ' || last_name, email FROM users
+                 WHERE id = ?1 AND is_active = 1 AND deleted_at IS NULL",
+                params![user_id],
+                |row| self.row_to_recipient(row),
+            ).optional()?;
+            let Some(recipient) = recipient else {
+                return Ok(None);
+            };
+
+            let mut stmt = conn.prepare(
+                "SELECT channel FROM notification_preferences WHERE user_id = ?1 AND kind = ?2 AND NOT enabled"
+            )?;
+            let mut disabled = stmt
+                .query_map(params![user_id, kind.to_string()], |row| row.get::<_, String>(0))?
+                .filter_map(|channel| channel.ok()?.parse::<NotificationChannelKind>().ok())
+                .collect::<Vec<_>>();
+            // Channels the user opted out of entirely in their preferences
+            let opted_in = user_preferences(conn, user_id)?.notification_channels;
+            disabled.extend(NotificationChannelKind::ALL.iter().filter(|channel| !opted_in.contains(channel)));
+            // Digest users are emailed once a day instead
+            if digest_settings(conn, user_id)?.delivery == NotificationDelivery::DailyDigest {
+                disabled.push(NotificationChannelKind::Email);
+            }
+
+            // With the inbox switched off the record is kept for history but
+            // never shows as unread
+            let notification = conn.query_row(
+                &format!(
+                    "INSERT INTO notifications (user_id, kind, title, body, entity_type, entity_id, read_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, CASE WHEN ?7 THEN CURRENT_TIMESTAMP END)
+                     RETURNING {}",
+                    NOTIFICATION_COLUMNS
+                ),
+                params![
+                    user_id, kind.to_string(), title, body, entity.map(|(entity_type, _)| entity_type),
+                    entity.map(|(_, entity_id)| entity_id), disabled.contains(&NotificationChannelKind::InApp),
+                ],
+                row_to_notification,
+            )?;
+
+            // Recording the notification is its in-app delivery
+            let mut queued = Vec::new();
+            for channel in registered.iter().filter(|channel| **channel != NotificationChannelKind::InApp && !disabled.contains(channel)) {
+                let message = OutboxMessage::Notification {
+                    notification_id: notification.id,
+                    channel: *channel,
+                    recipient: recipient.clone(),
+                };
+                queued.push(enqueue_outbox(conn, &message, now)?);
+            }
+            Ok(Some((notification, queued)))
+        })?;
+        let Some((notification, queued)) = recorded else {
+            return Ok(None);
+        };
+
+        for id in queued {
+            if let Err(e) = self.deliver_outbox_entry(id, now) {
+                warn!("Failed to deliver outbox message {}: {}", id, e);
+            }
+        }
+        Ok(Some(notification))
+    }
+
+    /// Kinds of the registered channels
+    fn channel_kinds(&self) -> Vec<NotificationChannelKind> {
+        self.channels.read().unwrap_or_else(|e| e.into_inner()).iter().map(|channel| channel.kind()).collect()
+    }
+
+    /// Attempt every outbox delivery due by `now`, and drop delivered rows
+    /// past their retention. Returns how many were delivered.
+    pub fn dispatch_outbox(&self, now: DateTime<Utc>) -> AppResult<usize> {
+        let conn = self.database.get_connection()?;
+        let due = (|| -> AppResult<Vec<i64>> {
+            conn.execute(
+                "DELETE FROM outbox WHERE delivered_at < ?1",
+                params![now - chrono::Duration::days(OUTBOX_RETENTION_DAYS)],
+            )?;
+            let mut stmt = conn.prepare(
+                "SELECT id FROM outbox
+                 WHERE delivered_at IS NULL AND abandoned_at IS NULL AND next_attempt_at <= ?1
+                 ORDER BY id"
+            )?;
+            let ids = stmt.query_map(params![now], |row| row.get(0))?.collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(ids)
+        })();
+        self.database.return_connection(conn);
+
+        let mut delivered = 0;
+        for id in due? {
+            if self.deliver_outbox_entry(id, now)? {
+                delivered += 1;
+            }
+        }
+        Ok(delivered)
+    }
+
+    /// Claim one outbox delivery and attempt it. Returns whether it was
+    /// delivered; `false` also when it is not due or another dispatcher
+    /// holds it. A failure is recorded on the row and retried later, and
+    /// given up on after `MAX_OUTBOX_ATTEMPTS`.
+    fn deliver_outbox_entry(&self, id: i64, now: DateTime<Utc>) -> AppResult<bool> {
+        let claimed = self.database.with_transaction(|conn| {
+            let claimed = conn.query_row(
+                "UPDATE outbox SET attempts = attempts + 1, next_attempt_at = ?3
+                 WHERE id = ?1 AND delivered_at IS NULL AND abandoned_at IS NULL AND next_attempt_at <= ?2
+                 RETURNING message, attempts",
+                params![id, now, now + chrono::Duration::minutes(OUTBOX_LEASE_MINUTES)],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+            ).optional()?;
+            Ok(claimed)
+        })?;
+        let Some((message, attempts)) = claimed else {
+            return Ok(false);
+        };
+
+        let outcome = serde_json::from_str::<OutboxMessage>(&message)
+            .map_err(AppError::from)
+            .and_then(|message| self.deliver_outbox_message(&message));
+        let conn = self.database.get_connection()?;
+        let result = match &outcome {
+            Ok(()) => conn.execute(
+                "UPDATE outbox SET delivered_at = ?2, last_error = NULL WHERE id = ?1",
+                params![id, Utc::now()],
+            ),
+            Err(e) if attempts >= MAX_OUTBOX_ATTEMPTS => {
+                error!("Giving up on outbox message {} after {} attempts: {}", id, attempts, e);
+                conn.execute(
+                    "UPDATE outbox SET abandoned_at = ?2, last_error = ?3 WHERE id = ?1",
+                    params![id, Utc::now(), e.to_string()],
+                )
+            }
+            Err(e) => {
+                warn!("Outbox message {} failed on attempt {}: {}", id, attempts, e);
+                conn.execute(
+                    "UPDATE outbox SET next_attempt_at = ?2, last_error = ?3 WHERE id = ?1",
+                    params![id, Utc::now() + notifications::outbox_retry_delay(attempts), e.to_string()],
+                )
+            }
+        };
+        self.database.return_connection(conn);
+        result?;
+        Ok(outcome.is_ok())
+    }
+
+    fn deliver_outbox_message(&self, message: &OutboxMessage) -> AppResult<()> {
+        let channel = self.channels.read().unwrap_or_else(|e| e.into_inner()).iter()
+            .find(|channel| channel.kind() == message.channel())
+            .cloned()
+            .ok_or_else(|| AppError::internal(format!("No {} notification channel is registered", message.channel())))?;
+        match message {
+            OutboxMessage::Notification { notification_id, recipient, .. } => {
+                let conn = self.database.get_connection()?;
+                let notification = conn.query_row(
+                    &format!("SELECT {} FROM notifications WHERE id = ?1", NOTIFICATION_COLUMNS),
+                    params![notification_id],
+                    row_to_notification,
+                ).optional();
+                self.database.return_connection(conn);
+                // A notification deleted since has nothing left to deliver
+                match notification? {
+                    Some(notification) => channel.deliver(&notification, recipient),
+                    None => Ok(()),
+                }
+            }
+            OutboxMessage::Message { recipient, subject, body, .. } => {
+                if channel.send_private(recipient, subject, body)? {
+                    Ok(())
+                } else {
+                    Err(AppError::internal(format!("The {} channel cannot send messages", channel.kind())))
+                }
+            }
+        }
+    }
+
+    /// Send a message that must not be kept in the user's inbox, such as a
+    /// password reset link, on the first channel able to carry it. Returns
+    /// whether one did; inactive users are never sent anything.
+    pub fn send_private(&self, user_id: i64, subject: &str, body: &str) -> AppResult<bool> {
+        let conn = self.database.get_connection()?;
+        let recipient = conn.query_row(
+            "SELECT id, first_name || ' ' || last_name, email FROM users
+             WHERE id = ?1 AND is_active = 1 AND deleted_at IS NULL",
+            params![user_id],
+            |row| self.row_to_recipient(row),
+        ).optional();
+        self.database.return_connection(conn);
+        let Some(recipient) = recipient? else {
+            return Ok(false);
+        };
+
+        let channels = self.channels.read().unwrap_or_else(|e| e.into_inner()).clone();
+        for channel in channels.iter() {
+            match channel.send_private(&recipient, subject, body) {
+                Ok(true) => return Ok(true),
+                Ok(false) => {}
+                Err(e) => warn!("Failed to send private message by {}: {}", channel.kind(), e),
+            }
+        }
+        Ok(false)
+    }
+
+    /// Tell an inspector about an inspection assigned to them, unless they
+    /// assigned it themselves or already had it
+    pub fn inspection_assigned(
+        &self,
+        context: &RequestContext,
+        inspection: &Inspection,
+        previous_inspector_id: Option<i64>,
+    ) -> AppResult<Vec<Notification>> {
+        let actor_id = context.current_user().map(|u| u.user_id).ok();
+        if previous_inspector_id == Some(inspection.inspector_id) || actor_id == Some(inspection.inspector_id) {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.database.get_connection()?;
+        let asset = conn.query_row(
+            "SELECT asset_number || ' ' || asset_name FROM assets WHERE id = ?1",
+            params![inspection.asset_id],
+            |row| row.get::<_, String>(0),
+        ).optional();
+        self.database.return_connection(conn);
+        let asset = asset?.unwrap_or_else(|| format!("asset {}", inspection.asset_id));
+
+        let scheduled = inspection.scheduled_date
+            .map(|date| format!(" scheduled for {}", date.format("%Y-%m-%d")))
+            .unwrap_or_default();
+        let body = format!("{} inspection of {}{}", inspection.inspection_type, asset, scheduled);
+        Ok(self
+            .notify(inspection.inspector_id, NotificationKind::Assignment, "Inspection assigned", &body,
+                    Some(("inspection", inspection.id)))?
+            .into_iter()
+            .collect())
+    }
+
+    /// Tell the assignee of a work order about it, unless they assigned it
+    /// themselves or already had it
+    pub fn work_order_assigned(
+        &self,
+        context: &RequestContext,
+        work_order: &WorkOrder,
+        previous_assignee_id: Option<i64>,
+    ) -> AppResult<Vec<Notification>> {
+        let Some(assignee_id) = work_order.assignee_id else {
+            return Ok(Vec::new());
+        };
+        let actor_id = context.current_user().map(|u| u.user_id).ok();
+        if previous_assignee_id == Some(assignee_id) || actor_id == Some(assignee_id) {
+            return Ok(Vec::new());
+        }
+
+        let due = work_order.due_date.map(|date| format!(", due {}", date)).unwrap_or_default();
+        let body = format!("{} priority work order: {}{}", work_order.priority, work_order.title, due);
+        Ok(self
+            .notify(assignee_id, NotificationKind::Assignment, "Work order assigned", &body,
+                    Some(("work_order", work_order.id)))?
+            .into_iter()
+            .collect())
+    }
+
+    /// Tell the assignee of a corrective action about it, unless they
+    /// assigned it themselves or already had it
+    pub fn corrective_action_assigned(
+        &self,
+        context: &RequestContext,
+        action: &CorrectiveAction,
+        previous_assignee_id: Option<i64>,
+    ) -> AppResult<Vec<Notification>> {
+        let Some(assignee_id) = action.assignee_id else {
+            return Ok(Vec::new());
+        };
+        let actor_id = context.current_user().map(|u| u.user_id).ok();
+        if previous_assignee_id == Some(assignee_id) || actor_id == Some(assignee_id) {
+            return Ok(Vec::new());
+        }
+
+        let due = action.due_date.map(|date| format!(", due {}", date)).unwrap_or_default();
+        let body = format!("Corrective action: {}{}", action.description, due);
+        Ok(self
+            .notify(assignee_id, NotificationKind::Assignment, "Corrective action assigned", &body,
+                    Some(("corrective_action", action.id)))?
+            .into_iter()
+            .collect())
+    }
+
+    /// Alert supervisors, administrators and the inspector when an item is
+    /// recorded with a critical finding. Each user is told once per item.
+    pub fn finding_recorded(&self, context: &RequestContext, item: &InspectionItem) -> AppResult<Vec<Notification>> {
+        if item.severity != Some(Severity::Critical) {
+            return Ok(Vec::new());
+        }
+        let actor_id = context.current_user().map(|u| u.user_id).ok();
+
+        let conn = self.database.get_connection()?;
+        let result = (|| -> AppResult<(String, Vec<i64>)> {
+            let asset: String = conn.query_row(
+                "SELECT a.asset_number || ' ' || a.asset_name FROM inspections i JOIN assets a ON i.asset_id = a.id
+                 WHERE i.id = ?1",
+                params![item.inspection_id],
+                |row| row.get(0),
+            )?;
+            let mut stmt = conn.prepare(
+                "SELECT id FROM users u
+                 WHERE u.is_active = 1 AND u.deleted_at IS NULL
+                   AND (u.role IN ('Supervisor', 'Administrator', 'SuperAdmin')
+                        OR u.id = (SELECT inspector_id FROM inspections WHERE id = ?1))
+                   AND NOT EXISTS (
+                       SELECT 1 FROM notifications n
+                       WHERE n.user_id = u.id AND n.kind = 'CriticalFinding'
+                         AND n.entity_type = 'inspection_item' AND n.entity_id = ?2
+                   )
+                 ORDER BY u.id"
+            )?;
+            let recipients = stmt
+                .query_map(params![item.inspection_id, item.id], |row| row.get(0))?
+                .collect::<rusqlite::Result<Vec<i64>>>()?;
+            Ok((asset, recipients))
+        })();
+        self.database.return_connection(conn);
+        let (asset, recipients) = result?;
+
+        let finding = item.finding.as_deref().filter(|f| !f.trim().is_empty()).unwrap_or("no details recorded");
+        let body = format!("{} on {}: {}", item.item_name, asset, finding);
+        let mut notifications = Vec::new();
+        for user_id in recipients.into_iter().filter(|user_id| Some(*user_id) != actor_id) {
+            notifications.extend(self.notify(user_id, NotificationKind::CriticalFinding, "Critical finding", &body,
+                                             Some(("inspection_item", item.id)))?);
+        }
+        Ok(notifications)
+    }
+
+    /// Tell inspectors about their scheduled inspections due within
+    /// `NOTIFICATION_DUE_DAYS`, once per inspection
+    pub fn notify_due_inspections(&self) -> AppResult<Vec<Notification>> {
+        let conn = self.database.get_connection()?;
+        let result = (|| -> AppResult<Vec<(i64, i64, String)>> {
+            let now = Utc::now();
+            let mut stmt = conn.prepare(
+                "SELECT i.id, i.inspector_id,
+                        i.inspection_type || ' inspection of ' || a.asset_number || ' ' || a.asset_name
+                            || ' is due ' || date(i.scheduled_date)
+                 FROM inspections i
+                 JOIN assets a ON i.asset_id = a.id
+                 WHERE i.status = 'Scheduled' AND i.deleted_at IS NULL
+                   AND i.scheduled_date >= ?1 AND i.scheduled_date < ?2
+                   AND NOT EXISTS (
+                       SELECT 1 FROM notifications n
+                       WHERE n.user_id = i.inspector_id AND n.kind = 'InspectionDue'
+                         AND n.entity_type = 'inspection' AND n.entity_id = i.id
+                   )
+                 ORDER BY i.scheduled_date"
+            )?;
+            let due = stmt
+                .query_map(params![now, now + chrono::Duration::days(NOTIFICATION_DUE_DAYS)], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(due)
+        })();
+        self.database.return_connection(conn);
+
+        let mut notifications = Vec::new();
+        for (inspection_id, inspector_id, body) in result? {
+            notifications.extend(self.notify(inspector_id, NotificationKind::InspectionDue, "Inspection due", &body,
+                                             Some(("inspection", inspection_id)))?);
+        }
+        Ok(notifications)
+    }
+
+    /// Tell supervisors, administrators and the inspector when a critical
+    /// finding misses its acknowledgement or resolution target, once per stage
+    pub fn notify_sla_breaches(&self) -> AppResult<Vec<Notification>> {
+        let breaches = self.database.with_transaction(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT s.item_id, 'Acknowledge', i.inspector_id,
+                        ii.item_name || ' on ' || a.asset_number || ' ' || a.asset_name
+                            || ' was not acknowledged by ' || s.acknowledge_due_at || ' UTC'
+                 FROM finding_slas s
+                 JOIN inspection_items ii ON ii.id = s.item_id
+                 JOIN inspections i ON i.id = ii.inspection_id
+                 JOIN assets a ON a.id = i.asset_id
+                 WHERE ii.severity = 'Critical' AND s.acknowledged_at IS NULL
+                   AND s.acknowledge_due_at < datetime('now') AND s.acknowledge_breach_notified_at IS NULL
+                 UNION ALL
+                 SELECT s.item_id, 'Resolve', i.inspector_id,
+                        ii.item_name || ' on ' || a.asset_number || ' ' || a.asset_name
+                            || ' was not resolved by ' || s.resolve_due_at || ' UTC'
+                 FROM finding_slas s
+                 JOIN inspection_items ii ON ii.id = s.item_id
+                 JOIN inspections i ON i.id = ii.inspection_id
+                 JOIN assets a ON a.id = i.asset_id
+                 WHERE ii.severity = 'Critical' AND s.resolved_at IS NULL
+                   AND s.resolve_due_at < datetime('now') AND s.resolve_breach_notified_at IS NULL"
+            )?;
+            let breaches = stmt
+                .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?, row.get::<_, String>(3)?)))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            drop(stmt);
+
+            // Mark them first so a failed delivery is not retried every run
+            for (item_id, stage, _, _) in &breaches {
+                let column = if stage == "Acknowledge" { "acknowledge_breach_notified_at" } else { "resolve_breach_notified_at" };
+                conn.execute(
+                    &format!("UPDATE finding_slas SET {} = CURRENT_TIMESTAMP WHERE item_id = ?1", column),
+                    params![item_id],
+                )?;
+            }
+
+            let mut stmt = conn.prepare(
+                "SELECT id FROM users
+                 WHERE is_active = 1 AND deleted_at IS NULL AND role IN ('Supervisor', 'Administrator', 'SuperAdmin')
+                 ORDER BY id"
+            )?;
+            let managers = stmt.query_map([], |row| row.get::<_, i64>(0))?.collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok((breaches, managers))
+        });
+        let (breaches, managers) = breaches?;
+
+        let mut notifications = Vec::new();
+        for (item_id, stage, inspector_id, body) in breaches {
+            let title = if stage == "Acknowledge" {
+                "Critical finding not acknowledged"
+            } else {
+                "Critical finding not resolved"
+            };
+            let mut recipients = managers.clone();
+            if !recipients.contains(&inspector_id) {
+                recipients.push(inspector_id);
+            }
+            for user_id in recipients {
+                notifications.extend(self.notify(user_id, NotificationKind::CriticalFinding, title, &body,
+                                                 Some(("inspection_item", item_id)))?);
+            }
+        }
+        Ok(notifications)
+    }
+
+    /// A user's notifications, newest first
+    pub fn get_notifications(&self, user_id: i64, unread_only: bool, limit: i64) -> AppResult<Vec<Notification>> {
+        debug!("Fetching notifications for user: {} (unread only: {})", user_id, unread_only);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<Notification>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM notifications
+                 WHERE user_id = ?1 AND (NOT ?2 OR read_at IS NULL)
+                 ORDER BY created_at DESC, id DESC
+                 LIMIT ?3",
+                NOTIFICATION_COLUMNS
+            ))?;
+            let notifications = stmt
+                .query_map(params![user_id, unread_only, limit.clamp(1, 500)], row_to_notification)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(notifications)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    pub fn get_unread_count(&self, user_id: i64) -> AppResult<i64> {
+        let conn = self.database.get_connection()?;
+        let result = conn.query_row(
+            "SELECT COUNT(*) FROM notifications WHERE user_id = ?1 AND read_at IS NULL",
+            params![user_id],
+            |row| row.get(0),
+        );
+        self.database.return_connection(conn);
+        Ok(result?)
+    }
+
+    /// Mark one of a user's notifications read
+    pub fn mark_read(&self, user_id: i64, id: i64) -> AppResult<Notification> {
+        self.database.with_transaction(|conn| {
+            conn.query_row(
+                &format!(
+                    "UPDATE notifications SET read_at = COALESCE(read_at, CURRENT_TIMESTAMP)
+                     WHERE id = ?1 AND user_id = ?2
+                     RETURNING {}",
+                    NOTIFICATION_COLUMNS
+                ),
+                params![id, user_id],
+                row_to_notification,
+            ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+                entity: "Notification".to_string(),
+                field: "id".to_string(),
+                value: id.to_string(),
+            })
+        })
+    }
+
+    /// Mark all of a user's notifications read. Returns how many were unread.
+    pub fn mark_all_read(&self, user_id: i64) -> AppResult<usize> {
+        self.database.with_transaction(|conn| {
+            Ok(conn.execute(
+                "UPDATE notifications SET read_at = CURRENT_TIMESTAMP WHERE user_id = ?1 AND read_at IS NULL",
+                params![user_id],
+            )?)
+        })
+    }
+
+    /// Every kind and channel with whether the user receives it
+    pub fn get_preferences(&self, user_id: i64) -> AppResult<Vec<NotificationPreference>> {
+        let conn = self.database.get_connection()?;
+        let result = (|| -> AppResult<HashMap<(String, String), bool>> {
+            let mut stmt = conn.prepare(
+                "SELECT kind, channel, enabled FROM notification_preferences WHERE user_id = ?1"
+            )?;
+            let stored = stmt
+                .query_map(params![user_id], |row| Ok(((row.get(0)?, row.get(1)?), row.get(2)?)))?
+                .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+            Ok(stored)
+        })();
+        self.database.return_connection(conn);
+        let stored = result?;
+
+        Ok(NotificationKind::ALL
+            .iter()
+            .flat_map(|kind| NotificationChannelKind::ALL.iter().map(move |channel| (*kind, *channel)))
+            .map(|(kind, channel)| NotificationPreference {
+                kind,
+                channel,
+                enabled: stored.get(&(kind.to_string(), channel.to_string())).copied().unwrap_or(true),
+            })
+            .collect())
+    }
+
+    /// Switch one kind of notification on or off for a channel
+    pub fn set_preference(
+        &self,
+        user_id: i64,
+        kind: NotificationKind,
+        channel: NotificationChannelKind,
+        enabled: bool,
+    ) -> AppResult<NotificationPreference> {
+        self.database.with_transaction(|conn| {
+            conn.execute(
+                "INSERT INTO notification_preferences (user_id, kind, channel, enabled) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(user_id, kind, channel) DO UPDATE SET enabled = excluded.enabled",
+                params![user_id, kind.to_string(), channel.to_string(), enabled],
+            )?;
+            Ok(NotificationPreference { kind, channel, enabled })
+        })
+    }
+
+    /// Whether the user takes notification emails immediately or as a digest
+    pub fn get_digest_settings(&self, user_id: i64) -> AppResult<NotificationDigestSettings> {
+        let conn = self.database.get_connection()?;
+        let result = digest_settings(&conn, user_id);
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Choose between immediate emails and a daily digest. Switching to the
+    /// digest starts its period now, since earlier notifications were
+    /// already emailed.
+    pub fn set_digest_settings(
+        &self,
+        user_id: i64,
+        input: NotificationDigestInput,
+    ) -> AppResult<NotificationDigestSettings> {
+        input.validate()?;
+        let defaults = NotificationDigestSettings::defaults();
+        let send_at = input.send_at.unwrap_or(defaults.send_at);
+        let time_zone = input.time_zone.unwrap_or(defaults.time_zone);
+
+        self.database.with_transaction(|conn| {
+            conn.execute(
+                "INSERT INTO notification_digests (user_id, delivery, send_at, time_zone, last_sent_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+                 ON CONFLICT(user_id) DO UPDATE SET
+                     delivery = excluded.delivery,
+                     send_at = excluded.send_at,
+                     time_zone = excluded.time_zone,
+                     last_sent_at = CASE WHEN notification_digests.delivery = 'DailyDigest'
+                                         THEN notification_digests.last_sent_at ELSE excluded.last_sent_at END,
+                     updated_at = excluded.updated_at",
+                params![user_id, input.delivery.to_string(), send_at.format("%H:%M").to_string(), time_zone, Utc::now()],
+            )?;
+            digest_settings(conn, user_id)
+        })
+    }
+
+    /// The digest the user would be sent now, without sending it
+    pub fn preview_digest(&self, user_id: i64) -> AppResult<NotificationDigest> {
+        let conn = self.database.get_connection()?;
+        let result = (|| -> AppResult<NotificationDigest> {
+            let settings = digest_settings(&conn, user_id)?;
+            let tz = scheduling::time_zone_or_default(Some(&settings.time_zone));
+            user_digest(&conn, user_id, settings.last_sent_at, Utc::now(), tz)
+        })();
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Email the digest of every active user whose send time has passed
+    /// since their last one. Users with nothing to report are skipped but
+    /// their period still restarts. Returns how many digests were queued.
+    pub fn send_due_digests(&self, now: DateTime<Utc>) -> AppResult<usize> {
+        let email_registered = self.channel_kinds().contains(&NotificationChannelKind::Email);
+        let queued = self.database.with_transaction(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT d.user_id FROM notification_digests d
+                 JOIN users u ON u.id = d.user_id
+                 WHERE d.delivery = 'DailyDigest' AND u.is_active = 1 AND u.deleted_at IS NULL
+                 ORDER BY d.user_id"
+            )?;
+            let users = stmt.query_map([], |row| row.get::<_, i64>(0))?.collect::<rusqlite::Result<Vec<_>>>()?;
+            drop(stmt);
+
+            let mut queued = Vec::new();
+            for user_id in users {
+                let settings = digest_settings(conn, user_id)?;
+                let tz = scheduling::time_zone_or_default(Some(&settings.time_zone));
+                let due_at = scheduling::latest_local_time(now, settings.send_at, tz);
+                if settings.last_sent_at.is_some_and(|last| last >= due_at) {
+                    continue;
+                }
+                // Marked sent with the email queued in the outbox, which
+                // retries it if it fails
+                conn.execute(
+                    "UPDATE notification_digests SET last_sent_at = ?2 WHERE user_id = ?1",
+                    params![user_id, now],
+                )?;
+                if !email_registered
+                    || !user_preferences(conn, user_id)?.notification_channels.contains(&NotificationChannelKind::Email)
+                {
+                    continue;
+                }
+                let digest = user_digest(conn, user_id, settings.last_sent_at, now, tz)?;
+                if digest.is_empty() {
+                    continue;
+                }
+                let recipient = conn.query_row(
+                    "SELECT id, first_name || ' ' || last_name, email FROM users WHERE id = ?1",
+                    params![user_id],
+                    |row| self.row_to_recipient(row),
+                )?;
+                let (subject, body) = notifications::render_digest(&digest);
+                let message = OutboxMessage::Message { channel: NotificationChannelKind::Email, recipient, subject, body };
+                queued.push(enqueue_outbox(conn, &message, now)?);
+            }
+            Ok(queued)
+        })?;
+
+        let sent = queued.len();
+        for id in queued {
+            if let Err(e) = self.deliver_outbox_entry(id, now) {
+                warn!("Failed to deliver outbox message {}: {}", id, e);
+            }
+        }
+        Ok(sent)
+    }
+}
+
+// =============================================================================
+// Risk Matrix Service
+// =============================================================================
+
+pub struct RiskMatrixService {
+    database: Arc<Database>,
+}
+
+impl RiskMatrixService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    pub fn get_matrix(&self) -> AppResult<RiskMatrix> {
+        debug!("Fetching risk matrix");
+        let conn = self.database.get_connection()?;
+        let result = read_risk_matrix(&conn);
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Replace the installation's risk matrix. Findings already rated keep
+    /// their rating until their severity next changes.
+    pub fn set_matrix(&self, context: &RequestContext, cells: Vec<RiskMatrixCell>) -> AppResult<RiskMatrix> {
+        let user_id = context.current_user()?.user_id;
+        info!("[{}] Updating risk matrix", context.request_id);
+        let matrix = RiskMatrix { cells, updated_by: None, updated_at: None };
+        matrix.validate()?;
+
+        self.database.with_transaction(|conn| {
+            let mut stmt = conn.prepare(
+                "UPDATE risk_matrix SET rating = ?3, response_hours = ?4, updated_by = ?5, updated_at = CURRENT_TIMESTAMP
+                 WHERE severity = ?1 AND criticality = ?2"
+            )?;
+            for cell in &matrix.cells {
+                stmt.execute(params![
+                    cell.severity.to_string(), cell.criticality.to_string(),
+                    cell.rating.to_string(), cell.response_hours, user_id
+                ])?;
+            }
+            drop(stmt);
+            read_risk_matrix(conn)
+        })
+    }
+}
+
+fn read_risk_matrix(conn: &Connection) -> AppResult<RiskMatrix> {
+    let mut stmt = conn.prepare(
+        "SELECT severity, criticality, rating, response_hours, updated_by, updated_at FROM risk_matrix
+         ORDER BY CASE severity WHEN 'Low' THEN 1 WHEN 'Medium' THEN 2 WHEN 'High' THEN 3 ELSE 4 END,
+                  CASE criticality WHEN 'Low' THEN 1 WHEN 'Medium' THEN 2 WHEN 'High' THEN 3 ELSE 4 END"
+    )?;
+    let mut matrix = RiskMatrix { cells: Vec::new(), updated_by: None, updated_at: None };
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        matrix.cells.push(RiskMatrixCell {
+            severity: row.get::<_, String>(0)?.parse()?,
+            criticality: row.get::<_, String>(1)?.parse()?,
+            rating: row.get::<_, String>(2)?.parse()?,
+            response_hours: row.get(3)?,
+        });
+        // Report the most recent change to any cell
+        let updated_at: Option<DateTime<Utc>> = row.get(5)?;
+        if updated_at > matrix.updated_at {
+            matrix.updated_by = row.get(4)?;
+            matrix.updated_at = updated_at;
+        }
+    }
+    Ok(matrix)
+}
+
+/// Rate a finding from its severity and its asset's criticality, and start
+/// the response clock. Items without a severity are left unrated.
+fn apply_risk_matrix(conn: &Connection, item_id: i64) -> AppResult<()> {
+    conn.execute(
+        "UPDATE inspection_items SET (risk_rating, response_due_at) = (
+             SELECT m.rating, datetime('now', '+' || m.response_hours || ' hours')
+             FROM inspections i
+             JOIN assets a ON a.id = i.asset_id
+             JOIN risk_matrix m ON m.severity = inspection_items.severity AND m.criticality = a.criticality
+             WHERE i.id = inspection_items.inspection_id)
+         WHERE id = ?1",
+        params![item_id],
+    )?;
+    Ok(())
+}
+
+// =============================================================================
+// Finding SLA Service
+// =============================================================================
+
+const FINDING_SLA_SELECT: &str =
+    "SELECT s.item_id, ii.inspection_id, i.asset_id, a.asset_number, a.location_id, ii.item_name,
+            s.opened_at, s.acknowledge_due_at, s.acknowledged_at, s.acknowledged_by,
+            s.resolve_due_at, s.resolved_at, s.resolved_by, s.resolution_notes
+     FROM finding_slas s
+     JOIN inspection_items ii ON ii.id = s.item_id
+     JOIN inspections i ON i.id = ii.inspection_id
+     JOIN assets a ON a.id = i.asset_id";
+
+fn row_to_finding_sla(row: &Row) -> rusqlite::Result<FindingSla> {
+    Ok(FindingSla {
+        item_id: row.get(0)?,
+        inspection_id: row.get(1)?,
+        asset_id: row.get(2)?,
+        asset_number: row.get(3)?,
+        location_id: row.get(4)?,
+        item_name: row.get(5)?,
+        opened_at: row.get(6)?,
+        acknowledge_due_at: row.get(7)?,
+        acknowledged_at: row.get(8)?,
+        acknowledged_by: row.get(9)?,
+        resolve_due_at: row.get(10)?,
+        resolved_at: row.get(11)?,
+        resolved_by: row.get(12)?,
+        resolution_notes: row.get(13)?,
+    })
+}
+
+fn finding_sla_by_item(conn: &Connection, item_id: i64) -> AppResult<FindingSla> {
+    conn.query_row(&format!("{} WHERE s.item_id = ?1", FINDING_SLA_SELECT), params![item_id], row_to_finding_sla)
+        .optional()?
+        .ok_or_else(|| AppError::RecordNotFound {
+            entity: "FindingSla".to_string(),
+            field: "item_id".to_string(),
+            value: item_id.to_string(),
+        })
+}
+
+/// Start the SLA clock of an item recorded as critical, unless it already
+/// has one
+fn open_finding_sla(conn: &Connection, item_id: i64) -> AppResult<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO finding_slas (item_id, opened_at, acknowledge_due_at, resolve_due_at)
+         SELECT ii.id, datetime('now'),
+                datetime('now', '+' || ack.target_hours || ' hours'),
+                datetime('now', '+' || res.target_hours || ' hours')
+         FROM inspection_items ii
+         JOIN finding_sla_targets ack ON ack.stage = 'Acknowledge'
+         JOIN finding_sla_targets res ON res.stage = 'Resolve'
+         WHERE ii.id = ?1 AND ii.severity = 'Critical'",
+        params![item_id],
+    )?;
+    Ok(())
+}
+
+/// Stop the acknowledgement clock of a finding, if it has one running
+fn acknowledge_finding_sla(conn: &Connection, item_id: i64, user_id: Option<i64>) -> AppResult<()> {
+    conn.execute(
+        "UPDATE finding_slas SET acknowledged_at = datetime('now'), acknowledged_by = ?2
+         WHERE item_id = ?1 AND acknowledged_at IS NULL",
+        params![item_id, user_id],
+    )?;
+    Ok(())
+}
+
+/// Stop both clocks of a finding; resolving it acknowledges it too
+fn resolve_finding_sla(conn: &Connection, item_id: i64, user_id: Option<i64>, notes: Option<&str>) -> AppResult<()> {
+    acknowledge_finding_sla(conn, item_id, user_id)?;
+    conn.execute(
+        "UPDATE finding_slas SET resolved_at = datetime('now'), resolved_by = ?2, resolution_notes = ?3
+         WHERE item_id = ?1 AND resolved_at IS NULL",
+        params![item_id, user_id, notes],
+    )?;
+    Ok(())
+}
+
+/// Time-to-acknowledge and time-to-resolve tracking for critical findings.
+/// Raising a work order for a finding acknowledges it and verifying the
+/// repair resolves it.
+pub struct FindingSlaService {
+    database: Arc<Database>,
+}
+
+impl FindingSlaService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    pub fn acknowledge(&self, context: &RequestContext, item_id: i64) -> AppResult<FindingSla> {
+        let user_id = context.current_user()?.user_id;
+        info!("[{}] Acknowledging critical finding {}", context.request_id, item_id);
+
+        self.database.with_transaction(|conn| {
+            let sla = finding_sla_by_item(conn, item_id)?;
+            if sla.acknowledged_at.is_some() {
+                return Err(AppError::validation("item_id", format!("Finding {} is already acknowledged", item_id)));
+            }
+            acknowledge_finding_sla(conn, item_id, Some(user_id))?;
+            finding_sla_by_item(conn, item_id)
+        })
+    }
+
+    pub fn resolve(&self, context: &RequestContext, item_id: i64, notes: Option<String>) -> AppResult<FindingSla> {
+        let user_id = context.current_user()?.user_id;
+        info!("[{}] Resolving critical finding {}", context.request_id, item_id);
+
+        self.database.with_transaction(|conn| {
+            let sla = finding_sla_by_item(conn, item_id)?;
+            if sla.resolved_at.is_some() {
+                return Err(AppError::validation("item_id", format!("Finding {} is already resolved", item_id)));
+            }
+            resolve_finding_sla(conn, item_id, Some(user_id), notes.as_deref())?;
+            finding_sla_by_item(conn, item_id)
+        })
+    }
+
+    /// SLA clocks of critical findings, oldest first, optionally only those
+    /// still unresolved or at one location
+    pub fn get_findings(&self, open_only: bool, location_id: Option<i64>) -> AppResult<Vec<FindingSla>> {
+        debug!("Fetching finding SLAs (open only: {}, location: {:?})", open_only, location_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<FindingSla>> {
+            let mut stmt = conn.prepare(&format!(
+                "{} WHERE ii.severity = 'Critical'
+                   AND (NOT ?1 OR s.resolved_at IS NULL)
+                   AND (?2 IS NULL OR a.location_id = ?2)
+                 ORDER BY s.opened_at, s.item_id",
+                FINDING_SLA_SELECT
+            ))?;
+            let findings = stmt.query_map(params![open_only, location_id], row_to_finding_sla)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(findings)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    pub fn get_targets(&self) -> AppResult<Vec<SlaTarget>> {
+        let conn = self.database.get_connection()?;
+        let result = read_sla_targets(&conn);
+        self.database.return_connection(conn);
+        result
+    }
+
+    pub fn set_targets(&self, context: &RequestContext, targets: Vec<SlaTarget>) -> AppResult<Vec<SlaTarget>> {
+        let user_id = context.current_user()?.user_id;
+        info!("[{}] Updating finding SLA targets", context.request_id);
+        for target in &targets {
+            target.validate()?;
+        }
+
+        self.database.with_transaction(|conn| {
+            for target in &targets {
+                conn.execute(
+                    "UPDATE finding_sla_targets SET target_hours = ?2, updated_by = ?3, updated_at = CURRENT_TIMESTAMP
+                     WHERE stage = ?1",
+                    params![target.stage.to_string(), target.target_hours, user_id],
+                )?;
+            }
+            read_sla_targets(conn)
+        })
+    }
+
+    /// How well each location met the SLA targets for the critical findings
+    /// recorded between the dates
+    pub fn get_performance_report(&self, start_date: DateTime<Utc>, end_date: DateTime<Utc>) -> AppResult<SlaPerformanceReport> {
+        info!("Generating SLA performance report from {} to {}", start_date, end_date);
+        let conn = self.database.get_read_connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT l.id, l.name, COUNT(*),
+                    COUNT(s.acknowledged_at),
+                    COUNT(CASE WHEN s.acknowledged_at <= s.acknowledge_due_at THEN 1 END),
+                    AVG((julianday(s.acknowledged_at) - julianday(s.opened_at)) * 24),
+                    COUNT(s.resolved_at),
+                    COUNT(CASE WHEN s.resolved_at <= s.resolve_due_at THEN 1 END),
+                    AVG((julianday(s.resolved_at) - julianday(s.opened_at)) * 24),
+                    COUNT(CASE WHEN (s.acknowledged_at IS NULL AND s.acknowledge_due_at < datetime('now'))
+                                 OR (s.resolved_at IS NULL AND s.resolve_due_at < datetime('now')) THEN 1 END)
+             FROM finding_slas s
+             JOIN inspection_items ii ON ii.id = s.item_id
+             JOIN inspections i ON i.id = ii.inspection_id
+             JOIN assets a ON a.id = i.asset_id
+             JOIN locations l ON l.id = a.location_id
+             WHERE ii.severity = 'Critical'
+               AND s.opened_at >= datetime(?1) AND s.opened_at < datetime(?2)
+             GROUP BY l.id
+             ORDER BY l.name"
+        )?;
+        let locations = stmt
+            .query_map(params![start_date, end_date], |row| {
+                Ok(LocationSlaPerformance {
+                    location_id: row.get(0)?,
+                    location_name: row.get(1)?,
+                    findings: row.get(2)?,
+                    acknowledged: row.get(3)?,
+                    acknowledged_within_target: row.get(4)?,
+                    average_hours_to_acknowledge: row.get(5)?,
+                    resolved: row.get(6)?,
+                    resolved_within_target: row.get(7)?,
+                    average_hours_to_resolve: row.get(8)?,
+                    open_breaches: row.get(9)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        drop(stmt);
+
+        Ok(SlaPerformanceReport {
+            start_date,
+            end_date,
+            generated_at: Utc::now(),
+            targets: read_sla_targets(&conn)?,
+            locations,
+        })
+    }
+}
+
+fn read_sla_targets(conn: &Connection) -> AppResult<Vec<SlaTarget>> {
+    let mut stmt = conn.prepare("SELECT stage, target_hours FROM finding_sla_targets ORDER BY stage")?;
+    let mut rows = stmt.query([])?;
+    let mut targets = Vec::new();
+    while let Some(row) = rows.next()? {
+        targets.push(SlaTarget {
+            stage: row.get::<_, String>(0)?.parse()?,
+            target_hours: row.get(1)?,
+        });
+    }
+    Ok(targets)
+}
+
+// =============================================================================
+// Defect Service
+// =============================================================================
+
+const DEFECT_SELECT: &str = "SELECT d.id, d.asset_id, d.component_id, d.title, d.description, d.severity, d.status,
+        d.first_item_id, d.latest_item_id,
+        (SELECT COUNT(*) FROM defect_observations o WHERE o.defect_id = d.id),
+        d.deferred_until, d.deferral_reason, d.repaired_at, d.repaired_by, d.verified_at, d.verified_by,
+        d.created_at, d.updated_at
+     FROM defects d";
+
+fn row_to_defect(row: &Row) -> rusqlite::Result<Defect> {
+    Ok(Defect {
+        id: row.get(0)?,
+        asset_id: row.get(1)?,
+        component_id: row.get(2)?,
+        title: row.get(3)?,
+        description: row.get(4)?,
+        severity: row.get::<_, Option<String>>(5)?.and_then(|s| s.parse().ok()),
+        status: row.get::<_, String>(6)?.parse().unwrap_or(DefectStatus::Open),
+        first_item_id: row.get(7)?,
+        latest_item_id: row.get(8)?,
+        observation_count: row.get(9)?,
+        deferred_until: row.get(10)?,
+        deferral_reason: row.get(11)?,
+        repaired_at: row.get(12)?,
+        repaired_by: row.get(13)?,
+        verified_at: row.get(14)?,
+        verified_by: row.get(15)?,
+        created_at: row.get(16)?,
+        updated_at: row.get(17)?,
+    })
+}
+
+fn defect_by_id(conn: &Connection, id: i64) -> AppResult<Defect> {
+    conn.query_row(&format!("{} WHERE d.id = ?1", DEFECT_SELECT), params![id], row_to_defect)
+        .optional()?
+        .ok_or_else(|| AppError::RecordNotFound {
+            entity: "Defect".to_string(),
+            field: "id".to_string(),
+            value: id.to_string(),
+        })
+}
+
+/// Keep the defect an inspection item records up to date.
+///
+/// A non-compliant item is linked to the unverified defect with the same
+/// asset, component and item name, reopening it if its repair had not been
+/// verified yet, or else opens a new defect. Changes of severity are kept in
+/// the defect's history. An item corrected to compliant before its defect
+/// was seen again or worked on withdraws the defect.
+fn track_defect(conn: &Connection, item_id: i64) -> AppResult<Option<i64>> {
+    let Some((asset_id, component_id, item_name, finding, severity, is_compliant)) = conn.query_row(
+        "SELECT i.asset_id, ii.component_id, ii.item_name, ii.finding, ii.severity, ii.is_compliant
+         FROM inspection_items ii JOIN inspections i ON i.id = ii.inspection_id
+         WHERE ii.id = ?1",
+        params![item_id],
+        |row| Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, Option<i64>>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Option<String>>(4)?,
+            row.get::<_, Option<bool>>(5)?,
+        )),
+    ).optional()? else {
+        return Ok(None);
+    };
+    let linked: Option<i64> = conn.query_row(
+        "SELECT defect_id FROM defect_observations WHERE item_id = ?1",
+        params![item_id],
+        |row| row.get(0),
+    ).optional()?;
+
+    if is_compliant != Some(false) {
+        if let Some(defect_id) = linked {
+            conn.execute(
+                "DELETE FROM defects
+                 WHERE id = ?1 AND status = 'Open' AND first_item_id = ?2 AND latest_item_id = ?2",
+                params![defect_id, item_id],
+            )?;
+        }
+        return Ok(None);
+    }
+
+    let defect_id = match linked {
+        Some(defect_id) => defect_id,
+        None => {
+            let existing: Option<i64> = conn.query_row(
+                "SELECT id FROM defects
+                 WHERE asset_id = ?1 AND component_id IS ?2 AND title = ?3 COLLATE NOCASE AND status != 'Verified'
+                 ORDER BY id DESC LIMIT 1",
+                params![asset_id, component_id, item_name],
+                |row| row.get(0),
+            ).optional()?;
+
+            match existing {
+                Some(defect_id) => {
+                    // Seen again after a repair that was never verified: the repair didn't hold
+                    conn.execute(
+                        "UPDATE defects
+                         SET latest_item_id = ?2,
+                             status = CASE status WHEN 'Repaired' THEN 'Open' ELSE status END,
+                             repaired_at = CASE status WHEN 'Repaired' THEN NULL ELSE repaired_at END,
+                             repaired_by = CASE status WHEN 'Repaired' THEN NULL ELSE repaired_by END,
+                             updated_at = CURRENT_TIMESTAMP
+                         WHERE id = ?1",
+                        params![defect_id, item_id],
+                    )?;
+                    defect_id
+                }
+                None => {
+                    let defect_id: i64 = conn.query_row(
+                        "INSERT INTO defects (asset_id, component_id, title, description, severity, first_item_id, latest_item_id)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+                         RETURNING id",
+                        params![asset_id, component_id, item_name, finding, severity, item_id],
+                        |row| row.get(0),
+                    )?;
+                    conn.execute(
+                        "INSERT INTO defect_severity_history (defect_id, severity, item_id) VALUES (?1, ?2, ?3)",
+                        params![defect_id, severity, item_id],
+                    )?;
+                    defect_id
+                }
+            }
+        }
+    };
+    conn.execute(
+        "INSERT OR IGNORE INTO defect_observations (defect_id, item_id) VALUES (?1, ?2)",
+        params![defect_id, item_id],
+    )?;
+
+    // The latest observation describes the defect
+    conn.execute(
+        "UPDATE defects SET description = ?2 WHERE id = ?1 AND latest_item_id = ?3",
+        params![defect_id, finding, item_id],
+    )?;
+    let changed = conn.execute(
+        "UPDATE defects SET severity = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?1 AND severity IS NOT ?2",
+        params![defect_id, severity],
+    )?;
+    if changed > 0 {
+        conn.execute(
+            "INSERT INTO defect_severity_history (defect_id, severity, item_id) VALUES (?1, ?2, ?3)",
+            params![defect_id, severity, item_id],
+        )?;
+    }
+    Ok(Some(defect_id))
+}
+
+/// Move a defect to a new status, recording who repaired or verified it
+fn set_defect_status(
+    conn: &Connection,
+    defect: &Defect,
+    status: DefectStatus,
+    user_id: Option<i64>,
+    deferral: Option<(DateTime<Utc>, &str)>,
+) -> AppResult<()> {
+    if !defect.status.can_transition_to(status) {
+        return Err(AppError::validation(
+            "status",
+            format!("Defect {} cannot move from {} to {}", defect.id, defect.status, status),
+        ));
+    }
+    let (deferred_until, deferral_reason) = deferral.unzip();
+    conn.execute(
+        "UPDATE defects
+         SET status = ?2,
+             deferred_until = ?3,
+             deferral_reason = ?4,
+             repaired_at = CASE ?2 WHEN 'Repaired' THEN ?5 WHEN 'Open' THEN NULL ELSE repaired_at END,
+             repaired_by = CASE ?2 WHEN 'Repaired' THEN ?6 WHEN 'Open' THEN NULL ELSE repaired_by END,
+             verified_at = CASE WHEN ?2 = 'Verified' THEN ?5 ELSE verified_at END,
+             verified_by = CASE WHEN ?2 = 'Verified' THEN ?6 ELSE verified_by END,
+             updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?1",
+        params![defect.id, status.to_string(), deferred_until, deferral_reason, Utc::now(), user_id],
+    )?;
+    Ok(())
+}
+
+/// Carry a work order's progress over to the defect of its inspection item:
+/// completing the work repairs the defect, verifying it verifies the defect
+/// and sending it back reopens the defect
+fn sync_defect_with_work_order(conn: &Connection, item_id: i64, status: WorkOrderStatus, user_id: Option<i64>) -> AppResult<()> {
+    let next = match status {
+        WorkOrderStatus::Completed => DefectStatus::Repaired,
+        WorkOrderStatus::Verified => DefectStatus::Verified,
+        WorkOrderStatus::InProgress => DefectStatus::Open,
+        WorkOrderStatus::Open | WorkOrderStatus::Cancelled => return Ok(()),
+    };
+    let defect_id: Option<i64> = conn.query_row(
+        "SELECT defect_id FROM defect_observations WHERE item_id = ?1",
+        params![item_id],
+        |row| row.get(0),
+    ).optional()?;
+    if let Some(defect_id) = defect_id {
+        let defect = defect_by_id(conn, defect_id)?;
+        // Only follow the work order when the defect is where the work order expects it
+        if defect.status.can_transition_to(next) {
+            set_defect_status(conn, &defect, next, user_id, None)?;
+        }
+    }
+    Ok(())
+}
+
+/// Defects followed across inspections from the item that found them until
+/// their repair is verified
+pub struct DefectService {
+    database: Arc<Database>,
+}
+
+impl DefectService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Defects matching the filter, the most severe and oldest first
+    pub fn get_defects(&self, filter: &DefectFilter) -> AppResult<Vec<Defect>> {
+        debug!("Fetching defects: {:?}", filter);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<Defect>> {
+            let mut stmt = conn.prepare(&format!(
+                "{} WHERE (?1 IS NULL OR d.asset_id = ?1)
+                   AND (?2 IS NULL OR d.status = ?2)
+                   AND (NOT ?3 OR d.status != 'Verified')
+                 ORDER BY CASE d.severity WHEN 'Critical' THEN 0 WHEN 'High' THEN 1 WHEN 'Medium' THEN 2
+                                          WHEN 'Low' THEN 3 ELSE 4 END,
+                          d.created_at, d.id",
+                DEFECT_SELECT
+            ))?;
+            let defects = stmt.query_map(
+                params![filter.asset_id, filter.status.map(|s| s.to_string()), filter.active_only],
+                row_to_defect,
+            )?.collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(defects)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// A defect with every inspection that recorded it and its severity history
+    pub fn get_defect(&self, id: i64) -> AppResult<DefectDetail> {
+        debug!("Fetching defect: {}", id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<DefectDetail> {
+            let defect = defect_by_id(&conn, id)?;
+
+            let mut stmt = conn.prepare(
+                "SELECT ii.id, ii.inspection_id, ii.finding, ii.severity, o.observed_at
+                 FROM defect_observations o JOIN inspection_items ii ON ii.id = o.item_id
+                 WHERE o.defect_id = ?1
+                 ORDER BY o.observed_at, ii.id"
+            )?;
+            let observations = stmt.query_map(params![id], |row| Ok(DefectObservation {
+                item_id: row.get(0)?,
+                inspection_id: row.get(1)?,
+                finding: row.get(2)?,
+                severity: row.get::<_, Option<String>>(3)?.and_then(|s| s.parse().ok()),
+                observed_at: row.get(4)?,
+            }))?.collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut stmt = conn.prepare(
+                "SELECT severity, item_id, changed_at FROM defect_severity_history
+                 WHERE defect_id = ?1 ORDER BY changed_at, id"
+            )?;
+            let severity_history = stmt.query_map(params![id], |row| Ok(DefectSeverityChange {
+                severity: row.get::<_, Option<String>>(0)?.and_then(|s| s.parse().ok()),
+                item_id: row.get(1)?,
+                changed_at: row.get(2)?,
+            }))?.collect::<rusqlite::Result<Vec<_>>>()?;
+
+            Ok(DefectDetail { defect, observations, severity_history })
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Move a defect along its lifecycle. Deferring needs a reason and a
+    /// future date, and a repair is verified by someone other than whoever
+    /// repaired it.
+    pub fn update_status(
+        &self,
+        context: &RequestContext,
+        id: i64,
+        status: DefectStatus,
+        deferred_until: Option<DateTime<Utc>>,
+        reason: Option<String>,
+    ) -> AppResult<Defect> {
+        info!("[{}] Moving defect {} to {}", context.request_id, id, status);
+        let user_id = context.current_user()?.user_id;
+
+        let deferral = if status == DefectStatus::Deferred {
+            let until = deferred_until
+                .ok_or_else(|| AppError::validation("deferred_until", "A deferred defect needs a date to be repaired by"))?;
+            if until <= Utc::now() {
+                return Err(AppError::validation("deferred_until", "Deferral date must be in the future"));
+            }
+            let reason = reason.as_deref().map(str::trim).filter(|r| !r.is_empty())
+                .ok_or_else(|| AppError::validation("reason", "Deferring a defect needs a reason"))?;
+            Some((until, reason))
+        } else {
+            None
+        };
+
+        self.database.with_transaction(|conn| {
+            let defect = defect_by_id(conn, id)?;
+            if status == DefectStatus::Verified && defect.repaired_by == Some(user_id) {
+                return Err(AppError::validation(
+                    "verified_by",
+                    "A repair must be verified by someone other than who repaired it",
+                ));
+            }
+            set_defect_status(conn, &defect, status, Some(user_id), deferral)?;
+            defect_by_id(conn, id)
+        })
+    }
+}
+
+// =============================================================================
+// Corrective Action Service
+// =============================================================================
+
+const CORRECTIVE_ACTION_COLUMNS: &str =
+    "id, inspection_item_id, inspection_id, asset_id, description, status, assignee_id, due_date,
+     completion_notes, submitted_at, submitted_by, verified_at, verified_by, verification_notes,
+     created_by, created_at, updated_at,
+     (SELECT json_group_array(media_id) FROM
+         (SELECT media_id FROM corrective_action_evidence e WHERE e.action_id = corrective_actions.id
+          ORDER BY attached_at, media_id))";
+
+fn row_to_corrective_action(row: &Row) -> rusqlite::Result<CorrectiveAction> {
+    let evidence: String = row.get(17)?;
+    Ok(CorrectiveAction {
+        id: row.get(0)?,
+        inspection_item_id: row.get(1)?,
+        inspection_id: row.get(2)?,
+        asset_id: row.get(3)?,
+        description: row.get(4)?,
+        status: row.get::<_, String>(5)?.parse().unwrap_or(CorrectiveActionStatus::Open),
+        assignee_id: row.get(6)?,
+        due_date: row.get(7)?,
+        evidence_media_ids: serde_json::from_str(&evidence).unwrap_or_default(),
+        completion_notes: row.get(8)?,
+        submitted_at: row.get(9)?,
+        submitted_by: row.get(10)?,
+        verified_at: row.get(11)?,
+        verified_by: row.get(12)?,
+        verification_notes: row.get(13)?,
+        created_by: row.get(14)?,
+        created_at: row.get(15)?,
+        updated_at: row.get(16)?,
+    })
+}
+
+fn corrective_action_by_id(conn: &Connection, id: i64) -> AppResult<CorrectiveAction> {
+    conn.query_row(
+        &format!("SELECT {} FROM corrective_actions WHERE id = ?1", CORRECTIVE_ACTION_COLUMNS),
+        params![id],
+        row_to_corrective_action,
+    ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+        entity: "CorrectiveAction".to_string(),
+        field: "id".to_string(),
+        value: id.to_string(),
+    })
+}
+
+/// Actions taken to correct findings, from assignment through evidence to
+/// a supervisor's verification. Creating an action acknowledges the
+/// finding and closing it resolves the finding.
+pub struct CorrectiveActionService {
+    database: Arc<Database>,
+}
+
+impl CorrectiveActionService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Create a corrective action for a non-compliant inspection item
+    pub fn create_from_item(&self, context: &RequestContext, item_id: i64, input: CorrectiveActionInput) -> AppResult<CorrectiveAction> {
+        info!("[{}] Creating corrective action for inspection item: {}", context.request_id, item_id);
+        input.validate()?;
+        let created_by = context.current_user().map(|u| u.user_id).ok();
+
+        self.database.with_transaction(|conn| {
+            let (inspection_id, asset_id, is_compliant, item_name, finding, recorded_action) = conn.query_row(
+                "SELECT ii.inspection_id, i.asset_id, ii.is_compliant, ii.item_name, ii.finding, ii.corrective_action
+                 FROM inspection_items ii
+                 JOIN inspections i ON ii.inspection_id = i.id
+                 WHERE ii.id = ?1",
+                params![item_id],
+                |row| Ok((
+                    row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, Option<bool>>(2)?,
+                    row.get::<_, String>(3)?, row.get::<_, Option<String>>(4)?, row.get::<_, Option<String>>(5)?,
+                )),
+            ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+                entity: "InspectionItem".to_string(),
+                field: "id".to_string(),
+                value: item_id.to_string(),
+            })?;
+
+            if is_compliant != Some(false) {
+                return Err(AppError::validation(
+                    "inspection_item_id",
+                    format!("Inspection item {} is not marked non-compliant", item_id),
+                ));
+            }
+            ensure_assignable(conn, input.assignee_id)?;
+
+            let description = input.description.as_deref().map(str::trim).map(str::to_string)
+                .or(recorded_action.filter(|a| !a.trim().is_empty()))
+                .or(finding.filter(|f| !f.trim().is_empty()))
+                .unwrap_or_else(|| format!("Correct finding: {}", item_name));
+
+            let id: i64 = conn.query_row(
+                "INSERT INTO corrective_actions
+                    (inspection_item_id, inspection_id, asset_id, description, status, assignee_id, due_date, created_by)
+                 VALUES (?1, ?2, ?3, ?4, 'Open', ?5, ?6, ?7)
+                 RETURNING id",
+                params![item_id, inspection_id, asset_id, description, input.assignee_id, input.due_date, created_by],
+                |row| row.get(0),
+            )?;
+            acknowledge_finding_sla(conn, item_id, created_by)?;
+            corrective_action_by_id(conn, id)
+        })
+    }
+
+    /// Change the description, assignee or due date of an unfinished action
+    pub fn update_action(&self, context: &RequestContext, id: i64, updates: CorrectiveActionUpdateData) -> AppResult<CorrectiveAction> {
+        info!("[{}] Updating corrective action: {}", context.request_id, id);
+        updates.validate()?;
+
+        self.database.with_transaction(|conn| {
+            let mut action = corrective_action_by_id(conn, id)?;
+            if !action.status.is_open() {
+                return Err(AppError::validation(
+                    "status",
+                    format!("Only open corrective actions can be changed, this is {}", action.status),
+                ));
+            }
+
+            if let Some(description) = updates.description {
+                action.description = description.trim().to_string();
+            }
+            if let Some(assignee_id) = updates.assignee_id {
+                ensure_assignable(conn, assignee_id)?;
+                action.assignee_id = assignee_id;
+            }
+            if let Some(due_date) = updates.due_date {
+                action.due_date = due_date;
+            }
+
+            conn.execute(
+                "UPDATE corrective_actions
+                 SET description = ?1, assignee_id = ?2, due_date = ?3, updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?4",
+                params![action.description, action.assignee_id, action.due_date, id],
+            )?;
+            corrective_action_by_id(conn, id)
+        })
+    }
+
+    /// Attach a photo showing the corrective work
+    pub fn attach_evidence(&self, context: &RequestContext, id: i64, media_id: i64) -> AppResult<CorrectiveAction> {
+        info!("[{}] Attaching media {} to corrective action {}", context.request_id, media_id, id);
+        let user_id = context.current_user().map(|u| u.user_id).ok();
+
+        self.database.with_transaction(|conn| {
+            let action = corrective_action_by_id(conn, id)?;
+            if !action.status.is_open() {
+                return Err(AppError::validation(
+                    "status",
+                    format!("Evidence can only be added to open corrective actions, this is {}", action.status),
+                ));
+            }
+            let file_type: Option<String> = conn.query_row(
+                "SELECT file_type FROM media_files WHERE id = ?1",
+                params![media_id],
+                |row| row.get(0),
+            ).optional()?;
+            match file_type.as_deref() {
+                None => return Err(AppError::RecordNotFound {
+                    entity: "MediaFile".to_string(),
+                    field: "id".to_string(),
+                    value: media_id.to_string(),
+                }),
+                Some("image") => {}
+                Some(other) => return Err(AppError::validation(
+                    "media_id",
+                    format!("Evidence must be a photo, media {} is {}", media_id, other),
+                )),
+            }
+
+            conn.execute(
+                "INSERT OR IGNORE INTO corrective_action_evidence (action_id, media_id, attached_by) VALUES (?1, ?2, ?3)",
+                params![id, media_id, user_id],
+            )?;
+            conn.execute("UPDATE corrective_actions SET updated_at = CURRENT_TIMESTAMP WHERE id = ?1", params![id])?;
+            corrective_action_by_id(conn, id)
+        })
+    }
+
+    /// Record that work on an action has begun
+    pub fn start(&self, context: &RequestContext, id: i64) -> AppResult<CorrectiveAction> {
+        info!("[{}] Starting corrective action {}", context.request_id, id);
+
+        self.database.with_transaction(|conn| {
+            let action = corrective_action_by_id(conn, id)?;
+            ensure_corrective_transition(&action, CorrectiveActionStatus::InProgress)?;
+            conn.execute(
+                "UPDATE corrective_actions SET status = 'In Progress', updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+                params![id],
+            )?;
+            corrective_action_by_id(conn, id)
+        })
+    }
+
+    /// Hand finished work to a supervisor. At least one evidence photo must
+    /// be attached first.
+    pub fn submit_for_verification(&self, context: &RequestContext, id: i64, notes: Option<String>) -> AppResult<CorrectiveAction> {
+        info!("[{}] Submitting corrective action {} for verification", context.request_id, id);
+        let user_id = context.current_user().map(|u| u.user_id).ok();
+
+        self.database.with_transaction(|conn| {
+            let action = corrective_action_by_id(conn, id)?;
+            ensure_corrective_transition(&action, CorrectiveActionStatus::PendingVerification)?;
+            if action.evidence_media_ids.is_empty() {
+                return Err(AppError::validation(
+                    "evidence_media_ids",
+                    "Attach at least one evidence photo before submitting for verification",
+                ));
+            }
+
+            conn.execute(
+                "UPDATE corrective_actions
+                 SET status = 'Pending Verification', submitted_at = ?1, submitted_by = ?2,
+                     completion_notes = COALESCE(?3, completion_notes), updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?4",
+                params![Utc::now(), user_id, notes, id],
+            )?;
+            corrective_action_by_id(conn, id)
+        })
+    }
+
+    /// A supervisor's decision on submitted work: close the action, or send
+    /// it back with notes on what is still needed. The verifier cannot be
+    /// the assignee or the person who submitted the work.
+    pub fn verify(&self, context: &RequestContext, id: i64, approved: bool, notes: Option<String>) -> AppResult<CorrectiveAction> {
+        info!("[{}] Verifying corrective action {}: approved = {}", context.request_id, id, approved);
+        let user_id = context.current_user()?.user_id;
+        let notes = notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+
+        self.database.with_transaction(|conn| {
+            let action = corrective_action_by_id(conn, id)?;
+            let next = if approved { CorrectiveActionStatus::Closed } else { CorrectiveActionStatus::InProgress };
+            ensure_corrective_transition(&action, next)?;
+
+            if action.assignee_id == Some(user_id) || action.submitted_by == Some(user_id) {
+                return Err(AppError::validation(
+                    "verified_by",
+                    "A corrective action must be verified by someone other than the person who did the work",
+                ));
+            }
+            if !approved && notes.is_none() {
+                return Err(AppError::RequiredField { field: "notes".to_string() });
+            }
+
+            if approved {
+                conn.execute(
+                    "UPDATE corrective_actions
+                     SET status = 'Closed', verified_at = ?1, verified_by = ?2, verification_notes = ?3,
+                         updated_at = CURRENT_TIMESTAMP
+                     WHERE id = ?4",
+                    params![Utc::now(), user_id, notes, id],
+                )?;
+                let resolution = notes.as_deref().or(action.completion_notes.as_deref()).or(Some(action.description.as_str()));
+                resolve_finding_sla(conn, action.inspection_item_id, Some(user_id), resolution)?;
+            } else {
+                // Sent back: the submission no longer stands, the comments do
+                conn.execute(
+                    "UPDATE corrective_actions
+                     SET status = 'In Progress', submitted_at = NULL, submitted_by = NULL, verification_notes = ?1,
+                         updated_at = CURRENT_TIMESTAMP
+                     WHERE id = ?2",
+                    params![notes, id],
+                )?;
+            }
+            corrective_action_by_id(conn, id)
+        })
+    }
+
+    /// Cancel an action that has not been submitted, with the reason
+    pub fn cancel(&self, context: &RequestContext, id: i64, reason: String) -> AppResult<CorrectiveAction> {
+        info!("[{}] Cancelling corrective action {}", context.request_id, id);
+        if reason.trim().is_empty() {
+            return Err(AppError::RequiredField { field: "reason".to_string() });
+        }
+
+        self.database.with_transaction(|conn| {
+            let action = corrective_action_by_id(conn, id)?;
+            ensure_corrective_transition(&action, CorrectiveActionStatus::Cancelled)?;
+            conn.execute(
+                "UPDATE corrective_actions
+                 SET status = 'Cancelled', completion_notes = ?1, updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?2",
+                params![reason.trim(), id],
+            )?;
+            corrective_action_by_id(conn, id)
+        })
+    }
+
+    pub fn get_action(&self, id: i64) -> AppResult<CorrectiveAction> {
+        debug!("Fetching corrective action: {}", id);
+        let conn = self.database.get_connection()?;
+        let result = corrective_action_by_id(&conn, id);
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Corrective actions matching the filter, soonest due first
+    pub fn get_actions(&self, filter: &CorrectiveActionFilter) -> AppResult<Vec<CorrectiveAction>> {
+        debug!("Fetching corrective actions: {:?}", filter);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<CorrectiveAction>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM corrective_actions
+                 WHERE (?1 IS NULL OR status = ?1)
+                   AND (?2 IS NULL OR asset_id = ?2)
+                   AND (?3 IS NULL OR assignee_id = ?3)
+                   AND (?4 IS NULL OR inspection_id = ?4)
+                   AND (NOT ?5 OR (status IN ('Open', 'In Progress') AND due_date < ?6))
+                 ORDER BY due_date IS NULL, due_date, id",
+                CORRECTIVE_ACTION_COLUMNS
+            ))?;
+            let actions = stmt
+                .query_map(
+                    params![
+                        filter.status.map(|s| s.to_string()), filter.asset_id, filter.assignee_id,
+                        filter.inspection_id, filter.overdue_only, Utc::now().date_naive(),
+                    ],
+                    row_to_corrective_action,
+                )?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(actions)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+}
+
+fn ensure_corrective_transition(action: &CorrectiveAction, next: CorrectiveActionStatus) -> AppResult<()> {
+    if action.status.can_transition_to(next) {
+        Ok(())
+    } else {
+        Err(AppError::validation(
+            "status",
+            format!("Corrective action {} cannot move from {} to {}", action.id, action.status, next),
+        ))
+    }
+}
+
+// =============================================================================
+// Parts Service
+// =============================================================================
+
+const PART_COLUMNS: &str = "id, part_number, name, description, manufacturer, unit_cost,
+     quantity_on_hand, reorder_level, created_at, updated_at";
+
+const STOCK_MOVEMENT_COLUMNS: &str =
+    "id, part_id, kind, quantity_change, maintenance_record_id, notes, created_by, created_at";
+
+fn row_to_part(row: &Row) -> rusqlite::Result<Part> {
+    Ok(Part {
+        id: row.get(0)?,
+        part_number: row.get(1)?,
+        name: row.get(2)?,
+        description: row.get(3)?,
+        manufacturer: row.get(4)?,
+        unit_cost: row.get(5)?,
+        quantity_on_hand: row.get(6)?,
+        reorder_level: row.get(7)?,
+        compatibility: Vec::new(),
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+fn row_to_stock_movement(row: &Row) -> rusqlite::Result<StockMovement> {
+    Ok(StockMovement {
+        id: row.get(0)?,
+        part_id: row.get(1)?,
+        kind: row.get::<_, String>(2)?.parse().unwrap_or(StockMovementKind::Adjusted),
+        quantity_change: row.get(3)?,
+        maintenance_record_id: row.get(4)?,
+        notes: row.get(5)?,
+        created_by: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+/// Parts from a query over `parts`, with their compatibility filled in
+fn query_parts(conn: &Connection, sql: &str, params: impl rusqlite::Params) -> AppResult<Vec<Part>> {
+    let mut stmt = conn.prepare(sql)?;
+    let mut parts = stmt.query_map(params, row_to_part)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut compatibility_stmt = conn.prepare(
+        "SELECT component_type, manufacturer, model FROM part_compatibility WHERE part_id = ?1 ORDER BY id"
+    )?;
+    for part in &mut parts {
+        part.compatibility = compatibility_stmt
+            .query_map(params![part.id], |row| Ok(PartCompatibility {
+                component_type: row.get(0)?,
+                manufacturer: row.get(1)?,
+                model: row.get(2)?,
+            }))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+    }
+    Ok(parts)
+}
+
+fn part_by_id(conn: &Connection, id: i64) -> AppResult<Part> {
+    query_parts(conn, &format!("SELECT {} FROM parts WHERE id = ?1", PART_COLUMNS), params![id])?
+        .pop()
+        .ok_or_else(|| AppError::RecordNotFound {
+            entity: "Part".to_string(),
+            field: "id".to_string(),
+            value: id.to_string(),
+        })
+}
+
+/// Change a part's stock on hand and record why. Stock cannot go below zero.
+fn record_stock_movement(
+    conn: &Connection,
+    part_id: i64,
+    kind: StockMovementKind,
+    quantity_change: i64,
+    maintenance_record_id: Option<i64>,
+    notes: Option<&str>,
+    user_id: Option<i64>,
+) -> AppResult<StockMovement> {
+    if quantity_change == 0 {
+        return Err(AppError::validation("quantity_change", "Quantity change cannot be zero"));
+    }
+    let part = part_by_id(conn, part_id)?;
+    if part.quantity_on_hand + quantity_change < 0 {
+        return Err(AppError::validation(
+            "quantity_change",
+            format!("Only {} of part {} in stock, cannot take out {}",
+                    part.quantity_on_hand, part.part_number, -quantity_change),
+        ));
+    }
+
+    conn.execute(
+        "UPDATE parts SET quantity_on_hand = quantity_on_hand + ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+        params![quantity_change, part_id],
+    )?;
+    let movement = conn.query_row(
+        &format!(
+            "INSERT INTO part_stock_movements (part_id, kind, quantity_change, maintenance_record_id, notes, created_by)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             RETURNING {}",
+            STOCK_MOVEMENT_COLUMNS
+        ),
+        params![part_id, kind.to_string(), quantity_change, maintenance_record_id, notes, user_id],
+        row_to_stock_movement,
+    )?;
+    Ok(movement)
+}
+
+/// Take the parts used by maintenance work out of stock
+fn consume_parts(
+    conn: &Connection,
+    maintenance_record_id: i64,
+    usages: &[PartUsage],
+    user_id: Option<i64>,
+) -> AppResult<Vec<StockMovement>> {
+    let mut movements = Vec::with_capacity(usages.len());
+    for usage in usages {
+        usage.validate()?;
+        let part_id: i64 = conn.query_row(
+            "SELECT id FROM parts WHERE part_number = ?1",
+            params![usage.part_number.trim()],
+            |row| row.get(0),
+        ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+            entity: "Part".to_string(),
+            field: "part_number".to_string(),
+            value: usage.part_number.clone(),
+        })?;
+        movements.push(record_stock_movement(
+            conn, part_id, StockMovementKind::Consumed, -usage.quantity,
+            Some(maintenance_record_id), None, user_id,
+        )?);
+    }
+    Ok(movements)
+}
+
+/// Parts a maintenance record lists as used, when `parts_used` holds a list
+/// of part numbers and quantities rather than free-form notes
+fn maintenance_part_usages(parts_used: Option<&JsonValue>) -> Option<Vec<PartUsage>> {
+    parts_used.and_then(|value| serde_json::from_value(value.clone()).ok())
+}
+
+/// Spare parts stock: part numbers, stock levels, which components each part
+/// fits and the stock taken out by maintenance work
+pub struct PartsService {
+    database: Arc<Database>,
+}
+
+impl PartsService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Add a part, with the stock already on hand recorded as received
+    pub fn create_part(&self, context: &RequestContext, input: PartInput, initial_quantity: i64) -> AppResult<Part> {
+        info!("[{}] Creating part {}", context.request_id, input.part_number);
+        input.validate()?;
+        if initial_quantity < 0 {
+            return Err(AppError::validation("initial_quantity", "Initial quantity cannot be negative"));
+        }
+        let user_id = context.session.as_ref().map(|s| s.user_id);
+
+        self.database.with_transaction(|conn| {
+            let exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM parts WHERE part_number = ?1)",
+                params![input.part_number.trim()],
+                |row| row.get(0),
+            )?;
+            if exists {
+                return Err(AppError::DuplicateRecord {
+                    entity: "Part".to_string(),
+                    field: "part_number".to_string(),
+                    value: input.part_number.clone(),
+                });
+            }
+
+            let id: i64 = conn.query_row(
+                "INSERT INTO parts (part_number, name, description, manufacturer, unit_cost, reorder_level)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 RETURNING id",
+                params![
+                    input.part_number.trim(), input.name.trim(), input.description,
+                    input.manufacturer, input.unit_cost, input.reorder_level,
+                ],
+                |row| row.get(0),
+            )?;
+            if initial_quantity > 0 {
+                record_stock_movement(conn, id, StockMovementKind::Received, initial_quantity,
+                                      None, Some("Initial stock"), user_id)?;
+            }
+            part_by_id(conn, id)
+        })
+    }
+
+    pub fn update_part(&self, context: &RequestContext, id: i64, input: PartInput) -> AppResult<Part> {
+        info!("[{}] Updating part {}", context.request_id, id);
+        input.validate()?;
+
+        self.database.with_transaction(|conn| {
+            part_by_id(conn, id)?;
+            let taken: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM parts WHERE part_number = ?1 AND id != ?2)",
+                params![input.part_number.trim(), id],
+                |row| row.get(0),
+            )?;
+            if taken {
+                return Err(AppError::DuplicateRecord {
+                    entity: "Part".to_string(),
+                    field: "part_number".to_string(),
+                    value: input.part_number.clone(),
+                });
+            }
+
+            conn.execute(
+                "UPDATE parts SET part_number = ?1, name = ?2, description = ?3, manufacturer = ?4,
+                     unit_cost = ?5, reorder_level = ?6, updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?7",
+                params![
+                    input.part_number.trim(), input.name.trim(), input.description,
+                    input.manufacturer, input.unit_cost, input.reorder_level, id,
+                ],
+            )?;
+            part_by_id(conn, id)
+        })
+    }
+
+    pub fn get_part(&self, id: i64) -> AppResult<Part> {
+        let conn = self.database.get_connection()?;
+        let result = part_by_id(&conn, id);
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Parts ordered by part number, optionally only those whose number or
+    /// name contains the search text
+    pub fn get_parts(&self, search: Option<String>) -> AppResult<Vec<Part>> {
+        debug!("Fetching parts (search: {:?})", search);
+        let pattern = search.map(|text| format!("%{}%", text.trim()));
+        let conn = self.database.get_connection()?;
+        let result = query_parts(
+            &conn,
+            &format!(
+                "SELECT {} FROM parts
+                 WHERE ?1 IS NULL OR part_number LIKE ?1 OR name LIKE ?1
+                 ORDER BY part_number",
+                PART_COLUMNS
+            ),
+            params![pattern],
+        );
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Replace the list of components a part fits
+    pub fn set_compatibility(&self, context: &RequestContext, part_id: i64, compatibility: Vec<PartCompatibility>) -> AppResult<Part> {
+        info!("[{}] Setting compatibility of part {} ({} entries)", context.request_id, part_id, compatibility.len());
+        for entry in &compatibility {
+            entry.validate()?;
+        }
+
+        self.database.with_transaction(|conn| {
+            part_by_id(conn, part_id)?;
+            conn.execute("DELETE FROM part_compatibility WHERE part_id = ?1", params![part_id])?;
+            for entry in &compatibility {
+                conn.execute(
+                    "INSERT INTO part_compatibility (part_id, component_type, manufacturer, model)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![part_id, entry.component_type.trim(), entry.manufacturer, entry.model],
+                )?;
+            }
+            part_by_id(conn, part_id)
+        })
+    }
+
+    /// Parts that fit a component
+    pub fn get_compatible_parts(&self, component_id: i64) -> AppResult<Vec<Part>> {
+        debug!("Fetching parts compatible with component {}", component_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<Part>> {
+            let exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM components WHERE id = ?1)",
+                params![component_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Err(AppError::RecordNotFound {
+                    entity: "Component".to_string(),
+                    field: "id".to_string(),
+                    value: component_id.to_string(),
+                });
+            }
+            query_parts(
+                &conn,
+                &format!(
+                    "SELECT {} FROM parts WHERE id IN (
+                         SELECT pc.part_id FROM part_compatibility pc, components c
+                         WHERE c.id = ?1
+                           AND pc.component_type = c.component_type
+                           AND (pc.manufacturer IS NULL OR pc.manufacturer = c.manufacturer)
+                           AND (pc.model IS NULL OR pc.model = c.model)
+                     )
+                     ORDER BY part_number",
+                    PART_COLUMNS
+                ),
+                params![component_id],
+            )
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Receive stock or correct it after a count. Consumption is recorded
+    /// against maintenance work instead.
+    pub fn adjust_stock(
+        &self,
+        context: &RequestContext,
+        part_id: i64,
+        kind: StockMovementKind,
+        quantity_change: i64,
+        notes: Option<String>,
+    ) -> AppResult<Part> {
+        info!("[{}] {} stock change of {} for part {}", context.request_id, kind, quantity_change, part_id);
+        match kind {
+            StockMovementKind::Consumed => {
+                return Err(AppError::validation("kind", "Consumption is recorded against maintenance records"));
+            }
+            StockMovementKind::Received if quantity_change <= 0 => {
+                return Err(AppError::validation("quantity_change", "Received stock must be a positive quantity"));
+            }
+            _ => {}
+        }
+        let user_id = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            record_stock_movement(conn, part_id, kind, quantity_change, None, notes.as_deref(), Some(user_id))?;
+            part_by_id(conn, part_id)
+        })
+    }
+
+    /// Take parts used by maintenance work out of stock
+    pub fn record_consumption(
+        &self,
+        context: &RequestContext,
+        maintenance_record_id: i64,
+        usages: Vec<PartUsage>,
+    ) -> AppResult<Vec<StockMovement>> {
+        info!("[{}] Recording {} parts used by maintenance record {}",
+              context.request_id, usages.len(), maintenance_record_id);
+        let user_id = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            let record = maintenance_record_by_id(conn, maintenance_record_id)?;
+            if record.status == MaintenanceStatus::Cancelled {
+                return Err(AppError::validation("maintenance_record_id", "Maintenance was cancelled"));
+            }
+            consume_parts(conn, maintenance_record_id, &usages, Some(user_id))
+        })
+    }
+
+    /// Parts at or below their reorder level, the emptiest first
+    pub fn get_low_stock_parts(&self) -> AppResult<Vec<Part>> {
+        debug!("Fetching low stock parts");
+        let conn = self.database.get_connection()?;
+        let result = query_parts(
+            &conn,
+            &format!(
+                "SELECT {} FROM parts WHERE quantity_on_hand <= reorder_level
+                 ORDER BY quantity_on_hand - reorder_level, part_number",
+                PART_COLUMNS
+            ),
+            [],
+        );
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Stock movements of a part, newest first
+    pub fn get_movements(&self, part_id: i64) -> AppResult<Vec<StockMovement>> {
+        debug!("Fetching stock movements for part {}", part_id);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<StockMovement>> {
+            part_by_id(&conn, part_id)?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM part_stock_movements WHERE part_id = ?1 ORDER BY created_at DESC, id DESC",
+                STOCK_MOVEMENT_COLUMNS
+            ))?;
+            let movements = stmt.query_map(params![part_id], row_to_stock_movement)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(movements)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+}
+
+// =============================================================================
+// Audit Service
+// =============================================================================
+
+const AUDIT_LOG_COLUMNS: &str = "id, request_id, user_id, username, action, resource_type, resource_id,
+     details, ip_address, user_agent, timestamp, success, error_message";
+
+fn row_to_audit_entry(row: &Row) -> rusqlite::Result<AuditLogEntry> {
+    let details: String = row.get(7)?;
+    Ok(AuditLogEntry {
+        id: row.get(0)?,
+        request_id: row.get(1)?,
+        user_id: row.get(2)?,
+        username: row.get(3)?,
+        action: row.get(4)?,
+        resource_type: row.get(5)?,
+        resource_id: row.get(6)?,
+        details: serde_json::from_str(&details).unwrap_or_default(),
+        ip_address: row.get(8)?,
+        user_agent: row.get(9)?,
+        timestamp: row.get(10)?,
+        success: row.get(11)?,
+        error_message: row.get(12)?,
+    })
+}
+
+/// Durable store for audit entries, installed as the application's audit sink
+pub struct AuditService {
+    database: Arc<Database>,
+}
+
+impl AuditService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Audit entries matching the filter, newest first
+    pub fn query(&self, filter: &AuditLogFilter, limit: usize, offset: usize) -> AppResult<Vec<AuditLogEntry>> {
+        debug!("Querying audit log: {:?} (limit {}, offset {})", filter, limit, offset);
+        let conn = self.database.get_read_connection()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM audit_log
+             WHERE (?1 IS NULL OR user_id = ?1)
+               AND (?2 IS NULL OR resource_type = ?2)
+               AND (?3 IS NULL OR resource_id = ?3)
+               AND (?4 IS NULL OR action = ?4)
+               AND (?5 IS NULL OR timestamp >= ?5)
+               AND (?6 IS NULL OR timestamp <= ?6)
+               AND (?7 IS NULL OR success = ?7)
+             ORDER BY timestamp DESC, rowid DESC
+             LIMIT ?8 OFFSET ?9",
+            AUDIT_LOG_COLUMNS
+        ))?;
+        let entries = stmt.query_map(
+            params![
+                filter.user_id,
+                filter.resource_type,
+                filter.resource_id,
+                filter.action,
+                filter.start_date,
+                filter.end_date,
+                filter.success,
+                limit as i64,
+                offset as i64,
+            ],
+            row_to_audit_entry,
+        )?.collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    /// Visit every audit entry, oldest first
+    pub fn stream_entries<F>(&self, mut visit: F) -> AppResult<u64>
+    where
+        F: FnMut(AuditLogEntry) -> AppResult<()>,
+    {
+        debug!("Streaming audit log");
+        let conn = self.database.get_read_connection()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM audit_log ORDER BY timestamp, rowid", AUDIT_LOG_COLUMNS
+        ))?;
+        let mut count = 0;
+        for entry in stmt.query_map([], row_to_audit_entry)? {
+            visit(entry?)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    pub fn count_entries(&self) -> AppResult<u64> {
+        let conn = self.database.get_read_connection()?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM audit_log", [], |row| row.get(0))?;
+        Ok(count as u64)
+    }
+}
+
+impl AuditSink for AuditService {
+    fn record(&self, entry: &AuditLogEntry) -> AppResult<()> {
+        let details = serde_json::to_string(&entry.details)?;
+        let conn = self.database.get_connection()?;
+        let result = conn.execute(
+            &format!(
+                "INSERT OR IGNORE INTO audit_log ({})
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                AUDIT_LOG_COLUMNS
+            ),
+            params![
+                entry.id,
+                entry.request_id,
+                entry.user_id,
+                entry.username,
+                entry.action,
+                entry.resource_type,
+                entry.resource_id,
+                details,
+                entry.ip_address,
+                entry.user_agent,
+                entry.timestamp,
+                entry.success,
+                entry.error_message,
+            ],
+        );
+        self.database.return_connection(conn);
+        result?;
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Security Event Service
+// =============================================================================
+
+const SECURITY_EVENT_COLUMNS: &str = "id, kind, user_id, username, request_id, detail, occurred_at";
+
+fn row_to_security_event(row: &Row) -> rusqlite::Result<SecurityEvent> {
+    Ok(SecurityEvent {
+        id: row.get(0)?,
+        kind: row.get::<_, String>(1)?.parse().unwrap_or(SecurityEventKind::TokenAnomaly),
+        user_id: row.get(2)?,
+        username: row.get(3)?,
+        request_id: row.get(4)?,
+        detail: row.get(5)?,
+        occurred_at: row.get(6)?,
+    })
+}
+
+/// Durable store for security events, installed as the application's
+/// security event sink. Kept apart from the audit log so it can be reviewed
+/// on its own and kept for a shorter time.
+pub struct SecurityEventService {
+    database: Arc<Database>,
+}
+
+impl SecurityEventService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Security events matching the filter, newest first
+    pub fn query(&self, filter: &SecurityEventFilter, limit: usize, offset: usize) -> AppResult<Vec<SecurityEvent>> {
+        debug!("Querying security events: {:?} (limit {}, offset {})", filter, limit, offset);
+        let conn = self.database.get_read_connection()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM security_events
+             WHERE (?1 IS NULL OR kind = ?1)
+               AND (?2 IS NULL OR user_id = ?2)
+               AND (?3 IS NULL OR LOWER(username) = LOWER(?3))
+               AND (?4 IS NULL OR occurred_at >= ?4)
+               AND (?5 IS NULL OR occurred_at <= ?5)
+             ORDER BY occurred_at DESC, rowid DESC
+             LIMIT ?6 OFFSET ?7",
+            SECURITY_EVENT_COLUMNS
+        ))?;
+        let events = stmt.query_map(
+            params![
+                filter.kind.map(|kind| kind.to_string()),
+                filter.user_id,
+                filter.username.as_deref().map(str::trim),
+                filter.start_date,
+                filter.end_date,
+                limit as i64,
+                offset as i64,
+            ],
+            row_to_security_event,
+        )?.collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(events)
+    }
+
+    /// Permanently drop events older than the configured retention. Returns
+    /// how many were dropped.
+    pub fn purge_expired(&self) -> AppResult<usize> {
+        self.database.with_transaction(|conn| {
+            let retention_days = read_app_settings(conn)?.security_event_retention_days;
+            let cutoff = Utc::now() - chrono::Duration::days(retention_days);
+            let purged = conn.execute("DELETE FROM security_events WHERE occurred_at < ?1", params![cutoff])?;
+            if purged > 0 {
+                info!("Purged {} security events older than {} days", purged, retention_days);
+            }
+            Ok(purged)
+        })
+    }
+}
+
+impl SecurityEventSink for SecurityEventService {
+    fn record(&self, event: &SecurityEvent) -> AppResult<()> {
+        let conn = self.database.get_connection()?;
+        let result = conn.execute(
+            &format!(
+                "INSERT OR IGNORE INTO security_events ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                SECURITY_EVENT_COLUMNS
+            ),
+            params![
+                event.id,
+                event.kind.to_string(),
+                event.user_id,
+                event.username,
+                event.request_id,
+                event.detail,
+                event.occurred_at,
+            ],
+        );
+        self.database.return_connection(conn);
+        result?;
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Login History Service
+// =============================================================================
+
+const LOGIN_HISTORY_COLUMNS: &str =
+    "id, user_id, username, method, client_id, device_name, success, failure_reason, occurred_at";
+
+fn row_to_login_history_entry(row: &Row) -> rusqlite::Result<LoginHistoryEntry> {
+    Ok(LoginHistoryEntry {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        username: row.get(2)?,
+        method: row.get::<_, String>(3)?.parse().unwrap_or(LoginMethod::Password),
+        client_id: row.get(4)?,
+        device_name: row.get(5)?,
+        success: row.get(6)?,
+        failure_reason: row.get(7)?,
+        occurred_at: row.get(8)?,
+    })
+}
+
+/// Every login to an account, so users can spot ones that were not theirs
+pub struct LoginHistoryService {
+    database: Arc<Database>,
+}
+
+impl LoginHistoryService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Record a login attempt. Attempts on names that match no account are
+    /// left to the security event log, since there is no history to add
+    /// them to.
+    pub fn record(&self, attempt: &LoginAttempt) -> AppResult<()> {
+        let device = attempt.device.normalized();
+        self.database.with_transaction(|conn| {
+            let user_id = match attempt.user_id {
+                Some(user_id) => Some(user_id),
+                None => conn.query_row(
+                    "SELECT id FROM users WHERE LOWER(username) = LOWER(?1)",
+                    params![attempt.username.trim()],
+                    |row| row.get::<_, i64>(0),
+                ).optional()?,
+            };
+            let Some(user_id) = user_id else {
+                return Ok(());
+            };
+
+            conn.execute(
+                "INSERT INTO login_history
+                     (user_id, username, method, client_id, device_name, success, failure_reason, occurred_at)
+                 SELECT id, username, ?2, ?3, ?4, ?5, ?6, ?7 FROM users WHERE id = ?1",
+                params![
+                    user_id,
+                    attempt.method.to_string(),
+                    device.client_id,
+                    device.name,
+                    attempt.failure_reason.is_none(),
+                    attempt.failure_reason,
+                    Utc::now(),
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Logins matching the filter, newest first
+    pub fn query(&self, filter: &LoginHistoryFilter, limit: usize, offset: usize) -> AppResult<Vec<LoginHistoryEntry>> {
+        debug!("Querying login history: {:?} (limit {}, offset {})", filter, limit, offset);
+        let conn = self.database.get_read_connection()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM login_history
+             WHERE (?1 IS NULL OR user_id = ?1)
+               AND (?2 IS NULL OR success = ?2)
+               AND (?3 IS NULL OR occurred_at >= ?3)
+               AND (?4 IS NULL OR occurred_at <= ?4)
+             ORDER BY occurred_at DESC, id DESC
+             LIMIT ?5 OFFSET ?6",
+            LOGIN_HISTORY_COLUMNS
+        ))?;
+        let entries = stmt.query_map(
+            params![
+                filter.user_id,
+                filter.success,
+                filter.start_date,
+                filter.end_date,
+                limit as i64,
+                offset as i64,
+            ],
+            row_to_login_history_entry,
+        )?.collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    /// Permanently drop logins older than the security event retention.
+    /// Returns how many were dropped.
+    pub fn purge_expired(&self) -> AppResult<usize> {
+        self.database.with_transaction(|conn| {
+            let retention_days = read_app_settings(conn)?.security_event_retention_days;
+            let cutoff = Utc::now() - chrono::Duration::days(retention_days);
+            let purged = conn.execute("DELETE FROM login_history WHERE occurred_at < ?1", params![cutoff])?;
+            if purged > 0 {
+                info!("Purged {} logins older than {} days from login history", purged, retention_days);
+            }
+            Ok(purged)
+        })
+    }
+}
+
+// =============================================================================
+// Settings Service
+// =============================================================================
+
+pub struct SettingsService {
+    database: Arc<Database>,
+}
+
+impl SettingsService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    pub fn get_settings(&self) -> AppResult<AppSettings> {
+        let conn = self.database.get_connection()?;
+        let result = read_app_settings(&conn);
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Change one setting and return the settings now in effect
+    pub fn set_setting(&self, context: &RequestContext, key: SettingKey, value: JsonValue) -> AppResult<AppSettings> {
+        let user_id = context.current_user()?.user_id;
+        info!("[{}] Setting {} to {}", context.request_id, key, value);
+        key.validate_value(&value)?;
+
+        self.database.with_transaction(|conn| {
+            if key == SettingKey::DefaultComplianceStandard {
+                let code = value.as_str().unwrap_or_default();
+                let exists: bool = conn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM compliance_standards WHERE standard_code = ?1 AND is_active = 1)",
+                    params![code],
+                    |row| row.get(0),
+                )?;
+                if !exists {
+                    return Err(AppError::validation(key.as_str(), format!("Unknown compliance standard: {}", code)));
+                }
+            }
+
+            conn.execute(
+                "INSERT INTO app_settings (key, value, updated_by, updated_at)
+                 VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)
+                 ON CONFLICT(key) DO UPDATE SET
+                     value = excluded.value,
+                     updated_by = excluded.updated_by,
+                     updated_at = excluded.updated_at",
+                params![key.as_str(), value.to_string(), user_id],
+            )?;
+            read_app_settings(conn)
+        })
+    }
+}
+
+/// Settings in effect, with defaults for those never changed
+fn read_app_settings(conn: &Connection) -> AppResult<AppSettings> {
+    let mut settings = AppSettings::default();
+    let mut stmt = conn.prepare("SELECT key, value FROM app_settings")?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let key: String = row.get(0)?;
+        let value: String = row.get(1)?;
+        // Rows left behind by older versions are ignored rather than failing every read
+        let Ok(key) = key.parse::<SettingKey>() else {
+            warn!("Ignoring unknown application setting '{}'", key);
+            continue;
+        };
+        settings.apply(key, serde_json::from_str(&value)?)?;
+    }
+    Ok(settings)
+}
+
+// =============================================================================
+// Validation Rule Service
+// =============================================================================
+
+const VALIDATION_RULE_COLUMNS: &str =
+    "id, name, kind, stage, item_category, item_name, min_severity, min_value, max_value, unit,
+     is_active, created_at, updated_at";
+
+fn row_to_validation_rule(row: &Row) -> rusqlite::Result<ValidationRule> {
+    Ok(ValidationRule {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        kind: row.get::<_, String>(2)?.parse().unwrap_or(ValidationRuleKind::PhotoRequired),
+        stage: row.get::<_, String>(3)?.parse().unwrap_or(RuleStage::Submission),
+        item_category: row.get(4)?,
+        item_name: row.get(5)?,
+        min_severity: row.get::<_, Option<String>>(6)?.and_then(|s| s.parse().ok()),
+        min_value: row.get(7)?,
+        max_value: row.get(8)?,
+        unit: row.get(9)?,
+        is_active: row.get(10)?,
+        created_at: row.get(11)?,
+        updated_at: row.get(12)?,
+    })
+}
+
+fn validation_rule_by_id(conn: &Connection, id: i64) -> AppResult<ValidationRule> {
+    conn.query_row(
+        &format!("SELECT {} FROM validation_rules WHERE id = ?1", VALIDATION_RULE_COLUMNS),
+        params![id],
+        row_to_validation_rule,
+    ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+        entity: "ValidationRule".to_string(),
+        field: "id".to_string(),
+        value: id.to_string(),
+    })
+}
+
+/// Active rules enforced at `stage`; submission enforces every rule
+fn active_validation_rules(conn: &Connection, stage: RuleStage) -> AppResult<Vec<ValidationRule>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM validation_rules WHERE is_active = 1 AND (?1 = 'Submission' OR stage = ?1) ORDER BY id",
+        VALIDATION_RULE_COLUMNS
+    ))?;
+    let rules = stmt.query_map(params![stage.to_string()], row_to_validation_rule)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rules)
+}
+
+/// The stored facts about an item that rules look at
+struct RuleSubject {
+    item_id: i64,
+    item_name: String,
+    item_category: String,
+    severity: Option<Severity>,
+    is_compliant: Option<bool>,
+    corrective_action: Option<String>,
+    measured_value: Option<f64>,
+    measurement_unit: Option<String>,
+    photo_count: i64,
+}
+
+const RULE_SUBJECT_SELECT: &str =
+    "SELECT ii.id, ii.item_name, ii.item_category, ii.severity, ii.is_compliant, ii.corrective_action,
+            ii.measured_value, ii.measurement_unit,
+            (SELECT COUNT(*) FROM inspection_item_media m WHERE m.item_id = ii.id)
+     FROM inspection_items ii";
+
+fn row_to_rule_subject(row: &Row) -> rusqlite::Result<RuleSubject> {
+    Ok(RuleSubject {
+        item_id: row.get(0)?,
+        item_name: row.get(1)?,
+        item_category: row.get(2)?,
+        severity: row.get::<_, Option<String>>(3)?.and_then(|s| s.parse().ok()),
+        is_compliant: row.get(4)?,
+        corrective_action: row.get(5)?,
+        measured_value: row.get(6)?,
+        measurement_unit: row.get(7)?,
+        photo_count: row.get(8)?,
+    })
+}
+
+fn rule_violations(subject: &RuleSubject, rules: &[ValidationRule]) -> Vec<RuleViolation> {
+    let mut violations = Vec::new();
+    for rule in rules {
+        if !rule.applies_to(&subject.item_category, &subject.item_name, subject.severity.as_ref()) {
+            continue;
+        }
+        let violation = |field: &str, message: String| RuleViolation {
+            rule_id: rule.id,
+            rule_name: rule.name.clone(),
+            field: field.to_string(),
+            message,
+        };
+        match rule.kind {
+            ValidationRuleKind::MeasurementRange => {
+                // Items without a reading are not measured and so not checked
+                let Some(value) = subject.measured_value else { continue };
+                let unit = subject.measurement_unit.as_deref().unwrap_or("").trim();
+                if let Some(expected) = rule.unit.as_deref().map(str::trim) {
+                    if !expected.eq_ignore_ascii_case(unit) {
+                        violations.push(violation(
+                            "measurement_unit",
+                            format!("Reading must be recorded in {}, not '{}'", expected, unit),
+                        ));
+                        continue;
+                    }
+                }
+                let below = rule.min_value.is_some_and(|min| value < min);
+                let above = rule.max_value.is_some_and(|max| value > max);
+                if below || above {
+                    let range = match (rule.min_value, rule.max_value) {
+                        (Some(min), Some(max)) => format!("between {} and {}", min, max),
+                        (Some(min), None) => format!("at least {}", min),
+                        (None, Some(max)) => format!("at most {}", max),
+                        (None, None) => String::new(),
+                    };
+                    let reading = if unit.is_empty() { value.to_string() } else { format!("{} {}", value, unit) };
+                    violations.push(violation(
+                        "measured_value",
+                        format!("Reading {} is outside the plausible range: expected {}", reading, range),
+                    ));
+                }
+            }
+            ValidationRuleKind::PhotoRequired => {
+                if subject.photo_count == 0 {
+                    violations.push(violation("photos", "A photo of the item is required".to_string()));
+                }
+            }
+            ValidationRuleKind::CorrectiveActionRequired => {
+                let missing = subject.corrective_action.as_deref().is_none_or(|a| a.trim().is_empty());
+                if subject.is_compliant == Some(false) && missing {
+                    violations.push(violation(
+                        "corrective_action",
+                        "A corrective action is required for a non-compliant item".to_string(),
+                    ));
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// Reject an item being saved that breaks a rule enforced on save
+fn ensure_item_passes_save_rules(conn: &Connection, item_id: i64) -> AppResult<()> {
+    let rules = active_validation_rules(conn, RuleStage::Save)?;
+    if rules.is_empty() {
+        return Ok(());
+    }
+    let subject = conn.query_row(
+        &format!("{} WHERE ii.id = ?1", RULE_SUBJECT_SELECT),
+        params![item_id],
+        row_to_rule_subject,
+    )?;
+    let violations = rule_violations(&subject, &rules);
+    match violations.first() {
+        None => Ok(()),
+        Some(first) => Err(AppError::validation(
+            first.field.clone(),
+            violations.iter().map(|v| v.message.as_str()).collect::<Vec<_>>().join("; "),
+        )),
+    }
+}
+
+/// Check every item of an inspection against all active rules
+fn inspection_rule_report(conn: &Connection, inspection_id: i64) -> AppResult<InspectionRuleReport> {
+    let rules = active_validation_rules(conn, RuleStage::Submission)?;
+    let mut stmt = conn.prepare(&format!("{} WHERE ii.inspection_id = ?1 ORDER BY ii.id", RULE_SUBJECT_SELECT))?;
+    let subjects = stmt.query_map(params![inspection_id], row_to_rule_subject)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let items = subjects.iter()
+        .filter_map(|subject| {
+            let violations = rule_violations(subject, &rules);
+            (!violations.is_empty()).then(|| ItemRuleViolations {
+                item_id: subject.item_id,
+                item_name: subject.item_name.clone(),
+                violations,
+            })
+        })
+        .collect();
+    Ok(InspectionRuleReport { inspection_id, items_checked: subjects.len(), items })
+}
+
+/// Refuse to submit an inspection while any of its items break a rule
+fn ensure_inspection_passes_rules(conn: &Connection, inspection_id: i64) -> AppResult<()> {
+    let report = inspection_rule_report(conn, inspection_id)?;
+    if report.passed() {
+        return Ok(());
+    }
+    let summary: Vec<String> = report.items.iter()
+        .map(|item| format!(
+            "{}: {}",
+            item.item_name,
+            item.violations.iter().map(|v| v.message.as_str()).collect::<Vec<_>>().join(", ")
+        ))
+        .collect();
+    Err(AppError::validation(
+        "items",
+        format!("{} items break validation rules. {}", report.items.len(), summary.join("; ")),
+    ))
+}
+
+/// Configurable checks on inspection data entry
+pub struct ValidationRuleService {
+    database: Arc<Database>,
+}
+
+impl ValidationRuleService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    pub fn get_rules(&self, include_inactive: bool) -> AppResult<Vec<ValidationRule>> {
+        debug!("Fetching validation rules, include inactive: {}", include_inactive);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<ValidationRule>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM validation_rules WHERE ?1 OR is_active = 1 ORDER BY name, id",
+                VALIDATION_RULE_COLUMNS
+            ))?;
+            let rules = stmt.query_map(params![include_inactive], row_to_validation_rule)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(rules)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    pub fn create_rule(&self, context: &RequestContext, input: ValidationRuleInput) -> AppResult<ValidationRule> {
+        info!("[{}] Creating validation rule: {}", context.request_id, input.name);
+        input.validate()?;
+
+        self.database.with_transaction(|conn| {
+            let id: i64 = conn.query_row(
+                "INSERT INTO validation_rules
+                    (name, kind, stage, item_category, item_name, min_severity, min_value, max_value, unit, is_active)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                 RETURNING id",
+                params![
+                    input.name.trim(), input.kind.to_string(), input.stage.to_string(),
+                    input.item_category, input.item_name, input.min_severity.as_ref().map(|s| s.to_string()),
+                    input.min_value, input.max_value, input.unit, input.is_active,
+                ],
+                |row| row.get(0),
+            )?;
+            validation_rule_by_id(conn, id)
+        })
+    }
+
+    /// Replace a rule's definition. Items already saved are not rechecked
+    /// until their inspection is submitted.
+    pub fn update_rule(&self, context: &RequestContext, id: i64, input: ValidationRuleInput) -> AppResult<ValidationRule> {
+        info!("[{}] Updating validation rule: {}", context.request_id, id);
+        input.validate()?;
+
+        self.database.with_transaction(|conn| {
+            validation_rule_by_id(conn, id)?;
+            conn.execute(
+                "UPDATE validation_rules
+                 SET name = ?1, kind = ?2, stage = ?3, item_category = ?4, item_name = ?5, min_severity = ?6,
+                     min_value = ?7, max_value = ?8, unit = ?9, is_active = ?10, updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ?11",
+                params![
+                    input.name.trim(), input.kind.to_string(), input.stage.to_string(),
+                    input.item_category, input.item_name, input.min_severity.as_ref().map(|s| s.to_string()),
+                    input.min_value, input.max_value, input.unit, input.is_active, id,
+                ],
+            )?;
+            validation_rule_by_id(conn, id)
+        })
+    }
+
+    pub fn delete_rule(&self, context: &RequestContext, id: i64) -> AppResult<()> {
+        info!("[{}] Deleting validation rule: {}", context.request_id, id);
+
+        self.database.with_transaction(|conn| {
+            let deleted = conn.execute("DELETE FROM validation_rules WHERE id = ?1", params![id])?;
+            if deleted == 0 {
+                return Err(AppError::RecordNotFound {
+                    entity: "ValidationRule".to_string(),
+                    field: "id".to_string(),
+                    value: id.to_string(),
+                });
+            }
+            Ok(())
+        })
+    }
+
+    /// The rules each item of an inspection currently breaks
+    pub fn check_inspection(&self, inspection_id: i64) -> AppResult<InspectionRuleReport> {
+        debug!("Checking inspection {} against validation rules", inspection_id);
+        let conn = self.database.get_connection()?;
+        let result = inspection_rule_report(&conn, inspection_id);
+        self.database.return_connection(conn);
+        result
+    }
+}
+
+// =============================================================================
+// JSON Schema Service
+// =============================================================================
+
+const JSON_SCHEMA_COLUMNS: &str =
+    "id, target, scope, schema, description, updated_by, created_at, updated_at";
+
+fn row_to_json_schema(row: &Row) -> rusqlite::Result<JsonSchemaDefinition> {
+    Ok(JsonSchemaDefinition {
+        id: row.get(0)?,
+        target: row.get::<_, String>(1)?.parse().unwrap_or(SchemaTarget::AssetSpecifications),
+        scope: row.get(2)?,
+        schema: row.get(3)?,
+        description: row.get(4)?,
+        updated_by: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+/// Check a JSON field against the schema for its scope, if one is defined.
+/// Missing values are not checked.
+fn ensure_matches_schema(conn: &Connection, target: SchemaTarget, scope: &str, value: Option<&JsonValue>) -> AppResult<()> {
+    let Some(value) = value else {
+        return Ok(());
+    };
+    let schema: Option<JsonValue> = conn.query_row(
+        "SELECT schema FROM json_schemas WHERE target = ?1 AND scope = ?2",
+        params![target.to_string(), scope],
+        |row| row.get(0),
+    ).optional()?;
+    match schema {
+        Some(schema) => json_schema::ensure_valid(target.field_name(), &schema, value),
+        None => Ok(()),
+    }
+}
+
+/// Stored JSON for one target joined with the schema for its scope:
+/// record ID, label, scope, schema and value
+fn schema_audit_sql(target: SchemaTarget) -> &'static str {
+    match target {
+        SchemaTarget::AssetSpecifications =>
+            "SELECT a.id, a.asset_number, s.scope, s.schema, a.specifications
+             FROM assets a JOIN json_schemas s ON s.target = 'AssetSpecifications' AND s.scope = a.asset_type
+             WHERE a.deleted_at IS NULL AND a.specifications IS NOT NULL ORDER BY a.id",
+        SchemaTarget::ComponentSpecifications =>
+            "SELECT c.id, c.component_name, s.scope, s.schema, c.specifications
+             FROM components c JOIN json_schemas s ON s.target = 'ComponentSpecifications' AND s.scope = c.component_type
+             WHERE c.specifications IS NOT NULL ORDER BY c.id",
+        SchemaTarget::ChecklistData =>
+            "SELECT i.id, 'Inspection ' || i.id, s.scope, s.schema, i.checklist_data
+             FROM inspections i JOIN json_schemas s ON s.target = 'ChecklistData' AND s.scope = i.inspection_type
+             WHERE i.deleted_at IS NULL AND i.checklist_data IS NOT NULL ORDER BY i.id",
+        SchemaTarget::StandardRequirements =>
+            "SELECT c.id, c.standard_code, s.scope, s.schema, c.requirements
+             FROM compliance_standards c JOIN json_schemas s ON s.target = 'StandardRequirements' AND s.scope = c.standard_code
+             WHERE c.requirements IS NOT NULL ORDER BY c.id",
+    }
+}
+
+pub struct JsonSchemaService {
+    database: Arc<Database>,
+}
+
+impl JsonSchemaService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Schemas for one target or all of them
+    pub fn get_schemas(&self, target: Option<SchemaTarget>) -> AppResult<Vec<JsonSchemaDefinition>> {
+        debug!("Fetching JSON schemas for {:?}", target);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<JsonSchemaDefinition>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM json_schemas WHERE ?1 IS NULL OR target = ?1 ORDER BY target, scope",
+                JSON_SCHEMA_COLUMNS
+            ))?;
+            let schemas = stmt.query_map(params![target.map(|t| t.to_string())], row_to_json_schema)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(schemas)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Add a schema, or replace the one for the same target and scope.
+    /// Existing records are not checked; use `audit_records` for that.
+    pub fn save_schema(&self, context: &RequestContext, input: JsonSchemaInput) -> AppResult<JsonSchemaDefinition> {
+        let user_id = context.current_user()?.user_id;
+        info!("[{}] Saving {} schema for {}", context.request_id, input.target, input.scope);
+        input.validate()?;
+
+        self.database.with_transaction(|conn| {
+            let schema = conn.query_row(
+                &format!(
+                    "INSERT INTO json_schemas (target, scope, schema, description, updated_by)
+                     VALUES (?1, ?2, ?3, ?4, ?5)
+                     ON CONFLICT(target, scope) DO UPDATE SET
+                         schema = excluded.schema,
+                         description = excluded.description,
+                         updated_by = excluded.updated_by,
+                         updated_at = CURRENT_TIMESTAMP
+                     RETURNING {}",
+                    JSON_SCHEMA_COLUMNS
+                ),
+                params![
+                    input.target.to_string(), input.scope.trim(), input.schema.to_string(),
+                    input.description, user_id
+                ],
+                row_to_json_schema,
+            )?;
+            Ok(schema)
+        })
+    }
+
+    pub fn delete_schema(&self, context: &RequestContext, id: i64) -> AppResult<()> {
+        info!("[{}] Deleting JSON schema {}", context.request_id, id);
+
+        self.database.with_transaction(|conn| {
+            let deleted = conn.execute("DELETE FROM json_schemas WHERE id = ?1", params![id])?;
+            if deleted == 0 {
+                return Err(AppError::RecordNotFound {
+                    entity: "JsonSchema".to_string(),
+                    field: "id".to_string(),
+                    value: id.to_string(),
+                });
+            }
+            Ok(())
+        })
+    }
+
+    /// Check stored records against the current schemas. Records with no
+    /// schema for their scope are not counted.
+    pub fn audit_records(&self, target: Option<SchemaTarget>) -> AppResult<SchemaAuditReport> {
+        info!("Auditing stored JSON against schemas for {:?}", target);
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<SchemaAuditReport> {
+            let mut report = SchemaAuditReport::default();
+            let targets: Vec<SchemaTarget> = match target {
+                Some(target) => vec![target],
+                None => SchemaTarget::ALL.to_vec(),
+            };
+            for target in targets {
+                let mut stmt = conn.prepare(schema_audit_sql(target))?;
+                let mut rows = stmt.query([])?;
+                while let Some(row) = rows.next()? {
+                    report.records_checked += 1;
+                    let schema: JsonValue = row.get(3)?;
+                    // Text that is not JSON at all is reported rather than aborting the audit
+                    let violations = match serde_json::from_str::<JsonValue>(&row.get::<_, String>(4)?) {
+                        Ok(value) => json_schema::validate(&schema, &value),
+                        Err(e) => vec![json_schema::SchemaViolation {
+                            path: String::new(),
+                            message: format!("is not valid JSON: {}", e),
+                        }],
+                    };
+                    if !violations.is_empty() {
+                        report.findings.push(SchemaAuditFinding {
+                            target,
+                            scope: row.get(2)?,
+                            record_id: row.get(0)?,
+                            record_label: row.get(1)?,
+                            violations,
+                        });
+                    }
+                }
+            }
+            Ok(report)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+}
+
+// =============================================================================
+// Data Warehouse Export Service
+// =============================================================================
+
+/// Visit each row of `sql`, mapped by `map`, and return how many there were
+fn stream_rows<R>(
+    conn: &Connection,
+    sql: &str,
+    map: impl Fn(&Row) -> rusqlite::Result<R>,
+    visit: &mut dyn FnMut(R) -> AppResult<()>,
+) -> AppResult<u64> {
+    let mut stmt = conn.prepare(sql)?;
+    let mut count = 0;
+    for record in stmt.query_map([], map)? {
+        visit(record?)?;
+        count += 1;
     }
+    Ok(count)
+}
 
-    /// Check if user is rate limited (placeholder)
-    ///
-    /// Note: This is a placeholder implementation for future rate limiting functionality
-    pub fn is_rate_limited(&self, user_id: i64, _action: &str) -> AppResult<bool> {
-        debug!("Checking rate limit for user: {} (placeholder)", user_id);
-        // TODO: Implement actual rate limiting check
-        Ok(false)
-    }
+/// Star-schema extracts of inspections, findings and assets for BI tools,
+/// written on demand or on the schedule set in the application settings
+pub struct WarehouseExportService {
+    database: Arc<Database>,
+    settings: Arc<SettingsService>,
+}
 
-    /// Log user activity for audit purposes (placeholder)
-    ///
-    /// Note: This is a placeholder implementation for future audit functionality
-    pub fn log_user_activity(&self, user_id: i64, activity: &str, metadata: Option<&str>) -> AppResult<()> {
-        info!("User activity - ID: {}, Action: {}, Metadata: {:?} (placeholder)",
-              user_id, activity, metadata);
-        // TODO: Implement actual activity logging to database or external service
-        Ok(())
+impl WarehouseExportService {
+    pub fn new(database: Arc<Database>, settings: Arc<SettingsService>) -> Self {
+        Self { database, settings }
     }
 
-    /// Get user activity history (placeholder)
-    ///
-    /// Note: This is a placeholder implementation for future audit functionality
-    pub fn get_user_activity_history(&self, user_id: i64, _filter: QueryFilter) -> AppResult<Vec<String>> {
-        debug!("Getting activity history for user: {} (placeholder)", user_id);
-        // TODO: Implement actual activity history retrieval
-        Ok(vec!["Activity logging not yet implemented".to_string()])
+    /// Write an extract to the configured directory now
+    pub fn export_now(&self, context: &RequestContext, format: ExportFormat, tracker: &OperationHandle) -> AppResult<WarehouseManifest> {
+        info!("[{}] Writing data warehouse extract ({})", context.request_id, format.extension());
+        let settings = self.settings.get_settings()?;
+        let root = settings.warehouse_export_directory.trim();
+        if root.is_empty() {
+            return Err(AppError::validation(
+                SettingKey::WarehouseExportDirectory.as_str(),
+                "No data warehouse export directory is configured",
+            ));
+        }
+        self.write_extract(Path::new(root), format, Some(tracker))
     }
 
-    /// Get account lockout information for a user
-    ///
-    /// Note: Basic implementation - extend as needed for production use
-    pub fn get_account_lockout_info(&self, user_id: i64) -> AppResult<AccountLockoutInfo> {
-        debug!("Getting account lockout info for user: {}", user_id);
-        
-        // For now, return a basic implementation
-        // TODO: Implement actual lockout tracking with database table
-        Ok(AccountLockoutInfo {
-            user_id,
-            failed_attempts: 0,
-            locked_at: None,
-            locked_until: None,
-            is_locked: false,
-        })
+    /// Write a CSV extract when a directory is configured and the interval
+    /// has passed since the latest one. Returns `None` when nothing was due.
+    pub fn export_if_due(&self) -> AppResult<Option<WarehouseManifest>> {
+        let settings = self.settings.get_settings()?;
+        let root = settings.warehouse_export_directory.trim();
+        if root.is_empty() {
+            return Ok(None);
+        }
+        let root = Path::new(root);
+        if let Some(latest) = warehouse::read_manifest(root)? {
+            let due = latest.generated_at + chrono::Duration::hours(settings.warehouse_export_interval_hours);
+            if Utc::now() < due {
+                return Ok(None);
+            }
+        }
+        self.write_extract(root, ExportFormat::Csv, None).map(Some)
     }
 
-    /// Lock user account (placeholder)
-    ///
-    /// Note: This is a placeholder implementation for future account lockout functionality
-    pub fn lock_user_account(&self, user_id: i64, _reason: &str) -> AppResult<()> {
-        info!("Locking user account: {} (placeholder)", user_id);
-        // TODO: Implement actual account locking logic
-        self.database.with_transaction(|conn| {
-            conn.execute(
-                "UPDATE users SET is_active = 0, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
-                params![user_id]
-            )?;
-            Ok(())
-        })
+    /// Manifest of the latest extract, if any has been written
+    pub fn latest_extract(&self) -> AppResult<Option<WarehouseManifest>> {
+        let settings = self.settings.get_settings()?;
+        match settings.warehouse_export_directory.trim() {
+            "" => Ok(None),
+            root => warehouse::read_manifest(Path::new(root)),
+        }
     }
 
-    /// Unlock user account (placeholder)
-    ///
-    /// Note: This is a placeholder implementation for future account lockout functionality
-    pub fn unlock_user_account(&self, user_id: i64) -> AppResult<()> {
-        info!("Unlocking user account: {} (placeholder)", user_id);
-        // TODO: Implement actual account unlocking logic
-        self.database.with_transaction(|conn| {
-            conn.execute(
-                "UPDATE users SET is_active = 1, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
-                params![user_id]
-            )?;
-            Ok(())
-        })
-    }
+    /// Write every table into a new extract directory under `root`, then
+    /// point the manifest at it and prune old extracts. A failed extract
+    /// leaves the previous one current.
+    /// When `tracker` is given the extract reports to it and stops, leaving
+    /// the previous extract current, once it is cancelled.
+    pub fn write_extract(&self, root: &Path, format: ExportFormat, tracker: Option<&OperationHandle>) -> AppResult<WarehouseManifest> {
+        let generated_at = Utc::now();
+        let directory = root.join(warehouse::extract_directory_name(generated_at));
+        std::fs::create_dir_all(&directory)
+            .map_err(|e| AppError::file_system("create", directory.display().to_string(), e.to_string()))?;
+
+        let conn = self.database.get_read_connection()?;
+        let result = (|| -> AppResult<Vec<WarehouseTable>> {
+            let mut tables = Vec::new();
+            let mut dates = warehouse::DateRange::default();
+            let file = |name: &str| format!("{}.{}", name, format.extension());
+            let progress = |_| tracker.map_or(Ok(()), |t| t.check_cancelled());
+
+            let rows = export_to_file(&directory.join(file("fact_inspections")), format, |visit| {
+                stream_rows(
+                    &conn,
+                    "SELECT i.id, i.asset_id, i.inspector_id, i.scheduled_date, i.actual_date, i.inspection_type,
+                            i.status, i.overall_condition, COUNT(ii.id),
+                            COUNT(CASE WHEN ii.is_compliant = 1 THEN 1 END),
+                            COUNT(CASE WHEN TRIM(COALESCE(ii.finding, '')) != '' OR ii.severity IS NOT NULL
+                                            OR ii.is_compliant = 0 THEN 1 END)
+                     FROM inspections i
+                     LEFT JOIN inspection_items ii ON ii.inspection_id = i.id
+                     WHERE i.deleted_at IS NULL
+                     GROUP BY i.id
+                     ORDER BY i.id",
+                    |row| {
+                        let scheduled: Option<DateTime<Utc>> = row.get(3)?;
+                        let completed: Option<DateTime<Utc>> = row.get(4)?;
+                        let overall_condition: Option<String> = row.get(7)?;
+                        Ok((scheduled, completed, InspectionFact {
+                            inspection_key: row.get(0)?,
+                            asset_key: row.get(1)?,
+                            inspector_id: row.get(2)?,
+                            scheduled_date_key: scheduled.map(|d| warehouse::date_key(d.date_naive())),
+                            completed_date_key: completed.map(|d| warehouse::date_key(d.date_naive())),
+                            inspection_type: row.get(5)?,
+                            status: row.get(6)?,
+                            condition_score: overall_condition.as_deref()
+                                .and_then(|c| c.parse::<Condition>().ok())
+                                .map(|c| c.score()),
+                            overall_condition,
+                            item_count: row.get(8)?,
+                            compliant_item_count: row.get(9)?,
+                            finding_count: row.get(10)?,
+                        }))
+                    },
+                    &mut |(scheduled, completed, fact): (Option<DateTime<Utc>>, Option<DateTime<Utc>>, InspectionFact)| {
+                        for date in [scheduled, completed].into_iter().flatten() {
+                            dates.include(date.date_naive());
+                        }
+                        visit(fact)
+                    },
+                )
+            }, progress)?;
+            tables.push(WarehouseTable { name: "fact_inspections".to_string(), file_name: file("fact_inspections"), rows });
+
+            let rows = export_to_file(&directory.join(file("fact_findings")), format, |visit| {
+                stream_rows(
+                    &conn,
+                    "SELECT ii.id, i.id, i.asset_id, COALESCE(i.actual_date, ii.created_at), ii.item_category,
+                            ii.item_name, ii.severity, ii.is_compliant, ii.condition,
+                            TRIM(COALESCE(ii.corrective_action, '')) != ''
+                                OR EXISTS(SELECT 1 FROM corrective_actions ca WHERE ca.inspection_item_id = ii.id)
+                     FROM inspection_items ii
+                     JOIN inspections i ON i.id = ii.inspection_id
+                     WHERE i.deleted_at IS NULL
+                       AND (TRIM(COALESCE(ii.finding, '')) != '' OR ii.severity IS NOT NULL OR ii.is_compliant = 0)
+                     ORDER BY ii.id",
+                    |row| {
+                        let date: DateTime<Utc> = row.get(3)?;
+                        let severity: Option<String> = row.get(6)?;
+                        Ok((date, FindingFact {
+                            finding_key: row.get(0)?,
+                            inspection_key: row.get(1)?,
+                            asset_key: row.get(2)?,
+                            date_key: warehouse::date_key(date.date_naive()),
+                            item_category: row.get(4)?,
+                            item_name: row.get(5)?,
+                            severity_rank: severity.as_deref()
+                                .and_then(|s| s.parse::<Severity>().ok())
+                                .map(|s| s.rank()),
+                            severity,
+                            is_compliant: row.get(7)?,
+                            condition: row.get(8)?,
+                            has_corrective_action: row.get(9)?,
+                        }))
+                    },
+                    &mut |(date, fact): (DateTime<Utc>, FindingFact)| {
+                        dates.include(date.date_naive());
+                        visit(fact)
+                    },
+                )
+            }, progress)?;
+            tables.push(WarehouseTable { name: "fact_findings".to_string(), file_name: file("fact_findings"), rows });
+
+            // Deleted assets stay in the dimension so older facts still resolve
+            let rows = export_to_file(&directory.join(file("dim_assets")), format, |visit| {
+                stream_rows(
+                    &conn,
+                    "SELECT a.id, a.asset_number, a.asset_name, a.asset_type, a.manufacturer, a.model, a.capacity,
+                            a.capacity_unit, a.status, a.criticality, a.installation_date, a.location_id,
+                            COALESCE(l.name, ''), a.deleted_at IS NOT NULL
+                     FROM assets a
+                     LEFT JOIN locations l ON l.id = a.location_id
+                     ORDER BY a.id",
+                    |row| Ok(AssetDimension {
+                        asset_key: row.get(0)?,
+                        asset_number: row.get(1)?,
+                        asset_name: row.get(2)?,
+                        asset_type: row.get(3)?,
+                        manufacturer: row.get(4)?,
+                        model: row.get(5)?,
+                        capacity: row.get(6)?,
+                        capacity_unit: row.get(7)?,
+                        status: row.get(8)?,
+                        criticality: row.get(9)?,
+                        installation_date: row.get(10)?,
+                        location_id: row.get(11)?,
+                        location_name: row.get(12)?,
+                        is_deleted: row.get(13)?,
+                    }),
+                    visit,
+                )
+            }, progress)?;
+            tables.push(WarehouseTable { name: "dim_assets".to_string(), file_name: file("dim_assets"), rows });
+
+            let rows = export_to_file(&directory.join(file("dim_dates")), format, |visit| {
+                let mut count = 0;
+                for date in dates.days() {
+                    visit(DateDimension::new(date))?;
+                    count += 1;
+                }
+                Ok(count)
+            }, progress)?;
+            tables.push(WarehouseTable { name: "dim_dates".to_string(), file_name: file("dim_dates"), rows });
 
-    fn row_to_user(&self, row: &Row) -> rusqlite::Result<User> {
-        Ok(User {
-            id: row.get(0)?,
-            username: row.get(1)?,
-            email: row.get(2)?,
-            password_hash: row.get(3)?,
-            role: row.get::<_, String>(4)?.parse().unwrap_or(UserRole::Inspector),
-            first_name: row.get(5)?,
-            last_name: row.get(6)?,
-            phone: row.get(7)?,
-            created_at: row.get(8)?,
-            updated_at: row.get(9)?,
-            is_active: row.get(10)?,
-        })
+            Ok(tables)
+        })();
+
+        let tables = match result {
+            Ok(tables) => tables,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&directory);
+                return Err(e);
+            }
+        };
+        let manifest = WarehouseManifest {
+            generated_at,
+            format,
+            extract_directory: directory.display().to_string(),
+            tables,
+        };
+        warehouse::write_manifest(root, &manifest)?;
+        let pruned = warehouse::prune_extracts(root, WAREHOUSE_EXTRACTS_KEPT)?;
+        debug!("Data warehouse extract written to {} ({} old extracts removed)", manifest.extract_directory, pruned);
+        Ok(manifest)
     }
 }
 
@@ -2414,6 +15240,59 @@ impl UserService {
 // Media Service
 // =============================================================================
 
+/// Warnings for a photo about to be attached: the same file already on
+/// another inspection of the asset, or a capture date before the inspection
+fn photo_warnings(conn: &Connection, media: &MediaFile, fingerprint: &PhotoFingerprint) -> AppResult<Vec<PhotoWarning>> {
+    let mut warnings = Vec::new();
+
+    let asset_id: Option<i64> = match (media.inspection_id, media.component_id) {
+        (Some(inspection_id), _) => conn.query_row(
+            "SELECT asset_id FROM inspections WHERE id = ?1", params![inspection_id], |row| row.get(0),
+        ).optional()?,
+        (None, Some(component_id)) => conn.query_row(
+            "SELECT asset_id FROM components WHERE id = ?1", params![component_id], |row| row.get(0),
+        ).optional()?,
+        (None, None) => None,
+    };
+    if let Some(asset_id) = asset_id {
+        let mut stmt = conn.prepare(
+            "SELECT m.id, i.id, COALESCE(i.actual_date, i.scheduled_date, i.created_at)
+             FROM media_files m JOIN inspections i ON i.id = m.inspection_id
+             WHERE m.content_hash = ?1 AND i.asset_id = ?2 AND (?3 IS NULL OR i.id != ?3)
+             ORDER BY 3 DESC"
+        )?;
+        let duplicates = stmt.query_map(params![fingerprint.content_hash, asset_id, media.inspection_id], |row| {
+            Ok(PhotoWarning::DuplicateOfEarlierInspection {
+                media_id: row.get(0)?,
+                inspection_id: row.get(1)?,
+                inspection_date: row.get(2)?,
+            })
+        })?;
+        for duplicate in duplicates {
+            warnings.push(duplicate?);
+        }
+    }
+
+    if let (Some(inspection_id), Some(captured_at)) = (media.inspection_id, fingerprint.captured_at) {
+        let inspected: Option<(DateTime<Utc>, Option<String>)> = conn.query_row(
+            "SELECT COALESCE(actual_date, scheduled_date, created_at), time_zone FROM inspections WHERE id = ?1",
+            params![inspection_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).optional()?;
+        if let Some((inspected_at, time_zone)) = inspected {
+            // The camera clock is local, so compare with the date at the site
+            let inspection_date = inspected_at
+                .with_timezone(&scheduling::time_zone_or_default(time_zone.as_deref()))
+                .date_naive();
+            if captured_at.date() < inspection_date {
+                warnings.push(PhotoWarning::TakenBeforeInspection { captured_at, inspection_date });
+            }
+        }
+    }
+
+    Ok(warnings)
+}
+
 pub struct MediaService {
     database: Arc<Database>,
 }
@@ -2423,27 +15302,50 @@ impl MediaService {
         Self { database }
     }
 
-    pub fn create_media_file(&self, media: MediaFile) -> AppResult<MediaFile> {
-        info!("Creating new media file: {}", media.file_name);
+    /// Store a media record with the fingerprint of its file. Photos that
+    /// look like the wrong one are stored anyway, with warnings.
+    pub fn create_media_file(
+        &self,
+        context: &RequestContext,
+        media: MediaFile,
+        fingerprint: &PhotoFingerprint,
+    ) -> AppResult<UploadedMedia> {
+        info!("[{}] Creating new media file: {}", context.request_id, media.file_name);
         media.validate()?;
 
-        self.database.with_transaction(|conn| {
+        let (id, warnings) = self.database.with_transaction(|conn| {
+            let warnings = match media.file_type {
+                MediaType::Image => photo_warnings(conn, &media, fingerprint)?,
+                _ => Vec::new(),
+            };
             let id = conn.query_row(
                 "INSERT INTO media_files (inspection_id, component_id, file_name, file_path,
-                 file_type, mime_type, file_size, description, ai_analysis_metadata)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                 file_type, mime_type, file_size, description, ai_analysis_metadata, content_hash, captured_at,
+                 uploaded_by)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
                  RETURNING id",
                 params![
                     media.inspection_id, media.component_id, media.file_name, media.file_path,
                     media.file_type.to_string(), media.mime_type, media.file_size,
                     media.description,
-                    media.ai_analysis_metadata.as_ref().map(|m| m.to_string())
+                    media.ai_analysis_metadata.as_ref().map(|m| m.to_string()),
+                    fingerprint.content_hash, fingerprint.captured_at,
+                    context.current_user().map(|u| u.user_id).ok(),
                 ],
                 |row| row.get::<_, i64>(0),
             )?;
 
-            debug!("Media file created with ID: {}", id);
-            self.get_media_file_by_id(id)
+            Ok((id, warnings))
+        })?;
+
+        if !warnings.is_empty() {
+            warn!("[{}] Media file {} may be the wrong photo: {:?}", context.request_id, id, warnings);
+        }
+        debug!("Media file created with ID: {}", id);
+        Ok(UploadedMedia {
+            media: self.get_media_file_by_id(id)?,
+            needs_review: !warnings.is_empty(),
+            warnings,
         })
     }
 
@@ -2453,7 +15355,7 @@ impl MediaService {
         
         let media_file = conn.query_row(
             "SELECT id, inspection_id, component_id, file_name, file_path, file_type,
-             mime_type, file_size, description, ai_analysis_metadata, created_at
+             mime_type, file_size, description, ai_analysis_metadata, created_at, uploaded_by
              FROM media_files WHERE id = ?1",
             params![id],
             |row| self.row_to_media_file(row),
@@ -2467,13 +15369,74 @@ impl MediaService {
         Ok(media_file)
     }
 
+    /// Record a media file with these links takes its access from: the
+    /// inspection it belongs to, or else the asset owning its component, or
+    /// else whoever uploaded it
+    pub fn linked_record(
+        &self,
+        inspection_id: Option<i64>,
+        component_id: Option<i64>,
+        uploaded_by: Option<i64>,
+    ) -> AppResult<LinkedRecord> {
+        let conn = self.database.get_read_connection()?;
+
+        if let Some(id) = inspection_id {
+            let inspector_id = conn.query_row(
+                "SELECT inspector_id FROM inspections WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+                entity: "Inspection".to_string(),
+                field: "id".to_string(),
+                value: id.to_string(),
+            })?;
+            let assignee_ids = conn.prepare(
+                "SELECT assignee_id FROM work_orders WHERE inspection_id = ?1 AND assignee_id IS NOT NULL
+                 UNION
+                 SELECT assignee_id FROM corrective_actions WHERE inspection_id = ?1 AND assignee_id IS NOT NULL",
+            )?
+            .query_map(params![id], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<i64>>>()?;
+            return Ok(LinkedRecord::Inspection { id, inspector_id, assignee_ids });
+        }
+
+        if let Some(component_id) = component_id {
+            let asset_id = conn.query_row(
+                "SELECT asset_id FROM components WHERE id = ?1",
+                params![component_id],
+                |row| row.get(0),
+            ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+                entity: "Component".to_string(),
+                field: "id".to_string(),
+                value: component_id.to_string(),
+            })?;
+            return Ok(LinkedRecord::Asset { id: asset_id });
+        }
+
+        Ok(LinkedRecord::Unlinked { uploaded_by })
+    }
+
+    /// Require `action` on media with these links, through the record they
+    /// point at
+    pub fn authorize_media(
+        &self,
+        context: &RequestContext,
+        inspection_id: Option<i64>,
+        component_id: Option<i64>,
+        uploaded_by: Option<i64>,
+        action: RecordAction,
+    ) -> AppResult<()> {
+        let record = self.linked_record(inspection_id, component_id, uploaded_by)?;
+        context.require_record_access(&record, action)
+    }
+
     pub fn get_media_files_by_inspection(&self, inspection_id: i64) -> AppResult<Vec<MediaFile>> {
         debug!("Fetching media files for inspection: {}", inspection_id);
         let conn = self.database.get_connection()?;
 
         let mut stmt = conn.prepare(
             "SELECT id, inspection_id, component_id, file_name, file_path, file_type,
-             mime_type, file_size, description, ai_analysis_metadata, created_at
+             mime_type, file_size, description, ai_analysis_metadata, created_at, uploaded_by
              FROM media_files WHERE inspection_id = ?1 ORDER BY created_at DESC"
         )?;
 
@@ -2495,7 +15458,7 @@ impl MediaService {
 
         let mut stmt = conn.prepare(
             "SELECT id, inspection_id, component_id, file_name, file_path, file_type,
-             mime_type, file_size, description, ai_analysis_metadata, created_at
+             mime_type, file_size, description, ai_analysis_metadata, created_at, uploaded_by
              FROM media_files WHERE component_id = ?1 ORDER BY created_at DESC"
         )?;
 
@@ -2511,8 +15474,8 @@ impl MediaService {
         Ok(media_files)
     }
 
-    pub fn update_media_file(&self, id: i64, updates: MediaFileUpdateData) -> AppResult<MediaFile> {
-        info!("Updating media file: {}", id);
+    pub fn update_media_file(&self, context: &RequestContext, id: i64, updates: MediaFileUpdateData) -> AppResult<MediaFile> {
+        info!("[{}] Updating media file: {}", context.request_id, id);
         
         self.database.with_transaction(|conn| {
             // Simple implementation - update individual fields
@@ -2531,10 +15494,11 @@ impl MediaService {
         })
     }
 
-    pub fn delete_media_file(&self, id: i64) -> AppResult<()> {
-        info!("Deleting media file: {}", id);
+    pub fn delete_media_file(&self, context: &RequestContext, id: i64) -> AppResult<()> {
+        info!("[{}] Deleting media file: {}", context.request_id, id);
         
         self.database.with_transaction(|conn| {
+            ensure_media_not_on_legal_hold(conn, id)?;
             let rows_affected = conn.execute("DELETE FROM media_files WHERE id = ?1", params![id])?;
             
             if rows_affected == 0 {
@@ -2550,8 +15514,8 @@ impl MediaService {
         })
     }
 
-    pub fn queue_for_ai_analysis(&self, media_file_id: i64) -> AppResult<()> {
-        info!("Queueing media file {} for AI analysis", media_file_id);
+    pub fn queue_for_ai_analysis(&self, context: &RequestContext, media_file_id: i64) -> AppResult<()> {
+        info!("[{}] Queueing media file {} for AI analysis", context.request_id, media_file_id);
         
         // This is a stub implementation - in a real system, this would:
         // 1. Add the media file to an AI processing queue
@@ -2559,19 +15523,53 @@ impl MediaService {
         // 3. Trigger the AI processing pipeline
         
         self.database.with_transaction(|conn| {
-            // Create a pending AI analysis record
+            // Create a pending AI analysis record, linked to this request's trace
             conn.execute(
                 "INSERT INTO ai_model_results (media_file_id, model_name, model_version,
-                 predictions, confidence_score, status)
-                 VALUES (?1, 'vision_model_v1', '1.0', '{}', 0.0, 'Pending')",
-                params![media_file_id]
+                 predictions, confidence_score, status, trace_id)
+                 VALUES (?1, 'vision_model_v1', '1.0', '{}', 0.0, 'Pending', ?2)",
+                params![media_file_id, context.request_id]
             )?;
             
-            debug!("Media file {} queued for AI analysis", media_file_id);
+            info!(target: AI_JOB_TARGET, "[{}] AI job {} queued for media file {}",
+                  context.request_id, conn.last_insert_rowid(), media_file_id);
             Ok(())
         })
     }
 
+    /// AI jobs queued by the request with `trace_id`
+    pub fn get_ai_jobs_for_trace(&self, trace_id: &str) -> AppResult<Vec<AiModelResult>> {
+        let conn = self.database.get_connection()?;
+
+        let result = (|| -> AppResult<Vec<AiModelResult>> {
+            let mut stmt = conn.prepare(
+                "SELECT id, inspection_id, media_file_id, model_name, model_version, predictions,
+                        confidence_score, status, processed_at, trace_id
+                 FROM ai_model_results WHERE trace_id = ?1 ORDER BY id"
+            )?;
+            let jobs = stmt.query_map(params![trace_id], |row| {
+                Ok(AiModelResult {
+                    id: row.get(0)?,
+                    inspection_id: row.get(1)?,
+                    media_file_id: row.get(2)?,
+                    model_name: row.get(3)?,
+                    model_version: row.get(4)?,
+                    predictions: row.get::<_, Option<String>>(5)?
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or(JsonValue::Null),
+                    confidence_score: row.get(6)?,
+                    status: row.get::<_, String>(7)?.parse().unwrap_or(AiAnalysisStatus::Pending),
+                    processed_at: row.get(8)?,
+                    trace_id: row.get(9)?,
+                })
+            })?.collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(jobs)
+        })();
+
+        self.database.return_connection(conn);
+        result
+    }
+
     fn row_to_media_file(&self, row: &Row) -> rusqlite::Result<MediaFile> {
         Ok(MediaFile {
             id: row.get(0)?,
@@ -2586,6 +15584,7 @@ impl MediaService {
             ai_analysis_metadata: row.get::<_, Option<String>>(9)?
                 .and_then(|s| serde_json::from_str(&s).ok()),
             created_at: row.get(10)?,
+            uploaded_by: row.get(11)?,
         })
     }
 }
@@ -2594,6 +15593,93 @@ impl MediaService {
 // Report Service
 // =============================================================================
 
+const REPORT_SHARE_LINK_COLUMNS: &str =
+    "id, report_id, recipient, expires_at, max_views, view_count, last_viewed_at,
+     created_by, created_at, revoked_at";
+
+fn row_to_report_share_link(row: &Row) -> rusqlite::Result<ReportShareLink> {
+    Ok(ReportShareLink {
+        id: row.get(0)?,
+        report_id: row.get(1)?,
+        recipient: row.get(2)?,
+        expires_at: row.get(3)?,
+        max_views: row.get(4)?,
+        view_count: row.get(5)?,
+        last_viewed_at: row.get(6)?,
+        created_by: row.get(7)?,
+        created_at: row.get(8)?,
+        revoked_at: row.get(9)?,
+    })
+}
+
+fn report_share_link_by_id(conn: &Connection, id: i64) -> AppResult<ReportShareLink> {
+    conn.query_row(
+        &format!("SELECT {} FROM report_share_links WHERE id = ?1", REPORT_SHARE_LINK_COLUMNS),
+        params![id],
+        row_to_report_share_link,
+    ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+        entity: "ReportShareLink".to_string(),
+        field: "id".to_string(),
+        value: id.to_string(),
+    })
+}
+
+/// SHA-256 of a share token; tokens themselves are never stored
+fn share_token_hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+const REPORT_TEMPLATE_COLUMNS: &str =
+    "t.id, t.name, t.report_type, t.description, t.template, a.template_id IS NOT NULL,
+     t.created_by, t.created_at, t.updated_at";
+
+const REPORT_TEMPLATE_FROM: &str =
+    "report_templates t LEFT JOIN report_template_assignments a ON a.template_id = t.id";
+
+fn row_to_report_template(row: &Row) -> rusqlite::Result<CustomReportTemplate> {
+    Ok(CustomReportTemplate {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        report_type: row.get::<_, String>(2)?.parse().unwrap_or(CustomReportType::Inspection),
+        description: row.get(3)?,
+        template: row.get(4)?,
+        is_assigned: row.get(5)?,
+        created_by: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+fn read_report_template(conn: &Connection, id: i64) -> AppResult<CustomReportTemplate> {
+    conn.query_row(
+        &format!("SELECT {} FROM {} WHERE t.id = ?1", REPORT_TEMPLATE_COLUMNS, REPORT_TEMPLATE_FROM),
+        params![id],
+        row_to_report_template,
+    ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+        entity: "ReportTemplate".to_string(),
+        field: "id".to_string(),
+        value: id.to_string(),
+    })
+}
+
+fn ensure_unique_template_name(conn: &Connection, name: &str, id: Option<i64>) -> AppResult<()> {
+    let taken: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM report_templates WHERE name = ?1 COLLATE NOCASE AND id IS NOT ?2)",
+        params![name, id],
+        |row| row.get(0),
+    )?;
+    if taken {
+        return Err(AppError::DuplicateRecord {
+            entity: "ReportTemplate".to_string(),
+            field: "name".to_string(),
+            value: name.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Report queries run on the database's read-only pool so long reports never
+/// hold up inspection writes
 pub struct ReportService {
     database: Arc<Database>,
 }
@@ -2605,7 +15691,7 @@ impl ReportService {
 
     pub fn generate_asset_summary_report(&self, asset_id: i64) -> AppResult<AssetSummaryReport> {
         info!("Generating asset summary report for asset: {}", asset_id);
-        let conn = self.database.get_connection()?;
+        let conn = self.database.get_read_connection()?;
 
         // Get asset basic info
         let (asset_name, _asset_status): (String, String) = conn.query_row(
@@ -2679,8 +15765,6 @@ impl ReportService {
             .map(|date| date + chrono::Duration::days(365)) // Assume yearly inspections
             .or_else(|| Some(Utc::now() + chrono::Duration::days(30)));
 
-        self.database.return_connection(conn);
-
         Ok(AssetSummaryReport {
             asset_id,
             asset_name,
@@ -2697,7 +15781,7 @@ impl ReportService {
 
     pub fn generate_inspection_completion_report(&self, start_date: DateTime<Utc>, end_date: DateTime<Utc>) -> AppResult<InspectionCompletionReport> {
         info!("Generating inspection completion report from {} to {}", start_date, end_date);
-        let conn = self.database.get_connection()?;
+        let conn = self.database.get_read_connection()?;
 
         // Get total scheduled and completed inspections
         let (total_scheduled, total_completed): (i64, i64) = conn.query_row(
@@ -2772,8 +15856,6 @@ impl ReportService {
             |row| row.get(0),
         ).unwrap_or(0.0);
 
-        self.database.return_connection(conn);
-
         Ok(InspectionCompletionReport {
             period_start: start_date,
             period_end: end_date,
@@ -2788,7 +15870,7 @@ impl ReportService {
 
     pub fn generate_compliance_status_report(&self, location_id: Option<i64>) -> AppResult<ComplianceStatusReport> {
         info!("Generating compliance status report for location: {:?}", location_id);
-        let conn = self.database.get_connection()?;
+        let conn = self.database.get_read_connection()?;
 
         // Get total assets
         let total_assets: i64 = if let Some(loc_id) = location_id {
@@ -2874,7 +15956,7 @@ impl ReportService {
                 "SELECT COUNT(DISTINCT a.id)
                  FROM assets a
                  LEFT JOIN inspections i ON a.id = i.asset_id
-                 WHERE (i.scheduled_date < datetime('now') AND i.status NOT IN ('Completed', 'Cancelled'))
+                 WHERE (COALESCE(i.overdue_at, i.scheduled_date) < datetime('now') AND i.status NOT IN ('Completed', 'Cancelled'))
                     OR (i.id IS NULL)  -- Assets with no inspections
                  AND a.location_id = ?1",
                 params![loc_id],
@@ -2885,7 +15967,7 @@ impl ReportService {
                 "SELECT COUNT(DISTINCT a.id)
                  FROM assets a
                  LEFT JOIN inspections i ON a.id = i.asset_id
-                 WHERE (i.scheduled_date < datetime('now') AND i.status NOT IN ('Completed', 'Cancelled'))
+                 WHERE (COALESCE(i.overdue_at, i.scheduled_date) < datetime('now') AND i.status NOT IN ('Completed', 'Cancelled'))
                     OR (i.id IS NULL)",
                 [],
                 |row| row.get(0),
@@ -2916,9 +15998,22 @@ impl ReportService {
             )?
         };
 
+        // Get insurance and registration renewals coming due
+        let expiring_records: i64 = conn.query_row(
+            "SELECT COUNT(*)
+             FROM asset_records r
+             JOIN assets a ON r.asset_id = a.id
+             WHERE a.deleted_at IS NULL
+               AND a.status != 'Decommissioned'
+               AND r.renewal_date <= ?1
+               AND (?2 IS NULL OR a.location_id = ?2)",
+            params![Utc::now().date_naive() + chrono::Duration::days(DEFAULT_RENEWAL_WARNING_DAYS), location_id],
+            |row| row.get(0),
+        )?;
+
         // Get compliance by standard
         let mut by_standard = HashMap::new();
-        let stmt = if let Some(loc_id) = location_id {
+        if let Some(loc_id) = location_id {
             let mut stmt = conn.prepare(
                 "SELECT
                     i.compliance_standard,
@@ -2960,7 +16055,6 @@ impl ReportService {
                     compliance_rate,
                 });
             }
-            stmt
         } else {
             let mut stmt = conn.prepare(
                 "SELECT
@@ -3003,11 +16097,7 @@ impl ReportService {
                     compliance_rate,
                 });
             }
-            stmt
-        };
-
-        drop(stmt);
-        self.database.return_connection(conn);
+        }
 
         Ok(ComplianceStatusReport {
             location_id,
@@ -3017,13 +16107,333 @@ impl ReportService {
             overdue_inspections,
             compliance_percentage,
             critical_findings,
+            expiring_records,
             by_standard,
         })
     }
 
+    pub fn generate_asset_summary_csv(&self, asset_id: i64, options: &CsvOptions) -> AppResult<Vec<u8>> {
+        let report = self.generate_asset_summary_report(asset_id)?;
+        render_csv(&report.to_table(Utc::now()), options)
+    }
+
+    pub fn generate_inspection_completion_csv(
+        &self,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+        options: &CsvOptions,
+    ) -> AppResult<Vec<u8>> {
+        let report = self.generate_inspection_completion_report(start_date, end_date)?;
+        render_csv(&report.to_table(Utc::now()), options)
+    }
+
+    pub fn generate_compliance_status_csv(&self, location_id: Option<i64>, options: &CsvOptions) -> AppResult<Vec<u8>> {
+        let report = self.generate_compliance_status_report(location_id)?;
+        render_csv(&report.to_table(Utc::now()), options)
+    }
+
+    /// Charts for an asset's compliance report: the monthly compliance score
+    /// of its completed inspections, its findings by severity, and the share
+    /// of its scheduled inspections completed each month, all between
+    /// `start_date` and `end_date`
+    pub fn generate_asset_charts(
+        &self,
+        asset_id: i64,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+        locale: Locale,
+    ) -> AppResult<Vec<ChartSpec>> {
+        debug!("Generating report charts for asset {} from {} to {}", asset_id, start_date, end_date);
+        let conn = self.database.get_read_connection()?;
+
+        let percentage_chart = |key: &str, kind: ChartKind, points: Vec<ChartPoint>| ChartSpec {
+            title: translate(locale, &format!("report.{}", key)),
+            kind,
+            unit: Some("%".to_string()),
+            max_value: Some(100.0),
+            points,
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT substr(i.actual_date, 1, 7) AS month, AVG(scores.score)
+             FROM inspections i
+             JOIN (
+                 SELECT inspection_id,
+                        COUNT(CASE WHEN is_compliant = 1 THEN 1 END) * 100.0 / COUNT(*) AS score
+                 FROM inspection_items
+                 GROUP BY inspection_id
+             ) scores ON scores.inspection_id = i.id
+             WHERE i.asset_id = ?1 AND i.status = 'Completed' AND i.actual_date BETWEEN ?2 AND ?3
+             GROUP BY month
+             ORDER BY month"
+        )?;
+        let score_trend = stmt
+            .query_map(params![asset_id, start_date, end_date], |row| {
+                Ok(ChartPoint { label: row.get(0)?, value: row.get(1)? })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        drop(stmt);
+
+        let mut stmt = conn.prepare(
+            "SELECT ii.severity, COUNT(*)
+             FROM inspection_items ii
+             JOIN inspections i ON ii.inspection_id = i.id
+             WHERE i.asset_id = ?1 AND i.status = 'Completed' AND i.actual_date BETWEEN ?2 AND ?3
+               AND ii.severity IS NOT NULL
+             GROUP BY ii.severity"
+        )?;
+        let severity_counts = stmt
+            .query_map(params![asset_id, start_date, end_date], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+        drop(stmt);
+        let severity_distribution = [Severity::Low, Severity::Medium, Severity::High, Severity::Critical]
+            .into_iter()
+            .map(|severity| ChartPoint {
+                value: severity_counts.get(&severity.to_string()).copied().unwrap_or(0) as f64,
+                label: severity.localize(locale),
+            })
+            .collect();
+
+        let mut stmt = conn.prepare(
+            "SELECT substr(scheduled_date, 1, 7) AS month,
+                    COUNT(CASE WHEN status = 'Completed' THEN 1 END) * 100.0 / COUNT(*)
+             FROM inspections
+             WHERE asset_id = ?1 AND status != 'Cancelled' AND scheduled_date BETWEEN ?2 AND ?3
+             GROUP BY month
+             ORDER BY month"
+        )?;
+        let completion_rate = stmt
+            .query_map(params![asset_id, start_date, end_date], |row| {
+                Ok(ChartPoint { label: row.get(0)?, value: row.get(1)? })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        drop(stmt);
+
+        Ok(vec![
+            percentage_chart("compliance_score_trend", ChartKind::Line, score_trend),
+            ChartSpec {
+                title: translate(locale, "report.finding_severity_distribution"),
+                kind: ChartKind::Bar,
+                unit: None,
+                max_value: None,
+                points: severity_distribution,
+            },
+            percentage_chart("completion_rate_trend", ChartKind::Bar, completion_rate),
+        ])
+    }
+
+    /// Custom report templates, of one report type when given, by name
+    pub fn get_report_templates(&self, report_type: Option<CustomReportType>) -> AppResult<Vec<CustomReportTemplate>> {
+        let conn = self.database.get_connection()?;
+        let result = (|| -> AppResult<Vec<CustomReportTemplate>> {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM {} WHERE ?1 IS NULL OR t.report_type = ?1 ORDER BY t.name COLLATE NOCASE",
+                REPORT_TEMPLATE_COLUMNS, REPORT_TEMPLATE_FROM
+            ))?;
+            let templates = stmt
+                .query_map(params![report_type.map(|t| t.to_string())], row_to_report_template)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(templates)
+        })();
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Save a new template; it is not used until assigned
+    pub fn create_report_template(&self, context: &RequestContext, input: CustomReportTemplateInput) -> AppResult<CustomReportTemplate> {
+        info!("[{}] Creating {} report template '{}'", context.request_id, input.report_type, input.name.trim());
+        input.validate()?;
+        template::check_template(&input.template)?;
+        let user_id = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            ensure_unique_template_name(conn, input.name.trim(), None)?;
+            let now = Utc::now();
+            let id = conn.query_row(
+                "INSERT INTO report_templates (name, report_type, description, template, created_by, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+                 RETURNING id",
+                params![input.name.trim(), input.report_type.to_string(), input.description, input.template, user_id, now],
+                |row| row.get::<_, i64>(0),
+            )?;
+            read_report_template(conn, id)
+        })
+    }
+
+    /// Replace a template's name, description and markup. Its report type
+    /// cannot change while it is assigned.
+    pub fn update_report_template(&self, context: &RequestContext, id: i64, input: CustomReportTemplateInput) -> AppResult<CustomReportTemplate> {
+        info!("[{}] Updating report template {}", context.request_id, id);
+        input.validate()?;
+        template::check_template(&input.template)?;
+
+        self.database.with_transaction(|conn| {
+            let existing = read_report_template(conn, id)?;
+            if existing.is_assigned && existing.report_type != input.report_type {
+                return Err(AppError::validation(
+                    "report_type",
+                    format!("Template is assigned to {} reports; unassign it before changing its type", existing.report_type),
+                ));
+            }
+            ensure_unique_template_name(conn, input.name.trim(), Some(id))?;
+            conn.execute(
+                "UPDATE report_templates SET name = ?2, report_type = ?3, description = ?4, template = ?5, updated_at = ?6
+                 WHERE id = ?1",
+                params![id, input.name.trim(), input.report_type.to_string(), input.description, input.template, Utc::now()],
+            )?;
+            read_report_template(conn, id)
+        })
+    }
+
+    /// Render HTML reports of `report_type` with a template, or with the
+    /// built-in layout again when `template_id` is `None`
+    pub fn assign_report_template(
+        &self,
+        context: &RequestContext,
+        report_type: CustomReportType,
+        template_id: Option<i64>,
+    ) -> AppResult<Option<CustomReportTemplate>> {
+        info!("[{}] Assigning template {:?} to {} reports", context.request_id, template_id, report_type);
+        let user_id = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            let Some(template_id) = template_id else {
+                conn.execute("DELETE FROM report_template_assignments WHERE report_type = ?1", params![report_type.to_string()])?;
+                return Ok(None);
+            };
+            let template = read_report_template(conn, template_id)?;
+            if template.report_type != report_type {
+                return Err(AppError::validation(
+                    "template_id",
+                    format!("Template '{}' is for {} reports, not {}", template.name, template.report_type, report_type),
+                ));
+            }
+            conn.execute(
+                "INSERT INTO report_template_assignments (report_type, template_id, assigned_by, assigned_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(report_type) DO UPDATE SET
+                     template_id = excluded.template_id, assigned_by = excluded.assigned_by, assigned_at = excluded.assigned_at",
+                params![report_type.to_string(), template_id, user_id, Utc::now()],
+            )?;
+            read_report_template(conn, template_id).map(Some)
+        })
+    }
+
+    /// The template HTML reports of `report_type` are rendered with, if any
+    pub fn assigned_report_template(&self, report_type: CustomReportType) -> AppResult<Option<CustomReportTemplate>> {
+        let conn = self.database.get_connection()?;
+        let result = (|| -> AppResult<Option<CustomReportTemplate>> {
+            let id: Option<i64> = conn.query_row(
+                "SELECT template_id FROM report_template_assignments WHERE report_type = ?1",
+                params![report_type.to_string()],
+                |row| row.get(0),
+            ).optional()?;
+            id.map(|id| read_report_template(&conn, id)).transpose()
+        })();
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Render `template` with a report's data, for saved reports and for
+    /// previewing a template while it is edited
+    pub fn render_report_template(&self, template: &str, data: &JsonValue) -> AppResult<String> {
+        template::render_template(template, data)
+    }
+
+    /// The report last generated for `key`, if it has not expired, was built
+    /// from the same data and its file is still there
+    pub fn cached_report(&self, key: &ReportKey, now: DateTime<Utc>) -> AppResult<Option<ReportArtifact>> {
+        let conn = self.database.get_read_connection()?;
+        let artifact = conn.query_row(
+            &format!("SELECT {} FROM report_artifacts WHERE cache_key = ?1 ORDER BY generated_at DESC LIMIT 1",
+                     REPORT_ARTIFACT_COLUMNS),
+            params![key.cache_key],
+            row_to_report_artifact,
+        ).optional()?;
+
+        Ok(artifact.filter(|artifact| {
+            artifact.input_fingerprint == key.input_fingerprint
+                && !artifact.is_expired(now)
+                && Path::new(&artifact.file_path).is_file()
+        }))
+    }
+
+    /// Record a generated report file. It expires after the report
+    /// retention setting.
+    pub fn record_report(&self, context: &RequestContext, key: &ReportKey, report_id: &str, file_path: &str,
+                         generated_at: DateTime<Utc>) -> AppResult<ReportArtifact> {
+        self.database.with_transaction(|conn| {
+            let settings = read_app_settings(conn)?;
+            let artifact = ReportArtifact {
+                report_id: report_id.to_string(),
+                report_type: key.report_type.clone(),
+                cache_key: key.cache_key.clone(),
+                input_fingerprint: key.input_fingerprint.clone(),
+                file_path: file_path.to_string(),
+                generated_by: context.current_user().map(|u| u.user_id).ok(),
+                generated_at,
+                expires_at: generated_at + chrono::Duration::days(settings.report_retention_days),
+            };
+            conn.execute(
+                "INSERT OR REPLACE INTO report_artifacts (report_id, report_type, cache_key, input_fingerprint,
+                 file_path, generated_by, generated_at, expires_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    artifact.report_id, artifact.report_type, artifact.cache_key, artifact.input_fingerprint,
+                    artifact.file_path, artifact.generated_by, artifact.generated_at, artifact.expires_at
+                ],
+            )?;
+            Ok(artifact)
+        })
+    }
+
+    /// The record of the report written to `file_path`, if it was recorded
+    pub fn get_report_artifact(&self, file_path: &str) -> AppResult<Option<ReportArtifact>> {
+        let conn = self.database.get_read_connection()?;
+        Ok(conn.query_row(
+            &format!("SELECT {} FROM report_artifacts WHERE file_path = ?1", REPORT_ARTIFACT_COLUMNS),
+            params![file_path],
+            row_to_report_artifact,
+        ).optional()?)
+    }
+
+    /// Delete the files and records of reports expired by `now`, except
+    /// those whose report IDs start with one of `held_prefixes`. Returns the
+    /// number of files deleted.
+    pub fn purge_expired_artifacts(&self, now: DateTime<Utc>, held_prefixes: &[String]) -> AppResult<usize> {
+        self.database.with_transaction(|conn| {
+            let expired = conn.prepare(&format!(
+                "SELECT {} FROM report_artifacts WHERE expires_at <= ?1", REPORT_ARTIFACT_COLUMNS
+            ))?
+                .query_map(params![now], row_to_report_artifact)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut purged = 0;
+            for artifact in expired {
+                if held_prefixes.iter().any(|prefix| artifact.report_id.starts_with(prefix.as_str())) {
+                    continue;
+                }
+                match std::fs::remove_file(&artifact.file_path) {
+                    Ok(()) => purged += 1,
+                    // Already gone, by hand or by the age-based purge
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(e.into()),
+                }
+                conn.execute("DELETE FROM report_artifacts WHERE file_path = ?1", params![artifact.file_path])?;
+            }
+
+            if purged > 0 {
+                info!("Deleted {} expired reports", purged);
+            }
+            Ok(purged)
+        })
+    }
+
     pub fn generate_maintenance_history_report(&self, asset_id: i64) -> AppResult<MaintenanceHistoryReport> {
         info!("Generating maintenance history report for asset: {}", asset_id);
-        let conn = self.database.get_connection()?;
+        let conn = self.database.get_read_connection()?;
 
         // Get asset name
         let asset_name: String = conn.query_row(
@@ -3075,8 +16485,6 @@ impl ReportService {
             |row| row.get(0),
         ).unwrap_or(None);
 
-        self.database.return_connection(conn);
-
         Ok(MaintenanceHistoryReport {
             asset_id,
             asset_name,
@@ -3090,12 +16498,253 @@ impl ReportService {
             next_scheduled_maintenance,
         })
     }
+
+    /// Create a read-only link to a generated report. The returned token is
+    /// what the recipient uses to open the report and cannot be recovered
+    /// later.
+    pub fn create_share_link(&self, context: &RequestContext, input: ShareLinkInput) -> AppResult<CreatedShareLink> {
+        info!("[{}] Creating share link for report {}", context.request_id, input.report_id);
+        input.validate()?;
+        let user_id = context.current_user()?.user_id;
+
+        let token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+        let expires_at = Utc::now() + chrono::Duration::hours(input.expires_in_hours.unwrap_or(DEFAULT_SHARE_LINK_HOURS));
+        let recipient = input.recipient.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+
+        let link = self.database.with_transaction(|conn| {
+            let id = conn.query_row(
+                "INSERT INTO report_share_links (report_id, token_hash, recipient, expires_at, max_views, created_by)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 RETURNING id",
+                params![input.report_id, share_token_hash(&token), recipient, expires_at, input.max_views, user_id],
+                |row| row.get::<_, i64>(0),
+            )?;
+            report_share_link_by_id(conn, id)
+        })?;
+
+        debug!("Share link {} created for report {}", link.id, link.report_id);
+        Ok(CreatedShareLink { link, token })
+    }
+
+    /// Share links created for a report, newest first
+    pub fn get_share_links(&self, report_id: &str) -> AppResult<Vec<ReportShareLink>> {
+        let conn = self.database.get_read_connection()?;
+        let links = conn.prepare(&format!(
+            "SELECT {} FROM report_share_links WHERE report_id = ?1 ORDER BY created_at DESC, id DESC",
+            REPORT_SHARE_LINK_COLUMNS
+        ))?
+            .query_map(params![report_id], row_to_report_share_link)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(links)
+    }
+
+    /// Stop a share link from opening its report
+    pub fn revoke_share_link(&self, context: &RequestContext, id: i64) -> AppResult<ReportShareLink> {
+        info!("[{}] Revoking share link {}", context.request_id, id);
+        self.database.with_transaction(|conn| {
+            report_share_link_by_id(conn, id)?;
+            conn.execute(
+                "UPDATE report_share_links SET revoked_at = COALESCE(revoked_at, ?2) WHERE id = ?1",
+                params![id, Utc::now()],
+            )?;
+            report_share_link_by_id(conn, id)
+        })
+    }
+
+    /// Record a view through the link with `token` and return the link.
+    ///
+    /// Unknown, expired, revoked and used-up links are all rejected with the
+    /// same error so a token cannot be probed for its state.
+    pub fn open_share_link(&self, token: &str) -> AppResult<ReportShareLink> {
+        let now = Utc::now();
+        self.database.with_transaction(|conn| {
+            let link = conn.query_row(
+                &format!("SELECT {} FROM report_share_links WHERE token_hash = ?1", REPORT_SHARE_LINK_COLUMNS),
+                params![share_token_hash(token.trim())],
+                row_to_report_share_link,
+            ).optional()?;
+            let link = match link {
+                Some(link) if link.is_active(now) => link,
+                _ => {
+                    return Err(AppError::Token {
+                        operation: "open share link".to_string(),
+                        reason: "The link is invalid, expired or revoked".to_string(),
+                    })
+                }
+            };
+            conn.execute(
+                "UPDATE report_share_links SET view_count = view_count + 1, last_viewed_at = ?2 WHERE id = ?1",
+                params![link.id, now],
+            )?;
+            info!("Report {} opened through share link {}", link.report_id, link.id);
+            report_share_link_by_id(conn, link.id)
+        })
+    }
+}
+
+const REPORT_ARTIFACT_COLUMNS: &str =
+    "report_id, report_type, cache_key, input_fingerprint, file_path, generated_by, generated_at, expires_at";
+
+fn row_to_report_artifact(row: &Row) -> rusqlite::Result<ReportArtifact> {
+    Ok(ReportArtifact {
+        report_id: row.get(0)?,
+        report_type: row.get(1)?,
+        cache_key: row.get(2)?,
+        input_fingerprint: row.get(3)?,
+        file_path: row.get(4)?,
+        generated_by: row.get(5)?,
+        generated_at: row.get(6)?,
+        expires_at: row.get(7)?,
+    })
 }
 
 // =============================================================================
 // Location Service
 // =============================================================================
 
+/// Recursive CTE selecting location `?1` and its descendants as
+/// `subtree(id, depth)`, stopping at depth `?2`
+const LOCATION_SUBTREE_CTE: &str = "WITH RECURSIVE subtree(id, depth) AS (
+    SELECT id, 0 FROM locations WHERE id = ?1
+    UNION ALL
+    SELECT l.id, s.depth + 1 FROM locations l JOIN subtree s ON l.parent_location_id = s.id
+    WHERE s.depth < ?2
+)";
+
+/// Recursive CTE selecting location `?1` and its ancestors as
+/// `ancestors(id, depth)`, stopping at depth `?2`
+const LOCATION_ANCESTORS_CTE: &str = "WITH RECURSIVE ancestors(id, depth) AS (
+    SELECT id, 0 FROM locations WHERE id = ?1
+    UNION ALL
+    SELECT l.parent_location_id, a.depth + 1 FROM locations l JOIN ancestors a ON l.id = a.id
+    WHERE l.parent_location_id IS NOT NULL AND a.depth < ?2
+)";
+
+const LOCATION_CLOSURE_COLUMNS: &str = "id, location_id, name, kind, start_date, end_date, created_by, created_at";
+
+fn row_to_location_closure(row: &Row) -> rusqlite::Result<LocationClosure> {
+    Ok(LocationClosure {
+        id: row.get(0)?,
+        location_id: row.get(1)?,
+        name: row.get(2)?,
+        kind: row.get::<_, String>(3)?.parse().unwrap_or(ClosureKind::Holiday),
+        start_date: row.get(4)?,
+        end_date: row.get(5)?,
+        created_by: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+/// Stored form of a set of working days, e.g. `Mon,Tue,Wed,Thu,Fri`
+fn format_working_days(days: &[Weekday]) -> String {
+    scheduling::ALL_WEEKDAYS.iter()
+        .filter(|day| days.contains(day))
+        .map(|day| day.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn parse_working_days(stored: &str) -> Vec<Weekday> {
+    stored.split(',').filter_map(|day| day.trim().parse().ok()).collect()
+}
+
+/// Working calendar in effect at a location. Working days come from the
+/// nearest location that sets them; closures from the location and all of
+/// its ancestors.
+fn load_location_calendar(conn: &Connection, location_id: i64) -> AppResult<LocationCalendar> {
+    let working_days: Option<(i64, String)> = conn.query_row(
+        &format!(
+            "{}
+             SELECT c.location_id, c.working_days FROM ancestors a
+             JOIN location_calendars c ON c.location_id = a.id
+             ORDER BY a.depth LIMIT 1",
+            LOCATION_ANCESTORS_CTE
+        ),
+        params![location_id, MAX_LOCATION_DEPTH as i64],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).optional()?;
+
+    let mut stmt = conn.prepare(&format!(
+        "{}
+         SELECT {} FROM location_closures WHERE location_id IN (SELECT id FROM ancestors)
+         ORDER BY start_date, id",
+        LOCATION_ANCESTORS_CTE, LOCATION_CLOSURE_COLUMNS
+    ))?;
+    let closures = stmt
+        .query_map(params![location_id, MAX_LOCATION_DEPTH as i64], row_to_location_closure)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (working_days_location_id, working_days) = match working_days {
+        Some((id, days)) => (Some(id), parse_working_days(&days)),
+        None => (None, scheduling::ALL_WEEKDAYS.to_vec()),
+    };
+    Ok(LocationCalendar { location_id, working_days, working_days_location_id, closures })
+}
+
+fn location_working_calendar(conn: &Connection, location_id: i64) -> AppResult<scheduling::WorkingCalendar> {
+    let calendar = load_location_calendar(conn, location_id)?;
+    Ok(scheduling::WorkingCalendar::new(
+        calendar.working_days,
+        calendar.closures.iter().map(|closure| (closure.start_date, closure.end_date)).collect(),
+    ))
+}
+
+/// Working calendar of the site an asset is installed at; every day is a
+/// working day for an asset without a location
+fn asset_working_calendar(conn: &Connection, asset_id: i64) -> AppResult<scheduling::WorkingCalendar> {
+    let location_id: Option<i64> = conn.query_row(
+        "SELECT location_id FROM assets WHERE id = ?1",
+        params![asset_id],
+        |row| row.get(0),
+    ).optional()?.flatten();
+    match location_id {
+        Some(location_id) => location_working_calendar(conn, location_id),
+        None => Ok(scheduling::WorkingCalendar::default()),
+    }
+}
+
+/// Recompute when open inspections in a location's subtree become overdue,
+/// after its working calendar changes. Returns how many were updated.
+fn reanchor_open_inspections(conn: &Connection, location_id: i64) -> AppResult<usize> {
+    let mut stmt = conn.prepare(&format!(
+        "{}
+         SELECT i.id, i.scheduled_date, i.time_zone, a.location_id FROM inspections i
+         JOIN assets a ON i.asset_id = a.id
+         WHERE a.location_id IN (SELECT id FROM subtree)
+           AND i.scheduled_date IS NOT NULL
+           AND i.status NOT IN ('Completed', 'Cancelled')",
+        LOCATION_SUBTREE_CTE
+    ))?;
+    let open_inspections = stmt
+        .query_map(params![location_id, MAX_LOCATION_DEPTH as i64], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, DateTime<Utc>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(stmt);
+
+    let mut calendars: HashMap<i64, scheduling::WorkingCalendar> = HashMap::new();
+    for (inspection_id, scheduled_date, time_zone, asset_location_id) in &open_inspections {
+        let calendar = match calendars.get(asset_location_id) {
+            Some(calendar) => calendar,
+            None => {
+                let calendar = location_working_calendar(conn, *asset_location_id)?;
+                calendars.entry(*asset_location_id).or_insert(calendar)
+            }
+        };
+        let tz = scheduling::time_zone_or_default(time_zone.as_deref());
+        conn.execute(
+            "UPDATE inspections SET overdue_at = ?1 WHERE id = ?2",
+            params![calendar.overdue_at(*scheduled_date, tz), inspection_id],
+        )?;
+    }
+    Ok(open_inspections.len())
+}
+
 pub struct LocationService {
     database: Arc<Database>,
     asset_service: Arc<AssetService>,
@@ -3106,18 +16755,28 @@ impl LocationService {
         Self { database, asset_service }
     }
 
-    pub fn create_location(&self, location: Location) -> AppResult<Location> {
-        info!("Creating new location: {}", location.name);
+    pub fn create_location(&self, context: &RequestContext, location: Location) -> AppResult<Location> {
+        info!("[{}] Creating new location: {}", context.request_id, location.name);
         location.validate()?;
 
         self.database.with_transaction(|conn| {
+            if let Some(parent_id) = location.parent_location_id {
+                if Self::location_depth(conn, parent_id)? + 1 >= MAX_LOCATION_DEPTH {
+                    return Err(AppError::validation(
+                        "parent_location_id",
+                        format!("Locations cannot be nested more than {} levels deep", MAX_LOCATION_DEPTH),
+                    ));
+                }
+            }
+
             let id = conn.query_row(
-                "INSERT INTO locations (name, address, latitude, longitude, description, parent_location_id, created_by, created_at, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, datetime('now'), datetime('now'))
+                "INSERT INTO locations (name, address, latitude, longitude, description, parent_location_id, created_by, time_zone, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, datetime('now'), datetime('now'))
                  RETURNING id",
                 params![
                     location.name, location.address, location.latitude, location.longitude,
-                    location.description, location.parent_location_id, location.created_by
+                    location.description, location.parent_location_id, location.created_by,
+                    location.time_zone
                 ],
                 |row| row.get::<_, i64>(0),
             )?;
@@ -3132,7 +16791,7 @@ impl LocationService {
         let conn = self.database.get_connection()?;
         
         let location = conn.query_row(
-            "SELECT id, name, address, latitude, longitude, description, parent_location_id, created_by, created_at, updated_at
+            "SELECT id, name, address, latitude, longitude, description, parent_location_id, created_by, created_at, updated_at, time_zone
              FROM locations WHERE id = ?1",
             params![id],
             |row| self.row_to_location(row),
@@ -3146,8 +16805,8 @@ impl LocationService {
         Ok(location)
     }
 
-    pub fn update_location(&self, id: i64, updates: LocationUpdateData) -> AppResult<Location> {
-        info!("Updating location: {}", id);
+    pub fn update_location(&self, context: &RequestContext, id: i64, updates: LocationUpdateData) -> AppResult<Location> {
+        info!("[{}] Updating location: {}", context.request_id, id);
         
         self.database.with_transaction(|conn| {
             if let Some(name) = &updates.name {
@@ -3166,7 +16825,35 @@ impl LocationService {
                 conn.execute("UPDATE locations SET description = ?1, updated_at = datetime('now') WHERE id = ?2", params![description, id])?;
             }
             if let Some(parent_location_id) = &updates.parent_location_id {
+                Self::ensure_valid_parent(conn, id, *parent_location_id)?;
                 conn.execute("UPDATE locations SET parent_location_id = ?1, updated_at = datetime('now') WHERE id = ?2", params![parent_location_id, id])?;
+                // The subtree now inherits a different working calendar
+                reanchor_open_inspections(conn, id)?;
+            }
+            if let Some(time_zone) = &updates.time_zone {
+                let tz = scheduling::parse_time_zone(time_zone)?;
+                conn.execute("UPDATE locations SET time_zone = ?1, updated_at = datetime('now') WHERE id = ?2", params![time_zone, id])?;
+                let calendar = location_working_calendar(conn, id)?;
+
+                // Re-anchor open inspections at this site to the new local calendar
+                let mut stmt = conn.prepare(
+                    "SELECT i.id, i.scheduled_date FROM inspections i
+                     JOIN assets a ON i.asset_id = a.id
+                     WHERE a.location_id = ?1 AND i.scheduled_date IS NOT NULL
+                       AND i.status NOT IN ('Completed', 'Cancelled')"
+                )?;
+                let open_inspections = stmt
+                    .query_map(params![id], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, DateTime<Utc>>(1)?)))?
+                    .collect::<Result<Vec<_>, _>>()?;
+                drop(stmt);
+
+                for (inspection_id, scheduled_date) in &open_inspections {
+                    conn.execute(
+                        "UPDATE inspections SET time_zone = ?1, overdue_at = ?2 WHERE id = ?3",
+                        params![time_zone, calendar.overdue_at(*scheduled_date, tz), inspection_id],
+                    )?;
+                }
+                debug!("Re-anchored {} open inspections to time zone {}", open_inspections.len(), time_zone);
             }
 
             debug!("Location {} updated successfully", id);
@@ -3174,8 +16861,8 @@ impl LocationService {
         })
     }
 
-    pub fn delete_location_safe(&self, id: i64) -> AppResult<LocationDeletionResult> {
-        info!("Safely deleting location: {}", id);
+    pub fn delete_location_safe(&self, context: &RequestContext, id: i64) -> AppResult<LocationDeletionResult> {
+        info!("[{}] Safely deleting location: {}", context.request_id, id);
         
         self.database.with_transaction(|conn| {
             // Check for dependent assets first
@@ -3211,6 +16898,7 @@ impl LocationService {
             }
 
             // Safe to delete
+            move_to_recycle_bin(conn, context, RecycleEntityType::Location, id)?;
             let rows_affected = conn.execute("DELETE FROM locations WHERE id = ?1", params![id])?;
             
             if rows_affected == 0 {
@@ -3245,6 +16933,7 @@ impl LocationService {
         };
         
         let asset_result = self.asset_service.get_assets_by_location(id, filter)?;
+        let path = self.get_location_path(id)?;
         
         Ok(LocationWithAssets {
             id: location.id,
@@ -3254,6 +16943,7 @@ impl LocationService {
             longitude: location.longitude,
             description: location.description,
             parent_location_id: location.parent_location_id,
+            path,
             created_by: location.created_by,
             created_at: location.created_at,
             updated_at: location.updated_at,
@@ -3264,7 +16954,7 @@ impl LocationService {
     pub fn get_location_with_asset_summary(&self, id: i64) -> AppResult<LocationAssetSummary> {
         debug!("Fetching location with asset summary: {}", id);
         let location = self.get_location_by_id(id)?;
-        let conn = self.database.get_connection()?;
+        let conn = self.database.get_read_connection()?;
 
         // Get asset count
         let asset_count: i64 = conn.query_row(
@@ -3282,7 +16972,7 @@ impl LocationService {
             |row| row.get(0),
         )?;
 
-        self.database.return_connection(conn);
+        let path = Self::location_path(&conn, id)?;
 
         Ok(LocationAssetSummary {
             id: location.id,
@@ -3292,6 +16982,7 @@ impl LocationService {
             longitude: location.longitude,
             description: location.description,
             parent_location_id: location.parent_location_id,
+            path,
             created_by: location.created_by,
             created_at: location.created_at,
             updated_at: location.updated_at,
@@ -3315,15 +17006,13 @@ impl LocationService {
 
     pub fn search_locations_with_asset_counts(&self, query: String, filter: QueryFilter) -> AppResult<PaginatedResult<LocationWithAssetCount>> {
         info!("Searching locations with asset counts: {}", query);
+        let paging = QuerySpec::LOCATIONS.validate(&filter)?;
         let conn = self.database.get_connection()?;
 
         let search_term = format!("%{}%", query);
-        let offset = ((filter.page.unwrap_or(1) - 1) * filter.limit.unwrap_or(50)).max(0);
-        let limit = filter.limit.unwrap_or(50);
-        let sort_order = filter.sort_order.unwrap_or(SortOrder::Desc);
-        let sort_by = filter.sort_by.unwrap_or("name".to_string());
+        let (offset, limit) = (paging.offset, paging.limit);
 
-        let order_by = format!(" ORDER BY {} {}", sort_by, sort_order);
+        let order_by = paging.order_by("name");
 
         let search_query = format!(
             "SELECT l.id, l.name, l.address, l.latitude, l.longitude, l.description,
@@ -3331,15 +17020,15 @@ impl LocationService {
                     COUNT(a.id) as asset_count
              FROM locations l
              LEFT JOIN assets a ON l.id = a.location_id
-             WHERE l.name LIKE ?1 OR l.address LIKE ?1 OR l.description LIKE ?1
+             WHERE (l.name LIKE ?1 OR l.address LIKE ?1 OR l.description LIKE ?1){}
              GROUP BY l.id, l.name, l.address, l.latitude, l.longitude, l.description,
                       l.parent_location_id, l.created_by, l.created_at, l.updated_at
              {} LIMIT {} OFFSET {}",
-            order_by, limit, offset
+            paging.filter_condition(2), order_by, limit, offset
         );
 
         let mut stmt = conn.prepare(&search_query)?;
-        let location_iter = stmt.query_map([&search_term], |row| {
+        let location_iter = stmt.query_map(paging.sql_params(&[&search_term]).as_slice(), |row| {
             Ok(LocationWithAssetCount {
                 id: row.get(0)?,
                 name: row.get(1)?,
@@ -3348,6 +17037,7 @@ impl LocationService {
                 longitude: row.get(4)?,
                 description: row.get(5)?,
                 parent_location_id: row.get(6)?,
+                path: Vec::new(),
                 created_by: row.get(7)?,
                 created_at: row.get(8)?,
                 updated_at: row.get(9)?,
@@ -3357,19 +17047,369 @@ impl LocationService {
 
         let mut locations = Vec::new();
         for location in location_iter {
-            locations.push(location?);
+            let mut location = location?;
+            location.path = Self::location_path(&conn, location.id)?;
+            locations.push(location);
         }
 
         let total_count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM locations
-             WHERE name LIKE ?1 OR address LIKE ?1 OR description LIKE ?1",
-            [&search_term],
+            &format!(
+                "SELECT COUNT(*) FROM locations
+                 WHERE (name LIKE ?1 OR address LIKE ?1 OR description LIKE ?1){}",
+                paging.filter_condition(2)
+            ),
+            paging.sql_params(&[&search_term]).as_slice(),
             |row| row.get(0),
         )?;
 
         drop(stmt);
         self.database.return_connection(conn);
-        Ok(PaginatedResult::new(locations, total_count, filter.page.unwrap_or(1), limit))
+        Ok(PaginatedResult::new(locations, total_count, paging.page, limit))
+    }
+
+    /// Locations inside a viewport or within a radius; radius results are
+    /// ordered nearest first
+    pub fn search_locations_geo(&self, query: GeoQuery) -> AppResult<Vec<LocationDistance>> {
+        debug!("Geo search on locations: {:?}", query);
+        query.validate()?;
+        let bounds = query.bounding_box();
+        let conn = self.database.get_connection()?;
+
+        let sql = format!(
+            "SELECT id, name, address, latitude, longitude, description, parent_location_id, created_by, created_at, updated_at, time_zone
+             FROM locations
+             WHERE latitude IS NOT NULL AND longitude IS NOT NULL AND {}
+             ORDER BY name",
+            bounds.sql_condition("latitude", "longitude", 1)
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let candidates = stmt
+            .query_map(params_from_iter(bounds.sql_params()), |row| self.row_to_location(row))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        drop(stmt);
+        self.database.return_connection(conn);
+
+        let mut results: Vec<LocationDistance> = candidates
+            .into_iter()
+            .filter_map(|location| {
+                let point = geo::location_point(&location)?;
+                query.matches(&point).map(|distance_km| LocationDistance { location, distance_km })
+            })
+            .collect();
+        if matches!(query, GeoQuery::Radius { .. }) {
+            results.sort_by(|a, b| a.distance_km.partial_cmp(&b.distance_km).unwrap_or(std::cmp::Ordering::Equal));
+        }
+        Ok(results)
+    }
+
+    /// Breadcrumb path from the root of the hierarchy down to `id`
+    pub fn get_location_path(&self, id: i64) -> AppResult<Vec<LocationBreadcrumb>> {
+        let conn = self.database.get_connection()?;
+        let path = Self::location_path(&conn, id);
+        self.database.return_connection(conn);
+        path
+    }
+
+    /// Location hierarchy as nested trees, either every top-level location
+    /// or the subtree under `root_id`
+    pub fn get_location_tree(&self, root_id: Option<i64>) -> AppResult<Vec<LocationTreeNode>> {
+        debug!("Building location tree (root: {:?})", root_id);
+        if let Some(root_id) = root_id {
+            self.get_location_by_id(root_id)?;
+        }
+        let conn = self.database.get_connection()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT l.id, l.name, l.address, l.latitude, l.longitude, l.description, l.parent_location_id,
+                    l.created_by, l.created_at, l.updated_at, l.time_zone,
+                    (SELECT COUNT(*) FROM assets a WHERE a.location_id = l.id) AS asset_count
+             FROM locations l"
+        )?;
+        let rows = stmt
+            .query_map([], |row| Ok((self.row_to_location(row)?, row.get::<_, i64>(11)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        drop(stmt);
+        self.database.return_connection(conn);
+        Ok(LocationTreeNode::build(rows, root_id))
+    }
+
+    /// Asset, inspection and compliance totals for a location and all of
+    /// its descendants
+    pub fn get_location_rollup(&self, id: i64) -> AppResult<LocationRollup> {
+        debug!("Computing subtree roll-up for location: {}", id);
+        let location = self.get_location_by_id(id)?;
+        let conn = self.database.get_connection()?;
+
+        let (location_count, asset_count, active_assets, maintenance_assets): (i64, i64, i64, i64) = conn.query_row(
+            &format!(
+                "{}
+                 SELECT (SELECT COUNT(DISTINCT id) FROM subtree),
+                        COUNT(a.id),
+                        COUNT(CASE WHEN a.status = 'Active' THEN 1 END),
+                        COUNT(CASE WHEN a.status = 'Maintenance' THEN 1 END)
+                 FROM assets a WHERE a.location_id IN (SELECT id FROM subtree)",
+                LOCATION_SUBTREE_CTE
+            ),
+            params![id, MAX_LOCATION_DEPTH as i64],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+
+        let (open_inspections, overdue_inspections): (i64, i64) = conn.query_row(
+            &format!(
+                "{}
+                 SELECT COUNT(*),
+                        COUNT(CASE WHEN COALESCE(i.overdue_at, i.scheduled_date) < datetime('now') THEN 1 END)
+                 FROM inspections i JOIN assets a ON i.asset_id = a.id
+                 WHERE a.location_id IN (SELECT id FROM subtree)
+                   AND i.status NOT IN ('Completed', 'Cancelled')",
+                LOCATION_SUBTREE_CTE
+            ),
+            params![id, MAX_LOCATION_DEPTH as i64],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        let critical_findings: i64 = conn.query_row(
+            &format!(
+                "{}
+                 SELECT COUNT(*) FROM inspection_items ii
+                 JOIN inspections i ON ii.inspection_id = i.id
+                 JOIN assets a ON i.asset_id = a.id
+                 WHERE a.location_id IN (SELECT id FROM subtree)
+                   AND ii.severity = 'Critical' AND i.status = 'Completed'",
+                LOCATION_SUBTREE_CTE
+            ),
+            params![id, MAX_LOCATION_DEPTH as i64],
+            |row| row.get(0),
+        )?;
+
+        // Same per-inspection score as the asset summary, averaged over the subtree
+        let compliance_score: Option<f64> = conn.query_row(
+            &format!(
+                "{}
+                 SELECT AVG(score) FROM (
+                     SELECT 100.0 * COUNT(CASE WHEN ii.is_compliant = 1 THEN 1 END) / COUNT(*) AS score
+                     FROM inspection_items ii
+                     JOIN inspections i ON ii.inspection_id = i.id
+                     JOIN assets a ON i.asset_id = a.id
+                     WHERE a.location_id IN (SELECT id FROM subtree) AND i.status = 'Completed'
+                     GROUP BY i.id
+                 )",
+                LOCATION_SUBTREE_CTE
+            ),
+            params![id, MAX_LOCATION_DEPTH as i64],
+            |row| row.get(0),
+        )?;
+
+        let path = Self::location_path(&conn, id)?;
+        self.database.return_connection(conn);
+
+        Ok(LocationRollup {
+            location_id: location.id,
+            name: location.name,
+            path,
+            location_count,
+            asset_count,
+            active_assets,
+            maintenance_assets,
+            open_inspections,
+            overdue_inspections,
+            critical_findings,
+            compliance_score: compliance_score.unwrap_or(0.0),
+        })
+    }
+
+    /// Move a location, together with everything beneath it, under a new
+    /// parent (or to the top level)
+    pub fn move_location(&self, context: &RequestContext, id: i64, new_parent_id: Option<i64>) -> AppResult<Location> {
+        info!("[{}] Moving location {} under {:?}", context.request_id, id, new_parent_id);
+        self.get_location_by_id(id)?;
+
+        self.database.with_transaction(|conn| {
+            Self::ensure_valid_parent(conn, id, new_parent_id)?;
+            conn.execute(
+                "UPDATE locations SET parent_location_id = ?1, updated_at = datetime('now') WHERE id = ?2",
+                params![new_parent_id, id],
+            )?;
+            reanchor_open_inspections(conn, id)?;
+
+            debug!("Location {} moved under {:?}", id, new_parent_id);
+            self.get_location_by_id(id)
+        })
+    }
+
+    /// Working days and closures in effect at a location, including those
+    /// inherited from its ancestors
+    pub fn get_location_calendar(&self, id: i64) -> AppResult<LocationCalendar> {
+        debug!("Fetching working calendar for location {}", id);
+        self.get_location_by_id(id)?;
+        let conn = self.database.get_connection()?;
+        let result = load_location_calendar(&conn, id);
+        self.database.return_connection(conn);
+        result
+    }
+
+    /// Set the weekdays a location and the sites beneath it work, or clear
+    /// them to inherit from the parent
+    pub fn set_location_working_days(
+        &self,
+        context: &RequestContext,
+        id: i64,
+        working_days: Option<Vec<Weekday>>,
+    ) -> AppResult<LocationCalendar> {
+        info!("[{}] Setting working days for location {}: {:?}", context.request_id, id, working_days);
+        self.get_location_by_id(id)?;
+        let updated_by = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            match &working_days {
+                Some(days) => {
+                    if days.is_empty() {
+                        return Err(AppError::validation("working_days", "A location must work at least one day a week"));
+                    }
+                    conn.execute(
+                        "INSERT INTO location_calendars (location_id, working_days, updated_by)
+                         VALUES (?1, ?2, ?3)
+                         ON CONFLICT(location_id) DO UPDATE SET
+                            working_days = excluded.working_days,
+                            updated_by = excluded.updated_by,
+                            updated_at = CURRENT_TIMESTAMP",
+                        params![id, format_working_days(days), updated_by],
+                    )?;
+                }
+                None => {
+                    conn.execute("DELETE FROM location_calendars WHERE location_id = ?1", params![id])?;
+                }
+            }
+            let reanchored = reanchor_open_inspections(conn, id)?;
+            debug!("Re-anchored {} open inspections to the new working days", reanchored);
+            load_location_calendar(conn, id)
+        })
+    }
+
+    /// Close a location and the sites beneath it for a holiday or shutdown
+    pub fn add_location_closure(
+        &self,
+        context: &RequestContext,
+        id: i64,
+        closure: LocationClosureInput,
+    ) -> AppResult<LocationClosure> {
+        info!("[{}] Adding {} '{}' to location {}", context.request_id, closure.kind, closure.name, id);
+        closure.validate()?;
+        self.get_location_by_id(id)?;
+        let created_by = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            let closure_id = conn.query_row(
+                "INSERT INTO location_closures (location_id, name, kind, start_date, end_date, created_by)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 RETURNING id",
+                params![
+                    id, closure.name.trim(), closure.kind.to_string(),
+                    closure.start_date, closure.end_date, created_by
+                ],
+                |row| row.get::<_, i64>(0),
+            )?;
+            let reanchored = reanchor_open_inspections(conn, id)?;
+            debug!("Re-anchored {} open inspections around closure {}", reanchored, closure_id);
+
+            conn.query_row(
+                &format!("SELECT {} FROM location_closures WHERE id = ?1", LOCATION_CLOSURE_COLUMNS),
+                params![closure_id],
+                row_to_location_closure,
+            ).map_err(AppError::from)
+        })
+    }
+
+    /// Remove a holiday or shutdown, returning the location it was set on
+    pub fn delete_location_closure(&self, context: &RequestContext, closure_id: i64) -> AppResult<i64> {
+        info!("[{}] Deleting location closure {}", context.request_id, closure_id);
+
+        self.database.with_transaction(|conn| {
+            let location_id: i64 = conn.query_row(
+                "DELETE FROM location_closures WHERE id = ?1 RETURNING location_id",
+                params![closure_id],
+                |row| row.get(0),
+            ).optional()?.ok_or_else(|| AppError::RecordNotFound {
+                entity: "LocationClosure".to_string(),
+                field: "id".to_string(),
+                value: closure_id.to_string(),
+            })?;
+            let reanchored = reanchor_open_inspections(conn, location_id)?;
+            debug!("Re-anchored {} open inspections after removing closure {}", reanchored, closure_id);
+            Ok(location_id)
+        })
+    }
+
+    /// Reject a parent that does not exist, is the location itself or one
+    /// of its descendants, or would nest the subtree too deeply
+    fn ensure_valid_parent(conn: &Connection, id: i64, new_parent_id: Option<i64>) -> AppResult<()> {
+        let Some(parent_id) = new_parent_id else {
+            return Ok(());
+        };
+
+        let parent_exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM locations WHERE id = ?1)",
+            params![parent_id],
+            |row| row.get(0),
+        )?;
+        if !parent_exists {
+            return Err(AppError::RecordNotFound {
+                entity: "Location".to_string(),
+                field: "id".to_string(),
+                value: parent_id.to_string(),
+            });
+        }
+
+        let (in_subtree, subtree_height): (bool, Option<i64>) = conn.query_row(
+            &format!(
+                "{}
+                 SELECT EXISTS(SELECT 1 FROM subtree WHERE id = ?3), MAX(depth) FROM subtree",
+                LOCATION_SUBTREE_CTE
+            ),
+            params![id, MAX_LOCATION_DEPTH as i64, parent_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        if in_subtree {
+            return Err(AppError::validation(
+                "parent_location_id",
+                "A location cannot be moved under itself or one of its descendants",
+            ));
+        }
+
+        let new_depth = Self::location_depth(conn, parent_id)? + 1 + subtree_height.unwrap_or(0) as usize;
+        if new_depth >= MAX_LOCATION_DEPTH {
+            return Err(AppError::validation(
+                "parent_location_id",
+                format!("Locations cannot be nested more than {} levels deep", MAX_LOCATION_DEPTH),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Number of ancestors above `id`
+    fn location_depth(conn: &Connection, id: i64) -> AppResult<usize> {
+        Ok(Self::location_path(conn, id)?.len().saturating_sub(1))
+    }
+
+    fn location_path(conn: &Connection, id: i64) -> AppResult<Vec<LocationBreadcrumb>> {
+        let mut stmt = conn.prepare(
+            "WITH RECURSIVE ancestors(id, name, parent_location_id, depth) AS (
+                 SELECT id, name, parent_location_id, 0 FROM locations WHERE id = ?1
+                 UNION ALL
+                 SELECT l.id, l.name, l.parent_location_id, a.depth + 1
+                 FROM locations l JOIN ancestors a ON l.id = a.parent_location_id
+                 WHERE a.depth < ?2
+             )
+             SELECT id, name FROM ancestors ORDER BY depth DESC"
+        )?;
+        let path = stmt
+            .query_map(params![id, MAX_LOCATION_DEPTH as i64], |row| {
+                Ok(LocationBreadcrumb { id: row.get(0)?, name: row.get(1)? })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(path)
     }
 
     fn row_to_location(&self, row: &Row) -> rusqlite::Result<Location> {
@@ -3381,6 +17421,7 @@ impl LocationService {
             longitude: row.get(4)?,
             description: row.get(5)?,
             parent_location_id: row.get(6)?,
+            time_zone: row.get(10)?,
             created_by: row.get(7)?,
             created_at: row.get(8)?,
             updated_at: row.get(9)?,
@@ -3388,6 +17429,272 @@ impl LocationService {
     }
 }
 
+// =============================================================================
+// Geocoding Service
+// =============================================================================
+
+/// Environment variable holding a Nominatim-compatible search endpoint,
+/// e.g. `https://nominatim.openstreetmap.org/search`. Geocoding is off when unset.
+pub const GEOCODER_URL_ENV: &str = "CRANEPRO_GEOCODER_URL";
+
+/// Seconds to wait for the geocoder before giving up
+const GEOCODER_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug, Deserialize)]
+struct GeocoderMatch {
+    lat: String,
+    lon: String,
+}
+
+pub struct GeocodingService {
+    endpoint: Option<String>,
+    client: reqwest::Client,
+}
+
+impl GeocodingService {
+    pub fn new(endpoint: Option<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(GEOCODER_TIMEOUT_SECS))
+            .user_agent(concat!("CranePro/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self { endpoint, client }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(std::env::var(GEOCODER_URL_ENV).ok().filter(|url| !url.trim().is_empty()))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.endpoint.is_some()
+    }
+
+    /// Look up coordinates for a postal address. Returns `None` when
+    /// geocoding is disabled or the address has no match.
+    pub async fn geocode(&self, address: &str) -> AppResult<Option<GeoPoint>> {
+        let Some(endpoint) = &self.endpoint else {
+            return Ok(None);
+        };
+        debug!("Geocoding address: {}", address);
+
+        let matches: Vec<GeocoderMatch> = self.client
+            .get(endpoint)
+            .query(&[("q", address), ("format", "json"), ("limit", "1")])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let Some(first) = matches.into_iter().next() else {
+            return Ok(None);
+        };
+        let point = GeoPoint {
+            latitude: first.lat.parse().map_err(|_| AppError::ExternalService {
+                service: "geocoder".to_string(),
+                message: format!("Invalid latitude in response: {}", first.lat),
+            })?,
+            longitude: first.lon.parse().map_err(|_| AppError::ExternalService {
+                service: "geocoder".to_string(),
+                message: format!("Invalid longitude in response: {}", first.lon),
+            })?,
+        };
+        point.validate()?;
+        Ok(Some(point))
+    }
+}
+
+// =============================================================================
+// Demo Data Service
+// =============================================================================
+
+pub struct DemoDataService {
+    database: Arc<Database>,
+}
+
+impl DemoDataService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// Generate demo data. Refuses to run once any locations, assets or
+    /// inspections exist, so it can never mix with real records.
+    pub fn seed_demo_data(&self, context: &RequestContext, options: &SeedOptions) -> AppResult<SeedSummary> {
+        info!("[{}] Generating demo data: {:?}", context.request_id, options);
+        options.validate()?;
+        let created_by = context.current_user()?.user_id;
+
+        self.database.with_transaction(|conn| {
+            let existing: i64 = conn.query_row(
+                "SELECT (SELECT COUNT(*) FROM locations) + (SELECT COUNT(*) FROM assets)
+                        + (SELECT COUNT(*) FROM inspections)",
+                [],
+                |row| row.get(0),
+            )?;
+            if existing > 0 {
+                return Err(AppError::validation(
+                    "database",
+                    "Demo data can only be generated into an empty database",
+                ));
+            }
+
+            let inspectors = {
+                let mut stmt = conn.prepare(
+                    "SELECT id FROM users WHERE role = 'Inspector' AND is_active = 1 ORDER BY id"
+                )?;
+                let ids = stmt.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<Vec<i64>>>()?;
+                ids
+            };
+
+            let summary = seed::populate(conn, options, created_by, &inspectors, Utc::now().date_naive())?;
+            info!("[{}] Demo data generated: {} locations, {} assets, {} inspections, {} items, {} media",
+                  context.request_id, summary.locations, summary.assets, summary.inspections,
+                  summary.inspection_items, summary.media_files);
+            Ok(summary)
+        })
+    }
+}
+
+// =============================================================================
+// Idempotency Service
+// =============================================================================
+
+/// Hours an idempotency key is remembered before it may be reused
+pub const IDEMPOTENCY_KEY_TTL_HOURS: i64 = 24;
+
+/// Longest accepted idempotency key
+pub const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 128;
+
+/// Outcome of claiming an idempotency key
+#[derive(Debug, Clone)]
+pub enum IdempotencyClaim<T> {
+    /// First use of the key; run the operation and then call `complete`
+    New,
+    /// The key was already used for the same request; return this result
+    Replay(T),
+}
+
+pub struct IdempotencyService {
+    database: Arc<Database>,
+}
+
+impl IdempotencyService {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self { database }
+    }
+
+    /// SHA-256 fingerprint of the request parts, used to detect a key being
+    /// reused for a different request
+    pub fn fingerprint(parts: &[&[u8]]) -> String {
+        let mut hasher = Sha256::new();
+        for part in parts {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Claim `key` for `command` on behalf of the current user.
+    ///
+    /// Returns the stored result when the key has already completed for the
+    /// same request. A key reused with a different request, or one whose
+    /// first call is still running, is rejected.
+    pub fn claim<T: DeserializeOwned>(
+        &self,
+        context: &RequestContext,
+        command: &str,
+        key: &str,
+        request_hash: &str,
+    ) -> AppResult<IdempotencyClaim<T>> {
+        let key = key.trim();
+        if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LENGTH {
+            return Err(AppError::validation(
+                "idempotency_key",
+                format!("Idempotency key must be 1-{} characters", MAX_IDEMPOTENCY_KEY_LENGTH),
+            ));
+        }
+        let user_id = context.current_user()?.user_id;
+
+        let stored = self.database.with_transaction(|conn| {
+            conn.execute(
+                "DELETE FROM idempotency_keys WHERE created_at < datetime('now', ?1)",
+                [format!("-{} hours", IDEMPOTENCY_KEY_TTL_HOURS)],
+            )?;
+
+            let inserted = conn.execute(
+                "INSERT OR IGNORE INTO idempotency_keys (idempotency_key, user_id, command, request_hash)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![key, user_id, command, request_hash],
+            )?;
+            if inserted == 1 {
+                return Ok(None);
+            }
+
+            let stored = conn.query_row(
+                "SELECT request_hash, response FROM idempotency_keys
+                 WHERE idempotency_key = ?1 AND user_id = ?2 AND command = ?3",
+                params![key, user_id, command],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)),
+            )?;
+            Ok(Some(stored))
+        })?;
+
+        match stored {
+            None => {
+                debug!("[{}] Claimed idempotency key {} for {}", context.request_id, key, command);
+                Ok(IdempotencyClaim::New)
+            }
+            Some((stored_hash, _)) if stored_hash != request_hash => Err(AppError::validation(
+                "idempotency_key",
+                format!("Idempotency key {} was already used for a different {} request", key, command),
+            )),
+            Some((_, None)) => Err(AppError::ResourceUnavailable {
+                resource: format!("{} request with idempotency key {} is still in progress", command, key),
+            }),
+            Some((_, Some(response))) => {
+                info!("[{}] Replaying {} result for idempotency key {}", context.request_id, command, key);
+                Ok(IdempotencyClaim::Replay(serde_json::from_str(&response)?))
+            }
+        }
+    }
+
+    /// Store the result of a claimed key so retries can replay it
+    pub fn complete<T: Serialize>(
+        &self,
+        context: &RequestContext,
+        command: &str,
+        key: &str,
+        response: &T,
+    ) -> AppResult<()> {
+        let user_id = context.current_user()?.user_id;
+        let response = serde_json::to_string(response)?;
+
+        self.database.with_transaction(|conn| {
+            conn.execute(
+                "UPDATE idempotency_keys SET response = ?1, completed_at = CURRENT_TIMESTAMP
+                 WHERE idempotency_key = ?2 AND user_id = ?3 AND command = ?4",
+                params![response, key.trim(), user_id, command],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Release a claimed key after the operation failed so it can be retried
+    pub fn release(&self, context: &RequestContext, command: &str, key: &str) -> AppResult<()> {
+        let user_id = context.current_user()?.user_id;
+        debug!("[{}] Releasing idempotency key {} for {}", context.request_id, key, command);
+
+        self.database.with_transaction(|conn| {
+            conn.execute(
+                "DELETE FROM idempotency_keys
+                 WHERE idempotency_key = ?1 AND user_id = ?2 AND command = ?3 AND response IS NULL",
+                params![key.trim(), user_id, command],
+            )?;
+            Ok(())
+        })
+    }
+}
+
 // =============================================================================
 // Main Services Struct
 // =============================================================================
@@ -3400,19 +17707,111 @@ pub struct Services {
     pub media: Arc<MediaService>,
     pub reports: Arc<ReportService>,
     pub locations: Arc<LocationService>,
+    pub idempotency: Arc<IdempotencyService>,
+    pub geocoding: Arc<GeocodingService>,
+    pub availability: Arc<AvailabilityService>,
+    pub teams: Arc<TeamService>,
+    pub demo: Arc<DemoDataService>,
+    pub comments: Arc<CommentService>,
+    pub activity: Arc<ActivityService>,
+    pub watches: Arc<WatchService>,
+    pub recycle_bin: Arc<RecycleBinService>,
+    pub bulk: Arc<BulkOperationService>,
+    pub data_quality: Arc<DataQualityService>,
+    pub search: Arc<SearchService>,
+    pub dashboard: Arc<DashboardService>,
+    pub migrations: Arc<MigrationService>,
+    pub prestart_checks: Arc<PrestartCheckService>,
+    pub db_maintenance: Arc<DatabaseMaintenanceService>,
+    pub history: Arc<EntityHistoryService>,
+    pub asset_records: Arc<AssetRecordService>,
+    pub maintenance: Arc<MaintenanceService>,
+    pub operator_authorizations: Arc<OperatorAuthorizationService>,
+    pub training: Arc<TrainingService>,
+    pub work_orders: Arc<WorkOrderService>,
+    pub notifications: Arc<NotificationService>,
+    pub risk_matrix: Arc<RiskMatrixService>,
+    pub finding_slas: Arc<FindingSlaService>,
+    pub audit: Arc<AuditService>,
+    pub security_events: Arc<SecurityEventService>,
+    pub login_history: Arc<LoginHistoryService>,
+    pub settings: Arc<SettingsService>,
+    pub parts: Arc<PartsService>,
+    pub defects: Arc<DefectService>,
+    pub json_schemas: Arc<JsonSchemaService>,
+    pub corrective_actions: Arc<CorrectiveActionService>,
+    pub validation_rules: Arc<ValidationRuleService>,
+    pub warehouse: Arc<WarehouseExportService>,
+    pub vendor_documents: Arc<VendorDocumentService>,
+    pub mfa: Arc<MfaService>,
+    pub kiosk: Arc<KioskService>,
+    pub trusted_devices: Arc<TrustedDeviceService>,
+    pub legal_holds: Arc<LegalHoldService>,
+    pub sessions: Arc<SessionService>,
+    pub roles: Arc<RoleService>,
+    pub api_keys: Arc<ApiKeyService>,
+    pub checklist_packs: Arc<ChecklistPackService>,
 }
 
 impl Services {
-    pub async fn init(database: Arc<Database>) -> AppResult<Self> {
+    pub async fn init(database: Arc<Database>, field_cipher: Arc<FieldCipher>) -> AppResult<Self> {
         info!("Initializing services layer");
         
         let assets = Arc::new(AssetService::new(database.clone()));
         let inspections = Arc::new(InspectionService::new(database.clone()));
         let compliance = Arc::new(ComplianceService::new(database.clone()));
-        let users = Arc::new(UserService::new(database.clone()));
+        let users = Arc::new(UserService::new(database.clone(), field_cipher.clone()));
         let media = Arc::new(MediaService::new(database.clone()));
         let reports = Arc::new(ReportService::new(database.clone()));
         let locations = Arc::new(LocationService::new(database.clone(), assets.clone()));
+        let idempotency = Arc::new(IdempotencyService::new(database.clone()));
+        let geocoding = Arc::new(GeocodingService::from_env());
+        let availability = Arc::new(AvailabilityService::new(database.clone()));
+        let teams = Arc::new(TeamService::new(database.clone(), inspections.clone()));
+        let demo = Arc::new(DemoDataService::new(database.clone()));
+        let comments = Arc::new(CommentService::new(database.clone()));
+        let activity = Arc::new(ActivityService::new(database.clone()));
+        let watches = Arc::new(WatchService::new(database.clone()));
+        let recycle_bin = Arc::new(RecycleBinService::from_env(database.clone()));
+        let bulk = Arc::new(BulkOperationService::new(database.clone()));
+        let data_quality = Arc::new(DataQualityService::new(database.clone()));
+        let search = Arc::new(SearchService::new(database.clone()));
+        let dashboard = Arc::new(DashboardService::new(
+            database.clone(), inspections.clone(), teams.clone(), reports.clone(),
+        ));
+        let migrations = Arc::new(MigrationService::new(database.clone()));
+        let prestart_checks = Arc::new(PrestartCheckService::new(database.clone()));
+        let db_maintenance = Arc::new(DatabaseMaintenanceService::new(database.clone()));
+        let history = Arc::new(EntityHistoryService::new(database.clone()));
+        let asset_records = Arc::new(AssetRecordService::new(database.clone()));
+        let maintenance = Arc::new(MaintenanceService::new(database.clone()));
+        let operator_authorizations = Arc::new(OperatorAuthorizationService::new(database.clone()));
+        let training = Arc::new(TrainingService::new(database.clone()));
+        let work_orders = Arc::new(WorkOrderService::new(database.clone()));
+        let notifications = Arc::new(NotificationService::new(database.clone(), field_cipher));
+        let risk_matrix = Arc::new(RiskMatrixService::new(database.clone()));
+        let finding_slas = Arc::new(FindingSlaService::new(database.clone()));
+        let audit = Arc::new(AuditService::new(database.clone()));
+        let security_events = Arc::new(SecurityEventService::new(database.clone()));
+        let login_history = Arc::new(LoginHistoryService::new(database.clone()));
+        let settings = Arc::new(SettingsService::new(database.clone()));
+        let parts = Arc::new(PartsService::new(database.clone()));
+        let defects = Arc::new(DefectService::new(database.clone()));
+        let json_schemas = Arc::new(JsonSchemaService::new(database.clone()));
+        let corrective_actions = Arc::new(CorrectiveActionService::new(database.clone()));
+        let validation_rules = Arc::new(ValidationRuleService::new(database.clone()));
+        let warehouse = Arc::new(WarehouseExportService::new(database.clone(), settings.clone()));
+        let vendor_documents = Arc::new(VendorDocumentService::new(database.clone()));
+        let mfa = Arc::new(MfaService::new(database.clone()));
+        let kiosk = Arc::new(KioskService::new(database.clone()));
+        let trusted_devices = Arc::new(TrustedDeviceService::new(database.clone()));
+        let legal_holds = Arc::new(LegalHoldService::new(database.clone()));
+        let sessions = Arc::new(SessionService::new(database.clone()));
+        let roles = Arc::new(RoleService::new(database.clone()));
+        let api_keys = Arc::new(ApiKeyService::new(database.clone()));
+        let checklist_packs = Arc::new(ChecklistPackService::new(database.clone()));
+        roles.seed_builtin_roles()?;
+        users.encrypt_plaintext_fields()?;
         
         info!("Services layer initialized successfully");
         Ok(Services {
@@ -3423,6 +17822,50 @@ impl Services {
             media,
             reports,
             locations,
+            idempotency,
+            geocoding,
+            availability,
+            teams,
+            demo,
+            comments,
+            activity,
+            watches,
+            recycle_bin,
+            bulk,
+            data_quality,
+            search,
+            dashboard,
+            migrations,
+            prestart_checks,
+            db_maintenance,
+            history,
+            asset_records,
+            maintenance,
+            operator_authorizations,
+            training,
+            work_orders,
+            notifications,
+            risk_matrix,
+            finding_slas,
+            audit,
+            security_events,
+            login_history,
+            settings,
+            parts,
+            defects,
+            json_schemas,
+            corrective_actions,
+            validation_rules,
+            warehouse,
+            vendor_documents,
+            mfa,
+            kiosk,
+            trusted_devices,
+            legal_holds,
+            sessions,
+            roles,
+            api_keys,
+            checklist_packs,
         })
     }
 }
@@ -0,0 +1,78 @@
+//! Bulk asset updates with a result for every asset
+
+use super::TestServices;
+use crate::errors::AppError;
+use crate::models::{Asset, AssetStatus, CmaaServiceClass, MAX_BULK_RECORDS};
+use crate::services::AssetUpdateData;
+use serde_json::json;
+
+/// Into maintenance and CMAA class A, which a crane already in FEM group 5m
+/// cannot take
+fn into_maintenance() -> AssetUpdateData {
+    serde_json::from_value(json!({ "status": "Maintenance", "service_class": "A" })).unwrap()
+}
+
+#[tokio::test]
+async fn test_bulk_update_reports_each_asset_and_keeps_the_rest() {
+    let test = TestServices::new().await;
+    let location = test.add_location("Bay 3");
+    let (first, heavy, last) = (test.add_asset(location, "OHC-1"), test.add_asset(location, "OHC-2"), test.add_asset(location, "OHC-3"));
+    test.database.with_transaction(|conn| {
+        conn.execute("UPDATE assets SET fem_group = '5m' WHERE id = ?1", [heavy])?;
+        Ok(())
+    }).unwrap();
+    let (services, context) = (&test.services, &test.context);
+    let missing = last + 100;
+
+    // Repeated IDs are updated once
+    let outcome = services.assets
+        .bulk_update_assets(context, &[first, heavy, missing, last, first], into_maintenance())
+        .unwrap();
+    assert_eq!((outcome.total, outcome.succeeded, outcome.failed), (4, 2, 2));
+    assert_eq!(outcome.results.iter().map(|r| (r.id, r.success)).collect::<Vec<_>>(),
+               vec![(first, true), (heavy, false), (missing, false), (last, true)]);
+    assert!(outcome.results[0].error.is_none());
+    assert!(outcome.results[1].error.as_deref().is_some_and(|e| e.contains("FEM group 5m")));
+    assert!(outcome.results[2].error.as_deref().is_some_and(|e| e.contains(&missing.to_string())));
+
+    let asset = |id| -> Asset { services.assets.get_asset_by_id(id).unwrap() };
+    for id in [first, last] {
+        assert_eq!(asset(id).status, AssetStatus::Maintenance);
+        assert_eq!(asset(id).service_class, Some(CmaaServiceClass::A));
+    }
+    // The status change made before the class was refused is rolled back
+    assert_eq!(asset(heavy).status, AssetStatus::Active);
+    assert_eq!(asset(heavy).service_class, None);
+}
+
+#[tokio::test]
+async fn test_bulk_update_skips_deleted_assets() {
+    let test = TestServices::new().await;
+    let location = test.add_location("Bay 3");
+    let (kept, deleted) = (test.add_asset(location, "OHC-1"), test.add_asset(location, "OHC-2"));
+    let (services, context) = (&test.services, &test.context);
+    services.assets.delete_asset(context, deleted).unwrap();
+
+    let outcome = services.assets.bulk_update_assets(context, &[kept, deleted], into_maintenance()).unwrap();
+    assert_eq!((outcome.succeeded, outcome.failed), (1, 1));
+    assert!(!outcome.results[1].success);
+
+    let restored = services.assets.restore_asset(context, deleted).unwrap();
+    assert_eq!(restored.status, AssetStatus::Active);
+}
+
+#[tokio::test]
+async fn test_bulk_update_needs_ids() {
+    let test = TestServices::new().await;
+    let (services, context) = (&test.services, &test.context);
+
+    assert!(matches!(
+        services.assets.bulk_update_assets(context, &[], into_maintenance()),
+        Err(AppError::Validation { .. })
+    ));
+    let too_many: Vec<i64> = (1..=MAX_BULK_RECORDS as i64 + 1).collect();
+    assert!(matches!(
+        services.assets.bulk_update_assets(context, &too_many, into_maintenance()),
+        Err(AppError::OutOfRange { .. })
+    ));
+}
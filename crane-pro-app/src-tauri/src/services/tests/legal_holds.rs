@@ -0,0 +1,116 @@
+//! Legal holds blocking purges, media deletion and report retention
+
+use super::TestServices;
+use crate::api::ReportFormat;
+use crate::errors::AppError;
+use crate::i18n::Locale;
+use crate::models::{LegalHold, LegalHoldEntityType, LegalHoldInput};
+use crate::reports::store::{cleanup_reports, ReportKey};
+use chrono::{Duration, Utc};
+use rusqlite::params;
+
+impl TestServices {
+    fn hold(&self, entity_type: LegalHoldEntityType, entity_id: i64) -> LegalHold {
+        self.services.legal_holds.place_hold(&self.context, LegalHoldInput {
+            entity_type,
+            entity_id,
+            reason: "Crane collapse claim".to_string(),
+            reference: Some("CV-2026-114".to_string()),
+        }).unwrap()
+    }
+
+    fn release(&self, hold: &LegalHold) {
+        self.services.legal_holds.release_hold(&self.context, hold.id, "Claim settled".to_string()).unwrap();
+    }
+
+    /// Insert a photo taken during `inspection_id` and return its ID
+    fn add_media(&self, inspection_id: i64) -> i64 {
+        self.database.with_transaction(|conn| {
+            conn.execute(
+                "INSERT INTO media_files (inspection_id, file_name, file_path, file_type, mime_type, file_size)
+                 VALUES (?1, 'hook.jpg', './data/media/hook.jpg', 'image', 'image/jpeg', 1024)",
+                params![inspection_id],
+            )?;
+            Ok(conn.last_insert_rowid())
+        }).unwrap()
+    }
+}
+
+fn is_held<T>(result: Result<T, AppError>) -> bool {
+    matches!(result, Err(AppError::Validation { ref message, .. }) if message.contains("legal hold"))
+}
+
+#[tokio::test]
+async fn test_held_records_cannot_be_purged_until_released() {
+    let test = TestServices::new().await;
+    let asset = test.add_asset(test.add_location("Bay 3"), "OHC-1");
+    let inspection = test.add_inspection(asset);
+    let (services, context) = (&test.services, &test.context);
+    services.inspections.delete_inspection(context, inspection).unwrap();
+    services.assets.delete_asset(context, asset).unwrap();
+
+    // A hold on the asset covers its inspections too
+    let asset_hold = test.hold(LegalHoldEntityType::Asset, asset);
+    assert!(is_held(services.inspections.purge_inspection(context, inspection)));
+    assert!(is_held(services.assets.purge_asset(context, asset)));
+    test.release(&asset_hold);
+
+    let inspection_hold = test.hold(LegalHoldEntityType::Inspection, inspection);
+    assert!(is_held(services.inspections.purge_inspection(context, inspection)));
+    test.release(&inspection_hold);
+
+    services.inspections.purge_inspection(context, inspection).unwrap();
+    services.assets.purge_asset(context, asset).unwrap();
+}
+
+#[tokio::test]
+async fn test_media_of_held_records_cannot_be_deleted_until_released() {
+    let test = TestServices::new().await;
+    let asset = test.add_asset(test.add_location("Bay 3"), "OHC-1");
+    let inspection = test.add_inspection(asset);
+    let (first, second) = (test.add_media(inspection), test.add_media(inspection));
+    let (services, context) = (&test.services, &test.context);
+
+    let inspection_hold = test.hold(LegalHoldEntityType::Inspection, inspection);
+    assert!(is_held(services.media.delete_media_file(context, first)));
+    test.release(&inspection_hold);
+    services.media.delete_media_file(context, first).unwrap();
+
+    let asset_hold = test.hold(LegalHoldEntityType::Asset, asset);
+    assert!(is_held(services.media.delete_media_file(context, second)));
+    test.release(&asset_hold);
+    services.media.delete_media_file(context, second).unwrap();
+}
+
+#[tokio::test]
+async fn test_report_retention_keeps_reports_on_held_records() {
+    let test = TestServices::new().await;
+    let asset = test.add_asset(test.add_location("Bay 3"), "OHC-1");
+    let inspection = test.add_inspection(asset);
+    let dir = tempfile::tempdir().unwrap();
+    let generated_at = Utc::now();
+    let retention_days = test.services.settings.get_settings().unwrap().report_retention_days;
+    let after_expiry = generated_at + Duration::days(retention_days + 1);
+
+    let reports = [
+        format!("compliance_{}_20261017", asset),
+        format!("inspection_{}_20261017", inspection),
+    ];
+    let paths: Vec<_> = reports.iter().map(|report_id| {
+        let path = dir.path().join(format!("{}.pdf", report_id));
+        std::fs::write(&path, b"%PDF-1.7").unwrap();
+        let key = ReportKey::new("compliance", &ReportFormat::Pdf, Locale::default(), report_id, &asset).unwrap();
+        test.services.reports
+            .record_report(&test.context, &key, report_id, path.to_str().unwrap(), generated_at)
+            .unwrap();
+        path
+    }).collect();
+
+    let hold = test.hold(LegalHoldEntityType::Asset, asset);
+    assert_eq!(cleanup_reports(&test.services, dir.path(), after_expiry).unwrap(), 0);
+    assert!(paths.iter().all(|path| path.is_file()));
+
+    test.release(&hold);
+    assert_eq!(cleanup_reports(&test.services, dir.path(), after_expiry).unwrap(), 2);
+    assert!(paths.iter().all(|path| !path.exists()));
+}
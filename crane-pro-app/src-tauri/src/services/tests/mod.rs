@@ -7,4 +7,13 @@
 pub mod test_utils;
 
 #[cfg(test)]
-pub use test_utils::*;
\ No newline at end of file
+pub use test_utils::*;
+
+#[cfg(test)]
+mod soft_delete;
+
+#[cfg(test)]
+mod legal_holds;
+
+#[cfg(test)]
+mod bulk_update;
@@ -0,0 +1,121 @@
+//! Soft delete, restore and purge of assets, inspections and users
+
+use super::TestServices;
+use crate::errors::AppError;
+use crate::models::{QueryFilter, RecycleEntityType};
+
+#[tokio::test]
+async fn test_soft_deleted_assets_and_inspections_are_hidden_until_restored() {
+    let test = TestServices::new().await;
+    let location = test.add_location("Bay 3");
+    let kept = test.add_asset(location, "OHC-1");
+    let asset = test.add_asset(location, "OHC-2");
+    let inspection = test.add_inspection(kept);
+    let (services, context) = (&test.services, &test.context);
+
+    services.assets.delete_asset(context, asset).unwrap();
+    services.inspections.delete_inspection(context, inspection).unwrap();
+    let listed = services.assets.get_assets_by_location(location, QueryFilter::default()).unwrap();
+    assert_eq!(listed.data.iter().map(|a| a.id).collect::<Vec<_>>(), vec![kept]);
+    assert_eq!(listed.total_count, 1);
+    assert_eq!(services.assets.count_assets().unwrap(), 1);
+    assert!(services.assets.get_asset_by_id(asset).is_err());
+    assert_eq!(services.inspections.get_inspections_by_asset(kept, QueryFilter::default()).unwrap().total_count, 0);
+    assert!(services.inspections.get_inspection_by_id(inspection).is_err());
+
+    // Deleting twice finds nothing left to delete
+    assert!(matches!(services.assets.delete_asset(context, asset), Err(AppError::RecordNotFound { .. })));
+
+    assert_eq!(services.assets.restore_asset(context, asset).unwrap().id, asset);
+    assert_eq!(services.inspections.restore_inspection(context, inspection).unwrap().id, inspection);
+    assert_eq!(services.assets.get_assets_by_location(location, QueryFilter::default()).unwrap().total_count, 2);
+    assert_eq!(services.inspections.get_inspections_by_asset(kept, QueryFilter::default()).unwrap().total_count, 1);
+
+    // Restoring a record that is not deleted is refused
+    assert!(matches!(services.assets.restore_asset(context, asset), Err(AppError::RecordNotFound { .. })));
+}
+
+#[tokio::test]
+async fn test_inspection_of_a_deleted_asset_is_restored_after_its_asset() {
+    let test = TestServices::new().await;
+    let asset = test.add_asset(test.add_location("Bay 3"), "OHC-1");
+    let inspection = test.add_inspection(asset);
+    let (services, context) = (&test.services, &test.context);
+
+    services.inspections.delete_inspection(context, inspection).unwrap();
+    services.assets.delete_asset(context, asset).unwrap();
+    assert!(matches!(
+        services.inspections.restore_inspection(context, inspection),
+        Err(AppError::Validation { .. })
+    ));
+
+    services.recycle_bin.restore(context, RecycleEntityType::Asset, asset).unwrap();
+    services.recycle_bin.restore(context, RecycleEntityType::Inspection, inspection).unwrap();
+    assert_eq!(services.inspections.get_inspections_by_asset(asset, QueryFilter::default()).unwrap().total_count, 1);
+}
+
+#[tokio::test]
+async fn test_soft_deleted_users_are_hidden_until_restored() {
+    let test = TestServices::new().await;
+    let (services, context) = (&test.services, &test.context);
+    let user = test.add_user("jsmith");
+    let usernames = || -> Vec<String> {
+        services.users.get_all_users(QueryFilter::default()).unwrap().data.into_iter().map(|u| u.username).collect()
+    };
+    assert!(usernames().contains(&"jsmith".to_string()));
+
+    services.users.delete_user(context, user).unwrap();
+    assert!(!usernames().contains(&"jsmith".to_string()));
+    assert!(services.users.get_user_by_id(user).is_err());
+
+    assert_eq!(services.users.restore_user(context, user).unwrap().username, "jsmith");
+    assert!(usernames().contains(&"jsmith".to_string()));
+}
+
+#[tokio::test]
+async fn test_only_soft_deleted_records_can_be_purged() {
+    let test = TestServices::new().await;
+    let asset = test.add_asset(test.add_location("Bay 3"), "OHC-1");
+    let inspection = test.add_inspection(asset);
+    let user = test.add_user("jsmith");
+    let (services, context) = (&test.services, &test.context);
+    let not_deleted = |result| matches!(result, Err(AppError::RecordNotFound { ref entity, .. }) if entity.starts_with("Deleted"));
+
+    assert!(not_deleted(services.inspections.purge_inspection(context, inspection)));
+    assert!(not_deleted(services.assets.purge_asset(context, asset)));
+    assert!(not_deleted(services.users.purge_user(context, user)));
+    assert!(services.inspections.get_inspection_by_id(inspection).is_ok());
+    assert!(services.assets.get_asset_by_id(asset).is_ok());
+    assert!(services.users.get_user_by_id(user).is_ok());
+
+    services.inspections.delete_inspection(context, inspection).unwrap();
+    services.inspections.purge_inspection(context, inspection).unwrap();
+    services.assets.delete_asset(context, asset).unwrap();
+    services.assets.purge_asset(context, asset).unwrap();
+    services.users.delete_user(context, user).unwrap();
+    services.users.purge_user(context, user).unwrap();
+
+    // Purged records are gone for good
+    let count = |table: &str, id: i64| -> i64 {
+        let conn = test.database.get_connection().unwrap();
+        let count = conn.query_row(&format!("SELECT COUNT(*) FROM {} WHERE id = ?1", table), [id], |row| row.get(0)).unwrap();
+        test.database.return_connection(conn);
+        count
+    };
+    assert_eq!(count("inspections", inspection), 0);
+    assert_eq!(count("assets", asset), 0);
+    assert_eq!(count("users", user), 0);
+    assert!(not_deleted(services.assets.restore_asset(context, asset).map(|_| ())));
+}
+
+#[tokio::test]
+async fn test_asset_with_history_cannot_be_purged() {
+    let test = TestServices::new().await;
+    let asset = test.add_asset(test.add_location("Bay 3"), "OHC-1");
+    test.add_inspection(asset);
+    let (services, context) = (&test.services, &test.context);
+
+    services.assets.delete_asset(context, asset).unwrap();
+    assert!(matches!(services.assets.purge_asset(context, asset), Err(AppError::Validation { .. })));
+    assert_eq!(services.assets.restore_asset(context, asset).unwrap().id, asset);
+}
@@ -0,0 +1,73 @@
+//! Shared setup for service tests
+
+use crate::database::Database;
+use crate::middleware::{Permissions, RequestContext, UserSession};
+use crate::models::User;
+use crate::security::fields::FieldCipher;
+use crate::services::Services;
+use rusqlite::params;
+use std::sync::Arc;
+
+/// In-memory services with a request context signed in as the seeded admin
+pub struct TestServices {
+    pub database: Arc<Database>,
+    pub services: Services,
+    pub admin: User,
+    pub context: RequestContext,
+}
+
+impl TestServices {
+    pub async fn new() -> Self {
+        let database = Arc::new(Database::new_in_memory().await.unwrap());
+        let services = Services::init(database.clone(), Arc::new(FieldCipher::ephemeral().unwrap())).await.unwrap();
+        let admin = services.users.get_user_by_id(1).unwrap();
+        let context = RequestContext::new()
+            .with_session(UserSession::new(&admin, "setup".to_string(), Permissions::for_role(&admin.role)));
+        Self { database, services, admin, context }
+    }
+
+    /// Insert an inspector account and return its ID
+    pub fn add_user(&self, username: &str) -> i64 {
+        self.database.with_transaction(|conn| {
+            conn.execute(
+                "INSERT INTO users (username, email, password_hash, role, first_name, last_name)
+                 VALUES (?1, ?1 || '@example.com', 'x', 'Inspector', 'Test', 'Inspector')",
+                params![username],
+            )?;
+            Ok(conn.last_insert_rowid())
+        }).unwrap()
+    }
+
+    /// Insert a location and return its ID
+    pub fn add_location(&self, name: &str) -> i64 {
+        self.database.with_transaction(|conn| {
+            conn.execute("INSERT INTO locations (name, created_by) VALUES (?1, 1)", params![name])?;
+            Ok(conn.last_insert_rowid())
+        }).unwrap()
+    }
+
+    /// Insert an asset at `location_id` and return its ID
+    pub fn add_asset(&self, location_id: i64, asset_number: &str) -> i64 {
+        self.database.with_transaction(|conn| {
+            conn.execute(
+                "INSERT INTO assets (asset_number, asset_name, asset_type, location_id, created_by)
+                 VALUES (?1, ?1 || ' Overhead', 'Overhead Crane', ?2, 1)",
+                params![asset_number, location_id],
+            )?;
+            Ok(conn.last_insert_rowid())
+        }).unwrap()
+    }
+
+    /// Insert a scheduled periodic inspection of `asset_id` by the admin and
+    /// return its ID
+    pub fn add_inspection(&self, asset_id: i64) -> i64 {
+        self.database.with_transaction(|conn| {
+            conn.execute(
+                "INSERT INTO inspections (asset_id, inspector_id, inspection_type, compliance_standard, status)
+                 VALUES (?1, 1, 'Periodic', 'OSHA_1910_179', 'Scheduled')",
+                params![asset_id],
+            )?;
+            Ok(conn.last_insert_rowid())
+        }).unwrap()
+    }
+}
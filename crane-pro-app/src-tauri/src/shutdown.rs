@@ -0,0 +1,199 @@
+//! Graceful shutdown coordination
+//!
+//! When the app exits, background workers are signalled to stop and the
+//! registered flush hooks (queue drains, WAL checkpoint) run in registration
+//! order. A session marker file in the app data directory records whether
+//! the previous run got that far, so startup can tell a clean exit from a
+//! crash or forced kill.
+
+use crate::errors::{AppError, AppResult};
+use chrono::Utc;
+use log::{error, info, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tokio::sync::watch;
+
+/// Session marker file name inside the app data directory
+pub const SESSION_MARKER_FILE: &str = "session.state";
+
+const MARKER_RUNNING: &str = "running";
+const MARKER_CLEAN: &str = "clean";
+
+/// How the previous run ended, as recorded by the session marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviousShutdown {
+    /// No marker: first start with this data directory
+    FirstRun,
+    /// The previous run completed its shutdown sequence
+    Clean,
+    /// The previous run was still marked as running (crash or forced kill)
+    Unclean,
+}
+
+/// Flush hook run during shutdown
+pub type ShutdownHook = Box<dyn FnOnce() -> AppResult<()> + Send>;
+
+/// Receiver side of the shutdown signal, handed to background workers
+#[derive(Debug, Clone)]
+pub struct ShutdownSignal(watch::Receiver<bool>);
+
+impl ShutdownSignal {
+    pub fn is_shutdown(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Wait until shutdown has been signalled
+    pub async fn wait(&mut self) {
+        // An error means the coordinator is gone, which also means shutdown
+        let _ = self.0.wait_for(|stopping| *stopping).await;
+    }
+}
+
+/// Coordinates app shutdown, kept in Tauri managed state
+pub struct ShutdownCoordinator {
+    signal: watch::Sender<bool>,
+    hooks: Mutex<Vec<(String, ShutdownHook)>>,
+    marker_path: PathBuf,
+    previous: PreviousShutdown,
+    completed: AtomicBool,
+}
+
+impl ShutdownCoordinator {
+    /// Read the marker left by the previous run and mark this run as started
+    pub fn start(data_dir: &Path) -> AppResult<Self> {
+        fs::create_dir_all(data_dir)
+            .map_err(|e| AppError::file_system("create_dir", data_dir.display().to_string(), e.to_string()))?;
+        let marker_path = data_dir.join(SESSION_MARKER_FILE);
+
+        let previous = match fs::read_to_string(&marker_path) {
+            Ok(contents) if contents.split_whitespace().next() == Some(MARKER_CLEAN) => PreviousShutdown::Clean,
+            Ok(_) => PreviousShutdown::Unclean,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => PreviousShutdown::FirstRun,
+            Err(e) => return Err(e.into()),
+        };
+        write_marker(&marker_path, MARKER_RUNNING)?;
+
+        let (signal, _) = watch::channel(false);
+        Ok(Self {
+            signal,
+            hooks: Mutex::new(Vec::new()),
+            marker_path,
+            previous,
+            completed: AtomicBool::new(false),
+        })
+    }
+
+    pub fn previous_shutdown(&self) -> PreviousShutdown {
+        self.previous
+    }
+
+    /// Signal for a background worker to watch
+    pub fn subscribe(&self) -> ShutdownSignal {
+        ShutdownSignal(self.signal.subscribe())
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        *self.signal.borrow()
+    }
+
+    /// Register a flush hook; hooks run in registration order
+    pub fn register_hook<F>(&self, name: impl Into<String>, hook: F)
+    where
+        F: FnOnce() -> AppResult<()> + Send + 'static,
+    {
+        match self.hooks.lock() {
+            Ok(mut hooks) => hooks.push((name.into(), Box::new(hook))),
+            Err(_) => error!("Failed to register shutdown hook: hook list lock poisoned"),
+        }
+    }
+
+    /// Run the shutdown sequence: signal workers, run flush hooks, then
+    /// record the clean-shutdown marker. Only the first call does anything.
+    ///
+    /// Returns the number of hooks that failed. The marker is only written
+    /// as clean when every hook succeeded.
+    pub fn shutdown(&self) -> usize {
+        if self.completed.swap(true, Ordering::SeqCst) {
+            return 0;
+        }
+        info!("Shutting down: signalling background workers");
+        self.signal.send_replace(true);
+
+        let hooks = self.hooks.lock().map(|mut hooks| std::mem::take(&mut *hooks)).unwrap_or_default();
+        let mut failures = 0;
+        for (name, hook) in hooks {
+            match hook() {
+                Ok(()) => info!("Shutdown hook '{}' completed", name),
+                Err(e) => {
+                    failures += 1;
+                    error!("Shutdown hook '{}' failed: {}", name, e);
+                }
+            }
+        }
+
+        if failures > 0 {
+            warn!("Shutdown finished with {} failed hook(s); next start will report an unclean shutdown", failures);
+        } else if let Err(e) = write_marker(&self.marker_path, MARKER_CLEAN) {
+            error!("Failed to record clean shutdown: {}", e);
+        } else {
+            info!("Clean shutdown recorded");
+        }
+        failures
+    }
+}
+
+fn write_marker(path: &Path, state: &str) -> AppResult<()> {
+    fs::write(path, format!("{} {}\n", state, Utc::now().to_rfc3339()))
+        .map_err(|e| AppError::file_system("write", path.display().to_string(), e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_session_marker_detects_unclean_exit() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let first = ShutdownCoordinator::start(dir.path()).unwrap();
+        assert_eq!(first.previous_shutdown(), PreviousShutdown::FirstRun);
+        drop(first); // exits without running shutdown
+
+        let second = ShutdownCoordinator::start(dir.path()).unwrap();
+        assert_eq!(second.previous_shutdown(), PreviousShutdown::Unclean);
+        assert_eq!(second.shutdown(), 0);
+
+        let third = ShutdownCoordinator::start(dir.path()).unwrap();
+        assert_eq!(third.previous_shutdown(), PreviousShutdown::Clean);
+    }
+
+    #[test]
+    fn test_hooks_run_once_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let coordinator = ShutdownCoordinator::start(dir.path()).unwrap();
+        let signal = coordinator.subscribe();
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        for name in ["drain queue", "checkpoint"] {
+            let order = order.clone();
+            coordinator.register_hook(name, move || {
+                order.lock().unwrap().push(name);
+                Ok(())
+            });
+        }
+        coordinator.register_hook("failing", || Err(AppError::internal("flush failed")));
+
+        assert!(!signal.is_shutdown());
+        assert_eq!(coordinator.shutdown(), 1);
+        assert_eq!(coordinator.shutdown(), 0);
+        assert!(signal.is_shutdown());
+        assert_eq!(*order.lock().unwrap(), vec!["drain queue", "checkpoint"]);
+
+        // A failed hook leaves the run marked as unclean
+        let next = ShutdownCoordinator::start(dir.path()).unwrap();
+        assert_eq!(next.previous_shutdown(), PreviousShutdown::Unclean);
+    }
+}
@@ -422,6 +422,9 @@ pub mod generators {
             created_by: 1,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            criticality: AssetCriticality::Medium,
+            service_class: None,
+            fem_group: None,
         }
     }
 
@@ -435,6 +438,7 @@ pub mod generators {
             longitude: Some(-74.0060),
             description: Some("Test facility for automated testing".to_string()),
             parent_location_id: None,
+            time_zone: "America/New_York".to_string(),
             created_by: 1,
             created_at: Utc::now(),
             updated_at: Utc::now(),
@@ -458,6 +462,8 @@ pub mod generators {
             ai_analysis_results: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            time_zone: None,
+            overdue_at: None,
         }
     }
 
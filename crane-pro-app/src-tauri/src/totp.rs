@@ -0,0 +1,200 @@
+//! Time-based one-time passwords (RFC 6238)
+//!
+//! Second factor for sign-in, compatible with common authenticator apps:
+//! HMAC-SHA1, six digits and a 30 second step. Secrets are exchanged as
+//! unpadded Base32 inside an `otpauth://` URI, which the frontend renders
+//! as a QR code. Backup codes are for when the authenticator is lost; each
+//! works once.
+
+use crate::errors::{AppError, AppResult};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// Seconds each code is valid for
+pub const TOTP_STEP_SECONDS: i64 = 30;
+
+/// Digits in a code
+pub const TOTP_DIGITS: u32 = 6;
+
+/// Steps either side of the current one that are still accepted, to allow
+/// for clock drift on the phone
+pub const TOTP_ALLOWED_DRIFT_STEPS: i64 = 1;
+
+/// Backup codes issued at enrollment
+pub const BACKUP_CODE_COUNT: usize = 10;
+
+/// Bytes of secret; 160 bits as recommended for HMAC-SHA1
+const SECRET_BYTES: usize = 20;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+fn random_bytes(count: usize) -> AppResult<Vec<u8>> {
+    let mut bytes = vec![0u8; count];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| AppError::Encryption { reason: "Secure random number generator failed".to_string() })?;
+    Ok(bytes)
+}
+
+/// Unpadded RFC 4648 Base32
+pub fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    for chunk in bytes.chunks(5) {
+        let mut buffer = [0u8; 5];
+        buffer[..chunk.len()].copy_from_slice(chunk);
+        let bits = buffer.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64);
+        let symbols = (chunk.len() * 8).div_ceil(5);
+        for i in 0..symbols {
+            let index = (bits >> (35 - i * 5)) & 0x1f;
+            encoded.push(BASE32_ALPHABET[index as usize] as char);
+        }
+    }
+    encoded
+}
+
+/// Decode Base32, ignoring case, spaces and padding
+pub fn base32_decode(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 5 / 8);
+    let mut buffer = 0u64;
+    let mut bits = 0;
+    for c in text.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let value = BASE32_ALPHABET.iter().position(|&a| a as char == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u64;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+/// A new random secret, Base32 encoded
+pub fn generate_secret() -> AppResult<String> {
+    Ok(base32_encode(&random_bytes(SECRET_BYTES)?))
+}
+
+/// `otpauth://` URI for enrolling `account` in an authenticator app
+pub fn provisioning_uri(issuer: &str, account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account),
+        secret,
+        percent_encode(issuer),
+        TOTP_DIGITS,
+        TOTP_STEP_SECONDS
+    )
+}
+
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// HOTP value (RFC 4226) of `key` for `counter`
+fn hotp(key: &[u8], counter: u64, digits: u32) -> u32 {
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, key);
+    let tag = hmac::sign(&key, &counter.to_be_bytes());
+    let digest = tag.as_ref();
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]])
+        & 0x7fff_ffff;
+    binary % 10u32.pow(digits)
+}
+
+/// Time step containing `unix_time`
+pub fn time_step(unix_time: i64) -> i64 {
+    unix_time.div_euclid(TOTP_STEP_SECONDS)
+}
+
+/// The code for `secret` during time step `step`
+pub fn code_for_step(secret: &str, step: i64) -> AppResult<String> {
+    let key = base32_decode(secret)
+        .ok_or_else(|| AppError::validation("secret", "MFA secret is not valid Base32"))?;
+    Ok(format!("{:0width$}", hotp(&key, step as u64, TOTP_DIGITS), width = TOTP_DIGITS as usize))
+}
+
+/// The time step `code` is valid for at `unix_time`, allowing for drift.
+/// Steps at or before `last_used_step` are refused so a code cannot be
+/// replayed.
+pub fn verify_code(secret: &str, code: &str, unix_time: i64, last_used_step: Option<i64>) -> AppResult<Option<i64>> {
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    if code.len() != TOTP_DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(None);
+    }
+    let current = time_step(unix_time);
+    for step in current - TOTP_ALLOWED_DRIFT_STEPS..=current + TOTP_ALLOWED_DRIFT_STEPS {
+        if last_used_step.is_some_and(|last| step <= last) {
+            continue;
+        }
+        if code_for_step(secret, step)? == code {
+            return Ok(Some(step));
+        }
+    }
+    Ok(None)
+}
+
+/// Fresh backup codes in the form `XXXXX-XXXXX`
+pub fn generate_backup_codes() -> AppResult<Vec<String>> {
+    (0..BACKUP_CODE_COUNT)
+        .map(|_| {
+            let code = base32_encode(&random_bytes(7)?);
+            Ok(format!("{}-{}", &code[..5], &code[5..10]))
+        })
+        .collect()
+}
+
+/// Backup code as stored for comparison: upper case without separators
+pub fn normalize_backup_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base32_round_trip() {
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(base32_decode("mzxw6ytboi======").unwrap(), b"foobar");
+        let secret = generate_secret().unwrap();
+        assert_eq!(secret.len(), 32);
+        assert_eq!(base32_decode(&secret).unwrap().len(), SECRET_BYTES);
+        assert!(base32_decode("not base32!").is_none());
+    }
+
+    #[test]
+    fn test_rfc6238_vectors_and_replay() {
+        // RFC 6238 appendix B, SHA-1 key "12345678901234567890", last six digits
+        let secret = base32_encode(b"12345678901234567890");
+        assert_eq!(code_for_step(&secret, time_step(59)).unwrap(), "287082");
+        assert_eq!(code_for_step(&secret, time_step(1111111109)).unwrap(), "081804");
+        assert_eq!(code_for_step(&secret, time_step(1234567890)).unwrap(), "005924");
+
+        let step = verify_code(&secret, "081 804", 1111111109 + 25, None).unwrap();
+        assert_eq!(step, Some(time_step(1111111109)));
+        assert_eq!(verify_code(&secret, "081804", 1111111109, step).unwrap(), None);
+        assert_eq!(verify_code(&secret, "081804", 1111111109 + 120, None).unwrap(), None);
+        assert_eq!(verify_code(&secret, "12345", 59, None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_backup_codes_and_uri() {
+        let codes = generate_backup_codes().unwrap();
+        assert_eq!(codes.len(), BACKUP_CODE_COUNT);
+        assert_eq!(normalize_backup_code(&codes[0].to_lowercase()).len(), 10);
+        assert_eq!(
+            provisioning_uri("CranePro", "j.smith@example.com", "ABC"),
+            "otpauth://totp/CranePro:j.smith%40example.com?secret=ABC&issuer=CranePro&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+}
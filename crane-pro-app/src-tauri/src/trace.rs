@@ -0,0 +1,367 @@
+//! Request tracing
+//!
+//! Every command is given a request ID on entry, which `RequestContext`
+//! records on the command's span; that ID is the trace ID. A layer on the
+//! application logger files each event raised inside a traced span under
+//! its trace: service logs, every SQL statement with its timing, AI jobs
+//! being queued and events emitted to the frontend. The most recent traces
+//! are kept in memory so support can rebuild the timeline of a slow or
+//! failed request from the request ID the user was shown.
+
+use crate::models::{Trace, TraceEvent, TraceEventKind};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::{LookupSpan, SpanRef};
+use tracing_subscriber::Layer;
+
+/// Log target for SQL statement timings
+pub const SQL_TARGET: &str = "sql";
+
+/// Log target for AI jobs being queued
+pub const AI_JOB_TARGET: &str = "ai_job";
+
+/// Log target for events emitted to the frontend
+pub const EVENT_TARGET: &str = "event";
+
+/// Traces kept in memory; the oldest is dropped first
+pub const MAX_TRACES: usize = 500;
+
+/// Events kept per trace
+pub const MAX_TRACE_EVENTS: usize = 1000;
+
+/// Statements taking at least this long are logged as warnings
+pub const SLOW_STATEMENT: Duration = Duration::from_millis(250);
+
+/// Longest statement text kept in a trace
+const MAX_STATEMENT_CHARS: usize = 500;
+
+/// Span field holding the trace ID
+const TRACE_ID_FIELD: &str = "request_id";
+
+/// Span field holding the signed-in user
+const USER_ID_FIELD: &str = "user_id";
+
+/// Trace a span belongs to, inherited from its parent
+#[derive(Clone)]
+struct TraceId(String);
+
+/// When a span was entered, for spans that start a trace
+struct SpanStart {
+    started: Instant,
+    started_at: DateTime<Utc>,
+}
+
+/// Recent traces, newest last
+#[derive(Default)]
+pub struct TraceStore {
+    traces: Mutex<VecDeque<Trace>>,
+}
+
+impl TraceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The trace with `trace_id`, if it is still kept
+    pub fn get(&self, trace_id: &str) -> Option<Trace> {
+        let traces = self.traces.lock().ok()?;
+        traces.iter().rev().find(|trace| trace.trace_id == trace_id).cloned()
+    }
+
+    fn start(&self, trace: Trace) {
+        if let Ok(mut traces) = self.traces.lock() {
+            if traces.len() >= MAX_TRACES {
+                traces.pop_front();
+            }
+            traces.push_back(trace);
+        }
+    }
+
+    fn update(&self, trace_id: &str, update: impl FnOnce(&mut Trace)) {
+        if let Ok(mut traces) = self.traces.lock() {
+            if let Some(trace) = traces.iter_mut().rev().find(|trace| trace.trace_id == trace_id) {
+                update(trace);
+            }
+        }
+    }
+}
+
+/// Files events under the trace of the span they were raised in
+pub struct TraceLayer {
+    store: Arc<TraceStore>,
+}
+
+impl TraceLayer {
+    pub fn new(store: Arc<TraceStore>) -> Self {
+        Self { store }
+    }
+
+    fn record_fields<S>(&self, span: &SpanRef<'_, S>, fields: SpanFields)
+    where
+        S: for<'a> LookupSpan<'a>,
+    {
+        if let Some(trace_id) = fields.request_id {
+            let mut extensions = span.extensions_mut();
+            if extensions.get_mut::<TraceId>().is_some_and(|current| current.0 == trace_id) {
+                return;
+            }
+            let started_at = extensions.get_mut::<SpanStart>().map(|start| start.started_at).unwrap_or_else(Utc::now);
+            extensions.replace(TraceId(trace_id.clone()));
+            self.store.start(Trace {
+                trace_id,
+                command: span.name().to_string(),
+                user_id: fields.user_id,
+                started_at,
+                duration_ms: None,
+                failed: false,
+                sql_statements: 0,
+                sql_ms: 0,
+                dropped_events: 0,
+                events: Vec::new(),
+                ai_jobs: Vec::new(),
+            });
+        } else if let Some(user_id) = fields.user_id {
+            if let Some(trace_id) = span.extensions().get::<TraceId>() {
+                self.store.update(&trace_id.0, |trace| trace.user_id = Some(user_id));
+            }
+        }
+    }
+}
+
+impl<S> Layer<S> for TraceLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let inherited = span.parent().and_then(|parent| parent.extensions().get::<TraceId>().cloned());
+        {
+            let mut extensions = span.extensions_mut();
+            extensions.insert(SpanStart { started: Instant::now(), started_at: Utc::now() });
+            if let Some(trace_id) = inherited {
+                extensions.insert(trace_id);
+            }
+        }
+        let mut fields = SpanFields::default();
+        attrs.record(&mut fields);
+        self.record_fields(&span, fields);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = SpanFields::default();
+        values.record(&mut fields);
+        self.record_fields(&span, fields);
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(trace_id) = ctx.event_scope(event)
+            .and_then(|mut scope| scope.find_map(|span| span.extensions().get::<TraceId>().cloned()))
+        else {
+            return;
+        };
+        let mut fields = EventFields::default();
+        event.record(&mut fields);
+        let metadata = event.metadata();
+        // Records from the `log` crate carry their real target in a field
+        let target = fields.target.unwrap_or_else(|| metadata.target().to_string());
+        let kind = match target.as_str() {
+            SQL_TARGET => TraceEventKind::Sql,
+            AI_JOB_TARGET => TraceEventKind::AiJob,
+            EVENT_TARGET => TraceEventKind::Event,
+            _ => TraceEventKind::Log,
+        };
+        let level = *metadata.level();
+        let now = Utc::now();
+        self.store.update(&trace_id.0, |trace| {
+            if level == Level::ERROR {
+                trace.failed = true;
+            }
+            if kind == TraceEventKind::Sql {
+                trace.sql_statements += 1;
+                trace.sql_ms += fields.duration_ms.unwrap_or(0);
+            }
+            if trace.events.len() >= MAX_TRACE_EVENTS {
+                trace.dropped_events += 1;
+                return;
+            }
+            trace.events.push(TraceEvent {
+                offset_ms: (now - trace.started_at).num_milliseconds(),
+                kind,
+                level: level.to_string(),
+                target,
+                message: fields.message,
+                duration_ms: fields.duration_ms,
+            });
+        });
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        // Only the span that started a trace ends it
+        if span.parent().is_some_and(|parent| parent.extensions().get::<TraceId>().is_some()) {
+            return;
+        }
+        let extensions = span.extensions();
+        if let (Some(trace_id), Some(start)) = (extensions.get::<TraceId>(), extensions.get::<SpanStart>()) {
+            let duration_ms = start.started.elapsed().as_millis() as u64;
+            self.store.update(&trace_id.0, |trace| trace.duration_ms = Some(duration_ms));
+        }
+    }
+}
+
+/// Trace fields recorded on a span
+#[derive(Default)]
+struct SpanFields {
+    request_id: Option<String>,
+    user_id: Option<i64>,
+}
+
+impl Visit for SpanFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == TRACE_ID_FIELD {
+            self.request_id = Some(value.to_string());
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        if field.name() == USER_ID_FIELD {
+            self.user_id = Some(value);
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
+
+/// Fields of an event that go into a trace
+#[derive(Default)]
+struct EventFields {
+    message: String,
+    target: Option<String>,
+    duration_ms: Option<u64>,
+}
+
+impl Visit for EventFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message = value.to_string(),
+            "log.target" => self.target = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "duration_ms" {
+            self.duration_ms = Some(value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        }
+    }
+}
+
+/// Trace ID of the request being handled by the current span, if any
+pub fn current_trace_id() -> Option<String> {
+    let id = tracing::Span::current().id()?;
+    tracing::dispatcher::get_default(|dispatch| {
+        let registry = dispatch.downcast_ref::<tracing_subscriber::Registry>()?;
+        let span = registry.span(&id)?;
+        let trace_id = span.extensions().get::<TraceId>().map(|trace_id| trace_id.0.clone());
+        trace_id
+    })
+}
+
+/// Statement text on one line, cut to [`MAX_STATEMENT_CHARS`]
+fn compact_statement(statement: &str) -> String {
+    let mut compact = statement.split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Some((cut, _)) = compact.char_indices().nth(MAX_STATEMENT_CHARS) {
+        compact.truncate(cut);
+        compact.push('…');
+    }
+    compact
+}
+
+/// Profile callback set on every database connection
+pub fn record_statement(statement: &str, duration: Duration) {
+    let duration_ms = duration.as_millis() as u64;
+    if duration >= SLOW_STATEMENT {
+        tracing::warn!(target: SQL_TARGET, duration_ms, "Slow statement took {} ms: {}", duration_ms, compact_statement(statement));
+    } else {
+        tracing::debug!(target: SQL_TARGET, duration_ms, "{}", compact_statement(statement));
+    }
+}
+
+/// An event payload with the trace of the request that raised it
+#[derive(Debug, Clone, Serialize)]
+pub struct Traced<T> {
+    pub trace_id: Option<String>,
+    #[serde(flatten)]
+    pub payload: T,
+}
+
+/// Emit an event to the frontend, tagged with the current trace
+pub fn emit<T: Serialize + Clone>(app: &AppHandle, event: &str, payload: T) -> tauri::Result<()> {
+    tracing::debug!(target: EVENT_TARGET, "Emitting {}", event);
+    app.emit(event, Traced { trace_id: current_trace_id(), payload })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_trace_collects_the_timeline_of_a_request() {
+        let store = Arc::new(TraceStore::new());
+        let subscriber = tracing_subscriber::registry().with(TraceLayer::new(store.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("Outside any request");
+            let span = tracing::info_span!("delete_asset_command",
+                request_id = tracing::field::Empty, user_id = tracing::field::Empty);
+            let _entered = span.enter();
+            span.record("request_id", "trace-1");
+            span.record("user_id", 7);
+
+            tracing::info_span!("nested").in_scope(|| {
+                assert_eq!(current_trace_id().as_deref(), Some("trace-1"));
+                record_statement("SELECT *\n    FROM assets", Duration::from_millis(3));
+            });
+            tracing::info!(target: AI_JOB_TARGET, "Queued media file 4");
+            tracing::error!("Asset is in use");
+        });
+
+        let trace = store.get("trace-1").unwrap();
+        assert_eq!(trace.command, "delete_asset_command");
+        assert_eq!(trace.user_id, Some(7));
+        assert!(trace.failed);
+        assert!(trace.duration_ms.is_some());
+        assert_eq!((trace.sql_statements, trace.sql_ms), (1, 3));
+        let kinds: Vec<_> = trace.events.iter().map(|event| event.kind).collect();
+        assert_eq!(kinds, [TraceEventKind::Sql, TraceEventKind::AiJob, TraceEventKind::Log]);
+        assert_eq!(trace.events[0].message, "SELECT * FROM assets");
+        assert!(store.get("unknown").is_none());
+
+        let payload = serde_json::to_value(Traced { trace_id: Some("trace-1".to_string()), payload: trace.events[1].clone() }).unwrap();
+        assert_eq!(payload["trace_id"], "trace-1");
+        assert_eq!(payload["kind"], "AiJob");
+    }
+}
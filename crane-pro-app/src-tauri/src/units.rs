@@ -0,0 +1,544 @@
+//! Unit conversion for crane capacities and measurements
+//!
+//! Capacities are stored as a value plus a free-text unit. This module gives
+//! those units a typed model (metric tonnes, US short tons, kilograms and
+//! pounds for capacity; millimetres and inches for length) with conversion
+//! helpers used by asset validation, capacity search ("over 10 t" matches a
+//! 22,000 lb crane) and report formatting.
+
+use crate::errors::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Largest rated capacity accepted for a single asset, in kilograms (25,000 t)
+pub const MAX_CAPACITY_KG: f64 = 25_000_000.0;
+
+// =============================================================================
+// Capacity
+// =============================================================================
+
+/// Units a rated capacity can be expressed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapacityUnit {
+    /// Metric tonne (1,000 kg)
+    Tonne,
+    /// US short ton (2,000 lb)
+    UsTon,
+    Kilogram,
+    Pound,
+}
+
+impl CapacityUnit {
+    pub const ALL: [CapacityUnit; 4] = [
+        CapacityUnit::Tonne,
+        CapacityUnit::UsTon,
+        CapacityUnit::Kilogram,
+        CapacityUnit::Pound,
+    ];
+
+    /// Canonical symbol stored in `assets.capacity_unit`
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            CapacityUnit::Tonne => "t",
+            CapacityUnit::UsTon => "US ton",
+            CapacityUnit::Kilogram => "kg",
+            CapacityUnit::Pound => "lb",
+        }
+    }
+
+    /// Number of kilograms in one unit
+    pub fn kilograms_per_unit(&self) -> f64 {
+        match self {
+            CapacityUnit::Tonne => 1000.0,
+            CapacityUnit::UsTon => 907.184_74,
+            CapacityUnit::Kilogram => 1.0,
+            CapacityUnit::Pound => 0.453_592_37,
+        }
+    }
+
+    /// Accepted spellings, lowercase. Bare "ton"/"tons" is read as US tons,
+    /// matching how capacities are written on US nameplates.
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            CapacityUnit::Tonne => &["t", "tonne", "tonnes", "metric ton", "metric tons", "mt"],
+            CapacityUnit::UsTon => &["us ton", "us tons", "ton", "tons", "short ton", "short tons", "tn"],
+            CapacityUnit::Kilogram => &["kg", "kgs", "kilogram", "kilograms"],
+            CapacityUnit::Pound => &["lb", "lbs", "pound", "pounds"],
+        }
+    }
+}
+
+impl fmt::Display for CapacityUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+impl std::str::FromStr for CapacityUnit {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = normalize_unit(s);
+        CapacityUnit::ALL
+            .into_iter()
+            .find(|unit| unit.aliases().contains(&normalized.as_str()))
+            .ok_or_else(|| AppError::validation("capacity_unit", format!("Unknown capacity unit: {}", s)))
+    }
+}
+
+/// A rated capacity with its unit
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Capacity {
+    pub value: f64,
+    pub unit: CapacityUnit,
+}
+
+impl Capacity {
+    pub fn new(value: f64, unit: CapacityUnit) -> Self {
+        Self { value, unit }
+    }
+
+    /// Build a capacity from the stored asset columns.
+    ///
+    /// Returns `Ok(None)` when either column is missing, so legacy assets
+    /// without a unit are left alone.
+    pub fn from_parts(value: Option<f64>, unit: Option<&str>) -> AppResult<Option<Self>> {
+        match (value, unit) {
+            (Some(value), Some(unit)) => Ok(Some(Self::new(value, unit.parse()?))),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn to_kilograms(&self) -> f64 {
+        self.value * self.unit.kilograms_per_unit()
+    }
+
+    pub fn convert_to(&self, unit: CapacityUnit) -> Self {
+        Self::new(self.to_kilograms() / unit.kilograms_per_unit(), unit)
+    }
+
+    /// Check the capacity is positive and within [`MAX_CAPACITY_KG`]
+    pub fn validate(&self) -> AppResult<()> {
+        if !self.value.is_finite() || self.value <= 0.0 {
+            return Err(AppError::validation("capacity", "Capacity must be greater than 0"));
+        }
+        if self.to_kilograms() > MAX_CAPACITY_KG {
+            return Err(AppError::OutOfRange {
+                field: "capacity".to_string(),
+                value: self.to_string(),
+                min: "0".to_string(),
+                max: Capacity::new(MAX_CAPACITY_KG, CapacityUnit::Kilogram)
+                    .convert_to(self.unit)
+                    .to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Format alongside the customary counterpart unit: metric capacities
+    /// are shown with pounds and US capacities with tonnes
+    pub fn format_dual(&self) -> String {
+        match self.unit {
+            CapacityUnit::Tonne | CapacityUnit::Kilogram => self.format_with(CapacityUnit::Pound),
+            CapacityUnit::UsTon | CapacityUnit::Pound => self.format_with(CapacityUnit::Tonne),
+        }
+    }
+
+    /// Format with a conversion in brackets, e.g. `10 t (11.02 US ton)`
+    pub fn format_with(&self, other: CapacityUnit) -> String {
+        if other == self.unit {
+            return self.to_string();
+        }
+        format!("{} ({})", self, self.convert_to(other))
+    }
+}
+
+impl fmt::Display for Capacity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", format_number(self.value), self.unit)
+    }
+}
+
+impl std::str::FromStr for Capacity {
+    type Err = AppError;
+
+    /// Parse values such as `10 t`, `22,000 lbs` or `5.5US tons`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (value, unit) = split_value_and_unit(s)
+            .ok_or_else(|| AppError::validation("capacity", format!("Invalid capacity: {}", s)))?;
+        Ok(Self::new(value, unit.parse()?))
+    }
+}
+
+// =============================================================================
+// Length
+// =============================================================================
+
+/// Units a length measurement can be expressed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LengthUnit {
+    Millimeter,
+    Inch,
+}
+
+impl LengthUnit {
+    pub const ALL: [LengthUnit; 2] = [LengthUnit::Millimeter, LengthUnit::Inch];
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            LengthUnit::Millimeter => "mm",
+            LengthUnit::Inch => "in",
+        }
+    }
+
+    /// Number of millimetres in one unit
+    pub fn millimeters_per_unit(&self) -> f64 {
+        match self {
+            LengthUnit::Millimeter => 1.0,
+            LengthUnit::Inch => 25.4,
+        }
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            LengthUnit::Millimeter => &["mm", "millimeter", "millimeters", "millimetre", "millimetres"],
+            LengthUnit::Inch => &["in", "inch", "inches", "\""],
+        }
+    }
+}
+
+impl fmt::Display for LengthUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+impl std::str::FromStr for LengthUnit {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = normalize_unit(s);
+        LengthUnit::ALL
+            .into_iter()
+            .find(|unit| unit.aliases().contains(&normalized.as_str()))
+            .ok_or_else(|| AppError::validation("unit", format!("Unknown length unit: {}", s)))
+    }
+}
+
+/// A length measurement, e.g. hook throat opening or wire rope diameter
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Length {
+    pub value: f64,
+    pub unit: LengthUnit,
+}
+
+impl Length {
+    pub fn new(value: f64, unit: LengthUnit) -> Self {
+        Self { value, unit }
+    }
+
+    pub fn to_millimeters(&self) -> f64 {
+        self.value * self.unit.millimeters_per_unit()
+    }
+
+    pub fn convert_to(&self, unit: LengthUnit) -> Self {
+        Self::new(self.to_millimeters() / unit.millimeters_per_unit(), unit)
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", format_number(self.value), self.unit)
+    }
+}
+
+impl std::str::FromStr for Length {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (value, unit) = split_value_and_unit(s)
+            .ok_or_else(|| AppError::validation("measurement", format!("Invalid measurement: {}", s)))?;
+        Ok(Self::new(value, unit.parse()?))
+    }
+}
+
+// =============================================================================
+// Capacity search
+// =============================================================================
+
+/// Comparison used by a capacity search filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapacityComparison {
+    GreaterThan,
+    AtLeast,
+    LessThan,
+    AtMost,
+}
+
+impl CapacityComparison {
+    pub fn sql_operator(&self) -> &'static str {
+        match self {
+            CapacityComparison::GreaterThan => ">",
+            CapacityComparison::AtLeast => ">=",
+            CapacityComparison::LessThan => "<",
+            CapacityComparison::AtMost => "<=",
+        }
+    }
+}
+
+/// Relative slack applied to search thresholds. Nameplate ratings are
+/// rounded when converted (a 10 t hoist is usually rated 22,000 lb), so a
+/// strict comparison would drop cranes that are equivalent in practice.
+pub const CAPACITY_MATCH_TOLERANCE: f64 = 0.01;
+
+/// Capacity condition extracted from a free-text search query
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CapacityFilter {
+    pub comparison: CapacityComparison,
+    pub capacity: Capacity,
+}
+
+impl CapacityFilter {
+    /// Threshold in kilograms, widened by [`CAPACITY_MATCH_TOLERANCE`]
+    pub fn threshold_kg(&self) -> f64 {
+        let threshold = self.capacity.to_kilograms();
+        match self.comparison {
+            CapacityComparison::GreaterThan | CapacityComparison::AtLeast => threshold * (1.0 - CAPACITY_MATCH_TOLERANCE),
+            CapacityComparison::LessThan | CapacityComparison::AtMost => threshold * (1.0 + CAPACITY_MATCH_TOLERANCE),
+        }
+    }
+
+    pub fn matches(&self, capacity: &Capacity) -> bool {
+        let actual = capacity.to_kilograms();
+        let threshold = self.threshold_kg();
+        match self.comparison {
+            CapacityComparison::GreaterThan => actual > threshold,
+            CapacityComparison::AtLeast => actual >= threshold,
+            CapacityComparison::LessThan => actual < threshold,
+            CapacityComparison::AtMost => actual <= threshold,
+        }
+    }
+}
+
+/// Comparison phrases recognised in search queries, longest first
+const COMPARISON_PHRASES: &[(&str, CapacityComparison)] = &[
+    ("more than", CapacityComparison::GreaterThan),
+    ("greater than", CapacityComparison::GreaterThan),
+    ("at least", CapacityComparison::AtLeast),
+    ("less than", CapacityComparison::LessThan),
+    ("at most", CapacityComparison::AtMost),
+    ("over", CapacityComparison::GreaterThan),
+    ("above", CapacityComparison::GreaterThan),
+    (">=", CapacityComparison::AtLeast),
+    (">", CapacityComparison::GreaterThan),
+    ("min", CapacityComparison::AtLeast),
+    ("under", CapacityComparison::LessThan),
+    ("below", CapacityComparison::LessThan),
+    ("<=", CapacityComparison::AtMost),
+    ("<", CapacityComparison::LessThan),
+    ("max", CapacityComparison::AtMost),
+];
+
+/// Words that describe every asset. They are dropped from the text left over
+/// after a capacity filter, which would otherwise only match assets with the
+/// word in their name or type.
+const GENERIC_ASSET_WORDS: &[&str] = &["crane", "cranes", "asset", "assets", "equipment"];
+
+/// Split a search query into an optional capacity filter and the remaining text.
+///
+/// `"cranes over 10 t"` yields a `> 10 t` filter and no text, since "cranes"
+/// alone would leave out every asset not named as one. Queries without a
+/// recognisable comparison and capacity are returned unchanged.
+pub fn extract_capacity_filter(query: &str) -> (Option<CapacityFilter>, String) {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let lowered: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+
+    for start in 0..words.len() {
+        for (phrase, comparison) in COMPARISON_PHRASES {
+            let phrase_words: Vec<&str> = phrase.split(' ').collect();
+            let end = start + phrase_words.len();
+            if end > words.len() || lowered[start..end] != phrase_words[..] {
+                continue;
+            }
+
+            // Try the longest capacity expression first ("10 US tons" before "10 US")
+            for len in (1..=3).rev() {
+                if end + len > words.len() {
+                    continue;
+                }
+                if let Ok(capacity) = words[end..end + len].join(" ").parse::<Capacity>() {
+                    let remainder = words[..start]
+                        .iter()
+                        .chain(words[end + len..].iter())
+                        .copied()
+                        .filter(|word| !GENERIC_ASSET_WORDS.contains(&word.to_lowercase().as_str()))
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let filter = CapacityFilter { comparison: *comparison, capacity };
+                    return (Some(filter), remainder);
+                }
+            }
+        }
+    }
+
+    (None, query.trim().to_string())
+}
+
+/// SQL expression converting a value/unit column pair to kilograms.
+///
+/// Unknown or missing units evaluate to `NULL`, so such rows never match a
+/// capacity comparison.
+pub fn capacity_kg_sql(value_column: &str, unit_column: &str) -> String {
+    let mut sql = String::from("CASE");
+    for unit in CapacityUnit::ALL {
+        let aliases = unit
+            .aliases()
+            .iter()
+            .map(|alias| format!("'{}'", alias))
+            .collect::<Vec<_>>()
+            .join(", ");
+        sql.push_str(&format!(
+            " WHEN LOWER(TRIM({})) IN ({}) THEN {} * {}",
+            unit_column, aliases, value_column, unit.kilograms_per_unit()
+        ));
+    }
+    sql.push_str(" ELSE NULL END");
+    sql
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn normalize_unit(s: &str) -> String {
+    s.trim()
+        .trim_end_matches('.')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Split `"22,000 lbs"` or `"10t"` into a number and the unit text
+fn split_value_and_unit(s: &str) -> Option<(f64, &str)> {
+    let s = s.trim();
+    let split_at = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split_at);
+    let value = number.replace(',', "").parse::<f64>().ok()?;
+    let unit = unit.trim();
+    if unit.is_empty() {
+        return None;
+    }
+    Some((value, unit))
+}
+
+/// Format a value with up to two decimals and thousands separators
+pub fn format_number(value: f64) -> String {
+    let rounded = format!("{:.2}", value);
+    let (integer, fraction) = rounded.split_once('.').unwrap_or((&rounded, ""));
+    let (sign, digits) = match integer.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", integer),
+    };
+
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        format!("{}{}", sign, grouped)
+    } else {
+        format!("{}{}.{}", sign, grouped, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capacity_parsing_and_conversion() {
+        let capacity: Capacity = "22,000 lbs".parse().unwrap();
+        assert_eq!(capacity.unit, CapacityUnit::Pound);
+        assert!((capacity.convert_to(CapacityUnit::Tonne).value - 9.979).abs() < 0.001);
+
+        assert_eq!("10t".parse::<Capacity>().unwrap(), Capacity::new(10.0, CapacityUnit::Tonne));
+        assert_eq!("tons".parse::<CapacityUnit>().unwrap(), CapacityUnit::UsTon);
+        assert!("furlongs".parse::<CapacityUnit>().is_err());
+        assert_eq!(Capacity::new(10.0, CapacityUnit::Tonne).format_with(CapacityUnit::Pound), "10 t (22,046.23 lb)");
+    }
+
+    #[test]
+    fn test_length_conversion() {
+        let length: Length = "2 in".parse().unwrap();
+        assert_eq!(length.convert_to(LengthUnit::Millimeter).to_string(), "50.8 mm");
+    }
+
+    #[test]
+    fn test_extract_capacity_filter() {
+        let (filter, text) = extract_capacity_filter("cranes over 10 t");
+        let filter = filter.unwrap();
+        assert_eq!(filter.comparison, CapacityComparison::GreaterThan);
+        assert_eq!(text, "");
+        assert!(filter.matches(&"22,000 lbs".parse().unwrap()));
+        assert!(!filter.matches(&"20,000 lbs".parse().unwrap()));
+
+        let (filter, text) = extract_capacity_filter("gantry at most 5 US tons north");
+        assert_eq!(filter.unwrap().capacity, Capacity::new(5.0, CapacityUnit::UsTon));
+        assert_eq!(text, "gantry north");
+
+        let (filter, text) = extract_capacity_filter("overhead crane");
+        assert!(filter.is_none());
+        assert_eq!(text, "overhead crane");
+    }
+
+    #[tokio::test]
+    async fn test_capacity_search_and_legacy_units() {
+        use crate::middleware::{Permissions, RequestContext, UserSession};
+        use crate::models::QueryFilter;
+        use std::sync::Arc;
+
+        let database = Arc::new(crate::database::Database::new_in_memory().await.unwrap());
+        let cipher = Arc::new(crate::security::fields::FieldCipher::ephemeral().unwrap());
+        let services = crate::services::Services::init(database.clone(), cipher).await.unwrap();
+        let admin = services.users.get_user_by_id(1).unwrap();
+        let context = RequestContext::new()
+            .with_session(UserSession::new(&admin, "setup".to_string(), Permissions::for_role(&admin.role)));
+        let crane_id = database.with_transaction(|conn| {
+            conn.execute("INSERT INTO locations (name, created_by) VALUES ('Bay 3', 1)", [])?;
+            Ok(conn.query_row(
+                "INSERT INTO assets (asset_number, asset_name, asset_type, capacity, capacity_unit, location_id, created_by)
+                 VALUES ('OHC-1', 'Bay 3 Overhead', 'Overhead Crane', 22000, 'lbs', last_insert_rowid(), 1)
+                 RETURNING id",
+                [],
+                |row| row.get::<_, i64>(0),
+            )?)
+        }).unwrap();
+
+        let found = services.assets.search_assets("cranes over 10 t".to_string(), QueryFilter::default()).unwrap();
+        assert_eq!(found.data.iter().map(|asset| asset.id).collect::<Vec<_>>(), vec![crane_id]);
+
+        // A unit from before units were typed survives a change to the value
+        database.with_transaction(|conn| {
+            conn.execute("UPDATE assets SET capacity_unit = 'tonnes (metric)' WHERE id = ?1", [crane_id])?;
+            Ok(())
+        }).unwrap();
+        let updates = serde_json::from_value(serde_json::json!({ "capacity": 12.0 })).unwrap();
+        assert_eq!(services.assets.bulk_update_assets(&context, &[crane_id], updates).unwrap().succeeded, 1);
+        let updated = services.assets.get_asset_by_id(crane_id).unwrap();
+        assert_eq!(updated.capacity, Some(12.0));
+        assert_eq!(updated.capacity_unit.as_deref(), Some("tonnes (metric)"));
+
+        let updates = serde_json::from_value(serde_json::json!({ "capacity_unit": "furlongs" })).unwrap();
+        assert_eq!(services.assets.bulk_update_assets(&context, &[crane_id], updates).unwrap().failed, 1);
+    }
+}
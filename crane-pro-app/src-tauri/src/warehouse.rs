@@ -0,0 +1,382 @@
+//! Data warehouse extracts
+//!
+//! BI tools such as Power BI load a star schema more readily than the
+//! application tables. Each extract is a directory of one file per table:
+//!
+//! - `fact_inspections`: one row per inspection, with item and finding counts
+//! - `fact_findings`: one row per inspection item with a finding
+//! - `dim_assets`: assets with their location folded in
+//! - `dim_dates`: every calendar day between the earliest and latest date
+//!   referenced by the facts
+//!
+//! Facts refer to dates by a `YYYYMMDD` integer key. Extracts are written
+//! under `extract-<timestamp>` in the configured directory, and
+//! `manifest.json` beside them always describes the latest one, so a report
+//! can be pointed at the manifest rather than a changing path. Only the most
+//! recent [`WAREHOUSE_EXTRACTS_KEPT`] extracts are kept.
+
+use crate::errors::{AppError, AppResult};
+use crate::export::{ExportFormat, ExportRecord};
+use crate::services::WarehouseExportService;
+use crate::shutdown::ShutdownSignal;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use log::{debug, error, info};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Name of the file describing the latest extract
+pub const WAREHOUSE_MANIFEST_FILE: &str = "manifest.json";
+
+/// Extract directories kept; older ones are removed after each export
+pub const WAREHOUSE_EXTRACTS_KEPT: usize = 7;
+
+/// How often the scheduler checks whether an extract is due
+pub const WAREHOUSE_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Prefix of extract directory names
+const EXTRACT_PREFIX: &str = "extract-";
+
+/// `YYYYMMDD` key of a date in the date dimension
+pub fn date_key(date: NaiveDate) -> i64 {
+    date.year() as i64 * 10_000 + date.month() as i64 * 100 + date.day() as i64
+}
+
+fn opt<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// One row of `fact_inspections`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InspectionFact {
+    pub inspection_key: i64,
+    pub asset_key: i64,
+    pub inspector_id: i64,
+    pub scheduled_date_key: Option<i64>,
+    pub completed_date_key: Option<i64>,
+    pub inspection_type: String,
+    pub status: String,
+    pub overall_condition: Option<String>,
+    pub condition_score: Option<u8>,
+    pub item_count: i64,
+    pub compliant_item_count: i64,
+    pub finding_count: i64,
+}
+
+impl ExportRecord for InspectionFact {
+    fn csv_header() -> &'static [&'static str] {
+        &[
+            "inspection_key", "asset_key", "inspector_id", "scheduled_date_key", "completed_date_key",
+            "inspection_type", "status", "overall_condition", "condition_score", "item_count",
+            "compliant_item_count", "finding_count",
+        ]
+    }
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.inspection_key.to_string(),
+            self.asset_key.to_string(),
+            self.inspector_id.to_string(),
+            opt(&self.scheduled_date_key),
+            opt(&self.completed_date_key),
+            self.inspection_type.clone(),
+            self.status.clone(),
+            opt(&self.overall_condition),
+            opt(&self.condition_score),
+            self.item_count.to_string(),
+            self.compliant_item_count.to_string(),
+            self.finding_count.to_string(),
+        ]
+    }
+}
+
+/// One row of `fact_findings`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindingFact {
+    pub finding_key: i64,
+    pub inspection_key: i64,
+    pub asset_key: i64,
+    /// Day the inspection was carried out, or the item was recorded
+    pub date_key: i64,
+    pub item_category: String,
+    pub item_name: String,
+    pub severity: Option<String>,
+    /// 0 (Low) to 3 (Critical)
+    pub severity_rank: Option<u8>,
+    pub is_compliant: Option<bool>,
+    pub condition: Option<String>,
+    pub has_corrective_action: bool,
+}
+
+impl ExportRecord for FindingFact {
+    fn csv_header() -> &'static [&'static str] {
+        &[
+            "finding_key", "inspection_key", "asset_key", "date_key", "item_category", "item_name",
+            "severity", "severity_rank", "is_compliant", "condition", "has_corrective_action",
+        ]
+    }
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.finding_key.to_string(),
+            self.inspection_key.to_string(),
+            self.asset_key.to_string(),
+            self.date_key.to_string(),
+            self.item_category.clone(),
+            self.item_name.clone(),
+            opt(&self.severity),
+            opt(&self.severity_rank),
+            opt(&self.is_compliant),
+            opt(&self.condition),
+            self.has_corrective_action.to_string(),
+        ]
+    }
+}
+
+/// One row of `dim_assets`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetDimension {
+    pub asset_key: i64,
+    pub asset_number: String,
+    pub asset_name: String,
+    pub asset_type: String,
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    pub capacity: Option<f64>,
+    pub capacity_unit: Option<String>,
+    pub status: String,
+    pub criticality: Option<String>,
+    pub installation_date: Option<NaiveDate>,
+    pub location_id: i64,
+    pub location_name: String,
+    pub is_deleted: bool,
+}
+
+impl ExportRecord for AssetDimension {
+    fn csv_header() -> &'static [&'static str] {
+        &[
+            "asset_key", "asset_number", "asset_name", "asset_type", "manufacturer", "model", "capacity",
+            "capacity_unit", "status", "criticality", "installation_date", "location_id", "location_name",
+            "is_deleted",
+        ]
+    }
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.asset_key.to_string(),
+            self.asset_number.clone(),
+            self.asset_name.clone(),
+            self.asset_type.clone(),
+            opt(&self.manufacturer),
+            opt(&self.model),
+            opt(&self.capacity),
+            opt(&self.capacity_unit),
+            self.status.clone(),
+            opt(&self.criticality),
+            opt(&self.installation_date),
+            self.location_id.to_string(),
+            self.location_name.clone(),
+            self.is_deleted.to_string(),
+        ]
+    }
+}
+
+/// One row of `dim_dates`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DateDimension {
+    pub date_key: i64,
+    pub date: NaiveDate,
+    pub year: i32,
+    pub quarter: u32,
+    pub month: u32,
+    pub month_name: String,
+    pub day: u32,
+    /// ISO day of the week, 1 (Monday) to 7 (Sunday)
+    pub day_of_week: u32,
+    pub day_name: String,
+    pub iso_week: u32,
+    pub is_weekend: bool,
+}
+
+impl DateDimension {
+    pub fn new(date: NaiveDate) -> Self {
+        Self {
+            date_key: date_key(date),
+            date,
+            year: date.year(),
+            quarter: date.month0() / 3 + 1,
+            month: date.month(),
+            month_name: date.format("%B").to_string(),
+            day: date.day(),
+            day_of_week: date.weekday().number_from_monday(),
+            day_name: date.format("%A").to_string(),
+            iso_week: date.iso_week().week(),
+            is_weekend: date.weekday().number_from_monday() >= 6,
+        }
+    }
+}
+
+impl ExportRecord for DateDimension {
+    fn csv_header() -> &'static [&'static str] {
+        &[
+            "date_key", "date", "year", "quarter", "month", "month_name", "day", "day_of_week", "day_name",
+            "iso_week", "is_weekend",
+        ]
+    }
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.date_key.to_string(),
+            self.date.to_string(),
+            self.year.to_string(),
+            self.quarter.to_string(),
+            self.month.to_string(),
+            self.month_name.clone(),
+            self.day.to_string(),
+            self.day_of_week.to_string(),
+            self.day_name.clone(),
+            self.iso_week.to_string(),
+            self.is_weekend.to_string(),
+        ]
+    }
+}
+
+/// Earliest and latest dates seen while writing the facts
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DateRange {
+    bounds: Option<(NaiveDate, NaiveDate)>,
+}
+
+impl DateRange {
+    pub fn include(&mut self, date: NaiveDate) {
+        self.bounds = Some(match self.bounds {
+            Some((first, last)) => (first.min(date), last.max(date)),
+            None => (date, date),
+        });
+    }
+
+    /// Every day in the range, in order
+    pub fn days(&self) -> impl Iterator<Item = NaiveDate> {
+        let (first, last) = match self.bounds {
+            Some(bounds) => bounds,
+            // An empty range yields nothing
+            None => (NaiveDate::MAX, NaiveDate::MIN),
+        };
+        first.iter_days().take_while(move |date| *date <= last)
+    }
+}
+
+/// Table written to an extract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarehouseTable {
+    pub name: String,
+    pub file_name: String,
+    pub rows: u64,
+}
+
+/// Description of a completed extract, also written as `manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarehouseManifest {
+    pub generated_at: DateTime<Utc>,
+    pub format: ExportFormat,
+    /// Directory holding the table files
+    pub extract_directory: String,
+    pub tables: Vec<WarehouseTable>,
+}
+
+/// Name of the directory for an extract taken at `generated_at`
+pub fn extract_directory_name(generated_at: DateTime<Utc>) -> String {
+    format!("{}{}", EXTRACT_PREFIX, generated_at.format("%Y%m%dT%H%M%SZ"))
+}
+
+/// Manifest of the latest extract under `root`, if there is one
+pub fn read_manifest(root: &Path) -> AppResult<Option<WarehouseManifest>> {
+    let path = root.join(WAREHOUSE_MANIFEST_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::file_system("read", path.display().to_string(), e.to_string()))?;
+    Ok(Some(serde_json::from_str(&text)?))
+}
+
+/// Replace the manifest under `root`, via a temporary file so readers never
+/// see it half written
+pub fn write_manifest(root: &Path, manifest: &WarehouseManifest) -> AppResult<()> {
+    let path = root.join(WAREHOUSE_MANIFEST_FILE);
+    let partial = root.join(format!("{}.tmp", WAREHOUSE_MANIFEST_FILE));
+    std::fs::write(&partial, serde_json::to_vec_pretty(manifest)?)?;
+    std::fs::rename(&partial, &path)
+        .map_err(|e| AppError::file_system("rename", path.display().to_string(), e.to_string()))
+}
+
+/// Remove all but the newest `keep` extract directories under `root`
+pub fn prune_extracts(root: &Path, keep: usize) -> AppResult<usize> {
+    let mut extracts: Vec<_> = std::fs::read_dir(root)?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir() && entry.file_name().to_string_lossy().starts_with(EXTRACT_PREFIX))
+        .map(|entry| entry.path())
+        .collect();
+    // Timestamped names sort oldest first
+    extracts.sort();
+    let excess = extracts.len().saturating_sub(keep);
+    for path in &extracts[..excess] {
+        std::fs::remove_dir_all(path)
+            .map_err(|e| AppError::file_system("remove", path.display().to_string(), e.to_string()))?;
+    }
+    Ok(excess)
+}
+
+/// Background task writing an extract whenever one is due, until shutdown
+pub async fn run_scheduled_warehouse_exports(service: Arc<WarehouseExportService>, mut shutdown: ShutdownSignal) {
+    info!("Checking for due data warehouse extracts every {:?}", WAREHOUSE_CHECK_INTERVAL);
+    loop {
+        let warehouse = service.clone();
+        match tokio::task::spawn_blocking(move || warehouse.export_if_due()).await {
+            Ok(Ok(Some(manifest))) => info!("Data warehouse extract written to {}", manifest.extract_directory),
+            Ok(Ok(None)) => debug!("No data warehouse extract due"),
+            Ok(Err(e)) => error!("Data warehouse extract failed: {}", e),
+            Err(e) => error!("Data warehouse extract task panicked: {}", e),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(WAREHOUSE_CHECK_INTERVAL) => {}
+            _ = shutdown.wait() => break,
+        }
+    }
+    debug!("Data warehouse extracts stopped");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_dimension_and_range() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let row = DateDimension::new(date);
+        assert_eq!(row.date_key, 20261017);
+        assert_eq!((row.quarter, row.day_of_week, row.is_weekend), (4, 6, true));
+        assert_eq!(row.month_name, "October");
+
+        let mut range = DateRange::default();
+        assert_eq!(range.days().count(), 0);
+        range.include(date);
+        range.include(NaiveDate::from_ymd_opt(2026, 10, 14).unwrap());
+        let days: Vec<i64> = range.days().map(date_key).collect();
+        assert_eq!(days, vec![20261014, 20261015, 20261016, 20261017]);
+    }
+
+    #[test]
+    fn test_prune_extracts_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["extract-20260101T000000Z", "extract-20260102T000000Z", "extract-20260103T000000Z", "other"] {
+            std::fs::create_dir(dir.path().join(name)).unwrap();
+        }
+        assert_eq!(prune_extracts(dir.path(), 2).unwrap(), 1);
+        assert!(!dir.path().join("extract-20260101T000000Z").exists());
+        assert!(dir.path().join("extract-20260103T000000Z").exists());
+        assert!(dir.path().join("other").exists());
+    }
+}